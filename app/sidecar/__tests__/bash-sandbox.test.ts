@@ -0,0 +1,224 @@
+import { describe, it, expect, vi } from "vitest";
+import {
+  buildBashSandboxHooks,
+  evaluateBashCommand,
+  hasExpansion,
+  matchesPattern,
+  shellWords,
+  splitSegments,
+} from "../bash-sandbox.js";
+import type { BashSandboxConfig } from "../config.js";
+
+function makePolicy(overrides: Partial<BashSandboxConfig> = {}): BashSandboxConfig {
+  return {
+    allowPatterns: [],
+    denyPatterns: ["sudo *", "rm -rf *"],
+    allowNetwork: false,
+    allowedDirs: ["/work/my-skill", "/skills/my-skill"],
+    cwd: "/work/my-skill",
+    ...overrides,
+  };
+}
+
+describe("splitSegments", () => {
+  it("splits on shell control operators", () => {
+    expect(splitSegments("ls -la && cat a.md | wc -l; echo  done")).toEqual([
+      "ls -la",
+      "cat a.md",
+      "wc -l",
+      "echo done",
+    ]);
+  });
+
+  it("splits on a background & but not on redirections", () => {
+    expect(splitSegments("ls & cat x")).toEqual(["ls", "cat x"]);
+    expect(splitSegments("make 2>&1 &> log")).toEqual(["make 2>&1 &> log"]);
+  });
+});
+
+describe("shellWords", () => {
+  it("removes quotes and escapes and separates redirections", () => {
+    expect(shellWords(`cat "a b.md" 'c d' e\\ f>out.txt`)).toEqual(["cat", "a b.md", "c d", "e f", "out.txt"]);
+  });
+});
+
+describe("hasExpansion", () => {
+  it("finds expansion and substitution outside single quotes", () => {
+    for (const command of ["cat $HOME/x", "cat ${X}", "ls $(pwd)", "ls `pwd`", "cat <(ls)", 'echo "$OLDPWD"', "echo $'\\x2f'"]) {
+      expect(hasExpansion(command)).toBe(true);
+    }
+  });
+
+  it("ignores literal dollars", () => {
+    for (const command of ["grep 'a$' x", "echo \\$HOME", "echo costs $ 5", "grep 'a$(b)' x"]) {
+      expect(hasExpansion(command)).toBe(false);
+    }
+  });
+});
+
+describe("matchesPattern", () => {
+  it("treats a plain pattern as a command prefix", () => {
+    expect(matchesPattern("git status -s", "git status")).toBe(true);
+    expect(matchesPattern("git statusx", "git status")).toBe(false);
+  });
+
+  it("supports * wildcards", () => {
+    expect(matchesPattern("rm -rf /tmp/x", "rm -rf *")).toBe(true);
+    expect(matchesPattern("rm file", "rm -rf *")).toBe(false);
+  });
+});
+
+describe("evaluateBashCommand", () => {
+  it("allows commands inside the skill directories", () => {
+    const decision = evaluateBashCommand("ls /skills/my-skill/references", makePolicy(), "/work/my-skill");
+    expect(decision).toEqual({ allowed: true, reason: "allowed by sandbox policy", cwd: "/work/my-skill" });
+  });
+
+  it("refuses deny patterns in any segment", () => {
+    const decision = evaluateBashCommand("ls && sudo rm x", makePolicy(), "/work");
+    expect(decision).toEqual({ allowed: false, reason: 'matches deny pattern "sudo *"' });
+  });
+
+  it("enforces the allowlist and blocks command substitution", () => {
+    const policy = makePolicy({ allowPatterns: ["ls", "cat *"] });
+    expect(evaluateBashCommand("cat /work/my-skill/a.md", policy, "/work/my-skill").allowed).toBe(true);
+    expect(evaluateBashCommand("python script.py", policy, "/work").allowed).toBe(false);
+    expect(evaluateBashCommand("ls $(cat /etc/passwd)", policy, "/work").allowed).toBe(false);
+  });
+
+  it("refuses expansion and substitution without an allowlist", () => {
+    for (const command of ["cat $HOME/.ssh/id_rsa", "cd $OLDPWD", "ls `echo /`", "cat ${X}"]) {
+      expect(evaluateBashCommand(command, makePolicy(), "/work/my-skill")).toEqual({
+        allowed: false,
+        reason: "variable expansion and command substitution are not allowed",
+      });
+    }
+  });
+
+  it("blocks network commands unless allowed", () => {
+    expect(evaluateBashCommand("curl https://example.com", makePolicy(), "/work").allowed).toBe(false);
+    expect(evaluateBashCommand("FOO=1 git fetch origin", makePolicy(), "/work").allowed).toBe(false);
+    expect(
+      evaluateBashCommand("curl https://example.com", makePolicy({ allowNetwork: true }), "/work/my-skill").allowed,
+    ).toBe(true);
+  });
+
+  it("finds network commands behind wrappers and in inline scripts", () => {
+    const policy = makePolicy();
+    for (const command of [
+      "bash -c 'curl https://x'",
+      "sh -lc \"cat a.md; wget https://x\"",
+      "env curl https://x",
+      "env",
+      "timeout 5 /usr/bin/curl https://x",
+      "cat urls.txt | xargs curl",
+      "xargs -n1 echo",
+      "find . -name x -exec curl https://x ;",
+      "git -C . fetch",
+      "git -c core.sshCommand=x ls-remote origin",
+      "git --git-dir .git fetch",
+      "git --no-pager fetch origin",
+      "python3 -c \"import urllib.request\"",
+      "node -e \"fetch('https://x')\"",
+      "perl -e 'print 1'",
+    ]) {
+      const decision = evaluateBashCommand(command, policy, "/work/my-skill");
+      expect(decision.allowed, command).toBe(false);
+      expect(decision.reason, command).toMatch(/^network access is disabled/);
+    }
+  });
+
+  it("allows wrappers and inline scripts when the network is allowed", () => {
+    const policy = makePolicy({ allowNetwork: true });
+    expect(evaluateBashCommand("git -C references log", policy, "/work/my-skill").allowed).toBe(true);
+    expect(evaluateBashCommand("python3 -c 'print(1)'", policy, "/work/my-skill").allowed).toBe(true);
+    expect(evaluateBashCommand("git --no-pager log", makePolicy(), "/work/my-skill").allowed).toBe(true);
+    expect(evaluateBashCommand("python3 script.py", makePolicy(), "/work/my-skill").allowed).toBe(true);
+  });
+
+  it("finds deny patterns behind wrappers and in inline scripts", () => {
+    const policy = makePolicy({ allowNetwork: true });
+    for (const command of [
+      "bash -c 'ls; rm -rf references'",
+      "env rm -rf references",
+      "timeout 5 sudo ls",
+      "ls | xargs rm -rf tmp",
+      "python3 -c 'x' && sh -c \"sudo ls\"",
+    ]) {
+      const decision = evaluateBashCommand(command, policy, "/work/my-skill");
+      expect(decision.allowed, command).toBe(false);
+      expect(decision.reason, command).toMatch(/^matches deny pattern/);
+    }
+  });
+
+  it("keeps paths inside the allowed directories", () => {
+    const policy = makePolicy();
+    expect(evaluateBashCommand("cat /etc/passwd", policy, "/work").allowed).toBe(false);
+    expect(evaluateBashCommand("cat /work/my-skill/../other/x", policy, "/work").allowed).toBe(false);
+    expect(evaluateBashCommand("cat ~/.ssh/id_rsa", policy, "/work").allowed).toBe(false);
+    expect(evaluateBashCommand("echo hi > /work/my-skill/out.txt", policy, "/work/my-skill").allowed).toBe(true);
+    expect(evaluateBashCommand("cd .. && ls", policy, "/work/my-skill").allowed).toBe(false);
+  });
+
+  it("resolves every argument against the working directory", () => {
+    const policy = makePolicy();
+    expect(evaluateBashCommand("cat other-skill/x", policy, "/work").allowed).toBe(false);
+    expect(evaluateBashCommand("cat \"/etc/passwd\"", policy, "/work/my-skill").allowed).toBe(false);
+    expect(evaluateBashCommand("sh -c 'cat /etc/passwd'", policy, "/work/my-skill").allowed).toBe(false);
+    expect(evaluateBashCommand("ls & cat /etc/passwd", policy, "/work/my-skill").allowed).toBe(false);
+    expect(evaluateBashCommand("grep -r --include=*.md term .", policy, "/work/my-skill").allowed).toBe(true);
+  });
+
+  it("tracks cd targets through the command", () => {
+    const policy = makePolicy();
+    expect(evaluateBashCommand("cd references && cat a.md", policy, "/work/my-skill")).toMatchObject({
+      allowed: true,
+      cwd: "/work/my-skill/references",
+    });
+    expect(evaluateBashCommand("cd /skills/my-skill && ls", policy, "/work/my-skill").cwd).toBe("/skills/my-skill");
+    expect(evaluateBashCommand("cd references && cat ../../other/x", policy, "/work/my-skill").allowed).toBe(false);
+    expect(evaluateBashCommand("(cd /etc; cat passwd)", policy, "/work/my-skill").allowed).toBe(false);
+    expect(evaluateBashCommand("cd ~", policy, "/work/my-skill").allowed).toBe(false);
+  });
+
+  it("refuses a cd that leaves for $HOME or $OLDPWD", () => {
+    for (const command of ["cd", "cd && cat .ssh/id_rsa", "cd -", "popd"]) {
+      expect(evaluateBashCommand(command, makePolicy(), "/work/my-skill").allowed).toBe(false);
+    }
+  });
+});
+
+describe("buildBashSandboxHooks", () => {
+  it("denies blocked commands and logs every decision", async () => {
+    const log = vi.fn();
+    const hooks = buildBashSandboxHooks(makePolicy(), "/work", log);
+    expect(hooks.PreToolUse[0].matcher).toBe("Bash");
+    const hook = hooks.PreToolUse[0].hooks[0];
+
+    const denied = await hook({ tool_name: "Bash", tool_input: { command: "cat /etc/hosts" }, cwd: "/work" });
+    expect(denied).toHaveProperty("hookSpecificOutput.permissionDecision", "deny");
+
+    const allowed = await hook({ tool_name: "Bash", tool_input: { command: "ls" }, cwd: "/work/my-skill" });
+    expect(allowed).toEqual({});
+
+    expect(log).toHaveBeenCalledTimes(2);
+    expect(log.mock.calls[0][0]).toMatchObject({
+      type: "system",
+      subtype: "bash_sandbox",
+      command: "cat /etc/hosts",
+      decision: "deny",
+    });
+    expect(log.mock.calls[1][0]).toMatchObject({ command: "ls", decision: "allow" });
+  });
+
+  it("starts commands in the policy cwd while the shell is outside the allowed directories", async () => {
+    const hook = buildBashSandboxHooks(makePolicy(), "/work", vi.fn()).PreToolUse[0].hooks[0];
+
+    const rewritten = await hook({ tool_name: "Bash", tool_input: { command: "cat SKILL.md" }, cwd: "/work" });
+    expect(rewritten).toHaveProperty("hookSpecificOutput.permissionDecision", "allow");
+    expect(rewritten).toHaveProperty("hookSpecificOutput.updatedInput.command", "cd '/work/my-skill' && cat SKILL.md");
+
+    const denied = await hook({ tool_name: "Bash", tool_input: { command: "cat ../other/SKILL.md" }, cwd: "/work" });
+    expect(denied).toHaveProperty("hookSpecificOutput.permissionDecision", "deny");
+  });
+});
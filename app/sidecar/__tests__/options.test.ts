@@ -37,7 +37,7 @@ describe("buildQueryOptions", () => {
     expect(opts).toHaveProperty("settingSources", ["project"]);
   });
 
-  it("passes only agent when both agentName and model are present", () => {
+  it("passes model as an override when both agentName and model are present", () => {
     const config = makeConfig({
       agentName: "my-agent",
       model: "claude-sonnet-4-20250514",
@@ -46,8 +46,8 @@ describe("buildQueryOptions", () => {
     const opts = buildQueryOptions(config, ac, []);
 
     expect(opts).toHaveProperty("agent", "my-agent");
+    expect(opts).toHaveProperty("model", "claude-sonnet-4-20250514");
     expect(opts).toHaveProperty("settingSources", ["project"]);
-    expect(opts).not.toHaveProperty("model");
   });
 
   it("defaults maxTurns to 50 when not specified", () => {
@@ -87,6 +87,18 @@ describe("buildQueryOptions", () => {
     expect(opts).toHaveProperty("betas", ["beta-1", "beta-2"]);
   });
 
+  it("adds a Bash PreToolUse hook only when bashSandbox is set", () => {
+    expect(buildQueryOptions(makeConfig(), new AbortController(), [])).not.toHaveProperty("hooks");
+    const opts = buildQueryOptions(
+      makeConfig({
+        bashSandbox: { allowPatterns: [], denyPatterns: [], allowNetwork: false, allowedDirs: ["/tmp/project"], cwd: "/tmp/project" },
+      }),
+      new AbortController(),
+      [],
+    );
+    expect(opts).toHaveProperty("hooks.PreToolUse.0.matcher", "Bash");
+  });
+
   it("excludes betas when absent", () => {
     const opts = buildQueryOptions(makeConfig(), new AbortController(), []);
     expect(opts).not.toHaveProperty("betas");
@@ -147,6 +159,16 @@ describe("buildQueryOptions", () => {
     expect(opts).toHaveProperty("promptSuggestions", true);
   });
 
+  it("includes resume only when a session id is given", () => {
+    const opts = buildQueryOptions(
+      makeConfig({ resume: "sdk-session-1" }),
+      new AbortController(),
+      []
+    );
+    expect(opts).toHaveProperty("resume", "sdk-session-1");
+    expect(buildQueryOptions(makeConfig(), new AbortController(), [])).not.toHaveProperty("resume");
+  });
+
   it("includes pathToClaudeCodeExecutable when present", () => {
     const opts = buildQueryOptions(
       makeConfig({ pathToClaudeCodeExecutable: "/usr/local/bin/claude" }),
@@ -173,6 +195,34 @@ describe("buildQueryOptions", () => {
     expect(opts.allowedTools).toEqual(["Read", "Write", "Bash"]);
   });
 
+  it("includes disallowedTools only when non-empty", () => {
+    const trimmed = buildQueryOptions(
+      makeConfig({ disallowedTools: ["Bash"] }),
+      new AbortController(),
+      []
+    );
+    expect(trimmed.disallowedTools).toEqual(["Bash"]);
+
+    const empty = buildQueryOptions(makeConfig({ disallowedTools: [] }), new AbortController(), []);
+    expect(empty).not.toHaveProperty("disallowedTools");
+  });
+
+  it("points every model alias at the local server when localModel is set", () => {
+    const opts = buildQueryOptions(
+      makeConfig({ localModel: { baseUrl: "http://localhost:11434", model: "qwen3-coder" } }),
+      new AbortController(),
+      []
+    );
+    const env = (opts as { env?: Record<string, string> }).env;
+    expect(env?.ANTHROPIC_BASE_URL).toBe("http://localhost:11434");
+    expect(env?.ANTHROPIC_DEFAULT_SONNET_MODEL).toBe("qwen3-coder");
+    expect(env?.CLAUDE_CODE_SUBAGENT_MODEL).toBe("qwen3-coder");
+    expect(env?.CLAUDE_CODE_DISABLE_NONESSENTIAL_TRAFFIC).toBe("1");
+
+    const online = buildQueryOptions(makeConfig(), new AbortController(), []);
+    expect((online as { env?: Record<string, string> }).env?.CLAUDE_CODE_SUBAGENT_MODEL).not.toBe("qwen3-coder");
+  });
+
   it("passes the abort controller through", () => {
     const ac = new AbortController();
     const opts = buildQueryOptions(makeConfig(), ac, []);
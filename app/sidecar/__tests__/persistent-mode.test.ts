@@ -9,9 +9,11 @@ vi.mock("@anthropic-ai/claude-agent-sdk", () => ({
 import { query } from "@anthropic-ai/claude-agent-sdk";
 import {
   parseIncomingMessage,
+  isUnknownMessageType,
   wrapWithRequestId,
   runPersistent,
 } from "../persistent-mode.js";
+import { PROTOCOL_VERSION } from "../protocol.js";
 
 const mockQuery = vi.mocked(query);
 
@@ -75,6 +77,13 @@ describe("parseIncomingMessage", () => {
     expect(parseIncomingMessage(line)).toBeNull();
   });
 
+  it("flags well-formed envelopes with unknown types", () => {
+    expect(isUnknownMessageType(JSON.stringify({ type: "future_message" }))).toBe(true);
+    expect(isUnknownMessageType(JSON.stringify({ type: "ping" }))).toBe(false);
+    expect(isUnknownMessageType("this is not json")).toBe(false);
+    expect(isUnknownMessageType(JSON.stringify({ no_type: true }))).toBe(false);
+  });
+
   it("returns null for agent_request without request_id", () => {
     const line = JSON.stringify({
       type: "agent_request",
@@ -318,7 +327,10 @@ describe("runPersistent", () => {
       return parsed.type === "sidecar_ready";
     });
     expect(readyLine).toBeDefined();
-    expect(JSON.parse(readyLine!)).toEqual({ type: "sidecar_ready" });
+    expect(JSON.parse(readyLine!)).toEqual({
+      type: "sidecar_ready",
+      protocol_version: PROTOCOL_VERSION,
+    });
   });
 
   it("exits with code 0 on shutdown message", async () => {
@@ -540,6 +552,60 @@ describe("runPersistent", () => {
     expect(exitFn).toHaveBeenCalledWith(0);
   });
 
+  it("runs concurrent requests side by side without aborting", async () => {
+    // The first request takes a while; a concurrent second request must not abort it.
+    let callCount = 0;
+    mockQuery.mockImplementation((args: Record<string, unknown>) => {
+      const opts = args.options as Record<string, unknown> | undefined;
+      const ac = opts?.abortController as AbortController | undefined;
+      callCount++;
+      const current = callCount;
+      async function* fakeConversation() {
+        if (current === 1) {
+          await new Promise<void>((resolve, reject) => {
+            setTimeout(resolve, 40);
+            ac?.signal.addEventListener(
+              "abort",
+              () => reject(new Error("aborted")),
+              { once: true },
+            );
+          });
+        }
+        yield { type: "result", content: `result_${current}` };
+      }
+      return fakeConversation() as ReturnType<typeof query>;
+    });
+
+    const { Readable } = await import("node:stream");
+    const input = new Readable({ read() {} });
+    const exitFn = vi.fn();
+    const capture = captureStdout();
+
+    const runPromise = runPersistent(input, exitFn);
+
+    for (const [requestId, prompt] of [["req_a", "first"], ["req_b", "second"]]) {
+      input.push(JSON.stringify({
+        type: "agent_request",
+        request_id: requestId,
+        config: { prompt, apiKey: "sk-test", cwd: "/tmp" },
+        concurrent: true,
+      }) + "\n");
+      await new Promise((r) => setTimeout(r, 10));
+    }
+
+    input.push(JSON.stringify({ type: "shutdown" }) + "\n");
+    input.push(null);
+    await runPromise;
+
+    capture.restore();
+
+    const parsed = capture.lines.map((l) => JSON.parse(l));
+    expect(parsed.find((m) => m.request_id === "req_a" && m.type === "error")).toBeUndefined();
+    expect(parsed.find((m) => m.request_id === "req_a" && m.content === "result_1")).toBeDefined();
+    expect(parsed.find((m) => m.request_id === "req_b" && m.content === "result_2")).toBeDefined();
+    expect(exitFn).toHaveBeenCalledWith(0);
+  });
+
   it("handles sequential requests when first completes before second arrives", async () => {
     let callCount = 0;
     mockQuery.mockImplementation(() => {
@@ -628,6 +694,25 @@ describe("runPersistent", () => {
     expect(errorMsg.message).toContain("Unrecognized input");
   });
 
+  it("ignores unknown message types from a newer host", async () => {
+    const input = createInputStream([
+      JSON.stringify({ type: "future_message", payload: 1 }),
+      JSON.stringify({ type: "shutdown" }),
+    ]);
+    const exitFn = vi.fn();
+    const capture = captureStdout();
+
+    try {
+      await runPersistent(input, exitFn);
+    } finally {
+      capture.restore();
+    }
+
+    const errorLine = capture.lines.find((l) => JSON.parse(l).type === "error");
+    expect(errorLine).toBeUndefined();
+    expect(exitFn).toHaveBeenCalledWith(0);
+  });
+
   it("responds to ping with pong", async () => {
     const input = createInputStream([
       JSON.stringify({ type: "ping" }),
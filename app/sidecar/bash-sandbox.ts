@@ -0,0 +1,375 @@
+import * as path from "node:path";
+import type { BashSandboxConfig } from "./config.js";
+
+/**
+ * Static checks applied to Bash commands before the SDK runs them.
+ *
+ * These are best-effort guards, not an OS sandbox: commands that expand
+ * variables or substitute commands are refused outright, and the rest are
+ * split on shell control operators and each segment is matched against the
+ * deny patterns, the allowlist, a list of network clients, and the allowed
+ * directories. Deny patterns and network clients are also looked for behind
+ * wrapper programs and in scripts passed inline to a shell.
+ */
+
+/** Commands (or command + subcommand) that reach the network. */
+const NETWORK_COMMANDS = [
+  "curl", "wget", "nc", "ncat", "netcat", "telnet", "ssh", "scp", "sftp", "ftp", "rsync",
+  "ping", "dig", "nslookup", "http", "https", "gh",
+  "git clone", "git fetch", "git pull", "git push", "git ls-remote", "git submodule",
+  "npm install", "npm i", "npm ci", "npx", "pnpm add", "pnpm install", "yarn add", "yarn install",
+  "pip install", "pip3 install", "uv pip", "cargo install", "go get", "brew install",
+];
+
+/** Programs that run a command given in their arguments. */
+const WRAPPER_PROGRAMS = ["env", "xargs"];
+
+/** Shells and interpreters, which run a script passed inline with `-c`/`-e`. */
+const SCRIPT_PROGRAMS = [
+  "sh", "bash", "zsh", "dash", "ksh", "fish",
+  "python", "python3", "node", "deno", "bun", "perl", "ruby", "php",
+];
+
+/** Whether `word` is a flag that takes an inline script: `-c`, `-lc`, `-e`, `--eval`. */
+function isInlineScriptFlag(word: string): boolean {
+  return /^-[A-Za-z]*[ce]$/.test(word) || ["--eval", "--command", "-p", "--print"].includes(word);
+}
+
+/**
+ * Split a command line into segments at `;`, `&&`, `||`, `|`, `&` and
+ * newlines. The `&` of a redirection such as `2>&1` or `&>` does not split.
+ */
+export function splitSegments(command: string): string[] {
+  return command
+    .split(/\|\||&&|(?<![<>])&(?!>)|[;|\n]/)
+    .map((s) => s.trim().replace(/\s+/g, " "))
+    .filter((s) => s.length > 0);
+}
+
+/**
+ * Match a segment against a pattern. `*` matches anything; a pattern without
+ * `*` also matches the same command with extra arguments (`git status` matches
+ * `git status -s`).
+ */
+export function matchesPattern(segment: string, pattern: string): boolean {
+  const p = pattern.trim().replace(/\s+/g, " ");
+  if (!p.includes("*")) {
+    return segment === p || segment.startsWith(`${p} `);
+  }
+  const escaped = p.split("*").map((part) => part.replace(/[.+?^${}()|[\]\\]/g, "\\$&"));
+  return new RegExp(`^${escaped.join(".*")}$`).test(segment);
+}
+
+/** Strip leading `VAR=value` assignments so `FOO=1 curl x` is seen as `curl x`. */
+function commandPart(segment: string): string {
+  return segment.replace(/^(?:[A-Za-z_][A-Za-z0-9_]*=\S*\s+)+/, "");
+}
+
+/**
+ * Whether `command` expands a variable (`$HOME`, `${x}`, `$'...'`) or
+ * substitutes a command (`$(...)`, backticks, `<(...)`) anywhere outside
+ * single quotes. Expanded text is unknown here, so no path check could hold.
+ */
+export function hasExpansion(command: string): boolean {
+  let quote: "'" | '"' | null = null;
+  for (let i = 0; i < command.length; i++) {
+    const c = command[i];
+    const next = command[i + 1];
+    if (quote === "'") {
+      if (c === "'") quote = null;
+    } else if (c === "\\") {
+      i++;
+    } else if (c === "`") {
+      return true;
+    } else if (c === "$" && next !== undefined && !/\s/.test(next) && !(quote === '"' && next === '"')) {
+      return true;
+    } else if ((c === "<" || c === ">") && next === "(") {
+      return true;
+    } else if (c === '"') {
+      quote = quote ? null : '"';
+    } else if (c === "'" && !quote) {
+      quote = "'";
+    }
+  }
+  return false;
+}
+
+/**
+ * Split a segment into words the way the shell would, removing quotes and
+ * escapes. Unquoted `<` and `>` separate words, so redirection targets are
+ * words of their own.
+ */
+export function shellWords(segment: string): string[] {
+  const words: string[] = [];
+  let word = "";
+  let inWord = false;
+  let quote: "'" | '"' | null = null;
+  for (let i = 0; i < segment.length; i++) {
+    const c = segment[i];
+    if (quote) {
+      if (c === quote) {
+        quote = null;
+      } else if (c === "\\" && quote === '"' && i + 1 < segment.length) {
+        word += segment[++i];
+      } else {
+        word += c;
+      }
+    } else if (c === "'" || c === '"') {
+      quote = c;
+      inWord = true;
+    } else if (c === "\\" && i + 1 < segment.length) {
+      word += segment[++i];
+      inWord = true;
+    } else if (/\s|[<>]/.test(c)) {
+      if (inWord) words.push(word);
+      word = "";
+      inWord = false;
+    } else {
+      word += c;
+      inWord = true;
+    }
+  }
+  if (inWord) words.push(word);
+  return words;
+}
+
+function isInside(dir: string, target: string): boolean {
+  const rel = path.relative(path.resolve(dir), target);
+  return rel === "" || (!rel.startsWith("..") && !path.isAbsolute(rel));
+}
+
+/** Tokens that name a filesystem path: absolute, home-relative, or containing a `/` or `..`. */
+function pathTokens(segment: string): string[] {
+  return segment
+    .split(/\s+|[<>]+/)
+    .map((t) => t.replace(/^['"]|['"]$/g, ""))
+    .map((t) => (t.startsWith("-") && t.includes("=") ? t.slice(t.indexOf("=") + 1) : t))
+    .filter((t) => t.length > 0 && !/^[a-z][a-z0-9+.-]*:\/\//i.test(t))
+    .filter((t) => t.startsWith("/") || t.startsWith("~") || t.includes("/") || t === "..");
+}
+
+/**
+ * The words of a segment's command: grouping `(`/`{` and `)`/`}` around it
+ * removed, so `(cd x; ls)` is seen as `cd x` and `ls`.
+ */
+function commandWords(cmd: string): string[] {
+  const words = shellWords(cmd);
+  if (words.length > 0) words[0] = words[0].replace(/^[({]+/, "");
+  if (words.length > 0) words[words.length - 1] = words[words.length - 1].replace(/[)}]+$/, "");
+  return words.filter((w) => w.length > 0);
+}
+
+/**
+ * The path a word names, if any: the value of a `--flag=value`, or any word
+ * that is not a flag. URLs name no path.
+ */
+function pathArgument(word: string): string | null {
+  if (word.startsWith("-") && !word.includes("=")) return null;
+  const value = word.startsWith("-") ? word.slice(word.indexOf("=") + 1) : word;
+  return /^[a-z][a-z0-9+.-]*:\/\//i.test(value) ? null : value;
+}
+
+/**
+ * Every way `words` can be read as a command: from each word on, with the
+ * program's directory dropped, so a command run by another one (`env curl x`,
+ * `timeout 5 /usr/bin/curl x`, `find . -exec curl x ;`) is seen as well.
+ */
+function commandReadings(words: string[]): string[] {
+  return words.map((word, i) => [path.basename(word), ...words.slice(i + 1)].join(" "));
+}
+
+/** git global options that take no value and change nothing a check relies on. */
+const GIT_PLAIN_OPTIONS = [
+  "--no-pager", "-P", "-p", "--paginate", "--no-optional-locks", "--literal-pathspecs", "--version", "--help",
+];
+
+/**
+ * `git` with its global options removed, so `git --no-pager fetch` is seen as
+ * `git fetch`. Any other option before the subcommand (`-C`, `-c
+ * core.sshCommand=...`, `--git-dir x`) is reported instead: it changes the
+ * repository or its configuration, or takes a value that would be read as the
+ * subcommand.
+ */
+function gitSubcommand(words: string[]): string | { option: string } {
+  let i = 1;
+  while (i < words.length && words[i].startsWith("-")) {
+    if (!GIT_PLAIN_OPTIONS.includes(words[i])) {
+      return { option: words[i] };
+    }
+    i++;
+  }
+  return ["git", ...words.slice(i)].join(" ");
+}
+
+/**
+ * Deny-pattern and network checks for one segment's command. Patterns are
+ * matched against the command as written and read from every word on, and
+ * scripts passed inline to a shell or interpreter are checked the same way.
+ * Without network access, programs that run a command the check cannot see
+ * (`env`, `xargs`, `bash -c`, `python -c`, `node -e`, `git -C`) are refused.
+ */
+function screenCommand(cmd: string, policy: BashSandboxConfig): BashDecision | null {
+  const words = commandWords(cmd);
+  const readings = [cmd, ...commandReadings(words)];
+  const denied = policy.denyPatterns.find((p) => readings.some((r) => matchesPattern(r, p)));
+  if (denied) {
+    return { allowed: false, reason: `matches deny pattern "${denied}"` };
+  }
+  const program = words.length > 0 ? path.basename(words[0]) : "";
+  const isScriptProgram = SCRIPT_PROGRAMS.includes(program.replace(/[\d.]+$/, ""));
+  if (!policy.allowNetwork) {
+    const disabled = (what: string): BashDecision => ({
+      allowed: false,
+      reason: `network access is disabled ("${what}")`,
+    });
+    const git = program === "git" ? gitSubcommand(words) : null;
+    if (git !== null && typeof git !== "string") {
+      return disabled(`git ${git.option}`);
+    }
+    const network = NETWORK_COMMANDS.find((n) =>
+      [...readings, ...(git ? [git] : [])].some((r) => matchesPattern(r, n)),
+    );
+    if (network) return disabled(network);
+    if (WRAPPER_PROGRAMS.includes(program)) return disabled(program);
+    const flag = isScriptProgram ? words.slice(1).find(isInlineScriptFlag) : undefined;
+    if (flag) return disabled(`${program} ${flag}`);
+  }
+  if (isScriptProgram) {
+    for (let i = 1; i < words.length - 1; i++) {
+      if (!isInlineScriptFlag(words[i])) continue;
+      for (const nested of splitSegments(words[i + 1])) {
+        const decision = screenCommand(commandPart(nested), policy);
+        if (decision) return decision;
+      }
+    }
+  }
+  return null;
+}
+
+export interface BashDecision {
+  allowed: boolean;
+  reason: string;
+  /** Working directory after the command's `cd`s; set when allowed. */
+  cwd?: string;
+}
+
+/**
+ * Check `command` against `policy`, starting in `cwd`. With `allowedDirs`,
+ * every `cd` target and every argument that is not a flag is resolved against
+ * the working directory at that point of the command and must stay inside
+ * them; a `cd` with no target or `cd -` is refused, since it leaves for
+ * `$HOME` or `$OLDPWD`.
+ */
+export function evaluateBashCommand(
+  command: string,
+  policy: BashSandboxConfig,
+  cwd: string,
+): BashDecision {
+  const segments = splitSegments(command);
+  if (segments.length === 0) {
+    return { allowed: false, reason: "empty command" };
+  }
+  if (hasExpansion(command)) {
+    return { allowed: false, reason: "variable expansion and command substitution are not allowed" };
+  }
+
+  const outside = (token: string): BashDecision => ({
+    allowed: false,
+    reason: `path "${token}" is outside the skill directories`,
+  });
+  const inAllowedDir = (target: string) => policy.allowedDirs.some((dir) => isInside(dir, target));
+  let current = cwd;
+  for (const segment of segments) {
+    const cmd = commandPart(segment);
+    const screened = screenCommand(cmd, policy);
+    if (screened) return screened;
+    if (policy.allowPatterns.length > 0 && !policy.allowPatterns.some((p) => matchesPattern(cmd, p))) {
+      return { allowed: false, reason: `"${cmd}" is not in the allowlist` };
+    }
+    if (policy.allowedDirs.length === 0) continue;
+
+    const [program, ...args] = commandWords(cmd);
+    const targets = args.map(pathArgument).filter((t): t is string => t !== null && t.length > 0);
+    if (program === "popd") {
+      return { allowed: false, reason: "popd is not allowed" };
+    }
+    if (program === "cd" || program === "pushd") {
+      const target = targets[0];
+      if (target === undefined || target === "-") {
+        return { allowed: false, reason: `"${cmd}" leaves the skill directories` };
+      }
+      if (target.startsWith("~") || !inAllowedDir(path.resolve(current, target))) {
+        return outside(target);
+      }
+      current = path.resolve(current, target);
+      continue;
+    }
+    // Words are checked as parsed, and path-like text inside quoted words
+    // (`sh -c 'cat /etc/x'`) as well.
+    for (const token of [...targets, ...pathTokens(cmd)]) {
+      if (token.startsWith("~") || !inAllowedDir(path.resolve(current, token))) {
+        return outside(token);
+      }
+    }
+  }
+  return { allowed: true, reason: "allowed by sandbox policy", cwd: current };
+}
+
+/** Quote `value` as one shell word. */
+function shellQuote(value: string): string {
+  return `'${value.replace(/'/g, "'\\''")}'`;
+}
+
+/**
+ * Build the SDK `hooks` option enforcing `policy` on every Bash call.
+ * `log` receives one system message per command with the decision.
+ *
+ * Commands start in `policy.cwd`: while the shell's working directory (the
+ * hook input's `cwd`) is outside the allowed directories, an allowed command
+ * is rewritten to `cd` there first, and the shell keeps that directory for
+ * the next call.
+ */
+export function buildBashSandboxHooks(
+  policy: BashSandboxConfig,
+  defaultCwd: string,
+  log: (message: Record<string, unknown>) => void,
+) {
+  const hook = async (rawInput: unknown) => {
+    const input = rawInput as { tool_input?: { command?: unknown }; cwd?: unknown };
+    const toolInput = input.tool_input ?? {};
+    const command = typeof toolInput.command === "string" ? toolInput.command : "";
+    const shellCwd = typeof input.cwd === "string" ? input.cwd : defaultCwd;
+    const cwd = policy.allowedDirs.length === 0 || policy.allowedDirs.some((dir) => isInside(dir, shellCwd))
+      ? shellCwd
+      : policy.cwd;
+    const decision = evaluateBashCommand(command, policy, cwd);
+    log({
+      type: "system",
+      subtype: "bash_sandbox",
+      command,
+      cwd,
+      decision: decision.allowed ? "allow" : "deny",
+      reason: decision.reason,
+      timestamp: Date.now(),
+    });
+    if (!decision.allowed) {
+      return {
+        hookSpecificOutput: {
+          hookEventName: "PreToolUse" as const,
+          permissionDecision: "deny" as const,
+          permissionDecisionReason: `Blocked by Bash sandbox: ${decision.reason}`,
+        },
+      };
+    }
+    if (cwd === shellCwd) return {};
+    return {
+      hookSpecificOutput: {
+        hookEventName: "PreToolUse" as const,
+        permissionDecision: "allow" as const,
+        permissionDecisionReason: `Bash sandbox: starting in ${cwd}`,
+        updatedInput: { ...toolInput, command: `cd ${shellQuote(cwd)} && ${command}` },
+      },
+    };
+  };
+  return { PreToolUse: [{ matcher: "Bash", hooks: [hook] }] };
+}
@@ -1,3 +1,14 @@
+/** Bash restrictions enforced in a PreToolUse hook (see bash-sandbox.ts). */
+export interface BashSandboxConfig {
+  allowPatterns: string[];
+  denyPatterns: string[];
+  allowNetwork: boolean;
+  /** Paths used in commands must resolve inside one of these directories. */
+  allowedDirs: string[];
+  /** Directory Bash commands start in, one of `allowedDirs`. */
+  cwd: string;
+}
+
 export interface SidecarConfig {
   prompt: string;
   model?: string;
@@ -6,6 +17,8 @@ export interface SidecarConfig {
   cwd: string;
   requiredPlugins?: string[];
   allowedTools?: string[];
+  /** Tools removed from the session, including ones a tool policy trimmed. */
+  disallowedTools?: string[];
   maxTurns?: number;
   permissionMode?: string;
   betas?: string[];
@@ -18,6 +31,11 @@ export interface SidecarConfig {
   };
   promptSuggestions?: boolean;
   pathToClaudeCodeExecutable?: string;
+  bashSandbox?: BashSandboxConfig;
+  /** SDK session id to continue instead of starting a new conversation. */
+  resume?: string;
+  /** Locally hosted, Anthropic-compatible server to run against (offline mode). */
+  localModel?: { baseUrl: string; model: string };
 }
 
 /**
@@ -39,5 +57,24 @@ export function parseSidecarConfig(raw: unknown): SidecarConfig {
     }
   }
 
+  if (c.bashSandbox !== undefined) {
+    const b = c.bashSandbox as Record<string, unknown> | null;
+    const isStrings = (v: unknown) => Array.isArray(v) && v.every((p) => typeof p === "string");
+    if (
+      typeof b !== "object" || b === null
+      || !isStrings(b.allowPatterns) || !isStrings(b.denyPatterns) || !isStrings(b.allowedDirs)
+      || typeof b.allowNetwork !== "boolean" || typeof b.cwd !== "string"
+    ) {
+      throw new Error("Invalid SidecarConfig: malformed bashSandbox");
+    }
+  }
+
+  if (c.localModel !== undefined) {
+    const l = c.localModel as Record<string, unknown> | null;
+    if (typeof l !== "object" || l === null || typeof l.baseUrl !== "string" || typeof l.model !== "string") {
+      throw new Error("Invalid SidecarConfig: malformed localModel");
+    }
+  }
+
   return raw as SidecarConfig;
 }
@@ -1,5 +1,26 @@
 import type { Options } from "@anthropic-ai/claude-agent-sdk";
 import type { SidecarConfig } from "./config.js";
+import { buildBashSandboxHooks } from "./bash-sandbox.js";
+
+/**
+ * Environment that points the SDK at a locally hosted model server (offline
+ * mode). Every model alias, including ones in agent front matter and for
+ * sub-agents, resolves to the local model, and non-essential traffic such as
+ * telemetry and update checks is turned off.
+ */
+export function localModelEnv(config: SidecarConfig): Record<string, string> {
+  if (!config.localModel) return {};
+  const { baseUrl, model } = config.localModel;
+  return {
+    ANTHROPIC_BASE_URL: baseUrl,
+    ANTHROPIC_MODEL: model,
+    ANTHROPIC_DEFAULT_OPUS_MODEL: model,
+    ANTHROPIC_DEFAULT_SONNET_MODEL: model,
+    ANTHROPIC_DEFAULT_HAIKU_MODEL: model,
+    CLAUDE_CODE_SUBAGENT_MODEL: model,
+    CLAUDE_CODE_DISABLE_NONESSENTIAL_TRAFFIC: "1",
+  };
+}
 
 /**
  * Build the options object to pass to the SDK query() function.
@@ -7,32 +28,39 @@ import type { SidecarConfig } from "./config.js";
  * Agent / model resolution (settingSources: ['project'] always passed for project settings):
  *  - agentName only  → agent (front-matter model used)
  *  - model only      → model
- *  - both            → agent only (front-matter model authoritative)
+ *  - both            → agent, with model overriding its front-matter model
+ *                       (per-step model routes)
  *
  * @param pluginPaths  Absolute paths to installed plugin directories discovered by the caller.
  *                     Each entry becomes { type: 'local', path } in the SDK plugins array.
+ * @param onSandboxEvent  Receives one system message per Bash command checked by the sandbox.
  */
 export function buildQueryOptions(
   config: SidecarConfig,
   abortController: AbortController,
   pluginPaths: string[],
   stderr?: (data: string) => void,
+  onSandboxEvent?: (message: Record<string, unknown>) => void,
 ) {
   // --- agent / model resolution ---
   const hasAgent = typeof config.agentName === "string" && config.agentName.length > 0;
   const agentField = hasAgent ? { agent: config.agentName } : {};
-  const modelField = !hasAgent && config.model ? { model: config.model } : {};
+  const modelField = config.model ? { model: config.model } : {};
 
   // Pass the API key through the SDK's env option instead of mutating
   // process.env, which avoids races on concurrent requests.
   const envField = config.apiKey
-    ? { env: { ...process.env, ANTHROPIC_API_KEY: config.apiKey } }
+    ? { env: { ...process.env, ANTHROPIC_API_KEY: config.apiKey, ...localModelEnv(config) } }
     : {};
 
   const pluginsField = pluginPaths.length > 0
     ? { plugins: pluginPaths.map((p) => ({ type: "local" as const, path: p })) }
     : {};
 
+  const hooksField = config.bashSandbox
+    ? { hooks: buildBashSandboxHooks(config.bashSandbox, config.cwd, onSandboxEvent ?? (() => {})) }
+    : {};
+
   return {
     ...agentField,
     ...modelField,
@@ -46,6 +74,9 @@ export function buildQueryOptions(
     settingSources: ['project' as const],
     cwd: config.cwd,
     allowedTools: config.allowedTools,
+    ...(config.disallowedTools && config.disallowedTools.length > 0
+      ? { disallowedTools: config.disallowedTools }
+      : {}),
     maxTurns: config.maxTurns ?? 50,
     permissionMode: (config.permissionMode || "bypassPermissions") as
       | "default"
@@ -64,9 +95,11 @@ export function buildQueryOptions(
     ...(config.effort ? { effort: config.effort as Options["effort"] } : {}),
     ...(config.fallbackModel ? { fallbackModel: config.fallbackModel } : {}),
     ...(config.outputFormat ? { outputFormat: config.outputFormat as Options["outputFormat"] } : {}),
+    ...(config.resume ? { resume: config.resume } : {}),
     ...(typeof config.promptSuggestions === "boolean"
       ? { promptSuggestions: config.promptSuggestions }
       : {}),
     ...(stderr ? { stderr } : {}),
+    ...hooksField,
   };
 }
@@ -1,5 +1,6 @@
 import { createInterface, type Interface } from "node:readline";
 import { type SidecarConfig, parseSidecarConfig } from "./config.js";
+import { PROTOCOL_VERSION } from "./protocol.js";
 import { runAgentRequest } from "./run-agent.js";
 import { StreamSession } from "./stream-session.js";
 
@@ -8,6 +9,8 @@ interface AgentRequest {
   type: "agent_request";
   request_id: string;
   config: SidecarConfig;
+  /** Run alongside in-flight requests instead of replacing them. */
+  concurrent?: boolean;
 }
 
 /** Incoming shutdown envelope. */
@@ -105,6 +108,7 @@ export function parseIncomingMessage(line: string): IncomingMessage | null {
         type: "agent_request",
         request_id: obj.request_id,
         config: parseSidecarConfig(obj.config),
+        ...(obj.concurrent === true ? { concurrent: true } : {}),
       };
     } catch {
       return null;
@@ -150,6 +154,33 @@ export function parseIncomingMessage(line: string): IncomingMessage | null {
   return null;
 }
 
+/** Incoming message types this sidecar handles. */
+const KNOWN_MESSAGE_TYPES = new Set<string>([
+  "agent_request",
+  "shutdown",
+  "ping",
+  "cancel",
+  "stream_start",
+  "stream_message",
+  "stream_end",
+]);
+
+/**
+ * Whether a line is a well-formed envelope whose `type` this sidecar does not
+ * know. A newer minor protocol version may add message types; those are
+ * ignored rather than reported as errors.
+ */
+export function isUnknownMessageType(line: string): boolean {
+  try {
+    const parsed: unknown = JSON.parse(line.trim());
+    if (typeof parsed !== "object" || parsed === null) return false;
+    const type = (parsed as Record<string, unknown>).type;
+    return typeof type === "string" && !KNOWN_MESSAGE_TYPES.has(type);
+  } catch {
+    return false;
+  }
+}
+
 /**
  * Wrap an SDK message with a request_id prefix.
  */
@@ -163,7 +194,7 @@ export function wrapWithRequestId(
 /**
  * Run the sidecar in persistent mode.
  *
- * - Emits `{"type":"sidecar_ready"}` on startup
+ * - Emits `{"type":"sidecar_ready","protocol_version":...}` on startup
  * - Reads stdin line-by-line for `agent_request` and `shutdown` messages
  * - Each `agent_request` runs the SDK and streams responses with `request_id` prefix
  * - `shutdown` causes a clean exit
@@ -177,8 +208,8 @@ export async function runPersistent(
   exitFn: (code: number) => void = (code) => process.exit(code),
 ): Promise<void> {
   // Signal readiness
-  writeLine({ type: "sidecar_ready" });
-  process.stderr.write("[sidecar] Persistent mode ready\n");
+  writeLine({ type: "sidecar_ready", protocol_version: PROTOCOL_VERSION });
+  process.stderr.write(`[sidecar] Persistent mode ready (protocol ${PROTOCOL_VERSION})\n`);
 
   const rl: Interface = createInterface({
     input,
@@ -186,11 +217,10 @@ export async function runPersistent(
   });
 
   // Track in-flight requests so we can wait for them before shutdown.
-  // Also track the current request's AbortController and ID so we can cancel
-  // a stuck request when a new one arrives or Rust sends a cancel message.
+  // Also track each request's AbortController by ID so we can cancel a stuck
+  // request when a new one arrives or Rust sends a cancel message.
   const inFlight = new Set<Promise<void>>();
-  let currentAbort: AbortController | null = null;
-  let currentRequestId: string | null = null;
+  const aborts = new Map<string, AbortController>();
 
   // Active streaming sessions (refine chat uses these for multi-turn conversations)
   const activeSessions = new Map<string, StreamSession>();
@@ -199,6 +229,12 @@ export async function runPersistent(
     const message = parseIncomingMessage(line);
 
     if (!message) {
+      if (isUnknownMessageType(line)) {
+        process.stderr.write(
+          `[sidecar] Ignoring unknown message type (protocol ${PROTOCOL_VERSION}): ${line.trim().substring(0, 200)}\n`,
+        );
+        continue;
+      }
       // Unrecognized input — emit an error line (no request_id since we couldn't parse one)
       writeLine({
         type: "error",
@@ -227,9 +263,7 @@ export async function runPersistent(
       process.stderr.write(`[sidecar] Cancel request for ${message.request_id}\n`);
       // Rust sends cancel when a request times out.
       // Abort the matching in-flight request so the SDK stops waiting.
-      if (currentAbort && currentRequestId === message.request_id) {
-        currentAbort.abort();
-      }
+      aborts.get(message.request_id)?.abort();
       continue;
     }
 
@@ -306,9 +340,12 @@ export async function runPersistent(
 
     if (message.type === "agent_request") {
       // If a previous request is still in-flight (e.g., SDK hanging on API),
-      // abort it before starting the new one.
-      if (inFlight.size > 0 && currentAbort) {
-        currentAbort.abort();
+      // abort it before starting the new one. Concurrent requests are part of
+      // a batch Rust runs side by side, so they leave in-flight ones alone.
+      if (inFlight.size > 0 && aborts.size > 0 && !message.concurrent) {
+        for (const controller of aborts.values()) {
+          controller.abort();
+        }
         // Fire-and-forget: don't block the readline loop while the aborted
         // request tears down. The stdout writer routes by request_id so
         // concurrent requests with different IDs are safe.
@@ -318,8 +355,7 @@ export async function runPersistent(
       const { request_id, config } = message;
       process.stderr.write(`[sidecar] Agent request: ${request_id}\n`);
       const abortController = new AbortController();
-      currentAbort = abortController;
-      currentRequestId = request_id;
+      aborts.set(request_id, abortController);
 
       // Run the agent request without blocking the readline loop.
       // This lets ping/shutdown messages be processed while the agent runs.
@@ -349,9 +385,8 @@ export async function runPersistent(
       inFlight.add(requestPromise);
       requestPromise.finally(() => {
         inFlight.delete(requestPromise);
-        if (currentAbort === abortController) {
-          currentAbort = null;
-          currentRequestId = null;
+        if (aborts.get(request_id) === abortController) {
+          aborts.delete(request_id);
         }
       });
     }
@@ -0,0 +1,10 @@
+/**
+ * Wire protocol version announced to the Rust host in `sidecar_ready`.
+ *
+ * Bump MINOR for additive changes (new message types or optional fields) that
+ * an older peer can safely ignore; bump MAJOR for anything else. The host
+ * rejects a sidecar whose MAJOR differs from its own.
+ *
+ * Must match `PROTOCOL_VERSION` in `src-tauri/src/agents/protocol.rs`.
+ */
+export const PROTOCOL_VERSION = "1.1";
@@ -67,7 +67,8 @@ export async function runAgentRequest(
     onMessage({ type: "system", subtype: "sdk_stderr", data: data.trimEnd(), timestamp: Date.now() });
   };
 
-  const options = buildQueryOptions(config, state.abortController, pluginPaths, stderrHandler);
+  // Bash sandbox decisions go to the transcript alongside the SDK messages.
+  const options = buildQueryOptions(config, state.abortController, pluginPaths, stderrHandler, onMessage);
 
   // Emit plugins passed to the SDK as a system event so it appears in the JSONL transcript.
   const pluginsToLog = (options as Record<string, unknown>).plugins as unknown[] | undefined;
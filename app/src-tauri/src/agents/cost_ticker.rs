@@ -0,0 +1,480 @@
+//! Live cost ticker for running agents.
+//!
+//! Assistant messages carry per-turn token usage, but the SDK only reports cost
+//! in the final `result`. The ticker accumulates usage as messages pass through
+//! `handle_sidecar_message`, emits cumulative `agent-cost-tick` events every few
+//! seconds or tokens, and snapshots each tick to `agent_run_ticks` so a crash
+//! mid-step leaves approximate usage instead of nothing. Startup reconciliation
+//! turns snapshots from dead processes into `shutdown` runs.
+//!
+//! Each tick is also checked against the per-skill and global budgets. An
+//! agent that crosses one is cancelled in its sidecar, its workflow step is
+//! marked `budget_exceeded`, and `agent-budget-exceeded` is emitted. Refine
+//! sessions count toward spend but are not cut off mid-turn.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tauri::{Emitter, Manager};
+
+use crate::db::Db;
+use crate::types::{AgentCostTick, BudgetStatus};
+
+/// Emit a tick at least this often while usage is changing.
+const TICK_INTERVAL: Duration = Duration::from_secs(5);
+/// ...or as soon as this many tokens have accumulated since the last tick.
+const TICK_TOKENS: i64 = 10_000;
+
+/// Step ids used for usage attribution outside the workflow (see agent-store).
+pub(crate) const REFINE_STEP_ID: i32 = -10;
+const UNATTRIBUTED_STEP_ID: i32 = -1;
+
+const UNKNOWN_MODEL: &str = "unknown";
+
+/// Tickers for in-flight agents, keyed by agent id.
+static TICKERS: Mutex<BTreeMap<String, Ticker>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Usage {
+    input: i64,
+    output: i64,
+    cache_read: i64,
+    cache_write: i64,
+}
+
+impl Usage {
+    fn from_json(usage: &serde_json::Value) -> Self {
+        let field = |k: &str| usage.get(k).and_then(|v| v.as_i64()).unwrap_or(0);
+        Self {
+            input: field("input_tokens"),
+            output: field("output_tokens"),
+            cache_read: field("cache_read_input_tokens"),
+            cache_write: field("cache_creation_input_tokens"),
+        }
+    }
+
+    fn total(&self) -> i64 {
+        self.input + self.output + self.cache_read + self.cache_write
+    }
+
+    fn add(&mut self, other: Usage, sign: i64) {
+        self.input += sign * other.input;
+        self.output += sign * other.output;
+        self.cache_read += sign * other.cache_read;
+        self.cache_write += sign * other.cache_write;
+    }
+}
+
+/// List price estimate in USD. Cache reads bill at 0.1x input, writes at 1.25x.
+pub(crate) fn list_price(model: &str, input: i64, output: i64, cache_read: i64, cache_write: i64) -> f64 {
+    let lower = model.to_lowercase();
+    let (input_rate, output_rate) = if lower.contains("opus") {
+        (5.0, 25.0)
+    } else if lower.contains("haiku") {
+        (1.0, 5.0)
+    } else {
+        (3.0, 15.0)
+    };
+    (input as f64 * input_rate
+        + output as f64 * output_rate
+        + cache_read as f64 * input_rate * 0.1
+        + cache_write as f64 * input_rate * 1.25)
+        / 1_000_000.0
+}
+
+fn estimate_cost(model: &str, usage: &Usage) -> f64 {
+    list_price(model, usage.input, usage.output, usage.cache_read, usage.cache_write)
+}
+
+/// Step id for an agent id: `{skill}-step{N}-{ts}` for workflow steps,
+/// `refine-{skill}-{ts}` for refine turns, unattributed otherwise.
+pub(crate) fn infer_step_id(agent_id: &str, skill_name: &str) -> i32 {
+    if agent_id.starts_with("refine-") {
+        return REFINE_STEP_ID;
+    }
+    agent_id
+        .strip_prefix(skill_name)
+        .and_then(|rest| rest.strip_prefix("-step"))
+        .and_then(|rest| rest.split('-').next())
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(UNATTRIBUTED_STEP_ID)
+}
+
+#[derive(Debug)]
+struct Ticker {
+    skill_name: String,
+    model: String,
+    step_id: i32,
+    workflow_session_id: Option<String>,
+    started: Instant,
+    last_tick: Option<Instant>,
+    tokens_at_last_tick: i64,
+    usage: Usage,
+    /// The SDK repeats a message's usage on every content block, so only the
+    /// latest figure per message id counts.
+    current_message: Option<(String, Usage)>,
+    num_turns: i32,
+    reported_cost: Option<f64>,
+    /// Set once the agent has been stopped for crossing a budget.
+    budget_stopped: bool,
+    /// Runs on a local model server have no price; ticks report zero cost.
+    unpriced: bool,
+}
+
+impl Ticker {
+    fn new(
+        skill_name: &str,
+        model: &str,
+        step_id: i32,
+        workflow_session_id: Option<String>,
+        now: Instant,
+    ) -> Self {
+        Self {
+            skill_name: skill_name.to_string(),
+            model: model.to_string(),
+            step_id,
+            workflow_session_id,
+            started: now,
+            last_tick: None,
+            tokens_at_last_tick: 0,
+            usage: Usage::default(),
+            current_message: None,
+            num_turns: 0,
+            reported_cost: None,
+            budget_stopped: false,
+            unpriced: false,
+        }
+    }
+
+    fn cost(&self) -> f64 {
+        if self.unpriced {
+            return 0.0;
+        }
+        self.reported_cost
+            .unwrap_or_else(|| estimate_cost(&self.model, &self.usage))
+    }
+
+    /// Fold one sidecar message into the running totals. Returns true when a
+    /// tick should be emitted now.
+    fn record(&mut self, message: &serde_json::Value, now: Instant) -> bool {
+        match message.get("type").and_then(|t| t.as_str()) {
+            Some("assistant") => {
+                let Some(inner) = message.get("message") else {
+                    return false;
+                };
+                let Some(usage) = inner.get("usage").map(Usage::from_json) else {
+                    return false;
+                };
+                // Follow-up turns of a streaming session start without a model.
+                if self.model == UNKNOWN_MODEL {
+                    if let Some(model) = inner.get("model").and_then(|m| m.as_str()) {
+                        self.model = model.to_string();
+                    }
+                }
+                let id = inner
+                    .get("id")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                match self.current_message.take() {
+                    Some((prev_id, prev)) if !id.is_empty() && prev_id == id => {
+                        self.usage.add(prev, -1);
+                    }
+                    _ => self.num_turns += 1,
+                }
+                self.usage.add(usage, 1);
+                self.current_message = Some((id, usage));
+                self.due(now)
+            }
+            Some("result") => {
+                if let Some(cost) = message.get("total_cost_usd").and_then(|c| c.as_f64()) {
+                    self.reported_cost = Some(cost);
+                }
+                if let Some(turns) = message.get("num_turns").and_then(|n| n.as_i64()) {
+                    self.num_turns = turns as i32;
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn due(&self, now: Instant) -> bool {
+        match self.last_tick {
+            None => true,
+            Some(last) => {
+                now.duration_since(last) >= TICK_INTERVAL
+                    || self.usage.total() - self.tokens_at_last_tick >= TICK_TOKENS
+            }
+        }
+    }
+
+    fn tick(&mut self, agent_id: &str, now: Instant) -> AgentCostTick {
+        self.last_tick = Some(now);
+        self.tokens_at_last_tick = self.usage.total();
+        AgentCostTick {
+            agent_id: agent_id.to_string(),
+            skill_name: self.skill_name.clone(),
+            step_id: self.step_id,
+            model: self.model.clone(),
+            input_tokens: self.usage.input as i32,
+            output_tokens: self.usage.output as i32,
+            cache_read_tokens: self.usage.cache_read as i32,
+            cache_write_tokens: self.usage.cache_write as i32,
+            total_cost: self.cost(),
+            estimated: self.reported_cost.is_none(),
+            num_turns: self.num_turns,
+            elapsed_ms: now.duration_since(self.started).as_millis() as i64,
+            workflow_session_id: self.workflow_session_id.clone(),
+        }
+    }
+}
+
+/// Start tracking an agent. Called when its request is sent to the sidecar.
+pub fn start(app_handle: &tauri::AppHandle, agent_id: &str, skill_name: &str, model: Option<&str>) {
+    let step_id = infer_step_id(agent_id, skill_name);
+    let workflow_session_id = if step_id == REFINE_STEP_ID {
+        Some(format!("synthetic:refine:{}:{}", skill_name, agent_id))
+    } else if step_id >= 0 {
+        app_handle.try_state::<Db>().and_then(|db| {
+            let conn = db.0.lock().ok()?;
+            crate::db::open_workflow_session_for_pid(&conn, skill_name, std::process::id())
+                .unwrap_or_else(|e| {
+                    log::warn!("[cost_ticker] Failed to look up workflow session: {}", e);
+                    None
+                })
+        })
+    } else {
+        None
+    };
+    let mut ticker = Ticker::new(
+        skill_name,
+        model.unwrap_or(UNKNOWN_MODEL),
+        step_id,
+        workflow_session_id,
+        Instant::now(),
+    );
+    ticker.unpriced = crate::commands::offline::runs_unpriced(app_handle);
+    if let Ok(mut tickers) = TICKERS.lock() {
+        tickers.insert(agent_id.to_string(), ticker);
+    }
+}
+
+/// Fold a sidecar message into the agent's ticker, emitting and snapshotting a
+/// tick when one is due.
+pub fn observe(app_handle: &tauri::AppHandle, agent_id: &str, message: &serde_json::Value) {
+    let tick = {
+        let Ok(mut tickers) = TICKERS.lock() else {
+            return;
+        };
+        let Some(ticker) = tickers.get_mut(agent_id) else {
+            return;
+        };
+        let now = Instant::now();
+        if !ticker.record(message, now) {
+            return;
+        }
+        ticker.tick(agent_id, now)
+    };
+
+    if let Some(db) = app_handle.try_state::<Db>() {
+        match db.0.lock() {
+            Ok(conn) => {
+                if let Err(e) = crate::db::save_agent_run_tick(&conn, &tick) {
+                    log::warn!("[cost_ticker] Failed to save tick for {}: {}", agent_id, e);
+                }
+                enforce_budget(app_handle, &conn, &tick);
+            }
+            Err(e) => log::warn!("[cost_ticker] Failed to acquire DB lock: {}", e),
+        }
+    }
+    if let Err(e) = app_handle.emit("agent-cost-tick", &tick) {
+        log::warn!("Failed to emit agent-cost-tick for {}: {}", agent_id, e);
+    }
+}
+
+/// Current cost of running agents, for one skill or all. Their usage is not
+/// in `agent_runs` until they finish.
+pub fn live_cost(skill_name: Option<&str>) -> f64 {
+    let Ok(tickers) = TICKERS.lock() else {
+        return 0.0;
+    };
+    tickers
+        .values()
+        .filter(|t| skill_name.is_none_or(|s| t.skill_name == s))
+        .map(Ticker::cost)
+        .sum()
+}
+
+/// Payload of `agent-budget-exceeded`.
+#[derive(Debug, Clone, serde::Serialize)]
+struct BudgetExceeded {
+    agent_id: String,
+    step_id: i32,
+    status: BudgetStatus,
+}
+
+/// Stop the agent behind `tick` if its skill or the global budget is spent.
+fn enforce_budget(app_handle: &tauri::AppHandle, conn: &rusqlite::Connection, tick: &AgentCostTick) {
+    if tick.step_id == REFINE_STEP_ID {
+        return;
+    }
+    let status = match crate::commands::usage::budget_status(conn, Some(&tick.skill_name)) {
+        Ok(status) if status.state == "exceeded" => status,
+        Ok(_) => return,
+        Err(e) => {
+            log::warn!("[cost_ticker] Failed to check budget for {}: {}", tick.agent_id, e);
+            return;
+        }
+    };
+    let first = TICKERS
+        .lock()
+        .ok()
+        .and_then(|mut tickers| tickers.get_mut(&tick.agent_id).map(|t| !std::mem::replace(&mut t.budget_stopped, true)))
+        .unwrap_or(false);
+    if !first {
+        return;
+    }
+    let reason = match status.exceeded_scope.as_deref() {
+        Some("skill") => format!(
+            "Stopped: '{}' reached its ${:.2} budget (${:.2} spent)",
+            tick.skill_name,
+            status.skill_budget_usd.unwrap_or_default(),
+            status.skill_spent_usd
+        ),
+        _ => format!(
+            "Stopped: the global ${:.2} budget was reached (${:.2} spent)",
+            status.global_budget_usd.unwrap_or_default(),
+            status.global_spent_usd
+        ),
+    };
+    log::warn!("[cost_ticker] {} — {}", tick.agent_id, reason);
+    let (spent_usd, budget_usd) = match status.exceeded_scope.as_deref() {
+        Some("skill") => (status.skill_spent_usd, status.skill_budget_usd.unwrap_or_default()),
+        _ => (status.global_spent_usd, status.global_budget_usd.unwrap_or_default()),
+    };
+    crate::automation_hooks::publish(crate::automation_hooks::HookEvent::BudgetExceeded {
+        skill_name: tick.skill_name.clone(),
+        scope: status.exceeded_scope.clone().unwrap_or_else(|| "global".to_string()),
+        spent_usd,
+        budget_usd,
+    });
+    if tick.step_id >= 0 {
+        if let Err(e) = crate::db::save_workflow_step(conn, &tick.skill_name, tick.step_id, "budget_exceeded") {
+            log::warn!("[cost_ticker] Failed to mark step {} budget_exceeded: {}", tick.step_id, e);
+        }
+    }
+    let payload = BudgetExceeded {
+        agent_id: tick.agent_id.clone(),
+        step_id: tick.step_id,
+        status,
+    };
+    if let Err(e) = app_handle.emit("agent-budget-exceeded", &payload) {
+        log::warn!("Failed to emit agent-budget-exceeded for {}: {}", tick.agent_id, e);
+    }
+    if let Some(pool) = app_handle.try_state::<crate::agents::sidecar_pool::SidecarPool>() {
+        let pool = pool.inner().clone();
+        let app_handle = app_handle.clone();
+        let agent_id = tick.agent_id.clone();
+        tauri::async_runtime::spawn(async move {
+            let message = serde_json::json!({"type": "error", "subtype": "budget_exceeded", "error": reason});
+            if let Err(e) = pool.cancel_request(&agent_id, message, &app_handle).await {
+                log::error!("[cost_ticker] Failed to stop {} over budget: {}", agent_id, e);
+            }
+        });
+    }
+}
+
+/// Stop tracking an agent once it has exited or shut down.
+pub fn finish(agent_id: &str) {
+    if let Ok(mut tickers) = TICKERS.lock() {
+        tickers.remove(agent_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn assistant(id: &str, input: i64, output: i64) -> serde_json::Value {
+        json!({
+            "type": "assistant",
+            "message": {
+                "id": id,
+                "usage": {
+                    "input_tokens": input,
+                    "output_tokens": output,
+                    "cache_read_input_tokens": 0,
+                    "cache_creation_input_tokens": 0
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_infer_step_id() {
+        assert_eq!(infer_step_id("my-skill-step3-1700000000000", "my-skill"), 3);
+        assert_eq!(infer_step_id("refine-my-skill-1700000000000", "my-skill"), REFINE_STEP_ID);
+        assert_eq!(infer_step_id("my-skill-gate-eval-1700000000000", "my-skill"), UNATTRIBUTED_STEP_ID);
+    }
+
+    #[test]
+    fn test_record_counts_repeated_message_usage_once() {
+        let start = Instant::now();
+        let mut ticker = Ticker::new("s", "claude-sonnet-4-6", 0, None, start);
+        assert!(ticker.record(&assistant("m1", 1000, 10), start));
+        let t = ticker.tick("a", start);
+
+        // Same message id (another content block): usage replaces, not adds.
+        assert!(!ticker.record(&assistant("m1", 1000, 50), start));
+        assert!(!ticker.record(&assistant("m2", 2000, 40), start));
+        assert_eq!(ticker.usage.input, 3000);
+        assert_eq!(ticker.usage.output, 90);
+        assert_eq!(ticker.num_turns, 2);
+        assert!(t.estimated);
+        assert!((t.total_cost - (1000.0 * 3.0 + 10.0 * 15.0) / 1_000_000.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_tick_due_on_interval_or_tokens() {
+        let start = Instant::now();
+        let mut ticker = Ticker::new("s", "sonnet", 0, None, start);
+        ticker.record(&assistant("m1", 100, 10), start);
+        ticker.tick("a", start);
+
+        assert!(!ticker.record(&assistant("m2", 100, 10), start + Duration::from_secs(1)));
+        assert!(ticker.record(&assistant("m3", TICK_TOKENS, 0), start + Duration::from_secs(2)));
+        ticker.tick("a", start + Duration::from_secs(2));
+        assert!(ticker.record(&assistant("m4", 1, 1), start + Duration::from_secs(8)));
+    }
+
+    #[test]
+    fn test_result_reports_actual_cost() {
+        let start = Instant::now();
+        let mut ticker = Ticker::new("s", "sonnet", 0, None, start);
+        ticker.record(&assistant("m1", 100, 10), start);
+        assert!(ticker.record(
+            &json!({"type": "result", "total_cost_usd": 0.42, "num_turns": 3}),
+            start
+        ));
+        let t = ticker.tick("a", start + Duration::from_millis(1500));
+        assert!(!t.estimated);
+        assert!((t.total_cost - 0.42).abs() < 1e-12);
+        assert_eq!(t.num_turns, 3);
+        assert_eq!(t.elapsed_ms, 1500);
+    }
+
+    #[test]
+    fn test_unpriced_run_reports_zero_cost() {
+        let start = Instant::now();
+        let mut ticker = Ticker::new("s", "qwen3-coder", 0, None, start);
+        ticker.unpriced = true;
+        ticker.record(&assistant("m1", 1000, 100), start);
+        ticker.record(&json!({"type": "result", "total_cost_usd": 0.42}), start);
+        let t = ticker.tick("a", start);
+        assert_eq!(t.input_tokens, 1000);
+        assert!(t.total_cost.abs() < f64::EPSILON);
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use tauri::Emitter;
 
+use super::sidecar::RetryPlan;
 use super::sidecar_pool::SidecarStartupError;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,9 +26,26 @@ pub struct AgentInitError {
     pub fix_hint: String,
 }
 
+/// Payload for `agent-retry`, sent when a run failed with a transient error
+/// and will be sent again after `delay_ms`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentRetry {
+    pub agent_id: String,
+    pub attempt: u32,
+    pub max_retries: u32,
+    pub error_class: String,
+    pub message: String,
+    pub delay_ms: u64,
+    pub resumed: bool,
+}
+
 pub fn handle_sidecar_message(app_handle: &tauri::AppHandle, agent_id: &str, line: &str) {
     match serde_json::from_str::<serde_json::Value>(line) {
         Ok(message) => {
+            // The SDK's init message already carries the session id to resume.
+            super::run_checkpoint::observe(app_handle, agent_id, &message);
+            super::sidecar::observe_for_retry(agent_id, &message);
+
             // Detect system init progress events and emit on a dedicated channel
             if message.get("type").and_then(|t| t.as_str()) == Some("system") {
                 if let Some(subtype) = message.get("subtype").and_then(|s| s.as_str()) {
@@ -51,6 +69,13 @@ pub fn handle_sidecar_message(app_handle: &tauri::AppHandle, agent_id: &str, lin
                 }
             }
 
+            super::cost_ticker::observe(app_handle, agent_id, &message);
+            super::tool_audit::observe(app_handle, agent_id, &message);
+            super::step_progress::observe(app_handle, agent_id, &message);
+            if agent_id.starts_with("refine-") {
+                crate::commands::refine::observe_refine_message(app_handle, agent_id, &message);
+            }
+
             let event = AgentEvent {
                 agent_id: agent_id.to_string(),
                 message,
@@ -69,6 +94,11 @@ pub fn handle_sidecar_message(app_handle: &tauri::AppHandle, agent_id: &str, lin
 
 pub fn handle_sidecar_exit(app_handle: &tauri::AppHandle, agent_id: &str, success: bool) {
     log::info!("[event:agent-exit:{}] success={}", agent_id, success);
+    super::cost_ticker::finish(agent_id);
+    super::tool_audit::finish(agent_id);
+    super::step_progress::finish(agent_id);
+    super::run_checkpoint::finish(app_handle, agent_id, success);
+    super::sidecar::finish_retries(agent_id);
     if let Err(e) = app_handle.emit(
         "agent-exit",
         serde_json::json!({
@@ -85,6 +115,11 @@ pub fn handle_sidecar_exit(app_handle: &tauri::AppHandle, agent_id: &str, succes
 
 pub fn handle_agent_shutdown(app_handle: &tauri::AppHandle, agent_id: &str) {
     log::info!("[event:agent-shutdown:{}]", agent_id);
+    super::cost_ticker::finish(agent_id);
+    super::tool_audit::finish(agent_id);
+    super::step_progress::finish(agent_id);
+    super::run_checkpoint::finish(app_handle, agent_id, false);
+    super::sidecar::finish_retries(agent_id);
     if let Err(e) = app_handle.emit(
         "agent-shutdown",
         serde_json::json!({
@@ -95,6 +130,25 @@ pub fn handle_agent_shutdown(app_handle: &tauri::AppHandle, agent_id: &str) {
     }
 }
 
+pub fn emit_retry(app_handle: &tauri::AppHandle, agent_id: &str, plan: &RetryPlan) {
+    let payload = AgentRetry {
+        agent_id: agent_id.to_string(),
+        attempt: plan.attempt,
+        max_retries: super::sidecar::MAX_RETRIES,
+        error_class: plan.error_class.as_str().to_string(),
+        message: plan.detail.clone(),
+        delay_ms: plan.delay.as_millis() as u64,
+        resumed: plan.resumed,
+    };
+    log::info!(
+        "[event:agent-retry:{}] attempt={} class={} delay_ms={}",
+        agent_id, payload.attempt, payload.error_class, payload.delay_ms
+    );
+    if let Err(e) = app_handle.emit("agent-retry", &payload) {
+        log::warn!("Failed to emit agent-retry for {}: {}", agent_id, e);
+    }
+}
+
 /// Emit a structured error event when sidecar startup fails.
 /// The frontend listens for `agent-init-error` to show an actionable dialog.
 pub fn emit_init_error(app_handle: &tauri::AppHandle, error: &SidecarStartupError) {
@@ -1,3 +1,8 @@
+pub mod cost_ticker;
 pub mod events;
+pub mod protocol;
+pub mod run_checkpoint;
 pub mod sidecar;
 pub mod sidecar_pool;
+pub mod step_progress;
+pub mod tool_audit;
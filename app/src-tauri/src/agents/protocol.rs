@@ -0,0 +1,116 @@
+//! Wire protocol version negotiated with the Node sidecar at startup.
+//!
+//! The sidecar announces `{"type":"sidecar_ready","protocol_version":"MAJOR.MINOR"}`
+//! as its first stdout line. A different MAJOR means the message shapes have
+//! changed incompatibly and the sidecar is rejected with a structured error.
+//! MINOR drift is tolerated in both directions: each side ignores message types
+//! and fields it does not recognise, so additive changes only bump MINOR.
+//!
+//! Must match `PROTOCOL_VERSION` in `sidecar/protocol.ts`.
+
+use super::sidecar_pool::SidecarStartupError;
+use std::fmt;
+
+/// Protocol version spoken by this build of the app.
+pub const PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion { major: 1, minor: 1 };
+
+/// First version whose sidecar runs `concurrent` agent requests side by side
+/// instead of aborting the one in flight.
+pub const CONCURRENT_REQUESTS_SINCE: ProtocolVersion = ProtocolVersion { major: 1, minor: 1 };
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ProtocolVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl ProtocolVersion {
+    /// Parse `"MAJOR.MINOR"`. A bare `"MAJOR"` is read as `MAJOR.0`.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let mut parts = raw.trim().split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = match parts.next() {
+            Some(m) => m.parse().ok()?,
+            None => 0,
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self { major, minor })
+    }
+}
+
+impl fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// Check the version announced in a `sidecar_ready` message against ours.
+/// Returns the sidecar's version when the two can talk to each other.
+pub fn negotiate(ready: &serde_json::Value) -> Result<ProtocolVersion, SidecarStartupError> {
+    let announced = ready.get("protocol_version").and_then(|v| v.as_str());
+    let incompatible = |found: &str| SidecarStartupError::ProtocolIncompatible {
+        found: found.to_string(),
+        required: format!("{}.x", PROTOCOL_VERSION.major),
+    };
+
+    let Some(raw) = announced else {
+        // Sidecars built before the handshake existed send a bare ready signal.
+        return Err(incompatible("none"));
+    };
+    let version = ProtocolVersion::parse(raw).ok_or_else(|| incompatible(raw))?;
+    if version.major != PROTOCOL_VERSION.major {
+        return Err(incompatible(raw));
+    }
+
+    if version.minor != PROTOCOL_VERSION.minor {
+        log::warn!(
+            "[protocol] sidecar speaks {} and app speaks {} — minor drift, unknown messages will be ignored",
+            version,
+            PROTOCOL_VERSION
+        );
+    }
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(ProtocolVersion::parse("1.2"), Some(ProtocolVersion { major: 1, minor: 2 }));
+        assert_eq!(ProtocolVersion::parse("3"), Some(ProtocolVersion { major: 3, minor: 0 }));
+        assert_eq!(ProtocolVersion::parse("1.2.3"), None);
+        assert_eq!(ProtocolVersion::parse("one"), None);
+        assert_eq!(ProtocolVersion::parse(""), None);
+        assert!(ProtocolVersion::parse("1.0").unwrap() < CONCURRENT_REQUESTS_SINCE);
+        assert!(ProtocolVersion::parse("2.0").unwrap() > ProtocolVersion::parse("1.9").unwrap());
+    }
+
+    #[test]
+    fn test_negotiate_tolerates_minor_drift() {
+        let same = json!({"type": "sidecar_ready", "protocol_version": PROTOCOL_VERSION.to_string()});
+        assert_eq!(negotiate(&same).unwrap(), PROTOCOL_VERSION);
+
+        let newer_minor = format!("{}.{}", PROTOCOL_VERSION.major, PROTOCOL_VERSION.minor + 1);
+        let ready = json!({"type": "sidecar_ready", "protocol_version": newer_minor});
+        assert_eq!(negotiate(&ready).unwrap().minor, PROTOCOL_VERSION.minor + 1);
+    }
+
+    #[test]
+    fn test_negotiate_rejects_major_mismatch_and_legacy_ready() {
+        let newer_major = format!("{}.0", PROTOCOL_VERSION.major + 1);
+        let err = negotiate(&json!({"type": "sidecar_ready", "protocol_version": newer_major})).unwrap_err();
+        assert_eq!(err.error_type(), "protocol_incompatible");
+        assert!(err.message().contains(&newer_major));
+
+        let err = negotiate(&json!({"type": "sidecar_ready"})).unwrap_err();
+        assert_eq!(err.error_type(), "protocol_incompatible");
+        assert!(err.fix_hint().contains("sidecar:build"));
+
+        assert!(negotiate(&json!({"type": "sidecar_ready", "protocol_version": "garbage"})).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+//! Checkpoints of workflow step runs, for resuming after a crash or quit.
+//!
+//! `run_workflow_step` registers each step run here and in
+//! `agent_run_checkpoints`. As the run's messages pass through
+//! `handle_sidecar_message`, the SDK session id (carried on every SDK message)
+//! and the count of top-level assistant turns are recorded, and the sidecar
+//! pool records where the run's JSONL transcript is being written. A run that
+//! ends without a successful result, or is cut off by a sidecar shutdown, is
+//! marked interrupted; one still marked running after its app instance died
+//! was killed outright. Either way `resume_workflow_step` continues it in the
+//! same SDK session, which restores the conversation so far instead of paying
+//! for the whole step again.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use tauri::Manager;
+
+use crate::db::Db;
+
+#[derive(Debug, Default)]
+struct Checkpoint {
+    session_recorded: bool,
+    turns: u32,
+}
+
+/// Step runs being checkpointed, keyed by agent id.
+static RUNS: Mutex<BTreeMap<String, Checkpoint>> = Mutex::new(BTreeMap::new());
+
+/// Start tracking a step run whose `agent_run_checkpoints` row was just created.
+pub fn start(agent_id: &str) {
+    if let Ok(mut runs) = RUNS.lock() {
+        runs.insert(agent_id.to_string(), Checkpoint::default());
+    }
+}
+
+fn is_tracked(agent_id: &str) -> bool {
+    RUNS.lock().is_ok_and(|runs| runs.contains_key(agent_id))
+}
+
+fn sdk_session_id(message: &serde_json::Value) -> Option<&str> {
+    message
+        .get("session_id")
+        .and_then(|s| s.as_str())
+        .filter(|s| !s.is_empty())
+}
+
+/// An assistant message from the run's own agent, not one of its sub-agents.
+fn is_top_level_turn(message: &serde_json::Value) -> bool {
+    message.get("type").and_then(|t| t.as_str()) == Some("assistant")
+        && !message.get("parent_tool_use_id").is_some_and(|id| id.is_string())
+}
+
+fn with_conn(app_handle: &tauri::AppHandle, f: impl FnOnce(&rusqlite::Connection) -> Result<(), String>) {
+    let Some(db) = app_handle.try_state::<Db>() else {
+        return;
+    };
+    match db.0.lock() {
+        Ok(conn) => {
+            if let Err(e) = f(&conn) {
+                log::warn!("[run_checkpoint] Failed to update checkpoint: {}", e);
+            }
+        }
+        Err(e) => log::warn!("[run_checkpoint] Failed to acquire DB lock: {}", e),
+    }
+}
+
+/// Record the SDK session and turn count carried by a sidecar message, if the
+/// agent is a checkpointed step run.
+pub fn observe(app_handle: &tauri::AppHandle, agent_id: &str, message: &serde_json::Value) {
+    let (session, turns) = {
+        let Ok(mut runs) = RUNS.lock() else {
+            return;
+        };
+        let Some(run) = runs.get_mut(agent_id) else {
+            return;
+        };
+        let session = sdk_session_id(message).filter(|_| !run.session_recorded);
+        run.session_recorded |= session.is_some();
+        let turns = is_top_level_turn(message).then(|| {
+            run.turns += 1;
+            run.turns
+        });
+        (session, turns)
+    };
+    if session.is_none() && turns.is_none() {
+        return;
+    }
+    with_conn(app_handle, |conn| {
+        if let Some(session) = session {
+            crate::db::set_checkpoint_sdk_session(conn, agent_id, session)?;
+        }
+        if let Some(turns) = turns {
+            crate::db::set_checkpoint_turns(conn, agent_id, turns)?;
+        }
+        Ok(())
+    });
+}
+
+/// Record where a checkpointed run's transcript is written.
+pub fn set_transcript(app_handle: &tauri::AppHandle, agent_id: &str, path: &Path) {
+    if !is_tracked(agent_id) {
+        return;
+    }
+    let path = path.to_string_lossy();
+    with_conn(app_handle, |conn| crate::db::set_checkpoint_transcript(conn, agent_id, &path));
+}
+
+/// Close a checkpointed run: "completed" after a successful result, otherwise
+/// "interrupted" so it can be resumed.
+pub fn finish(app_handle: &tauri::AppHandle, agent_id: &str, success: bool) {
+    let tracked = RUNS.lock().is_ok_and(|mut runs| runs.remove(agent_id).is_some());
+    if !tracked {
+        return;
+    }
+    let status = if success { "completed" } else { "interrupted" };
+    log::info!("[run_checkpoint] {} {}", agent_id, status);
+    with_conn(app_handle, |conn| crate::db::set_checkpoint_status(conn, agent_id, status));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_session_and_turns_come_from_top_level_messages() {
+        let init = json!({"type": "system", "subtype": "init", "session_id": "sdk-1"});
+        let turn = json!({"type": "assistant", "session_id": "sdk-1", "parent_tool_use_id": null,
+                          "message": {"content": []}});
+        let subagent = json!({"type": "assistant", "session_id": "sdk-1", "parent_tool_use_id": "t1",
+                              "message": {"content": []}});
+        assert_eq!(sdk_session_id(&init), Some("sdk-1"));
+        assert_eq!(sdk_session_id(&json!({"type": "config", "session_id": ""})), None);
+        assert!(!is_top_level_turn(&init));
+        assert!(is_top_level_turn(&turn));
+        assert!(!is_top_level_turn(&subagent));
+    }
+}
@@ -1,3 +1,6 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -40,6 +43,41 @@ pub struct SidecarConfig {
         skip_serializing_if = "Option::is_none"
     )]
     pub conversation_history: Option<Vec<serde_json::Value>>,
+    /// SDK session to continue, e.g. a refine conversation interrupted by a crash.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resume: Option<String>,
+    #[serde(rename = "bashSandbox", skip_serializing_if = "Option::is_none")]
+    pub bash_sandbox: Option<BashSandboxConfig>,
+    /// Tools removed from the session entirely. `allowed_tools` alone does not
+    /// stop a tool under `bypassPermissions`, so tool policy trims land here too.
+    #[serde(rename = "disallowedTools", skip_serializing_if = "Option::is_none")]
+    pub disallowed_tools: Option<Vec<String>>,
+    /// Locally hosted model server to run against instead of the Anthropic
+    /// API. Set by the sidecar pool when offline mode is on.
+    #[serde(rename = "localModel", skip_serializing_if = "Option::is_none")]
+    pub local_model: Option<LocalModelTarget>,
+}
+
+/// Bash restrictions enforced by the sidecar's PreToolUse hook. Paths in
+/// commands must resolve inside one of `allowed_dirs`, and commands start in
+/// `cwd` rather than the agent's working directory.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BashSandboxConfig {
+    pub allow_patterns: Vec<String>,
+    pub deny_patterns: Vec<String>,
+    pub allow_network: bool,
+    pub allowed_dirs: Vec<String>,
+    #[serde(default)]
+    pub cwd: String,
+}
+
+/// Base URL and model name of a locally hosted, Anthropic-compatible server.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModelTarget {
+    pub base_url: String,
+    pub model: String,
 }
 
 impl std::fmt::Debug for SidecarConfig {
@@ -60,6 +98,9 @@ impl std::fmt::Debug for SidecarConfig {
             .field("prompt_suggestions", &self.prompt_suggestions)
             .field("agent_name", &self.agent_name)
             .field("required_plugins", &self.required_plugins)
+            .field("bash_sandbox", &self.bash_sandbox)
+            .field("disallowed_tools", &self.disallowed_tools)
+            .field("local_model", &self.local_model)
             .finish()
     }
 }
@@ -89,6 +130,390 @@ pub async fn spawn_sidecar(
     Ok(())
 }
 
+/// Run independent agents for one skill side by side through its persistent
+/// sidecar, at most the `max_concurrent_agents` setting at once, and wait for
+/// all of them.
+///
+/// Their messages never reach the frontend's usage tracking, so each run's
+/// usage is recorded from its `result` message here, all under
+/// `workflow_session_id` so the batch shows as one session in Usage.
+pub async fn run_concurrent(
+    tasks: Vec<super::sidecar_pool::ConcurrentTask>,
+    pool: &super::sidecar_pool::SidecarPool,
+    app_handle: &tauri::AppHandle,
+    skill_name: &str,
+    step_id: i32,
+    workflow_session_id: &str,
+) -> Result<Vec<super::sidecar_pool::ConcurrentOutcome>, String> {
+    use tauri::Manager;
+
+    let db = app_handle.state::<crate::db::Db>();
+    let max_concurrent = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        crate::db::read_settings(&conn)?.max_concurrent_agents
+    };
+    let cli_path = resolve_sdk_cli_path(app_handle).ok();
+    let mut models = std::collections::HashMap::new();
+    let tasks: Vec<_> = tasks
+        .into_iter()
+        .map(|mut task| {
+            if task.config.path_to_claude_code_executable.is_none() {
+                task.config.path_to_claude_code_executable = cli_path.clone();
+            }
+            models.insert(task.agent_id.clone(), task.config.model.clone().unwrap_or_default());
+            task
+        })
+        .collect();
+
+    let outcomes = pool
+        .run_concurrent(skill_name, tasks, max_concurrent as usize, app_handle)
+        .await;
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    for outcome in &outcomes {
+        let model = models.get(&outcome.agent_id).map(String::as_str).unwrap_or_default();
+        if let Err(e) = crate::commands::usage::record_result_usage(
+            &conn,
+            &outcome.agent_id,
+            skill_name,
+            step_id,
+            model,
+            workflow_session_id,
+            &outcome.messages,
+        ) {
+            log::warn!("[run_concurrent] Failed to record usage for {}: {}", outcome.agent_id, e);
+        }
+    }
+    Ok(outcomes)
+}
+
+// ─── Retry policy ────────────────────────────────────────────────────────────
+//
+// A run that fails with a transient API error (rate limited, overloaded, or a
+// dropped connection) is sent again after an exponential backoff with jitter
+// instead of failing its step. The failed attempt's terminal message is kept
+// from the frontend, which sees an `agent-retry` event and then the resent
+// run's messages under the same agent id. A run that got past its first turn
+// resumes its SDK session, so finished work is not paid for twice.
+// Authentication errors, and any error once the retries are used up, fail the
+// run as before.
+
+/// Resends after the first attempt before a transient failure fails the run.
+pub const MAX_RETRIES: u32 = 3;
+/// First backoff for rate limits; the API asks for longer waits there.
+const RATE_LIMIT_BASE_DELAY_MS: u64 = 10_000;
+/// First backoff for the other transient errors.
+const BASE_DELAY_MS: u64 = 2_000;
+/// Longest backoff between attempts.
+const MAX_DELAY_MS: u64 = 60_000;
+/// Finished runs whose retry counts are kept for `retry_count`.
+const FINISHED_RETRIES_KEPT: usize = 100;
+
+/// First message of a retry that resumes the failed attempt's SDK session.
+const RETRY_RESUME_PROMPT: &str = "Your previous turn was interrupted by a temporary API error, \
+and your earlier turns have been restored. Continue from where you left off. Check which output \
+files already exist instead of redoing finished work, then complete the task exactly as originally instructed.";
+
+/// What kind of failure ended an agent run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    RateLimit,
+    Overloaded,
+    /// Dropped connections, timeouts and 5xx responses.
+    Network,
+    Auth,
+    Other,
+}
+
+impl ErrorClass {
+    /// Machine-readable class for events and logs.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorClass::RateLimit => "rate_limit",
+            ErrorClass::Overloaded => "overloaded",
+            ErrorClass::Network => "network",
+            ErrorClass::Auth => "auth",
+            ErrorClass::Other => "other",
+        }
+    }
+
+    /// Whether the same request can be expected to succeed a little later.
+    pub fn is_transient(self) -> bool {
+        matches!(self, ErrorClass::RateLimit | ErrorClass::Overloaded | ErrorClass::Network)
+    }
+}
+
+/// HTTP status in an SDK error such as `API Error: 529 {...}`.
+fn api_status(detail: &str) -> Option<u16> {
+    let lower = detail.to_ascii_lowercase();
+    let start = lower.find("api error: ")? + "api error: ".len();
+    let digits: String = lower[start..].chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+/// Classify an agent failure from its error text.
+pub fn classify_error(detail: &str) -> ErrorClass {
+    let lower = detail.to_ascii_lowercase();
+    let mentions = |needles: &[&str]| needles.iter().any(|n| lower.contains(n));
+
+    // Error types in the API's JSON body are more specific than the status.
+    if mentions(&["authentication_error", "permission_error", "invalid x-api-key", "invalid api key"]) {
+        return ErrorClass::Auth;
+    }
+    if mentions(&["overloaded"]) {
+        return ErrorClass::Overloaded;
+    }
+    if mentions(&["rate_limit", "rate limit", "too many requests"]) {
+        return ErrorClass::RateLimit;
+    }
+    match api_status(detail) {
+        Some(401) | Some(403) => return ErrorClass::Auth,
+        Some(429) => return ErrorClass::RateLimit,
+        Some(529) => return ErrorClass::Overloaded,
+        Some(500..=599) => return ErrorClass::Network,
+        _ => {}
+    }
+    if mentions(&[
+        "econnreset",
+        "econnrefused",
+        "etimedout",
+        "enotfound",
+        "eai_again",
+        "socket hang up",
+        "fetch failed",
+        "connection error",
+        "network error",
+        "request timed out",
+    ]) {
+        return ErrorClass::Network;
+    }
+    ErrorClass::Other
+}
+
+/// Error text of a message that ends a run unsuccessfully, `None` for any
+/// other message.
+fn failure_detail(message: &serde_json::Value) -> Option<String> {
+    match message.get("type").and_then(|t| t.as_str())? {
+        "error" => Some(
+            message
+                .get("message")
+                .or_else(|| message.get("error"))
+                .and_then(|m| m.as_str())
+                .unwrap_or("(no message)")
+                .to_string(),
+        ),
+        "result" => {
+            let subtype = message.get("subtype").and_then(|s| s.as_str()).unwrap_or("success");
+            let is_error = message.get("is_error").and_then(|e| e.as_bool()).unwrap_or(false);
+            if !is_error && !subtype.starts_with("error_") {
+                return None;
+            }
+            let errors: Vec<&str> = message
+                .get("errors")
+                .and_then(|e| e.as_array())
+                .map(|e| e.iter().filter_map(|v| v.as_str()).collect())
+                .unwrap_or_default();
+            Some(match message.get("result").and_then(|r| r.as_str()) {
+                Some(result) if !result.is_empty() => result.to_string(),
+                _ if !errors.is_empty() => errors.join("; "),
+                _ => subtype.to_string(),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Backoff before retry number `attempt` (1-based). Doubles each attempt up
+/// to `MAX_DELAY_MS`; `jitter` (0.0–1.0) spreads the second half of the wait
+/// so runs failing together don't all come back at once.
+pub fn backoff_delay(class: ErrorClass, attempt: u32, jitter: f64) -> std::time::Duration {
+    let base = match class {
+        ErrorClass::RateLimit => RATE_LIMIT_BASE_DELAY_MS,
+        _ => BASE_DELAY_MS,
+    };
+    let full = base
+        .saturating_mul(1u64 << attempt.saturating_sub(1).min(16))
+        .min(MAX_DELAY_MS);
+    let jittered = full / 2 + (full as f64 / 2.0 * jitter.clamp(0.0, 1.0)) as u64;
+    std::time::Duration::from_millis(jittered)
+}
+
+fn jitter() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as f64 / 1_000_000_000.0)
+        .unwrap_or(0.5)
+}
+
+#[derive(Clone)]
+struct RetryRun {
+    skill_name: String,
+    config: SidecarConfig,
+    transcript_log_dir: Option<String>,
+    concurrent: bool,
+    attempts: u32,
+    session_id: Option<String>,
+    turns: u32,
+}
+
+/// Runs that may be retried, keyed by agent id.
+static RETRY_RUNS: Mutex<BTreeMap<String, RetryRun>> = Mutex::new(BTreeMap::new());
+/// Retry counts of recently finished runs, until their usage is persisted.
+static FINISHED_RETRIES: Mutex<VecDeque<(String, u32)>> = Mutex::new(VecDeque::new());
+
+/// A resend decided by `plan_retry`.
+pub struct RetryPlan {
+    pub skill_name: String,
+    /// Config for the resend: the original, or a resume of the failed attempt's session.
+    pub config: SidecarConfig,
+    pub transcript_log_dir: Option<String>,
+    pub concurrent: bool,
+    pub attempt: u32,
+    pub error_class: ErrorClass,
+    pub detail: String,
+    pub delay: std::time::Duration,
+    pub resumed: bool,
+}
+
+/// Make an agent request retryable. Later sends of the same agent (its
+/// retries) keep the original config and attempt count.
+pub fn track_retries(
+    agent_id: &str,
+    skill_name: &str,
+    config: &SidecarConfig,
+    transcript_log_dir: Option<&str>,
+    concurrent: bool,
+) {
+    if let Ok(mut runs) = RETRY_RUNS.lock() {
+        runs.entry(agent_id.to_string()).or_insert_with(|| RetryRun {
+            skill_name: skill_name.to_string(),
+            config: config.clone(),
+            transcript_log_dir: transcript_log_dir.map(str::to_string),
+            concurrent,
+            attempts: 0,
+            session_id: None,
+            turns: 0,
+        });
+    }
+}
+
+/// Record the SDK session and top-level turns of a tracked run, so a retry
+/// after the first turn can resume instead of starting over.
+pub fn observe_for_retry(agent_id: &str, message: &serde_json::Value) {
+    let Ok(mut runs) = RETRY_RUNS.lock() else {
+        return;
+    };
+    let Some(run) = runs.get_mut(agent_id) else {
+        return;
+    };
+    if let Some(session) = message.get("session_id").and_then(|s| s.as_str()).filter(|s| !s.is_empty()) {
+        if run.session_id.as_deref() != Some(session) {
+            run.session_id = Some(session.to_string());
+        }
+    }
+    if message.get("type").and_then(|t| t.as_str()) == Some("assistant")
+        && !message.get("parent_tool_use_id").is_some_and(|id| id.is_string())
+    {
+        run.turns += 1;
+    }
+}
+
+fn resend_config(run: &RetryRun) -> (SidecarConfig, bool) {
+    let mut config = run.config.clone();
+    match run.session_id.as_ref().filter(|_| run.turns > 0) {
+        Some(session) => {
+            config.resume = Some(session.clone());
+            config.prompt = RETRY_RESUME_PROMPT.to_string();
+            config.conversation_history = None;
+            (config, true)
+        }
+        None => (config, false),
+    }
+}
+
+/// Decide whether `message` ends a tracked run with a failure worth
+/// retrying. Counts the attempt when it does.
+pub fn plan_retry(agent_id: &str, message: &serde_json::Value) -> Option<RetryPlan> {
+    let detail = failure_detail(message)?;
+    let error_class = classify_error(&detail);
+    let mut runs = RETRY_RUNS.lock().ok()?;
+    let run = runs.get_mut(agent_id)?;
+    if !error_class.is_transient() || run.attempts >= MAX_RETRIES {
+        log::info!(
+            "[retry] '{}' failed ({}) after {} retr{}; not retrying",
+            agent_id,
+            error_class.as_str(),
+            run.attempts,
+            if run.attempts == 1 { "y" } else { "ies" }
+        );
+        return None;
+    }
+    run.attempts += 1;
+    let (config, resumed) = resend_config(run);
+    let plan = RetryPlan {
+        skill_name: run.skill_name.clone(),
+        config,
+        transcript_log_dir: run.transcript_log_dir.clone(),
+        concurrent: run.concurrent,
+        attempt: run.attempts,
+        error_class,
+        detail,
+        delay: backoff_delay(error_class, run.attempts, jitter()),
+        resumed,
+    };
+    log::warn!(
+        "[retry] '{}' failed ({}): {} — retry {}/{} in {}ms{}",
+        agent_id,
+        error_class.as_str(),
+        plan.detail,
+        plan.attempt,
+        MAX_RETRIES,
+        plan.delay.as_millis(),
+        if resumed { ", resuming session" } else { "" }
+    );
+    Some(plan)
+}
+
+/// Whether the retry numbered `attempt` is still wanted: the run has not
+/// been cancelled or shut down while it waited.
+pub fn retry_pending(agent_id: &str, attempt: u32) -> bool {
+    RETRY_RUNS
+        .lock()
+        .is_ok_and(|runs| runs.get(agent_id).is_some_and(|run| run.attempts == attempt))
+}
+
+/// Stop tracking a run that exited. Its retry count stays available to
+/// `retry_count` until its usage is recorded.
+pub fn finish_retries(agent_id: &str) {
+    let attempts = RETRY_RUNS
+        .lock()
+        .ok()
+        .and_then(|mut runs| runs.remove(agent_id))
+        .map_or(0, |run| run.attempts);
+    if attempts == 0 {
+        return;
+    }
+    if let Ok(mut finished) = FINISHED_RETRIES.lock() {
+        finished.retain(|(id, _)| id != agent_id);
+        finished.push_back((agent_id.to_string(), attempts));
+        while finished.len() > FINISHED_RETRIES_KEPT {
+            finished.pop_front();
+        }
+    }
+}
+
+/// How many times `agent_id` was retried so far.
+pub fn retry_count(agent_id: &str) -> u32 {
+    if let Some(attempts) = RETRY_RUNS.lock().ok().and_then(|runs| runs.get(agent_id).map(|r| r.attempts)) {
+        return attempts;
+    }
+    FINISHED_RETRIES
+        .lock()
+        .ok()
+        .and_then(|finished| finished.iter().find(|(id, _)| id == agent_id).map(|(_, n)| *n))
+        .unwrap_or(0)
+}
+
 /// Public accessor for startup dependency checks.
 pub fn resolve_sdk_cli_path_public(app_handle: &tauri::AppHandle) -> Result<String, String> {
     resolve_sdk_cli_path(app_handle)
@@ -163,6 +588,10 @@ mod tests {
             agent_name: Some("research-entities".to_string()),
             required_plugins: None,
             conversation_history: None,
+            resume: None,
+            bash_sandbox: None,
+            disallowed_tools: None,
+            local_model: None,
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -204,6 +633,10 @@ mod tests {
             agent_name: None,
             required_plugins: None,
             conversation_history: None,
+            resume: None,
+            bash_sandbox: None,
+            disallowed_tools: None,
+            local_model: None,
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -213,4 +646,86 @@ mod tests {
         assert_eq!(parsed["thinking"]["budgetTokens"], 32000);
     }
 
+    #[test]
+    fn test_classify_error() {
+        let overloaded = r#"API Error: 529 {"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#;
+        assert_eq!(classify_error(overloaded), ErrorClass::Overloaded);
+        assert_eq!(classify_error("API Error: 429 Too Many Requests"), ErrorClass::RateLimit);
+        assert_eq!(classify_error("API Error: 502 Bad Gateway"), ErrorClass::Network);
+        assert_eq!(classify_error("Connection error: read ECONNRESET"), ErrorClass::Network);
+        assert_eq!(
+            classify_error(r#"API Error: 401 {"error":{"type":"authentication_error","message":"invalid x-api-key"}}"#),
+            ErrorClass::Auth
+        );
+        assert_eq!(classify_error("error_max_turns"), ErrorClass::Other);
+        assert!(!ErrorClass::Auth.is_transient());
+        assert!(ErrorClass::Overloaded.is_transient());
+    }
+
+    #[test]
+    fn test_backoff_delay_doubles_with_jitter_and_cap() {
+        let ms = |class, attempt, jitter| backoff_delay(class, attempt, jitter).as_millis();
+        assert_eq!(ms(ErrorClass::Overloaded, 1, 0.0), 1_000);
+        assert_eq!(ms(ErrorClass::Overloaded, 1, 1.0), 2_000);
+        assert_eq!(ms(ErrorClass::Overloaded, 3, 1.0), 8_000);
+        assert_eq!(ms(ErrorClass::RateLimit, 1, 1.0), 10_000);
+        assert_eq!(ms(ErrorClass::RateLimit, 10, 1.0), 60_000);
+    }
+
+    #[test]
+    fn test_plan_retry_resumes_after_first_turn_and_stops_at_limit() {
+        let agent_id = "retry-skill-step2-1";
+        let config = SidecarConfig {
+            prompt: "Run step 2".to_string(),
+            model: None,
+            api_key: "sk-ant-test".to_string(),
+            cwd: "/tmp".to_string(),
+            allowed_tools: None,
+            max_turns: None,
+            permission_mode: None,
+            betas: None,
+            thinking: None,
+            fallback_model: None,
+            effort: None,
+            output_format: None,
+            prompt_suggestions: None,
+            path_to_claude_code_executable: None,
+            agent_name: None,
+            required_plugins: None,
+            conversation_history: None,
+            resume: None,
+            bash_sandbox: None,
+            disallowed_tools: None,
+            local_model: None,
+        };
+        let overloaded = serde_json::json!({"type": "error", "message": "API Error: 529 overloaded_error"});
+
+        // Untracked runs and non-failures are never retried.
+        assert!(plan_retry(agent_id, &overloaded).is_none());
+        track_retries(agent_id, "retry-skill", &config, None, false);
+        assert!(plan_retry(agent_id, &serde_json::json!({"type": "result", "subtype": "success"})).is_none());
+
+        let first = plan_retry(agent_id, &overloaded).unwrap();
+        assert_eq!((first.attempt, first.error_class, first.resumed), (1, ErrorClass::Overloaded, false));
+        assert_eq!(first.config.prompt, "Run step 2");
+
+        observe_for_retry(agent_id, &serde_json::json!({"type": "system", "session_id": "sess-1"}));
+        observe_for_retry(agent_id, &serde_json::json!({"type": "assistant", "session_id": "sess-1"}));
+        let second = plan_retry(agent_id, &overloaded).unwrap();
+        assert!(second.resumed);
+        assert_eq!(second.config.resume.as_deref(), Some("sess-1"));
+        assert_eq!(second.config.prompt, RETRY_RESUME_PROMPT);
+        assert!(retry_pending(agent_id, 2));
+        assert!(!retry_pending(agent_id, 1));
+
+        let auth = serde_json::json!({"type": "result", "is_error": true, "result": "API Error: 401 authentication_error"});
+        assert!(plan_retry(agent_id, &auth).is_none());
+        assert!(plan_retry(agent_id, &overloaded).is_some());
+        assert!(plan_retry(agent_id, &overloaded).is_none());
+
+        assert_eq!(retry_count(agent_id), MAX_RETRIES);
+        finish_retries(agent_id);
+        assert!(!retry_pending(agent_id, MAX_RETRIES));
+        assert_eq!(retry_count(agent_id), MAX_RETRIES);
+    }
 }
@@ -6,13 +6,16 @@ use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-use futures::FutureExt;
+use futures::{FutureExt, StreamExt};
+use tauri::Listener;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, Command};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 
 use super::events;
+use super::protocol::{self, ProtocolVersion};
 use super::sidecar::SidecarConfig;
 
 /// Categorized sidecar startup failure with actionable fix instructions.
@@ -31,6 +34,11 @@ pub enum SidecarStartupError {
     SpawnFailed {
         detail: String,
     },
+    /// The sidecar speaks a wire protocol this app cannot parse (major version mismatch).
+    ProtocolIncompatible {
+        found: String,
+        required: String,
+    },
     /// The sidecar started but did not send the ready signal within the timeout.
     ReadyTimeout {
         pid: u32,
@@ -49,6 +57,7 @@ impl SidecarStartupError {
             SidecarStartupError::NodeMissing => "node_missing",
             SidecarStartupError::NodeIncompatible { .. } => "node_incompatible",
             SidecarStartupError::SpawnFailed { .. } => "spawn_failed",
+            SidecarStartupError::ProtocolIncompatible { .. } => "protocol_incompatible",
             SidecarStartupError::ReadyTimeout { .. } => "ready_timeout",
             SidecarStartupError::Other { .. } => "other",
         }
@@ -72,6 +81,12 @@ impl SidecarStartupError {
             SidecarStartupError::SpawnFailed { detail } => {
                 format!("Failed to start agent runtime: {}", detail)
             }
+            SidecarStartupError::ProtocolIncompatible { found, required } => {
+                format!(
+                    "Agent runtime speaks protocol version {}, but this app requires {}. The runtime and app are out of sync.",
+                    found, required
+                )
+            }
             SidecarStartupError::ReadyTimeout { pid } => {
                 format!(
                     "Agent runtime started (pid {}) but failed to initialize within 10 seconds.",
@@ -97,6 +112,9 @@ impl SidecarStartupError {
             SidecarStartupError::SpawnFailed { .. } => {
                 "Check file permissions and ensure the sidecar bundle exists. Try running `npm run sidecar:build` in the app/ directory.".to_string()
             }
+            SidecarStartupError::ProtocolIncompatible { .. } => {
+                "Repair the agent runtime: run `npm run sidecar:build` in the app/ directory, or reinstall Skill Builder so the app and runtime versions match.".to_string()
+            }
             SidecarStartupError::ReadyTimeout { .. } => {
                 "Check the app logs for details (Settings > Log File). The sidecar process may have crashed during initialization.".to_string()
             }
@@ -159,6 +177,8 @@ struct PersistentSidecar {
     /// Timestamp of the last activity (agent request sent) for this sidecar.
     /// Used by the idle cleanup task to determine if a sidecar can be reclaimed.
     last_activity: Arc<Mutex<tokio::time::Instant>>,
+    /// Protocol version announced in `sidecar_ready`, recorded in each request transcript.
+    protocol_version: ProtocolVersion,
 }
 
 /// Abort the reader tasks and heartbeat task, then drop the sidecar, cleaning up all resources.
@@ -251,9 +271,71 @@ fn spawn_heartbeat_task(
     })
 }
 
+/// An open JSONL transcript and where it lives, so it can be compressed once closed.
+struct TranscriptLog {
+    file: std::fs::File,
+    path: PathBuf,
+}
+
 /// A per-request JSONL log file handle, shared between `do_send_request` (which creates it)
 /// and the stdout reader task (which appends each message line).
-type RequestLogFile = Arc<Mutex<Option<std::fs::File>>>;
+type RequestLogFile = Arc<Mutex<Option<TranscriptLog>>>;
+
+/// Archive a finished transcript in `agent_transcripts`, then evict the least
+/// recently used ones over the `transcript_archive_mb` cap. Failures are only
+/// logged; the file in the skill's logs folder is kept either way.
+fn archive_transcript(app_handle: &tauri::AppHandle, agent_id: &str, skill_name: &str, path: &Path) {
+    use tauri::Manager;
+    let Some(db) = app_handle.try_state::<crate::db::Db>() else {
+        return;
+    };
+    let max_mb = match db.0.lock() {
+        Ok(conn) => crate::db::read_settings(&conn).map(|s| s.transcript_archive_mb),
+        Err(e) => Err(e.to_string()),
+    };
+    let max_mb = match max_mb {
+        Ok(0) => return,
+        Ok(mb) => mb,
+        Err(e) => {
+            log::warn!("[archive_transcript] Failed to read settings: {}", e);
+            return;
+        }
+    };
+    let archived = match crate::transcripts::pack_file_for_archive(path) {
+        Ok(archived) => archived,
+        Err(e) => {
+            log::warn!("[archive_transcript] {}: {}", agent_id, e);
+            return;
+        }
+    };
+    let result = db.0.lock().map_err(|e| e.to_string()).and_then(|conn| {
+        crate::db::save_agent_transcript(&conn, agent_id, skill_name, &archived)?;
+        crate::db::evict_agent_transcripts(&conn, max_mb as u64 * 1024 * 1024)
+    });
+    match result {
+        Ok(0) => {}
+        Ok(evicted) => log::info!("[archive_transcript] evicted {} transcript(s) over {} MB", evicted, max_mb),
+        Err(e) => log::warn!("[archive_transcript] Failed to archive {}: {}", agent_id, e),
+    }
+}
+
+/// Close a request's transcript, then archive and compress it off the async runtime.
+async fn close_transcript(handle: RequestLogFile, app_handle: &tauri::AppHandle, agent_id: &str, skill_name: &str) {
+    let Some(transcript) = handle.lock().await.take() else {
+        return;
+    };
+    drop(transcript.file);
+    let path = transcript.path;
+    let app_handle = app_handle.clone();
+    let agent_id = agent_id.to_string();
+    let skill_name = skill_name.to_string();
+    tokio::task::spawn_blocking(move || {
+        archive_transcript(&app_handle, &agent_id, &skill_name, &path);
+        if let Err(e) = crate::transcripts::compress_transcript(&path) {
+            log::warn!("Failed to compress transcript: {}", e);
+        }
+    });
+}
 
 /// Default shutdown timeout in seconds. If graceful shutdown takes longer,
 /// the app force-exits.
@@ -265,6 +347,57 @@ pub const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 600; // 10 minutes
 /// How often the idle cleanup task checks for idle sidecars, in seconds.
 const IDLE_CHECK_INTERVAL_SECS: u64 = 60;
 
+/// Events a backend-driven agent run is followed through.
+pub(crate) const AGENT_EVENTS: [&str; 4] = ["agent-init-error", "agent-message", "agent-exit", "agent-shutdown"];
+
+/// Per-agent senders for events received while `run_concurrent` waits.
+type EventRoutes = Arc<std::sync::Mutex<HashMap<String, mpsc::UnboundedSender<(&'static str, serde_json::Value)>>>>;
+
+/// One independent agent request for `SidecarPool::run_concurrent`.
+pub struct ConcurrentTask {
+    pub agent_id: String,
+    pub config: SidecarConfig,
+}
+
+/// How a `ConcurrentTask` ended: every message it produced, and why it failed if it did.
+#[derive(Debug)]
+pub struct ConcurrentOutcome {
+    pub agent_id: String,
+    pub messages: Vec<serde_json::Value>,
+    pub error: Option<String>,
+}
+
+/// Wait for `agent_id` to exit, collecting its messages. Sets `error` when it
+/// fails to start or exits unsuccessfully.
+pub(crate) async fn collect_agent_messages(
+    rx: &mut UnboundedReceiver<(&'static str, serde_json::Value)>,
+    agent_id: &str,
+) -> ConcurrentOutcome {
+    let mut messages = Vec::new();
+    let error = loop {
+        let Some((name, payload)) = rx.recv().await else {
+            break Some("Agent event stream closed".to_string());
+        };
+        if name != "agent-init-error" && payload["agent_id"] != agent_id {
+            continue;
+        }
+        match name {
+            "agent-init-error" => {
+                break Some(payload["message"].as_str().unwrap_or("Agent failed to start").to_string());
+            }
+            "agent-message" => messages.push(payload["message"].clone()),
+            "agent-exit" if payload["success"].as_bool().unwrap_or(false) => break None,
+            "agent-exit" | "agent-shutdown" => break Some("Agent run failed".to_string()),
+            _ => {}
+        }
+    };
+    ConcurrentOutcome {
+        agent_id: agent_id.to_string(),
+        messages,
+        error,
+    }
+}
+
 /// Pool of persistent sidecar processes, one per skill.
 /// Reuses existing processes across agent invocations to reduce startup latency.
 /// Wraps an `Arc` so cloning is cheap and all clones share the same pool.
@@ -640,6 +773,10 @@ impl SidecarPool {
             skill_name,
             pid
         );
+        crate::journal::record(crate::journal::JournalEvent::SidecarSpawned {
+            skill: skill_name.to_string(),
+            pid,
+        });
 
         // Start early stderr capture for startup diagnostics.
         // Lines are collected in a shared buffer so that if startup fails (timeout,
@@ -742,9 +879,9 @@ impl SidecarPool {
             return Err(err.to_string());
         }
 
-        // Validate the ready signal
+        // Validate the ready signal and negotiate the protocol version
         let ready_line = ready_line.trim();
-        match serde_json::from_str::<serde_json::Value>(ready_line) {
+        let protocol_version = match serde_json::from_str::<serde_json::Value>(ready_line) {
             Ok(val) => {
                 if val.get("type").and_then(|t| t.as_str()) != Some("sidecar_ready") {
                     let stderr_lines = drain_stderr(stderr_task, early_stderr).await;
@@ -760,6 +897,13 @@ impl SidecarPool {
                     events::emit_init_error(app_handle, &err);
                     return Err(err.to_string());
                 }
+                match protocol::negotiate(&val) {
+                    Ok(version) => version,
+                    Err(err) => {
+                        events::emit_init_error(app_handle, &err);
+                        return Err(err.to_string());
+                    }
+                }
             }
             Err(e) => {
                 let stderr_lines = drain_stderr(stderr_task, early_stderr).await;
@@ -778,9 +922,13 @@ impl SidecarPool {
                 events::emit_init_error(app_handle, &err);
                 return Err(err.to_string());
             }
-        }
+        };
 
-        log::info!("Persistent sidecar for '{}' is ready (pid [REDACTED])", skill_name);
+        log::info!(
+            "Persistent sidecar for '{}' is ready (pid [REDACTED], protocol {})",
+            skill_name,
+            protocol_version
+        );
 
         // Issue 3: Store JoinHandles so we can abort them on shutdown/crash-respawn
         // The stderr_task is already spawned above and will keep running,
@@ -835,24 +983,58 @@ impl SidecarPool {
                                     return;
                                 }
 
+                                // A transient failure the retry policy will resend is
+                                // kept from the frontend: the run is still in flight.
+                                let retry = super::sidecar::plan_retry(request_id, &msg);
+
                                 // Route this message to the correct agent using the request_id as agent_id
-                                events::handle_sidecar_message(
-                                    &app_handle_stdout,
-                                    request_id,
-                                    &line,
-                                );
+                                if retry.is_none() {
+                                    events::handle_sidecar_message(
+                                        &app_handle_stdout,
+                                        request_id,
+                                        &line,
+                                    );
+                                }
 
                                 // Append to per-request JSONL transcript
                                 {
                                     let logs = stdout_request_logs.lock().await;
                                     if let Some(log_file) = logs.get(request_id) {
                                         let mut guard = log_file.lock().await;
-                                        if let Some(ref mut f) = *guard {
-                                            let _ = writeln!(f, "{}", line);
+                                        if let Some(ref mut t) = *guard {
+                                            let _ = writeln!(t.file, "{}", line);
                                         }
                                     }
                                 }
 
+                                // Keep the request pending through the backoff so a
+                                // cancel or shutdown meanwhile still finds it; the
+                                // resend gets a transcript of its own.
+                                if let Some(plan) = retry {
+                                    {
+                                        let pool = stdout_pool.lock().await;
+                                        if let Some(s) = pool.get(&skill_name_stdout) {
+                                            *s.last_activity.lock().await = tokio::time::Instant::now();
+                                        }
+                                    }
+                                    {
+                                        let mut logs = stdout_request_logs.lock().await;
+                                        if let Some(handle) = logs.remove(request_id) {
+                                            close_transcript(handle, &app_handle_stdout, request_id, &skill_name_stdout).await;
+                                        }
+                                    }
+                                    events::emit_retry(&app_handle_stdout, request_id, &plan);
+                                    let app_handle = app_handle_stdout.clone();
+                                    let agent_id = request_id.to_string();
+                                    tokio::spawn(async move {
+                                        use tauri::Manager;
+                                        tokio::time::sleep(plan.delay).await;
+                                        let pool = app_handle.state::<SidecarPool>().inner().clone();
+                                        pool.retry_request(&agent_id, plan, &app_handle).await;
+                                    });
+                                    return;
+                                }
+
                                 // Log lifecycle events at INFO so the log file tells the full story.
                                 // Streaming messages (assistant, user, tool_use, etc.) stay at debug.
                                 if let Some("system") = msg.get("type").and_then(|t| t.as_str()) {
@@ -904,7 +1086,9 @@ impl SidecarPool {
                                         );
                                         // Close JSONL log for this turn
                                         let mut logs = stdout_request_logs.lock().await;
-                                        logs.remove(request_id);
+                                        if let Some(handle) = logs.remove(request_id) {
+                                            close_transcript(handle, &app_handle_stdout, request_id, &skill_name_stdout).await;
+                                        }
                                         return;
                                     }
 
@@ -930,7 +1114,9 @@ impl SidecarPool {
                                             true,
                                         );
                                         let mut logs = stdout_request_logs.lock().await;
-                                        logs.remove(request_id);
+                                        if let Some(handle) = logs.remove(request_id) {
+                                            close_transcript(handle, &app_handle_stdout, request_id, &skill_name_stdout).await;
+                                        }
                                         return;
                                     }
 
@@ -1011,7 +1197,9 @@ impl SidecarPool {
                                     // Close and remove the JSONL log file on terminal messages
                                     if is_terminal {
                                         let mut logs = stdout_request_logs.lock().await;
-                                        logs.remove(request_id);
+                                        if let Some(handle) = logs.remove(request_id) {
+                                            close_transcript(handle, &app_handle_stdout, request_id, &skill_name_stdout).await;
+                                        }
                                     }
                                 }
                             } else {
@@ -1074,6 +1262,7 @@ impl SidecarPool {
             heartbeat_task,
             last_pong,
             last_activity: Arc::new(Mutex::new(tokio::time::Instant::now())),
+            protocol_version,
         };
 
         // Re-acquire pool lock to insert the new sidecar
@@ -1082,6 +1271,12 @@ impl SidecarPool {
         Ok(())
     }
 
+    /// Protocol version negotiated with the skill's running sidecar, if any.
+    async fn protocol_version_for(&self, skill_name: &str) -> Option<String> {
+        let pool = self.sidecars.lock().await;
+        pool.get(skill_name).map(|s| s.protocol_version.to_string())
+    }
+
     /// Send an agent request to the persistent sidecar for a skill.
     /// The request_id is set to the agent_id so events route to the correct frontend handler.
     ///
@@ -1103,7 +1298,7 @@ impl SidecarPool {
 
         // Issue 4: If anything below fails, emit agent_exit so the frontend doesn't hang.
         let result = self
-            .do_send_request(skill_name, agent_id, config, app_handle, transcript_log_dir)
+            .do_send_request(skill_name, agent_id, config, app_handle, transcript_log_dir, false)
             .await;
 
         if let Err(ref e) = result {
@@ -1119,8 +1314,140 @@ impl SidecarPool {
         result
     }
 
+    /// Run independent agent requests on `skill_name`'s sidecar side by side,
+    /// at most `max_concurrent` at once, and wait for all of them.
+    ///
+    /// Each request is flagged `concurrent` so the sidecar runs it alongside the
+    /// others instead of treating it as a replacement for a stuck one. A sidecar
+    /// older than protocol 1.1 cannot, so the tasks then run one at a time.
+    /// Outcomes are returned in task order.
+    pub async fn run_concurrent(
+        &self,
+        skill_name: &str,
+        tasks: Vec<ConcurrentTask>,
+        max_concurrent: usize,
+        app_handle: &tauri::AppHandle,
+    ) -> Vec<ConcurrentOutcome> {
+        if let Err(e) = self.get_or_spawn(skill_name, app_handle).await {
+            return tasks
+                .into_iter()
+                .map(|task| ConcurrentOutcome {
+                    agent_id: task.agent_id,
+                    messages: Vec::new(),
+                    error: Some(e.clone()),
+                })
+                .collect();
+        }
+        let side_by_side = {
+            let pool = self.sidecars.lock().await;
+            pool.get(skill_name)
+                .is_some_and(|s| s.protocol_version >= protocol::CONCURRENT_REQUESTS_SINCE)
+        };
+        let limit = if side_by_side { max_concurrent.max(1) } else { 1 };
+        log::info!(
+            "[run_concurrent] skill='{}' tasks={} at once={}",
+            skill_name,
+            tasks.len(),
+            limit
+        );
+
+        // Listen before sending: events can arrive before the send returns.
+        let routes: EventRoutes = Default::default();
+        let listeners: Vec<_> = AGENT_EVENTS
+            .iter()
+            .map(|&name| {
+                let routes = routes.clone();
+                app_handle.listen_any(name, move |event| {
+                    let payload: serde_json::Value =
+                        serde_json::from_str(event.payload()).unwrap_or(serde_json::Value::Null);
+                    let Ok(routes) = routes.lock() else { return };
+                    if name == "agent-init-error" {
+                        for tx in routes.values() {
+                            let _ = tx.send((name, payload.clone()));
+                        }
+                    } else if let Some(tx) = payload["agent_id"].as_str().and_then(|id| routes.get(id)) {
+                        let _ = tx.send((name, payload));
+                    }
+                })
+            })
+            .collect();
+
+        let outcomes = futures::stream::iter(tasks)
+            .map(|task| {
+                let routes = routes.clone();
+                async move {
+                    let (tx, mut rx) = mpsc::unbounded_channel();
+                    if let Ok(mut routes) = routes.lock() {
+                        routes.insert(task.agent_id.clone(), tx);
+                    }
+                    let sent = self
+                        .do_send_request(skill_name, &task.agent_id, task.config, app_handle, None, side_by_side)
+                        .await;
+                    let outcome = match sent {
+                        Ok(()) => collect_agent_messages(&mut rx, &task.agent_id).await,
+                        Err(e) => {
+                            log::warn!("[run_concurrent] failed to send '{}': {}", task.agent_id, e);
+                            events::handle_sidecar_exit(app_handle, &task.agent_id, false);
+                            ConcurrentOutcome {
+                                agent_id: task.agent_id.clone(),
+                                messages: Vec::new(),
+                                error: Some(e),
+                            }
+                        }
+                    };
+                    if let Ok(mut routes) = routes.lock() {
+                        routes.remove(&task.agent_id);
+                    }
+                    outcome
+                }
+            })
+            .buffered(limit)
+            .collect::<Vec<_>>()
+            .await;
+
+        for id in listeners {
+            app_handle.unlisten(id);
+        }
+        outcomes
+    }
+
+    /// Resend an agent request the retry policy planned after a transient
+    /// failure, unless the run was cancelled or shut down during the backoff.
+    async fn retry_request(&self, agent_id: &str, plan: super::sidecar::RetryPlan, app_handle: &tauri::AppHandle) {
+        if !super::sidecar::retry_pending(agent_id, plan.attempt) {
+            log::info!("[retry_request] '{}' finished during backoff; not resending", agent_id);
+            return;
+        }
+        log::info!(
+            "[retry_request] Resending '{}' on sidecar for '{}' (retry {})",
+            agent_id,
+            plan.skill_name,
+            plan.attempt
+        );
+        let result = match self.get_or_spawn(&plan.skill_name, app_handle).await {
+            Ok(()) => {
+                self.do_send_request(
+                    &plan.skill_name,
+                    agent_id,
+                    plan.config,
+                    app_handle,
+                    plan.transcript_log_dir.as_deref(),
+                    plan.concurrent,
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            log::warn!("[retry_request] Resend of '{}' failed: {}", agent_id, e);
+            self.unregister_pending(agent_id).await;
+            events::handle_sidecar_exit(app_handle, agent_id, false);
+        }
+    }
+
     /// Internal: perform the actual request send. Separated so `send_request` can
-    /// emit `agent_exit` on error.
+    /// emit `agent_exit` on error. `concurrent` lets the request run alongside
+    /// others already in flight on the sidecar (see `run_concurrent`).
     async fn do_send_request(
         &self,
         skill_name: &str,
@@ -1128,13 +1455,21 @@ impl SidecarPool {
         config: SidecarConfig,
         app_handle: &tauri::AppHandle,
         transcript_log_dir: Option<&str>,
+        concurrent: bool,
     ) -> Result<(), String> {
+        super::sidecar::track_retries(agent_id, skill_name, &config, transcript_log_dir, concurrent);
+        let mut config = config;
+        crate::commands::offline::apply_for_app(app_handle, &mut config)?;
+
         // Build the request message (before acquiring any lock)
-        let request = serde_json::json!({
+        let mut request = serde_json::json!({
             "type": "agent_request",
             "request_id": agent_id,
             "config": config,
         });
+        if concurrent {
+            request["concurrent"] = serde_json::json!(true);
+        }
 
         let mut request_line = serde_json::to_string(&request)
             .map_err(|e| format!("Failed to serialize agent request: {}", e))?;
@@ -1147,6 +1482,10 @@ impl SidecarPool {
             config.prompt,
         );
 
+        super::cost_ticker::start(app_handle, agent_id, skill_name, config.model.as_deref());
+        super::tool_audit::start(agent_id, skill_name, config.allowed_tools.as_deref());
+        super::step_progress::start(app_handle, agent_id, skill_name, config.model.as_deref(), config.max_turns);
+
         // Emit redacted config to frontend (strip both apiKey and prompt)
         {
             let mut config_val = serde_json::to_value(&config).unwrap_or_default();
@@ -1162,6 +1501,7 @@ impl SidecarPool {
         }
 
         // Build transcript first line: config with prompt intact, only apiKey redacted
+        let protocol_version = self.protocol_version_for(skill_name).await;
         let transcript_first_line = {
             let mut config_val = serde_json::to_value(&config).unwrap_or_default();
             if let Some(obj) = config_val.as_object_mut() {
@@ -1170,6 +1510,7 @@ impl SidecarPool {
             serde_json::json!({
                 "type": "config",
                 "config": config_val,
+                "protocol_version": protocol_version,
             })
         };
 
@@ -1200,7 +1541,9 @@ impl SidecarPool {
                 Ok(mut f) => {
                     // Write config with prompt as the first line (apiKey redacted)
                     let _ = writeln!(f, "{}", transcript_first_line);
-                    let log_handle: RequestLogFile = Arc::new(Mutex::new(Some(f)));
+                    super::run_checkpoint::set_transcript(app_handle, agent_id, &log_path);
+                    let log_handle: RequestLogFile =
+                        Arc::new(Mutex::new(Some(TranscriptLog { file: f, path: log_path })));
                     let mut logs = self.request_logs.lock().await;
                     logs.insert(agent_id.to_string(), log_handle);
                 }
@@ -1409,6 +1752,8 @@ impl SidecarPool {
         config: SidecarConfig,
         app_handle: &tauri::AppHandle,
     ) -> Result<(), String> {
+        let mut config = config;
+        crate::commands::offline::apply_for_app(app_handle, &mut config)?;
         self.get_or_spawn(skill_name, app_handle).await?;
 
         log::debug!(
@@ -1418,6 +1763,10 @@ impl SidecarPool {
             config.prompt,
         );
 
+        super::cost_ticker::start(app_handle, agent_id, skill_name, config.model.as_deref());
+        super::tool_audit::start(agent_id, skill_name, config.allowed_tools.as_deref());
+        super::step_progress::start(app_handle, agent_id, skill_name, config.model.as_deref(), config.max_turns);
+
         // Emit redacted config to frontend (strip both apiKey and prompt)
         {
             let mut config_val = serde_json::to_value(&config).unwrap_or_default();
@@ -1430,6 +1779,7 @@ impl SidecarPool {
         }
 
         // Build transcript first line: config with prompt intact, only apiKey redacted
+        let protocol_version = self.protocol_version_for(skill_name).await;
         let transcript_first_line = {
             let mut config_val = serde_json::to_value(&config).unwrap_or_default();
             if let Some(obj) = config_val.as_object_mut() {
@@ -1438,6 +1788,7 @@ impl SidecarPool {
             serde_json::json!({
                 "type": "config",
                 "config": config_val,
+                "protocol_version": protocol_version,
             })
         };
 
@@ -1452,7 +1803,8 @@ impl SidecarPool {
             if let Ok(mut f) = std::fs::create_dir_all(&log_dir).and_then(|_| std::fs::File::create(&log_path)) {
                 // Write config with prompt as the first line (apiKey redacted)
                 let _ = writeln!(f, "{}", transcript_first_line);
-                let log_handle: RequestLogFile = Arc::new(Mutex::new(Some(f)));
+                let log_handle: RequestLogFile =
+                    Arc::new(Mutex::new(Some(TranscriptLog { file: f, path: log_path })));
                 let mut logs = self.request_logs.lock().await;
                 logs.insert(agent_id.to_string(), log_handle);
             }
@@ -1515,6 +1867,8 @@ impl SidecarPool {
             let mut pending = self.pending_requests.lock().await;
             pending.insert(agent_id.to_string(), skill_name.to_string());
         }
+        super::cost_ticker::start(app_handle, agent_id, skill_name, None);
+        super::tool_audit::start(agent_id, skill_name, None);
 
         log::debug!(
             "[send_stream_message] session=[REDACTED] agent='{}' skill='{}' user_message:\n{}",
@@ -1564,6 +1918,45 @@ impl SidecarPool {
         result
     }
 
+    /// Abort an in-flight agent request and finish it as failed, with `message`
+    /// (an agent `error` message) forwarded first so the UI can say why. The
+    /// sidecar stays up for the next request. No-op when the request has
+    /// already finished.
+    pub async fn cancel_request(
+        &self,
+        agent_id: &str,
+        message: serde_json::Value,
+        app_handle: &tauri::AppHandle,
+    ) -> Result<(), String> {
+        let Some(skill_name) = self.pending_requests.lock().await.remove(agent_id) else {
+            return Ok(());
+        };
+        log::info!("[cancel_request] Cancelling '{}' on sidecar for '{}'", agent_id, skill_name);
+        let stdin = {
+            let pool = self.sidecars.lock().await;
+            pool.get(&skill_name).map(|s| s.stdin.clone())
+        };
+        let written = match stdin {
+            Some(stdin) => {
+                let line = format!("{}\n", serde_json::json!({"type": "cancel", "request_id": agent_id}));
+                let mut guard = stdin.lock().await;
+                match guard.write_all(line.as_bytes()).await {
+                    Ok(()) => guard.flush().await,
+                    Err(e) => Err(e),
+                }
+                .map_err(|e| format!("Failed to send cancel for '{}': {}", agent_id, e))
+            }
+            None => Ok(()),
+        };
+
+        events::handle_sidecar_message(app_handle, agent_id, &message.to_string());
+        events::handle_sidecar_exit(app_handle, agent_id, false);
+        if let Some(handle) = self.request_logs.lock().await.remove(agent_id) {
+            close_transcript(handle, app_handle, agent_id, &skill_name).await;
+        }
+        written
+    }
+
     /// Shutdown a single skill's sidecar. Sends a shutdown message, waits up to 3 seconds,
     /// then kills if necessary.
     ///
@@ -1615,7 +2008,9 @@ impl SidecarPool {
                     .cloned()
                     .collect();
                 for agent_id in to_close {
-                    logs.remove(&agent_id);
+                    if let Some(handle) = logs.remove(&agent_id) {
+                        close_transcript(handle, app_handle, &agent_id, skill_name).await;
+                    }
                 }
             }
 
@@ -1726,6 +2121,31 @@ impl SidecarPool {
         log::info!("All persistent sidecars shut down");
     }
 
+    /// Returns `true` when no agent request is in flight on any sidecar.
+    /// Background work (e.g. catalog prefetch) uses this to stay out of the way.
+    pub async fn is_idle(&self) -> bool {
+        self.pending_requests.lock().await.is_empty()
+    }
+
+    /// Number of agent requests in flight across all sidecars.
+    pub async fn running_count(&self) -> usize {
+        self.pending_requests.lock().await.len()
+    }
+
+    /// Cancel every in-flight agent request with the same `message`. Returns
+    /// how many were cancelled.
+    pub async fn cancel_all_requests(&self, message: serde_json::Value, app_handle: &tauri::AppHandle) -> usize {
+        let agent_ids: Vec<String> = self.pending_requests.lock().await.keys().cloned().collect();
+        let mut cancelled = 0;
+        for agent_id in agent_ids {
+            match self.cancel_request(&agent_id, message.clone(), app_handle).await {
+                Ok(()) => cancelled += 1,
+                Err(e) => log::warn!("[cancel_all_requests] {}", e),
+            }
+        }
+        cancelled
+    }
+
     /// Returns `true` if `shutdown_all` has already completed successfully.
     /// Used by `RunEvent::Exit` to skip redundant shutdown calls.
     pub fn is_shutdown_completed(&self) -> bool {
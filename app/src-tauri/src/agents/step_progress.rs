@@ -0,0 +1,333 @@
+//! Progress events for running workflow steps.
+//!
+//! A step can run for minutes with nothing but a spinner to show for it. As
+//! messages pass through `handle_sidecar_message`, the tracker counts turns
+//! against the run's `max_turns`, notes the tool the agent is using and the
+//! tokens it has spent, and emits `workflow-step-progress`. The ETA comes from
+//! how long the same step took on the same model in `agent_runs`; a step with
+//! no history reports progress by turns only.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tauri::{Emitter, Manager};
+
+use crate::db::Db;
+use crate::types::StepProgress;
+
+/// Emit at least this often while messages are arriving. A change of tool is
+/// emitted straight away.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Progress stays below this until the result arrives.
+const MAX_RUNNING_PERCENT: f64 = 99.0;
+
+const UNKNOWN_MODEL: &str = "unknown";
+
+/// Trackers for in-flight workflow steps, keyed by agent id.
+static TRACKERS: Mutex<BTreeMap<String, Tracker>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug)]
+struct Tracker {
+    skill_name: String,
+    step_id: i32,
+    model: String,
+    max_turns: Option<u32>,
+    typical_ms: Option<i64>,
+    started: Instant,
+    last_emit: Option<Instant>,
+    turns: u32,
+    tokens: i64,
+    /// The SDK repeats a message's usage on every content block, so only the
+    /// latest figure per message id counts.
+    current_message: Option<(String, i64)>,
+    current_tool: Option<String>,
+    finished: bool,
+}
+
+impl Tracker {
+    fn new(
+        skill_name: &str,
+        step_id: i32,
+        model: &str,
+        max_turns: Option<u32>,
+        typical_ms: Option<i64>,
+        now: Instant,
+    ) -> Self {
+        Self {
+            skill_name: skill_name.to_string(),
+            step_id,
+            model: model.to_string(),
+            max_turns,
+            typical_ms,
+            started: now,
+            last_emit: None,
+            turns: 0,
+            tokens: 0,
+            current_message: None,
+            current_tool: None,
+            finished: false,
+        }
+    }
+
+    /// Fold one sidecar message into the tracker. Returns true when progress
+    /// should be emitted now.
+    fn record(&mut self, message: &serde_json::Value, now: Instant) -> bool {
+        match message.get("type").and_then(|t| t.as_str()) {
+            Some("assistant") => {
+                let Some(inner) = message.get("message") else {
+                    return false;
+                };
+                let tokens = inner
+                    .get("usage")
+                    .map(|usage| {
+                        [
+                            "input_tokens",
+                            "output_tokens",
+                            "cache_read_input_tokens",
+                            "cache_creation_input_tokens",
+                        ]
+                        .iter()
+                        .filter_map(|k| usage.get(*k).and_then(|v| v.as_i64()))
+                        .sum::<i64>()
+                    })
+                    .unwrap_or(0);
+                let id = inner
+                    .get("id")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                // Sub-agent turns do not count toward the step's max_turns.
+                let from_subagent = message
+                    .get("parent_tool_use_id")
+                    .is_some_and(|id| id.is_string());
+                match self.current_message.take() {
+                    Some((prev_id, prev)) if !id.is_empty() && prev_id == id => self.tokens -= prev,
+                    _ if from_subagent => {}
+                    _ => self.turns += 1,
+                }
+                self.tokens += tokens;
+                self.current_message = Some((id, tokens));
+
+                let tool = inner
+                    .get("content")
+                    .and_then(|c| c.as_array())
+                    .and_then(|blocks| {
+                        blocks
+                            .iter()
+                            .rev()
+                            .find(|b| b.get("type").and_then(|t| t.as_str()) == Some("tool_use"))
+                    })
+                    .and_then(|b| b.get("name").and_then(|n| n.as_str()));
+                let tool_changed = match tool {
+                    Some(name) if self.current_tool.as_deref() != Some(name) => {
+                        self.current_tool = Some(name.to_string());
+                        true
+                    }
+                    _ => false,
+                };
+                tool_changed || self.due(now)
+            }
+            Some("result") => {
+                if let Some(turns) = message.get("num_turns").and_then(|n| n.as_u64()) {
+                    self.turns = turns as u32;
+                }
+                self.current_tool = None;
+                self.finished = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn due(&self, now: Instant) -> bool {
+        self.last_emit
+            .is_none_or(|last| now.duration_since(last) >= PROGRESS_INTERVAL)
+    }
+
+    fn snapshot(&mut self, agent_id: &str, now: Instant) -> StepProgress {
+        self.last_emit = Some(now);
+        let elapsed_ms = now.duration_since(self.started).as_millis() as i64;
+        let typical_ms = self.typical_ms.filter(|ms| *ms > 0);
+        let fraction = match (typical_ms, self.max_turns.filter(|t| *t > 0)) {
+            (Some(total), _) => Some(elapsed_ms as f64 / total as f64),
+            (None, Some(max)) => Some(self.turns as f64 / max as f64),
+            (None, None) => None,
+        };
+        let (percent, eta_ms) = if self.finished {
+            (Some(100), Some(0))
+        } else {
+            (
+                fraction.map(|f| (f * 100.0).clamp(0.0, MAX_RUNNING_PERCENT) as u8),
+                // A step running past its usual time has no meaningful ETA.
+                typical_ms
+                    .map(|total| total - elapsed_ms)
+                    .filter(|ms| *ms > 0),
+            )
+        };
+        StepProgress {
+            agent_id: agent_id.to_string(),
+            skill_name: self.skill_name.clone(),
+            step_id: self.step_id,
+            model: self.model.clone(),
+            turns_completed: self.turns,
+            max_turns: self.max_turns,
+            current_tool: self.current_tool.clone(),
+            tokens_used: self.tokens,
+            elapsed_ms,
+            estimated_total_ms: typical_ms,
+            eta_ms,
+            percent,
+            finished: self.finished,
+        }
+    }
+}
+
+/// Start tracking an agent if it runs a workflow step. Called when its request
+/// is sent to the sidecar.
+pub fn start(
+    app_handle: &tauri::AppHandle,
+    agent_id: &str,
+    skill_name: &str,
+    model: Option<&str>,
+    max_turns: Option<u32>,
+) {
+    let step_id = super::cost_ticker::infer_step_id(agent_id, skill_name);
+    if step_id < 0 {
+        return;
+    }
+    let model = model.unwrap_or(UNKNOWN_MODEL);
+    let typical_ms = app_handle.try_state::<Db>().and_then(|db| {
+        let conn = db.0.read().ok()?;
+        crate::db::typical_step_duration_ms(&conn, step_id, model).unwrap_or_else(|e| {
+            log::warn!(
+                "[step_progress] Failed to look up step {} history: {}",
+                step_id,
+                e
+            );
+            None
+        })
+    });
+    let tracker = Tracker::new(
+        skill_name,
+        step_id,
+        model,
+        max_turns,
+        typical_ms,
+        Instant::now(),
+    );
+    if let Ok(mut trackers) = TRACKERS.lock() {
+        trackers.insert(agent_id.to_string(), tracker);
+    }
+}
+
+/// Fold a sidecar message into the agent's tracker, emitting
+/// `workflow-step-progress` when an update is due.
+pub fn observe(app_handle: &tauri::AppHandle, agent_id: &str, message: &serde_json::Value) {
+    let progress = {
+        let Ok(mut trackers) = TRACKERS.lock() else {
+            return;
+        };
+        let Some(tracker) = trackers.get_mut(agent_id) else {
+            return;
+        };
+        let now = Instant::now();
+        if !tracker.record(message, now) {
+            return;
+        }
+        tracker.snapshot(agent_id, now)
+    };
+    if let Err(e) = app_handle.emit("workflow-step-progress", &progress) {
+        log::warn!(
+            "Failed to emit workflow-step-progress for {}: {}",
+            agent_id,
+            e
+        );
+    }
+}
+
+/// Stop tracking an agent once it has exited or shut down.
+pub fn finish(agent_id: &str) {
+    if let Ok(mut trackers) = TRACKERS.lock() {
+        trackers.remove(agent_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn assistant(id: &str, tokens: i64, tool: Option<&str>) -> serde_json::Value {
+        let content = match tool {
+            Some(name) => {
+                json!([{"type": "text", "text": "..."}, {"type": "tool_use", "id": "t", "name": name, "input": {}}])
+            }
+            None => json!([{"type": "text", "text": "..."}]),
+        };
+        json!({
+            "type": "assistant",
+            "message": {
+                "id": id,
+                "content": content,
+                "usage": {"input_tokens": tokens, "output_tokens": 0}
+            }
+        })
+    }
+
+    #[test]
+    fn test_record_counts_turns_tokens_and_current_tool() {
+        let start = Instant::now();
+        let mut tracker = Tracker::new("s", 2, "sonnet", Some(10), None, start);
+        assert!(tracker.record(&assistant("m1", 1000, None), start));
+        tracker.snapshot("a", start);
+
+        // Same message id: tokens replace, turn count holds.
+        assert!(tracker.record(&assistant("m1", 1200, Some("Read")), start));
+        tracker.snapshot("a", start);
+        assert!(!tracker.record(&assistant("m2", 500, Some("Read")), start));
+        let mut subagent = assistant("m3", 300, Some("Grep"));
+        subagent["parent_tool_use_id"] = json!("t");
+        assert!(tracker.record(&subagent, start));
+
+        let p = tracker.snapshot("a", start);
+        assert_eq!(p.turns_completed, 2);
+        assert_eq!(p.tokens_used, 2000);
+        assert_eq!(p.current_tool.as_deref(), Some("Grep"));
+        assert_eq!(p.percent, Some(20));
+        assert_eq!(p.eta_ms, None);
+    }
+
+    #[test]
+    fn test_snapshot_estimates_eta_from_history() {
+        let start = Instant::now();
+        let mut tracker = Tracker::new("s", 0, "sonnet", Some(50), Some(120_000), start);
+        tracker.record(&assistant("m1", 100, None), start);
+        let p = tracker.snapshot("a", start + Duration::from_secs(30));
+        assert_eq!(p.percent, Some(25));
+        assert_eq!(p.eta_ms, Some(90_000));
+        assert_eq!(p.estimated_total_ms, Some(120_000));
+
+        // Running past the usual time: capped below 100, no ETA.
+        let p = tracker.snapshot("a", start + Duration::from_secs(200));
+        assert_eq!(p.percent, Some(99));
+        assert_eq!(p.eta_ms, None);
+    }
+
+    #[test]
+    fn test_result_completes_progress() {
+        let start = Instant::now();
+        let mut tracker = Tracker::new("s", 0, "sonnet", None, None, start);
+        tracker.record(&assistant("m1", 100, Some("Write")), start);
+        assert_eq!(tracker.snapshot("a", start).percent, None);
+
+        assert!(tracker.record(&json!({"type": "result", "num_turns": 4}), start));
+        let p = tracker.snapshot("a", start);
+        assert!(p.finished);
+        assert_eq!(p.percent, Some(100));
+        assert_eq!(p.eta_ms, Some(0));
+        assert_eq!(p.turns_completed, 4);
+        assert_eq!(p.current_tool, None);
+    }
+}
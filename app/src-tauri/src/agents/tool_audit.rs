@@ -0,0 +1,198 @@
+//! Audit log of the tool calls agents make.
+//!
+//! `tool_use` blocks in assistant messages (including those of sub-agents)
+//! are recorded to `agent_tool_calls` as messages pass through
+//! `handle_sidecar_message`, together with whether the tool was among the
+//! run's allowed tools. Sub-agents declare their own tools, so their calls are
+//! recorded without that check. A call the SDK refused still shows up here,
+//! so the log answers both "what did this run do" and "what did it try to do".
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use tauri::Manager;
+
+use crate::db::Db;
+
+/// Longest input summary stored per call.
+const MAX_SUMMARY_CHARS: usize = 200;
+
+/// Input fields that identify what a tool call touched, in preference order.
+const SUMMARY_FIELDS: &[&str] = &[
+    "command",
+    "file_path",
+    "path",
+    "pattern",
+    "skill",
+    "description",
+    "url",
+    "query",
+];
+
+#[derive(Debug)]
+struct AuditRun {
+    skill_name: String,
+    step_id: i32,
+    allowed_tools: Option<Vec<String>>,
+}
+
+/// Runs being audited, keyed by agent id.
+static RUNS: Mutex<BTreeMap<String, AuditRun>> = Mutex::new(BTreeMap::new());
+
+/// Start auditing an agent. `allowed_tools` is `None` when the run's tools are
+/// not known (follow-up messages in a streaming session).
+pub fn start(agent_id: &str, skill_name: &str, allowed_tools: Option<&[String]>) {
+    let run = AuditRun {
+        skill_name: skill_name.to_string(),
+        step_id: super::cost_ticker::infer_step_id(agent_id, skill_name),
+        allowed_tools: allowed_tools.map(|t| t.to_vec()),
+    };
+    if let Ok(mut runs) = RUNS.lock() {
+        runs.insert(agent_id.to_string(), run);
+    }
+}
+
+/// A call extracted from a `tool_use` block.
+#[derive(Debug, PartialEq)]
+struct ToolUse {
+    id: String,
+    name: String,
+    input_summary: String,
+}
+
+fn summarize_input(input: &serde_json::Value) -> String {
+    let summary = SUMMARY_FIELDS
+        .iter()
+        .find_map(|field| input.get(*field).and_then(|v| v.as_str()))
+        .map(str::to_string)
+        .unwrap_or_default();
+    let summary = summary.split_whitespace().collect::<Vec<_>>().join(" ");
+    match summary.char_indices().nth(MAX_SUMMARY_CHARS) {
+        Some((end, _)) => format!("{}…", &summary[..end]),
+        None => summary,
+    }
+}
+
+/// `tool_use` blocks of an assistant message.
+fn tool_uses(message: &serde_json::Value) -> Vec<ToolUse> {
+    if message.get("type").and_then(|t| t.as_str()) != Some("assistant") {
+        return Vec::new();
+    }
+    message
+        .pointer("/message/content")
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|block| block.get("type").and_then(|t| t.as_str()) == Some("tool_use"))
+        .filter_map(|block| {
+            Some(ToolUse {
+                id: block.get("id")?.as_str()?.to_string(),
+                name: block.get("name")?.as_str()?.to_string(),
+                input_summary: summarize_input(block.get("input").unwrap_or(&serde_json::Value::Null)),
+            })
+        })
+        .collect()
+}
+
+/// Record the tool calls in a sidecar message, if the agent is being audited.
+pub fn observe(app_handle: &tauri::AppHandle, agent_id: &str, message: &serde_json::Value) {
+    let uses = tool_uses(message);
+    if uses.is_empty() {
+        return;
+    }
+    let from_subagent = message
+        .get("parent_tool_use_id")
+        .is_some_and(|id| id.is_string());
+    let (skill_name, step_id, allowed_tools) = {
+        let Ok(runs) = RUNS.lock() else {
+            return;
+        };
+        let Some(run) = runs.get(agent_id) else {
+            return;
+        };
+        (run.skill_name.clone(), run.step_id, run.allowed_tools.clone())
+    };
+    let Some(db) = app_handle.try_state::<Db>() else {
+        return;
+    };
+    let conn = match db.0.lock() {
+        Ok(conn) => conn,
+        Err(e) => {
+            log::warn!("[tool_audit] Failed to acquire DB lock: {}", e);
+            return;
+        }
+    };
+    for tool in uses {
+        let allowed = allowed_tools
+            .as_ref()
+            .filter(|_| !from_subagent)
+            .map(|tools| tools.contains(&tool.name));
+        if allowed == Some(false) {
+            log::warn!(
+                "[tool_audit] {} called '{}' outside its allowed tools",
+                agent_id,
+                tool.name
+            );
+        }
+        if let Err(e) = crate::db::insert_agent_tool_call(
+            &conn,
+            agent_id,
+            &skill_name,
+            step_id,
+            &tool.id,
+            &tool.name,
+            &tool.input_summary,
+            allowed,
+        ) {
+            log::warn!("[tool_audit] Failed to record {} for {}: {}", tool.name, agent_id, e);
+        }
+    }
+}
+
+/// Stop auditing an agent once it has exited or shut down.
+pub fn finish(agent_id: &str) {
+    if let Ok(mut runs) = RUNS.lock() {
+        runs.remove(agent_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_tool_uses_extracts_calls_from_assistant_messages() {
+        let message = json!({
+            "type": "assistant",
+            "message": {"content": [
+                {"type": "text", "text": "Let me look."},
+                {"type": "tool_use", "id": "t1", "name": "Bash",
+                 "input": {"command": "ls   -la\n  context/", "description": "List files"}},
+                {"type": "tool_use", "id": "t2", "name": "Read",
+                 "input": {"file_path": "/w/skill/SKILL.md"}},
+            ]}
+        });
+        assert_eq!(
+            tool_uses(&message),
+            vec![
+                ToolUse { id: "t1".into(), name: "Bash".into(), input_summary: "ls -la context/".into() },
+                ToolUse { id: "t2".into(), name: "Read".into(), input_summary: "/w/skill/SKILL.md".into() },
+            ]
+        );
+
+        let result = json!({"type": "user", "message": {"content": [
+            {"type": "tool_result", "tool_use_id": "t1", "content": "ok"}
+        ]}});
+        assert!(tool_uses(&result).is_empty());
+    }
+
+    #[test]
+    fn test_summarize_input_truncates_long_values() {
+        let long = "x".repeat(MAX_SUMMARY_CHARS + 50);
+        let summary = summarize_input(&json!({"command": long}));
+        assert_eq!(summary.chars().count(), MAX_SUMMARY_CHARS + 1);
+        assert!(summary.ends_with('…'));
+        assert_eq!(summarize_input(&json!({"todos": []})), "");
+    }
+}
@@ -0,0 +1,104 @@
+//! Append-only record of who changed what, for compliance.
+//!
+//! Commands that create, rename, delete or push a skill, or take or release
+//! its lock, call `record` once the change has gone through; `write_settings`
+//! records every write that changes a field, naming the fields but never their
+//! values. Retention passes and the user data purge record what they deleted.
+//! Each entry carries this instance's id and the actor: the signed-in GitHub
+//! login, else the OS user. Triggers on `audit_log` refuse updates and
+//! deletes; only a retention pass lifts them (see `db::prune_expired_data`).
+//! Entries are queried and exported with `commands::audit_log`. Recording
+//! never fails the caller, and uses the `unknown` instance until `init` runs
+//! in `setup()`.
+
+use std::sync::Mutex;
+
+pub const SKILL_CREATED: &str = "skill.created";
+pub const SKILL_RENAMED: &str = "skill.renamed";
+pub const SKILL_DELETED: &str = "skill.deleted";
+pub const SKILL_PUSHED: &str = "skill.pushed";
+pub const LOCK_ACQUIRED: &str = "lock.acquired";
+pub const LOCK_RELEASED: &str = "lock.released";
+pub const SETTINGS_CHANGED: &str = "settings.changed";
+pub const DATA_PRUNED: &str = "data.pruned";
+pub const DATA_PURGED: &str = "data.purged";
+
+pub const AUDIT_ACTIONS: &[&str] = &[
+    SKILL_CREATED,
+    SKILL_RENAMED,
+    SKILL_DELETED,
+    SKILL_PUSHED,
+    LOCK_ACQUIRED,
+    LOCK_RELEASED,
+    SETTINGS_CHANGED,
+    DATA_PRUNED,
+    DATA_PURGED,
+];
+
+/// Longest summary stored per entry.
+const MAX_SUMMARY_CHARS: usize = 500;
+
+const UNKNOWN_INSTANCE: &str = "unknown";
+
+static INSTANCE_ID: Mutex<Option<String>> = Mutex::new(None);
+
+/// Attribute entries to this instance. Called once from `setup()`.
+pub fn init(instance_id: &str) {
+    match INSTANCE_ID.lock() {
+        Ok(mut id) => *id = Some(instance_id.to_string()),
+        Err(e) => log::warn!("[audit] Failed to acquire instance lock: {}", e),
+    }
+}
+
+fn instance_id() -> String {
+    INSTANCE_ID
+        .lock()
+        .ok()
+        .and_then(|id| id.clone())
+        .unwrap_or_else(|| UNKNOWN_INSTANCE.to_string())
+}
+
+/// The signed-in GitHub login, else the OS user.
+fn actor(conn: &rusqlite::Connection) -> Option<String> {
+    crate::db::read_settings(conn)
+        .ok()
+        .and_then(|s| s.github_user_login)
+        .filter(|login| !login.is_empty())
+        .or_else(|| std::env::var("USER").or_else(|_| std::env::var("USERNAME")).ok())
+}
+
+/// Append an entry. `summary` says what changed and must not include secrets.
+pub fn record(conn: &rusqlite::Connection, action: &str, skill_name: Option<&str>, summary: &str) {
+    let summary = match summary.char_indices().nth(MAX_SUMMARY_CHARS) {
+        Some((end, _)) => format!("{}…", &summary[..end]),
+        None => summary.to_string(),
+    };
+    if let Err(e) = crate::db::insert_audit_entry(
+        conn,
+        &instance_id(),
+        actor(conn).as_deref(),
+        action,
+        skill_name,
+        &summary,
+    ) {
+        log::warn!("[audit] Failed to record {} for {:?}: {}", action, skill_name, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AuditLogFilter;
+
+    #[test]
+    fn test_record_truncates_long_summaries() {
+        let conn = crate::commands::test_utils::create_test_db();
+        record(&conn, SKILL_RENAMED, Some("revenue"), &"é".repeat(MAX_SUMMARY_CHARS + 10));
+
+        let entries = crate::db::list_audit_log(&conn, &AuditLogFilter::default(), None).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, SKILL_RENAMED);
+        assert_eq!(entries[0].summary.chars().count(), MAX_SUMMARY_CHARS + 1);
+        assert!(entries[0].summary.ends_with("é…"));
+    }
+}
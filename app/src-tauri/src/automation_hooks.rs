@@ -0,0 +1,362 @@
+//! Shell commands and webhooks run on workflow events.
+//!
+//! The workflow, packaging and budget code call `publish` when a step is
+//! completed (`step_completed`), a skill is packaged (`skill_packaged`) or an
+//! agent is stopped over budget (`budget_exceeded`). Every enabled hook in
+//! `AppSettings::automation_hooks` subscribed to the event receives it as
+//! JSON: a webhook as the body of a POST, a command on stdin with
+//! `SKILL_BUILDER_EVENT` and `SKILL_BUILDER_SKILL` set in its environment.
+//! Failed deliveries are retried with backoff, and the outcome is written to
+//! `automation_hook_deliveries`. Delivery runs in the background, so callers
+//! may publish while holding the DB lock. Publishing is a no-op until `init`
+//! runs in `setup()`.
+
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::Manager;
+use tokio::io::AsyncWriteExt;
+
+use crate::db::Db;
+use crate::types::AutomationHook;
+
+pub const HOOK_EVENTS: &[&str] = &["step_completed", "skill_packaged", "budget_exceeded"];
+pub const HOOK_KIND_COMMAND: &str = "command";
+pub const HOOK_KIND_WEBHOOK: &str = "webhook";
+
+const MAX_ATTEMPTS: u32 = 3;
+/// Delay before the first retry; doubled for each later one.
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(30);
+/// Longest command stderr kept in the delivery log.
+const MAX_DETAIL_CHARS: usize = 500;
+
+static APP: Mutex<Option<tauri::AppHandle>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum HookEvent {
+    StepCompleted {
+        skill_name: String,
+        step_id: u32,
+    },
+    SkillPackaged {
+        skill_name: String,
+        file_path: String,
+        size_bytes: u64,
+        locale: Option<String>,
+    },
+    BudgetExceeded {
+        skill_name: String,
+        /// "skill" or "global".
+        scope: String,
+        spent_usd: f64,
+        budget_usd: f64,
+    },
+}
+
+impl HookEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookEvent::StepCompleted { .. } => "step_completed",
+            HookEvent::SkillPackaged { .. } => "skill_packaged",
+            HookEvent::BudgetExceeded { .. } => "budget_exceeded",
+        }
+    }
+
+    fn skill_name(&self) -> &str {
+        match self {
+            HookEvent::StepCompleted { skill_name, .. }
+            | HookEvent::SkillPackaged { skill_name, .. }
+            | HookEvent::BudgetExceeded { skill_name, .. } => skill_name,
+        }
+    }
+}
+
+/// Start delivering events. Called once from `setup()`.
+pub fn init(app: tauri::AppHandle) {
+    match APP.lock() {
+        Ok(mut handle) => *handle = Some(app),
+        Err(e) => log::warn!("[automation_hooks] Failed to acquire app lock: {}", e),
+    }
+}
+
+/// Check hooks before they are saved to settings.
+pub fn validate_hooks(hooks: &[AutomationHook]) -> Result<(), String> {
+    for (i, hook) in hooks.iter().enumerate() {
+        let name = hook.name.trim();
+        if name.is_empty() {
+            return Err("Automation hook name cannot be empty".to_string());
+        }
+        if hooks[..i].iter().any(|h| h.name.trim() == name) {
+            return Err(format!("Duplicate automation hook name '{}'", name));
+        }
+        if hook.events.is_empty() {
+            return Err(format!("Automation hook '{}' has no events", name));
+        }
+        if let Some(event) = hook.events.iter().find(|e| !HOOK_EVENTS.contains(&e.as_str())) {
+            return Err(format!(
+                "Automation hook '{}' has unknown event '{}' (expected one of: {})",
+                name,
+                event,
+                HOOK_EVENTS.join(", ")
+            ));
+        }
+        match hook.kind.as_str() {
+            HOOK_KIND_WEBHOOK => {
+                let target = hook.target.trim();
+                if !target.starts_with("https://") && !target.starts_with("http://") {
+                    return Err(format!("Automation hook '{}' needs an http(s) URL", name));
+                }
+            }
+            HOOK_KIND_COMMAND => {
+                if hook.target.trim().is_empty() {
+                    return Err(format!("Automation hook '{}' has no command", name));
+                }
+            }
+            other => {
+                return Err(format!(
+                    "Automation hook '{}' has unknown kind '{}' (expected 'command' or 'webhook')",
+                    name, other
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+fn subscribed<'a>(hooks: &'a [AutomationHook], event: &str) -> Vec<&'a AutomationHook> {
+    hooks
+        .iter()
+        .filter(|h| h.enabled && h.events.iter().any(|e| e == event))
+        .collect()
+}
+
+/// The JSON sent to hooks: the event's fields plus when it happened.
+fn payload(event: &HookEvent, timestamp: &str) -> serde_json::Value {
+    let mut value = serde_json::to_value(event).unwrap_or_default();
+    if let Some(fields) = value.as_object_mut() {
+        fields.insert("timestamp".to_string(), timestamp.into());
+    }
+    value
+}
+
+/// Send `event` to every hook subscribed to it. Never blocks or fails the caller.
+pub fn publish(event: HookEvent) {
+    let app = match APP.lock() {
+        Ok(app) => app.clone(),
+        Err(e) => {
+            log::warn!("[automation_hooks] Failed to acquire app lock: {}", e);
+            return;
+        }
+    };
+    let Some(app) = app else {
+        return;
+    };
+    tauri::async_runtime::spawn(async move { deliver_all(&app, event).await });
+}
+
+async fn deliver_all(app: &tauri::AppHandle, event: HookEvent) {
+    let Some(db) = app.try_state::<Db>() else {
+        return;
+    };
+    let hooks = match db.0.lock() {
+        Ok(conn) => crate::db::read_settings(&conn).map(|s| s.automation_hooks),
+        Err(e) => Err(e.to_string()),
+    };
+    let hooks = match hooks {
+        Ok(hooks) => hooks,
+        Err(e) => {
+            log::warn!("[automation_hooks] Failed to read hooks for {}: {}", event.name(), e);
+            return;
+        }
+    };
+    let body = payload(&event, &chrono::Utc::now().to_rfc3339());
+    for hook in subscribed(&hooks, event.name()) {
+        let (attempts, result) = deliver_with_retry(hook, &event, &body).await;
+        let (status, detail) = match result {
+            Ok(detail) => {
+                log::info!("[automation_hooks] {} -> '{}': {}", event.name(), hook.name, detail);
+                ("delivered", detail)
+            }
+            Err(e) => {
+                log::warn!(
+                    "[automation_hooks] {} -> '{}' failed after {} attempt(s): {}",
+                    event.name(),
+                    hook.name,
+                    attempts,
+                    e
+                );
+                ("failed", e)
+            }
+        };
+        let recorded = match db.0.lock() {
+            Ok(conn) => crate::db::record_hook_delivery(
+                &conn,
+                &hook.name,
+                event.name(),
+                event.skill_name(),
+                &body.to_string(),
+                attempts,
+                status,
+                &detail,
+            ),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = recorded {
+            log::warn!("[automation_hooks] Failed to log delivery to '{}': {}", hook.name, e);
+        }
+    }
+}
+
+/// Returns the number of attempts made and the last attempt's outcome.
+async fn deliver_with_retry(
+    hook: &AutomationHook,
+    event: &HookEvent,
+    body: &serde_json::Value,
+) -> (u32, Result<String, String>) {
+    let mut attempt = 1;
+    loop {
+        let result = match hook.kind.as_str() {
+            HOOK_KIND_WEBHOOK => post_webhook(hook.target.trim(), body).await,
+            _ => run_command(&hook.target, event, body).await,
+        };
+        if result.is_ok() || attempt == MAX_ATTEMPTS {
+            return (attempt, result);
+        }
+        tokio::time::sleep(RETRY_DELAY * 2u32.pow(attempt - 1)).await;
+        attempt += 1;
+    }
+}
+
+async fn post_webhook(url: &str, body: &serde_json::Value) -> Result<String, String> {
+    let response = reqwest::Client::new()
+        .post(url)
+        .header("User-Agent", "SkillBuilder")
+        .timeout(DELIVERY_TIMEOUT)
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(format!("HTTP {}", status.as_u16()))
+    } else {
+        Err(format!("HTTP {}", status))
+    }
+}
+
+async fn run_command(command: &str, event: &HookEvent, body: &serde_json::Value) -> Result<String, String> {
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut cmd = tokio::process::Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        cmd
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut cmd = {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    };
+    cmd.env("SKILL_BUILDER_EVENT", event.name())
+        .env("SKILL_BUILDER_SKILL", event.skill_name())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let mut child = cmd.spawn().map_err(|e| format!("Failed to start command: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // Commands that ignore stdin may exit before it is written.
+        if let Err(e) = stdin.write_all(body.to_string().as_bytes()).await {
+            log::debug!("[automation_hooks] Command closed stdin early: {}", e);
+        }
+    }
+    let output = tokio::time::timeout(DELIVERY_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| format!("Command timed out after {}s", DELIVERY_TIMEOUT.as_secs()))?
+        .map_err(|e| format!("Command failed: {}", e))?;
+    if output.status.success() {
+        return Ok("exit 0".to_string());
+    }
+    let stderr: String = String::from_utf8_lossy(&output.stderr).trim().chars().take(MAX_DETAIL_CHARS).collect();
+    if stderr.is_empty() {
+        Err(output.status.to_string())
+    } else {
+        Err(format!("{}: {}", output.status, stderr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(name: &str, kind: &str, target: &str, events: &[&str]) -> AutomationHook {
+        AutomationHook {
+            name: name.to_string(),
+            events: events.iter().map(|e| e.to_string()).collect(),
+            kind: kind.to_string(),
+            target: target.to_string(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_validate_hooks() {
+        let slack = hook("slack", HOOK_KIND_WEBHOOK, "https://hooks.example.com/T0", &["skill_packaged"]);
+        let notify = hook("notify", HOOK_KIND_COMMAND, "notify-send done", &["step_completed", "budget_exceeded"]);
+        assert!(validate_hooks(&[slack.clone(), notify.clone()]).is_ok());
+
+        assert!(validate_hooks(&[slack.clone(), slack.clone()]).unwrap_err().contains("Duplicate"));
+        let bad_event = hook("x", HOOK_KIND_COMMAND, "true", &["skill_deleted"]);
+        assert!(validate_hooks(&[bad_event]).unwrap_err().contains("unknown event"));
+        let bad_url = hook("x", HOOK_KIND_WEBHOOK, "hooks.example.com", &["skill_packaged"]);
+        assert!(validate_hooks(&[bad_url]).unwrap_err().contains("http(s) URL"));
+        assert!(validate_hooks(&[hook("x", "email", "a@b.c", &["skill_packaged"])]).is_err());
+        assert!(validate_hooks(&[hook("x", HOOK_KIND_COMMAND, "true", &[])]).is_err());
+    }
+
+    #[test]
+    fn test_payload_and_subscriptions() {
+        let event = HookEvent::SkillPackaged {
+            skill_name: "sales".to_string(),
+            file_path: "/skills/sales/sales.skill".to_string(),
+            size_bytes: 2048,
+            locale: None,
+        };
+        let body = payload(&event, "2026-10-16T00:00:00Z");
+        assert_eq!(body["event"], "skill_packaged");
+        assert_eq!(body["skill_name"], "sales");
+        assert_eq!(body["size_bytes"], 2048);
+        assert_eq!(body["timestamp"], "2026-10-16T00:00:00Z");
+
+        let mut disabled = hook("off", HOOK_KIND_COMMAND, "true", &["skill_packaged"]);
+        disabled.enabled = false;
+        let hooks = vec![
+            hook("slack", HOOK_KIND_WEBHOOK, "https://hooks.example.com/T0", &["skill_packaged"]),
+            hook("steps", HOOK_KIND_COMMAND, "true", &["step_completed"]),
+            disabled,
+        ];
+        let names: Vec<&str> = subscribed(&hooks, event.name()).iter().map(|h| h.name.as_str()).collect();
+        assert_eq!(names, vec!["slack"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_hook_gets_event_on_stdin_and_env() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("event.json");
+        let event = HookEvent::StepCompleted { skill_name: "sales".to_string(), step_id: 2 };
+        let body = payload(&event, "2026-10-16T00:00:00Z");
+        let command = format!("cat > '{}' && test \"$SKILL_BUILDER_EVENT\" = step_completed", out.display());
+        assert_eq!(run_command(&command, &event, &body).await.unwrap(), "exit 0");
+        let written: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(written["step_id"], 2);
+
+        let failed = run_command("echo boom >&2; exit 3", &event, &body).await.unwrap_err();
+        assert!(failed.contains("boom"), "{}", failed);
+    }
+}
@@ -80,6 +80,7 @@ pub fn clean_step_output_thorough(workspace_path: &str, skill_name: &str, step_i
         "[clean_step_output_thorough] skill='{}': step={} workspace={} skills_path={}",
         skill_name, step_id, workspace_path, skills_path
     );
+    crate::commands::step_artifacts::delete_step_artifacts(workspace_path, skill_name, step_id);
 
     if step_id == 3 {
         // Step 3 output lives in skills_path/skill_name/
@@ -0,0 +1,313 @@
+//! Import-time activation policy for workspace skills.
+//!
+//! Newly imported skills are matched against the rules in settings, then the
+//! team default rules synced with the taxonomy, first match wins; the policy's
+//! default action applies when nothing matches. Every decision is recorded in
+//! `activation_decisions`, and toggling the skill afterwards marks it overridden.
+
+use std::path::Path;
+
+use crate::db::Db;
+use crate::types::{ActivationDecision, ActivationPolicy, ActivationRule};
+
+pub(crate) const ACTION_ACTIVATE: &str = "activate";
+pub(crate) const ACTION_DEACTIVATE: &str = "deactivate";
+
+/// Source label for skills uploaded from a `.skill` file.
+pub(crate) const SOURCE_UPLOAD: &str = "upload";
+
+/// Facts about an import that rules are matched against.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ActivationContext {
+    /// Marketplace source URL, or `upload`.
+    pub source: String,
+    pub author: Option<String>,
+    pub domain: Option<String>,
+    /// `unsigned`, `trusted` or `untrusted`.
+    pub signature: String,
+}
+
+impl ActivationContext {
+    fn describe(&self) -> String {
+        format!(
+            "source={}, author={}, domain={}, signature={}",
+            self.source,
+            self.author.as_deref().unwrap_or("(none)"),
+            self.domain.as_deref().unwrap_or("(none)"),
+            self.signature
+        )
+    }
+}
+
+/// Read a scalar frontmatter key that `parse_frontmatter_full` does not track.
+fn frontmatter_value(content: &str, key: &str) -> Option<String> {
+    let body = content.trim_start().strip_prefix("---")?;
+    let block = &body[..body.find("\n---")?];
+    let prefix = format!("{}:", key);
+    block.lines().find_map(|line| {
+        let value = line.trim().strip_prefix(&prefix)?;
+        let value = value.trim().trim_matches('"').trim_matches('\'').trim();
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+/// Build the context for a skill already extracted to `disk_path`.
+pub(crate) fn context_for(disk_path: &str, source_url: Option<&str>, signature: &str) -> ActivationContext {
+    let skill_md = std::fs::read_to_string(Path::new(disk_path).join("SKILL.md")).unwrap_or_default();
+    ActivationContext {
+        source: source_url.unwrap_or(SOURCE_UPLOAD).to_string(),
+        author: frontmatter_value(&skill_md, "author"),
+        domain: frontmatter_value(&skill_md, "domain"),
+        signature: signature.to_string(),
+    }
+}
+
+fn normalize_source(source: &str) -> String {
+    source
+        .trim()
+        .trim_start_matches("https://")
+        .trim_start_matches("github.com/")
+        .trim_end_matches('/')
+        .to_lowercase()
+}
+
+/// A rule criterion counts only when it is non-blank.
+fn criterion(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn rule_matches(rule: &ActivationRule, ctx: &ActivationContext) -> bool {
+    if let Some(source) = criterion(&rule.source) {
+        if !normalize_source(&ctx.source).starts_with(&normalize_source(source)) {
+            return false;
+        }
+    }
+    for (want, have) in [(criterion(&rule.author), &ctx.author), (criterion(&rule.domain), &ctx.domain)] {
+        if let Some(want) = want {
+            if !have.as_deref().is_some_and(|h| h.eq_ignore_ascii_case(want)) {
+                return false;
+            }
+        }
+    }
+    if let Some(signature) = criterion(&rule.signature) {
+        if !signature.eq_ignore_ascii_case(&ctx.signature) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Evaluate local rules, then team rules, then the default action.
+/// Returns `(is_active, matched_rule)`.
+pub(crate) fn evaluate(
+    policy: &ActivationPolicy,
+    team_rules: &[ActivationRule],
+    ctx: &ActivationContext,
+) -> (bool, String) {
+    let candidates = policy
+        .rules
+        .iter()
+        .enumerate()
+        .map(|(i, r)| (r, format!("settings rule {}", i + 1)))
+        .chain(
+            team_rules
+                .iter()
+                .enumerate()
+                .map(|(i, r)| (r, format!("team rule {}", i + 1))),
+        );
+    for (rule, label) in candidates {
+        if rule_matches(rule, ctx) {
+            return (rule.action != ACTION_DEACTIVATE, label);
+        }
+    }
+    (policy.default_action != ACTION_DEACTIVATE, "default".to_string())
+}
+
+/// Check the rule actions so a typo cannot silently activate everything.
+pub(crate) fn validate_policy(policy: &ActivationPolicy) -> Result<(), String> {
+    let valid = |a: &str| a == ACTION_ACTIVATE || a == ACTION_DEACTIVATE;
+    if !valid(&policy.default_action) {
+        return Err(format!("Invalid default activation action '{}'", policy.default_action));
+    }
+    for (i, rule) in policy.rules.iter().enumerate() {
+        if !valid(&rule.action) {
+            return Err(format!("Activation rule {}: invalid action '{}'", i + 1, rule.action));
+        }
+        if let Some(sig) = rule.signature.as_deref().filter(|s| !s.is_empty()) {
+            if !matches!(sig, "unsigned" | "trusted" | "untrusted") {
+                return Err(format!("Activation rule {}: invalid signature status '{}'", i + 1, sig));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Apply the policy to a freshly inserted workspace skill, deactivating it when
+/// the policy says so, and record the decision. Returns whether it stays active.
+pub(crate) fn apply_activation_policy(
+    conn: &rusqlite::Connection,
+    workspace_path: &str,
+    skill_id: &str,
+    skill_name: &str,
+    ctx: &ActivationContext,
+) -> Result<bool, String> {
+    let settings = crate::db::read_settings(conn)?;
+    let team_rules = crate::db::read_team_taxonomy(conn)?
+        .map(|t| t.activation_rules)
+        .unwrap_or_default();
+    let (is_active, matched_rule) = evaluate(&settings.activation_policy, &team_rules, ctx);
+
+    if !is_active {
+        super::imported_skills::toggle_skill_active_inner(skill_id, skill_name, false, workspace_path, conn)?;
+    }
+    let reason = ctx.describe();
+    if let Err(e) = crate::db::record_activation_decision(conn, skill_name, is_active, &matched_rule, &reason) {
+        log::warn!("[apply_activation_policy] failed to record decision for '{}': {}", skill_name, e);
+    }
+    log::info!(
+        "[apply_activation_policy] '{}' imported as {} by {} ({})",
+        skill_name,
+        if is_active { "active" } else { "inactive" },
+        matched_rule,
+        reason
+    );
+    Ok(is_active)
+}
+
+#[tauri::command]
+pub fn list_activation_decisions(
+    skill_name: Option<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<ActivationDecision>, String> {
+    log::info!("[list_activation_decisions] skill={:?}", skill_name);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[list_activation_decisions] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::list_activation_decisions(&conn, skill_name.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(action: &str) -> ActivationRule {
+        ActivationRule {
+            action: action.to_string(),
+            source: None,
+            author: None,
+            domain: None,
+            signature: None,
+        }
+    }
+
+    fn ctx(source: &str, signature: &str) -> ActivationContext {
+        ActivationContext {
+            source: source.to_string(),
+            author: Some("Data Platform".to_string()),
+            domain: Some("Finance".to_string()),
+            signature: signature.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_frontmatter_value_reads_untracked_keys() {
+        let md = "---\nname: s\nauthor: \"Data Platform\"\ndomain: Finance\n---\nbody\nauthor: nope";
+        assert_eq!(frontmatter_value(md, "author").as_deref(), Some("Data Platform"));
+        assert_eq!(frontmatter_value(md, "domain").as_deref(), Some("Finance"));
+        assert_eq!(frontmatter_value(md, "owner"), None);
+        assert_eq!(frontmatter_value("no frontmatter", "author"), None);
+    }
+
+    #[test]
+    fn test_evaluate_first_match_wins_local_before_team() {
+        let policy = ActivationPolicy {
+            rules: vec![ActivationRule {
+                source: Some("https://github.com/acme/skills".to_string()),
+                ..rule(ACTION_ACTIVATE)
+            }],
+            default_action: ACTION_DEACTIVATE.to_string(),
+        };
+        let team = vec![
+            ActivationRule { signature: Some("trusted".to_string()), ..rule(ACTION_ACTIVATE) },
+            ActivationRule { domain: Some("finance".to_string()), ..rule(ACTION_DEACTIVATE) },
+        ];
+
+        assert_eq!(
+            evaluate(&policy, &team, &ctx("acme/skills#main", "unsigned")),
+            (true, "settings rule 1".to_string())
+        );
+        assert_eq!(
+            evaluate(&policy, &team, &ctx("other/repo", "trusted")),
+            (true, "team rule 1".to_string())
+        );
+        assert_eq!(
+            evaluate(&policy, &team, &ctx("other/repo", "untrusted")),
+            (false, "team rule 2".to_string())
+        );
+        let mut unknown = ctx(SOURCE_UPLOAD, "unsigned");
+        unknown.domain = None;
+        assert_eq!(evaluate(&policy, &team, &unknown), (false, "default".to_string()));
+    }
+
+    #[test]
+    fn test_default_policy_activates_everything() {
+        let (active, matched) = evaluate(&ActivationPolicy::default(), &[], &ctx(SOURCE_UPLOAD, "unsigned"));
+        assert!(active);
+        assert_eq!(matched, "default");
+    }
+
+    #[test]
+    fn test_validate_policy_rejects_unknown_actions() {
+        assert!(validate_policy(&ActivationPolicy::default()).is_ok());
+        let bad = ActivationPolicy { rules: vec![rule("enable")], ..Default::default() };
+        assert!(validate_policy(&bad).unwrap_err().contains("rule 1"));
+        let bad_sig = ActivationPolicy {
+            rules: vec![ActivationRule { signature: Some("signed".to_string()), ..rule(ACTION_ACTIVATE) }],
+            ..Default::default()
+        };
+        assert!(validate_policy(&bad_sig).is_err());
+    }
+
+    #[test]
+    fn test_apply_policy_deactivates_and_records_decision() {
+        let conn = crate::commands::test_utils::create_test_db();
+        let tmp = tempfile::tempdir().unwrap();
+        let workspace = tmp.path().to_str().unwrap();
+        let skill_dir = tmp.path().join(".claude/skills/untrusted-skill");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(skill_dir.join("SKILL.md"), "---\nname: untrusted-skill\n---\n").unwrap();
+
+        let mut settings = crate::db::read_settings(&conn).unwrap();
+        settings.activation_policy.default_action = ACTION_DEACTIVATE.to_string();
+        crate::db::write_settings(&conn, &settings).unwrap();
+
+        let skill = crate::types::WorkspaceSkill {
+            skill_id: "id-u".to_string(),
+            skill_name: "untrusted-skill".to_string(),
+            description: None,
+            is_active: true,
+            is_bundled: false,
+            disk_path: skill_dir.to_string_lossy().to_string(),
+            imported_at: "2025-01-01 00:00:00".to_string(),
+            purpose: None,
+            version: None,
+            model: None,
+            argument_hint: None,
+            user_invocable: None,
+            disable_model_invocation: None,
+            marketplace_source_url: None,
+        };
+        crate::db::insert_workspace_skill(&conn, &skill).unwrap();
+
+        let context = context_for(&skill.disk_path, None, "unsigned");
+        let active = apply_activation_policy(&conn, workspace, "id-u", "untrusted-skill", &context).unwrap();
+        assert!(!active);
+        assert!(tmp.path().join(".claude/skills/.inactive/untrusted-skill").exists());
+
+        let decisions = crate::db::list_activation_decisions(&conn, Some("untrusted-skill")).unwrap();
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].matched_rule, "default");
+        assert!(decisions[0].reason.contains("source=upload"));
+    }
+}
@@ -75,13 +75,13 @@ pub async fn start_agent(
         "[start_agent] agent_id={} model={} skill_name={} agent_name={:?}",
         agent_id, model, skill_name, agent_name
     );
-    let (api_key, extended_thinking, interleaved_thinking_beta, sdk_effort, fallback_model) = {
+    let (api_key, extended_thinking, interleaved_thinking_beta, sdk_effort, fallback_model, bash_sandbox) = {
         let conn = db.0.lock().map_err(|e| {
             log::error!("[start_agent] Failed to acquire DB lock: {}", e);
             e.to_string()
         })?;
         let settings = crate::db::read_settings_hydrated(&conn)?;
-        let key = match settings.anthropic_api_key {
+        let key = match crate::commands::offline::agent_api_key(&settings) {
             Some(k) => k,
             None => return Err("Anthropic API key not configured".to_string()),
         };
@@ -90,12 +90,15 @@ pub async fn start_agent(
             .preferred_model
             .clone()
             .unwrap_or_else(|| "sonnet".to_string());
+        let bash_sandbox =
+            crate::commands::tool_policy::bash_sandbox_for_run(&conn, &skill_name, &[&cwd])?;
         (
             key,
             settings.extended_thinking,
             settings.interleaved_thinking_beta,
             settings.sdk_effort.clone(),
             Some(preferred_model),
+            bash_sandbox,
         )
     };
 
@@ -144,6 +147,10 @@ pub async fn start_agent(
         agent_name,
         required_plugins: None,
         conversation_history: None,
+        resume: None,
+        bash_sandbox,
+        disallowed_tools: None,
+        local_model: None,
     };
 
     sidecar::spawn_sidecar(
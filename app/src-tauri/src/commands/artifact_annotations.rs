@@ -0,0 +1,371 @@
+//! Reviewer annotations on a skill's context files.
+//!
+//! During step review a comment can be attached to a line range of a file
+//! under `{workspace}/{skill}/context/`, such as `clarifications.json` or the
+//! decisions file. Each annotation keeps a copy of the lines it was made on.
+//! Whenever annotations are listed the file is re-read and every annotation
+//! is re-anchored: if its lines moved, it follows them (exact match nearest
+//! the old position first, then the closest near-match); if they can no
+//! longer be found, it keeps its last range and is flagged as orphaned.
+//! Annotations travel with the skill in `.skillpack` exports.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::imported_skills::validate_skill_name;
+use crate::db::Db;
+use crate::types::ArtifactAnnotation;
+
+/// Files that can be annotated live under this workspace subdirectory.
+const ANNOTATABLE_DIR: &str = "context/";
+
+/// Minimum word overlap for a near-match to keep an annotation anchored.
+const MIN_FUZZY_SIMILARITY: f64 = 0.6;
+
+fn validate_file(file: &str) -> Result<(), String> {
+    let path = Path::new(file);
+    let inside = file.starts_with(ANNOTATABLE_DIR)
+        && path
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_)));
+    if !inside || file.len() == ANNOTATABLE_DIR.len() {
+        return Err(format!("Only files under {} can be annotated: {}", ANNOTATABLE_DIR, file));
+    }
+    Ok(())
+}
+
+fn normalize(line: &str) -> String {
+    line.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn words(lines: &[&str]) -> HashSet<String> {
+    lines
+        .iter()
+        .flat_map(|l| l.split(|c: char| !c.is_alphanumeric()))
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Jaccard similarity of the words in two line windows.
+fn similarity(a: &[&str], b: &[&str]) -> f64 {
+    let (a, b) = (words(a), words(b));
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    a.intersection(&b).count() as f64 / a.union(&b).count() as f64
+}
+
+/// Where the anchored lines of an annotation made at `start_line` are in
+/// `content` now, as a 1-based inclusive range. `None` when neither an
+/// exact nor a close enough match exists.
+pub(crate) fn relocate(content: &str, start_line: u32, anchor_text: &str) -> Option<(u32, u32)> {
+    let lines: Vec<&str> = content.lines().collect();
+    let anchor: Vec<&str> = anchor_text.lines().collect();
+    let n = anchor.len().max(1);
+    if lines.len() < n {
+        return None;
+    }
+    let original = start_line.saturating_sub(1) as usize;
+    let distance = |i: usize| i.abs_diff(original);
+    let range = |i: usize| Some((i as u32 + 1, (i + n) as u32));
+
+    let anchor_norm: Vec<String> = anchor.iter().map(|l| normalize(l)).collect();
+    let exact = (0..=lines.len() - n)
+        .filter(|&i| lines[i..i + n].iter().map(|l| normalize(l)).eq(anchor_norm.iter().cloned()))
+        .min_by_key(|&i| distance(i));
+    if let Some(i) = exact {
+        return range(i);
+    }
+
+    let (best, score) = (0..=lines.len() - n)
+        .map(|i| (i, similarity(&lines[i..i + n], &anchor)))
+        .fold((0, f64::MIN), |best, cur| {
+            if cur.1 > best.1 || (cur.1 == best.1 && distance(cur.0) < distance(best.0)) {
+                cur
+            } else {
+                best
+            }
+        });
+    if score >= MIN_FUZZY_SIMILARITY {
+        range(best)
+    } else {
+        None
+    }
+}
+
+/// Lines `start..=end` (1-based) of `content`, or `None` if out of range.
+fn slice_lines(content: &str, start_line: u32, end_line: u32) -> Option<String> {
+    if start_line == 0 || end_line < start_line {
+        return None;
+    }
+    let lines: Vec<&str> = content.lines().collect();
+    if end_line as usize > lines.len() {
+        return None;
+    }
+    Some(lines[start_line as usize - 1..end_line as usize].join("\n"))
+}
+
+/// Re-anchor a skill's annotations against the files on disk and persist any
+/// moves. Annotations on files that no longer exist are left untouched, since
+/// a step reset deletes context files before they are regenerated.
+pub(crate) fn reanchor_annotations(
+    conn: &rusqlite::Connection,
+    workspace_path: &str,
+    skill_name: &str,
+    file: Option<&str>,
+) -> Result<Vec<ArtifactAnnotation>, String> {
+    let mut annotations = crate::db::list_artifact_annotations(conn, skill_name, file)?;
+    let skill_dir = Path::new(workspace_path).join(skill_name);
+    let mut current_file: Option<(String, Option<String>)> = None;
+    for annotation in annotations.iter_mut() {
+        if current_file.as_ref().map(|(f, _)| f) != Some(&annotation.file) {
+            let content = std::fs::read_to_string(skill_dir.join(&annotation.file)).ok();
+            current_file = Some((annotation.file.clone(), content));
+        }
+        let Some(content) = current_file.as_ref().and_then(|(_, c)| c.as_deref()) else {
+            continue;
+        };
+        let (start, end, anchor_text, orphaned) =
+            match relocate(content, annotation.start_line, &annotation.anchor_text) {
+                Some((start, end)) => {
+                    let text = slice_lines(content, start, end).unwrap_or_default();
+                    (start, end, text, false)
+                }
+                None => (annotation.start_line, annotation.end_line, annotation.anchor_text.clone(), true),
+            };
+        if (start, end, orphaned) == (annotation.start_line, annotation.end_line, annotation.orphaned)
+            && anchor_text == annotation.anchor_text
+        {
+            continue;
+        }
+        log::debug!(
+            "[reanchor_annotations] {}#{} {} {}-{} -> {}-{}{}",
+            skill_name,
+            annotation.id,
+            annotation.file,
+            annotation.start_line,
+            annotation.end_line,
+            start,
+            end,
+            if orphaned { " (orphaned)" } else { "" }
+        );
+        crate::db::update_artifact_annotation_anchor(conn, annotation.id, start, end, &anchor_text, orphaned)?;
+        annotation.start_line = start;
+        annotation.end_line = end;
+        annotation.anchor_text = anchor_text;
+        annotation.orphaned = orphaned;
+    }
+    annotations.sort_by(|a, b| (&a.file, a.start_line, a.id).cmp(&(&b.file, b.start_line, b.id)));
+    Ok(annotations)
+}
+
+fn add_annotation_inner(
+    conn: &rusqlite::Connection,
+    workspace_path: &str,
+    skill_name: &str,
+    file: &str,
+    start_line: u32,
+    end_line: u32,
+    comment: &str,
+) -> Result<ArtifactAnnotation, String> {
+    validate_file(file)?;
+    let comment = comment.trim();
+    if comment.is_empty() {
+        return Err("An annotation needs a comment".to_string());
+    }
+    let path = Path::new(workspace_path).join(skill_name).join(file);
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", file, e))?;
+    let anchor_text = slice_lines(&content, start_line, end_line)
+        .ok_or_else(|| format!("Lines {}-{} are outside {}", start_line, end_line, file))?;
+    let now = chrono::Utc::now().to_rfc3339();
+    crate::db::insert_artifact_annotation(
+        conn,
+        &ArtifactAnnotation {
+            id: 0,
+            skill_name: skill_name.to_string(),
+            file: file.to_string(),
+            start_line,
+            end_line,
+            anchor_text,
+            comment: comment.to_string(),
+            author: crate::db::read_settings(conn)?.github_user_login,
+            orphaned: false,
+            created_at: now.clone(),
+            updated_at: now,
+        },
+    )
+}
+
+/// Annotations on a skill's context files, re-anchored to the current file
+/// contents. Limited to `file` when given.
+#[tauri::command]
+pub fn list_artifact_annotations(
+    skill_name: String,
+    file: Option<String>,
+    workspace_path: String,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<ArtifactAnnotation>, String> {
+    log::info!("[list_artifact_annotations] skill={} file={:?}", skill_name, file);
+    validate_skill_name(&skill_name)?;
+    if let Some(f) = file.as_deref() {
+        validate_file(f)?;
+    }
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[list_artifact_annotations] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    reanchor_annotations(&conn, &workspace_path, &skill_name, file.as_deref()).map_err(|e| {
+        log::error!("[list_artifact_annotations] {}", e);
+        e
+    })
+}
+
+/// Comment on lines `start_line..=end_line` (1-based) of a context file.
+#[tauri::command]
+pub fn add_artifact_annotation(
+    skill_name: String,
+    file: String,
+    start_line: u32,
+    end_line: u32,
+    comment: String,
+    workspace_path: String,
+    db: tauri::State<'_, Db>,
+) -> Result<ArtifactAnnotation, String> {
+    log::info!(
+        "[add_artifact_annotation] skill={} file={} lines={}-{}",
+        skill_name,
+        file,
+        start_line,
+        end_line
+    );
+    super::observer::ensure_writable(&skill_name)?;
+    validate_skill_name(&skill_name)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[add_artifact_annotation] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    add_annotation_inner(&conn, &workspace_path, &skill_name, &file, start_line, end_line, &comment).map_err(|e| {
+        log::error!("[add_artifact_annotation] {}", e);
+        e
+    })
+}
+
+#[tauri::command]
+pub fn update_artifact_annotation(id: i64, comment: String, db: tauri::State<'_, Db>) -> Result<ArtifactAnnotation, String> {
+    log::info!("[update_artifact_annotation] id={}", id);
+    let comment = comment.trim();
+    if comment.is_empty() {
+        return Err("An annotation needs a comment".to_string());
+    }
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[update_artifact_annotation] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    ensure_annotation_writable(&conn, id)?;
+    crate::db::update_artifact_annotation_comment(&conn, id, comment)?;
+    crate::db::get_artifact_annotation(&conn, id)?.ok_or_else(|| format!("Annotation {} not found", id))
+}
+
+#[tauri::command]
+pub fn delete_artifact_annotation(id: i64, db: tauri::State<'_, Db>) -> Result<(), String> {
+    log::info!("[delete_artifact_annotation] id={}", id);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[delete_artifact_annotation] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    ensure_annotation_writable(&conn, id)?;
+    crate::db::delete_artifact_annotation(&conn, id)
+}
+
+/// Refuse to change an annotation on a skill this instance only observes.
+fn ensure_annotation_writable(conn: &rusqlite::Connection, id: i64) -> Result<(), String> {
+    match crate::db::get_artifact_annotation(conn, id)? {
+        Some(annotation) => super::observer::ensure_writable(&annotation.skill_name),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::create_test_db;
+    use tempfile::tempdir;
+
+    const DECISIONS: &str = "# Decisions\n\n### D1: Revenue\nUse net revenue.\n\n### D2: Currency\nReport in USD.\n";
+
+    #[test]
+    fn test_relocate_follows_moved_and_edited_lines() {
+        // Unchanged.
+        assert_eq!(relocate(DECISIONS, 6, "### D2: Currency\nReport in USD."), Some((6, 7)));
+        // Moved down by an inserted decision.
+        let moved = DECISIONS.replace("### D2", "### D1b: Region\nEMEA only.\n\n### D2");
+        assert_eq!(relocate(&moved, 6, "### D2: Currency\nReport in USD."), Some((9, 10)));
+        // Reworded slightly: near-match still wins.
+        let edited = DECISIONS.replace("Report in USD.", "Report in USD only.");
+        assert_eq!(relocate(&edited, 6, "### D2: Currency\nReport in USD."), Some((6, 7)));
+        // Removed entirely.
+        let removed = DECISIONS.replace("### D2: Currency\nReport in USD.\n", "");
+        assert_eq!(relocate(&removed, 6, "### D2: Currency\nReport in USD."), None);
+    }
+
+    #[test]
+    fn test_annotations_reanchor_and_orphan_on_list() {
+        let conn = create_test_db();
+        let workspace = tempdir().unwrap();
+        let workspace_path = workspace.path().to_str().unwrap();
+        let file = workspace.path().join("sales/context/decisions.md");
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, DECISIONS).unwrap();
+
+        let d2 = add_annotation_inner(&conn, workspace_path, "sales", "context/decisions.md", 6, 7, "Which rate date?")
+            .unwrap();
+        assert_eq!(d2.anchor_text, "### D2: Currency\nReport in USD.");
+        add_annotation_inner(&conn, workspace_path, "sales", "context/decisions.md", 4, 4, "Gross?").unwrap();
+
+        std::fs::write(&file, "# Decisions\n\n### D2: Currency\nReport in USD.\n").unwrap();
+        let listed = reanchor_annotations(&conn, workspace_path, "sales", None).unwrap();
+        let moved = listed.iter().find(|a| a.id == d2.id).unwrap();
+        assert_eq!((moved.start_line, moved.end_line, moved.orphaned), (3, 4, false));
+        let gone = listed.iter().find(|a| a.id != d2.id).unwrap();
+        assert!(gone.orphaned);
+        assert_eq!(crate::db::list_artifact_annotations(&conn, "sales", None).unwrap(), listed);
+    }
+
+    #[test]
+    fn test_add_annotation_validates_input() {
+        let conn = create_test_db();
+        let workspace = tempdir().unwrap();
+        let workspace_path = workspace.path().to_str().unwrap();
+        std::fs::create_dir_all(workspace.path().join("sales/context")).unwrap();
+        std::fs::write(workspace.path().join("sales/context/clarifications.json"), "{\n}\n").unwrap();
+
+        let add = |file: &str, start: u32, end: u32, comment: &str| {
+            add_annotation_inner(&conn, workspace_path, "sales", file, start, end, comment)
+        };
+        assert!(add("context/../../secrets", 1, 1, "x").is_err());
+        assert!(add("SKILL.md", 1, 1, "x").is_err());
+        assert!(add("context/clarifications.json", 2, 3, "x").unwrap_err().contains("outside"));
+        assert!(add("context/clarifications.json", 1, 1, "  ").is_err());
+        assert!(add("context/clarifications.json", 1, 2, "ok").is_ok());
+    }
+
+    #[test]
+    fn test_annotations_of_an_observed_skill_are_read_only() {
+        let conn = create_test_db();
+        let workspace = tempdir().unwrap();
+        let workspace_path = workspace.path().to_str().unwrap();
+        let file = workspace.path().join("observed-notes/context/decisions.md");
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, DECISIONS).unwrap();
+        let note =
+            add_annotation_inner(&conn, workspace_path, "observed-notes", "context/decisions.md", 6, 7, "Rate date?")
+                .unwrap();
+
+        crate::commands::observer::start_observing("observed-notes");
+        let err = ensure_annotation_writable(&conn, note.id).unwrap_err();
+        crate::commands::observer::stop_observing("observed-notes");
+        assert!(err.contains("read-only"));
+        assert!(ensure_annotation_writable(&conn, note.id).is_ok());
+    }
+}
@@ -0,0 +1,193 @@
+//! Querying and exporting the append-only audit log (see `crate::audit`).
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::db::Db;
+use crate::types::{AuditLogEntry, AuditLogExport, AuditLogFilter};
+
+const DEFAULT_LIMIT: u32 = 200;
+const MAX_LIMIT: u32 = 1000;
+
+const CSV_HEADER: &str = "id,created_at,instance_id,actor,action,skill_name,summary";
+
+/// Quote a CSV field when it holds a delimiter, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn validate_filter(filter: &AuditLogFilter) -> Result<(), String> {
+    match filter.action.as_deref() {
+        Some(action) if !crate::audit::AUDIT_ACTIONS.contains(&action) => {
+            Err(format!("Unknown audit action '{}'", action))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn csv_row(entry: &AuditLogEntry) -> String {
+    [
+        entry.id.to_string(),
+        entry.created_at.clone(),
+        entry.instance_id.clone(),
+        entry.actor.clone().unwrap_or_default(),
+        entry.action.clone(),
+        entry.skill_name.clone().unwrap_or_default(),
+        entry.summary.clone(),
+    ]
+    .iter()
+    .map(|v| csv_field(v))
+    .collect::<Vec<_>>()
+    .join(",")
+}
+
+/// Write `entries` as CSV or JSON Lines, oldest first.
+fn write_export(output: &Path, entries: &[AuditLogEntry], format: &str) -> Result<(), String> {
+    let mut file = std::fs::File::create(output)
+        .map_err(|e| format!("Failed to create {}: {}", output.display(), e))?;
+    let write_err = |e: std::io::Error| format!("Failed to write {}: {}", output.display(), e);
+    if format == "csv" {
+        writeln!(file, "{}", CSV_HEADER).map_err(write_err)?;
+    }
+    for entry in entries.iter().rev() {
+        let line = match format {
+            "csv" => csv_row(entry),
+            _ => serde_json::to_string(entry).map_err(|e| e.to_string())?,
+        };
+        writeln!(file, "{}", line).map_err(write_err)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_audit_log(
+    filter: Option<AuditLogFilter>,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<AuditLogEntry>, String> {
+    let filter = filter.unwrap_or_default();
+    log::info!(
+        "[get_audit_log] action={:?} skill={:?} query={:?}",
+        filter.action,
+        filter.skill_name,
+        filter.query
+    );
+    validate_filter(&filter)?;
+    let limit = filter.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[get_audit_log] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::list_audit_log(&conn, &filter, Some(limit))
+}
+
+/// Export every entry matching `filter` as `csv` or `jsonl`. Defaults to
+/// `{workspace}/exports/audit-log-{timestamp}.{format}`.
+#[tauri::command]
+pub fn export_audit_log(
+    workspace_path: String,
+    filter: Option<AuditLogFilter>,
+    format: String,
+    output_path: Option<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<AuditLogExport, String> {
+    log::info!("[export_audit_log] format={} output_path={:?}", format, output_path);
+    if format != "csv" && format != "jsonl" {
+        return Err(format!("Unsupported export format '{}'; use csv or jsonl", format));
+    }
+    let filter = filter.unwrap_or_default();
+    validate_filter(&filter)?;
+    let entries = {
+        let conn = db.0.read().map_err(|e| {
+            log::error!("[export_audit_log] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        crate::db::list_audit_log(&conn, &filter, None)?
+    };
+
+    let exports_dir = Path::new(&workspace_path).join("exports");
+    let output: PathBuf = match output_path {
+        // Relative output paths land in the workspace exports folder
+        Some(p) => crate::path_input::check(&p, crate::path_input::PathKind::File, false, Some(&exports_dir))
+            .map(|c| PathBuf::from(c.path))
+            .map_err(|e| {
+                log::error!("[export_audit_log] Invalid output path: {}", e);
+                format!("Invalid output path: {}", e)
+            })?,
+        None => exports_dir.join(format!(
+            "audit-log-{}.{}",
+            chrono::Utc::now().format("%Y-%m-%dT%H-%M-%S"),
+            format
+        )),
+    };
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            log::error!("[export_audit_log] Failed to create {}: {}", parent.display(), e);
+            format!("Failed to create export directory: {}", e)
+        })?;
+    }
+    write_export(&output, &entries, &format).map_err(|e| {
+        log::error!("[export_audit_log] {}", e);
+        e
+    })?;
+
+    let export = AuditLogExport {
+        output_path: output.to_string_lossy().to_string(),
+        entry_count: entries.len(),
+    };
+    log::info!(
+        "[export_audit_log] wrote {} entries to {}",
+        export.entry_count,
+        export.output_path
+    );
+    Ok(export)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::create_test_db;
+
+    #[test]
+    fn test_csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("revenue"), "revenue");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn test_validate_filter_rejects_unknown_action() {
+        let known = AuditLogFilter { action: Some("skill.pushed".into()), ..Default::default() };
+        assert!(validate_filter(&known).is_ok());
+        let unknown = AuditLogFilter { action: Some("skill.exploded".into()), ..Default::default() };
+        assert!(validate_filter(&unknown).unwrap_err().contains("skill.exploded"));
+    }
+
+    #[test]
+    fn test_write_export_is_chronological() {
+        let conn = create_test_db();
+        crate::db::insert_audit_entry(&conn, "inst-1", Some("alice"), "skill.created", Some("revenue"), "created")
+            .unwrap();
+        crate::db::insert_audit_entry(&conn, "inst-1", None, "settings.changed", None, "log_level, max_dimensions")
+            .unwrap();
+        let entries = crate::db::list_audit_log(&conn, &AuditLogFilter::default(), None).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+
+        let csv = dir.path().join("audit.csv");
+        write_export(&csv, &entries, "csv").unwrap();
+        let lines: Vec<String> = std::fs::read_to_string(&csv).unwrap().lines().map(String::from).collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert!(lines[1].ends_with(",inst-1,alice,skill.created,revenue,created"));
+        assert!(lines[2].ends_with(",inst-1,,settings.changed,,\"log_level, max_dimensions\""));
+
+        let jsonl = dir.path().join("audit.jsonl");
+        write_export(&jsonl, &entries, "jsonl").unwrap();
+        let first: AuditLogEntry =
+            serde_json::from_str(std::fs::read_to_string(&jsonl).unwrap().lines().next().unwrap()).unwrap();
+        assert_eq!(first, entries[1]);
+    }
+}
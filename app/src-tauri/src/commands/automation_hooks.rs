@@ -0,0 +1,15 @@
+use crate::db::Db;
+use crate::types::HookDelivery;
+
+const DEFAULT_DELIVERY_LIMIT: u32 = 100;
+
+/// Recent deliveries of workflow events to automation hooks, newest first.
+#[tauri::command]
+pub fn list_hook_deliveries(limit: Option<u32>, db: tauri::State<'_, Db>) -> Result<Vec<HookDelivery>, String> {
+    log::info!("[list_hook_deliveries] limit={:?}", limit);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[list_hook_deliveries] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::list_hook_deliveries(&conn, limit.unwrap_or(DEFAULT_DELIVERY_LIMIT))
+}
@@ -0,0 +1,42 @@
+use crate::scheduler::BackgroundScheduler;
+use crate::types::{BackgroundQueue, BackgroundTask, BackgroundTaskKind, TaskPriority};
+
+#[tauri::command]
+pub async fn list_background_tasks(app: tauri::AppHandle) -> Result<BackgroundQueue, String> {
+    log::debug!("[list_background_tasks]");
+    Ok(crate::scheduler::queue(&app).await)
+}
+
+/// Queue a job by hand (e.g. "refresh catalogs now"). Returns the task id; an
+/// already-queued job of the same kind is reused and its priority raised.
+#[tauri::command]
+pub fn enqueue_background_task(
+    kind: BackgroundTaskKind,
+    priority: TaskPriority,
+    scheduler: tauri::State<'_, BackgroundScheduler>,
+) -> Result<u64, String> {
+    log::info!("[enqueue_background_task] kind={:?} priority={:?}", kind, priority);
+    Ok(scheduler.enqueue(kind, priority))
+}
+
+#[tauri::command]
+pub fn set_background_task_priority(
+    task_id: u64,
+    priority: TaskPriority,
+    scheduler: tauri::State<'_, BackgroundScheduler>,
+) -> Result<BackgroundTask, String> {
+    log::info!("[set_background_task_priority] task={} priority={:?}", task_id, priority);
+    scheduler.set_priority(task_id, priority).map_err(|e| {
+        log::error!("[set_background_task_priority] {}", e);
+        e
+    })
+}
+
+#[tauri::command]
+pub fn cancel_background_task(task_id: u64, scheduler: tauri::State<'_, BackgroundScheduler>) -> Result<(), String> {
+    log::info!("[cancel_background_task] task={}", task_id);
+    scheduler.cancel(task_id).map_err(|e| {
+        log::error!("[cancel_background_task] {}", e);
+        e
+    })
+}
@@ -0,0 +1,526 @@
+//! Benchmark a skill against a no-skill baseline.
+//!
+//! Each scenario prompt is sent to the subject model twice: once with the
+//! skill's SKILL.md and reference files as the system prompt, once without. A
+//! judge model scores both answers against the scenario's expected behavior;
+//! the order the answers are shown in alternates between scenarios to offset
+//! position bias. Token and latency overhead come from the same calls. Runs are
+//! stored with the skill's version so successive versions can be compared.
+
+use std::path::{Path, PathBuf};
+
+use crate::db::Db;
+use crate::types::{BenchmarkScenarioResult, SkillBenchmark};
+
+/// Scenario set name for the skill's generated `context/evaluations.md`.
+const DEFAULT_SCENARIO_SET: &str = "evaluations";
+const DEFAULT_MODEL: &str = "claude-sonnet-4-6";
+const JUDGE_MODEL: &str = "claude-sonnet-4-6";
+const MAX_SCENARIOS: usize = 20;
+/// Large skills are truncated rather than rejected; the cap keeps each call well inside the context window.
+const MAX_SKILL_CONTEXT_CHARS: usize = 150_000;
+const RESPONSE_MAX_TOKENS: u32 = 2048;
+const JUDGE_MAX_TOKENS: u32 = 512;
+const API_TIMEOUT_SECS: u64 = 120;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Scenario {
+    name: String,
+    prompt: String,
+    expected: Option<String>,
+}
+
+/// Which labelled field (`**Prompt**:`, `Expected behavior:`, ...) a line starts, and the text after the label.
+fn field_start(line: &str) -> Option<(&'static str, String)> {
+    let bullet_stripped = line.trim().trim_start_matches(['-', ' ']);
+    let bold = bullet_stripped.starts_with("**");
+    let (label, rest) = bullet_stripped.trim_start_matches('*').split_once(':')?;
+    let label = label.trim_end_matches('*').trim().to_lowercase();
+    let rest = rest.trim_start_matches('*').trim().to_string();
+    if label == "prompt" {
+        Some(("prompt", rest))
+    } else if label.starts_with("expected") {
+        Some(("expected", rest))
+    } else if bold {
+        Some(("other", rest))
+    } else {
+        None
+    }
+}
+
+/// Parse scenarios from evaluations markdown: each `##`/`###` heading is a scenario
+/// with **Prompt** and **Expected behavior** fields. Sections without a Prompt
+/// label use their first non-empty line as the prompt.
+fn parse_scenarios(markdown: &str) -> Vec<Scenario> {
+    let mut scenarios = Vec::new();
+    let mut sections: Vec<(String, Vec<&str>)> = Vec::new();
+    for line in markdown.lines() {
+        if let Some(heading) = line.strip_prefix("## ").or_else(|| line.strip_prefix("### ")) {
+            sections.push((heading.trim().to_string(), Vec::new()));
+        } else if let Some((_, body)) = sections.last_mut() {
+            body.push(line);
+        }
+    }
+
+    for (name, body) in sections {
+        let mut prompt = String::new();
+        let mut expected = String::new();
+        let mut current: Option<&str> = None;
+        for line in &body {
+            if let Some((field, rest)) = field_start(line) {
+                current = Some(field);
+                match field {
+                    "prompt" => prompt = rest,
+                    "expected" => expected = rest,
+                    _ => {}
+                }
+            } else if !line.trim().is_empty() {
+                let target = match current {
+                    Some("prompt") => &mut prompt,
+                    Some("expected") => &mut expected,
+                    _ => continue,
+                };
+                if !target.is_empty() {
+                    target.push('\n');
+                }
+                target.push_str(line.trim());
+            }
+        }
+        if prompt.trim().is_empty() && current.is_none() {
+            if let Some(first) = body.iter().map(|l| l.trim()).find(|l| !l.is_empty()) {
+                prompt = first.trim_start_matches(['-', '*', ' ']).to_string();
+            }
+        }
+        let prompt = prompt.trim().trim_matches('"').trim().to_string();
+        if prompt.is_empty() {
+            continue;
+        }
+        scenarios.push(Scenario {
+            name,
+            prompt,
+            expected: Some(expected.trim().to_string()).filter(|e| !e.is_empty()),
+        });
+    }
+    scenarios
+}
+
+/// SKILL.md followed by the reference files, as the model would read them.
+fn skill_context(skill_dir: &Path) -> Result<String, String> {
+    let skill_md = std::fs::read_to_string(skill_dir.join("SKILL.md"))
+        .map_err(|e| format!("Failed to read SKILL.md in '{}': {}", skill_dir.display(), e))?;
+    let mut context = format!("<skill>\n{}\n</skill>\n", skill_md.trim());
+
+    let mut references: Vec<PathBuf> = std::fs::read_dir(skill_dir.join("references"))
+        .map(|entries| entries.filter_map(|e| e.ok().map(|e| e.path())).filter(|p| p.is_file()).collect())
+        .unwrap_or_default();
+    references.sort();
+    for path in references {
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            context.push_str(&format!("<reference name=\"{}\">\n{}\n</reference>\n", name, content.trim()));
+        }
+    }
+    if context.len() > MAX_SKILL_CONTEXT_CHARS {
+        let mut end = MAX_SKILL_CONTEXT_CHARS;
+        while !context.is_char_boundary(end) {
+            end -= 1;
+        }
+        context.truncate(end);
+    }
+    Ok(format!(
+        "You have the following skill available. Apply it where relevant.\n\n{}",
+        context
+    ))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ModelReply {
+    text: String,
+    tokens: i64,
+    duration_ms: i64,
+}
+
+async fn call_model(
+    client: &reqwest::Client,
+    api_key: &str,
+    model: &str,
+    system: Option<&str>,
+    prompt: &str,
+    max_tokens: u32,
+) -> Result<ModelReply, String> {
+    let started = std::time::Instant::now();
+    let mut body = serde_json::json!({
+        "model": model,
+        "max_tokens": max_tokens,
+        "messages": [{"role": "user", "content": prompt}]
+    });
+    if let Some(system) = system {
+        body["system"] = serde_json::json!(system);
+    }
+    let resp = client
+        .post("https://api.anthropic.com/v1/messages")
+        .header("x-api-key", api_key)
+        .header("anthropic-version", "2023-06-01")
+        .header("content-type", "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .map_err(|e| format!("API request failed: {}", e))?;
+
+    if !resp.status().is_success() {
+        let status = resp.status().as_u16();
+        let body = resp.text().await.unwrap_or_default();
+        log::debug!("Anthropic API error body: {}", body);
+        return Err(format!("Anthropic API error ({})", status));
+    }
+    let body: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+    let (text, input_tokens, output_tokens) = super::local_model::parse_anthropic_response(&body)?;
+    Ok(ModelReply {
+        text,
+        tokens: input_tokens as i64 + output_tokens as i64,
+        duration_ms: started.elapsed().as_millis() as i64,
+    })
+}
+
+fn judge_prompt(scenario: &Scenario, first: &str, second: &str) -> String {
+    format!(
+        "You are grading two answers to the same request. Score each from 1 (useless or wrong) \
+         to 10 (fully correct, specific and actionable), judged against the expected behavior.\n\n\
+         <request>\n{}\n</request>\n\n<expected_behavior>\n{}\n</expected_behavior>\n\n\
+         <answer_a>\n{}\n</answer_a>\n\n<answer_b>\n{}\n</answer_b>\n\n\
+         Reply with JSON only: {{\"score_a\": <1-10>, \"score_b\": <1-10>, \"reason\": \"<one sentence>\"}}",
+        scenario.prompt,
+        scenario.expected.as_deref().unwrap_or("A correct, complete and practical answer."),
+        first,
+        second
+    )
+}
+
+/// Parse the judge reply into `(score_a, score_b, reason)`.
+fn parse_verdict(text: &str) -> Result<(f64, f64, String), String> {
+    let start = text.find('{').ok_or_else(|| "Judge reply has no JSON object".to_string())?;
+    let end = text.rfind('}').ok_or_else(|| "Judge reply has no JSON object".to_string())?;
+    let value: serde_json::Value = serde_json::from_str(&text[start..=end.max(start)])
+        .map_err(|e| format!("Invalid judge JSON: {}", e))?;
+    let score = |key: &str| {
+        value[key]
+            .as_f64()
+            .filter(|s| (1.0..=10.0).contains(s))
+            .ok_or_else(|| format!("Judge reply missing a 1-10 '{}'", key))
+    };
+    Ok((
+        score("score_a")?,
+        score("score_b")?,
+        value["reason"].as_str().unwrap_or_default().to_string(),
+    ))
+}
+
+/// Even scenarios show the skill answer first, odd ones the baseline answer first.
+fn skill_shown_first(index: usize) -> bool {
+    index.is_multiple_of(2)
+}
+
+fn scenario_result(
+    index: usize,
+    scenario: &Scenario,
+    skill: &ModelReply,
+    baseline: &ModelReply,
+    verdict: (f64, f64, String),
+) -> BenchmarkScenarioResult {
+    let (score_a, score_b, reason) = verdict;
+    let (skill_score, baseline_score) = if skill_shown_first(index) {
+        (score_a, score_b)
+    } else {
+        (score_b, score_a)
+    };
+    let winner = if skill_score > baseline_score {
+        "skill"
+    } else if baseline_score > skill_score {
+        "baseline"
+    } else {
+        "tie"
+    };
+    BenchmarkScenarioResult {
+        name: scenario.name.clone(),
+        prompt: scenario.prompt.clone(),
+        skill_score,
+        baseline_score,
+        winner: winner.to_string(),
+        reason,
+        skill_tokens: skill.tokens,
+        baseline_tokens: baseline.tokens,
+        skill_ms: skill.duration_ms,
+        baseline_ms: baseline.duration_ms,
+    }
+}
+
+fn overhead_pct(with: i64, without: i64) -> f64 {
+    if without <= 0 {
+        return 0.0;
+    }
+    ((with - without) as f64 / without as f64 * 1000.0).round() / 10.0
+}
+
+/// Aggregate scenario results into a benchmark (id and created_at are set on insert).
+fn summarize(
+    skill_name: &str,
+    skill_version: Option<String>,
+    scenario_set: &str,
+    model: &str,
+    results: Vec<BenchmarkScenarioResult>,
+) -> SkillBenchmark {
+    let count = |w: &str| results.iter().filter(|r| r.winner == w).count() as u32;
+    let n = results.len().max(1) as f64;
+    let avg = |f: fn(&BenchmarkScenarioResult) -> f64| (results.iter().map(f).sum::<f64>() / n * 100.0).round() / 100.0;
+    let sum = |f: fn(&BenchmarkScenarioResult) -> i64| results.iter().map(f).sum::<i64>();
+    SkillBenchmark {
+        id: 0,
+        skill_name: skill_name.to_string(),
+        skill_version,
+        scenario_set: scenario_set.to_string(),
+        model: model.to_string(),
+        judge_model: JUDGE_MODEL.to_string(),
+        skill_wins: count("skill"),
+        baseline_wins: count("baseline"),
+        ties: count("tie"),
+        avg_skill_score: avg(|r| r.skill_score),
+        avg_baseline_score: avg(|r| r.baseline_score),
+        token_overhead_pct: overhead_pct(sum(|r| r.skill_tokens), sum(|r| r.baseline_tokens)),
+        latency_overhead_pct: overhead_pct(sum(|r| r.skill_ms), sum(|r| r.baseline_ms)),
+        results,
+        created_at: String::new(),
+    }
+}
+
+/// The skill's folder under the skills path, else the disk path of a workspace skill.
+pub(crate) fn resolve_skill_dir(
+    conn: &rusqlite::Connection,
+    settings: &crate::types::AppSettings,
+    skill_name: &str,
+) -> Result<PathBuf, String> {
+    let built = settings.skills_path.as_ref().map(|p| Path::new(p).join(skill_name));
+    match built.filter(|d| d.join("SKILL.md").is_file()) {
+        Some(dir) => Ok(dir),
+        None => crate::db::get_workspace_skill_by_name(conn, skill_name)?
+            .map(|s| PathBuf::from(s.disk_path))
+            .ok_or_else(|| format!("Skill '{}' not found", skill_name)),
+    }
+}
+
+/// Resolve the scenario file: `evaluations` (default) is the skill's generated
+/// `context/evaluations.md`; anything else is a path to a markdown file.
+fn scenario_file(scenario_set: &str, workspace_path: &str, skill_name: &str) -> Result<PathBuf, String> {
+    let path = if scenario_set == DEFAULT_SCENARIO_SET {
+        Path::new(workspace_path).join(skill_name).join("context").join("evaluations.md")
+    } else {
+        crate::path_input::normalize(scenario_set, None)?.0
+    };
+    if !path.is_file() {
+        return Err(format!("Scenario set not found: {}", path.display()));
+    }
+    Ok(path)
+}
+
+/// Run the scenario set with the skill active and inactive, judge each pair and store the result.
+#[tauri::command]
+pub async fn benchmark_skill(
+    skill_name: String,
+    scenario_set: Option<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<SkillBenchmark, String> {
+    let scenario_set = scenario_set
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| DEFAULT_SCENARIO_SET.to_string());
+    log::info!("[benchmark_skill] skill={} scenario_set={}", skill_name, scenario_set);
+    super::imported_skills::validate_skill_name(&skill_name)?;
+    super::offline::require_feature(&db, super::offline::NetworkFeature::AnthropicApi, "benchmark_skill")?;
+
+    let (api_key, model, skill_dir, workspace_path) = {
+        let conn = db.0.lock().map_err(|e| {
+            log::error!("[benchmark_skill] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        let settings = crate::db::read_settings(&conn)?;
+        let api_key = settings
+            .anthropic_api_key
+            .clone()
+            .ok_or_else(|| "Anthropic API key not configured".to_string())?;
+        let workspace_path = settings
+            .workspace_path
+            .clone()
+            .ok_or_else(|| "Workspace path not initialized".to_string())?;
+        let skill_dir = resolve_skill_dir(&conn, &settings, &skill_name)?;
+        let model = settings.preferred_model.clone().unwrap_or_else(|| DEFAULT_MODEL.to_string());
+        (api_key, model, skill_dir, workspace_path)
+    };
+
+    let scenarios_path = scenario_file(&scenario_set, &workspace_path, &skill_name)?;
+    let markdown = std::fs::read_to_string(&scenarios_path)
+        .map_err(|e| format!("Failed to read '{}': {}", scenarios_path.display(), e))?;
+    let mut scenarios = parse_scenarios(&markdown);
+    if scenarios.is_empty() {
+        return Err(format!("No scenarios found in {}", scenarios_path.display()));
+    }
+    if scenarios.len() > MAX_SCENARIOS {
+        log::warn!("[benchmark_skill] {} scenarios; running the first {}", scenarios.len(), MAX_SCENARIOS);
+        scenarios.truncate(MAX_SCENARIOS);
+    }
+
+    let system = skill_context(&skill_dir)?;
+    let skill_version = std::fs::read_to_string(skill_dir.join("SKILL.md"))
+        .ok()
+        .and_then(|md| super::imported_skills::parse_frontmatter_full(&md).version);
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(API_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut results = Vec::with_capacity(scenarios.len());
+    for (index, scenario) in scenarios.iter().enumerate() {
+        let skill = call_model(&client, &api_key, &model, Some(&system), &scenario.prompt, RESPONSE_MAX_TOKENS).await?;
+        let baseline = call_model(&client, &api_key, &model, None, &scenario.prompt, RESPONSE_MAX_TOKENS).await?;
+        let (first, second) = if skill_shown_first(index) {
+            (&skill.text, &baseline.text)
+        } else {
+            (&baseline.text, &skill.text)
+        };
+        let judged = call_model(
+            &client,
+            &api_key,
+            JUDGE_MODEL,
+            None,
+            &judge_prompt(scenario, first, second),
+            JUDGE_MAX_TOKENS,
+        )
+        .await?;
+        let verdict = parse_verdict(&judged.text).map_err(|e| {
+            log::error!("[benchmark_skill] scenario '{}': {}", scenario.name, e);
+            format!("Scenario '{}': {}", scenario.name, e)
+        })?;
+        let result = scenario_result(index, scenario, &skill, &baseline, verdict);
+        log::debug!(
+            "[benchmark_skill] '{}': skill={} baseline={} ({})",
+            result.name,
+            result.skill_score,
+            result.baseline_score,
+            result.winner
+        );
+        results.push(result);
+    }
+
+    let mut benchmark = summarize(&skill_name, skill_version, &scenario_set, &model, results);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[benchmark_skill] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    benchmark.id = crate::db::insert_skill_benchmark(&conn, &benchmark)?;
+    if let Some(stored) = crate::db::list_skill_benchmarks(&conn, &skill_name)?.into_iter().next() {
+        benchmark.created_at = stored.created_at;
+    }
+    log::info!(
+        "[benchmark_skill] '{}' v{}: skill won {}/{} (avg {} vs {}), tokens {:+}%, latency {:+}%",
+        skill_name,
+        benchmark.skill_version.as_deref().unwrap_or("?"),
+        benchmark.skill_wins,
+        benchmark.results.len(),
+        benchmark.avg_skill_score,
+        benchmark.avg_baseline_score,
+        benchmark.token_overhead_pct,
+        benchmark.latency_overhead_pct
+    );
+    Ok(benchmark)
+}
+
+#[tauri::command]
+pub fn list_skill_benchmarks(skill_name: String, db: tauri::State<'_, Db>) -> Result<Vec<SkillBenchmark>, String> {
+    log::info!("[list_skill_benchmarks] skill={}", skill_name);
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[list_skill_benchmarks] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::list_skill_benchmarks(&conn, &skill_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reply(tokens: i64, duration_ms: i64) -> ModelReply {
+        ModelReply { text: String::new(), tokens, duration_ms }
+    }
+
+    #[test]
+    fn test_parse_scenarios_labelled_and_bare() {
+        let md = "# Evaluations\n\n## Scenario 1: MRR rollup\n\n**Prompt**: How do I roll up MRR by segment?\n\
+                  **Expected behavior**: Uses the segment mapping.\nExcludes churned accounts.\n\
+                  **Pass criteria**: Mentions churn.\n\n## Scenario 2\n- Build a pipeline report\n- expected output\n\n\
+                  ## Notes\n";
+        let scenarios = parse_scenarios(md);
+        assert_eq!(scenarios.len(), 2);
+        assert_eq!(scenarios[0].name, "Scenario 1: MRR rollup");
+        assert_eq!(scenarios[0].prompt, "How do I roll up MRR by segment?");
+        assert_eq!(
+            scenarios[0].expected.as_deref(),
+            Some("Uses the segment mapping.\nExcludes churned accounts.")
+        );
+        assert_eq!(scenarios[1].prompt, "Build a pipeline report");
+        assert_eq!(scenarios[1].expected, None);
+    }
+
+    #[test]
+    fn test_parse_verdict() {
+        let (a, b, reason) =
+            parse_verdict("Here you go:\n{\"score_a\": 8, \"score_b\": 5.5, \"reason\": \"A is specific\"}").unwrap();
+        assert_eq!((a, b, reason.as_str()), (8.0, 5.5, "A is specific"));
+        assert!(parse_verdict("{\"score_a\": 11, \"score_b\": 5}").is_err());
+        assert!(parse_verdict("no json").is_err());
+    }
+
+    #[test]
+    fn test_scenario_result_undoes_alternating_order() {
+        let scenario = Scenario { name: "s".into(), prompt: "p".into(), expected: None };
+        let (skill, baseline) = (reply(1200, 900), reply(200, 600));
+        let first = scenario_result(0, &scenario, &skill, &baseline, (9.0, 4.0, String::new()));
+        assert_eq!((first.skill_score, first.baseline_score, first.winner.as_str()), (9.0, 4.0, "skill"));
+        let second = scenario_result(1, &scenario, &skill, &baseline, (9.0, 4.0, String::new()));
+        assert_eq!((second.skill_score, second.baseline_score, second.winner.as_str()), (4.0, 9.0, "baseline"));
+    }
+
+    #[test]
+    fn test_summarize_and_store_over_versions() {
+        let conn = crate::commands::test_utils::create_test_db();
+        let scenario = Scenario { name: "s".into(), prompt: "p".into(), expected: None };
+        let results = vec![
+            scenario_result(0, &scenario, &reply(1500, 1100), &reply(1000, 1000), (8.0, 5.0, String::new())),
+            scenario_result(2, &scenario, &reply(1500, 1100), &reply(1000, 1000), (6.0, 6.0, String::new())),
+        ];
+        let benchmark = summarize("sales", Some("1.1.0".into()), "evaluations", "claude-sonnet-4-6", results);
+        assert_eq!((benchmark.skill_wins, benchmark.baseline_wins, benchmark.ties), (1, 0, 1));
+        assert_eq!(benchmark.avg_skill_score, 7.0);
+        assert_eq!(benchmark.avg_baseline_score, 5.5);
+        assert_eq!(benchmark.token_overhead_pct, 50.0);
+        assert_eq!(benchmark.latency_overhead_pct, 10.0);
+
+        crate::db::insert_skill_benchmark(&conn, &SkillBenchmark { skill_version: Some("1.0.0".into()), ..benchmark.clone() })
+            .unwrap();
+        crate::db::insert_skill_benchmark(&conn, &benchmark).unwrap();
+        let stored = crate::db::list_skill_benchmarks(&conn, "sales").unwrap();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[0].skill_version.as_deref(), Some("1.1.0"));
+        assert_eq!(stored[0].results, benchmark.results);
+        assert!(crate::db::list_skill_benchmarks(&conn, "other").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_skill_context_includes_references() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("SKILL.md"), "---\nname: s\n---\nUse the mapping.").unwrap();
+        std::fs::create_dir_all(tmp.path().join("references")).unwrap();
+        std::fs::write(tmp.path().join("references/mapping.md"), "segment -> region").unwrap();
+        let context = skill_context(tmp.path()).unwrap();
+        assert!(context.contains("Use the mapping."));
+        assert!(context.contains("<reference name=\"mapping.md\">\nsegment -> region"));
+        assert!(skill_context(&tmp.path().join("missing")).is_err());
+    }
+}
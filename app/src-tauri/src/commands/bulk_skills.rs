@@ -0,0 +1,411 @@
+//! Bulk tag, domain, deactivate and delete operations over many skills.
+//!
+//! Each command first plans the change for every listed skill from the
+//! current DB state. An unknown skill fails the whole request; a skill the
+//! operation does not apply to (e.g. deactivating a library-only skill) is
+//! reported as skipped. With `dry_run` the plan is returned as is. Otherwise
+//! all DB writes run in one transaction, and only after it commits are skill
+//! folders moved (deactivate) or removed (delete); a folder that cannot be
+//! moved is reverted and reported on its skill.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use rusqlite::Connection;
+
+use super::imported_skills::validate_skill_name;
+use crate::db::Db;
+use crate::types::{BulkSkillChange, BulkSkillOperation, BulkSkillResult};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Action {
+    None,
+    SetTags(Vec<String>),
+    SetIntake(String),
+    Deactivate { skill_id: String },
+    Delete,
+}
+
+struct Planned {
+    change: BulkSkillChange,
+    action: Action,
+}
+
+fn planned(skill_name: &str, summary: String, action: Action) -> Planned {
+    Planned {
+        change: BulkSkillChange {
+            skill_name: skill_name.to_string(),
+            changed: action != Action::None,
+            summary,
+            error: None,
+            warnings: Vec::new(),
+        },
+        action,
+    }
+}
+
+/// Normalized and de-duplicated, as `set_skill_tags` stores them (before aliases).
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    tags.iter()
+        .map(|t| crate::db::normalize_tag(t))
+        .filter(|t| !t.is_empty() && seen.insert(t.clone()))
+        .collect()
+}
+
+fn list_or_none(items: &[String]) -> String {
+    if items.is_empty() {
+        "(none)".to_string()
+    } else {
+        items.join(", ")
+    }
+}
+
+/// Listed names once each, in order; fails on an invalid name.
+fn unique_names(skill_names: &[String]) -> Result<Vec<String>, String> {
+    if skill_names.is_empty() {
+        return Err("No skills selected".to_string());
+    }
+    let mut seen = HashSet::new();
+    let mut names = Vec::new();
+    for name in skill_names {
+        validate_skill_name(name)?;
+        if seen.insert(name.as_str()) {
+            names.push(name.clone());
+        }
+    }
+    Ok(names)
+}
+
+fn plan_update(conn: &Connection, skill_names: &[String], operation: &BulkSkillOperation) -> Result<Vec<Planned>, String> {
+    let mut plan = Vec::new();
+    for name in unique_names(skill_names)? {
+        let in_library = crate::db::get_live_skill_source(conn, &name)?.is_some();
+        let workspace_skill = crate::db::get_workspace_skill_by_name(conn, &name)?;
+        if !in_library && workspace_skill.is_none() {
+            return Err(format!("Skill '{}' not found", name));
+        }
+
+        let entry = match operation {
+            BulkSkillOperation::AddTags { tags } | BulkSkillOperation::RemoveTags { tags } => {
+                if !in_library {
+                    plan.push(planned(&name, "skipped: not in the skills library".to_string(), Action::None));
+                    continue;
+                }
+                let current = crate::db::get_tags_for_skills(conn, std::slice::from_ref(&name))?
+                    .remove(&name)
+                    .unwrap_or_default();
+                let tags = normalize_tags(tags);
+                let next: Vec<String> = if matches!(operation, BulkSkillOperation::AddTags { .. }) {
+                    current
+                        .iter()
+                        .cloned()
+                        .chain(tags.into_iter().filter(|t| !current.contains(t)))
+                        .collect()
+                } else {
+                    current.iter().filter(|t| !tags.contains(t)).cloned().collect()
+                };
+                if next == current {
+                    planned(&name, format!("tags unchanged: {}", list_or_none(&current)), Action::None)
+                } else {
+                    let summary = format!("tags: {} -> {}", list_or_none(&current), list_or_none(&next));
+                    let warnings = super::team_taxonomy::tag_warnings(conn, &name, &next)?;
+                    let mut entry = planned(&name, summary, Action::SetTags(next));
+                    entry.change.warnings = warnings;
+                    entry
+                }
+            }
+            BulkSkillOperation::SetDomain { domain } => {
+                let Some(run) = crate::db::get_workflow_run(conn, &name)? else {
+                    plan.push(planned(&name, "skipped: not built in Skill Builder".to_string(), Action::None));
+                    continue;
+                };
+                let mut intake = run
+                    .intake_json
+                    .as_deref()
+                    .and_then(|j| serde_json::from_str::<serde_json::Value>(j).ok())
+                    .filter(|v| v.is_object())
+                    .unwrap_or_else(|| serde_json::json!({}));
+                let domain = domain.as_deref().map(str::trim).filter(|d| !d.is_empty());
+                let current = intake["domain"].as_str().map(str::to_string);
+                let describe = |d: Option<&str>| d.unwrap_or("(none)").to_string();
+                if current.as_deref() == domain {
+                    planned(&name, format!("domain unchanged: {}", describe(domain)), Action::None)
+                } else {
+                    let summary = format!("domain: {} -> {}", describe(current.as_deref()), describe(domain));
+                    let fields = intake.as_object_mut().expect("intake is an object");
+                    match domain {
+                        Some(d) => fields.insert("domain".to_string(), serde_json::json!(d)),
+                        None => fields.remove("domain"),
+                    };
+                    planned(&name, summary, Action::SetIntake(intake.to_string()))
+                }
+            }
+            BulkSkillOperation::Deactivate => match workspace_skill {
+                None => planned(&name, "skipped: not a workspace skill".to_string(), Action::None),
+                Some(s) if !s.is_active => planned(&name, "already inactive".to_string(), Action::None),
+                Some(s) => planned(&name, "deactivate".to_string(), Action::Deactivate { skill_id: s.skill_id }),
+            },
+        };
+        plan.push(entry);
+    }
+    Ok(plan)
+}
+
+fn plan_delete(conn: &Connection, skill_names: &[String]) -> Result<Vec<Planned>, String> {
+    unique_names(skill_names)?
+        .into_iter()
+        .map(|name| {
+            let summary = match crate::db::get_live_skill_source(conn, &name)?.as_deref() {
+                None => return Err(format!("Skill '{}' not found in the skills library", name)),
+                Some("skill-builder") => "delete skill, its workflow state and files",
+                Some(_) => "delete imported skill and its files",
+            };
+            Ok(planned(&name, summary.to_string(), Action::Delete))
+        })
+        .collect()
+}
+
+/// Run every planned DB write in one transaction.
+fn apply_db(conn: &Connection, plan: &[Planned], workspace_path: Option<&str>) -> Result<(), String> {
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    for p in plan {
+        let name = &p.change.skill_name;
+        match &p.action {
+            Action::None => {}
+            Action::SetTags(tags) => crate::db::set_skill_tags(&tx, name, tags)?,
+            Action::SetIntake(intake) => crate::db::set_skill_intake(&tx, name, Some(intake))?,
+            Action::Deactivate { skill_id } => {
+                let workspace_path = workspace_path.ok_or_else(|| "Workspace path not initialized".to_string())?;
+                let inactive = Path::new(workspace_path)
+                    .join(".claude")
+                    .join("skills")
+                    .join(".inactive")
+                    .join(name);
+                crate::db::update_workspace_skill_active(&tx, skill_id, false, &inactive.to_string_lossy())?;
+            }
+            Action::Delete => super::skill::delete_skill_records(&tx, name)?,
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// Refuse the whole batch if any skill is only observed by this instance.
+fn ensure_all_writable(skill_names: &[String]) -> Result<(), String> {
+    skill_names
+        .iter()
+        .try_for_each(|name| super::observer::ensure_writable(name))
+}
+
+fn result(dry_run: bool, plan: Vec<Planned>) -> BulkSkillResult {
+    BulkSkillResult {
+        dry_run,
+        changes: plan.into_iter().map(|p| p.change).collect(),
+    }
+}
+
+/// Add or remove tags, set the domain, or deactivate a list of skills in one transaction.
+#[tauri::command]
+pub fn bulk_update_skills(
+    skill_names: Vec<String>,
+    operation: BulkSkillOperation,
+    dry_run: bool,
+    db: tauri::State<'_, Db>,
+) -> Result<BulkSkillResult, String> {
+    log::info!(
+        "[bulk_update_skills] {} skills operation={:?} dry_run={}",
+        skill_names.len(),
+        operation,
+        dry_run
+    );
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[bulk_update_skills] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let mut plan = plan_update(&conn, &skill_names, &operation)?;
+    if dry_run {
+        return Ok(result(true, plan));
+    }
+    ensure_all_writable(&skill_names)?;
+
+    let workspace_path = crate::db::read_settings(&conn)?.workspace_path;
+    apply_db(&conn, &plan, workspace_path.as_deref()).map_err(|e| {
+        log::error!("[bulk_update_skills] rolled back: {}", e);
+        e
+    })?;
+
+    if let Some(workspace_path) = workspace_path.as_deref() {
+        let mut deactivated = false;
+        for p in &mut plan {
+            if let Action::Deactivate { skill_id } = &p.action {
+                deactivated = true;
+                let name = &p.change.skill_name;
+                // Re-applies the committed DB change and moves the folder, reverting the row if the move fails.
+                if let Err(e) =
+                    super::imported_skills::toggle_skill_active_inner(skill_id, name, false, workspace_path, &conn)
+                {
+                    log::warn!("[bulk_update_skills] failed to deactivate '{}': {}", name, e);
+                    p.change.changed = false;
+                    p.change.error = Some(e);
+                }
+            }
+        }
+        if deactivated {
+            if let Err(e) = super::workflow::update_skills_section(workspace_path, &conn) {
+                log::warn!("Failed to update CLAUDE.md after bulk deactivate: {}", e);
+            }
+        }
+    }
+
+    log::info!(
+        "[bulk_update_skills] changed {} of {} skills",
+        plan.iter().filter(|p| p.change.changed).count(),
+        plan.len()
+    );
+    Ok(result(false, plan))
+}
+
+/// Delete a list of library skills: their DB records in one transaction, then their folders.
+#[tauri::command]
+pub fn bulk_delete_skills(
+    skill_names: Vec<String>,
+    dry_run: bool,
+    db: tauri::State<'_, Db>,
+) -> Result<BulkSkillResult, String> {
+    log::info!("[bulk_delete_skills] {} skills dry_run={}", skill_names.len(), dry_run);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[bulk_delete_skills] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let mut plan = plan_delete(&conn, &skill_names)?;
+    if dry_run {
+        return Ok(result(true, plan));
+    }
+    ensure_all_writable(&skill_names)?;
+
+    let settings = crate::db::read_settings(&conn)?;
+    let workspace_path = settings
+        .workspace_path
+        .ok_or_else(|| "Workspace path not initialized".to_string())?;
+    apply_db(&conn, &plan, Some(&workspace_path)).map_err(|e| {
+        log::error!("[bulk_delete_skills] rolled back: {}", e);
+        e
+    })?;
+
+    let skills_path = settings.skills_path.as_deref();
+    for p in &mut plan {
+        if let Err(e) = super::skill::delete_skill_files(&workspace_path, &p.change.skill_name, skills_path) {
+            log::warn!("[bulk_delete_skills] failed to remove files of '{}': {}", p.change.skill_name, e);
+            p.change.error = Some(e);
+        }
+        crate::audit::record(&conn, crate::audit::SKILL_DELETED, Some(&p.change.skill_name), "deleted in bulk");
+    }
+    if let Some(sp) = skills_path {
+        let names: Vec<&str> = plan.iter().map(|p| p.change.skill_name.as_str()).collect();
+        let msg = format!("{}: deleted", names.join(", "));
+        if let Err(e) = crate::git::commit_all(Path::new(sp), &msg) {
+            log::warn!("Git auto-commit failed ({}): {}", msg, e);
+        }
+    }
+
+    log::info!("[bulk_delete_skills] deleted {} skills", plan.len());
+    Ok(result(false, plan))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Connection {
+        let conn = crate::commands::test_utils::create_test_db();
+        crate::db::save_workflow_run(&conn, "alpha", 0, "pending", "domain").unwrap();
+        crate::db::save_workflow_run(&conn, "beta", 0, "pending", "domain").unwrap();
+        crate::db::set_skill_tags(&conn, "alpha", &["sales".to_string()]).unwrap();
+        crate::db::set_skill_intake(&conn, "beta", Some(r#"{"domain":"Finance","audience":"analysts"}"#)).unwrap();
+        conn
+    }
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn tags(conn: &Connection, name: &str) -> Vec<String> {
+        crate::db::get_tags_for_skills(conn, &[name.to_string()])
+            .unwrap()
+            .remove(name)
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_plan_tags_without_writing() {
+        let conn = setup();
+        let op = BulkSkillOperation::AddTags { tags: names(&[" Sales ", "Revenue"]) };
+        let plan = plan_update(&conn, &names(&["alpha", "beta", "alpha"]), &op).unwrap();
+        let changes: Vec<_> = plan.iter().map(|p| (p.change.changed, p.change.summary.as_str())).collect();
+        assert_eq!(
+            changes,
+            vec![
+                (true, "tags: sales -> sales, revenue"),
+                (true, "tags: (none) -> sales, revenue"),
+            ]
+        );
+        assert_eq!(tags(&conn, "beta"), Vec::<String>::new());
+
+        let op = BulkSkillOperation::RemoveTags { tags: names(&["sales"]) };
+        let plan = plan_update(&conn, &names(&["alpha", "beta"]), &op).unwrap();
+        assert_eq!(plan[0].action, Action::SetTags(vec![]));
+        assert_eq!(plan[1].action, Action::None);
+    }
+
+    #[test]
+    fn test_unknown_skill_fails_whole_request() {
+        let conn = setup();
+        let err = plan_update(&conn, &names(&["alpha", "ghost"]), &BulkSkillOperation::Deactivate).unwrap_err();
+        assert!(err.contains("ghost"));
+        assert!(plan_delete(&conn, &names(&["ghost"])).is_err());
+        assert!(plan_delete(&conn, &[]).is_err());
+    }
+
+    #[test]
+    fn test_apply_set_domain_and_deactivate_skip() {
+        let conn = setup();
+        let op = BulkSkillOperation::SetDomain { domain: Some("Revenue".to_string()) };
+        let plan = plan_update(&conn, &names(&["alpha", "beta"]), &op).unwrap();
+        assert_eq!(plan[1].change.summary, "domain: Finance -> Revenue");
+        apply_db(&conn, &plan, None).unwrap();
+        let intake: serde_json::Value =
+            serde_json::from_str(&crate::db::get_workflow_run(&conn, "beta").unwrap().unwrap().intake_json.unwrap())
+                .unwrap();
+        assert_eq!(intake["domain"], "Revenue");
+        assert_eq!(intake["audience"], "analysts");
+
+        let plan = plan_update(&conn, &names(&["alpha"]), &BulkSkillOperation::Deactivate).unwrap();
+        assert_eq!(plan[0].change.summary, "skipped: not a workspace skill");
+        assert!(!plan[0].change.changed);
+    }
+
+    #[test]
+    fn test_failed_write_rolls_back_earlier_ones() {
+        let conn = setup();
+        let mut plan = plan_update(
+            &conn,
+            &names(&["alpha", "beta"]),
+            &BulkSkillOperation::AddTags { tags: names(&["q3"]) },
+        )
+        .unwrap();
+        // A skill removed after planning makes its write fail.
+        plan[1].change.skill_name = "gone".to_string();
+        assert!(apply_db(&conn, &plan, None).is_err());
+        assert_eq!(tags(&conn, "alpha"), vec!["sales".to_string()]);
+    }
+
+    #[test]
+    fn test_delete_records_in_one_transaction() {
+        let conn = setup();
+        let plan = plan_delete(&conn, &names(&["alpha", "beta"])).unwrap();
+        assert_eq!(plan[0].change.summary, "delete skill, its workflow state and files");
+        apply_db(&conn, &plan, Some("/unused")).unwrap();
+        assert!(crate::db::get_live_skill_source(&conn, "alpha").unwrap().is_none());
+        assert!(crate::db::get_workflow_run(&conn, "beta").unwrap().is_none());
+    }
+}
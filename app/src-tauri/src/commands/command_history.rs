@@ -0,0 +1,222 @@
+//! Searchable history of commands the user ran, for support and for repeating
+//! parameterized operations.
+//!
+//! The frontend `invoke` wrapper reports each command it sends, with its
+//! arguments and outcome, via `record_command`. Read-only queries are not
+//! recorded (see `is_recorded`, mirrored in `lib/tauri.ts`). Argument values
+//! under secret-looking keys are redacted before storage, and redacted entries
+//! cannot be re-run. `rerun_command` hands the stored invocation back to the
+//! frontend, which dispatches it through the same wrapper.
+
+use serde_json::Value;
+
+use crate::db::Db;
+use crate::types::{CommandHistoryEntry, CommandHistoryFilter, CommandRerun};
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Lower-cased key fragments (underscores removed) that mark a secret value.
+const SECRET_KEY_FRAGMENTS: &[&str] = &["apikey", "token", "secret", "password", "privatekey", "credential"];
+
+/// Queries that read state without changing it; too frequent and not useful to replay.
+const READ_ONLY_PREFIXES: &[&str] = &["get_", "list_", "check_", "read_", "parse_", "verify_", "has_", "is_"];
+
+/// History commands themselves are never recorded.
+const HISTORY_COMMANDS: &[&str] = &["record_command", "rerun_command"];
+
+/// Destructive commands are recorded but not replayed.
+const NON_RERUNNABLE_PREFIXES: &[&str] = &["delete_", "remove_", "reset_", "cleanup_"];
+
+const MAX_ERROR_CHARS: usize = 2000;
+
+pub(crate) fn is_recorded(command: &str) -> bool {
+    !HISTORY_COMMANDS.contains(&command) && !READ_ONLY_PREFIXES.iter().any(|p| command.starts_with(p))
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let normalized: String = key.chars().filter(|c| *c != '_' && *c != '-').collect::<String>().to_lowercase();
+    SECRET_KEY_FRAGMENTS.iter().any(|f| normalized.contains(f))
+}
+
+/// Replace values under secret-looking keys, recursively. Returns true if anything was replaced.
+pub(crate) fn redact_params(value: &mut Value) -> bool {
+    let mut redacted = false;
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                let present = !(v.is_null() || v.as_str().is_some_and(str::is_empty));
+                if is_secret_key(key) && present {
+                    *v = Value::String(REDACTED.to_string());
+                    redacted = true;
+                } else {
+                    redacted |= redact_params(v);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for v in items {
+                redacted |= redact_params(v);
+            }
+        }
+        _ => {}
+    }
+    redacted
+}
+
+#[tauri::command]
+pub fn record_command(
+    command: String,
+    params: Option<Value>,
+    outcome: String,
+    error: Option<String>,
+    duration_ms: u64,
+    db: tauri::State<'_, Db>,
+) -> Result<(), String> {
+    if !is_recorded(&command) {
+        return Ok(());
+    }
+    if outcome != "ok" && outcome != "error" {
+        return Err(format!("Invalid outcome '{}'", outcome));
+    }
+    let mut params = params.unwrap_or(Value::Object(Default::default()));
+    let redacted = redact_params(&mut params);
+    let params_json = serde_json::to_string(&params).map_err(|e| e.to_string())?;
+    let error: Option<String> = error.map(|e| e.chars().take(MAX_ERROR_CHARS).collect());
+
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[record_command] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::insert_command_history(&conn, &command, &params_json, redacted, &outcome, error.as_deref(), duration_ms)?;
+    log::debug!("[record_command] {} -> {} ({}ms)", command, outcome, duration_ms);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_command_history(
+    filter: Option<CommandHistoryFilter>,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<CommandHistoryEntry>, String> {
+    let filter = filter.unwrap_or_default();
+    log::info!(
+        "[list_command_history] query={:?} command={:?} outcome={:?}",
+        filter.query,
+        filter.command,
+        filter.outcome
+    );
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[list_command_history] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::list_command_history(&conn, &filter)
+}
+
+fn rerun_for(entry: CommandHistoryEntry) -> Result<CommandRerun, String> {
+    if NON_RERUNNABLE_PREFIXES.iter().any(|p| entry.command.starts_with(p)) {
+        return Err(format!("'{}' is destructive and cannot be re-run from history", entry.command));
+    }
+    if entry.redacted {
+        return Err(format!(
+            "'{}' was recorded with redacted secrets and cannot be re-run from history",
+            entry.command
+        ));
+    }
+    Ok(CommandRerun {
+        command: entry.command,
+        params: entry.params,
+    })
+}
+
+/// Return the command and arguments to repeat a history entry.
+#[tauri::command]
+pub fn rerun_command(history_id: i64, db: tauri::State<'_, Db>) -> Result<CommandRerun, String> {
+    log::info!("[rerun_command] history_id={}", history_id);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[rerun_command] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let entry = crate::db::get_command_history_entry(&conn, history_id)?
+        .ok_or_else(|| format!("Command history entry {} not found", history_id))?;
+    rerun_for(entry).map_err(|e| {
+        log::warn!("[rerun_command] {}", e);
+        e
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_is_recorded_skips_queries_and_history_commands() {
+        assert!(is_recorded("export_eval_dataset"));
+        assert!(is_recorded("save_settings"));
+        assert!(!is_recorded("get_settings"));
+        assert!(!is_recorded("list_workspace_skills"));
+        assert!(!is_recorded("record_command"));
+        assert!(!is_recorded("rerun_command"));
+    }
+
+    #[test]
+    fn test_redact_params_nested_secrets() {
+        let mut params = json!({
+            "settings": {"anthropic_api_key": "sk-ant-123", "github_oauth_token": null, "log_level": "info"},
+            "apiKey": "sk-ant-456",
+            "items": [{"password": "hunter2"}],
+            "skillName": "my-skill"
+        });
+        assert!(redact_params(&mut params));
+        assert_eq!(params["settings"]["anthropic_api_key"], REDACTED);
+        assert_eq!(params["settings"]["github_oauth_token"], Value::Null);
+        assert_eq!(params["settings"]["log_level"], "info");
+        assert_eq!(params["apiKey"], REDACTED);
+        assert_eq!(params["items"][0]["password"], REDACTED);
+        assert_eq!(params["skillName"], "my-skill");
+
+        let mut plain = json!({"skillName": "x", "filter": {"tags": ["a"]}});
+        assert!(!redact_params(&mut plain));
+    }
+
+    #[test]
+    fn test_history_filter_and_rerun() {
+        let conn = crate::commands::test_utils::create_test_db();
+        let export = json!({"skillName": "sales", "filter": {"minRating": 4}}).to_string();
+        crate::db::insert_command_history(&conn, "export_eval_dataset", &export, false, "ok", None, 120).unwrap();
+        crate::db::insert_command_history(&conn, "save_settings", "{}", true, "ok", None, 5).unwrap();
+        crate::db::insert_command_history(&conn, "delete_skill", "{\"name\":\"sales\"}", false, "error", Some("locked"), 3)
+            .unwrap();
+
+        let by_text = crate::db::list_command_history(
+            &conn,
+            &CommandHistoryFilter { query: Some("sales".into()), ..Default::default() },
+        )
+        .unwrap();
+        assert_eq!(by_text.len(), 2);
+        assert_eq!(by_text[0].command, "delete_skill");
+
+        let errors = crate::db::list_command_history(
+            &conn,
+            &CommandHistoryFilter { outcome: Some("error".into()), ..Default::default() },
+        )
+        .unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].error.as_deref(), Some("locked"));
+
+        // LIKE wildcards in the query are literal
+        let none = crate::db::list_command_history(
+            &conn,
+            &CommandHistoryFilter { query: Some("%".into()), ..Default::default() },
+        )
+        .unwrap();
+        assert!(none.is_empty());
+
+        let all = crate::db::list_command_history(&conn, &CommandHistoryFilter::default()).unwrap();
+        let by_command = |c: &str| all.iter().find(|e| e.command == c).cloned().unwrap();
+
+        let rerun = rerun_for(by_command("export_eval_dataset")).unwrap();
+        assert_eq!(rerun.params["filter"]["minRating"], 4);
+        assert!(rerun_for(by_command("save_settings")).unwrap_err().contains("redacted"));
+        assert!(rerun_for(by_command("delete_skill")).unwrap_err().contains("destructive"));
+    }
+}
@@ -0,0 +1,243 @@
+//! Pre-run cost estimates for workflow steps.
+//!
+//! `estimate_step_cost` prices a step on each candidate model before it runs.
+//! Recent completed runs of the same step (any skill) are the main basis:
+//! each run's primary model usage — the row with the most output tokens — is
+//! re-priced at the candidate model's list price, and sub-agent rows keep
+//! their recorded cost. With fewer than `MIN_HISTORY_RUNS` runs the history
+//! is blended with a size-based guess from the step's prompt template, the
+//! skill's context artifacts and its current SKILL.md and references.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::agents::cost_ticker::list_price;
+use crate::db::Db;
+use crate::types::{ModelCostEstimate, StepCostEstimate, StepRunUsage};
+
+const HISTORY_RUNS: usize = 50;
+/// Runs needed before history is used on its own.
+const MIN_HISTORY_RUNS: usize = 3;
+/// Same-model runs needed for a `high` confidence estimate.
+const HIGH_CONFIDENCE_RUNS: usize = 10;
+const BYTES_PER_TOKEN: u64 = 4;
+/// Size-based guess: an agent uses about a third of its turn limit, re-reads
+/// its inputs from cache every turn and writes this much per turn.
+const OUTPUT_TOKENS_PER_TURN: i64 = 1_500;
+/// Bounds of the size-based guess, as multiples of the expected cost.
+const SIZE_LOW_FACTOR: f64 = 0.5;
+const SIZE_HIGH_FACTOR: f64 = 2.0;
+const DEFAULT_CANDIDATES: &[&str] = &["sonnet", "opus"];
+
+fn bytes_under(path: &Path) -> u64 {
+    if path.is_file() {
+        return std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| bytes_under(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+/// Cost of one historical run had its primary model been `model`.
+fn repriced_run_cost(rows: &[&StepRunUsage], model: &str) -> f64 {
+    let primary = rows.iter().enumerate().max_by_key(|(_, r)| r.output_tokens).map(|(i, _)| i);
+    rows.iter()
+        .enumerate()
+        .map(|(i, r)| {
+            if Some(i) == primary {
+                list_price(model, r.input_tokens, r.output_tokens, r.cache_read_tokens, r.cache_write_tokens)
+            } else {
+                r.total_cost
+            }
+        })
+        .sum()
+}
+
+/// The value at fraction `p` of `sorted` (nearest rank).
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let idx = ((sorted.len() - 1) as f64 * p).round() as usize;
+    sorted[idx]
+}
+
+fn size_based_cost(model: &str, input_tokens: i64, max_turns: u32) -> f64 {
+    let turns = (max_turns / 3).max(1) as i64;
+    list_price(model, input_tokens, OUTPUT_TOKENS_PER_TURN * turns, input_tokens * turns, input_tokens)
+}
+
+/// Estimate `model`'s cost from `history` (usage rows of earlier runs) and
+/// the current input size.
+fn estimate_for_model(model: &str, history: &[StepRunUsage], input_tokens: i64, max_turns: u32) -> ModelCostEstimate {
+    let mut runs: BTreeMap<&str, Vec<&StepRunUsage>> = BTreeMap::new();
+    for row in history {
+        runs.entry(row.agent_id.as_str()).or_default().push(row);
+    }
+    let same_model_runs = runs
+        .values()
+        .filter(|rows| {
+            rows.iter()
+                .max_by_key(|r| r.output_tokens)
+                .is_some_and(|r| crate::db::normalize_model_name(&r.model) == model)
+        })
+        .count();
+    let mut costs: Vec<f64> = runs.values().map(|rows| repriced_run_cost(rows, model)).collect();
+    costs.sort_by(|a, b| a.total_cmp(b));
+
+    let guess = size_based_cost(model, input_tokens, max_turns);
+    let n = costs.len();
+    let (expected, low, high, basis) = if n >= MIN_HISTORY_RUNS {
+        let mean = costs.iter().sum::<f64>() / n as f64;
+        (mean, percentile(&costs, 0.1), percentile(&costs, 0.9), "history")
+    } else if n > 0 {
+        // Weight history by how close it is to being trusted on its own.
+        let weight = n as f64 / MIN_HISTORY_RUNS as f64;
+        let mean = costs.iter().sum::<f64>() / n as f64;
+        let expected = mean * weight + guess * (1.0 - weight);
+        (
+            expected,
+            costs[0].min(expected * SIZE_LOW_FACTOR),
+            costs[n - 1].max(expected * SIZE_HIGH_FACTOR),
+            "blended",
+        )
+    } else {
+        (guess, guess * SIZE_LOW_FACTOR, guess * SIZE_HIGH_FACTOR, "size")
+    };
+    let confidence = if same_model_runs >= HIGH_CONFIDENCE_RUNS {
+        "high"
+    } else if n >= MIN_HISTORY_RUNS {
+        "medium"
+    } else {
+        "low"
+    };
+
+    ModelCostEstimate {
+        model: model.to_string(),
+        expected_cost: expected,
+        low_cost: low,
+        high_cost: high,
+        basis: basis.to_string(),
+        confidence: confidence.to_string(),
+        sample_runs: n,
+    }
+}
+
+/// Estimate what running `step_id` for a skill would cost on each of `models`
+/// (default: sonnet, opus and the preferred model), so a model can be chosen
+/// before pressing Run.
+#[tauri::command]
+pub fn estimate_step_cost(
+    skill_name: String,
+    step_id: u32,
+    workspace_path: String,
+    models: Option<Vec<String>>,
+    db: tauri::State<'_, Db>,
+) -> Result<StepCostEstimate, String> {
+    log::info!("[estimate_step_cost] skill={} step={}", skill_name, step_id);
+    super::imported_skills::validate_skill_name(&skill_name)?;
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[estimate_step_cost] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let settings = crate::db::read_settings(&conn)?;
+    let skills_path = settings.skills_path.clone().unwrap_or_else(|| workspace_path.clone());
+    let purpose = crate::db::get_workflow_run(&conn, &skill_name)?
+        .map(|r| r.purpose)
+        .unwrap_or_else(|| "domain".to_string());
+    let step = super::workflow_manifest::resolve_step(&workspace_path, &purpose, step_id)?;
+    let history = crate::db::get_step_run_usage(&conn, step_id as i32, HISTORY_RUNS)?;
+    drop(conn);
+
+    let ws = Path::new(&workspace_path);
+    let prompt_template_bytes = [
+        ws.join(".claude").join("agents").join(&step.prompt_template),
+        ws.join(".claude").join("workflows").join("agents").join(&step.prompt_template),
+    ]
+    .iter()
+    .find(|p| p.is_file())
+    .map(|p| bytes_under(p))
+    .unwrap_or(0);
+    let context_bytes = bytes_under(&ws.join(&skill_name).join("context"))
+        + bytes_under(&ws.join(&skill_name).join("user-context.md"));
+    let skill_dir = Path::new(&skills_path).join(&skill_name);
+    let skill_bytes = bytes_under(&skill_dir.join("SKILL.md")) + bytes_under(&skill_dir.join("references"));
+    let input_tokens = ((prompt_template_bytes + context_bytes + skill_bytes) / BYTES_PER_TOKEN) as i64;
+
+    let requested: Vec<String> = match models {
+        Some(models) if !models.is_empty() => models,
+        _ => DEFAULT_CANDIDATES
+            .iter()
+            .map(|m| m.to_string())
+            .chain(settings.preferred_model.clone())
+            .collect(),
+    };
+    let mut candidates: Vec<String> = Vec::new();
+    for model in requested.iter().map(|m| crate::db::normalize_model_name(m)) {
+        if !candidates.contains(&model) {
+            candidates.push(model);
+        }
+    }
+
+    Ok(StepCostEstimate {
+        skill_name,
+        step_id,
+        prompt_template_bytes,
+        context_bytes,
+        skill_bytes,
+        input_tokens,
+        estimates: candidates
+            .iter()
+            .map(|model| estimate_for_model(model, &history, input_tokens, step.max_turns))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(agent_id: &str, model: &str, output_tokens: i64, total_cost: f64) -> StepRunUsage {
+        StepRunUsage {
+            agent_id: agent_id.to_string(),
+            model: model.to_string(),
+            input_tokens: 10_000,
+            output_tokens,
+            cache_read_tokens: 100_000,
+            cache_write_tokens: 10_000,
+            total_cost,
+        }
+    }
+
+    #[test]
+    fn test_history_is_repriced_for_other_models() {
+        let history: Vec<StepRunUsage> = (0..4)
+            .map(|i| run(&format!("a{}", i), "claude-sonnet-4-6", 20_000, 0.5))
+            .collect();
+        let sonnet = estimate_for_model("claude-sonnet-4-6", &history, 5_000, 50);
+        let opus = estimate_for_model("claude-opus-4-6", &history, 5_000, 50);
+        assert_eq!(sonnet.basis, "history");
+        assert_eq!(sonnet.confidence, "medium");
+        assert_eq!(sonnet.sample_runs, 4);
+        assert!(opus.expected_cost > sonnet.expected_cost);
+        assert!(sonnet.low_cost <= sonnet.expected_cost && sonnet.expected_cost <= sonnet.high_cost);
+    }
+
+    #[test]
+    fn test_sub_agent_rows_keep_recorded_cost() {
+        let history = vec![
+            run("a1", "claude-sonnet-4-6", 20_000, 0.5),
+            run("a1", "claude-haiku-4-5-20251001", 1_000, 0.02),
+        ];
+        let rows: Vec<&StepRunUsage> = history.iter().collect();
+        let sonnet_only = list_price("claude-opus-4-6", 10_000, 20_000, 100_000, 10_000);
+        assert!((repriced_run_cost(&rows, "claude-opus-4-6") - (sonnet_only + 0.02)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_without_history_estimate_comes_from_size() {
+        let small = estimate_for_model("claude-sonnet-4-6", &[], 1_000, 30);
+        let large = estimate_for_model("claude-sonnet-4-6", &[], 50_000, 30);
+        assert_eq!(small.basis, "size");
+        assert_eq!(small.confidence, "low");
+        assert!(large.expected_cost > small.expected_cost);
+        assert!((small.high_cost - small.expected_cost * SIZE_HIGH_FACTOR).abs() < 1e-12);
+    }
+}
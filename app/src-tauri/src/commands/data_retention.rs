@@ -0,0 +1,139 @@
+//! Retention policy and user data purge.
+//!
+//! A `RetentionPrune` background task deletes agent runs, workflow sessions,
+//! archived transcripts and audit entries older than `AppSettings::retention`
+//! allows. It is queued at startup and once a day after that. The
+//! `purge_all_user_data` command wipes usage history, transcripts and stored
+//! credentials in one go, leaving skills in place.
+
+use std::time::Duration;
+
+use tauri::Manager;
+
+use crate::agents::sidecar_pool::SidecarPool;
+use crate::db::Db;
+use crate::scheduler::BackgroundScheduler;
+use crate::types::{BackgroundTaskKind, RetentionPruneResult, TaskPriority, UserDataPurgeResult};
+
+/// Time between retention passes after the one queued at startup.
+const PRUNE_INTERVAL_SECS: u64 = 24 * 60 * 60;
+
+/// Queue a retention pass now and once a day after that. Safe to call from `setup()`.
+pub fn start(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            app.state::<BackgroundScheduler>()
+                .enqueue(BackgroundTaskKind::RetentionPrune, TaskPriority::Low);
+            tokio::time::sleep(Duration::from_secs(PRUNE_INTERVAL_SECS)).await;
+        }
+    });
+}
+
+/// Apply the retention policy in settings. Run by the background scheduler.
+pub(crate) fn prune_expired(db: &Db) -> Result<RetentionPruneResult, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let policy = crate::db::read_settings(&conn)?.retention;
+    let result = crate::db::prune_expired_data(&conn, &policy)?;
+    if result != RetentionPruneResult::default() {
+        let summary = format!(
+            "Deleted {} agent runs, {} workflow sessions, {} transcripts and {} audit entries past retention",
+            result.agent_runs, result.workflow_sessions, result.transcripts, result.audit_entries
+        );
+        log::info!("[data_retention] {}", summary);
+        crate::audit::record(&conn, crate::audit::DATA_PRUNED, None, &summary);
+    }
+    Ok(result)
+}
+
+/// Sign out of GitHub and forget git host tokens. Returns whether any
+/// credential was stored.
+fn clear_credentials(conn: &rusqlite::Connection) -> Result<bool, String> {
+    let mut settings = crate::db::read_settings(conn)?;
+    let hosts = crate::db::read_git_host_credentials(conn)?;
+    let had_credentials = settings.github_oauth_token.is_some() || !hosts.is_empty();
+    settings.github_oauth_token = None;
+    settings.github_oauth_host = None;
+    settings.github_user_login = None;
+    settings.github_user_avatar = None;
+    settings.github_user_email = None;
+    crate::db::write_settings(conn, &settings)?;
+    crate::db::write_git_host_credentials(conn, &[])?;
+    Ok(had_credentials)
+}
+
+/// Delete usage history, archived and on-disk transcripts, the GitHub token
+/// and git host tokens. Skills, settings and the audit log are kept. Refused
+/// while agents are running, since they would write usage back straight away.
+#[tauri::command]
+pub async fn purge_all_user_data(
+    workspace_path: String,
+    db: tauri::State<'_, Db>,
+    pool: tauri::State<'_, SidecarPool>,
+) -> Result<UserDataPurgeResult, String> {
+    log::info!("[purge_all_user_data]");
+    if !pool.is_idle().await {
+        log::error!("[purge_all_user_data] Agents are running");
+        return Err("Stop running agents before purging user data".to_string());
+    }
+
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[purge_all_user_data] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let mut result = crate::db::purge_user_data(&conn).map_err(|e| {
+        log::error!("[purge_all_user_data] Failed to delete usage history: {}", e);
+        e
+    })?;
+    result.credentials_cleared = clear_credentials(&conn).map_err(|e| {
+        log::error!("[purge_all_user_data] Failed to clear credentials: {}", e);
+        e
+    })?;
+    result.transcript_files = crate::logging::delete_all_transcript_files(&workspace_path) as usize;
+
+    let summary = format!(
+        "Deleted {} usage rows, {} archived transcripts and {} transcript files{}",
+        result.usage_rows,
+        result.transcripts,
+        result.transcript_files,
+        if result.credentials_cleared { "; cleared stored credentials" } else { "" }
+    );
+    log::info!("[purge_all_user_data] {}", summary);
+    crate::audit::record(&conn, crate::audit::DATA_PURGED, None, &summary);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::create_test_db;
+    use crate::types::GitHostCredential;
+
+    #[test]
+    fn test_clear_credentials_signs_out_and_keeps_other_settings() {
+        let conn = create_test_db();
+        assert!(!clear_credentials(&conn).unwrap());
+
+        let mut settings = crate::db::read_settings(&conn).unwrap();
+        settings.github_oauth_token = Some("gho_test".into());
+        settings.github_user_login = Some("alice".into());
+        settings.max_dimensions = 7;
+        crate::db::write_settings(&conn, &settings).unwrap();
+        crate::db::write_git_host_credentials(
+            &conn,
+            &[GitHostCredential {
+                host: "gitlab".into(),
+                login: "alice".into(),
+                token: "glpat-test".into(),
+                api_base: None,
+            }],
+        )
+        .unwrap();
+
+        assert!(clear_credentials(&conn).unwrap());
+        let settings = crate::db::read_settings(&conn).unwrap();
+        assert!(settings.github_oauth_token.is_none());
+        assert!(settings.github_user_login.is_none());
+        assert_eq!(settings.max_dimensions, 7);
+        assert!(crate::db::read_git_host_credentials(&conn).unwrap().is_empty());
+    }
+}
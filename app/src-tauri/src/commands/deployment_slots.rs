@@ -0,0 +1,474 @@
+//! Stable and beta deployment slots for a skill.
+//!
+//! Beta is the skill folder in the skills path, which the workflow and
+//! refine edit. Stable is a snapshot under `.slots/{skill}/stable`, only
+//! changed by `promote_beta_to_stable`, which bumps the version, records a
+//! changelog entry and updates `.slots/{skill}/slots.json`. Each slot
+//! packages to its own `.slots/{skill}/{skill}-{slot}.skill`, and either
+//! slot can be installed into `~/.claude/skills/{skill}` on this machine;
+//! a marker file in the installed copy records which one.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::imported_skills::{parse_frontmatter_full, validate_skill_name};
+use super::skill_version::{bump_version, propose_bump, DEFAULT_VERSION};
+use crate::db::Db;
+use crate::types::{PackageResult, SkillChangelogEntry, SkillSlot, SkillSlotsStatus};
+
+const SLOT_STABLE: &str = "stable";
+const SLOT_BETA: &str = "beta";
+
+const SLOTS_DIR: &str = ".slots";
+const SLOTS_MANIFEST: &str = "slots.json";
+/// Written into an installed copy so switching slots never overwrites a
+/// skill the user installed some other way.
+const INSTALLED_SLOT_MARKER: &str = ".skill-builder-slot";
+
+/// Contents of `.slots/{skill}/slots.json`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SlotsManifest {
+    #[serde(default)]
+    stable: Option<StableRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StableRecord {
+    version: String,
+    previous_version: Option<String>,
+    promoted_at: String,
+}
+
+fn parse_slot(slot: &str) -> Result<&'static str, String> {
+    match slot {
+        SLOT_STABLE => Ok(SLOT_STABLE),
+        SLOT_BETA => Ok(SLOT_BETA),
+        other => Err(format!("Unknown slot '{}': expected stable or beta", other)),
+    }
+}
+
+fn slots_root(skills_path: &Path, skill_name: &str) -> PathBuf {
+    skills_path.join(SLOTS_DIR).join(skill_name)
+}
+
+fn slot_dir(skills_path: &Path, skill_name: &str, slot: &str) -> PathBuf {
+    if slot == SLOT_STABLE {
+        slots_root(skills_path, skill_name).join(SLOT_STABLE)
+    } else {
+        skills_path.join(skill_name)
+    }
+}
+
+fn slot_package_path(skills_path: &Path, skill_name: &str, slot: &str) -> PathBuf {
+    slots_root(skills_path, skill_name).join(format!("{}-{}.skill", skill_name, slot))
+}
+
+fn read_manifest(skills_path: &Path, skill_name: &str) -> SlotsManifest {
+    let path = slots_root(skills_path, skill_name).join(SLOTS_MANIFEST);
+    std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn write_manifest(skills_path: &Path, skill_name: &str, manifest: &SlotsManifest) -> Result<(), String> {
+    let path = slots_root(skills_path, skill_name).join(SLOTS_MANIFEST);
+    let json = serde_json::to_string_pretty(manifest).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Replace `dst` with the deployable files of `src`: SKILL.md, the
+/// onboarding checklist and `references/`.
+fn copy_deployable(src: &Path, dst: &Path) -> Result<(), String> {
+    if dst.exists() {
+        std::fs::remove_dir_all(dst).map_err(|e| format!("Failed to clear {}: {}", dst.display(), e))?;
+    }
+    std::fs::create_dir_all(dst).map_err(|e| format!("Failed to create {}: {}", dst.display(), e))?;
+    for file in ["SKILL.md", super::onboarding::CHECKLIST_FILE] {
+        if src.join(file).is_file() {
+            std::fs::copy(src.join(file), dst.join(file)).map_err(|e| format!("Failed to copy {}: {}", file, e))?;
+        }
+    }
+    let references = src.join("references");
+    if references.is_dir() {
+        std::fs::create_dir_all(dst.join("references")).map_err(|e| e.to_string())?;
+        super::imported_skills::copy_dir_recursive(&references, &dst.join("references"))?;
+    }
+    Ok(())
+}
+
+fn read_skill_md(dir: &Path) -> Option<String> {
+    std::fs::read_to_string(dir.join("SKILL.md")).ok()
+}
+
+fn installed_slot(claude_skills_dir: &Path, skill_name: &str) -> Option<String> {
+    let marker = claude_skills_dir.join(skill_name).join(INSTALLED_SLOT_MARKER);
+    std::fs::read_to_string(marker).ok().map(|s| s.trim().to_string())
+}
+
+fn slot_info(skills_path: &Path, skill_name: &str, slot: &str, promoted_at: Option<String>) -> SkillSlot {
+    let dir = slot_dir(skills_path, skill_name, slot);
+    let package = slot_package_path(skills_path, skill_name, slot);
+    SkillSlot {
+        slot: slot.to_string(),
+        version: read_skill_md(&dir).and_then(|md| parse_frontmatter_full(&md).version),
+        path: dir.to_string_lossy().to_string(),
+        package_path: package.is_file().then(|| package.to_string_lossy().to_string()),
+        promoted_at,
+    }
+}
+
+fn slots_status_inner(skills_path: &Path, claude_skills_dir: &Path, skill_name: &str) -> SkillSlotsStatus {
+    let manifest = read_manifest(skills_path, skill_name);
+    let stable = slot_dir(skills_path, skill_name, SLOT_STABLE)
+        .join("SKILL.md")
+        .is_file()
+        .then(|| {
+            let promoted_at = manifest.stable.map(|r| r.promoted_at);
+            slot_info(skills_path, skill_name, SLOT_STABLE, promoted_at)
+        });
+    SkillSlotsStatus {
+        skill_name: skill_name.to_string(),
+        stable,
+        beta: slot_info(skills_path, skill_name, SLOT_BETA, None),
+        installed_slot: installed_slot(claude_skills_dir, skill_name),
+        install_path: claude_skills_dir.join(skill_name).to_string_lossy().to_string(),
+    }
+}
+
+/// Copy `slot` into the Claude skills folder, replacing a previously
+/// installed slot. Refuses to overwrite a copy this app did not install.
+fn install_slot_inner(skills_path: &Path, claude_skills_dir: &Path, skill_name: &str, slot: &str) -> Result<(), String> {
+    let src = slot_dir(skills_path, skill_name, slot);
+    if !src.join("SKILL.md").is_file() {
+        return Err(format!("The {} slot of '{}' has no SKILL.md", slot, skill_name));
+    }
+    let dst = claude_skills_dir.join(skill_name);
+    if dst.exists() && installed_slot(claude_skills_dir, skill_name).is_none() {
+        return Err(format!(
+            "{} already exists and was not installed by Skill Builder; remove it first",
+            dst.display()
+        ));
+    }
+    copy_deployable(&src, &dst)?;
+    std::fs::write(dst.join(INSTALLED_SLOT_MARKER), slot).map_err(|e| format!("Failed to write slot marker: {}", e))?;
+    log::info!("[install_skill_slot] installed '{}' {} slot at {}", skill_name, slot, dst.display());
+    Ok(())
+}
+
+fn uninstall_slot_inner(claude_skills_dir: &Path, skill_name: &str) -> Result<(), String> {
+    if installed_slot(claude_skills_dir, skill_name).is_none() {
+        return Err(format!("'{}' is not installed from a deployment slot", skill_name));
+    }
+    let dst = claude_skills_dir.join(skill_name);
+    std::fs::remove_dir_all(&dst).map_err(|e| format!("Failed to remove {}: {}", dst.display(), e))
+}
+
+/// `candidate` when it parses as a newer semver than `floor`, else `floor`.
+fn newer_version(candidate: Option<&str>, floor: String) -> String {
+    let parsed_floor = semver::Version::parse(&floor).ok();
+    match (candidate.and_then(|c| semver::Version::parse(c.trim()).ok()), parsed_floor) {
+        (Some(c), Some(f)) if c > f => c.to_string(),
+        _ => floor,
+    }
+}
+
+/// Copy beta over stable with a bumped version. The bump compares beta's
+/// SKILL.md with stable's; a beta version already past the bumped one wins.
+fn promote_inner(
+    conn: &rusqlite::Connection,
+    skills_path: &Path,
+    claude_skills_dir: &Path,
+    skill_name: &str,
+) -> Result<SkillChangelogEntry, String> {
+    let beta_dir = slot_dir(skills_path, skill_name, SLOT_BETA);
+    let stable_dir = slot_dir(skills_path, skill_name, SLOT_STABLE);
+    let beta_md = read_skill_md(&beta_dir).ok_or_else(|| format!("'{}' has no SKILL.md to promote", skill_name))?;
+    let stable_md = read_skill_md(&stable_dir);
+    let beta_version = parse_frontmatter_full(&beta_md).version;
+
+    let (version, previous_version, bump, summary) = match &stable_md {
+        Some(old) => {
+            if *old == beta_md {
+                return Err(format!("Beta of '{}' matches stable; nothing to promote", skill_name));
+            }
+            let previous = parse_frontmatter_full(old).version;
+            let (bump, summary) = propose_bump(old, &beta_md);
+            let bumped = bump_version(previous.as_deref().unwrap_or(DEFAULT_VERSION), bump);
+            (newer_version(beta_version.as_deref(), bumped), previous, bump.as_str(), summary)
+        }
+        None => (
+            beta_version.clone().unwrap_or_else(|| DEFAULT_VERSION.to_string()),
+            None,
+            "initial",
+            "first stable release".to_string(),
+        ),
+    };
+
+    let updated = super::frontmatter_migration::set_frontmatter_fields(&beta_md, &[("version", &version)]);
+    if updated != beta_md {
+        std::fs::write(beta_dir.join("SKILL.md"), &updated).map_err(|e| format!("Failed to write SKILL.md: {}", e))?;
+    }
+    copy_deployable(&beta_dir, &stable_dir)?;
+    crate::db::set_skill_behaviour(conn, skill_name, None, Some(&version), None, None, None, None)?;
+
+    let promoted_at = chrono::Utc::now().to_rfc3339();
+    let manifest = SlotsManifest {
+        stable: Some(StableRecord {
+            version: version.clone(),
+            previous_version: previous_version.clone(),
+            promoted_at: promoted_at.clone(),
+        }),
+    };
+    write_manifest(skills_path, skill_name, &manifest)?;
+
+    let stable_rel = format!("{}/{}/{}/SKILL.md", SLOTS_DIR, skill_name, SLOT_STABLE);
+    let previous_sha = crate::git::last_committed_file(skills_path, &stable_rel)
+        .ok()
+        .flatten()
+        .map(|(sha, _)| sha);
+    let entry = SkillChangelogEntry {
+        skill_name: skill_name.to_string(),
+        version,
+        previous_version,
+        bump: bump.to_string(),
+        summary: format!("promoted beta to stable: {}", summary),
+        previous_sha,
+        created_at: promoted_at,
+    };
+    crate::db::insert_skill_changelog_entry(conn, &entry)?;
+
+    let msg = format!("{}: promote beta to stable (v{})", skill_name, entry.version);
+    if let Err(e) = crate::git::commit_all(skills_path, &msg) {
+        log::warn!("Git auto-commit failed ({}): {}", msg, e);
+    }
+    if installed_slot(claude_skills_dir, skill_name).as_deref() == Some(SLOT_STABLE) {
+        install_slot_inner(skills_path, claude_skills_dir, skill_name, SLOT_STABLE)?;
+    }
+    Ok(entry)
+}
+
+/// Drop a deleted skill's stable slot and packages. Best-effort: failures
+/// are logged.
+pub(crate) fn remove_slots(skills_path: &Path, skill_name: &str) {
+    let root = slots_root(skills_path, skill_name);
+    if root.exists() {
+        if let Err(e) = std::fs::remove_dir_all(&root) {
+            log::warn!("[delete_skill] Failed to remove slots of '{}': {}", skill_name, e);
+        }
+    }
+}
+
+/// Move a renamed skill's slots along with it, dropping packages built
+/// under the old name. Best-effort: failures are logged.
+pub(crate) fn rename_slots(skills_path: &Path, old_name: &str, new_name: &str) {
+    let old_root = slots_root(skills_path, old_name);
+    if !old_root.exists() {
+        return;
+    }
+    for slot in [SLOT_STABLE, SLOT_BETA] {
+        let _ = std::fs::remove_file(slot_package_path(skills_path, old_name, slot));
+    }
+    if let Err(e) = std::fs::rename(&old_root, slots_root(skills_path, new_name)) {
+        log::warn!("[rename_skill] Failed to move slots of '{}' to '{}': {}", old_name, new_name, e);
+    }
+}
+
+fn claude_skills_dir() -> Result<PathBuf, String> {
+    dirs::home_dir()
+        .map(|home| home.join(".claude").join("skills"))
+        .ok_or_else(|| "Could not determine home directory".to_string())
+}
+
+fn configured_skills_path(conn: &rusqlite::Connection) -> Result<PathBuf, String> {
+    crate::db::read_settings(conn)?
+        .skills_path
+        .map(PathBuf::from)
+        .ok_or_else(|| "Skills path not configured. Please set it in Settings.".to_string())
+}
+
+#[tauri::command]
+pub fn get_skill_slots(skill_name: String, db: tauri::State<'_, Db>) -> Result<SkillSlotsStatus, String> {
+    log::info!("[get_skill_slots] skill={}", skill_name);
+    validate_skill_name(&skill_name)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[get_skill_slots] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let skills_path = configured_skills_path(&conn)?;
+    Ok(slots_status_inner(&skills_path, &claude_skills_dir()?, &skill_name))
+}
+
+/// Package one slot to `.slots/{skill}/{skill}-{slot}.skill` after the same
+/// checks as `package_skill`.
+#[tauri::command]
+pub async fn package_skill_slot(
+    skill_name: String,
+    slot: String,
+    db: tauri::State<'_, Db>,
+) -> Result<PackageResult, String> {
+    log::info!("[package_skill_slot] skill={} slot={}", skill_name, slot);
+    super::observer::ensure_writable(&skill_name)?;
+    validate_skill_name(&skill_name)?;
+    let slot = parse_slot(&slot)?;
+    let skills_path = {
+        let conn = db.0.lock().map_err(|e| {
+            log::error!("[package_skill_slot] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        configured_skills_path(&conn)?
+    };
+    let source_dir = slot_dir(&skills_path, &skill_name, slot);
+    if !source_dir.join("SKILL.md").is_file() {
+        return Err(format!("The {} slot of '{}' has no SKILL.md", slot, skill_name));
+    }
+    std::fs::create_dir_all(slots_root(&skills_path, &skill_name)).map_err(|e| e.to_string())?;
+    let output_path = slot_package_path(&skills_path, &skill_name, slot);
+    super::workflow::package_skill_dir(
+        &db,
+        &skill_name,
+        source_dir,
+        output_path,
+        false,
+        None,
+        None,
+        super::package_formats::PackageFormat::GenericZip,
+    )
+    .await
+}
+
+/// Install `slot` into `~/.claude/skills` on this machine, switching from
+/// the other slot if it was installed.
+#[tauri::command]
+pub fn install_skill_slot(skill_name: String, slot: String, db: tauri::State<'_, Db>) -> Result<SkillSlotsStatus, String> {
+    log::info!("[install_skill_slot] skill={} slot={}", skill_name, slot);
+    super::observer::ensure_writable(&skill_name)?;
+    validate_skill_name(&skill_name)?;
+    let slot = parse_slot(&slot)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[install_skill_slot] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let skills_path = configured_skills_path(&conn)?;
+    let claude_dir = claude_skills_dir()?;
+    install_slot_inner(&skills_path, &claude_dir, &skill_name, slot).map_err(|e| {
+        log::error!("[install_skill_slot] {}", e);
+        e
+    })?;
+    Ok(slots_status_inner(&skills_path, &claude_dir, &skill_name))
+}
+
+#[tauri::command]
+pub fn uninstall_skill_slot(skill_name: String, db: tauri::State<'_, Db>) -> Result<SkillSlotsStatus, String> {
+    log::info!("[uninstall_skill_slot] skill={}", skill_name);
+    super::observer::ensure_writable(&skill_name)?;
+    validate_skill_name(&skill_name)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[uninstall_skill_slot] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let skills_path = configured_skills_path(&conn)?;
+    let claude_dir = claude_skills_dir()?;
+    uninstall_slot_inner(&claude_dir, &skill_name).map_err(|e| {
+        log::error!("[uninstall_skill_slot] {}", e);
+        e
+    })?;
+    Ok(slots_status_inner(&skills_path, &claude_dir, &skill_name))
+}
+
+/// Promote beta to stable: bump the version, record a changelog entry,
+/// update the slot manifest, commit, and refresh this machine's stable
+/// install if it has one.
+#[tauri::command]
+pub fn promote_beta_to_stable(skill_name: String, db: tauri::State<'_, Db>) -> Result<SkillChangelogEntry, String> {
+    log::info!("[promote_beta_to_stable] skill={}", skill_name);
+    super::observer::ensure_writable(&skill_name)?;
+    validate_skill_name(&skill_name)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[promote_beta_to_stable] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let skills_path = configured_skills_path(&conn)?;
+    promote_inner(&conn, &skills_path, &claude_skills_dir()?, &skill_name).map_err(|e| {
+        log::error!("[promote_beta_to_stable] {}", e);
+        e
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::create_test_db;
+    use tempfile::tempdir;
+
+    const SKILL: &str = "---\nname: sales\ndescription: Sales metrics\nversion: 1.0.0\n---\n# Sales\n\n## Metrics\nRevenue.\n";
+
+    fn write_beta(skills: &Path, content: &str) {
+        let dir = skills.join("sales");
+        std::fs::create_dir_all(dir.join("references")).unwrap();
+        std::fs::write(dir.join("SKILL.md"), content).unwrap();
+        std::fs::write(dir.join("references/glossary.md"), "ARR").unwrap();
+        std::fs::write(dir.join("sales.skill"), "zip").unwrap();
+    }
+
+    #[test]
+    fn test_promote_creates_then_bumps_stable() {
+        let conn = create_test_db();
+        crate::db::save_workflow_run(&conn, "sales", 3, "completed", "domain").unwrap();
+        let tmp = tempdir().unwrap();
+        let skills = tmp.path().join("skills");
+        let claude = tmp.path().join("claude-skills");
+        write_beta(&skills, SKILL);
+
+        let first = promote_inner(&conn, &skills, &claude, "sales").unwrap();
+        assert_eq!((first.version.as_str(), first.bump.as_str()), ("1.0.0", "initial"));
+        let stable = skills.join(".slots/sales/stable");
+        assert!(stable.join("references/glossary.md").is_file());
+        assert!(!stable.join("sales.skill").exists());
+        assert!(promote_inner(&conn, &skills, &claude, "sales").unwrap_err().contains("nothing to promote"));
+
+        write_beta(&skills, &format!("{}\n## Filters\nActive only.\n", SKILL));
+        let second = promote_inner(&conn, &skills, &claude, "sales").unwrap();
+        assert_eq!(second.version, "1.1.0");
+        assert_eq!(second.previous_version.as_deref(), Some("1.0.0"));
+        assert_eq!(second.bump, "minor");
+
+        let status = slots_status_inner(&skills, &claude, "sales");
+        assert_eq!(status.stable.as_ref().unwrap().version.as_deref(), Some("1.1.0"));
+        assert_eq!(status.beta.version.as_deref(), Some("1.1.0"));
+        assert_eq!(read_manifest(&skills, "sales").stable.unwrap().previous_version.as_deref(), Some("1.0.0"));
+        assert_eq!(crate::db::list_skill_changelog(&conn, "sales").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_install_switches_slots_and_protects_foreign_installs() {
+        let conn = create_test_db();
+        crate::db::save_workflow_run(&conn, "sales", 3, "completed", "domain").unwrap();
+        let tmp = tempdir().unwrap();
+        let skills = tmp.path().join("skills");
+        let claude = tmp.path().join("claude-skills");
+        write_beta(&skills, SKILL);
+        assert!(install_slot_inner(&skills, &claude, "sales", SLOT_STABLE).is_err());
+
+        promote_inner(&conn, &skills, &claude, "sales").unwrap();
+        install_slot_inner(&skills, &claude, "sales", SLOT_STABLE).unwrap();
+        assert_eq!(installed_slot(&claude, "sales").as_deref(), Some(SLOT_STABLE));
+
+        write_beta(&skills, &SKILL.replace("Revenue.", "Net revenue."));
+        install_slot_inner(&skills, &claude, "sales", SLOT_BETA).unwrap();
+        assert_eq!(installed_slot(&claude, "sales").as_deref(), Some(SLOT_BETA));
+        assert!(std::fs::read_to_string(claude.join("sales/SKILL.md")).unwrap().contains("Net revenue."));
+
+        uninstall_slot_inner(&claude, "sales").unwrap();
+        std::fs::create_dir_all(claude.join("sales")).unwrap();
+        assert!(install_slot_inner(&skills, &claude, "sales", SLOT_BETA).unwrap_err().contains("not installed by Skill Builder"));
+        assert!(uninstall_slot_inner(&claude, "sales").is_err());
+    }
+
+    #[test]
+    fn test_parse_slot_rejects_unknown() {
+        assert_eq!(parse_slot("beta").unwrap(), SLOT_BETA);
+        assert!(parse_slot("canary").is_err());
+    }
+}
@@ -0,0 +1,345 @@
+//! One-shot health check of everything Skill Builder depends on.
+//!
+//! `run_diagnostics` repeats the startup checks (Node.js, sidecar bundle, SDK
+//! CLI, git), then spawns and stops a throwaway sidecar, verifies the API key
+//! (or notes that offline mode uses a local model), runs SQLite's
+//! `PRAGMA integrity_check`, summarizes database lock contention, writes a
+//! probe file to the skills path and reads the scopes granted to the GitHub
+//! token. The report uses the same
+//! `DepStatus` rows as the startup check, and `create_github_issue` can attach
+//! it to a feedback issue as a markdown table.
+
+use std::path::Path;
+
+use super::node::{dep_fail, dep_ok};
+use crate::agents::sidecar_pool::SidecarPool;
+use crate::db::Db;
+use crate::types::{AppSettings, DbContention, DepStatus, DiagnosticsReport};
+
+/// Pool key of the sidecar spawned to test spawning; never a valid skill name.
+const DIAGNOSTICS_SIDECAR: &str = "__diagnostics__";
+const WRITE_PROBE_FILE: &str = ".skill-builder-write-probe";
+/// Scopes the GitHub device flow requests.
+const REQUIRED_GITHUB_SCOPES: &[&str] = &["repo", "read:user"];
+
+async fn check_sidecar_spawn(app: &tauri::AppHandle, pool: &SidecarPool) -> DepStatus {
+    let started = std::time::Instant::now();
+    let spawned = pool.get_or_spawn(DIAGNOSTICS_SIDECAR, app).await;
+    let elapsed = started.elapsed().as_millis();
+    if let Err(e) = pool.shutdown_skill(DIAGNOSTICS_SIDECAR, app).await {
+        log::warn!("[run_diagnostics] Failed to stop diagnostics sidecar: {}", e);
+    }
+    match spawned {
+        Ok(()) => dep_ok("sidecar_spawn", "Sidecar spawn", format!("Ready in {} ms", elapsed)),
+        Err(e) => dep_fail(
+            "sidecar_spawn",
+            "startup_failure",
+            "Sidecar spawn",
+            e,
+            "Check the Node.js and sidecar rows above, then see the app log for the sidecar's stderr.",
+        ),
+    }
+}
+
+async fn check_api_key(settings: &AppSettings) -> DepStatus {
+    if settings.offline_mode {
+        return match super::offline::local_target(settings) {
+            Some(target) => dep_ok(
+                "api_key",
+                "API key",
+                format!("Offline mode: {} at {}", target.model, target.base_url),
+            ),
+            None => dep_fail(
+                "api_key",
+                "missing_configuration",
+                "API key",
+                "Offline mode is on but no local model endpoint or model is set".to_string(),
+                "Set the local model endpoint and model in Settings.",
+            ),
+        };
+    }
+    let Some(key) = settings.anthropic_api_key.clone() else {
+        return dep_fail(
+            "api_key",
+            "missing_configuration",
+            "API key",
+            "No Anthropic API key configured".to_string(),
+            "Add your Anthropic API key in Settings.",
+        );
+    };
+    match super::settings::test_api_key(key).await {
+        Ok(_) => dep_ok("api_key", "API key", "Accepted by the Anthropic API".to_string()),
+        Err(e) if e.starts_with("Invalid") || e.contains("disabled") => dep_fail(
+            "api_key",
+            "invalid_credentials",
+            "API key",
+            e,
+            "Create a new key in the Anthropic Console and update it in Settings.",
+        ),
+        Err(e) => dep_fail(
+            "api_key",
+            "transient",
+            "API key",
+            format!("Could not reach the Anthropic API: {}", e),
+            "Check your network connection or proxy, then run diagnostics again.",
+        ),
+    }
+}
+
+fn check_db_integrity(conn: &rusqlite::Connection) -> DepStatus {
+    let rows = conn.prepare("PRAGMA integrity_check").and_then(|mut stmt| {
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        rows.collect::<Result<Vec<_>, _>>()
+    });
+    match rows {
+        Ok(rows) if rows == ["ok"] => dep_ok("db_integrity", "Database", "integrity_check: ok".to_string()),
+        Ok(rows) => dep_fail(
+            "db_integrity",
+            "corruption",
+            "Database",
+            rows.join("; "),
+            "Restore the database from a backup, or attach this report to an issue.",
+        ),
+        Err(e) => dep_fail(
+            "db_integrity",
+            "corruption",
+            "Database",
+            format!("integrity_check failed: {}", e),
+            "Restore the database from a backup, or attach this report to an issue.",
+        ),
+    }
+}
+
+/// Informational: contention never fails the report, but slow waits point at
+/// a command holding the writer too long (the log names its call site).
+fn check_db_contention(stats: &DbContention) -> DepStatus {
+    dep_ok(
+        "db_contention",
+        "Database locking",
+        format!(
+            "{} read connection(s); writer: {} slow wait(s), max wait {} ms, max hold {} ms; {} read(s) fell back to the writer",
+            stats.read_connections,
+            stats.writer.slow_waits,
+            stats.writer.max_wait_ms,
+            stats.writer.max_hold_ms,
+            stats.reader_fallbacks
+        ),
+    )
+}
+
+fn check_skills_path_writable(skills_path: Option<&str>) -> DepStatus {
+    let Some(skills_path) = skills_path else {
+        return dep_fail(
+            "skills_path_writable",
+            "missing_configuration",
+            "Skills path",
+            "Skills path not configured".to_string(),
+            "Choose a skills folder in Settings.",
+        );
+    };
+    let probe = Path::new(skills_path).join(WRITE_PROBE_FILE);
+    let written = std::fs::write(&probe, b"probe").and_then(|_| std::fs::remove_file(&probe));
+    match written {
+        Ok(()) => dep_ok("skills_path_writable", "Skills path", format!("{} is writable", skills_path)),
+        Err(e) => dep_fail(
+            "skills_path_writable",
+            "permissions",
+            "Skills path",
+            format!("Cannot write to {}: {}", skills_path, e),
+            "Fix the folder's permissions or choose another skills folder in Settings.",
+        ),
+    }
+}
+
+/// Required scopes missing from a GitHub `X-OAuth-Scopes` header value.
+fn missing_github_scopes(header: &str) -> Vec<&'static str> {
+    let granted: Vec<&str> = header.split(',').map(str::trim).collect();
+    REQUIRED_GITHUB_SCOPES
+        .iter()
+        .copied()
+        .filter(|s| !granted.contains(s))
+        .collect()
+}
+
+async fn check_github_token(settings: &AppSettings) -> DepStatus {
+    if !super::offline::feature_enabled(settings, super::offline::NetworkFeature::GitHub) {
+        return dep_ok("github_token", "GitHub", "GitHub features are turned off".to_string());
+    }
+    let Some(token) = settings.github_oauth_token.as_deref() else {
+        return dep_ok("github_token", "GitHub", "Not signed in".to_string());
+    };
+    let github = crate::github::GitHubServer::from_settings(settings).and_then(|server| {
+        let client = server.client_builder()?.build().map_err(|e| e.to_string())?;
+        Ok((server, client))
+    });
+    let (server, client) = match github {
+        Ok(github) => github,
+        Err(e) => {
+            return dep_fail(
+                "github_token",
+                "missing_configuration",
+                "GitHub",
+                e,
+                "Fix the GitHub Enterprise Server settings in Settings.",
+            )
+        }
+    };
+    let response = client
+        .get(format!("{}/user", server.api_base))
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", "SkillBuilder")
+        .header("X-GitHub-Api-Version", "2022-11-28")
+        .send()
+        .await;
+    let response = match response {
+        Ok(r) => r,
+        Err(e) => {
+            return dep_fail(
+                "github_token",
+                "transient",
+                "GitHub",
+                format!("Could not reach GitHub: {}", e),
+                "Check your network connection or proxy, then run diagnostics again.",
+            )
+        }
+    };
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        return dep_fail(
+            "github_token",
+            "invalid_credentials",
+            "GitHub",
+            "GitHub rejected the saved token".to_string(),
+            "Sign out of GitHub in Settings and sign in again.",
+        );
+    }
+    let scopes = response
+        .headers()
+        .get("x-oauth-scopes")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    let missing = missing_github_scopes(&scopes);
+    if missing.is_empty() {
+        dep_ok("github_token", "GitHub", format!("Token scopes: {}", scopes))
+    } else {
+        dep_fail(
+            "github_token",
+            "permissions",
+            "GitHub",
+            format!("Token scopes: {} (missing {})", scopes, missing.join(", ")),
+            "Sign out of GitHub in Settings and sign in again to grant the missing scopes.",
+        )
+    }
+}
+
+/// Run every health check and return the report.
+#[tauri::command]
+pub async fn run_diagnostics(
+    app: tauri::AppHandle,
+    pool: tauri::State<'_, SidecarPool>,
+    db: tauri::State<'_, Db>,
+) -> Result<DiagnosticsReport, String> {
+    log::info!("[run_diagnostics]");
+    let (settings, db_check) = {
+        let conn = db.0.lock().map_err(|e| {
+            log::error!("[run_diagnostics] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        (crate::db::read_settings_hydrated(&conn)?, check_db_integrity(&conn))
+    };
+
+    let mut checks = super::node::startup_checks(&app).await;
+    checks.push(check_sidecar_spawn(&app, &pool).await);
+    checks.push(check_api_key(&settings).await);
+    checks.push(db_check);
+    checks.push(check_db_contention(&db.0.contention()));
+    checks.push(check_skills_path_writable(settings.skills_path.as_deref()));
+    checks.push(check_github_token(&settings).await);
+
+    for check in checks.iter().filter(|c| !c.ok) {
+        log::warn!("[run_diagnostics] {} failed: {}", check.name, check.detail);
+    }
+    Ok(DiagnosticsReport {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH),
+        all_ok: checks.iter().all(|c| c.ok),
+        checks,
+    })
+}
+
+/// Database connection waits and holds since startup.
+#[tauri::command]
+pub fn get_db_contention(db: tauri::State<'_, Db>) -> DbContention {
+    log::info!("[get_db_contention]");
+    db.0.contention()
+}
+
+/// The report as a collapsible markdown section for an issue body.
+pub(crate) fn render_markdown(report: &DiagnosticsReport) -> String {
+    let passed = report.checks.iter().filter(|c| c.ok).count();
+    let cell = |s: &str| s.replace('|', "\\|").replace('\n', " ");
+    let mut out = format!(
+        "<details>\n<summary>Diagnostics: {} of {} checks passed</summary>\n\n\
+         Skill Builder {} on {}, {}\n\n| Check | Status | Detail |\n|---|---|---|\n",
+        passed,
+        report.checks.len(),
+        report.app_version,
+        report.os,
+        report.generated_at
+    );
+    for check in &report.checks {
+        out.push_str(&format!(
+            "| {} | {} | {} |\n",
+            cell(&check.name),
+            if check.ok { "ok" } else { "FAIL" },
+            cell(&check.detail)
+        ));
+    }
+    out.push_str("\n</details>");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_db_integrity_and_skills_path_checks() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        assert!(check_db_integrity(&conn).ok);
+        let contention = check_db_contention(&DbContention::default());
+        assert!(contention.ok);
+        assert!(contention.detail.starts_with("0 read connection(s)"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let writable = check_skills_path_writable(dir.path().to_str());
+        assert!(writable.ok);
+        assert!(!dir.path().join(WRITE_PROBE_FILE).exists());
+        assert!(!check_skills_path_writable(None).ok);
+        let missing = dir.path().join("missing");
+        assert!(!check_skills_path_writable(missing.to_str()).ok);
+    }
+
+    #[test]
+    fn test_missing_github_scopes_and_markdown() {
+        assert!(missing_github_scopes("repo, read:user, gist").is_empty());
+        assert_eq!(missing_github_scopes("public_repo"), vec!["repo", "read:user"]);
+
+        let report = DiagnosticsReport {
+            generated_at: "2026-10-16T00:00:00Z".to_string(),
+            app_version: "1.0.0".to_string(),
+            os: "macos-aarch64".to_string(),
+            all_ok: false,
+            checks: vec![
+                dep_ok("git_binary", "Git", "git version 2.44".to_string()),
+                dep_fail("api_key", "transient", "API key", "timeout | retry".to_string(), "Retry."),
+            ],
+        };
+        let md = render_markdown(&report);
+        assert!(md.contains("1 of 2 checks passed"));
+        assert!(md.contains("| Git | ok | git version 2.44 |"));
+        assert!(md.contains("| API key | FAIL | timeout \\| retry |"));
+    }
+}
@@ -0,0 +1,296 @@
+//! Maps command error strings to bundled help content.
+//!
+//! Commands keep returning `Result<T, String>`; the frontend passes a failed
+//! command's error to `get_error_help` and renders the remediation steps and
+//! settings link alongside the raw message.
+
+/// Structured help for a command error.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ErrorHelp {
+    /// Stable machine-readable code (`"unknown"` when nothing matched).
+    pub code: String,
+    pub title: String,
+    /// The original error string, unchanged.
+    pub message: String,
+    /// Ordered remediation steps.
+    pub steps: Vec<String>,
+    /// Settings section to deep-link to (`/settings?tab=…`), when one applies.
+    pub settings_tab: Option<String>,
+}
+
+struct HelpEntry {
+    code: &'static str,
+    title: &'static str,
+    /// Lowercase substrings; the first entry with any match wins.
+    patterns: &'static [&'static str],
+    steps: &'static [&'static str],
+    settings_tab: Option<&'static str>,
+}
+
+/// Bundled help content. Order matters: more specific entries come first.
+const HELP_ENTRIES: &[HelpEntry] = &[
+    HelpEntry {
+        code: "import_conflict",
+        title: "A skill with this name already exists",
+        patterns: &["conflict_overwrite_required:"],
+        steps: &[
+            "Choose Overwrite to replace the installed skill with the imported one.",
+            "Or rename the skill in the package before importing it again.",
+        ],
+        settings_tab: Some("skills"),
+    },
+    HelpEntry {
+        code: "package_tampered",
+        title: "Package was modified after it was signed",
+        patterns: &["signature_invalid:"],
+        steps: &[
+            "Do not import this package — its contents no longer match the author's signature.",
+            "Ask the author to export and sign the skill again, and fetch a fresh copy.",
+        ],
+        settings_tab: None,
+    },
+    HelpEntry {
+        code: "package_untrusted",
+        title: "Package is not signed by a trusted key",
+        patterns: &["signature_untrusted:"],
+        steps: &[
+            "Ask the author for their public signing key and add it to your trusted keys.",
+            "Or turn off \"Only import packages signed by a trusted key\" in Settings > Skills.",
+        ],
+        settings_tab: Some("skills"),
+    },
+    HelpEntry {
+        code: "import_scan_flagged",
+        title: "Skill was flagged by the import scan",
+        patterns: &["scan_flagged:"],
+        steps: &[
+            "The skill's text tries to override the agent's instructions, send data to an outside URL or hide commands.",
+            "Only import it if you trust its author; ask them for a copy without the flagged content.",
+        ],
+        settings_tab: None,
+    },
+    HelpEntry {
+        code: "skill_locked",
+        title: "Skill is open in another window",
+        patterns: &["is being edited in another instance"],
+        steps: &[
+            "Switch to the other Skill Builder window and close the skill there.",
+            "If that window has crashed or was force-quit, restart Skill Builder — locks held by dead processes are released automatically.",
+        ],
+        settings_tab: None,
+    },
+    HelpEntry {
+        code: "api_key_missing",
+        title: "Anthropic API key is not set",
+        patterns: &["api key not configured"],
+        steps: &[
+            "Open Settings > General and paste your Anthropic API key.",
+            "Click Test to confirm the key works, then retry.",
+        ],
+        settings_tab: Some("general"),
+    },
+    HelpEntry {
+        code: "api_key_invalid",
+        title: "Anthropic API key was rejected",
+        patterns: &["anthropic api error (401", "invalid x-api-key", "authentication_error"],
+        steps: &[
+            "Open Settings > General and check the API key for typos or extra whitespace.",
+            "Confirm the key has not been revoked in the Anthropic Console.",
+        ],
+        settings_tab: Some("general"),
+    },
+    HelpEntry {
+        code: "skills_path_missing",
+        title: "Skills folder is not set",
+        patterns: &["skills path not configured"],
+        steps: &["Open Settings > Advanced and choose a Skills Folder."],
+        settings_tab: Some("advanced"),
+    },
+    HelpEntry {
+        code: "scope_too_broad",
+        title: "Skill scope is too broad",
+        patterns: &["scope is too broad"],
+        steps: &[
+            "Review the scope recommendations in the research step's clarifications.",
+            "Reset to step 0 and describe a narrower focus for the skill.",
+        ],
+        settings_tab: None,
+    },
+    HelpEntry {
+        code: "decisions_contradictory",
+        title: "Decisions contain contradictions",
+        patterns: &["unresolvable contradictions in decisions"],
+        steps: &[
+            "Reset to step 2 (Confirm Decisions).",
+            "Revise the clarification answers the reasoning agent flagged, then rerun the step.",
+        ],
+        settings_tab: None,
+    },
+    HelpEntry {
+        code: "decisions_missing",
+        title: "Decisions have not been generated yet",
+        patterns: &["failed to read decisions from", "decisions.json content cannot be empty"],
+        steps: &[
+            "Run step 2 (Confirm Decisions) to generate decisions before continuing.",
+            "If step 2 already completed, reset to step 2 and rerun it — the decisions file is missing or empty.",
+        ],
+        settings_tab: None,
+    },
+    HelpEntry {
+        code: "budget_exceeded",
+        title: "Agent ran out of turns or budget",
+        patterns: &["error_max_turns", "error_max_budget", "budget exceeded", "maximum number of turns"],
+        steps: &[
+            "Retry the step — partial progress is often enough for the next attempt to finish.",
+            "Lower Reasoning effort or disable extended thinking in Settings > Skill Building to use fewer tokens per turn.",
+            "Split the request into smaller pieces (e.g. refine one file at a time).",
+        ],
+        settings_tab: Some("skill-building"),
+    },
+    HelpEntry {
+        code: "github_not_authenticated",
+        title: "GitHub sign-in required",
+        patterns: &["bad credentials", "github api error (401", "requires authentication"],
+        steps: &[
+            "Open Settings > GitHub and sign in (or sign out and back in to refresh the token).",
+            "For private repositories, make sure your account has access.",
+        ],
+        settings_tab: Some("github"),
+    },
+    HelpEntry {
+        code: "github_rate_limited",
+        title: "GitHub rate limit reached",
+        patterns: &["rate limit"],
+        steps: &[
+            "Sign in under Settings > GitHub — authenticated requests get a much higher limit.",
+            "Otherwise wait a few minutes and retry.",
+        ],
+        settings_tab: Some("github"),
+    },
+    HelpEntry {
+        code: "team_repo_missing",
+        title: "Team repository is not set",
+        patterns: &["no team repository configured"],
+        steps: &["Set the team repository (owner/repo) in Settings, then retry."],
+        settings_tab: Some("github"),
+    },
+    HelpEntry {
+        code: "marketplace_manifest_missing",
+        title: "Repository is not a skill marketplace",
+        patterns: &["marketplace.json not found"],
+        steps: &[
+            "Check the registry URL in Settings > Marketplace points at the right repository and branch.",
+            "The repository needs a .claude-plugin/marketplace.json file listing its skills.",
+        ],
+        settings_tab: Some("marketplace"),
+    },
+    HelpEntry {
+        code: "network",
+        title: "Network request failed",
+        patterns: &[
+            "error sending request",
+            "connection refused",
+            "dns error",
+            "timed out",
+            "failed to fetch",
+        ],
+        steps: &[
+            "Check your internet connection and any proxy or VPN settings.",
+            "Retry in a moment — the service may be temporarily unavailable.",
+        ],
+        settings_tab: None,
+    },
+];
+
+const FALLBACK_STEPS: &[&str] = &[
+    "Retry the action.",
+    "If it keeps failing, check the app logs for details (Settings > Log File).",
+];
+
+/// Look up help for an error string. Always returns something: unmatched
+/// errors get the generic `"unknown"` entry.
+pub fn help_for_error(error: &str) -> ErrorHelp {
+    let lower = error.to_lowercase();
+    let entry = HELP_ENTRIES
+        .iter()
+        .find(|e| e.patterns.iter().any(|p| lower.contains(p)));
+
+    match entry {
+        Some(e) => ErrorHelp {
+            code: e.code.to_string(),
+            title: e.title.to_string(),
+            message: error.to_string(),
+            steps: e.steps.iter().map(|s| s.to_string()).collect(),
+            settings_tab: e.settings_tab.map(|s| s.to_string()),
+        },
+        None => ErrorHelp {
+            code: "unknown".to_string(),
+            title: "Something went wrong".to_string(),
+            message: error.to_string(),
+            steps: FALLBACK_STEPS.iter().map(|s| s.to_string()).collect(),
+            settings_tab: None,
+        },
+    }
+}
+
+/// Return structured help (code, remediation steps, settings link) for a
+/// command error string.
+#[tauri::command]
+pub fn get_error_help(error: String) -> ErrorHelp {
+    let help = help_for_error(&error);
+    log::info!("[get_error_help] code={}", help.code);
+    help
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_errors_map_to_codes() {
+        let cases = [
+            ("Skill 'my-skill' is being edited in another instance", "skill_locked"),
+            ("Anthropic API key not configured", "api_key_missing"),
+            ("conflict_overwrite_required:my-skill", "import_conflict"),
+            ("scan_flagged:my-skill: Flagged by import scan: hidden-text", "import_scan_flagged"),
+            (
+                "Failed to read decisions from '/ws/s/context/decisions.json': No such file",
+                "decisions_missing",
+            ),
+            ("Agent finished with error: subtype=error_max_turns", "budget_exceeded"),
+            ("GitHub API error (403 Forbidden): API rate limit exceeded", "github_rate_limited"),
+            ("Skills path not configured. Please set it in Settings.", "skills_path_missing"),
+        ];
+        for (error, code) in cases {
+            assert_eq!(help_for_error(error).code, code, "error: {error}");
+        }
+    }
+
+    #[test]
+    fn test_help_includes_settings_link_and_original_message() {
+        let help = help_for_error("API key not configured");
+        assert_eq!(help.settings_tab.as_deref(), Some("general"));
+        assert_eq!(help.message, "API key not configured");
+        assert!(!help.steps.is_empty());
+    }
+
+    #[test]
+    fn test_unmatched_error_falls_back_to_unknown() {
+        let help = help_for_error("something unexpected");
+        assert_eq!(help.code, "unknown");
+        assert!(help.settings_tab.is_none());
+        assert_eq!(help.steps.len(), FALLBACK_STEPS.len());
+    }
+
+    #[test]
+    fn test_help_entries_are_well_formed() {
+        let mut codes = std::collections::HashSet::new();
+        for entry in HELP_ENTRIES {
+            assert!(codes.insert(entry.code), "duplicate code {}", entry.code);
+            assert!(!entry.steps.is_empty(), "{} has no steps", entry.code);
+            for p in entry.patterns {
+                assert_eq!(*p, p.to_lowercase(), "{} pattern must be lowercase", entry.code);
+            }
+        }
+    }
+}
@@ -0,0 +1,396 @@
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use crate::commands::imported_skills::validate_skill_name;
+
+/// One line of an exported eval dataset.
+///
+/// `expected` is always written (as `null` when unknown) so the file loads
+/// cleanly into tools that require the column; graders fill it in.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct EvalRecord {
+    pub input: String,
+    pub expected: Option<String>,
+    pub actual: String,
+    pub metadata: serde_json::Value,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct EvalExportSummary {
+    pub output_path: String,
+    pub record_count: usize,
+    pub test_runs: usize,
+    pub refine_exchanges: usize,
+    pub skipped_transcripts: usize,
+}
+
+/// Where a transcript came from, derived from its file name.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TranscriptKind {
+    TestWith,
+    TestWithout,
+    Refine,
+}
+
+impl TranscriptKind {
+    /// Transcript files are named `{step_label}-{YYYY-MM-DDTHH-MM-SS}.jsonl`
+    /// (`.jsonl.zst` once compressed).
+    /// Test runs use the `test-with` / `test-without` labels; refine sessions
+    /// use `refine-{skill_name}`.
+    fn from_file_name(file_name: &str, skill_name: &str) -> Option<Self> {
+        let refine_prefix = format!("refine-{}-", skill_name);
+        if file_name.starts_with("test-with-") {
+            Some(Self::TestWith)
+        } else if file_name.starts_with("test-without-") {
+            Some(Self::TestWithout)
+        } else if file_name.starts_with(&refine_prefix) {
+            Some(Self::Refine)
+        } else {
+            None
+        }
+    }
+}
+
+/// The `YYYY-MM-DDTHH-MM-SS` suffix of a transcript file name.
+fn transcript_timestamp(file_name: &str) -> &str {
+    let stem = crate::transcripts::transcript_stem(file_name);
+    stem.get(stem.len().saturating_sub(19)..).unwrap_or(stem)
+}
+
+/// The parts of a transcript needed to build an eval record.
+#[derive(Debug, Default, PartialEq)]
+struct ParsedTranscript {
+    prompt: String,
+    output: String,
+    model: Option<String>,
+    is_error: bool,
+    num_turns: Option<u64>,
+    total_cost_usd: Option<f64>,
+}
+
+/// Extract the prompt (from the leading config line) and the final output
+/// (the last `result` message, falling back to the last assistant text block).
+/// Returns `None` when the transcript has no prompt or produced no output.
+fn parse_transcript<R: BufRead>(reader: R) -> Option<ParsedTranscript> {
+    let mut parsed = ParsedTranscript::default();
+    let mut last_assistant_text: Option<String> = None;
+    let mut result_text: Option<String> = None;
+
+    for line in reader.lines().map_while(Result::ok) {
+        let msg: serde_json::Value = match serde_json::from_str(&line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        match msg["type"].as_str() {
+            Some("config") => {
+                parsed.prompt = msg["config"]["prompt"].as_str().unwrap_or_default().to_string();
+                parsed.model = msg["config"]["model"].as_str().map(|s| s.to_string());
+            }
+            Some("assistant") => {
+                let text: Vec<&str> = msg["message"]["content"]
+                    .as_array()
+                    .map(|blocks| {
+                        blocks
+                            .iter()
+                            .filter(|b| b["type"] == "text")
+                            .filter_map(|b| b["text"].as_str())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                if !text.is_empty() {
+                    last_assistant_text = Some(text.join("\n"));
+                }
+            }
+            Some("result") => {
+                let subtype = msg["subtype"].as_str().unwrap_or("success");
+                parsed.is_error =
+                    msg["is_error"].as_bool().unwrap_or(false) || subtype.starts_with("error_");
+                parsed.num_turns = msg["num_turns"].as_u64();
+                parsed.total_cost_usd = msg["total_cost_usd"].as_f64();
+                result_text = msg["result"].as_str().map(|s| s.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    parsed.output = result_text
+        .filter(|s| !s.trim().is_empty())
+        .or(last_assistant_text)?;
+    if parsed.prompt.trim().is_empty() {
+        return None;
+    }
+    Some(parsed)
+}
+
+/// Build eval records from every test and refine transcript in `logs_dir`.
+/// With-skill test runs are paired with the baseline run that received the
+/// same prompt; the baseline output goes in `metadata.baseline_output`.
+/// Returns the records (oldest first) and the number of transcripts skipped.
+fn collect_eval_records(logs_dir: &Path, skill_name: &str) -> Result<(Vec<EvalRecord>, usize), String> {
+    if !logs_dir.is_dir() {
+        return Ok((Vec::new(), 0));
+    }
+
+    let mut files: Vec<(String, TranscriptKind)> = std::fs::read_dir(logs_dir)
+        .map_err(|e| format!("Failed to read transcript dir {}: {}", logs_dir.display(), e))?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !crate::transcripts::is_transcript(&name) {
+                return None;
+            }
+            TranscriptKind::from_file_name(&name, skill_name).map(|kind| (name, kind))
+        })
+        .collect();
+    // Order by the timestamp suffix so records come out chronologically
+    // regardless of label.
+    files.sort_by(|a, b| {
+        transcript_timestamp(&a.0)
+            .cmp(transcript_timestamp(&b.0))
+            .then_with(|| a.0.cmp(&b.0))
+    });
+
+    let mut skipped = 0usize;
+    let mut parsed: Vec<(String, TranscriptKind, ParsedTranscript)> = Vec::new();
+    for (name, kind) in files {
+        let reader = match crate::transcripts::open_transcript(&logs_dir.join(&name)) {
+            Ok(r) => r,
+            Err(e) => {
+                log::warn!("[export_eval_dataset] {}", e);
+                skipped += 1;
+                continue;
+            }
+        };
+        match parse_transcript(reader) {
+            Some(p) => parsed.push((name, kind, p)),
+            None => skipped += 1,
+        }
+    }
+
+    // Latest baseline output per prompt
+    let baselines: HashMap<&str, &str> = parsed
+        .iter()
+        .filter(|(_, kind, _)| *kind == TranscriptKind::TestWithout)
+        .map(|(_, _, p)| (p.prompt.as_str(), p.output.as_str()))
+        .collect();
+
+    let records = parsed
+        .iter()
+        .filter(|(_, kind, _)| *kind != TranscriptKind::TestWithout)
+        .map(|(name, kind, p)| {
+            let mut metadata = serde_json::json!({
+                "skill_name": skill_name,
+                "source": match kind {
+                    TranscriptKind::Refine => "refine",
+                    _ => "test",
+                },
+                "transcript": name,
+                "model": p.model,
+                "is_error": p.is_error,
+                "num_turns": p.num_turns,
+                "total_cost_usd": p.total_cost_usd,
+            });
+            if *kind == TranscriptKind::TestWith {
+                if let Some(baseline) = baselines.get(p.prompt.as_str()) {
+                    metadata["baseline_output"] = serde_json::Value::String(baseline.to_string());
+                }
+            }
+            EvalRecord {
+                input: p.prompt.clone(),
+                expected: None,
+                actual: p.output.clone(),
+                metadata,
+            }
+        })
+        .collect();
+
+    Ok((records, skipped))
+}
+
+/// Export recorded test runs and refine exchanges for a skill as an eval
+/// dataset (one JSON object per line with `input`, `expected`, `actual`, and
+/// `metadata`). Defaults to `{workspace}/{skill}/exports/eval-dataset.jsonl`.
+///
+/// Only transcripts still on disk are exported — transcripts older than today
+/// are pruned at startup.
+#[tauri::command]
+pub fn export_eval_dataset(
+    workspace_path: String,
+    skill_name: String,
+    output_path: Option<String>,
+) -> Result<EvalExportSummary, String> {
+    log::info!(
+        "[export_eval_dataset] skill={} output_path={:?}",
+        skill_name,
+        output_path
+    );
+    validate_skill_name(&skill_name)?;
+
+    let skill_dir = Path::new(&workspace_path).join(&skill_name);
+    let (records, skipped_transcripts) = collect_eval_records(&skill_dir.join("logs"), &skill_name)
+        .map_err(|e| {
+            log::error!("[export_eval_dataset] {}", e);
+            e
+        })?;
+    if records.is_empty() {
+        return Err(format!(
+            "No test runs or refine exchanges recorded for '{}'",
+            skill_name
+        ));
+    }
+
+    let exports_dir = skill_dir.join("exports");
+    let output: PathBuf = match output_path {
+        // Relative output paths land in the skill's exports folder
+        Some(p) => crate::path_input::check(&p, crate::path_input::PathKind::File, false, Some(&exports_dir))
+            .map(|c| PathBuf::from(c.path))
+            .map_err(|e| {
+                log::error!("[export_eval_dataset] Invalid output path: {}", e);
+                format!("Invalid output path: {}", e)
+            })?,
+        None => exports_dir.join("eval-dataset.jsonl"),
+    };
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            log::error!("[export_eval_dataset] Failed to create {}: {}", parent.display(), e);
+            format!("Failed to create export directory: {}", e)
+        })?;
+    }
+
+    let mut file = std::fs::File::create(&output).map_err(|e| {
+        log::error!("[export_eval_dataset] Failed to create {}: {}", output.display(), e);
+        format!("Failed to create {}: {}", output.display(), e)
+    })?;
+    for record in &records {
+        let line = serde_json::to_string(record).map_err(|e| e.to_string())?;
+        writeln!(file, "{}", line).map_err(|e| {
+            log::error!("[export_eval_dataset] Failed to write {}: {}", output.display(), e);
+            format!("Failed to write {}: {}", output.display(), e)
+        })?;
+    }
+
+    let refine_exchanges = records
+        .iter()
+        .filter(|r| r.metadata["source"] == "refine")
+        .count();
+    let summary = EvalExportSummary {
+        output_path: output.to_string_lossy().to_string(),
+        record_count: records.len(),
+        test_runs: records.len() - refine_exchanges,
+        refine_exchanges,
+        skipped_transcripts,
+    };
+    log::info!(
+        "[export_eval_dataset] wrote {} records ({} test, {} refine, {} skipped) to {}",
+        summary.record_count,
+        summary.test_runs,
+        summary.refine_exchanges,
+        summary.skipped_transcripts,
+        summary.output_path
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_transcript(dir: &Path, name: &str, prompt: &str, result: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        let lines = [
+            serde_json::json!({"type": "config", "config": {"prompt": prompt, "model": "sonnet", "apiKey": "[REDACTED]"}}),
+            serde_json::json!({"type": "assistant", "message": {"content": [{"type": "text", "text": "thinking out loud"}]}}),
+            serde_json::json!({"type": "result", "subtype": "success", "result": result, "num_turns": 3, "total_cost_usd": 0.05}),
+        ];
+        let body: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        std::fs::write(dir.join(name), body.join("\n")).unwrap();
+    }
+
+    #[test]
+    fn test_parse_transcript_prefers_result_over_assistant_text() {
+        let input = [
+            r#"{"type":"config","config":{"prompt":"Build a model","model":"opus"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"draft"}]}}"#,
+            "not json",
+            r#"{"type":"result","subtype":"error_max_turns","result":"final","num_turns":15}"#,
+        ]
+        .join("\n");
+        let parsed = parse_transcript(input.as_bytes()).unwrap();
+        assert_eq!(parsed.prompt, "Build a model");
+        assert_eq!(parsed.output, "final");
+        assert_eq!(parsed.model.as_deref(), Some("opus"));
+        assert!(parsed.is_error);
+        assert_eq!(parsed.num_turns, Some(15));
+    }
+
+    #[test]
+    fn test_parse_transcript_falls_back_to_last_assistant_text() {
+        let input = [
+            r#"{"type":"config","config":{"prompt":"Refine it"}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"first"}]}}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","name":"Edit"},{"type":"text","text":"done"}]}}"#,
+        ]
+        .join("\n");
+        let parsed = parse_transcript(input.as_bytes()).unwrap();
+        assert_eq!(parsed.output, "done");
+        assert!(!parsed.is_error);
+    }
+
+    #[test]
+    fn test_parse_transcript_without_output_is_skipped() {
+        let input = r#"{"type":"config","config":{"prompt":"Nothing happened"}}"#;
+        assert!(parse_transcript(input.as_bytes()).is_none());
+    }
+
+    #[test]
+    fn test_collect_pairs_test_runs_with_baseline_and_ignores_other_steps() {
+        let tmp = tempdir().unwrap();
+        let logs = tmp.path().join("logs");
+        write_transcript(&logs, "test-with-2026-01-01T10-00-00.jsonl", "task A", "with skill");
+        write_transcript(&logs, "test-without-2026-01-01T10-00-01.jsonl", "task A", "without skill");
+        write_transcript(&logs, "test-eval-2026-01-01T10-01-00.jsonl", "grade", "graded");
+        write_transcript(&logs, "refine-my-skill-2026-01-01T09-00-00.jsonl", "tighten wording", "edited");
+        write_transcript(&logs, "step5-2026-01-01T08-00-00.jsonl", "generate", "skill");
+
+        let (records, skipped) = collect_eval_records(&logs, "my-skill").unwrap();
+        assert_eq!(skipped, 0);
+        assert_eq!(records.len(), 2);
+
+        assert_eq!(records[0].metadata["source"], "refine");
+        assert_eq!(records[0].input, "tighten wording");
+        assert_eq!(records[0].actual, "edited");
+
+        assert_eq!(records[1].metadata["source"], "test");
+        assert_eq!(records[1].actual, "with skill");
+        assert_eq!(records[1].metadata["baseline_output"], "without skill");
+        assert!(records[1].expected.is_none());
+    }
+
+    #[test]
+    fn test_export_writes_jsonl_with_expected_column() {
+        let tmp = tempdir().unwrap();
+        let workspace = tmp.path().to_str().unwrap().to_string();
+        let logs = tmp.path().join("my-skill").join("logs");
+        write_transcript(&logs, "test-with-2026-01-01T10-00-00.jsonl", "task A", "with skill");
+
+        let summary = export_eval_dataset(workspace, "my-skill".into(), None).unwrap();
+        assert_eq!(summary.record_count, 1);
+        assert_eq!(summary.test_runs, 1);
+
+        let content = std::fs::read_to_string(&summary.output_path).unwrap();
+        let line: serde_json::Value = serde_json::from_str(content.lines().next().unwrap()).unwrap();
+        assert_eq!(line["input"], "task A");
+        assert!(line.get("expected").unwrap().is_null());
+        assert_eq!(line["actual"], "with skill");
+    }
+
+    #[test]
+    fn test_export_errors_when_nothing_recorded() {
+        let tmp = tempdir().unwrap();
+        let workspace = tmp.path().to_str().unwrap().to_string();
+        let err = export_eval_dataset(workspace, "my-skill".into(), None).unwrap_err();
+        assert!(err.contains("No test runs"), "got: {err}");
+    }
+}
@@ -0,0 +1,204 @@
+//! One-click import of Anthropic's published example skills into the Skill Library.
+//!
+//! The examples repo is added as a marketplace registry so the existing
+//! update check tracks upstream changes. Each skill goes through the regular
+//! marketplace import with a metadata override, which rewrites its SKILL.md
+//! frontmatter into our key set and fills in a version where upstream has
+//! none. Imported skills are tagged `anthropic-example` so their provenance
+//! shows in the library. Skills already in the library are left untouched.
+
+use std::collections::{HashMap, HashSet};
+
+use super::github_import::MarketplaceImportResult;
+use crate::db::Db;
+use crate::types::{AvailableSkill, MarketplaceRegistry, SkillMetadataOverride};
+
+pub(crate) const EXAMPLES_SOURCE_URL: &str = "anthropics/skills";
+pub(crate) const EXAMPLES_REGISTRY_NAME: &str = "Anthropic Examples";
+pub(crate) const EXAMPLES_TAG: &str = "anthropic-example";
+
+/// Version stamped on examples whose SKILL.md does not declare one.
+const DEFAULT_EXAMPLE_VERSION: &str = "1.0.0";
+
+/// Add the examples registry, or re-enable it. Returns true if settings changed.
+pub(crate) fn ensure_examples_registry(registries: &mut Vec<MarketplaceRegistry>) -> bool {
+    if let Some(existing) = registries.iter_mut().find(|r| r.source_url == EXAMPLES_SOURCE_URL) {
+        if existing.enabled {
+            return false;
+        }
+        existing.enabled = true;
+        return true;
+    }
+    registries.push(MarketplaceRegistry {
+        name: EXAMPLES_REGISTRY_NAME.to_string(),
+        source_url: EXAMPLES_SOURCE_URL.to_string(),
+        enabled: true,
+    });
+    true
+}
+
+/// Frontmatter for an example in our conventions: single-line description and
+/// an explicit version. Keys we don't track (e.g. `license`) are dropped by the
+/// rewrite; the license files themselves are copied with the skill.
+pub(crate) fn normalized_override(skill: &AvailableSkill) -> SkillMetadataOverride {
+    SkillMetadataOverride {
+        name: Some(skill.name.clone()),
+        description: skill
+            .description
+            .as_deref()
+            .map(|d| d.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|d| !d.is_empty()),
+        purpose: None,
+        version: Some(
+            skill
+                .version
+                .clone()
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_EXAMPLE_VERSION.to_string()),
+        ),
+        model: None,
+        argument_hint: None,
+        user_invocable: None,
+        disable_model_invocation: None,
+    }
+}
+
+/// Examples not yet in the library, one per skill name, in catalog order.
+pub(crate) fn select_new_examples(catalog: Vec<AvailableSkill>, existing: &HashSet<String>) -> Vec<AvailableSkill> {
+    let mut seen = HashSet::new();
+    catalog
+        .into_iter()
+        .filter(|s| !existing.contains(&s.name) && seen.insert(s.name.clone()))
+        .collect()
+}
+
+#[tauri::command]
+pub async fn import_example_skills(db: tauri::State<'_, Db>) -> Result<Vec<MarketplaceImportResult>, String> {
+    log::info!("[import_example_skills] source={}", EXAMPLES_SOURCE_URL);
+    super::offline::require_feature(&db, super::offline::NetworkFeature::Marketplace, "import_example_skills")?;
+
+    let (server, token, existing) = {
+        let conn = db.0.lock().map_err(|e| {
+            log::error!("[import_example_skills] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        let mut settings = crate::db::read_settings(&conn)?;
+        if ensure_examples_registry(&mut settings.marketplace_registries) {
+            crate::db::write_settings(&conn, &settings)?;
+            log::info!("[import_example_skills] registered '{}' for update tracking", EXAMPLES_SOURCE_URL);
+        }
+        let server = crate::github::GitHubServer::from_settings(&settings)?;
+        let token = crate::db::read_settings_hydrated(&conn)?.github_oauth_token;
+        let existing: HashSet<String> = crate::db::get_dashboard_skill_names(&conn)?.into_iter().collect();
+        (server, token, existing)
+    };
+
+    let repo_info = super::github_import::parse_github_url_inner(EXAMPLES_SOURCE_URL)?;
+    let (_, catalog) = super::github_import::list_github_skills_inner(
+        &server,
+        &repo_info.owner,
+        &repo_info.repo,
+        &repo_info.branch,
+        repo_info.subpath.as_deref(),
+        token.as_deref(),
+    )
+    .await
+    .map_err(|e| {
+        log::error!("[import_example_skills] failed to list examples: {}", e);
+        e
+    })?;
+
+    let examples = select_new_examples(catalog, &existing);
+    if examples.is_empty() {
+        log::info!("[import_example_skills] all examples already in the library");
+        return Ok(Vec::new());
+    }
+    let paths: Vec<String> = examples.iter().map(|s| s.path.clone()).collect();
+    let overrides: HashMap<String, SkillMetadataOverride> =
+        examples.iter().map(|s| (s.path.clone(), normalized_override(s))).collect();
+
+    let results =
+        super::github_import::import_marketplace_to_library_inner(&db, EXAMPLES_SOURCE_URL, &paths, Some(&overrides))
+            .await?;
+
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[import_example_skills] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    for result in results.iter().filter(|r| r.success) {
+        let mut tags = crate::db::get_tags_for_skills(&conn, std::slice::from_ref(&result.skill_name))?
+            .remove(&result.skill_name)
+            .unwrap_or_default();
+        if !tags.iter().any(|t| t == EXAMPLES_TAG) {
+            tags.push(EXAMPLES_TAG.to_string());
+        }
+        if let Err(e) = crate::db::set_skill_tags(&conn, &result.skill_name, &tags) {
+            log::warn!("[import_example_skills] failed to tag '{}': {}", result.skill_name, e);
+        }
+    }
+    log::info!(
+        "[import_example_skills] imported {}/{} examples",
+        results.iter().filter(|r| r.success).count(),
+        results.len()
+    );
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn available(name: &str, path: &str, version: Option<&str>) -> AvailableSkill {
+        AvailableSkill {
+            path: path.to_string(),
+            name: name.to_string(),
+            plugin_name: None,
+            description: Some("  Create and edit\n  spreadsheets.  ".to_string()),
+            purpose: None,
+            version: version.map(str::to_string),
+            model: None,
+            argument_hint: None,
+            user_invocable: None,
+            disable_model_invocation: None,
+        }
+    }
+
+    #[test]
+    fn test_ensure_examples_registry_adds_once_and_reenables() {
+        let mut registries = Vec::new();
+        assert!(ensure_examples_registry(&mut registries));
+        assert!(!ensure_examples_registry(&mut registries));
+        assert_eq!(registries.len(), 1);
+        assert_eq!(registries[0].name, EXAMPLES_REGISTRY_NAME);
+
+        registries[0].enabled = false;
+        assert!(ensure_examples_registry(&mut registries));
+        assert!(registries[0].enabled);
+        assert_eq!(registries.len(), 1);
+    }
+
+    #[test]
+    fn test_normalized_override_fills_conventions() {
+        let ov = normalized_override(&available("xlsx", "skills/xlsx", None));
+        assert_eq!(ov.name.as_deref(), Some("xlsx"));
+        assert_eq!(ov.description.as_deref(), Some("Create and edit spreadsheets."));
+        assert_eq!(ov.version.as_deref(), Some(DEFAULT_EXAMPLE_VERSION));
+
+        let ov = normalized_override(&available("pdf", "skills/pdf", Some("2.1.0")));
+        assert_eq!(ov.version.as_deref(), Some("2.1.0"));
+    }
+
+    #[test]
+    fn test_select_new_examples_skips_existing_and_duplicates() {
+        let catalog = vec![
+            available("xlsx", "document-skills/xlsx", None),
+            available("pdf", "document-skills/pdf", None),
+            available("pdf", "skills/pdf", None),
+            available("mcp-builder", "skills/mcp-builder", None),
+        ];
+        let existing: HashSet<String> = ["mcp-builder".to_string()].into_iter().collect();
+        let picked = select_new_examples(catalog, &existing);
+        let paths: Vec<&str> = picked.iter().map(|s| s.path.as_str()).collect();
+        assert_eq!(paths, vec!["document-skills/xlsx", "document-skills/pdf"]);
+    }
+}
@@ -7,6 +7,15 @@ pub struct CreateGithubIssueRequest {
     pub title: String,
     pub body: String,
     pub labels: Vec<String>,
+    /// Screenshots and logs to scrub and attach.
+    #[serde(default)]
+    pub attachments: Vec<String>,
+    /// Encrypt the attachments to `feedback_public_key`.
+    #[serde(default)]
+    pub encrypt_attachments: bool,
+    /// A `run_diagnostics` report to append to the body.
+    #[serde(default)]
+    pub diagnostics: Option<crate::types::DiagnosticsReport>,
 }
 
 #[derive(Debug, Serialize)]
@@ -21,9 +30,14 @@ pub async fn create_github_issue(
     db: tauri::State<'_, crate::db::Db>,
     request: CreateGithubIssueRequest,
 ) -> Result<CreateGithubIssueResponse, String> {
-    log::info!("[create_github_issue] title={}", request.title);
-    // 1. Get GitHub OAuth token from settings
-    let github_token = {
+    log::info!(
+        "[create_github_issue] title={} attachments={}",
+        request.title,
+        request.attachments.len()
+    );
+    super::offline::require_feature(&db, super::offline::NetworkFeature::GitHub, "create_github_issue")?;
+    // 1. Get GitHub OAuth token and attachment settings
+    let (github_token, settings) = {
         let conn = db.0.lock().map_err(|e| {
             log::error!("[create_github_issue] Failed to acquire DB lock: {}", e);
             e.to_string()
@@ -32,19 +46,47 @@ pub async fn create_github_issue(
             log::error!("[create_github_issue] Failed to read settings: {}", e);
             e.to_string()
         })?;
-        settings.github_oauth_token.ok_or_else(|| {
+        let token = settings.github_oauth_token.clone().ok_or_else(|| {
             "Not signed in to GitHub. Sign in with GitHub in Settings.".to_string()
-        })?
+        })?;
+        (token, settings)
     };
 
+    // 2. Scrub attachments into a bundle carried in the issue body
+    let mut body = request.body.clone();
+    if let Some(report) = &request.diagnostics {
+        let section = super::diagnostics::render_markdown(report);
+        let (section, _) = super::feedback_bundle::redact_keywords(&section, &settings.feedback_redaction_keywords);
+        body = format!("{}\n\n{}", body, section);
+    }
+    if !request.attachments.is_empty() {
+        let public_key = if request.encrypt_attachments {
+            Some(settings.feedback_public_key.as_deref().ok_or_else(|| {
+                "No feedback public key is configured. Add one in Settings or send without encryption.".to_string()
+            })?)
+        } else {
+            None
+        };
+        let section = super::feedback_bundle::bundle_section(
+            &request.attachments,
+            &settings.feedback_redaction_keywords,
+            public_key,
+        )
+        .map_err(|e| {
+            log::error!("[create_github_issue] Failed to bundle attachments: {}", e);
+            e
+        })?;
+        body = format!("{}\n\n{}", body, section);
+    }
+
     let client = reqwest::Client::new();
 
-    // 2. Ensure labels exist (create if needed, best-effort)
+    // 3. Ensure labels exist (create if needed, best-effort)
     for label in &request.labels {
         ensure_label(&client, &github_token, label).await.ok();
     }
 
-    // 3. Create the issue
+    // 4. Create the issue
     let response = client
         .post(format!(
             "https://api.github.com/repos/{}/issues",
@@ -56,7 +98,7 @@ pub async fn create_github_issue(
         .header("X-GitHub-Api-Version", "2022-11-28")
         .json(&serde_json::json!({
             "title": request.title,
-            "body": request.body,
+            "body": body,
             "labels": request.labels,
         }))
         .send()
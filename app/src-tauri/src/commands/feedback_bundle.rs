@@ -0,0 +1,371 @@
+//! Attachments for feedback reports.
+//!
+//! Screenshots and logs attached to a report are scrubbed before they leave
+//! the machine: JPEG and PNG files lose their metadata blocks (EXIF, XMP,
+//! IPTC, comments, text chunks) and text files have the configured
+//! `feedback_redaction_keywords` replaced. Image content itself is not
+//! inspected, so text visible in a screenshot is sent as is.
+//!
+//! The scrubbed files are zipped into one bundle that travels inside the
+//! issue body. With encryption on, the bundle is sealed to the maintainers'
+//! X25519 `feedback_public_key`:
+//!
+//! `MAGIC || ephemeral public key (32) || nonce (12) || AES-256-GCM ciphertext`
+//!
+//! The AES key is HKDF-SHA256 over the X25519 shared secret, salted with the
+//! ephemeral and recipient public keys and with `HKDF_INFO` as info; `MAGIC`
+//! is the associated data.
+
+use std::io::{Cursor, Write};
+use std::path::Path;
+
+use base64::Engine;
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::{aead, agreement, hkdf};
+
+use crate::db::Db;
+use crate::types::ScrubbedAttachment;
+
+const MAGIC: &[u8] = b"SBFB1";
+const HKDF_INFO: &[u8] = b"skill-builder feedback bundle v1";
+/// Base64 bundle size that still fits in a GitHub issue body (65,536
+/// characters) next to the report itself.
+const MAX_INLINE_BUNDLE_CHARS: usize = 50_000;
+const REDACTED: &str = "[REDACTED]";
+const TEXT_EXTENSIONS: &[&str] = &["txt", "log", "md", "json", "jsonl", "yaml", "yml", "csv", "toml"];
+
+/// JPEG segments that carry metadata rather than image data.
+fn jpeg_metadata_name(marker: u8, data: &[u8]) -> Option<&'static str> {
+    match marker {
+        0xE1 if data.starts_with(b"Exif\0") => Some("EXIF"),
+        0xE1 => Some("XMP"),
+        0xEC => Some("APP12"),
+        0xED => Some("IPTC"),
+        0xFE => Some("comment"),
+        _ => None,
+    }
+}
+
+fn strip_jpeg(bytes: &[u8]) -> Result<(Vec<u8>, Vec<String>), String> {
+    let mut out = vec![0xFF, 0xD8];
+    let mut removed = Vec::new();
+    let mut i = 2;
+    while i + 4 <= bytes.len() {
+        if bytes[i] != 0xFF {
+            return Err("Malformed JPEG: expected a segment marker".to_string());
+        }
+        let marker = bytes[i + 1];
+        if marker == 0xFF {
+            i += 1; // fill byte
+            continue;
+        }
+        if marker == 0xDA {
+            // Start of scan: the rest is entropy-coded image data.
+            out.extend_from_slice(&bytes[i..]);
+            return Ok((out, removed));
+        }
+        let len = u16::from_be_bytes([bytes[i + 2], bytes[i + 3]]) as usize;
+        let end = i + 2 + len;
+        if len < 2 || end > bytes.len() {
+            return Err("Malformed JPEG: segment runs past the end of the file".to_string());
+        }
+        match jpeg_metadata_name(marker, &bytes[i + 4..end]) {
+            Some(name) => removed.push(name.to_string()),
+            None => out.extend_from_slice(&bytes[i..end]),
+        }
+        i = end;
+    }
+    Err("Malformed JPEG: no image data".to_string())
+}
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const PNG_METADATA_CHUNKS: &[&[u8]] = &[b"eXIf", b"tEXt", b"zTXt", b"iTXt", b"tIME"];
+
+fn strip_png(bytes: &[u8]) -> Result<(Vec<u8>, Vec<String>), String> {
+    let mut out = PNG_SIGNATURE.to_vec();
+    let mut removed = Vec::new();
+    let mut i = PNG_SIGNATURE.len();
+    while i + 12 <= bytes.len() {
+        let len = u32::from_be_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]) as usize;
+        let end = i + 12 + len;
+        if end > bytes.len() {
+            return Err("Malformed PNG: chunk runs past the end of the file".to_string());
+        }
+        let kind = &bytes[i + 4..i + 8];
+        if PNG_METADATA_CHUNKS.contains(&kind) {
+            removed.push(String::from_utf8_lossy(kind).to_string());
+        } else {
+            out.extend_from_slice(&bytes[i..end]);
+        }
+        i = end;
+        if kind == b"IEND" {
+            return Ok((out, removed));
+        }
+    }
+    Err("Malformed PNG: no IEND chunk".to_string())
+}
+
+/// Image bytes without metadata blocks, and the names of the blocks dropped.
+fn strip_image_metadata(bytes: &[u8]) -> Result<(Vec<u8>, Vec<String>), String> {
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        strip_jpeg(bytes)
+    } else if bytes.starts_with(PNG_SIGNATURE) {
+        strip_png(bytes)
+    } else {
+        Err("Only JPEG and PNG images can be attached".to_string())
+    }
+}
+
+/// `text` with every case-insensitive match of `keywords` replaced, and the
+/// number of replacements. Overlapping matches become one replacement.
+pub(crate) fn redact_keywords(text: &str, keywords: &[String]) -> (String, usize) {
+    // ASCII lowercasing keeps byte offsets, so matches index into `text` too.
+    let lower = text.to_ascii_lowercase();
+    let mut matches: Vec<(usize, usize)> = keywords
+        .iter()
+        .map(|k| k.trim().to_ascii_lowercase())
+        .filter(|k| !k.is_empty())
+        .flat_map(|k| {
+            lower
+                .match_indices(k.as_str())
+                .map(|(i, m)| (i, i + m.len()))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    matches.sort();
+
+    let mut redacted = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut count = 0;
+    for (start, end) in matches {
+        if start < last {
+            last = last.max(end);
+            continue;
+        }
+        redacted.push_str(&text[last..start]);
+        redacted.push_str(REDACTED);
+        last = end;
+        count += 1;
+    }
+    redacted.push_str(&text[last..]);
+    (redacted, count)
+}
+
+/// Read and scrub one attachment.
+fn scrub_file(path: &Path, keywords: &[String]) -> Result<(ScrubbedAttachment, Vec<u8>), String> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid attachment path: {}", path.display()))?;
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", name, e))?;
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let (kind, scrubbed, removed_metadata, redactions) = if TEXT_EXTENSIONS.contains(&extension.as_str()) {
+        let text = String::from_utf8(bytes.clone()).map_err(|_| format!("{} is not UTF-8 text", name))?;
+        let (redacted, count) = redact_keywords(&text, keywords);
+        ("text", redacted.into_bytes(), Vec::new(), count)
+    } else {
+        let (stripped, removed) = strip_image_metadata(&bytes).map_err(|e| format!("{}: {}", name, e))?;
+        ("image", stripped, removed, 0)
+    };
+    Ok((
+        ScrubbedAttachment {
+            name,
+            kind: kind.to_string(),
+            original_bytes: bytes.len() as u64,
+            scrubbed_bytes: scrubbed.len() as u64,
+            removed_metadata,
+            redactions,
+        },
+        scrubbed,
+    ))
+}
+
+/// Scrub `paths` and zip them into one bundle.
+fn build_bundle(paths: &[String], keywords: &[String]) -> Result<(Vec<u8>, Vec<ScrubbedAttachment>), String> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut summaries = Vec::new();
+    for (i, path) in paths.iter().enumerate() {
+        let (summary, bytes) = scrub_file(Path::new(path), keywords)?;
+        // Prefix with the position so two files with the same name both fit.
+        zip.start_file(format!("{:02}-{}", i + 1, summary.name), options)
+            .map_err(|e| format!("Failed to add {}: {}", summary.name, e))?;
+        zip.write_all(&bytes).map_err(|e| format!("Failed to add {}: {}", summary.name, e))?;
+        summaries.push(summary);
+    }
+    let bundle = zip.finish().map_err(|e| format!("Failed to write bundle: {}", e))?.into_inner();
+    Ok((bundle, summaries))
+}
+
+fn derive_key(shared_secret: &[u8], ephemeral_public: &[u8], recipient: &[u8]) -> Result<[u8; 32], ring::error::Unspecified> {
+    let salt = [ephemeral_public, recipient].concat();
+    let mut key = [0u8; 32];
+    hkdf::Salt::new(hkdf::HKDF_SHA256, &salt)
+        .extract(shared_secret)
+        .expand(&[HKDF_INFO], &aead::AES_256_GCM)?
+        .fill(&mut key)?;
+    Ok(key)
+}
+
+/// Seal `plain` to the base64 X25519 public key `recipient_b64`.
+fn encrypt_to(recipient_b64: &str, plain: &[u8]) -> Result<Vec<u8>, String> {
+    let recipient = base64::engine::general_purpose::STANDARD
+        .decode(recipient_b64.trim())
+        .map_err(|_| "Feedback public key is not valid base64".to_string())?;
+    if recipient.len() != 32 {
+        return Err("Feedback public key must be a 32-byte X25519 key".to_string());
+    }
+    let rng = SystemRandom::new();
+    let ephemeral = agreement::EphemeralPrivateKey::generate(&agreement::X25519, &rng)
+        .map_err(|_| "Failed to generate an encryption key".to_string())?;
+    let ephemeral_public = ephemeral
+        .compute_public_key()
+        .map_err(|_| "Failed to generate an encryption key".to_string())?;
+    let key = agreement::agree_ephemeral(
+        ephemeral,
+        &agreement::UnparsedPublicKey::new(&agreement::X25519, &recipient),
+        |shared| derive_key(shared, ephemeral_public.as_ref(), &recipient),
+    )
+    .map_err(|_| "Feedback public key was rejected".to_string())?
+    .map_err(|_| "Failed to derive the encryption key".to_string())?;
+
+    let mut nonce = [0u8; aead::NONCE_LEN];
+    rng.fill(&mut nonce).map_err(|_| "Failed to generate a nonce".to_string())?;
+    let sealing_key = aead::LessSafeKey::new(
+        aead::UnboundKey::new(&aead::AES_256_GCM, &key).map_err(|_| "Invalid encryption key".to_string())?,
+    );
+    let mut sealed = plain.to_vec();
+    sealing_key
+        .seal_in_place_append_tag(aead::Nonce::assume_unique_for_key(nonce), aead::Aad::from(MAGIC), &mut sealed)
+        .map_err(|_| "Failed to encrypt the bundle".to_string())?;
+
+    Ok([MAGIC, ephemeral_public.as_ref(), &nonce[..], &sealed[..]].concat())
+}
+
+/// Markdown section carrying the scrubbed (and, with `public_key`, encrypted)
+/// bundle of `paths`, for appending to an issue body.
+pub(crate) fn bundle_section(paths: &[String], keywords: &[String], public_key: Option<&str>) -> Result<String, String> {
+    let (bundle, summaries) = build_bundle(paths, keywords)?;
+    let (payload, how) = match public_key {
+        Some(key) => (encrypt_to(key, &bundle)?, "encrypted to the maintainers' feedback key"),
+        None => (bundle, "zip"),
+    };
+    let encoded = base64::engine::general_purpose::STANDARD.encode(&payload);
+    if encoded.len() > MAX_INLINE_BUNDLE_CHARS {
+        return Err(format!(
+            "Attachments are {} KB after scrubbing; a report holds about {} KB. Remove or crop some and try again.",
+            payload.len() / 1024,
+            MAX_INLINE_BUNDLE_CHARS * 3 / 4 / 1024
+        ));
+    }
+    let names: Vec<&str> = summaries.iter().map(|s| s.name.as_str()).collect();
+    Ok(format!(
+        "<details>\n<summary>Attachments ({}, {}): {}</summary>\n\n```\n{}\n```\n\n</details>",
+        summaries.len(),
+        how,
+        names.join(", "),
+        encoded
+    ))
+}
+
+/// Scrub feedback attachments without sending them, so the dialog can show
+/// what will be removed.
+#[tauri::command]
+pub fn scrub_feedback_attachments(paths: Vec<String>, db: tauri::State<'_, Db>) -> Result<Vec<ScrubbedAttachment>, String> {
+    log::info!("[scrub_feedback_attachments] {} files", paths.len());
+    let keywords = {
+        let conn = db.0.lock().map_err(|e| {
+            log::error!("[scrub_feedback_attachments] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        crate::db::read_settings(&conn)?.feedback_redaction_keywords
+    };
+    paths
+        .iter()
+        .map(|p| scrub_file(Path::new(p), &keywords).map(|(summary, _)| summary))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jpeg_segment(marker: u8, data: &[u8]) -> Vec<u8> {
+        let len = (data.len() + 2) as u16;
+        [&[0xFF, marker][..], &len.to_be_bytes()[..], data].concat()
+    }
+
+    fn png_chunk(kind: &[u8], data: &[u8]) -> Vec<u8> {
+        [&(data.len() as u32).to_be_bytes()[..], kind, data, &[0u8; 4][..]].concat()
+    }
+
+    #[test]
+    fn test_strip_jpeg_drops_exif_and_comments_only() {
+        let jfif = jpeg_segment(0xE0, b"JFIF\0\x01\x01");
+        let scan = [&jpeg_segment(0xDA, b"\x01\x01\x00")[..], &b"\x12\x34\xFF\xD9"[..]].concat();
+        let jpeg = [
+            &[0xFF, 0xD8][..],
+            &jfif[..],
+            &jpeg_segment(0xE1, b"Exif\0\0GPS")[..],
+            &jpeg_segment(0xFE, b"secret comment")[..],
+            &scan[..],
+        ]
+        .concat();
+
+        let (stripped, removed) = strip_image_metadata(&jpeg).unwrap();
+        assert_eq!(removed, vec!["EXIF", "comment"]);
+        assert_eq!(stripped, [&[0xFF, 0xD8][..], &jfif[..], &scan[..]].concat());
+    }
+
+    #[test]
+    fn test_strip_png_drops_text_chunks() {
+        let ihdr = png_chunk(b"IHDR", &[0; 13]);
+        let idat = png_chunk(b"IDAT", b"pixels");
+        let iend = png_chunk(b"IEND", b"");
+        let png = [PNG_SIGNATURE, &ihdr[..], &png_chunk(b"tEXt", b"Author\0me")[..], &idat[..], &iend[..]].concat();
+
+        let (stripped, removed) = strip_image_metadata(&png).unwrap();
+        assert_eq!(removed, vec!["tEXt"]);
+        assert_eq!(stripped, [PNG_SIGNATURE, &ihdr[..], &idat[..], &iend[..]].concat());
+        assert!(strip_image_metadata(b"GIF89a").is_err());
+    }
+
+    #[test]
+    fn test_redact_keywords_is_case_insensitive() {
+        let keywords = vec!["Acme".to_string(), " ".to_string(), "project x".to_string()];
+        let (text, count) = redact_keywords("ACME deploys Project X for acme.", &keywords);
+        assert_eq!(text, "[REDACTED] deploys [REDACTED] for [REDACTED].");
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn test_encrypted_bundle_opens_with_recipient_key() {
+        let rng = SystemRandom::new();
+        let recipient = agreement::EphemeralPrivateKey::generate(&agreement::X25519, &rng).unwrap();
+        let recipient_public = recipient.compute_public_key().unwrap();
+        let key_b64 = base64::engine::general_purpose::STANDARD.encode(recipient_public.as_ref());
+
+        let sealed = encrypt_to(&key_b64, b"bundle").unwrap();
+        assert!(sealed.starts_with(MAGIC));
+        let ephemeral_public = &sealed[MAGIC.len()..MAGIC.len() + 32];
+        let nonce: [u8; aead::NONCE_LEN] = sealed[MAGIC.len() + 32..MAGIC.len() + 44].try_into().unwrap();
+        let key = agreement::agree_ephemeral(
+            recipient,
+            &agreement::UnparsedPublicKey::new(&agreement::X25519, ephemeral_public),
+            |shared| derive_key(shared, ephemeral_public, recipient_public.as_ref()),
+        )
+        .unwrap()
+        .unwrap();
+        let opening_key = aead::LessSafeKey::new(aead::UnboundKey::new(&aead::AES_256_GCM, &key).unwrap());
+        let mut ciphertext = sealed[MAGIC.len() + 44..].to_vec();
+        let plain = opening_key
+            .open_in_place(aead::Nonce::assume_unique_for_key(nonce), aead::Aad::from(MAGIC), &mut ciphertext)
+            .unwrap();
+        assert_eq!(&plain[..], b"bundle");
+
+        assert!(encrypt_to("not a key", b"bundle").is_err());
+    }
+}
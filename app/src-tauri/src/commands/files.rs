@@ -177,6 +177,34 @@ fn get_workspace_root(db: &tauri::State<'_, Db>) -> Option<PathBuf> {
     fs::canonicalize(workspace).ok()
 }
 
+/// Apply the file rules when `target` lies inside a skill folder under the
+/// skills path; other writes are left to the allowed-roots check.
+fn check_file_rules(db: &Db, target: &Path, size: u64) -> Result<(), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let Some(skills_path) = crate::db::read_settings(&conn)?.skills_path else {
+        return Ok(());
+    };
+    let Some(relative) = fs::canonicalize(&skills_path)
+        .ok()
+        .and_then(|root| target.strip_prefix(root).ok().map(Path::to_path_buf))
+    else {
+        return Ok(());
+    };
+    let mut parts = relative.components().filter_map(|c| match c {
+        Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+        _ => None,
+    });
+    let Some(skill_name) = parts.next() else {
+        return Ok(());
+    };
+    let path_in_skill = parts.collect::<Vec<_>>().join("/");
+    if path_in_skill.is_empty() {
+        return Ok(());
+    }
+    let skill_type = crate::db::get_workflow_run(&conn, &skill_name)?.map(|run| run.purpose);
+    crate::fs_validation::FileRuleSet::load(&conn)?.check_file(&path_in_skill, size, skill_type.as_deref())
+}
+
 fn is_workspace_context_path(path: &Path, workspace_root: &Path) -> bool {
     if !path.starts_with(workspace_root) {
         return false;
@@ -296,6 +324,27 @@ fn read_file_as_base64_with_roots(
     Ok(base64::engine::general_purpose::STANDARD.encode(&bytes))
 }
 
+/// Normalize a user-entered path (quotes, `~`, separators, relative paths) and
+/// report whether it exists and is usable, with warnings for anything rewritten.
+#[tauri::command]
+pub fn check_path_input(
+    path: String,
+    kind: Option<crate::path_input::PathKind>,
+    must_exist: Option<bool>,
+) -> Result<crate::path_input::PathCheck, String> {
+    log::info!("[check_path_input] path={}", path);
+    crate::path_input::check(
+        &path,
+        kind.unwrap_or(crate::path_input::PathKind::Any),
+        must_exist.unwrap_or(false),
+        None,
+    )
+    .map_err(|e| {
+        log::warn!("[check_path_input] {}", e);
+        e
+    })
+}
+
 #[tauri::command]
 pub fn read_file(file_path: String, db: tauri::State<'_, Db>) -> Result<String, String> {
     log::info!("[read_file] path={}", file_path);
@@ -331,6 +380,12 @@ pub fn write_file(path: String, content: String, db: tauri::State<'_, Db>) -> Re
             }
         }
     }
+    if let Ok(canonical_target) = canonicalize_for_write_target(Path::new(&path)) {
+        check_file_rules(&db, &canonical_target, content.len() as u64).map_err(|e| {
+            log::error!("[write_file] {}", e);
+            format!("Write rejected: {}", e)
+        })?;
+    }
     write_file_with_roots(&path, &content, &allowed_roots).map_err(|e| {
         log::error!("[write_file] Failed to write {}: {}", path, e);
         e
@@ -360,6 +415,12 @@ pub fn copy_file(src: String, dest: String, db: tauri::State<'_, Db>) -> Result<
             }
         }
     }
+    if let (Ok(canonical_dest), Ok(meta)) = (canonicalize_for_write_target(Path::new(&dest)), fs::metadata(&src)) {
+        check_file_rules(&db, &canonical_dest, meta.len()).map_err(|e| {
+            log::error!("[copy_file] {}", e);
+            format!("Copy rejected: {}", e)
+        })?;
+    }
     copy_file_with_roots(&src, &dest, &allowed_roots).map_err(|e| {
         log::error!("[copy_file] Failed to copy {} to {}: {}", src, dest, e);
         e
@@ -374,8 +435,24 @@ pub fn read_file_as_base64(file_path: String, db: tauri::State<'_, Db>) -> Resul
 }
 
 #[tauri::command]
-pub fn write_base64_to_temp_file(file_name: String, base64_content: String) -> Result<String, String> {
+pub fn write_base64_to_temp_file(
+    file_name: String,
+    base64_content: String,
+    db: tauri::State<'_, Db>,
+) -> Result<String, String> {
     log::info!("[write_base64_to_temp_file] file_name={}", file_name);
+    let rules = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        crate::fs_validation::FileRuleSet::load(&conn)?
+    };
+    write_base64_to_temp_file_inner(&file_name, &base64_content, &rules)
+}
+
+fn write_base64_to_temp_file_inner(
+    file_name: &str,
+    base64_content: &str,
+    rules: &crate::fs_validation::FileRuleSet,
+) -> Result<String, String> {
 
     // Reject path traversal attempts: no separators, no "..", no leading "."
     if file_name.contains('/') || file_name.contains('\\') || file_name.contains("..") || file_name.starts_with('.') {
@@ -384,11 +461,15 @@ pub fn write_base64_to_temp_file(file_name: String, base64_content: String) -> R
     }
 
     let bytes = base64::engine::general_purpose::STANDARD
-        .decode(&base64_content)
+        .decode(base64_content)
         .map_err(|e| format!("Invalid base64: {e}"))?;
+    rules.check_file(file_name, bytes.len() as u64, None).map_err(|e| {
+        log::error!("[write_base64_to_temp_file] {}", e);
+        format!("Upload rejected: {}", e)
+    })?;
     let temp_dir = attachment_temp_dir();
     std::fs::create_dir_all(&temp_dir).map_err(|e| format!("Cannot create temp dir: {e}"))?;
-    let dest = temp_dir.join(&file_name);
+    let dest = temp_dir.join(file_name);
     std::fs::write(&dest, &bytes).map_err(|e| format!("Cannot write file: {e}"))?;
     dest.to_str()
         .map(|s| s.to_string())
@@ -401,6 +482,10 @@ mod tests {
     use std::fs;
     use tempfile::tempdir;
 
+    fn no_team_rules() -> crate::fs_validation::FileRuleSet {
+        crate::fs_validation::FileRuleSet::new(Vec::new(), None)
+    }
+
     fn setup_skill_dir(base: &Path) {
         let skill = base.join("my-skill");
         fs::create_dir_all(skill.join("context")).unwrap();
@@ -696,8 +781,7 @@ mod tests {
     #[test]
     fn test_write_base64_to_temp_file_success() {
         // "hello world" in base64
-        let result =
-            write_base64_to_temp_file("test-att.txt".to_string(), "aGVsbG8gd29ybGQ=".to_string());
+        let result = write_base64_to_temp_file_inner("test-att.txt", "aGVsbG8gd29ybGQ=", &no_team_rules());
         assert!(result.is_ok());
         let path = result.unwrap();
         assert!(path.ends_with("test-att.txt"));
@@ -709,35 +793,42 @@ mod tests {
 
     #[test]
     fn test_write_base64_to_temp_file_invalid_base64() {
-        let result =
-            write_base64_to_temp_file("bad.txt".to_string(), "!!!not-base64!!!".to_string());
+        let result = write_base64_to_temp_file_inner("bad.txt", "!!!not-base64!!!", &no_team_rules());
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Invalid base64"));
     }
 
     #[test]
     fn test_write_base64_rejects_path_traversal() {
-        let result = write_base64_to_temp_file("../../etc/passwd".into(), "aGVsbG8=".into());
+        let result = write_base64_to_temp_file_inner("../../etc/passwd", "aGVsbG8=", &no_team_rules());
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("path traversal"));
     }
 
     #[test]
     fn test_write_base64_rejects_nested_path() {
-        let result = write_base64_to_temp_file("subdir/evil.txt".into(), "aGVsbG8=".into());
+        let result = write_base64_to_temp_file_inner("subdir/evil.txt", "aGVsbG8=", &no_team_rules());
         assert!(result.is_err());
     }
 
     #[test]
     fn test_write_base64_rejects_absolute_path() {
-        let result = write_base64_to_temp_file("/etc/passwd".into(), "aGVsbG8=".into());
+        let result = write_base64_to_temp_file_inner("/etc/passwd", "aGVsbG8=", &no_team_rules());
         assert!(result.is_err());
     }
 
     #[test]
     fn test_write_base64_rejects_leading_dot() {
-        let result = write_base64_to_temp_file(".hidden".into(), "aGVsbG8=".into());
+        let result = write_base64_to_temp_file_inner(".hidden", "aGVsbG8=", &no_team_rules());
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("path traversal"));
     }
+
+    #[test]
+    fn test_write_base64_rejects_forbidden_extension() {
+        let result = write_base64_to_temp_file_inner("setup.exe", "aGVsbG8=", &no_team_rules());
+        let err = result.unwrap_err();
+        assert!(err.starts_with("Upload rejected:"));
+        assert!(err.contains("built-in rule 2"));
+    }
 }
@@ -0,0 +1,626 @@
+//! Library-wide SKILL.md frontmatter migrations.
+//!
+//! A `FrontmatterTransform` is either built in (set, rename or remove a key,
+//! split `description`) or a script hook that rewrites SKILL.md from stdin
+//! to stdout. `preview_frontmatter_migration` runs it over every skill in
+//! the skills folder without writing anything. `apply_frontmatter_migration`
+//! refuses if any skill fails, writes all changes (restoring the originals if
+//! a write fails), and records them as one commit in the skills repo after
+//! snapshotting any pending edits. `rollback_frontmatter_migration` restores
+//! the files that commit changed, provided none were edited since.
+
+use std::path::{Path, PathBuf};
+
+use crate::db::Db;
+use crate::types::{FrontmatterMigrationPreview, FrontmatterMigrationResult, FrontmatterTransform};
+
+const COMMIT_PREFIX: &str = "Frontmatter migration:";
+
+/// Phrases that start the "when to use" part of a description, lower-cased.
+const TRIGGER_PHRASES: &[&str] = &[
+    "use when",
+    "use this when",
+    "use this skill when",
+    "use for",
+    "trigger when",
+    "triggers:",
+    "invoke when",
+];
+
+/// One top-level frontmatter key and its lines (including indented continuations).
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    key: Option<String>,
+    lines: Vec<String>,
+}
+
+/// SKILL.md split into frontmatter entries and everything after them.
+#[derive(Debug)]
+struct FrontmatterDoc {
+    entries: Vec<Entry>,
+    /// Closing `---` line onward; empty when the file had no frontmatter.
+    rest: String,
+}
+
+fn entry_key(line: &str) -> Option<String> {
+    if line.starts_with(|c: char| c.is_whitespace() || c == '#' || c == '-') {
+        return None;
+    }
+    let (key, _) = line.split_once(':')?;
+    let valid = !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then(|| key.to_string())
+}
+
+impl FrontmatterDoc {
+    fn parse(content: &str) -> Self {
+        let content = content.replace("\r\n", "\n");
+        let Some(after_open) = content.strip_prefix("---\n") else {
+            return Self { entries: Vec::new(), rest: content };
+        };
+        let mut entries: Vec<Entry> = Vec::new();
+        let mut offset = 0;
+        for line in after_open.split_inclusive('\n') {
+            let text = line.trim_end_matches('\n');
+            if text.trim_end() == "---" {
+                return Self { entries, rest: after_open[offset..].to_string() };
+            }
+            offset += line.len();
+            match (entry_key(text), entries.last_mut()) {
+                (None, Some(last)) => last.lines.push(text.to_string()),
+                (key, _) => entries.push(Entry { key, lines: vec![text.to_string()] }),
+            }
+        }
+        // Unterminated frontmatter: treat the whole file as body.
+        Self { entries: Vec::new(), rest: content }
+    }
+
+    fn render(&self) -> String {
+        if self.entries.is_empty() && !self.rest.starts_with("---") {
+            return self.rest.clone();
+        }
+        let mut out = String::from("---\n");
+        for line in self.entries.iter().flat_map(|e| &e.lines) {
+            out.push_str(line);
+            out.push('\n');
+        }
+        if self.rest.is_empty() {
+            out.push_str("---\n");
+        } else {
+            out.push_str(&self.rest);
+        }
+        out
+    }
+
+    fn position(&self, key: &str) -> Option<usize> {
+        self.entries.iter().position(|e| e.key.as_deref() == Some(key))
+    }
+
+    /// Scalar value of `key`, unquoted; block scalars are joined.
+    fn get(&self, key: &str) -> Option<String> {
+        let entry = &self.entries[self.position(key)?];
+        let first = entry.lines[0].split_once(':').map(|(_, v)| v.trim()).unwrap_or("");
+        let continuation = entry.lines[1..].iter().map(|l| l.trim()).filter(|l| !l.is_empty());
+        let value = if first.starts_with('|') {
+            continuation.collect::<Vec<_>>().join("\n")
+        } else if first.starts_with('>') || first.is_empty() {
+            continuation.collect::<Vec<_>>().join(" ")
+        } else if let Some(inner) = first.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+            inner.replace("\\n", "\n").replace("\\\"", "\"").replace("\\\\", "\\")
+        } else if let Some(inner) = first.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+            inner.replace("''", "'")
+        } else {
+            first.to_string()
+        };
+        Some(value)
+    }
+
+    fn set(&mut self, key: &str, value: &str) {
+        let entry = Entry {
+            key: Some(key.to_string()),
+            lines: vec![format!("{}: {}", key, super::github_import::yaml_quote(value))],
+        };
+        match self.position(key) {
+            Some(i) => self.entries[i] = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        self.entries.retain(|e| e.key.as_deref() != Some(key));
+    }
+}
+
+/// Split a description at its earliest trigger phrase into `(summary, trigger)`.
+fn split_description(description: &str) -> Option<(String, String)> {
+    // ASCII lowercasing keeps byte offsets, so matches index into `description` too.
+    let lower = description.to_ascii_lowercase();
+    let at = TRIGGER_PHRASES
+        .iter()
+        .filter_map(|p| lower.find(p))
+        .filter(|&i| i > 0)
+        .min()?;
+    let summary = description[..at].trim().trim_end_matches([',', ';', '-', '—']).trim();
+    let trigger = description[at..].trim();
+    if summary.is_empty() || trigger.is_empty() {
+        return None;
+    }
+    Some((summary.to_string(), trigger.to_string()))
+}
+
+fn run_script(command: &str, skill_name: &str, skill_dir: &Path, content: &str) -> Result<String, String> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut c = Command::new("cmd");
+        c.args(["/C", command]);
+        c
+    };
+    #[cfg(not(windows))]
+    let mut cmd = {
+        let mut c = Command::new("sh");
+        c.args(["-c", command]);
+        c
+    };
+    let mut child = cmd
+        .current_dir(skill_dir)
+        .env("SKILL_NAME", skill_name)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start migration script: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A script may exit without reading stdin; its exit status decides the outcome.
+        match stdin.write_all(content.as_bytes()) {
+            Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => {
+                return Err(format!("Failed to write to migration script: {}", e));
+            }
+            _ => {}
+        }
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Migration script failed: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "Migration script exited with {}: {}",
+            output.status,
+            stderr.trim().chars().take(500).collect::<String>()
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|_| "Migration script output is not UTF-8".to_string())
+}
+
+/// Apply `transform` to one SKILL.md. Returns the new content (equal to the
+/// input when nothing changed). The skill's `name` must survive unchanged.
+fn transform_skill_md(
+    transform: &FrontmatterTransform,
+    skill_name: &str,
+    skill_dir: &Path,
+    content: &str,
+) -> Result<String, String> {
+    let mut doc = FrontmatterDoc::parse(content);
+    let original = doc.entries.clone();
+    match transform {
+        FrontmatterTransform::SetField { key, value, overwrite } => {
+            if *overwrite || doc.position(key).is_none() {
+                doc.set(key, value);
+            }
+        }
+        FrontmatterTransform::RenameField { from, to } => {
+            if let Some(i) = doc.position(from) {
+                if doc.position(to).is_some() {
+                    return Err(format!("Both '{}' and '{}' are present", from, to));
+                }
+                let entry = &mut doc.entries[i];
+                entry.lines[0] = format!("{}{}", to, &entry.lines[0][from.len()..]);
+                entry.key = Some(to.clone());
+            }
+        }
+        FrontmatterTransform::RemoveField { key } => doc.remove(key),
+        FrontmatterTransform::SplitDescription { summary_key, trigger_key, keep_description } => {
+            if let Some((summary, trigger)) = doc.get("description").as_deref().and_then(split_description) {
+                doc.set(summary_key, &summary);
+                doc.set(trigger_key, &trigger);
+                if !keep_description {
+                    doc.remove("description");
+                }
+            }
+        }
+        FrontmatterTransform::Script { command } => {
+            let output = run_script(command, skill_name, skill_dir, content)?;
+            doc = FrontmatterDoc::parse(&output);
+            if doc.get("name").as_deref() != Some(skill_name) {
+                return Err(format!("Script output must keep 'name: {}' in the frontmatter", skill_name));
+            }
+            return Ok(output);
+        }
+    }
+    if doc.entries == original {
+        return Ok(content.to_string());
+    }
+    if doc.get("name").as_deref() != FrontmatterDoc::parse(content).get("name").as_deref() {
+        return Err("The migration may not change the skill name".to_string());
+    }
+    Ok(doc.render())
+}
+
+/// Set frontmatter `fields` in a SKILL.md, keeping everything else as written.
+/// Files without frontmatter are returned unchanged.
+pub(crate) fn set_frontmatter_fields(content: &str, fields: &[(&str, &str)]) -> String {
+    let mut doc = FrontmatterDoc::parse(content);
+    if doc.entries.is_empty() {
+        return content.to_string();
+    }
+    let original = doc.entries.clone();
+    for (key, value) in fields {
+        doc.set(key, value);
+    }
+    if doc.entries == original {
+        content.to_string()
+    } else {
+        doc.render()
+    }
+}
+
+/// Remove frontmatter `keys` from a SKILL.md, keeping everything else as written.
+pub(crate) fn remove_frontmatter_fields(content: &str, keys: &[&str]) -> String {
+    let mut doc = FrontmatterDoc::parse(content);
+    let before = doc.entries.len();
+    for key in keys {
+        doc.remove(key);
+    }
+    if doc.entries.len() == before {
+        content.to_string()
+    } else {
+        doc.render()
+    }
+}
+
+fn describe(transform: &FrontmatterTransform) -> String {
+    match transform {
+        FrontmatterTransform::SetField { key, .. } => format!("set {}", key),
+        FrontmatterTransform::RenameField { from, to } => format!("rename {} to {}", from, to),
+        FrontmatterTransform::RemoveField { key } => format!("remove {}", key),
+        FrontmatterTransform::SplitDescription { summary_key, trigger_key, .. } => {
+            format!("split description into {}/{}", summary_key, trigger_key)
+        }
+        FrontmatterTransform::Script { command } => format!("script `{}`", command),
+    }
+}
+
+/// Skill directories (with a SKILL.md) in the skills folder, sorted by name.
+fn library_skills(skills_dir: &Path) -> Result<Vec<(String, PathBuf)>, String> {
+    let mut skills = Vec::new();
+    let entries = std::fs::read_dir(skills_dir)
+        .map_err(|e| format!("Failed to read skills folder {}: {}", skills_dir.display(), e))?;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let skill_md = entry.path().join("SKILL.md");
+        if !name.starts_with('.') && skill_md.is_file() {
+            skills.push((name, skill_md));
+        }
+    }
+    skills.sort();
+    Ok(skills)
+}
+
+fn plan_migration(
+    skills_dir: &Path,
+    transform: &FrontmatterTransform,
+    only: Option<&[String]>,
+) -> Result<Vec<FrontmatterMigrationPreview>, String> {
+    let mut previews = Vec::new();
+    for (name, skill_md) in library_skills(skills_dir)? {
+        if only.is_some_and(|names| !names.contains(&name)) {
+            continue;
+        }
+        let outcome = std::fs::read_to_string(&skill_md)
+            .map_err(|e| format!("Failed to read SKILL.md: {}", e))
+            .and_then(|before| {
+                let after = transform_skill_md(transform, &name, skill_md.parent().unwrap_or(skills_dir), &before)?;
+                Ok((before, after))
+            });
+        previews.push(match outcome {
+            Ok((before, after)) if before != after => FrontmatterMigrationPreview {
+                skill_name: name,
+                changed: true,
+                before: Some(before),
+                after: Some(after),
+                error: None,
+            },
+            Ok(_) => FrontmatterMigrationPreview {
+                skill_name: name,
+                changed: false,
+                before: None,
+                after: None,
+                error: None,
+            },
+            Err(e) => FrontmatterMigrationPreview {
+                skill_name: name,
+                changed: false,
+                before: None,
+                after: None,
+                error: Some(e),
+            },
+        });
+    }
+    Ok(previews)
+}
+
+/// Write every changed SKILL.md and commit. On a write failure, files already
+/// written are restored and nothing is committed.
+fn apply_planned(
+    skills_dir: &Path,
+    previews: &[FrontmatterMigrationPreview],
+    label: &str,
+) -> Result<FrontmatterMigrationResult, String> {
+    let failed: Vec<String> = previews
+        .iter()
+        .filter_map(|p| p.error.as_ref().map(|e| format!("{}: {}", p.skill_name, e)))
+        .collect();
+    if !failed.is_empty() {
+        return Err(format!(
+            "Migration not applied; {} skill(s) failed: {}",
+            failed.len(),
+            failed.join("; ")
+        ));
+    }
+    let changed: Vec<&FrontmatterMigrationPreview> = previews.iter().filter(|p| p.changed).collect();
+    if changed.is_empty() {
+        return Ok(FrontmatterMigrationResult { commit_sha: None, skills: Vec::new() });
+    }
+
+    // Keep unrelated pending edits out of the migration commit so rollback is exact.
+    crate::git::commit_all(skills_dir, "Snapshot before frontmatter migration")?;
+
+    let mut written: Vec<&FrontmatterMigrationPreview> = Vec::new();
+    for preview in &changed {
+        let path = skills_dir.join(&preview.skill_name).join("SKILL.md");
+        if let Err(e) = std::fs::write(&path, preview.after.as_deref().unwrap_or_default()) {
+            for done in &written {
+                let restore = skills_dir.join(&done.skill_name).join("SKILL.md");
+                if let Err(re) = std::fs::write(&restore, done.before.as_deref().unwrap_or_default()) {
+                    log::error!("[apply_frontmatter_migration] failed to restore {}: {}", restore.display(), re);
+                }
+            }
+            return Err(format!("Failed to write {}: {}; migration rolled back", path.display(), e));
+        }
+        written.push(preview);
+    }
+
+    let message = format!("{} {} ({} skills)", COMMIT_PREFIX, label, changed.len());
+    let commit_sha = crate::git::commit_all(skills_dir, &message)?;
+    Ok(FrontmatterMigrationResult {
+        commit_sha,
+        skills: changed.iter().map(|p| p.skill_name.clone()).collect(),
+    })
+}
+
+/// Copy frontmatter fields the DB mirrors from each skill's SKILL.md.
+fn sync_skill_metadata(conn: &rusqlite::Connection, skills_dir: &Path, skills: &[String]) {
+    for name in skills {
+        let Ok(content) = std::fs::read_to_string(skills_dir.join(name).join("SKILL.md")) else {
+            continue;
+        };
+        let fm = super::imported_skills::parse_frontmatter_full(&content);
+        if let Err(e) = crate::db::set_skill_behaviour(
+            conn,
+            name,
+            fm.description.as_deref(),
+            fm.version.as_deref(),
+            fm.model.as_deref(),
+            fm.argument_hint.as_deref(),
+            fm.user_invocable,
+            fm.disable_model_invocation,
+        ) {
+            log::warn!("[frontmatter_migration] failed to sync metadata for '{}': {}", name, e);
+        }
+    }
+}
+
+fn skills_dir(db: &Db, cmd: &str) -> Result<PathBuf, String> {
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[{}] Failed to acquire DB lock: {}", cmd, e);
+        e.to_string()
+    })?;
+    crate::db::read_settings(&conn)?
+        .skills_path
+        .map(PathBuf::from)
+        .ok_or_else(|| "Skills path not configured. Set it in Settings.".to_string())
+}
+
+/// Dry run: the change `transform` would make to each skill, without writing.
+#[tauri::command]
+pub fn preview_frontmatter_migration(
+    transform: FrontmatterTransform,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<FrontmatterMigrationPreview>, String> {
+    log::info!("[preview_frontmatter_migration] {}", describe(&transform));
+    let dir = skills_dir(&db, "preview_frontmatter_migration")?;
+    plan_migration(&dir, &transform, None)
+}
+
+#[tauri::command]
+pub fn apply_frontmatter_migration(
+    transform: FrontmatterTransform,
+    skill_names: Option<Vec<String>>,
+    db: tauri::State<'_, Db>,
+) -> Result<FrontmatterMigrationResult, String> {
+    let label = describe(&transform);
+    log::info!("[apply_frontmatter_migration] {} skills={:?}", label, skill_names);
+    let dir = skills_dir(&db, "apply_frontmatter_migration")?;
+    let previews = plan_migration(&dir, &transform, skill_names.as_deref())?;
+    for preview in previews.iter().filter(|p| p.changed) {
+        super::observer::ensure_writable(&preview.skill_name)?;
+    }
+    let result = apply_planned(&dir, &previews, &label).map_err(|e| {
+        log::error!("[apply_frontmatter_migration] {}", e);
+        e
+    })?;
+
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[apply_frontmatter_migration] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    sync_skill_metadata(&conn, &dir, &result.skills);
+    log::info!(
+        "[apply_frontmatter_migration] migrated {} skills in {:?}",
+        result.skills.len(),
+        result.commit_sha
+    );
+    Ok(result)
+}
+
+fn rollback_commit(skills_dir: &Path, commit_sha: &str) -> Result<FrontmatterMigrationResult, String> {
+    let message = crate::git::commit_message(skills_dir, commit_sha)?;
+    if !message.starts_with(COMMIT_PREFIX) {
+        return Err(format!("Commit {} is not a frontmatter migration", commit_sha));
+    }
+    let paths = crate::git::revert_commit_files(skills_dir, commit_sha)?;
+    let mut skills: Vec<String> = paths
+        .iter()
+        .filter_map(|p| p.split('/').next().map(str::to_string))
+        .collect();
+    skills.dedup();
+    let short = &commit_sha[..8.min(commit_sha.len())];
+    let sha = crate::git::commit_all(skills_dir, &format!("Roll back frontmatter migration {}", short))?;
+    Ok(FrontmatterMigrationResult { commit_sha: sha, skills })
+}
+
+/// Undo an applied migration by restoring the files its commit changed.
+#[tauri::command]
+pub fn rollback_frontmatter_migration(
+    commit_sha: String,
+    db: tauri::State<'_, Db>,
+) -> Result<FrontmatterMigrationResult, String> {
+    log::info!("[rollback_frontmatter_migration] commit={}", commit_sha);
+    let dir = skills_dir(&db, "rollback_frontmatter_migration")?;
+    let result = rollback_commit(&dir, &commit_sha).map_err(|e| {
+        log::error!("[rollback_frontmatter_migration] {}", e);
+        e
+    })?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[rollback_frontmatter_migration] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    sync_skill_metadata(&conn, &dir, &result.skills);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SKILL: &str = "---\nname: sales\ndescription: >\n  Pipeline reporting conventions.\n  Use when building sales dashboards.\nlicense: MIT\n---\n# Sales\n\n---\nBody rule\n";
+
+    fn apply(transform: FrontmatterTransform, content: &str) -> Result<String, String> {
+        transform_skill_md(&transform, "sales", Path::new("."), content)
+    }
+
+    #[test]
+    fn test_parse_render_round_trip_and_get() {
+        let doc = FrontmatterDoc::parse(SKILL);
+        assert_eq!(doc.render(), SKILL);
+        assert_eq!(
+            doc.get("description").as_deref(),
+            Some("Pipeline reporting conventions. Use when building sales dashboards.")
+        );
+        assert_eq!(doc.get("license").as_deref(), Some("MIT"));
+        let plain = FrontmatterDoc::parse("# No frontmatter\n");
+        assert_eq!(plain.render(), "# No frontmatter\n");
+    }
+
+    #[test]
+    fn test_builtin_transforms() {
+        let split = apply(
+            FrontmatterTransform::SplitDescription {
+                summary_key: "summary".into(),
+                trigger_key: "trigger".into(),
+                keep_description: false,
+            },
+            SKILL,
+        )
+        .unwrap();
+        assert!(split.contains("summary: \"Pipeline reporting conventions.\"\n"));
+        assert!(split.contains("trigger: \"Use when building sales dashboards.\"\n"));
+        assert!(!split.contains("description:"));
+        assert!(split.ends_with("---\n# Sales\n\n---\nBody rule\n"));
+
+        let renamed = apply(FrontmatterTransform::RenameField { from: "license".into(), to: "licence".into() }, SKILL)
+            .unwrap();
+        assert!(renamed.contains("\nlicence: MIT\n"));
+
+        let kept = apply(
+            FrontmatterTransform::SetField { key: "license".into(), value: "Apache-2.0".into(), overwrite: false },
+            SKILL,
+        )
+        .unwrap();
+        assert_eq!(kept, SKILL);
+
+        let removed = apply(FrontmatterTransform::RemoveField { key: "license".into() }, SKILL).unwrap();
+        assert!(!removed.contains("license"));
+
+        assert!(apply(FrontmatterTransform::RemoveField { key: "name".into() }, SKILL).is_err());
+    }
+
+    #[test]
+    fn test_split_description_after_non_ascii_summary() {
+        // `İ` lowercases to three bytes, so a full lowercase shifts every later offset.
+        assert_eq!(
+            split_description("İstanbul Ürün raporları. Use when building dashboards."),
+            Some((
+                "İstanbul Ürün raporları.".to_string(),
+                "Use when building dashboards.".to_string()
+            ))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_script_hook_must_keep_name() {
+        let upper = apply(FrontmatterTransform::Script { command: "sed 's/license: MIT/license: BSD/'".into() }, SKILL)
+            .unwrap();
+        assert!(upper.contains("license: BSD"));
+        let broken = apply(FrontmatterTransform::Script { command: "echo nope".into() }, SKILL);
+        assert!(broken.unwrap_err().contains("name: sales"));
+        let failing = apply(FrontmatterTransform::Script { command: "exit 3".into() }, SKILL);
+        assert!(failing.unwrap_err().contains("exited"));
+    }
+
+    #[test]
+    fn test_apply_commits_and_rolls_back() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        for (name, content) in [("sales", SKILL), ("hr", "---\nname: hr\ndescription: HR terms\n---\n")] {
+            std::fs::create_dir_all(dir.join(name)).unwrap();
+            std::fs::write(dir.join(name).join("SKILL.md"), content).unwrap();
+        }
+        crate::git::commit_all(dir, "initial").unwrap();
+
+        let transform = FrontmatterTransform::SetField { key: "owner".into(), value: "data".into(), overwrite: false };
+        let previews = plan_migration(dir, &transform, None).unwrap();
+        assert_eq!(previews.iter().filter(|p| p.changed).count(), 2);
+        // Dry run writes nothing
+        assert_eq!(std::fs::read_to_string(dir.join("sales/SKILL.md")).unwrap(), SKILL);
+
+        let result = apply_planned(dir, &previews, &describe(&transform)).unwrap();
+        assert_eq!(result.skills, vec!["hr", "sales"]);
+        let sha = result.commit_sha.unwrap();
+        assert!(std::fs::read_to_string(dir.join("sales/SKILL.md")).unwrap().contains("owner: \"data\""));
+
+        let rolled = rollback_commit(dir, &sha).unwrap();
+        assert_eq!(rolled.skills, vec!["hr", "sales"]);
+        assert_eq!(std::fs::read_to_string(dir.join("sales/SKILL.md")).unwrap(), SKILL);
+        assert!(rollback_commit(dir, &rolled.commit_sha.unwrap()).unwrap_err().contains("not a frontmatter migration"));
+
+        // Any failing skill blocks the whole migration
+        let bad = FrontmatterTransform::RemoveField { key: "name".into() };
+        let previews = plan_migration(dir, &bad, Some(&["hr".to_string()])).unwrap();
+        assert_eq!(previews.len(), 1);
+        assert!(apply_planned(dir, &previews, "bad").unwrap_err().contains("hr:"));
+    }
+}
@@ -4,7 +4,7 @@ use crate::db::Db;
 use crate::types::{SkillCommit, SkillDiff};
 
 /// Resolve the skill output root: skills_path if configured, else workspace_path.
-fn resolve_output_root(db: &Db, workspace_path: &str) -> Result<String, String> {
+pub(crate) fn resolve_output_root(db: &Db, workspace_path: &str) -> Result<String, String> {
     let conn = db.0.lock().map_err(|e| e.to_string())?;
     let settings = crate::db::read_settings(&conn)?;
     Ok(settings
@@ -49,6 +49,7 @@ pub fn restore_skill_version(
     db: tauri::State<'_, Db>,
 ) -> Result<(), String> {
     log::info!("[restore_skill_version] skill={} sha={}", skill_name, sha);
+    super::observer::ensure_writable(&skill_name)?;
     let output_root = resolve_output_root(&db, &workspace_path)?;
     let root = Path::new(&output_root);
     crate::git::restore_version(root, &sha, &skill_name)?;
@@ -0,0 +1,1046 @@
+//! Team sharing over GitLab and Bitbucket (and GitHub through the same path):
+//! token-based account connection, repo listing, browsing and importing
+//! marketplace skills, and pushing a built skill with its `marketplace.json`
+//! entry.
+//!
+//! GitHub keeps its device-flow sign-in in `github_auth`; its token is reused
+//! here so every command works against any supported host.
+
+use crate::commands::github_import::{
+    discover_skills_from_catalog, extract_plugin_path, import_workspace_skills,
+    marketplace_manifest_path, WorkspaceSkillImportRequest,
+};
+use crate::db::Db;
+use crate::git_hosting::{self, GitHost, HostedRepo, HostedRepoSummary};
+use crate::github::GitHubServer;
+use crate::types::{
+    AvailableSkill, GitHostAccount, GitHostCredential, HostedSkillPushResult, ImportedSkill,
+    MarketplaceJson, SkillUpstreamReview,
+};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Files larger than this are left out of a push.
+const MAX_PUSH_FILE_BYTES: u64 = 1_000_000;
+
+/// Push strategies: commit straight to the repo branch, or commit to a branch
+/// per skill and open a pull/merge request into it (for a protected main).
+const PUSH_DIRECT: &str = "direct";
+const PUSH_BRANCH_PER_SKILL: &str = "branch-per-skill";
+
+/// Prefix of the review branch a branch-per-skill push commits to.
+const SKILL_BRANCH_PREFIX: &str = "skill/";
+
+/// `(relative path, content)` pairs to push, plus the relative paths left out.
+pub(crate) type SkillFiles = (Vec<(String, String)>, Vec<String>);
+
+/// The stored token to send to `repo`: the GitHub OAuth token, or a connected
+/// GitLab/Bitbucket credential, and only when the account lives on the API
+/// base `repo` names. Any other host gets no token, so a URL pointing at
+/// `gitlab.example.net` cannot collect the gitlab.com credential.
+pub(crate) fn token_for(db: &Db, repo: &HostedRepo) -> Result<Option<String>, String> {
+    Ok(token_for_repo(account_for(db, repo.host)?, repo))
+}
+
+fn token_for_repo(account: Option<(String, String)>, repo: &HostedRepo) -> Option<String> {
+    let (token, api_base) = account?;
+    if api_base.trim_end_matches('/') == repo.api_base.trim_end_matches('/') {
+        Some(token)
+    } else {
+        log::warn!(
+            "[token_for] not sending the {} token to {}: the account is on {}",
+            repo.host.as_str(),
+            repo.api_base,
+            api_base
+        );
+        None
+    }
+}
+
+/// The stored token for `host` with the API base its account lives on.
+fn account_for(db: &Db, host: GitHost) -> Result<Option<(String, String)>, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    if host == GitHost::GitHub {
+        let settings = crate::db::read_settings_hydrated(&conn)?;
+        let api_base = GitHubServer::from_settings(&settings)?.api_base;
+        return Ok(settings.github_oauth_token.map(|t| (t, api_base)));
+    }
+    Ok(crate::db::read_git_host_credentials(&conn)?
+        .into_iter()
+        .find(|c| c.host == host.as_str())
+        .map(|c| {
+            let api_base = c.api_base.unwrap_or_else(|| host.default_api_base().to_string());
+            (c.token, api_base)
+        }))
+}
+
+/// The configured GitHub server: github.com or a GitHub Enterprise Server.
+pub(crate) fn github_server(db: &Db) -> Result<GitHubServer, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    GitHubServer::from_settings(&crate::db::read_settings(&conn)?)
+}
+
+pub(crate) fn not_connected(host: GitHost) -> String {
+    match host {
+        GitHost::GitHub => "Not signed in to GitHub. Sign in with GitHub in Settings.".to_string(),
+        GitHost::GitLab => "Not connected to GitLab. Add an access token in Settings.".to_string(),
+        GitHost::Bitbucket => {
+            "Not connected to Bitbucket. Add an access token in Settings.".to_string()
+        }
+    }
+}
+
+/// Connect a GitLab or Bitbucket account with a personal/project access token
+/// (Bitbucket also accepts `username:app_password`). `instance_url` points at a
+/// self-managed GitLab. The token is verified against the host before it is stored.
+#[tauri::command]
+pub async fn git_host_connect(
+    host: String,
+    token: String,
+    instance_url: Option<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<GitHostAccount, String> {
+    log::info!("[git_host_connect] host={} instance_url={:?}", host, instance_url);
+    super::offline::require_feature(&db, super::offline::NetworkFeature::GitHub, "git_host_connect")?;
+    let host = GitHost::parse(&host)?;
+    if host == GitHost::GitHub {
+        return Err("GitHub uses Sign in with GitHub in Settings.".to_string());
+    }
+    let token = token.trim().to_string();
+    if token.is_empty() {
+        return Err("Access token cannot be empty".to_string());
+    }
+    let api_base = match instance_url.as_deref().map(str::trim).filter(|u| !u.is_empty()) {
+        Some(url) if host == GitHost::GitLab => Some(git_hosting::gitlab_api_base(url)?),
+        Some(_) => return Err("Only GitLab supports a self-managed instance URL".to_string()),
+        None => None,
+    };
+
+    let client = git_hosting::build_client(host, Some(&token), &GitHubServer::default())?;
+    let login = git_hosting::fetch_login(
+        &client,
+        host,
+        api_base.as_deref().unwrap_or(host.default_api_base()),
+    )
+        .await
+        .map_err(|e| {
+            log::error!("[git_host_connect] token check failed for {}: {}", host.as_str(), e);
+            e
+        })?;
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let mut credentials = crate::db::read_git_host_credentials(&conn)?;
+    credentials.retain(|c| c.host != host.as_str());
+    credentials.push(GitHostCredential {
+        host: host.as_str().to_string(),
+        login: login.clone(),
+        token,
+        api_base: api_base.clone(),
+    });
+    crate::db::write_git_host_credentials(&conn, &credentials)?;
+    log::info!("[git_host_connect] connected {} as {}", host.as_str(), login);
+
+    Ok(GitHostAccount {
+        host: host.as_str().to_string(),
+        login,
+        api_base,
+    })
+}
+
+/// Forget the stored token for a GitLab or Bitbucket account.
+#[tauri::command]
+pub fn git_host_disconnect(host: String, db: tauri::State<'_, Db>) -> Result<(), String> {
+    log::info!("[git_host_disconnect] host={}", host);
+    let host = GitHost::parse(&host)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[git_host_disconnect] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let mut credentials = crate::db::read_git_host_credentials(&conn)?;
+    credentials.retain(|c| c.host != host.as_str());
+    crate::db::write_git_host_credentials(&conn, &credentials)
+}
+
+/// Connected accounts across all hosts, including the GitHub sign-in.
+#[tauri::command]
+pub fn git_host_list_accounts(db: tauri::State<'_, Db>) -> Result<Vec<GitHostAccount>, String> {
+    log::info!("[git_host_list_accounts]");
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[git_host_list_accounts] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let settings = crate::db::read_settings_hydrated(&conn)?;
+    let mut accounts = Vec::new();
+    if settings.github_oauth_token.is_some() {
+        let github = GitHubServer::from_settings(&settings)?;
+        accounts.push(GitHostAccount {
+            host: GitHost::GitHub.as_str().to_string(),
+            login: settings.github_user_login.unwrap_or_default(),
+            api_base: github.is_enterprise().then_some(github.api_base),
+        });
+    }
+    accounts.extend(
+        crate::db::read_git_host_credentials(&conn)?
+            .into_iter()
+            .map(|c| GitHostAccount {
+                host: c.host,
+                login: c.login,
+                api_base: c.api_base,
+            }),
+    );
+    Ok(accounts)
+}
+
+/// Repositories the connected account on `host` is a member of.
+#[tauri::command]
+pub async fn git_host_list_repos(
+    host: String,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<HostedRepoSummary>, String> {
+    log::info!("[git_host_list_repos] host={}", host);
+    super::offline::require_feature(&db, super::offline::NetworkFeature::GitHub, "git_host_list_repos")?;
+    let host = GitHost::parse(&host)?;
+    let (token, api_base) = account_for(&db, host)?.ok_or_else(|| not_connected(host))?;
+    let client = git_hosting::build_client(host, Some(&token), &github_server(&db)?)?;
+    git_hosting::list_repos(&client, host, &api_base)
+        .await
+        .map_err(|e| {
+            log::error!("[git_host_list_repos] {}", e);
+            e
+        })
+}
+
+// ---------------------------------------------------------------------------
+// list_hosted_skills
+// ---------------------------------------------------------------------------
+
+/// List the marketplace skills in a GitLab, Bitbucket or GitHub repository.
+/// Public repositories work without a connected account.
+#[tauri::command]
+pub async fn list_hosted_skills(
+    repo_url: String,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<AvailableSkill>, String> {
+    log::info!("[list_hosted_skills] repo_url={}", repo_url);
+    super::offline::require_feature(&db, super::offline::NetworkFeature::GitHub, "list_hosted_skills")?;
+    let github = github_server(&db)?;
+    let repo = git_hosting::parse_repo_url(&repo_url, &github)?;
+    let token = token_for(&db, &repo)?;
+    let client = git_hosting::build_client(repo.host, token.as_deref(), &github)?;
+    list_hosted_skills_inner(&client, &repo).await.map_err(|e| {
+        log::error!("[list_hosted_skills] {}: {}", repo.full_name(), e);
+        e
+    })
+}
+
+pub(crate) async fn list_hosted_skills_inner(
+    client: &reqwest::Client,
+    repo: &HostedRepo,
+) -> Result<Vec<AvailableSkill>, String> {
+    let branch = git_hosting::resolve_branch(client, repo).await?;
+    let subpath = repo.subpath.as_deref();
+    let manifest_path = marketplace_manifest_path(subpath);
+    let manifest = git_hosting::get_text(client, repo, &branch, &manifest_path)
+        .await?
+        .ok_or_else(|| {
+            format!(
+                "marketplace.json not found at {} in {}. Ensure the repository has this file.",
+                manifest_path,
+                repo.full_name()
+            )
+        })?;
+    let marketplace: MarketplaceJson = serde_json::from_str(&manifest)
+        .map_err(|e| format!("Failed to parse marketplace.json: {}", e))?;
+
+    let skill_dirs: HashSet<String> = git_hosting::list_files(client, repo, &branch)
+        .await?
+        .iter()
+        .filter_map(|p| p.strip_suffix("/SKILL.md").map(|d| d.to_string()))
+        .collect();
+    let plugin_root = marketplace
+        .metadata
+        .as_ref()
+        .and_then(|m| m.plugin_root.as_deref());
+    let candidates =
+        discover_skills_from_catalog(&marketplace.plugins, plugin_root, &skill_dirs, subpath);
+
+    let contents = futures::future::join_all(candidates.iter().map(|skill| {
+        let path = format!("{}/SKILL.md", skill.path);
+        let branch = branch.as_str();
+        async move { git_hosting::get_text(client, repo, branch, &path).await.ok().flatten() }
+    }))
+    .await;
+
+    // Same rule as the GitHub listing: the name must come from SKILL.md frontmatter.
+    let mut skills = Vec::new();
+    for (mut skill, content) in candidates.into_iter().zip(contents) {
+        let Some(content) = content else {
+            log::debug!("[list_hosted_skills] skipping '{}': SKILL.md could not be fetched", skill.path);
+            continue;
+        };
+        let fm = super::imported_skills::parse_frontmatter_full(&content);
+        let Some(name) = fm.name else {
+            log::debug!("[list_hosted_skills] skipping '{}': no 'name' in SKILL.md frontmatter", skill.path);
+            continue;
+        };
+        skill.name = name;
+        if fm.description.is_some() {
+            skill.description = fm.description;
+        }
+        skill.version = fm.version;
+        skill.model = fm.model;
+        skill.argument_hint = fm.argument_hint;
+        skill.user_invocable = fm.user_invocable;
+        skill.disable_model_invocation = fm.disable_model_invocation;
+        skills.push(skill);
+    }
+
+    let plugin_paths: HashSet<String> = skills
+        .iter()
+        .map(|s| extract_plugin_path(&s.path).to_string())
+        .collect();
+    for plugin_path in plugin_paths {
+        let plugin_json = if plugin_path.is_empty() {
+            ".claude-plugin/plugin.json".to_string()
+        } else {
+            format!("{}/.claude-plugin/plugin.json", plugin_path)
+        };
+        let name = git_hosting::get_text(client, repo, &branch, &plugin_json)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|body| serde_json::from_str::<serde_json::Value>(&body).ok())
+            .and_then(|v| v["name"].as_str().map(|s| s.to_string()))
+            .filter(|n| !n.trim().is_empty());
+        for skill in skills
+            .iter_mut()
+            .filter(|s| extract_plugin_path(&s.path) == plugin_path)
+        {
+            skill.plugin_name = name.clone();
+        }
+    }
+
+    log::info!(
+        "[list_hosted_skills] {} skills in {} ({})",
+        skills.len(),
+        repo.full_name(),
+        repo.host.as_str()
+    );
+    Ok(skills)
+}
+
+// ---------------------------------------------------------------------------
+// import_hosted_skills
+// ---------------------------------------------------------------------------
+
+/// Import selected skills from a hosted repository into the workspace, with the
+/// same versioning, activation and purpose rules as `import_github_skills`.
+#[tauri::command]
+pub async fn import_hosted_skills(
+    app: tauri::AppHandle,
+    repo_url: String,
+    skill_requests: Vec<WorkspaceSkillImportRequest>,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<ImportedSkill>, String> {
+    log::info!(
+        "[import_hosted_skills] repo_url={} count={}",
+        repo_url,
+        skill_requests.len()
+    );
+    super::offline::require_feature(&db, super::offline::NetworkFeature::GitHub, "import_hosted_skills")?;
+    let github = github_server(&db)?;
+    let repo = git_hosting::parse_repo_url(&repo_url, &github)?;
+    let workspace_path = {
+        let conn = db.0.lock().map_err(|e| {
+            log::error!("[import_hosted_skills] failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        crate::db::read_settings_hydrated(&conn)?
+            .workspace_path
+            .ok_or_else(|| "Workspace path not initialized".to_string())?
+    };
+    let token = token_for(&db, &repo)?;
+    let client = git_hosting::build_client(repo.host, token.as_deref(), &github)?;
+
+    let branch = git_hosting::resolve_branch(&client, &repo).await?;
+    let tree: Vec<serde_json::Value> = git_hosting::list_files(&client, &repo, &branch)
+        .await?
+        .into_iter()
+        .map(|path| serde_json::json!({"path": path, "type": "blob"}))
+        .collect();
+    let raw_url = |file_path: &str| git_hosting::raw_file_url(&repo, &branch, file_path);
+
+    let skills = import_workspace_skills(
+        &db,
+        &client,
+        &raw_url,
+        &tree,
+        &workspace_path,
+        &skill_requests,
+        Some(repo_url),
+        None,
+    )
+    .await
+    .map_err(|e| {
+        log::error!("[import_hosted_skills] {}", e);
+        e
+    })?;
+    let names: Vec<String> = skills.iter().map(|s| s.skill_name.clone()).collect();
+    super::skill_similarity::flag_near_duplicates(&app, &db, &names, "import_hosted_skills");
+    Ok(skills)
+}
+
+// ---------------------------------------------------------------------------
+// push_skill_to_host
+// ---------------------------------------------------------------------------
+
+/// Repo path a pushed skill lives at: `skills/<name>` under the repo URL's subpath.
+pub(crate) fn hosted_skill_path(subpath: Option<&str>, skill_name: &str) -> String {
+    match subpath.filter(|s| !s.is_empty()) {
+        Some(sp) => format!("{}/skills/{}", sp.trim_end_matches('/'), skill_name),
+        None => format!("skills/{}", skill_name),
+    }
+}
+
+/// Collect the text files of a skill directory as `(relative path, content)`.
+/// Hidden entries, binary files and files over `MAX_PUSH_FILE_BYTES` are
+/// returned in the second list instead.
+pub(crate) fn collect_skill_files(skill_dir: &Path) -> Result<SkillFiles, String> {
+    fn walk(
+        root: &Path,
+        dir: &Path,
+        files: &mut Vec<(String, String)>,
+        skipped: &mut Vec<String>,
+    ) -> Result<(), String> {
+        let mut entries: Vec<_> = std::fs::read_dir(dir)
+            .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+            .filter_map(|e| e.ok())
+            .collect();
+        entries.sort_by_key(|e| e.file_name());
+        for entry in entries {
+            let path = entry.path();
+            let rel = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            let Ok(file_type) = entry.file_type() else { continue };
+            if entry.file_name().to_string_lossy().starts_with('.') || file_type.is_symlink() {
+                skipped.push(rel);
+            } else if file_type.is_dir() {
+                walk(root, &path, files, skipped)?;
+            } else if entry.metadata().map(|m| m.len()).unwrap_or(0) > MAX_PUSH_FILE_BYTES {
+                skipped.push(rel);
+            } else {
+                match std::fs::read(&path).map(String::from_utf8) {
+                    Ok(Ok(text)) => files.push((rel, text)),
+                    _ => skipped.push(rel),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    let mut skipped = Vec::new();
+    walk(skill_dir, skill_dir, &mut files, &mut skipped)?;
+    Ok((files, skipped))
+}
+
+/// Make sure the repo's `marketplace.json` lists a plugin that covers
+/// `skill_path`, so the pushed skill shows up when teammates browse the repo.
+///
+/// Returns the new manifest content, or `None` when it already covers the skill.
+/// A missing manifest is created with a root plugin (`"source": "./"`).
+fn reconcile_manifest(
+    existing: Option<&str>,
+    marketplace_name: &str,
+    skill_path: &str,
+    subpath: Option<&str>,
+) -> Result<Option<String>, String> {
+    let mut doc: serde_json::Value = match existing {
+        Some(text) => serde_json::from_str(text)
+            .map_err(|e| format!("Failed to parse marketplace.json: {}", e))?,
+        None => serde_json::json!({ "name": marketplace_name, "plugins": [] }),
+    };
+    let parsed: MarketplaceJson = serde_json::from_value(doc.clone())
+        .map_err(|e| format!("Failed to parse marketplace.json: {}", e))?;
+    let dirs: HashSet<String> = [skill_path.to_string()].into_iter().collect();
+    let plugin_root = parsed.metadata.as_ref().and_then(|m| m.plugin_root.as_deref());
+    if !discover_skills_from_catalog(&parsed.plugins, plugin_root, &dirs, subpath).is_empty() {
+        return Ok(None);
+    }
+
+    let taken: HashSet<&str> = parsed.plugins.iter().filter_map(|p| p.name.as_deref()).collect();
+    let plugin_name = if taken.contains(marketplace_name) {
+        format!("{}-skills", marketplace_name)
+    } else {
+        marketplace_name.to_string()
+    };
+    let plugins = doc["plugins"]
+        .as_array_mut()
+        .ok_or("marketplace.json has no 'plugins' array")?;
+    plugins.push(serde_json::json!({
+        "name": plugin_name,
+        "source": "./",
+        "description": "Skills shared from Skill Builder",
+    }));
+    let content = serde_json::to_string_pretty(&doc).map_err(|e| e.to_string())? + "\n";
+    Ok(Some(content))
+}
+
+fn parse_push_strategy(strategy: Option<&str>) -> Result<bool, String> {
+    match strategy.unwrap_or(PUSH_DIRECT) {
+        PUSH_DIRECT => Ok(false),
+        PUSH_BRANCH_PER_SKILL => Ok(true),
+        other => Err(format!(
+            "Unknown push strategy '{}': expected {} or {}",
+            other, PUSH_DIRECT, PUSH_BRANCH_PER_SKILL
+        )),
+    }
+}
+
+/// Push a built skill to a hosted team repository under `{subpath}/skills/{name}`
+/// and reconcile the repo's `marketplace.json` so the skill is discoverable.
+///
+/// With the `branch-per-skill` strategy the files go to `skill/{name}` instead,
+/// and a pull/merge request into the repo branch is opened (or reused when one
+/// is already open). The request is tracked locally until it is merged or closed.
+/// Its description is regenerated on every push (see `review_description`).
+///
+/// Before committing, SKILL.md is merged with the remote copy against the one
+/// last pushed from here (see `push_merge`). A clean merge is written back to
+/// the local skill and pushed; overlapping edits return `conflict` with nothing
+/// committed.
+#[tauri::command]
+pub async fn push_skill_to_host(
+    skill_name: String,
+    repo_url: String,
+    strategy: Option<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<HostedSkillPushResult, String> {
+    log::info!(
+        "[push_skill_to_host] skill={} repo_url={} strategy={:?}",
+        skill_name, repo_url, strategy
+    );
+    push_skill(skill_name, repo_url, strategy, None, &db).await
+}
+
+/// Push `resolved_content` as the skill's SKILL.md after `push_skill_to_host`
+/// reported a conflict. `remote_digest` is the conflict's digest; if the remote
+/// SKILL.md has changed since, nothing is written and the push must be retried.
+#[tauri::command]
+pub async fn push_skill_resolve_conflicts(
+    skill_name: String,
+    repo_url: String,
+    strategy: Option<String>,
+    resolved_content: String,
+    remote_digest: String,
+    db: tauri::State<'_, Db>,
+) -> Result<HostedSkillPushResult, String> {
+    log::info!(
+        "[push_skill_resolve_conflicts] skill={} repo_url={} strategy={:?}",
+        skill_name, repo_url, strategy
+    );
+    super::observer::ensure_writable(&skill_name)?;
+    if resolved_content.lines().any(|line| line.starts_with("<<<<<<< ")) {
+        return Err("The resolved SKILL.md still contains conflict markers".to_string());
+    }
+    let resolution = ConflictResolution { content: resolved_content, remote_digest };
+    push_skill(skill_name, repo_url, strategy, Some(resolution), &db).await
+}
+
+/// A user-resolved SKILL.md and the remote content it was resolved against.
+struct ConflictResolution {
+    content: String,
+    remote_digest: String,
+}
+
+/// Replace the local SKILL.md with `content` and commit it to the skills repo.
+fn write_local_skill_md(skill_dir: &Path, skill_name: &str, content: &str) -> Result<(), String> {
+    let path = skill_dir.join("SKILL.md");
+    std::fs::write(&path, content).map_err(|e| {
+        log::error!("[push_skill_to_host] failed to write {}: {}", path.display(), e);
+        format!("Failed to write SKILL.md for '{}': {}", skill_name, e)
+    })?;
+    let msg = format!("{}: merge SKILL.md with shared repo", skill_name);
+    let repo_root = skill_dir.parent().unwrap_or(skill_dir);
+    if let Err(e) = crate::git::commit_all(repo_root, &msg) {
+        log::warn!("Git auto-commit failed ({}): {}", msg, e);
+    }
+    Ok(())
+}
+
+async fn push_skill(
+    skill_name: String,
+    repo_url: String,
+    strategy: Option<String>,
+    resolution: Option<ConflictResolution>,
+    db: &Db,
+) -> Result<HostedSkillPushResult, String> {
+    super::offline::require_feature(db, super::offline::NetworkFeature::GitHub, "push_skill_to_host")?;
+    super::imported_skills::validate_skill_name(&skill_name)?;
+    let review_push = parse_push_strategy(strategy.as_deref())?;
+    let github = github_server(db)?;
+    let repo = git_hosting::parse_repo_url(&repo_url, &github)?;
+    let skill_dir = {
+        let conn = db.0.lock().map_err(|e| {
+            log::error!("[push_skill_to_host] failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        let skills_path = crate::db::read_settings_hydrated(&conn)?
+            .skills_path
+            .ok_or_else(|| "Skills path not configured. Set it in Settings.".to_string())?;
+        let skill_dir = Path::new(&skills_path).join(&skill_name);
+        if !skill_dir.join("SKILL.md").is_file() {
+            return Err(format!("Skill '{}' has no SKILL.md to push", skill_name));
+        }
+        super::quality_gates::gate_for_packaging(&conn, &skill_name, &skill_dir).map_err(|e| {
+            log::error!("[push_skill_to_host] {}", e);
+            e
+        })?;
+        skill_dir
+    };
+
+    let token = token_for(db, &repo)?.ok_or_else(|| not_connected(repo.host))?;
+    let client = git_hosting::build_client(repo.host, Some(&token), &github)?;
+
+    let result: Result<HostedSkillPushResult, String> = async {
+        // On GitHub, confirm scopes, write access and branch protection before
+        // reading the skill, so a doomed push fails fast with a clear reason.
+        // Review pushes never commit to the base branch, so it may be protected.
+        let base = match (repo.host, review_push) {
+            (GitHost::GitHub, false) => {
+                crate::github::preflight_push(&client, &repo.api_base, &repo.owner, &repo.repo, repo.branch.as_deref())
+                    .await?
+            }
+            (GitHost::GitHub, true) => {
+                crate::github::preflight_review_push(
+                    &client,
+                    &repo.api_base,
+                    &repo.owner,
+                    &repo.repo,
+                    repo.branch.as_deref(),
+                )
+                .await?
+            }
+            _ => git_hosting::resolve_branch(&client, &repo).await?,
+        };
+        let branch = if review_push {
+            let branch = format!("{}{}", SKILL_BRANCH_PREFIX, skill_name);
+            if git_hosting::ensure_branch(&client, &repo, &branch, &base).await? {
+                log::info!("[push_skill_to_host] created review branch {} from {}", branch, base);
+            }
+            branch
+        } else {
+            base.clone()
+        };
+        let (mut skill_files, skipped) = collect_skill_files(&skill_dir)?;
+        let subpath = repo.subpath.as_deref().filter(|s| !s.is_empty());
+        let skill_path = hosted_skill_path(subpath, &skill_name);
+
+        let skill_md_path = format!("{}/SKILL.md", skill_path);
+        let remote_skill_md = git_hosting::get_text(&client, &repo, &branch, &skill_md_path).await?;
+        let local_skill_md = skill_files
+            .iter_mut()
+            .find(|(rel, _)| rel == "SKILL.md")
+            .ok_or_else(|| format!("Skill '{}' has no SKILL.md to push", skill_name))?;
+        let skill_md = match resolution {
+            Some(resolved) => {
+                let remote_digest = remote_skill_md.as_deref().map(super::push_merge::digest);
+                if remote_digest.as_deref() != Some(resolved.remote_digest.as_str()) {
+                    return Err(format!(
+                        "SKILL.md for '{}' changed in the shared repo after the conflict was reported. Push again to merge the new changes.",
+                        skill_name
+                    ));
+                }
+                resolved.content
+            }
+            None => {
+                let base = {
+                    let conn = db.0.lock().map_err(|e| e.to_string())?;
+                    crate::db::get_skill_push_base(&conn, &skill_name, &repo_url)?
+                };
+                match super::push_merge::merge_skill_md(
+                    &skill_md_path,
+                    base.as_deref(),
+                    &local_skill_md.1,
+                    remote_skill_md.as_deref(),
+                ) {
+                    super::push_merge::MergeOutcome::Clean(content) => content,
+                    super::push_merge::MergeOutcome::Conflict(conflict) => {
+                        log::info!(
+                            "[push_skill_to_host] {} conflicting hunks in {}; nothing pushed",
+                            conflict.hunks.len(),
+                            skill_md_path
+                        );
+                        return Ok(HostedSkillPushResult {
+                            repo: repo.full_name(),
+                            branch,
+                            skill_path,
+                            files: Vec::new(),
+                            skipped,
+                            manifest_updated: false,
+                            commit_sha: None,
+                            review: None,
+                            review_description: None,
+                            skill_md_merged: false,
+                            conflict: Some(conflict),
+                        });
+                    }
+                }
+            }
+        };
+        let skill_md_merged = skill_md != local_skill_md.1;
+        if skill_md_merged {
+            write_local_skill_md(&skill_dir, &skill_name, &skill_md)?;
+            local_skill_md.1 = skill_md.clone();
+        }
+        // Described against the base branch before the commit, so a reused
+        // review shows every change it will merge, not just this push's.
+        let description = if review_push {
+            Some(
+                super::review_description::build_review_description(
+                    db,
+                    &client,
+                    &repo,
+                    &base,
+                    &skill_name,
+                    &skill_path,
+                    &skill_dir,
+                    &skill_files,
+                    &skipped,
+                )
+                .await,
+            )
+        } else {
+            None
+        };
+
+        let mut files: Vec<(String, String)> = skill_files
+            .into_iter()
+            .map(|(rel, content)| (format!("{}/{}", skill_path, rel), content))
+            .collect();
+        let manifest_path = marketplace_manifest_path(subpath);
+        let existing = git_hosting::get_text(&client, &repo, &branch, &manifest_path).await?;
+        let manifest = reconcile_manifest(existing.as_deref(), &repo.repo, &skill_path, subpath)?;
+        let manifest_updated = manifest.is_some();
+        if let Some(content) = manifest {
+            files.push((manifest_path, content));
+        }
+
+        let message = format!("Share skill '{}' from Skill Builder", skill_name);
+        let commit_sha = git_hosting::commit_files(&client, &repo, &branch, &files, &message).await?;
+        let recorded = db.0.lock().map_err(|e| e.to_string()).and_then(|conn| {
+            crate::db::set_skill_push_base(
+                &conn,
+                &skill_name,
+                &repo_url,
+                &skill_path,
+                &skill_md,
+                commit_sha.as_deref(),
+            )
+        });
+        if let Err(e) = recorded {
+            log::warn!("[push_skill_to_host] failed to record push base for '{}': {}", skill_name, e);
+        }
+
+        let review = if let Some(description) = &description {
+            let title = format!("Update skill '{}'", skill_name);
+            let request = match git_hosting::find_open_review(&client, &repo, &branch, &base).await? {
+                Some(open) => {
+                    if let Err(e) = git_hosting::update_review(&client, &repo, open.number, &title, description).await {
+                        log::warn!("[push_skill_to_host] failed to update description of #{}: {}", open.number, e);
+                    }
+                    open
+                }
+                None => git_hosting::open_review(&client, &repo, &branch, &base, &title, description).await?,
+            };
+            let now = chrono::Utc::now().to_rfc3339();
+            Some(SkillUpstreamReview {
+                skill_name: skill_name.clone(),
+                host: repo.host.as_str().to_string(),
+                repo: repo.full_name(),
+                repo_url: repo_url.clone(),
+                branch: branch.clone(),
+                base_branch: base,
+                number: request.number,
+                url: request.url,
+                state: request.state,
+                commit_sha: commit_sha.clone(),
+                pushed_at: now.clone(),
+                updated_at: now,
+            })
+        } else {
+            None
+        };
+
+        Ok(HostedSkillPushResult {
+            repo: repo.full_name(),
+            branch,
+            skill_path,
+            files: files.into_iter().map(|(path, _)| path).collect(),
+            skipped,
+            manifest_updated,
+            commit_sha,
+            review,
+            review_description: description,
+            skill_md_merged,
+            conflict: None,
+        })
+    }
+    .await;
+
+    if let Some(review) = result.as_ref().ok().and_then(|r| r.review.as_ref()) {
+        let recorded = db
+            .0
+            .lock()
+            .map_err(|e| e.to_string())
+            .and_then(|conn| crate::db::upsert_upstream_review(&conn, review));
+        if let Err(e) = recorded {
+            log::warn!("[push_skill_to_host] failed to record review for '{}': {}", skill_name, e);
+        }
+    }
+
+    match &result {
+        Ok(r) if r.conflict.is_some() => {}
+        Ok(r) => {
+            log::info!(
+                "[push_skill_to_host] pushed {} files to {} ({}) branch={} manifest_updated={}",
+                r.files.len(),
+                r.repo,
+                repo.host.as_str(),
+                r.branch,
+                r.manifest_updated
+            );
+            if let Ok(conn) = db.0.lock() {
+                let summary = format!(
+                    "pushed {} file(s) to {} ({}) branch {} commit {}",
+                    r.files.len(),
+                    r.repo,
+                    repo.host.as_str(),
+                    r.branch,
+                    r.commit_sha.as_deref().unwrap_or("none")
+                );
+                crate::audit::record(&conn, crate::audit::SKILL_PUSHED, Some(&skill_name), &summary);
+            }
+        }
+        Err(e) => log::error!("[push_skill_to_host] failed: {}", e),
+    }
+    result
+}
+
+// ---------------------------------------------------------------------------
+// Upstream reviews
+// ---------------------------------------------------------------------------
+
+/// Reviews opened by branch-per-skill pushes, as last seen. Skills whose
+/// review is still `open` have changes pending upstream.
+#[tauri::command]
+pub fn list_upstream_reviews(db: tauri::State<'_, Db>) -> Result<Vec<SkillUpstreamReview>, String> {
+    log::info!("[list_upstream_reviews]");
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[list_upstream_reviews] failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::list_upstream_reviews(&conn)
+}
+
+/// Ask each host for the current state of every open review and record
+/// merges and closures. Reviews whose host can't be reached keep their last
+/// known state.
+#[tauri::command]
+pub async fn refresh_upstream_reviews(db: tauri::State<'_, Db>) -> Result<Vec<SkillUpstreamReview>, String> {
+    log::info!("[refresh_upstream_reviews]");
+    super::offline::require_feature(&db, super::offline::NetworkFeature::GitHub, "refresh_upstream_reviews")?;
+    let open: Vec<SkillUpstreamReview> = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        crate::db::list_upstream_reviews(&conn)?
+    }
+    .into_iter()
+    .filter(|r| r.state == git_hosting::REVIEW_OPEN)
+    .collect();
+
+    let github = github_server(&db)?;
+    for review in open {
+        let checked: Result<String, String> = async {
+            let repo = git_hosting::parse_repo_url(&review.repo_url, &github)?;
+            let token = token_for(&db, &repo)?.ok_or_else(|| not_connected(repo.host))?;
+            let client = git_hosting::build_client(repo.host, Some(&token), &github)?;
+            Ok(git_hosting::get_review(&client, &repo, review.number).await?.state)
+        }
+        .await;
+        match checked {
+            Ok(state) if state != review.state => {
+                log::info!(
+                    "[refresh_upstream_reviews] review #{} for '{}' is now {}",
+                    review.number,
+                    review.skill_name,
+                    state
+                );
+                let conn = db.0.lock().map_err(|e| e.to_string())?;
+                crate::db::update_upstream_review_state(&conn, &review.skill_name, &state)?;
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!(
+                "[refresh_upstream_reviews] could not check review for '{}': {}",
+                review.skill_name,
+                e
+            ),
+        }
+    }
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    crate::db::list_upstream_reviews(&conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_push_strategy() {
+        assert!(!parse_push_strategy(None).unwrap());
+        assert!(!parse_push_strategy(Some("direct")).unwrap());
+        assert!(parse_push_strategy(Some("branch-per-skill")).unwrap());
+        assert!(parse_push_strategy(Some("fork")).unwrap_err().contains("Unknown push strategy"));
+    }
+
+    #[test]
+    fn test_upstream_review_tracking_follows_state_and_rename() {
+        let conn = crate::commands::test_utils::create_test_db();
+        let review = SkillUpstreamReview {
+            skill_name: "standup".to_string(),
+            host: "github".to_string(),
+            repo: "acme/skills".to_string(),
+            repo_url: "https://github.com/acme/skills".to_string(),
+            branch: "skill/standup".to_string(),
+            base_branch: "main".to_string(),
+            number: 12,
+            url: "https://github.com/acme/skills/pull/12".to_string(),
+            state: git_hosting::REVIEW_OPEN.to_string(),
+            commit_sha: Some("abc123".to_string()),
+            pushed_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        crate::db::upsert_upstream_review(&conn, &review).unwrap();
+        // A second push replaces the row rather than adding one
+        crate::db::upsert_upstream_review(&conn, &SkillUpstreamReview { number: 13, ..review.clone() }).unwrap();
+        crate::db::update_upstream_review_state(&conn, "standup", git_hosting::REVIEW_MERGED).unwrap();
+
+        let reviews = crate::db::list_upstream_reviews(&conn).unwrap();
+        assert_eq!(reviews.len(), 1);
+        assert_eq!(reviews[0].number, 13);
+        assert_eq!(reviews[0].state, git_hosting::REVIEW_MERGED);
+    }
+
+    #[test]
+    fn test_token_is_only_sent_to_the_account_host() {
+        let github = GitHubServer::default();
+        let account = || Some(("glpat-secret".to_string(), "https://gitlab.com/api/v4".to_string()));
+
+        let own = git_hosting::parse_repo_url("https://gitlab.com/acme/skills", &github).unwrap();
+        assert_eq!(token_for_repo(account(), &own).as_deref(), Some("glpat-secret"));
+
+        let other = git_hosting::parse_repo_url("https://gitlab.evil.example/a/b", &github).unwrap();
+        assert_eq!(other.host, GitHost::GitLab);
+        assert_eq!(token_for_repo(account(), &other), None);
+
+        let self_managed = Some((
+            "glpat-secret".to_string(),
+            git_hosting::gitlab_api_base("https://gitlab.acme.internal").unwrap(),
+        ));
+        let repo = git_hosting::parse_repo_url("https://gitlab.acme.internal/team/skills", &github).unwrap();
+        assert_eq!(token_for_repo(self_managed.clone(), &repo).as_deref(), Some("glpat-secret"));
+        assert_eq!(token_for_repo(self_managed, &own), None);
+    }
+
+    #[test]
+    fn test_reconcile_manifest_creates_missing_manifest() {
+        let content = reconcile_manifest(None, "team-skills", "skills/standup", None)
+            .unwrap()
+            .expect("missing manifest should be created");
+        let doc: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(doc["name"], "team-skills");
+        assert_eq!(doc["plugins"][0]["source"], "./");
+    }
+
+    #[test]
+    fn test_reconcile_manifest_leaves_covering_manifest_alone() {
+        let existing = r#"{"name":"team","plugins":[{"name":"team","source":"./"}]}"#;
+        assert!(reconcile_manifest(Some(existing), "team", "plugins/skills/standup", Some("plugins"))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_reconcile_manifest_adds_plugin_and_keeps_existing_entries() {
+        let existing = r#"{"name":"team","owner":{"name":"Acme"},"plugins":[{"name":"team","source":"./engineering"}]}"#;
+        let content = reconcile_manifest(Some(existing), "team", "skills/standup", None)
+            .unwrap()
+            .expect("uncovered skill should add a plugin");
+        let doc: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(doc["owner"]["name"], "Acme");
+        assert_eq!(doc["plugins"].as_array().unwrap().len(), 2);
+        assert_eq!(doc["plugins"][1]["name"], "team-skills");
+        assert_eq!(doc["plugins"][1]["source"], "./");
+    }
+
+    #[test]
+    fn test_collect_skill_files_skips_hidden_and_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("SKILL.md"), "---\nname: a\n---\n").unwrap();
+        std::fs::create_dir_all(dir.path().join("references")).unwrap();
+        std::fs::write(dir.path().join("references/guide.md"), "guide").unwrap();
+        std::fs::write(dir.path().join(".DS_Store"), "x").unwrap();
+        std::fs::write(dir.path().join("logo.png"), [0xff, 0xfe, 0x00]).unwrap();
+
+        let (files, skipped) = collect_skill_files(dir.path()).unwrap();
+        let paths: Vec<&str> = files.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(paths, vec!["SKILL.md", "references/guide.md"]);
+        assert_eq!(skipped, vec![".DS_Store", "logo.png"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_hosted_skills_reads_gitlab_catalog() {
+        let mut server = mockito::Server::new_async().await;
+        let base = "/projects/acme%2Fskills/repository";
+        let _manifest = server
+            .mock("GET", format!("{base}/files/.claude-plugin%2Fmarketplace.json/raw?ref=main").as_str())
+            .with_status(200)
+            .with_body(r#"{"name":"acme","plugins":[{"name":"acme","source":"./"}]}"#)
+            .create_async()
+            .await;
+        let _tree = server
+            .mock("GET", format!("{base}/tree?recursive=true&per_page=100&ref=main&page=1").as_str())
+            .with_status(200)
+            .with_body(
+                r#"[{"type":"blob","path":"skills/standup/SKILL.md"},{"type":"tree","path":"skills"}]"#,
+            )
+            .create_async()
+            .await;
+        let _skill = server
+            .mock("GET", format!("{base}/files/skills%2Fstandup%2FSKILL.md/raw?ref=main").as_str())
+            .with_status(200)
+            .with_body("---\nname: standup\ndescription: Daily standup notes\nversion: 1.2.0\n---\n")
+            .create_async()
+            .await;
+        let _plugin = server
+            .mock("GET", format!("{base}/files/.claude-plugin%2Fplugin.json/raw?ref=main").as_str())
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let mut repo =
+            git_hosting::parse_repo_url("https://gitlab.com/acme/skills/-/tree/main", &GitHubServer::default()).unwrap();
+        repo.api_base = server.url();
+        let skills = list_hosted_skills_inner(&reqwest::Client::new(), &repo)
+            .await
+            .unwrap();
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[0].name, "standup");
+        assert_eq!(skills[0].path, "skills/standup");
+        assert_eq!(skills[0].version.as_deref(), Some("1.2.0"));
+        assert_eq!(skills[0].plugin_name, None);
+    }
+}
@@ -1,19 +1,50 @@
 use crate::db::Db;
+use crate::github::GitHubServer;
 use crate::types::{DeviceFlowResponse, GitHubAuthResult, GitHubUser};
 
 const GITHUB_CLIENT_ID: &str = "Ov23lioPbQz4gAFxEfhM";
 
+/// The server to sign in to, a client for it, and the OAuth app client ID.
+/// The built-in OAuth app only exists on github.com, so an enterprise server
+/// needs one registered there (`github_oauth_client_id`).
+fn sign_in_target(db: &Db) -> Result<(GitHubServer, reqwest::Client, String), String> {
+    let server = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        GitHubServer::from_settings(&crate::db::read_settings(&conn)?)?
+    };
+    let client_id = match (&server.oauth_client_id, server.is_enterprise()) {
+        (Some(id), _) => id.clone(),
+        (None, false) => GITHUB_CLIENT_ID.to_string(),
+        (None, true) => {
+            return Err(format!(
+                "Signing in to {} needs the client ID of an OAuth app registered there. \
+                 Add it under GitHub Enterprise Server in Settings.",
+                server.host()
+            ))
+        }
+    };
+    let client = server
+        .client_builder()?
+        .build()
+        .map_err(|e| format!("Failed to build GitHub client: {}", e))?;
+    Ok((server, client, client_id))
+}
+
 /// Start the GitHub Device Flow by requesting a device code.
 #[tauri::command]
-pub async fn github_start_device_flow() -> Result<DeviceFlowResponse, String> {
+pub async fn github_start_device_flow(db: tauri::State<'_, Db>) -> Result<DeviceFlowResponse, String> {
     log::info!("[github_start_device_flow] starting device flow");
-    let client = reqwest::Client::new();
+    super::offline::require_feature(&db, super::offline::NetworkFeature::GitHub, "github_start_device_flow")?;
+    let (server, client, client_id) = sign_in_target(&db).map_err(|e| {
+        log::error!("[github_start_device_flow] {}", e);
+        e
+    })?;
 
     let response = client
-        .post("https://github.com/login/device/code")
+        .post(server.device_code_url())
         .header("Accept", "application/json")
         .form(&[
-            ("client_id", GITHUB_CLIENT_ID),
+            ("client_id", client_id.as_str()),
             ("scope", "repo,read:user"),
         ])
         .send()
@@ -69,13 +100,17 @@ pub async fn github_poll_for_token(
     device_code: String,
 ) -> Result<GitHubAuthResult, String> {
     log::info!("[github_poll_for_token] polling for token");
-    let client = reqwest::Client::new();
+    super::offline::require_feature(&db, super::offline::NetworkFeature::GitHub, "github_poll_for_token")?;
+    let (server, client, client_id) = sign_in_target(&db).map_err(|e| {
+        log::error!("[github_poll_for_token] {}", e);
+        e
+    })?;
 
     let response = client
-        .post("https://github.com/login/oauth/access_token")
+        .post(server.access_token_url())
         .header("Accept", "application/json")
         .form(&[
-            ("client_id", GITHUB_CLIENT_ID),
+            ("client_id", client_id.as_str()),
             ("device_code", device_code.as_str()),
             (
                 "grant_type",
@@ -112,7 +147,7 @@ pub async fn github_poll_for_token(
         .to_string();
 
     // Fetch user profile
-    let user = fetch_github_user(&client, &access_token).await?;
+    let user = fetch_github_user(&client, &server.api_base, &access_token).await?;
 
     // Save token and user profile to DB
     {
@@ -122,6 +157,7 @@ pub async fn github_poll_for_token(
         settings.github_user_avatar = Some(user.avatar_url.clone());
         settings.github_user_email = user.email.clone();
         settings.github_oauth_token = Some(access_token);
+        settings.github_oauth_host = Some(server.web_base.clone());
         crate::db::write_settings(&conn, &settings)?;
     }
 
@@ -164,6 +200,7 @@ pub fn github_logout(db: tauri::State<'_, Db>) -> Result<(), String> {
     })?;
     let mut settings = crate::db::read_settings(&conn)?;
     settings.github_oauth_token = None;
+    settings.github_oauth_host = None;
     settings.github_user_login = None;
     settings.github_user_avatar = None;
     settings.github_user_email = None;
@@ -174,10 +211,11 @@ pub fn github_logout(db: tauri::State<'_, Db>) -> Result<(), String> {
 /// Fetch the authenticated user's profile from GitHub.
 async fn fetch_github_user(
     client: &reqwest::Client,
+    api_base: &str,
     token: &str,
 ) -> Result<GitHubUser, String> {
     let response = client
-        .get("https://api.github.com/user")
+        .get(format!("{}/user", api_base))
         .header("Authorization", format!("Bearer {}", token))
         .header("Accept", "application/vnd.github+json")
         .header("User-Agent", "SkillBuilder")
@@ -1,10 +1,19 @@
+use crate::commands::marketplace_catalog::{
+    catalog_cache_key, is_stale, refresh_catalog, spawn_background_refresh, CATALOG_TTL_SECS,
+};
+use crate::commands::import_progress::ImportProgressReporter;
 use crate::db::Db;
-use crate::types::{AvailableSkill, GitHubRepoInfo, ImportedSkill, MarketplaceJson};
+use crate::github::GitHubServer;
+use crate::types::{AvailableSkill, CachedCatalog, GitHubRepoInfo, ImportedSkill, MarketplaceJson};
+use futures::StreamExt;
 use sha2::Digest;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 
+/// SKILL.md files fetched at once while listing a repo.
+const SKILL_MD_FETCH_CONCURRENCY: usize = 16;
+
 /// Returns true if `marketplace` is strictly newer than `installed` by semver rules.
 /// Returns false if either value fails to parse (avoids false positives for non-standard version strings).
 fn semver_gt(marketplace: &str, installed: &str) -> bool {
@@ -43,10 +52,11 @@ fn merge_imported_fields(skill: &mut ImportedSkill, existing: &ImportedSkill) {
 /// Fetch the default branch name for a GitHub repo via the API.
 pub(crate) async fn get_default_branch(
     client: &reqwest::Client,
+    server: &GitHubServer,
     owner: &str,
     repo: &str,
 ) -> Result<String, String> {
-    let url = format!("https://api.github.com/repos/{}/{}", owner, repo);
+    let url = format!("{}/repos/{}/{}", server.api_base, owner, repo);
     let response = client
         .get(&url)
         .send()
@@ -74,17 +84,29 @@ pub(crate) async fn get_default_branch(
 /// `import_marketplace_to_library`.
 async fn fetch_repo_tree(
     client: &reqwest::Client,
+    server: &GitHubServer,
     owner: &str,
     repo: &str,
     fallback_branch: &str,
 ) -> Result<(String, Vec<serde_json::Value>), String> {
-    let branch = get_default_branch(client, owner, repo)
+    let branch = get_default_branch(client, server, owner, repo)
         .await
         .unwrap_or_else(|_| fallback_branch.to_string());
+    let (_, tree) = fetch_tree(client, server, owner, repo, &branch).await?;
+    Ok((branch, tree))
+}
 
+/// Fetch the full recursive git tree of `branch`, with the root tree SHA.
+async fn fetch_tree(
+    client: &reqwest::Client,
+    server: &GitHubServer,
+    owner: &str,
+    repo: &str,
+    branch: &str,
+) -> Result<(String, Vec<serde_json::Value>), String> {
     let tree_url = format!(
-        "https://api.github.com/repos/{}/{}/git/trees/{}?recursive=1",
-        owner, repo, branch
+        "{}/repos/{}/{}/git/trees/{}?recursive=1",
+        server.api_base, owner, repo, branch
     );
 
     let response = client
@@ -108,13 +130,18 @@ async fn fetch_repo_tree(
         .as_array()
         .ok_or("Invalid tree response: missing 'tree' array")?
         .clone();
+    let sha = body["sha"].as_str().unwrap_or_default().to_string();
 
-    Ok((branch, tree))
+    Ok((sha, tree))
 }
 
-/// Build a `reqwest::Client` with standard GitHub API headers.
-/// If an OAuth token is available in settings, it is included as a Bearer token.
-pub(crate) fn build_github_client(token: Option<&str>) -> reqwest::Client {
+/// Build a `reqwest::Client` with standard GitHub API headers and `server`'s
+/// TLS overrides. If an OAuth token is available in settings, it is included
+/// as a Bearer token.
+pub(crate) fn build_github_client(
+    token: Option<&str>,
+    server: &GitHubServer,
+) -> Result<reqwest::Client, String> {
     let mut headers = reqwest::header::HeaderMap::new();
     headers.insert("Accept", "application/vnd.github+json".parse().unwrap());
     headers.insert("User-Agent", "SkillBuilder".parse().unwrap());
@@ -124,12 +151,13 @@ pub(crate) fn build_github_client(token: Option<&str>) -> reqwest::Client {
             headers.insert("Authorization", val);
         }
     }
-    reqwest::Client::builder()
+    server
+        .client_builder()?
         .default_headers(headers)
         .timeout(std::time::Duration::from_secs(30))
         .connect_timeout(std::time::Duration::from_secs(10))
         .build()
-        .unwrap_or_else(|_| reqwest::Client::new())
+        .map_err(|e| format!("Failed to build GitHub client: {}", e))
 }
 
 // ---------------------------------------------------------------------------
@@ -145,10 +173,24 @@ pub(crate) fn build_github_client(token: Option<&str>) -> reqwest::Client {
 /// - `github.com/owner/repo`
 /// - `owner/repo`
 /// - `owner/repo#branch`
+///
+/// With a GitHub Enterprise Server configured, its URLs are accepted too.
 #[tauri::command]
-pub fn parse_github_url(url: String) -> Result<GitHubRepoInfo, String> {
+pub fn parse_github_url(url: String, db: tauri::State<'_, Db>) -> Result<GitHubRepoInfo, String> {
     log::info!("[parse_github_url] url={}", url);
-    parse_github_url_inner(&url)
+    let server = {
+        let conn = db.0.read().map_err(|e| {
+            log::error!("[parse_github_url] failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        GitHubServer::from_settings(&crate::db::read_settings(&conn)?)?
+    };
+    parse_github_url_for(&url, &server)
+}
+
+/// `parse_github_url_inner` for a URL on `server`.
+pub(crate) fn parse_github_url_for(url: &str, server: &GitHubServer) -> Result<GitHubRepoInfo, String> {
+    parse_github_url_inner(server.repo_path(url)?)
 }
 
 pub(crate) fn parse_github_url_inner(url: &str) -> Result<GitHubRepoInfo, String> {
@@ -244,7 +286,7 @@ pub(crate) fn parse_github_url_inner(url: &str) -> Result<GitHubRepoInfo, String
 ///
 /// With subpath:    `plugins/.claude-plugin/marketplace.json`
 /// Without subpath: `.claude-plugin/marketplace.json`
-fn marketplace_manifest_path(subpath: Option<&str>) -> String {
+pub(crate) fn marketplace_manifest_path(subpath: Option<&str>) -> String {
     match subpath {
         Some(sp) => format!("{}/.claude-plugin/marketplace.json", sp),
         None => ".claude-plugin/marketplace.json".to_string(),
@@ -263,7 +305,7 @@ fn marketplace_manifest_path(subpath: Option<&str>) -> String {
 /// that occurs when the repo's default branch is not "main".
 ///
 /// After confirming the repo is accessible it fetches
-/// `.claude-plugin/marketplace.json` via `raw.githubusercontent.com` and
+/// `.claude-plugin/marketplace.json` via the server's raw file URL and
 /// returns a clear error if the file is missing or not valid JSON.
 /// Returns the `name` field from `.claude-plugin/marketplace.json`, falling back
 /// to `"{owner}/{repo}"` if the field is absent.
@@ -273,27 +315,25 @@ pub async fn check_marketplace_url(
     url: String,
 ) -> Result<String, String> {
     log::info!("[check_marketplace_url] url={}", url);
-    let repo_info = parse_github_url_inner(&url)?;
-    let token = {
+    super::offline::require_feature(&db, super::offline::NetworkFeature::Marketplace, "check_marketplace_url")?;
+    let (server, token) = {
         let conn = db.0.lock().map_err(|e| {
             log::error!("[check_marketplace_url] failed to acquire DB lock: {}", e);
             e.to_string()
         })?;
         let settings = crate::db::read_settings_hydrated(&conn)?;
-        settings.github_oauth_token.clone()
+        (GitHubServer::from_settings(&settings)?, settings.github_oauth_token.clone())
     };
-    let client = build_github_client(token.as_deref());
+    let repo_info = parse_github_url_for(&url, &server)?;
+    let client = build_github_client(token.as_deref(), &server)?;
     let owner = &repo_info.owner;
     let repo = &repo_info.repo;
-    let resolved_branch = get_default_branch(&client, owner, repo).await?;
+    let resolved_branch = get_default_branch(&client, &server, owner, repo).await?;
 
     // Verify that .claude-plugin/marketplace.json exists and is valid JSON.
     // Respect any subpath in the URL (e.g. /tree/main/plugins → plugins/.claude-plugin/marketplace.json).
     let manifest_path = marketplace_manifest_path(repo_info.subpath.as_deref());
-    let raw_url = format!(
-        "https://raw.githubusercontent.com/{}/{}/{}/{}",
-        owner, repo, resolved_branch, manifest_path
-    );
+    let raw_url = server.raw_url(owner, repo, &resolved_branch, &manifest_path);
     log::info!(
         "[check_marketplace_url] fetching marketplace.json from {}/{} branch={}",
         owner,
@@ -505,7 +545,7 @@ pub(crate) fn discover_skills_from_catalog(
 /// - `"engineering/skills/standup"` → `"engineering"`
 /// - `"plugins/eng/skills/standup"` → `"plugins/eng"`
 /// - `"skills/standup"` → `""` (root plugin: `skills/` is at the repo root)
-fn extract_plugin_path(skill_path: &str) -> &str {
+pub(crate) fn extract_plugin_path(skill_path: &str) -> &str {
     if let Some(idx) = skill_path.find("/skills/") {
         &skill_path[..idx]
     } else {
@@ -520,13 +560,21 @@ fn extract_plugin_path(skill_path: &str) -> &str {
 // ---------------------------------------------------------------------------
 
 /// Fetch the repo tree and find all SKILL.md files, returning metadata for each.
+///
+/// Cache-first: a cached listing is returned immediately and, when older than
+/// the catalog TTL, refreshed in the background (`marketplace-catalog-updated`
+/// fires if it changed). Only a cache miss waits on the network; with an
+/// `operation_id` it reports progress and can be cancelled (see `import_progress`),
+/// returning the skills read so far uncached.
 #[tauri::command]
 pub async fn list_github_skills(
+    app: tauri::AppHandle,
     db: tauri::State<'_, Db>,
     owner: String,
     repo: String,
     branch: String,
     subpath: Option<String>,
+    operation_id: Option<String>,
 ) -> Result<Vec<AvailableSkill>, String> {
     log::info!(
         "[list_github_skills] owner={} repo={} branch={} subpath={:?}",
@@ -535,49 +583,161 @@ pub async fn list_github_skills(
         branch,
         subpath
     );
-    // Read OAuth token if available
-    let token = {
+    super::offline::require_feature(&db, super::offline::NetworkFeature::GitHub, "list_github_skills")?;
+    // Read the server and OAuth token if available
+    let (server, token) = {
         let conn = db.0.lock().map_err(|e| {
             log::error!("[list_github_skills] failed to acquire DB lock: {}", e);
             e.to_string()
         })?;
         let settings = crate::db::read_settings_hydrated(&conn)?;
-        settings.github_oauth_token.clone()
+        (GitHubServer::from_settings(&settings)?, settings.github_oauth_token.clone())
     };
 
-    let (_, skills) =
-        list_github_skills_inner(&owner, &repo, &branch, subpath.as_deref(), token.as_deref())
-            .await?;
-    Ok(skills)
+    let cache_key = catalog_cache_key(&server, &owner, &repo, &branch, subpath.as_deref());
+    let cached = {
+        let conn = db.0.lock().map_err(|e| {
+            log::error!("[list_github_skills] failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        crate::db::get_cached_catalog(&conn, &cache_key).unwrap_or_else(|e| {
+            log::warn!("[list_github_skills] ignoring unreadable cache for {}: {}", cache_key, e);
+            None
+        })
+    };
+
+    if let Some(cached) = cached {
+        let stale = is_stale(&cached.fetched_at, chrono::Utc::now(), CATALOG_TTL_SECS);
+        log::info!(
+            "[list_github_skills] serving {} skills from cache for {} (stale={})",
+            cached.skills.len(),
+            cache_key,
+            stale
+        );
+        if stale {
+            spawn_background_refresh(app, server, owner, repo, branch, subpath, token);
+        }
+        return Ok(cached.skills);
+    }
+
+    let progress = operation_id
+        .as_deref()
+        .map(|id| ImportProgressReporter::start(&app, id));
+    let result = refresh_catalog(
+        &db,
+        &server,
+        &owner,
+        &repo,
+        &branch,
+        subpath.as_deref(),
+        token.as_deref(),
+        progress.as_ref(),
+    )
+    .await;
+    if let Some(progress) = &progress {
+        progress.finish();
+    }
+    let catalog = result?.ok_or_else(|| format!("Catalog for {} was not cached after fetch", cache_key))?;
+    Ok(catalog.skills)
 }
 
 pub(crate) async fn list_github_skills_inner(
+    server: &GitHubServer,
     owner: &str,
     repo: &str,
     branch: &str,
     subpath: Option<&str>,
     token: Option<&str>,
 ) -> Result<(Option<String>, Vec<AvailableSkill>), String> {
-    let client = build_github_client(token);
+    match list_github_skills_conditional(server, owner, repo, branch, subpath, token, None, None).await? {
+        CatalogFetch::Fetched {
+            registry_name,
+            skills,
+            ..
+        } => Ok((registry_name, skills)),
+        // Unreachable without a previous listing, but keep the caller total.
+        CatalogFetch::NotModified => Ok((None, Vec::new())),
+    }
+}
+
+/// Result of a conditional catalog fetch.
+pub(crate) enum CatalogFetch {
+    /// The repository tree matched the previous listing's — the cached catalog is current.
+    NotModified,
+    Fetched {
+        tree_sha: Option<String>,
+        /// Blob SHA of `marketplace.json` and of each listed SKILL.md, by repo path.
+        blob_shas: BTreeMap<String, String>,
+        registry_name: Option<String>,
+        skills: Vec<AvailableSkill>,
+        /// False when the listing was cancelled part-way; `skills` holds
+        /// those read before the cancel.
+        complete: bool,
+    },
+}
+
+/// A catalog entry's SKILL.md, as read for a listing.
+enum SkillMd {
+    /// Blob unchanged since the previous listing; its entry is reused.
+    Unchanged,
+    Fetched(String),
+    Missing,
+}
+
+/// Like `list_github_skills_inner`, but revalidates against `previous`: an
+/// unchanged tree SHA skips every file fetch, and a SKILL.md whose blob SHA is
+/// unchanged keeps its previous entry unless `marketplace.json` changed.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn list_github_skills_conditional(
+    server: &GitHubServer,
+    owner: &str,
+    repo: &str,
+    branch: &str,
+    subpath: Option<&str>,
+    token: Option<&str>,
+    previous: Option<&CachedCatalog>,
+    progress: Option<&ImportProgressReporter>,
+) -> Result<CatalogFetch, String> {
+    let client = build_github_client(token, server)?;
 
     // Resolve the actual default branch when the caller passed a placeholder.
     let resolved_branch = if branch.is_empty() {
-        get_default_branch(&client, owner, repo)
+        get_default_branch(&client, server, owner, repo)
             .await
             .unwrap_or_else(|_| "main".to_string())
     } else {
-        get_default_branch(&client, owner, repo)
+        get_default_branch(&client, server, owner, repo)
             .await
             .unwrap_or_else(|_| branch.to_string())
     };
 
-    // Fetch .claude-plugin/marketplace.json via raw.githubusercontent.com.
+    // Fetch the repo tree first: its SHA tells whether anything changed at all,
+    // and it lists the skill directories.
+    let (tree_sha, tree) = fetch_tree(&client, server, owner, repo, &resolved_branch).await?;
+    if let Some(p) = progress {
+        p.update(|s| {
+            s.repos_scanned += 1;
+            s.tree_entries = tree.len();
+        });
+    }
+    if !tree_sha.is_empty() && previous.and_then(|c| c.tree_sha.as_deref()) == Some(tree_sha.as_str()) {
+        log::debug!(
+            "[list_github_skills_inner] tree unchanged for {}/{}",
+            owner,
+            repo
+        );
+        return Ok(CatalogFetch::NotModified);
+    }
+    let blob_shas: HashMap<&str, &str> = tree
+        .iter()
+        .filter(|entry| entry["type"].as_str() == Some("blob"))
+        .filter_map(|entry| Some((entry["path"].as_str()?, entry["sha"].as_str()?)))
+        .collect();
+
+    // Fetch .claude-plugin/marketplace.json via the server's raw file URL.
     // Respect any subpath in the URL (e.g. /tree/main/plugins → plugins/.claude-plugin/marketplace.json).
     let manifest_path = marketplace_manifest_path(subpath);
-    let raw_url = format!(
-        "https://raw.githubusercontent.com/{}/{}/{}/{}",
-        owner, repo, resolved_branch, manifest_path
-    );
+    let raw_url = server.raw_url(owner, repo, &resolved_branch, &manifest_path);
 
     log::info!(
         "[list_github_skills_inner] fetching marketplace.json from {}/{} branch={}",
@@ -633,9 +793,6 @@ pub(crate) async fn list_github_skills_inner(
         format!("Failed to parse marketplace.json: {}", e)
     })?;
 
-    // Fetch the repo tree to discover which skill directories exist.
-    let (_, tree) = fetch_repo_tree(&client, owner, repo, &resolved_branch).await?;
-
     // Build the set of directories that own a SKILL.md blob in the tree.
     let skill_dirs: std::collections::HashSet<String> = tree
         .iter()
@@ -663,16 +820,39 @@ pub(crate) async fn list_github_skills_inner(
         marketplace.name.as_deref().unwrap_or("unknown")
     );
 
-    // Fetch each skill's SKILL.md concurrently to populate version, purpose, and other frontmatter.
+    if let Some(p) = progress {
+        p.update(|s| s.skills_found = skills.len());
+        p.set_phase("reading_metadata");
+    }
+
+    // An entry is built from marketplace.json and its SKILL.md, so it can be
+    // reused only while both blobs match the previous listing.
+    let unchanged = |prev: &CachedCatalog, path: &str| {
+        blob_shas
+            .get(path)
+            .is_some_and(|sha| prev.blob_shas.get(path).map(String::as_str) == Some(*sha))
+    };
+    let reusable: HashMap<&str, &AvailableSkill> = match previous {
+        Some(prev) if unchanged(prev, &manifest_path) => prev
+            .skills
+            .iter()
+            .filter(|s| unchanged(prev, &format!("{}/SKILL.md", s.path)))
+            .map(|s| (s.path.as_str(), s))
+            .collect(),
+        _ => HashMap::new(),
+    };
+
+    // Fetch each changed skill's SKILL.md concurrently to populate version, purpose, and other frontmatter.
     let fetch_fns: Vec<_> = skills
         .iter()
         .map(|skill| {
             let client = client.clone();
-            let url = format!(
-                "https://raw.githubusercontent.com/{}/{}/{}/{}/SKILL.md",
-                owner, repo, resolved_branch, skill.path
-            );
+            let url = server.raw_url(owner, repo, &resolved_branch, &format!("{}/SKILL.md", skill.path));
+            let reuse = reusable.contains_key(skill.path.as_str());
             async move {
+                if reuse {
+                    return SkillMd::Unchanged;
+                }
                 match client
                     .get(&url)
                     .header("Cache-Control", "no-cache")
@@ -680,21 +860,44 @@ pub(crate) async fn list_github_skills_inner(
                     .send()
                     .await
                 {
-                    Ok(resp) if resp.status().is_success() => resp.text().await.ok(),
-                    _ => None,
+                    Ok(resp) if resp.status().is_success() => {
+                        resp.text().await.map_or(SkillMd::Missing, SkillMd::Fetched)
+                    }
+                    _ => SkillMd::Missing,
                 }
             }
         })
         .collect();
 
-    let contents = futures::future::join_all(fetch_fns).await;
+    // Bounded and in order, so a cancel keeps a prefix of the listing.
+    let mut contents = Vec::with_capacity(fetch_fns.len());
+    let mut fetches = std::pin::pin!(futures::stream::iter(fetch_fns).buffered(SKILL_MD_FETCH_CONCURRENCY));
+    let mut complete = true;
+    while let Some(content) = fetches.next().await {
+        contents.push(content);
+        if let Some(p) = progress {
+            p.update(|s| s.skills_processed += 1);
+            if p.is_cancelled() {
+                log::info!(
+                    "[list_github_skills_inner] cancelled after {} of {} skills in {}/{}",
+                    contents.len(),
+                    skills.len(),
+                    owner,
+                    repo
+                );
+                complete = false;
+                break;
+            }
+        }
+    }
 
     // Skill name MUST come from SKILL.md frontmatter `name:` field — no directory fallback.
     // Skills whose SKILL.md is missing or has no `name:` are excluded from results.
     let mut final_skills: Vec<AvailableSkill> = Vec::new();
-    for (mut skill, content_opt) in skills.into_iter().zip(contents) {
-        match content_opt {
-            Some(content) => {
+    for (mut skill, content) in skills.into_iter().zip(contents) {
+        match content {
+            SkillMd::Unchanged => final_skills.push(reusable[skill.path.as_str()].clone()),
+            SkillMd::Fetched(content) => {
                 let fm = super::imported_skills::parse_frontmatter_full(&content);
                 match fm.name {
                     Some(name) => {
@@ -717,7 +920,7 @@ pub(crate) async fn list_github_skills_inner(
                     }
                 }
             }
-            None => {
+            SkillMd::Missing => {
                 log::debug!(
                     "[list_github_skills_inner] skipping skill at '{}': SKILL.md could not be fetched",
                     skill.path
@@ -743,10 +946,7 @@ pub(crate) async fn list_github_skills_inner(
             } else {
                 format!("{}/.claude-plugin/plugin.json", pp)
             };
-            let url = format!(
-                "https://raw.githubusercontent.com/{}/{}/{}/{}",
-                owner, repo, resolved_branch, plugin_json_path
-            );
+            let url = server.raw_url(owner, repo, &resolved_branch, &plugin_json_path);
             let pp = pp.clone();
             async move {
                 let name = match client.get(&url).send().await {
@@ -781,7 +981,18 @@ pub(crate) async fn list_github_skills_inner(
         final_skills.len()
     );
 
-    Ok((marketplace.name.clone(), final_skills))
+    let blob_shas: BTreeMap<String, String> = std::iter::once(manifest_path)
+        .chain(final_skills.iter().map(|s| format!("{}/SKILL.md", s.path)))
+        .filter_map(|path| blob_shas.get(path.as_str()).map(|sha| (path, sha.to_string())))
+        .collect();
+
+    Ok(CatalogFetch::Fetched {
+        tree_sha: Some(tree_sha).filter(|sha| !sha.is_empty()),
+        blob_shas,
+        registry_name: marketplace.name.clone(),
+        skills: final_skills,
+        complete,
+    })
 }
 
 // ---------------------------------------------------------------------------
@@ -809,14 +1020,20 @@ pub struct WorkspaceSkillImportRequest {
 /// If a workspace_skills row with the same skill_name already exists, it is
 /// updated (version, model, domain, description, disk_path, etc.) while
 /// preserving `is_active` and `is_bundled`. New skills are inserted.
+///
+/// With an `operation_id`, progress is emitted per file and a cancel stops
+/// before the next skill, returning the skills imported so far.
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
 pub async fn import_github_skills(
+    app: tauri::AppHandle,
     db: tauri::State<'_, Db>,
     owner: String,
     repo: String,
     branch: String,
     skill_requests: Vec<WorkspaceSkillImportRequest>,
     source_url: Option<String>,
+    operation_id: Option<String>,
 ) -> Result<Vec<ImportedSkill>, String> {
     log::info!(
         "[import_github_skills] owner={} repo={} branch={} count={} source_url={:?}",
@@ -826,28 +1043,96 @@ pub async fn import_github_skills(
         skill_requests.len(),
         source_url
     );
+    super::offline::require_feature(&db, super::offline::NetworkFeature::GitHub, "import_github_skills")?;
     // Read settings
-    let (workspace_path, token) = {
+    let (workspace_path, server, token) = {
         let conn = db.0.lock().map_err(|e| {
             log::error!("[import_github_skills] failed to acquire DB lock: {}", e);
             e.to_string()
         })?;
         let settings = crate::db::read_settings_hydrated(&conn)?;
+        let server = GitHubServer::from_settings(&settings)?;
         let wp = settings
             .workspace_path
             .ok_or_else(|| "Workspace path not initialized".to_string())?;
-        (wp, settings.github_oauth_token.clone())
+        (wp, server, settings.github_oauth_token.clone())
     };
 
-    let client = build_github_client(token.as_deref());
-    let (branch, tree) = fetch_repo_tree(&client, &owner, &repo, &branch).await?;
+    let progress = operation_id
+        .as_deref()
+        .map(|id| ImportProgressReporter::start(&app, id));
+    let client = build_github_client(token.as_deref(), &server)?;
+    let result = async {
+        let (branch, tree) = fetch_repo_tree(&client, &server, &owner, &repo, &branch).await?;
+        if let Some(p) = &progress {
+            p.update(|s| {
+                s.repos_scanned += 1;
+                s.tree_entries = tree.len();
+                s.skills_found = skill_requests.len();
+            });
+            p.set_phase("downloading");
+        }
+        let raw_url = |file_path: &str| server.raw_url(&owner, &repo, &branch, file_path);
+        import_workspace_skills(
+            &db,
+            &client,
+            &raw_url,
+            &tree,
+            &workspace_path,
+            &skill_requests,
+            source_url,
+            progress.as_ref(),
+        )
+        .await
+    }
+    .await;
+    if let Some(p) = &progress {
+        p.finish();
+    }
+    if let Ok(skills) = &result {
+        let names: Vec<String> = skills.iter().map(|s| s.skill_name.clone()).collect();
+        super::skill_similarity::flag_near_duplicates(&app, &db, &names, "import_github_skills");
+    }
+    result
+}
 
-    let skills_dir = Path::new(&workspace_path).join(".claude").join("skills");
+/// Install the requested skills from a fetched repo tree into the workspace.
+///
+/// Shared by the GitHub and hosted (GitLab, Bitbucket) import commands;
+/// `raw_url` maps a repo-relative path to its raw-content URL. A cancel via
+/// `progress` stops before the next skill and keeps those already imported.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn import_workspace_skills(
+    db: &Db,
+    client: &reqwest::Client,
+    raw_url: &(dyn Fn(&str) -> String + Sync),
+    tree: &[serde_json::Value],
+    workspace_path: &str,
+    skill_requests: &[WorkspaceSkillImportRequest],
+    source_url: Option<String>,
+    progress: Option<&ImportProgressReporter>,
+) -> Result<Vec<ImportedSkill>, String> {
+
+    let skills_dir = Path::new(workspace_path).join(".claude").join("skills");
     let mut imported: Vec<ImportedSkill> = Vec::new();
     let mut errors: Vec<String> = Vec::new();
     let mut skipped: Vec<String> = Vec::new();
 
-    for req in &skill_requests {
+    for (i, req) in skill_requests.iter().enumerate() {
+        if let Some(p) = progress {
+            if p.is_cancelled() {
+                log::info!(
+                    "[import_workspace_skills] cancelled with {} of {} skills imported",
+                    imported.len(),
+                    skill_requests.len()
+                );
+                break;
+            }
+            p.update(|s| {
+                s.skills_processed = i;
+                s.current = Some(req.path.clone());
+            });
+        }
         let skill_path = &req.path;
         let purpose = req.purpose.clone();
         let metadata_override = req.metadata_override.as_ref();
@@ -872,7 +1157,7 @@ pub async fn import_github_skills(
             let inst_ver = existing_skill.version.as_deref().unwrap_or("");
             if !semver_gt(mp_ver, inst_ver) {
                 log::info!(
-                    "[import_github_skills] {} already at version {:?}, skipping (pre-download guard)",
+                    "[import_workspace_skills] {} already at version {:?}, skipping (pre-download guard)",
                     dir_name, existing_skill.version
                 );
                 skipped.push(dir_name.to_string());
@@ -883,17 +1168,15 @@ pub async fn import_github_skills(
         // Overwrite the on-disk directory if an existing installation is found.
         let should_overwrite = existing.is_some();
 
-        match import_single_skill(
-            &client,
-            "https://raw.githubusercontent.com",
-            &owner,
-            &repo,
-            &branch,
+        match import_single_skill_from(
+            client,
+            raw_url,
             skill_path,
-            &tree,
+            tree,
             &skills_dir,
             should_overwrite,
             metadata_override,
+            progress,
         )
         .await
         {
@@ -906,13 +1189,13 @@ pub async fn import_github_skills(
                     let inst_ver = existing_skill.version.as_deref().unwrap_or("");
                     if !semver_gt(mp_ver, inst_ver) {
                         log::info!(
-                            "[import_github_skills] {} already at version {:?}, skipping",
+                            "[import_workspace_skills] {} already at version {:?}, skipping",
                             skill.skill_name,
                             skill.version
                         );
                         if let Err(e) = fs::remove_dir_all(&skill.disk_path) {
                             log::warn!(
-                                "[import_github_skills] cleanup failed for {}: {}",
+                                "[import_workspace_skills] cleanup failed for {}: {}",
                                 skill.disk_path,
                                 e
                             );
@@ -940,7 +1223,7 @@ pub async fn import_github_skills(
                         skill.disable_model_invocation = existing_skill.disable_model_invocation;
                     }
                     log::info!(
-                        "[import_github_skills] upgrading {} from {:?} to {:?}",
+                        "[import_workspace_skills] upgrading {} from {:?} to {:?}",
                         skill.skill_name,
                         existing_skill.version,
                         skill.version
@@ -960,16 +1243,30 @@ pub async fn import_github_skills(
                     if let Err(e) = crate::db::upsert_workspace_skill(&conn, &ws_skill) {
                         if let Err(cleanup_err) = fs::remove_dir_all(&skill.disk_path) {
                             log::warn!(
-                                "[import_github_skills] cleanup failed after upsert error for {}: {}",
+                                "[import_workspace_skills] cleanup failed after upsert error for {}: {}",
                                 skill.disk_path, cleanup_err
                             );
                         }
                         errors.push(format!("{}: {}", skill.skill_name, e));
                     } else {
+                        match super::skill_scan::scan_imported_skill(
+                            &conn,
+                            workspace_path,
+                            &ws_skill.skill_id,
+                            &ws_skill.skill_name,
+                            &ws_skill.disk_path,
+                            ws_skill.is_active,
+                        ) {
+                            Ok(active) => ws_skill.is_active = active,
+                            Err(e) => {
+                                errors.push(format!("{}: {}", skill.skill_name, e));
+                                continue;
+                            }
+                        }
                         if ws_skill.is_active {
                             if let Err(e) = super::imported_skills::apply_import_purpose_conflict_policy(
                                 &conn,
-                                &workspace_path,
+                                workspace_path,
                                 &ws_skill.skill_id,
                                 &ws_skill.skill_name,
                                 ws_skill.purpose.as_deref(),
@@ -985,22 +1282,55 @@ pub async fn import_github_skills(
                                 &skill.skill_name,
                                 &hash,
                             ) {
-                                log::warn!("[import_github_skills] failed to set content_hash for '{}': {}", skill.skill_name, e);
+                                log::warn!("[import_workspace_skills] failed to set content_hash for '{}': {}", skill.skill_name, e);
                             }
                         }
                         imported.push(skill);
                     }
                 } else {
                     log::debug!(
-                        "[import_github_skills] inserting new workspace skill '{}'",
+                        "[import_workspace_skills] inserting new workspace skill '{}'",
                         ws_skill.skill_name
                     );
                     match crate::db::insert_workspace_skill(&conn, &ws_skill) {
                         Ok(()) => {
+                            // Marketplace imports carry no package signature.
+                            let context = super::activation_policy::context_for(
+                                &ws_skill.disk_path,
+                                ws_skill.marketplace_source_url.as_deref(),
+                                "unsigned",
+                            );
+                            match super::activation_policy::apply_activation_policy(
+                                &conn,
+                                workspace_path,
+                                &ws_skill.skill_id,
+                                &ws_skill.skill_name,
+                                &context,
+                            ) {
+                                Ok(active) => ws_skill.is_active = active,
+                                Err(e) => {
+                                    errors.push(format!("{}: {}", skill.skill_name, e));
+                                    continue;
+                                }
+                            }
+                            match super::skill_scan::scan_imported_skill(
+                                &conn,
+                                workspace_path,
+                                &ws_skill.skill_id,
+                                &ws_skill.skill_name,
+                                &ws_skill.disk_path,
+                                ws_skill.is_active,
+                            ) {
+                                Ok(active) => ws_skill.is_active = active,
+                                Err(e) => {
+                                    errors.push(format!("{}: {}", skill.skill_name, e));
+                                    continue;
+                                }
+                            }
                             if ws_skill.is_active {
                                 if let Err(e) = super::imported_skills::apply_import_purpose_conflict_policy(
                                     &conn,
-                                    &workspace_path,
+                                    workspace_path,
                                     &ws_skill.skill_id,
                                     &ws_skill.skill_name,
                                     ws_skill.purpose.as_deref(),
@@ -1016,7 +1346,7 @@ pub async fn import_github_skills(
                                     &ws_skill.skill_name,
                                     &hash,
                                 ) {
-                                    log::warn!("[import_github_skills] failed to set content_hash for '{}': {}", ws_skill.skill_name, e);
+                                    log::warn!("[import_workspace_skills] failed to set content_hash for '{}': {}", ws_skill.skill_name, e);
                                 }
                             }
                             imported.push(skill);
@@ -1040,6 +1370,13 @@ pub async fn import_github_skills(
         }
     }
 
+    if let Some(p) = progress {
+        p.update(|s| {
+            s.skills_processed = imported.len() + errors.len() + skipped.len();
+            s.current = None;
+        });
+    }
+
     if imported.is_empty() && !errors.is_empty() {
         return Err(format!("All imports failed: {}", errors.join("; ")));
     }
@@ -1050,7 +1387,7 @@ pub async fn import_github_skills(
     }
     for name in &skipped {
         log::info!(
-            "[import_github_skills] skipped '{}': already at same or newer version",
+            "[import_workspace_skills] skipped '{}': already at same or newer version",
             name
         );
     }
@@ -1058,8 +1395,8 @@ pub async fn import_github_skills(
     // Regenerate CLAUDE.md with imported skills section
     if !imported.is_empty() {
         let conn = db.0.lock().map_err(|e| e.to_string())?;
-        if let Err(e) = super::workflow::update_skills_section(&workspace_path, &conn) {
-            log::warn!("Failed to update CLAUDE.md after GitHub import: {}", e);
+        if let Err(e) = super::workflow::update_skills_section(workspace_path, &conn) {
+            log::warn!("Failed to update CLAUDE.md after skill import: {}", e);
         }
     }
 
@@ -1103,6 +1440,18 @@ pub async fn import_marketplace_to_library(
     metadata_overrides: Option<
         std::collections::HashMap<String, crate::types::SkillMetadataOverride>,
     >,
+) -> Result<Vec<MarketplaceImportResult>, String> {
+    super::offline::require_feature(&db, super::offline::NetworkFeature::Marketplace, "import_marketplace_to_library")?;
+    import_marketplace_to_library_inner(&db, &source_url, &skill_paths, metadata_overrides.as_ref()).await
+}
+
+pub(crate) async fn import_marketplace_to_library_inner(
+    db: &Db,
+    source_url: &str,
+    skill_paths: &[String],
+    metadata_overrides: Option<
+        &std::collections::HashMap<String, crate::types::SkillMetadataOverride>,
+    >,
 ) -> Result<Vec<MarketplaceImportResult>, String> {
     log::info!(
         "[import_marketplace_to_library] importing {} skills from {} (with_overrides={})",
@@ -1112,7 +1461,7 @@ pub async fn import_marketplace_to_library(
     );
 
     // Read settings
-    let (workspace_path, skills_path, token) = {
+    let (workspace_path, skills_path, server, token) = {
         let conn = db.0.lock().map_err(|e| {
             log::error!(
                 "[import_marketplace_to_library] failed to acquire DB lock: {}",
@@ -1137,11 +1486,12 @@ pub async fn import_marketplace_to_library(
             log::error!("[import_marketplace_to_library] {}", msg);
             msg
         })?;
-        (wp, sp, settings.github_oauth_token.clone())
+        let server = GitHubServer::from_settings(&settings)?;
+        (wp, sp, server, settings.github_oauth_token.clone())
     };
 
     // Parse the registry URL into owner/repo/branch
-    let repo_info = parse_github_url_inner(&source_url).map_err(|e| {
+    let repo_info = parse_github_url_for(source_url, &server).map_err(|e| {
         log::error!(
             "[import_marketplace_to_library] failed to parse source_url '{}': {}",
             source_url,
@@ -1152,8 +1502,8 @@ pub async fn import_marketplace_to_library(
     let owner = &repo_info.owner;
     let repo = &repo_info.repo;
 
-    let client = build_github_client(token.as_deref());
-    let (branch, tree) = fetch_repo_tree(&client, owner, repo, &repo_info.branch)
+    let client = build_github_client(token.as_deref(), &server)?;
+    let (branch, tree) = fetch_repo_tree(&client, &server, owner, repo, &repo_info.branch)
         .await
         .map_err(|e| {
             log::error!(
@@ -1168,13 +1518,11 @@ pub async fn import_marketplace_to_library(
     let skills_dir = Path::new(&skills_path);
     let mut results: Vec<MarketplaceImportResult> = Vec::new();
 
-    for skill_path in &skill_paths {
-        let override_ref = metadata_overrides
-            .as_ref()
-            .and_then(|m| m.get(skill_path.as_str()));
+    for skill_path in skill_paths {
+        let override_ref = metadata_overrides.and_then(|m| m.get(skill_path.as_str()));
         match import_single_skill(
             &client,
-            "https://raw.githubusercontent.com",
+            &server.raw_base,
             owner,
             repo,
             &branch,
@@ -1197,7 +1545,7 @@ pub async fn import_marketplace_to_library(
                 })?;
 
                 // Tag the skill with the registry it was imported from
-                skill.marketplace_source_url = Some(source_url.clone());
+                skill.marketplace_source_url = Some(source_url.to_string());
 
                 // Fetch existing imported skill metadata (if any) for merging on upgrade
                 let existing_imported =
@@ -1244,6 +1592,16 @@ pub async fn import_marketplace_to_library(
                     continue;
                 }
 
+                if let Err(e) =
+                    super::skill_scan::scan_library_skill(&conn, &skill.skill_name, &skill.disk_path)
+                {
+                    log::warn!(
+                        "[import_marketplace_to_library] failed to scan '{}': {}",
+                        skill.skill_name,
+                        e
+                    );
+                }
+
                 // Compute and store the content hash as the baseline for customization detection
                 if let Some(hash) = compute_skill_content_hash(&skill.disk_path) {
                     if let Err(e) =
@@ -1304,7 +1662,7 @@ pub async fn import_marketplace_to_library(
 
 /// Wrap a YAML string value in double quotes, escaping backslashes, double
 /// quotes, and newlines so that user-supplied values cannot inject extra keys.
-fn yaml_quote(s: &str) -> String {
+pub(crate) fn yaml_quote(s: &str) -> String {
     let escaped = s
         .replace('\\', "\\\\")
         .replace('"', "\\\"")
@@ -1360,6 +1718,7 @@ fn rewrite_skill_md(
     add_field("version", &fm.version);
     add_field("model", &fm.model);
     add_field("argument-hint", &fm.argument_hint);
+    add_field("review-by", &fm.review_by);
     if let Some(user_inv) = fm.user_invocable {
         yaml.push_str(&format!("user-invocable: {}\n", user_inv));
     }
@@ -1391,6 +1750,36 @@ pub(crate) async fn import_single_skill(
     skills_dir: &Path,
     overwrite: bool,
     metadata_override: Option<&crate::types::SkillMetadataOverride>,
+) -> Result<ImportedSkill, String> {
+    let raw_url = |file_path: &str| {
+        format!("{}/{}/{}/{}/{}", raw_base_url, owner, repo, branch, file_path)
+    };
+    import_single_skill_from(
+        client,
+        &raw_url,
+        skill_path,
+        tree,
+        skills_dir,
+        overwrite,
+        metadata_override,
+        None,
+    )
+    .await
+}
+
+/// Host-neutral core of `import_single_skill`: `raw_url` maps a repo-relative
+/// file path to the URL that serves its raw content. Downloaded files are
+/// counted in `progress` when given.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn import_single_skill_from(
+    client: &reqwest::Client,
+    raw_url: &(dyn Fn(&str) -> String + Sync),
+    skill_path: &str,
+    tree: &[serde_json::Value],
+    skills_dir: &Path,
+    overwrite: bool,
+    metadata_override: Option<&crate::types::SkillMetadataOverride>,
+    progress: Option<&ImportProgressReporter>,
 ) -> Result<ImportedSkill, String> {
     let prefix = if skill_path.is_empty() {
         String::new()
@@ -1446,18 +1835,7 @@ pub(crate) async fn import_single_skill(
         .unwrap_or(skill_path);
 
     // Download SKILL.md first to get frontmatter
-    let skill_md_url = format!(
-        "{}/{}/{}/{}/{}",
-        raw_base_url,
-        owner,
-        repo,
-        branch,
-        if prefix.is_empty() {
-            "SKILL.md".to_string()
-        } else {
-            format!("{}SKILL.md", prefix)
-        }
-    );
+    let skill_md_url = raw_url(&format!("{}SKILL.md", prefix));
 
     let skill_md_content = client
         .get(&skill_md_url)
@@ -1590,13 +1968,8 @@ pub(crate) async fn import_single_skill(
             }
         }
 
-        let raw_url = format!(
-            "{}/{}/{}/{}/{}",
-            raw_base_url, owner, repo, branch, file_path
-        );
-
         let response = client
-            .get(&raw_url)
+            .get(raw_url(file_path))
             .send()
             .await
             .map_err(|e| format!("Failed to download '{}': {}", file_path, e))?;
@@ -1619,6 +1992,12 @@ pub(crate) async fn import_single_skill(
 
         fs::write(&out_path, &content)
             .map_err(|e| format!("Failed to write '{}': {}", out_path.display(), e))?;
+        if let Some(p) = progress {
+            p.update(|s| {
+                s.files_downloaded += 1;
+                s.bytes_downloaded += content.len() as u64;
+            });
+        }
     }
 
     // Rewrite SKILL.md with updated frontmatter if a metadata override was applied
@@ -1801,8 +2180,9 @@ fn collect_updates_for_installed(
 #[tauri::command]
 pub async fn check_marketplace_updates(db: tauri::State<'_, Db>) -> Result<MarketplaceUpdateResult, String> {
     log::info!("[check_marketplace_updates] checking all enabled registries");
+    super::offline::require_feature(&db, super::offline::NetworkFeature::Marketplace, "check_marketplace_updates")?;
 
-    let (token, enabled_sources, library_rows, workspace_rows) = {
+    let (server, token, enabled_sources, library_rows, workspace_rows) = {
         let conn = db.0.lock().map_err(|e| {
             log::error!(
                 "[check_marketplace_updates] failed to acquire DB lock: {}",
@@ -1811,6 +2191,7 @@ pub async fn check_marketplace_updates(db: tauri::State<'_, Db>) -> Result<Marke
             e.to_string()
         })?;
         let settings = crate::db::read_settings_hydrated(&conn)?;
+        let server = GitHubServer::from_settings(&settings)?;
         let enabled_sources: HashSet<String> = settings
             .marketplace_registries
             .into_iter()
@@ -1819,6 +2200,7 @@ pub async fn check_marketplace_updates(db: tauri::State<'_, Db>) -> Result<Marke
             .collect();
         let (library_rows, workspace_rows) = load_installed_marketplace_skills(&conn)?;
         (
+            server,
             settings.github_oauth_token.clone(),
             enabled_sources,
             library_rows,
@@ -1854,7 +2236,7 @@ pub async fn check_marketplace_updates(db: tauri::State<'_, Db>) -> Result<Marke
     let mut registry_names = Vec::new();
 
     for source_url in all_sources {
-        let repo_info = match parse_github_url_inner(&source_url) {
+        let repo_info = match parse_github_url_for(&source_url, &server) {
             Ok(info) => info,
             Err(err) => {
                 log::warn!(
@@ -1866,6 +2248,7 @@ pub async fn check_marketplace_updates(db: tauri::State<'_, Db>) -> Result<Marke
             }
         };
         let list_result = list_github_skills_inner(
+            &server,
             &repo_info.owner,
             &repo_info.repo,
             &repo_info.branch,
@@ -2605,6 +2988,7 @@ mod tests {
             argument_hint: None,
             user_invocable: None,
             disable_model_invocation: None,
+            review_by: None,
         };
 
         rewrite_skill_md(dir.path(), &fm).unwrap();
@@ -2647,6 +3031,7 @@ mod tests {
             argument_hint: None,
             user_invocable: None,
             disable_model_invocation: None,
+            review_by: None,
         };
 
         rewrite_skill_md(dir.path(), &fm).unwrap();
@@ -2690,6 +3075,7 @@ mod tests {
             argument_hint: None,
             user_invocable: None,
             disable_model_invocation: None,
+            review_by: None,
         };
 
         rewrite_skill_md(dir.path(), &fm).unwrap();
@@ -2810,6 +3196,7 @@ mod tests {
                 argument_hint: None,
                 user_invocable: None,
                 disable_model_invocation: None,
+                review_by: None,
             };
 
             rewrite_skill_md(dir.path(), &fm).unwrap();
@@ -3328,4 +3715,155 @@ mod tests {
             "skill dir must be written to disk"
         );
     }
+
+    // -----------------------------------------------------------------------
+    // list_github_skills_conditional — revalidation against a cached listing
+    // -----------------------------------------------------------------------
+
+    const MANIFEST: &str = r#"{"name": "Acme", "plugins": [{"name": "p", "source": "./plugins/p"}]}"#;
+
+    fn mock_github_server(url: &str) -> GitHubServer {
+        GitHubServer {
+            web_base: url.to_string(),
+            api_base: url.to_string(),
+            raw_base: url.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn tree_body(sha: &str, blobs: &[(&str, &str)]) -> String {
+        let tree: Vec<_> = blobs
+            .iter()
+            .map(|(path, sha)| serde_json::json!({"path": path, "type": "blob", "sha": sha}))
+            .collect();
+        serde_json::json!({"sha": sha, "tree": tree}).to_string()
+    }
+
+    fn previous_catalog() -> CachedCatalog {
+        let listed = |name: &str| AvailableSkill {
+            path: format!("plugins/p/skills/{}", name),
+            name: name.to_string(),
+            plugin_name: None,
+            description: None,
+            purpose: None,
+            version: Some("1.0.0".to_string()),
+            model: None,
+            argument_hint: None,
+            user_invocable: None,
+            disable_model_invocation: None,
+        };
+        CachedCatalog {
+            cache_key: "github.com/owner/repo@main".to_string(),
+            tree_sha: Some("tree-1".to_string()),
+            blob_shas: [
+                (".claude-plugin/marketplace.json", "m1"),
+                ("plugins/p/skills/a/SKILL.md", "a1"),
+                ("plugins/p/skills/b/SKILL.md", "b1"),
+            ]
+            .into_iter()
+            .map(|(path, sha)| (path.to_string(), sha.to_string()))
+            .collect(),
+            registry_name: Some("Acme".to_string()),
+            skills: vec![listed("a"), listed("b")],
+            fetched_at: "2026-01-01T00:00:00+00:00".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conditional_listing_skips_fetches_for_unchanged_tree() {
+        let mut server = mockito::Server::new_async().await;
+        let _tree = server
+            .mock("GET", "/repos/owner/repo/git/trees/main?recursive=1")
+            .with_status(200)
+            .with_body(tree_body("tree-1", &[]))
+            .create_async()
+            .await;
+        let manifest = server
+            .mock("GET", "/owner/repo/main/.claude-plugin/marketplace.json")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let previous = previous_catalog();
+        let fetch = list_github_skills_conditional(
+            &mock_github_server(&server.url()),
+            "owner",
+            "repo",
+            "main",
+            None,
+            None,
+            Some(&previous),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(matches!(fetch, CatalogFetch::NotModified));
+        manifest.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_conditional_listing_refetches_only_changed_skill_md() {
+        let mut server = mockito::Server::new_async().await;
+        let _tree = server
+            .mock("GET", "/repos/owner/repo/git/trees/main?recursive=1")
+            .with_status(200)
+            .with_body(tree_body(
+                "tree-2",
+                &[
+                    (".claude-plugin/marketplace.json", "m1"),
+                    ("plugins/p/skills/a/SKILL.md", "a1"),
+                    ("plugins/p/skills/b/SKILL.md", "b2"),
+                ],
+            ))
+            .create_async()
+            .await;
+        let _manifest = server
+            .mock("GET", "/owner/repo/main/.claude-plugin/marketplace.json")
+            .with_status(200)
+            .with_body(MANIFEST)
+            .create_async()
+            .await;
+        let unchanged_md = server
+            .mock("GET", "/owner/repo/main/plugins/p/skills/a/SKILL.md")
+            .expect(0)
+            .create_async()
+            .await;
+        let _changed_md = server
+            .mock("GET", "/owner/repo/main/plugins/p/skills/b/SKILL.md")
+            .with_status(200)
+            .with_body("---\nname: b\nversion: 2.0.0\n---\n# B\n")
+            .create_async()
+            .await;
+
+        let previous = previous_catalog();
+        let fetch = list_github_skills_conditional(
+            &mock_github_server(&server.url()),
+            "owner",
+            "repo",
+            "main",
+            None,
+            None,
+            Some(&previous),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let CatalogFetch::Fetched {
+            tree_sha,
+            blob_shas,
+            skills,
+            ..
+        } = fetch
+        else {
+            panic!("a changed tree must be refetched");
+        };
+        assert_eq!(tree_sha.as_deref(), Some("tree-2"));
+        assert_eq!(blob_shas.get("plugins/p/skills/b/SKILL.md").map(String::as_str), Some("b2"));
+        let version = |name: &str| skills.iter().find(|s| s.name == name).and_then(|s| s.version.clone());
+        assert_eq!(version("a").as_deref(), Some("1.0.0"));
+        assert_eq!(version("b").as_deref(), Some("2.0.0"));
+        unchanged_md.assert_async().await;
+    }
 }
@@ -0,0 +1,174 @@
+//! Progress events and cancellation for GitHub skill listing and import.
+//!
+//! Callers of `list_github_skills` and `import_github_skills` may pass an
+//! `operation_id`. While the operation runs, cumulative `ImportProgress`
+//! snapshots are emitted as `IMPORT_PROGRESS_EVENT` (at most every
+//! `EMIT_INTERVAL`, plus every phase change), and `cancel_github_import`
+//! stops it at the next skill boundary. A cancelled operation returns what it
+//! finished: the skills listed or imported so far.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::Emitter;
+
+use crate::types::ImportProgress;
+
+pub const IMPORT_PROGRESS_EVENT: &str = "github-import-progress";
+const EMIT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Cancellation flags of running operations, keyed by operation id.
+static OPERATIONS: Mutex<Option<HashMap<String, Arc<AtomicBool>>>> = Mutex::new(None);
+
+fn register(operation_id: &str) -> Arc<AtomicBool> {
+    let flag = Arc::new(AtomicBool::new(false));
+    if let Ok(mut ops) = OPERATIONS.lock() {
+        ops.get_or_insert_with(HashMap::new)
+            .insert(operation_id.to_string(), flag.clone());
+    }
+    flag
+}
+
+fn unregister(operation_id: &str, flag: &Arc<AtomicBool>) {
+    if let Ok(mut ops) = OPERATIONS.lock() {
+        if let Some(ops) = ops.as_mut() {
+            // A newer operation may have reused the id; leave its flag alone.
+            if ops.get(operation_id).is_some_and(|f| Arc::ptr_eq(f, flag)) {
+                ops.remove(operation_id);
+            }
+        }
+    }
+}
+
+fn cancel(operation_id: &str) -> Result<(), String> {
+    let ops = OPERATIONS.lock().map_err(|e| e.to_string())?;
+    let flag = ops
+        .as_ref()
+        .and_then(|ops| ops.get(operation_id))
+        .ok_or_else(|| format!("No running import with id '{}'", operation_id))?;
+    flag.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+fn due(last_emit: Option<Instant>, now: Instant) -> bool {
+    last_emit.is_none_or(|last| now.duration_since(last) >= EMIT_INTERVAL)
+}
+
+/// Progress of one listing or import, registered for cancellation while it
+/// lives.
+pub(crate) struct ImportProgressReporter {
+    app: tauri::AppHandle,
+    cancelled: Arc<AtomicBool>,
+    state: Mutex<(ImportProgress, Option<Instant>)>,
+}
+
+impl ImportProgressReporter {
+    pub(crate) fn start(app: &tauri::AppHandle, operation_id: &str) -> Self {
+        let progress = ImportProgress {
+            operation_id: operation_id.to_string(),
+            phase: "discovering".to_string(),
+            ..Default::default()
+        };
+        let reporter = Self {
+            app: app.clone(),
+            cancelled: register(operation_id),
+            state: Mutex::new((progress, None)),
+        };
+        reporter.emit(true);
+        reporter
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Apply `f` to the progress and emit it if the last emit is old enough.
+    pub(crate) fn update(&self, f: impl FnOnce(&mut ImportProgress)) {
+        if let Ok(mut state) = self.state.lock() {
+            f(&mut state.0);
+        }
+        self.emit(false);
+    }
+
+    pub(crate) fn set_phase(&self, phase: &str) {
+        if let Ok(mut state) = self.state.lock() {
+            state.0.phase = phase.to_string();
+        }
+        self.emit(true);
+    }
+
+    /// Emit the final snapshot: `cancelled` if a cancel arrived, else `finished`.
+    pub(crate) fn finish(&self) {
+        self.set_phase(if self.is_cancelled() { "cancelled" } else { "finished" });
+    }
+
+    fn emit(&self, force: bool) {
+        let now = Instant::now();
+        let snapshot = match self.state.lock() {
+            Ok(mut state) if force || due(state.1, now) => {
+                state.1 = Some(now);
+                state.0.clone()
+            }
+            _ => return,
+        };
+        if let Err(e) = self.app.emit(IMPORT_PROGRESS_EVENT, &snapshot) {
+            log::warn!("Failed to emit {}: {}", IMPORT_PROGRESS_EVENT, e);
+        }
+    }
+}
+
+impl Drop for ImportProgressReporter {
+    fn drop(&mut self) {
+        if let Ok(state) = self.state.lock() {
+            unregister(&state.0.operation_id, &self.cancelled);
+        }
+    }
+}
+
+/// Stop a running `list_github_skills` or `import_github_skills` call at its
+/// next skill boundary. It returns the skills it finished.
+#[tauri::command]
+pub fn cancel_github_import(operation_id: String) -> Result<(), String> {
+    log::info!("[cancel_github_import] operation={}", operation_id);
+    cancel(&operation_id).map_err(|e| {
+        log::warn!("[cancel_github_import] {}", e);
+        e
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_sets_flag_of_registered_operation_only() {
+        let flag = register("op-cancel-test");
+        assert!(cancel("op-unknown-test").is_err());
+        assert!(!flag.load(Ordering::SeqCst));
+        cancel("op-cancel-test").unwrap();
+        assert!(flag.load(Ordering::SeqCst));
+
+        unregister("op-cancel-test", &flag);
+        assert!(cancel("op-cancel-test").is_err());
+    }
+
+    #[test]
+    fn test_unregister_keeps_newer_operation_with_same_id() {
+        let old = register("op-reused-test");
+        let new = register("op-reused-test");
+        unregister("op-reused-test", &old);
+        cancel("op-reused-test").unwrap();
+        assert!(new.load(Ordering::SeqCst));
+        unregister("op-reused-test", &new);
+    }
+
+    #[test]
+    fn test_emits_are_throttled() {
+        let now = Instant::now();
+        assert!(due(None, now));
+        assert!(!due(Some(now), now + Duration::from_millis(100)));
+        assert!(due(Some(now), now + EMIT_INTERVAL));
+    }
+}
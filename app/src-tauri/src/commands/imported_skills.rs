@@ -1,6 +1,7 @@
 use crate::db::Db;
 use crate::types::WorkspaceSkill;
 use rusqlite::OptionalExtension;
+use std::collections::HashMap;
 use std::fs;
 use std::io::Read;
 use std::path::Path;
@@ -37,6 +38,7 @@ pub(crate) struct Frontmatter {
     pub argument_hint: Option<String>,
     pub user_invocable: Option<bool>,
     pub disable_model_invocation: Option<bool>,
+    pub review_by: Option<String>,
 }
 
 /// Parse YAML frontmatter from SKILL.md content.
@@ -71,6 +73,7 @@ pub(crate) fn parse_frontmatter_full(content: &str) -> Frontmatter {
     let mut argument_hint = None;
     let mut user_invocable: Option<bool> = None;
     let mut disable_model_invocation: Option<bool> = None;
+    let mut review_by = None;
 
     // Track which multi-line field we're accumulating (for `>` folded scalars)
     let mut current_multiline: Option<&str> = None;
@@ -122,6 +125,8 @@ pub(crate) fn parse_frontmatter_full(content: &str) -> Frontmatter {
         } else if let Some(val) = trimmed_line.strip_prefix("disable-model-invocation:") {
             let v = val.trim().to_lowercase();
             disable_model_invocation = Some(v == "true" || v == "yes" || v == "1");
+        } else if let Some(val) = trimmed_line.strip_prefix("review-by:") {
+            review_by = Some(val.trim().trim_matches('"').trim_matches('\'').to_string());
         }
         // All other keys (domain:, type:, purpose:, tools:, trigger:, etc.) are silently ignored.
     }
@@ -147,6 +152,7 @@ pub(crate) fn parse_frontmatter_full(content: &str) -> Frontmatter {
         argument_hint: trim_opt(argument_hint),
         user_invocable,
         disable_model_invocation,
+        review_by: trim_opt(review_by),
     }
 }
 
@@ -222,6 +228,7 @@ pub fn upload_skill(
     disable_model_invocation: Option<bool>,
     purpose: Option<String>,
     force_overwrite: bool,
+    app: tauri::AppHandle,
     db: tauri::State<'_, Db>,
 ) -> Result<WorkspaceSkill, String> {
     log::info!(
@@ -230,6 +237,7 @@ pub fn upload_skill(
         name,
         force_overwrite
     );
+    let file_path = crate::path_input::require_file(&file_path, "upload_skill")?;
     let conn = db.0.lock().map_err(|e| {
         log::error!("[upload_skill] Failed to acquire DB lock: {}", e);
         e.to_string()
@@ -258,6 +266,13 @@ pub fn upload_skill(
     if let Err(e) = super::workflow::update_skills_section(&workspace_path, &conn) {
         log::warn!("Failed to update CLAUDE.md after skill upload: {}", e);
     }
+    drop(conn);
+    super::skill_similarity::flag_near_duplicates(
+        &app,
+        &db,
+        std::slice::from_ref(&result.skill_name),
+        "upload_skill",
+    );
 
     Ok(result)
 }
@@ -284,6 +299,10 @@ fn upload_skill_inner(
         .map_err(|e| format!("Invalid zip file '{}': {}", file_path, e))?;
     let (skill_md_path, _) = find_skill_md(&mut archive)?;
     let prefix = get_archive_prefix(&skill_md_path);
+    crate::fs_validation::FileRuleSet::load(conn)?
+        .check_archive(&mut archive, &prefix, purpose.as_deref(), true)
+        .map_err(|e| format!("Import rejected: {}", e))?;
+    let verification = super::skill_signing::ensure_package_importable(conn, file_path)?;
 
     // Conflict check
     let skills_dir = Path::new(workspace_path).join(".claude").join("skills");
@@ -342,13 +361,31 @@ fn upload_skill_inner(
     };
 
     crate::db::insert_workspace_skill(conn, &skill)?;
-    let imported_is_active = apply_import_purpose_conflict_policy(
+    let context = super::activation_policy::context_for(&skill.disk_path, None, &verification.status);
+    let mut imported_is_active = super::activation_policy::apply_activation_policy(
         conn,
         workspace_path,
         &skill.skill_id,
         &skill.skill_name,
-        skill.purpose.as_deref(),
+        &context,
     )?;
+    imported_is_active = super::skill_scan::scan_imported_skill(
+        conn,
+        workspace_path,
+        &skill.skill_id,
+        &skill.skill_name,
+        &skill.disk_path,
+        imported_is_active,
+    )?;
+    if imported_is_active {
+        imported_is_active = apply_import_purpose_conflict_policy(
+            conn,
+            workspace_path,
+            &skill.skill_id,
+            &skill.skill_name,
+            skill.purpose.as_deref(),
+        )?;
+    }
 
     let mut persisted = crate::db::get_workspace_skill(conn, &skill.skill_id)?
         .ok_or_else(|| format!("Uploaded skill '{}' not found after insert", skill.skill_name))?;
@@ -367,7 +404,7 @@ pub(crate) fn generate_skill_id(skill_name: &str) -> String {
 }
 
 /// Extract archive contents to destination, stripping the prefix.
-fn extract_archive(
+pub(crate) fn extract_archive(
     archive: &mut zip::ZipArchive<std::fs::File>,
     prefix: &str,
     dest_dir: &Path,
@@ -399,7 +436,8 @@ fn extract_archive(
             raw_name.clone()
         };
 
-        if relative.is_empty() {
+        // The embedded package signature is verification metadata, not skill content
+        if relative.is_empty() || raw_name == super::skill_signing::SIGNATURE_ENTRY {
             continue;
         }
 
@@ -489,6 +527,11 @@ pub fn toggle_skill_active(
     let skill_name = &skill.skill_name;
 
     toggle_skill_active_inner(&skill_id, skill_name, active, &workspace_path, &conn)?;
+    match crate::db::mark_activation_decision_overridden(&conn, skill_name, active) {
+        Ok(true) => log::info!("[toggle_skill_active] '{}' overrides its import activation decision", skill_name),
+        Ok(false) => {}
+        Err(e) => log::warn!("[toggle_skill_active] failed to mark activation override: {}", e),
+    }
 
     // When activating, auto-deactivate any other active skill with the same purpose.
     if active {
@@ -508,7 +551,7 @@ pub fn toggle_skill_active(
     Ok(())
 }
 
-fn toggle_skill_active_inner(
+pub(crate) fn toggle_skill_active_inner(
     skill_id: &str,
     skill_name: &str,
     active: bool,
@@ -1039,15 +1082,21 @@ pub(crate) fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), String> {
 #[tauri::command]
 pub fn parse_skill_file(file_path: String) -> Result<crate::types::SkillFileMeta, String> {
     log::info!("[parse_skill_file] file_path={}", file_path);
+    let file_path = crate::path_input::require_file(&file_path, "parse_skill_file")?;
     let zip_file =
         std::fs::File::open(&file_path).map_err(|e| format!("Failed to open file: {}", e))?;
     let mut archive =
         zip::ZipArchive::new(zip_file).map_err(|_| "not a valid skill package".to_string())?;
-    let (_, skill_md_content) = find_skill_md(&mut archive)?;
+    let (skill_md_path, skill_md_content) = find_skill_md(&mut archive)?;
     let fm = parse_frontmatter_full(&skill_md_content);
     if fm.name.is_none() {
         return Err("not a valid skill package: missing name field".to_string());
     }
+    let variables = super::skill_params::parse_skill_variables(&skill_md_content)?;
+    let checklist = read_archive_checklist(&mut archive, &skill_md_path).unwrap_or_else(|| {
+        let name = fm.name.as_deref().unwrap_or_default();
+        super::onboarding::derive_checklist(name, &skill_md_content, &[])
+    });
     Ok(crate::types::SkillFileMeta {
         name: fm.name,
         description: fm.description,
@@ -1056,9 +1105,24 @@ pub fn parse_skill_file(file_path: String) -> Result<crate::types::SkillFileMeta
         argument_hint: fm.argument_hint,
         user_invocable: fm.user_invocable,
         disable_model_invocation: fm.disable_model_invocation,
+        variables,
+        checklist,
     })
 }
 
+/// Read CHECKLIST.md from beside SKILL.md in the archive, if the package has one.
+fn read_archive_checklist(
+    archive: &mut zip::ZipArchive<std::fs::File>,
+    skill_md_path: &str,
+) -> Option<crate::types::OnboardingChecklist> {
+    let prefix = skill_md_path.strip_suffix("SKILL.md").unwrap_or_default();
+    let entry_name = format!("{}{}", prefix, super::onboarding::CHECKLIST_FILE);
+    let mut entry = archive.by_name(&entry_name).ok()?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content).ok()?;
+    Some(super::onboarding::parse_checklist_md(&content))
+}
+
 #[tauri::command]
 #[allow(clippy::too_many_arguments)]
 pub fn import_skill_from_file(
@@ -1071,6 +1135,7 @@ pub fn import_skill_from_file(
     user_invocable: Option<bool>,
     disable_model_invocation: Option<bool>,
     force_overwrite: bool,
+    parameters: Option<HashMap<String, String>>,
     db: tauri::State<'_, Db>,
 ) -> Result<String, String> {
     log::info!(
@@ -1080,6 +1145,7 @@ pub fn import_skill_from_file(
     );
 
     validate_skill_name(&name)?;
+    let file_path = crate::path_input::require_file(&file_path, "import_skill_from_file")?;
 
     let conn = db.0.lock().map_err(|e| {
         log::error!("[import_skill_from_file] failed to acquire DB lock: {}", e);
@@ -1099,8 +1165,41 @@ pub fn import_skill_from_file(
         std::fs::File::open(&file_path).map_err(|e| format!("Failed to open file: {}", e))?;
     let mut archive =
         zip::ZipArchive::new(zip_file).map_err(|_| "not a valid skill package".to_string())?;
-    let (skill_md_path, _) = find_skill_md(&mut archive)?;
+    let (skill_md_path, skill_md_content) = find_skill_md(&mut archive)?;
     let prefix = get_archive_prefix(&skill_md_path);
+    crate::fs_validation::FileRuleSet::load(&conn)?
+        .check_archive(&mut archive, &prefix, None, true)
+        .map_err(|e| {
+            log::error!("[import_skill_from_file] {}", e);
+            format!("Import rejected: {}", e)
+        })?;
+
+    // Resolve template variables before touching disk so invalid values fail cleanly
+    let variables = super::skill_params::parse_skill_variables(&skill_md_content)?;
+    let parameter_values = if variables.is_empty() {
+        None
+    } else {
+        let values = super::skill_params::resolve_parameters(
+            &variables,
+            &parameters.unwrap_or_default(),
+        )
+        .map_err(|e| {
+            log::error!("[import_skill_from_file] {}", e);
+            e
+        })?;
+        Some(values)
+    };
+
+    let verification = super::skill_signing::ensure_package_importable(&conn, &file_path)
+        .map_err(|e| {
+            log::error!("[import_skill_from_file] {}", e);
+            e
+        })?;
+    log::info!(
+        "[import_skill_from_file] signature status={} key_id={:?}",
+        verification.status,
+        verification.key_id
+    );
 
     // Conflict check
     let existing_source: Option<String> = conn
@@ -1130,6 +1229,7 @@ pub fn import_skill_from_file(
             }
             crate::db::delete_imported_skill_by_name(&conn, &name)?;
             crate::db::delete_skill(&conn, &name)?;
+            crate::db::delete_skill_parameter_set(&conn, &name)?;
         }
         _ => {} // Not found — proceed normally
     }
@@ -1144,6 +1244,17 @@ pub fn import_skill_from_file(
         zip::ZipArchive::new(zip_file2).map_err(|_| "not a valid skill package".to_string())?;
     extract_archive(&mut archive2, &prefix, &dest_dir)?;
 
+    if let Some(values) = &parameter_values {
+        let rendered = super::skill_params::render_dir(&dest_dir, values)?;
+        let source_name = parse_frontmatter_full(&skill_md_content).name;
+        crate::db::save_skill_parameter_set(&conn, &name, source_name.as_deref(), values)?;
+        log::info!(
+            "[import_skill_from_file] applied {} parameter(s) to {} file(s)",
+            values.len(),
+            rendered
+        );
+    }
+
     // Write to skills master table
     crate::db::upsert_skill_with_source(&conn, &name, "imported", "domain")?;
 
@@ -1178,6 +1289,11 @@ pub fn import_skill_from_file(
         marketplace_source_url: None,
     };
     crate::db::upsert_imported_skill(&conn, &skill)?;
+    super::skill_scan::scan_library_skill(&conn, &name, &skill.disk_path).map_err(|e| {
+        log::error!("[import_skill_from_file] scan failed: {}", e);
+        e
+    })?;
+    super::skill_review::sync_review_from_frontmatter(&conn, &name, &skill_md_content);
 
     // Regenerate CLAUDE.md
     if !workspace_path.is_empty() {
@@ -0,0 +1,227 @@
+//! Incremental regeneration of a skill after its decisions change.
+//!
+//! Step 3 normally writes SKILL.md from scratch. When the skill was generated
+//! before and only some decisions changed since, the agent is instead pointed
+//! at the existing SKILL.md with the list of changed decisions and told to
+//! patch only the sections they affect. That costs a fraction of a full run
+//! and keeps manual edits to the rest of the skill.
+//!
+//! Each completed generation records the `decisions.json` it was built from in
+//! `skill_generation_bases`. A run is incremental only when that record is
+//! intact, SKILL.md still exists, and the decisions changed but not wholesale;
+//! otherwise the step runs as before.
+
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use super::review_description::{decision_changes, decisions_by_key};
+use crate::db::Db;
+use crate::types::{DecisionDelta, RegenerationMode};
+
+const DECISIONS_FILE: &str = "context/decisions.json";
+
+fn decisions_hash(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// The decisions that changed from `base` (hash, content) to `current`, or
+/// why SKILL.md has to be regenerated in full.
+fn plan(
+    base: Option<(String, String)>,
+    current: Option<&str>,
+    skill_md_exists: bool,
+) -> Result<Vec<DecisionDelta>, String> {
+    let current = current
+        .filter(|c| !c.trim().is_empty())
+        .ok_or("decisions.json is missing")?;
+    if !skill_md_exists {
+        return Err("SKILL.md has not been generated yet".to_string());
+    }
+    let (base_hash, base_json) =
+        base.ok_or("no record of the decisions SKILL.md was generated from")?;
+    if decisions_hash(&base_json) != base_hash {
+        return Err("the recorded base decisions do not match their hash".to_string());
+    }
+    if decisions_hash(current) == base_hash {
+        return Err("decisions are unchanged since the last generation".to_string());
+    }
+
+    let previous = decisions_by_key(Some(&base_json));
+    let total = previous.len().max(decisions_by_key(Some(current)).len());
+    let changes: Vec<DecisionDelta> = decision_changes(Some(&base_json), Some(current))
+        .into_iter()
+        .map(|c| DecisionDelta {
+            previous: previous
+                .get(&c.id)
+                .and_then(|d| d["decision"].as_str())
+                .map(str::to_string),
+            decision: if c.change == "removed" {
+                None
+            } else {
+                c.decision
+            },
+            id: c.id,
+            title: c.title,
+            change: c.change.to_string(),
+        })
+        .collect();
+    if changes.is_empty() {
+        return Err("no decision was added, removed or changed".to_string());
+    }
+    // Past half the decisions, a patch touches most of the skill anyway.
+    if changes.len() * 2 > total {
+        return Err(format!("{} of {} decisions changed", changes.len(), total));
+    }
+    Ok(changes)
+}
+
+/// How step 3 would regenerate `skill_name` now.
+pub(crate) fn regeneration_mode(
+    conn: &rusqlite::Connection,
+    workspace_path: &str,
+    skills_path: &str,
+    skill_name: &str,
+) -> Result<RegenerationMode, String> {
+    let base = crate::db::get_generation_base(conn, skill_name)?;
+    let current = std::fs::read_to_string(
+        Path::new(workspace_path)
+            .join(skill_name)
+            .join(DECISIONS_FILE),
+    )
+    .ok();
+    let skill_md_exists = Path::new(skills_path)
+        .join(skill_name)
+        .join("SKILL.md")
+        .is_file();
+    Ok(match plan(base, current.as_deref(), skill_md_exists) {
+        Ok(changes) => RegenerationMode {
+            incremental: true,
+            reason: None,
+            changes,
+        },
+        Err(reason) => RegenerationMode {
+            incremental: false,
+            reason: Some(reason),
+            changes: Vec::new(),
+        },
+    })
+}
+
+/// Instructions appended to the step 3 prompt for an incremental run.
+pub(crate) fn prompt_section(changes: &[DecisionDelta]) -> String {
+    let mut section = String::from(
+        " This is an incremental regeneration. SKILL.md and the files in references/ were generated from an earlier version of decisions.json and may have been edited by hand since. \
+         Do not rewrite them: read the existing SKILL.md, update only the sections and reference files affected by the decision changes below, \
+         and leave everything else exactly as it is, including manual edits. Write the evaluations as usual. \
+         Decisions changed since the last generation:",
+    );
+    for c in changes {
+        let line = match (c.change.as_str(), &c.previous, &c.decision) {
+            ("added", _, Some(now)) => format!("{} {}: added \"{}\"", c.id, c.title, now),
+            ("removed", Some(was), _) => format!("{} {}: removed (was \"{}\")", c.id, c.title, was),
+            (_, Some(was), Some(now)) => {
+                format!(
+                    "{} {}: changed from \"{}\" to \"{}\"",
+                    c.id, c.title, was, now
+                )
+            }
+            (change, _, _) => format!("{} {}: {}", c.id, c.title, change),
+        };
+        section.push_str("\n- ");
+        section.push_str(&line);
+    }
+    section
+}
+
+/// Record the current `decisions.json` as the base of the SKILL.md step 3
+/// just generated. Never fails the caller.
+pub(crate) fn record_base(conn: &rusqlite::Connection, workspace_path: &str, skill_name: &str) {
+    let path = Path::new(workspace_path)
+        .join(skill_name)
+        .join(DECISIONS_FILE);
+    let Ok(decisions) = std::fs::read_to_string(&path) else {
+        log::warn!(
+            "[incremental_generation] No decisions to record for '{}' at {}",
+            skill_name,
+            path.display()
+        );
+        return;
+    };
+    if let Err(e) =
+        crate::db::set_generation_base(conn, skill_name, &decisions_hash(&decisions), &decisions)
+    {
+        log::warn!(
+            "[incremental_generation] Failed to record generation base for '{}': {}",
+            skill_name,
+            e
+        );
+    }
+}
+
+/// Whether re-running Generate Skill would patch SKILL.md or rewrite it, and why.
+#[tauri::command]
+pub fn get_regeneration_mode(
+    skill_name: String,
+    workspace_path: String,
+    db: tauri::State<'_, Db>,
+) -> Result<RegenerationMode, String> {
+    log::info!("[get_regeneration_mode] skill={}", skill_name);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[get_regeneration_mode] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let skills_path = crate::db::read_settings(&conn)?
+        .skills_path
+        .ok_or_else(|| "Skills path not configured. Please set it in Settings.".to_string())?;
+    regeneration_mode(&conn, &workspace_path, &skills_path, &skill_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r#"{"decisions":[
+        {"id":"D1","title":"Hierarchy","decision":"Two levels","status":"resolved"},
+        {"id":"D2","title":"Timing","decision":"At invoice","status":"resolved"},
+        {"id":"D3","title":"Currency","decision":"USD","status":"resolved"}]}"#;
+
+    fn base() -> Option<(String, String)> {
+        Some((decisions_hash(BASE), BASE.to_string()))
+    }
+
+    #[test]
+    fn test_plan_lists_changed_decisions() {
+        let current = BASE.replace("Two levels", "Three levels");
+        let changes = plan(base(), Some(&current), true).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].id, "D1");
+        assert_eq!(changes[0].previous.as_deref(), Some("Two levels"));
+        assert_eq!(changes[0].decision.as_deref(), Some("Three levels"));
+        assert!(prompt_section(&changes)
+            .ends_with("\n- D1 Hierarchy: changed from \"Two levels\" to \"Three levels\""));
+    }
+
+    #[test]
+    fn test_plan_falls_back_to_full_generation() {
+        let current = BASE.replace("Two levels", "Three levels");
+        assert!(plan(None, Some(&current), true)
+            .unwrap_err()
+            .contains("no record"));
+        assert!(plan(base(), Some(&current), false)
+            .unwrap_err()
+            .contains("not been generated"));
+        assert!(plan(base(), Some(BASE), true)
+            .unwrap_err()
+            .contains("unchanged"));
+        let tampered = Some((decisions_hash("{}"), BASE.to_string()));
+        assert!(plan(tampered, Some(&current), true)
+            .unwrap_err()
+            .contains("hash"));
+        let rewritten = current.replace("At invoice", "At delivery");
+        assert_eq!(
+            plan(base(), Some(&rewritten), true).unwrap_err(),
+            "2 of 3 decisions changed"
+        );
+    }
+}
@@ -0,0 +1,685 @@
+//! Structured intake: the questions the new-skill wizard asks, per skill type.
+//!
+//! Each skill type has an ordered list of questions. A question can depend on
+//! an earlier answer (`show_if`), so the wizard calls `get_intake_questions`
+//! with the answers so far and renders whatever comes back. Answers to
+//! questions hidden by a branch are dropped on submit.
+//!
+//! `submit_intake_answers` validates the answers and stores them in
+//! `workflow_runs.intake_json` as
+//! `{"schema_version", "skill_type", "answers", "context", "domain"}`.
+//! `context` is the answers rendered as markdown and `domain` is copied from
+//! its answer, so prompt building, bulk edits and the taxonomy check read a
+//! structured intake exactly like the free-text one. Free-text intakes from
+//! before the wizard are migrated on read: their fields become the answers
+//! of the questions with the same id.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::db::Db;
+use crate::types::{
+    IntakeAnswerError, IntakeCondition, IntakeQuestion, IntakeQuestionnaire, IntakeSubmission,
+};
+
+pub const INTAKE_SCHEMA_VERSION: u32 = 1;
+
+const TEXT: &str = "text";
+const LONG_TEXT: &str = "long_text";
+const SINGLE_CHOICE: &str = "single_choice";
+const MULTI_CHOICE: &str = "multi_choice";
+const BOOLEAN: &str = "boolean";
+
+const MAX_TEXT_CHARS: usize = 200;
+const MAX_LONG_TEXT_CHARS: usize = 5000;
+
+/// Fields of the free-text intake, each the id of the question it migrates to.
+const LEGACY_FIELDS: &[&str] = &[
+    "audience",
+    "scope",
+    "domain",
+    "challenges",
+    "unique_setup",
+    "claude_mistakes",
+    "context",
+];
+
+struct QuestionDef {
+    id: &'static str,
+    prompt: &'static str,
+    help: Option<&'static str>,
+    kind: &'static str,
+    options: &'static [&'static str],
+    required: bool,
+    /// (question id, answers that show this question)
+    show_if: Option<(&'static str, &'static [&'static str])>,
+}
+
+const fn q(id: &'static str, prompt: &'static str, kind: &'static str) -> QuestionDef {
+    QuestionDef {
+        id,
+        prompt,
+        help: None,
+        kind,
+        options: &[],
+        required: false,
+        show_if: None,
+    }
+}
+
+const COMMON_START: &[QuestionDef] = &[
+    QuestionDef {
+        help: Some("For example: finance analysts building board reports."),
+        required: true,
+        ..q("audience", "Who will use this skill?", TEXT)
+    },
+    QuestionDef {
+        required: true,
+        ..q(
+            "scope",
+            "What should the skill cover, and what is out of scope?",
+            LONG_TEXT,
+        )
+    },
+    QuestionDef {
+        help: Some("Used to group skills and checked against the team taxonomy."),
+        ..q("domain", "Which business domain does it belong to?", TEXT)
+    },
+];
+
+const DOMAIN_QUESTIONS: &[QuestionDef] = &[
+    QuestionDef {
+        options: &[
+            "Standardized",
+            "Varies by team or region",
+            "Being redesigned",
+        ],
+        required: true,
+        ..q(
+            "process_maturity",
+            "How settled is the process?",
+            SINGLE_CHOICE,
+        )
+    },
+    QuestionDef {
+        required: true,
+        show_if: Some((
+            "process_maturity",
+            &["Varies by team or region", "Being redesigned"],
+        )),
+        ..q(
+            "process_variations",
+            "How does it vary, or what is changing?",
+            LONG_TEXT,
+        )
+    },
+    q(
+        "has_metrics",
+        "Does the skill need to explain metrics or KPIs?",
+        BOOLEAN,
+    ),
+    QuestionDef {
+        help: Some("Name, formula and grain of each metric."),
+        required: true,
+        show_if: Some(("has_metrics", &["true"])),
+        ..q(
+            "metric_definitions",
+            "Which metrics, and how are they calculated here?",
+            LONG_TEXT,
+        )
+    },
+];
+
+const SOURCE_QUESTIONS: &[QuestionDef] = &[
+    QuestionDef {
+        help: Some("For example: Salesforce, SAP S/4HANA, Workday."),
+        required: true,
+        ..q("source_system", "Which source system?", TEXT)
+    },
+    QuestionDef {
+        options: &["Out of the box", "Lightly customized", "Heavily customized"],
+        required: true,
+        ..q(
+            "customization_level",
+            "How customized is it?",
+            SINGLE_CHOICE,
+        )
+    },
+    QuestionDef {
+        required: true,
+        show_if: Some((
+            "customization_level",
+            &["Lightly customized", "Heavily customized"],
+        )),
+        ..q(
+            "custom_objects",
+            "Which objects and fields are custom, and what do they mean?",
+            LONG_TEXT,
+        )
+    },
+    QuestionDef {
+        options: &[
+            "API",
+            "Database replica",
+            "File export",
+            "Change data capture",
+        ],
+        ..q("extraction_method", "How is data extracted?", SINGLE_CHOICE)
+    },
+];
+
+const PLATFORM_QUESTIONS: &[QuestionDef] = &[
+    QuestionDef {
+        options: &[
+            "Microsoft Fabric",
+            "Azure Synapse",
+            "Azure Databricks",
+            "Other",
+        ],
+        required: true,
+        ..q("platform", "Which platform?", SINGLE_CHOICE)
+    },
+    QuestionDef {
+        required: true,
+        show_if: Some(("platform", &["Other"])),
+        ..q("platform_other", "Which one?", TEXT)
+    },
+    QuestionDef {
+        options: &["Development", "Test", "Production"],
+        ..q(
+            "environments",
+            "Which environments does the skill cover?",
+            MULTI_CHOICE,
+        )
+    },
+    QuestionDef {
+        options: &["Manual", "CI/CD pipeline"],
+        ..q("deployment", "How are changes deployed?", SINGLE_CHOICE)
+    },
+];
+
+const DATA_ENGINEERING_QUESTIONS: &[QuestionDef] = &[
+    QuestionDef {
+        options: &[
+            "Raw / bronze",
+            "Cleansed / silver",
+            "Curated / gold",
+            "Semantic model",
+        ],
+        required: true,
+        ..q("layers", "Which layers does the skill cover?", MULTI_CHOICE)
+    },
+    QuestionDef {
+        options: &["Full reload", "Incremental", "SCD type 2", "Snapshot"],
+        ..q(
+            "load_patterns",
+            "Which load patterns do you use?",
+            MULTI_CHOICE,
+        )
+    },
+    QuestionDef {
+        show_if: Some(("load_patterns", &["SCD type 2"])),
+        ..q(
+            "history_rules",
+            "How is history kept: keys, effective dates, deletes?",
+            LONG_TEXT,
+        )
+    },
+    QuestionDef {
+        help: Some("For example: dbt, Spark notebooks, Data Factory."),
+        ..q("tooling", "Which tools build the pipelines?", TEXT)
+    },
+];
+
+const COMMON_END: &[QuestionDef] = &[
+    q(
+        "challenges",
+        "What are the hardest parts to get right?",
+        LONG_TEXT,
+    ),
+    q(
+        "unique_setup",
+        "What makes your setup different from the textbook version?",
+        LONG_TEXT,
+    ),
+    q(
+        "claude_mistakes",
+        "What does Claude usually get wrong here?",
+        LONG_TEXT,
+    ),
+    q("context", "Anything else Claude needs to know?", LONG_TEXT),
+];
+
+fn questions_for(skill_type: &str) -> Result<Vec<&'static QuestionDef>, String> {
+    let specific = match skill_type {
+        "domain" => DOMAIN_QUESTIONS,
+        "source" => SOURCE_QUESTIONS,
+        "platform" => PLATFORM_QUESTIONS,
+        "data-engineering" => DATA_ENGINEERING_QUESTIONS,
+        other => return Err(format!("Unknown skill type '{}'", other)),
+    };
+    Ok(COMMON_START
+        .iter()
+        .chain(specific)
+        .chain(COMMON_END)
+        .collect())
+}
+
+fn is_answered(value: Option<&Value>) -> bool {
+    match value {
+        Some(Value::String(s)) => !s.trim().is_empty(),
+        Some(Value::Array(items)) => !items.is_empty(),
+        Some(Value::Bool(_)) => true,
+        _ => false,
+    }
+}
+
+fn answer_matches(value: Option<&Value>, values: &[&str]) -> bool {
+    match value {
+        Some(Value::String(s)) => values.contains(&s.as_str()),
+        Some(Value::Bool(b)) => values.contains(&if *b { "true" } else { "false" }),
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|s| values.contains(&s)),
+        _ => false,
+    }
+}
+
+/// The questions shown for `answers`. A question whose condition refers to a
+/// hidden question is hidden too.
+fn visible<'a>(
+    defs: &[&'a QuestionDef],
+    answers: &BTreeMap<String, Value>,
+) -> Vec<&'a QuestionDef> {
+    let mut shown: Vec<&QuestionDef> = Vec::new();
+    for def in defs {
+        let show = match def.show_if {
+            None => true,
+            Some((parent, values)) => {
+                shown.iter().any(|d| d.id == parent) && answer_matches(answers.get(parent), values)
+            }
+        };
+        if show {
+            shown.push(def);
+        }
+    }
+    shown
+}
+
+/// Why `value` is not a valid answer to `def`, if it is not.
+fn answer_error(def: &QuestionDef, value: &Value) -> Option<String> {
+    match (def.kind, value) {
+        (TEXT, Value::String(s)) if s.chars().count() > MAX_TEXT_CHARS => {
+            Some(format!("Keep this under {} characters", MAX_TEXT_CHARS))
+        }
+        (LONG_TEXT, Value::String(s)) if s.chars().count() > MAX_LONG_TEXT_CHARS => Some(format!(
+            "Keep this under {} characters",
+            MAX_LONG_TEXT_CHARS
+        )),
+        (TEXT | LONG_TEXT, Value::String(_)) => None,
+        (SINGLE_CHOICE, Value::String(s)) if def.options.contains(&s.as_str()) => None,
+        (SINGLE_CHOICE, _) => Some(format!("Choose one of: {}", def.options.join(", "))),
+        (MULTI_CHOICE, Value::Array(items))
+            if items
+                .iter()
+                .all(|v| v.as_str().is_some_and(|s| def.options.contains(&s))) =>
+        {
+            None
+        }
+        (MULTI_CHOICE, _) => Some(format!("Choose from: {}", def.options.join(", "))),
+        (BOOLEAN, Value::Bool(_)) => None,
+        (BOOLEAN, _) => Some("Answer yes or no".to_string()),
+        _ => Some("Expected text".to_string()),
+    }
+}
+
+/// The answers to keep (visible questions only) and the errors in them.
+fn validate_answers(
+    skill_type: &str,
+    answers: &BTreeMap<String, Value>,
+) -> Result<(BTreeMap<String, Value>, Vec<IntakeAnswerError>), String> {
+    let defs = questions_for(skill_type)?;
+    let mut errors: Vec<IntakeAnswerError> = answers
+        .keys()
+        .filter(|id| !defs.iter().any(|d| d.id == id.as_str()))
+        .map(|id| IntakeAnswerError {
+            question_id: id.clone(),
+            message: format!("Not a {} intake question", skill_type),
+        })
+        .collect();
+    let mut kept = BTreeMap::new();
+    for def in visible(&defs, answers) {
+        let value = answers.get(def.id);
+        if !is_answered(value) {
+            if def.required {
+                errors.push(IntakeAnswerError {
+                    question_id: def.id.to_string(),
+                    message: "An answer is required".to_string(),
+                });
+            }
+            continue;
+        }
+        let value = value.expect("answered");
+        match answer_error(def, value) {
+            Some(message) => errors.push(IntakeAnswerError {
+                question_id: def.id.to_string(),
+                message,
+            }),
+            None => {
+                let value = match value {
+                    Value::String(s) => Value::String(s.trim().to_string()),
+                    other => other.clone(),
+                };
+                kept.insert(def.id.to_string(), value);
+            }
+        }
+    }
+    Ok((kept, errors))
+}
+
+/// The answers as markdown, in question order, for `user-context.md`.
+fn render_context(skill_type: &str, answers: &BTreeMap<String, Value>) -> Result<String, String> {
+    let defs = questions_for(skill_type)?;
+    let blocks: Vec<String> = visible(&defs, answers)
+        .into_iter()
+        .filter_map(|def| {
+            let answer = match answers.get(def.id)? {
+                Value::String(s) => s.clone(),
+                Value::Bool(b) => if *b { "Yes" } else { "No" }.to_string(),
+                Value::Array(items) => items
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                _ => return None,
+            };
+            Some(format!("**{}**\n{}", def.prompt, answer))
+        })
+        .collect();
+    Ok(blocks.join("\n\n"))
+}
+
+/// The stored form of validated answers.
+fn build_intake_json(
+    skill_type: &str,
+    answers: &BTreeMap<String, Value>,
+) -> Result<String, String> {
+    let mut intake = serde_json::json!({
+        "schema_version": INTAKE_SCHEMA_VERSION,
+        "skill_type": skill_type,
+        "answers": answers,
+        "context": render_context(skill_type, answers)?,
+    });
+    if let Some(domain) = answers.get("domain") {
+        intake["domain"] = domain.clone();
+    }
+    Ok(intake.to_string())
+}
+
+/// Answers from a stored intake: a structured intake's own answers, or the
+/// fields of a free-text one under the question ids they map to.
+fn migrate_intake(intake_json: Option<&str>) -> BTreeMap<String, Value> {
+    let Some(intake) = intake_json.and_then(|ij| serde_json::from_str::<Value>(ij).ok()) else {
+        return BTreeMap::new();
+    };
+    if let Some(answers) = intake.get("answers").and_then(Value::as_object) {
+        return answers
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+    }
+    LEGACY_FIELDS
+        .iter()
+        .filter_map(|field| {
+            let value = intake.get(*field)?.as_str()?.trim();
+            (!value.is_empty()).then(|| (field.to_string(), Value::String(value.to_string())))
+        })
+        .collect()
+}
+
+fn to_question(def: &QuestionDef) -> IntakeQuestion {
+    IntakeQuestion {
+        id: def.id.to_string(),
+        prompt: def.prompt.to_string(),
+        help: def.help.map(str::to_string),
+        kind: def.kind.to_string(),
+        options: def.options.iter().map(|o| o.to_string()).collect(),
+        required: def.required,
+        show_if: def.show_if.map(|(question_id, equals)| IntakeCondition {
+            question_id: question_id.to_string(),
+            equals: equals.iter().map(|v| v.to_string()).collect(),
+        }),
+    }
+}
+
+fn questionnaire(
+    skill_type: &str,
+    answers: BTreeMap<String, Value>,
+) -> Result<IntakeQuestionnaire, String> {
+    let defs = questions_for(skill_type)?;
+    let shown = visible(&defs, &answers);
+    Ok(IntakeQuestionnaire {
+        schema_version: INTAKE_SCHEMA_VERSION,
+        skill_type: skill_type.to_string(),
+        next_question_id: shown
+            .iter()
+            .find(|d| d.required && !is_answered(answers.get(d.id)))
+            .map(|d| d.id.to_string()),
+        questions: shown.into_iter().map(to_question).collect(),
+        answers,
+    })
+}
+
+/// Validate `answers` against the skill's type and store them as its intake.
+fn submit_intake_answers_inner(
+    conn: &rusqlite::Connection,
+    skill_name: &str,
+    answers: &BTreeMap<String, Value>,
+) -> Result<IntakeSubmission, String> {
+    let run = crate::db::get_workflow_run(conn, skill_name)?
+        .ok_or_else(|| format!("Skill '{}' has no workflow run", skill_name))?;
+    let (kept, errors) = validate_answers(&run.purpose, answers)?;
+    if !errors.is_empty() {
+        return Ok(IntakeSubmission {
+            errors,
+            intake_json: None,
+        });
+    }
+    let intake_json = build_intake_json(&run.purpose, &kept)?;
+    crate::db::set_skill_intake(conn, skill_name, Some(&intake_json))?;
+    Ok(IntakeSubmission {
+        errors,
+        intake_json: Some(intake_json),
+    })
+}
+
+/// The intake questions of `skill_type` visible for `answers`. Without
+/// answers, a skill's stored intake (migrated if free-text) is used.
+#[tauri::command]
+pub fn get_intake_questions(
+    skill_type: String,
+    skill_name: Option<String>,
+    answers: Option<BTreeMap<String, Value>>,
+    db: tauri::State<'_, Db>,
+) -> Result<IntakeQuestionnaire, String> {
+    log::info!(
+        "[get_intake_questions] skill_type={} skill={:?} answers={}",
+        skill_type,
+        skill_name,
+        answers.as_ref().map_or(0, |a| a.len())
+    );
+    let answers = match (answers, skill_name) {
+        (Some(answers), _) => answers,
+        (None, Some(name)) => {
+            let conn = db.0.read().map_err(|e| {
+                log::error!("[get_intake_questions] Failed to acquire DB lock: {}", e);
+                e.to_string()
+            })?;
+            let run = crate::db::get_workflow_run(&conn, &name)?;
+            migrate_intake(run.as_ref().and_then(|r| r.intake_json.as_deref()))
+        }
+        (None, None) => BTreeMap::new(),
+    };
+    questionnaire(&skill_type, answers)
+}
+
+/// Validate and save a skill's intake answers. Validation problems come back
+/// in `errors` and nothing is saved.
+#[tauri::command]
+pub fn submit_intake_answers(
+    skill_name: String,
+    answers: BTreeMap<String, Value>,
+    db: tauri::State<'_, Db>,
+) -> Result<IntakeSubmission, String> {
+    log::info!(
+        "[submit_intake_answers] skill={} answers={}",
+        skill_name,
+        answers.len()
+    );
+    super::observer::ensure_writable(&skill_name)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[submit_intake_answers] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let submission = submit_intake_answers_inner(&conn, &skill_name, &answers).map_err(|e| {
+        log::error!("[submit_intake_answers] {}", e);
+        e
+    })?;
+    if !submission.errors.is_empty() {
+        log::warn!(
+            "[submit_intake_answers] {} invalid answer(s) for '{}'",
+            submission.errors.len(),
+            skill_name
+        );
+    }
+    Ok(submission)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::create_test_db;
+    use serde_json::json;
+
+    fn answers(value: Value) -> BTreeMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn ids(q: &IntakeQuestionnaire) -> Vec<&str> {
+        q.questions.iter().map(|q| q.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_branches_follow_answers() {
+        let empty = questionnaire("platform", BTreeMap::new()).unwrap();
+        assert!(!ids(&empty).contains(&"platform_other"));
+        assert_eq!(empty.next_question_id.as_deref(), Some("audience"));
+
+        let other = questionnaire(
+            "platform",
+            answers(json!({"audience": "Engineers", "scope": "Fabric", "platform": "Other"})),
+        )
+        .unwrap();
+        assert!(ids(&other).contains(&"platform_other"));
+        assert_eq!(other.next_question_id.as_deref(), Some("platform_other"));
+
+        let scd = questionnaire(
+            "data-engineering",
+            answers(json!({"load_patterns": ["Incremental", "SCD type 2"]})),
+        )
+        .unwrap();
+        assert!(ids(&scd).contains(&"history_rules"));
+        assert!(questionnaire("marketing", BTreeMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_validation_reports_errors_and_drops_hidden_answers() {
+        let (_, errors) = validate_answers(
+            "domain",
+            &answers(json!({
+                "audience": "x".repeat(MAX_TEXT_CHARS + 1),
+                "process_maturity": "Chaotic",
+                "has_metrics": "yes",
+                "color": "blue"
+            })),
+        )
+        .unwrap();
+        let mut failed: Vec<&str> = errors.iter().map(|e| e.question_id.as_str()).collect();
+        failed.sort_unstable();
+        assert_eq!(
+            failed,
+            vec![
+                "audience",
+                "color",
+                "has_metrics",
+                "process_maturity",
+                "scope"
+            ]
+        );
+
+        let (kept, errors) = validate_answers(
+            "domain",
+            &answers(json!({
+                "audience": " Analysts ",
+                "scope": "Revenue recognition",
+                "process_maturity": "Standardized",
+                "process_variations": "ignored: hidden",
+                "has_metrics": false
+            })),
+        )
+        .unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(kept["audience"], "Analysts");
+        assert!(!kept.contains_key("process_variations"));
+    }
+
+    #[test]
+    fn test_submit_stores_structured_intake_and_migrates_legacy() {
+        let conn = create_test_db();
+        crate::db::save_workflow_run(&conn, "revenue", 0, "pending", "domain").unwrap();
+        crate::db::set_skill_intake(
+            &conn,
+            "revenue",
+            Some(r#"{"audience":"Finance","context":"Uses ASC 606","ignored":"x"}"#),
+        )
+        .unwrap();
+        let run = crate::db::get_workflow_run(&conn, "revenue")
+            .unwrap()
+            .unwrap();
+        let migrated = migrate_intake(run.intake_json.as_deref());
+        assert_eq!(
+            migrated,
+            answers(json!({"audience": "Finance", "context": "Uses ASC 606"}))
+        );
+
+        let invalid = submit_intake_answers_inner(&conn, "revenue", &migrated).unwrap();
+        assert_eq!(invalid.errors.len(), 2);
+        assert!(invalid.intake_json.is_none());
+
+        let mut complete = migrated;
+        complete.insert("scope".into(), json!("Recognition timing"));
+        complete.insert("domain".into(), json!("Finance"));
+        complete.insert("process_maturity".into(), json!("Standardized"));
+        complete.insert("has_metrics".into(), json!(true));
+        complete.insert("metric_definitions".into(), json!("ARR = MRR x 12"));
+        let saved = submit_intake_answers_inner(&conn, "revenue", &complete).unwrap();
+        assert!(saved.errors.is_empty());
+
+        let stored: Value = serde_json::from_str(
+            &crate::db::get_workflow_run(&conn, "revenue")
+                .unwrap()
+                .unwrap()
+                .intake_json
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(stored["schema_version"], INTAKE_SCHEMA_VERSION);
+        assert_eq!(stored["domain"], "Finance");
+        let context = stored["context"].as_str().unwrap();
+        assert!(context.starts_with("**Who will use this skill?**\nFinance"));
+        assert!(context.contains("**Does the skill need to explain metrics or KPIs?**\nYes"));
+        assert_eq!(migrate_intake(Some(&stored.to_string())), complete);
+    }
+}
@@ -0,0 +1,193 @@
+//! Point-in-time view of the skills library.
+//!
+//! Built skills are read from the git history of the skills folder: the last
+//! commit at or before the requested time lists which skill directories existed
+//! and their SKILL.md versions. Installed skills (workspace and marketplace) are
+//! replayed from the `skill_library_events` audit log, which also carries their
+//! active state. Used to answer "what did the library look like before X" when a
+//! regression is reported long after the change that caused it.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::db::{Db, LIBRARY_SOURCE_MARKETPLACE};
+use crate::git::TreeSkillsAt;
+use crate::types::{LibraryEvent, LibrarySkillState, LibrarySnapshot};
+
+/// Parse an RFC 3339 timestamp, or a bare `YYYY-MM-DD` meaning the end of that day (UTC).
+fn parse_timestamp(raw: &str) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    let raw = raw.trim();
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(raw) {
+        return Ok(dt.with_timezone(&chrono::Utc));
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(raw, "%Y-%m-%d") {
+        if let Some(end) = date.and_hms_opt(23, 59, 59) {
+            return Ok(end.and_utc());
+        }
+    }
+    Err(format!(
+        "Invalid timestamp '{}': expected RFC 3339 (2025-06-30T12:00:00Z) or a date (2025-06-30)",
+        raw
+    ))
+}
+
+/// Combine the git tree and the audit log into one snapshot. Events must be oldest first.
+fn build_snapshot(at: &str, tree: Option<TreeSkillsAt>, events: Vec<LibraryEvent>) -> LibrarySnapshot {
+    let mut installed: BTreeMap<(String, String), LibraryEvent> = BTreeMap::new();
+    for event in events {
+        let key = (event.skill_name.clone(), event.source.clone());
+        if event.event_type == "removed" {
+            installed.remove(&key);
+        } else {
+            installed.insert(key, event);
+        }
+    }
+
+    let mut skills = Vec::new();
+    let (commit_sha, commit_message) = match tree {
+        Some(tree) => {
+            for (name, content) in tree.skills {
+                // Marketplace imports also live in the skills folder; the audit log is authoritative for them.
+                if installed.contains_key(&(name.clone(), LIBRARY_SOURCE_MARKETPLACE.to_string())) {
+                    continue;
+                }
+                let fm = super::imported_skills::parse_frontmatter_full(&content);
+                skills.push(LibrarySkillState {
+                    skill_name: name,
+                    source: "built".to_string(),
+                    version: fm.version,
+                    is_active: true,
+                    since: tree.timestamp.clone(),
+                });
+            }
+            (Some(tree.sha), Some(tree.message.trim().to_string()))
+        }
+        None => (None, None),
+    };
+
+    skills.extend(installed.into_values().map(|e| LibrarySkillState {
+        skill_name: e.skill_name,
+        source: e.source,
+        version: e.version,
+        is_active: e.is_active,
+        since: e.created_at,
+    }));
+    skills.sort_by(|a, b| a.skill_name.cmp(&b.skill_name).then(a.source.cmp(&b.source)));
+
+    LibrarySnapshot {
+        at: at.to_string(),
+        commit_sha,
+        commit_message,
+        skills,
+    }
+}
+
+/// Reconstruct which skills existed, their versions and active states at `timestamp`.
+#[tauri::command]
+pub fn get_library_at(timestamp: String, db: tauri::State<'_, Db>) -> Result<LibrarySnapshot, String> {
+    log::info!("[get_library_at] timestamp={}", timestamp);
+    let at = parse_timestamp(&timestamp).map_err(|e| {
+        log::error!("[get_library_at] {}", e);
+        e
+    })?;
+
+    let (skills_path, events) = {
+        let conn = db.0.read().map_err(|e| {
+            log::error!("[get_library_at] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        let settings = crate::db::read_settings(&conn)?;
+        let events = crate::db::list_library_events_until(&conn, &at.format("%Y-%m-%d %H:%M:%SZ").to_string())?;
+        (settings.skills_path, events)
+    };
+
+    let tree = match skills_path {
+        Some(path) if Path::new(&path).join(".git").exists() => {
+            crate::git::skills_at(Path::new(&path), at.timestamp())?
+        }
+        _ => None,
+    };
+
+    let snapshot = build_snapshot(&at.to_rfc3339(), tree, events);
+    log::info!(
+        "[get_library_at] {} skills at {} (commit {})",
+        snapshot.skills.len(),
+        snapshot.at,
+        snapshot.commit_sha.as_deref().map(|s| &s[..8.min(s.len())]).unwrap_or("none")
+    );
+    Ok(snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(name: &str, source: &str, event_type: &str, version: Option<&str>, active: bool) -> LibraryEvent {
+        LibraryEvent {
+            skill_name: name.to_string(),
+            source: source.to_string(),
+            event_type: event_type.to_string(),
+            version: version.map(str::to_string),
+            is_active: active,
+            created_at: "2025-03-01 10:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_timestamp_accepts_rfc3339_and_dates() {
+        assert_eq!(
+            parse_timestamp("2025-06-30T12:00:00+02:00").unwrap().to_rfc3339(),
+            "2025-06-30T10:00:00+00:00"
+        );
+        assert_eq!(
+            parse_timestamp("2025-06-30").unwrap().to_rfc3339(),
+            "2025-06-30T23:59:59+00:00"
+        );
+        assert!(parse_timestamp("last tuesday").is_err());
+    }
+
+    #[test]
+    fn test_build_snapshot_replays_events_and_reads_tree() {
+        let tree = TreeSkillsAt {
+            sha: "abc123".to_string(),
+            message: "sales-pipeline: step 3 completed\n".to_string(),
+            timestamp: "2025-02-01T00:00:00+00:00".to_string(),
+            skills: vec![
+                ("sales-pipeline".to_string(), "---\nversion: 2.1.0\n---\n".to_string()),
+                ("dbt-helper".to_string(), "---\nversion: 1.0.0\n---\n".to_string()),
+            ],
+        };
+        let events = vec![
+            event("dbt-helper", "marketplace", "installed", Some("1.0.0"), true),
+            event("dbt-helper", "marketplace", "deactivated", Some("1.0.0"), false),
+            event("research", "workspace", "installed", Some("0.3.0"), true),
+            event("old-skill", "workspace", "installed", None, true),
+            event("old-skill", "workspace", "removed", None, false),
+        ];
+
+        let snapshot = build_snapshot("2025-03-02T00:00:00+00:00", Some(tree), events);
+        assert_eq!(snapshot.commit_sha.as_deref(), Some("abc123"));
+        assert_eq!(snapshot.commit_message.as_deref(), Some("sales-pipeline: step 3 completed"));
+
+        let summary: Vec<(&str, &str, Option<&str>, bool)> = snapshot
+            .skills
+            .iter()
+            .map(|s| (s.skill_name.as_str(), s.source.as_str(), s.version.as_deref(), s.is_active))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("dbt-helper", "marketplace", Some("1.0.0"), false),
+                ("research", "workspace", Some("0.3.0"), true),
+                ("sales-pipeline", "built", Some("2.1.0"), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_snapshot_before_any_history_is_empty() {
+        let snapshot = build_snapshot("2000-01-01T00:00:00+00:00", None, Vec::new());
+        assert!(snapshot.commit_sha.is_none());
+        assert!(snapshot.skills.is_empty());
+    }
+}
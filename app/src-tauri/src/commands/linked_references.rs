@@ -0,0 +1,447 @@
+//! Reference files sourced from GitHub.
+//!
+//! A skill's `references/` file can be linked to a path in a GitHub repo at a
+//! branch, tag or commit, so standards docs maintained elsewhere stay current
+//! inside the skill. Links live in `skill_linked_references` with the git blob
+//! SHA last written to disk. `refresh_linked_references` fetches each source
+//! and returns the file on disk next to the latest upstream version without
+//! writing; the user approves updates through `apply_linked_reference_updates`,
+//! which writes them and commits to the skills repo. Drift is reported on every
+//! link: upstream changed, edited locally, or missing on either side.
+
+use std::path::{Path, PathBuf};
+
+use crate::db::Db;
+use crate::github::{self, GitHubServer};
+use crate::types::{ApprovedReferenceUpdate, LinkedReference, LinkedReferenceRefresh};
+
+const DRIFT_CURRENT: &str = "current";
+const DRIFT_UPSTREAM_CHANGED: &str = "upstream_changed";
+const DRIFT_LOCAL_MODIFIED: &str = "local_modified";
+const DRIFT_UPSTREAM_MISSING: &str = "upstream_missing";
+const DRIFT_LOCAL_MISSING: &str = "local_missing";
+
+/// Git blob SHA of `content`, the same id the GitHub contents API reports.
+fn blob_sha(content: &[u8]) -> Result<String, String> {
+    git2::Oid::hash_object(git2::ObjectType::Blob, content)
+        .map(|oid| oid.to_string())
+        .map_err(|e| format!("Failed to hash content: {}", e))
+}
+
+/// Reference paths must stay inside the skill's `references/` folder.
+pub(crate) fn validate_reference_path(path: &str) -> Result<(), String> {
+    let rest = path
+        .strip_prefix("references/")
+        .ok_or_else(|| format!("Linked references must be under references/, got '{}'", path))?;
+    if rest.is_empty() || path.contains('\\') || rest.split('/').any(|c| c.is_empty() || c == "." || c == "..") {
+        return Err(format!("Invalid reference path '{}'", path));
+    }
+    Ok(())
+}
+
+fn split_repo(repo: &str) -> Result<(&str, &str), String> {
+    match repo.split_once('/') {
+        Some((owner, name)) if !owner.is_empty() && !name.is_empty() && !name.contains('/') => Ok((owner, name)),
+        _ => Err(format!("Repository must be 'owner/repo', got '{}'", repo)),
+    }
+}
+
+/// How a link differs from its last sync, from the file on disk and the last upstream check.
+fn compute_drift(skill_dir: &Path, link: &LinkedReference) -> String {
+    let local = match std::fs::read(skill_dir.join(&link.reference_path)) {
+        Ok(bytes) => blob_sha(&bytes).ok(),
+        Err(_) => return DRIFT_LOCAL_MISSING.to_string(),
+    };
+    let drift = if local.as_deref() != Some(link.synced_sha.as_str()) {
+        DRIFT_LOCAL_MODIFIED
+    } else if link.checked_at.is_some() && link.upstream_sha.is_none() {
+        DRIFT_UPSTREAM_MISSING
+    } else if link.upstream_sha.as_deref().is_some_and(|sha| sha != link.synced_sha) {
+        DRIFT_UPSTREAM_CHANGED
+    } else {
+        DRIFT_CURRENT
+    };
+    drift.to_string()
+}
+
+/// Skill directory in the skills folder, plus the GitHub token if signed in.
+pub(crate) fn skill_dir_and_token(db: &Db, skill_name: &str, cmd: &str) -> Result<(PathBuf, Option<String>), String> {
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[{}] Failed to acquire DB lock: {}", cmd, e);
+        e.to_string()
+    })?;
+    let settings = crate::db::read_settings_hydrated(&conn)?;
+    let skills_path = settings
+        .skills_path
+        .ok_or_else(|| "Skills path not configured. Set it in Settings.".to_string())?;
+    let skill_dir = Path::new(&skills_path).join(skill_name);
+    if !skill_dir.join("SKILL.md").is_file() {
+        return Err(format!("Skill '{}' not found in the skills folder", skill_name));
+    }
+    Ok((skill_dir, settings.github_oauth_token))
+}
+
+fn write_reference(skill_dir: &Path, reference_path: &str, content: &str) -> Result<(), String> {
+    let path = skill_dir.join(reference_path);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+pub(crate) fn commit_skills_repo(skill_dir: &Path, message: &str) {
+    if let Some(skills_root) = skill_dir.parent() {
+        if let Err(e) = crate::git::commit_all(skills_root, message) {
+            log::warn!("[linked_references] git commit failed: {}", e);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn link_reference_inner(
+    db: &Db,
+    server: &GitHubServer,
+    skill_dir: &Path,
+    token: Option<&str>,
+    skill_name: &str,
+    reference_path: &str,
+    repo: &str,
+    git_ref: &str,
+    source_path: &str,
+) -> Result<LinkedReference, String> {
+    validate_reference_path(reference_path)?;
+    let (owner, name) = split_repo(repo)?;
+    let client = super::github_import::build_github_client(token, server)?;
+    let file = github::get_file(&client, &server.api_base, owner, name, source_path, git_ref)
+        .await?
+        .ok_or_else(|| format!("{} not found in {} at {}", source_path, repo, git_ref))?;
+    write_reference(skill_dir, reference_path, &file.content)?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut link = LinkedReference {
+        skill_name: skill_name.to_string(),
+        reference_path: reference_path.to_string(),
+        repo: repo.to_string(),
+        git_ref: git_ref.to_string(),
+        source_path: source_path.to_string(),
+        synced_sha: file.sha.clone(),
+        synced_at: now.clone(),
+        upstream_sha: Some(file.sha),
+        checked_at: Some(now),
+        drift: String::new(),
+    };
+    {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        crate::db::upsert_linked_reference(&conn, &link)?;
+    }
+    commit_skills_repo(
+        skill_dir,
+        &format!("{}: link {} to {}:{}@{}", skill_name, reference_path, repo, source_path, git_ref),
+    );
+    link.drift = compute_drift(skill_dir, &link);
+    Ok(link)
+}
+
+pub(crate) async fn refresh_linked_references_inner(
+    db: &Db,
+    server: &GitHubServer,
+    skill_dir: &Path,
+    token: Option<&str>,
+    skill_name: &str,
+) -> Result<Vec<LinkedReferenceRefresh>, String> {
+    let links = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        crate::db::list_linked_references(&conn, skill_name)?
+    };
+    let client = super::github_import::build_github_client(token, server)?;
+    let mut results = Vec::new();
+    for mut link in links {
+        let current_content = std::fs::read_to_string(skill_dir.join(&link.reference_path)).ok();
+        let fetched = match split_repo(&link.repo) {
+            Ok((owner, name)) => github::get_file(&client, &server.api_base, owner, name, &link.source_path, &link.git_ref).await,
+            Err(e) => Err(e),
+        };
+        let upstream = match fetched {
+            Ok(upstream) => upstream,
+            Err(e) => {
+                log::warn!("[refresh_linked_references] {}: {}", link.reference_path, e);
+                results.push(LinkedReferenceRefresh {
+                    drift: compute_drift(skill_dir, &link),
+                    reference_path: link.reference_path,
+                    current_content,
+                    upstream_content: None,
+                    upstream_sha: None,
+                    error: Some(e),
+                });
+                continue;
+            }
+        };
+
+        link.checked_at = Some(chrono::Utc::now().to_rfc3339());
+        link.upstream_sha = upstream.as_ref().map(|f| f.sha.clone());
+        {
+            let conn = db.0.lock().map_err(|e| e.to_string())?;
+            crate::db::record_linked_reference_check(
+                &conn,
+                skill_name,
+                &link.reference_path,
+                link.upstream_sha.as_deref(),
+                link.checked_at.as_deref().unwrap_or_default(),
+            )?;
+        }
+        let upstream_content = upstream
+            .map(|f| f.content)
+            .filter(|content| current_content.as_deref() != Some(content.as_str()));
+        results.push(LinkedReferenceRefresh {
+            drift: compute_drift(skill_dir, &link),
+            reference_path: link.reference_path,
+            current_content,
+            upstream_content,
+            upstream_sha: link.upstream_sha,
+            error: None,
+        });
+    }
+    Ok(results)
+}
+
+pub(crate) fn apply_linked_reference_updates_inner(
+    conn: &rusqlite::Connection,
+    skill_dir: &Path,
+    skill_name: &str,
+    updates: &[ApprovedReferenceUpdate],
+) -> Result<Vec<String>, String> {
+    let linked: Vec<String> = crate::db::list_linked_references(conn, skill_name)?
+        .into_iter()
+        .map(|l| l.reference_path)
+        .collect();
+    for update in updates {
+        if !linked.contains(&update.reference_path) {
+            return Err(format!("'{}' is not a linked reference of '{}'", update.reference_path, skill_name));
+        }
+        // The content must be exactly the upstream version the user reviewed.
+        if blob_sha(update.content.as_bytes())? != update.upstream_sha {
+            return Err(format!("Content for '{}' does not match upstream {}", update.reference_path, update.upstream_sha));
+        }
+    }
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut updated = Vec::new();
+    for update in updates {
+        write_reference(skill_dir, &update.reference_path, &update.content)?;
+        crate::db::mark_linked_reference_synced(conn, skill_name, &update.reference_path, &update.upstream_sha, &now)?;
+        updated.push(update.reference_path.clone());
+    }
+    if !updated.is_empty() {
+        commit_skills_repo(skill_dir, &format!("{}: refresh linked {}", skill_name, updated.join(", ")));
+    }
+    Ok(updated)
+}
+
+/// Link `reference_path` (under `references/`) to `source_path` in `repo` at
+/// `git_ref`, writing the current upstream content into the skill.
+#[tauri::command]
+pub async fn link_reference(
+    skill_name: String,
+    reference_path: String,
+    repo: String,
+    git_ref: String,
+    source_path: String,
+    db: tauri::State<'_, Db>,
+) -> Result<LinkedReference, String> {
+    log::info!(
+        "[link_reference] skill={} path={} source={}:{}@{}",
+        skill_name,
+        reference_path,
+        repo,
+        source_path,
+        git_ref
+    );
+    super::observer::ensure_writable(&skill_name)?;
+    super::offline::require_feature(&db, super::offline::NetworkFeature::GitHub, "link_reference")?;
+    super::imported_skills::validate_skill_name(&skill_name)?;
+    let (skill_dir, token) = skill_dir_and_token(&db, &skill_name, "link_reference")?;
+    let server = super::git_hosting::github_server(&db)?;
+    link_reference_inner(
+        &db,
+        &server,
+        &skill_dir,
+        token.as_deref(),
+        &skill_name,
+        &reference_path,
+        &repo,
+        &git_ref,
+        &source_path,
+    )
+    .await
+    .map_err(|e| {
+        log::error!("[link_reference] {}", e);
+        e
+    })
+}
+
+/// Stop syncing a reference. The file stays in the skill.
+#[tauri::command]
+pub fn unlink_reference(skill_name: String, reference_path: String, db: tauri::State<'_, Db>) -> Result<(), String> {
+    log::info!("[unlink_reference] skill={} path={}", skill_name, reference_path);
+    super::observer::ensure_writable(&skill_name)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[unlink_reference] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    if !crate::db::delete_linked_reference(&conn, &skill_name, &reference_path)? {
+        return Err(format!("'{}' is not a linked reference of '{}'", reference_path, skill_name));
+    }
+    Ok(())
+}
+
+/// Links for a skill with their drift as of the last check (no network).
+#[tauri::command]
+pub fn list_linked_references(skill_name: String, db: tauri::State<'_, Db>) -> Result<Vec<LinkedReference>, String> {
+    log::info!("[list_linked_references] skill={}", skill_name);
+    let (skill_dir, _) = skill_dir_and_token(&db, &skill_name, "list_linked_references")?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[list_linked_references] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let mut links = crate::db::list_linked_references(&conn, &skill_name)?;
+    for link in &mut links {
+        link.drift = compute_drift(&skill_dir, link);
+    }
+    Ok(links)
+}
+
+/// Fetch the latest upstream content for each linked reference. Writes nothing
+/// to the skill; pass approved entries to `apply_linked_reference_updates`.
+#[tauri::command]
+pub async fn refresh_linked_references(
+    skill_name: String,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<LinkedReferenceRefresh>, String> {
+    log::info!("[refresh_linked_references] skill={}", skill_name);
+    super::observer::ensure_writable(&skill_name)?;
+    super::offline::require_feature(&db, super::offline::NetworkFeature::GitHub, "refresh_linked_references")?;
+    let (skill_dir, token) = skill_dir_and_token(&db, &skill_name, "refresh_linked_references")?;
+    let server = super::git_hosting::github_server(&db)?;
+    refresh_linked_references_inner(&db, &server, &skill_dir, token.as_deref(), &skill_name)
+        .await
+        .map_err(|e| {
+            log::error!("[refresh_linked_references] {}", e);
+            e
+        })
+}
+
+#[tauri::command]
+pub fn apply_linked_reference_updates(
+    skill_name: String,
+    updates: Vec<ApprovedReferenceUpdate>,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<String>, String> {
+    log::info!("[apply_linked_reference_updates] skill={} count={}", skill_name, updates.len());
+    super::observer::ensure_writable(&skill_name)?;
+    let (skill_dir, _) = skill_dir_and_token(&db, &skill_name, "apply_linked_reference_updates")?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[apply_linked_reference_updates] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    apply_linked_reference_updates_inner(&conn, &skill_dir, &skill_name, &updates).map_err(|e| {
+        log::error!("[apply_linked_reference_updates] {}", e);
+        e
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+
+    fn contents_body(content: &str) -> String {
+        serde_json::json!({
+            "sha": blob_sha(content.as_bytes()).unwrap(),
+            "content": base64::engine::general_purpose::STANDARD.encode(content),
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_validate_reference_path() {
+        assert!(validate_reference_path("references/style.md").is_ok());
+        assert!(validate_reference_path("references/std/naming.md").is_ok());
+        assert!(validate_reference_path("SKILL.md").is_err());
+        assert!(validate_reference_path("references/../SKILL.md").is_err());
+        assert!(validate_reference_path("references/").is_err());
+        assert!(split_repo("acme/standards").is_ok());
+        assert!(split_repo("acme").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_link_refresh_apply_and_drift() {
+        let mut server = mockito::Server::new_async().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let skill_dir = tmp.path().join("sales");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(skill_dir.join("SKILL.md"), "---\nname: sales\n---\n").unwrap();
+        let db = Db::new(crate::commands::test_utils::create_test_db());
+        let github = GitHubServer { api_base: server.url(), ..Default::default() };
+
+        let v1 = server
+            .mock("GET", "/repos/acme/standards/contents/docs/naming.md?ref=main")
+            .with_status(200)
+            .with_body(contents_body("# Naming v1\n"))
+            .create_async()
+            .await;
+        let link = link_reference_inner(
+            &db,
+            &github,
+            &skill_dir,
+            None,
+            "sales",
+            "references/naming.md",
+            "acme/standards",
+            "main",
+            "docs/naming.md",
+        )
+        .await
+        .unwrap();
+        v1.remove_async().await;
+        assert_eq!(link.drift, DRIFT_CURRENT);
+        assert_eq!(std::fs::read_to_string(skill_dir.join("references/naming.md")).unwrap(), "# Naming v1\n");
+
+        let _v2 = server
+            .mock("GET", "/repos/acme/standards/contents/docs/naming.md?ref=main")
+            .with_status(200)
+            .with_body(contents_body("# Naming v2\n"))
+            .create_async()
+            .await;
+        let refresh = refresh_linked_references_inner(&db, &github, &skill_dir, None, "sales")
+            .await
+            .unwrap();
+        assert_eq!(refresh.len(), 1);
+        assert_eq!(refresh[0].drift, DRIFT_UPSTREAM_CHANGED);
+        assert_eq!(refresh[0].current_content.as_deref(), Some("# Naming v1\n"));
+        assert_eq!(refresh[0].upstream_content.as_deref(), Some("# Naming v2\n"));
+        // Refresh alone does not touch the skill
+        assert_eq!(std::fs::read_to_string(skill_dir.join("references/naming.md")).unwrap(), "# Naming v1\n");
+
+        let conn = db.0.lock().unwrap();
+        let tampered = ApprovedReferenceUpdate {
+            reference_path: "references/naming.md".to_string(),
+            content: "# Something else\n".to_string(),
+            upstream_sha: refresh[0].upstream_sha.clone().unwrap(),
+        };
+        assert!(apply_linked_reference_updates_inner(&conn, &skill_dir, "sales", &[tampered]).is_err());
+
+        let approved = ApprovedReferenceUpdate {
+            reference_path: "references/naming.md".to_string(),
+            content: refresh[0].upstream_content.clone().unwrap(),
+            upstream_sha: refresh[0].upstream_sha.clone().unwrap(),
+        };
+        let updated = apply_linked_reference_updates_inner(&conn, &skill_dir, "sales", &[approved]).unwrap();
+        assert_eq!(updated, vec!["references/naming.md"]);
+        let link = crate::db::list_linked_references(&conn, "sales").unwrap().remove(0);
+        assert_eq!(compute_drift(&skill_dir, &link), DRIFT_CURRENT);
+
+        std::fs::write(skill_dir.join("references/naming.md"), "# Local edit\n").unwrap();
+        assert_eq!(compute_drift(&skill_dir, &link), DRIFT_LOCAL_MODIFIED);
+        std::fs::remove_file(skill_dir.join("references/naming.md")).unwrap();
+        assert_eq!(compute_drift(&skill_dir, &link), DRIFT_LOCAL_MISSING);
+    }
+}
@@ -0,0 +1,359 @@
+//! Routing for low-stakes auxiliary passes (field suggestions and similar).
+//!
+//! When a local Ollama endpoint and model are configured, auxiliary prompts run
+//! there first and fall back to the Anthropic API on any failure. Every call is
+//! recorded in usage under the synthetic "Auxiliary" step; local runs cost zero.
+
+use crate::agents::cost_ticker::list_price;
+use crate::types::AppSettings;
+
+/// Synthetic step id for auxiliary passes in `agent_runs` (Refine is -10, Test is -11).
+pub(crate) const AUXILIARY_STEP_ID: i32 = -12;
+
+/// Anthropic model used when no local model is configured or the local call fails.
+const FALLBACK_MODEL: &str = "claude-haiku-4-5";
+
+/// Local models are slower to first token than the API, especially on a cold load.
+const LOCAL_TIMEOUT_SECS: u64 = 60;
+const API_TIMEOUT_SECS: u64 = 15;
+
+/// Where auxiliary prompts can run, resolved from settings.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AuxiliaryRoute {
+    pub local_endpoint: Option<String>,
+    pub local_model: Option<String>,
+    pub api_key: Option<String>,
+}
+
+impl AuxiliaryRoute {
+    pub(crate) fn from_settings(settings: &AppSettings) -> Self {
+        let non_empty = |v: &Option<String>| {
+            v.as_deref()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            local_endpoint: non_empty(&settings.local_model_endpoint)
+                .map(|e| e.trim_end_matches('/').to_string()),
+            local_model: non_empty(&settings.local_model),
+            // Offline mode has no API to fall back to.
+            api_key: if settings.offline_mode {
+                None
+            } else {
+                settings.anthropic_api_key.clone()
+            },
+        }
+    }
+
+    fn local(&self) -> Option<(&str, &str)> {
+        Some((self.local_endpoint.as_deref()?, self.local_model.as_deref()?))
+    }
+}
+
+/// Result of one auxiliary completion, local or API.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct AuxiliaryCompletion {
+    pub text: String,
+    /// `ollama/<model>` for local runs, the Anthropic model id otherwise.
+    pub model: String,
+    pub local: bool,
+    pub input_tokens: i32,
+    pub output_tokens: i32,
+    pub total_cost: f64,
+    pub duration_ms: i64,
+}
+
+/// Run `prompt` on the local model when configured, falling back to the API.
+/// `caller` is the command name used as the log prefix.
+pub(crate) async fn complete(
+    route: &AuxiliaryRoute,
+    prompt: &str,
+    max_tokens: u32,
+    caller: &str,
+) -> Result<AuxiliaryCompletion, String> {
+    if let Some((endpoint, model)) = route.local() {
+        match complete_local(endpoint, model, prompt, max_tokens).await {
+            Ok(completion) => {
+                log::info!(
+                    "[{}] served by local model {} ({} ms)",
+                    caller,
+                    model,
+                    completion.duration_ms
+                );
+                return Ok(completion);
+            }
+            Err(e) => log::warn!(
+                "[{}] local model {} failed, falling back to API: {}",
+                caller,
+                model,
+                e
+            ),
+        }
+    }
+
+    let api_key = route.api_key.as_deref().ok_or_else(|| {
+        log::error!("[{}] API key not configured", caller);
+        "API key not configured".to_string()
+    })?;
+    complete_anthropic(api_key, prompt, max_tokens).await.map_err(|e| {
+        log::error!("[{}] {}", caller, e);
+        e
+    })
+}
+
+async fn complete_local(
+    endpoint: &str,
+    model: &str,
+    prompt: &str,
+    max_tokens: u32,
+) -> Result<AuxiliaryCompletion, String> {
+    let started = std::time::Instant::now();
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(LOCAL_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let resp = client
+        .post(format!("{}/api/chat", endpoint))
+        .json(&serde_json::json!({
+            "model": model,
+            "stream": false,
+            "options": { "num_predict": max_tokens },
+            "messages": [{"role": "user", "content": prompt}]
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("Local model request failed: {}", e))?;
+
+    if !resp.status().is_success() {
+        let status = resp.status().as_u16();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(format!("Local model error ({}): {}", status, body));
+    }
+    let body: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+    let (text, input_tokens, output_tokens) = parse_ollama_response(&body)?;
+
+    Ok(AuxiliaryCompletion {
+        text,
+        model: format!("ollama/{}", model),
+        local: true,
+        input_tokens,
+        output_tokens,
+        total_cost: 0.0,
+        duration_ms: started.elapsed().as_millis() as i64,
+    })
+}
+
+async fn complete_anthropic(
+    api_key: &str,
+    prompt: &str,
+    max_tokens: u32,
+) -> Result<AuxiliaryCompletion, String> {
+    let started = std::time::Instant::now();
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(API_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let resp = client
+        .post("https://api.anthropic.com/v1/messages")
+        .header("x-api-key", api_key)
+        .header("anthropic-version", "2023-06-01")
+        .header("content-type", "application/json")
+        .body(
+            serde_json::json!({
+                "model": FALLBACK_MODEL,
+                "max_tokens": max_tokens,
+                "messages": [{"role": "user", "content": prompt}]
+            })
+            .to_string(),
+        )
+        .send()
+        .await
+        .map_err(|e| format!("API request failed: {}", e))?;
+
+    if !resp.status().is_success() {
+        let status = resp.status().as_u16();
+        let body = resp.text().await.unwrap_or_default();
+        log::debug!("Anthropic API error body: {}", body);
+        return Err(format!("Anthropic API error ({})", status));
+    }
+    let body: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+    let (text, input_tokens, output_tokens) = parse_anthropic_response(&body)?;
+
+    Ok(AuxiliaryCompletion {
+        text,
+        model: FALLBACK_MODEL.to_string(),
+        local: false,
+        input_tokens,
+        output_tokens,
+        total_cost: fallback_cost(input_tokens, output_tokens),
+        duration_ms: started.elapsed().as_millis() as i64,
+    })
+}
+
+/// Extract `(text, prompt_tokens, completion_tokens)` from an Ollama `/api/chat` reply.
+fn parse_ollama_response(body: &serde_json::Value) -> Result<(String, i32, i32), String> {
+    let text = body["message"]["content"]
+        .as_str()
+        .filter(|t| !t.trim().is_empty())
+        .ok_or_else(|| "No text in local model response".to_string())?;
+    Ok((
+        text.to_string(),
+        body["prompt_eval_count"].as_i64().unwrap_or(0) as i32,
+        body["eval_count"].as_i64().unwrap_or(0) as i32,
+    ))
+}
+
+/// Extract `(text, input_tokens, output_tokens)` from an Anthropic Messages reply.
+pub(crate) fn parse_anthropic_response(body: &serde_json::Value) -> Result<(String, i32, i32), String> {
+    let text = body["content"][0]["text"]
+        .as_str()
+        .ok_or_else(|| "No text in API response".to_string())?;
+    Ok((
+        text.to_string(),
+        body["usage"]["input_tokens"].as_i64().unwrap_or(0) as i32,
+        body["usage"]["output_tokens"].as_i64().unwrap_or(0) as i32,
+    ))
+}
+
+fn fallback_cost(input_tokens: i32, output_tokens: i32) -> f64 {
+    list_price(FALLBACK_MODEL, input_tokens as i64, output_tokens as i64, 0, 0)
+}
+
+/// Record an auxiliary completion in usage as a one-turn synthetic session.
+pub(crate) fn record_usage(
+    conn: &rusqlite::Connection,
+    skill_name: &str,
+    completion: &AuxiliaryCompletion,
+) -> Result<(), String> {
+    let agent_id = format!("aux-{}", uuid::Uuid::new_v4());
+    let session_id = format!("synthetic:auxiliary:{}:{}", skill_name, agent_id);
+    crate::db::persist_agent_run(
+        conn,
+        &agent_id,
+        skill_name,
+        AUXILIARY_STEP_ID,
+        &completion.model,
+        "completed",
+        completion.input_tokens,
+        completion.output_tokens,
+        0,
+        0,
+        completion.total_cost,
+        completion.duration_ms,
+        1,
+        Some("end_turn"),
+        Some(completion.duration_ms),
+        0,
+        0,
+        None,
+        Some(&session_id),
+    )
+}
+
+/// List models installed on an Ollama server, for picking the local model in Settings.
+#[tauri::command]
+pub async fn list_local_models(endpoint: String) -> Result<Vec<String>, String> {
+    log::info!("[list_local_models] endpoint={}", endpoint);
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(5))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let resp = client
+        .get(format!("{}/api/tags", endpoint.trim().trim_end_matches('/')))
+        .send()
+        .await
+        .map_err(|e| {
+            log::error!("[list_local_models] request failed: {}", e);
+            format!("Could not reach local model server: {}", e)
+        })?;
+    if !resp.status().is_success() {
+        let status = resp.status().as_u16();
+        log::error!("[list_local_models] server returned {}", status);
+        return Err(format!("Local model server error ({})", status));
+    }
+    let body: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+    Ok(body["models"]
+        .as_array()
+        .map(|models| {
+            models
+                .iter()
+                .filter_map(|m| m["name"].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_requires_both_endpoint_and_model() {
+        let mut settings = AppSettings {
+            local_model_endpoint: Some("http://localhost:11434/".to_string()),
+            ..Default::default()
+        };
+        assert!(AuxiliaryRoute::from_settings(&settings).local().is_none());
+
+        settings.local_model = Some("  ".to_string());
+        assert!(AuxiliaryRoute::from_settings(&settings).local().is_none());
+
+        settings.local_model = Some("llama3.1:8b".to_string());
+        assert_eq!(
+            AuxiliaryRoute::from_settings(&settings).local(),
+            Some(("http://localhost:11434", "llama3.1:8b"))
+        );
+    }
+
+    #[test]
+    fn test_parse_responses() {
+        let ollama = serde_json::json!({
+            "message": {"role": "assistant", "content": "{\"domain\":\"Sales\"}"},
+            "prompt_eval_count": 120,
+            "eval_count": 30
+        });
+        assert_eq!(
+            parse_ollama_response(&ollama).unwrap(),
+            ("{\"domain\":\"Sales\"}".to_string(), 120, 30)
+        );
+        assert!(parse_ollama_response(&serde_json::json!({"message": {"content": ""}})).is_err());
+
+        let anthropic = serde_json::json!({
+            "content": [{"type": "text", "text": "ok"}],
+            "usage": {"input_tokens": 1000, "output_tokens": 200}
+        });
+        assert_eq!(parse_anthropic_response(&anthropic).unwrap(), ("ok".to_string(), 1000, 200));
+        assert!((fallback_cost(1000, 200) - 0.002).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_local_usage_recorded_at_zero_cost() {
+        // agent_runs needs the full migrated schema (cache/token columns).
+        let tmp = tempfile::tempdir().unwrap();
+        let db = crate::db::init_db(tmp.path()).unwrap();
+        let conn = db.0.lock().unwrap();
+        let completion = AuxiliaryCompletion {
+            text: "{}".to_string(),
+            model: "ollama/llama3.1:8b".to_string(),
+            local: true,
+            input_tokens: 500,
+            output_tokens: 80,
+            total_cost: 0.0,
+            duration_ms: 900,
+        };
+        record_usage(&conn, "my-skill", &completion).unwrap();
+
+        let (model, step_id, cost, tokens): (String, i32, f64, i32) = conn
+            .query_row(
+                "SELECT model, step_id, total_cost, input_tokens FROM agent_runs",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(model, "ollama/llama3.1:8b");
+        assert_eq!(step_id, AUXILIARY_STEP_ID);
+        assert_eq!(cost, 0.0);
+        assert_eq!(tokens, 500);
+    }
+}
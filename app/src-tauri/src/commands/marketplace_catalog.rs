@@ -0,0 +1,319 @@
+//! Local cache of marketplace catalog listings.
+//!
+//! `list_github_skills` answers from `marketplace_catalog_cache` when it can and
+//! revalidates stale entries in the background. Enabled registries are also
+//! prefetched as a low-priority background task, so opening the marketplace
+//! rarely waits on the network.
+//! Revalidation compares the repository tree SHA first; an unchanged tree skips
+//! every file fetch. Otherwise only the SKILL.md files whose blob SHA changed
+//! are refetched, unless `marketplace.json` itself changed.
+
+use tauri::{Emitter, Manager};
+
+use crate::commands::github_import::{list_github_skills_conditional, parse_github_url_for, CatalogFetch};
+use crate::commands::import_progress::ImportProgressReporter;
+use crate::db::Db;
+use crate::github::GitHubServer;
+use crate::scheduler::BackgroundScheduler;
+use crate::types::{BackgroundTaskKind, CachedCatalog, TaskPriority};
+
+/// Cached listings older than this are served, then refreshed in the background.
+pub(crate) const CATALOG_TTL_SECS: i64 = 15 * 60;
+
+/// How often the idle prefetch loop wakes up.
+const PREFETCH_INTERVAL_SECS: u64 = 5 * 60;
+
+/// Emitted with the new `CachedCatalog` when a refresh changes a listing.
+pub(crate) const CATALOG_UPDATED_EVENT: &str = "marketplace-catalog-updated";
+
+/// Identify a listing by its server and the same arguments the browse command receives.
+pub(crate) fn catalog_cache_key(
+    server: &GitHubServer,
+    owner: &str,
+    repo: &str,
+    branch: &str,
+    subpath: Option<&str>,
+) -> String {
+    let host = server.host();
+    match subpath.filter(|s| !s.is_empty()) {
+        Some(sub) => format!("{}/{}/{}@{}:{}", host, owner, repo, branch, sub),
+        None => format!("{}/{}/{}@{}", host, owner, repo, branch),
+    }
+}
+
+/// True when `fetched_at` is unparseable or older than `ttl_secs` relative to `now`.
+pub(crate) fn is_stale(fetched_at: &str, now: chrono::DateTime<chrono::Utc>, ttl_secs: i64) -> bool {
+    match chrono::DateTime::parse_from_rfc3339(fetched_at) {
+        Ok(ts) => now.signed_duration_since(ts.with_timezone(&chrono::Utc)).num_seconds() >= ttl_secs,
+        Err(_) => true,
+    }
+}
+
+fn skills_changed(previous: Option<&CachedCatalog>, fetched: &CachedCatalog) -> bool {
+    match previous {
+        None => true,
+        Some(prev) => {
+            prev.registry_name != fetched.registry_name
+                || serde_json::to_value(&prev.skills).ok() != serde_json::to_value(&fetched.skills).ok()
+        }
+    }
+}
+
+/// Revalidate one listing against GitHub and update the cache.
+/// Returns the new listing when it differs from what was cached, `None` when unchanged.
+/// A listing cancelled through `progress` is returned as-is and not cached.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn refresh_catalog(
+    db: &Db,
+    server: &GitHubServer,
+    owner: &str,
+    repo: &str,
+    branch: &str,
+    subpath: Option<&str>,
+    token: Option<&str>,
+    progress: Option<&ImportProgressReporter>,
+) -> Result<Option<CachedCatalog>, String> {
+    let cache_key = catalog_cache_key(server, owner, repo, branch, subpath);
+    let previous = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        crate::db::get_cached_catalog(&conn, &cache_key)?
+    };
+    let fetched_at = chrono::Utc::now().to_rfc3339();
+
+    match list_github_skills_conditional(server, owner, repo, branch, subpath, token, previous.as_ref(), progress)
+        .await?
+    {
+        CatalogFetch::NotModified => {
+            let conn = db.0.lock().map_err(|e| e.to_string())?;
+            crate::db::touch_cached_catalog(&conn, &cache_key, &fetched_at)?;
+            Ok(None)
+        }
+        CatalogFetch::Fetched {
+            tree_sha,
+            blob_shas,
+            registry_name,
+            skills,
+            complete,
+        } => {
+            let catalog = CachedCatalog {
+                cache_key,
+                tree_sha,
+                blob_shas,
+                registry_name,
+                skills,
+                fetched_at,
+            };
+            if !complete {
+                return Ok(Some(catalog));
+            }
+            let changed = skills_changed(previous.as_ref(), &catalog);
+            let conn = db.0.lock().map_err(|e| e.to_string())?;
+            crate::db::save_cached_catalog(&conn, &catalog)?;
+            Ok(changed.then_some(catalog))
+        }
+    }
+}
+
+/// Refresh a listing off the request path and emit `CATALOG_UPDATED_EVENT` if it changed.
+pub(crate) fn spawn_background_refresh(
+    app: tauri::AppHandle,
+    server: GitHubServer,
+    owner: String,
+    repo: String,
+    branch: String,
+    subpath: Option<String>,
+    token: Option<String>,
+) {
+    tauri::async_runtime::spawn(async move {
+        let db = app.state::<Db>();
+        match refresh_catalog(&db, &server, &owner, &repo, &branch, subpath.as_deref(), token.as_deref(), None).await {
+            Ok(Some(catalog)) => emit_catalog_updated(&app, &catalog),
+            Ok(None) => {}
+            Err(e) => log::warn!(
+                "[marketplace_catalog] background refresh failed for {}/{}: {}",
+                owner,
+                repo,
+                e
+            ),
+        }
+    });
+}
+
+fn emit_catalog_updated(app: &tauri::AppHandle, catalog: &CachedCatalog) {
+    log::info!(
+        "[marketplace_catalog] {} updated ({} skills)",
+        catalog.cache_key,
+        catalog.skills.len()
+    );
+    if let Err(e) = app.emit(CATALOG_UPDATED_EVENT, catalog) {
+        log::warn!("Failed to emit {}: {}", CATALOG_UPDATED_EVENT, e);
+    }
+}
+
+/// Queue a catalog prefetch every `PREFETCH_INTERVAL_SECS`. The background
+/// scheduler runs it at low priority, so it waits while agents are running or
+/// the battery is low. Safe to call from `setup()`.
+pub fn start_idle_prefetch(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(PREFETCH_INTERVAL_SECS)).await;
+            app.state::<BackgroundScheduler>()
+                .enqueue(BackgroundTaskKind::CatalogPrefetch, TaskPriority::Low);
+        }
+    });
+}
+
+/// Revalidate every enabled registry whose cached listing is missing or stale.
+pub(crate) async fn prefetch_registries(app: &tauri::AppHandle) {
+    let db = app.state::<Db>();
+    let (registries, server, token) = {
+        let conn = match db.0.lock() {
+            Ok(c) => c,
+            Err(e) => {
+                log::warn!("[marketplace_catalog] Failed to acquire DB lock: {}", e);
+                return;
+            }
+        };
+        match crate::db::read_settings_hydrated(&conn) {
+            Ok(settings) if !super::offline::feature_enabled(&settings, super::offline::NetworkFeature::Marketplace) => {
+                log::debug!("[marketplace_catalog] marketplace disabled; skipping prefetch");
+                return;
+            }
+            Ok(settings) => match GitHubServer::from_settings(&settings) {
+                Ok(server) => (settings.marketplace_registries, server, settings.github_oauth_token),
+                Err(e) => {
+                    log::warn!("[marketplace_catalog] Invalid GitHub server settings: {}", e);
+                    return;
+                }
+            },
+            Err(e) => {
+                log::warn!("[marketplace_catalog] Failed to read settings: {}", e);
+                return;
+            }
+        }
+    };
+
+    let now = chrono::Utc::now();
+    for registry in registries.iter().filter(|r| r.enabled) {
+        let info = match parse_github_url_for(&registry.source_url, &server) {
+            Ok(info) => info,
+            Err(e) => {
+                log::debug!("[marketplace_catalog] skipping registry '{}': {}", registry.name, e);
+                continue;
+            }
+        };
+        let cache_key = catalog_cache_key(&server, &info.owner, &info.repo, &info.branch, info.subpath.as_deref());
+        let fresh = db
+            .0
+            .lock()
+            .ok()
+            .and_then(|conn| crate::db::get_cached_catalog(&conn, &cache_key).ok().flatten())
+            .is_some_and(|c| !is_stale(&c.fetched_at, now, CATALOG_TTL_SECS));
+        if fresh {
+            continue;
+        }
+
+        match refresh_catalog(
+            &db,
+            &server,
+            &info.owner,
+            &info.repo,
+            &info.branch,
+            info.subpath.as_deref(),
+            token.as_deref(),
+            None,
+        )
+        .await
+        {
+            Ok(Some(catalog)) => emit_catalog_updated(app, &catalog),
+            Ok(None) => log::debug!("[marketplace_catalog] {} unchanged", cache_key),
+            Err(e) => log::warn!(
+                "[marketplace_catalog] prefetch failed for registry '{}': {}",
+                registry.name,
+                e
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AvailableSkill;
+
+    fn skill(name: &str, version: &str) -> AvailableSkill {
+        AvailableSkill {
+            path: format!("skills/{}", name),
+            name: name.to_string(),
+            plugin_name: None,
+            description: None,
+            purpose: None,
+            version: Some(version.to_string()),
+            model: None,
+            argument_hint: None,
+            user_invocable: None,
+            disable_model_invocation: None,
+        }
+    }
+
+    fn catalog(skills: Vec<AvailableSkill>) -> CachedCatalog {
+        CachedCatalog {
+            cache_key: "github.com/acme/skills@main".to_string(),
+            tree_sha: None,
+            blob_shas: Default::default(),
+            registry_name: Some("Acme".to_string()),
+            skills,
+            fetched_at: "2026-01-01T00:00:00+00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_cache_key_includes_subpath_only_when_present() {
+        let server = GitHubServer::default();
+        assert_eq!(catalog_cache_key(&server, "acme", "skills", "main", None), "github.com/acme/skills@main");
+        assert_eq!(
+            catalog_cache_key(&server, "acme", "skills", "main", Some("")),
+            "github.com/acme/skills@main"
+        );
+        assert_eq!(
+            catalog_cache_key(&server, "acme", "skills", "main", Some("plugins")),
+            "github.com/acme/skills@main:plugins"
+        );
+    }
+
+    #[test]
+    fn test_cache_key_separates_servers() {
+        let enterprise = GitHubServer::enterprise("https://github.acme.com").unwrap();
+        assert_eq!(
+            catalog_cache_key(&enterprise, "acme", "skills", "main", None),
+            "github.acme.com/acme/skills@main"
+        );
+        assert_ne!(
+            catalog_cache_key(&enterprise, "acme", "skills", "main", None),
+            catalog_cache_key(&GitHubServer::default(), "acme", "skills", "main", None)
+        );
+    }
+
+    #[test]
+    fn test_is_stale_respects_ttl_and_bad_timestamps() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-01-01T00:20:00+00:00")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert!(!is_stale("2026-01-01T00:10:00+00:00", now, CATALOG_TTL_SECS));
+        assert!(is_stale("2026-01-01T00:05:00+00:00", now, CATALOG_TTL_SECS));
+        assert!(is_stale("not a timestamp", now, CATALOG_TTL_SECS));
+    }
+
+    #[test]
+    fn test_skills_changed_compares_listing_not_fetch_time() {
+        let old = catalog(vec![skill("a", "1.0.0")]);
+        let mut same = catalog(vec![skill("a", "1.0.0")]);
+        same.fetched_at = "2026-02-01T00:00:00+00:00".to_string();
+        same.tree_sha = Some("new".to_string());
+        assert!(!skills_changed(Some(&old), &same));
+
+        let bumped = catalog(vec![skill("a", "1.1.0")]);
+        assert!(skills_changed(Some(&old), &bumped));
+        assert!(skills_changed(None, &old));
+    }
+}
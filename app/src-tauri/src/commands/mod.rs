@@ -1,21 +1,89 @@
+pub mod activation_policy;
 pub mod agent;
+pub mod artifact_annotations;
+pub mod audit_log;
+pub mod automation_hooks;
+pub mod background_tasks;
+pub mod benchmark;
+pub mod bulk_skills;
 pub mod clarification;
+pub mod command_history;
+pub mod cost_estimate;
+pub mod data_retention;
+pub mod deployment_slots;
+pub mod diagnostics;
+pub mod error_help;
+pub mod eval_export;
+pub mod example_skills;
 pub mod feedback;
+pub mod feedback_bundle;
 pub mod files;
+pub mod frontmatter_migration;
 pub mod git;
+pub mod git_hosting;
 pub mod github_auth;
 pub mod github_import;
+pub mod import_progress;
 pub mod imported_skills;
+pub mod incremental_generation;
+pub mod intake;
+pub mod library_history;
 pub mod lifecycle;
+pub mod linked_references;
+pub mod local_model;
+pub mod marketplace_catalog;
+pub mod model_routing;
 pub mod node;
+pub mod observer;
+pub mod offline;
+pub mod onboarding;
+pub mod package_formats;
+pub mod project_context;
+pub mod prompt_contract;
+pub mod push_merge;
+pub mod quality_gates;
 pub mod refine;
+pub mod repo_context;
+pub mod research_fanout;
+pub mod review_description;
 pub mod settings;
+pub mod settings_sync;
 pub mod sidecar_lifecycle;
 pub mod skill;
+pub mod skill_compare;
+pub mod skill_dependencies;
+pub mod skill_impact;
+pub mod skill_invocations;
+pub mod skill_lint;
+pub mod skill_localization;
+pub mod skill_params;
+pub mod skill_preview;
+pub mod skill_references;
+pub mod skill_review;
+pub mod skill_scan;
+pub mod skill_signing;
+pub mod skill_similarity;
+pub mod skill_templates;
 pub mod skill_test;
+pub mod skill_test_cases;
+pub mod skill_validation;
+pub mod skill_version;
+pub mod step_artifacts;
+pub mod step_cache;
+pub mod step_explain;
+pub mod step_perf;
+pub mod step_replay;
+pub mod structured_diff;
+pub mod tag_taxonomy;
+pub mod team_taxonomy;
 #[cfg(test)]
 pub mod test_utils;
+pub mod tool_policy;
+pub mod transcripts;
 pub mod usage;
 pub mod workflow;
 pub mod workflow_lifecycle;
+pub mod workflow_manifest;
+pub mod workflow_queue;
 pub mod workspace;
+pub mod workspace_snapshots;
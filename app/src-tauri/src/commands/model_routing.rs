@@ -0,0 +1,190 @@
+//! Model routing for workflow steps.
+//!
+//! `AppSettings::model_routes` pick the model per step id, per skill type
+//! (purpose), or both. A route naming both beats one naming the step, which
+//! beats one naming the skill type; among equals the first listed wins.
+//! Without a match a step runs on its agent's front-matter model, or the
+//! preferred model when the agent names none.
+
+use crate::db::Db;
+use crate::types::{AppSettings, EffectiveModel, ModelRoute};
+
+use super::workflow::resolve_model_id;
+
+const SKILL_TYPES: &[&str] = &["domain", "source", "data-engineering", "platform"];
+
+pub(crate) const SOURCE_ROUTE: &str = "route";
+pub(crate) const SOURCE_AGENT: &str = "agent";
+pub(crate) const SOURCE_PREFERRED: &str = "preferred";
+
+/// Reject routes that match nothing, name an unknown skill type, have no
+/// model, or repeat another route's match.
+pub fn validate_routes(routes: &[ModelRoute]) -> Result<(), String> {
+    for (i, route) in routes.iter().enumerate() {
+        if route.step_id.is_none() && route.skill_type.is_none() {
+            return Err(format!("Model route {} needs a step or a skill type", i + 1));
+        }
+        if let Some(skill_type) = route.skill_type.as_deref() {
+            if !SKILL_TYPES.contains(&skill_type) {
+                return Err(format!(
+                    "Model route {} has unknown skill type '{}' (expected one of: {})",
+                    i + 1,
+                    skill_type,
+                    SKILL_TYPES.join(", ")
+                ));
+            }
+        }
+        let model = route.model.trim();
+        if model.is_empty() || model.contains(char::is_whitespace) {
+            return Err(format!("Model route {} has an invalid model '{}'", i + 1, route.model));
+        }
+        if routes[..i]
+            .iter()
+            .any(|r| r.step_id == route.step_id && r.skill_type == route.skill_type)
+        {
+            return Err(format!("Model route {} repeats an earlier route", i + 1));
+        }
+    }
+    Ok(())
+}
+
+fn specificity(route: &ModelRoute) -> u8 {
+    2 * u8::from(route.step_id.is_some()) + u8::from(route.skill_type.is_some())
+}
+
+/// The route deciding the model of `step_id` for a skill of `skill_type`.
+pub(crate) fn route_for<'a>(routes: &'a [ModelRoute], step_id: u32, skill_type: &str) -> Option<&'a ModelRoute> {
+    let mut best: Option<&ModelRoute> = None;
+    for route in routes {
+        let matches = route.step_id.is_none_or(|id| id == step_id)
+            && route.skill_type.as_deref().is_none_or(|t| t == skill_type);
+        if matches && best.is_none_or(|b| specificity(route) > specificity(b)) {
+            best = Some(route);
+        }
+    }
+    best
+}
+
+/// Full model id a route assigns to the step, if any.
+pub(crate) fn routed_model(routes: &[ModelRoute], step_id: u32, skill_type: &str) -> Option<String> {
+    route_for(routes, step_id, skill_type).map(|r| resolve_model_id(r.model.trim()))
+}
+
+fn effective_model_inner(
+    settings: &AppSettings,
+    workspace_path: &str,
+    step_id: u32,
+    skill_type: &str,
+) -> Result<EffectiveModel, String> {
+    if let Some(route) = route_for(&settings.model_routes, step_id, skill_type) {
+        return Ok(EffectiveModel {
+            step_id,
+            model: resolve_model_id(route.model.trim()),
+            source: SOURCE_ROUTE.to_string(),
+            route: Some(route.clone()),
+        });
+    }
+    let step = super::workflow_manifest::resolve_step(workspace_path, skill_type, step_id)?;
+    let phase = step.prompt_template.trim_end_matches(".md");
+    let agent_model = super::workflow::read_agent_frontmatter_field(workspace_path, phase, "model")
+        .filter(|m| m != "inherit");
+    let (model, source) = match agent_model {
+        Some(m) => (m, SOURCE_AGENT),
+        None => (
+            settings.preferred_model.clone().unwrap_or_else(|| "sonnet".to_string()),
+            SOURCE_PREFERRED,
+        ),
+    };
+    Ok(EffectiveModel {
+        step_id,
+        model: resolve_model_id(&model),
+        source: source.to_string(),
+        route: None,
+    })
+}
+
+/// The model `run_workflow_step` will use for a step of this skill.
+#[tauri::command]
+pub fn get_effective_model(
+    db: tauri::State<'_, Db>,
+    skill_name: String,
+    step_id: u32,
+    workspace_path: String,
+) -> Result<EffectiveModel, String> {
+    log::info!("[get_effective_model] skill={} step={}", skill_name, step_id);
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[get_effective_model] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let settings = crate::db::read_settings(&conn)?;
+    let skill_type = crate::db::get_purpose(&conn, &skill_name)?;
+    effective_model_inner(&settings, &workspace_path, step_id, &skill_type).map_err(|e| {
+        log::error!("[get_effective_model] {}", e);
+        e
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(step_id: Option<u32>, skill_type: Option<&str>, model: &str) -> ModelRoute {
+        ModelRoute {
+            step_id,
+            skill_type: skill_type.map(str::to_string),
+            model: model.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_most_specific_route_wins() {
+        let routes = vec![
+            route(None, Some("source"), "haiku"),
+            route(Some(2), None, "opus"),
+            route(Some(2), Some("source"), "sonnet"),
+        ];
+        assert_eq!(route_for(&routes, 2, "source").unwrap().model, "sonnet");
+        assert_eq!(route_for(&routes, 2, "domain").unwrap().model, "opus");
+        assert_eq!(route_for(&routes, 0, "source").unwrap().model, "haiku");
+        assert!(route_for(&routes, 0, "domain").is_none());
+        assert_eq!(routed_model(&routes, 2, "domain").as_deref(), Some("claude-opus-4-6"));
+    }
+
+    #[test]
+    fn test_validate_routes_rejects_bad_entries() {
+        assert!(validate_routes(&[route(Some(0), None, "haiku"), route(None, Some("platform"), "opus")]).is_ok());
+        assert!(validate_routes(&[route(None, None, "haiku")]).unwrap_err().contains("needs a step"));
+        assert!(validate_routes(&[route(None, Some("marketing"), "haiku")]).unwrap_err().contains("unknown skill type"));
+        assert!(validate_routes(&[route(Some(1), None, " ")]).unwrap_err().contains("invalid model"));
+        assert!(validate_routes(&[route(Some(1), None, "haiku"), route(Some(1), None, "opus")])
+            .unwrap_err()
+            .contains("repeats"));
+    }
+
+    #[test]
+    fn test_effective_model_falls_back_to_agent_then_preferred() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().to_str().unwrap();
+        let agents = dir.path().join(".claude").join("agents");
+        std::fs::create_dir_all(&agents).unwrap();
+        std::fs::write(agents.join("confirm-decisions.md"), "---\nname: confirm-decisions\nmodel: opus\n---\n").unwrap();
+        let mut settings = AppSettings {
+            preferred_model: Some("haiku".to_string()),
+            ..AppSettings::default()
+        };
+
+        let agent = effective_model_inner(&settings, workspace, 2, "domain").unwrap();
+        assert_eq!(agent.model, "claude-opus-4-6");
+        assert_eq!(agent.source, SOURCE_AGENT);
+
+        let preferred = effective_model_inner(&settings, workspace, 3, "domain").unwrap();
+        assert_eq!(preferred.model, "claude-haiku-4-5");
+        assert_eq!(preferred.source, SOURCE_PREFERRED);
+
+        settings.model_routes = vec![route(Some(2), None, "sonnet")];
+        let routed = effective_model_inner(&settings, workspace, 2, "domain").unwrap();
+        assert_eq!(routed.model, "claude-sonnet-4-6");
+        assert_eq!(routed.source, SOURCE_ROUTE);
+        assert_eq!(routed.route, Some(route(Some(2), None, "sonnet")));
+    }
+}
@@ -1,7 +1,7 @@
 use crate::agents::sidecar_pool;
 use crate::types::{DepStatus, NodeStatus, StartupDeps};
 
-fn dep_ok(code: &str, name: &str, detail: String) -> DepStatus {
+pub(crate) fn dep_ok(code: &str, name: &str, detail: String) -> DepStatus {
     DepStatus {
         code: Some(code.to_string()),
         failure_kind: None,
@@ -12,7 +12,7 @@ fn dep_ok(code: &str, name: &str, detail: String) -> DepStatus {
     }
 }
 
-fn dep_fail(
+pub(crate) fn dep_fail(
     code: &str,
     failure_kind: &str,
     name: &str,
@@ -67,10 +67,17 @@ pub async fn check_node(app: tauri::AppHandle) -> Result<NodeStatus, String> {
 #[tauri::command]
 pub async fn check_startup_deps(app: tauri::AppHandle) -> Result<StartupDeps, String> {
     log::info!("[check_startup_deps]");
+    let checks = startup_checks(&app).await;
+    let all_ok = checks.iter().all(|c| c.ok);
+    Ok(StartupDeps { all_ok, checks })
+}
+
+/// Node.js, the sidecar bundle, the SDK CLI and git. Shared with `run_diagnostics`.
+pub(crate) async fn startup_checks(app: &tauri::AppHandle) -> Vec<DepStatus> {
     let mut checks = Vec::new();
 
     // 1. Node.js
-    let node = match sidecar_pool::resolve_node_binary(&app).await {
+    let node = match sidecar_pool::resolve_node_binary(app).await {
         Ok(res) if res.meets_minimum => dep_ok(
             "node_runtime",
             "Node.js",
@@ -102,7 +109,7 @@ pub async fn check_startup_deps(app: tauri::AppHandle) -> Result<StartupDeps, St
     checks.push(node);
 
     // 2. Sidecar (agent-runner.js)
-    let sidecar = match sidecar_pool::resolve_sidecar_path_public(&app) {
+    let sidecar = match sidecar_pool::resolve_sidecar_path_public(app) {
         Ok(path) => dep_ok("agent_sidecar_bundle", "Agent sidecar", path),
         Err(e) => dep_fail(
             "agent_sidecar_bundle",
@@ -115,7 +122,7 @@ pub async fn check_startup_deps(app: tauri::AppHandle) -> Result<StartupDeps, St
     checks.push(sidecar);
 
     // 3. SDK CLI (cli.js)
-    let sdk = match crate::agents::sidecar::resolve_sdk_cli_path_public(&app) {
+    let sdk = match crate::agents::sidecar::resolve_sdk_cli_path_public(app) {
         Ok(path) => dep_ok("claude_sdk_cli", "Claude SDK", path),
         Err(e) => dep_fail(
             "claude_sdk_cli",
@@ -131,14 +138,12 @@ pub async fn check_startup_deps(app: tauri::AppHandle) -> Result<StartupDeps, St
     //    Windows: also validates git-bash which the SDK needs for the Bash tool
     let git_check = check_git_available().await;
     checks.push(git_check);
-
-    let all_ok = checks.iter().all(|c| c.ok);
-    Ok(StartupDeps { all_ok, checks })
+    checks
 }
 
 /// Check that git is available on PATH (both platforms) and git-bash is
 /// available on Windows (required by the Claude Code SDK for the Bash tool).
-async fn check_git_available() -> DepStatus {
+pub(crate) async fn check_git_available() -> DepStatus {
     // Check git on PATH
     let git_output = tokio::process::Command::new("git")
         .arg("--version")
@@ -0,0 +1,142 @@
+//! Read-only observer mode for a skill being edited in another instance.
+//!
+//! When `acquire_lock` fails because another live instance holds the skill,
+//! the workflow page opens it with `open_skill_readonly` instead of leaving.
+//! The skill is marked observed in this instance, and commands that change a
+//! skill refuse to run for it (`ensure_writable`) until this instance
+//! acquires the lock itself. The editing instance records its changes in
+//! `skill_events`; the observer polls `poll_skill_events` for rows written by
+//! other instances and reloads what changed.
+
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+use crate::db::Db;
+use crate::types::{ReadonlySkill, SkillEvent};
+use crate::InstanceInfo;
+
+pub const EVENT_WORKFLOW_SAVED: &str = "workflow.saved";
+pub const EVENT_WORKFLOW_RESET: &str = "workflow.reset";
+pub const EVENT_STEP_OUTPUT: &str = "step.output";
+pub const EVENT_LOCK_RELEASED: &str = "lock.released";
+
+/// Skills this instance has open read-only.
+static OBSERVED: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+fn is_observed(skill_name: &str) -> bool {
+    OBSERVED
+        .lock()
+        .is_ok_and(|observed| observed.contains(skill_name))
+}
+
+/// Refuse a change to a skill this instance only observes.
+pub(crate) fn ensure_writable(skill_name: &str) -> Result<(), String> {
+    if is_observed(skill_name) {
+        let msg = format!(
+            "Skill '{}' is open read-only because another instance is editing it",
+            skill_name
+        );
+        log::warn!("[observer] {}", msg);
+        return Err(msg);
+    }
+    Ok(())
+}
+
+/// Mark a skill observed so changes to it are refused.
+pub(crate) fn start_observing(skill_name: &str) {
+    if let Ok(mut observed) = OBSERVED.lock() {
+        observed.insert(skill_name.to_string());
+    }
+}
+
+/// Stop observing a skill, e.g. once this instance holds its lock.
+pub(crate) fn stop_observing(skill_name: &str) {
+    if let Ok(mut observed) = OBSERVED.lock() {
+        observed.remove(skill_name);
+    }
+}
+
+/// Tell observers in other instances that this one changed a skill. Never fails the caller.
+pub(crate) fn record(
+    conn: &rusqlite::Connection,
+    instance: &InstanceInfo,
+    skill_name: &str,
+    kind: &str,
+) {
+    if let Err(e) = crate::db::record_skill_event(conn, skill_name, &instance.id, kind) {
+        log::warn!(
+            "[observer] Failed to record {} for '{}': {}",
+            kind,
+            skill_name,
+            e
+        );
+    }
+}
+
+/// Open a skill without taking its lock. Changes to it are refused until
+/// `close_skill_readonly` or a successful `acquire_lock`.
+#[tauri::command]
+pub fn open_skill_readonly(
+    skill_name: String,
+    instance: tauri::State<'_, InstanceInfo>,
+    db: tauri::State<'_, Db>,
+) -> Result<ReadonlySkill, String> {
+    log::info!("[open_skill_readonly] skill={}", skill_name);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[open_skill_readonly] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let lock = crate::db::get_skill_lock(&conn, &skill_name)?
+        .filter(|lock| lock.instance_id != instance.id);
+    let last_event_id = crate::db::latest_skill_event_id(&conn)?;
+    start_observing(&skill_name);
+    Ok(ReadonlySkill {
+        skill_name,
+        lock,
+        last_event_id,
+    })
+}
+
+#[tauri::command]
+pub fn close_skill_readonly(skill_name: String) -> Result<(), String> {
+    log::info!("[close_skill_readonly] skill={}", skill_name);
+    stop_observing(&skill_name);
+    Ok(())
+}
+
+/// Changes other instances made to a skill since event `after_id`, oldest first.
+#[tauri::command]
+pub fn poll_skill_events(
+    skill_name: String,
+    after_id: i64,
+    instance: tauri::State<'_, InstanceInfo>,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<SkillEvent>, String> {
+    log::debug!(
+        "[poll_skill_events] skill={} after={}",
+        skill_name,
+        after_id
+    );
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[poll_skill_events] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::list_skill_events_after(&conn, &skill_name, after_id, &instance.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_observed_skill_is_not_writable() {
+        assert!(ensure_writable("observer-test-skill").is_ok());
+        start_observing("observer-test-skill");
+        assert!(ensure_writable("observer-test-skill")
+            .unwrap_err()
+            .contains("read-only"));
+        assert!(ensure_writable("other-skill").is_ok());
+        stop_observing("observer-test-skill");
+        assert!(ensure_writable("observer-test-skill").is_ok());
+    }
+}
@@ -0,0 +1,231 @@
+//! Offline (air-gapped) mode and network feature flags.
+//!
+//! With `offline_mode` on, every agent runs against the locally hosted server
+//! in `local_model_endpoint` using `local_model`, and marketplace and GitHub
+//! features are refused before they make a request. The server must accept
+//! Anthropic Messages API requests: Ollama (0.14+) and vLLM do natively, and
+//! other OpenAI-compatible servers work behind a LiteLLM proxy.
+//!
+//! Local runs still record token usage, but their cost is stored as NULL since
+//! there is no price to apply. `disable_marketplace` and `disable_github` turn
+//! off those features individually while leaving agents on the Anthropic API.
+
+use tauri::Manager;
+
+use crate::agents::sidecar::{LocalModelTarget, SidecarConfig};
+use crate::db::Db;
+use crate::types::{AppSettings, NetworkFeatures};
+
+/// Placeholder API key for local servers, which ignore it but the SDK requires one.
+pub(crate) const OFFLINE_API_KEY: &str = "offline";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NetworkFeature {
+    Marketplace,
+    GitHub,
+    /// Direct Anthropic API calls made outside the sidecar (benchmarks, lint fixes).
+    AnthropicApi,
+}
+
+impl NetworkFeature {
+    fn label(self) -> &'static str {
+        match self {
+            NetworkFeature::Marketplace => "Marketplace",
+            NetworkFeature::GitHub => "GitHub",
+            NetworkFeature::AnthropicApi => "Anthropic API",
+        }
+    }
+}
+
+pub(crate) fn feature_enabled(settings: &AppSettings, feature: NetworkFeature) -> bool {
+    if settings.offline_mode {
+        return false;
+    }
+    match feature {
+        NetworkFeature::Marketplace => !settings.disable_marketplace,
+        NetworkFeature::GitHub => !settings.disable_github,
+        NetworkFeature::AnthropicApi => true,
+    }
+}
+
+/// Fail with a user-facing error when `feature` is turned off. `caller` is the
+/// command name used as the log prefix.
+pub(crate) fn require_feature(db: &Db, feature: NetworkFeature, caller: &str) -> Result<(), String> {
+    let settings = {
+        let conn = db.0.lock().map_err(|e| {
+            log::error!("[{}] Failed to acquire DB lock: {}", caller, e);
+            e.to_string()
+        })?;
+        crate::db::read_settings(&conn)?
+    };
+    if feature_enabled(&settings, feature) {
+        return Ok(());
+    }
+    let reason = if settings.offline_mode { "offline mode is on" } else { "it is turned off in Settings" };
+    log::warn!("[{}] {} is disabled: {}", caller, feature.label(), reason);
+    Err(format!("{} features are unavailable because {}.", feature.label(), reason))
+}
+
+/// Local server to run agents against, when offline mode is on and both the
+/// endpoint and model are set.
+pub(crate) fn local_target(settings: &AppSettings) -> Option<LocalModelTarget> {
+    if !settings.offline_mode {
+        return None;
+    }
+    let base_url = settings.local_model_endpoint.as_deref().map(str::trim).filter(|s| !s.is_empty())?;
+    let model = settings.local_model.as_deref().map(str::trim).filter(|s| !s.is_empty())?;
+    Some(LocalModelTarget {
+        base_url: base_url.trim_end_matches('/').to_string(),
+        model: model.to_string(),
+    })
+}
+
+/// API key for agent runs: the placeholder when offline, otherwise the configured key.
+pub(crate) fn agent_api_key(settings: &AppSettings) -> Option<String> {
+    if local_target(settings).is_some() {
+        return Some(OFFLINE_API_KEY.to_string());
+    }
+    settings.anthropic_api_key.clone()
+}
+
+/// Whether runs started now go to a local server and so have no cost.
+pub(crate) fn runs_unpriced(app_handle: &tauri::AppHandle) -> bool {
+    app_handle
+        .try_state::<Db>()
+        .and_then(|db| {
+            let conn = db.0.lock().ok()?;
+            crate::db::read_settings(&conn).ok()
+        })
+        .is_some_and(|settings| local_target(&settings).is_some())
+}
+
+/// Point `config` at the local server when offline mode is on. Options the
+/// local server cannot honour (betas, thinking, an Anthropic fallback model)
+/// are dropped. Called by the sidecar pool for every agent launch.
+pub(crate) fn apply_to_config(settings: &AppSettings, config: &mut SidecarConfig) -> Result<(), String> {
+    if !settings.offline_mode {
+        config.local_model = None;
+        return Ok(());
+    }
+    let target = local_target(settings).ok_or_else(|| {
+        "Offline mode needs a local model endpoint and model name in Settings.".to_string()
+    })?;
+    config.model = Some(target.model.clone());
+    config.api_key = OFFLINE_API_KEY.to_string();
+    config.betas = None;
+    config.thinking = None;
+    config.fallback_model = None;
+    config.local_model = Some(target);
+    Ok(())
+}
+
+/// `apply_to_config` with settings read from the app's database.
+pub(crate) fn apply_for_app(app_handle: &tauri::AppHandle, config: &mut SidecarConfig) -> Result<(), String> {
+    let Some(db) = app_handle.try_state::<Db>() else {
+        return Ok(());
+    };
+    let settings = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        crate::db::read_settings(&conn)?
+    };
+    apply_to_config(&settings, config)
+}
+
+#[tauri::command]
+pub fn get_network_features(db: tauri::State<'_, Db>) -> Result<NetworkFeatures, String> {
+    log::info!("[get_network_features]");
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[get_network_features] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let settings = crate::db::read_settings(&conn)?;
+    let target = local_target(&settings);
+    Ok(NetworkFeatures {
+        offline_mode: settings.offline_mode,
+        marketplace: feature_enabled(&settings, NetworkFeature::Marketplace),
+        github: feature_enabled(&settings, NetworkFeature::GitHub),
+        local_model_ready: target.is_some(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SidecarConfig {
+        SidecarConfig {
+            prompt: "p".to_string(),
+            model: Some("claude-sonnet-4-6".to_string()),
+            api_key: "sk-ant-test".to_string(),
+            cwd: "/tmp".to_string(),
+            allowed_tools: None,
+            max_turns: None,
+            permission_mode: None,
+            betas: Some(vec!["interleaved-thinking-2025-05-14".to_string()]),
+            thinking: Some(serde_json::json!({"type": "adaptive"})),
+            fallback_model: Some("claude-sonnet-4-6".to_string()),
+            effort: None,
+            output_format: None,
+            prompt_suggestions: None,
+            path_to_claude_code_executable: None,
+            agent_name: None,
+            required_plugins: None,
+            conversation_history: None,
+            resume: None,
+            bash_sandbox: None,
+            disallowed_tools: None,
+            local_model: None,
+        }
+    }
+
+    #[test]
+    fn test_offline_mode_disables_network_features() {
+        let mut settings = AppSettings::default();
+        assert!(feature_enabled(&settings, NetworkFeature::Marketplace));
+        assert!(feature_enabled(&settings, NetworkFeature::GitHub));
+
+        settings.disable_github = true;
+        assert!(feature_enabled(&settings, NetworkFeature::Marketplace));
+        assert!(!feature_enabled(&settings, NetworkFeature::GitHub));
+
+        settings.disable_github = false;
+        settings.offline_mode = true;
+        assert!(!feature_enabled(&settings, NetworkFeature::Marketplace));
+        assert!(!feature_enabled(&settings, NetworkFeature::GitHub));
+        assert!(!feature_enabled(&settings, NetworkFeature::AnthropicApi));
+    }
+
+    #[test]
+    fn test_apply_to_config_targets_local_server() {
+        let mut settings = AppSettings {
+            anthropic_api_key: Some("sk-ant-test".to_string()),
+            local_model_endpoint: Some("http://localhost:11434/".to_string()),
+            local_model: Some("qwen3-coder".to_string()),
+            ..Default::default()
+        };
+
+        let mut online = config();
+        apply_to_config(&settings, &mut online).unwrap();
+        assert_eq!(online.api_key, "sk-ant-test");
+        assert!(online.local_model.is_none());
+        assert_eq!(agent_api_key(&settings).as_deref(), Some("sk-ant-test"));
+
+        settings.offline_mode = true;
+        let mut offline = config();
+        apply_to_config(&settings, &mut offline).unwrap();
+        assert_eq!(
+            offline.local_model,
+            Some(LocalModelTarget {
+                base_url: "http://localhost:11434".to_string(),
+                model: "qwen3-coder".to_string(),
+            })
+        );
+        assert_eq!(offline.model.as_deref(), Some("qwen3-coder"));
+        assert_eq!(offline.api_key, OFFLINE_API_KEY);
+        assert!(offline.betas.is_none() && offline.thinking.is_none() && offline.fallback_model.is_none());
+        assert_eq!(agent_api_key(&settings).as_deref(), Some(OFFLINE_API_KEY));
+
+        settings.local_model = None;
+        assert!(apply_to_config(&settings, &mut config()).is_err());
+    }
+}
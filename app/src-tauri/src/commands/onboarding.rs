@@ -0,0 +1,360 @@
+//! Onboarding checklists for skill consumers.
+//!
+//! A teammate importing a skill needs to know what to set up before it works:
+//! MCP servers it calls, credentials it reads, tool versions it assumes, and a
+//! few prompts to try. These are derived from SKILL.md and its references,
+//! written to CHECKLIST.md next to SKILL.md, and shipped in the `.skill`
+//! package. Authors may edit CHECKLIST.md; packaging only generates it when
+//! missing, and imports read it back as structured data.
+
+use crate::db::Db;
+use crate::types::OnboardingChecklist;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+pub const CHECKLIST_FILE: &str = "CHECKLIST.md";
+
+const SECTION_MCP: &str = "MCP servers";
+const SECTION_CREDENTIALS: &str = "Credentials";
+const SECTION_ENVIRONMENT: &str = "Environment";
+const SECTION_PROMPTS: &str = "First prompts";
+
+/// Env var suffixes that indicate a secret.
+const CREDENTIAL_SUFFIXES: &[&str] = &["_KEY", "_TOKEN", "_SECRET", "_PASSWORD", "_CREDENTIALS", "_PAT"];
+
+/// Tools whose versions are worth checking, with their display names.
+const VERSIONED_TOOLS: &[(&str, &str)] = &[
+    ("dbt", "dbt"),
+    ("dbt-core", "dbt-core"),
+    ("python", "Python"),
+    ("node", "Node.js"),
+    ("node.js", "Node.js"),
+    ("java", "Java"),
+    ("spark", "Spark"),
+    ("airflow", "Airflow"),
+    ("terraform", "Terraform"),
+    ("postgres", "PostgreSQL"),
+    ("postgresql", "PostgreSQL"),
+];
+
+const MAX_FIRST_PROMPTS: usize = 3;
+
+fn trim_token(token: &str) -> &str {
+    token.trim_matches(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-'))
+}
+
+/// `mcp__{server}__{tool}` references plus prose like "the Snowflake MCP server".
+fn find_mcp_servers(text: &str) -> BTreeSet<String> {
+    let mut servers = BTreeSet::new();
+    let mut rest = text;
+    while let Some(pos) = rest.find("mcp__") {
+        let after = &rest[pos + 5..];
+        let name: String = after
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+            .collect();
+        if let Some(server) = name.split("__").next().filter(|s| !s.is_empty()) {
+            servers.insert(server.to_string());
+        }
+        rest = after;
+    }
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    for i in 1..words.len().saturating_sub(1) {
+        if words[i] == "MCP" && words[i + 1].to_lowercase().starts_with("server") {
+            let prev = trim_token(words[i - 1]);
+            if prev.chars().next().is_some_and(|c| c.is_ascii_uppercase()) {
+                servers.insert(prev.to_lowercase());
+            }
+        }
+    }
+    servers
+}
+
+/// Upper-snake-case identifiers ending in a secret-like suffix.
+fn find_credentials(text: &str) -> BTreeSet<String> {
+    text.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .filter(|t| {
+            t.len() > 4
+                && t.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+                && t.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+                && CREDENTIAL_SUFFIXES.iter().any(|s| t.ends_with(s))
+        })
+        .map(|t| t.to_string())
+        .collect()
+}
+
+fn is_version(token: &str) -> bool {
+    let core = token
+        .trim_start_matches(['>', '<', '=', '~', '^', 'v'])
+        .trim_end_matches('+')
+        .trim_end_matches(".x");
+    !core.is_empty()
+        && core.chars().next().is_some_and(|c| c.is_ascii_digit())
+        && core.chars().all(|c| c.is_ascii_digit() || c == '.')
+}
+
+/// "dbt 1.7+", "Python >= 3.11", "Node.js v18".
+fn find_environment(text: &str) -> BTreeSet<String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut found = BTreeSet::new();
+    for (i, word) in words.iter().enumerate() {
+        let tool = trim_token(word).to_lowercase();
+        let Some((_, display)) = VERSIONED_TOOLS.iter().find(|(k, _)| *k == tool) else {
+            continue;
+        };
+        let mut j = i + 1;
+        let mut op = String::new();
+        if let Some(next) = words.get(j) {
+            if matches!(*next, ">=" | ">" | "<=" | "~=" | "==") {
+                op = next.to_string();
+                j += 1;
+            } else if next.eq_ignore_ascii_case("version") {
+                j += 1;
+            }
+        }
+        if let Some(candidate) = words.get(j).map(|w| w.trim_end_matches([',', ';', ')', '`', '.', ':'])) {
+            let candidate = candidate.trim_start_matches('`');
+            if is_version(candidate) {
+                found.insert(format!("{} {}{}", display, op, candidate));
+            }
+        }
+    }
+    found
+}
+
+/// Quoted list items under an "Example" heading, or a slash command built
+/// from the argument hint, falling back to the description.
+fn find_first_prompts(skill_md: &str, name: &str) -> Vec<String> {
+    let fm = super::imported_skills::parse_frontmatter_full(skill_md);
+    let mut prompts = Vec::new();
+
+    let mut in_examples = false;
+    for line in skill_md.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') {
+            in_examples = trimmed.to_lowercase().contains("example");
+            continue;
+        }
+        if !in_examples {
+            continue;
+        }
+        let Some(item) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) else {
+            continue;
+        };
+        let item = item.trim();
+        for quote in ['"', '“'] {
+            if let Some(inner) = item.strip_prefix(quote) {
+                if let Some(end) = inner.find(['"', '”']) {
+                    prompts.push(inner[..end].to_string());
+                }
+            }
+        }
+        if prompts.len() >= MAX_FIRST_PROMPTS {
+            break;
+        }
+    }
+
+    if prompts.is_empty() && fm.user_invocable == Some(true) {
+        match fm.argument_hint.as_deref().filter(|h| !h.trim().is_empty()) {
+            Some(hint) => prompts.push(format!("/{} {}", name, hint.trim())),
+            None => prompts.push(format!("/{}", name)),
+        }
+    }
+    if prompts.is_empty() {
+        if let Some(desc) = fm.description.filter(|d| !d.trim().is_empty()) {
+            prompts.push(format!("Using the {} skill: {}", name, desc.trim()));
+        }
+    }
+    prompts.truncate(MAX_FIRST_PROMPTS);
+    prompts
+}
+
+/// Derive the checklist from SKILL.md and the text of its reference files.
+pub fn derive_checklist(skill_name: &str, skill_md: &str, references: &[String]) -> OnboardingChecklist {
+    let mut text = skill_md.to_string();
+    for r in references {
+        text.push('\n');
+        text.push_str(r);
+    }
+    OnboardingChecklist {
+        mcp_servers: find_mcp_servers(&text).into_iter().collect(),
+        credentials: find_credentials(&text).into_iter().collect(),
+        environment: find_environment(&text).into_iter().collect(),
+        first_prompts: find_first_prompts(skill_md, skill_name),
+    }
+}
+
+/// Render CHECKLIST.md. Section titles are what `parse_checklist_md` reads back.
+pub fn render_checklist_md(skill_name: &str, checklist: &OnboardingChecklist) -> String {
+    let mut out = format!(
+        "# Onboarding checklist: {}\n\nComplete these before using the skill.\n",
+        skill_name
+    );
+    let sections: [(&str, &Vec<String>, bool); 4] = [
+        (SECTION_MCP, &checklist.mcp_servers, true),
+        (SECTION_CREDENTIALS, &checklist.credentials, true),
+        (SECTION_ENVIRONMENT, &checklist.environment, true),
+        (SECTION_PROMPTS, &checklist.first_prompts, false),
+    ];
+    for (title, items, is_task) in sections {
+        out.push_str(&format!("\n## {}\n\n", title));
+        if items.is_empty() {
+            out.push_str("_None detected._\n");
+            continue;
+        }
+        for item in items {
+            if is_task {
+                out.push_str(&format!("- [ ] `{}`\n", item));
+            } else {
+                out.push_str(&format!("- {}\n", item));
+            }
+        }
+    }
+    out
+}
+
+/// Read a CHECKLIST.md back into structured data. Unknown sections are ignored.
+pub fn parse_checklist_md(content: &str) -> OnboardingChecklist {
+    let mut checklist = OnboardingChecklist::default();
+    let mut section: Option<&mut Vec<String>> = None;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if let Some(title) = trimmed.strip_prefix("## ") {
+            section = match title.trim() {
+                SECTION_MCP => Some(&mut checklist.mcp_servers),
+                SECTION_CREDENTIALS => Some(&mut checklist.credentials),
+                SECTION_ENVIRONMENT => Some(&mut checklist.environment),
+                SECTION_PROMPTS => Some(&mut checklist.first_prompts),
+                _ => None,
+            };
+            continue;
+        }
+        let Some(items) = section.as_deref_mut() else {
+            continue;
+        };
+        let Some(item) = trimmed.strip_prefix("- ") else {
+            continue;
+        };
+        let item = item
+            .trim_start_matches("[ ]")
+            .trim_start_matches("[x]")
+            .trim()
+            .trim_matches('`')
+            .to_string();
+        if !item.is_empty() {
+            items.push(item);
+        }
+    }
+    checklist
+}
+
+fn read_references(dir: &Path, out: &mut Vec<String>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            read_references(&path, out);
+        } else if let Ok(text) = std::fs::read_to_string(&path) {
+            out.push(text);
+        }
+    }
+}
+
+/// Derive and write CHECKLIST.md into a skill directory (overwrites).
+pub fn write_checklist(skill_dir: &Path, skill_name: &str) -> Result<OnboardingChecklist, String> {
+    let skill_md = std::fs::read_to_string(skill_dir.join("SKILL.md"))
+        .map_err(|e| format!("Failed to read SKILL.md for '{}': {}", skill_name, e))?;
+    let mut references = Vec::new();
+    read_references(&skill_dir.join("references"), &mut references);
+    let checklist = derive_checklist(skill_name, &skill_md, &references);
+    std::fs::write(skill_dir.join(CHECKLIST_FILE), render_checklist_md(skill_name, &checklist))
+        .map_err(|e| format!("Failed to write {}: {}", CHECKLIST_FILE, e))?;
+    Ok(checklist)
+}
+
+/// Generate CHECKLIST.md for a skill in the skills folder and return it as structured data.
+#[tauri::command]
+pub fn generate_onboarding_checklist(
+    skill_name: String,
+    db: tauri::State<'_, Db>,
+) -> Result<OnboardingChecklist, String> {
+    log::info!("[generate_onboarding_checklist] skill={}", skill_name);
+    super::imported_skills::validate_skill_name(&skill_name)?;
+    let skills_path = {
+        let conn = db.0.lock().map_err(|e| {
+            log::error!("[generate_onboarding_checklist] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        crate::db::read_settings(&conn)?
+            .skills_path
+            .ok_or_else(|| "Skills path not configured. Please set it in Settings.".to_string())?
+    };
+    let checklist = write_checklist(&Path::new(&skills_path).join(&skill_name), &skill_name).map_err(|e| {
+        log::error!("[generate_onboarding_checklist] {}", e);
+        e
+    })?;
+    log::info!(
+        "[generate_onboarding_checklist] '{}': {} MCP, {} credentials, {} environment, {} prompts",
+        skill_name,
+        checklist.mcp_servers.len(),
+        checklist.credentials.len(),
+        checklist.environment.len(),
+        checklist.first_prompts.len()
+    );
+    Ok(checklist)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SKILL_MD: &str = "---\nname: warehouse-loads\ndescription: Build incremental loads\nuser-invocable: true\nargument-hint: \"[model-name]\"\n---\n# Warehouse loads\n\nRequires dbt >= 1.7 and Python 3.11. Query with `mcp__snowflake__run_query`.\nSet `SNOWFLAKE_PASSWORD` and `DBT_CLOUD_API_KEY`; `DBT_PROFILE` is optional.\n\n## Examples\n\n- \"Build an incremental model for orders\"\n- \"Add a snapshot for customers\"\n";
+
+    #[test]
+    fn test_derive_checklist_from_content() {
+        let refs = vec!["Uses the GitHub MCP server to open PRs. Node.js v18 for tooling.".to_string()];
+        let c = derive_checklist("warehouse-loads", SKILL_MD, &refs);
+        assert_eq!(c.mcp_servers, vec!["github", "snowflake"]);
+        assert_eq!(c.credentials, vec!["DBT_CLOUD_API_KEY", "SNOWFLAKE_PASSWORD"]);
+        assert_eq!(c.environment, vec!["Node.js v18", "Python 3.11", "dbt >=1.7"]);
+        assert_eq!(
+            c.first_prompts,
+            vec!["Build an incremental model for orders", "Add a snapshot for customers"]
+        );
+    }
+
+    #[test]
+    fn test_first_prompt_falls_back_to_slash_command_and_description() {
+        let invocable = "---\nname: s\nuser-invocable: true\nargument-hint: \"[table]\"\n---\nbody";
+        assert_eq!(find_first_prompts(invocable, "s"), vec!["/s [table]"]);
+        let plain = "---\nname: s\ndescription: Explain models\n---\nbody";
+        assert_eq!(find_first_prompts(plain, "s"), vec!["Using the s skill: Explain models"]);
+    }
+
+    #[test]
+    fn test_render_and_parse_round_trip() {
+        let c = derive_checklist("warehouse-loads", SKILL_MD, &[]);
+        let md = render_checklist_md("warehouse-loads", &c);
+        assert!(md.contains("- [ ] `SNOWFLAKE_PASSWORD`"));
+        assert_eq!(parse_checklist_md(&md), c);
+
+        let empty = render_checklist_md("x", &OnboardingChecklist::default());
+        assert!(empty.contains("_None detected._"));
+        assert_eq!(parse_checklist_md(&empty), OnboardingChecklist::default());
+    }
+
+    #[test]
+    fn test_write_checklist_reads_references() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("references")).unwrap();
+        std::fs::write(tmp.path().join("SKILL.md"), "---\nname: s\n---\nbody").unwrap();
+        std::fs::write(tmp.path().join("references/setup.md"), "Export GITHUB_TOKEN first.").unwrap();
+
+        let c = write_checklist(tmp.path(), "s").unwrap();
+        assert_eq!(c.credentials, vec!["GITHUB_TOKEN"]);
+        assert!(tmp.path().join(CHECKLIST_FILE).exists());
+    }
+}
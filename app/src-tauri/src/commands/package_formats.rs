@@ -0,0 +1,387 @@
+//! Target layouts for `package_skill`.
+//!
+//! `generic-zip` is the layout `create_skill_zip` writes: SKILL.md,
+//! references/ and the other packaged files at the archive root, saved as
+//! `{name}.skill`. The Claude formats are rewritten from that archive:
+//!
+//! - `claude-code` is a plugin: `.claude-plugin/plugin.json` plus the skill
+//!   under `skills/{name}/`, with bundled dependencies as sibling skills.
+//! - `claude-desktop` puts the skill in a `{name}/` folder, the layout the
+//!   Claude apps accept for upload, with the frontmatter `name` set to match.
+//!
+//! Relative links in markdown files are rewritten to follow the files they
+//! point at, and links written as absolute paths into the skill directory
+//! become relative. The format last packaged is remembered per skill.
+
+use std::io::{Read, Write};
+use std::path::Path;
+
+pub(crate) const FORMAT_GENERIC_ZIP: &str = "generic-zip";
+pub(crate) const FORMAT_CLAUDE_CODE: &str = "claude-code";
+pub(crate) const FORMAT_CLAUDE_DESKTOP: &str = "claude-desktop";
+
+/// Claude apps reject uploads whose description is longer than this.
+const DESKTOP_MAX_DESCRIPTION: usize = 1024;
+/// Plugin version when SKILL.md declares none.
+const DEFAULT_PLUGIN_VERSION: &str = "0.1.0";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PackageFormat {
+    GenericZip,
+    ClaudeCode,
+    ClaudeDesktop,
+}
+
+impl PackageFormat {
+    pub(crate) fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            FORMAT_GENERIC_ZIP => Ok(PackageFormat::GenericZip),
+            FORMAT_CLAUDE_CODE => Ok(PackageFormat::ClaudeCode),
+            FORMAT_CLAUDE_DESKTOP => Ok(PackageFormat::ClaudeDesktop),
+            other => Err(format!(
+                "Unknown package format '{}': expected '{}', '{}' or '{}'",
+                other, FORMAT_CLAUDE_CODE, FORMAT_CLAUDE_DESKTOP, FORMAT_GENERIC_ZIP
+            )),
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            PackageFormat::GenericZip => FORMAT_GENERIC_ZIP,
+            PackageFormat::ClaudeCode => FORMAT_CLAUDE_CODE,
+            PackageFormat::ClaudeDesktop => FORMAT_CLAUDE_DESKTOP,
+        }
+    }
+
+    /// Package file name: `{name}.skill` for the generic layout, otherwise a
+    /// `.zip` named after the format so packages for different surfaces can
+    /// sit side by side.
+    pub(crate) fn file_name(self, skill_name: &str, locale: Option<&str>) -> String {
+        let stem = match locale {
+            Some(lang) => format!("{}.{}", skill_name, lang),
+            None => skill_name.to_string(),
+        };
+        match self {
+            PackageFormat::GenericZip => format!("{}.skill", stem),
+            other => format!("{}-{}.zip", stem, other.as_str()),
+        }
+    }
+}
+
+/// The format to package `skill_name` in: `requested` when given, which is
+/// then remembered for the skill, else the skill's last format, else
+/// `generic-zip`.
+pub(crate) fn resolve_format(
+    conn: &rusqlite::Connection,
+    skill_name: &str,
+    requested: Option<&str>,
+) -> Result<PackageFormat, String> {
+    if let Some(requested) = requested {
+        let format = PackageFormat::parse(requested)?;
+        crate::db::set_skill_package_format(conn, skill_name, format.as_str())?;
+        return Ok(format);
+    }
+    match crate::db::get_skill_package_format(conn, skill_name)? {
+        Some(stored) => PackageFormat::parse(&stored).or_else(|e| {
+            log::warn!("[package_skill] Ignoring stored format for '{}': {}", skill_name, e);
+            Ok(PackageFormat::GenericZip)
+        }),
+        None => Ok(PackageFormat::GenericZip),
+    }
+}
+
+/// Where a file of the generic layout goes in `format`.
+fn target_path(format: PackageFormat, skill_name: &str, path: &str) -> String {
+    match format {
+        PackageFormat::GenericZip => path.to_string(),
+        PackageFormat::ClaudeDesktop => format!("{}/{}", skill_name, path),
+        PackageFormat::ClaudeCode => match path.strip_prefix("dependencies/") {
+            Some(dependency) => format!("skills/{}", dependency),
+            None => format!("skills/{}/{}", skill_name, path),
+        },
+    }
+}
+
+/// Collapse `.` and `..` segments; `None` when the path climbs above its root.
+fn normalize(path: &str) -> Option<String> {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            other => parts.push(other),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+/// `to` relative to the directory `from_dir`, both archive paths.
+fn relative_to(from_dir: &str, to: &str) -> String {
+    let from: Vec<&str> = from_dir.split('/').filter(|p| !p.is_empty()).collect();
+    let to_parts: Vec<&str> = to.split('/').filter(|p| !p.is_empty()).collect();
+    let common = from.iter().zip(&to_parts).take_while(|(a, b)| a == b).count();
+    let mut parts = vec![".."; from.len() - common];
+    parts.extend(&to_parts[common..]);
+    parts.join("/")
+}
+
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+/// New target for a link in the generic-layout file `file`, or `None` to
+/// leave it as written.
+fn rewrite_link(
+    format: PackageFormat,
+    skill_name: &str,
+    source_dir: &str,
+    file: &str,
+    target: &str,
+) -> Option<String> {
+    if target.is_empty() || target.starts_with('#') || target.contains("://") || target.starts_with("mailto:") {
+        return None;
+    }
+    let (path, fragment) = match target.find(['#', '?']) {
+        Some(pos) => target.split_at(pos),
+        None => (target, ""),
+    };
+    let linked = match path.strip_prefix(source_dir).and_then(|rest| rest.strip_prefix('/')) {
+        Some(inside) => normalize(inside)?,
+        None if path.starts_with('/') => return None,
+        None => normalize(&format!("{}/{}", parent_dir(file), path))?,
+    };
+    let rewritten = relative_to(
+        parent_dir(&target_path(format, skill_name, file)),
+        &target_path(format, skill_name, &linked),
+    );
+    let rewritten = format!("{}{}", rewritten, fragment);
+    (rewritten != target).then_some(rewritten)
+}
+
+/// Rewrite the inline links of a markdown file moving from `file` in the
+/// generic layout to its place in `format`. Code fences are left alone.
+pub(crate) fn rewrite_links(
+    format: PackageFormat,
+    skill_name: &str,
+    source_dir: &str,
+    file: &str,
+    content: &str,
+) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut in_fence = false;
+    for line in content.split_inclusive('\n') {
+        let fence = line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~");
+        if fence {
+            in_fence = !in_fence;
+        }
+        if fence || in_fence {
+            out.push_str(line);
+            continue;
+        }
+        let mut rest = line;
+        while let Some(pos) = rest.find("](") {
+            let (before, after) = rest.split_at(pos + 2);
+            out.push_str(before);
+            let end = after.find([')', ' ', '\t', '\n']).unwrap_or(after.len());
+            let target = &after[..end];
+            let bare = target.trim_matches(['<', '>']);
+            match rewrite_link(format, skill_name, source_dir, file, bare) {
+                Some(new_target) => out.push_str(&target.replace(bare, &new_target)),
+                None => out.push_str(target),
+            }
+            rest = &after[end..];
+        }
+        out.push_str(rest);
+    }
+    out
+}
+
+/// `.claude-plugin/plugin.json` for a Claude Code package.
+fn plugin_manifest(skill_name: &str, skill_md: &str) -> Result<String, String> {
+    let fm = super::imported_skills::parse_frontmatter_full(skill_md);
+    let mut manifest = serde_json::Map::new();
+    manifest.insert("name".to_string(), skill_name.into());
+    if let Some(description) = fm.description {
+        manifest.insert("description".to_string(), description.into());
+    }
+    manifest.insert(
+        "version".to_string(),
+        fm.version.unwrap_or_else(|| DEFAULT_PLUGIN_VERSION.to_string()).into(),
+    );
+    serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())
+}
+
+/// SKILL.md as the Claude apps expect it: a description within their limit
+/// and a `name` matching the skill folder.
+fn desktop_skill_md(skill_name: &str, skill_md: &str) -> Result<String, String> {
+    let fm = super::imported_skills::parse_frontmatter_full(skill_md);
+    let description = fm
+        .description
+        .filter(|d| !d.trim().is_empty())
+        .ok_or_else(|| "Claude Desktop packages need a description in the SKILL.md frontmatter".to_string())?;
+    if description.chars().count() > DESKTOP_MAX_DESCRIPTION {
+        return Err(format!(
+            "Claude Desktop allows descriptions of at most {} characters; this one has {}",
+            DESKTOP_MAX_DESCRIPTION,
+            description.chars().count()
+        ));
+    }
+    Ok(super::frontmatter_migration::set_frontmatter_fields(skill_md, &[("name", skill_name)]))
+}
+
+/// Rewrite the generic-layout package at `generic` into `format` at
+/// `output_path`. Returns the new package size.
+pub(crate) fn convert_package(
+    generic: &Path,
+    output_path: &Path,
+    format: PackageFormat,
+    skill_name: &str,
+    source_dir: &Path,
+) -> Result<u64, String> {
+    let file = std::fs::File::open(generic).map_err(|e| format!("Failed to open {}: {}", generic.display(), e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Failed to read package: {}", e))?;
+    let out = std::fs::File::create(output_path).map_err(|e| format!("Failed to create zip file: {}", e))?;
+    let mut zip = zip::ZipWriter::new(out);
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let source_dir = source_dir.to_string_lossy().trim_end_matches('/').to_string();
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| format!("Failed to read package entry: {}", e))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        let mut bytes = Vec::new();
+        entry
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("Failed to read {} from package: {}", name, e))?;
+        if name.ends_with(".md") {
+            if let Ok(text) = String::from_utf8(bytes.clone()) {
+                let mut text = rewrite_links(format, skill_name, &source_dir, &name, &text);
+                if name == "SKILL.md" {
+                    match format {
+                        PackageFormat::ClaudeCode => {
+                            zip.start_file(".claude-plugin/plugin.json", options)
+                                .map_err(|e| format!("Failed to add plugin.json to zip: {}", e))?;
+                            zip.write_all(plugin_manifest(skill_name, &text)?.as_bytes())
+                                .map_err(|e| format!("Failed to write plugin.json to zip: {}", e))?;
+                        }
+                        PackageFormat::ClaudeDesktop => text = desktop_skill_md(skill_name, &text)?,
+                        PackageFormat::GenericZip => {}
+                    }
+                }
+                bytes = text.into_bytes();
+            }
+        }
+        let target = target_path(format, skill_name, &name);
+        zip.start_file(target.as_str(), options)
+            .map_err(|e| format!("Failed to add {} to zip: {}", target, e))?;
+        zip.write_all(&bytes)
+            .map_err(|e| format!("Failed to write {} to zip: {}", target, e))?;
+    }
+    zip.finish().map_err(|e| format!("Failed to finalize zip: {}", e))?;
+
+    std::fs::metadata(output_path)
+        .map(|m| m.len())
+        .map_err(|e| format!("Failed to read zip metadata: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn archive_names(path: &Path) -> Vec<String> {
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(path).unwrap()).unwrap();
+        (0..archive.len()).map(|i| archive.by_index(i).unwrap().name().to_string()).collect()
+    }
+
+    fn read_entry(path: &Path, name: &str) -> String {
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(path).unwrap()).unwrap();
+        let mut content = String::new();
+        archive.by_name(name).unwrap().read_to_string(&mut content).unwrap();
+        content
+    }
+
+    #[test]
+    fn test_file_names_and_parse() {
+        assert_eq!(PackageFormat::GenericZip.file_name("s", None), "s.skill");
+        assert_eq!(PackageFormat::ClaudeCode.file_name("s", Some("de")), "s.de-claude-code.zip");
+        assert_eq!(PackageFormat::parse("claude-desktop").unwrap(), PackageFormat::ClaudeDesktop);
+        assert!(PackageFormat::parse("tarball").is_err());
+    }
+
+    #[test]
+    fn test_rewrite_links_follow_moved_files() {
+        let skill_md = "See [deps](dependencies/base/SKILL.md), [ref](references/a.md#top), \
+[abs](/skills/my-skill/references/b.md) and [web](https://x.io).\n```\n[code](dependencies/x)\n```\n";
+        let code = rewrite_links(PackageFormat::ClaudeCode, "my-skill", "/skills/my-skill", "SKILL.md", skill_md);
+        assert!(code.contains("[deps](../base/SKILL.md)"));
+        assert!(code.contains("[ref](references/a.md#top)"));
+        assert!(code.contains("[abs](references/b.md)"));
+        assert!(code.contains("[web](https://x.io)"));
+        assert!(code.contains("[code](dependencies/x)"));
+
+        let nested = rewrite_links(
+            PackageFormat::ClaudeCode,
+            "my-skill",
+            "/skills/my-skill",
+            "references/a.md",
+            "[dep](../dependencies/base/references/c.md)",
+        );
+        assert_eq!(nested, "[dep](../../base/references/c.md)");
+
+        let desktop = rewrite_links(PackageFormat::ClaudeDesktop, "my-skill", "/skills/my-skill", "SKILL.md", skill_md);
+        assert!(desktop.contains("[deps](dependencies/base/SKILL.md)"));
+        assert!(desktop.contains("[abs](references/b.md)"));
+    }
+
+    #[test]
+    fn test_convert_package_layouts() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source_dir = tmp.path().join("my-skill");
+        std::fs::create_dir_all(source_dir.join("references")).unwrap();
+        std::fs::write(
+            source_dir.join("SKILL.md"),
+            "---\nname: old-name\ndescription: Revenue recognition rules\nversion: 1.2.0\n---\n# Skill\n[a](references/a.md)\n",
+        )
+        .unwrap();
+        std::fs::write(source_dir.join("references").join("a.md"), "# A").unwrap();
+        let generic = tmp.path().join("my-skill.skill");
+        crate::commands::workflow::create_skill_zip(&source_dir, &generic).unwrap();
+
+        let code = tmp.path().join("code.zip");
+        convert_package(&generic, &code, PackageFormat::ClaudeCode, "my-skill", &source_dir).unwrap();
+        let names = archive_names(&code);
+        assert!(names.contains(&".claude-plugin/plugin.json".to_string()));
+        assert!(names.contains(&"skills/my-skill/SKILL.md".to_string()));
+        assert!(names.contains(&"skills/my-skill/references/a.md".to_string()));
+        let manifest: serde_json::Value =
+            serde_json::from_str(&read_entry(&code, ".claude-plugin/plugin.json")).unwrap();
+        assert_eq!(manifest["name"], "my-skill");
+        assert_eq!(manifest["version"], "1.2.0");
+
+        let desktop = tmp.path().join("desktop.zip");
+        convert_package(&generic, &desktop, PackageFormat::ClaudeDesktop, "my-skill", &source_dir).unwrap();
+        assert!(archive_names(&desktop).contains(&"my-skill/references/a.md".to_string()));
+        assert!(read_entry(&desktop, "my-skill/SKILL.md").contains("name: my-skill"));
+
+        std::fs::write(source_dir.join("SKILL.md"), "# No frontmatter").unwrap();
+        crate::commands::workflow::create_skill_zip(&source_dir, &generic).unwrap();
+        assert!(convert_package(&generic, &desktop, PackageFormat::ClaudeDesktop, "my-skill", &source_dir).is_err());
+    }
+
+    #[test]
+    fn test_resolve_format_remembers_choice() {
+        let conn = crate::commands::test_utils::create_test_db();
+        crate::db::upsert_skill(&conn, "my-skill", "skill-builder", "domain").unwrap();
+        assert_eq!(resolve_format(&conn, "my-skill", None).unwrap(), PackageFormat::GenericZip);
+        assert_eq!(
+            resolve_format(&conn, "my-skill", Some("claude-code")).unwrap(),
+            PackageFormat::ClaudeCode
+        );
+        assert_eq!(resolve_format(&conn, "my-skill", None).unwrap(), PackageFormat::ClaudeCode);
+        assert!(resolve_format(&conn, "my-skill", Some("rpm")).is_err());
+    }
+}
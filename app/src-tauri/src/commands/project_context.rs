@@ -0,0 +1,436 @@
+//! Multi-skill document bundles for Claude Projects.
+//!
+//! Claude Projects take documents, not skills. `package_project_context`
+//! writes one markdown document per selected skill plus `00-index.md`, all
+//! within an estimated token budget (`CHARS_PER_TOKEN` characters per token).
+//! Every skill's SKILL.md comes first: small skills are kept whole and the
+//! remaining budget is split evenly across the rest, which are condensed by
+//! dropping whole sections. Whatever budget is left is spent on reference
+//! files, in skill order, each either included whole or listed as omitted.
+//! The index records every skill and version that went into the bundle.
+
+use std::path::{Path, PathBuf};
+
+use crate::db::Db;
+use crate::types::{ProjectContextPackage, ProjectContextSkill};
+
+const CHARS_PER_TOKEN: usize = 4;
+const INDEX_FILE: &str = "00-index.md";
+/// Budget held back for the index: a fixed header plus a row per skill.
+const INDEX_BASE_TOKENS: usize = 100;
+const INDEX_TOKENS_PER_SKILL: usize = 100;
+/// Smallest share of the budget a skill can be condensed to.
+const MIN_SKILL_TOKENS: usize = 200;
+/// Descriptions are cut to this many characters in the index.
+const INDEX_DESCRIPTION_CHARS: usize = 200;
+
+fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+struct SourceSkill {
+    name: String,
+    version: Option<String>,
+    description: Option<String>,
+    body: String,
+    /// `(path relative to the skill, content)`, sorted by path.
+    references: Vec<(String, String)>,
+}
+
+fn read_source_skill(skill_dir: &Path, name: &str, fallback_version: Option<String>) -> Result<SourceSkill, String> {
+    let skill_md = std::fs::read_to_string(skill_dir.join("SKILL.md"))
+        .map_err(|e| format!("Failed to read SKILL.md for '{}': {}", name, e))?;
+    let frontmatter = super::imported_skills::parse_frontmatter_full(&skill_md);
+
+    let mut references = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(skill_dir.join("references")) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("md") {
+                continue;
+            }
+            let content = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            references.push((format!("references/{}", entry.file_name().to_string_lossy()), content));
+        }
+    }
+    references.sort_by(|a, b| a.0.cmp(&b.0));
+
+    Ok(SourceSkill {
+        name: name.to_string(),
+        version: frontmatter.version.or(fallback_version),
+        description: frontmatter.description,
+        body: super::skill_validation::frontmatter_body(&skill_md).trim().to_string(),
+        references,
+    })
+}
+
+fn document_header(skill: &SourceSkill) -> String {
+    let mut header = match &skill.version {
+        Some(version) => format!("# {} (v{})\n\n", skill.name, version),
+        None => format!("# {}\n\n", skill.name),
+    };
+    if let Some(description) = &skill.description {
+        header.push_str(&format!("> {}\n\n", description));
+    }
+    header
+}
+
+/// Markdown split before each heading outside fenced code blocks.
+fn split_sections(body: &str) -> Vec<&str> {
+    let mut sections = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+    let mut in_fence = false;
+    for line in body.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            in_fence = !in_fence;
+        } else if !in_fence && trimmed.starts_with('#') && offset > start {
+            sections.push(&body[start..offset]);
+            start = offset;
+        }
+        offset += line.len();
+    }
+    if offset > start {
+        sections.push(&body[start..]);
+    }
+    sections
+}
+
+fn condensed_note(omitted: usize, total: usize) -> String {
+    format!("_[Condensed: {} of {} sections omitted to fit the token budget]_\n", omitted, total)
+}
+
+/// `body` cut to about `max_tokens` by keeping whole sections in order.
+/// Returns the text and whether anything was dropped.
+fn condense(body: &str, max_tokens: usize) -> (String, bool) {
+    if estimate_tokens(body) <= max_tokens {
+        return (body.to_string(), false);
+    }
+    let sections = split_sections(body);
+    // The note and the blank line before it come out of the same allowance.
+    let room = max_tokens.saturating_sub(estimate_tokens(&condensed_note(sections.len(), sections.len())) + 1);
+    let mut kept = String::new();
+    let mut used = 0;
+    let mut omitted = 0;
+    for section in &sections {
+        let tokens = estimate_tokens(section);
+        if used + tokens <= room {
+            kept.push_str(section);
+            used += tokens;
+        } else {
+            omitted += 1;
+        }
+    }
+    if kept.trim().is_empty() {
+        // Not even one section fits: keep the start of the first, cut at a line.
+        let cut: String = body.chars().take(room * CHARS_PER_TOKEN).collect();
+        kept = match cut.rfind('\n') {
+            Some(end) => cut[..end].to_string(),
+            None => cut,
+        };
+        omitted = sections.len();
+    }
+    (format!("{}\n\n{}", kept.trim_end(), condensed_note(omitted, sections.len())), true)
+}
+
+/// Token allowance per skill for its header and SKILL.md: skills that fit an
+/// even share keep everything, and what they leave is shared by the rest.
+fn allocate(core_tokens: &[usize], available: usize) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..core_tokens.len()).collect();
+    order.sort_by_key(|&i| core_tokens[i]);
+    let mut allowance = vec![0; core_tokens.len()];
+    let mut remaining = available;
+    for (k, &i) in order.iter().enumerate() {
+        let share = remaining / (core_tokens.len() - k);
+        allowance[i] = core_tokens[i].min(share);
+        remaining -= allowance[i];
+    }
+    allowance
+}
+
+fn build_index(skills: &[SourceSkill], entries: &[ProjectContextSkill], budget: usize, total: usize) -> String {
+    let mut index = format!(
+        "# Project context\n\nGenerated by Skill Builder on {}. {} skill(s), about {} of {} tokens.\n\n\
+         | Skill | Version | Document | Tokens | Notes |\n|---|---|---|---|---|\n",
+        chrono::Utc::now().format("%Y-%m-%d"),
+        entries.len(),
+        total,
+        budget
+    );
+    for entry in entries {
+        let mut notes = Vec::new();
+        if entry.condensed {
+            notes.push("condensed".to_string());
+        }
+        if !entry.omitted_references.is_empty() {
+            notes.push(format!("{} reference(s) omitted", entry.omitted_references.len()));
+        }
+        index.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            entry.skill_name,
+            entry.version.as_deref().unwrap_or("—"),
+            entry.document,
+            entry.tokens,
+            notes.join("; ")
+        ));
+    }
+    for (skill, entry) in skills.iter().zip(entries) {
+        index.push_str(&format!("\n## {}\n\n", entry.skill_name));
+        if let Some(description) = &skill.description {
+            let short: String = description.chars().take(INDEX_DESCRIPTION_CHARS).collect();
+            index.push_str(&format!("{}\n\n", short));
+        }
+        if !entry.included_references.is_empty() {
+            index.push_str(&format!("Includes: {}\n", entry.included_references.join(", ")));
+        }
+    }
+    index
+}
+
+/// Lay out the bundle: `(file name, content)` pairs, index first, and the
+/// per-skill summary.
+fn plan_bundle(
+    skills: &[SourceSkill],
+    budget_tokens: usize,
+) -> Result<(Vec<(String, String)>, Vec<ProjectContextSkill>, usize), String> {
+    let reserve = INDEX_BASE_TOKENS + INDEX_TOKENS_PER_SKILL * skills.len();
+    let minimum = reserve + MIN_SKILL_TOKENS * skills.len();
+    if budget_tokens < minimum {
+        return Err(format!(
+            "A budget of {} tokens is too small for {} skill(s); use at least {}",
+            budget_tokens,
+            skills.len(),
+            minimum
+        ));
+    }
+    let available = budget_tokens - reserve;
+
+    let headers: Vec<String> = skills.iter().map(document_header).collect();
+    let core_tokens: Vec<usize> = skills
+        .iter()
+        .zip(&headers)
+        .map(|(skill, header)| estimate_tokens(header) + estimate_tokens(&skill.body))
+        .collect();
+    let allowance = allocate(&core_tokens, available);
+
+    let mut documents: Vec<String> = Vec::new();
+    let mut entries: Vec<ProjectContextSkill> = Vec::new();
+    for ((skill, header), allowed) in skills.iter().zip(&headers).zip(&allowance) {
+        let (body, condensed) = condense(&skill.body, allowed.saturating_sub(estimate_tokens(header)));
+        let document = format!("{}{}\n", header, body.trim_end());
+        entries.push(ProjectContextSkill {
+            skill_name: skill.name.clone(),
+            version: skill.version.clone(),
+            document: format!("{}.md", skill.name),
+            tokens: estimate_tokens(&document),
+            condensed,
+            included_references: Vec::new(),
+            omitted_references: Vec::new(),
+        });
+        documents.push(document);
+    }
+
+    let mut left = available.saturating_sub(entries.iter().map(|e| e.tokens).sum());
+    for ((skill, document), entry) in skills.iter().zip(documents.iter_mut()).zip(entries.iter_mut()) {
+        for (path, content) in &skill.references {
+            let section = format!("\n## Reference: {}\n\n{}\n", path, content.trim());
+            let tokens = estimate_tokens(&section);
+            if tokens <= left {
+                document.push_str(&section);
+                entry.included_references.push(path.clone());
+                left -= tokens;
+            } else {
+                entry.omitted_references.push(path.clone());
+            }
+        }
+        entry.tokens = estimate_tokens(document);
+    }
+
+    let documents_total: usize = entries.iter().map(|e| e.tokens).sum();
+    let index_estimate = build_index(skills, &entries, budget_tokens, documents_total + reserve);
+    let total = documents_total + estimate_tokens(&index_estimate);
+    let index = build_index(skills, &entries, budget_tokens, total);
+
+    let mut files = vec![(INDEX_FILE.to_string(), index)];
+    files.extend(entries.iter().map(|e| e.document.clone()).zip(documents));
+    Ok((files, entries, total))
+}
+
+fn package_project_context_inner(
+    conn: &rusqlite::Connection,
+    skills_path: &str,
+    skill_names: &[String],
+    budget_tokens: usize,
+    output_dir: &Path,
+) -> Result<ProjectContextPackage, String> {
+    let mut names: Vec<&String> = Vec::new();
+    for name in skill_names {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    if names.is_empty() {
+        return Err("Select at least one skill to package".to_string());
+    }
+
+    let mut skills = Vec::new();
+    for name in names {
+        super::imported_skills::validate_skill_name(name)?;
+        let skill_dir = Path::new(skills_path).join(name);
+        if !skill_dir.join("SKILL.md").is_file() {
+            return Err(format!("Skill '{}' has no SKILL.md in {}", name, skill_dir.display()));
+        }
+        let db_version = crate::db::get_workflow_run(conn, name)?.and_then(|run| run.version);
+        skills.push(read_source_skill(&skill_dir, name, db_version)?);
+    }
+
+    let (files, entries, total) = plan_bundle(&skills, budget_tokens)?;
+    std::fs::create_dir_all(output_dir)
+        .map_err(|e| format!("Failed to create {}: {}", output_dir.display(), e))?;
+    for (file_name, content) in &files {
+        let path = output_dir.join(file_name);
+        std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+
+    Ok(ProjectContextPackage {
+        output_dir: output_dir.to_string_lossy().to_string(),
+        index_file: output_dir.join(INDEX_FILE).to_string_lossy().to_string(),
+        budget_tokens,
+        total_tokens: total,
+        skills: entries,
+    })
+}
+
+/// Condense several skills into a token-budgeted document set with an index,
+/// ready to upload to a Claude Project. Defaults to
+/// `{workspace}/exports/project-context-{timestamp}/`.
+#[tauri::command]
+pub fn package_project_context(
+    skill_names: Vec<String>,
+    budget_tokens: usize,
+    output_dir: Option<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<ProjectContextPackage, String> {
+    log::info!(
+        "[package_project_context] skills={:?} budget={} output_dir={:?}",
+        skill_names,
+        budget_tokens,
+        output_dir
+    );
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[package_project_context] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let settings = crate::db::read_settings(&conn)?;
+    let skills_path = settings
+        .skills_path
+        .ok_or_else(|| "Skills path not configured. Please set it in Settings.".to_string())?;
+    let workspace_path = settings
+        .workspace_path
+        .ok_or_else(|| "Workspace path not configured".to_string())?;
+    let exports_dir = Path::new(&workspace_path).join("exports");
+    let output: PathBuf = match output_dir {
+        Some(p) => crate::path_input::check(&p, crate::path_input::PathKind::Directory, false, Some(&exports_dir))
+            .map(|c| PathBuf::from(c.path))
+            .map_err(|e| {
+                log::error!("[package_project_context] Invalid output folder: {}", e);
+                format!("Invalid output folder: {}", e)
+            })?,
+        None => exports_dir.join(format!(
+            "project-context-{}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        )),
+    };
+
+    let package = package_project_context_inner(&conn, &skills_path, &skill_names, budget_tokens, &output)
+        .map_err(|e| {
+            log::error!("[package_project_context] {}", e);
+            e
+        })?;
+    log::info!(
+        "[package_project_context] wrote {} skill(s), ~{} tokens, to {}",
+        package.skills.len(),
+        package.total_tokens,
+        package.output_dir
+    );
+    Ok(package)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::create_test_db;
+    use tempfile::tempdir;
+
+    fn write_skill(skills_path: &Path, name: &str, body: &str, references: &[(&str, &str)]) {
+        let dir = skills_path.join(name);
+        std::fs::create_dir_all(dir.join("references")).unwrap();
+        std::fs::write(
+            dir.join("SKILL.md"),
+            format!("---\nname: {}\ndescription: Use when working on {}\nversion: 1.2.0\n---\n{}", name, name, body),
+        )
+        .unwrap();
+        for (file, content) in references {
+            std::fs::write(dir.join("references").join(file), content).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_condense_keeps_whole_sections_and_notes_omissions() {
+        let body = format!("# Intro\nshort\n\n## Big\n{}\n\n## Tail\n```\n# not a heading\n```\n", "x".repeat(2000));
+        assert_eq!(split_sections(&body).len(), 3);
+        let (condensed, dropped) = condense(&body, 100);
+        assert!(dropped);
+        assert!(condensed.contains("# Intro") && condensed.contains("## Tail"));
+        assert!(!condensed.contains("## Big"));
+        assert!(condensed.contains("1 of 3 sections omitted"));
+        assert!(estimate_tokens(&condensed) <= 100);
+        assert_eq!(condense("# Small\nfits", 100), ("# Small\nfits".to_string(), false));
+    }
+
+    #[test]
+    fn test_allocate_gives_leftover_to_larger_skills() {
+        assert_eq!(allocate(&[100, 5_000, 5_000], 3_100), vec![100, 1_500, 1_500]);
+        assert_eq!(allocate(&[100, 200], 1_000), vec![100, 200]);
+    }
+
+    #[test]
+    fn test_package_project_context_respects_budget_and_tracks_versions() {
+        let tmp = tempdir().unwrap();
+        let skills_path = tmp.path().join("skills");
+        write_skill(&skills_path, "standup", "# Standup\nRun the daily standup.\n", &[("agenda.md", "Agenda items")]);
+        let long_body = (0..40).map(|i| format!("## Part {}\n{}\n", i, "y".repeat(400))).collect::<String>();
+        write_skill(&skills_path, "retro", &long_body, &[("huge.md", &"z".repeat(80_000))]);
+        let conn = create_test_db();
+        let skills = skills_path.to_string_lossy().to_string();
+        let names = ["standup".to_string(), "retro".to_string(), "standup".to_string()];
+
+        // Tight budget: SKILL.md content wins over references.
+        let tight = tmp.path().join("tight");
+        let package = package_project_context_inner(&conn, &skills, &names, 2_000, &tight).unwrap();
+        assert_eq!(package.skills.len(), 2);
+        assert!(package.total_tokens <= 2_000, "total {}", package.total_tokens);
+        assert_eq!(package.skills[0].version.as_deref(), Some("1.2.0"));
+        assert!(!package.skills[0].condensed);
+        assert!(package.skills[1].condensed);
+        let index = std::fs::read_to_string(tight.join(INDEX_FILE)).unwrap();
+        assert!(index.contains("| standup | 1.2.0 | standup.md |"));
+        assert!(index.contains("| retro | 1.2.0 | retro.md |"));
+
+        // Roomy budget: whole SKILL.md files plus the references that fit.
+        let roomy = tmp.path().join("roomy");
+        let package = package_project_context_inner(&conn, &skills, &names, 20_000, &roomy).unwrap();
+        assert!(package.total_tokens <= 20_000, "total {}", package.total_tokens);
+        assert!(package.skills.iter().all(|s| !s.condensed));
+        assert_eq!(package.skills[0].included_references, vec!["references/agenda.md"]);
+        assert_eq!(package.skills[1].omitted_references, vec!["references/huge.md"]);
+        let doc = std::fs::read_to_string(roomy.join("standup.md")).unwrap();
+        assert!(doc.starts_with("# standup (v1.2.0)"));
+        assert!(doc.contains("## Reference: references/agenda.md"));
+
+        let err = package_project_context_inner(&conn, &skills, &["standup".to_string()], 100, &roomy).unwrap_err();
+        assert!(err.contains("too small"));
+    }
+}
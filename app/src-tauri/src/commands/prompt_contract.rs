@@ -0,0 +1,355 @@
+//! Versioned step output contracts and the upgrade path for in-progress runs.
+//!
+//! Each workflow run is stamped with the prompt contract version in effect when
+//! it was created. When a release changes what a step writes (for example the
+//! `clarifications.json` schema), bump `PROMPT_CONTRACT_VERSION` and append a
+//! `ContractChange` naming the earliest affected step and, where possible, a
+//! transform that rewrites the old artifact in place. Runs that already
+//! completed an affected step are surfaced at startup and must be upgraded
+//! (transform or re-run) before their next step runs, instead of failing
+//! mid-pipeline on output the new prompts cannot read.
+
+use std::path::Path;
+
+use crate::db::Db;
+use crate::types::ContractUpgrade;
+
+/// Contract version new workflow runs are stamped with.
+pub(crate) const PROMPT_CONTRACT_VERSION: i32 = 1;
+
+/// Rewrites an artifact written under the previous contract.
+pub(crate) struct ArtifactTransform {
+    /// Path relative to the skill's workspace directory, e.g. `context/clarifications.json`.
+    pub artifact: &'static str,
+    pub apply: fn(&str) -> Result<String, String>,
+}
+
+pub(crate) struct ContractChange {
+    /// Contract version that introduced the change.
+    pub version: i32,
+    pub summary: &'static str,
+    /// Earliest step whose output format changed; later steps consumed it.
+    pub step_id: u32,
+    pub transform: Option<ArtifactTransform>,
+}
+
+/// Contract changes, oldest first. Version 1 is the baseline.
+pub(crate) const CONTRACT_CHANGES: &[ContractChange] = &[];
+
+pub(crate) const ACTION_TRANSFORM: &str = "transform";
+pub(crate) const ACTION_RERUN: &str = "rerun";
+
+/// Changes between `from` (exclusive) and `to` (inclusive) that touch output a run
+/// at `current_step` has already produced.
+fn affecting_changes(changes: &[ContractChange], current_step: i32, from: i32, to: i32) -> Vec<&ContractChange> {
+    changes
+        .iter()
+        .filter(|c| c.version > from && c.version <= to && (c.step_id as i32) < current_step)
+        .collect()
+}
+
+/// Describe the upgrade a run needs, or `None` when no completed step is affected.
+fn upgrade_for(
+    changes: &[ContractChange],
+    skill_name: &str,
+    current_step: i32,
+    from: i32,
+    to: i32,
+) -> Option<ContractUpgrade> {
+    let affecting = affecting_changes(changes, current_step, from, to);
+    let rerun_from_step = affecting.iter().map(|c| c.step_id).min()?;
+    Some(ContractUpgrade {
+        skill_name: skill_name.to_string(),
+        current_step,
+        from_version: from,
+        to_version: to,
+        changes: affecting.iter().map(|c| c.summary.to_string()).collect(),
+        rerun_from_step,
+        can_transform: affecting.iter().all(|c| c.transform.is_some()),
+    })
+}
+
+fn pending_upgrades(
+    conn: &rusqlite::Connection,
+    changes: &[ContractChange],
+    current: i32,
+) -> Result<Vec<ContractUpgrade>, String> {
+    Ok(crate::db::list_runs_before_contract(conn, current)?
+        .into_iter()
+        .filter_map(|(name, step, version)| upgrade_for(changes, &name, step, version, current))
+        .collect())
+}
+
+/// Refuse to run a step on top of outputs from an older contract. Runs whose
+/// completed steps are unaffected are restamped to the current contract.
+pub(crate) fn ensure_current_contract(conn: &rusqlite::Connection, skill_name: &str) -> Result<(), String> {
+    ensure_contract(conn, CONTRACT_CHANGES, PROMPT_CONTRACT_VERSION, skill_name)
+}
+
+fn ensure_contract(
+    conn: &rusqlite::Connection,
+    changes: &[ContractChange],
+    current: i32,
+    skill_name: &str,
+) -> Result<(), String> {
+    let Some(version) = crate::db::get_workflow_contract_version(conn, skill_name)? else {
+        return Ok(());
+    };
+    if version >= current {
+        return Ok(());
+    }
+    let current_step = crate::db::get_workflow_run(conn, skill_name)?
+        .map(|r| r.current_step)
+        .unwrap_or(0);
+    match upgrade_for(changes, skill_name, current_step, version, current) {
+        Some(upgrade) => Err(format!(
+            "'{}' has step outputs from prompt contract v{} but this version expects v{}: {}. \
+             Upgrade it from the startup prompt or re-run from step {}.",
+            skill_name,
+            version,
+            current,
+            upgrade.changes.join("; "),
+            upgrade.rerun_from_step + 1
+        )),
+        None => {
+            log::info!(
+                "[ensure_current_contract] '{}' unaffected by contract v{} -> v{}; restamping",
+                skill_name,
+                version,
+                current
+            );
+            crate::db::set_workflow_contract_version(conn, skill_name, current)
+        }
+    }
+}
+
+/// Rewrite each affected artifact in order, keeping a `.v<from>.bak` copy of the original.
+fn transform_artifacts(workspace_skill_dir: &Path, upgrade_from: i32, affecting: &[&ContractChange]) -> Result<(), String> {
+    for change in affecting {
+        let transform = change
+            .transform
+            .as_ref()
+            .ok_or_else(|| format!("No artifact transform for contract v{}: {}", change.version, change.summary))?;
+        let path = workspace_skill_dir.join(transform.artifact);
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+        let backup = path.with_extension(format!(
+            "{}.v{}.bak",
+            path.extension().and_then(|e| e.to_str()).unwrap_or(""),
+            upgrade_from
+        ));
+        if !backup.exists() {
+            std::fs::write(&backup, &content)
+                .map_err(|e| format!("Failed to back up '{}': {}", path.display(), e))?;
+        }
+        let updated = (transform.apply)(&content)
+            .map_err(|e| format!("Contract v{} transform failed for '{}': {}", change.version, transform.artifact, e))?;
+        std::fs::write(&path, updated).map_err(|e| format!("Failed to write '{}': {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// In-progress runs that need a guided upgrade before they can continue.
+#[tauri::command]
+pub fn list_contract_upgrades(db: tauri::State<'_, Db>) -> Result<Vec<ContractUpgrade>, String> {
+    log::info!("[list_contract_upgrades] current contract v{}", PROMPT_CONTRACT_VERSION);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[list_contract_upgrades] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let upgrades = pending_upgrades(&conn, CONTRACT_CHANGES, PROMPT_CONTRACT_VERSION)?;
+    if !upgrades.is_empty() {
+        log::warn!("[list_contract_upgrades] {} run(s) span a contract change", upgrades.len());
+    }
+    Ok(upgrades)
+}
+
+/// Bring a run up to the current contract, either by transforming its artifacts
+/// in place (`transform`) or by resetting it to the earliest affected step (`rerun`).
+#[tauri::command]
+pub fn apply_contract_upgrade(
+    skill_name: String,
+    action: String,
+    db: tauri::State<'_, Db>,
+) -> Result<(), String> {
+    log::info!("[apply_contract_upgrade] skill={} action={}", skill_name, action);
+    super::observer::ensure_writable(&skill_name)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[apply_contract_upgrade] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let settings = crate::db::read_settings(&conn)?;
+    let workspace_path = settings
+        .workspace_path
+        .ok_or_else(|| "Workspace path not initialized".to_string())?;
+    let skills_path = settings
+        .skills_path
+        .ok_or_else(|| "Skills path not configured. Please set it in Settings.".to_string())?;
+    apply_upgrade(
+        &conn,
+        CONTRACT_CHANGES,
+        PROMPT_CONTRACT_VERSION,
+        &skill_name,
+        &action,
+        &workspace_path,
+        &skills_path,
+    )
+    .map_err(|e| {
+        log::error!("[apply_contract_upgrade] {}", e);
+        e
+    })
+}
+
+fn apply_upgrade(
+    conn: &rusqlite::Connection,
+    changes: &[ContractChange],
+    current: i32,
+    skill_name: &str,
+    action: &str,
+    workspace_path: &str,
+    skills_path: &str,
+) -> Result<(), String> {
+    let version = crate::db::get_workflow_contract_version(conn, skill_name)?
+        .ok_or_else(|| format!("No workflow run for '{}'", skill_name))?;
+    let run = crate::db::get_workflow_run(conn, skill_name)?
+        .ok_or_else(|| format!("No workflow run for '{}'", skill_name))?;
+    let affecting = affecting_changes(changes, run.current_step, version, current);
+
+    match action {
+        ACTION_TRANSFORM => {
+            transform_artifacts(&Path::new(workspace_path).join(skill_name), version, &affecting)?;
+        }
+        ACTION_RERUN => {
+            if let Some(from_step) = affecting.iter().map(|c| c.step_id).min() {
+                let msg = format!("{}: checkpoint before contract v{} upgrade", skill_name, current);
+                if let Err(e) = crate::git::commit_all(Path::new(skills_path), &msg) {
+                    log::warn!("Git auto-commit failed ({}): {}", msg, e);
+                }
+                crate::cleanup::delete_step_output_files(workspace_path, skill_name, from_step, skills_path);
+                crate::db::reset_workflow_steps_from(conn, skill_name, from_step as i32)?;
+                crate::db::save_workflow_run(conn, skill_name, from_step as i32, "pending", &run.purpose)?;
+            }
+        }
+        other => return Err(format!("Invalid contract upgrade action '{}'", other)),
+    }
+
+    crate::db::set_workflow_contract_version(conn, skill_name, current)?;
+    log::info!(
+        "[apply_contract_upgrade] '{}' upgraded from contract v{} to v{} via {}",
+        skill_name,
+        version,
+        current,
+        action
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add_status_field(content: &str) -> Result<String, String> {
+        let mut value: serde_json::Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+        value["status"] = serde_json::json!("draft");
+        serde_json::to_string(&value).map_err(|e| e.to_string())
+    }
+
+    const CHANGES: &[ContractChange] = &[
+        ContractChange {
+            version: 2,
+            summary: "decisions.json gains a status field",
+            step_id: 2,
+            transform: Some(ArtifactTransform {
+                artifact: "context/decisions.json",
+                apply: add_status_field,
+            }),
+        },
+        ContractChange {
+            version: 3,
+            summary: "clarifications.json questions are grouped by dimension",
+            step_id: 0,
+            transform: None,
+        },
+    ];
+
+    #[test]
+    fn test_upgrade_only_for_completed_affected_steps() {
+        // At step 2, decisions.json (step 2) is not written yet
+        let upgrade = upgrade_for(CHANGES, "s", 2, 1, 2);
+        assert!(upgrade.is_none());
+
+        let upgrade = upgrade_for(CHANGES, "s", 3, 1, 2).unwrap();
+        assert_eq!(upgrade.rerun_from_step, 2);
+        assert!(upgrade.can_transform);
+
+        let upgrade = upgrade_for(CHANGES, "s", 3, 1, 3).unwrap();
+        assert_eq!(upgrade.rerun_from_step, 0);
+        assert_eq!(upgrade.changes.len(), 2);
+        assert!(!upgrade.can_transform);
+
+        // Already on v2: only the v3 change applies
+        let upgrade = upgrade_for(CHANGES, "s", 3, 2, 3).unwrap();
+        assert_eq!(upgrade.changes, vec!["clarifications.json questions are grouped by dimension"]);
+    }
+
+    #[test]
+    fn test_new_runs_are_stamped_with_current_contract() {
+        let conn = crate::commands::test_utils::create_test_db();
+        crate::db::save_workflow_run(&conn, "fresh", 0, "pending", "domain").unwrap();
+        assert_eq!(
+            crate::db::get_workflow_contract_version(&conn, "fresh").unwrap(),
+            Some(PROMPT_CONTRACT_VERSION)
+        );
+        assert!(pending_upgrades(&conn, CONTRACT_CHANGES, PROMPT_CONTRACT_VERSION).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_ensure_contract_blocks_affected_and_restamps_unaffected() {
+        let conn = crate::commands::test_utils::create_test_db();
+        crate::db::save_workflow_run(&conn, "early", 1, "in_progress", "domain").unwrap();
+        crate::db::save_workflow_run(&conn, "late", 3, "in_progress", "domain").unwrap();
+        crate::db::save_workflow_run(&conn, "done", 3, "completed", "domain").unwrap();
+
+        let upgrades = pending_upgrades(&conn, CHANGES, 2).unwrap();
+        assert_eq!(upgrades.len(), 1);
+        assert_eq!(upgrades[0].skill_name, "late");
+
+        ensure_contract(&conn, CHANGES, 2, "early").unwrap();
+        assert_eq!(crate::db::get_workflow_contract_version(&conn, "early").unwrap(), Some(2));
+
+        let err = ensure_contract(&conn, CHANGES, 2, "late").unwrap_err();
+        assert!(err.contains("re-run from step 3"), "{}", err);
+        assert_eq!(crate::db::get_workflow_contract_version(&conn, "late").unwrap(), Some(1));
+    }
+
+    #[test]
+    fn test_apply_upgrade_transform_and_rerun() {
+        let conn = crate::commands::test_utils::create_test_db();
+        let workspace = tempfile::tempdir().unwrap();
+        let skills = tempfile::tempdir().unwrap();
+        let ws = workspace.path().to_str().unwrap();
+        let sk = skills.path().to_str().unwrap();
+        let context = workspace.path().join("sales/context");
+        std::fs::create_dir_all(&context).unwrap();
+        std::fs::write(context.join("decisions.json"), r#"{"decisions":[]}"#).unwrap();
+        std::fs::write(context.join("clarifications.json"), r#"{"version":"1"}"#).unwrap();
+        crate::db::save_workflow_run(&conn, "sales", 3, "in_progress", "domain").unwrap();
+
+        apply_upgrade(&conn, CHANGES, 2, "sales", ACTION_TRANSFORM, ws, sk).unwrap();
+        let decisions = std::fs::read_to_string(context.join("decisions.json")).unwrap();
+        assert!(decisions.contains("\"status\":\"draft\""));
+        assert!(context.join("decisions.json.v1.bak").exists());
+        assert_eq!(crate::db::get_workflow_contract_version(&conn, "sales").unwrap(), Some(2));
+
+        // v3 has no transform; re-run resets to step 0
+        assert!(apply_upgrade(&conn, CHANGES, 3, "sales", ACTION_TRANSFORM, ws, sk).is_err());
+        assert_eq!(crate::db::get_workflow_contract_version(&conn, "sales").unwrap(), Some(2));
+        apply_upgrade(&conn, CHANGES, 3, "sales", ACTION_RERUN, ws, sk).unwrap();
+        let run = crate::db::get_workflow_run(&conn, "sales").unwrap().unwrap();
+        assert_eq!(run.current_step, 0);
+        assert!(!context.join("clarifications.json").exists());
+        assert_eq!(crate::db::get_workflow_contract_version(&conn, "sales").unwrap(), Some(3));
+
+        assert!(apply_upgrade(&conn, CHANGES, 3, "sales", "ignore", ws, sk).is_err());
+    }
+}
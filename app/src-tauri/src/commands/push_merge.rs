@@ -0,0 +1,231 @@
+//! Three-way merge of SKILL.md when pushing to a shared repo.
+//!
+//! The base is the SKILL.md this machine last pushed to the repo (recorded in
+//! `skill_push_bases`). When only one side changed since then that side wins;
+//! when both changed, the edits are merged line by line as `diff3` would, and
+//! regions changed differently on both sides come back as conflict hunks for
+//! the user to resolve. Without a recorded base (the first push from this
+//! machine) a remote copy that differs from the local one is a conflict, so a
+//! teammate's work is never overwritten silently.
+
+use sha2::{Digest, Sha256};
+
+use crate::types::{SkillMergeHunk, SkillPushConflict};
+
+/// What to push as SKILL.md.
+#[derive(Debug, PartialEq)]
+pub(crate) enum MergeOutcome {
+    /// Push this content: the local copy, the remote copy, or a clean merge.
+    Clean(String),
+    Conflict(SkillPushConflict),
+}
+
+pub(crate) fn digest(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// Decide what to push for `path` given the last-pushed `base`, the `local`
+/// content and the `remote` content (`None` when the file does not exist yet).
+pub(crate) fn merge_skill_md(path: &str, base: Option<&str>, local: &str, remote: Option<&str>) -> MergeOutcome {
+    let Some(remote) = remote else {
+        return MergeOutcome::Clean(local.to_string());
+    };
+    if remote == local || base == Some(remote) {
+        return MergeOutcome::Clean(local.to_string());
+    }
+    if base == Some(local) {
+        return MergeOutcome::Clean(remote.to_string());
+    }
+    let merged = merge3(base.unwrap_or(""), local, remote);
+    if merged.hunks.is_empty() {
+        return MergeOutcome::Clean(merged.text);
+    }
+    MergeOutcome::Conflict(SkillPushConflict {
+        path: path.to_string(),
+        remote_digest: digest(remote),
+        merged_with_markers: merged.text,
+        hunks: merged.hunks,
+    })
+}
+
+struct Merged {
+    /// Merged text, with conflict markers when `hunks` is non-empty.
+    text: String,
+    hunks: Vec<SkillMergeHunk>,
+}
+
+fn lines(text: &str) -> Vec<&str> {
+    text.split_inclusive('\n').collect()
+}
+
+/// For each line of `a`, the index of the line of `b` it is paired with by a
+/// longest common subsequence, if any.
+pub(crate) fn lcs_pairs(a: &[&str], b: &[&str]) -> Vec<Option<usize>> {
+    let (n, m) = (a.len(), b.len());
+    let mut table = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i][j] = if a[i] == b[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+    let mut pairs = vec![None; n];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            pairs[i] = Some(j);
+            i += 1;
+            j += 1;
+        } else if table[i + 1][j] >= table[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+fn push_block(out: &mut String, block: &[&str]) {
+    for line in block {
+        out.push_str(line);
+    }
+}
+
+fn push_marked(out: &mut String, marker: &str, block: &[&str]) {
+    out.push_str(marker);
+    out.push('\n');
+    push_block(out, block);
+    if !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+/// Line-based three-way merge. Walks the base, copying lines both sides kept
+/// and resolving each changed region between them: a region changed on one
+/// side takes that side, one changed identically on both takes either, and
+/// anything else is a conflict.
+fn merge3(base: &str, local: &str, remote: &str) -> Merged {
+    let (o, a, b) = (lines(base), lines(local), lines(remote));
+    let in_a = lcs_pairs(&o, &a);
+    let in_b = lcs_pairs(&o, &b);
+    let mut text = String::new();
+    let mut hunks = Vec::new();
+    let (mut io, mut ia, mut ib) = (0, 0, 0);
+
+    loop {
+        // Next base line both sides kept.
+        let stable = (io..o.len()).find_map(|k| Some((k, in_a[k]?, in_b[k]?)));
+        if let Some((k, ka, kb)) = stable {
+            if k == io && ka == ia && kb == ib {
+                text.push_str(o[io]);
+                io += 1;
+                ia += 1;
+                ib += 1;
+                continue;
+            }
+        }
+        let (eo, ea, eb) = stable.unwrap_or((o.len(), a.len(), b.len()));
+        if io == eo && ia == ea && ib == eb {
+            break;
+        }
+        let (base_block, local_block, remote_block) = (&o[io..eo], &a[ia..ea], &b[ib..eb]);
+        if local_block == base_block || local_block == remote_block {
+            push_block(&mut text, remote_block);
+        } else if remote_block == base_block {
+            push_block(&mut text, local_block);
+        } else {
+            push_marked(&mut text, "<<<<<<< local", local_block);
+            push_marked(&mut text, "||||||| base", base_block);
+            push_marked(&mut text, "=======", remote_block);
+            text.push_str(">>>>>>> remote\n");
+            hunks.push(SkillMergeHunk {
+                local_start: ia + 1,
+                remote_start: ib + 1,
+                base: base_block.concat(),
+                local: local_block.concat(),
+                remote: remote_block.concat(),
+            });
+        }
+        io = eo;
+        ia = ea;
+        ib = eb;
+    }
+    Merged { text, hunks }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "# Skill\n\nIntro.\n\n## Steps\n\n1. One\n2. Two\n\n## Notes\n\nNone.\n";
+
+    #[test]
+    fn test_one_sided_changes_take_that_side() {
+        let changed = BASE.replace("Intro.", "Better intro.");
+        assert_eq!(
+            merge_skill_md("SKILL.md", Some(BASE), &changed, Some(BASE)),
+            MergeOutcome::Clean(changed.clone())
+        );
+        assert_eq!(
+            merge_skill_md("SKILL.md", Some(BASE), BASE, Some(&changed)),
+            MergeOutcome::Clean(changed.clone())
+        );
+        assert_eq!(
+            merge_skill_md("SKILL.md", None, &changed, None),
+            MergeOutcome::Clean(changed)
+        );
+    }
+
+    #[test]
+    fn test_non_overlapping_edits_merge_cleanly() {
+        let local = BASE.replace("Intro.", "Better intro.");
+        let remote = BASE.replace("None.", "Watch the rate limits.");
+        let expected = local.replace("None.", "Watch the rate limits.");
+        assert_eq!(
+            merge_skill_md("SKILL.md", Some(BASE), &local, Some(&remote)),
+            MergeOutcome::Clean(expected)
+        );
+    }
+
+    #[test]
+    fn test_overlapping_edits_return_hunks() {
+        let local = BASE.replace("2. Two\n", "2. Two, carefully\n3. Three\n");
+        let remote = BASE.replace("2. Two\n", "2. Second\n");
+        let MergeOutcome::Conflict(conflict) =
+            merge_skill_md("skills/x/SKILL.md", Some(BASE), &local, Some(&remote))
+        else {
+            panic!("expected a conflict");
+        };
+        assert_eq!(conflict.path, "skills/x/SKILL.md");
+        assert_eq!(conflict.remote_digest, digest(&remote));
+        assert_eq!(
+            conflict.hunks,
+            vec![SkillMergeHunk {
+                local_start: 8,
+                remote_start: 8,
+                base: "2. Two\n".to_string(),
+                local: "2. Two, carefully\n3. Three\n".to_string(),
+                remote: "2. Second\n".to_string(),
+            }]
+        );
+        assert!(conflict.merged_with_markers.contains(
+            "1. One\n<<<<<<< local\n2. Two, carefully\n3. Three\n||||||| base\n2. Two\n=======\n2. Second\n>>>>>>> remote\n\n## Notes"
+        ));
+    }
+
+    #[test]
+    fn test_missing_base_with_differing_remote_conflicts() {
+        let remote = BASE.replace("Intro.", "Their intro.");
+        assert!(matches!(
+            merge_skill_md("SKILL.md", None, BASE, Some(&remote)),
+            MergeOutcome::Conflict(_)
+        ));
+        assert_eq!(
+            merge_skill_md("SKILL.md", None, BASE, Some(BASE)),
+            MergeOutcome::Clean(BASE.to_string())
+        );
+    }
+}
@@ -0,0 +1,588 @@
+//! Quality gate profiles.
+//!
+//! A profile names the checks that must pass before a skill is packaged or
+//! pushed to a shared repo: accessibility `lint`, `size` budgets, a `secrets`
+//! scan, prose `readability` and frontmatter `compatibility` with the skill
+//! format. Teams define profiles in the `quality_gates` list of their taxonomy
+//! file; the built-in "team-standard" and "strict" profiles apply unless the
+//! team overrides them by name. Every check runs on each pass and the report
+//! marks which ones the profile enforces. A failing enforced check can be
+//! waived per skill with a recorded justification.
+
+use std::path::Path;
+
+use super::imported_skills::validate_skill_name;
+use crate::db::Db;
+use crate::types::{QualityBudgets, QualityCheckResult, QualityGateProfile, QualityGateReport, QualityWaiver};
+
+pub(crate) const CHECK_LINT: &str = "lint";
+pub(crate) const CHECK_SIZE: &str = "size";
+pub(crate) const CHECK_SECRETS: &str = "secrets";
+pub(crate) const CHECK_READABILITY: &str = "readability";
+pub(crate) const CHECK_COMPATIBILITY: &str = "compatibility";
+
+const ALL_CHECKS: [&str; 5] = [CHECK_LINT, CHECK_SIZE, CHECK_SECRETS, CHECK_READABILITY, CHECK_COMPATIBILITY];
+
+/// Skill format limits checked by `compatibility`.
+const MAX_NAME_CHARS: usize = 64;
+const MAX_DESCRIPTION_CHARS: usize = 1024;
+
+/// Files larger than this are not scanned for secrets.
+const MAX_SCAN_BYTES: u64 = 1024 * 1024;
+/// Findings kept per check in the report.
+const MAX_FINDINGS: usize = 20;
+
+/// Token prefixes of well-known credential formats, with the minimum length
+/// of a real token and a label for the finding.
+const SECRET_PREFIXES: [(&str, usize, &str); 7] = [
+    ("sk-ant-", 30, "Anthropic API key"),
+    ("AKIA", 20, "AWS access key"),
+    ("ghp_", 36, "GitHub token"),
+    ("gho_", 36, "GitHub token"),
+    ("github_pat_", 40, "GitHub token"),
+    ("xoxb-", 24, "Slack token"),
+    ("xoxp-", 24, "Slack token"),
+];
+
+/// Assignment keys whose quoted values are treated as credentials.
+const SECRET_KEYS: [&str; 6] = ["api_key", "apikey", "secret", "password", "passwd", "access_token"];
+
+/// Built-in profiles, used when the team taxonomy doesn't define one of the same name.
+pub(crate) fn builtin_profiles() -> Vec<QualityGateProfile> {
+    vec![
+        QualityGateProfile {
+            name: "team-standard".to_string(),
+            description: Some("No secrets, valid frontmatter and accessible markdown".to_string()),
+            checks: vec![CHECK_LINT.to_string(), CHECK_SECRETS.to_string(), CHECK_COMPATIBILITY.to_string()],
+            budgets: QualityBudgets::default(),
+        },
+        QualityGateProfile {
+            name: "strict".to_string(),
+            description: Some("Every check, including size and readability budgets".to_string()),
+            checks: ALL_CHECKS.iter().map(|c| c.to_string()).collect(),
+            budgets: QualityBudgets::default(),
+        },
+    ]
+}
+
+/// Team profiles first, then built-ins the team hasn't redefined.
+pub(crate) fn available_profiles(conn: &rusqlite::Connection) -> Result<Vec<QualityGateProfile>, String> {
+    let mut profiles = crate::db::read_team_taxonomy(conn)?
+        .map(|t| t.quality_gates)
+        .unwrap_or_default();
+    for builtin in builtin_profiles() {
+        if !profiles.iter().any(|p| p.name == builtin.name) {
+            profiles.push(builtin);
+        }
+    }
+    Ok(profiles)
+}
+
+fn find_profile(conn: &rusqlite::Connection, name: &str) -> Result<QualityGateProfile, String> {
+    available_profiles(conn)?
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("Unknown quality gate profile '{}'", name))
+}
+
+fn validate_check(check: &str) -> Result<(), String> {
+    if ALL_CHECKS.contains(&check) {
+        Ok(())
+    } else {
+        Err(format!("Unknown quality check '{}': expected one of {}", check, ALL_CHECKS.join(", ")))
+    }
+}
+
+fn check_lint(skill_dir: &Path) -> Vec<String> {
+    let (_, issues) = super::skill_lint::lint_skill_dir(skill_dir);
+    issues
+        .into_iter()
+        .map(|i| format!("{}:{} {}", i.file, i.line, i.message))
+        .collect()
+}
+
+fn check_size(skill_dir: &Path, budgets: &QualityBudgets) -> Vec<String> {
+    let mut findings = Vec::new();
+    let skill_md_lines = std::fs::read_to_string(skill_dir.join("SKILL.md"))
+        .map(|c| c.lines().count())
+        .unwrap_or(0);
+    if skill_md_lines > budgets.skill_md_max_lines {
+        findings.push(format!(
+            "SKILL.md has {} lines (budget {}); move detail into references/",
+            skill_md_lines, budgets.skill_md_max_lines
+        ));
+    }
+    let total_bytes: u64 = super::skill_impact::skill_markdown_files(skill_dir)
+        .iter()
+        .filter_map(|f| std::fs::metadata(skill_dir.join(f)).ok())
+        .map(|m| m.len())
+        .sum();
+    if total_bytes > budgets.total_max_kb * 1024 {
+        findings.push(format!(
+            "Skill markdown totals {} KB (budget {} KB)",
+            total_bytes.div_ceil(1024),
+            budgets.total_max_kb
+        ));
+    }
+    findings
+}
+
+/// Kind of credential `token` looks like, if any.
+fn secret_kind(token: &str) -> Option<&'static str> {
+    SECRET_PREFIXES
+        .iter()
+        .find(|(prefix, min_len, _)| token.starts_with(prefix) && token.len() >= *min_len)
+        .map(|(_, _, label)| *label)
+}
+
+/// Whether `line` assigns a literal credential, e.g. `api_key: "f3a9..."`.
+/// Placeholders such as `<your-key>` or `{{ token }}` are ignored.
+fn assigns_secret(line: &str) -> bool {
+    let lower = line.to_ascii_lowercase();
+    SECRET_KEYS.iter().any(|key| {
+        lower.match_indices(key).any(|(i, _)| {
+            let rest = lower[i + key.len()..].trim_start_matches(['"', '\'']).trim_start();
+            let Some(value) = rest.strip_prefix([':', '=']) else {
+                return false;
+            };
+            let value = value.trim();
+            let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+                return false;
+            };
+            let literal = value[1..].split(quote).next().unwrap_or("");
+            literal.len() >= 12
+                && !literal.contains(char::is_whitespace)
+                && !["<", "{", "$", "xxx", "your", "example", "placeholder"]
+                    .iter()
+                    .any(|p| literal.contains(p))
+        })
+    })
+}
+
+/// Every text file in the skill, excluding built packages.
+fn scan_files(skill_dir: &Path) -> Vec<(String, String)> {
+    let mut files = Vec::new();
+    let mut stack = vec![skill_dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if path.is_dir() {
+                if !name.starts_with('.') {
+                    stack.push(path);
+                }
+                continue;
+            }
+            if path.extension().is_some_and(|e| e == "skill" || e == "zip")
+                || entry.metadata().map_or(true, |m| m.len() > MAX_SCAN_BYTES)
+            {
+                continue;
+            }
+            let (Ok(content), Ok(rel)) = (std::fs::read_to_string(&path), path.strip_prefix(skill_dir)) else {
+                continue;
+            };
+            files.push((rel.to_string_lossy().replace('\\', "/"), content));
+        }
+    }
+    files.sort();
+    files
+}
+
+fn check_secrets(skill_dir: &Path) -> Vec<String> {
+    let mut findings = Vec::new();
+    for (file, content) in scan_files(skill_dir) {
+        for (idx, line) in content.lines().enumerate() {
+            let kind = if line.contains("PRIVATE KEY-----") {
+                Some("private key")
+            } else {
+                line.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+                    .find_map(secret_kind)
+                    .or_else(|| assigns_secret(line).then_some("hard-coded credential"))
+            };
+            // Never echo the value itself into the report.
+            if let Some(kind) = kind {
+                findings.push(format!("{}:{} possible {}", file, idx + 1, kind));
+            }
+        }
+    }
+    findings
+}
+
+/// Sentences of prose in `body`, skipping code, headings, tables and HTML.
+fn sentences(body: &str) -> Vec<String> {
+    let mut prose = String::new();
+    let mut in_fence = false;
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence || trimmed.is_empty() || trimmed.starts_with(['#', '|', '<']) || line.starts_with("    ") {
+            // Blank lines and block boundaries end a sentence.
+            prose.push_str(".\n");
+            continue;
+        }
+        let text = trimmed.trim_start_matches(['-', '*', '+', '>']).trim_start();
+        prose.push_str(text);
+        prose.push(' ');
+    }
+    prose
+        .split(['.', '!', '?', ':', ';', '\n'])
+        .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|s| s.split_whitespace().count() >= 3)
+        .collect()
+}
+
+fn check_readability(skill_dir: &Path, budgets: &QualityBudgets) -> Vec<String> {
+    let Ok(content) = std::fs::read_to_string(skill_dir.join("SKILL.md")) else {
+        return Vec::new();
+    };
+    let sentences = sentences(super::skill_validation::frontmatter_body(&content));
+    if sentences.is_empty() {
+        return Vec::new();
+    }
+    let words: usize = sentences.iter().map(|s| s.split_whitespace().count()).sum();
+    let average = words as f64 / sentences.len() as f64;
+    if average <= budgets.max_sentence_words as f64 {
+        return Vec::new();
+    }
+    let long = sentences
+        .iter()
+        .filter(|s| s.split_whitespace().count() > budgets.max_sentence_words * 2)
+        .count();
+    vec![format!(
+        "Average sentence in SKILL.md is {:.1} words (budget {}); {} sentence(s) over {} words",
+        average,
+        budgets.max_sentence_words,
+        long,
+        budgets.max_sentence_words * 2
+    )]
+}
+
+fn check_compatibility(skill_dir: &Path) -> Vec<String> {
+    let Ok(content) = std::fs::read_to_string(skill_dir.join("SKILL.md")) else {
+        return vec!["SKILL.md is missing".to_string()];
+    };
+    let fm = super::imported_skills::parse_frontmatter_full(&content);
+    let dir_name = skill_dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let mut findings = Vec::new();
+    match fm.name.as_deref() {
+        None => findings.push("Frontmatter has no name".to_string()),
+        Some(name) => {
+            if !crate::commands::skill::is_valid_kebab(name) || name.chars().count() > MAX_NAME_CHARS {
+                findings.push(format!(
+                    "Name '{}' must be kebab-case and at most {} characters",
+                    name, MAX_NAME_CHARS
+                ));
+            }
+            if name != dir_name {
+                findings.push(format!("Name '{}' doesn't match the skill folder '{}'", name, dir_name));
+            }
+        }
+    }
+    match fm.description.as_deref().map(str::trim) {
+        None | Some("") => findings.push("Frontmatter has no description".to_string()),
+        Some(d) if d.chars().count() > MAX_DESCRIPTION_CHARS => findings.push(format!(
+            "Description is {} characters (limit {})",
+            d.chars().count(),
+            MAX_DESCRIPTION_CHARS
+        )),
+        Some(d) if d.contains('<') && d.contains('>') => {
+            findings.push("Description contains XML/HTML tags".to_string())
+        }
+        _ => {}
+    }
+    findings
+}
+
+fn run_check(check: &str, skill_dir: &Path, budgets: &QualityBudgets) -> Vec<String> {
+    match check {
+        CHECK_LINT => check_lint(skill_dir),
+        CHECK_SIZE => check_size(skill_dir, budgets),
+        CHECK_SECRETS => check_secrets(skill_dir),
+        CHECK_READABILITY => check_readability(skill_dir, budgets),
+        _ => check_compatibility(skill_dir),
+    }
+}
+
+/// Run every check against `skill_dir` and apply `profile` and `waivers`.
+pub(crate) fn build_report(
+    skill_name: &str,
+    skill_dir: &Path,
+    profile: &QualityGateProfile,
+    waivers: &[QualityWaiver],
+) -> QualityGateReport {
+    let checks: Vec<QualityCheckResult> = ALL_CHECKS
+        .iter()
+        .map(|&check| {
+            let mut findings = run_check(check, skill_dir, &profile.budgets);
+            let passed = findings.is_empty();
+            if findings.len() > MAX_FINDINGS {
+                let more = findings.len() - MAX_FINDINGS;
+                findings.truncate(MAX_FINDINGS);
+                findings.push(format!("and {} more", more));
+            }
+            QualityCheckResult {
+                check: check.to_string(),
+                enforced: profile.checks.iter().any(|c| c == check),
+                passed,
+                findings,
+                waiver: if passed { None } else { waivers.iter().find(|w| w.check == check).cloned() },
+            }
+        })
+        .collect();
+    let passed = checks.iter().all(|c| c.passed || !c.enforced || c.waiver.is_some());
+    QualityGateReport {
+        skill_name: skill_name.to_string(),
+        profile: profile.name.clone(),
+        passed,
+        checks,
+    }
+}
+
+pub(crate) fn report_for(
+    conn: &rusqlite::Connection,
+    skill_name: &str,
+    skill_dir: &Path,
+    profile_name: &str,
+) -> Result<QualityGateReport, String> {
+    let profile = find_profile(conn, profile_name)?;
+    let waivers = crate::db::list_quality_waivers(conn, skill_name)?;
+    Ok(build_report(skill_name, skill_dir, &profile, &waivers))
+}
+
+/// Run the profile selected in settings before packaging or pushing a skill.
+/// `Ok(None)` when no profile is selected; an error naming the failing checks
+/// when the gate fails.
+pub(crate) fn gate_for_packaging(
+    conn: &rusqlite::Connection,
+    skill_name: &str,
+    skill_dir: &Path,
+) -> Result<Option<QualityGateReport>, String> {
+    let Some(profile) = crate::db::read_settings(conn)?.quality_gate_profile else {
+        return Ok(None);
+    };
+    let report = report_for(conn, skill_name, skill_dir, &profile)?;
+    if report.passed {
+        return Ok(Some(report));
+    }
+    let failing: Vec<String> = report
+        .checks
+        .iter()
+        .filter(|c| c.enforced && !c.passed && c.waiver.is_none())
+        .map(|c| format!("{} ({})", c.check, c.findings.first().map_or("failed", String::as_str)))
+        .collect();
+    Err(format!(
+        "Quality gate '{}' failed for '{}': {}. Fix the findings or record a waiver.",
+        report.profile,
+        skill_name,
+        failing.join("; ")
+    ))
+}
+
+fn skill_dir(conn: &rusqlite::Connection, skill_name: &str) -> Result<std::path::PathBuf, String> {
+    let skills_path = crate::db::read_settings(conn)?
+        .skills_path
+        .ok_or_else(|| "Skills path not configured. Please set it in Settings.".to_string())?;
+    let dir = Path::new(&skills_path).join(skill_name);
+    if !dir.join("SKILL.md").is_file() {
+        return Err(format!("Skill '{}' not found in {}", skill_name, skills_path));
+    }
+    Ok(dir)
+}
+
+/// Team and built-in quality gate profiles.
+#[tauri::command]
+pub fn list_quality_gate_profiles(db: tauri::State<'_, Db>) -> Result<Vec<QualityGateProfile>, String> {
+    log::info!("[list_quality_gate_profiles]");
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[list_quality_gate_profiles] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    available_profiles(&conn)
+}
+
+/// Consolidated gate report for a skill under `profile`, or the profile
+/// selected in settings when omitted.
+#[tauri::command]
+pub fn run_quality_gate(
+    skill_name: String,
+    profile: Option<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<QualityGateReport, String> {
+    log::info!("[run_quality_gate] skill={} profile={:?}", skill_name, profile);
+    validate_skill_name(&skill_name)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[run_quality_gate] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let profile = match profile {
+        Some(p) => p,
+        None => crate::db::read_settings(&conn)?
+            .quality_gate_profile
+            .ok_or_else(|| "No quality gate profile selected. Choose one in Settings.".to_string())?,
+    };
+    let dir = skill_dir(&conn, &skill_name)?;
+    report_for(&conn, &skill_name, &dir, &profile).map_err(|e| {
+        log::error!("[run_quality_gate] {}", e);
+        e
+    })
+}
+
+/// Let `check` fail for a skill, recording why and who decided.
+#[tauri::command]
+pub fn waive_quality_check(
+    skill_name: String,
+    check: String,
+    justification: String,
+    db: tauri::State<'_, Db>,
+) -> Result<QualityWaiver, String> {
+    log::info!("[waive_quality_check] skill={} check={}", skill_name, check);
+    super::observer::ensure_writable(&skill_name)?;
+    validate_skill_name(&skill_name)?;
+    validate_check(&check)?;
+    let justification = justification.trim();
+    if justification.is_empty() {
+        return Err("A waiver needs a justification".to_string());
+    }
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[waive_quality_check] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let waiver = QualityWaiver {
+        skill_name,
+        check,
+        justification: justification.to_string(),
+        waived_by: crate::db::read_settings(&conn)?.github_user_login,
+        waived_at: chrono::Utc::now().to_rfc3339(),
+    };
+    crate::db::upsert_quality_waiver(&conn, &waiver).map_err(|e| {
+        log::error!("[waive_quality_check] {}", e);
+        e
+    })?;
+    Ok(waiver)
+}
+
+#[tauri::command]
+pub fn revoke_quality_waiver(skill_name: String, check: String, db: tauri::State<'_, Db>) -> Result<(), String> {
+    log::info!("[revoke_quality_waiver] skill={} check={}", skill_name, check);
+    super::observer::ensure_writable(&skill_name)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[revoke_quality_waiver] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    if !crate::db::delete_quality_waiver(&conn, &skill_name, &check)? {
+        return Err(format!("No waiver for '{}' on '{}'", check, skill_name));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::create_test_db;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_secret_scan_flags_tokens_without_echoing_them() {
+        let tmp = tempdir().unwrap();
+        let dir = tmp.path().join("warehouse");
+        std::fs::create_dir_all(dir.join("references")).unwrap();
+        std::fs::write(dir.join("SKILL.md"), "---\nname: warehouse\ndescription: d\n---\n# Warehouse\n").unwrap();
+        std::fs::write(
+            dir.join("references/setup.md"),
+            "Export ANTHROPIC_API_KEY=sk-ant-REDACTED\n\
+             api_key: \"9f8e7d6c5b4a3f2e1d0c\"\n\
+             api_key: \"<your-key-here>\"\n\
+             password = 'hunter2'\n",
+        )
+        .unwrap();
+
+        let findings = check_secrets(&dir);
+        assert_eq!(
+            findings,
+            vec![
+                "references/setup.md:1 possible Anthropic API key",
+                "references/setup.md:2 possible hard-coded credential",
+            ]
+        );
+        assert!(findings.iter().all(|f| !f.contains("sk-ant-api03")));
+    }
+
+    #[test]
+    fn test_profile_enforcement_and_waivers() {
+        let tmp = tempdir().unwrap();
+        let dir = tmp.path().join("long-winded");
+        std::fs::create_dir_all(&dir).unwrap();
+        let rambling = "This sentence keeps going with many words that never seem to end at all because it wants to be long and it also repeats itself again and again without any pause for breath. ";
+        std::fs::write(
+            dir.join("SKILL.md"),
+            format!("---\nname: long-winded\ndescription: Rambles\n---\n# Long\n\n{}\n", rambling.repeat(3)),
+        )
+        .unwrap();
+
+        let conn = create_test_db();
+        let profiles = available_profiles(&conn).unwrap();
+        let standard = profiles.iter().find(|p| p.name == "team-standard").unwrap();
+        let strict = profiles.iter().find(|p| p.name == "strict").unwrap();
+
+        // Readability fails everywhere but is only enforced by strict.
+        let report = build_report("long-winded", &dir, standard, &[]);
+        assert!(report.passed);
+        let readability = report.checks.iter().find(|c| c.check == CHECK_READABILITY).unwrap();
+        assert!(!readability.passed && !readability.enforced);
+        assert!(!build_report("long-winded", &dir, strict, &[]).passed);
+
+        let waiver = QualityWaiver {
+            skill_name: "long-winded".to_string(),
+            check: CHECK_READABILITY.to_string(),
+            justification: "Legal wording must stay verbatim".to_string(),
+            waived_by: None,
+            waived_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        crate::db::upsert_quality_waiver(&conn, &waiver).unwrap();
+        let waivers = crate::db::list_quality_waivers(&conn, "long-winded").unwrap();
+        assert_eq!(waivers, vec![waiver]);
+        assert!(build_report("long-winded", &dir, strict, &waivers).passed);
+
+        // Selected in settings, the gate blocks packaging until waived.
+        let mut settings = crate::db::read_settings(&conn).unwrap();
+        settings.quality_gate_profile = Some("strict".to_string());
+        crate::db::write_settings(&conn, &settings).unwrap();
+        assert!(gate_for_packaging(&conn, "long-winded", &dir).unwrap().unwrap().passed);
+        crate::db::delete_quality_waiver(&conn, "long-winded", CHECK_READABILITY).unwrap();
+        let err = gate_for_packaging(&conn, "long-winded", &dir).unwrap_err();
+        assert!(err.contains("Quality gate 'strict' failed") && err.contains("readability"), "{}", err);
+
+        // Team profiles replace built-ins of the same name.
+        let taxonomy = crate::types::TeamTaxonomy {
+            quality_gates: vec![QualityGateProfile {
+                name: "strict".to_string(),
+                description: None,
+                checks: vec![CHECK_SECRETS.to_string()],
+                budgets: QualityBudgets::default(),
+            }],
+            ..Default::default()
+        };
+        crate::db::write_team_taxonomy(&conn, &taxonomy).unwrap();
+        assert!(gate_for_packaging(&conn, "long-winded", &dir).unwrap().unwrap().passed);
+        assert_eq!(available_profiles(&conn).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_compatibility_checks_frontmatter() {
+        let tmp = tempdir().unwrap();
+        let dir = tmp.path().join("sales");
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_eq!(check_compatibility(&dir), vec!["SKILL.md is missing"]);
+        std::fs::write(dir.join("SKILL.md"), "---\nname: Sales_Pipeline\n---\n# Sales\n").unwrap();
+        let findings = check_compatibility(&dir);
+        assert_eq!(findings.len(), 3, "{:?}", findings);
+        std::fs::write(dir.join("SKILL.md"), "---\nname: sales\ndescription: Pipeline reviews\n---\n# Sales\n").unwrap();
+        assert!(check_compatibility(&dir).is_empty());
+    }
+}
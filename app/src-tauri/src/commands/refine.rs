@@ -7,7 +7,10 @@ use crate::agents::sidecar_pool::SidecarPool;
 use crate::commands::imported_skills::validate_skill_name;
 use crate::commands::workflow::{resolve_model_id, write_skill_output_dir_file};
 use crate::db::{self, Db};
-use crate::types::{RefineFileDiff, RefineDiff, RefineSessionInfo, SkillFileContent};
+use crate::types::{
+    AppSettings, RefineBatch, RefineDiff, RefineFileDiff, RefineFileReview, RefineSessionInfo, RefineSessionMessage,
+    ResumableRefineSession, ResumedRefineSession, SkillFileContent,
+};
 
 /// Tools available to the refine-skill agent. Matches the agent's frontmatter
 /// `tools: Read, Edit, Write, Glob, Grep, Task`. Task is required for the
@@ -25,14 +28,21 @@ const REFINE_STREAM_MAX_TURNS: u32 = 400;
 
 /// In-memory state for a single refine session.
 ///
-/// Created by `start_refine_session`, used by `send_refine_message`.
-/// The streaming session is started on the first message and maintained
-/// across subsequent messages — the SDK preserves full conversation state.
+/// Created by `start_refine_session` or `resume_refine_session`, used by
+/// `send_refine_message`. The streaming session is started on the first
+/// message and maintained across subsequent messages — the SDK preserves full
+/// conversation state. Sessions are also persisted in `refine_sessions` until
+/// closed, so one interrupted by a crash can be resumed.
 pub struct RefineSession {
     pub skill_name: String,
     /// Whether the sidecar streaming session has been started.
     /// First `send_refine_message` sends `stream_start`, subsequent sends `stream_message`.
     pub stream_started: bool,
+    /// SDK session to continue when the stream starts (resumed sessions only).
+    pub resume_sdk_session_id: Option<String>,
+    /// Earlier messages of a resumed session, recapped in the first prompt
+    /// when the SDK session can't be continued.
+    pub history: Vec<RefineSessionMessage>,
 }
 
 /// Manages active refine sessions. Registered as Tauri managed state.
@@ -107,6 +117,10 @@ fn build_refine_config(
         agent_name: Some(REFINE_AGENT_NAME.to_string()),
         required_plugins: None,
         conversation_history: None,
+        resume: None,
+        bash_sandbox: None,
+        disallowed_tools: None,
+        local_model: None,
     };
 
     (config, agent_id)
@@ -166,6 +180,8 @@ fn build_refine_prompt(
         "The skill name is: {}. The command is: {}. The workspace directory is: {}. \
          Read user-context.md and .skill_output_dir from the workspace directory first. \
          Derive context_dir as workspace_dir/context. The skill output directory (SKILL.md and references/) is the path in .skill_output_dir. \
+         Changes may span SKILL.md and any file under references/: edit, add or remove reference files as the request needs. \
+         The user accepts or rejects each changed file on its own, so keep every file consistent by itself. \
          All directories already exist — never create directories with mkdir or any other method.",
         skill_name,
         effective_command,
@@ -187,7 +203,54 @@ fn build_refine_prompt(
     prompt
 }
 
-fn resolve_skills_path(db: &Db, workspace_path: &str) -> Result<String, String> {
+/// Write user-context.md for a refine agent from the skill's workflow run.
+fn write_refine_user_context(conn: &rusqlite::Connection, settings: &AppSettings, workspace_path: &str, skill_name: &str) {
+    let run_row = db::get_workflow_run(conn, skill_name).ok().flatten();
+    let purpose = run_row
+        .as_ref()
+        .map(|r| r.purpose.clone())
+        .unwrap_or_else(|| "domain".to_string());
+
+    let intake_json = run_row.as_ref().and_then(|r| r.intake_json.clone());
+
+    crate::commands::workflow::write_user_context_file(
+        workspace_path,
+        skill_name,
+        &[], // tags not fetched in refine path — lightweight call
+        settings.industry.as_deref(),
+        settings.function_role.as_deref(),
+        intake_json.as_deref(),
+        None, Some(purpose.as_str()), None, None, None, None, None,
+    );
+}
+
+/// Ensure the skill's workspace dir exists before building the prompt, and
+/// write `.skill_output_dir` so the agent derives paths (SDK calling protocol).
+/// The prompt tells the agent "All directories already exist", so this must
+/// be true by the time the prompt is constructed. For marketplace skills,
+/// workspace_path/skill_name/ is not created during import.
+fn prepare_refine_workspace_dir(workspace_path: &str, skills_path: &str, skill_name: &str) {
+    let skill_workspace_dir = Path::new(workspace_path).join(skill_name);
+    if !skill_workspace_dir.exists() {
+        if let Err(e) = std::fs::create_dir_all(&skill_workspace_dir) {
+            log::warn!(
+                "[prepare_refine_workspace_dir] failed to create skill workspace dir '{}': {}",
+                skill_workspace_dir.display(),
+                e
+            );
+        } else {
+            log::debug!(
+                "[prepare_refine_workspace_dir] created skill workspace dir '{}'",
+                skill_workspace_dir.display()
+            );
+        }
+    }
+
+    let skill_output_dir = Path::new(skills_path).join(skill_name);
+    write_skill_output_dir_file(&skill_workspace_dir, &skill_output_dir);
+}
+
+pub(crate) fn resolve_skills_path(db: &Db, workspace_path: &str) -> Result<String, String> {
     let conn = db.0.lock().map_err(|e| e.to_string())?;
     let settings = db::read_settings(&conn)?;
     Ok(settings
@@ -195,6 +258,90 @@ fn resolve_skills_path(db: &Db, workspace_path: &str) -> Result<String, String>
         .unwrap_or_else(|| workspace_path.to_string()))
 }
 
+/// Insert a recap of a resumed session's earlier requests before the current
+/// request, for when its SDK conversation can't be continued.
+fn with_resume_recap(prompt: String, history: &[RefineSessionMessage]) -> String {
+    let requests: Vec<String> = history
+        .iter()
+        .filter(|m| m.role == "user")
+        .filter_map(|m| m.text.as_deref())
+        .map(|t| format!("- {}", t))
+        .collect();
+    if requests.is_empty() {
+        return prompt;
+    }
+    let recap = format!(
+        "This continues an interrupted refine session. The skill files may already reflect \
+         these earlier requests, oldest first:\n{}",
+        requests.join("\n")
+    );
+    match prompt.rfind("\n\nCurrent request: ") {
+        Some(at) => format!("{}\n\n{}{}", &prompt[..at], recap, &prompt[at..]),
+        None => format!("{}\n\n{}", prompt, recap),
+    }
+}
+
+fn persist_refine_message(db: &Db, session_id: &str, message: RefineSessionMessage) {
+    match db.0.lock() {
+        Ok(conn) => {
+            if let Err(e) = db::append_refine_session_message(&conn, session_id, &message) {
+                log::warn!("[persist_refine_message] Failed to persist message: {}", e);
+            }
+        }
+        Err(e) => log::warn!("[persist_refine_message] Failed to acquire DB lock: {}", e),
+    }
+}
+
+/// Record a refine agent's reply, SDK session id and the resulting pending
+/// diff on the session that sent it the message.
+fn record_refine_result(conn: &rusqlite::Connection, agent_id: &str, message: &serde_json::Value) -> Result<(), String> {
+    let Some((session_id, skill_name)) = db::find_refine_session_for_agent(conn, agent_id)? else {
+        return Ok(());
+    };
+    db::append_refine_session_message(
+        conn,
+        &session_id,
+        &RefineSessionMessage {
+            role: "agent".to_string(),
+            text: message["result"].as_str().map(|s| s.to_string()),
+            target_files: Vec::new(),
+            command: None,
+            agent_id: Some(agent_id.to_string()),
+            created_at: chrono::Utc::now().to_rfc3339(),
+        },
+    )?;
+    let pending_diff = db::read_settings(conn)?
+        .skills_path
+        .and_then(|skills_path| match get_refine_diff_inner(&skill_name, &skills_path) {
+            Ok(diff) => Some(diff),
+            Err(e) => {
+                log::warn!("[record_refine_result] Failed to compute diff for '{}': {}", skill_name, e);
+                None
+            }
+        });
+    db::update_refine_session_state(conn, &session_id, message["session_id"].as_str(), pending_diff.as_ref())
+}
+
+/// Persist what a refine agent's result message says about its session.
+/// Called for every message of `refine-*` agents; ignores all but results.
+pub fn observe_refine_message(app_handle: &tauri::AppHandle, agent_id: &str, message: &serde_json::Value) {
+    use tauri::Manager;
+    if message.get("type").and_then(|t| t.as_str()) != Some("result") {
+        return;
+    }
+    let Some(db) = app_handle.try_state::<Db>() else {
+        return;
+    };
+    match db.0.lock() {
+        Ok(conn) => {
+            if let Err(e) = record_refine_result(&conn, agent_id, message) {
+                log::warn!("[observe_refine_message] Failed to persist result of {}: {}", agent_id, e);
+            }
+        }
+        Err(e) => log::warn!("[observe_refine_message] Failed to acquire DB lock: {}", e),
+    }
+}
+
 // ─── get_skill_content_for_refine ────────────────────────────────────────────
 
 /// Returns the content of SKILL.md and all reference files for a skill.
@@ -275,13 +422,14 @@ fn get_skill_content_inner(
 /// Returns the git diff for a skill's directory — both staged and unstaged changes.
 /// Used by the preview panel to show what the refine agent changed.
 ///
-/// Also supports per-file diffs via the `files` array in the response.
-/// The frontend can use `git checkout -- <file>` (via a separate command) to undo
-/// individual file changes.
+/// Each changed file under the skill (SKILL.md, references/, ...) has its own
+/// entry in `files`. With `session_id`, files that session accepted are marked;
+/// `review_refine_file` accepts or rejects them one at a time.
 #[tauri::command]
 pub fn get_refine_diff(
     skill_name: String,
     workspace_path: String,
+    session_id: Option<String>,
     db: tauri::State<'_, Db>,
 ) -> Result<RefineDiff, String> {
     log::info!("[get_refine_diff] skill={}", skill_name);
@@ -290,13 +438,33 @@ pub fn get_refine_diff(
         log::error!("[get_refine_diff] Failed to resolve skills path: {}", e);
         e
     })?;
-    get_refine_diff_inner(&skill_name, &skills_path).map_err(|e| {
+    let mut diff = get_refine_diff_inner(&skill_name, &skills_path).map_err(|e| {
         log::error!("[get_refine_diff] {}", e);
         e
-    })
+    })?;
+    if let Some(session_id) = session_id {
+        let conn = db.0.read().map_err(|e| {
+            log::error!("[get_refine_diff] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        if let Some(record) = db::get_refine_session(&conn, &session_id)? {
+            mark_reviewed(&mut diff, &record.file_reviews);
+        }
+    }
+    Ok(diff)
+}
+
+/// Mark files whose current change is the one the session accepted.
+fn mark_reviewed(diff: &mut RefineDiff, reviews: &[RefineFileReview]) {
+    for file in &mut diff.files {
+        file.review = reviews
+            .iter()
+            .find(|r| r.path == file.path && r.diff == file.diff && r.decision == "accepted")
+            .map(|r| r.decision.clone());
+    }
 }
 
-fn get_refine_diff_inner(skill_name: &str, skills_path: &str) -> Result<RefineDiff, String> {
+pub(crate) fn get_refine_diff_inner(skill_name: &str, skills_path: &str) -> Result<RefineDiff, String> {
     use git2::{Delta, DiffFormat, DiffOptions, Repository};
 
     let repo_path = Path::new(skills_path);
@@ -314,7 +482,11 @@ fn get_refine_diff_inner(skill_name: &str, skills_path: &str) -> Result<RefineDi
     let prefix = format!("{}/", skill_name);
     log::debug!("[get_refine_diff] computing diff for prefix '{}'", prefix);
     let mut opts = DiffOptions::new();
-    opts.pathspec(&prefix);
+    // New files (e.g. a reference the agent added) are untracked until committed
+    opts.pathspec(&prefix)
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
 
     // Get HEAD tree (may not exist in a fresh repo)
     let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
@@ -338,7 +510,7 @@ fn get_refine_diff_inner(skill_name: &str, skills_path: &str) -> Result<RefineDi
             .unwrap_or_default();
 
         let status = match delta.status() {
-            Delta::Added => "added",
+            Delta::Added | Delta::Untracked => "added",
             Delta::Deleted => "deleted",
             _ => "modified",
         };
@@ -346,6 +518,7 @@ fn get_refine_diff_inner(skill_name: &str, skills_path: &str) -> Result<RefineDi
             path,
             status: status.to_string(),
             diff: String::new(),
+            review: None,
         });
 
         // Append diff content: hunk headers, context, additions, deletions
@@ -413,6 +586,7 @@ pub async fn start_refine_session(
     db: tauri::State<'_, Db>,
 ) -> Result<RefineSessionInfo, String> {
     log::info!("[start_refine_session] skill={}", skill_name);
+    super::observer::ensure_writable(&skill_name)?;
     validate_skill_name(&skill_name)?;
 
     let skills_path = resolve_skills_path(&db, &workspace_path).map_err(|e| {
@@ -440,6 +614,19 @@ pub async fn start_refine_session(
         return Err(msg);
     }
 
+    // A batch refine owns the skill's uncommitted changes until they are reviewed
+    let in_batch = db
+        .0
+        .read()
+        .map_err(|e| e.to_string())
+        .and_then(|conn| db::open_refine_batch_skills(&conn))?
+        .contains(&skill_name);
+    if in_batch {
+        let msg = format!("'{}' is in a batch refine awaiting review", skill_name);
+        log::error!("[start_refine_session] {}", msg);
+        return Err(msg);
+    }
+
     let session_id = uuid::Uuid::new_v4().to_string();
     let created_at = chrono::Utc::now().to_rfc3339();
     log::debug!(
@@ -452,8 +639,21 @@ pub async fn start_refine_session(
         RefineSession {
             skill_name: skill_name.clone(),
             stream_started: false,
+            resume_sdk_session_id: None,
+            history: Vec::new(),
         },
     );
+    drop(map);
+
+    // Persist so the session can be resumed if the app quits before it's closed.
+    match db.0.lock() {
+        Ok(conn) => {
+            if let Err(e) = db::insert_refine_session(&conn, &session_id, &skill_name) {
+                log::warn!("[start_refine_session] Failed to persist session: {}", e);
+            }
+        }
+        Err(e) => log::warn!("[start_refine_session] Failed to acquire DB lock: {}", e),
+    }
 
     Ok(RefineSessionInfo {
         session_id,
@@ -514,6 +714,15 @@ pub async fn send_refine_message(
         })?;
         (session.skill_name.clone(), session.stream_started)
     };
+    super::observer::ensure_writable(&skill_name)?;
+    let user_entry = RefineSessionMessage {
+        role: "user".to_string(),
+        text: Some(user_message.clone()),
+        target_files: target_files.clone().unwrap_or_default(),
+        command: command.clone(),
+        agent_id: None,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
     log::info!(
         "[send_refine_message] skill={} stream_started={}",
         skill_name, stream_started
@@ -540,7 +749,7 @@ pub async fn send_refine_message(
                 log::error!("[send_refine_message] Failed to read settings: {}", e);
                 e
             })?;
-            let key = match settings.anthropic_api_key {
+            let key = match crate::commands::offline::agent_api_key(&settings) {
                 Some(k) => k,
                 None => {
                     log::error!("[send_refine_message] Anthropic API key not configured");
@@ -553,26 +762,11 @@ pub async fn send_refine_message(
 
             let skills_path = settings
                 .skills_path
+                .clone()
                 .unwrap_or_else(|| workspace_path.clone());
 
-            let run_row = db::get_workflow_run(&conn, &skill_name).ok().flatten();
-            let purpose = run_row
-                .as_ref()
-                .map(|r| r.purpose.clone())
-                .unwrap_or_else(|| "domain".to_string());
-
-            let intake_json = run_row.as_ref().and_then(|r| r.intake_json.clone());
-
             // Write user-context.md so the agent can read it (same as workflow steps)
-            crate::commands::workflow::write_user_context_file(
-                &workspace_path,
-                &skill_name,
-                &[], // tags not fetched in refine path — lightweight call
-                settings.industry.as_deref(),
-                settings.function_role.as_deref(),
-                intake_json.as_deref(),
-                None, Some(purpose.as_str()), None, None, None, None, None,
-            );
+            write_refine_user_context(&conn, &settings, &workspace_path, &skill_name);
 
             (
                 key,
@@ -586,31 +780,16 @@ pub async fn send_refine_message(
             )
         };
 
-        // 3. Ensure the skill's workspace dir exists before building the prompt.
-        // The prompt tells the agent "All directories already exist", so this must
-        // be true by the time the prompt is constructed. For marketplace skills,
-        // workspace_path/skill_name/ is not created during import.
-        let skill_workspace_dir = Path::new(&workspace_path).join(&skill_name);
-        if !skill_workspace_dir.exists() {
-            if let Err(e) = std::fs::create_dir_all(&skill_workspace_dir) {
-                log::warn!(
-                    "[send_refine_message] failed to create skill workspace dir '{}': {}",
-                    skill_workspace_dir.display(),
-                    e
-                );
-            } else {
-                log::debug!(
-                    "[send_refine_message] created skill workspace dir '{}'",
-                    skill_workspace_dir.display()
-                );
-            }
-        }
-
-        // 3b. Write .skill_output_dir so the agent derives paths (SDK calling protocol).
-        let skill_output_dir = Path::new(&skills_path).join(&skill_name);
-        write_skill_output_dir_file(&skill_workspace_dir, &skill_output_dir);
+        // 3. Ensure the skill's workspace dir exists and write .skill_output_dir.
+        prepare_refine_workspace_dir(&workspace_path, &skills_path, &skill_name);
 
         // 4. Build prompt: only skill name, workspace_dir, command (no inline paths).
+        let (resume_sdk_session_id, history) = {
+            let map = sessions.0.lock().map_err(|e| e.to_string())?;
+            map.get(&session_id)
+                .map(|s| (s.resume_sdk_session_id.clone(), s.history.clone()))
+                .unwrap_or_default()
+        };
         let prompt = build_refine_prompt(
             &skill_name,
             &workspace_path,
@@ -619,6 +798,11 @@ pub async fn send_refine_message(
             target_files.as_deref(),
             command.as_deref(),
         );
+        let prompt = if resume_sdk_session_id.is_none() {
+            with_resume_recap(prompt, &history)
+        } else {
+            prompt
+        };
         log::debug!(
             "[send_refine_message] first message prompt ({} chars) for skill '{}' command={:?}:\n{}",
             prompt.len(),
@@ -642,6 +826,8 @@ pub async fn send_refine_message(
             refine_prompt_suggestions,
         );
 
+        config.resume = resume_sdk_session_id;
+
         // Resolve SDK cli.js path
         if config.path_to_claude_code_executable.is_none() {
             if let Ok(cli_path) = sidecar::resolve_sdk_cli_path_public(&app) {
@@ -676,6 +862,7 @@ pub async fn send_refine_message(
             }
         }
 
+        persist_refine_message(&db, &session_id, RefineSessionMessage { agent_id: Some(agent_id.clone()), ..user_entry });
         Ok(agent_id)
     } else {
         // ─── Follow-up message: push into existing stream ─────────────────
@@ -716,6 +903,7 @@ pub async fn send_refine_message(
             e
         })?;
 
+        persist_refine_message(&db, &session_id, RefineSessionMessage { agent_id: Some(agent_id.clone()), ..user_entry });
         Ok(agent_id)
     }
 }
@@ -729,15 +917,26 @@ pub async fn send_refine_message(
 /// so a new session can be started for the same skill.
 ///
 /// If a streaming session was started, sends `stream_end` to the sidecar to
-/// close the async generator and finish the SDK query.
+/// close the async generator and finish the SDK query. The persisted session
+/// is deleted, so closing also discards a resumable session that isn't open.
 #[tauri::command]
 pub async fn close_refine_session(
     session_id: String,
     sessions: tauri::State<'_, RefineSessionManager>,
     pool: tauri::State<'_, SidecarPool>,
+    db: tauri::State<'_, Db>,
 ) -> Result<(), String> {
     log::info!("[close_refine_session] session=[REDACTED]");
 
+    match db.0.lock() {
+        Ok(conn) => {
+            if let Err(e) = db::delete_refine_session(&conn, &session_id) {
+                log::warn!("[close_refine_session] Failed to delete persisted session: {}", e);
+            }
+        }
+        Err(e) => log::warn!("[close_refine_session] Failed to acquire DB lock: {}", e),
+    }
+
     let removed = {
         let mut map = sessions.0.lock().map_err(|e| {
             log::error!("[close_refine_session] Failed to acquire session lock: {}", e);
@@ -771,6 +970,208 @@ pub async fn close_refine_session(
     Ok(())
 }
 
+// ─── resumable sessions ───────────────────────────────────────────────────────
+
+fn list_resumable_inner(
+    conn: &rusqlite::Connection,
+    open: &HashMap<String, RefineSession>,
+) -> Result<Vec<ResumableRefineSession>, String> {
+    Ok(db::list_refine_sessions(conn)?
+        .into_iter()
+        .filter(|r| !open.contains_key(&r.session_id) && !r.messages.is_empty())
+        .map(|r| ResumableRefineSession {
+            message_count: r.messages.len(),
+            last_user_message: r
+                .messages
+                .iter()
+                .rev()
+                .find(|m| m.role == "user")
+                .and_then(|m| m.text.clone()),
+            has_pending_changes: r.pending_diff.is_some(),
+            can_resume_conversation: r.sdk_session_id.is_some(),
+            session_id: r.session_id,
+            skill_name: r.skill_name,
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+        })
+        .collect())
+}
+
+/// Persisted refine sessions with at least one message that aren't open in
+/// this app instance — left behind by a crash or force-quit.
+#[tauri::command]
+pub fn list_resumable_refine_sessions(
+    sessions: tauri::State<'_, RefineSessionManager>,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<ResumableRefineSession>, String> {
+    log::info!("[list_resumable_refine_sessions]");
+    let map = sessions.0.lock().map_err(|e| {
+        log::error!("[list_resumable_refine_sessions] Failed to acquire session lock: {}", e);
+        e.to_string()
+    })?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[list_resumable_refine_sessions] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    list_resumable_inner(&conn, &map)
+}
+
+fn resume_inner(
+    conn: &rusqlite::Connection,
+    map: &mut HashMap<String, RefineSession>,
+    session_id: &str,
+    skills_path: &str,
+) -> Result<ResumedRefineSession, String> {
+    let record = db::get_refine_session(conn, session_id)?
+        .ok_or_else(|| "Refine session not found. It may have been closed.".to_string())?;
+    if map.contains_key(session_id) {
+        return Err("This refine session is already open".to_string());
+    }
+    if map.values().any(|s| s.skill_name == record.skill_name) {
+        return Err(format!("A refine session already exists for skill '{}'", record.skill_name));
+    }
+    let skill_md = Path::new(skills_path).join(&record.skill_name).join("SKILL.md");
+    if !skill_md.exists() {
+        return Err(format!("SKILL.md not found at {}", skill_md.display()));
+    }
+
+    let mut pending_diff = get_refine_diff_inner(&record.skill_name, skills_path)?;
+    mark_reviewed(&mut pending_diff, &record.file_reviews);
+    map.insert(
+        session_id.to_string(),
+        RefineSession {
+            skill_name: record.skill_name.clone(),
+            stream_started: false,
+            resume_sdk_session_id: record.sdk_session_id.clone(),
+            history: record.messages.clone(),
+        },
+    );
+    Ok(ResumedRefineSession {
+        session: RefineSessionInfo {
+            session_id: record.session_id,
+            skill_name: record.skill_name,
+            created_at: record.created_at,
+        },
+        can_resume_conversation: record.sdk_session_id.is_some(),
+        messages: record.messages,
+        pending_diff,
+    })
+}
+
+/// Reopen a persisted refine session with its chat log and the skill's
+/// current uncommitted changes. The next message continues the SDK
+/// conversation when its session id was recorded, otherwise it starts a new
+/// one with a recap of the earlier requests.
+#[tauri::command]
+pub fn resume_refine_session(
+    session_id: String,
+    workspace_path: String,
+    sessions: tauri::State<'_, RefineSessionManager>,
+    db: tauri::State<'_, Db>,
+) -> Result<ResumedRefineSession, String> {
+    log::info!("[resume_refine_session] session=[REDACTED]");
+    let skills_path = resolve_skills_path(&db, &workspace_path).map_err(|e| {
+        log::error!("[resume_refine_session] Failed to resolve skills path: {}", e);
+        e
+    })?;
+    let mut map = sessions.0.lock().map_err(|e| {
+        log::error!("[resume_refine_session] Failed to acquire session lock: {}", e);
+        e.to_string()
+    })?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[resume_refine_session] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let resumed = resume_inner(&conn, &mut map, &session_id, &skills_path).map_err(|e| {
+        log::error!("[resume_refine_session] {}", e);
+        e
+    })?;
+    log::info!(
+        "[resume_refine_session] skill={} messages={} conversation_restored={}",
+        resumed.session.skill_name,
+        resumed.messages.len(),
+        resumed.can_resume_conversation
+    );
+    Ok(resumed)
+}
+
+// ─── per-file review ──────────────────────────────────────────────────────────
+
+/// Accept (keep) or reject (put back to HEAD) one file of a session's pending
+/// changes and record the decision. Returns the skill's remaining diff.
+fn review_file_inner(
+    conn: &rusqlite::Connection,
+    session_id: &str,
+    skill_name: &str,
+    skills_path: &str,
+    path: &str,
+    accept: bool,
+) -> Result<RefineDiff, String> {
+    let diff = get_refine_diff_inner(skill_name, skills_path)?;
+    let file = diff
+        .files
+        .iter()
+        .find(|f| f.path == path)
+        .ok_or_else(|| format!("'{}' has no pending changes in '{}'", path, skill_name))?;
+    if !accept {
+        crate::git::discard_file_changes(Path::new(skills_path), path)?;
+    }
+    let review = RefineFileReview {
+        path: path.to_string(),
+        decision: if accept { "accepted" } else { "rejected" }.to_string(),
+        diff: file.diff.clone(),
+        reviewed_at: chrono::Utc::now().to_rfc3339(),
+    };
+    db::record_refine_file_review(conn, session_id, &review)?;
+
+    let mut diff = get_refine_diff_inner(skill_name, skills_path)?;
+    db::update_refine_session_state(conn, session_id, None, Some(&diff))?;
+    let reviews = db::get_refine_session(conn, session_id)?
+        .map(|r| r.file_reviews)
+        .unwrap_or_default();
+    mark_reviewed(&mut diff, &reviews);
+    Ok(diff)
+}
+
+/// Accept or reject one changed file of an open refine session. `path` is as
+/// returned by `get_refine_diff`. Rejecting puts the file back to its last
+/// committed version; accepting keeps it and marks it reviewed until it
+/// changes again.
+#[tauri::command]
+pub fn review_refine_file(
+    session_id: String,
+    workspace_path: String,
+    path: String,
+    accept: bool,
+    sessions: tauri::State<'_, RefineSessionManager>,
+    db: tauri::State<'_, Db>,
+) -> Result<RefineDiff, String> {
+    log::info!("[review_refine_file] session=[REDACTED] path={} accept={}", path, accept);
+    let skill_name = sessions
+        .0
+        .lock()
+        .map_err(|e| {
+            log::error!("[review_refine_file] Failed to acquire session lock: {}", e);
+            e.to_string()
+        })?
+        .get(&session_id)
+        .map(|s| s.skill_name.clone())
+        .ok_or_else(|| {
+            log::error!("[review_refine_file] No refine session found");
+            "No refine session found".to_string()
+        })?;
+    super::observer::ensure_writable(&skill_name)?;
+    let skills_path = resolve_skills_path(&db, &workspace_path)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[review_refine_file] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    review_file_inner(&conn, &session_id, &skill_name, &skills_path, &path, accept).map_err(|e| {
+        log::error!("[review_refine_file] {}", e);
+        e
+    })
+}
+
 fn materialize_refine_validation_output_value(
     skill_root: &Path,
     structured_output: &serde_json::Value,
@@ -849,10 +1250,418 @@ pub fn materialize_refine_validation_output(
         "[materialize_refine_validation_output] skill={}",
         skill_name
     );
+    super::observer::ensure_writable(&skill_name)?;
     let skill_root = Path::new(&workspace_path).join(&skill_name);
     materialize_refine_validation_output_value(&skill_root, &structured_output)
 }
 
+// ─── Batch refine ────────────────────────────────────────────────────────────
+//
+// One instruction run against several skills, one skill at a time. Each run
+// leaves its edits uncommitted in the skills repo and the skill's diff is
+// queued for review: approving commits just that skill, rejecting puts it back
+// to HEAD. All runs record usage under one workflow session so the batch's
+// cost shows as a whole.
+
+pub const REFINE_BATCH_EVENT: &str = "refine-batch-progress";
+
+/// Turn limit for one skill's unattended run.
+const REFINE_BATCH_MAX_TURNS: u32 = 60;
+
+/// Prompt for one skill of a batch: the first-message refine prompt, told
+/// there is nobody to answer questions.
+fn build_batch_refine_prompt(skill_name: &str, workspace_path: &str, skills_path: &str, instruction: &str) -> String {
+    let prompt = build_refine_prompt(skill_name, workspace_path, skills_path, instruction, None, None);
+    match prompt.rfind("\n\nCurrent request: ") {
+        Some(at) => format!(
+            "{}\n\nThis request is being applied to several skills in an unattended batch. \
+             Make the edits without asking questions. If the request does not apply to this skill, \
+             change nothing.{}",
+            &prompt[..at],
+            &prompt[at..]
+        ),
+        None => prompt,
+    }
+}
+
+/// Check a batch request and return its de-duplicated skills. Every skill must
+/// exist, have no uncommitted changes (so a reject can't discard other work),
+/// and not be open in a refine session or another batch.
+fn validate_batch_request(
+    conn: &rusqlite::Connection,
+    skills_path: &str,
+    instruction: &str,
+    skill_names: &[String],
+    open_sessions: &[String],
+) -> Result<Vec<String>, String> {
+    if instruction.trim().is_empty() {
+        return Err("Batch refine needs an instruction".to_string());
+    }
+    let mut names: Vec<String> = Vec::new();
+    for name in skill_names {
+        validate_skill_name(name)?;
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+    if names.is_empty() {
+        return Err("Select at least one skill to refine".to_string());
+    }
+    let in_batches = db::open_refine_batch_skills(conn)?;
+    for name in &names {
+        if !Path::new(skills_path).join(name).join("SKILL.md").exists() {
+            return Err(format!("SKILL.md not found for '{}'", name));
+        }
+        if open_sessions.contains(name) {
+            return Err(format!("'{}' has an open refine session", name));
+        }
+        if in_batches.contains(name) {
+            return Err(format!("'{}' is already in a batch refine awaiting review", name));
+        }
+        if !get_refine_diff_inner(name, skills_path)?.files.is_empty() {
+            return Err(format!(
+                "'{}' has uncommitted changes; save or discard them before a batch refine",
+                name
+            ));
+        }
+    }
+    Ok(names)
+}
+
+fn emit_batch(app: &tauri::AppHandle, batch_id: &str) {
+    use tauri::{Emitter, Manager};
+    let db = app.state::<Db>();
+    let batch = match db.0.read() {
+        Ok(conn) => db::get_refine_batch(&conn, batch_id),
+        Err(e) => Err(e.to_string()),
+    };
+    match batch {
+        Ok(Some(batch)) => {
+            if let Err(e) = app.emit(REFINE_BATCH_EVENT, &batch) {
+                log::warn!("Failed to emit {}: {}", REFINE_BATCH_EVENT, e);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => log::warn!("[refine_batch] Failed to read batch {}: {}", batch_id, e),
+    }
+}
+
+/// Write the agent's context files for one skill of a batch and build its
+/// config. Returns the config, agent id and skills path.
+fn prepare_batch_item(
+    conn: &rusqlite::Connection,
+    batch: &RefineBatch,
+    skill_name: &str,
+) -> Result<(SidecarConfig, String, String), String> {
+    let settings = db::read_settings_hydrated(conn)?;
+    let api_key = crate::commands::offline::agent_api_key(&settings)
+        .ok_or_else(|| "Anthropic API key not configured".to_string())?;
+    crate::commands::usage::check_budget_allows_run(conn, skill_name)?;
+    let skills_path = settings
+        .skills_path
+        .clone()
+        .unwrap_or_else(|| batch.workspace_path.clone());
+    if !get_refine_diff_inner(skill_name, &skills_path)?.files.is_empty() {
+        return Err("The skill has uncommitted changes".to_string());
+    }
+    write_refine_user_context(conn, &settings, &batch.workspace_path, skill_name);
+    prepare_refine_workspace_dir(&batch.workspace_path, &skills_path, skill_name);
+
+    let model = resolve_model_id(settings.preferred_model.as_deref().unwrap_or("sonnet"));
+    let prompt = build_batch_refine_prompt(skill_name, &batch.workspace_path, &skills_path, &batch.instruction);
+    let (mut config, agent_id) = build_refine_config(
+        prompt,
+        skill_name,
+        &batch.workspace_path,
+        api_key,
+        model.clone(),
+        settings.extended_thinking,
+        settings.interleaved_thinking_beta,
+        settings.sdk_effort.clone(),
+        Some(model),
+        false,
+    );
+    config.max_turns = Some(REFINE_BATCH_MAX_TURNS);
+    Ok((config, agent_id, skills_path))
+}
+
+/// Run the refine agent for one skill of a batch and return the skill's diff afterwards.
+async fn run_batch_agent(
+    app: &tauri::AppHandle,
+    batch: &RefineBatch,
+    skill_name: &str,
+    task: crate::agents::sidecar_pool::ConcurrentTask,
+    skills_path: &str,
+) -> Result<RefineDiff, String> {
+    use tauri::Manager;
+    let pool = app.state::<SidecarPool>();
+    let outcome = sidecar::run_concurrent(
+        vec![task],
+        pool.inner(),
+        app,
+        skill_name,
+        crate::agents::cost_ticker::REFINE_STEP_ID,
+        &batch.workflow_session_id,
+    )
+    .await?
+    .pop()
+    .ok_or_else(|| "The refine agent did not run".to_string())?;
+    if let Some(e) = outcome.error {
+        return Err(e);
+    }
+    get_refine_diff_inner(skill_name, skills_path)
+}
+
+/// Refine one skill of a batch, marking it running while its agent works.
+/// Returns the agent id, when one was started, and the outcome.
+async fn run_batch_item(
+    app: &tauri::AppHandle,
+    batch: &RefineBatch,
+    skill_name: &str,
+) -> (Option<String>, Result<RefineDiff, String>) {
+    use tauri::Manager;
+    let db = app.state::<Db>();
+    let prepared = db.0.lock().map_err(|e| e.to_string()).and_then(|conn| {
+        let prepared = prepare_batch_item(&conn, batch, skill_name)?;
+        db::update_refine_batch_item(&conn, &batch.batch_id, skill_name, "running", Some(&prepared.1), None, None)?;
+        Ok(prepared)
+    });
+    let (config, agent_id, skills_path) = match prepared {
+        Ok(prepared) => prepared,
+        Err(e) => return (None, Err(e)),
+    };
+    emit_batch(app, &batch.batch_id);
+
+    let task = crate::agents::sidecar_pool::ConcurrentTask {
+        agent_id: agent_id.clone(),
+        config,
+    };
+    let result = run_batch_agent(app, batch, skill_name, task, &skills_path).await;
+    (Some(agent_id), result)
+}
+
+/// Work through a batch's queued skills in order, then end its usage session.
+async fn run_refine_batch(app: tauri::AppHandle, batch_id: String) {
+    use tauri::Manager;
+    loop {
+        let next = {
+            let db = app.state::<Db>();
+            let conn = match db.0.lock() {
+                Ok(conn) => conn,
+                Err(e) => {
+                    log::error!("[refine_batch] Failed to acquire DB lock: {}", e);
+                    return;
+                }
+            };
+            match db::get_refine_batch(&conn, &batch_id) {
+                Ok(Some(batch)) => db::next_queued_refine_batch_item(&conn, &batch_id).map(|next| Some((batch, next))),
+                Ok(None) => Ok(None),
+                Err(e) => Err(e),
+            }
+        };
+        let (batch, skill_name) = match next {
+            Ok(Some((batch, Some(skill_name)))) => (batch, skill_name),
+            Ok(Some((batch, None))) => {
+                log::info!("[refine_batch] batch {} finished", batch_id);
+                let db = app.state::<Db>();
+                if let Ok(conn) = db.0.lock() {
+                    if let Err(e) = db::end_workflow_session(&conn, &batch.workflow_session_id) {
+                        log::warn!("[refine_batch] Failed to end session of batch {}: {}", batch_id, e);
+                    }
+                }
+                break;
+            }
+            Ok(None) => break,
+            Err(e) => {
+                log::error!("[refine_batch] Failed to read batch {}: {}", batch_id, e);
+                break;
+            }
+        };
+
+        log::info!("[refine_batch] batch {} refining '{}'", batch_id, skill_name);
+        let (agent_id, result) = run_batch_item(&app, &batch, &skill_name).await;
+        let (status, diff, error) = match result {
+            Ok(diff) if diff.files.is_empty() => ("no_changes", None, None),
+            Ok(diff) => ("review", Some(diff), None),
+            Err(e) => {
+                log::warn!("[refine_batch] batch {} '{}' failed: {}", batch_id, skill_name, e);
+                ("failed", None, Some(e))
+            }
+        };
+        let db = app.state::<Db>();
+        let updated = db.0.lock().map_err(|e| e.to_string()).and_then(|conn| {
+            db::update_refine_batch_item(
+                &conn,
+                &batch_id,
+                &skill_name,
+                status,
+                agent_id.as_deref(),
+                diff.as_ref(),
+                error.as_deref(),
+            )
+        });
+        if let Err(e) = updated {
+            // Leaving the item queued would rerun it forever.
+            log::error!("[refine_batch] Failed to record '{}' in batch {}: {}", skill_name, batch_id, e);
+            break;
+        }
+        emit_batch(&app, &batch_id);
+    }
+}
+
+/// Requeue skills interrupted by a crash and resume their batches. Safe to
+/// call from `setup()`.
+pub fn resume_refine_batches(app: tauri::AppHandle) {
+    use tauri::Manager;
+    let db = app.state::<Db>();
+    let batch_ids = db
+        .0
+        .lock()
+        .map_err(|e| e.to_string())
+        .and_then(|conn| db::requeue_interrupted_refine_batches(&conn));
+    match batch_ids {
+        Ok(batch_ids) => {
+            for batch_id in batch_ids {
+                log::info!("[refine_batch] resuming batch {}", batch_id);
+                tauri::async_runtime::spawn(run_refine_batch(app.clone(), batch_id));
+            }
+        }
+        Err(e) => log::warn!("[refine_batch] Failed to resume interrupted batches: {}", e),
+    }
+}
+
+/// Approve (commit) or reject (put back to HEAD) one reviewed skill of a
+/// batch. A queued skill can be rejected to skip it.
+fn resolve_batch_item_inner(
+    conn: &rusqlite::Connection,
+    skills_path: &Path,
+    batch: &RefineBatch,
+    skill_name: &str,
+    approve: bool,
+) -> Result<(), String> {
+    let item = batch
+        .items
+        .iter()
+        .find(|i| i.skill_name == skill_name)
+        .ok_or_else(|| format!("'{}' is not part of this batch refine", skill_name))?;
+    match (item.status.as_str(), approve) {
+        ("review", true) => {
+            let instruction = batch.instruction.lines().next().unwrap_or_default();
+            let summary: String = instruction.chars().take(72).collect();
+            let message = format!("{}: batch refine — {}", skill_name, summary);
+            crate::git::commit_skill(skills_path, skill_name, &message)?;
+            db::update_refine_batch_item(conn, &batch.batch_id, skill_name, "approved", None, None, None)
+        }
+        ("review", false) => {
+            crate::git::discard_skill_changes(skills_path, skill_name)?;
+            db::update_refine_batch_item(conn, &batch.batch_id, skill_name, "rejected", None, None, None)
+        }
+        ("queued", false) => {
+            db::update_refine_batch_item(conn, &batch.batch_id, skill_name, "rejected", None, None, None)
+        }
+        (status, _) => {
+            let action = if approve { "approved" } else { "rejected" };
+            Err(format!("'{}' cannot be {} while {}", skill_name, action, status))
+        }
+    }
+}
+
+/// Start refining `skill_names` one after another with the same instruction.
+/// Progress is emitted as `refine-batch-progress` with the whole batch.
+#[tauri::command]
+pub fn start_refine_batch(
+    instruction: String,
+    skill_names: Vec<String>,
+    workspace_path: String,
+    sessions: tauri::State<'_, RefineSessionManager>,
+    db: tauri::State<'_, Db>,
+    app: tauri::AppHandle,
+) -> Result<RefineBatch, String> {
+    log::info!("[start_refine_batch] skills={:?}", skill_names);
+    for name in &skill_names {
+        super::observer::ensure_writable(name)?;
+    }
+    let open_sessions: Vec<String> = sessions
+        .0
+        .lock()
+        .map_err(|e| {
+            log::error!("[start_refine_batch] Failed to acquire session lock: {}", e);
+            e.to_string()
+        })?
+        .values()
+        .map(|s| s.skill_name.clone())
+        .collect();
+    let skills_path = resolve_skills_path(&db, &workspace_path)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[start_refine_batch] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let names = validate_batch_request(&conn, &skills_path, &instruction, &skill_names, &open_sessions).map_err(|e| {
+        log::error!("[start_refine_batch] {}", e);
+        e
+    })?;
+
+    let batch_id = uuid::Uuid::new_v4().to_string();
+    let workflow_session_id = uuid::Uuid::new_v4().to_string();
+    db::insert_refine_batch(&conn, &batch_id, instruction.trim(), &workspace_path, &workflow_session_id, &names)?;
+    db::create_workflow_session(&conn, &workflow_session_id, &names[0], std::process::id())?;
+    let batch = db::get_refine_batch(&conn, &batch_id)?.ok_or("Batch refine was not saved")?;
+    drop(conn);
+
+    tauri::async_runtime::spawn(run_refine_batch(app, batch_id));
+    Ok(batch)
+}
+
+#[tauri::command]
+pub fn get_refine_batch(batch_id: String, db: tauri::State<'_, Db>) -> Result<RefineBatch, String> {
+    log::info!("[get_refine_batch] batch={}", batch_id);
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[get_refine_batch] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    db::get_refine_batch(&conn, &batch_id)?.ok_or_else(|| format!("Batch refine {} not found", batch_id))
+}
+
+/// Recent batches, newest first.
+#[tauri::command]
+pub fn list_refine_batches(limit: Option<u32>, db: tauri::State<'_, Db>) -> Result<Vec<RefineBatch>, String> {
+    log::info!("[list_refine_batches] limit={:?}", limit);
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[list_refine_batches] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    db::list_refine_batches(&conn, limit.unwrap_or(20))
+}
+
+/// Approve or reject one skill's changes from a batch refine.
+#[tauri::command]
+pub fn resolve_refine_batch_item(
+    batch_id: String,
+    skill_name: String,
+    approve: bool,
+    db: tauri::State<'_, Db>,
+    app: tauri::AppHandle,
+) -> Result<RefineBatch, String> {
+    log::info!("[resolve_refine_batch_item] batch={} skill={} approve={}", batch_id, skill_name, approve);
+    super::observer::ensure_writable(&skill_name)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[resolve_refine_batch_item] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let batch = db::get_refine_batch(&conn, &batch_id)?.ok_or_else(|| format!("Batch refine {} not found", batch_id))?;
+    let skills_path = db::read_settings(&conn)?
+        .skills_path
+        .unwrap_or_else(|| batch.workspace_path.clone());
+    resolve_batch_item_inner(&conn, Path::new(&skills_path), &batch, &skill_name, approve).map_err(|e| {
+        log::error!("[resolve_refine_batch_item] {}", e);
+        e
+    })?;
+    let batch = db::get_refine_batch(&conn, &batch_id)?.ok_or_else(|| format!("Batch refine {} not found", batch_id))?;
+    drop(conn);
+    emit_batch(&app, &batch_id);
+    Ok(batch)
+}
+
 // ─── Tests ───────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -1031,12 +1840,10 @@ mod tests {
         .unwrap();
 
         let result = get_refine_diff_inner("my-skill", dir.path().to_str().unwrap()).unwrap();
-        // The new file should appear (untracked files may not show in index-to-workdir diff
-        // unless added to index first, so this tests the staged path too)
-        // Note: git2's diff_index_to_workdir may not show untracked files by default.
-        // This is acceptable — the agent's changes are typically staged by the sidecar.
-        // We just verify no error occurs.
-        assert!(result.stat != "no git repository");
+        assert_eq!(result.files.len(), 1);
+        assert_eq!(result.files[0].path, "my-skill/references/new-ref.md");
+        assert_eq!(result.files[0].status, "added");
+        assert!(result.files[0].diff.contains("+New reference"));
     }
 
     #[test]
@@ -1060,6 +1867,8 @@ mod tests {
                 RefineSession {
                     skill_name: "my-skill".to_string(),
                     stream_started: false,
+                    resume_sdk_session_id: None,
+                    history: Vec::new(),
                 },
             );
         }
@@ -1080,6 +1889,8 @@ mod tests {
                 RefineSession {
                     skill_name: "my-skill".to_string(),
                     stream_started: false,
+                    resume_sdk_session_id: None,
+                    history: Vec::new(),
                 },
             );
         }
@@ -1118,6 +1929,147 @@ mod tests {
         )
     }
 
+    // ===== persisted session tests =====
+
+    fn user_message(text: &str, agent_id: &str) -> RefineSessionMessage {
+        RefineSessionMessage {
+            role: "user".to_string(),
+            text: Some(text.to_string()),
+            target_files: Vec::new(),
+            command: None,
+            agent_id: Some(agent_id.to_string()),
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_record_refine_result_appends_reply_and_sdk_session() {
+        let conn = crate::commands::test_utils::create_test_db();
+        db::insert_refine_session(&conn, "sess-1", "my-skill").unwrap();
+        db::append_refine_session_message(&conn, "sess-1", &user_message("tighten the intro", "refine-my-skill-1"))
+            .unwrap();
+
+        let result = serde_json::json!({
+            "type": "result",
+            "result": "Shortened the overview section.",
+            "session_id": "sdk-abc",
+        });
+        record_refine_result(&conn, "refine-my-skill-1", &result).unwrap();
+        // Unknown agents are ignored
+        record_refine_result(&conn, "refine-other-2", &result).unwrap();
+
+        let record = db::get_refine_session(&conn, "sess-1").unwrap().unwrap();
+        assert_eq!(record.sdk_session_id.as_deref(), Some("sdk-abc"));
+        assert_eq!(record.messages.len(), 2);
+        assert_eq!(record.messages[1].role, "agent");
+        assert_eq!(record.messages[1].text.as_deref(), Some("Shortened the overview section."));
+    }
+
+    #[test]
+    fn test_list_resumable_skips_open_and_empty_sessions() {
+        let conn = crate::commands::test_utils::create_test_db();
+        db::insert_refine_session(&conn, "open", "skill-a").unwrap();
+        db::insert_refine_session(&conn, "empty", "skill-b").unwrap();
+        db::insert_refine_session(&conn, "crashed", "skill-c").unwrap();
+        db::append_refine_session_message(&conn, "open", &user_message("a", "refine-skill-a-1")).unwrap();
+        db::append_refine_session_message(&conn, "crashed", &user_message("c", "refine-skill-c-1")).unwrap();
+
+        let mut open = HashMap::new();
+        open.insert(
+            "open".to_string(),
+            RefineSession {
+                skill_name: "skill-a".to_string(),
+                stream_started: true,
+                resume_sdk_session_id: None,
+                history: Vec::new(),
+            },
+        );
+
+        let resumable = list_resumable_inner(&conn, &open).unwrap();
+        assert_eq!(resumable.len(), 1);
+        assert_eq!(resumable[0].session_id, "crashed");
+        assert_eq!(resumable[0].last_user_message.as_deref(), Some("c"));
+        assert!(!resumable[0].can_resume_conversation);
+    }
+
+    #[test]
+    fn test_resume_inner_restores_session_once() {
+        let conn = crate::commands::test_utils::create_test_db();
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("my-skill")).unwrap();
+        std::fs::write(dir.path().join("my-skill").join("SKILL.md"), "# Skill").unwrap();
+        db::insert_refine_session(&conn, "sess-1", "my-skill").unwrap();
+        db::append_refine_session_message(&conn, "sess-1", &user_message("add examples", "refine-my-skill-1"))
+            .unwrap();
+        db::update_refine_session_state(&conn, "sess-1", Some("sdk-abc"), None).unwrap();
+
+        let mut map = HashMap::new();
+        let skills_path = dir.path().to_str().unwrap();
+        let resumed = resume_inner(&conn, &mut map, "sess-1", skills_path).unwrap();
+        assert!(resumed.can_resume_conversation);
+        assert_eq!(resumed.messages.len(), 1);
+        assert_eq!(map["sess-1"].resume_sdk_session_id.as_deref(), Some("sdk-abc"));
+        assert!(!map["sess-1"].stream_started);
+
+        let err = resume_inner(&conn, &mut map, "sess-1", skills_path).unwrap_err();
+        assert!(err.contains("already open"));
+        assert!(resume_inner(&conn, &mut map, "missing", skills_path).is_err());
+    }
+
+    #[test]
+    fn test_review_file_accepts_and_rejects_each_file() {
+        let conn = crate::commands::test_utils::create_test_db();
+        let dir = tempdir().unwrap();
+        crate::git::ensure_repo(dir.path()).unwrap();
+        let skill_dir = dir.path().join("my-skill");
+        std::fs::create_dir_all(skill_dir.join("references")).unwrap();
+        std::fs::write(skill_dir.join("SKILL.md"), "# V1\n").unwrap();
+        std::fs::write(skill_dir.join("references").join("guide.md"), "guide v1\n").unwrap();
+        crate::git::commit_all(dir.path(), "v1").unwrap();
+        db::insert_refine_session(&conn, "sess-1", "my-skill").unwrap();
+
+        std::fs::write(skill_dir.join("SKILL.md"), "# V2\n").unwrap();
+        std::fs::write(skill_dir.join("references").join("guide.md"), "guide v2\n").unwrap();
+        let skills_path = dir.path().to_str().unwrap();
+
+        let diff = review_file_inner(&conn, "sess-1", "my-skill", skills_path, "my-skill/SKILL.md", true).unwrap();
+        assert_eq!(diff.files.len(), 2);
+        assert_eq!(diff.files[0].review.as_deref(), Some("accepted"));
+        assert_eq!(diff.files[1].review, None);
+
+        let diff =
+            review_file_inner(&conn, "sess-1", "my-skill", skills_path, "my-skill/references/guide.md", false)
+                .unwrap();
+        assert_eq!(diff.files.len(), 1);
+        assert_eq!(diff.files[0].path, "my-skill/SKILL.md");
+        assert_eq!(
+            std::fs::read_to_string(skill_dir.join("references").join("guide.md")).unwrap(),
+            "guide v1\n"
+        );
+        let record = db::get_refine_session(&conn, "sess-1").unwrap().unwrap();
+        assert_eq!(record.file_reviews.len(), 2);
+        assert_eq!(record.pending_diff.unwrap().files.len(), 1);
+
+        // A further edit to an accepted file needs review again
+        std::fs::write(skill_dir.join("SKILL.md"), "# V3\n").unwrap();
+        let mut diff = get_refine_diff_inner("my-skill", skills_path).unwrap();
+        mark_reviewed(&mut diff, &record.file_reviews);
+        assert_eq!(diff.files[0].review, None);
+
+        let err = review_file_inner(&conn, "sess-1", "my-skill", skills_path, "other-skill/SKILL.md", true)
+            .unwrap_err();
+        assert!(err.contains("no pending changes"));
+    }
+
+    #[test]
+    fn test_with_resume_recap_inserts_before_current_request() {
+        let history = vec![user_message("add examples", "refine-s-1")];
+        let prompt = with_resume_recap("Skill: s\n\nCurrent request: fix typos".to_string(), &history);
+        let recap_at = prompt.find("- add examples").unwrap();
+        assert!(recap_at < prompt.find("Current request: fix typos").unwrap());
+        assert_eq!(with_resume_recap("unchanged".to_string(), &[]), "unchanged");
+    }
+
     #[test]
     fn test_refine_config_always_uses_refine_skill_agent() {
         // agent_name must always be "refine-skill" — it handles /rewrite and /validate
@@ -1351,6 +2303,7 @@ mod tests {
         assert!(prompt.contains("The workspace directory is: /home/user/.vibedata/skill-builder/my-skill"));
         assert!(prompt.contains("Read user-context.md and .skill_output_dir"));
         assert!(prompt.contains("Derive context_dir as workspace_dir/context"));
+        assert!(prompt.contains("any file under references/"));
     }
 
     #[test]
@@ -1449,6 +2402,8 @@ mod tests {
                 RefineSession {
                     skill_name: "my-skill".to_string(),
                     stream_started: false,
+                    resume_sdk_session_id: None,
+                    history: Vec::new(),
                 },
             );
             assert_eq!(map.len(), 1);
@@ -1627,6 +2582,8 @@ mod tests {
                 RefineSession {
                     skill_name: "my-skill".to_string(),
                     stream_started: false,
+                    resume_sdk_session_id: None,
+                    history: Vec::new(),
                 },
             );
         }
@@ -1644,6 +2601,8 @@ mod tests {
                 RefineSession {
                     skill_name: "my-skill".to_string(),
                     stream_started: false,
+                    resume_sdk_session_id: None,
+                    history: Vec::new(),
                 },
             );
         }
@@ -1697,4 +2656,96 @@ mod tests {
         assert!(ctx.is_none());
     }
 
+    // ===== batch refine tests =====
+
+    fn skills_repo(skills: &[&str]) -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        crate::git::ensure_repo(dir.path()).unwrap();
+        for skill in skills {
+            std::fs::create_dir_all(dir.path().join(skill)).unwrap();
+            std::fs::write(dir.path().join(skill).join("SKILL.md"), "Covers FY24.\n").unwrap();
+        }
+        crate::git::commit_all(dir.path(), "initial").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_batch_refine_prompt_is_unattended() {
+        let prompt = build_batch_refine_prompt("my-skill", "/ws", "/skills", "FY24 becomes FY25");
+        let note = prompt.find("unattended batch").unwrap();
+        let request = prompt.find("Current request: FY24 becomes FY25").unwrap();
+        assert!(note < request);
+        assert!(prompt.ends_with("FY24 becomes FY25"));
+    }
+
+    #[test]
+    fn test_validate_batch_request() {
+        let dir = skills_repo(&["alpha", "beta", "gamma"]);
+        let skills_path = dir.path().to_str().unwrap();
+        let conn = crate::commands::test_utils::create_test_db();
+        let names = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let ok = validate_batch_request(&conn, skills_path, "FY25", &names(&["beta", "alpha", "beta"]), &[]).unwrap();
+        assert_eq!(ok, names(&["beta", "alpha"]));
+
+        assert!(validate_batch_request(&conn, skills_path, " ", &names(&["alpha"]), &[]).is_err());
+        assert!(validate_batch_request(&conn, skills_path, "FY25", &[], &[]).is_err());
+        assert!(validate_batch_request(&conn, skills_path, "FY25", &names(&["missing"]), &[])
+            .unwrap_err()
+            .contains("SKILL.md not found"));
+        assert!(validate_batch_request(&conn, skills_path, "FY25", &names(&["alpha"]), &names(&["alpha"]))
+            .unwrap_err()
+            .contains("open refine session"));
+
+        std::fs::write(dir.path().join("gamma").join("SKILL.md"), "Edited.\n").unwrap();
+        assert!(validate_batch_request(&conn, skills_path, "FY25", &names(&["alpha", "gamma"]), &[])
+            .unwrap_err()
+            .contains("uncommitted changes"));
+
+        db::insert_refine_batch(&conn, "b1", "FY25", "/ws", "ws-1", &names(&["alpha"])).unwrap();
+        assert!(validate_batch_request(&conn, skills_path, "FY26", &names(&["alpha"]), &[])
+            .unwrap_err()
+            .contains("already in a batch"));
+    }
+
+    #[test]
+    fn test_resolve_batch_items_commit_or_discard_one_skill() {
+        let dir = skills_repo(&["alpha", "beta", "gamma"]);
+        let skills_path = dir.path().to_str().unwrap();
+        let conn = crate::commands::test_utils::create_test_db();
+        let skills: Vec<String> = ["alpha", "beta", "gamma"].iter().map(|s| s.to_string()).collect();
+        db::insert_refine_batch(&conn, "b1", "Update FY24 to FY25\nin every skill", "/ws", "ws-1", &skills).unwrap();
+        for skill in ["alpha", "beta"] {
+            std::fs::write(dir.path().join(skill).join("SKILL.md"), "Covers FY25.\n").unwrap();
+            let diff = get_refine_diff_inner(skill, skills_path).unwrap();
+            db::update_refine_batch_item(&conn, "b1", skill, "review", Some("refine-x-1"), Some(&diff), None).unwrap();
+        }
+        let batch = db::get_refine_batch(&conn, "b1").unwrap().unwrap();
+        assert_eq!(batch.items[0].diff.as_ref().unwrap().files.len(), 1);
+
+        resolve_batch_item_inner(&conn, dir.path(), &batch, "alpha", true).unwrap();
+        // Only alpha is committed; beta's changes are still pending
+        assert_eq!(
+            crate::git::head_file_content(dir.path(), "alpha/SKILL.md").unwrap().as_deref(),
+            Some("Covers FY25.\n")
+        );
+        assert_eq!(get_refine_diff_inner("beta", skills_path).unwrap().files.len(), 1);
+        let (sha, _) = crate::git::last_committed_file(dir.path(), "alpha/SKILL.md").unwrap().unwrap();
+        assert_eq!(
+            crate::git::commit_message(dir.path(), &sha).unwrap(),
+            "alpha: batch refine — Update FY24 to FY25"
+        );
+
+        resolve_batch_item_inner(&conn, dir.path(), &batch, "beta", false).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.path().join("beta").join("SKILL.md")).unwrap(), "Covers FY24.\n");
+
+        assert!(resolve_batch_item_inner(&conn, dir.path(), &batch, "gamma", true).is_err());
+        resolve_batch_item_inner(&conn, dir.path(), &batch, "gamma", false).unwrap();
+
+        let batch = db::get_refine_batch(&conn, "b1").unwrap().unwrap();
+        let statuses: Vec<&str> = batch.items.iter().map(|i| i.status.as_str()).collect();
+        assert_eq!(statuses, vec!["approved", "rejected", "rejected"]);
+        assert!(batch.items[1].diff.is_some());
+        assert!(db::open_refine_batch_skills(&conn).unwrap().is_empty());
+    }
 }
@@ -0,0 +1,410 @@
+//! Repository context for skills about an internal codebase.
+//!
+//! `attach_repo_context` walks a local checkout, copies the files matching the
+//! given globs into the skill's `references/repo-context/files/`, and writes
+//! `references/repo-context/overview.md` with the directory structure and a
+//! summary from the auxiliary model. Research agents are pointed at the
+//! overview so they work from the actual system rather than the user's memory
+//! of it. Ignored paths are skipped using the repo's own .gitignore rules;
+//! outside a git checkout only hidden entries are skipped. Each capture
+//! replaces the previous one and is committed to the skills repo.
+
+use std::path::{Path, PathBuf};
+
+use crate::commands::local_model::{self, AuxiliaryRoute};
+use crate::db::Db;
+use crate::types::{RepoContextFile, RepoContextResult};
+
+/// Folder under the skill directory that holds the capture.
+pub(crate) const REPO_CONTEXT_DIR: &str = "references/repo-context";
+pub(crate) const OVERVIEW_FILE: &str = "references/repo-context/overview.md";
+
+/// Files larger than this are cut at the last line break before the limit.
+const MAX_FILE_BYTES: usize = 64 * 1024;
+/// Budget for all captured files together; later matches are skipped.
+const MAX_TOTAL_BYTES: usize = 512 * 1024;
+const MAX_FILES: usize = 200;
+/// Paths listed in the structure section and sent to the summarizer.
+const MAX_TREE_ENTRIES: usize = 2000;
+/// Lines of each captured file included in the summary prompt.
+const PROMPT_HEAD_LINES: usize = 40;
+/// Excerpts stop once the summary prompt reaches this size.
+const PROMPT_MAX_CHARS: usize = 60_000;
+const SUMMARY_MAX_TOKENS: u32 = 1500;
+
+/// Match a `/`-separated relative path against a glob. `*` and `?` stay
+/// within one path segment and `**` spans any number of segments. A pattern
+/// without `/` matches the file name at any depth, as in .gitignore.
+pub(crate) fn glob_match(pattern: &str, path: &str) -> bool {
+    let parts: Vec<&str> = path.split('/').collect();
+    let pattern = pattern.trim().trim_start_matches("./");
+    if !pattern.contains('/') {
+        return parts.last().is_some_and(|name| segment_match(pattern, name));
+    }
+    let pattern: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    match_segments(&pattern, &parts)
+}
+
+fn match_segments(pattern: &[&str], parts: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => parts.is_empty(),
+        Some((&"**", rest)) => (0..=parts.len()).any(|i| match_segments(rest, &parts[i..])),
+        Some((p, rest)) => parts
+            .split_first()
+            .is_some_and(|(s, tail)| segment_match(p, s) && match_segments(rest, tail)),
+    }
+}
+
+fn segment_match(pattern: &str, segment: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let s: Vec<char> = segment.chars().collect();
+    let (mut pi, mut si) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while si < s.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == s[si]) {
+            pi += 1;
+            si += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, si));
+            pi += 1;
+        } else if let Some((star_pi, star_si)) = star {
+            pi = star_pi + 1;
+            si = star_si + 1;
+            star = Some((star_pi, star_si + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+/// Git checkout containing `root`, plus `root`'s path inside its work tree.
+fn open_checkout(root: &Path) -> Option<(git2::Repository, PathBuf)> {
+    let repo = git2::Repository::discover(root).ok()?;
+    let workdir = repo.workdir()?.canonicalize().ok()?;
+    let prefix = root.canonicalize().ok()?.strip_prefix(&workdir).ok()?.to_path_buf();
+    Some((repo, prefix))
+}
+
+/// Non-ignored files under `root`, as sorted `/`-separated relative paths.
+/// Stops after `MAX_TREE_ENTRIES`; the flag reports whether it did.
+fn walk_repo(root: &Path) -> Result<(Vec<String>, bool), String> {
+    let checkout = open_checkout(root);
+    if checkout.is_none() {
+        log::debug!("[attach_repo_context] {} is not in a git checkout; skipping hidden entries only", root.display());
+    }
+    let ignored = |rel: &str| -> bool {
+        match &checkout {
+            Some((repo, prefix)) => repo.is_path_ignored(prefix.join(rel)).unwrap_or(false),
+            None => rel.rsplit('/').next().is_some_and(|name| name.starts_with('.')),
+        }
+    };
+
+    let mut files = Vec::new();
+    let mut stack = vec![String::new()];
+    while let Some(dir) = stack.pop() {
+        let mut entries: Vec<_> = std::fs::read_dir(root.join(&dir))
+            .map_err(|e| format!("Failed to read {}: {}", root.join(&dir).display(), e))?
+            .flatten()
+            .collect();
+        entries.sort_by_key(|e| e.file_name());
+        let mut subdirs = Vec::new();
+        for entry in entries {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name == ".git" {
+                continue;
+            }
+            let rel = if dir.is_empty() { name } else { format!("{}/{}", dir, name) };
+            let Ok(file_type) = entry.file_type() else { continue };
+            if file_type.is_symlink() || ignored(&rel) {
+                continue;
+            }
+            if file_type.is_dir() {
+                subdirs.push(rel);
+            } else if file_type.is_file() {
+                if files.len() >= MAX_TREE_ENTRIES {
+                    return Ok((files, true));
+                }
+                files.push(rel);
+            }
+        }
+        // Reverse so the stack pops directories in name order.
+        stack.extend(subdirs.into_iter().rev());
+    }
+    files.sort();
+    Ok((files, false))
+}
+
+/// Text content of `bytes` cut to `MAX_FILE_BYTES`, or `None` for binary files.
+fn capture_text(bytes: &[u8]) -> Option<(String, bool)> {
+    if bytes.iter().take(8192).any(|b| *b == 0) {
+        return None;
+    }
+    let text = String::from_utf8_lossy(bytes);
+    if text.len() <= MAX_FILE_BYTES {
+        return Some((text.into_owned(), false));
+    }
+    let mut end = MAX_FILE_BYTES;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let cut = text[..end].rfind('\n').map(|i| i + 1).unwrap_or(end);
+    Some((text[..cut].to_string(), true))
+}
+
+fn summary_prompt(repo_name: &str, tree: &[String], captured: &[(RepoContextFile, String)]) -> String {
+    let mut budget = PROMPT_MAX_CHARS.saturating_sub(tree.iter().map(|p| p.len() + 1).sum());
+    let mut heads = Vec::new();
+    for (file, content) in captured {
+        let head: Vec<&str> = content.lines().take(PROMPT_HEAD_LINES).collect();
+        let excerpt = format!("--- {} ---\n{}", file.path, head.join("\n"));
+        if excerpt.len() > budget {
+            break;
+        }
+        budget -= excerpt.len();
+        heads.push(excerpt);
+    }
+    format!(
+        "You are documenting the codebase \"{repo_name}\" so that another agent can write an \
+         accurate skill about it. From the file list and file excerpts below, describe the \
+         system's structure in markdown: what it does, its main modules and where they live, \
+         entry points, key data models or schemas, configuration, and naming conventions. \
+         Only state what the files show; say so where something is unclear. Reply with the \
+         markdown only, starting at level-3 headings.\n\n\
+         Files:\n{tree}\n\nExcerpts:\n{heads}",
+        tree = tree.join("\n"),
+        heads = heads.join("\n\n"),
+    )
+}
+
+fn overview_markdown(
+    repo_name: &str,
+    globs: &[String],
+    summary: Result<&str, &str>,
+    tree: &[String],
+    tree_truncated: bool,
+    files: &[RepoContextFile],
+    skipped: &[String],
+) -> String {
+    let mut md = format!(
+        "# Repository context: {}\n\nCaptured {} from the `{}` repository with globs {}. \
+         Copies of the captured files are under `files/`, at their path in the repository.\n\n## Summary\n\n",
+        repo_name,
+        chrono::Utc::now().format("%Y-%m-%d"),
+        repo_name,
+        globs.iter().map(|g| format!("`{}`", g)).collect::<Vec<_>>().join(", "),
+    );
+    match summary {
+        Ok(text) => md.push_str(text.trim()),
+        Err(e) => md.push_str(&format!("_Summary unavailable: {}_", e)),
+    }
+    md.push_str("\n\n## Structure\n\n```text\n");
+    md.push_str(&tree.join("\n"));
+    if tree_truncated {
+        md.push_str(&format!("\n… (listing stopped at {} files)", MAX_TREE_ENTRIES));
+    }
+    md.push_str("\n```\n\n## Captured files\n\n");
+    for file in files {
+        let note = if file.truncated { ", truncated" } else { "" };
+        md.push_str(&format!("- `files/{}` ({} bytes{})\n", file.path, file.size_bytes, note));
+    }
+    if !skipped.is_empty() {
+        md.push_str("\nMatched but not captured (binary or over the size budget):\n\n");
+        for path in skipped {
+            md.push_str(&format!("- `{}`\n", path));
+        }
+    }
+    md
+}
+
+pub(crate) async fn attach_repo_context_inner(
+    route: &AuxiliaryRoute,
+    skill_dir: &Path,
+    repo_path: &Path,
+    globs: &[String],
+) -> Result<(RepoContextResult, Option<local_model::AuxiliaryCompletion>), String> {
+    if !repo_path.is_dir() {
+        return Err(format!("Repository folder not found: {}", repo_path.display()));
+    }
+    let repo_name = repo_path
+        .canonicalize()
+        .ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "repository".to_string());
+
+    let (tree, tree_truncated) = walk_repo(repo_path)?;
+    let mut captured: Vec<(RepoContextFile, String)> = Vec::new();
+    let mut skipped = Vec::new();
+    let mut total = 0usize;
+    for path in tree.iter().filter(|p| globs.iter().any(|g| glob_match(g, p))) {
+        let bytes = std::fs::read(repo_path.join(path)).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        match capture_text(&bytes) {
+            Some((content, truncated)) if captured.len() < MAX_FILES && total + content.len() <= MAX_TOTAL_BYTES => {
+                total += content.len();
+                let file = RepoContextFile { path: path.clone(), size_bytes: bytes.len() as u64, truncated };
+                captured.push((file, content));
+            }
+            _ => skipped.push(path.clone()),
+        }
+    }
+    if captured.is_empty() {
+        return Err(format!("No readable files in {} match {}", repo_name, globs.join(", ")));
+    }
+
+    let prompt = summary_prompt(&repo_name, &tree, &captured);
+    let completion = local_model::complete(route, &prompt, SUMMARY_MAX_TOKENS, "attach_repo_context").await;
+    let summary = match &completion {
+        Ok(c) => Ok(c.text.as_str()),
+        Err(e) => {
+            log::warn!("[attach_repo_context] Summary failed, writing structure only: {}", e);
+            Err(e.as_str())
+        }
+    };
+    let files: Vec<RepoContextFile> = captured.iter().map(|(f, _)| f.clone()).collect();
+    let overview = overview_markdown(&repo_name, globs, summary, &tree, tree_truncated, &files, &skipped);
+
+    let context_dir = skill_dir.join(REPO_CONTEXT_DIR);
+    if context_dir.exists() {
+        std::fs::remove_dir_all(&context_dir)
+            .map_err(|e| format!("Failed to clear {}: {}", context_dir.display(), e))?;
+    }
+    let mut reference_paths = vec![OVERVIEW_FILE.to_string()];
+    for (file, content) in &captured {
+        let dest = context_dir.join("files").join(&file.path);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        std::fs::write(&dest, content).map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+        reference_paths.push(format!("{}/files/{}", REPO_CONTEXT_DIR, file.path));
+    }
+    std::fs::write(skill_dir.join(OVERVIEW_FILE), overview)
+        .map_err(|e| format!("Failed to write {}: {}", OVERVIEW_FILE, e))?;
+
+    let result = RepoContextResult {
+        files,
+        skipped,
+        reference_paths,
+        summary_model: completion.as_ref().ok().map(|c| c.model.clone()),
+    };
+    Ok((result, completion.ok()))
+}
+
+/// Capture a local repository into the skill's references: the files matching
+/// `globs` (all files when empty), the directory structure, and a model-written
+/// summary of the system. Replaces any earlier capture.
+#[tauri::command]
+pub async fn attach_repo_context(
+    skill_name: String,
+    repo_path: String,
+    globs: Vec<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<RepoContextResult, String> {
+    log::info!("[attach_repo_context] skill={} repo={} globs={:?}", skill_name, repo_path, globs);
+    super::observer::ensure_writable(&skill_name)?;
+    super::imported_skills::validate_skill_name(&skill_name)?;
+    let mut globs: Vec<String> = globs.into_iter().map(|g| g.trim().to_string()).filter(|g| !g.is_empty()).collect();
+    if globs.is_empty() {
+        globs.push("**".to_string());
+    }
+    let (skill_dir, route) = {
+        let conn = db.0.lock().map_err(|e| {
+            log::error!("[attach_repo_context] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        let settings = crate::db::read_settings_hydrated(&conn)?;
+        let skills_path = settings
+            .skills_path
+            .clone()
+            .ok_or_else(|| "Skills path not configured. Set it in Settings.".to_string())?;
+        (Path::new(&skills_path).join(&skill_name), AuxiliaryRoute::from_settings(&settings))
+    };
+    if !skill_dir.join("SKILL.md").is_file() {
+        return Err(format!("Skill '{}' not found in the skills folder", skill_name));
+    }
+
+    let (result, completion) = attach_repo_context_inner(&route, &skill_dir, Path::new(&repo_path), &globs)
+        .await
+        .map_err(|e| {
+            log::error!("[attach_repo_context] {}", e);
+            e
+        })?;
+    if let Some(completion) = completion {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        if let Err(e) = local_model::record_usage(&conn, &skill_name, &completion) {
+            log::warn!("[attach_repo_context] Failed to record usage: {}", e);
+        }
+    }
+    if let Some(skills_root) = skill_dir.parent() {
+        let msg = format!("{}: capture repo context ({} files)", skill_name, result.files.len());
+        if let Err(e) = crate::git::commit_all(skills_root, &msg) {
+            log::warn!("Git auto-commit failed ({}): {}", msg, e);
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*.rs", "src/commands/mod.rs"));
+        assert!(glob_match("src/*.rs", "src/lib.rs"));
+        assert!(!glob_match("src/*.rs", "src/commands/mod.rs"));
+        assert!(glob_match("src/**/*.rs", "src/commands/mod.rs"));
+        assert!(glob_match("src/**/*.rs", "src/lib.rs"));
+        assert!(glob_match("**", "a/b/c.txt"));
+        assert!(glob_match("models/?ales_*.sql", "models/sales_orders.sql"));
+        assert!(!glob_match("models/*.sql", "models/orders.yml"));
+    }
+
+    #[tokio::test]
+    async fn test_attach_respects_gitignore_globs_and_size_limits() {
+        let tmp = tempdir().unwrap();
+        let repo_dir = tmp.path().join("billing-service");
+        std::fs::create_dir_all(repo_dir.join("src/api")).unwrap();
+        std::fs::create_dir_all(repo_dir.join("target")).unwrap();
+        git2::Repository::init(&repo_dir).unwrap();
+        std::fs::write(repo_dir.join(".gitignore"), "target/\n*.log\n").unwrap();
+        std::fs::write(repo_dir.join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(repo_dir.join("src/api/invoices.rs"), "pub struct Invoice;\n").unwrap();
+        std::fs::write(repo_dir.join("src/big.rs"), "// filler line\n".repeat(MAX_FILE_BYTES / 10)).unwrap();
+        std::fs::write(repo_dir.join("src/logo.rs"), [0u8, 1, 2]).unwrap();
+        std::fs::write(repo_dir.join("target/out.rs"), "generated\n").unwrap();
+        std::fs::write(repo_dir.join("debug.log"), "noise\n").unwrap();
+        std::fs::write(repo_dir.join("README.md"), "# Billing\n").unwrap();
+
+        let skill_dir = tmp.path().join("skills/billing");
+        std::fs::create_dir_all(skill_dir.join(REPO_CONTEXT_DIR)).unwrap();
+        std::fs::write(skill_dir.join(REPO_CONTEXT_DIR).join("stale.md"), "old").unwrap();
+
+        // No model configured: the summary fails and the capture still lands.
+        let route = AuxiliaryRoute::default();
+        let (result, completion) =
+            attach_repo_context_inner(&route, &skill_dir, &repo_dir, &["src/**/*.rs".to_string()]).await.unwrap();
+        assert!(completion.is_none());
+        assert_eq!(result.summary_model, None);
+
+        let paths: Vec<&str> = result.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/api/invoices.rs", "src/big.rs", "src/main.rs"]);
+        assert!(result.files[1].truncated);
+        assert_eq!(result.skipped, vec!["src/logo.rs"]);
+        assert!(skill_dir.join(REPO_CONTEXT_DIR).join("files/src/api/invoices.rs").is_file());
+        assert!(!skill_dir.join(REPO_CONTEXT_DIR).join("stale.md").exists());
+        let big = std::fs::read_to_string(skill_dir.join(REPO_CONTEXT_DIR).join("files/src/big.rs")).unwrap();
+        assert!(big.len() <= MAX_FILE_BYTES && big.ends_with('\n'));
+
+        let overview = std::fs::read_to_string(skill_dir.join(OVERVIEW_FILE)).unwrap();
+        assert!(overview.contains("# Repository context: billing-service"));
+        assert!(overview.contains("_Summary unavailable:"));
+        assert!(overview.contains("README.md"));
+        assert!(!overview.contains("target/out.rs"));
+        assert!(!overview.contains("debug.log"));
+        assert!(!overview.contains(tmp.path().to_str().unwrap()));
+
+        let err = attach_repo_context_inner(&route, &skill_dir, &repo_dir, &["*.py".to_string()]).await.unwrap_err();
+        assert!(err.contains("No readable files"), "{}", err);
+    }
+}
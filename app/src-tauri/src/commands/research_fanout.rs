@@ -0,0 +1,460 @@
+//! Step 0 dimension research as concurrent sidecar runs.
+//!
+//! The research skill scores the candidate dimensions, selects a few and then
+//! researches each one in a Task sub-agent, all inside the orchestrator's SDK
+//! session, so the dimensions are researched one after another. Before the
+//! orchestrator starts, the backend now runs the scoring pass on its own and
+//! researches every selected dimension as an independent request through
+//! `sidecar::run_concurrent`, under the step's workflow session. The plan and
+//! the raw research land in `context/research/`, where the research skill
+//! picks them up and goes straight to consolidation.
+//!
+//! Anything that goes wrong here is logged and skipped: without a plan the
+//! skill scores and researches the dimensions itself as before, and a
+//! dimension whose run failed is researched by a Task sub-agent.
+
+use std::path::Path;
+
+use crate::agents::sidecar::{self, SidecarConfig};
+use crate::agents::sidecar_pool::{ConcurrentTask, SidecarPool};
+
+/// Precomputed research, relative to the skill's workspace directory.
+pub(crate) const RESEARCH_DIR: &str = "context/research";
+const PLAN_FILE: &str = "plan.json";
+/// The research skill as installed by the plugin, relative to the workspace root.
+const RESEARCH_SKILL_DIR: &str = ".claude/plugins/skill-content-researcher/skills/research";
+/// The research skill selects 3-5 dimensions; a plan naming more is trimmed.
+const MAX_SELECTED_DIMENSIONS: usize = 5;
+const PLANNING_MAX_TURNS: u32 = 20;
+const DIMENSION_MAX_TURNS: u32 = 15;
+const PLANNING_TOOLS: &[&str] = &["Read"];
+const DIMENSION_TOOLS: &[&str] = &["Read", "WebSearch", "WebFetch"];
+
+fn planning_prompt(workspace_dir: &str, skill_dir: &str) -> String {
+    format!(
+        "Read {workspace_dir}/user-context.md, then the research skill at {skill_dir}/SKILL.md. \
+         Follow only its Step 1 (select the dimension set), Step 2 (score dimensions) and \
+         Step 3 (select dimensions for research), reading the reference files they name from {skill_dir}/references. \
+         Do not research any dimension and do not spawn sub-agents. \
+         Return only the resulting `metadata.research_plan` object as defined in {skill_dir}/references/schemas.md."
+    )
+}
+
+fn dimension_prompt(spec: &str, user_context: &str, focus: &str) -> String {
+    format!(
+        "Research one dimension of a skill being built, following the dimension spec below.\n\n\
+         ## Dimension spec\n\n{spec}\n\n\
+         ## User context\n\n{user_context}\n\n\
+         ## Focus\n\n{focus}\n\n\
+         Return raw research text only (500-800 words), with no preamble and no JSON."
+    )
+}
+
+fn plan_output_format() -> serde_json::Value {
+    serde_json::json!({
+        "type": "json_schema",
+        "schema": {
+            "type": "object",
+            "required": [
+                "topic_relevance",
+                "dimensions_evaluated",
+                "dimensions_selected",
+                "dimension_scores",
+                "selected_dimensions"
+            ],
+            "properties": {
+                "topic_relevance": { "type": "string", "enum": ["relevant", "not_relevant"] },
+                "dimensions_evaluated": { "type": "integer", "minimum": 0 },
+                "dimensions_selected": { "type": "integer", "minimum": 0 },
+                "dimension_scores": { "type": "array" },
+                "selected_dimensions": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["name", "focus"],
+                        "properties": {
+                            "name": { "type": "string" },
+                            "focus": { "type": "string" }
+                        }
+                    }
+                }
+            },
+            "additionalProperties": true
+        }
+    })
+}
+
+/// The `result` message of a finished run.
+fn result_message(messages: &[serde_json::Value]) -> Option<&serde_json::Value> {
+    messages.iter().rev().find(|m| m["type"] == "result")
+}
+
+/// The research plan a planning run returned: its structured output, or its
+/// result text when that parses as a JSON object.
+fn parse_plan(messages: &[serde_json::Value]) -> Option<serde_json::Value> {
+    let result = result_message(messages)?;
+    let plan = match &result["structured_output"] {
+        serde_json::Value::Object(_) => result["structured_output"].clone(),
+        _ => serde_json::from_str(result["result"].as_str()?).ok()?,
+    };
+    plan["topic_relevance"].is_string().then_some(plan)
+}
+
+/// The selected dimensions of `plan` that have a spec in `skill_dir`, as
+/// `(name, focus)`. Names are slugs, so anything else is dropped.
+fn selected_dimensions(plan: &serde_json::Value, skill_dir: &Path) -> Vec<(String, String)> {
+    if plan["topic_relevance"] != "relevant" {
+        return Vec::new();
+    }
+    let Some(selected) = plan["selected_dimensions"].as_array() else {
+        return Vec::new();
+    };
+    let mut dimensions: Vec<(String, String)> = Vec::new();
+    for entry in selected {
+        let (Some(name), Some(focus)) = (entry["name"].as_str(), entry["focus"].as_str()) else {
+            continue;
+        };
+        let is_slug = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        if !is_slug || dimensions.iter().any(|(n, _)| n == name) {
+            continue;
+        }
+        if !skill_dir
+            .join("references")
+            .join("dimensions")
+            .join(format!("{}.md", name))
+            .is_file()
+        {
+            log::warn!(
+                "[research_fanout] plan selected unknown dimension '{}'",
+                name
+            );
+            continue;
+        }
+        dimensions.push((name.to_string(), focus.to_string()));
+    }
+    dimensions.truncate(MAX_SELECTED_DIMENSIONS);
+    dimensions
+}
+
+/// `base` narrowed to a plain run with `prompt`: no agent, plugins, thinking
+/// or session carried over from the step it was built for.
+fn task_config(
+    base: &SidecarConfig,
+    prompt: String,
+    tools: &[&str],
+    max_turns: u32,
+) -> SidecarConfig {
+    SidecarConfig {
+        prompt,
+        allowed_tools: Some(tools.iter().map(|t| t.to_string()).collect()),
+        max_turns: Some(max_turns),
+        betas: None,
+        thinking: None,
+        output_format: None,
+        prompt_suggestions: None,
+        agent_name: None,
+        required_plugins: None,
+        conversation_history: None,
+        resume: None,
+        disallowed_tools: None,
+        ..base.clone()
+    }
+}
+
+/// Create `research_dir`, removing the plan and research of an earlier run so a
+/// dimension this run does not select leaves nothing behind.
+fn reset_research_dir(research_dir: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(research_dir)
+        .map_err(|e| format!("Failed to create {}: {}", research_dir.display(), e))?;
+    let entries = std::fs::read_dir(research_dir)
+        .map_err(|e| format!("Failed to read {}: {}", research_dir.display(), e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let stale = path.is_file()
+            && (path.file_name().is_some_and(|n| n == PLAN_FILE)
+                || path.extension().is_some_and(|ext| ext == "md"));
+        if stale {
+            std::fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Line appended to the orchestrator's prompt when a plan was written.
+pub(crate) fn prompt_section(workspace_dir: &Path) -> String {
+    format!(
+        " Dimension research for this run was prepared in {}: pass that path to the research agent as research_dir.",
+        workspace_dir.join(RESEARCH_DIR).to_string_lossy().replace('\\', "/")
+    )
+}
+
+/// Score and select Step 0's dimensions, research each selected one as its own
+/// concurrent run, and write the plan and results under `RESEARCH_DIR`.
+/// `base` is the orchestrator's config, whose model, key and sandbox the runs
+/// share. Returns whether a plan was written.
+pub(crate) async fn prefetch_dimension_research(
+    app: &tauri::AppHandle,
+    pool: &SidecarPool,
+    skill_name: &str,
+    workspace_path: &str,
+    base: &SidecarConfig,
+) -> Result<bool, String> {
+    use tauri::Manager;
+
+    let skill_dir = Path::new(workspace_path).join(RESEARCH_SKILL_DIR);
+    if !skill_dir.join("SKILL.md").is_file() {
+        log::debug!(
+            "[research_fanout] research skill not installed at {}",
+            skill_dir.display()
+        );
+        return Ok(false);
+    }
+    let workspace_dir = Path::new(workspace_path).join(skill_name);
+    let user_context = std::fs::read_to_string(workspace_dir.join("user-context.md"))
+        .map_err(|e| format!("Failed to read user-context.md: {}", e))?;
+
+    let session_id = {
+        let db = app.state::<crate::db::Db>();
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        crate::db::open_workflow_session_for_pid(&conn, skill_name, std::process::id())?
+    }
+    .unwrap_or_else(|| format!("synthetic:research:{}:{}", skill_name, uuid::Uuid::new_v4()));
+
+    let research_dir = workspace_dir.join(RESEARCH_DIR);
+    reset_research_dir(&research_dir)?;
+
+    let slash = |p: &Path| p.to_string_lossy().replace('\\', "/");
+    let mut planning = task_config(
+        base,
+        planning_prompt(&slash(&workspace_dir), &slash(&skill_dir)),
+        PLANNING_TOOLS,
+        PLANNING_MAX_TURNS,
+    );
+    planning.output_format = Some(plan_output_format());
+    let planning = ConcurrentTask {
+        agent_id: super::workflow::make_agent_id(skill_name, "research-plan"),
+        config: planning,
+    };
+    let outcome = sidecar::run_concurrent(vec![planning], pool, app, skill_name, 0, &session_id)
+        .await?
+        .pop()
+        .ok_or("The research planning run did not run")?;
+    if let Some(e) = outcome.error {
+        return Err(format!("Research planning failed: {}", e));
+    }
+    let plan = parse_plan(&outcome.messages).ok_or("Research planning returned no plan")?;
+
+    let plan_json = serde_json::to_string_pretty(&plan).map_err(|e| e.to_string())?;
+    std::fs::write(research_dir.join(PLAN_FILE), plan_json)
+        .map_err(|e| format!("Failed to write research plan: {}", e))?;
+
+    let dimensions = selected_dimensions(&plan, &skill_dir);
+    let mut tasks = Vec::with_capacity(dimensions.len());
+    for (name, focus) in &dimensions {
+        let spec_path = skill_dir
+            .join("references")
+            .join("dimensions")
+            .join(format!("{}.md", name));
+        let spec = std::fs::read_to_string(&spec_path)
+            .map_err(|e| format!("Failed to read {}: {}", spec_path.display(), e))?;
+        tasks.push(ConcurrentTask {
+            agent_id: super::workflow::make_agent_id(skill_name, &format!("research-{}", name)),
+            config: task_config(
+                base,
+                dimension_prompt(&spec, &user_context, focus),
+                DIMENSION_TOOLS,
+                DIMENSION_MAX_TURNS,
+            ),
+        });
+    }
+    if tasks.is_empty() {
+        log::info!(
+            "[research_fanout] '{}': plan selected no dimensions",
+            skill_name
+        );
+        return Ok(true);
+    }
+
+    let outcomes = sidecar::run_concurrent(tasks, pool, app, skill_name, 0, &session_id).await?;
+    let mut researched = 0;
+    for ((name, _), outcome) in dimensions.iter().zip(outcomes) {
+        let text = match outcome.error {
+            None => result_message(&outcome.messages).and_then(|m| m["result"].as_str()),
+            Some(ref e) => {
+                log::warn!(
+                    "[research_fanout] '{}' dimension {} failed: {}",
+                    skill_name,
+                    name,
+                    e
+                );
+                None
+            }
+        };
+        let Some(text) = text.filter(|t| !t.trim().is_empty()) else {
+            continue;
+        };
+        std::fs::write(research_dir.join(format!("{}.md", name)), text)
+            .map_err(|e| format!("Failed to write research for {}: {}", name, e))?;
+        researched += 1;
+    }
+    log::info!(
+        "[research_fanout] '{}': researched {}/{} dimension(s) concurrently",
+        skill_name,
+        researched,
+        dimensions.len()
+    );
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn skill_dir_with(dimensions: &[&str]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let specs = dir.path().join("references").join("dimensions");
+        std::fs::create_dir_all(&specs).unwrap();
+        for name in dimensions {
+            std::fs::write(specs.join(format!("{}.md", name)), "# spec").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_parse_plan_prefers_structured_output() {
+        let messages = vec![
+            json!({ "type": "assistant", "message": { "content": [] } }),
+            json!({ "type": "result", "result": "done", "structured_output": { "topic_relevance": "relevant" } }),
+        ];
+        assert_eq!(
+            parse_plan(&messages).unwrap()["topic_relevance"],
+            "relevant"
+        );
+
+        let text =
+            vec![json!({ "type": "result", "result": "{\"topic_relevance\": \"not_relevant\"}" })];
+        assert_eq!(
+            parse_plan(&text).unwrap()["topic_relevance"],
+            "not_relevant"
+        );
+
+        assert!(parse_plan(&[json!({ "type": "result", "result": "no plan" })]).is_none());
+        assert!(
+            parse_plan(&[json!({ "type": "result", "structured_output": { "a": 1 } })]).is_none()
+        );
+        assert!(parse_plan(&[]).is_none());
+    }
+
+    #[test]
+    fn test_selected_dimensions_keeps_known_slugs_only() {
+        let dir = skill_dir_with(&["entities", "metrics"]);
+        let plan = json!({
+            "topic_relevance": "relevant",
+            "selected_dimensions": [
+                { "name": "entities", "focus": "Customer hierarchy" },
+                { "name": "unknown-dimension", "focus": "x" },
+                { "name": "../secrets", "focus": "x" },
+                { "name": "entities", "focus": "duplicate" },
+                { "name": "metrics" },
+                { "name": "metrics", "focus": "MRR roll-up" }
+            ]
+        });
+        assert_eq!(
+            selected_dimensions(&plan, dir.path()),
+            vec![
+                ("entities".to_string(), "Customer hierarchy".to_string()),
+                ("metrics".to_string(), "MRR roll-up".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_selected_dimensions_empty_when_not_relevant_and_capped() {
+        let names = ["a", "b", "c", "d", "e", "f", "g"];
+        let dir = skill_dir_with(&names);
+        let selected: Vec<_> = names
+            .iter()
+            .map(|n| json!({ "name": n, "focus": "f" }))
+            .collect();
+
+        let relevant = json!({ "topic_relevance": "relevant", "selected_dimensions": selected });
+        assert_eq!(
+            selected_dimensions(&relevant, dir.path()).len(),
+            MAX_SELECTED_DIMENSIONS
+        );
+
+        let not_relevant =
+            json!({ "topic_relevance": "not_relevant", "selected_dimensions": selected });
+        assert!(selected_dimensions(&not_relevant, dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_task_config_drops_step_specific_fields() {
+        let base = SidecarConfig {
+            prompt: "orchestrate".into(),
+            model: Some("claude-sonnet-4-6".into()),
+            api_key: "sk-test".into(),
+            cwd: "/ws".into(),
+            allowed_tools: Some(vec!["Task".into()]),
+            max_turns: Some(50),
+            permission_mode: Some("bypassPermissions".into()),
+            betas: Some(vec!["interleaved-thinking-2025-05-14".into()]),
+            thinking: Some(json!({ "type": "enabled", "budgetTokens": 8000 })),
+            fallback_model: None,
+            effort: Some("high".into()),
+            output_format: Some(json!({ "type": "json_schema" })),
+            prompt_suggestions: None,
+            path_to_claude_code_executable: None,
+            agent_name: Some("research-orchestrator".into()),
+            required_plugins: Some(vec!["skill-content-researcher".into()]),
+            conversation_history: None,
+            resume: Some("session-1".into()),
+            bash_sandbox: None,
+            disallowed_tools: None,
+            local_model: None,
+        };
+        let config = task_config(
+            &base,
+            "research".into(),
+            DIMENSION_TOOLS,
+            DIMENSION_MAX_TURNS,
+        );
+        assert_eq!(config.prompt, "research");
+        assert_eq!(config.model.as_deref(), Some("claude-sonnet-4-6"));
+        assert_eq!(config.api_key, "sk-test");
+        assert_eq!(config.cwd, "/ws");
+        assert_eq!(config.effort.as_deref(), Some("high"));
+        assert_eq!(config.max_turns, Some(DIMENSION_MAX_TURNS));
+        assert!(config.agent_name.is_none());
+        assert!(config.required_plugins.is_none());
+        assert!(config.thinking.is_none());
+        assert!(config.betas.is_none());
+        assert!(config.output_format.is_none());
+        assert!(config.resume.is_none());
+    }
+
+    #[test]
+    fn test_reset_research_dir_removes_previous_plan_and_research() {
+        let workspace = tempfile::tempdir().unwrap();
+        let research_dir = workspace.path().join(RESEARCH_DIR);
+        std::fs::create_dir_all(&research_dir).unwrap();
+        std::fs::write(research_dir.join(PLAN_FILE), "{}").unwrap();
+        std::fs::write(research_dir.join("old-dimension.md"), "stale").unwrap();
+        std::fs::write(research_dir.join("notes.txt"), "kept").unwrap();
+
+        reset_research_dir(&research_dir).unwrap();
+
+        assert!(!research_dir.join(PLAN_FILE).exists());
+        assert!(!research_dir.join("old-dimension.md").exists());
+        assert!(research_dir.join("notes.txt").exists());
+
+        let fresh = workspace.path().join("other").join(RESEARCH_DIR);
+        reset_research_dir(&fresh).unwrap();
+        assert!(fresh.is_dir());
+    }
+}
@@ -0,0 +1,569 @@
+//! Descriptions for the pull/merge requests opened by branch-per-skill pushes.
+//!
+//! The description is assembled from what the backend already knows: the
+//! files the push adds, changes and removes against the base branch, how the
+//! SKILL.md sections changed, the version bump, decisions that changed in
+//! `context/decisions.json`, the validation, lint and quality gate results,
+//! and a reviewer checklist derived from all of that. When an auxiliary model
+//! is available it writes a short summary of the change on top; without one
+//! (or when it fails) the description is the deterministic part alone.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::imported_skills::parse_frontmatter_full;
+use super::local_model::{self, AuxiliaryRoute};
+use super::skill_version::{propose_bump, VersionBump};
+use crate::db::Db;
+use crate::git_hosting::{self, HostedRepo};
+use crate::types::{LintIssue, QualityGateReport, SkillDiagnostic};
+
+const DECISIONS_FILE: &str = "context/decisions.json";
+
+/// Base-branch files fetched for comparison; files past the cap count as unchanged.
+const MAX_COMPARED_FILES: usize = 100;
+
+/// Items listed per section before "and N more".
+const MAX_LISTED: usize = 15;
+
+const SUMMARY_MAX_TOKENS: u32 = 300;
+/// SKILL.md excerpt sent with the summary prompt.
+const SUMMARY_EXCERPT_CHARS: usize = 8_000;
+
+/// How the pushed version relates to the one on the base branch.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct VersionChange {
+    pub previous: Option<String>,
+    pub current: Option<String>,
+    /// "major" | "minor" | "patch"
+    pub bump: String,
+    pub reason: String,
+}
+
+/// A decision added, changed or removed since the base branch.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DecisionChange {
+    pub id: String,
+    pub title: String,
+    /// "added" | "changed" | "removed"
+    pub change: &'static str,
+    pub decision: Option<String>,
+}
+
+/// Everything the description is built from.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ReviewFacts {
+    pub skill_name: String,
+    pub skill_path: String,
+    /// False when the base branch could not be read; the diff is then unknown.
+    pub compared: bool,
+    pub new_skill: bool,
+    pub added: Vec<String>,
+    pub modified: Vec<String>,
+    pub removed: Vec<String>,
+    pub skipped: Vec<String>,
+    pub section_changes: Vec<String>,
+    pub version: Option<VersionChange>,
+    pub decisions: Vec<DecisionChange>,
+    pub validation: Vec<SkillDiagnostic>,
+    pub lint: Vec<LintIssue>,
+    pub quality_gate: Option<QualityGateReport>,
+}
+
+/// `## ` sections of a SKILL.md body with their text, in order.
+fn section_bodies(content: &str) -> Vec<(String, String)> {
+    let mut sections: Vec<(String, String)> = Vec::new();
+    for line in content.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            sections.push((heading.trim().to_string(), String::new()));
+        } else if let Some((_, body)) = sections.last_mut() {
+            body.push_str(line.trim_end());
+            body.push('\n');
+        }
+    }
+    sections
+}
+
+/// Human-readable SKILL.md section changes from `old` to `new`.
+pub(crate) fn section_changes(old: &str, new: &str) -> Vec<String> {
+    let old_sections = section_bodies(old);
+    let new_sections = section_bodies(new);
+    let mut changes = Vec::new();
+    for (heading, body) in &new_sections {
+        match old_sections.iter().find(|(h, _)| h == heading) {
+            None => changes.push(format!("Added section **{}**", heading)),
+            Some((_, old_body)) if old_body.trim() != body.trim() => {
+                changes.push(format!("Edited section **{}**", heading))
+            }
+            Some(_) => {}
+        }
+    }
+    for (heading, _) in &old_sections {
+        if !new_sections.iter().any(|(h, _)| h == heading) {
+            changes.push(format!("Removed section **{}**", heading));
+        }
+    }
+    let old_fm = parse_frontmatter_full(old);
+    let new_fm = parse_frontmatter_full(new);
+    if old_fm.description != new_fm.description {
+        changes.push("Changed the frontmatter description".to_string());
+    }
+    if old_fm.argument_hint != new_fm.argument_hint {
+        changes.push("Changed the `argument-hint`".to_string());
+    }
+    changes
+}
+
+/// The version bump from the base SKILL.md to the pushed one.
+pub(crate) fn version_change(old: &str, new: &str) -> Option<VersionChange> {
+    if old == new {
+        return None;
+    }
+    let (bump, reason) = propose_bump(old, new);
+    Some(VersionChange {
+        previous: parse_frontmatter_full(old).version,
+        current: parse_frontmatter_full(new).version,
+        bump: bump.as_str().to_string(),
+        reason,
+    })
+}
+
+/// Decisions keyed by id (or title when a decision has no id).
+pub(crate) fn decisions_by_key(content: Option<&str>) -> BTreeMap<String, serde_json::Value> {
+    let doc: serde_json::Value = content
+        .and_then(|c| serde_json::from_str(c).ok())
+        .unwrap_or(serde_json::Value::Null);
+    doc["decisions"]
+        .as_array()
+        .map(|decisions| {
+            decisions
+                .iter()
+                .filter_map(|d| {
+                    let key = d["id"].as_str().or_else(|| d["title"].as_str())?;
+                    Some((key.to_string(), d.clone()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Decisions added, changed (decision text or status) or removed between
+/// two `decisions.json` documents.
+pub(crate) fn decision_changes(old: Option<&str>, new: Option<&str>) -> Vec<DecisionChange> {
+    let old = decisions_by_key(old);
+    let new = decisions_by_key(new);
+    let entry = |key: &str, d: &serde_json::Value, change: &'static str| DecisionChange {
+        id: key.to_string(),
+        title: d["title"].as_str().unwrap_or(key).to_string(),
+        change,
+        decision: d["decision"].as_str().map(str::to_string),
+    };
+    let mut changes = Vec::new();
+    for (key, d) in &new {
+        match old.get(key) {
+            None => changes.push(entry(key, d, "added")),
+            Some(prev) if prev["decision"] != d["decision"] || prev["status"] != d["status"] => {
+                changes.push(entry(key, d, "changed"))
+            }
+            Some(_) => {}
+        }
+    }
+    for (key, d) in &old {
+        if !new.contains_key(key) {
+            changes.push(entry(key, d, "removed"));
+        }
+    }
+    changes
+}
+
+/// Compare the pushed files with the skill's files on the base branch.
+/// `base` holds the base content of every file fetched; `base_paths` lists
+/// every base file under the skill, fetched or not.
+fn diff_files(
+    local: &[(String, String)],
+    base_paths: &[String],
+    base: &BTreeMap<String, String>,
+) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let mut added = Vec::new();
+    let mut modified = Vec::new();
+    for (path, content) in local {
+        if !base_paths.contains(path) {
+            added.push(path.clone());
+        } else if base.get(path).is_some_and(|old| old != content) {
+            modified.push(path.clone());
+        }
+    }
+    let removed = base_paths
+        .iter()
+        .filter(|p| !local.iter().any(|(path, _)| path == *p))
+        .cloned()
+        .collect();
+    (added, modified, removed)
+}
+
+/// Build the facts for a push from the local files and the base branch's.
+/// `base` is `None` when the base branch could not be read.
+pub(crate) fn collect_facts(
+    skill_name: &str,
+    skill_path: &str,
+    skill_dir: &Path,
+    local: &[(String, String)],
+    skipped: &[String],
+    base: Option<(&[String], &BTreeMap<String, String>)>,
+) -> ReviewFacts {
+    let local_text = |path: &str| local.iter().find(|(p, _)| p == path).map(|(_, c)| c.as_str());
+    let mut facts = ReviewFacts {
+        skill_name: skill_name.to_string(),
+        skill_path: skill_path.to_string(),
+        compared: base.is_some(),
+        skipped: skipped.to_vec(),
+        validation: super::skill_validation::validate_skill_dir(skill_dir).1,
+        lint: super::skill_lint::lint_skill_dir(skill_dir).1,
+        ..Default::default()
+    };
+    if let Some((base_paths, base_files)) = base {
+        let (added, modified, removed) = diff_files(local, base_paths, base_files);
+        facts.new_skill = base_paths.is_empty();
+        facts.added = added;
+        facts.modified = modified;
+        facts.removed = removed.into_iter().filter(|p| !skipped.contains(p)).collect();
+        if let (Some(old), Some(new)) = (base_files.get("SKILL.md"), local_text("SKILL.md")) {
+            facts.section_changes = section_changes(old, new);
+            facts.version = version_change(old, new);
+        }
+        facts.decisions = decision_changes(
+            base_files.get(DECISIONS_FILE).map(String::as_str),
+            local_text(DECISIONS_FILE),
+        );
+    }
+    facts
+}
+
+fn listed(items: &[String]) -> String {
+    let mut out: Vec<String> = items.iter().take(MAX_LISTED).map(|i| format!("`{}`", i)).collect();
+    if items.len() > MAX_LISTED {
+        out.push(format!("and {} more", items.len() - MAX_LISTED));
+    }
+    out.join(", ")
+}
+
+fn checklist(facts: &ReviewFacts) -> Vec<String> {
+    let mut items = vec!["The frontmatter description says when the skill should be used".to_string()];
+    if let Some(version) = facts.version.as_ref().filter(|v| v.bump == VersionBump::Major.as_str()) {
+        items.push(format!(
+            "Breaking change ({}): consumers of this skill still work",
+            version.reason
+        ));
+    }
+    if !facts.decisions.is_empty() {
+        items.push("SKILL.md reflects the changed decisions".to_string());
+    }
+    if !facts.removed.is_empty() {
+        items.push("Files no longer in the skill are deleted here or still needed".to_string());
+    }
+    if facts.validation.iter().any(|d| d.severity != super::skill_validation::SEVERITY_INFO) || !facts.lint.is_empty() {
+        items.push("Validation and lint findings below are fixed or acceptable".to_string());
+    }
+    if let Some(gate) = &facts.quality_gate {
+        let waived: Vec<&str> = gate
+            .checks
+            .iter()
+            .filter(|c| c.waiver.is_some())
+            .map(|c| c.check.as_str())
+            .collect();
+        if !waived.is_empty() {
+            items.push(format!("Waivers for {} are still justified", waived.join(", ")));
+        }
+    }
+    if !facts.skipped.is_empty() {
+        items.push("Files left out of the push aren't needed by the skill".to_string());
+    }
+    items.push("References contain no secrets or internal-only data".to_string());
+    items
+}
+
+/// Render the description. `summary` is the optional model-written overview.
+pub(crate) fn render(facts: &ReviewFacts, summary: Option<&str>) -> String {
+    let mut out = Vec::new();
+    let intro = if facts.new_skill {
+        format!("Adds the `{}` skill in `{}`.", facts.skill_name, facts.skill_path)
+    } else {
+        format!("Updates the `{}` skill in `{}`.", facts.skill_name, facts.skill_path)
+    };
+    out.push(intro);
+    if let Some(summary) = summary.map(str::trim).filter(|s| !s.is_empty()) {
+        out.push(summary.to_string());
+    }
+
+    out.push("## Changes".to_string());
+    let mut changes = Vec::new();
+    if !facts.compared {
+        changes.push("- The base branch could not be read, so the changes are not listed.".to_string());
+    }
+    if let Some(v) = &facts.version {
+        changes.push(format!(
+            "- Version: {} → {} ({}: {})",
+            v.previous.as_deref().unwrap_or("unversioned"),
+            v.current.as_deref().unwrap_or("unversioned"),
+            v.bump,
+            v.reason
+        ));
+    }
+    changes.extend(facts.section_changes.iter().map(|c| format!("- {}", c)));
+    if !facts.added.is_empty() && !facts.new_skill {
+        changes.push(format!("- Added files: {}", listed(&facts.added)));
+    }
+    if !facts.modified.is_empty() {
+        changes.push(format!("- Changed files: {}", listed(&facts.modified)));
+    }
+    if !facts.removed.is_empty() {
+        changes.push(format!(
+            "- No longer in the skill (pushes don't delete files): {}",
+            listed(&facts.removed)
+        ));
+    }
+    if !facts.skipped.is_empty() {
+        changes.push(format!("- Left out (hidden, binary or too large): {}", listed(&facts.skipped)));
+    }
+    if changes.is_empty() {
+        changes.push("- No file changes against the base branch.".to_string());
+    }
+    out.push(changes.join("\n"));
+
+    if !facts.decisions.is_empty() {
+        out.push("## Decisions".to_string());
+        let lines: Vec<String> = facts
+            .decisions
+            .iter()
+            .map(|d| match (&d.decision, d.change) {
+                (Some(decision), "added" | "changed") => {
+                    format!("- {} **{}** ({}): {}", d.change, d.title, d.id, decision)
+                }
+                _ => format!("- {} **{}** ({})", d.change, d.title, d.id),
+            })
+            .collect();
+        out.push(lines.join("\n"));
+    }
+
+    out.push("## Checks".to_string());
+    let mut checks = Vec::new();
+    let count = |severity: &str| facts.validation.iter().filter(|d| d.severity == severity).count();
+    let (errors, warnings) = (
+        count(super::skill_validation::SEVERITY_ERROR),
+        count(super::skill_validation::SEVERITY_WARNING),
+    );
+    checks.push(if errors + warnings == 0 {
+        "- Validation: passed".to_string()
+    } else {
+        let first: Vec<String> = facts
+            .validation
+            .iter()
+            .filter(|d| d.severity != super::skill_validation::SEVERITY_INFO)
+            .take(3)
+            .map(|d| format!("`{}`: {}", d.file, d.message))
+            .collect();
+        format!("- Validation: {} error(s), {} warning(s) — {}", errors, warnings, first.join("; "))
+    });
+    checks.push(match facts.lint.len() {
+        0 => "- Accessibility lint: passed".to_string(),
+        n => format!("- Accessibility lint: {} finding(s)", n),
+    });
+    if let Some(gate) = &facts.quality_gate {
+        let failing: Vec<&str> = gate
+            .checks
+            .iter()
+            .filter(|c| c.enforced && !c.passed && c.waiver.is_none())
+            .map(|c| c.check.as_str())
+            .collect();
+        checks.push(if gate.passed {
+            format!("- Quality gate `{}`: passed", gate.profile)
+        } else {
+            format!("- Quality gate `{}`: failed ({})", gate.profile, failing.join(", "))
+        });
+    }
+    out.push(checks.join("\n"));
+
+    out.push("## Reviewer checklist".to_string());
+    out.push(
+        checklist(facts)
+            .iter()
+            .map(|item| format!("- [ ] {}", item))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    );
+
+    out.push("---\nPushed from Skill Builder. Further pushes of this skill update this request.".to_string());
+    out.join("\n\n")
+}
+
+fn summary_prompt(facts: &ReviewFacts, skill_md: &str) -> String {
+    let excerpt: String = skill_md.chars().take(SUMMARY_EXCERPT_CHARS).collect();
+    let render_facts = render(facts, None);
+    format!(
+        "You are writing the opening paragraph of a pull request that updates a Claude skill.\n\
+         Explain in 2-4 plain sentences what changed for someone using the skill and why a reviewer \
+         should care. Do not repeat file lists, versions or checklists; do not use headings.\n\n\
+         Facts gathered about the change:\n{}\n\nThe skill's SKILL.md after the change:\n{}",
+        render_facts, excerpt
+    )
+}
+
+/// Build the description for a branch-per-skill push of `local` (paths
+/// relative to the skill) to `skill_path`, comparing against `base`.
+/// Never fails: base-branch and model errors degrade the description.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn build_review_description(
+    db: &Db,
+    client: &reqwest::Client,
+    repo: &HostedRepo,
+    base: &str,
+    skill_name: &str,
+    skill_path: &str,
+    skill_dir: &Path,
+    local: &[(String, String)],
+    skipped: &[String],
+) -> String {
+    let base_files: Result<(Vec<String>, BTreeMap<String, String>), String> = async {
+        let prefix = format!("{}/", skill_path);
+        let paths: Vec<String> = git_hosting::list_files(client, repo, base)
+            .await?
+            .into_iter()
+            .filter_map(|p| p.strip_prefix(&prefix).map(str::to_string))
+            .collect();
+        let mut contents = BTreeMap::new();
+        for path in paths
+            .iter()
+            .filter(|p| local.iter().any(|(l, _)| l == *p))
+            .take(MAX_COMPARED_FILES)
+        {
+            if let Some(text) = git_hosting::get_text(client, repo, base, &format!("{}{}", prefix, path)).await? {
+                contents.insert(path.clone(), text);
+            }
+        }
+        Ok((paths, contents))
+    }
+    .await;
+    if let Err(e) = &base_files {
+        log::warn!("[push_skill_to_host] could not compare '{}' with {}: {}", skill_name, base, e);
+    }
+
+    let mut facts = collect_facts(
+        skill_name,
+        skill_path,
+        skill_dir,
+        local,
+        skipped,
+        base_files.as_ref().ok().map(|(paths, contents)| (paths.as_slice(), contents)),
+    );
+    let route = match db.0.lock() {
+        Ok(conn) => {
+            facts.quality_gate = crate::db::read_settings(&conn)
+                .ok()
+                .and_then(|s| s.quality_gate_profile)
+                .and_then(|profile| super::quality_gates::report_for(&conn, skill_name, skill_dir, &profile).ok());
+            crate::db::read_settings_hydrated(&conn)
+                .ok()
+                .map(|s| AuxiliaryRoute::from_settings(&s))
+        }
+        Err(e) => {
+            log::warn!("[push_skill_to_host] failed to acquire DB lock: {}", e);
+            None
+        }
+    };
+
+    let skill_md = local.iter().find(|(p, _)| p == "SKILL.md").map_or("", |(_, c)| c.as_str());
+    let summary = match route {
+        Some(route) => {
+            match local_model::complete(&route, &summary_prompt(&facts, skill_md), SUMMARY_MAX_TOKENS, "push_skill_to_host")
+                .await
+            {
+                Ok(completion) => {
+                    if let Ok(conn) = db.0.lock() {
+                        if let Err(e) = local_model::record_usage(&conn, skill_name, &completion) {
+                            log::warn!("[push_skill_to_host] failed to record usage: {}", e);
+                        }
+                    }
+                    Some(completion.text)
+                }
+                Err(e) => {
+                    log::warn!("[push_skill_to_host] summary pass failed, using the assembled description: {}", e);
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+    render(&facts, summary.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD_SKILL: &str = "---\nname: revenue\ndescription: Revenue rules\nversion: 1.2.0\n---\n# Revenue\n\n## Joins\nUse dim_customer.\n\n## Metrics\nARR only.\n";
+    const NEW_SKILL: &str = "---\nname: revenue\ndescription: Revenue rules\nversion: 1.3.0\n---\n# Revenue\n\n## Joins\nUse dim_customer.\n\n## Metrics\nARR and NRR.\n\n## Edge cases\nRefunds.\n";
+
+    #[test]
+    fn test_section_changes_and_version() {
+        assert_eq!(
+            section_changes(OLD_SKILL, NEW_SKILL),
+            vec!["Edited section **Metrics**", "Added section **Edge cases**"]
+        );
+        let version = version_change(OLD_SKILL, NEW_SKILL).unwrap();
+        assert_eq!(version.previous.as_deref(), Some("1.2.0"));
+        assert_eq!(version.current.as_deref(), Some("1.3.0"));
+        assert_eq!(version.bump, "minor");
+        assert!(version_change(OLD_SKILL, OLD_SKILL).is_none());
+    }
+
+    #[test]
+    fn test_decision_changes() {
+        let old = r#"{"decisions":[
+            {"id":"D1","title":"Hierarchy","decision":"Two levels","status":"resolved"},
+            {"id":"D2","title":"Timing","decision":"At invoice","status":"resolved"}]}"#;
+        let new = r#"{"decisions":[
+            {"id":"D1","title":"Hierarchy","decision":"Three levels","status":"resolved"},
+            {"id":"D3","title":"Currency","decision":"USD","status":"resolved"}]}"#;
+        let changes = decision_changes(Some(old), Some(new));
+        let summary: Vec<(&str, &str)> = changes.iter().map(|c| (c.id.as_str(), c.change)).collect();
+        assert_eq!(summary, vec![("D1", "changed"), ("D3", "added"), ("D2", "removed")]);
+        assert_eq!(changes[0].decision.as_deref(), Some("Three levels"));
+        assert!(decision_changes(None, None).is_empty());
+    }
+
+    #[test]
+    fn test_render_lists_changes_checks_and_checklist() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skill_dir = tmp.path().join("revenue");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(skill_dir.join("SKILL.md"), NEW_SKILL).unwrap();
+
+        let local = vec![
+            ("SKILL.md".to_string(), NEW_SKILL.to_string()),
+            ("references/nrr.md".to_string(), "# NRR".to_string()),
+        ];
+        let base_paths = vec!["SKILL.md".to_string(), "references/old.md".to_string()];
+        let base = BTreeMap::from([("SKILL.md".to_string(), OLD_SKILL.to_string())]);
+        let facts = collect_facts(
+            "revenue",
+            "skills/revenue",
+            &skill_dir,
+            &local,
+            &[],
+            Some((base_paths.as_slice(), &base)),
+        );
+        assert_eq!(facts.added, vec!["references/nrr.md"]);
+        assert_eq!(facts.modified, vec!["SKILL.md"]);
+        assert_eq!(facts.removed, vec!["references/old.md"]);
+
+        let text = render(&facts, Some("Adds NRR to the metrics."));
+        assert!(text.starts_with("Updates the `revenue` skill in `skills/revenue`.\n\nAdds NRR to the metrics."));
+        assert!(text.contains("- Version: 1.2.0 → 1.3.0 (minor: added section: Edge cases)"));
+        assert!(text.contains("- Added files: `references/nrr.md`"));
+        assert!(text.contains("- No longer in the skill (pushes don't delete files): `references/old.md`"));
+        assert!(text.contains("## Checks"));
+
+        let unknown = collect_facts("revenue", "skills/revenue", &skill_dir, &local, &[], None);
+        assert!(render(&unknown, None).contains("The base branch could not be read"));
+    }
+}
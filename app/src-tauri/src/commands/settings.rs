@@ -57,8 +57,9 @@ pub fn get_settings(db: tauri::State<'_, Db>) -> Result<AppSettings, String> {
     // Normalize all stored registry URLs to canonical shorthand (owner/repo or owner/repo#branch).
     // This migrates existing entries that were saved as full HTTPS URLs.
     let mut normalized = false;
+    let github = crate::github::GitHubServer::from_settings(&settings).unwrap_or_default();
     for registry in &mut settings.marketplace_registries {
-        if let Ok(info) = crate::commands::github_import::parse_github_url_inner(&registry.source_url) {
+        if let Ok(info) = crate::commands::github_import::parse_github_url_for(&registry.source_url, &github) {
             let canonical = if info.branch == "main" {
                 format!("{}/{}", info.owner, info.repo)
             } else {
@@ -100,10 +101,37 @@ pub fn save_settings(
 ) -> Result<(), String> {
     log::info!("[save_settings]");
     let mut settings = settings;
-    // Normalize skills_path before persisting
+    crate::commands::activation_policy::validate_policy(&settings.activation_policy).map_err(|e| {
+        log::error!("[save_settings] {}", e);
+        e
+    })?;
+    crate::commands::skill_lint::validate_lint_mode(&settings.accessibility_lint).map_err(|e| {
+        log::error!("[save_settings] {}", e);
+        e
+    })?;
+    crate::automation_hooks::validate_hooks(&settings.automation_hooks).map_err(|e| {
+        log::error!("[save_settings] {}", e);
+        e
+    })?;
+    crate::commands::model_routing::validate_routes(&settings.model_routes).map_err(|e| {
+        log::error!("[save_settings] {}", e);
+        e
+    })?;
+    crate::github::GitHubServer::from_settings(&settings).map_err(|e| {
+        log::error!("[save_settings] {}", e);
+        e
+    })?;
+    // Normalize skills_path before persisting (quotes, ~, separators, relative paths)
     if let Some(ref sp) = settings.skills_path {
-        let normalized = normalize_path(sp);
-        settings.skills_path = Some(normalized);
+        let checked = crate::path_input::check(sp, crate::path_input::PathKind::Directory, false, None)
+            .map_err(|e| {
+                log::error!("[save_settings] Invalid skills path: {}", e);
+                format!("Invalid skills path: {}", e)
+            })?;
+        for warning in &checked.warnings {
+            log::info!("[save_settings] skills_path '{}': {}", sp, warning);
+        }
+        settings.skills_path = Some(normalize_path(&checked.path));
     }
 
     let conn = db.0.lock().map_err(|e| {
@@ -125,6 +153,12 @@ pub fn save_settings(
         settings.marketplace_initialized = true;
     }
 
+    // A GitHub token only works on the server that issued it, so switching
+    // between github.com and an enterprise server signs out of GitHub. The
+    // issuing server is set by sign-in only, never by the frontend.
+    settings.github_oauth_host = old_settings.github_oauth_host.clone();
+    crate::github::forget_token_for_other_server(&mut settings);
+
     // Log what changed
     let changes = diff_settings(&old_settings, &settings);
     if changes.is_empty() {
@@ -185,6 +219,67 @@ fn diff_settings(old: &AppSettings, new: &AppSettings) -> Vec<String> {
     cmp_opt!(function_role, "function_role");
     cmp_opt!(dashboard_view_mode, "dashboard_view_mode");
     cmp_bool!(auto_update, "auto_update");
+    cmp_opt!(team_repo, "team_repo");
+    cmp_opt!(github_enterprise_url, "github_enterprise_url");
+    cmp_opt!(github_oauth_client_id, "github_oauth_client_id");
+    cmp_opt!(github_ca_cert_path, "github_ca_cert_path");
+    cmp_bool!(github_accept_invalid_certs, "github_accept_invalid_certs");
+    cmp_opt!(local_model_endpoint, "local_model_endpoint");
+    cmp_opt!(local_model, "local_model");
+    if old.activation_policy != new.activation_policy {
+        changes.push(format!(
+            "activation_policy={} rules, default {}",
+            new.activation_policy.rules.len(),
+            new.activation_policy.default_action
+        ));
+    }
+    cmp_val!(accessibility_lint, "accessibility_lint");
+    cmp_val!(block_expired_packaging, "block_expired_packaging");
+    cmp_val!(require_trusted_packages, "require_trusted_packages");
+    cmp_val!(workflow_queue_concurrency, "workflow_queue_concurrency");
+    cmp_val!(max_concurrent_agents, "max_concurrent_agents");
+    cmp_val!(transcript_archive_mb, "transcript_archive_mb");
+    if old.retention != new.retention {
+        changes.push(format!(
+            "retention=runs {}d, sessions {}d, transcripts {}d, audit {}d",
+            new.retention.agent_runs_days,
+            new.retention.workflow_sessions_days,
+            new.retention.transcripts_days,
+            new.retention.audit_log_days
+        ));
+    }
+    cmp_opt!(quality_gate_profile, "quality_gate_profile");
+    if old.global_budget_usd != new.global_budget_usd {
+        changes.push(format!("global_budget_usd={:?}", new.global_budget_usd));
+    }
+    if old.skill_budgets_usd != new.skill_budgets_usd {
+        changes.push(format!("skill_budgets_usd={} entries", new.skill_budgets_usd.len()));
+    }
+    if old.feedback_redaction_keywords != new.feedback_redaction_keywords {
+        changes.push(format!(
+            "feedback_redaction_keywords={} entries",
+            new.feedback_redaction_keywords.len()
+        ));
+    }
+    cmp_opt!(feedback_public_key, "feedback_public_key");
+    cmp_opt!(custom_prompts_dir, "custom_prompts_dir");
+    cmp_val!(offline_mode, "offline_mode");
+    cmp_val!(disable_marketplace, "disable_marketplace");
+    cmp_val!(disable_github, "disable_github");
+    if old.automation_hooks != new.automation_hooks {
+        changes.push(format!("automation_hooks={} entries", new.automation_hooks.len()));
+    }
+    cmp_opt!(claude_data_dir, "claude_data_dir");
+    cmp_opt!(settings_sync_repo, "settings_sync_repo");
+    if old.model_routes != new.model_routes {
+        changes.push(format!("model_routes={} entries", new.model_routes.len()));
+    }
+    if old.settings_sync_local_overrides != new.settings_sync_local_overrides {
+        changes.push(format!(
+            "settings_sync_local_overrides={}",
+            new.settings_sync_local_overrides.join(",")
+        ));
+    }
     changes
 }
 
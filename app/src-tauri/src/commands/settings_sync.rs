@@ -0,0 +1,466 @@
+//! Organization-wide settings shared through a git repository.
+//!
+//! `settings_sync_repo` names a git remote, optionally with `#branch` (default
+//! `main`). `push_settings_sync` writes the shared settings — preferred
+//! models, marketplace registries, the global tool policy, budgets and
+//! packaging gates — to `skill-builder-settings.json` in that repo and pushes
+//! a commit. Secrets, sign-in state, local paths and per-machine preferences
+//! are never exported. `pull_settings_sync` merges the document into the local
+//! settings, and runs at startup unless offline mode is on.
+//!
+//! Fields named in `settings_sync_local_overrides` keep their local value on
+//! merge, and a push leaves the shared value of those fields as it was. The
+//! repo is cloned to `{data_dir}/settings-sync` with the `git` module; HTTPS
+//! remotes authenticate with the GitHub token.
+
+use std::path::{Path, PathBuf};
+
+use crate::db::{Db, GLOBAL_TOOL_POLICY_SCOPE};
+use crate::types::{AppSettings, SettingsSyncDocument, SettingsSyncResult, ToolPolicy};
+
+/// Repo-relative path of the shared settings document.
+pub(crate) const SYNC_FILE: &str = "skill-builder-settings.json";
+/// Clone of the sync repo, under the app data directory.
+const SYNC_DIR: &str = "settings-sync";
+const DEFAULT_BRANCH: &str = "main";
+const DOCUMENT_VERSION: u32 = 1;
+/// Document key carrying the global tool policy, which lives in its own table.
+pub(crate) const TOOL_POLICY_KEY: &str = "tool_policy";
+
+/// `AppSettings` fields shared across machines, by serialized name.
+pub(crate) const SYNCED_KEYS: &[&str] = &[
+    "preferred_model",
+    "model_routes",
+    "fallback_model",
+    "sdk_effort",
+    "extended_context",
+    "extended_thinking",
+    "interleaved_thinking_beta",
+    "refine_prompt_suggestions",
+    "marketplace_registries",
+    "max_dimensions",
+    "auto_update",
+    "team_repo",
+    "activation_policy",
+    "accessibility_lint",
+    "block_expired_packaging",
+    "workflow_queue_concurrency",
+    "max_concurrent_agents",
+    "quality_gate_profile",
+    "global_budget_usd",
+    "skill_budgets_usd",
+    "disable_marketplace",
+    "disable_github",
+    "transcript_archive_mb",
+    "retention",
+];
+
+struct SyncTarget {
+    url: String,
+    branch: String,
+    token: Option<String>,
+}
+
+fn sync_target(settings: &AppSettings) -> Result<SyncTarget, String> {
+    let spec = settings
+        .settings_sync_repo
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .ok_or_else(|| "No settings sync repository configured. Set one in Settings.".to_string())?;
+    let (url, branch) = match spec.rsplit_once('#') {
+        Some((url, branch)) if !branch.is_empty() => (url, branch),
+        _ => (spec, DEFAULT_BRANCH),
+    };
+    Ok(SyncTarget {
+        url: url.to_string(),
+        branch: branch.to_string(),
+        token: settings
+            .github_oauth_token
+            .clone()
+            .filter(|_| is_github_oauth_host(url, settings.github_oauth_host.as_deref())),
+    })
+}
+
+/// Whether `url` is an HTTP(S) URL on the server that issued the GitHub token,
+/// the only host the token may be offered to.
+fn is_github_oauth_host(url: &str, oauth_host: Option<&str>) -> bool {
+    let (Ok(url), Some(Ok(host))) = (
+        reqwest::Url::parse(url),
+        oauth_host.map(reqwest::Url::parse),
+    ) else {
+        return false;
+    };
+    matches!(url.scheme(), "https" | "http") && url.origin() == host.origin()
+}
+
+fn sync_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(SYNC_DIR)
+}
+
+fn is_local_override(settings: &AppSettings, key: &str) -> bool {
+    settings.settings_sync_local_overrides.iter().any(|k| k == key)
+}
+
+/// The shared settings of this machine. Local overrides keep the value they
+/// have in `existing`, the document currently in the repo.
+pub(crate) fn export_document(
+    settings: &AppSettings,
+    tool_policy: &ToolPolicy,
+    existing: Option<&SettingsSyncDocument>,
+) -> Result<SettingsSyncDocument, String> {
+    let serde_json::Value::Object(mut local) = serde_json::to_value(settings).map_err(|e| e.to_string())? else {
+        return Err("Settings did not serialize to an object".to_string());
+    };
+    local.insert(
+        TOOL_POLICY_KEY.to_string(),
+        serde_json::to_value(tool_policy).map_err(|e| e.to_string())?,
+    );
+    let mut shared = serde_json::Map::new();
+    for key in SYNCED_KEYS.iter().copied().chain([TOOL_POLICY_KEY]) {
+        let value = if is_local_override(settings, key) {
+            existing.and_then(|doc| doc.settings.get(key)).cloned()
+        } else {
+            local.remove(key)
+        };
+        if let Some(value) = value {
+            shared.insert(key.to_string(), value);
+        }
+    }
+    Ok(SettingsSyncDocument {
+        version: DOCUMENT_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        exported_by: settings.github_user_login.clone(),
+        settings: shared,
+    })
+}
+
+/// Local settings with a shared document merged in.
+pub(crate) struct MergedSettings {
+    pub settings: AppSettings,
+    /// Replacement global tool policy, when the shared one differs.
+    pub tool_policy: Option<ToolPolicy>,
+    pub applied: Vec<String>,
+    pub kept_local: Vec<String>,
+}
+
+/// Merge `doc` into the local settings. Shared values win except for local
+/// overrides; fields this version does not share are ignored.
+pub(crate) fn merge_document(
+    local: &AppSettings,
+    local_policy: &ToolPolicy,
+    doc: &SettingsSyncDocument,
+) -> Result<MergedSettings, String> {
+    if doc.version > DOCUMENT_VERSION {
+        return Err(format!(
+            "The shared settings were written by a newer version of Skill Builder (format {}); update to sync them",
+            doc.version
+        ));
+    }
+    let serde_json::Value::Object(mut merged) = serde_json::to_value(local).map_err(|e| e.to_string())? else {
+        return Err("Settings did not serialize to an object".to_string());
+    };
+    let mut tool_policy = None;
+    let mut applied = Vec::new();
+    let mut kept_local = Vec::new();
+    for (key, value) in &doc.settings {
+        let current = if key == TOOL_POLICY_KEY {
+            serde_json::to_value(local_policy).map_err(|e| e.to_string())?
+        } else if SYNCED_KEYS.contains(&key.as_str()) {
+            merged.get(key).cloned().unwrap_or_default()
+        } else {
+            log::debug!("[settings_sync] Ignoring unshared field '{}'", key);
+            continue;
+        };
+        if current == *value {
+            continue;
+        }
+        if is_local_override(local, key) {
+            kept_local.push(key.clone());
+            continue;
+        }
+        if key == TOOL_POLICY_KEY {
+            let policy: ToolPolicy = serde_json::from_value(value.clone())
+                .map_err(|e| format!("The shared tool policy is invalid: {}", e))?;
+            super::tool_policy::validate_policy(&policy)?;
+            tool_policy = Some(policy);
+        } else {
+            merged.insert(key.clone(), value.clone());
+        }
+        applied.push(key.clone());
+    }
+
+    let mut settings: AppSettings = serde_json::from_value(serde_json::Value::Object(merged))
+        .map_err(|e| format!("The shared settings are invalid: {}", e))?;
+    super::activation_policy::validate_policy(&settings.activation_policy)?;
+    super::skill_lint::validate_lint_mode(&settings.accessibility_lint)?;
+    super::model_routing::validate_routes(&settings.model_routes)?;
+    // Shared registries replace the first-run registry migration.
+    if applied.iter().any(|k| k == "marketplace_registries") {
+        settings.marketplace_initialized = true;
+    }
+    Ok(MergedSettings { settings, tool_policy, applied, kept_local })
+}
+
+fn read_document(dir: &Path) -> Result<Option<SettingsSyncDocument>, String> {
+    let path = dir.join(SYNC_FILE);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", SYNC_FILE, e))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("{} in the sync repo is not valid: {}", SYNC_FILE, e))
+}
+
+fn read_local(conn: &rusqlite::Connection) -> Result<(AppSettings, ToolPolicy), String> {
+    let settings = crate::db::read_settings_hydrated(conn)?;
+    let policy = crate::db::get_tool_policy(conn, GLOBAL_TOOL_POLICY_SCOPE)?.unwrap_or_default();
+    Ok((settings, policy))
+}
+
+/// Pull the sync repo into `dir` and merge its document into the settings in `db`.
+fn pull_into(db: &Db, dir: &Path) -> Result<SettingsSyncResult, String> {
+    let (settings, _) = read_local(&*db.0.read().map_err(|e| e.to_string())?)?;
+    let target = sync_target(&settings)?;
+    crate::git::pull_remote(dir, &target.url, &target.branch, target.token.as_deref())?;
+    let Some(doc) = read_document(dir)? else {
+        log::info!("[settings_sync] {} has no {} yet", target.url, SYNC_FILE);
+        return Ok(SettingsSyncResult { commit_sha: None, applied: Vec::new(), kept_local: Vec::new() });
+    };
+
+    // Merge against the settings as they are now, not before the fetch.
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let (settings, policy) = read_local(&conn)?;
+    let merged = merge_document(&settings, &policy, &doc)?;
+    if !merged.applied.is_empty() {
+        crate::db::write_settings(&conn, &merged.settings)?;
+    }
+    if let Some(policy) = merged.tool_policy.as_ref() {
+        crate::db::set_tool_policy(&conn, GLOBAL_TOOL_POLICY_SCOPE, Some(policy))?;
+    }
+    log::info!(
+        "[settings_sync] Applied {} shared setting(s); kept {} local override(s)",
+        merged.applied.len(),
+        merged.kept_local.len()
+    );
+    Ok(SettingsSyncResult { commit_sha: None, applied: merged.applied, kept_local: merged.kept_local })
+}
+
+/// Export the settings in `db` to the sync repo cloned at `dir` and push.
+fn push_from(db: &Db, dir: &Path) -> Result<SettingsSyncResult, String> {
+    let (settings, policy) = read_local(&*db.0.read().map_err(|e| e.to_string())?)?;
+    let target = sync_target(&settings)?;
+    crate::git::pull_remote(dir, &target.url, &target.branch, target.token.as_deref())?;
+    let existing = read_document(dir)?;
+    let doc = export_document(&settings, &policy, existing.as_ref())?;
+    // Only the timestamp would change: nothing to push.
+    if existing.as_ref().is_some_and(|e| e.settings == doc.settings) {
+        log::info!("[settings_sync] Shared settings already up to date");
+        return Ok(SettingsSyncResult { commit_sha: None, applied: Vec::new(), kept_local: Vec::new() });
+    }
+    let json = serde_json::to_string_pretty(&doc).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(SYNC_FILE), json + "\n").map_err(|e| format!("Failed to write {}: {}", SYNC_FILE, e))?;
+    let message = match doc.exported_by.as_deref() {
+        Some(login) => format!("Update shared settings ({})", login),
+        None => "Update shared settings".to_string(),
+    };
+    let commit_sha = crate::git::commit_all(dir, &message)?;
+    if commit_sha.is_some() {
+        crate::git::push_branch(dir, &target.branch, target.token.as_deref())?;
+    }
+    Ok(SettingsSyncResult { commit_sha, applied: Vec::new(), kept_local: Vec::new() })
+}
+
+/// The document `push_settings_sync` would write, for preview.
+#[tauri::command]
+pub fn get_settings_sync_document(db: tauri::State<'_, Db>) -> Result<SettingsSyncDocument, String> {
+    log::info!("[get_settings_sync_document]");
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[get_settings_sync_document] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let (settings, policy) = read_local(&conn)?;
+    export_document(&settings, &policy, None)
+}
+
+/// Commit this machine's shared settings to the sync repo and push them.
+#[tauri::command]
+pub async fn push_settings_sync(
+    app: tauri::AppHandle,
+    data_dir: tauri::State<'_, crate::DataDir>,
+) -> Result<SettingsSyncResult, String> {
+    log::info!("[push_settings_sync]");
+    let dir = sync_dir(&data_dir.0);
+    tokio::task::spawn_blocking(move || {
+        use tauri::Manager;
+        push_from(&app.state::<Db>(), &dir)
+    })
+    .await
+    .map_err(|e| format!("Settings sync failed: {}", e))?
+    .map_err(|e| {
+        log::error!("[push_settings_sync] {}", e);
+        e
+    })
+}
+
+/// Pull the sync repo and merge its settings into the local ones.
+#[tauri::command]
+pub async fn pull_settings_sync(
+    app: tauri::AppHandle,
+    data_dir: tauri::State<'_, crate::DataDir>,
+) -> Result<SettingsSyncResult, String> {
+    log::info!("[pull_settings_sync]");
+    let dir = sync_dir(&data_dir.0);
+    tokio::task::spawn_blocking(move || {
+        use tauri::Manager;
+        pull_into(&app.state::<Db>(), &dir)
+    })
+    .await
+    .map_err(|e| format!("Settings sync failed: {}", e))?
+    .map_err(|e| {
+        log::error!("[pull_settings_sync] {}", e);
+        e
+    })
+}
+
+/// Pull shared settings once at startup when a sync repo is configured and
+/// offline mode is off. Failures are logged; the local settings stay as they are.
+pub fn start(app: tauri::AppHandle, data_dir: PathBuf) {
+    std::thread::spawn(move || {
+        use tauri::Manager;
+        let db = app.state::<Db>();
+        let settings = match db.0.read().map_err(|e| e.to_string()).and_then(|conn| crate::db::read_settings(&conn)) {
+            Ok(settings) => settings,
+            Err(e) => {
+                log::warn!("[settings_sync] Could not read settings: {}", e);
+                return;
+            }
+        };
+        if settings.offline_mode || settings.settings_sync_repo.as_deref().is_none_or(|r| r.trim().is_empty()) {
+            return;
+        }
+        if let Err(e) = pull_into(&db, &sync_dir(&data_dir)) {
+            log::warn!("[settings_sync] Startup sync failed: {}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings_with_repo(url: &str) -> AppSettings {
+        AppSettings {
+            settings_sync_repo: Some(url.to_string()),
+            preferred_model: Some("sonnet".to_string()),
+            anthropic_api_key: Some("sk-ant-secret".to_string()),
+            skills_path: Some("/Users/me/skills".to_string()),
+            ..AppSettings::default()
+        }
+    }
+
+    fn test_db(settings: &AppSettings) -> Db {
+        let conn = crate::commands::test_utils::create_test_db();
+        crate::db::write_settings(&conn, settings).unwrap();
+        Db::new(conn)
+    }
+
+    #[test]
+    fn test_export_leaves_out_secrets_and_paths() {
+        let settings = settings_with_repo("/tmp/none");
+        let doc = export_document(&settings, &ToolPolicy::default(), None).unwrap();
+        assert_eq!(doc.settings["preferred_model"], "sonnet");
+        assert!(doc.settings.contains_key(TOOL_POLICY_KEY));
+        assert!(!doc.settings.contains_key("anthropic_api_key"));
+        assert!(!doc.settings.contains_key("skills_path"));
+        assert!(!doc.settings.contains_key("settings_sync_repo"));
+    }
+
+    #[test]
+    fn test_github_server_is_not_shared() {
+        let mut shared = settings_with_repo("/tmp/none");
+        shared.github_enterprise_url = Some("https://github.attacker.example".to_string());
+        shared.github_oauth_client_id = Some("Iv1.attacker".to_string());
+        let doc = export_document(&shared, &ToolPolicy::default(), None).unwrap();
+        assert!(!doc.settings.contains_key("github_enterprise_url"));
+        assert!(!doc.settings.contains_key("github_oauth_client_id"));
+
+        // A document naming a server anyway leaves the local one alone.
+        let mut doc = doc;
+        doc.settings.insert("github_enterprise_url".to_string(), "https://github.attacker.example".into());
+        let mut local = settings_with_repo("/tmp/none");
+        local.github_oauth_token = Some("gho_local".to_string());
+        let merged = merge_document(&local, &ToolPolicy::default(), &doc).unwrap();
+        assert!(merged.applied.is_empty());
+        assert!(merged.settings.github_enterprise_url.is_none());
+        assert_eq!(merged.settings.github_oauth_token.as_deref(), Some("gho_local"));
+    }
+
+    #[test]
+    fn test_token_is_only_offered_to_the_github_server() {
+        let mut settings = settings_with_repo("https://github.com/acme/settings.git");
+        settings.github_oauth_token = Some("gho_local".to_string());
+        settings.github_oauth_host = Some("https://github.com".to_string());
+        assert_eq!(sync_target(&settings).unwrap().token.as_deref(), Some("gho_local"));
+
+        for repo in [
+            "https://gitlab.example.com/acme/settings.git",
+            "https://github.com.attacker.example/acme/settings.git",
+            "git@github.com:acme/settings.git",
+            "/tmp/settings.git",
+        ] {
+            settings.settings_sync_repo = Some(repo.to_string());
+            assert!(sync_target(&settings).unwrap().token.is_none(), "{}", repo);
+        }
+
+        settings.settings_sync_repo = Some("https://github.com/acme/settings.git#main".to_string());
+        settings.github_oauth_host = None;
+        assert!(sync_target(&settings).unwrap().token.is_none());
+    }
+
+    #[test]
+    fn test_merge_prefers_local_overrides() {
+        let mut shared = settings_with_repo("/tmp/none");
+        shared.preferred_model = Some("opus".to_string());
+        shared.global_budget_usd = Some(50.0);
+        let doc = export_document(&shared, &ToolPolicy::default(), None).unwrap();
+
+        let mut local = settings_with_repo("/tmp/none");
+        local.settings_sync_local_overrides = vec!["preferred_model".to_string()];
+        let merged = merge_document(&local, &ToolPolicy::default(), &doc).unwrap();
+        assert_eq!(merged.applied, vec!["global_budget_usd"]);
+        assert_eq!(merged.kept_local, vec!["preferred_model"]);
+        assert_eq!(merged.settings.preferred_model.as_deref(), Some("sonnet"));
+        assert_eq!(merged.settings.global_budget_usd, Some(50.0));
+        assert_eq!(merged.settings.anthropic_api_key.as_deref(), Some("sk-ant-secret"));
+        assert!(merged.tool_policy.is_none());
+
+        let newer = SettingsSyncDocument { version: DOCUMENT_VERSION + 1, ..doc };
+        assert!(merge_document(&local, &ToolPolicy::default(), &newer).is_err());
+    }
+
+    #[test]
+    fn test_push_then_pull_through_git_repo() {
+        let tmp = tempfile::tempdir().unwrap();
+        let remote = tmp.path().join("remote.git");
+        git2::Repository::init_bare(&remote).unwrap();
+        let url = remote.to_string_lossy().to_string();
+
+        let mut first = settings_with_repo(&url);
+        first.preferred_model = Some("opus".to_string());
+        let first_db = test_db(&first);
+        let pushed = push_from(&first_db, &tmp.path().join("first")).unwrap();
+        assert!(pushed.commit_sha.is_some());
+        // Unchanged settings make no second commit.
+        assert!(push_from(&first_db, &tmp.path().join("first")).unwrap().commit_sha.is_none());
+
+        let second_db = test_db(&settings_with_repo(&url));
+        let pulled = pull_into(&second_db, &tmp.path().join("second")).unwrap();
+        assert_eq!(pulled.applied, vec!["preferred_model"]);
+        let conn = second_db.0.lock().unwrap();
+        let settings = crate::db::read_settings(&conn).unwrap();
+        assert_eq!(settings.preferred_model.as_deref(), Some("opus"));
+        assert_eq!(settings.settings_sync_repo.as_deref(), Some(url.as_str()));
+    }
+}
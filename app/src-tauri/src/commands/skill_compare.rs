@@ -0,0 +1,328 @@
+//! Compare two versions of a skill for review.
+//!
+//! A version is named by a ref: a commit SHA from `get_skill_history`,
+//! `working` for the files on disk, or `team` for the copy in the team
+//! repository from settings (where `push_skill_to_host` puts it, under
+//! `skills/<name>` on the default or `#branch` branch). The comparison lists
+//! the SKILL.md frontmatter fields that changed, the reference files added,
+//! removed and modified, and line counts for the body, so a reviewer can tell
+//! whether a team update is worth pulling before reading the full diff.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use super::imported_skills::validate_skill_name;
+use super::structured_diff::diff_file;
+use crate::db::Db;
+use crate::git_hosting;
+use crate::types::{SkillFileChangeStat, SkillVersionComparison, StructuredFileDiff};
+
+pub const REF_WORKING: &str = "working";
+pub const REF_TEAM: &str = "team";
+
+const SKILL_MD: &str = "SKILL.md";
+const REFERENCES_DIR: &str = "references/";
+/// Team repo files fetched per comparison; a larger skill is compared on its first files.
+const MAX_TEAM_FILES: usize = 200;
+
+/// A skill's files by path relative to the skill directory; binary files have no content.
+type SkillVersion = BTreeMap<String, Option<String>>;
+
+fn is_hidden(path: &str) -> bool {
+    path.split('/').any(|segment| segment.starts_with('.'))
+}
+
+fn working_version(skill_dir: &Path) -> Result<SkillVersion, String> {
+    if !skill_dir.is_dir() {
+        return Ok(SkillVersion::new());
+    }
+    let (files, skipped) = super::git_hosting::collect_skill_files(skill_dir)?;
+    let mut version: SkillVersion = files
+        .into_iter()
+        .map(|(path, content)| (path, Some(content)))
+        .collect();
+    // Binary and oversized files are skipped by the walk but still part of the skill.
+    for path in skipped {
+        if !is_hidden(&path) && skill_dir.join(&path).is_file() {
+            version.insert(path, None);
+        }
+    }
+    Ok(version)
+}
+
+/// A version that needs no network: `working` or a commit of the skills repo.
+fn local_version(
+    skills_root: &Path,
+    skill_name: &str,
+    reference: &str,
+) -> Result<SkillVersion, String> {
+    if reference == REF_WORKING {
+        return working_version(&skills_root.join(skill_name));
+    }
+    if !skills_root.join(".git").exists() {
+        return Err(format!("{} has no version history", skills_root.display()));
+    }
+    Ok(
+        crate::git::skill_files_at(skills_root, reference, skill_name)?
+            .into_iter()
+            .filter(|(path, _)| !is_hidden(path))
+            .collect(),
+    )
+}
+
+async fn team_version(db: &Db, skill_name: &str) -> Result<SkillVersion, String> {
+    super::offline::require_feature(
+        db,
+        super::offline::NetworkFeature::GitHub,
+        "compare_skill_versions",
+    )?;
+    let (team_repo, github, _) = super::team_taxonomy::read_team_repo(db)?;
+    let repo = git_hosting::parse_repo_url(&team_repo, &github)?;
+    let token = super::git_hosting::token_for(db, &repo)?;
+    let client = git_hosting::build_client(repo.host, token.as_deref(), &github)?;
+    let branch = git_hosting::resolve_branch(&client, &repo).await?;
+    let prefix = format!(
+        "{}/",
+        super::git_hosting::hosted_skill_path(repo.subpath.as_deref(), skill_name)
+    );
+
+    let paths: Vec<String> = git_hosting::list_files(&client, &repo, &branch)
+        .await?
+        .into_iter()
+        .filter(|path| {
+            path.strip_prefix(&prefix)
+                .is_some_and(|rel| !is_hidden(rel))
+        })
+        .collect();
+    if paths.len() > MAX_TEAM_FILES {
+        log::warn!(
+            "[compare_skill_versions] '{}' has {} files in {}; comparing the first {}",
+            skill_name,
+            paths.len(),
+            team_repo,
+            MAX_TEAM_FILES
+        );
+    }
+    let mut version = SkillVersion::new();
+    for path in paths.into_iter().take(MAX_TEAM_FILES) {
+        let content = git_hosting::get_text(&client, &repo, &branch, &path).await?;
+        version.insert(path[prefix.len()..].to_string(), content);
+    }
+    Ok(version)
+}
+
+async fn load_version(
+    db: &Db,
+    skills_root: &Path,
+    skill_name: &str,
+    reference: &str,
+) -> Result<SkillVersion, String> {
+    match reference {
+        "" => Err("A version ref is required: a commit SHA, \"working\" or \"team\"".to_string()),
+        REF_TEAM => team_version(db, skill_name).await,
+        _ => local_version(skills_root, skill_name, reference),
+    }
+}
+
+/// Lines added and removed by a file diff; each changed frontmatter field counts as a line.
+fn line_counts(diff: &StructuredFileDiff) -> (usize, usize) {
+    let mut added = 0;
+    let mut removed = 0;
+    for hunk in &diff.hunks {
+        added += hunk.new_text.lines().count();
+        removed += hunk.old_text.lines().count();
+    }
+    for field in &diff.frontmatter {
+        added += usize::from(field.new_value.is_some());
+        removed += usize::from(field.old_value.is_some());
+    }
+    (added, removed)
+}
+
+pub(crate) fn compare_versions(
+    skill_name: &str,
+    base_ref: &str,
+    target_ref: &str,
+    base: &SkillVersion,
+    target: &SkillVersion,
+) -> SkillVersionComparison {
+    let mut comparison = SkillVersionComparison {
+        skill_name: skill_name.to_string(),
+        base_ref: base_ref.to_string(),
+        target_ref: target_ref.to_string(),
+        frontmatter: Vec::new(),
+        added_references: Vec::new(),
+        removed_references: Vec::new(),
+        modified_references: Vec::new(),
+        body_hunks: 0,
+        body_lines_added: 0,
+        body_lines_removed: 0,
+        files: Vec::new(),
+    };
+    let paths: BTreeSet<&String> = base.keys().chain(target.keys()).collect();
+    for path in paths {
+        let (old, new) = (base.get(path), target.get(path));
+        if old == new {
+            continue;
+        }
+        let status = match (old, new) {
+            (None, Some(_)) => "added",
+            (Some(_), None) => "deleted",
+            _ => "modified",
+        };
+        let binary = matches!(old, Some(None)) || matches!(new, Some(None));
+        let (lines_added, lines_removed) = if binary {
+            (0, 0)
+        } else {
+            let diff = diff_file(
+                path,
+                old.and_then(Option::as_deref),
+                new.and_then(Option::as_deref),
+            );
+            if path == SKILL_MD {
+                comparison.body_hunks = diff.hunks.len();
+                comparison.body_lines_added =
+                    diff.hunks.iter().map(|h| h.new_text.lines().count()).sum();
+                comparison.body_lines_removed =
+                    diff.hunks.iter().map(|h| h.old_text.lines().count()).sum();
+                comparison.frontmatter = diff.frontmatter.clone();
+            }
+            line_counts(&diff)
+        };
+        if path.starts_with(REFERENCES_DIR) {
+            match status {
+                "added" => comparison.added_references.push(path.clone()),
+                "deleted" => comparison.removed_references.push(path.clone()),
+                _ => comparison.modified_references.push(path.clone()),
+            }
+        }
+        comparison.files.push(SkillFileChangeStat {
+            path: path.clone(),
+            status: status.to_string(),
+            text: !binary,
+            lines_added,
+            lines_removed,
+        });
+    }
+    comparison
+}
+
+/// Compare a skill between two refs: commit SHAs from `get_skill_history`,
+/// `working` (files on disk) or `team` (the copy in the team repository).
+#[tauri::command]
+pub async fn compare_skill_versions(
+    workspace_path: String,
+    skill_name: String,
+    base_ref: String,
+    target_ref: String,
+    db: tauri::State<'_, Db>,
+) -> Result<SkillVersionComparison, String> {
+    log::info!(
+        "[compare_skill_versions] skill={} base={} target={}",
+        skill_name,
+        base_ref,
+        target_ref
+    );
+    validate_skill_name(&skill_name)?;
+    let (base_ref, target_ref) = (base_ref.trim(), target_ref.trim());
+    let skills_root = super::git::resolve_output_root(&db, &workspace_path)?;
+    let skills_root = Path::new(&skills_root);
+    let result = async {
+        let base = load_version(&db, skills_root, &skill_name, base_ref).await?;
+        let target = load_version(&db, skills_root, &skill_name, target_ref).await?;
+        if base.is_empty() && target.is_empty() {
+            return Err(format!(
+                "Skill '{}' does not exist at {} or {}",
+                skill_name, base_ref, target_ref
+            ));
+        }
+        Ok(compare_versions(
+            &skill_name,
+            base_ref,
+            target_ref,
+            &base,
+            &target,
+        ))
+    }
+    .await;
+    result.map_err(|e| {
+        log::error!("[compare_skill_versions] {}", e);
+        e
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_commit_with_working_copy() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        crate::git::ensure_repo(root).unwrap();
+        let skill = root.join("sales");
+        std::fs::create_dir_all(skill.join("references")).unwrap();
+        std::fs::write(
+            skill.join("SKILL.md"),
+            "---\nname: sales\ndescription: Old\nversion: 1.0.0\n---\n# Sales\nKeep.\nDrop.\n",
+        )
+        .unwrap();
+        std::fs::write(skill.join("references/glossary.md"), "terms\n").unwrap();
+        std::fs::write(skill.join("references/old.md"), "old\n").unwrap();
+        let sha = crate::git::commit_all(root, "sales: v1").unwrap().unwrap();
+
+        std::fs::write(
+            skill.join("SKILL.md"),
+            "---\nname: sales\ndescription: New\nversion: 1.1.0\n---\n# Sales\nKeep.\nAdded one.\nAdded two.\n",
+        )
+        .unwrap();
+        std::fs::remove_file(skill.join("references/old.md")).unwrap();
+        std::fs::write(skill.join("references/glossary.md"), "terms\nmore\n").unwrap();
+        std::fs::write(skill.join("references/chart.png"), [0u8, 159, 146, 150]).unwrap();
+
+        let base = local_version(root, "sales", &sha[..10]).unwrap();
+        let target = local_version(root, "sales", REF_WORKING).unwrap();
+        let comparison = compare_versions("sales", &sha, REF_WORKING, &base, &target);
+
+        let fields: Vec<&str> = comparison
+            .frontmatter
+            .iter()
+            .map(|f| f.field.as_str())
+            .collect();
+        assert_eq!(fields, vec!["description", "version"]);
+        assert_eq!(
+            comparison.frontmatter[1].new_value.as_deref(),
+            Some("1.1.0")
+        );
+        assert_eq!(comparison.added_references, vec!["references/chart.png"]);
+        assert_eq!(comparison.removed_references, vec!["references/old.md"]);
+        assert_eq!(
+            comparison.modified_references,
+            vec!["references/glossary.md"]
+        );
+        assert_eq!(
+            (
+                comparison.body_hunks,
+                comparison.body_lines_added,
+                comparison.body_lines_removed
+            ),
+            (1, 2, 1)
+        );
+        let chart = comparison
+            .files
+            .iter()
+            .find(|f| f.path == "references/chart.png")
+            .unwrap();
+        assert!(!chart.text);
+        let skill_md = comparison
+            .files
+            .iter()
+            .find(|f| f.path == SKILL_MD)
+            .unwrap();
+        assert_eq!((skill_md.lines_added, skill_md.lines_removed), (4, 3));
+
+        let same = compare_versions("sales", REF_WORKING, REF_WORKING, &target, &target);
+        assert!(same.files.is_empty());
+        assert!(local_version(root, "sales", "not-a-ref").is_err());
+    }
+}
@@ -0,0 +1,409 @@
+//! Declared dependencies between skills ("use the salesforce-source skill for
+//! field mappings").
+//!
+//! Dependencies are stored one edge per row in `skill_dependencies` and must
+//! stay acyclic: `set_skill_dependencies` rejects a change that would close a
+//! cycle. A packaged skill lists its transitive dependencies in install order
+//! in `dependencies.json`; `package_skill` can also verify that each one is
+//! built or installed, or bundle them under `dependencies/{name}/`.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::imported_skills::{parse_frontmatter_full, validate_skill_name};
+use crate::db::Db;
+use crate::types::{SkillDependencyEdge, SkillDependencyGraph, SkillDependencyNode};
+
+/// Manifest written into packages of skills with dependencies.
+pub(crate) const DEPENDENCIES_FILE: &str = "dependencies.json";
+
+/// `package_skill` dependency modes: fail when a dependency is unavailable,
+/// or additionally copy every dependency into the package.
+pub(crate) const DEPENDENCIES_VERIFY: &str = "verify";
+pub(crate) const DEPENDENCIES_BUNDLE: &str = "bundle";
+
+fn adjacency(edges: &[(String, String)]) -> BTreeMap<&str, Vec<&str>> {
+    let mut graph: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (skill, dependency) in edges {
+        graph.entry(skill).or_default().push(dependency);
+    }
+    graph
+}
+
+/// Cycles in the graph, each rotated to start at its alphabetically first
+/// skill and listed once.
+pub(crate) fn find_cycles(edges: &[(String, String)]) -> Vec<Vec<String>> {
+    fn visit<'a>(
+        node: &'a str,
+        graph: &BTreeMap<&'a str, Vec<&'a str>>,
+        stack: &mut Vec<&'a str>,
+        done: &mut HashSet<&'a str>,
+        cycles: &mut Vec<Vec<String>>,
+    ) {
+        if done.contains(node) {
+            return;
+        }
+        if let Some(pos) = stack.iter().position(|n| *n == node) {
+            let mut cycle: Vec<String> = stack[pos..].iter().map(|n| n.to_string()).collect();
+            let first = cycle
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, name)| name.as_str())
+                .map_or(0, |(i, _)| i);
+            cycle.rotate_left(first);
+            if !cycles.contains(&cycle) {
+                cycles.push(cycle);
+            }
+            return;
+        }
+        stack.push(node);
+        for &next in graph.get(node).into_iter().flatten() {
+            visit(next, graph, stack, done, cycles);
+        }
+        stack.pop();
+        done.insert(node);
+    }
+
+    let graph = adjacency(edges);
+    let mut done = HashSet::new();
+    let mut cycles = Vec::new();
+    for &node in graph.keys() {
+        visit(node, &graph, &mut Vec::new(), &mut done, &mut cycles);
+    }
+    cycles
+}
+
+fn describe_cycle(cycle: &[String]) -> String {
+    let mut names = cycle.to_vec();
+    names.extend(cycle.first().cloned());
+    names.join(" → ")
+}
+
+/// Every skill `skill_name` depends on, directly or not, dependencies first.
+pub(crate) fn install_order(edges: &[(String, String)], skill_name: &str) -> Vec<String> {
+    fn visit<'a>(
+        node: &'a str,
+        graph: &BTreeMap<&'a str, Vec<&'a str>>,
+        seen: &mut HashSet<&'a str>,
+        order: &mut Vec<String>,
+    ) {
+        if !seen.insert(node) {
+            return;
+        }
+        for &next in graph.get(node).into_iter().flatten() {
+            visit(next, graph, seen, order);
+        }
+        order.push(node.to_string());
+    }
+
+    let graph = adjacency(edges);
+    let mut order = Vec::new();
+    visit(skill_name, &graph, &mut HashSet::new(), &mut order);
+    // The skill itself finishes last.
+    order.pop();
+    order
+}
+
+/// Where a skill's files live — the skills path for built skills, else the
+/// installed workspace skill — with its version when known.
+fn locate_skill(
+    conn: &rusqlite::Connection,
+    skills_path: Option<&str>,
+    name: &str,
+) -> Result<Option<(PathBuf, Option<String>)>, String> {
+    if let Some(skills_path) = skills_path {
+        let dir = Path::new(skills_path).join(name);
+        if let Ok(content) = std::fs::read_to_string(dir.join("SKILL.md")) {
+            return Ok(Some((dir, parse_frontmatter_full(&content).version)));
+        }
+    }
+    Ok(crate::db::get_workspace_skill_by_name(conn, name)?
+        .map(|skill| (PathBuf::from(skill.disk_path), skill.version))
+        .filter(|(dir, _)| dir.join("SKILL.md").is_file()))
+}
+
+fn set_skill_dependencies_inner(
+    conn: &rusqlite::Connection,
+    skill_name: &str,
+    depends_on: &[String],
+) -> Result<(), String> {
+    validate_skill_name(skill_name)?;
+    let mut dependencies = BTreeSet::new();
+    for dependency in depends_on.iter().map(|d| d.trim()) {
+        validate_skill_name(dependency)?;
+        if dependency == skill_name {
+            return Err(format!("Skill '{}' cannot depend on itself", skill_name));
+        }
+        dependencies.insert(dependency.to_string());
+    }
+
+    let mut edges: Vec<(String, String)> = crate::db::list_skill_dependencies(conn)?
+        .into_iter()
+        .filter(|(skill, _)| skill != skill_name)
+        .collect();
+    edges.extend(dependencies.iter().map(|d| (skill_name.to_string(), d.clone())));
+    if let Some(cycle) = find_cycles(&edges).into_iter().find(|c| c.iter().any(|n| n == skill_name)) {
+        return Err(format!("Dependency cycle: {}", describe_cycle(&cycle)));
+    }
+
+    let dependencies: Vec<String> = dependencies.into_iter().collect();
+    crate::db::set_skill_dependencies(conn, skill_name, &dependencies)
+}
+
+fn dependency_graph(conn: &rusqlite::Connection) -> Result<SkillDependencyGraph, String> {
+    let edges = crate::db::list_skill_dependencies(conn)?;
+    let skills_path = crate::db::read_settings(conn)?.skills_path;
+    let names: BTreeSet<&str> = edges
+        .iter()
+        .flat_map(|(skill, dependency)| [skill.as_str(), dependency.as_str()])
+        .collect();
+    let mut nodes = Vec::new();
+    for name in names {
+        let located = locate_skill(conn, skills_path.as_deref(), name)?;
+        nodes.push(SkillDependencyNode {
+            name: name.to_string(),
+            available: located.is_some(),
+            version: located.and_then(|(_, version)| version),
+        });
+    }
+    Ok(SkillDependencyGraph {
+        nodes,
+        cycles: find_cycles(&edges),
+        edges: edges
+            .into_iter()
+            .map(|(skill_name, depends_on)| SkillDependencyEdge { skill_name, depends_on })
+            .collect(),
+    })
+}
+
+/// Dependencies resolved for one package.
+#[derive(Debug)]
+pub(crate) struct PackagedDependencies {
+    /// Install order, dependencies first.
+    pub names: Vec<String>,
+    manifest: String,
+    /// `(name, skill dir)` to copy into the package in bundle mode.
+    bundle: Vec<(String, PathBuf)>,
+}
+
+/// Resolve `skill_name`'s dependencies for packaging. `Ok(None)` when it
+/// declares none. With a `mode`, every dependency must be available.
+pub(crate) fn prepare_for_packaging(
+    conn: &rusqlite::Connection,
+    skill_name: &str,
+    mode: Option<&str>,
+) -> Result<Option<PackagedDependencies>, String> {
+    if let Some(other) = mode.filter(|m| *m != DEPENDENCIES_VERIFY && *m != DEPENDENCIES_BUNDLE) {
+        return Err(format!(
+            "Unknown dependency mode '{}': expected {} or {}",
+            other, DEPENDENCIES_VERIFY, DEPENDENCIES_BUNDLE
+        ));
+    }
+    let edges = crate::db::list_skill_dependencies(conn)?;
+    let names = install_order(&edges, skill_name);
+    if names.is_empty() {
+        return Ok(None);
+    }
+    if let Some(cycle) = find_cycles(&edges)
+        .into_iter()
+        .find(|c| c.iter().any(|n| n == skill_name || names.contains(n)))
+    {
+        return Err(format!(
+            "Cannot package '{}': dependency cycle {}",
+            skill_name,
+            describe_cycle(&cycle)
+        ));
+    }
+
+    let skills_path = crate::db::read_settings(conn)?.skills_path;
+    let bundling = mode == Some(DEPENDENCIES_BUNDLE);
+    let mut entries = Vec::new();
+    let mut bundle = Vec::new();
+    let mut missing = Vec::new();
+    for name in &names {
+        let located = locate_skill(conn, skills_path.as_deref(), name)?;
+        let bundled = bundling && located.is_some();
+        entries.push(serde_json::json!({
+            "name": name,
+            "version": located.as_ref().and_then(|(_, version)| version.clone()),
+            "bundled": bundled,
+            "path": bundled.then(|| format!("dependencies/{}", name)),
+        }));
+        match located {
+            Some((dir, _)) if bundled => bundle.push((name.clone(), dir)),
+            Some(_) => {}
+            None => missing.push(name.as_str()),
+        }
+    }
+    if mode.is_some() && !missing.is_empty() {
+        return Err(format!(
+            "'{}' depends on skills that are neither built nor installed: {}",
+            skill_name,
+            missing.join(", ")
+        ));
+    }
+
+    let manifest = serde_json::to_string_pretty(&serde_json::json!({
+        "skill": skill_name,
+        "dependencies": entries,
+    }))
+    .map_err(|e| e.to_string())?;
+    Ok(Some(PackagedDependencies { names, manifest, bundle }))
+}
+
+/// Add `dependencies.json` and any bundled skills to a package written by
+/// `create_skill_zip`. Returns the new package size.
+pub(crate) fn append_to_package(output_path: &Path, dependencies: &PackagedDependencies) -> Result<u64, String> {
+    use std::io::Write;
+
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(output_path)
+        .map_err(|e| format!("Failed to open {}: {}", output_path.display(), e))?;
+    let mut zip = zip::ZipWriter::new_append(file).map_err(|e| format!("Failed to reopen package: {}", e))?;
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    zip.start_file(DEPENDENCIES_FILE, options)
+        .map_err(|e| format!("Failed to add {} to zip: {}", DEPENDENCIES_FILE, e))?;
+    zip.write_all(dependencies.manifest.as_bytes())
+        .map_err(|e| format!("Failed to write {} to zip: {}", DEPENDENCIES_FILE, e))?;
+    for (name, dir) in &dependencies.bundle {
+        let prefix = format!("dependencies/{}", name);
+        super::workflow::add_file_to_zip(&mut zip, &dir.join("SKILL.md"), &format!("{}/SKILL.md", prefix), options)?;
+        let references = dir.join("references");
+        if references.is_dir() {
+            super::workflow::add_dir_to_zip(&mut zip, &references, &format!("{}/references", prefix), options)?;
+        }
+    }
+    zip.finish().map_err(|e| format!("Failed to finalize zip: {}", e))?;
+
+    std::fs::metadata(output_path)
+        .map(|m| m.len())
+        .map_err(|e| format!("Failed to read zip metadata: {}", e))
+}
+
+/// Replace the skills `skill_name` depends on. Rejects unknown-format names,
+/// self-dependencies and changes that would create a cycle, then returns the
+/// updated graph.
+#[tauri::command]
+pub fn set_skill_dependencies(
+    skill_name: String,
+    depends_on: Vec<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<SkillDependencyGraph, String> {
+    log::info!("[set_skill_dependencies] skill={} depends_on={:?}", skill_name, depends_on);
+    super::observer::ensure_writable(&skill_name)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[set_skill_dependencies] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    set_skill_dependencies_inner(&conn, &skill_name, &depends_on).map_err(|e| {
+        log::error!("[set_skill_dependencies] {}", e);
+        e
+    })?;
+    dependency_graph(&conn)
+}
+
+/// Every declared dependency, whether each skill involved is available, and
+/// any cycles.
+#[tauri::command]
+pub fn get_dependency_graph(db: tauri::State<'_, Db>) -> Result<SkillDependencyGraph, String> {
+    log::info!("[get_dependency_graph]");
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[get_dependency_graph] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    dependency_graph(&conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(a, b)| (a.to_string(), b.to_string())).collect()
+    }
+
+    #[test]
+    fn test_find_cycles_and_install_order() {
+        let acyclic = edges(&[("report", "revenue"), ("revenue", "salesforce"), ("report", "salesforce")]);
+        assert!(find_cycles(&acyclic).is_empty());
+        assert_eq!(install_order(&acyclic, "report"), vec!["salesforce", "revenue"]);
+        assert!(install_order(&acyclic, "salesforce").is_empty());
+
+        let cyclic = edges(&[("b", "c"), ("c", "a"), ("a", "b"), ("d", "a")]);
+        assert_eq!(find_cycles(&cyclic), vec![vec!["a", "b", "c"]]);
+        assert_eq!(describe_cycle(&find_cycles(&cyclic)[0]), "a → b → c → a");
+    }
+
+    #[test]
+    fn test_set_dependencies_rejects_cycles_and_self() {
+        let conn = crate::commands::test_utils::create_test_db();
+        set_skill_dependencies_inner(&conn, "report", &["revenue".to_string(), "revenue".to_string()]).unwrap();
+        set_skill_dependencies_inner(&conn, "revenue", &["salesforce".to_string()]).unwrap();
+        assert_eq!(
+            crate::db::list_skill_dependencies(&conn).unwrap(),
+            edges(&[("report", "revenue"), ("revenue", "salesforce")])
+        );
+
+        let err = set_skill_dependencies_inner(&conn, "salesforce", &["report".to_string()]).unwrap_err();
+        assert_eq!(err, "Dependency cycle: report → revenue → salesforce → report");
+        let err = set_skill_dependencies_inner(&conn, "report", &["report".to_string()]).unwrap_err();
+        assert!(err.contains("cannot depend on itself"));
+
+        // Replacing the edge that closed the cycle is fine
+        set_skill_dependencies_inner(&conn, "report", &[]).unwrap();
+        set_skill_dependencies_inner(&conn, "salesforce", &["report".to_string()]).unwrap();
+    }
+
+    #[test]
+    fn test_prepare_and_bundle_dependencies() {
+        let tmp = tempfile::tempdir().unwrap();
+        let conn = crate::commands::test_utils::create_test_db();
+        let skills_path = tmp.path().to_string_lossy().to_string();
+        let mut settings = crate::db::read_settings(&conn).unwrap();
+        settings.skills_path = Some(skills_path.clone());
+        crate::db::write_settings(&conn, &settings).unwrap();
+        set_skill_dependencies_inner(&conn, "report", &["revenue".to_string()]).unwrap();
+        set_skill_dependencies_inner(&conn, "revenue", &["salesforce".to_string()]).unwrap();
+
+        let revenue = tmp.path().join("revenue");
+        std::fs::create_dir_all(revenue.join("references")).unwrap();
+        std::fs::write(revenue.join("SKILL.md"), "---\nname: revenue\nversion: 1.4.0\n---\n# Revenue").unwrap();
+        std::fs::write(revenue.join("references").join("arr.md"), "# ARR").unwrap();
+
+        // salesforce is neither built nor installed
+        let declared = prepare_for_packaging(&conn, "report", None).unwrap().unwrap();
+        assert_eq!(declared.names, vec!["salesforce", "revenue"]);
+        let err = prepare_for_packaging(&conn, "report", Some(DEPENDENCIES_VERIFY)).unwrap_err();
+        assert!(err.contains("neither built nor installed: salesforce"));
+        assert!(prepare_for_packaging(&conn, "report", Some("copy")).is_err());
+        assert!(prepare_for_packaging(&conn, "salesforce", Some(DEPENDENCIES_BUNDLE)).unwrap().is_none());
+
+        let salesforce = tmp.path().join("salesforce");
+        std::fs::create_dir_all(&salesforce).unwrap();
+        std::fs::write(salesforce.join("SKILL.md"), "# Salesforce").unwrap();
+        let bundled = prepare_for_packaging(&conn, "report", Some(DEPENDENCIES_BUNDLE)).unwrap().unwrap();
+
+        let report = tmp.path().join("report");
+        std::fs::create_dir_all(&report).unwrap();
+        std::fs::write(report.join("SKILL.md"), "# Report").unwrap();
+        let output = tmp.path().join("report.skill");
+        let packaged = crate::commands::workflow::create_skill_zip(&report, &output).unwrap();
+        let size = append_to_package(&output, &bundled).unwrap();
+        assert!(size > packaged.size_bytes);
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&output).unwrap()).unwrap();
+        let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        assert!(names.contains(&"SKILL.md".to_string()));
+        assert!(names.contains(&"dependencies/revenue/references/arr.md".to_string()));
+        assert!(names.contains(&"dependencies/salesforce/SKILL.md".to_string()));
+        let mut manifest = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name(DEPENDENCIES_FILE).unwrap(), &mut manifest).unwrap();
+        let manifest: serde_json::Value = serde_json::from_str(&manifest).unwrap();
+        assert_eq!(manifest["dependencies"][1]["name"], "revenue");
+        assert_eq!(manifest["dependencies"][1]["version"], "1.4.0");
+        assert_eq!(manifest["dependencies"][1]["path"], "dependencies/revenue");
+    }
+}
@@ -0,0 +1,297 @@
+//! Blast-radius report shown before a skill is deleted.
+//!
+//! Everything here is read-only and local: dependents are found by scanning
+//! other skills' SKILL.md and reference files for mentions of the skill name,
+//! team consumers come from the cached marketplace listings (no network), and
+//! run stats from `agent_runs`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::imported_skills::validate_skill_name;
+use super::refine::RefineSessionManager;
+use crate::db::Db;
+use crate::types::{SkillDependent, SkillImpactReport, SkillInstallation, SkillTeamConsumer};
+
+/// Window for `recent_runs`.
+const RECENT_RUN_DAYS: u32 = 30;
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_'
+}
+
+/// Whether `content` refers to the skill `name`. The name must appear as a
+/// whole token; single-word names additionally need a `/name` or `` `name` ``
+/// form so common words don't count as references.
+fn mentions_skill(content: &str, name: &str) -> bool {
+    let distinctive = name.contains('-');
+    content.match_indices(name).any(|(i, _)| {
+        let prev = content[..i].chars().next_back();
+        let next = content[i + name.len()..].chars().next();
+        if prev.is_some_and(is_name_char) || next.is_some_and(is_name_char) {
+            return false;
+        }
+        distinctive || matches!(prev, Some('/') | Some('`'))
+    })
+}
+
+/// SKILL.md plus every markdown file under `references/`, relative to `skill_dir`.
+pub(crate) fn skill_markdown_files(skill_dir: &Path) -> Vec<String> {
+    let mut files = Vec::new();
+    if skill_dir.join("SKILL.md").is_file() {
+        files.push("SKILL.md".to_string());
+    }
+    let mut stack = vec![skill_dir.join("references")];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else if path.extension().is_some_and(|e| e == "md") {
+                if let Ok(rel) = path.strip_prefix(skill_dir) {
+                    files.push(rel.to_string_lossy().replace('\\', "/"));
+                }
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+fn find_dependents(roots: &[PathBuf], skill_name: &str) -> Vec<SkillDependent> {
+    let mut found: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for root in roots {
+        let Ok(entries) = std::fs::read_dir(root) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let dir = entry.path();
+            let other = entry.file_name().to_string_lossy().to_string();
+            if other == skill_name || other.starts_with('.') || !dir.is_dir() {
+                continue;
+            }
+            for file in skill_markdown_files(&dir) {
+                let mentioned = std::fs::read_to_string(dir.join(&file))
+                    .is_ok_and(|content| mentions_skill(&content, skill_name));
+                if mentioned {
+                    let files = found.entry(other.clone()).or_default();
+                    if !files.contains(&file) {
+                        files.push(file);
+                    }
+                }
+            }
+        }
+    }
+    found
+        .into_iter()
+        .map(|(skill_name, files)| SkillDependent { skill_name, files })
+        .collect()
+}
+
+fn analyze_skill_impact_inner(
+    conn: &rusqlite::Connection,
+    workspace_path: &str,
+    skills_path: Option<&str>,
+    home_dir: Option<&Path>,
+    skill_name: &str,
+    open_refine_sessions: usize,
+) -> Result<SkillImpactReport, String> {
+    let workspace_skills = Path::new(workspace_path).join(".claude").join("skills");
+    let user_skills = home_dir.map(|h| h.join(".claude").join("skills"));
+
+    let mut locations: Vec<(&str, PathBuf)> = Vec::new();
+    if let Some(sp) = skills_path {
+        locations.push(("skills_library", PathBuf::from(sp)));
+    }
+    locations.push(("workspace", workspace_skills.clone()));
+    locations.push(("workspace_inactive", workspace_skills.join(".inactive")));
+    if let Some(ref user) = user_skills {
+        locations.push(("user", user.clone()));
+    }
+
+    let installations = locations
+        .iter()
+        .map(|(location, root)| (location, root.join(skill_name)))
+        .filter(|(_, path)| path.is_dir())
+        .map(|(location, path)| SkillInstallation {
+            location: location.to_string(),
+            path: path.to_string_lossy().to_string(),
+        })
+        .collect();
+
+    let roots: Vec<PathBuf> = locations.into_iter().map(|(_, root)| root).collect();
+    let dependents = find_dependents(&roots, skill_name);
+
+    let team_consumers = crate::db::list_cached_catalogs(conn)?
+        .into_iter()
+        .flat_map(|catalog| {
+            let registry = catalog.cache_key;
+            let registry_name = catalog.registry_name;
+            catalog
+                .skills
+                .into_iter()
+                .filter(|s| s.name == skill_name)
+                .map(move |s| SkillTeamConsumer {
+                    registry: registry.clone(),
+                    registry_name: registry_name.clone(),
+                    skill_path: s.path,
+                    version: s.version,
+                })
+        })
+        .collect();
+
+    Ok(SkillImpactReport {
+        skill_name: skill_name.to_string(),
+        dependents,
+        installations,
+        team_consumers,
+        recent_runs: crate::db::get_skill_run_stats(conn, skill_name, RECENT_RUN_DAYS)?,
+        open_refine_sessions,
+        active_workflow_session: crate::db::has_active_session_with_live_pid(conn, skill_name),
+    })
+}
+
+/// Report what deleting `skill_name` would affect: skills that reference it,
+/// where it is installed, team registries listing it, recent agent runs, and
+/// open sessions.
+#[tauri::command]
+pub fn analyze_skill_impact(
+    workspace_path: String,
+    skill_name: String,
+    db: tauri::State<'_, Db>,
+    refine_sessions: tauri::State<'_, RefineSessionManager>,
+) -> Result<SkillImpactReport, String> {
+    log::info!("[analyze_skill_impact] skill={}", skill_name);
+    validate_skill_name(&skill_name)?;
+    let open_refine_sessions = refine_sessions
+        .0
+        .lock()
+        .map_err(|e| {
+            log::error!("[analyze_skill_impact] Failed to acquire refine sessions lock: {}", e);
+            e.to_string()
+        })?
+        .values()
+        .filter(|s| s.skill_name == skill_name)
+        .count();
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[analyze_skill_impact] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let skills_path = crate::db::read_settings(&conn)?.skills_path;
+    analyze_skill_impact_inner(
+        &conn,
+        &workspace_path,
+        skills_path.as_deref(),
+        dirs::home_dir().as_deref(),
+        &skill_name,
+        open_refine_sessions,
+    )
+    .map_err(|e| {
+        log::error!("[analyze_skill_impact] {}", e);
+        e
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::create_test_db;
+    use crate::types::{AvailableSkill, CachedCatalog};
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_mentions_skill_requires_whole_token() {
+        assert!(mentions_skill("Run data-modeling first.", "data-modeling"));
+        assert!(!mentions_skill("see data-modeling-v2", "data-modeling"));
+        assert!(!mentions_skill("the dbt project", "dbt"));
+        assert!(mentions_skill("then call /dbt with the model", "dbt"));
+        assert!(mentions_skill("uses the `dbt` skill", "dbt"));
+    }
+
+    #[test]
+    fn test_analyze_skill_impact_collects_all_sources() {
+        let tmp = tempdir().unwrap();
+        let workspace = tmp.path().join("workspace");
+        let library = tmp.path().join("skills");
+        let home = tmp.path().join("home");
+
+        fs::create_dir_all(library.join("data-modeling")).unwrap();
+        fs::write(library.join("data-modeling/SKILL.md"), "# Data modeling").unwrap();
+        fs::create_dir_all(library.join("reporting/references/guides")).unwrap();
+        fs::write(library.join("reporting/SKILL.md"), "# Reporting").unwrap();
+        fs::write(
+            library.join("reporting/references/guides/setup.md"),
+            "Build tables with data-modeling before reporting.",
+        )
+        .unwrap();
+        fs::create_dir_all(workspace.join(".claude/skills/.inactive/data-modeling")).unwrap();
+        fs::create_dir_all(home.join(".claude/skills/notes")).unwrap();
+        fs::write(home.join(".claude/skills/notes/SKILL.md"), "Unrelated: data-modeling-v2").unwrap();
+
+        let conn = create_test_db();
+        let listed = |name: &str| AvailableSkill {
+            path: format!("skills/{}", name),
+            name: name.to_string(),
+            plugin_name: None,
+            description: None,
+            purpose: None,
+            version: Some("1.2.0".to_string()),
+            model: None,
+            argument_hint: None,
+            user_invocable: None,
+            disable_model_invocation: None,
+        };
+        crate::db::save_cached_catalog(
+            &conn,
+            &CachedCatalog {
+                cache_key: "github.com/acme/skills@main".to_string(),
+                tree_sha: None,
+                blob_shas: Default::default(),
+                registry_name: Some("Acme".to_string()),
+                skills: vec![listed("data-modeling"), listed("other")],
+                fetched_at: "2026-01-01T00:00:00Z".to_string(),
+            },
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO agent_runs (agent_id, skill_name, step_id, model, status, total_cost, started_at)
+             VALUES ('a1', 'data-modeling', 0, 'sonnet', 'completed', 0.5, datetime('now')),
+                    ('a2', 'data-modeling', 1, 'sonnet', 'error', 0.25, datetime('now')),
+                    ('a3', 'data-modeling', 1, 'sonnet', 'completed', 9.0, datetime('now', '-60 days'))",
+            [],
+        )
+        .unwrap();
+
+        let report = analyze_skill_impact_inner(
+            &conn,
+            workspace.to_str().unwrap(),
+            Some(library.to_str().unwrap()),
+            Some(&home),
+            "data-modeling",
+            1,
+        )
+        .unwrap();
+
+        assert_eq!(
+            report.dependents,
+            vec![SkillDependent {
+                skill_name: "reporting".to_string(),
+                files: vec!["references/guides/setup.md".to_string()],
+            }]
+        );
+        let locations: Vec<&str> = report.installations.iter().map(|i| i.location.as_str()).collect();
+        assert_eq!(locations, vec!["skills_library", "workspace_inactive"]);
+        assert_eq!(report.team_consumers.len(), 1);
+        assert_eq!(report.team_consumers[0].registry_name.as_deref(), Some("Acme"));
+        assert_eq!(report.team_consumers[0].version.as_deref(), Some("1.2.0"));
+        assert_eq!(report.recent_runs.runs, 2);
+        assert_eq!(report.recent_runs.completed_runs, 1);
+        assert!((report.recent_runs.total_cost - 0.75).abs() < 1e-9);
+        assert_eq!(report.open_refine_sessions, 1);
+        assert!(!report.active_workflow_session);
+    }
+}
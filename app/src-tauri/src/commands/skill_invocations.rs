@@ -0,0 +1,295 @@
+//! Skill usage imported from Claude Code's local session transcripts.
+//!
+//! Claude Code writes each session to `projects/<project>/<session>.jsonl`
+//! under its data directory (`~/.claude` unless `claude_data_dir` is set in
+//! settings). A skill shows up there in two ways: the model calls the `Skill`
+//! tool (`input.skill`, or `input.command` in older versions), or the user
+//! types `/name`, recorded as `<command-name>/name</command-name>` in the
+//! user message. `import_skill_invocations` scans those files, keeps the
+//! invocations of skills this app knows about, and stores them keyed by tool
+//! use or message id, so importing again only adds what is new.
+
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use crate::db::Db;
+use crate::types::{SkillInvocation, SkillInvocationImport, SkillInvocationStats};
+
+const TRIGGER_MODEL: &str = "model";
+const TRIGGER_USER: &str = "user";
+const COMMAND_NAME_OPEN: &str = "<command-name>";
+const COMMAND_NAME_CLOSE: &str = "</command-name>";
+
+fn claude_data_dir(configured: Option<&str>) -> Result<PathBuf, String> {
+    match configured.map(str::trim).filter(|dir| !dir.is_empty()) {
+        Some(dir) => Ok(PathBuf::from(dir)),
+        None => dirs::home_dir()
+            .map(|home| home.join(".claude"))
+            .ok_or_else(|| "Could not determine home directory".to_string()),
+    }
+}
+
+/// Every `.jsonl` file under `dir`, including sub-agent transcripts.
+fn transcript_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            transcript_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "jsonl") {
+            files.push(path);
+        }
+    }
+}
+
+/// The skill a `/plugin:name` command or `plugin:name` tool input refers to.
+fn bare_skill_name(raw: &str) -> &str {
+    let name = raw.trim().trim_start_matches('/');
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn command_name(text: &str) -> Option<&str> {
+    let start = text.find(COMMAND_NAME_OPEN)? + COMMAND_NAME_OPEN.len();
+    let len = text[start..].find(COMMAND_NAME_CLOSE)?;
+    Some(&text[start..start + len])
+}
+
+/// Skill invocations in one transcript line. Names are as invoked; matching
+/// them to installed skills is left to the caller.
+fn parse_line(line: &str) -> Vec<SkillInvocation> {
+    // Most lines are neither; skip them without parsing.
+    if !line.contains("\"Skill\"") && !line.contains(COMMAND_NAME_OPEN) {
+        return Vec::new();
+    }
+    let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) else {
+        return Vec::new();
+    };
+    let Some(invoked_at) = entry["timestamp"].as_str() else {
+        return Vec::new();
+    };
+    let session_id = entry["sessionId"].as_str().map(str::to_string);
+    let project_path = entry["cwd"].as_str().map(str::to_string);
+    let invocation = |event_id: String, skill_name: &str, triggered_by: &str| SkillInvocation {
+        event_id,
+        skill_name: bare_skill_name(skill_name).to_string(),
+        triggered_by: triggered_by.to_string(),
+        session_id: session_id.clone(),
+        project_path: project_path.clone(),
+        invoked_at: invoked_at.to_string(),
+    };
+    let content = &entry["message"]["content"];
+
+    match entry["type"].as_str() {
+        Some("assistant") => content
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|block| block["type"] == "tool_use" && block["name"] == "Skill")
+            .filter_map(|block| {
+                let id = block["id"].as_str()?;
+                let input = &block["input"];
+                let name = input["skill"].as_str().or(input["command"].as_str())?;
+                Some(invocation(id.to_string(), name, TRIGGER_MODEL))
+            })
+            .collect(),
+        Some("user") => {
+            let text = match content {
+                serde_json::Value::String(text) => Some(text.as_str()),
+                serde_json::Value::Array(blocks) => blocks
+                    .iter()
+                    .filter_map(|block| block["text"].as_str())
+                    .find(|text| text.contains(COMMAND_NAME_OPEN)),
+                _ => None,
+            };
+            match (entry["uuid"].as_str(), text.and_then(command_name)) {
+                (Some(uuid), Some(name)) => vec![invocation(uuid.to_string(), name, TRIGGER_USER)],
+                _ => Vec::new(),
+            }
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Invocations in every transcript under `<claude_dir>/projects`, and how
+/// many files were read.
+fn scan_transcripts(claude_dir: &Path) -> Result<(usize, Vec<SkillInvocation>), String> {
+    let projects = claude_dir.join("projects");
+    if !projects.is_dir() {
+        return Err(format!(
+            "No Claude Code session transcripts found in {}",
+            projects.display()
+        ));
+    }
+    let mut files = Vec::new();
+    transcript_files(&projects, &mut files);
+    let mut invocations = Vec::new();
+    for file in &files {
+        let reader = match std::fs::File::open(file) {
+            Ok(f) => std::io::BufReader::new(f),
+            Err(e) => {
+                log::warn!("[import_skill_invocations] Skipping {}: {}", file.display(), e);
+                continue;
+            }
+        };
+        for line in reader.lines().map_while(Result::ok) {
+            invocations.extend(parse_line(&line));
+        }
+    }
+    Ok((files.len(), invocations))
+}
+
+/// Keep the invocations of `installed` skills (case-insensitively), renamed
+/// to the installed spelling. Returns them and how many were dropped.
+fn match_installed(
+    invocations: Vec<SkillInvocation>,
+    installed: &[String],
+) -> (Vec<SkillInvocation>, usize) {
+    let by_lower: HashMap<String, &String> = installed
+        .iter()
+        .map(|name| (name.to_lowercase(), name))
+        .collect();
+    let total = invocations.len();
+    let matched: Vec<SkillInvocation> = invocations
+        .into_iter()
+        .filter_map(|mut inv| {
+            let name = by_lower.get(&inv.skill_name.to_lowercase())?;
+            inv.skill_name = name.to_string();
+            Some(inv)
+        })
+        .collect();
+    let unmatched = total - matched.len();
+    (matched, unmatched)
+}
+
+/// Import skill invocations from Claude Code's session transcripts under
+/// `source_dir`, or the `claude_data_dir` setting, or `~/.claude`.
+#[tauri::command]
+pub async fn import_skill_invocations(
+    source_dir: Option<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<SkillInvocationImport, String> {
+    log::info!("[import_skill_invocations] source_dir={:?}", source_dir);
+    let (claude_dir, installed) = {
+        let conn = db.0.read().map_err(|e| {
+            log::error!("[import_skill_invocations] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        let configured = source_dir.or(crate::db::read_settings(&conn)?.claude_data_dir);
+        (
+            claude_data_dir(configured.as_deref())?,
+            crate::db::get_all_installed_skill_names(&conn)?,
+        )
+    };
+
+    let dir = claude_dir.clone();
+    let (files_scanned, invocations) = tokio::task::spawn_blocking(move || scan_transcripts(&dir))
+        .await
+        .map_err(|e| format!("Transcript scan failed: {}", e))?
+        .map_err(|e| {
+            log::error!("[import_skill_invocations] {}", e);
+            e
+        })?;
+    let (matched, unmatched) = match_installed(invocations, &installed);
+
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[import_skill_invocations] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let added = crate::db::insert_skill_invocations(&conn, &matched)?;
+    log::info!(
+        "[import_skill_invocations] {} file(s), {} matching invocation(s), {} new",
+        files_scanned,
+        matched.len(),
+        added
+    );
+    Ok(SkillInvocationImport {
+        source_dir: claude_dir.display().to_string(),
+        files_scanned,
+        invocations_matched: matched.len(),
+        invocations_added: added,
+        invocations_unmatched: unmatched,
+    })
+}
+
+/// Imported invocation counts per installed skill, optionally since an RFC 3339 timestamp.
+#[tauri::command]
+pub fn get_skill_invocation_stats(
+    since: Option<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<SkillInvocationStats>, String> {
+    log::info!("[get_skill_invocation_stats] since={:?}", since);
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[get_skill_invocation_stats] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::get_skill_invocation_stats(&conn, since.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::create_test_db;
+
+    const SESSION: &str = r#"{"type":"user","uuid":"u1","sessionId":"s1","cwd":"/work/a","timestamp":"2026-10-01T09:00:00Z","message":{"role":"user","content":"<command-message>sales is running</command-message>\n<command-name>/sales</command-name>"}}
+{"type":"assistant","uuid":"a1","sessionId":"s1","cwd":"/work/a","timestamp":"2026-10-01T09:01:00Z","message":{"role":"assistant","content":[{"type":"text","text":"Loading"},{"type":"tool_use","id":"toolu_1","name":"Skill","input":{"skill":"acme:Sales"}}]}}
+{"type":"assistant","uuid":"a2","sessionId":"s1","cwd":"/work/a","timestamp":"2026-10-01T09:02:00Z","message":{"role":"assistant","content":[{"type":"tool_use","id":"toolu_2","name":"Read","input":{"file_path":"Skill"}}]}}
+{"type":"user","uuid":"u2","sessionId":"s1","cwd":"/work/a","timestamp":"2026-10-01T09:03:00Z","message":{"role":"user","content":"<command-name>/clear</command-name>"}}
+not json"#;
+
+    #[test]
+    fn test_parse_line_finds_tool_and_command_invocations() {
+        let found: Vec<SkillInvocation> = SESSION.lines().flat_map(parse_line).collect();
+        let summary: Vec<(&str, &str, &str)> = found
+            .iter()
+            .map(|i| (i.event_id.as_str(), i.skill_name.as_str(), i.triggered_by.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("u1", "sales", "user"),
+                ("toolu_1", "Sales", "model"),
+                ("u2", "clear", "user"),
+            ]
+        );
+        assert_eq!(found[0].project_path.as_deref(), Some("/work/a"));
+        assert_eq!(found[1].invoked_at, "2026-10-01T09:01:00Z");
+    }
+
+    #[test]
+    fn test_import_is_idempotent_and_stats_cover_installed_skills() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project = tmp.path().join("projects").join("-work-a");
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(project.join("s1.jsonl"), SESSION).unwrap();
+
+        let conn = create_test_db();
+        crate::db::save_workflow_run(&conn, "sales", 0, "pending", "domain").unwrap();
+        crate::db::save_workflow_run(&conn, "hr", 0, "pending", "domain").unwrap();
+        let installed = crate::db::get_all_installed_skill_names(&conn).unwrap();
+
+        let (files, invocations) = scan_transcripts(tmp.path()).unwrap();
+        assert_eq!(files, 1);
+        let (matched, unmatched) = match_installed(invocations, &installed);
+        assert_eq!((matched.len(), unmatched), (2, 1));
+        assert_eq!(crate::db::insert_skill_invocations(&conn, &matched).unwrap(), 2);
+        assert_eq!(crate::db::insert_skill_invocations(&conn, &matched).unwrap(), 0);
+
+        let stats = crate::db::get_skill_invocation_stats(&conn, None).unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].skill_name, "sales");
+        assert_eq!(
+            (stats[0].invocations, stats[0].model_invocations, stats[0].user_invocations),
+            (2, 1, 1)
+        );
+        assert_eq!((stats[0].sessions, stats[0].projects), (1, 1));
+        assert_eq!(stats[1].skill_name, "hr");
+        assert_eq!(stats[1].invocations, 0);
+
+        let later = crate::db::get_skill_invocation_stats(&conn, Some("2026-10-01T09:00:30Z")).unwrap();
+        assert_eq!(later[0].invocations, 1);
+        assert!(scan_transcripts(&tmp.path().join("missing")).is_err());
+    }
+}
@@ -0,0 +1,590 @@
+//! Accessibility lint for skill markdown.
+//!
+//! Images need alt text and headings must start at h1 and descend one level
+//! at a time. Frontmatter and fenced code blocks are ignored. Packaging runs
+//! the lint according to the `accessibility_lint` setting, and
+//! `fix_skill_accessibility` repairs findings: heading levels are normalized
+//! deterministically, alt text is written by a model from the image (when it
+//! is a local file) and the surrounding text.
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+
+use super::imported_skills::validate_skill_name;
+use super::skill_impact::skill_markdown_files;
+use crate::db::Db;
+use crate::types::{AccessibilityFixResult, LintIssue, SkillLintReport};
+
+pub(crate) const LINT_OFF: &str = "off";
+pub(crate) const LINT_ADVISORY: &str = "advisory";
+pub(crate) const LINT_BLOCKING: &str = "blocking";
+
+const RULE_IMAGE_ALT: &str = "image-alt-missing";
+const RULE_HEADING_FIRST: &str = "heading-first-level";
+const RULE_HEADING_SKIP: &str = "heading-skip";
+
+const ALT_TEXT_MODEL: &str = "claude-haiku-4-5";
+const ALT_TEXT_MAX_TOKENS: u32 = 200;
+const ALT_TEXT_MAX_CHARS: usize = 150;
+const API_TIMEOUT_SECS: u64 = 60;
+/// Local images larger than this are described from context only.
+const MAX_IMAGE_BYTES: u64 = 4 * 1024 * 1024;
+/// Lines on each side of an image sent as context for its alt text.
+const CONTEXT_LINES: usize = 4;
+/// Findings listed in the error when blocking lint refuses to package.
+const MAX_LISTED_ISSUES: usize = 5;
+
+pub(crate) fn validate_lint_mode(mode: &str) -> Result<(), String> {
+    match mode {
+        LINT_OFF | LINT_ADVISORY | LINT_BLOCKING => Ok(()),
+        other => Err(format!(
+            "Invalid accessibility lint mode '{}': expected off, advisory or blocking",
+            other
+        )),
+    }
+}
+
+struct Heading {
+    line: usize,
+    level: usize,
+    /// Byte offset of the heading line.
+    start: usize,
+}
+
+enum ImageKind {
+    Markdown,
+    Html,
+}
+
+struct ImageRef {
+    line: usize,
+    kind: ImageKind,
+    /// Byte range replaced when alt text is added: the empty alt of a markdown
+    /// image, or an empty range right after `<img`.
+    edit: Range<usize>,
+    src: String,
+}
+
+pub(crate) fn heading_level(line: &str) -> Option<usize> {
+    let level = line.bytes().take_while(|b| *b == b'#').count();
+    let rest = &line[level..];
+    ((1..=6).contains(&level) && (rest.is_empty() || rest.starts_with([' ', '\t']))).then_some(level)
+}
+
+/// Value of an HTML attribute in `tag`, quoted or bare.
+fn attr_value<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let lower = tag.to_ascii_lowercase();
+    let needle = format!("{}=", name);
+    let pos = lower.match_indices(&needle).find_map(|(i, _)| {
+        lower[..i].ends_with(|c: char| c.is_whitespace()).then_some(i + needle.len())
+    })?;
+    let rest = &tag[pos..];
+    match rest.chars().next()? {
+        q @ ('"' | '\'') => rest[1..].split(q).next(),
+        _ => rest.split(|c: char| c.is_whitespace() || c == '>').next(),
+    }
+}
+
+fn find_images(line: &str, line_start: usize, line_no: usize, out: &mut Vec<ImageRef>) {
+    for (i, _) in line.match_indices("![") {
+        let alt_start = i + 2;
+        let Some(close) = line[alt_start..].find(']').map(|j| alt_start + j) else {
+            continue;
+        };
+        if !line[close + 1..].starts_with('(') || !line[alt_start..close].trim().is_empty() {
+            continue;
+        }
+        let target = &line[close + 2..];
+        let target = &target[..target.find(')').unwrap_or(target.len())];
+        let src = target.split_whitespace().next().unwrap_or("");
+        out.push(ImageRef {
+            line: line_no,
+            kind: ImageKind::Markdown,
+            edit: line_start + alt_start..line_start + close,
+            src: src.trim_matches(['<', '>']).to_string(),
+        });
+    }
+
+    let lower = line.to_ascii_lowercase();
+    for (i, _) in lower.match_indices("<img") {
+        let after = i + "<img".len();
+        if !lower[after..].starts_with(|c: char| c.is_whitespace() || c == '>' || c == '/') {
+            continue;
+        }
+        let end = lower[after..].find('>').map_or(line.len(), |j| after + j);
+        let tag = &line[i..end];
+        if attr_value(tag, "alt").is_some() || tag.split_whitespace().any(|a| a.eq_ignore_ascii_case("alt")) {
+            continue;
+        }
+        out.push(ImageRef {
+            line: line_no,
+            kind: ImageKind::Html,
+            edit: line_start + after..line_start + after,
+            src: attr_value(tag, "src").unwrap_or("").to_string(),
+        });
+    }
+}
+
+/// Headings and images without alt text, outside frontmatter and fenced code.
+fn scan(content: &str) -> (Vec<Heading>, Vec<ImageRef>) {
+    let mut headings = Vec::new();
+    let mut images = Vec::new();
+    let mut offset = 0;
+    let mut fence: Option<&str> = None;
+    let mut in_frontmatter = false;
+    for (idx, raw) in content.split_inclusive('\n').enumerate() {
+        let start = offset;
+        offset += raw.len();
+        let line = raw.trim_end_matches(['\n', '\r']);
+        let trimmed = line.trim_start();
+        if idx == 0 && line == "---" {
+            in_frontmatter = true;
+            continue;
+        }
+        if in_frontmatter {
+            in_frontmatter = line != "---";
+            continue;
+        }
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m)) {
+            fence = Some(marker);
+            continue;
+        }
+        if let Some(level) = heading_level(line) {
+            headings.push(Heading { line: idx + 1, level, start });
+        }
+        find_images(line, start, idx + 1, &mut images);
+    }
+    (headings, images)
+}
+
+fn lint_markdown(file: &str, content: &str) -> Vec<LintIssue> {
+    let (headings, images) = scan(content);
+    let issue = |line: usize, rule: &str, message: String| LintIssue {
+        file: file.to_string(),
+        line,
+        rule: rule.to_string(),
+        message,
+    };
+    let mut issues: Vec<LintIssue> = images
+        .iter()
+        .map(|img| {
+            let src = if img.src.is_empty() { "(no src)" } else { &img.src };
+            issue(img.line, RULE_IMAGE_ALT, format!("Image {} has no alt text", src))
+        })
+        .collect();
+    if let Some(first) = headings.first().filter(|h| h.level != 1) {
+        issues.push(issue(
+            first.line,
+            RULE_HEADING_FIRST,
+            format!("First heading is h{}; start the document at h1", first.level),
+        ));
+    }
+    for pair in headings.windows(2) {
+        if pair[1].level > pair[0].level + 1 {
+            issues.push(issue(
+                pair[1].line,
+                RULE_HEADING_SKIP,
+                format!("Heading jumps from h{} to h{}", pair[0].level, pair[1].level),
+            ));
+        }
+    }
+    issues.sort_by_key(|i| i.line);
+    issues
+}
+
+/// Re-level headings so the document starts at h1 and never skips a level,
+/// keeping sibling and parent relationships. Returns the new content and the
+/// number of headings changed.
+fn normalize_headings(content: &str) -> (String, usize) {
+    let (headings, _) = scan(content);
+    // (original level, new level) of the open ancestors
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+    let mut out = String::with_capacity(content.len());
+    let mut last = 0;
+    let mut changed = 0;
+    for h in &headings {
+        while stack.last().is_some_and(|(orig, _)| *orig >= h.level) {
+            stack.pop();
+        }
+        let level = stack.last().map_or(1, |(_, new)| new + 1);
+        stack.push((h.level, level));
+        if level != h.level {
+            out.push_str(&content[last..h.start]);
+            out.push_str(&"#".repeat(level));
+            last = h.start + h.level;
+            changed += 1;
+        }
+    }
+    out.push_str(&content[last..]);
+    (out, changed)
+}
+
+/// Make model output safe to splice into `![...]` or `alt="..."`.
+fn sanitize_alt(text: &str) -> Option<String> {
+    let line = text.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let cleaned: String = line
+        .chars()
+        .filter(|c| !matches!(c, '[' | ']' | '"' | '<' | '>' | '\\'))
+        .take(ALT_TEXT_MAX_CHARS)
+        .collect();
+    let cleaned = cleaned.trim().trim_matches('\'').trim();
+    (!cleaned.is_empty()).then(|| cleaned.to_string())
+}
+
+fn with_alt_text(content: &str, mut fixes: Vec<(ImageRef, String)>) -> String {
+    let mut out = content.to_string();
+    fixes.sort_by_key(|(img, _)| std::cmp::Reverse(img.edit.start));
+    for (img, alt) in fixes {
+        let replacement = match img.kind {
+            ImageKind::Markdown => alt,
+            ImageKind::Html => format!(" alt=\"{}\"", alt),
+        };
+        out.replace_range(img.edit, &replacement);
+    }
+    out
+}
+
+/// Base64 image block for a local image inside the skill directory, when the
+/// format is one the API accepts.
+fn image_block(skill_dir: &Path, file: &str, src: &str) -> Option<serde_json::Value> {
+    if src.is_empty() || src.contains("://") || src.starts_with("data:") {
+        return None;
+    }
+    let media_type = match Path::new(src).extension()?.to_str()?.to_ascii_lowercase().as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        _ => return None,
+    };
+    let base = skill_dir.join(file).parent().map(Path::to_path_buf)?;
+    let path: PathBuf = base.join(src).canonicalize().ok()?;
+    if !path.starts_with(skill_dir.canonicalize().ok()?) || std::fs::metadata(&path).ok()?.len() > MAX_IMAGE_BYTES {
+        return None;
+    }
+    let bytes = std::fs::read(&path).ok()?;
+    Some(serde_json::json!({
+        "type": "image",
+        "source": {
+            "type": "base64",
+            "media_type": media_type,
+            "data": base64::engine::general_purpose::STANDARD.encode(bytes),
+        }
+    }))
+}
+
+fn alt_text_prompt(file: &str, content: &str, image: &ImageRef, has_image: bool) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let from = image.line.saturating_sub(CONTEXT_LINES + 1);
+    let to = (image.line + CONTEXT_LINES).min(lines.len());
+    let context = lines[from..to].join("\n");
+    let basis = if has_image {
+        "the attached image, using the surrounding text for terminology"
+    } else {
+        "the image file name and the surrounding text"
+    };
+    format!(
+        "Write alt text for an image in the skill file {file}. Describe what the image conveys \
+         to someone who cannot see it, based on {basis}. One sentence, at most {ALT_TEXT_MAX_CHARS} \
+         characters, no quotes, no leading \"Image of\". Reply with the alt text only.\n\n\
+         Image: {src}\n\nSurrounding text:\n{context}",
+        src = image.src,
+    )
+}
+
+async fn generate_alt_text(
+    client: &reqwest::Client,
+    api_key: &str,
+    prompt: String,
+    image: Option<serde_json::Value>,
+) -> Result<String, String> {
+    let mut content: Vec<serde_json::Value> = image.into_iter().collect();
+    content.push(serde_json::json!({"type": "text", "text": prompt}));
+    let body = serde_json::json!({
+        "model": ALT_TEXT_MODEL,
+        "max_tokens": ALT_TEXT_MAX_TOKENS,
+        "messages": [{"role": "user", "content": content}]
+    });
+    let resp = client
+        .post("https://api.anthropic.com/v1/messages")
+        .header("x-api-key", api_key)
+        .header("anthropic-version", "2023-06-01")
+        .header("content-type", "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .map_err(|e| format!("API request failed: {}", e))?;
+
+    if !resp.status().is_success() {
+        let status = resp.status().as_u16();
+        let body = resp.text().await.unwrap_or_default();
+        log::debug!("Anthropic API error body: {}", body);
+        return Err(format!("Anthropic API error ({})", status));
+    }
+    let body: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+    let (text, _, _) = super::local_model::parse_anthropic_response(&body)?;
+    sanitize_alt(&text).ok_or_else(|| "Model returned empty alt text".to_string())
+}
+
+/// Lint SKILL.md and every reference file. Returns the number of files checked
+/// and the findings.
+pub(crate) fn lint_skill_dir(skill_dir: &Path) -> (usize, Vec<LintIssue>) {
+    let files = skill_markdown_files(skill_dir);
+    let issues = files
+        .iter()
+        .filter_map(|file| {
+            std::fs::read_to_string(skill_dir.join(file))
+                .ok()
+                .map(|content| lint_markdown(file, &content))
+        })
+        .flatten()
+        .collect();
+    (files.len(), issues)
+}
+
+/// Apply the packaging gate for `mode`. Blocking mode fails when there are
+/// findings; advisory mode returns them so the caller can surface them.
+pub(crate) fn lint_for_packaging(mode: &str, skill_dir: &Path) -> Result<Vec<LintIssue>, String> {
+    if mode == LINT_OFF {
+        return Ok(Vec::new());
+    }
+    let (_, issues) = lint_skill_dir(skill_dir);
+    if mode != LINT_BLOCKING || issues.is_empty() {
+        return Ok(issues);
+    }
+    let mut listed: Vec<String> = issues
+        .iter()
+        .take(MAX_LISTED_ISSUES)
+        .map(|i| format!("{}:{} {}", i.file, i.line, i.message))
+        .collect();
+    if issues.len() > MAX_LISTED_ISSUES {
+        listed.push(format!("and {} more", issues.len() - MAX_LISTED_ISSUES));
+    }
+    Err(format!(
+        "Accessibility lint found {} issue(s): {}. Run the accessibility fix or set the lint to advisory in Settings.",
+        issues.len(),
+        listed.join("; ")
+    ))
+}
+
+fn skill_dir_from_settings(db: &Db, skill_name: &str, cmd: &str) -> Result<(PathBuf, Option<String>), String> {
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[{}] Failed to acquire DB lock: {}", cmd, e);
+        e.to_string()
+    })?;
+    let settings = crate::db::read_settings(&conn)?;
+    // Offline mode can't reach the API, so only headings get fixed.
+    let api_key = settings.anthropic_api_key.clone().filter(|_| {
+        super::offline::feature_enabled(&settings, super::offline::NetworkFeature::AnthropicApi)
+    });
+    let skills_path = settings
+        .skills_path
+        .ok_or_else(|| "Skills path not configured. Please set it in Settings.".to_string())?;
+    let skill_dir = Path::new(&skills_path).join(skill_name);
+    if !skill_dir.join("SKILL.md").is_file() {
+        return Err(format!("Skill '{}' not found in {}", skill_name, skills_path));
+    }
+    Ok((skill_dir, api_key))
+}
+
+/// Accessibility findings for a skill: images without alt text and heading
+/// levels that skip or don't start at h1.
+#[tauri::command]
+pub fn lint_skill(skill_name: String, db: tauri::State<'_, Db>) -> Result<SkillLintReport, String> {
+    log::info!("[lint_skill] skill={}", skill_name);
+    validate_skill_name(&skill_name)?;
+    let (skill_dir, _) = skill_dir_from_settings(&db, &skill_name, "lint_skill").map_err(|e| {
+        log::error!("[lint_skill] {}", e);
+        e
+    })?;
+    let (files_checked, issues) = lint_skill_dir(&skill_dir);
+    Ok(SkillLintReport {
+        skill_name,
+        files_checked,
+        issues,
+    })
+}
+
+/// Normalize heading levels and generate missing alt text, then commit the
+/// changes to the skills repo. Without an API key only headings are fixed.
+#[tauri::command]
+pub async fn fix_skill_accessibility(
+    skill_name: String,
+    db: tauri::State<'_, Db>,
+) -> Result<AccessibilityFixResult, String> {
+    log::info!("[fix_skill_accessibility] skill={}", skill_name);
+    super::observer::ensure_writable(&skill_name)?;
+    validate_skill_name(&skill_name)?;
+    let (skill_dir, api_key) =
+        skill_dir_from_settings(&db, &skill_name, "fix_skill_accessibility").map_err(|e| {
+            log::error!("[fix_skill_accessibility] {}", e);
+            e
+        })?;
+    if api_key.is_none() {
+        log::warn!("[fix_skill_accessibility] No API key configured; alt text will not be generated");
+    }
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(API_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut files_changed = Vec::new();
+    let mut headings_fixed = 0;
+    let mut alt_texts_added = 0;
+    for file in skill_markdown_files(&skill_dir) {
+        let path = skill_dir.join(&file);
+        let Ok(original) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let (mut content, fixed) = normalize_headings(&original);
+        headings_fixed += fixed;
+
+        if let Some(ref key) = api_key {
+            let (_, images) = scan(&content);
+            let mut fixes = Vec::new();
+            for image in images {
+                let block = image_block(&skill_dir, &file, &image.src);
+                let prompt = alt_text_prompt(&file, &content, &image, block.is_some());
+                match generate_alt_text(&client, key, prompt, block).await {
+                    Ok(alt) => fixes.push((image, alt)),
+                    Err(e) => log::warn!(
+                        "[fix_skill_accessibility] No alt text for {} in {}: {}",
+                        image.src,
+                        file,
+                        e
+                    ),
+                }
+            }
+            alt_texts_added += fixes.len();
+            content = with_alt_text(&content, fixes);
+        }
+
+        if content != original {
+            std::fs::write(&path, &content).map_err(|e| {
+                log::error!("[fix_skill_accessibility] Failed to write {}: {}", path.display(), e);
+                format!("Failed to write {}: {}", file, e)
+            })?;
+            files_changed.push(file);
+        }
+    }
+
+    let mut commit_sha = None;
+    if !files_changed.is_empty() {
+        let msg = format!("{}: accessibility fixes", skill_name);
+        let repo_root = skill_dir.parent().unwrap_or(&skill_dir);
+        match crate::git::commit_all(repo_root, &msg) {
+            Ok(sha) => commit_sha = sha,
+            Err(e) => log::warn!("Git auto-commit failed ({}): {}", msg, e),
+        }
+    }
+    log::info!(
+        "[fix_skill_accessibility] skill={} files={} headings={} alt_texts={}",
+        skill_name,
+        files_changed.len(),
+        headings_fixed,
+        alt_texts_added
+    );
+    let (_, remaining) = lint_skill_dir(&skill_dir);
+    Ok(AccessibilityFixResult {
+        skill_name,
+        files_changed,
+        headings_fixed,
+        alt_texts_added,
+        remaining,
+        commit_sha,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    const DOC: &str = "---\nname: demo\n---\n## Overview\n\n![](diagram.png)\n\n#### Details\n\n```md\n# not a heading\n![](ignored.png)\n```\n\n<img src=\"flow.png\" width=\"200\">\n<img src=\"logo.png\" alt=\"\">\n![Data flow](ok.png)\n";
+
+    #[test]
+    fn test_lint_markdown_reports_alt_and_heading_issues() {
+        let issues = lint_markdown("SKILL.md", DOC);
+        let found: Vec<(usize, &str)> = issues.iter().map(|i| (i.line, i.rule.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![
+                (4, RULE_HEADING_FIRST),
+                (6, RULE_IMAGE_ALT),
+                (8, RULE_HEADING_SKIP),
+                (15, RULE_IMAGE_ALT),
+            ]
+        );
+        assert_eq!(issues[1].message, "Image diagram.png has no alt text");
+        assert_eq!(issues[2].message, "Heading jumps from h2 to h4");
+    }
+
+    #[test]
+    fn test_normalize_headings_keeps_structure() {
+        let (out, changed) = normalize_headings("## A\n#### B\n### C\n## D\n```\n## code\n```\n");
+        assert_eq!(out, "# A\n## B\n## C\n# D\n```\n## code\n```\n");
+        assert_eq!(changed, 4);
+
+        let (out, changed) = normalize_headings("# A\n## B\n### C\n## D\n");
+        assert_eq!(out, "# A\n## B\n### C\n## D\n");
+        assert_eq!(changed, 0);
+    }
+
+    #[test]
+    fn test_with_alt_text_fills_markdown_and_html_images() {
+        let content = "# T\n![ ](a.png) and <img src='b.png'>\n";
+        let (_, images) = scan(content);
+        let alts = ["Sales by region", "Team logo"];
+        let fixes = images.into_iter().zip(alts).map(|(img, alt)| (img, alt.to_string())).collect();
+        assert_eq!(
+            with_alt_text(content, fixes),
+            "# T\n![Sales by region](a.png) and <img alt=\"Team logo\" src='b.png'>\n"
+        );
+        assert_eq!(sanitize_alt("\n\"Chart [v2] <draft>\"\nextra").as_deref(), Some("Chart v2 draft"));
+        assert!(sanitize_alt("  \n").is_none());
+    }
+
+    #[test]
+    fn test_lint_for_packaging_modes() {
+        let tmp = tempdir().unwrap();
+        let skill = tmp.path().join("demo");
+        fs::create_dir_all(skill.join("references")).unwrap();
+        fs::write(skill.join("SKILL.md"), "# Demo\n## Usage\n").unwrap();
+        fs::write(skill.join("references/flow.md"), "# Flow\n![](flow.png)\n").unwrap();
+
+        assert!(lint_for_packaging(LINT_OFF, &skill).unwrap().is_empty());
+        let advisory = lint_for_packaging(LINT_ADVISORY, &skill).unwrap();
+        assert_eq!(advisory.len(), 1);
+        assert_eq!(advisory[0].file, "references/flow.md");
+        let err = lint_for_packaging(LINT_BLOCKING, &skill).unwrap_err();
+        assert!(err.contains("references/flow.md:2 Image flow.png has no alt text"), "{}", err);
+
+        fs::write(skill.join("references/flow.md"), "# Flow\n![Flow](flow.png)\n").unwrap();
+        assert!(lint_for_packaging(LINT_BLOCKING, &skill).unwrap().is_empty());
+        assert!(validate_lint_mode("strict").is_err());
+    }
+
+    #[test]
+    fn test_image_block_stays_inside_skill_dir() {
+        let tmp = tempdir().unwrap();
+        let skill = tmp.path().join("demo");
+        fs::create_dir_all(skill.join("references/img")).unwrap();
+        fs::write(skill.join("references/img/a.png"), [0x89, b'P', b'N', b'G']).unwrap();
+        fs::write(tmp.path().join("outside.png"), [0u8; 4]).unwrap();
+
+        let block = image_block(&skill, "references/guide.md", "img/a.png").unwrap();
+        assert_eq!(block["source"]["media_type"], "image/png");
+        assert!(image_block(&skill, "references/guide.md", "../../outside.png").is_none());
+        assert!(image_block(&skill, "SKILL.md", "https://example.com/a.png").is_none());
+        assert!(image_block(&skill, "SKILL.md", "notes.svg").is_none());
+    }
+}
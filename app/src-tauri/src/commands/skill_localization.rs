@@ -0,0 +1,340 @@
+//! Language variants of SKILL.md.
+//!
+//! A skill's translations sit next to SKILL.md as `SKILL.<lang>.md`, e.g.
+//! `SKILL.de.md`, where `<lang>` is a two or three letter language code with
+//! an optional region (`de-AT`). `generate_skill_translation` runs an agent
+//! that returns the translated file as structured output, and
+//! `materialize_skill_translation` writes it once the frontmatter keys, the
+//! skill name, the heading outline and the code blocks are confirmed to have
+//! survived translation. `package_skill` bundles every variant, or with a
+//! locale ships that variant as the package's SKILL.md.
+
+use std::path::Path;
+
+use super::imported_skills::validate_skill_name;
+use crate::agents::sidecar::{self, SidecarConfig};
+use crate::agents::sidecar_pool::SidecarPool;
+use crate::db::Db;
+use crate::types::SkillTranslation;
+
+const TRANSLATION_MAX_TURNS: u32 = 5;
+
+/// Accepts `de`, `deu` and `de-AT`; the language part is lower case and the
+/// region upper case, so each language has one file name.
+pub(crate) fn validate_language(language: &str) -> Result<(), String> {
+    let (lang, region) = match language.split_once('-') {
+        Some((lang, region)) => (lang, Some(region)),
+        None => (language, None),
+    };
+    let lang_ok = (2..=3).contains(&lang.len()) && lang.chars().all(|c| c.is_ascii_lowercase());
+    let region_ok = region.is_none_or(|r| r.len() == 2 && r.chars().all(|c| c.is_ascii_uppercase()));
+    if lang_ok && region_ok {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid language code '{}': use a code like 'de' or 'de-AT'",
+            language
+        ))
+    }
+}
+
+pub(crate) fn variant_file_name(language: &str) -> String {
+    format!("SKILL.{}.md", language)
+}
+
+/// Languages with a `SKILL.<lang>.md` in `skill_dir`, sorted.
+pub(crate) fn list_variants(skill_dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(skill_dir) else {
+        return Vec::new();
+    };
+    let mut languages: Vec<String> = entries
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let lang = name.strip_prefix("SKILL.")?.strip_suffix(".md")?.to_string();
+            validate_language(&lang).is_ok().then_some(lang)
+        })
+        .collect();
+    languages.sort();
+    languages
+}
+
+/// The parts of a SKILL.md a translation must leave intact.
+#[derive(Debug, PartialEq)]
+struct Outline {
+    frontmatter_keys: Vec<String>,
+    name: Option<String>,
+    heading_levels: Vec<usize>,
+    code_blocks: Vec<String>,
+}
+
+fn outline(content: &str) -> Outline {
+    let fm = super::imported_skills::parse_frontmatter_full(content);
+    let mut frontmatter_keys = Vec::new();
+    let mut body = content;
+    if let Some(rest) = content.strip_prefix("---") {
+        if let Some(end) = rest.find("\n---") {
+            for line in rest[..end].lines() {
+                if !line.starts_with(char::is_whitespace) && !line.starts_with('-') {
+                    if let Some((key, _)) = line.split_once(':') {
+                        frontmatter_keys.push(key.trim().to_string());
+                    }
+                }
+            }
+            body = &rest[end + 4..];
+        }
+    }
+    frontmatter_keys.sort();
+
+    let mut heading_levels = Vec::new();
+    let mut code_blocks = Vec::new();
+    let mut fence: Option<String> = None;
+    for line in body.lines() {
+        if line.trim_start().starts_with("```") {
+            match fence.take() {
+                Some(block) => code_blocks.push(block),
+                None => fence = Some(String::new()),
+            }
+            continue;
+        }
+        if let Some(block) = fence.as_mut() {
+            block.push_str(line);
+            block.push('\n');
+        } else if line.starts_with('#') {
+            let level = line.chars().take_while(|c| *c == '#').count();
+            if line[level..].starts_with(' ') {
+                heading_levels.push(level);
+            }
+        }
+    }
+    Outline { frontmatter_keys, name: fm.name, heading_levels, code_blocks }
+}
+
+/// Check that `translated` keeps the structure of `source`.
+pub(crate) fn check_structure(source: &str, translated: &str) -> Result<(), String> {
+    let (src, tr) = (outline(source), outline(translated));
+    if src.frontmatter_keys != tr.frontmatter_keys {
+        return Err(format!(
+            "Translation changed the frontmatter keys: expected {:?}, got {:?}",
+            src.frontmatter_keys, tr.frontmatter_keys
+        ));
+    }
+    if src.name != tr.name {
+        return Err("Translation changed the skill name".to_string());
+    }
+    if src.heading_levels != tr.heading_levels {
+        return Err(format!(
+            "Translation changed the heading outline: {} heading(s) in the source, {} in the translation",
+            src.heading_levels.len(),
+            tr.heading_levels.len()
+        ));
+    }
+    if src.code_blocks != tr.code_blocks {
+        return Err("Translation changed the content of code blocks".to_string());
+    }
+    Ok(())
+}
+
+fn translation_prompt(skill_name: &str, language: &str, source: &str) -> String {
+    format!(
+        "Translate the SKILL.md of the skill '{}' into the language with code '{}'.\n\n\
+         Rules:\n\
+         - Keep the YAML frontmatter keys exactly as they are. Do not change `name`. \
+           Translate only human-readable values such as `description` and `argument-hint`.\n\
+         - Keep every heading, at the same level and in the same order; translate the heading text.\n\
+         - Copy code blocks, inline code, file paths, links and tool names unchanged.\n\
+         - Keep lists, tables and emphasis as they are.\n\n\
+         Return the complete translated file as `translated_markdown`.\n\n\
+         SKILL.md:\n\n{}",
+        skill_name, language, source
+    )
+}
+
+fn translation_output_format() -> serde_json::Value {
+    serde_json::json!({
+        "type": "json_schema",
+        "schema": {
+            "type": "object",
+            "required": ["translated_markdown"],
+            "properties": {
+                "translated_markdown": { "type": "string", "minLength": 1 }
+            },
+            "additionalProperties": false
+        }
+    })
+}
+
+fn skill_dir(db: &Db, skill_name: &str) -> Result<std::path::PathBuf, String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let skills_path = crate::db::read_settings(&conn)?
+        .skills_path
+        .ok_or_else(|| "Skills path not configured. Please set it in Settings.".to_string())?;
+    Ok(Path::new(&skills_path).join(skill_name))
+}
+
+fn translation_info(skill_dir: &Path, language: &str) -> SkillTranslation {
+    let file_name = variant_file_name(language);
+    let modified = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+    let stale = match (modified(&skill_dir.join("SKILL.md")), modified(&skill_dir.join(&file_name))) {
+        (Some(source), Some(variant)) => source > variant,
+        _ => false,
+    };
+    SkillTranslation {
+        language: language.to_string(),
+        file_name,
+        stale,
+    }
+}
+
+/// Translations of a skill. A translation is stale when SKILL.md was modified
+/// after it was written.
+#[tauri::command]
+pub fn list_skill_translations(
+    skill_name: String,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<SkillTranslation>, String> {
+    log::info!("[list_skill_translations] skill={}", skill_name);
+    validate_skill_name(&skill_name)?;
+    let dir = skill_dir(&db, &skill_name)?;
+    Ok(list_variants(&dir)
+        .iter()
+        .map(|lang| translation_info(&dir, lang))
+        .collect())
+}
+
+/// Start an agent translating SKILL.md into `language`. Returns the agent id;
+/// pass its structured output to `materialize_skill_translation`.
+#[tauri::command]
+pub async fn generate_skill_translation(
+    skill_name: String,
+    language: String,
+    app: tauri::AppHandle,
+    pool: tauri::State<'_, SidecarPool>,
+    db: tauri::State<'_, Db>,
+) -> Result<String, String> {
+    log::info!("[generate_skill_translation] skill={} language={}", skill_name, language);
+    super::observer::ensure_writable(&skill_name)?;
+    validate_skill_name(&skill_name)?;
+    validate_language(&language)?;
+
+    let (skills_path, api_key, model) = {
+        let conn = db.0.lock().map_err(|e| {
+            log::error!("[generate_skill_translation] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        let settings = crate::db::read_settings_hydrated(&conn)?;
+        let skills_path = settings
+            .skills_path
+            .clone()
+            .ok_or_else(|| "Skills path not configured. Please set it in Settings.".to_string())?;
+        let api_key = crate::commands::offline::agent_api_key(&settings)
+            .ok_or_else(|| "Anthropic API key not configured".to_string())?;
+        crate::commands::usage::check_budget_allows_run(&conn, &skill_name).map_err(|e| {
+            log::warn!("[generate_skill_translation] {}", e);
+            e
+        })?;
+        let model = super::workflow::resolve_model_id(settings.preferred_model.as_deref().unwrap_or("sonnet"));
+        (skills_path, api_key, model)
+    };
+
+    let dir = Path::new(&skills_path).join(&skill_name);
+    let source = std::fs::read_to_string(dir.join("SKILL.md")).map_err(|e| {
+        log::error!("[generate_skill_translation] Failed to read SKILL.md: {}", e);
+        format!("Failed to read SKILL.md for '{}': {}", skill_name, e)
+    })?;
+
+    let agent_id = super::workflow::make_agent_id(&skill_name, &format!("translate-{}", language));
+    let config = SidecarConfig {
+        prompt: translation_prompt(&skill_name, &language, &source),
+        model: Some(model),
+        api_key,
+        cwd: dir.to_string_lossy().to_string(),
+        allowed_tools: Some(Vec::new()),
+        max_turns: Some(TRANSLATION_MAX_TURNS),
+        permission_mode: Some("bypassPermissions".to_string()),
+        betas: None,
+        thinking: None,
+        fallback_model: None,
+        effort: None,
+        output_format: Some(translation_output_format()),
+        prompt_suggestions: None,
+        path_to_claude_code_executable: None,
+        agent_name: None,
+        required_plugins: None,
+        conversation_history: None,
+        resume: None,
+        bash_sandbox: None,
+        disallowed_tools: None,
+        local_model: None,
+    };
+    sidecar::spawn_sidecar(agent_id.clone(), config, pool.inner().clone(), app, skill_name, None).await?;
+    Ok(agent_id)
+}
+
+/// Write a translation agent's output to `SKILL.<lang>.md`, refusing it when
+/// the structure of SKILL.md was not preserved.
+#[tauri::command]
+pub fn materialize_skill_translation(
+    skill_name: String,
+    language: String,
+    structured_output: serde_json::Value,
+    db: tauri::State<'_, Db>,
+) -> Result<SkillTranslation, String> {
+    log::info!("[materialize_skill_translation] skill={} language={}", skill_name, language);
+    super::observer::ensure_writable(&skill_name)?;
+    validate_skill_name(&skill_name)?;
+    validate_language(&language)?;
+    let translated = structured_output
+        .get("translated_markdown")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Translation output is missing translated_markdown".to_string())?;
+    let dir = skill_dir(&db, &skill_name)?;
+    let source = std::fs::read_to_string(dir.join("SKILL.md"))
+        .map_err(|e| format!("Failed to read SKILL.md for '{}': {}", skill_name, e))?;
+    check_structure(&source, translated).map_err(|e| {
+        log::warn!("[materialize_skill_translation] {}", e);
+        e
+    })?;
+    let path = dir.join(variant_file_name(&language));
+    std::fs::write(&path, translated).map_err(|e| {
+        log::error!("[materialize_skill_translation] Failed to write {}: {}", path.display(), e);
+        format!("Failed to write {}: {}", path.display(), e)
+    })?;
+    Ok(translation_info(&dir, &language))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "---\nname: revenue\ndescription: Recognize revenue\n---\n# Revenue\n\n## Steps\n\nRun:\n\n```sql\nselect 1;\n```\n";
+
+    #[test]
+    fn test_language_codes_and_variant_listing() {
+        assert!(validate_language("de").is_ok());
+        assert!(validate_language("de-AT").is_ok());
+        assert!(validate_language("DE").is_err());
+        assert!(validate_language("../x").is_err());
+        assert!(validate_language("de-at").is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["SKILL.md", "SKILL.de.md", "SKILL.fr-CA.md", "SKILL.bak.md.orig", "SKILL.Notes.md"] {
+            std::fs::write(dir.path().join(name), "x").unwrap();
+        }
+        assert_eq!(list_variants(dir.path()), vec!["de", "fr-CA"]);
+    }
+
+    #[test]
+    fn test_check_structure_requires_same_outline() {
+        let german = SOURCE
+            .replace("description: Recognize revenue", "description: Umsatz erfassen")
+            .replace("# Revenue", "# Umsatz")
+            .replace("## Steps", "## Schritte")
+            .replace("Run:", "Ausführen:");
+        assert!(check_structure(SOURCE, &german).is_ok());
+        assert!(check_structure(SOURCE, &german.replace("name: revenue", "name: umsatz")).is_err());
+        assert!(check_structure(SOURCE, &german.replace("## Schritte", "### Schritte")).is_err());
+        assert!(check_structure(SOURCE, &german.replace("select 1;", "wähle 1;")).is_err());
+        assert!(check_structure(SOURCE, &german.replace("description:", "beschreibung:")).is_err());
+    }
+}
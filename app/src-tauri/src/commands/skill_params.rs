@@ -0,0 +1,454 @@
+//! Parameterized skills: template variables declared in SKILL.md frontmatter.
+//!
+//! A skill declares variables under `variables:` and references them as
+//! `{{name}}` in any text file. Values are validated and substituted when the
+//! skill is installed from a package or packaged for a specific environment, so
+//! one maintained skill can serve several environments. The values each install
+//! was resolved with are kept in `skill_parameter_sets`.
+//!
+//! ```yaml
+//! variables:
+//!   env:
+//!     description: Target environment
+//!     allowed: [dev, staging, prod]
+//!   schema_prefix:
+//!     default: analytics
+//! ```
+
+use crate::db::Db;
+use crate::types::{PackageResult, SkillParameterSet, SkillVariable};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+/// Extensions treated as text when substituting values into installed files.
+const TEMPLATE_EXTENSIONS: &[&str] = &["md", "txt", "sql", "yml", "yaml", "json", "py", "sh", "toml", "csv"];
+
+/// Return the YAML between the opening and closing `---` markers.
+fn frontmatter_block(content: &str) -> Option<&str> {
+    let trimmed = content.trim_start();
+    let after_first = trimmed.strip_prefix("---")?;
+    let end = after_first.find("\n---")?;
+    Some(&after_first[..end])
+}
+
+fn unquote(value: &str) -> String {
+    value.trim().trim_matches('"').trim_matches('\'').to_string()
+}
+
+/// Parse a YAML flow list (`[a, b]`) or a single scalar into values.
+fn parse_list(value: &str) -> Vec<String> {
+    let value = value.trim();
+    let inner = value
+        .strip_prefix('[')
+        .and_then(|v| v.strip_suffix(']'))
+        .unwrap_or(value);
+    inner
+        .split(',')
+        .map(unquote)
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+fn is_valid_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// A variable being parsed; `required` stays `None` unless set explicitly.
+struct PendingVariable {
+    var: SkillVariable,
+    required: Option<bool>,
+}
+
+impl PendingVariable {
+    fn finish(self) -> Result<SkillVariable, String> {
+        let mut var = self.var;
+        var.required = self.required.unwrap_or(var.default.is_none());
+        if let Some(default) = &var.default {
+            if !var.allowed.is_empty() && !var.allowed.contains(default) {
+                return Err(format!(
+                    "Variable '{}' has default '{}' outside its allowed values",
+                    var.name, default
+                ));
+            }
+        }
+        Ok(var)
+    }
+}
+
+/// Parse the `variables:` block of SKILL.md frontmatter.
+/// Returns an empty list when the skill declares no variables.
+pub(crate) fn parse_skill_variables(content: &str) -> Result<Vec<SkillVariable>, String> {
+    let Some(block) = frontmatter_block(content) else {
+        return Ok(Vec::new());
+    };
+
+    let mut vars: Vec<SkillVariable> = Vec::new();
+    let mut in_vars = false;
+    let mut var_indent: Option<usize> = None;
+    let mut current: Option<PendingVariable> = None;
+
+    for line in block.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+
+        if indent == 0 {
+            if let Some(pending) = current.take() {
+                vars.push(pending.finish()?);
+            }
+            in_vars = trimmed == "variables:";
+            var_indent = None;
+            continue;
+        }
+        if !in_vars {
+            continue;
+        }
+
+        let (key, value) = trimmed
+            .split_once(':')
+            .ok_or_else(|| format!("Invalid variables entry in frontmatter: '{}'", trimmed))?;
+        let (key, value) = (key.trim(), value.trim());
+        let level = *var_indent.get_or_insert(indent);
+
+        if indent == level {
+            if let Some(pending) = current.take() {
+                vars.push(pending.finish()?);
+            }
+            if !is_valid_variable_name(key) {
+                return Err(format!(
+                    "Invalid variable name '{}': use letters, digits and underscores",
+                    key
+                ));
+            }
+            if vars.iter().any(|v| v.name == key) {
+                return Err(format!("Variable '{}' is declared twice", key));
+            }
+            // `name: value` shorthand declares a variable with a default.
+            current = Some(PendingVariable {
+                var: SkillVariable {
+                    name: key.to_string(),
+                    default: Some(unquote(value)).filter(|v| !v.is_empty()),
+                    ..Default::default()
+                },
+                required: None,
+            });
+        } else if indent > level {
+            let pending = current
+                .as_mut()
+                .ok_or_else(|| format!("Invalid variables entry in frontmatter: '{}'", trimmed))?;
+            match key {
+                "description" => pending.var.description = Some(unquote(value)).filter(|v| !v.is_empty()),
+                "default" => pending.var.default = Some(unquote(value)).filter(|v| !v.is_empty()),
+                "required" => {
+                    let v = value.to_lowercase();
+                    pending.required = Some(v == "true" || v == "yes" || v == "1");
+                }
+                "allowed" => pending.var.allowed = parse_list(value),
+                _ => {}
+            }
+        } else {
+            return Err(format!("Inconsistent indentation in variables block: '{}'", trimmed));
+        }
+    }
+
+    if let Some(pending) = current.take() {
+        vars.push(pending.finish()?);
+    }
+    Ok(vars)
+}
+
+/// Validate user-provided values against the declarations and fill in defaults.
+/// All problems are reported together.
+pub(crate) fn resolve_parameters(
+    vars: &[SkillVariable],
+    provided: &HashMap<String, String>,
+) -> Result<BTreeMap<String, String>, String> {
+    let mut errors = Vec::new();
+
+    let declared: HashSet<&str> = vars.iter().map(|v| v.name.as_str()).collect();
+    let mut unknown: Vec<&String> = provided.keys().filter(|k| !declared.contains(k.as_str())).collect();
+    unknown.sort();
+    for key in unknown {
+        errors.push(format!("unknown parameter '{}'", key));
+    }
+
+    let mut resolved = BTreeMap::new();
+    for var in vars {
+        let value = provided
+            .get(&var.name)
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .or_else(|| var.default.clone());
+        match value {
+            None if var.required => errors.push(format!("'{}' is required", var.name)),
+            None => {
+                resolved.insert(var.name.clone(), String::new());
+            }
+            Some(v) if v.contains('\n') => errors.push(format!("'{}' must be a single line", var.name)),
+            Some(v) if !var.allowed.is_empty() && !var.allowed.contains(&v) => errors.push(format!(
+                "'{}' must be one of: {}",
+                var.name,
+                var.allowed.join(", ")
+            )),
+            Some(v) => {
+                resolved.insert(var.name.clone(), v);
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(resolved)
+    } else {
+        Err(format!("Invalid skill parameters: {}", errors.join("; ")))
+    }
+}
+
+/// Replace `{{name}}` / `{{ name }}` for every resolved variable.
+/// Placeholders that are not declared variables (e.g. dbt's `{{ ref('x') }}`) are left alone.
+pub(crate) fn render_template(content: &str, values: &BTreeMap<String, String>) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let inner = rest[start + 2..start + 2 + len].trim();
+        out.push_str(&rest[..start]);
+        match values.get(inner) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&rest[start..start + 2 + len + 2]),
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Substitute values into every text file under `dir`. Returns the number of files changed.
+pub(crate) fn render_dir(dir: &Path, values: &BTreeMap<String, String>) -> Result<usize, String> {
+    let mut changed = 0;
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            changed += render_dir(&path, values)?;
+            continue;
+        }
+        let is_text = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| TEMPLATE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()));
+        let is_signature = path
+            .file_name()
+            .is_some_and(|n| n == crate::commands::skill_signing::SIGNATURE_ENTRY);
+        if !is_text || is_signature {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let rendered = render_template(&content, values);
+        if rendered != content {
+            std::fs::write(&path, rendered)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            changed += 1;
+        }
+    }
+    Ok(changed)
+}
+
+/// Values stored for an installed parameterized skill, if any.
+#[tauri::command]
+pub fn get_skill_parameters(
+    skill_name: String,
+    db: tauri::State<'_, Db>,
+) -> Result<Option<SkillParameterSet>, String> {
+    log::info!("[get_skill_parameters] skill={}", skill_name);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[get_skill_parameters] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::get_skill_parameter_set(&conn, &skill_name)
+}
+
+/// Package a parameterized skill with values resolved for one environment.
+/// The skill is copied to a staging directory, rendered there, and packaged
+/// through `package_skill_dir` with the same checks and options as
+/// `package_skill`, as `{skill}-{label}` next to the regular package.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn package_skill_with_parameters(
+    skill_name: String,
+    label: String,
+    parameters: HashMap<String, String>,
+    refuse_on_errors: Option<bool>,
+    dependencies: Option<String>,
+    locale: Option<String>,
+    target_format: Option<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<PackageResult, String> {
+    log::info!(
+        "[package_skill_with_parameters] skill={} label={} locale={:?} target_format={:?}",
+        skill_name, label, locale, target_format
+    );
+    super::observer::ensure_writable(&skill_name)?;
+    if !crate::commands::skill::is_valid_kebab(&label) {
+        return Err(format!("Invalid package label '{}': use kebab-case", label));
+    }
+    if let Some(lang) = locale.as_deref() {
+        crate::commands::skill_localization::validate_language(lang)?;
+    }
+    let (skills_path, format) = {
+        let conn = db.0.lock().map_err(|e| {
+            log::error!("[package_skill_with_parameters] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        let skills_path = crate::db::read_settings(&conn)?
+            .skills_path
+            .ok_or_else(|| "Skills path not configured. Please set it in Settings.".to_string())?;
+        let format = crate::commands::package_formats::resolve_format(&conn, &skill_name, target_format.as_deref())
+            .map_err(|e| {
+                log::error!("[package_skill_with_parameters] {}", e);
+                e
+            })?;
+        (skills_path, format)
+    };
+    let source_dir = Path::new(&skills_path).join(&skill_name);
+    let skill_md = std::fs::read_to_string(source_dir.join("SKILL.md")).map_err(|e| {
+        log::error!("[package_skill_with_parameters] Failed to read SKILL.md: {}", e);
+        format!("Failed to read SKILL.md for '{}': {}", skill_name, e)
+    })?;
+    let vars = parse_skill_variables(&skill_md)?;
+    if vars.is_empty() {
+        return Err(format!("Skill '{}' declares no variables", skill_name));
+    }
+    let values = resolve_parameters(&vars, &parameters).map_err(|e| {
+        log::error!("[package_skill_with_parameters] {}", e);
+        e
+    })?;
+
+    // The staged copy keeps the skill's directory name, which validation
+    // checks against the frontmatter.
+    let staging_root = std::env::temp_dir().join(format!("skill-builder-package-{}", uuid::Uuid::new_v4()));
+    let staging = staging_root.join(&skill_name);
+    let rendered = {
+        let (source_dir, staging) = (source_dir.clone(), staging.clone());
+        tokio::task::spawn_blocking(move || {
+            std::fs::create_dir_all(&staging)
+                .map_err(|e| e.to_string())
+                .and_then(|_| crate::commands::imported_skills::copy_dir_recursive(&source_dir, &staging))
+                .and_then(|_| render_dir(&staging, &values))
+        })
+        .await
+        .map_err(|e| format!("Packaging task failed: {}", e))
+        .and_then(|r| r)
+    };
+    let result = match rendered {
+        Ok(_) => {
+            let output_path =
+                source_dir.join(format.file_name(&format!("{}-{}", skill_name, label), locale.as_deref()));
+            crate::commands::workflow::package_skill_dir(
+                &db,
+                &skill_name,
+                staging,
+                output_path,
+                refuse_on_errors.unwrap_or(false),
+                dependencies.as_deref(),
+                locale.as_deref(),
+                format,
+            )
+            .await
+        }
+        Err(e) => Err(e),
+    };
+    let _ = std::fs::remove_dir_all(&staging_root);
+    result.map_err(|e| {
+        log::error!("[package_skill_with_parameters] {}", e);
+        e
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SKILL_MD: &str = "---\nname: warehouse-loads\ndescription: Load patterns\nvariables:\n  env:\n    description: Target environment\n    allowed: [dev, staging, prod]\n  schema_prefix: analytics\n  owner:\n    required: false\n---\n# Loads for {{env}}\n\nUse `{{ schema_prefix }}_raw` and {{ ref('orders') }}.\n";
+
+    fn provided(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_parse_skill_variables() {
+        let vars = parse_skill_variables(SKILL_MD).unwrap();
+        assert_eq!(vars.len(), 3);
+        assert_eq!(vars[0].name, "env");
+        assert_eq!(vars[0].description.as_deref(), Some("Target environment"));
+        assert_eq!(vars[0].allowed, vec!["dev", "staging", "prod"]);
+        assert!(vars[0].required);
+        assert_eq!(vars[1].default.as_deref(), Some("analytics"));
+        assert!(!vars[1].required);
+        assert!(!vars[2].required);
+
+        assert!(parse_skill_variables("---\nname: plain\n---\nbody").unwrap().is_empty());
+        assert!(parse_skill_variables("no frontmatter").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_declarations() {
+        assert!(parse_skill_variables("---\nvariables:\n  bad-name: x\n---\n").is_err());
+        assert!(parse_skill_variables("---\nvariables:\n  a: x\n  a: y\n---\n").is_err());
+        assert!(
+            parse_skill_variables("---\nvariables:\n  env:\n    default: qa\n    allowed: [dev, prod]\n---\n")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_resolve_parameters_applies_defaults_and_validates() {
+        let vars = parse_skill_variables(SKILL_MD).unwrap();
+        let values = resolve_parameters(&vars, &provided(&[("env", "prod")])).unwrap();
+        assert_eq!(values["env"], "prod");
+        assert_eq!(values["schema_prefix"], "analytics");
+        assert_eq!(values["owner"], "");
+
+        let err = resolve_parameters(&vars, &provided(&[("env", "qa"), ("extra", "1")])).unwrap_err();
+        assert!(err.contains("unknown parameter 'extra'"), "{err}");
+        assert!(err.contains("'env' must be one of: dev, staging, prod"), "{err}");
+
+        let err = resolve_parameters(&vars, &HashMap::new()).unwrap_err();
+        assert!(err.contains("'env' is required"), "{err}");
+    }
+
+    #[test]
+    fn test_render_leaves_undeclared_placeholders() {
+        let vars = parse_skill_variables(SKILL_MD).unwrap();
+        let values = resolve_parameters(&vars, &provided(&[("env", "staging")])).unwrap();
+        let rendered = render_template(SKILL_MD, &values);
+        assert!(rendered.contains("# Loads for staging"));
+        assert!(rendered.contains("`analytics_raw`"));
+        assert!(rendered.contains("{{ ref('orders') }}"));
+        assert_eq!(render_template("unclosed {{env", &values), "unclosed {{env");
+    }
+
+    #[test]
+    fn test_render_dir_rewrites_text_files_only() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("references")).unwrap();
+        std::fs::write(dir.path().join("SKILL.md"), "env={{env}}").unwrap();
+        std::fs::write(dir.path().join("references/load.sql"), "select * from {{env}}.orders").unwrap();
+        std::fs::write(dir.path().join("logo.png"), "{{env}}").unwrap();
+
+        let values = BTreeMap::from([("env".to_string(), "prod".to_string())]);
+        assert_eq!(render_dir(dir.path(), &values).unwrap(), 2);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("references/load.sql")).unwrap(),
+            "select * from prod.orders"
+        );
+        assert_eq!(std::fs::read_to_string(dir.path().join("logo.png")).unwrap(), "{{env}}");
+    }
+}
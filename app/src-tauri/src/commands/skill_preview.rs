@@ -0,0 +1,612 @@
+//! SKILL.md rendered the way the model consumes it.
+//!
+//! The frontmatter is split into fields and the description into plain and
+//! trigger text — the sentences with a "when" clause the model matches
+//! requests against. The body becomes a block AST the UI renders directly, so
+//! raw HTML in the file is shown as text and never interpreted. Relative links
+//! to files in the skill resolve to stubs with the file's title, opening
+//! paragraph and size, which is all the model knows about a reference until
+//! it opens it.
+
+use std::path::Path;
+
+use super::imported_skills::validate_skill_name;
+use super::skill_lint::heading_level;
+use super::skill_validation::{frontmatter_lines, is_external};
+use crate::db::Db;
+use crate::types::{PreviewBlock, PreviewField, PreviewInline, PreviewListItem, ReferenceStub, SkillPreview};
+
+/// Longest reference summary, in characters.
+const SUMMARY_CHARS: usize = 200;
+
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = value.strip_prefix(quote).and_then(|v| v.strip_suffix(quote)) {
+            return inner;
+        }
+    }
+    value
+}
+
+/// Frontmatter keys in file order. Block scalars are folded onto one line and
+/// list items joined with commas.
+fn parse_fields(block: &[(usize, &str)]) -> Vec<PreviewField> {
+    let mut fields: Vec<PreviewField> = Vec::new();
+    for (_, line) in block {
+        let part = line.trim();
+        if part.is_empty() || part.starts_with('#') {
+            continue;
+        }
+        let continuation = line.starts_with([' ', '\t']) || part.starts_with("- ");
+        if let Some(field) = fields.last_mut().filter(|_| continuation) {
+            let (separator, text) = match part.strip_prefix("- ") {
+                Some(item) => (", ", unquote(item)),
+                None => (" ", part),
+            };
+            if !field.value.is_empty() {
+                field.value.push_str(separator);
+            }
+            field.value.push_str(text);
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        let value = if value.starts_with(['|', '>']) { "" } else { unquote(value) };
+        fields.push(PreviewField {
+            key: key.trim().to_string(),
+            value: value.to_string(),
+        });
+    }
+    fields
+}
+
+fn push_text(out: &mut Vec<PreviewInline>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    match out.last_mut() {
+        Some(PreviewInline::Text { text: last }) => last.push_str(text),
+        _ => out.push(PreviewInline::Text { text: text.to_string() }),
+    }
+}
+
+fn is_trigger_sentence(sentence: &str) -> bool {
+    sentence.split_whitespace().any(|w| {
+        let w = w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase();
+        w == "when" || w == "whenever"
+    })
+}
+
+/// The description with each sentence that says when to use the skill as a
+/// trigger span, using the same "when" test as skill validation.
+fn split_triggers(description: &str) -> Vec<PreviewInline> {
+    fn push_sentence(out: &mut Vec<PreviewInline>, sentence: &str) {
+        if is_trigger_sentence(sentence) {
+            out.push(PreviewInline::Trigger { text: sentence.to_string() });
+        } else {
+            push_text(out, sentence);
+        }
+    }
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut chars = description.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if !matches!(c, '.' | '!' | '?') || !chars.peek().is_some_and(|(_, next)| next.is_whitespace()) {
+            continue;
+        }
+        let end = i + c.len_utf8();
+        push_sentence(&mut out, &description[start..end]);
+        while chars.peek().is_some_and(|(_, next)| next.is_whitespace()) {
+            chars.next();
+        }
+        start = chars.peek().map_or(description.len(), |(j, _)| *j);
+        push_text(&mut out, &description[end..start]);
+    }
+    if start < description.len() {
+        push_sentence(&mut out, &description[start..]);
+    }
+    out
+}
+
+/// The inline element at the start of `s` and its length in bytes. `prev` is
+/// the character before it, so `snake_case` isn't read as emphasis.
+fn inline_at(s: &str, prev: Option<char>) -> Option<(PreviewInline, usize)> {
+    if let Some(after) = s.strip_prefix('`') {
+        let end = after.find('`')?;
+        return Some((PreviewInline::Code { text: after[..end].to_string() }, end + 2));
+    }
+    let link_start = if s.starts_with("![") { 2 } else { usize::from(s.starts_with('[')) };
+    if link_start > 0 {
+        let after = &s[link_start..];
+        let close = after.find("](")?;
+        let target = &after[close + 2..];
+        let end = target.find(')')?;
+        let href = target[..end].split_whitespace().next().unwrap_or("");
+        let link = PreviewInline::Link {
+            text: after[..close].to_string(),
+            href: href.trim_matches(['<', '>']).to_string(),
+            reference: None,
+        };
+        return Some((link, link_start + close + 2 + end + 1));
+    }
+    for marker in ["**", "__"] {
+        if let Some(after) = s.strip_prefix(marker) {
+            let end = after.find(marker).filter(|end| *end > 0)?;
+            return Some((PreviewInline::Strong { text: after[..end].to_string() }, end + 4));
+        }
+    }
+    for marker in ['*', '_'] {
+        let Some(after) = s.strip_prefix(marker) else {
+            continue;
+        };
+        if after.starts_with(char::is_whitespace) || (marker == '_' && prev.is_some_and(char::is_alphanumeric)) {
+            return None;
+        }
+        let end = after.find(marker).filter(|end| *end > 0)?;
+        if marker == '_' && after[end + 1..].starts_with(char::is_alphanumeric) {
+            return None;
+        }
+        return Some((PreviewInline::Emphasis { text: after[..end].to_string() }, end + 2));
+    }
+    None
+}
+
+fn parse_inline(text: &str) -> Vec<PreviewInline> {
+    let mut out = Vec::new();
+    let mut rest = text;
+    let mut prev = None;
+    while let Some(c) = rest.chars().next() {
+        if let Some((node, len)) = inline_at(rest, prev) {
+            out.push(node);
+            rest = &rest[len..];
+            prev = None;
+        } else {
+            let mut buf = [0; 4];
+            push_text(&mut out, c.encode_utf8(&mut buf));
+            rest = &rest[c.len_utf8()..];
+            prev = Some(c);
+        }
+    }
+    out
+}
+
+fn plain_text(inlines: &[PreviewInline]) -> String {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            PreviewInline::Text { text }
+            | PreviewInline::Code { text }
+            | PreviewInline::Strong { text }
+            | PreviewInline::Emphasis { text }
+            | PreviewInline::Link { text, .. }
+            | PreviewInline::Trigger { text } => text.as_str(),
+        })
+        .collect()
+}
+
+fn is_rule(line: &str) -> bool {
+    let marks: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3 && matches!(marks[0], '-' | '*' | '_') && marks.iter().all(|c| *c == marks[0])
+}
+
+/// Indent, whether it's numbered, and the text of a list item line.
+fn list_item(line: &str) -> Option<(usize, bool, &str)> {
+    let rest = line.trim_start();
+    let indent = line.len() - rest.len();
+    if let Some(text) = rest.strip_prefix(['-', '*', '+']).and_then(|r| r.strip_prefix(' ')) {
+        return Some((indent, false, text.trim()));
+    }
+    let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+    if digits == 0 {
+        return None;
+    }
+    let text = rest[digits..].strip_prefix(['.', ')'])?.strip_prefix(' ')?;
+    Some((indent, true, text.trim()))
+}
+
+fn is_table_separator(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with('|') && line.contains('-') && line.chars().all(|c| matches!(c, '|' | '-' | ':' | ' '))
+}
+
+fn table_cells(line: &str) -> Vec<Vec<PreviewInline>> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = line.strip_suffix('|').unwrap_or(line);
+    line.split('|').map(|cell| parse_inline(cell.trim())).collect()
+}
+
+fn flush_paragraph(paragraph: &mut Vec<&str>, blocks: &mut Vec<PreviewBlock>) {
+    if !paragraph.is_empty() {
+        blocks.push(PreviewBlock::Paragraph {
+            content: parse_inline(&paragraph.join(" ")),
+        });
+        paragraph.clear();
+    }
+}
+
+fn parse_blocks(lines: &[&str]) -> Vec<PreviewBlock> {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let trimmed = lines[i].trim();
+        if trimmed.is_empty() {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            i += 1;
+            continue;
+        }
+        if let Some(fence) = ["```", "~~~"].into_iter().find(|f| trimmed.starts_with(f)) {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            let language = trimmed.trim_start_matches(fence.chars().next().unwrap_or('`')).split_whitespace().next();
+            let mut code = Vec::new();
+            i += 1;
+            while i < lines.len() && !lines[i].trim_start().starts_with(fence) {
+                code.push(lines[i]);
+                i += 1;
+            }
+            blocks.push(PreviewBlock::Code {
+                language: language.map(str::to_string),
+                text: code.join("\n"),
+            });
+            i += 1;
+            continue;
+        }
+        if let Some(level) = heading_level(trimmed) {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            blocks.push(PreviewBlock::Heading {
+                level,
+                content: parse_inline(trimmed[level..].trim()),
+            });
+            i += 1;
+            continue;
+        }
+        if is_rule(trimmed) {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            blocks.push(PreviewBlock::Rule);
+            i += 1;
+            continue;
+        }
+        if trimmed.starts_with('>') {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            let mut quote = Vec::new();
+            while let Some(line) = lines.get(i).and_then(|l| l.trim().strip_prefix('>')) {
+                quote.push(line.trim());
+                i += 1;
+            }
+            blocks.push(PreviewBlock::Quote {
+                content: parse_inline(quote.join(" ").trim()),
+            });
+            continue;
+        }
+        if let Some((base, ordered, _)) = list_item(lines[i]) {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            let mut items: Vec<(usize, String)> = Vec::new();
+            let mut indents: Vec<usize> = Vec::new();
+            while i < lines.len() {
+                let line = lines[i];
+                if let Some((indent, item_ordered, text)) = list_item(line) {
+                    // A top-level item of the other kind starts a new list.
+                    if indent <= base && item_ordered != ordered {
+                        break;
+                    }
+                    while indents.last().is_some_and(|last| *last > indent) {
+                        indents.pop();
+                    }
+                    if indents.last().is_none_or(|last| *last < indent) {
+                        indents.push(indent);
+                    }
+                    items.push((indents.len() - 1, text.to_string()));
+                } else if line.starts_with([' ', '\t']) && !line.trim().is_empty() {
+                    // Continuation of the previous item.
+                    if let Some((_, text)) = items.last_mut() {
+                        text.push(' ');
+                        text.push_str(line.trim());
+                    }
+                } else if !(line.trim().is_empty() && lines.get(i + 1).is_some_and(|next| list_item(next).is_some())) {
+                    break;
+                }
+                i += 1;
+            }
+            blocks.push(PreviewBlock::List {
+                ordered,
+                items: items
+                    .into_iter()
+                    .map(|(depth, text)| PreviewListItem {
+                        depth,
+                        content: parse_inline(&text),
+                    })
+                    .collect(),
+            });
+            continue;
+        }
+        if trimmed.starts_with('|') && lines.get(i + 1).is_some_and(|next| is_table_separator(next)) {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            let header = table_cells(trimmed);
+            let mut rows = Vec::new();
+            i += 2;
+            while let Some(row) = lines.get(i).filter(|l| l.trim().starts_with('|')) {
+                rows.push(table_cells(row));
+                i += 1;
+            }
+            blocks.push(PreviewBlock::Table { header, rows });
+            continue;
+        }
+        paragraph.push(trimmed);
+        i += 1;
+    }
+    flush_paragraph(&mut paragraph, &mut blocks);
+    blocks
+}
+
+/// `content` after its frontmatter, as lines.
+fn body_lines(content: &str) -> Vec<&str> {
+    let skip = frontmatter_lines(content).map_or(0, |block| block.len() + 2);
+    content.lines().skip(skip).collect()
+}
+
+/// The skill file a relative link points at, or `None` for external links,
+/// anchors and paths that leave the skill directory.
+fn local_reference(href: &str) -> Option<String> {
+    if is_external(href) {
+        return None;
+    }
+    let path = href.split(['#', '?']).next().unwrap_or(href);
+    let mut parts = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => return None,
+            part => parts.push(part),
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+fn reference_stub(skill_dir: &Path, path: &str) -> ReferenceStub {
+    let file = skill_dir.join(path);
+    let bytes = std::fs::metadata(&file).ok().filter(|m| m.is_file()).map(|m| m.len());
+    let content = bytes.and_then(|_| std::fs::read_to_string(&file).ok()).unwrap_or_default();
+    let (mut title, mut summary) = (None, None);
+    if path.ends_with(".md") {
+        for block in parse_blocks(&body_lines(&content)) {
+            match block {
+                PreviewBlock::Heading { content, .. } if title.is_none() => title = Some(plain_text(&content)),
+                PreviewBlock::Paragraph { content } if summary.is_none() => {
+                    let text = plain_text(&content);
+                    summary = Some(match text.char_indices().nth(SUMMARY_CHARS) {
+                        Some((cut, _)) => format!("{}…", text[..cut].trim_end()),
+                        None => text,
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+    ReferenceStub {
+        path: path.to_string(),
+        exists: bytes.is_some(),
+        title,
+        summary,
+        lines: content.lines().count(),
+        bytes: bytes.unwrap_or(0),
+    }
+}
+
+fn block_inlines_mut(block: &mut PreviewBlock) -> Vec<&mut Vec<PreviewInline>> {
+    match block {
+        PreviewBlock::Heading { content, .. } | PreviewBlock::Paragraph { content } | PreviewBlock::Quote { content } => {
+            vec![content]
+        }
+        PreviewBlock::List { items, .. } => items.iter_mut().map(|item| &mut item.content).collect(),
+        PreviewBlock::Table { header, rows } => header.iter_mut().chain(rows.iter_mut().flatten()).collect(),
+        PreviewBlock::Code { .. } | PreviewBlock::Rule => Vec::new(),
+    }
+}
+
+/// Build the preview of `content`, the SKILL.md in `skill_dir`.
+pub(crate) fn render_preview(skill_name: &str, skill_dir: &Path, content: &str) -> SkillPreview {
+    let fields = frontmatter_lines(content).map(|block| parse_fields(&block)).unwrap_or_default();
+    let description = fields
+        .iter()
+        .find(|f| f.key == "description")
+        .map(|f| split_triggers(&f.value))
+        .unwrap_or_default();
+    let mut blocks = parse_blocks(&body_lines(content));
+
+    let mut references: Vec<ReferenceStub> = Vec::new();
+    for inlines in blocks.iter_mut().flat_map(block_inlines_mut) {
+        for inline in inlines.iter_mut() {
+            let PreviewInline::Link { href, reference, .. } = inline else {
+                continue;
+            };
+            *reference = local_reference(href);
+            if let Some(path) = reference.as_deref() {
+                if !references.iter().any(|r| r.path == path) {
+                    references.push(reference_stub(skill_dir, path));
+                }
+            }
+        }
+    }
+
+    SkillPreview {
+        skill_name: skill_name.to_string(),
+        fields,
+        description,
+        blocks,
+        references,
+    }
+}
+
+/// SKILL.md of a library skill as structured preview content: frontmatter
+/// fields, the description with trigger sentences marked, the body as blocks
+/// and a stub for each linked skill file.
+#[tauri::command]
+pub fn render_skill_preview(skill_name: String, db: tauri::State<'_, Db>) -> Result<SkillPreview, String> {
+    log::info!("[render_skill_preview] skill={}", skill_name);
+    validate_skill_name(&skill_name)?;
+    let skills_path = {
+        let conn = db.0.read().map_err(|e| {
+            log::error!("[render_skill_preview] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        crate::db::read_settings(&conn)?.skills_path
+    }
+    .ok_or_else(|| "Skills path not configured. Please set it in Settings.".to_string())?;
+    let skill_dir = Path::new(&skills_path).join(&skill_name);
+    let content = std::fs::read_to_string(skill_dir.join("SKILL.md")).map_err(|e| {
+        log::error!("[render_skill_preview] Failed to read SKILL.md for {}: {}", skill_name, e);
+        format!("Failed to read SKILL.md: {}", e)
+    })?;
+    let preview = render_preview(&skill_name, &skill_dir, &content);
+    log::info!(
+        "[render_skill_preview] skill={} blocks={} references={}",
+        skill_name,
+        preview.blocks.len(),
+        preview.references.len()
+    );
+    Ok(preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn text(s: &str) -> PreviewInline {
+        PreviewInline::Text { text: s.to_string() }
+    }
+
+    #[test]
+    fn test_description_trigger_sentences() {
+        let inlines = split_triggers("Analyzes sales pipelines. Use when reviewing pipeline health.  Not for forecasts.");
+        assert_eq!(
+            inlines,
+            vec![
+                text("Analyzes sales pipelines. "),
+                PreviewInline::Trigger { text: "Use when reviewing pipeline health.".into() },
+                text("  Not for forecasts."),
+            ]
+        );
+        assert_eq!(split_triggers("Formats reports"), vec![text("Formats reports")]);
+    }
+
+    #[test]
+    fn test_fields_fold_block_scalars_and_lists() {
+        let content = "---\nname: 'sales-pipeline'\ndescription: >\n  Analyzes pipelines.\n  Use when asked.\nallowed-tools:\n  - Read\n  - Grep\n---\n";
+        let fields = parse_fields(&frontmatter_lines(content).unwrap());
+        let pairs: Vec<(&str, &str)> = fields.iter().map(|f| (f.key.as_str(), f.value.as_str())).collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("name", "sales-pipeline"),
+                ("description", "Analyzes pipelines. Use when asked."),
+                ("allowed-tools", "Read, Grep"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inline_markup_keeps_html_and_snake_case_as_text() {
+        assert_eq!(
+            parse_inline("Run `make` on **all** <b>deal_stage</b> rows, _not_ [docs](https://x.io)"),
+            vec![
+                text("Run "),
+                PreviewInline::Code { text: "make".into() },
+                text(" on "),
+                PreviewInline::Strong { text: "all".into() },
+                text(" <b>deal_stage</b> rows, "),
+                PreviewInline::Emphasis { text: "not".into() },
+                text(" "),
+                PreviewInline::Link { text: "docs".into(), href: "https://x.io".into(), reference: None },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_blocks() {
+        let lines = [
+            "# Sales",
+            "First line",
+            "continues here.",
+            "",
+            "- one",
+            "  - nested",
+            "- two",
+            "",
+            "1. step",
+            "",
+            "```sql",
+            "# not a heading",
+            "```",
+            "> note",
+            "---",
+            "| Stage | Rate |",
+            "|---|---|",
+            "| Won | 30% |",
+        ];
+        let blocks = parse_blocks(&lines);
+        assert_eq!(blocks.len(), 8, "{:?}", blocks);
+        assert_eq!(blocks[0], PreviewBlock::Heading { level: 1, content: vec![text("Sales")] });
+        assert_eq!(blocks[1], PreviewBlock::Paragraph { content: vec![text("First line continues here.")] });
+        let PreviewBlock::List { ordered: false, items } = &blocks[2] else {
+            panic!("expected a bullet list: {:?}", blocks[2]);
+        };
+        assert_eq!(items.iter().map(|i| i.depth).collect::<Vec<_>>(), vec![0, 1, 0]);
+        assert!(matches!(&blocks[3], PreviewBlock::List { ordered: true, items } if items.len() == 1));
+        assert_eq!(
+            blocks[4],
+            PreviewBlock::Code { language: Some("sql".into()), text: "# not a heading".into() }
+        );
+        assert_eq!(blocks[5], PreviewBlock::Quote { content: vec![text("note")] });
+        assert_eq!(blocks[6], PreviewBlock::Rule);
+        let PreviewBlock::Table { header, rows } = &blocks[7] else {
+            panic!("expected a table: {:?}", blocks[7]);
+        };
+        assert_eq!((header.len(), rows.len()), (2, 1));
+    }
+
+    #[test]
+    fn test_render_preview_resolves_reference_stubs() {
+        let dir = tempdir().unwrap();
+        let skill_dir = dir.path().join("sales-pipeline");
+        std::fs::create_dir_all(skill_dir.join("references")).unwrap();
+        std::fs::write(
+            skill_dir.join("references/stages.md"),
+            "---\nname: stages\n---\n# Pipeline Stages\n\nEvery deal moves through **five** stages.\n",
+        )
+        .unwrap();
+        let content = "---\nname: sales-pipeline\ndescription: Analyzes pipelines. Use when asked.\n---\n# Sales\n\nSee [stages](./references/stages.md#won), [again](references/stages.md), [missing](references/gone.md), [parent](../other/SKILL.md) and [top](#sales).\n";
+
+        let preview = render_preview("sales-pipeline", &skill_dir, content);
+        assert_eq!(preview.fields.len(), 2);
+        assert!(matches!(preview.description.last(), Some(PreviewInline::Trigger { .. })));
+        let paths: Vec<&str> = preview.references.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, vec!["references/stages.md", "references/gone.md"]);
+
+        let stages = &preview.references[0];
+        assert!(stages.exists);
+        assert_eq!(stages.title.as_deref(), Some("Pipeline Stages"));
+        assert_eq!(stages.summary.as_deref(), Some("Every deal moves through five stages."));
+        assert_eq!(stages.lines, 6);
+        assert!(!preview.references[1].exists);
+
+        let PreviewBlock::Paragraph { content } = &preview.blocks[1] else {
+            panic!("expected a paragraph: {:?}", preview.blocks[1]);
+        };
+        let references: Vec<Option<&str>> = content
+            .iter()
+            .filter_map(|i| match i {
+                PreviewInline::Link { reference, .. } => Some(reference.as_deref()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            references,
+            vec![Some("references/stages.md"), Some("references/stages.md"), Some("references/gone.md"), None, None]
+        );
+    }
+}
@@ -0,0 +1,417 @@
+//! Supporting documents in a skill's `references/` folder.
+//!
+//! `add_skill_reference` copies a local file or fetches a URL into
+//! `references/`, refusing files over the size limits and binary files other
+//! than images and PDFs. Added files get a line in SKILL.md's reference index
+//! (the `## Reference Files` section, created when missing); removed files
+//! lose theirs. Each change is committed to the skills repo.
+
+use std::path::Path;
+
+use crate::db::Db;
+use crate::types::SkillReference;
+
+use super::linked_references::{commit_skills_repo, skill_dir_and_token, validate_reference_path};
+
+const REFERENCES_DIR: &str = "references";
+const INDEX_HEADING: &str = "## Reference Files";
+/// Headings (level 2, case-insensitive) treated as the reference index.
+const INDEX_TITLES: &[&str] = &["reference files", "references"];
+const MAX_TEXT_BYTES: u64 = 1024 * 1024;
+const MAX_BINARY_BYTES: u64 = 5 * 1024 * 1024;
+/// Binary files agents can read; anything else binary is refused.
+const BINARY_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "pdf"];
+const FETCH_TIMEOUT_SECS: u64 = 30;
+
+/// Text is valid UTF-8 without NUL bytes in the first 8 KB.
+fn is_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(8192)].contains(&0) || std::str::from_utf8(bytes).is_err()
+}
+
+/// Check `bytes` can be stored as `name`; returns whether the file is binary.
+fn check_content(name: &str, bytes: &[u8]) -> Result<bool, String> {
+    let binary = is_binary(bytes);
+    let size = bytes.len() as u64;
+    if binary {
+        let extension = Path::new(name)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if !BINARY_EXTENSIONS.contains(&extension.as_str()) {
+            return Err(format!(
+                "'{}' is a binary file. Only text files and {} files can be added as references.",
+                name,
+                BINARY_EXTENSIONS.join(", ")
+            ));
+        }
+        if size > MAX_BINARY_BYTES {
+            return Err(format!("'{}' is {} bytes; binary references are limited to {} bytes", name, size, MAX_BINARY_BYTES));
+        }
+    } else if size > MAX_TEXT_BYTES {
+        return Err(format!("'{}' is {} bytes; text references are limited to {} bytes", name, size, MAX_TEXT_BYTES));
+    }
+    Ok(binary)
+}
+
+fn is_index_heading(line: &str) -> bool {
+    line.strip_prefix("## ")
+        .is_some_and(|title| INDEX_TITLES.contains(&title.trim().to_lowercase().as_str()))
+}
+
+/// Line range of the reference index: the heading and the lines up to the next
+/// level 1 or 2 heading.
+fn index_section(lines: &[&str]) -> Option<(usize, usize)> {
+    let start = lines.iter().position(|l| is_index_heading(l))?;
+    let end = lines[start + 1..]
+        .iter()
+        .position(|l| l.starts_with("# ") || l.starts_with("## "))
+        .map_or(lines.len(), |i| start + 1 + i);
+    Some((start, end))
+}
+
+fn join_lines(lines: &[&str], trailing_newline: bool) -> String {
+    let mut out = lines.join("\n");
+    if trailing_newline {
+        out.push('\n');
+    }
+    out
+}
+
+/// SKILL.md with a line for `path` in the reference index, or `None` when
+/// SKILL.md already mentions it.
+fn add_to_index(skill_md: &str, path: &str) -> Option<String> {
+    if skill_md.contains(path) {
+        return None;
+    }
+    let label = path.strip_prefix("references/").unwrap_or(path);
+    let bullet = format!("- [{}]({})", label, path);
+    let mut lines: Vec<&str> = skill_md.lines().collect();
+    match index_section(&lines) {
+        Some((start, end)) => {
+            let last = (start + 1..end).rev().find(|&i| !lines[i].trim().is_empty());
+            match last {
+                Some(i) => lines.insert(i + 1, &bullet),
+                None => {
+                    lines.insert(start + 1, "");
+                    lines.insert(start + 2, &bullet);
+                    if start + 3 < lines.len() && !lines[start + 3].trim().is_empty() {
+                        lines.insert(start + 3, "");
+                    }
+                }
+            }
+            Some(join_lines(&lines, skill_md.ends_with('\n')))
+        }
+        None => Some(format!("{}\n\n{}\n\n{}\n", skill_md.trim_end(), INDEX_HEADING, bullet)),
+    }
+}
+
+/// SKILL.md without the reference index lines for `path`, or `None` when the
+/// index has none. An index left empty is removed.
+fn remove_from_index(skill_md: &str, path: &str) -> Option<String> {
+    let lines: Vec<&str> = skill_md.lines().collect();
+    let (start, end) = index_section(&lines)?;
+    let is_entry = |line: &str| {
+        let item = line.trim_start();
+        (item.starts_with("- ") || item.starts_with("* ")) && item.contains(path)
+    };
+    if !lines[start + 1..end].iter().any(|l| is_entry(l)) {
+        return None;
+    }
+    let section: Vec<&str> = lines[start + 1..end].iter().copied().filter(|l| !is_entry(l)).collect();
+    let mut out: Vec<&str> = lines[..start].to_vec();
+    if section.iter().any(|l| !l.trim().is_empty()) {
+        out.push(lines[start]);
+        out.extend(section);
+    } else {
+        while out.last().is_some_and(|l| l.trim().is_empty()) {
+            out.pop();
+        }
+        if end < lines.len() {
+            out.push("");
+        }
+    }
+    out.extend(&lines[end..]);
+    Some(join_lines(&out, skill_md.ends_with('\n')))
+}
+
+fn collect_references(skill_dir: &Path, dir: &Path, files: &mut Vec<(String, u64)>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let Ok(meta) = entry.metadata() else { continue };
+        if meta.is_dir() {
+            collect_references(skill_dir, &path, files);
+        } else if let Ok(relative) = path.strip_prefix(skill_dir) {
+            files.push((relative.to_string_lossy().replace('\\', "/"), meta.len()));
+        }
+    }
+}
+
+fn describe_reference(skill_dir: &Path, path: String, size_bytes: u64, skill_md: &str, linked: &[String]) -> SkillReference {
+    let binary = std::fs::read(skill_dir.join(&path)).map(|b| is_binary(&b)).unwrap_or(false);
+    SkillReference {
+        indexed: skill_md.contains(&path),
+        linked: linked.contains(&path),
+        binary,
+        size_bytes,
+        path,
+    }
+}
+
+pub(crate) fn list_references_inner(skill_dir: &Path, linked: &[String]) -> Vec<SkillReference> {
+    let skill_md = std::fs::read_to_string(skill_dir.join("SKILL.md")).unwrap_or_default();
+    let mut files = Vec::new();
+    collect_references(skill_dir, &skill_dir.join(REFERENCES_DIR), &mut files);
+    files.sort();
+    files
+        .into_iter()
+        .map(|(path, size)| describe_reference(skill_dir, path, size, &skill_md, linked))
+        .collect()
+}
+
+/// Write `bytes` to `references/{name}` and index it in SKILL.md.
+pub(crate) fn add_reference_inner(
+    skill_dir: &Path,
+    skill_name: &str,
+    name: &str,
+    bytes: &[u8],
+    replace: bool,
+) -> Result<SkillReference, String> {
+    let path = format!("{}/{}", REFERENCES_DIR, name.trim_start_matches('/'));
+    validate_reference_path(&path)?;
+    check_content(name, bytes)?;
+    let target = skill_dir.join(&path);
+    if target.exists() && !replace {
+        return Err(format!("'{}' already exists in '{}'", path, skill_name));
+    }
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    std::fs::write(&target, bytes).map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+
+    let skill_md_path = skill_dir.join("SKILL.md");
+    let skill_md = std::fs::read_to_string(&skill_md_path).map_err(|e| format!("Failed to read SKILL.md: {}", e))?;
+    let skill_md = match add_to_index(&skill_md, &path) {
+        Some(updated) => {
+            std::fs::write(&skill_md_path, &updated).map_err(|e| format!("Failed to write SKILL.md: {}", e))?;
+            updated
+        }
+        None => skill_md,
+    };
+    commit_skills_repo(skill_dir, &format!("{}: add {}", skill_name, path));
+    Ok(describe_reference(skill_dir, path, bytes.len() as u64, &skill_md, &[]))
+}
+
+/// Delete `references/...` at `path` and drop it from SKILL.md's index.
+pub(crate) fn remove_reference_inner(skill_dir: &Path, skill_name: &str, path: &str) -> Result<(), String> {
+    validate_reference_path(path)?;
+    let target = skill_dir.join(path);
+    if !target.is_file() {
+        return Err(format!("'{}' not found in '{}'", path, skill_name));
+    }
+    std::fs::remove_file(&target).map_err(|e| format!("Failed to delete {}: {}", target.display(), e))?;
+    let skill_md_path = skill_dir.join("SKILL.md");
+    if let Some(updated) = std::fs::read_to_string(&skill_md_path)
+        .ok()
+        .and_then(|content| remove_from_index(&content, path))
+    {
+        std::fs::write(&skill_md_path, updated).map_err(|e| format!("Failed to write SKILL.md: {}", e))?;
+    }
+    commit_skills_repo(skill_dir, &format!("{}: remove {}", skill_name, path));
+    Ok(())
+}
+
+/// Download `url`, stopping once it exceeds the binary size limit.
+async fn fetch_url(url: &str) -> Result<(Vec<u8>, Option<String>), String> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid URL '{}': {}", url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("Only http(s) URLs can be fetched, got '{}'", url));
+    }
+    let name = parsed
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|s| !s.is_empty())
+        .map(str::to_string);
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(FETCH_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut response = client
+        .get(parsed)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+    if response.content_length().is_some_and(|len| len > MAX_BINARY_BYTES) {
+        return Err(format!("{} is larger than the {} byte reference limit", url, MAX_BINARY_BYTES));
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("Failed to fetch {}: {}", url, e))? {
+        bytes.extend_from_slice(&chunk);
+        if bytes.len() as u64 > MAX_BINARY_BYTES {
+            return Err(format!("{} is larger than the {} byte reference limit", url, MAX_BINARY_BYTES));
+        }
+    }
+    Ok((bytes, name))
+}
+
+/// Files under the skill's `references/` folder.
+#[tauri::command]
+pub fn list_skill_references(skill_name: String, db: tauri::State<'_, Db>) -> Result<Vec<SkillReference>, String> {
+    log::info!("[list_skill_references] skill={}", skill_name);
+    super::imported_skills::validate_skill_name(&skill_name)?;
+    let (skill_dir, _) = skill_dir_and_token(&db, &skill_name, "list_skill_references")?;
+    let linked: Vec<String> = {
+        let conn = db.0.read().map_err(|e| {
+            log::error!("[list_skill_references] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        crate::db::list_linked_references(&conn, &skill_name)?
+            .into_iter()
+            .map(|l| l.reference_path)
+            .collect()
+    };
+    Ok(list_references_inner(&skill_dir, &linked))
+}
+
+/// Add a reference from a local file (`file_path`) or a URL (`url`), saved as
+/// `references/{file_name}` (default: the source's file name).
+#[tauri::command]
+pub async fn add_skill_reference(
+    skill_name: String,
+    file_path: Option<String>,
+    url: Option<String>,
+    file_name: Option<String>,
+    replace: Option<bool>,
+    db: tauri::State<'_, Db>,
+) -> Result<SkillReference, String> {
+    log::info!(
+        "[add_skill_reference] skill={} file={:?} url={:?} name={:?}",
+        skill_name,
+        file_path,
+        url,
+        file_name
+    );
+    super::observer::ensure_writable(&skill_name)?;
+    super::imported_skills::validate_skill_name(&skill_name)?;
+    let (skill_dir, _) = skill_dir_and_token(&db, &skill_name, "add_skill_reference")?;
+    let (bytes, source_name) = match (file_path, url) {
+        (Some(file_path), None) => {
+            let path = Path::new(&file_path);
+            let size = std::fs::metadata(path).map(|m| m.len()).map_err(|e| {
+                log::error!("[add_skill_reference] Failed to read {}: {}", file_path, e);
+                format!("Failed to read {}: {}", file_path, e)
+            })?;
+            if size > MAX_BINARY_BYTES {
+                return Err(format!("{} is larger than the {} byte reference limit", file_path, MAX_BINARY_BYTES));
+            }
+            let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
+            (bytes, path.file_name().map(|n| n.to_string_lossy().to_string()))
+        }
+        (None, Some(url)) => {
+            let offline = {
+                let conn = db.0.read().map_err(|e| e.to_string())?;
+                crate::db::read_settings(&conn)?.offline_mode
+            };
+            if offline {
+                return Err("Fetching references from a URL is unavailable in offline mode".to_string());
+            }
+            fetch_url(&url).await.map_err(|e| {
+                log::error!("[add_skill_reference] {}", e);
+                e
+            })?
+        }
+        _ => return Err("Provide either a file path or a URL".to_string()),
+    };
+    let name = file_name
+        .filter(|n| !n.trim().is_empty())
+        .or(source_name)
+        .ok_or_else(|| "Provide a file name for the reference".to_string())?;
+    add_reference_inner(&skill_dir, &skill_name, name.trim(), &bytes, replace.unwrap_or(false)).map_err(|e| {
+        log::error!("[add_skill_reference] {}", e);
+        e
+    })
+}
+
+/// Delete a reference file, unlinking it from GitHub if it was linked.
+#[tauri::command]
+pub fn remove_skill_reference(skill_name: String, path: String, db: tauri::State<'_, Db>) -> Result<(), String> {
+    log::info!("[remove_skill_reference] skill={} path={}", skill_name, path);
+    super::observer::ensure_writable(&skill_name)?;
+    super::imported_skills::validate_skill_name(&skill_name)?;
+    let (skill_dir, _) = skill_dir_and_token(&db, &skill_name, "remove_skill_reference")?;
+    remove_reference_inner(&skill_dir, &skill_name, &path).map_err(|e| {
+        log::error!("[remove_skill_reference] {}", e);
+        e
+    })?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[remove_skill_reference] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::delete_linked_reference(&conn, &skill_name, &path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SKILL_MD: &str = "---\nname: sales\n---\n# Sales\n\n## Reference Files\n\n- [glossary.md](references/glossary.md)\n\n## Usage\n\nUse it.\n";
+
+    #[test]
+    fn test_index_add_and_remove() {
+        let added = add_to_index(SKILL_MD, "references/stages.md").unwrap();
+        assert!(added.contains(
+            "- [glossary.md](references/glossary.md)\n- [stages.md](references/stages.md)\n\n## Usage"
+        ));
+        assert!(add_to_index(&added, "references/stages.md").is_none());
+
+        let removed = remove_from_index(&added, "references/stages.md").unwrap();
+        assert_eq!(removed, SKILL_MD);
+        let emptied = remove_from_index(SKILL_MD, "references/glossary.md").unwrap();
+        assert_eq!(emptied, "---\nname: sales\n---\n# Sales\n\n## Usage\n\nUse it.\n");
+        assert!(remove_from_index(SKILL_MD, "references/missing.md").is_none());
+
+        let created = add_to_index("# Sales\n\nBody.\n", "references/a.md").unwrap();
+        assert_eq!(created, "# Sales\n\nBody.\n\n## Reference Files\n\n- [a.md](references/a.md)\n");
+    }
+
+    #[test]
+    fn test_check_content_limits_and_binary() {
+        assert!(!check_content("notes.md", b"hello").unwrap());
+        assert!(check_content("flow.png", &[0x89, b'P', b'N', b'G', 0, 0]).unwrap());
+        assert!(check_content("tool.exe", &[0x4d, 0x5a, 0, 0]).unwrap_err().contains("binary file"));
+        let big = vec![b'a'; MAX_TEXT_BYTES as usize + 1];
+        assert!(check_content("big.md", &big).unwrap_err().contains("limited to"));
+    }
+
+    #[test]
+    fn test_add_list_remove_reference() {
+        let dir = tempfile::tempdir().unwrap();
+        let skill_dir = dir.path().join("sales");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(skill_dir.join("SKILL.md"), "# Sales\n").unwrap();
+
+        let added = add_reference_inner(&skill_dir, "sales", "guides/setup.md", b"# Setup\n", false).unwrap();
+        assert_eq!(added.path, "references/guides/setup.md");
+        assert!(added.indexed && !added.binary);
+        assert!(add_reference_inner(&skill_dir, "sales", "guides/setup.md", b"again", false)
+            .unwrap_err()
+            .contains("already exists"));
+        assert!(add_reference_inner(&skill_dir, "sales", "../escape.md", b"x", false).is_err());
+
+        let listed = list_references_inner(&skill_dir, &["references/guides/setup.md".to_string()]);
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].linked);
+        assert_eq!(listed[0].size_bytes, 8);
+
+        remove_reference_inner(&skill_dir, "sales", "references/guides/setup.md").unwrap();
+        assert!(list_references_inner(&skill_dir, &[]).is_empty());
+        assert_eq!(std::fs::read_to_string(skill_dir.join("SKILL.md")).unwrap(), "# Sales\n");
+    }
+}
@@ -0,0 +1,282 @@
+//! Review-by dates for skill content.
+//!
+//! A skill can carry a `review-by: YYYY-MM-DD` frontmatter field, mirrored in
+//! `skills.review_by`. Within `REVIEW_DUE_SOON_DAYS` of that date the skill is
+//! due for review, and after it the skill is expired. With the
+//! `block_expired_packaging` setting on, expired skills cannot be packaged
+//! until someone marks them reviewed with a new date.
+
+use std::path::Path;
+
+use chrono::NaiveDate;
+
+use super::imported_skills::validate_skill_name;
+use crate::db::Db;
+use crate::types::{SkillMasterRow, SkillReviewStatus};
+
+/// Days before the review-by date at which a skill counts as due.
+pub(crate) const REVIEW_DUE_SOON_DAYS: i64 = 14;
+
+pub(crate) const REVIEW_OK: &str = "ok";
+pub(crate) const REVIEW_DUE_SOON: &str = "due_soon";
+pub(crate) const REVIEW_EXPIRED: &str = "expired";
+
+const REVIEW_BY_KEY: &str = "review-by";
+
+pub(crate) fn parse_review_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("Invalid review-by date '{}': use YYYY-MM-DD", value))
+}
+
+fn today() -> NaiveDate {
+    chrono::Local::now().date_naive()
+}
+
+/// Review state and days remaining (negative once expired).
+fn review_state(review_by: NaiveDate, today: NaiveDate) -> (&'static str, i64) {
+    let days = (review_by - today).num_days();
+    let state = if days < 0 {
+        REVIEW_EXPIRED
+    } else if days <= REVIEW_DUE_SOON_DAYS {
+        REVIEW_DUE_SOON
+    } else {
+        REVIEW_OK
+    };
+    (state, days)
+}
+
+/// Review status of every skill with a review-by date, soonest first.
+fn build_review_report(skills: Vec<SkillMasterRow>, today: NaiveDate) -> Vec<SkillReviewStatus> {
+    let mut report: Vec<SkillReviewStatus> = skills
+        .into_iter()
+        .filter_map(|skill| {
+            let review_by = skill.review_by?;
+            let date = match parse_review_date(&review_by) {
+                Ok(date) => date,
+                Err(e) => {
+                    log::warn!("[get_skill_review_report] '{}': {}", skill.name, e);
+                    return None;
+                }
+            };
+            let (state, days_remaining) = review_state(date, today);
+            Some(SkillReviewStatus {
+                skill_name: skill.name,
+                review_by,
+                reviewed_at: skill.reviewed_at,
+                state: state.to_string(),
+                days_remaining,
+            })
+        })
+        .collect();
+    report.sort_by(|a, b| a.days_remaining.cmp(&b.days_remaining).then_with(|| a.skill_name.cmp(&b.skill_name)));
+    report
+}
+
+/// Refuse packaging when `block_expired_packaging` is on and the skill's
+/// review-by date has passed.
+pub(crate) fn check_packaging_allowed(conn: &rusqlite::Connection, skill_name: &str) -> Result<(), String> {
+    if !crate::db::read_settings(conn)?.block_expired_packaging {
+        return Ok(());
+    }
+    let (review_by, _) = crate::db::get_skill_review(conn, skill_name)?;
+    let Some(review_by) = review_by else {
+        return Ok(());
+    };
+    let date = parse_review_date(&review_by)?;
+    if review_state(date, today()).0 == REVIEW_EXPIRED {
+        return Err(format!(
+            "'{}' passed its review-by date ({}). Mark it reviewed before packaging.",
+            skill_name, review_by
+        ));
+    }
+    Ok(())
+}
+
+/// Mirror a `review-by` frontmatter value into the DB after an import.
+/// Invalid dates are logged and ignored.
+pub(crate) fn sync_review_from_frontmatter(conn: &rusqlite::Connection, skill_name: &str, skill_md: &str) {
+    let Some(review_by) = super::imported_skills::parse_frontmatter_full(skill_md).review_by else {
+        return;
+    };
+    let result = parse_review_date(&review_by)
+        .and_then(|_| crate::db::set_skill_review(conn, skill_name, Some(&review_by), false));
+    if let Err(e) = result {
+        log::warn!("[sync_review_from_frontmatter] '{}': {}", skill_name, e);
+    }
+}
+
+/// Write (or remove) `review-by` in the skill's SKILL.md. Returns whether the
+/// file changed.
+fn write_review_frontmatter(skill_dir: &Path, review_by: Option<&str>) -> Result<bool, String> {
+    let path = skill_dir.join("SKILL.md");
+    if !path.is_file() {
+        return Ok(false);
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read SKILL.md: {}", e))?;
+    let updated = match review_by {
+        Some(date) => super::frontmatter_migration::set_frontmatter_fields(&content, &[(REVIEW_BY_KEY, date)]),
+        None => super::frontmatter_migration::remove_frontmatter_fields(&content, &[REVIEW_BY_KEY]),
+    };
+    if updated == content {
+        return Ok(false);
+    }
+    std::fs::write(&path, updated).map_err(|e| format!("Failed to write SKILL.md: {}", e))?;
+    Ok(true)
+}
+
+fn set_review_inner(
+    conn: &rusqlite::Connection,
+    skills_path: Option<&str>,
+    skill_name: &str,
+    review_by: Option<&str>,
+    reviewed: bool,
+) -> Result<(), String> {
+    crate::db::set_skill_review(conn, skill_name, review_by, reviewed)?;
+    let Some(skills_path) = skills_path else {
+        return Ok(());
+    };
+    if write_review_frontmatter(&Path::new(skills_path).join(skill_name), review_by)? {
+        let msg = match (review_by, reviewed) {
+            (Some(date), true) => format!("{}: reviewed, next review by {}", skill_name, date),
+            (Some(date), false) => format!("{}: review by {}", skill_name, date),
+            (None, _) => format!("{}: clear review-by date", skill_name),
+        };
+        if let Err(e) = crate::git::commit_all(Path::new(skills_path), &msg) {
+            log::warn!("Git auto-commit failed ({}): {}", msg, e);
+        }
+    }
+    Ok(())
+}
+
+/// Set or clear a skill's review-by date in the DB and its SKILL.md.
+#[tauri::command]
+pub fn set_skill_review_date(
+    skill_name: String,
+    review_by: Option<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<(), String> {
+    log::info!("[set_skill_review_date] skill={} review_by={:?}", skill_name, review_by);
+    super::observer::ensure_writable(&skill_name)?;
+    validate_skill_name(&skill_name)?;
+    let review_by = review_by.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    if let Some(ref date) = review_by {
+        parse_review_date(date)?;
+    }
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[set_skill_review_date] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let skills_path = crate::db::read_settings(&conn)?.skills_path;
+    set_review_inner(&conn, skills_path.as_deref(), &skill_name, review_by.as_deref(), false).map_err(|e| {
+        log::error!("[set_skill_review_date] {}", e);
+        e
+    })
+}
+
+/// Record that a skill's content was reviewed and set the next review-by date,
+/// which must be in the future.
+#[tauri::command]
+pub fn mark_skill_reviewed(
+    skill_name: String,
+    next_review_by: String,
+    db: tauri::State<'_, Db>,
+) -> Result<(), String> {
+    log::info!("[mark_skill_reviewed] skill={} next_review_by={}", skill_name, next_review_by);
+    super::observer::ensure_writable(&skill_name)?;
+    validate_skill_name(&skill_name)?;
+    let next_review_by = next_review_by.trim();
+    if parse_review_date(next_review_by)? <= today() {
+        return Err("The next review-by date must be in the future".to_string());
+    }
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[mark_skill_reviewed] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let skills_path = crate::db::read_settings(&conn)?.skills_path;
+    set_review_inner(&conn, skills_path.as_deref(), &skill_name, Some(next_review_by), true).map_err(|e| {
+        log::error!("[mark_skill_reviewed] {}", e);
+        e
+    })
+}
+
+/// Review status of every skill with a review-by date, overdue and soonest first.
+#[tauri::command]
+pub fn get_skill_review_report(db: tauri::State<'_, Db>) -> Result<Vec<SkillReviewStatus>, String> {
+    log::info!("[get_skill_review_report]");
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[get_skill_review_report] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    review_report(&conn)
+}
+
+/// Review status of every skill with a review-by date, as of today.
+pub(crate) fn review_report(conn: &rusqlite::Connection) -> Result<Vec<SkillReviewStatus>, String> {
+    Ok(build_review_report(crate::db::list_all_skills(conn)?, today()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::create_test_db;
+    use tempfile::tempdir;
+
+    fn date(s: &str) -> NaiveDate {
+        parse_review_date(s).unwrap()
+    }
+
+    #[test]
+    fn test_review_state_thresholds() {
+        let today = date("2026-06-01");
+        assert_eq!(review_state(date("2026-07-01"), today), (REVIEW_OK, 30));
+        assert_eq!(review_state(date("2026-06-15"), today), (REVIEW_DUE_SOON, 14));
+        assert_eq!(review_state(date("2026-06-01"), today), (REVIEW_DUE_SOON, 0));
+        assert_eq!(review_state(date("2026-05-30"), today), (REVIEW_EXPIRED, -2));
+        assert!(parse_review_date("06/01/2026").is_err());
+    }
+
+    #[test]
+    fn test_set_review_updates_db_frontmatter_and_packaging_gate() {
+        let tmp = tempdir().unwrap();
+        let skills_path = tmp.path().to_str().unwrap();
+        let skill_dir = tmp.path().join("policies");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(skill_dir.join("SKILL.md"), "---\nname: policies\ndescription: d\n---\n# Policies\n").unwrap();
+
+        let conn = create_test_db();
+        crate::db::upsert_skill(&conn, "policies", "skill-builder", "domain").unwrap();
+        crate::db::upsert_skill(&conn, "undated", "skill-builder", "domain").unwrap();
+
+        set_review_inner(&conn, Some(skills_path), "policies", Some("2020-01-01"), false).unwrap();
+        let content = std::fs::read_to_string(skill_dir.join("SKILL.md")).unwrap();
+        let fm = crate::commands::imported_skills::parse_frontmatter_full(&content);
+        assert_eq!(fm.review_by.as_deref(), Some("2020-01-01"));
+        assert!(content.ends_with("# Policies\n"));
+
+        // Advisory by default; blocked once the setting is on.
+        check_packaging_allowed(&conn, "policies").unwrap();
+        let mut settings = crate::db::read_settings(&conn).unwrap();
+        settings.block_expired_packaging = true;
+        crate::db::write_settings(&conn, &settings).unwrap();
+        let err = check_packaging_allowed(&conn, "policies").unwrap_err();
+        assert!(err.contains("review-by date (2020-01-01)"), "{}", err);
+        check_packaging_allowed(&conn, "undated").unwrap();
+
+        let report = build_review_report(crate::db::list_all_skills(&conn).unwrap(), date("2020-01-05"));
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].state, REVIEW_EXPIRED);
+        assert_eq!(report[0].days_remaining, -4);
+        assert!(report[0].reviewed_at.is_none());
+
+        set_review_inner(&conn, Some(skills_path), "policies", Some("2999-01-01"), true).unwrap();
+        check_packaging_allowed(&conn, "policies").unwrap();
+        let (review_by, reviewed_at) = crate::db::get_skill_review(&conn, "policies").unwrap();
+        assert_eq!(review_by.as_deref(), Some("2999-01-01"));
+        assert!(reviewed_at.is_some());
+
+        set_review_inner(&conn, Some(skills_path), "policies", None, false).unwrap();
+        let content = std::fs::read_to_string(skill_dir.join("SKILL.md")).unwrap();
+        assert!(!content.contains("review-by"));
+        assert_eq!(crate::db::get_skill_review(&conn, "policies").unwrap().0, None);
+    }
+}
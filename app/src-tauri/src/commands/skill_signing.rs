@@ -0,0 +1,597 @@
+//! Signing and verification for skill package archives.
+//!
+//! A signed package carries `.skill-signature.json` at the archive root: a
+//! manifest of SHA-256 hashes for every other entry, the manifest hash, and an
+//! Ed25519 signature over that hash. Each installation has one signing key pair
+//! (generated on first use); recipients add its public key to their trusted-keys
+//! store. Imports reject packages whose contents no longer match their signature,
+//! and, with `require_trusted_packages` on, packages that are unsigned or signed
+//! by a key that is not trusted.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use base64::Engine;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use rusqlite::Connection;
+use sha2::{Digest, Sha256};
+
+use crate::db::Db;
+use crate::types::{PackageVerification, SigningKeyInfo, TrustedKey};
+
+/// Archive entry holding the embedded signature. Never extracted on import.
+pub(crate) const SIGNATURE_ENTRY: &str = ".skill-signature.json";
+
+const MANIFEST_VERSION: u32 = 1;
+
+/// `PK\x01\x02`, the start of each central directory file header.
+const CENTRAL_HEADER_SIGNATURE: [u8; 4] = [0x50, 0x4b, 0x01, 0x02];
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct PackageManifest {
+    version: u32,
+    /// Entry path -> hex SHA-256 of its contents. Sorted for a stable hash.
+    files: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct EmbeddedSignature {
+    manifest: PackageManifest,
+    manifest_hash: String,
+    key_id: String,
+    /// Base64 raw Ed25519 public key.
+    public_key: String,
+    /// Base64 signature over the UTF-8 bytes of `manifest_hash`.
+    signature: String,
+    signed_at: String,
+}
+
+fn b64() -> base64::engine::GeneralPurpose {
+    base64::engine::general_purpose::STANDARD
+}
+
+/// Short, stable identifier for a public key: first 8 bytes of its SHA-256, hex.
+fn key_id_for(public_key: &[u8]) -> String {
+    hex::encode(&Sha256::digest(public_key)[..8])
+}
+
+fn manifest_hash(manifest: &PackageManifest) -> Result<String, String> {
+    let bytes = serde_json::to_vec(manifest).map_err(|e| e.to_string())?;
+    Ok(hex::encode(Sha256::digest(&bytes)))
+}
+
+/// Hash every file entry except the signature itself. Archives come from
+/// `open_package`, which refuses duplicate entry names.
+fn compute_manifest<R: Read + Seek>(archive: &mut zip::ZipArchive<R>) -> Result<PackageManifest, String> {
+    let mut files = BTreeMap::new();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
+        if file.is_dir() || file.name() == SIGNATURE_ENTRY {
+            continue;
+        }
+        let name = file.name().to_string();
+        let mut buffer = Vec::new();
+        file.read_to_end(&mut buffer)
+            .map_err(|e| format!("Failed to read {} from package: {}", name, e))?;
+        files.insert(name, hex::encode(Sha256::digest(&buffer)));
+    }
+    Ok(PackageManifest {
+        version: MANIFEST_VERSION,
+        files,
+    })
+}
+
+/// Refuse an archive whose central directory names an entry twice. The zip
+/// reader keeps only the last entry of each name, so the manifest would hash
+/// one copy while another extractor may write the other; the directory is
+/// therefore walked here directly from `dir_start`.
+fn refuse_duplicate_entries<R: Read + Seek>(reader: &mut R, dir_start: u64) -> Result<(), String> {
+    reader.seek(SeekFrom::Start(dir_start)).map_err(|e| e.to_string())?;
+    let mut names = HashSet::new();
+    loop {
+        let mut header = [0u8; 46];
+        if reader.read_exact(&mut header).is_err() || header[..4] != CENTRAL_HEADER_SIGNATURE {
+            return Ok(());
+        }
+        let field = |at: usize| u16::from_le_bytes([header[at], header[at + 1]]);
+        let mut name = vec![0u8; usize::from(field(28))];
+        reader
+            .read_exact(&mut name)
+            .map_err(|e| format!("Failed to read package directory: {}", e))?;
+        let name = String::from_utf8_lossy(&name).into_owned();
+        if !names.insert(name.clone()) {
+            return Err(format!("Package contains '{}' more than once", name));
+        }
+        // Skip the extra field and the file comment.
+        reader
+            .seek(SeekFrom::Current(i64::from(field(30)) + i64::from(field(32))))
+            .map_err(|e| e.to_string())?;
+    }
+}
+
+/// Open a package for signing or verification.
+fn open_package(path: &Path) -> Result<zip::ZipArchive<std::fs::File>, String> {
+    let open = || std::fs::File::open(path).map_err(|e| format!("Failed to open package: {}", e));
+    let archive = zip::ZipArchive::new(open()?).map_err(|_| "not a valid skill package".to_string())?;
+    refuse_duplicate_entries(&mut open()?, archive.central_directory_start())?;
+    Ok(archive)
+}
+
+fn read_embedded_signature<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+) -> Result<Option<EmbeddedSignature>, String> {
+    let mut file = match archive.by_name(SIGNATURE_ENTRY) {
+        Ok(f) => f,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+    let mut content = String::new();
+    file.read_to_string(&mut content)
+        .map_err(|e| format!("Failed to read package signature: {}", e))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Malformed package signature: {}", e))
+}
+
+/// Load this installation's key pair, generating and storing one on first use.
+fn load_or_create_keypair(conn: &Connection) -> Result<Ed25519KeyPair, String> {
+    let pkcs8 = match crate::db::read_signing_keypair(conn)? {
+        Some(encoded) => b64()
+            .decode(encoded)
+            .map_err(|e| format!("Stored signing key is corrupt: {}", e))?,
+        None => {
+            let rng = ring::rand::SystemRandom::new();
+            let doc = Ed25519KeyPair::generate_pkcs8(&rng)
+                .map_err(|_| "Failed to generate signing key".to_string())?;
+            crate::db::write_signing_keypair(conn, &b64().encode(doc.as_ref()))?;
+            log::info!("[skill_signing] generated new package signing key");
+            doc.as_ref().to_vec()
+        }
+    };
+    Ed25519KeyPair::from_pkcs8(&pkcs8).map_err(|_| "Stored signing key is invalid".to_string())
+}
+
+fn signing_key_info(keypair: &Ed25519KeyPair) -> SigningKeyInfo {
+    let public = keypair.public_key().as_ref();
+    SigningKeyInfo {
+        key_id: key_id_for(public),
+        public_key: b64().encode(public),
+    }
+}
+
+/// Embed a signature in the package at `path`, replacing any existing one.
+/// The archive is rewritten to a sibling temp file and renamed into place.
+fn sign_package_file(path: &Path, keypair: &Ed25519KeyPair) -> Result<EmbeddedSignature, String> {
+    let mut archive = open_package(path)?;
+
+    let manifest = compute_manifest(&mut archive)?;
+    if manifest.files.is_empty() {
+        return Err("Cannot sign an empty package".to_string());
+    }
+    let hash = manifest_hash(&manifest)?;
+    let info = signing_key_info(keypair);
+    let embedded = EmbeddedSignature {
+        manifest,
+        signature: b64().encode(keypair.sign(hash.as_bytes()).as_ref()),
+        manifest_hash: hash,
+        key_id: info.key_id,
+        public_key: info.public_key,
+        signed_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    let tmp_path = path.with_extension("signing");
+    let tmp = std::fs::File::create(&tmp_path)
+        .map_err(|e| format!("Failed to create {}: {}", tmp_path.display(), e))?;
+    let mut writer = zip::ZipWriter::new(tmp);
+    let write_result = (|| -> Result<(), String> {
+        for i in 0..archive.len() {
+            let file = archive.by_index_raw(i).map_err(|e| e.to_string())?;
+            if file.name() == SIGNATURE_ENTRY {
+                continue;
+            }
+            writer.raw_copy_file(file).map_err(|e| e.to_string())?;
+        }
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+        writer
+            .start_file(SIGNATURE_ENTRY, options)
+            .map_err(|e| e.to_string())?;
+        let json = serde_json::to_vec_pretty(&embedded).map_err(|e| e.to_string())?;
+        std::io::Write::write_all(&mut writer, &json).map_err(|e| e.to_string())?;
+        writer.finish().map_err(|e| e.to_string())?;
+        Ok(())
+    })();
+    if let Err(e) = write_result {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(format!("Failed to write signed package: {}", e));
+    }
+    std::fs::rename(&tmp_path, path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp_path);
+        format!("Failed to replace package with signed copy: {}", e)
+    })?;
+    Ok(embedded)
+}
+
+fn invalid(key_id: Option<String>, detail: impl Into<String>) -> PackageVerification {
+    PackageVerification {
+        status: "invalid".to_string(),
+        key_id,
+        signer_name: None,
+        signed_at: None,
+        detail: Some(detail.into()),
+    }
+}
+
+/// Check a package's embedded signature against its contents and the trusted keys.
+fn verify_archive<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    lookup_trusted: impl Fn(&str) -> Result<Option<TrustedKey>, String>,
+) -> Result<PackageVerification, String> {
+    let Some(sig) = read_embedded_signature(archive)? else {
+        return Ok(PackageVerification {
+            status: "unsigned".to_string(),
+            key_id: None,
+            signer_name: None,
+            signed_at: None,
+            detail: None,
+        });
+    };
+    let key_id = Some(sig.key_id.clone());
+
+    let actual = compute_manifest(archive)?;
+    if actual != sig.manifest {
+        let changed = actual
+            .files
+            .iter()
+            .find(|(name, hash)| sig.manifest.files.get(*name) != Some(*hash))
+            .map(|(name, _)| name.clone())
+            .or_else(|| {
+                sig.manifest
+                    .files
+                    .keys()
+                    .find(|name| !actual.files.contains_key(*name))
+                    .cloned()
+            })
+            .unwrap_or_default();
+        return Ok(invalid(key_id, format!("'{}' was modified after signing", changed)));
+    }
+    if manifest_hash(&sig.manifest)? != sig.manifest_hash {
+        return Ok(invalid(key_id, "manifest hash does not match manifest"));
+    }
+
+    let public_key = match b64().decode(&sig.public_key) {
+        Ok(pk) => pk,
+        Err(_) => return Ok(invalid(key_id, "public key is not valid base64")),
+    };
+    if key_id_for(&public_key) != sig.key_id {
+        return Ok(invalid(key_id, "key id does not match public key"));
+    }
+    let signature = match b64().decode(&sig.signature) {
+        Ok(s) => s,
+        Err(_) => return Ok(invalid(key_id, "signature is not valid base64")),
+    };
+    if UnparsedPublicKey::new(&ED25519, &public_key)
+        .verify(sig.manifest_hash.as_bytes(), &signature)
+        .is_err()
+    {
+        return Ok(invalid(key_id, "signature does not match manifest"));
+    }
+
+    let trusted = lookup_trusted(&sig.key_id)?.filter(|k| k.public_key == sig.public_key);
+    Ok(PackageVerification {
+        status: if trusted.is_some() { "trusted" } else { "untrusted" }.to_string(),
+        key_id,
+        signer_name: trusted.map(|k| k.name),
+        signed_at: Some(sig.signed_at),
+        detail: None,
+    })
+}
+
+fn verify_package_path(conn: &Connection, path: &str) -> Result<PackageVerification, String> {
+    let mut archive = open_package(Path::new(path))?;
+    verify_archive(&mut archive, |key_id| crate::db::get_trusted_key(conn, key_id))
+}
+
+/// Import guard: tampered packages are rejected with a `signature_invalid:`
+/// coded error. Unsigned and untrusted packages pass (the UI surfaces their
+/// status via `verify_skill_package`) unless `require_trusted_packages` is on,
+/// in which case they are rejected with `signature_untrusted:`.
+pub(crate) fn ensure_package_importable(conn: &Connection, path: &str) -> Result<PackageVerification, String> {
+    let verification = verify_package_path(conn, path)?;
+    if verification.status == "invalid" {
+        return Err(format!(
+            "signature_invalid:{}",
+            verification.detail.as_deref().unwrap_or("signature check failed")
+        ));
+    }
+    if verification.status != "trusted" && crate::db::read_settings(conn)?.require_trusted_packages {
+        return Err(format!(
+            "signature_untrusted:{}",
+            match verification.key_id.as_deref() {
+                Some(key_id) => format!("package is signed by key {}, which is not trusted", key_id),
+                None => "package is not signed".to_string(),
+            }
+        ));
+    }
+    Ok(verification)
+}
+
+/// Sign the package at `path` when this installation already has a signing
+/// key. Returns the key id, or `None` when no key has been generated yet.
+pub(crate) fn sign_if_key_configured(conn: &Connection, path: &Path) -> Result<Option<String>, String> {
+    if crate::db::read_signing_keypair(conn)?.is_none() {
+        return Ok(None);
+    }
+    let embedded = sign_package_file(path, &load_or_create_keypair(conn)?)?;
+    Ok(Some(embedded.key_id))
+}
+
+/// Return this installation's public signing key, generating it on first use.
+#[tauri::command]
+pub fn get_signing_public_key(db: tauri::State<'_, Db>) -> Result<SigningKeyInfo, String> {
+    log::info!("[get_signing_public_key]");
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[get_signing_public_key] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    Ok(signing_key_info(&load_or_create_keypair(&conn)?))
+}
+
+/// Sign a packaged `.skill` archive in place with this installation's key.
+#[tauri::command]
+pub fn sign_skill_package(
+    package_path: String,
+    db: tauri::State<'_, Db>,
+) -> Result<PackageVerification, String> {
+    log::info!("[sign_skill_package] package_path={}", package_path);
+    let package_path = crate::path_input::require_file(&package_path, "sign_skill_package")?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[sign_skill_package] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let keypair = load_or_create_keypair(&conn)?;
+    let embedded = sign_package_file(Path::new(&package_path), &keypair).map_err(|e| {
+        log::error!("[sign_skill_package] {}", e);
+        e
+    })?;
+    log::info!(
+        "[sign_skill_package] signed {} files with key {}",
+        embedded.manifest.files.len(),
+        embedded.key_id
+    );
+    verify_package_path(&conn, &package_path)
+}
+
+/// Report whether a package is unsigned, signed by a trusted key, signed by an
+/// unknown key, or modified since signing.
+#[tauri::command]
+pub fn verify_skill_package(
+    package_path: String,
+    db: tauri::State<'_, Db>,
+) -> Result<PackageVerification, String> {
+    log::info!("[verify_skill_package] package_path={}", package_path);
+    let package_path = crate::path_input::require_file(&package_path, "verify_skill_package")?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[verify_skill_package] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let result = verify_package_path(&conn, &package_path)?;
+    log::info!(
+        "[verify_skill_package] status={} key_id={:?}",
+        result.status,
+        result.key_id
+    );
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn list_trusted_keys(db: tauri::State<'_, Db>) -> Result<Vec<TrustedKey>, String> {
+    log::info!("[list_trusted_keys]");
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[list_trusted_keys] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::list_trusted_keys(&conn)
+}
+
+/// Trust a base64 Ed25519 public key (as returned by `get_signing_public_key`).
+#[tauri::command]
+pub fn add_trusted_key(
+    name: String,
+    public_key: String,
+    db: tauri::State<'_, Db>,
+) -> Result<TrustedKey, String> {
+    log::info!("[add_trusted_key] name={}", name);
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Trusted key name cannot be empty".to_string());
+    }
+    let public_key = public_key.trim().to_string();
+    let raw = b64()
+        .decode(&public_key)
+        .map_err(|_| "Public key must be base64-encoded".to_string())?;
+    if raw.len() != 32 {
+        return Err(format!(
+            "Public key must be a 32-byte Ed25519 key (got {} bytes)",
+            raw.len()
+        ));
+    }
+    let key_id = key_id_for(&raw);
+
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[add_trusted_key] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::upsert_trusted_key(&conn, &key_id, &name, &public_key)?;
+    crate::db::get_trusted_key(&conn, &key_id)?
+        .ok_or_else(|| format!("Trusted key {} was not saved", key_id))
+}
+
+#[tauri::command]
+pub fn remove_trusted_key(key_id: String, db: tauri::State<'_, Db>) -> Result<(), String> {
+    log::info!("[remove_trusted_key] key_id={}", key_id);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[remove_trusted_key] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    if !crate::db::delete_trusted_key(&conn, &key_id)? {
+        return Err(format!("No trusted key with id '{}'", key_id));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::create_test_db;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    fn write_package(path: &Path, files: &[(&str, &str)]) {
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, content) in files {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    fn package_files() -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("SKILL.md", "---\nname: my-skill\n---\n# Skill"),
+            ("references/guide.md", "guide"),
+        ]
+    }
+
+    #[test]
+    fn test_signed_package_is_untrusted_until_key_is_trusted() {
+        let conn = create_test_db();
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("my-skill.skill");
+        write_package(&path, &package_files());
+
+        let keypair = load_or_create_keypair(&conn).unwrap();
+        sign_package_file(&path, &keypair).unwrap();
+
+        let result = verify_package_path(&conn, path.to_str().unwrap()).unwrap();
+        assert_eq!(result.status, "untrusted");
+
+        let info = signing_key_info(&keypair);
+        crate::db::upsert_trusted_key(&conn, &info.key_id, "Alice", &info.public_key).unwrap();
+        let result = verify_package_path(&conn, path.to_str().unwrap()).unwrap();
+        assert_eq!(result.status, "trusted");
+        assert_eq!(result.signer_name.as_deref(), Some("Alice"));
+        assert_eq!(result.key_id, Some(info.key_id));
+    }
+
+    #[test]
+    fn test_keypair_is_generated_once() {
+        let conn = create_test_db();
+        let first = signing_key_info(&load_or_create_keypair(&conn).unwrap());
+        let second = signing_key_info(&load_or_create_keypair(&conn).unwrap());
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_modified_package_is_invalid_and_blocks_import() {
+        let conn = create_test_db();
+        let tmp = tempdir().unwrap();
+        let signed = tmp.path().join("signed.skill");
+        write_package(&signed, &package_files());
+        let keypair = load_or_create_keypair(&conn).unwrap();
+        sign_package_file(&signed, &keypair).unwrap();
+
+        // Rebuild the archive with one file changed but the original signature kept.
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&signed).unwrap()).unwrap();
+        let mut sig = String::new();
+        archive.by_name(SIGNATURE_ENTRY).unwrap().read_to_string(&mut sig).unwrap();
+        let tampered = tmp.path().join("tampered.skill");
+        write_package(
+            &tampered,
+            &[
+                ("SKILL.md", "---\nname: my-skill\n---\n# Skill (edited)"),
+                ("references/guide.md", "guide"),
+                (SIGNATURE_ENTRY, &sig),
+            ],
+        );
+
+        let result = verify_package_path(&conn, tampered.to_str().unwrap()).unwrap();
+        assert_eq!(result.status, "invalid");
+        assert!(result.detail.unwrap().contains("SKILL.md"));
+
+        let err = ensure_package_importable(&conn, tampered.to_str().unwrap()).unwrap_err();
+        assert!(err.starts_with("signature_invalid:"), "got: {err}");
+    }
+
+    #[test]
+    fn test_unsigned_package_passes_import_guard() {
+        let conn = create_test_db();
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("plain.skill");
+        write_package(&path, &package_files());
+        let result = ensure_package_importable(&conn, path.to_str().unwrap()).unwrap();
+        assert_eq!(result.status, "unsigned");
+    }
+
+    #[test]
+    fn test_import_guard_requires_a_trusted_signature_when_configured() {
+        let conn = create_test_db();
+        let settings = crate::types::AppSettings {
+            require_trusted_packages: true,
+            ..crate::db::read_settings(&conn).unwrap()
+        };
+        crate::db::write_settings(&conn, &settings).unwrap();
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("my-skill.skill");
+        write_package(&path, &package_files());
+        let path_str = path.to_str().unwrap();
+
+        let err = ensure_package_importable(&conn, path_str).unwrap_err();
+        assert_eq!(err, "signature_untrusted:package is not signed");
+
+        let keypair = load_or_create_keypair(&conn).unwrap();
+        sign_package_file(&path, &keypair).unwrap();
+        let err = ensure_package_importable(&conn, path_str).unwrap_err();
+        assert!(err.starts_with("signature_untrusted:package is signed by key"), "got: {err}");
+
+        let info = signing_key_info(&keypair);
+        crate::db::upsert_trusted_key(&conn, &info.key_id, "Alice", &info.public_key).unwrap();
+        assert_eq!(ensure_package_importable(&conn, path_str).unwrap().status, "trusted");
+    }
+
+    #[test]
+    fn test_duplicate_entries_are_refused() {
+        let conn = create_test_db();
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("dup.skill");
+        // ZipWriter refuses duplicate names, so write a second name and then
+        // rename it to collide with the first, in both headers.
+        write_package(&path, &[("SKILL.md", "signed"), ("SKILL.mx", "swapped")]);
+        let mut bytes = std::fs::read(&path).unwrap();
+        while let Some(at) = bytes.windows(8).position(|w| w == b"SKILL.mx") {
+            bytes[at + 7] = b'd';
+        }
+        std::fs::write(&path, bytes).unwrap();
+
+        let err = verify_package_path(&conn, path.to_str().unwrap()).unwrap_err();
+        assert!(err.contains("'SKILL.md' more than once"), "got: {err}");
+    }
+
+    #[test]
+    fn test_resigning_replaces_existing_signature() {
+        let conn = create_test_db();
+        let tmp = tempdir().unwrap();
+        let path = tmp.path().join("my-skill.skill");
+        write_package(&path, &package_files());
+        let keypair = load_or_create_keypair(&conn).unwrap();
+        sign_package_file(&path, &keypair).unwrap();
+        sign_package_file(&path, &keypair).unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let sig_entries = archive.file_names().filter(|n| *n == SIGNATURE_ENTRY).count();
+        assert_eq!(sig_entries, 1);
+        assert_eq!(compute_manifest(&mut archive).unwrap().files.len(), 2);
+    }
+}
@@ -0,0 +1,281 @@
+//! Near-duplicate detection across the skill library.
+//!
+//! Each SKILL.md is fingerprinted as the set of hashed 4-word shingles of its
+//! body, plus 2-word shingles of its frontmatter description. Two skills are
+//! compared by the Jaccard overlap of those sets, so copies that were lightly
+//! edited, renamed or reformatted still score high while skills that merely
+//! share a domain do not. Skills with the same folder name in different
+//! locations are the same skill installed twice and are never reported.
+//!
+//! Imports (`import_github_skills`, `import_hosted_skills`, `upload_skill`)
+//! check the skills they installed and emit `SKILL_NEAR_DUPLICATES_EVENT` when
+//! they look like copies of something already in the library.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use tauri::Emitter;
+
+use crate::db::Db;
+use crate::types::SimilarSkill;
+
+pub const SKILL_NEAR_DUPLICATES_EVENT: &str = "skill-near-duplicates";
+
+/// Score at or above which two skills are reported as near-duplicates.
+pub(crate) const DEFAULT_THRESHOLD: f64 = 0.5;
+
+const BODY_SHINGLE: usize = 4;
+const DESCRIPTION_SHINGLE: usize = 2;
+/// Share of the score taken by the description when both skills have one.
+const DESCRIPTION_WEIGHT: f64 = 0.25;
+
+struct Fingerprint {
+    name: String,
+    location: &'static str,
+    body: HashSet<u64>,
+    description: Option<HashSet<u64>>,
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Hashes of every `k`-word window; text shorter than `k` words is one shingle.
+fn shingles(text: &str, k: usize) -> HashSet<u64> {
+    let words = words(text);
+    let hash = |window: &[String]| {
+        let mut hasher = DefaultHasher::new();
+        window.hash(&mut hasher);
+        hasher.finish()
+    };
+    if words.len() < k {
+        return if words.is_empty() { HashSet::new() } else { HashSet::from([hash(&words)]) };
+    }
+    words.windows(k).map(hash).collect()
+}
+
+fn jaccard(a: &HashSet<u64>, b: &HashSet<u64>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+fn fingerprint(name: &str, location: &'static str, content: &str) -> Fingerprint {
+    let description = super::imported_skills::parse_frontmatter_full(content)
+        .description
+        .filter(|d| !d.trim().is_empty())
+        .map(|d| shingles(&d, DESCRIPTION_SHINGLE));
+    Fingerprint {
+        name: name.to_string(),
+        location,
+        body: shingles(super::skill_validation::frontmatter_body(content), BODY_SHINGLE),
+        description,
+    }
+}
+
+fn compare(a: &Fingerprint, b: &Fingerprint) -> SimilarSkill {
+    let content_similarity = jaccard(&a.body, &b.body);
+    let description_similarity = match (&a.description, &b.description) {
+        (Some(da), Some(db)) => Some(jaccard(da, db)),
+        _ => None,
+    };
+    let score = match description_similarity {
+        Some(d) => content_similarity * (1.0 - DESCRIPTION_WEIGHT) + d * DESCRIPTION_WEIGHT,
+        None => content_similarity,
+    };
+    SimilarSkill {
+        skill_name: a.name.clone(),
+        location: a.location.to_string(),
+        similar_to: b.name.clone(),
+        similar_location: b.location.to_string(),
+        score,
+        content_similarity,
+        description_similarity,
+    }
+}
+
+/// Folders scanned for skills: the skills library and the workspace's active
+/// and deactivated skills.
+fn library_roots(skills_path: Option<&str>, workspace_path: Option<&str>) -> Vec<(&'static str, PathBuf)> {
+    let mut roots = Vec::new();
+    if let Some(sp) = skills_path {
+        roots.push(("skills_library", PathBuf::from(sp)));
+    }
+    if let Some(wp) = workspace_path {
+        let workspace = Path::new(wp).join(".claude").join("skills");
+        roots.push(("workspace_inactive", workspace.join(".inactive")));
+        roots.push(("workspace", workspace));
+    }
+    roots
+}
+
+fn load_fingerprints(roots: &[(&'static str, PathBuf)]) -> Vec<Fingerprint> {
+    let mut found = Vec::new();
+    for (location, root) in roots {
+        let Ok(entries) = std::fs::read_dir(root) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') {
+                continue;
+            }
+            if let Ok(content) = std::fs::read_to_string(entry.path().join("SKILL.md")) {
+                found.push(fingerprint(&name, location, &content));
+            }
+        }
+    }
+    found
+}
+
+/// Near-duplicate pairs among `fingerprints`, best match first. With `only`,
+/// just the matches for those skills; otherwise every pair once.
+fn find_similar(fingerprints: &[Fingerprint], only: Option<&[String]>, threshold: f64) -> Vec<SimilarSkill> {
+    let mut matches = Vec::new();
+    for (i, a) in fingerprints.iter().enumerate() {
+        if only.is_some_and(|names| !names.contains(&a.name)) {
+            continue;
+        }
+        for (j, b) in fingerprints.iter().enumerate() {
+            if a.name == b.name || (only.is_none() && j <= i) {
+                continue;
+            }
+            // A pair of two imported skills is reported once, under the first.
+            if only.is_some_and(|names| names.contains(&b.name)) && j <= i {
+                continue;
+            }
+            let similar = compare(a, b);
+            if similar.score >= threshold {
+                matches.push(similar);
+            }
+        }
+    }
+    matches.sort_by(|x, y| y.score.total_cmp(&x.score));
+    matches
+}
+
+fn library_matches(
+    conn: &rusqlite::Connection,
+    only: Option<&[String]>,
+    threshold: f64,
+) -> Result<Vec<SimilarSkill>, String> {
+    let settings = crate::db::read_settings(conn)?;
+    let roots = library_roots(settings.skills_path.as_deref(), settings.workspace_path.as_deref());
+    Ok(find_similar(&load_fingerprints(&roots), only, threshold))
+}
+
+/// Find skills in the library whose SKILL.md looks like a copy of another.
+/// With `skill_name`, only that skill's matches are returned.
+#[tauri::command]
+pub fn find_similar_skills(
+    skill_name: Option<String>,
+    threshold: Option<f64>,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<SimilarSkill>, String> {
+    log::info!(
+        "[find_similar_skills] skill_name={:?} threshold={:?}",
+        skill_name,
+        threshold
+    );
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[find_similar_skills] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let only = skill_name.map(|name| vec![name]);
+    let matches = library_matches(&conn, only.as_deref(), threshold.unwrap_or(DEFAULT_THRESHOLD))?;
+    log::info!("[find_similar_skills] {} near-duplicate pair(s)", matches.len());
+    Ok(matches)
+}
+
+/// Check freshly imported skills against the library and tell the frontend
+/// about any near-duplicates. Never fails the import.
+pub(crate) fn flag_near_duplicates(app: &tauri::AppHandle, db: &Db, imported: &[String], caller: &str) {
+    if imported.is_empty() {
+        return;
+    }
+    let matches = match db.0.lock() {
+        Ok(conn) => library_matches(&conn, Some(imported), DEFAULT_THRESHOLD),
+        Err(e) => Err(e.to_string()),
+    };
+    match matches {
+        Ok(matches) if matches.is_empty() => {}
+        Ok(matches) => {
+            for m in &matches {
+                log::warn!(
+                    "[{}] '{}' looks like a copy of '{}' in {} (score {:.2})",
+                    caller,
+                    m.skill_name,
+                    m.similar_to,
+                    m.similar_location,
+                    m.score
+                );
+            }
+            if let Err(e) = app.emit(SKILL_NEAR_DUPLICATES_EVENT, &matches) {
+                log::warn!("[{}] Failed to emit {}: {}", caller, SKILL_NEAR_DUPLICATES_EVENT, e);
+            }
+        }
+        Err(e) => log::warn!("[{}] Near-duplicate check failed: {}", caller, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REVENUE: &str = "---\nname: revenue-recognition\ndescription: Recognize revenue under ASC 606 for subscription contracts\n---\n# Revenue recognition\n\nIdentify the contract with the customer, then identify each performance obligation.\nDetermine the transaction price and allocate it to the performance obligations.\nRecognize revenue when or as each obligation is satisfied, ratably for subscriptions.\n";
+
+    fn write_skill(root: &Path, name: &str, content: &str) {
+        std::fs::create_dir_all(root.join(name)).unwrap();
+        std::fs::write(root.join(name).join("SKILL.md"), content).unwrap();
+    }
+
+    #[test]
+    fn test_lightly_edited_copy_scores_high() {
+        let copy = REVENUE
+            .replace("name: revenue-recognition", "name: rev-rec")
+            .replace("ratably for subscriptions", "ratably for SaaS subscriptions");
+        let unrelated = "---\nname: churn\ndescription: Analyze customer churn cohorts\n---\n# Churn\n\nGroup customers by signup month and chart retention per cohort.\n";
+        let a = fingerprint("revenue-recognition", "workspace", REVENUE);
+        let b = fingerprint("rev-rec", "skills_library", &copy);
+        let c = fingerprint("churn", "workspace", unrelated);
+
+        let similar = compare(&a, &b);
+        assert!(similar.score >= DEFAULT_THRESHOLD, "score {}", similar.score);
+        assert_eq!(similar.description_similarity, Some(1.0));
+        assert!(compare(&a, &c).score < 0.1);
+    }
+
+    #[test]
+    fn test_find_similar_scans_library_and_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let library = dir.path().join("library");
+        let workspace = dir.path().join("ws");
+        let workspace_skills = workspace.join(".claude").join("skills");
+        write_skill(&library, "revenue-recognition", REVENUE);
+        // Same skill deployed to the workspace: not a duplicate of itself.
+        write_skill(&workspace_skills, "revenue-recognition", REVENUE);
+        write_skill(&workspace_skills, "rev-rec-copy", &REVENUE.replace("Revenue recognition", "Rev rec"));
+        write_skill(&workspace_skills, "churn", "# Churn\n\nGroup customers by signup month.\n");
+
+        let roots = library_roots(library.to_str(), workspace.to_str());
+        let fingerprints = load_fingerprints(&roots);
+        assert_eq!(fingerprints.len(), 4);
+
+        let all = find_similar(&fingerprints, None, DEFAULT_THRESHOLD);
+        assert_eq!(all.len(), 2);
+        assert!(all.iter().all(|m| m.skill_name != m.similar_to));
+        assert!(all.iter().all(|m| m.skill_name == "rev-rec-copy" || m.similar_to == "rev-rec-copy"));
+
+        let only = vec!["rev-rec-copy".to_string()];
+        let imported = find_similar(&fingerprints, Some(&only), DEFAULT_THRESHOLD);
+        assert_eq!(imported.len(), 2);
+        assert!(imported.iter().all(|m| m.skill_name == "rev-rec-copy" && m.similar_to == "revenue-recognition"));
+    }
+}
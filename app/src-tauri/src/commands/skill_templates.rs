@@ -0,0 +1,293 @@
+//! Starter templates for new skills.
+//!
+//! Each template targets one skill purpose and carries what a user would
+//! otherwise type into the new-skill dialog: a description, the intake
+//! context the research agents read, and a skeleton SKILL.md with the
+//! sections a skill of that kind usually needs. `create_skill_from_template`
+//! creates the skill the same way `create_skill` does, then writes the
+//! skeleton into the skill's output folder so the workflow starts from it
+//! instead of an empty directory.
+
+use std::path::Path;
+
+use crate::db::Db;
+use crate::types::SkillTemplate;
+
+struct BundledTemplate {
+    id: &'static str,
+    name: &'static str,
+    skill_type: &'static str,
+    summary: &'static str,
+    description: &'static str,
+    context: &'static str,
+    body: &'static str,
+}
+
+const TEMPLATES: &[BundledTemplate] = &[
+    BundledTemplate {
+        id: "business-process",
+        name: "Business process",
+        skill_type: "domain",
+        summary: "A business process with its terms, rules and the metrics built on it",
+        description: "Explains the {{title}} process: its vocabulary, business rules and how its metrics are calculated. Use when answering questions or building reports about {{title}}.",
+        context: "Describe how {{title}} works in our organization: who owns it, the stages it moves through, the rules that differ from the textbook version, and the metrics leadership tracks. Note the terms people use inconsistently.",
+        body: "# {{title}}\n\n## Overview\n\n<!-- What the process is for and who owns it. -->\n\n## Glossary\n\n| Term | Meaning here |\n|---|---|\n| | |\n\n## Stages\n\n1. <!-- First stage and what moves a record to the next one. -->\n\n## Business rules\n\n- <!-- Rules that differ from the standard definition. -->\n\n## Metrics\n\n| Metric | Definition | Grain |\n|---|---|---|\n| | | |\n\n## Common mistakes\n\n- <!-- What gets calculated or interpreted wrong. -->\n",
+    },
+    BundledTemplate {
+        id: "source-system",
+        name: "Source system",
+        skill_type: "source",
+        summary: "A source system's customized objects, fields and extraction quirks",
+        description: "Describes how {{title}} is configured in our environment: custom objects and fields, how they map to business concepts, and what to watch for when extracting data. Use when querying or modelling data from {{title}}.",
+        context: "List the objects and fields in {{title}} that are customized or used differently from the vendor defaults, how records are deleted or archived, and any extraction limits or timing issues.",
+        body: "# {{title}}\n\n## Overview\n\n<!-- What the system is used for and which teams own its configuration. -->\n\n## Key objects\n\n| Object | Purpose | Customizations |\n|---|---|---|\n| | | |\n\n## Field mappings\n\n| Field | Business meaning | Notes |\n|---|---|---|\n| | | |\n\n## Extraction\n\n- <!-- Incremental keys, soft deletes, API limits, timezones. -->\n\n## Known data quality issues\n\n- <!-- Gaps, duplicates, fields that cannot be trusted. -->\n",
+    },
+    BundledTemplate {
+        id: "platform-standards",
+        name: "Platform standards",
+        skill_type: "platform",
+        summary: "Conventions for building on our Azure or Fabric platform",
+        description: "Captures our standards for {{title}}: workspace and naming conventions, security, deployment and the settings we change from the defaults. Use when designing or reviewing work on {{title}}.",
+        context: "Explain how {{title}} is set up here: environments and workspaces, naming conventions, access and security model, how changes are deployed, and the defaults you always override.",
+        body: "# {{title}}\n\n## Environments\n\n| Environment | Purpose | Who deploys |\n|---|---|---|\n| | | |\n\n## Naming conventions\n\n- <!-- Workspaces, items, connections. -->\n\n## Security and access\n\n- <!-- Groups, roles, secrets handling. -->\n\n## Deployment\n\n1. <!-- How a change reaches production. -->\n\n## Settings we change from the defaults\n\n- <!-- Setting, our value and why. -->\n",
+    },
+    BundledTemplate {
+        id: "pipeline-standards",
+        name: "Pipeline standards",
+        skill_type: "data-engineering",
+        summary: "Data engineering standards for pipelines, layers and testing",
+        description: "Defines our data engineering standards for {{title}}: layer conventions, load patterns, testing and ownership. Use when building or reviewing data pipelines for {{title}}.",
+        context: "Describe the layers data moves through for {{title}}, the load patterns you use (full, incremental, SCD), how models are tested and documented, and the mistakes reviewers keep catching.",
+        body: "# {{title}}\n\n## Layers\n\n| Layer | Purpose | Naming |\n|---|---|---|\n| | | |\n\n## Load patterns\n\n- <!-- Full, incremental, snapshot; when to use each. -->\n\n## Modelling conventions\n\n- <!-- Keys, history, naming, data types. -->\n\n## Testing\n\n- <!-- Required tests per model and where they run. -->\n\n## Review checklist\n\n- <!-- What reviewers check before merging. -->\n",
+    },
+];
+
+/// Title for a skill name: `order-to-cash` becomes `Order To Cash`.
+fn title_for(name: &str) -> String {
+    name.split(['-', '_'])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn to_template(bundled: &BundledTemplate) -> SkillTemplate {
+    SkillTemplate {
+        id: bundled.id.to_string(),
+        name: bundled.name.to_string(),
+        skill_type: bundled.skill_type.to_string(),
+        summary: bundled.summary.to_string(),
+        description: bundled.description.to_string(),
+        intake_json: serde_json::json!({ "context": bundled.context }).to_string(),
+        skill_md: bundled.body.to_string(),
+    }
+}
+
+fn list_templates(skill_type: Option<&str>) -> Vec<SkillTemplate> {
+    TEMPLATES
+        .iter()
+        .filter(|t| skill_type.is_none_or(|st| t.skill_type == st))
+        .map(to_template)
+        .collect()
+}
+
+/// The template with `{{title}}` filled in for `skill_name`, and its SKILL.md
+/// given the frontmatter the workflow writes.
+fn render_template(template_id: &str, skill_name: &str) -> Result<SkillTemplate, String> {
+    let bundled = TEMPLATES
+        .iter()
+        .find(|t| t.id == template_id)
+        .ok_or_else(|| format!("Unknown skill template '{}'", template_id))?;
+    let title = title_for(skill_name);
+    let mut template = to_template(bundled);
+    template.description = template.description.replace("{{title}}", &title);
+    template.intake_json =
+        serde_json::json!({ "context": bundled.context.replace("{{title}}", &title) }).to_string();
+    template.skill_md = format!(
+        "---\nname: {}\ndescription: {}\n---\n{}",
+        skill_name,
+        template.description,
+        bundled.body.replace("{{title}}", &title)
+    );
+    Ok(template)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_skill_from_template_inner(
+    workspace_path: &str,
+    name: &str,
+    template_id: &str,
+    tags: Option<&[String]>,
+    conn: &rusqlite::Connection,
+    skills_path: &str,
+    author_login: Option<&str>,
+    author_avatar: Option<&str>,
+) -> Result<(), String> {
+    let template = render_template(template_id, name)?;
+    super::skill::create_skill_inner(
+        workspace_path,
+        name,
+        tags,
+        Some(&template.skill_type),
+        Some(conn),
+        Some(skills_path),
+        author_login,
+        author_avatar,
+        Some(&template.intake_json),
+        Some(&template.description),
+        None,
+        None,
+        None,
+        None,
+        None,
+    )?;
+
+    let skill_md = Path::new(skills_path).join(name).join("SKILL.md");
+    std::fs::write(&skill_md, &template.skill_md)
+        .map_err(|e| format!("Failed to write {}: {}", skill_md.display(), e))?;
+    let msg = format!("{}: scaffolded from {} template", name, template.id);
+    if let Err(e) = crate::git::commit_all(Path::new(skills_path), &msg) {
+        log::warn!("Git auto-commit failed ({}): {}", msg, e);
+    }
+    Ok(())
+}
+
+/// Bundled starter templates, optionally only those for one skill purpose.
+#[tauri::command]
+pub fn list_skill_templates(skill_type: Option<String>) -> Vec<SkillTemplate> {
+    log::info!("[list_skill_templates] skill_type={:?}", skill_type);
+    list_templates(skill_type.as_deref())
+}
+
+/// Create a skill like `create_skill`, pre-filled from a template: purpose,
+/// description and intake context are set, and a skeleton SKILL.md is written.
+#[tauri::command]
+pub fn create_skill_from_template(
+    workspace_path: String,
+    name: String,
+    template_id: String,
+    tags: Option<Vec<String>>,
+    db: tauri::State<'_, Db>,
+) -> Result<(), String> {
+    log::info!(
+        "[create_skill_from_template] name={} template={}",
+        name,
+        template_id
+    );
+    super::imported_skills::validate_skill_name(&name)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!(
+            "[create_skill_from_template] Failed to acquire DB lock: {}",
+            e
+        );
+        e.to_string()
+    })?;
+    let settings = crate::db::read_settings(&conn).ok();
+    let skills_path = settings
+        .as_ref()
+        .and_then(|s| s.skills_path.clone())
+        .ok_or_else(|| {
+            "Skills output path is not configured. Please set it in Settings before creating skills."
+                .to_string()
+        })?;
+    let author_login = settings.as_ref().and_then(|s| s.github_user_login.clone());
+    let author_avatar = settings.as_ref().and_then(|s| s.github_user_avatar.clone());
+    create_skill_from_template_inner(
+        &workspace_path,
+        &name,
+        &template_id,
+        tags.as_deref(),
+        &conn,
+        &skills_path,
+        author_login.as_deref(),
+        author_avatar.as_deref(),
+    )
+    .map_err(|e| {
+        log::error!("[create_skill_from_template] {}", e);
+        e
+    })?;
+    crate::audit::record(
+        &conn,
+        crate::audit::SKILL_CREATED,
+        Some(&name),
+        &format!("created from template {}", template_id),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::create_test_db;
+
+    #[test]
+    fn test_every_purpose_has_a_template() {
+        for purpose in ["domain", "source", "platform", "data-engineering"] {
+            let templates = list_templates(Some(purpose));
+            assert!(!templates.is_empty(), "no template for {}", purpose);
+            assert!(templates.iter().all(|t| t.skill_type == purpose));
+        }
+        assert_eq!(list_templates(None).len(), TEMPLATES.len());
+    }
+
+    #[test]
+    fn test_create_skill_from_template() {
+        let workspace = tempfile::tempdir().unwrap();
+        let skills = tempfile::tempdir().unwrap();
+        let conn = create_test_db();
+        create_skill_from_template_inner(
+            workspace.path().to_str().unwrap(),
+            "order-to-cash",
+            "business-process",
+            None,
+            &conn,
+            skills.path().to_str().unwrap(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        let run = crate::db::get_workflow_run(&conn, "order-to-cash")
+            .unwrap()
+            .unwrap();
+        assert_eq!(run.purpose, "domain");
+        assert!(run
+            .description
+            .as_deref()
+            .unwrap()
+            .starts_with("Explains the Order To Cash process"));
+        let intake: serde_json::Value =
+            serde_json::from_str(run.intake_json.as_deref().unwrap()).unwrap();
+        assert!(intake["context"]
+            .as_str()
+            .unwrap()
+            .contains("how Order To Cash works"));
+
+        let skill_md =
+            std::fs::read_to_string(skills.path().join("order-to-cash").join("SKILL.md")).unwrap();
+        assert!(skill_md
+            .starts_with("---\nname: order-to-cash\ndescription: Explains the Order To Cash"));
+        assert!(skill_md.contains("# Order To Cash\n"));
+        assert!(!skill_md.contains("{{title}}"));
+
+        let err = create_skill_from_template_inner(
+            workspace.path().to_str().unwrap(),
+            "other",
+            "no-such-template",
+            None,
+            &conn,
+            skills.path().to_str().unwrap(),
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(err.contains("Unknown skill template"));
+        assert!(!skills.path().join("other").exists());
+    }
+}
@@ -12,7 +12,7 @@ pub struct PrepareResult {
 }
 
 /// Create a workspace root and copy `CLAUDE.md` from the main workspace into it.
-fn copy_workspace_claude_md(
+pub(crate) fn copy_workspace_claude_md(
     src_workspace_path: &Path,
     dest_workspace_dir: &Path,
     label: &str,
@@ -0,0 +1,417 @@
+//! Saved prompt scenarios for regression-testing a skill.
+//!
+//! A test case is a user prompt plus what should happen: whether the agent
+//! loads the skill, and substrings the response must contain. `run_skill_tests`
+//! copies the skill into a throwaway workspace, runs each prompt through the
+//! sidecar with read-only tools, and records per case whether the skill
+//! triggered, the final response and why the case failed. Cases run side by
+//! side up to the `max_concurrent_agents` setting, and their usage is recorded
+//! under one session per run. Results are grouped by run id so a run after
+//! refining can be compared with the one before.
+
+use std::path::Path;
+
+use super::imported_skills::validate_skill_name;
+use crate::agents::sidecar::{self, SidecarConfig};
+use crate::agents::sidecar_pool::{ConcurrentTask, SidecarPool};
+use crate::db::Db;
+use crate::types::{SkillTestCase, SkillTestResult, SkillTestRun};
+
+const DEFAULT_MODEL: &str = "sonnet";
+const ALLOWED_TOOLS: [&str; 4] = ["Skill", "Read", "Glob", "Grep"];
+const MAX_TURNS: u32 = 10;
+/// Usage step id test runs are recorded under.
+const TEST_STEP_ID: i32 = -11;
+
+/// Whether an assistant message invokes the skill: a `Skill` tool call naming
+/// it, or a `Read` of its SKILL.md.
+fn invokes_skill(message: &serde_json::Value, skill_name: &str) -> bool {
+    if message["type"] != "assistant" {
+        return false;
+    }
+    let skill_md = format!("skills/{}/SKILL.md", skill_name);
+    message["message"]["content"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|block| block["type"] == "tool_use")
+        .any(|block| match block["name"].as_str() {
+            Some("Skill") => [&block["input"]["skill"], &block["input"]["command"]]
+                .iter()
+                .any(|v| v.as_str() == Some(skill_name)),
+            Some("Read") => block["input"]["file_path"]
+                .as_str()
+                .is_some_and(|p| p.replace('\\', "/").ends_with(&skill_md)),
+            _ => false,
+        })
+}
+
+/// The final answer: the `result` message text, else the text of the last
+/// assistant message.
+fn final_response(messages: &[serde_json::Value]) -> String {
+    if let Some(result) = messages
+        .iter()
+        .rev()
+        .find(|m| m["type"] == "result")
+        .and_then(|m| m["result"].as_str())
+    {
+        return result.to_string();
+    }
+    messages
+        .iter()
+        .rev()
+        .find(|m| m["type"] == "assistant")
+        .and_then(|m| m["message"]["content"].as_array())
+        .map(|blocks| {
+            blocks
+                .iter()
+                .filter_map(|b| b["text"].as_str())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default()
+}
+
+/// Check a case against what the agent did. Returns `(triggered, response, failures)`.
+fn evaluate(case: &SkillTestCase, messages: &[serde_json::Value]) -> (bool, String, Vec<String>) {
+    let triggered = messages.iter().any(|m| invokes_skill(m, &case.skill_name));
+    let response = final_response(messages);
+    let mut failures = Vec::new();
+    match (case.expect_trigger, triggered) {
+        (true, false) => failures.push("Skill did not trigger".to_string()),
+        (false, true) => failures.push("Skill triggered but was not expected to".to_string()),
+        _ => {}
+    }
+    let lowered = response.to_lowercase();
+    for expected in &case.expected_contains {
+        if !lowered.contains(&expected.to_lowercase()) {
+            failures.push(format!("Response does not contain \"{}\"", expected));
+        }
+    }
+    (triggered, response, failures)
+}
+
+#[tauri::command]
+pub fn create_test_case(
+    skill_name: String,
+    name: String,
+    prompt: String,
+    expect_trigger: Option<bool>,
+    expected_contains: Option<Vec<String>>,
+    db: tauri::State<'_, Db>,
+) -> Result<SkillTestCase, String> {
+    log::info!("[create_test_case] skill={} name={}", skill_name, name);
+    super::observer::ensure_writable(&skill_name)?;
+    validate_skill_name(&skill_name)?;
+    if prompt.trim().is_empty() {
+        return Err("Test case prompt is empty".to_string());
+    }
+    let case = SkillTestCase {
+        id: 0,
+        skill_name,
+        name: Some(name.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| prompt.trim().chars().take(60).collect()),
+        prompt: prompt.trim().to_string(),
+        expect_trigger: expect_trigger.unwrap_or(true),
+        expected_contains: expected_contains
+            .unwrap_or_default()
+            .into_iter()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        created_at: String::new(),
+    };
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[create_test_case] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::insert_skill_test_case(&conn, &case)
+}
+
+#[tauri::command]
+pub fn list_test_cases(skill_name: String, db: tauri::State<'_, Db>) -> Result<Vec<SkillTestCase>, String> {
+    log::info!("[list_test_cases] skill={}", skill_name);
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[list_test_cases] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::list_skill_test_cases(&conn, &skill_name)
+}
+
+#[tauri::command]
+pub fn delete_test_case(id: i64, db: tauri::State<'_, Db>) -> Result<(), String> {
+    log::info!("[delete_test_case] id={}", id);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[delete_test_case] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    if !crate::db::delete_skill_test_case(&conn, id)? {
+        return Err(format!("Test case {} not found", id));
+    }
+    Ok(())
+}
+
+/// Run a skill's test cases (all of them, or `test_case_ids`) concurrently in
+/// a temporary workspace holding only the skill, and store the results under
+/// a new run id.
+#[tauri::command]
+pub async fn run_skill_tests(
+    app: tauri::AppHandle,
+    pool: tauri::State<'_, SidecarPool>,
+    db: tauri::State<'_, Db>,
+    skill_name: String,
+    test_case_ids: Option<Vec<i64>>,
+) -> Result<SkillTestRun, String> {
+    log::info!("[run_skill_tests] skill={} cases={:?}", skill_name, test_case_ids);
+    validate_skill_name(&skill_name)?;
+
+    let (api_key, model, workspace_path, skill_dir, cases) = {
+        let conn = db.0.lock().map_err(|e| {
+            log::error!("[run_skill_tests] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        let settings = crate::db::read_settings_hydrated(&conn)?;
+        let api_key = crate::commands::offline::agent_api_key(&settings)
+            .ok_or_else(|| "Anthropic API key not configured".to_string())?;
+        let workspace_path = settings
+            .workspace_path
+            .clone()
+            .ok_or_else(|| "Workspace path not initialized".to_string())?;
+        crate::commands::usage::check_budget_allows_run(&conn, &skill_name).map_err(|e| {
+            log::warn!("[run_skill_tests] {}", e);
+            e
+        })?;
+        let skill_dir = super::benchmark::resolve_skill_dir(&conn, &settings, &skill_name)?;
+        let model = settings.preferred_model.clone().unwrap_or_else(|| DEFAULT_MODEL.to_string());
+        let cases: Vec<SkillTestCase> = crate::db::list_skill_test_cases(&conn, &skill_name)?
+            .into_iter()
+            .filter(|c| test_case_ids.as_ref().is_none_or(|ids| ids.contains(&c.id)))
+            .collect();
+        (api_key, model, workspace_path, skill_dir, cases)
+    };
+    if cases.is_empty() {
+        return Err(format!("No test cases to run for '{}'", skill_name));
+    }
+
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let sandbox = std::env::temp_dir().join(format!("skill-builder-tests-{}", run_id));
+    super::skill_test::copy_workspace_claude_md(Path::new(&workspace_path), &sandbox, "test-case")?;
+    let sandbox_skill = sandbox.join(".claude").join("skills").join(&skill_name);
+    std::fs::create_dir_all(&sandbox_skill).map_err(|e| format!("Failed to create test workspace: {}", e))?;
+    super::imported_skills::copy_dir_recursive(&skill_dir, &sandbox_skill)?;
+    let skill_version = std::fs::read_to_string(skill_dir.join("SKILL.md"))
+        .ok()
+        .and_then(|md| super::imported_skills::parse_frontmatter_full(&md).version);
+
+    let tasks: Vec<ConcurrentTask> = cases
+        .iter()
+        .map(|case| ConcurrentTask {
+            agent_id: super::workflow::make_agent_id(&case.skill_name, &format!("test-case{}", case.id)),
+            config: SidecarConfig {
+                prompt: case.prompt.clone(),
+                model: Some(model.clone()),
+                api_key: api_key.clone(),
+                cwd: sandbox.to_string_lossy().to_string(),
+                allowed_tools: Some(ALLOWED_TOOLS.iter().map(|t| t.to_string()).collect()),
+                max_turns: Some(MAX_TURNS),
+                permission_mode: None,
+                betas: None,
+                thinking: None,
+                fallback_model: None,
+                effort: None,
+                output_format: None,
+                prompt_suggestions: None,
+                path_to_claude_code_executable: None,
+                agent_name: None,
+                required_plugins: None,
+                conversation_history: None,
+                resume: None,
+                bash_sandbox: None,
+                disallowed_tools: None,
+                local_model: None,
+            },
+        })
+        .collect();
+    let started = std::time::Instant::now();
+    let usage_session_id = format!("synthetic:test:{}:{}", skill_name, run_id);
+    let outcomes = sidecar::run_concurrent(tasks, pool.inner(), &app, &skill_name, TEST_STEP_ID, &usage_session_id).await;
+    let elapsed_ms = started.elapsed().as_millis() as i64;
+    if let Err(e) = std::fs::remove_dir_all(&sandbox) {
+        log::warn!("[run_skill_tests] Failed to remove {}: {}", sandbox.display(), e);
+    }
+
+    let mut results = Vec::with_capacity(cases.len());
+    for (case, outcome) in cases.iter().zip(outcomes?) {
+        let (triggered, response, failures, error) = match outcome.error {
+            None => {
+                let (triggered, response, failures) = evaluate(case, &outcome.messages);
+                (triggered, response, failures, None)
+            }
+            Some(e) => {
+                log::warn!("[run_skill_tests] case {} '{}': {}", case.id, case.name, e);
+                (false, String::new(), vec![e.clone()], Some(e))
+            }
+        };
+        let duration_ms = outcome
+            .messages
+            .iter()
+            .rev()
+            .find(|m| m["type"] == "result")
+            .and_then(|m| m["duration_ms"].as_i64())
+            .unwrap_or(elapsed_ms);
+        results.push(SkillTestResult {
+            id: 0,
+            test_case_id: case.id,
+            test_name: case.name.clone(),
+            skill_name: skill_name.clone(),
+            run_id: run_id.clone(),
+            skill_version: skill_version.clone(),
+            triggered,
+            passed: failures.is_empty(),
+            response,
+            failures,
+            error,
+            duration_ms,
+            created_at: String::new(),
+        });
+    }
+
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[run_skill_tests] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    for result in &results {
+        crate::db::insert_skill_test_result(&conn, result)?;
+    }
+    let results = crate::db::list_skill_test_results(&conn, &skill_name, Some(&run_id))?;
+    let passed = results.iter().filter(|r| r.passed).count() as u32;
+    log::info!(
+        "[run_skill_tests] '{}' run {}: {}/{} passed",
+        skill_name,
+        run_id,
+        passed,
+        results.len()
+    );
+    Ok(SkillTestRun {
+        run_id,
+        skill_name,
+        passed,
+        failed: results.len() as u32 - passed,
+        results,
+    })
+}
+
+/// Results of one test run, or of the latest run when `run_id` is omitted.
+#[tauri::command]
+pub fn get_test_results(
+    skill_name: String,
+    run_id: Option<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<SkillTestResult>, String> {
+    log::info!("[get_test_results] skill={} run={:?}", skill_name, run_id);
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[get_test_results] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::list_skill_test_results(&conn, &skill_name, run_id.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn case(expect_trigger: bool, expected_contains: &[&str]) -> SkillTestCase {
+        SkillTestCase {
+            id: 0,
+            skill_name: "sales-pipeline".into(),
+            name: "mrr".into(),
+            prompt: "How do I roll up MRR?".into(),
+            expect_trigger,
+            expected_contains: expected_contains.iter().map(|s| s.to_string()).collect(),
+            created_at: String::new(),
+        }
+    }
+
+    fn tool_use(name: &str, input: serde_json::Value) -> serde_json::Value {
+        json!({"type": "assistant", "message": {"content": [{"type": "tool_use", "name": name, "input": input}]}})
+    }
+
+    #[test]
+    fn test_invokes_skill_by_tool_or_read() {
+        assert!(invokes_skill(&tool_use("Skill", json!({"skill": "sales-pipeline"})), "sales-pipeline"));
+        assert!(invokes_skill(&tool_use("Skill", json!({"command": "sales-pipeline"})), "sales-pipeline"));
+        assert!(invokes_skill(
+            &tool_use("Read", json!({"file_path": "/tmp/x/.claude/skills/sales-pipeline/SKILL.md"})),
+            "sales-pipeline"
+        ));
+        assert!(!invokes_skill(&tool_use("Skill", json!({"skill": "other"})), "sales-pipeline"));
+        assert!(!invokes_skill(
+            &tool_use("Read", json!({"file_path": "/tmp/x/.claude/skills/sales-pipeline/references/a.md"})),
+            "sales-pipeline"
+        ));
+    }
+
+    #[test]
+    fn test_evaluate_reports_each_failure() {
+        let messages = vec![
+            tool_use("Skill", json!({"skill": "sales-pipeline"})),
+            json!({"type": "result", "result": "Sum MRR by Segment, excluding churned accounts."}),
+        ];
+        let (triggered, response, failures) = evaluate(&case(true, &["segment", "churn"]), &messages);
+        assert!(triggered);
+        assert!(response.starts_with("Sum MRR"));
+        assert!(failures.is_empty());
+
+        let (_, _, failures) = evaluate(&case(false, &["forecast"]), &messages);
+        assert_eq!(
+            failures,
+            vec![
+                "Skill triggered but was not expected to".to_string(),
+                "Response does not contain \"forecast\"".to_string(),
+            ]
+        );
+
+        let untriggered = vec![json!({"type": "assistant", "message": {"content": [{"type": "text", "text": "No idea."}]}})];
+        let (triggered, response, failures) = evaluate(&case(true, &[]), &untriggered);
+        assert!(!triggered);
+        assert_eq!(response, "No idea.");
+        assert_eq!(failures, vec!["Skill did not trigger".to_string()]);
+    }
+
+    #[test]
+    fn test_results_grouped_by_run() {
+        let conn = crate::commands::test_utils::create_test_db();
+        let stored = crate::db::insert_skill_test_case(&conn, &case(true, &["segment"])).unwrap();
+        assert_eq!(stored.expected_contains, vec!["segment".to_string()]);
+        let result = |run_id: &str, passed: bool| SkillTestResult {
+            id: 0,
+            test_case_id: stored.id,
+            test_name: String::new(),
+            skill_name: "sales-pipeline".into(),
+            run_id: run_id.into(),
+            skill_version: Some("1.0.0".into()),
+            triggered: passed,
+            passed,
+            response: "r".into(),
+            failures: if passed { vec![] } else { vec!["Skill did not trigger".into()] },
+            error: None,
+            duration_ms: 10,
+            created_at: String::new(),
+        };
+        crate::db::insert_skill_test_result(&conn, &result("run-1", false)).unwrap();
+        crate::db::insert_skill_test_result(&conn, &result("run-2", true)).unwrap();
+
+        let latest = crate::db::list_skill_test_results(&conn, "sales-pipeline", None).unwrap();
+        assert_eq!(latest.len(), 1);
+        assert_eq!((latest[0].run_id.as_str(), latest[0].passed), ("run-2", true));
+        assert_eq!(latest[0].test_name, "mrr");
+        let first = crate::db::list_skill_test_results(&conn, "sales-pipeline", Some("run-1")).unwrap();
+        assert_eq!(first[0].failures, vec!["Skill did not trigger".to_string()]);
+
+        assert!(crate::db::delete_skill_test_case(&conn, stored.id).unwrap());
+        assert!(crate::db::list_skill_test_results(&conn, "sales-pipeline", None).unwrap().is_empty());
+        assert!(!crate::db::delete_skill_test_case(&conn, stored.id).unwrap());
+    }
+}
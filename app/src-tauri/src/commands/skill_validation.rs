@@ -0,0 +1,482 @@
+//! Rule-based validation of a skill before packaging.
+//!
+//! Checks the SKILL.md frontmatter against the skill format, relative links
+//! in SKILL.md and `references/` that point at missing files, an
+//! `argument-hint` for user-invocable skills, heuristics for how well the
+//! description will trigger the skill, and oversized files. Each finding has
+//! a severity; `package_skill` can refuse to package when any is an error.
+
+use std::path::Path;
+
+use super::imported_skills::parse_frontmatter_full;
+use crate::types::SkillDiagnostic;
+
+pub(crate) const SEVERITY_ERROR: &str = "error";
+pub(crate) const SEVERITY_WARNING: &str = "warning";
+pub(crate) const SEVERITY_INFO: &str = "info";
+
+const RULE_FRONTMATTER_MISSING: &str = "frontmatter-missing";
+const RULE_NAME: &str = "frontmatter-name";
+const RULE_DESCRIPTION: &str = "frontmatter-description";
+const RULE_BOOLEAN: &str = "frontmatter-boolean";
+const RULE_ARGUMENT_HINT: &str = "argument-hint-missing";
+const RULE_TRIGGER_SHORT: &str = "trigger-too-short";
+const RULE_TRIGGER_NO_WHEN: &str = "trigger-no-when";
+const RULE_TRIGGER_PERSON: &str = "trigger-person";
+const RULE_BROKEN_LINK: &str = "broken-link";
+const RULE_OVERSIZED: &str = "oversized-file";
+
+/// Skill format limits.
+const MAX_NAME_CHARS: usize = 64;
+const MAX_DESCRIPTION_CHARS: usize = 1024;
+/// Descriptions shorter than this rarely say enough to be picked reliably.
+const MIN_DESCRIPTION_WORDS: usize = 12;
+/// SKILL.md is loaded whole when the skill triggers; longer bodies belong in references.
+const MAX_SKILL_MD_LINES: usize = 500;
+/// Files above this are flagged as warnings, and above the hard limit as errors.
+const WARN_FILE_BYTES: u64 = 100 * 1024;
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+const BOOLEAN_KEYS: [&str; 2] = ["user-invocable", "disable-model-invocation"];
+
+fn diagnostic(file: &str, line: Option<usize>, rule: &str, severity: &str, message: String) -> SkillDiagnostic {
+    SkillDiagnostic {
+        file: file.to_string(),
+        line,
+        rule: rule.to_string(),
+        severity: severity.to_string(),
+        message,
+    }
+}
+
+/// Lines of the YAML frontmatter with their 1-based line numbers, or `None`
+/// when the file doesn't open with a closed `---` block.
+pub(crate) fn frontmatter_lines(content: &str) -> Option<Vec<(usize, &str)>> {
+    let mut lines = content.lines().enumerate();
+    if lines.next().map(|(_, l)| l.trim()) != Some("---") {
+        return None;
+    }
+    let mut block = Vec::new();
+    for (i, line) in lines {
+        if line.trim() == "---" {
+            return Some(block);
+        }
+        block.push((i + 1, line));
+    }
+    None
+}
+
+/// `content` after the line closing its frontmatter block, or all of `content`
+/// when it doesn't open with a closed `---` block.
+pub(crate) fn frontmatter_body(content: &str) -> &str {
+    let mut lines = content.split_inclusive('\n');
+    let Some(opening) = lines.next().filter(|l| l.trim() == "---") else {
+        return content;
+    };
+    let mut offset = opening.len();
+    for line in lines {
+        offset += line.len();
+        if line.trim() == "---" {
+            return &content[offset..];
+        }
+    }
+    content
+}
+
+fn check_frontmatter(content: &str, dir_name: &str, out: &mut Vec<SkillDiagnostic>) {
+    let Some(block) = frontmatter_lines(content) else {
+        out.push(diagnostic(
+            "SKILL.md",
+            Some(1),
+            RULE_FRONTMATTER_MISSING,
+            SEVERITY_ERROR,
+            "SKILL.md must start with a YAML frontmatter block between --- lines".to_string(),
+        ));
+        return;
+    };
+    let line_of = |key: &str| {
+        block
+            .iter()
+            .find(|(_, l)| l.trim_start().starts_with(&format!("{}:", key)))
+            .map(|(n, _)| *n)
+    };
+    let fm = parse_frontmatter_full(content);
+
+    match fm.name.as_deref() {
+        None => out.push(diagnostic("SKILL.md", Some(1), RULE_NAME, SEVERITY_ERROR, "Frontmatter has no name".to_string())),
+        Some(name) => {
+            if !super::skill::is_valid_kebab(name) || name.chars().count() > MAX_NAME_CHARS {
+                out.push(diagnostic(
+                    "SKILL.md",
+                    line_of("name"),
+                    RULE_NAME,
+                    SEVERITY_ERROR,
+                    format!("Name '{}' must be kebab-case and at most {} characters", name, MAX_NAME_CHARS),
+                ));
+            } else if name != dir_name {
+                out.push(diagnostic(
+                    "SKILL.md",
+                    line_of("name"),
+                    RULE_NAME,
+                    SEVERITY_WARNING,
+                    format!("Name '{}' doesn't match the skill folder '{}'", name, dir_name),
+                ));
+            }
+        }
+    }
+
+    let description_line = line_of("description");
+    match fm.description.as_deref() {
+        None => out.push(diagnostic(
+            "SKILL.md",
+            Some(1),
+            RULE_DESCRIPTION,
+            SEVERITY_ERROR,
+            "Frontmatter has no description".to_string(),
+        )),
+        Some(d) => {
+            let chars = d.chars().count();
+            if chars > MAX_DESCRIPTION_CHARS {
+                out.push(diagnostic(
+                    "SKILL.md",
+                    description_line,
+                    RULE_DESCRIPTION,
+                    SEVERITY_ERROR,
+                    format!("Description is {} characters (limit {})", chars, MAX_DESCRIPTION_CHARS),
+                ));
+            }
+            if d.contains('<') && d.contains('>') {
+                out.push(diagnostic(
+                    "SKILL.md",
+                    description_line,
+                    RULE_DESCRIPTION,
+                    SEVERITY_ERROR,
+                    "Description contains XML/HTML tags".to_string(),
+                ));
+            }
+            check_trigger_text(d, description_line, out);
+        }
+    }
+
+    for key in BOOLEAN_KEYS {
+        let Some((n, line)) = block.iter().find(|(_, l)| l.trim_start().starts_with(&format!("{}:", key))) else {
+            continue;
+        };
+        let value = line.trim_start()[key.len() + 1..].trim().trim_matches(['"', '\'']);
+        if !matches!(value, "true" | "false") {
+            out.push(diagnostic(
+                "SKILL.md",
+                Some(*n),
+                RULE_BOOLEAN,
+                SEVERITY_ERROR,
+                format!("'{}' must be true or false, found '{}'", key, value),
+            ));
+        }
+    }
+
+    if fm.user_invocable == Some(true) && fm.argument_hint.is_none() {
+        out.push(diagnostic(
+            "SKILL.md",
+            line_of("user-invocable"),
+            RULE_ARGUMENT_HINT,
+            SEVERITY_WARNING,
+            "User-invocable skills should set argument-hint so the slash command shows what to pass".to_string(),
+        ));
+    }
+}
+
+/// Heuristics for descriptions the model is unlikely to match: too short, no
+/// "when" clause saying when to use the skill, or written in first/second person.
+fn check_trigger_text(description: &str, line: Option<usize>, out: &mut Vec<SkillDiagnostic>) {
+    let words: Vec<String> = description
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'').to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect();
+    if words.len() < MIN_DESCRIPTION_WORDS {
+        out.push(diagnostic(
+            "SKILL.md",
+            line,
+            RULE_TRIGGER_SHORT,
+            SEVERITY_WARNING,
+            format!(
+                "Description has {} words; say what the skill does and when to use it (at least {})",
+                words.len(),
+                MIN_DESCRIPTION_WORDS
+            ),
+        ));
+    }
+    if !words.iter().any(|w| w == "when" || w == "whenever") {
+        out.push(diagnostic(
+            "SKILL.md",
+            line,
+            RULE_TRIGGER_NO_WHEN,
+            SEVERITY_WARNING,
+            "Description doesn't say when to use the skill (e.g. \"Use when ...\")".to_string(),
+        ));
+    }
+    if words.iter().any(|w| matches!(w.as_str(), "i" | "i'm" | "my" | "you" | "your")) {
+        out.push(diagnostic(
+            "SKILL.md",
+            line,
+            RULE_TRIGGER_PERSON,
+            SEVERITY_INFO,
+            "Description should be written in the third person".to_string(),
+        ));
+    }
+}
+
+/// Targets of inline markdown links and images on `line`.
+fn link_targets(line: &str) -> Vec<&str> {
+    let mut targets = Vec::new();
+    let mut rest = line;
+    while let Some(pos) = rest.find("](") {
+        let after = &rest[pos + 2..];
+        let Some(end) = after.find(')') else {
+            break;
+        };
+        let target = after[..end].split_whitespace().next().unwrap_or("");
+        targets.push(target.trim_matches(['<', '>']));
+        rest = &after[end..];
+    }
+    targets
+}
+
+pub(crate) fn is_external(target: &str) -> bool {
+    target.is_empty()
+        || target.starts_with('#')
+        || target.starts_with('/')
+        || target.contains("://")
+        || target.starts_with("mailto:")
+}
+
+fn check_links(skill_dir: &Path, file: &str, content: &str, out: &mut Vec<SkillDiagnostic>) {
+    let base = Path::new(file).parent().unwrap_or_else(|| Path::new(""));
+    let mut in_fence = false;
+    for (i, line) in content.lines().enumerate() {
+        if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        for target in link_targets(line) {
+            if is_external(target) {
+                continue;
+            }
+            let path = target.split(['#', '?']).next().unwrap_or(target);
+            if !skill_dir.join(base).join(path).exists() {
+                out.push(diagnostic(
+                    file,
+                    Some(i + 1),
+                    RULE_BROKEN_LINK,
+                    SEVERITY_ERROR,
+                    format!("Link target '{}' does not exist", target),
+                ));
+            }
+        }
+    }
+}
+
+/// SKILL.md plus every file under `references/`, relative to `skill_dir`.
+fn packaged_files(skill_dir: &Path) -> Vec<String> {
+    let mut files = Vec::new();
+    if skill_dir.join("SKILL.md").is_file() {
+        files.push("SKILL.md".to_string());
+    }
+    let mut stack = vec![skill_dir.join("references")];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else if let Ok(rel) = path.strip_prefix(skill_dir) {
+                files.push(rel.to_string_lossy().replace('\\', "/"));
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+fn check_size(skill_dir: &Path, file: &str, out: &mut Vec<SkillDiagnostic>) {
+    let Ok(meta) = std::fs::metadata(skill_dir.join(file)) else {
+        return;
+    };
+    let kib = meta.len() / 1024;
+    if meta.len() > MAX_FILE_BYTES {
+        out.push(diagnostic(
+            file,
+            None,
+            RULE_OVERSIZED,
+            SEVERITY_ERROR,
+            format!("File is {} KiB (limit {} KiB)", kib, MAX_FILE_BYTES / 1024),
+        ));
+    } else if meta.len() > WARN_FILE_BYTES {
+        out.push(diagnostic(
+            file,
+            None,
+            RULE_OVERSIZED,
+            SEVERITY_WARNING,
+            format!("File is {} KiB; consider splitting it (over {} KiB)", kib, WARN_FILE_BYTES / 1024),
+        ));
+    }
+}
+
+/// Validate SKILL.md and `references/`. Returns the number of files checked
+/// and the diagnostics, errors first.
+pub(crate) fn validate_skill_dir(skill_dir: &Path) -> (usize, Vec<SkillDiagnostic>) {
+    let mut out = Vec::new();
+    let dir_name = skill_dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    match std::fs::read_to_string(skill_dir.join("SKILL.md")) {
+        Ok(content) => {
+            check_frontmatter(&content, &dir_name, &mut out);
+            let lines = content.lines().count();
+            if lines > MAX_SKILL_MD_LINES {
+                out.push(diagnostic(
+                    "SKILL.md",
+                    None,
+                    RULE_OVERSIZED,
+                    SEVERITY_WARNING,
+                    format!("SKILL.md has {} lines; move detail into references (over {})", lines, MAX_SKILL_MD_LINES),
+                ));
+            }
+        }
+        Err(_) => out.push(diagnostic("SKILL.md", None, RULE_FRONTMATTER_MISSING, SEVERITY_ERROR, "SKILL.md is missing".to_string())),
+    }
+
+    let files = packaged_files(skill_dir);
+    for file in &files {
+        check_size(skill_dir, file, &mut out);
+        if file.ends_with(".md") {
+            if let Ok(content) = std::fs::read_to_string(skill_dir.join(file)) {
+                check_links(skill_dir, file, &content, &mut out);
+            }
+        }
+    }
+
+    let rank = |s: &str| match s {
+        SEVERITY_ERROR => 0,
+        SEVERITY_WARNING => 1,
+        _ => 2,
+    };
+    out.sort_by_key(|d| rank(&d.severity));
+    (files.len(), out)
+}
+
+/// Validate before packaging. When `refuse_on_errors` is set, error-severity
+/// diagnostics fail packaging; otherwise all diagnostics are returned.
+pub(crate) fn validate_for_packaging(skill_dir: &Path, refuse_on_errors: bool) -> Result<Vec<SkillDiagnostic>, String> {
+    let (_, diagnostics) = validate_skill_dir(skill_dir);
+    let errors: Vec<String> = diagnostics
+        .iter()
+        .filter(|d| d.severity == SEVERITY_ERROR)
+        .map(|d| match d.line {
+            Some(line) => format!("{}:{} {}", d.file, line, d.message),
+            None => format!("{} {}", d.file, d.message),
+        })
+        .collect();
+    if refuse_on_errors && !errors.is_empty() {
+        return Err(format!(
+            "Skill validation found {} error(s): {}. Fix them before packaging.",
+            errors.len(),
+            errors.join("; ")
+        ));
+    }
+    Ok(diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_skill(skill_md: &str) -> (tempfile::TempDir, std::path::PathBuf) {
+        let dir = tempdir().unwrap();
+        let skill_dir = dir.path().join("sales-pipeline");
+        std::fs::create_dir_all(skill_dir.join("references")).unwrap();
+        std::fs::write(skill_dir.join("SKILL.md"), skill_md).unwrap();
+        (dir, skill_dir)
+    }
+
+    fn rules(diagnostics: &[SkillDiagnostic]) -> Vec<&str> {
+        diagnostics.iter().map(|d| d.rule.as_str()).collect()
+    }
+
+    #[test]
+    fn test_frontmatter_body_drops_only_the_block() {
+        assert_eq!(frontmatter_body("---\nname: a\n---\n- first item\n"), "- first item\n");
+        assert_eq!(frontmatter_body("---\r\nname: a\r\n---\r\n\n---\nrule\n"), "\n---\nrule\n");
+        assert_eq!(frontmatter_body("# No frontmatter\n"), "# No frontmatter\n");
+        assert_eq!(frontmatter_body("---\nname: a\n"), "---\nname: a\n");
+    }
+
+    const GOOD_DESCRIPTION: &str =
+        "Analyzes sales pipeline stages and conversion rates. Use when reviewing pipeline health or forecasting quarterly bookings.";
+
+    #[test]
+    fn test_clean_skill_has_no_diagnostics() {
+        let (_dir, skill_dir) = write_skill(&format!(
+            "---\nname: sales-pipeline\ndescription: {}\n---\n# Sales\n\nSee [stages](references/stages.md#won).\n",
+            GOOD_DESCRIPTION
+        ));
+        std::fs::write(skill_dir.join("references/stages.md"), "# Stages\n\nBack to [skill](../SKILL.md).\n").unwrap();
+
+        let (files, diagnostics) = validate_skill_dir(&skill_dir);
+        assert_eq!(files, 2);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_frontmatter_schema_and_argument_hint() {
+        let (_dir, skill_dir) = write_skill(&format!(
+            "---\nname: Sales Pipeline\ndescription: {}\nuser-invocable: yes\n---\n# Sales\n",
+            GOOD_DESCRIPTION
+        ));
+        let (_, diagnostics) = validate_skill_dir(&skill_dir);
+        let name = diagnostics.iter().find(|d| d.rule == RULE_NAME).unwrap();
+        assert_eq!((name.severity.as_str(), name.line), (SEVERITY_ERROR, Some(2)));
+        let boolean = diagnostics.iter().find(|d| d.rule == RULE_BOOLEAN).unwrap();
+        assert_eq!(boolean.line, Some(4));
+        assert!(rules(&diagnostics).contains(&RULE_ARGUMENT_HINT));
+
+        let (_dir, skill_dir) = write_skill("# No frontmatter\n");
+        assert_eq!(rules(&validate_skill_dir(&skill_dir).1), vec![RULE_FRONTMATTER_MISSING]);
+    }
+
+    #[test]
+    fn test_trigger_heuristics_and_broken_links() {
+        let (_dir, skill_dir) = write_skill(
+            "---\nname: sales-pipeline\ndescription: I help with your pipeline\n---\n\
+             [missing](references/gone.md) [web](https://example.com)\n```\n[ignored](nope.md)\n```\n",
+        );
+        let (_, diagnostics) = validate_skill_dir(&skill_dir);
+        let found = rules(&diagnostics);
+        assert!(found.contains(&RULE_TRIGGER_SHORT));
+        assert!(found.contains(&RULE_TRIGGER_NO_WHEN));
+        assert!(found.contains(&RULE_TRIGGER_PERSON));
+        let links: Vec<_> = diagnostics.iter().filter(|d| d.rule == RULE_BROKEN_LINK).collect();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].line, Some(5));
+        // Errors sort first
+        assert_eq!(diagnostics[0].severity, SEVERITY_ERROR);
+    }
+
+    #[test]
+    fn test_packaging_refuses_only_when_asked() {
+        let (_dir, skill_dir) = write_skill(&format!(
+            "---\nname: sales-pipeline\ndescription: {}\n---\n",
+            GOOD_DESCRIPTION
+        ));
+        std::fs::write(skill_dir.join("references/huge.txt"), vec![b'a'; (MAX_FILE_BYTES + 1) as usize]).unwrap();
+
+        let diagnostics = validate_for_packaging(&skill_dir, false).unwrap();
+        assert_eq!(rules(&diagnostics), vec![RULE_OVERSIZED]);
+        let err = validate_for_packaging(&skill_dir, true).unwrap_err();
+        assert!(err.contains("references/huge.txt"));
+    }
+}
@@ -0,0 +1,236 @@
+//! Automatic semantic versioning of generated skills.
+//!
+//! When step 3 (Generate Skill) completes for a skill with an earlier
+//! SKILL.md in the skills repo history, the new file is compared with the
+//! last committed one and the version is bumped: removing a section or
+//! changing the `argument-hint` is a major change, adding a section or
+//! changing the description is minor, and anything else is a patch. The new version is
+//! written to the SKILL.md frontmatter and the `skills` table, and a
+//! changelog entry is recorded for `get_skill_changelog`. A first
+//! generation has nothing to compare against and keeps its version.
+
+use std::path::Path;
+
+use super::imported_skills::{parse_frontmatter_full, validate_skill_name};
+use crate::db::Db;
+use crate::types::SkillChangelogEntry;
+
+pub(crate) const DEFAULT_VERSION: &str = "1.0.0";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VersionBump {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl VersionBump {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            VersionBump::Major => "major",
+            VersionBump::Minor => "minor",
+            VersionBump::Patch => "patch",
+        }
+    }
+}
+
+/// `## ` headings in the SKILL.md body, in order.
+fn sections(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|line| line.strip_prefix("## "))
+        .map(|heading| heading.trim().to_string())
+        .collect()
+}
+
+/// Choose the bump for a change from `old` to `new` SKILL.md content, with a
+/// one-line reason.
+pub(crate) fn propose_bump(old: &str, new: &str) -> (VersionBump, String) {
+    let old_fm = parse_frontmatter_full(old);
+    let new_fm = parse_frontmatter_full(new);
+    let old_sections = sections(old);
+    let new_sections = sections(new);
+
+    let removed: Vec<&str> = old_sections
+        .iter()
+        .filter(|s| !new_sections.contains(s))
+        .map(String::as_str)
+        .collect();
+    if !removed.is_empty() {
+        return (VersionBump::Major, format!("removed section: {}", removed.join(", ")));
+    }
+    if old_fm.argument_hint != new_fm.argument_hint {
+        return (VersionBump::Major, "changed argument-hint".to_string());
+    }
+
+    let added: Vec<&str> = new_sections
+        .iter()
+        .filter(|s| !old_sections.contains(s))
+        .map(String::as_str)
+        .collect();
+    if !added.is_empty() {
+        return (VersionBump::Minor, format!("added section: {}", added.join(", ")));
+    }
+    if old_fm.description != new_fm.description {
+        return (VersionBump::Minor, "changed description".to_string());
+    }
+
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let changed = new_lines.iter().filter(|l| !old_lines.contains(l)).count()
+        + old_lines.iter().filter(|l| !new_lines.contains(l)).count();
+    (VersionBump::Patch, format!("edited {} line(s)", changed))
+}
+
+/// Apply `bump` to a semver string. Unparseable versions restart from 1.0.0.
+pub(crate) fn bump_version(version: &str, bump: VersionBump) -> String {
+    let Ok(mut v) = semver::Version::parse(version.trim()) else {
+        return DEFAULT_VERSION.to_string();
+    };
+    match bump {
+        VersionBump::Major => {
+            v.major += 1;
+            v.minor = 0;
+            v.patch = 0;
+        }
+        VersionBump::Minor => {
+            v.minor += 1;
+            v.patch = 0;
+        }
+        VersionBump::Patch => v.patch += 1,
+    }
+    v.pre = semver::Prerelease::EMPTY;
+    v.build = semver::BuildMetadata::EMPTY;
+    v.to_string()
+}
+
+/// Bump the version of a regenerated skill against the last SKILL.md
+/// committed in the skills repo. Returns the recorded entry, or `None` when
+/// there is no earlier SKILL.md or it is unchanged.
+pub(crate) fn version_regenerated_skill(
+    conn: &rusqlite::Connection,
+    skills_path: &str,
+    skill_name: &str,
+) -> Result<Option<SkillChangelogEntry>, String> {
+    let skill_md = Path::new(skills_path).join(skill_name).join("SKILL.md");
+    if !skill_md.is_file() {
+        return Ok(None);
+    }
+    let Some((previous_sha, old)) =
+        crate::git::last_committed_file(Path::new(skills_path), &format!("{}/SKILL.md", skill_name))?
+    else {
+        return Ok(None);
+    };
+    let new = std::fs::read_to_string(&skill_md).map_err(|e| format!("Failed to read SKILL.md: {}", e))?;
+    if new == old {
+        return Ok(None);
+    }
+
+    let previous_version = parse_frontmatter_full(&old).version.or_else(|| {
+        crate::db::get_workflow_run(conn, skill_name)
+            .ok()
+            .flatten()
+            .and_then(|run| run.version)
+    });
+    let (bump, summary) = propose_bump(&old, &new);
+    let version = bump_version(previous_version.as_deref().unwrap_or(DEFAULT_VERSION), bump);
+
+    let updated = super::frontmatter_migration::set_frontmatter_fields(&new, &[("version", &version)]);
+    if updated != new {
+        std::fs::write(&skill_md, updated).map_err(|e| format!("Failed to write SKILL.md: {}", e))?;
+    }
+    crate::db::set_skill_behaviour(conn, skill_name, None, Some(&version), None, None, None, None)?;
+
+    let entry = SkillChangelogEntry {
+        skill_name: skill_name.to_string(),
+        version,
+        previous_version,
+        bump: bump.as_str().to_string(),
+        summary,
+        previous_sha: Some(previous_sha),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    crate::db::insert_skill_changelog_entry(conn, &entry)?;
+    log::info!(
+        "[version_regenerated_skill] '{}' {} -> {} ({}: {})",
+        skill_name,
+        entry.previous_version.as_deref().unwrap_or("none"),
+        entry.version,
+        entry.bump,
+        entry.summary
+    );
+    Ok(Some(entry))
+}
+
+/// Version bumps recorded for a skill, newest first.
+#[tauri::command]
+pub fn get_skill_changelog(skill_name: String, db: tauri::State<'_, Db>) -> Result<Vec<SkillChangelogEntry>, String> {
+    log::info!("[get_skill_changelog] skill={}", skill_name);
+    validate_skill_name(&skill_name)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[get_skill_changelog] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::list_skill_changelog(&conn, &skill_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::create_test_db;
+    use tempfile::tempdir;
+
+    const BASE: &str = "---\nname: sales\ndescription: Sales metrics\nversion: 1.2.3\n---\n# Sales\n\n## Metrics\nRevenue.\n\n## Joins\nOrders to customers.\n";
+
+    #[test]
+    fn test_propose_bump_by_kind_of_change() {
+        let removed = BASE.replace("## Joins\nOrders to customers.\n", "");
+        assert_eq!(propose_bump(BASE, &removed), (VersionBump::Major, "removed section: Joins".to_string()));
+
+        let added = format!("{}\n## Filters\nActive only.\n", BASE);
+        assert_eq!(propose_bump(BASE, &added).0, VersionBump::Minor);
+
+        let described = BASE.replace("Sales metrics", "Sales and pipeline metrics");
+        assert_eq!(propose_bump(BASE, &described).0, VersionBump::Minor);
+
+        let reworded = BASE.replace("Revenue.", "Net revenue.");
+        assert_eq!(propose_bump(BASE, &reworded), (VersionBump::Patch, "edited 2 line(s)".to_string()));
+    }
+
+    #[test]
+    fn test_bump_version() {
+        assert_eq!(bump_version("1.2.3", VersionBump::Major), "2.0.0");
+        assert_eq!(bump_version("1.2.3", VersionBump::Minor), "1.3.0");
+        assert_eq!(bump_version("1.2.3-rc.1", VersionBump::Patch), "1.2.4");
+        assert_eq!(bump_version("v1", VersionBump::Patch), "1.0.0");
+    }
+
+    #[test]
+    fn test_version_regenerated_skill_updates_frontmatter_db_and_changelog() {
+        let conn = create_test_db();
+        crate::db::save_workflow_run(&conn, "sales", 3, "in_progress", "domain").unwrap();
+        let tmp = tempdir().unwrap();
+        let skills_path = tmp.path().to_str().unwrap();
+        let skill_md = tmp.path().join("sales/SKILL.md");
+        std::fs::create_dir_all(skill_md.parent().unwrap()).unwrap();
+        crate::git::ensure_repo(tmp.path()).unwrap();
+
+        // First generation: nothing committed to compare against.
+        std::fs::write(&skill_md, BASE).unwrap();
+        assert!(version_regenerated_skill(&conn, skills_path, "sales").unwrap().is_none());
+        let sha = crate::git::commit_all(tmp.path(), "sales: step 3 completed").unwrap().unwrap();
+
+        // Regeneration that adds a section.
+        std::fs::write(&skill_md, format!("{}\n## Filters\nActive only.\n", BASE)).unwrap();
+        let entry = version_regenerated_skill(&conn, skills_path, "sales").unwrap().unwrap();
+        assert_eq!(entry.version, "1.3.0");
+        assert_eq!(entry.previous_version.as_deref(), Some("1.2.3"));
+        assert_eq!(entry.bump, "minor");
+        assert_eq!(entry.previous_sha.as_deref(), Some(sha.as_str()));
+        let written = parse_frontmatter_full(&std::fs::read_to_string(&skill_md).unwrap());
+        assert_eq!(written.version.as_deref(), Some("1.3.0"));
+        let run = crate::db::get_workflow_run(&conn, "sales").unwrap().unwrap();
+        assert_eq!(run.version.as_deref(), Some("1.3.0"));
+        assert_eq!(crate::db::list_skill_changelog(&conn, "sales").unwrap(), vec![entry]);
+    }
+}
@@ -0,0 +1,408 @@
+//! Retained outputs of sub-agents spawned during a workflow step.
+//!
+//! Steps like research fan out to sub-agents via the `Task` tool and only the
+//! final synthesis is kept as step output. When a step completes, each
+//! sub-agent's returned report is extracted from the step's transcript and
+//! written to `{workspace}/{skill}/artifacts/step{N}/`, one file per sub-agent
+//! call, with an `index.json` listing them in completion order. Files are
+//! size-capped and readable through the regular files API.
+
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use crate::commands::imported_skills::validate_skill_name;
+
+pub const ARTIFACTS_DIR: &str = "artifacts";
+const INDEX_FILE: &str = "index.json";
+
+/// Per-artifact cap; longer reports are truncated with a marker.
+const MAX_ARTIFACT_BYTES: usize = 64 * 1024;
+/// Per-step cap across all artifacts; later sub-agents are indexed but not written.
+const MAX_STEP_ARTIFACT_BYTES: usize = 1024 * 1024;
+
+/// Tool names the SDK uses for sub-agent calls.
+const SUBAGENT_TOOLS: &[&str] = &["Task", "Agent"];
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct StepArtifact {
+    /// 1-based position in completion order.
+    pub seq: usize,
+    pub tool_use_id: String,
+    /// The sub-agent type requested (`general-purpose` when unspecified).
+    pub subagent: String,
+    pub description: Option<String>,
+    /// Path relative to `{workspace}/{skill}`; `None` when the step cap was reached.
+    pub relative_path: Option<String>,
+    /// Size of the full report before truncation.
+    pub bytes: usize,
+    pub truncated: bool,
+    pub is_error: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct StepArtifactIndex {
+    pub skill_name: String,
+    pub step_id: u32,
+    /// Transcript file (under `{skill}/logs/`) the artifacts were extracted from.
+    pub transcript: String,
+    pub captured_at: String,
+    pub artifacts: Vec<StepArtifact>,
+}
+
+/// A sub-agent call and the report it returned.
+#[derive(Debug, Clone, PartialEq)]
+struct SubagentOutput {
+    tool_use_id: String,
+    subagent: String,
+    description: Option<String>,
+    output: String,
+    is_error: bool,
+}
+
+/// Pair sub-agent `tool_use` blocks with their `tool_result`, in completion order.
+fn extract_subagent_outputs<R: BufRead>(reader: R) -> Vec<SubagentOutput> {
+    let mut pending: HashMap<String, (String, Option<String>)> = HashMap::new();
+    let mut outputs = Vec::new();
+
+    for line in reader.lines().map_while(Result::ok) {
+        let msg: serde_json::Value = match serde_json::from_str(&line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let Some(blocks) = msg["message"]["content"].as_array() else {
+            continue;
+        };
+        match msg["type"].as_str() {
+            Some("assistant") => {
+                for block in blocks {
+                    let is_subagent_call = block["type"] == "tool_use"
+                        && block["name"].as_str().is_some_and(|n| SUBAGENT_TOOLS.contains(&n));
+                    if let (true, Some(id)) = (is_subagent_call, block["id"].as_str()) {
+                        let input = &block["input"];
+                        let subagent = input["subagent_type"]
+                            .as_str()
+                            .unwrap_or("general-purpose")
+                            .to_string();
+                        let description = input["description"].as_str().map(|s| s.to_string());
+                        pending.insert(id.to_string(), (subagent, description));
+                    }
+                }
+            }
+            Some("user") => {
+                for block in blocks.iter().filter(|b| b["type"] == "tool_result") {
+                    let Some(id) = block["tool_use_id"].as_str() else {
+                        continue;
+                    };
+                    let Some((subagent, description)) = pending.remove(id) else {
+                        continue;
+                    };
+                    outputs.push(SubagentOutput {
+                        tool_use_id: id.to_string(),
+                        subagent,
+                        description,
+                        output: tool_result_text(&block["content"]),
+                        is_error: block["is_error"].as_bool().unwrap_or(false),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    outputs
+}
+
+/// `tool_result.content` is either a string or a list of content blocks.
+fn tool_result_text(content: &serde_json::Value) -> String {
+    match content {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|b| b["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Lowercase, hyphen-separated file name fragment.
+fn slug(s: &str) -> String {
+    let mut out = String::new();
+    for c in s.chars() {
+        if c.is_ascii_alphanumeric() {
+            out.push(c.to_ascii_lowercase());
+        } else if !out.ends_with('-') {
+            out.push('-');
+        }
+    }
+    let trimmed = out.trim_matches('-');
+    if trimmed.is_empty() {
+        "subagent".to_string()
+    } else {
+        trimmed.chars().take(40).collect()
+    }
+}
+
+/// Truncate at a char boundary no later than `max` bytes.
+fn truncate_utf8(s: &str, max: usize) -> &str {
+    if s.len() <= max {
+        return s;
+    }
+    let mut end = max;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+fn step_artifacts_dir(workspace_path: &str, skill_name: &str, step_id: u32) -> PathBuf {
+    Path::new(workspace_path)
+        .join(skill_name)
+        .join(ARTIFACTS_DIR)
+        .join(format!("step{}", step_id))
+}
+
+/// Most recent transcript for a workflow step (`logs/step{N}-{timestamp}.jsonl`,
+/// or `.jsonl.zst` once compressed).
+fn latest_step_transcript(workspace_path: &str, skill_name: &str, step_id: u32) -> Option<PathBuf> {
+    let logs_dir = Path::new(workspace_path).join(skill_name).join("logs");
+    let prefix = format!("step{}-", step_id);
+    std::fs::read_dir(logs_dir)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(&prefix) && crate::transcripts::is_transcript(n))
+        })
+        // Timestamp suffix sorts lexicographically; a `.jsonl.zst` sorts after
+        // the plain file it replaces
+        .max()
+}
+
+/// Extract sub-agent reports from a step's latest transcript and persist them,
+/// replacing artifacts from any earlier run of the step. Returns `None` when
+/// there is no transcript or the step spawned no sub-agents.
+pub fn capture_step_artifacts(
+    workspace_path: &str,
+    skill_name: &str,
+    step_id: u32,
+) -> Result<Option<StepArtifactIndex>, String> {
+    let Some(transcript) = latest_step_transcript(workspace_path, skill_name, step_id) else {
+        return Ok(None);
+    };
+    let outputs = extract_subagent_outputs(crate::transcripts::open_transcript(&transcript)?);
+
+    let dir = step_artifacts_dir(workspace_path, skill_name, step_id);
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)
+            .map_err(|e| format!("Failed to clear {}: {}", dir.display(), e))?;
+    }
+    if outputs.is_empty() {
+        return Ok(None);
+    }
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let mut written = 0usize;
+    let mut artifacts = Vec::with_capacity(outputs.len());
+    for (i, out) in outputs.iter().enumerate() {
+        let seq = i + 1;
+        let kept = truncate_utf8(&out.output, MAX_ARTIFACT_BYTES);
+        let truncated = kept.len() < out.output.len();
+        let relative_path = if written + kept.len() <= MAX_STEP_ARTIFACT_BYTES {
+            let file_name = format!("{:02}-{}.md", seq, slug(&out.subagent));
+            let mut content = format!(
+                "# {}\n\n_Sub-agent: {} · tool_use_id: {}_\n\n{}",
+                out.description.as_deref().unwrap_or(&out.subagent),
+                out.subagent,
+                out.tool_use_id,
+                kept
+            );
+            if truncated {
+                content.push_str(&format!(
+                    "\n\n[truncated: kept {} of {} bytes]\n",
+                    kept.len(),
+                    out.output.len()
+                ));
+            }
+            std::fs::write(dir.join(&file_name), content)
+                .map_err(|e| format!("Failed to write artifact {}: {}", file_name, e))?;
+            written += kept.len();
+            Some(format!("{}/step{}/{}", ARTIFACTS_DIR, step_id, file_name))
+        } else {
+            None
+        };
+        artifacts.push(StepArtifact {
+            seq,
+            tool_use_id: out.tool_use_id.clone(),
+            subagent: out.subagent.clone(),
+            description: out.description.clone(),
+            truncated: truncated || relative_path.is_none(),
+            relative_path,
+            bytes: out.output.len(),
+            is_error: out.is_error,
+        });
+    }
+
+    let index = StepArtifactIndex {
+        skill_name: skill_name.to_string(),
+        step_id,
+        transcript: transcript
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        captured_at: chrono::Utc::now().to_rfc3339(),
+        artifacts,
+    };
+    let json = serde_json::to_string_pretty(&index).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(INDEX_FILE), json)
+        .map_err(|e| format!("Failed to write artifact index: {}", e))?;
+    Ok(Some(index))
+}
+
+/// Remove retained artifacts for one step (used when the step is reset).
+pub fn delete_step_artifacts(workspace_path: &str, skill_name: &str, step_id: u32) {
+    let dir = step_artifacts_dir(workspace_path, skill_name, step_id);
+    if dir.exists() {
+        match std::fs::remove_dir_all(&dir) {
+            Ok(()) => log::debug!("[delete_step_artifacts] deleted {}", dir.display()),
+            Err(e) => log::warn!("[delete_step_artifacts] FAILED to delete {}: {}", dir.display(), e),
+        }
+    }
+}
+
+/// Sub-agent artifacts retained for a step, in completion order.
+#[tauri::command]
+pub fn list_step_artifacts(
+    workspace_path: String,
+    skill_name: String,
+    step_id: u32,
+) -> Result<Option<StepArtifactIndex>, String> {
+    log::info!("[list_step_artifacts] skill={} step={}", skill_name, step_id);
+    validate_skill_name(&skill_name)?;
+    let path = step_artifacts_dir(&workspace_path, &skill_name, step_id).join(INDEX_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| {
+        log::error!("[list_step_artifacts] Failed to read {}: {}", path.display(), e);
+        e.to_string()
+    })?;
+    serde_json::from_str(&content).map(Some).map_err(|e| {
+        log::error!("[list_step_artifacts] Invalid index {}: {}", path.display(), e);
+        e.to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn transcript_lines(results: &[(&str, &str, &str)]) -> String {
+        let mut lines = vec![json!({"type": "config", "config": {"prompt": "research"}}).to_string()];
+        for (id, subagent, _) in results {
+            lines.push(
+                json!({"type": "assistant", "message": {"content": [
+                    {"type": "text", "text": "Delegating"},
+                    {"type": "tool_use", "id": id, "name": "Task",
+                     "input": {"subagent_type": subagent, "description": format!("Research {}", subagent), "prompt": "..."}}
+                ]}})
+                .to_string(),
+            );
+        }
+        for (id, _, output) in results {
+            lines.push(
+                json!({"type": "user", "message": {"content": [
+                    {"type": "tool_result", "tool_use_id": id, "content": [{"type": "text", "text": output}]}
+                ]}})
+                .to_string(),
+            );
+        }
+        lines.push(json!({"type": "result", "subtype": "success", "result": "done"}).to_string());
+        lines.join("\n")
+    }
+
+    fn write_transcript(workspace: &Path, name: &str, content: &str) {
+        let logs = workspace.join("my-skill").join("logs");
+        std::fs::create_dir_all(&logs).unwrap();
+        std::fs::write(logs.join(name), content).unwrap();
+    }
+
+    #[test]
+    fn test_extracts_only_subagent_results_in_completion_order() {
+        let mut content = transcript_lines(&[("t1", "entity-research", "Entities: A, B"), ("t2", "metrics", "Metrics: X")]);
+        // A non-sub-agent tool result must be ignored.
+        content.push('\n');
+        content.push_str(
+            &json!({"type": "user", "message": {"content": [
+                {"type": "tool_result", "tool_use_id": "read-1", "content": "file body"}
+            ]}})
+            .to_string(),
+        );
+        let outputs = extract_subagent_outputs(content.as_bytes());
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[0].subagent, "entity-research");
+        assert_eq!(outputs[0].output, "Entities: A, B");
+        assert_eq!(outputs[1].description.as_deref(), Some("Research metrics"));
+    }
+
+    #[test]
+    fn test_capture_writes_namespaced_artifacts_and_index() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ws = tmp.path().to_str().unwrap();
+        write_transcript(tmp.path(), "step0-2026-01-01T00-00-00.jsonl", &transcript_lines(&[("old", "stale", "old")]));
+        write_transcript(
+            tmp.path(),
+            "step0-2026-01-02T00-00-00.jsonl",
+            &transcript_lines(&[("t1", "Entity Research", "Entities")]),
+        );
+
+        let index = capture_step_artifacts(ws, "my-skill", 0).unwrap().unwrap();
+        assert_eq!(index.transcript, "step0-2026-01-02T00-00-00.jsonl");
+        assert_eq!(index.artifacts.len(), 1);
+        let rel = index.artifacts[0].relative_path.as_deref().unwrap();
+        assert_eq!(rel, "artifacts/step0/01-entity-research.md");
+        let body = std::fs::read_to_string(tmp.path().join("my-skill").join(rel)).unwrap();
+        assert!(body.contains("Entities"));
+
+        let listed = list_step_artifacts(ws.to_string(), "my-skill".to_string(), 0).unwrap();
+        assert_eq!(listed, Some(index));
+
+        delete_step_artifacts(ws, "my-skill", 0);
+        assert!(list_step_artifacts(ws.to_string(), "my-skill".to_string(), 0).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_capture_caps_artifact_size() {
+        let tmp = tempfile::tempdir().unwrap();
+        let big = "é".repeat(MAX_ARTIFACT_BYTES);
+        write_transcript(
+            tmp.path(),
+            "step0-2026-01-01T00-00-00.jsonl",
+            &transcript_lines(&[("t1", "big", &big)]),
+        );
+        let index = capture_step_artifacts(tmp.path().to_str().unwrap(), "my-skill", 0)
+            .unwrap()
+            .unwrap();
+        let artifact = &index.artifacts[0];
+        assert!(artifact.truncated);
+        assert_eq!(artifact.bytes, big.len());
+        let body = std::fs::read_to_string(
+            tmp.path().join("my-skill").join(artifact.relative_path.as_deref().unwrap()),
+        )
+        .unwrap();
+        assert!(body.len() < big.len());
+        assert!(body.contains("[truncated:"));
+    }
+
+    #[test]
+    fn test_no_subagents_leaves_no_artifacts() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_transcript(tmp.path(), "step2-2026-01-01T00-00-00.jsonl", &transcript_lines(&[]));
+        assert!(capture_step_artifacts(tmp.path().to_str().unwrap(), "my-skill", 2).unwrap().is_none());
+        assert!(capture_step_artifacts(tmp.path().to_str().unwrap(), "my-skill", 3).unwrap().is_none());
+        assert!(!tmp.path().join("my-skill").join(ARTIFACTS_DIR).exists());
+    }
+}
@@ -0,0 +1,336 @@
+//! Reuse of earlier step outputs when a step's inputs are unchanged.
+//!
+//! Every agent run of a workflow step records a fingerprint of its inputs:
+//! the step's prompt template, `user-context.md`, the model and the upstream
+//! context files the step reads. When the step completes, its outputs are
+//! copied to `{workspace}/{skill}/.step-cache/step{N}/{fingerprint}/`. Before
+//! re-running a step the frontend calls `find_cached_step_run`; on a match
+//! the user can choose `reuse_cached_step_run`, which restores the copied
+//! outputs and records a zero-cost `cached` agent run instead of spawning an
+//! agent. The newest `MAX_CACHED_RUNS_PER_STEP` outputs are kept per step.
+
+use std::path::{Path, PathBuf};
+
+use super::imported_skills::{copy_dir_recursive, validate_skill_name};
+use crate::db::Db;
+use crate::types::CachedStepRun;
+
+pub const STEP_CACHE_DIR: &str = ".step-cache";
+const MAX_CACHED_RUNS_PER_STEP: usize = 3;
+
+/// What a step run would see right now, from `workflow::step_run_inputs`.
+pub(crate) struct StepRunInputs {
+    pub fingerprint: String,
+    pub model: String,
+    pub skills_path: String,
+}
+
+/// Context files each step reads, relative to the skill's workspace directory.
+fn step_input_files(step_id: u32) -> &'static [&'static str] {
+    match step_id {
+        1 | 2 => &["context/clarifications.json"],
+        3 => &["context/clarifications.json", "context/decisions.json"],
+        _ => &[],
+    }
+}
+
+/// Files and directories a step writes, by name within the cache entry.
+fn step_outputs(workspace_path: &str, skills_path: &str, skill_name: &str, step_id: u32) -> Vec<(&'static str, PathBuf)> {
+    let context = Path::new(workspace_path).join(skill_name).join("context");
+    let skill_dir = Path::new(skills_path).join(skill_name);
+    match step_id {
+        0 | 1 => vec![("clarifications.json", context.join("clarifications.json"))],
+        2 => vec![("decisions.json", context.join("decisions.json"))],
+        3 => vec![("SKILL.md", skill_dir.join("SKILL.md")), ("references", skill_dir.join("references"))],
+        _ => Vec::new(),
+    }
+}
+
+fn cache_dir(workspace_path: &str, skill_name: &str, step_id: u32, fingerprint: &str) -> PathBuf {
+    Path::new(workspace_path)
+        .join(skill_name)
+        .join(STEP_CACHE_DIR)
+        .join(format!("step{}", step_id))
+        .join(fingerprint)
+}
+
+/// SHA-256 over everything that determines a step's output. The `Version`
+/// line of `user-context.md` is left out: regenerating a skill bumps its
+/// version, which would otherwise make step 3 never match.
+pub(crate) fn input_fingerprint(
+    workspace_path: &str,
+    skill_name: &str,
+    step_id: u32,
+    model: &str,
+    prompt_path: Option<&Path>,
+) -> String {
+    use sha2::{Digest, Sha256};
+    let mut hasher = Sha256::new();
+    let mut add = |label: &str, bytes: Option<Vec<u8>>| {
+        hasher.update(label.as_bytes());
+        match bytes {
+            Some(bytes) => {
+                hasher.update((bytes.len() as u64).to_le_bytes());
+                hasher.update(&bytes);
+            }
+            None => hasher.update(b"<missing>"),
+        }
+    };
+    add("step", Some(step_id.to_string().into_bytes()));
+    add("model", Some(model.as_bytes().to_vec()));
+    add("prompt", prompt_path.and_then(|p| std::fs::read(p).ok()));
+
+    let skill_dir = Path::new(workspace_path).join(skill_name);
+    let user_context = std::fs::read_to_string(skill_dir.join("user-context.md")).ok().map(|c| {
+        c.lines()
+            .filter(|l| !l.starts_with("**Version**:"))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .into_bytes()
+    });
+    add("user-context", user_context);
+    for file in step_input_files(step_id) {
+        add(file, std::fs::read(skill_dir.join(file)).ok());
+    }
+    hex::encode(&hasher.finalize()[..16])
+}
+
+fn remove_path(path: &Path) -> Result<(), String> {
+    let result = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else if path.exists() {
+        std::fs::remove_file(path)
+    } else {
+        return Ok(());
+    };
+    result.map_err(|e| format!("Failed to remove {}: {}", path.display(), e))
+}
+
+fn copy_path(src: &Path, dst: &Path) -> Result<(), String> {
+    if let Some(parent) = dst.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    if src.is_dir() {
+        std::fs::create_dir_all(dst).map_err(|e| format!("Failed to create {}: {}", dst.display(), e))?;
+        copy_dir_recursive(src, dst)
+    } else {
+        std::fs::copy(src, dst)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to copy {}: {}", src.display(), e))
+    }
+}
+
+/// Cache the outputs of a step that just completed under the fingerprint
+/// recorded when its agent started. Does nothing when no run is pending,
+/// e.g. when the step was completed by reusing a cached run.
+pub(crate) fn snapshot_completed_step(
+    conn: &rusqlite::Connection,
+    workspace_path: &str,
+    skills_path: &str,
+    skill_name: &str,
+    step_id: u32,
+) -> Result<(), String> {
+    let Some((pending_id, fingerprint)) = crate::db::pending_step_run(conn, skill_name, step_id)? else {
+        return Ok(());
+    };
+    let dir = cache_dir(workspace_path, skill_name, step_id, &fingerprint);
+    remove_path(&dir)?;
+    let mut copied = 0;
+    for (name, path) in step_outputs(workspace_path, skills_path, skill_name, step_id) {
+        if path.exists() {
+            copy_path(&path, &dir.join(name))?;
+            copied += 1;
+        }
+    }
+    if copied == 0 {
+        log::debug!("[snapshot_completed_step] '{}' step {} has no outputs to cache", skill_name, step_id);
+        return Ok(());
+    }
+    crate::db::mark_step_run_cached(conn, pending_id)?;
+    for stale in crate::db::prune_cached_step_runs(conn, skill_name, step_id, &fingerprint, MAX_CACHED_RUNS_PER_STEP)? {
+        let _ = remove_path(&cache_dir(workspace_path, skill_name, step_id, &stale));
+    }
+    log::info!(
+        "[snapshot_completed_step] cached '{}' step {} outputs as {}",
+        skill_name,
+        step_id,
+        fingerprint
+    );
+    Ok(())
+}
+
+/// The cached run matching `fingerprint`, if its outputs are still on disk.
+fn find_inner(
+    conn: &rusqlite::Connection,
+    workspace_path: &str,
+    skill_name: &str,
+    step_id: u32,
+    fingerprint: &str,
+) -> Result<Option<CachedStepRun>, String> {
+    let Some(run) = crate::db::find_cached_step_run(conn, skill_name, step_id, fingerprint)? else {
+        return Ok(None);
+    };
+    if !cache_dir(workspace_path, skill_name, step_id, fingerprint).is_dir() {
+        log::warn!("[find_cached_step_run] cache for '{}' step {} is missing on disk", skill_name, step_id);
+        return Ok(None);
+    }
+    Ok(Some(run))
+}
+
+fn reuse_inner(
+    conn: &rusqlite::Connection,
+    workspace_path: &str,
+    skill_name: &str,
+    step_id: u32,
+    inputs: &StepRunInputs,
+) -> Result<CachedStepRun, String> {
+    let run = find_inner(conn, workspace_path, skill_name, step_id, &inputs.fingerprint)?
+        .ok_or_else(|| format!("No cached output of step {} matches the current inputs", step_id))?;
+    let dir = cache_dir(workspace_path, skill_name, step_id, &inputs.fingerprint);
+    for (name, path) in step_outputs(workspace_path, &inputs.skills_path, skill_name, step_id) {
+        remove_path(&path)?;
+        let cached = dir.join(name);
+        if cached.exists() {
+            copy_path(&cached, &path)?;
+        }
+    }
+    // Completing the step must not cache the restored files a second time.
+    crate::db::clear_pending_step_runs(conn, skill_name, step_id)?;
+
+    let agent_id = format!(
+        "{}-step{}-cached-{}",
+        skill_name,
+        step_id,
+        chrono::Utc::now().timestamp_millis()
+    );
+    crate::db::persist_agent_run(
+        conn, &agent_id, skill_name, step_id as i32, &inputs.model, "cached",
+        0, 0, 0, 0, 0.0, 0, 0, None, None, 0, 0, None, None,
+    )?;
+    log::info!(
+        "[reuse_cached_step_run] restored '{}' step {} from {} (saved ${:.4})",
+        skill_name,
+        step_id,
+        run.agent_id,
+        run.cost_usd
+    );
+    Ok(run)
+}
+
+/// A completed earlier run of the step whose inputs match the current ones.
+#[tauri::command]
+pub fn find_cached_step_run(
+    skill_name: String,
+    step_id: u32,
+    workspace_path: String,
+    db: tauri::State<'_, Db>,
+) -> Result<Option<CachedStepRun>, String> {
+    log::info!("[find_cached_step_run] skill={} step={}", skill_name, step_id);
+    validate_skill_name(&skill_name)?;
+    let inputs = super::workflow::step_run_inputs(&db, &skill_name, step_id, &workspace_path)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[find_cached_step_run] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    find_inner(&conn, &workspace_path, &skill_name, step_id, &inputs.fingerprint)
+}
+
+/// Restore the outputs of the matching cached run instead of running the
+/// step's agent. The caller marks the step completed.
+#[tauri::command]
+pub fn reuse_cached_step_run(
+    skill_name: String,
+    step_id: u32,
+    workspace_path: String,
+    db: tauri::State<'_, Db>,
+) -> Result<CachedStepRun, String> {
+    log::info!("[reuse_cached_step_run] skill={} step={}", skill_name, step_id);
+    validate_skill_name(&skill_name)?;
+    super::observer::ensure_writable(&skill_name)?;
+    let inputs = super::workflow::step_run_inputs(&db, &skill_name, step_id, &workspace_path)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[reuse_cached_step_run] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    reuse_inner(&conn, &workspace_path, &skill_name, step_id, &inputs).map_err(|e| {
+        log::error!("[reuse_cached_step_run] {}", e);
+        e
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn setup(workspace: &Path) -> PathBuf {
+        let skill_dir = workspace.join("sales");
+        std::fs::create_dir_all(skill_dir.join("context")).unwrap();
+        std::fs::write(skill_dir.join("user-context.md"), "# User Context\n\n**Version**: 1.0.0\n**Tags**: a\n").unwrap();
+        std::fs::write(skill_dir.join("context/clarifications.json"), r#"{"questions":[]}"#).unwrap();
+        skill_dir
+    }
+
+    #[test]
+    fn test_fingerprint_tracks_inputs_but_not_version() {
+        let workspace = tempdir().unwrap();
+        let ws = workspace.path().to_str().unwrap();
+        let skill_dir = setup(workspace.path());
+        let base = input_fingerprint(ws, "sales", 2, "sonnet", None);
+        assert_eq!(base, input_fingerprint(ws, "sales", 2, "sonnet", None));
+
+        std::fs::write(skill_dir.join("user-context.md"), "# User Context\n\n**Version**: 1.1.0\n**Tags**: a\n").unwrap();
+        assert_eq!(base, input_fingerprint(ws, "sales", 2, "sonnet", None));
+
+        assert_ne!(base, input_fingerprint(ws, "sales", 2, "opus", None));
+        std::fs::write(skill_dir.join("context/clarifications.json"), r#"{"questions":[1]}"#).unwrap();
+        assert_ne!(base, input_fingerprint(ws, "sales", 2, "sonnet", None));
+    }
+
+    #[test]
+    fn test_completed_step_is_cached_and_reused() {
+        // agent_runs needs the full migrated schema (cache/token columns).
+        let data = tempdir().unwrap();
+        let db = crate::db::init_db(data.path()).unwrap();
+        let conn = db.0.lock().unwrap();
+        let workspace = tempdir().unwrap();
+        let ws = workspace.path().to_str().unwrap();
+        let skills = tempdir().unwrap();
+        let skills_path = skills.path().to_str().unwrap();
+        let skill_dir = setup(workspace.path());
+        let decisions = skill_dir.join("context/decisions.json");
+
+        let fingerprint = input_fingerprint(ws, "sales", 2, "sonnet", None);
+        crate::db::record_pending_step_run(&conn, "sales", 2, &fingerprint, "sales-step2-1", "sonnet").unwrap();
+        crate::db::persist_agent_run(
+            &conn, "sales-step2-1", "sales", 2, "sonnet", "completed",
+            10, 20, 0, 0, 0.42, 1000, 3, None, None, 0, 0, None, None,
+        )
+        .unwrap();
+        std::fs::write(&decisions, r#"{"decisions":["net revenue"]}"#).unwrap();
+        snapshot_completed_step(&conn, ws, skills_path, "sales", 2).unwrap();
+
+        // Step reset deletes the output; the inputs are unchanged.
+        std::fs::remove_file(&decisions).unwrap();
+        let inputs = StepRunInputs { fingerprint, model: "sonnet".into(), skills_path: skills_path.into() };
+        let found = find_inner(&conn, ws, "sales", 2, &inputs.fingerprint).unwrap().unwrap();
+        assert_eq!(found.agent_id, "sales-step2-1");
+        assert!((found.cost_usd - 0.42).abs() < 1e-9);
+
+        reuse_inner(&conn, ws, "sales", 2, &inputs).unwrap();
+        assert_eq!(std::fs::read_to_string(&decisions).unwrap(), r#"{"decisions":["net revenue"]}"#);
+        let cached_cost: f64 = conn
+            .query_row(
+                "SELECT total_cost FROM agent_runs WHERE skill_name = 'sales' AND step_id = 2 AND status = 'cached'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(cached_cost, 0.0);
+
+        // Different inputs: no match.
+        std::fs::write(skill_dir.join("context/clarifications.json"), "{}").unwrap();
+        let changed = input_fingerprint(ws, "sales", 2, "sonnet", None);
+        assert!(find_inner(&conn, ws, "sales", 2, &changed).unwrap().is_none());
+    }
+}
@@ -0,0 +1,320 @@
+//! "Explain this step" context for the workflow page.
+//!
+//! `explain_step` describes what the upcoming step will do for this skill:
+//! the inputs it reads and whether they are ready, the files it writes, the
+//! expected cost and time from past runs of the step, and what the user
+//! should do first. Everything is computed from the workflow state on disk
+//! and in the DB, so the text tracks the skill rather than a static tooltip.
+
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::db::Db;
+use crate::types::{StepExplanation, StepInput};
+
+const CLARIFICATIONS: &str = "context/clarifications.json";
+const DECISIONS: &str = "context/decisions.json";
+
+/// Answer progress in clarifications.json.
+#[derive(Debug, Default, PartialEq)]
+struct AnswerCounts {
+    questions: u32,
+    answered: u32,
+    required_unanswered: u32,
+    refinements: u32,
+    refinements_answered: u32,
+}
+
+fn is_answered(item: &Value) -> bool {
+    let choice = item.get("answer_choice").is_some_and(|v| !v.is_null());
+    let text = item
+        .get("answer_text")
+        .and_then(|v| v.as_str())
+        .is_some_and(|t| !t.trim().is_empty());
+    choice || text
+}
+
+fn count_answers(clarifications: &Value) -> AnswerCounts {
+    let mut counts = AnswerCounts::default();
+    let sections = clarifications["sections"].as_array().map(Vec::as_slice).unwrap_or(&[]);
+    for question in sections.iter().filter_map(|s| s["questions"].as_array()).flatten() {
+        counts.questions += 1;
+        if is_answered(question) {
+            counts.answered += 1;
+        } else if question["must_answer"].as_bool() == Some(true) {
+            counts.required_unanswered += 1;
+        }
+        for refinement in question["refinements"].as_array().map(Vec::as_slice).unwrap_or(&[]) {
+            counts.refinements += 1;
+            if is_answered(refinement) {
+                counts.refinements_answered += 1;
+            }
+        }
+    }
+    counts
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+fn file_input(skill_dir: &Path, label: &str, path: &str) -> StepInput {
+    StepInput {
+        label: label.to_string(),
+        path: Some(path.to_string()),
+        available: skill_dir.join(path).exists(),
+    }
+}
+
+fn answers_prepare(counts: &AnswerCounts, prepare: &mut Vec<String>) {
+    if counts.required_unanswered > 0 {
+        prepare.push(format!(
+            "Answer {} required question{} ({} of {} answered).",
+            counts.required_unanswered,
+            if counts.required_unanswered == 1 { "" } else { "s" },
+            counts.answered,
+            counts.questions
+        ));
+    } else if counts.answered < counts.questions {
+        prepare.push(format!(
+            "{} of {} questions answered; unanswered optional questions are left to the agent's judgement.",
+            counts.answered, counts.questions
+        ));
+    }
+}
+
+fn format_duration(ms: i64) -> String {
+    let secs = (ms / 1000).max(1);
+    if secs < 60 {
+        format!("{}s", secs)
+    } else {
+        format!("{} min", (secs + 30) / 60)
+    }
+}
+
+pub(crate) fn explain_step_inner(
+    conn: &rusqlite::Connection,
+    workspace_path: &str,
+    skill_name: &str,
+    step_id: u32,
+) -> Result<StepExplanation, String> {
+    let run = crate::db::get_workflow_run(conn, skill_name)?
+        .ok_or_else(|| format!("Workflow run not found for skill '{}'", skill_name))?;
+    let skill_dir = Path::new(workspace_path).join(skill_name);
+    let clarifications = read_json(&skill_dir.join(CLARIFICATIONS));
+    let counts = clarifications.as_ref().map(count_answers).unwrap_or_default();
+    let intake_ready = run.intake_json.as_deref().is_some_and(|j| !j.trim().is_empty());
+    let mut prepare = Vec::new();
+
+    let (step_name, summary, reads, writes) = match step_id {
+        0 => {
+            if !intake_ready {
+                prepare.push(
+                    "Add intake details (audience, challenges, scope) when editing the skill; research relies on them."
+                        .to_string(),
+                );
+            }
+            (
+                "Research",
+                format!(
+                    "Researches the key concepts, terminology and frameworks for a {} skill named '{}', then drafts clarification questions for you to answer.",
+                    run.purpose, skill_name
+                ),
+                vec![StepInput {
+                    label: "Skill intake and description".to_string(),
+                    path: None,
+                    available: intake_ready,
+                }],
+                vec![CLARIFICATIONS.to_string()],
+            )
+        }
+        1 => {
+            answers_prepare(&counts, &mut prepare);
+            (
+                "Detailed Research",
+                format!(
+                    "Reads your {} answers and adds follow-up refinement questions where an answer needs more detail.",
+                    counts.answered
+                ),
+                vec![file_input(&skill_dir, "Your answers to the research questions", CLARIFICATIONS)],
+                vec![format!("{} (refinements added in place)", CLARIFICATIONS)],
+            )
+        }
+        2 => {
+            answers_prepare(&counts, &mut prepare);
+            if counts.refinements_answered < counts.refinements {
+                prepare.push(format!(
+                    "Answer the remaining refinement questions ({} of {} answered).",
+                    counts.refinements_answered, counts.refinements
+                ));
+            }
+            (
+                "Confirm Decisions",
+                format!(
+                    "Turns {} answers and {} refinements into decisions, flagging gaps and contradictions for your review.",
+                    counts.answered, counts.refinements_answered
+                ),
+                vec![file_input(&skill_dir, "Answers and refinements", CLARIFICATIONS)],
+                vec![DECISIONS.to_string()],
+            )
+        }
+        3 => {
+            let decisions = read_json(&skill_dir.join(DECISIONS));
+            let metadata = decisions.as_ref().map(|d| &d["metadata"]);
+            let decision_count = decisions
+                .as_ref()
+                .and_then(|d| d["decisions"].as_array())
+                .map_or(0, Vec::len);
+            if metadata.is_some_and(|m| m["contradictory_inputs"].as_bool() == Some(true)) {
+                prepare.push("Resolve the contradictions flagged in the decisions before generating.".to_string());
+            } else if decisions.is_some() && decision_count == 0 {
+                prepare.push("No decisions were derived; revisit your answers before generating.".to_string());
+            } else {
+                prepare.push("Review the decisions; the skill is written from them as they stand.".to_string());
+            }
+            (
+                "Generate Skill",
+                format!(
+                    "Writes SKILL.md and reference files for '{}' from {} confirmed decision{}.",
+                    skill_name,
+                    decision_count,
+                    if decision_count == 1 { "" } else { "s" }
+                ),
+                vec![file_input(&skill_dir, "Confirmed decisions", DECISIONS)],
+                vec!["SKILL.md".to_string(), "references/".to_string()],
+            )
+        }
+        _ => return Err(format!("Unknown step_id {}. Valid steps are 0-3.", step_id)),
+    };
+
+    for input in reads.iter().filter(|i| !i.available && i.path.is_some()) {
+        prepare.push(format!("{} is missing; complete the earlier steps first.", input.label));
+    }
+
+    // Prefer this skill's own history (re-runs); otherwise use all skills.
+    let (mut history_runs, mut expected_cost_usd, mut expected_duration_ms) =
+        crate::db::get_step_history_stats(conn, step_id as i32, Some(skill_name))?;
+    let mut history_scope = "skill";
+    if history_runs == 0 {
+        (history_runs, expected_cost_usd, expected_duration_ms) =
+            crate::db::get_step_history_stats(conn, step_id as i32, None)?;
+        history_scope = "all";
+    }
+    let mut summary = summary;
+    if let (Some(cost), Some(ms)) = (expected_cost_usd, expected_duration_ms) {
+        if history_runs > 0 {
+            summary.push_str(&format!(
+                " Typically about ${:.2} and {} (from {} past run{}).",
+                cost,
+                format_duration(ms),
+                history_runs,
+                if history_runs == 1 { "" } else { "s" }
+            ));
+        }
+    }
+
+    Ok(StepExplanation {
+        skill_name: skill_name.to_string(),
+        step_id,
+        step_name: step_name.to_string(),
+        summary,
+        reads,
+        writes,
+        history_runs,
+        history_scope: history_scope.to_string(),
+        expected_cost_usd,
+        expected_duration_ms,
+        prepare,
+    })
+}
+
+#[tauri::command]
+pub fn explain_step(skill_name: String, step_id: u32, db: tauri::State<'_, Db>) -> Result<StepExplanation, String> {
+    log::info!("[explain_step] skill={} step={}", skill_name, step_id);
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[explain_step] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let workspace_path = crate::db::read_settings(&conn)?
+        .workspace_path
+        .ok_or_else(|| "Workspace path not configured".to_string())?;
+    explain_step_inner(&conn, &workspace_path, &skill_name, step_id).map_err(|e| {
+        log::error!("[explain_step] {}", e);
+        e
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn clarifications() -> Value {
+        json!({
+            "sections": [{
+                "id": "s1",
+                "title": "Scope",
+                "questions": [
+                    {"id": "q1", "must_answer": true, "answer_choice": "a", "answer_text": null,
+                     "refinements": [{"id": "r1", "answer_choice": null, "answer_text": ""}]},
+                    {"id": "q2", "must_answer": true, "answer_choice": null, "answer_text": "  "},
+                    {"id": "q3", "must_answer": false, "answer_choice": null, "answer_text": "Quarterly"}
+                ]
+            }]
+        })
+    }
+
+    #[test]
+    fn test_count_answers() {
+        assert_eq!(
+            count_answers(&clarifications()),
+            AnswerCounts {
+                questions: 3,
+                answered: 2,
+                required_unanswered: 1,
+                refinements: 1,
+                refinements_answered: 0,
+            }
+        );
+        assert_eq!(count_answers(&json!({})), AnswerCounts::default());
+    }
+
+    #[test]
+    fn test_explain_step_uses_state_and_history() {
+        let conn = crate::commands::test_utils::create_test_db();
+        let tmp = tempfile::tempdir().unwrap();
+        let workspace = tmp.path().to_str().unwrap();
+        crate::db::save_workflow_run(&conn, "sales", 2, "pending", "domain").unwrap();
+        let context = tmp.path().join("sales/context");
+        std::fs::create_dir_all(&context).unwrap();
+        std::fs::write(context.join("clarifications.json"), clarifications().to_string()).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO agent_runs (agent_id, skill_name, step_id, model, status, total_cost, duration_ms) VALUES
+                ('a1', 'other', 2, 'sonnet', 'completed', 0.40, 90000),
+                ('a2', 'other', 2, 'sonnet', 'completed', 0.50, 90000),
+                ('a2', 'other', 2, 'haiku', 'completed', 0.10, 30000),
+                ('a3', 'other', 2, 'sonnet', 'error', 5.00, 1000);",
+        )
+        .unwrap();
+
+        let explanation = explain_step_inner(&conn, workspace, "sales", 2).unwrap();
+        assert_eq!(explanation.step_name, "Confirm Decisions");
+        assert!(explanation.reads[0].available);
+        assert_eq!(explanation.writes, vec![DECISIONS.to_string()]);
+        assert_eq!(explanation.history_runs, 2);
+        assert_eq!(explanation.history_scope, "all");
+        assert!((explanation.expected_cost_usd.unwrap() - 0.50).abs() < 1e-9);
+        assert!(explanation.summary.contains("$0.50 and 2 min"));
+        assert!(explanation.prepare[0].starts_with("Answer 1 required question (2 of 3 answered)"));
+        assert!(explanation.prepare[1].contains("refinement questions (0 of 1"));
+
+        let generate = explain_step_inner(&conn, workspace, "sales", 3).unwrap();
+        assert!(!generate.reads[0].available);
+        assert_eq!(generate.history_runs, 0);
+        assert!(generate.prepare.iter().any(|p| p.starts_with("Confirmed decisions is missing")));
+
+        assert!(explain_step_inner(&conn, workspace, "sales", 7).is_err());
+    }
+}
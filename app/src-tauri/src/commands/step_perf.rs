@@ -0,0 +1,161 @@
+//! Step-level prompt size and latency history.
+//!
+//! Every completed workflow step agent run is recorded in `step_perf_history`
+//! with the app version and a hash of the step's deployed agent prompt.
+//! `get_step_perf_trends` averages them per (step, app version, prompt version)
+//! and reports how each combination moved against the previous one, so a
+//! prompt update that doubled a step's latency or cost stands out.
+
+use crate::db::Db;
+use crate::types::{StepPerfSample, StepPerfTrend};
+
+/// Record a completed workflow step run. Non-workflow runs (negative step ids)
+/// and unfinished runs are ignored.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn record_completed_step(
+    conn: &rusqlite::Connection,
+    agent_id: &str,
+    skill_name: &str,
+    step_id: i32,
+    model: &str,
+    status: &str,
+    prompt_tokens: i64,
+    output_tokens: i64,
+    total_cost: f64,
+    time_to_first_token_ms: Option<i64>,
+    duration_ms: i64,
+) -> Result<(), String> {
+    if status != "completed" || step_id < 0 {
+        return Ok(());
+    }
+    let workspace_path = crate::db::read_settings(conn).ok().and_then(|s| s.workspace_path);
+    let prompt_version = workspace_path
+        .as_deref()
+        .and_then(|wp| super::workflow::step_prompt_version(wp, step_id as u32));
+    crate::db::record_step_perf(
+        conn,
+        &StepPerfSample {
+            agent_id: agent_id.to_string(),
+            model: crate::db::normalize_model_name(model),
+            skill_name: skill_name.to_string(),
+            step_id,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            prompt_version,
+            prompt_tokens,
+            output_tokens,
+            total_cost,
+            time_to_first_token_ms,
+            duration_ms,
+        },
+    )
+}
+
+fn change_pct(previous: f64, current: f64) -> Option<f64> {
+    (previous > 0.0).then(|| (current - previous) / previous * 100.0)
+}
+
+/// Fill in each trend's change against the previous combination for its step.
+/// Expects trends grouped by step, oldest first.
+fn with_changes(mut trends: Vec<StepPerfTrend>) -> Vec<StepPerfTrend> {
+    for i in 1..trends.len() {
+        let (before, after) = trends.split_at_mut(i);
+        let (previous, current) = (&before[i - 1], &mut after[0]);
+        if previous.step_id != current.step_id {
+            continue;
+        }
+        current.prompt_tokens_change_pct = change_pct(previous.avg_prompt_tokens, current.avg_prompt_tokens);
+        current.cost_change_pct = change_pct(previous.avg_cost, current.avg_cost);
+        current.duration_change_pct = change_pct(previous.avg_duration_ms, current.avg_duration_ms);
+        current.time_to_first_token_change_pct = match (
+            previous.avg_time_to_first_token_ms,
+            current.avg_time_to_first_token_ms,
+        ) {
+            (Some(p), Some(c)) => change_pct(p, c),
+            _ => None,
+        };
+    }
+    trends
+}
+
+#[tauri::command]
+pub fn get_step_perf_trends(
+    step_id: Option<i32>,
+    start_date: Option<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<StepPerfTrend>, String> {
+    log::info!("[get_step_perf_trends] step_id={:?} start_date={:?}", step_id, start_date);
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[get_step_perf_trends] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let trends = crate::db::get_step_perf_trends(&conn, step_id, start_date.as_deref())?;
+    Ok(with_changes(trends))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::create_test_db;
+
+    fn sample(agent_id: &str, model: &str, step_id: i32, prompt_version: &str, tokens: i64, duration_ms: i64) -> StepPerfSample {
+        StepPerfSample {
+            agent_id: agent_id.to_string(),
+            model: model.to_string(),
+            skill_name: "my-skill".to_string(),
+            step_id,
+            app_version: "1.0.0".to_string(),
+            prompt_version: Some(prompt_version.to_string()),
+            prompt_tokens: tokens,
+            output_tokens: 100,
+            total_cost: tokens as f64 / 1000.0,
+            time_to_first_token_ms: Some(duration_ms / 10),
+            duration_ms,
+        }
+    }
+
+    #[test]
+    fn test_trends_group_by_prompt_version_and_sum_sub_agents() {
+        let conn = create_test_db();
+        crate::db::record_step_perf(&conn, &sample("a1", "sonnet", 0, "v1", 1000, 10_000)).unwrap();
+        crate::db::record_step_perf(&conn, &sample("a1", "haiku", 0, "v1", 1000, 4_000)).unwrap();
+        crate::db::record_step_perf(&conn, &sample("a2", "sonnet", 0, "v1", 2000, 10_000)).unwrap();
+        conn.execute("UPDATE step_perf_history SET recorded_at = '2026-01-01 00:00:00Z'", []).unwrap();
+        crate::db::record_step_perf(&conn, &sample("a3", "sonnet", 0, "v2", 4000, 20_000)).unwrap();
+        crate::db::record_step_perf(&conn, &sample("a4", "sonnet", 3, "v9", 500, 1_000)).unwrap();
+
+        let trends = with_changes(crate::db::get_step_perf_trends(&conn, None, None).unwrap());
+        assert_eq!(trends.len(), 3);
+
+        let v1 = &trends[0];
+        assert_eq!((v1.step_id, v1.prompt_version.as_deref(), v1.runs), (0, Some("v1"), 2));
+        assert_eq!(v1.avg_prompt_tokens, 2000.0);
+        assert_eq!(v1.avg_duration_ms, 10_000.0);
+        assert!(v1.duration_change_pct.is_none());
+
+        let v2 = &trends[1];
+        assert_eq!(v2.prompt_version.as_deref(), Some("v2"));
+        assert_eq!(v2.duration_change_pct, Some(100.0));
+        assert_eq!(v2.prompt_tokens_change_pct, Some(100.0));
+
+        // A different step never compares against step 0.
+        assert_eq!(trends[2].step_id, 3);
+        assert!(trends[2].cost_change_pct.is_none());
+
+        let step_3 = crate::db::get_step_perf_trends(&conn, Some(3), None).unwrap();
+        assert_eq!(step_3.len(), 1);
+    }
+
+    #[test]
+    fn test_record_completed_step_skips_unfinished_and_non_workflow_runs() {
+        let conn = create_test_db();
+        record_completed_step(&conn, "a1", "my-skill", 0, "sonnet", "error", 10, 10, 0.1, None, 100).unwrap();
+        record_completed_step(&conn, "a2", "my-skill", -10, "sonnet", "completed", 10, 10, 0.1, None, 100).unwrap();
+        assert!(crate::db::get_step_perf_trends(&conn, None, None).unwrap().is_empty());
+
+        record_completed_step(&conn, "a3", "my-skill", 1, "sonnet", "completed", 10, 10, 0.1, Some(40), 100).unwrap();
+        let trends = crate::db::get_step_perf_trends(&conn, None, None).unwrap();
+        assert_eq!(trends.len(), 1);
+        assert_eq!(trends[0].app_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(trends[0].avg_time_to_first_token_ms, Some(40.0));
+    }
+}
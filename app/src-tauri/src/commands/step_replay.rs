@@ -0,0 +1,392 @@
+//! Re-running a workflow step exactly as it ran before.
+//!
+//! Every step launch captures its environment under
+//! `{workspace}/{skill}/.replay/captures/{agent_id}/`: the sidecar config
+//! (without the API key), the app and prompt versions, the deployed agent
+//! prompts, the workspace `CLAUDE.md`, and the skill's inputs at launch time
+//! (`user-context.md`, `context/` and the skill output directory). Replaying
+//! a run rebuilds that environment in an isolated sandbox under
+//! `.replay/runs/{replay_id}/` and launches the captured config against it,
+//! so the output can be compared with the original without touching the
+//! real workspace or skills path.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::imported_skills::{copy_dir_recursive, validate_skill_name};
+use crate::agents::sidecar::{self, SidecarConfig};
+use crate::agents::sidecar_pool::SidecarPool;
+use crate::db::Db;
+use crate::types::StepReplay;
+
+pub const REPLAY_DIR: &str = ".replay";
+const CAPTURES_DIR: &str = "captures";
+const RUNS_DIR: &str = "runs";
+const CAPTURE_FILE: &str = "capture.json";
+const MAX_CAPTURES_PER_SKILL: usize = 20;
+const MAX_REPLAYS_PER_SKILL: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StepCapture {
+    agent_id: String,
+    skill_name: String,
+    step_id: u32,
+    captured_at: String,
+    app_version: String,
+    prompt_version: Option<String>,
+    model: String,
+    /// Paths the config and prompt were built against, rewritten on replay.
+    workspace_path: String,
+    skills_path: String,
+    config: SidecarConfig,
+}
+
+fn replay_dir(workspace_path: &str, skill_name: &str) -> PathBuf {
+    Path::new(workspace_path).join(skill_name).join(REPLAY_DIR)
+}
+
+fn copy_file_if_exists(src: &Path, dst: &Path) -> Result<(), String> {
+    if !src.is_file() {
+        return Ok(());
+    }
+    if let Some(parent) = dst.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    std::fs::copy(src, dst).map_err(|e| format!("Failed to copy {}: {}", src.display(), e))?;
+    Ok(())
+}
+
+fn copy_dir_if_exists(src: &Path, dst: &Path) -> Result<(), String> {
+    if !src.is_dir() {
+        return Ok(());
+    }
+    std::fs::create_dir_all(dst).map_err(|e| format!("Failed to create {}: {}", dst.display(), e))?;
+    copy_dir_recursive(src, dst)
+}
+
+fn read_capture(dir: &Path) -> Option<StepCapture> {
+    let text = std::fs::read_to_string(dir.join(CAPTURE_FILE)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+/// Remove all but the `keep` subdirectories of `dir` with the highest `key`.
+fn prune_dir<K: Ord>(dir: &Path, keep: usize, key: impl Fn(&Path) -> K) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect();
+    paths.sort_by_key(|p| std::cmp::Reverse(key(p)));
+    for path in paths.into_iter().skip(keep) {
+        if let Err(e) = std::fs::remove_dir_all(&path) {
+            log::warn!("[step_replay] Failed to prune {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Record everything needed to replay the step about to be launched with
+/// `config`. Called just before the sidecar is spawned.
+pub(crate) fn capture_step_environment(
+    workspace_path: &str,
+    skills_path: &str,
+    skill_name: &str,
+    step_id: u32,
+    agent_id: &str,
+    model: &str,
+    config: &SidecarConfig,
+) -> Result<(), String> {
+    let captures = replay_dir(workspace_path, skill_name).join(CAPTURES_DIR);
+    let dir = captures.join(agent_id);
+    let ws = Path::new(workspace_path);
+    let sandbox_ws = dir.join("workspace");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    copy_file_if_exists(&ws.join("CLAUDE.md"), &sandbox_ws.join("CLAUDE.md"))?;
+    copy_dir_if_exists(&ws.join(".claude").join("agents"), &sandbox_ws.join(".claude").join("agents"))?;
+    copy_file_if_exists(
+        &ws.join(skill_name).join("user-context.md"),
+        &sandbox_ws.join(skill_name).join("user-context.md"),
+    )?;
+    copy_dir_if_exists(&ws.join(skill_name).join("context"), &sandbox_ws.join(skill_name).join("context"))?;
+    // When the skills path falls back to the workspace, the output
+    // directory is the workspace directory already captured above.
+    if skills_path != workspace_path {
+        copy_dir_if_exists(&Path::new(skills_path).join(skill_name), &dir.join("skills").join(skill_name))?;
+    }
+
+    let mut config = config.clone();
+    config.api_key = String::new();
+    let capture = StepCapture {
+        agent_id: agent_id.to_string(),
+        skill_name: skill_name.to_string(),
+        step_id,
+        captured_at: chrono::Utc::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        prompt_version: super::workflow::step_prompt_version(workspace_path, step_id),
+        model: model.to_string(),
+        workspace_path: workspace_path.to_string(),
+        skills_path: skills_path.to_string(),
+        config,
+    };
+    let json = serde_json::to_string_pretty(&capture).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(CAPTURE_FILE), json).map_err(|e| format!("Failed to write capture: {}", e))?;
+
+    prune_dir(&captures, MAX_CAPTURES_PER_SKILL, |p| {
+        read_capture(p).map(|c| c.captured_at).unwrap_or_default()
+    });
+    Ok(())
+}
+
+/// Replace every occurrence of a `from` path with its `to` path in one pass,
+/// longest `from` first, so a replacement is never rewritten again.
+fn retarget(text: &str, pairs: &[(&str, &str)]) -> String {
+    let mut pairs: Vec<&(&str, &str)> = pairs.iter().filter(|(from, _)| !from.is_empty()).collect();
+    pairs.sort_by_key(|(from, _)| std::cmp::Reverse(from.len()));
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    'outer: while let Some(ch) = rest.chars().next() {
+        for (from, to) in &pairs {
+            if rest.starts_with(from) {
+                out.push_str(to);
+                rest = &rest[from.len()..];
+                continue 'outer;
+            }
+        }
+        out.push(ch);
+        rest = &rest[ch.len_utf8()..];
+    }
+    out
+}
+
+/// Build the sandbox for a replay of the capture in `capture_dir` and return
+/// the captured config pointed at it. The current workspace's `.claude`
+/// directory supplies plugins and bundled skills; the captured agent prompts
+/// are laid over it.
+fn prepare_sandbox(
+    workspace_path: &str,
+    capture_dir: &Path,
+    capture: &StepCapture,
+    sandbox: &Path,
+) -> Result<SidecarConfig, String> {
+    let sandbox_ws = sandbox.join("workspace");
+    let sandbox_skills = sandbox.join("skills");
+    std::fs::create_dir_all(sandbox_ws.join(&capture.skill_name))
+        .map_err(|e| format!("Failed to create replay sandbox: {}", e))?;
+    std::fs::create_dir_all(sandbox_skills.join(&capture.skill_name))
+        .map_err(|e| format!("Failed to create replay sandbox: {}", e))?;
+
+    copy_dir_if_exists(&Path::new(workspace_path).join(".claude"), &sandbox_ws.join(".claude"))?;
+    copy_dir_if_exists(&capture_dir.join("workspace"), &sandbox_ws)?;
+    copy_dir_if_exists(&capture_dir.join("skills"), &sandbox_skills)?;
+    super::workflow::write_skill_output_dir_file(
+        &sandbox_ws.join(&capture.skill_name),
+        &sandbox_skills.join(&capture.skill_name),
+    );
+
+    let sandbox_ws = sandbox_ws.to_string_lossy().replace('\\', "/");
+    let sandbox_skills = sandbox_skills.to_string_lossy().replace('\\', "/");
+    let mut pairs = vec![(capture.workspace_path.as_str(), sandbox_ws.as_str())];
+    if capture.skills_path != capture.workspace_path {
+        pairs.push((capture.skills_path.as_str(), sandbox_skills.as_str()));
+    }
+
+    let mut config = capture.config.clone();
+    config.prompt = retarget(&config.prompt, &pairs);
+    config.cwd = sandbox_ws.clone();
+    if let Some(sandbox_cfg) = config.bash_sandbox.as_mut() {
+        sandbox_cfg.allowed_dirs = sandbox_cfg.allowed_dirs.iter().map(|d| retarget(d, &pairs)).collect();
+        // Captures from before the sandbox had its own cwd start in the first allowed dir.
+        sandbox_cfg.cwd = match sandbox_cfg.cwd.as_str() {
+            "" => sandbox_cfg.allowed_dirs.first().cloned().unwrap_or_default(),
+            cwd => retarget(cwd, &pairs),
+        };
+    }
+    Ok(config)
+}
+
+/// Re-run a historical step in an isolated sandbox with the prompt bundle,
+/// model settings and inputs it originally ran with. `session_id` matches
+/// either the workflow session or the SDK session of the original run.
+/// Agent events stream under the returned `agent_id` as for any step; files
+/// the step writes land in `output_dir`, and nothing in the real workspace,
+/// skills path or workflow state changes.
+#[tauri::command]
+pub async fn replay_step(
+    app: tauri::AppHandle,
+    pool: tauri::State<'_, SidecarPool>,
+    db: tauri::State<'_, Db>,
+    skill_name: String,
+    step_id: u32,
+    session_id: String,
+) -> Result<StepReplay, String> {
+    log::info!("[replay_step] skill={} step={} session={}", skill_name, step_id, session_id);
+    validate_skill_name(&skill_name)?;
+
+    let (workspace_path, api_key, agent_ids) = {
+        let conn = db.0.lock().map_err(|e| {
+            log::error!("[replay_step] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        let settings = crate::db::read_settings_hydrated(&conn)?;
+        let workspace_path = settings
+            .workspace_path
+            .ok_or_else(|| "Workspace path not initialized".to_string())?;
+        let api_key = crate::commands::offline::agent_api_key(&settings)
+            .ok_or_else(|| "Anthropic API key not configured".to_string())?;
+        crate::commands::usage::check_budget_allows_run(&conn, &skill_name).map_err(|e| {
+            log::warn!("[replay_step] {}", e);
+            e
+        })?;
+        let agent_ids = crate::db::list_step_run_agent_ids(&conn, &skill_name, step_id, &session_id)?;
+        (workspace_path, api_key, agent_ids)
+    };
+    if agent_ids.is_empty() {
+        return Err(format!(
+            "No run of step {} for '{}' in session {}",
+            step_id, skill_name, session_id
+        ));
+    }
+
+    let replay_root = replay_dir(&workspace_path, &skill_name);
+    let (capture_dir, capture) = agent_ids
+        .iter()
+        .map(|id| replay_root.join(CAPTURES_DIR).join(id))
+        .find_map(|dir| read_capture(&dir).map(|c| (dir, c)))
+        .ok_or_else(|| {
+            let msg = format!(
+                "Step {} of '{}' in session {} has no captured environment. \
+                 Only runs started by version {} or later can be replayed.",
+                step_id,
+                skill_name,
+                session_id,
+                env!("CARGO_PKG_VERSION")
+            );
+            log::warn!("[replay_step] {}", msg);
+            msg
+        })?;
+
+    let replay_id = format!(
+        "{}-{}",
+        capture.agent_id,
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis()
+    );
+    let runs = replay_root.join(RUNS_DIR);
+    let sandbox = runs.join(&replay_id);
+    let mut config = prepare_sandbox(&workspace_path, &capture_dir, &capture, &sandbox).map_err(|e| {
+        log::error!("[replay_step] {}", e);
+        e
+    })?;
+    config.api_key = api_key;
+    // Replay ids end in the millisecond timestamp they were created at.
+    prune_dir(&runs, MAX_REPLAYS_PER_SKILL, |p| {
+        p.file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.rsplit('-').next())
+            .and_then(|ts| ts.parse::<u128>().ok())
+            .unwrap_or_default()
+    });
+
+    let agent_id = super::workflow::make_agent_id(&skill_name, &format!("replay-step{}", step_id));
+    sidecar::spawn_sidecar(agent_id.clone(), config, pool.inner().clone(), app, skill_name.clone(), None).await?;
+
+    Ok(StepReplay {
+        replay_id,
+        agent_id,
+        source_agent_id: capture.agent_id,
+        skill_name,
+        step_id,
+        output_dir: sandbox.to_string_lossy().to_string(),
+        model: capture.model,
+        captured_at: capture.captured_at,
+        captured_app_version: capture.app_version,
+        current_app_version: env!("CARGO_PKG_VERSION").to_string(),
+        captured_prompt_version: capture.prompt_version,
+        current_prompt_version: super::workflow::step_prompt_version(&workspace_path, step_id),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(prompt: &str, cwd: &str) -> SidecarConfig {
+        SidecarConfig {
+            prompt: prompt.to_string(),
+            model: None,
+            api_key: "sk-secret".to_string(),
+            cwd: cwd.to_string(),
+            allowed_tools: None,
+            max_turns: Some(50),
+            permission_mode: None,
+            betas: None,
+            thinking: None,
+            fallback_model: None,
+            effort: None,
+            output_format: None,
+            prompt_suggestions: None,
+            path_to_claude_code_executable: None,
+            agent_name: Some("research-orchestrator".to_string()),
+            required_plugins: None,
+            conversation_history: None,
+            resume: None,
+            bash_sandbox: None,
+            disallowed_tools: None,
+            local_model: None,
+        }
+    }
+
+    #[test]
+    fn test_retarget_does_not_rewrite_replacements() {
+        let pairs = [("/ws", "/ws/s/.replay/runs/1/workspace"), ("/ws/out", "/ws/s/.replay/runs/1/skills")];
+        assert_eq!(
+            retarget("read /ws/s/context and write /ws/out/s", &pairs),
+            "read /ws/s/.replay/runs/1/workspace/s/context and write /ws/s/.replay/runs/1/skills/s"
+        );
+    }
+
+    #[test]
+    fn test_capture_then_sandbox_replays_captured_inputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let ws = dir.path().join("ws");
+        let skills = dir.path().join("skills");
+        let ws_str = ws.to_string_lossy().replace('\\', "/");
+        let skills_str = skills.to_string_lossy().replace('\\', "/");
+        std::fs::create_dir_all(ws.join(".claude/agents")).unwrap();
+        std::fs::write(ws.join(".claude/agents/research.md"), "v1 prompt").unwrap();
+        std::fs::create_dir_all(ws.join("s/context")).unwrap();
+        std::fs::write(ws.join("s/context/clarifications.json"), "{\"v\":1}").unwrap();
+        std::fs::create_dir_all(skills.join("s")).unwrap();
+        std::fs::write(skills.join("s/SKILL.md"), "old").unwrap();
+
+        let prompt = format!("workspace {} skills {}", ws_str, skills_str);
+        capture_step_environment(&ws_str, &skills_str, "s", 1, "s-step1-1", "sonnet", &config(&prompt, &ws_str))
+            .unwrap();
+
+        // The workspace moves on after the run.
+        std::fs::write(ws.join(".claude/agents/research.md"), "v2 prompt").unwrap();
+        std::fs::write(ws.join("s/context/clarifications.json"), "{\"v\":2}").unwrap();
+
+        let capture_dir = replay_dir(&ws_str, "s").join(CAPTURES_DIR).join("s-step1-1");
+        let capture = read_capture(&capture_dir).unwrap();
+        assert!(capture.config.api_key.is_empty());
+        assert_eq!(capture.step_id, 1);
+
+        let sandbox = replay_dir(&ws_str, "s").join(RUNS_DIR).join("r1");
+        let config = prepare_sandbox(&ws_str, &capture_dir, &capture, &sandbox).unwrap();
+        let sandbox_ws = sandbox.join("workspace");
+        assert_eq!(std::fs::read_to_string(sandbox_ws.join(".claude/agents/research.md")).unwrap(), "v1 prompt");
+        assert_eq!(
+            std::fs::read_to_string(sandbox_ws.join("s/context/clarifications.json")).unwrap(),
+            "{\"v\":1}"
+        );
+        assert_eq!(std::fs::read_to_string(sandbox.join("skills/s/SKILL.md")).unwrap(), "old");
+        assert_eq!(config.cwd, sandbox_ws.to_string_lossy().replace('\\', "/"));
+        assert!(!config.prompt.contains(&format!("skills {}", skills_str)));
+        assert!(config.prompt.ends_with("/skills"));
+        // The real workspace is untouched.
+        assert_eq!(std::fs::read_to_string(ws.join("s/context/clarifications.json")).unwrap(), "{\"v\":2}");
+    }
+}
@@ -0,0 +1,546 @@
+//! Structured diffs of skill files for the in-app review UI.
+//!
+//! `get_refine_diff` and `get_skill_diff` hand back raw text. Here a markdown
+//! file's frontmatter is compared field by field, and its body is split into
+//! line hunks that each carry a word-level breakdown. Hunk ids are derived
+//! from the hunk's position and content, so the ids the frontend shows are
+//! still valid when `apply_partial_diff` recomputes the diff to keep only the
+//! hunks and fields the user accepted.
+
+use std::collections::HashSet;
+use std::ops::Range;
+use std::path::{Component, Path};
+
+use sha2::{Digest, Sha256};
+
+use crate::commands::imported_skills::validate_skill_name;
+use crate::commands::push_merge::lcs_pairs;
+use crate::commands::refine::{get_refine_diff_inner, resolve_skills_path};
+use crate::db::Db;
+use crate::types::{FrontmatterFieldChange, StructuredDiffHunk, StructuredFileDiff, WordDiffSegment};
+
+/// A top-level frontmatter field with its raw lines, continuation lines included.
+#[derive(Debug)]
+struct Field {
+    key: String,
+    raw: String,
+}
+
+impl Field {
+    fn value(&self) -> String {
+        let first_line_rest = self.raw.split_once(':').map_or("", |(_, rest)| rest);
+        first_line_rest.trim().to_string()
+    }
+}
+
+struct Split<'a> {
+    /// `None` when the file has no frontmatter block.
+    fields: Option<Vec<Field>>,
+    /// Lines taken by the frontmatter block, delimiters included.
+    header_lines: usize,
+    body: &'a str,
+}
+
+fn parse_fields(frontmatter: &str) -> Vec<Field> {
+    let mut fields: Vec<Field> = Vec::new();
+    for line in frontmatter.split_inclusive('\n') {
+        let starts_field = !line.starts_with(char::is_whitespace)
+            && !line.starts_with('-')
+            && !line.starts_with('#')
+            && line.contains(':');
+        match fields.last_mut() {
+            Some(field) if !starts_field => field.raw.push_str(line),
+            // Comments or blank lines before the first field stay with it.
+            None if !starts_field => fields.push(Field { key: String::new(), raw: line.to_string() }),
+            _ => fields.push(Field {
+                key: line.split(':').next().unwrap_or("").trim().to_string(),
+                raw: line.to_string(),
+            }),
+        }
+    }
+    fields
+}
+
+fn split_frontmatter<'a>(path: &str, content: &'a str) -> Split<'a> {
+    let none = Split { fields: None, header_lines: 0, body: content };
+    if !path.ends_with(".md") {
+        return none;
+    }
+    let Some(rest) = content.strip_prefix("---\n").or_else(|| content.strip_prefix("---\r\n")) else {
+        return none;
+    };
+    let mut offset = 0;
+    for (i, line) in rest.split_inclusive('\n').enumerate() {
+        if line.trim_end() == "---" {
+            return Split {
+                fields: Some(parse_fields(&rest[..offset])),
+                header_lines: i + 2,
+                body: &rest[offset + line.len()..],
+            };
+        }
+        offset += line.len();
+    }
+    none
+}
+
+fn field_changes(old: &[Field], new: &[Field]) -> Vec<FrontmatterFieldChange> {
+    let value_of = |fields: &[Field], key: &str| fields.iter().find(|f| f.key == key).map(Field::value);
+    let mut keys: Vec<&str> = Vec::new();
+    for field in old.iter().chain(new) {
+        if !field.key.is_empty() && !keys.contains(&field.key.as_str()) {
+            keys.push(&field.key);
+        }
+    }
+    keys.into_iter()
+        .filter_map(|key| {
+            let (old_value, new_value) = (value_of(old, key), value_of(new, key));
+            (old_value != new_value).then(|| FrontmatterFieldChange {
+                field: key.to_string(),
+                old_value,
+                new_value,
+            })
+        })
+        .collect()
+}
+
+/// Line ranges that differ between `a` and `b`, in order.
+fn changed_regions(a: &[&str], b: &[&str]) -> Vec<(Range<usize>, Range<usize>)> {
+    let pairs = lcs_pairs(a, b);
+    let mut regions = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    loop {
+        let next = (i..a.len()).find_map(|k| pairs[k].map(|m| (k, m)));
+        let (ei, ej) = next.unwrap_or((a.len(), b.len()));
+        if ei > i || ej > j {
+            regions.push((i..ei, j..ej));
+        }
+        match next {
+            Some(_) => {
+                i = ei + 1;
+                j = ej + 1;
+            }
+            None => return regions,
+        }
+    }
+}
+
+/// Runs of whitespace, runs of word characters, and single punctuation marks.
+fn tokens(text: &str) -> Vec<&str> {
+    let class = |c: char| {
+        if c.is_whitespace() {
+            0
+        } else if c.is_alphanumeric() || c == '_' {
+            1
+        } else {
+            2
+        }
+    };
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut prev = None;
+    for (i, c) in text.char_indices() {
+        let cls = class(c);
+        if i > start && (prev != Some(cls) || cls == 2) {
+            out.push(&text[start..i]);
+            start = i;
+        }
+        prev = Some(cls);
+    }
+    if start < text.len() {
+        out.push(&text[start..]);
+    }
+    out
+}
+
+fn word_segments(old: &str, new: &str) -> Vec<WordDiffSegment> {
+    let (a, b) = (tokens(old), tokens(new));
+    let pairs = lcs_pairs(&a, &b);
+    let mut segments: Vec<WordDiffSegment> = Vec::new();
+    let mut push = |kind: &str, text: &str| match segments.last_mut() {
+        Some(last) if last.kind == kind => last.text.push_str(text),
+        _ => segments.push(WordDiffSegment { kind: kind.to_string(), text: text.to_string() }),
+    };
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        match pairs.get(i).copied().flatten() {
+            Some(k) if k == j => {
+                push("equal", a[i]);
+                i += 1;
+                j += 1;
+            }
+            Some(_) => {
+                push("insert", b[j]);
+                j += 1;
+            }
+            None if i < a.len() => {
+                push("delete", a[i]);
+                i += 1;
+            }
+            None => {
+                push("insert", b[j]);
+                j += 1;
+            }
+        }
+    }
+    segments
+}
+
+fn hunk_id(old_start: usize, old_text: &str, new_text: &str) -> String {
+    let digest = Sha256::digest(format!("{}\0{}\0{}", old_start, old_text, new_text).as_bytes());
+    hex::encode(&digest[..6])
+}
+
+struct BodyDiff<'a> {
+    old_lines: Vec<&'a str>,
+    new_lines: Vec<&'a str>,
+    regions: Vec<(Range<usize>, Range<usize>)>,
+    old_offset: usize,
+    new_offset: usize,
+}
+
+impl<'a> BodyDiff<'a> {
+    fn new(old: &Split<'a>, new: &Split<'a>) -> Self {
+        let old_lines: Vec<&str> = old.body.split_inclusive('\n').collect();
+        let new_lines: Vec<&str> = new.body.split_inclusive('\n').collect();
+        let regions = changed_regions(&old_lines, &new_lines);
+        BodyDiff { old_lines, new_lines, regions, old_offset: old.header_lines, new_offset: new.header_lines }
+    }
+
+    fn hunk(&self, (old, new): &(Range<usize>, Range<usize>)) -> StructuredDiffHunk {
+        let old_text = self.old_lines[old.clone()].concat();
+        let new_text = self.new_lines[new.clone()].concat();
+        let old_start = self.old_offset + old.start + 1;
+        StructuredDiffHunk {
+            id: hunk_id(old_start, &old_text, &new_text),
+            old_start,
+            new_start: self.new_offset + new.start + 1,
+            words: word_segments(&old_text, &new_text),
+            old_text,
+            new_text,
+        }
+    }
+}
+
+fn status_of(old: Option<&str>, new: Option<&str>) -> &'static str {
+    match (old, new) {
+        (None, _) => "added",
+        (_, None) => "deleted",
+        _ => "modified",
+    }
+}
+
+/// Structured diff of one file. A missing side is an added or deleted file.
+pub(crate) fn diff_file(path: &str, old: Option<&str>, new: Option<&str>) -> StructuredFileDiff {
+    let status = status_of(old, new).to_string();
+    let (old, new) = (split_frontmatter(path, old.unwrap_or("")), split_frontmatter(path, new.unwrap_or("")));
+    let frontmatter = field_changes(
+        old.fields.as_deref().unwrap_or_default(),
+        new.fields.as_deref().unwrap_or_default(),
+    );
+    let body = BodyDiff::new(&old, &new);
+    StructuredFileDiff {
+        path: path.to_string(),
+        status,
+        text: true,
+        frontmatter,
+        hunks: body.regions.iter().map(|r| body.hunk(r)).collect(),
+    }
+}
+
+fn binary_file(path: &str, status: &str) -> StructuredFileDiff {
+    StructuredFileDiff {
+        path: path.to_string(),
+        status: status.to_string(),
+        text: false,
+        frontmatter: Vec::new(),
+        hunks: Vec::new(),
+    }
+}
+
+/// `old` with only the accepted hunks and frontmatter fields of the diff to
+/// `new` applied. Errors on ids the diff does not have, e.g. from a stale view.
+pub(crate) fn apply_partial(
+    path: &str,
+    old: &str,
+    new: &str,
+    accepted_hunks: &[String],
+    accepted_fields: &[String],
+) -> Result<String, String> {
+    let diff = diff_file(path, Some(old), Some(new));
+    if let Some(id) = accepted_hunks.iter().find(|id| !diff.hunks.iter().any(|h| &h.id == *id)) {
+        return Err(format!("Hunk {} is not in the current diff of {}; reload the diff", id, path));
+    }
+    if let Some(field) = accepted_fields.iter().find(|f| !diff.frontmatter.iter().any(|c| &c.field == *f)) {
+        return Err(format!("Field '{}' is not changed in {}; reload the diff", field, path));
+    }
+    let accepted_fields: HashSet<&str> = accepted_fields.iter().map(String::as_str).collect();
+    let (old_split, new_split) = (split_frontmatter(path, old), split_frontmatter(path, new));
+
+    let mut out = String::new();
+    let old_fields = old_split.fields.as_deref().unwrap_or_default();
+    let new_fields = new_split.fields.as_deref().unwrap_or_default();
+    let find = |fields: &[Field], key: &str| fields.iter().find(|f| f.key == key).map(|f| f.raw.clone());
+    let mut blocks = Vec::new();
+    for field in old_fields {
+        if field.key.is_empty() || !accepted_fields.contains(field.key.as_str()) {
+            blocks.push(field.raw.clone());
+        } else if let Some(raw) = find(new_fields, &field.key) {
+            blocks.push(raw);
+        }
+    }
+    for field in new_fields {
+        if accepted_fields.contains(field.key.as_str()) && find(old_fields, &field.key).is_none() {
+            blocks.push(field.raw.clone());
+        }
+    }
+    if old_split.fields.is_some() || !blocks.is_empty() {
+        out.push_str("---\n");
+        out.extend(blocks);
+        out.push_str("---\n");
+    }
+
+    let body = BodyDiff::new(&old_split, &new_split);
+    let mut next_old = 0;
+    for region in &body.regions {
+        out.extend(body.old_lines[next_old..region.0.start].iter().copied());
+        if accepted_hunks.contains(&body.hunk(region).id) {
+            out.extend(body.new_lines[region.1.clone()].iter().copied());
+        } else {
+            out.extend(body.old_lines[region.0.clone()].iter().copied());
+        }
+        next_old = region.0.end;
+    }
+    out.extend(body.old_lines[next_old..].iter().copied());
+    Ok(out)
+}
+
+/// Reject paths that are not inside the skill's folder.
+fn validate_skill_file_path(skill_name: &str, path: &str) -> Result<(), String> {
+    let p = Path::new(path);
+    let inside = p.starts_with(skill_name)
+        && p.components().all(|c| matches!(c, Component::Normal(_)))
+        && p.components().count() > 1;
+    if inside {
+        Ok(())
+    } else {
+        Err(format!("'{}' is not a file of skill '{}'", path, skill_name))
+    }
+}
+
+/// Structured version of `get_refine_diff`: HEAD against the working tree for
+/// each changed file of the skill.
+#[tauri::command]
+pub fn get_structured_refine_diff(
+    skill_name: String,
+    workspace_path: String,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<StructuredFileDiff>, String> {
+    log::info!("[get_structured_refine_diff] skill={}", skill_name);
+    validate_skill_name(&skill_name)?;
+    let skills_path = resolve_skills_path(&db, &workspace_path)?;
+    let root = Path::new(&skills_path);
+    let raw = get_refine_diff_inner(&skill_name, &skills_path).map_err(|e| {
+        log::error!("[get_structured_refine_diff] {}", e);
+        e
+    })?;
+    let mut files = Vec::new();
+    for file in raw.files {
+        let old = match file.status.as_str() {
+            "added" => None,
+            _ => crate::git::head_file_content(root, &file.path)?,
+        };
+        let new = match file.status.as_str() {
+            "deleted" => None,
+            _ => std::fs::read(root.join(&file.path)).ok().and_then(|b| String::from_utf8(b).ok()),
+        };
+        let missing_text = (file.status != "added" && old.is_none()) || (file.status != "deleted" && new.is_none());
+        files.push(if missing_text {
+            binary_file(&file.path, &file.status)
+        } else {
+            diff_file(&file.path, old.as_deref(), new.as_deref())
+        });
+    }
+    log::debug!("[get_structured_refine_diff] {} file(s)", files.len());
+    Ok(files)
+}
+
+/// Structured version of `get_skill_diff`, between two commits.
+#[tauri::command]
+pub fn get_structured_skill_diff(
+    workspace_path: String,
+    skill_name: String,
+    sha_a: String,
+    sha_b: String,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<StructuredFileDiff>, String> {
+    log::info!(
+        "[get_structured_skill_diff] skill={} sha_a={} sha_b={}",
+        skill_name,
+        sha_a,
+        sha_b
+    );
+    validate_skill_name(&skill_name)?;
+    let skills_path = resolve_skills_path(&db, &workspace_path)?;
+    let raw = crate::git::get_diff(Path::new(&skills_path), &sha_a, &sha_b, &skill_name).map_err(|e| {
+        log::error!("[get_structured_skill_diff] {}", e);
+        e
+    })?;
+    Ok(raw
+        .files
+        .into_iter()
+        .map(|file| {
+            let missing_text = (file.status != "added" && file.old_content.is_none())
+                || (file.status != "deleted" && file.new_content.is_none());
+            if missing_text {
+                binary_file(&file.path, &file.status)
+            } else {
+                diff_file(&file.path, file.old_content.as_deref(), file.new_content.as_deref())
+            }
+        })
+        .collect())
+}
+
+/// Keep only the accepted hunks and frontmatter fields of a file's pending
+/// (uncommitted) changes, putting everything else back to HEAD. `path` and the
+/// ids are as returned by `get_structured_refine_diff`. A file left empty that
+/// was added or deleted in the working tree ends up absent.
+#[tauri::command]
+pub fn apply_partial_diff(
+    skill_name: String,
+    workspace_path: String,
+    path: String,
+    accepted_hunk_ids: Vec<String>,
+    accepted_fields: Vec<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<(), String> {
+    log::info!(
+        "[apply_partial_diff] skill={} path={} hunks={} fields={}",
+        skill_name,
+        path,
+        accepted_hunk_ids.len(),
+        accepted_fields.len()
+    );
+    super::observer::ensure_writable(&skill_name)?;
+    validate_skill_name(&skill_name)?;
+    validate_skill_file_path(&skill_name, &path)?;
+    let skills_path = resolve_skills_path(&db, &workspace_path)?;
+    let root = Path::new(&skills_path);
+    let disk_path = root.join(&path);
+
+    let old = crate::git::head_file_content(root, &path)?;
+    let new = match std::fs::read(&disk_path) {
+        Ok(bytes) => Some(String::from_utf8(bytes).map_err(|_| format!("{} is not a text file", path))?),
+        Err(_) => None,
+    };
+    if old.is_none() && new.is_none() {
+        return Err(format!("{} has no pending changes", path));
+    }
+    let content = apply_partial(
+        &path,
+        old.as_deref().unwrap_or(""),
+        new.as_deref().unwrap_or(""),
+        &accepted_hunk_ids,
+        &accepted_fields,
+    )
+    .map_err(|e| {
+        log::error!("[apply_partial_diff] {}", e);
+        e
+    })?;
+
+    if content.is_empty() && (old.is_none() || new.is_none()) {
+        if disk_path.exists() {
+            std::fs::remove_file(&disk_path).map_err(|e| format!("Failed to remove {}: {}", path, e))?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = disk_path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(&disk_path, content).map_err(|e| {
+        log::error!("[apply_partial_diff] Failed to write {}: {}", disk_path.display(), e);
+        format!("Failed to write {}: {}", path, e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = "---\nname: revenue\ndescription: Recognize revenue\ntools:\n  - Read\n---\n# Revenue\n\nIdentify the contract.\nAllocate the price.\n\n## Notes\n\nNone.\n";
+
+    fn edited() -> String {
+        OLD.replace("description: Recognize revenue", "description: Recognize revenue under ASC 606")
+            .replace("  - Read\n", "  - Read\n  - Grep\n")
+            .replace("Identify the contract.", "Identify the customer contract.")
+            .replace("None.", "Watch for refunds.")
+    }
+
+    #[test]
+    fn test_frontmatter_and_body_are_diffed_separately() {
+        let diff = diff_file("revenue/SKILL.md", Some(OLD), Some(&edited()));
+        assert_eq!(diff.status, "modified");
+        assert_eq!(
+            diff.frontmatter,
+            vec![
+                FrontmatterFieldChange {
+                    field: "description".to_string(),
+                    old_value: Some("Recognize revenue".to_string()),
+                    new_value: Some("Recognize revenue under ASC 606".to_string()),
+                },
+                FrontmatterFieldChange {
+                    field: "tools".to_string(),
+                    old_value: Some("- Read".to_string()),
+                    new_value: Some("- Read\n  - Grep".to_string()),
+                },
+            ]
+        );
+        assert_eq!(diff.hunks.len(), 2);
+        let first = &diff.hunks[0];
+        assert_eq!((first.old_start, first.new_start), (9, 10));
+        assert_eq!(
+            first.words,
+            vec![
+                WordDiffSegment { kind: "equal".to_string(), text: "Identify the ".to_string() },
+                WordDiffSegment { kind: "insert".to_string(), text: "customer ".to_string() },
+                WordDiffSegment { kind: "equal".to_string(), text: "contract.\n".to_string() },
+            ]
+        );
+        // Ids are stable across recomputation and distinct per hunk.
+        let again = diff_file("revenue/SKILL.md", Some(OLD), Some(&edited()));
+        assert_eq!(again.hunks[0].id, first.id);
+        assert_ne!(diff.hunks[1].id, first.id);
+    }
+
+    #[test]
+    fn test_apply_partial_keeps_only_accepted_changes() {
+        let new = edited();
+        let diff = diff_file("revenue/SKILL.md", Some(OLD), Some(&new));
+        let result = apply_partial(
+            "revenue/SKILL.md",
+            OLD,
+            &new,
+            &[diff.hunks[1].id.clone()],
+            &["tools".to_string()],
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            OLD.replace("  - Read\n", "  - Read\n  - Grep\n").replace("None.", "Watch for refunds.")
+        );
+
+        assert_eq!(apply_partial("revenue/SKILL.md", OLD, &new, &[], &[]).unwrap(), OLD);
+        let all_hunks: Vec<String> = diff.hunks.iter().map(|h| h.id.clone()).collect();
+        let all_fields: Vec<String> = diff.frontmatter.iter().map(|f| f.field.clone()).collect();
+        assert_eq!(apply_partial("revenue/SKILL.md", OLD, &new, &all_hunks, &all_fields).unwrap(), new);
+        assert!(apply_partial("revenue/SKILL.md", OLD, &new, &["stale".to_string()], &[]).is_err());
+    }
+
+    #[test]
+    fn test_skill_file_paths_are_confined_to_the_skill() {
+        assert!(validate_skill_file_path("revenue", "revenue/references/a.md").is_ok());
+        assert!(validate_skill_file_path("revenue", "revenue").is_err());
+        assert!(validate_skill_file_path("revenue", "other/SKILL.md").is_err());
+        assert!(validate_skill_file_path("revenue", "revenue/../other/SKILL.md").is_err());
+        assert!(validate_skill_file_path("revenue", "/revenue/SKILL.md").is_err());
+    }
+}
@@ -0,0 +1,130 @@
+//! Library curation for skill tags.
+//!
+//! Tags are hierarchical: `finance/revenue/rev-rec` sits under `finance` and
+//! `finance/revenue`. Renaming or merging a tag carries the tags under it
+//! along and leaves the old name behind as an alias, so `set_skill_tags`
+//! rewrites it (and anything under it) to the canonical tag on later writes.
+//! Merging a tag no skill uses is how a new alias such as `sfdc` →
+//! `salesforce` is defined.
+
+use crate::db::Db;
+use crate::types::TagTree;
+
+/// Rename or merge, then return the updated tree with the team taxonomy's
+/// warnings for the retagged skills.
+fn curate(
+    conn: &rusqlite::Connection,
+    change: impl FnOnce(&rusqlite::Connection) -> Result<Vec<String>, String>,
+) -> Result<TagTree, String> {
+    let skills = change(conn)?;
+    log::info!("[tag_taxonomy] retagged {} skill(s)", skills.len());
+    let mut tree = crate::db::get_tag_tree(conn)?;
+    let tags = crate::db::get_tags_for_skills(conn, &skills)?;
+    for skill in &skills {
+        let skill_tags = tags.get(skill).map(Vec::as_slice).unwrap_or_default();
+        tree.warnings.extend(super::team_taxonomy::tag_warnings(conn, skill, skill_tags)?);
+    }
+    Ok(tree)
+}
+
+#[tauri::command]
+pub fn get_tag_tree(db: tauri::State<'_, Db>) -> Result<TagTree, String> {
+    log::info!("[get_tag_tree]");
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[get_tag_tree] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::get_tag_tree(&conn)
+}
+
+/// Rename `from` and the tags under it to `to`. Fails when `to` is already in
+/// use; merge the tags instead.
+#[tauri::command]
+pub fn rename_tag(from: String, to: String, db: tauri::State<'_, Db>) -> Result<TagTree, String> {
+    log::info!("[rename_tag] from={} to={}", from, to);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[rename_tag] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    curate(&conn, |conn| crate::db::rename_tag(conn, &from, &to)).map_err(|e| {
+        log::error!("[rename_tag] {}", e);
+        e
+    })
+}
+
+/// Fold `sources` into `target`, keeping each source as an alias of it.
+#[tauri::command]
+pub fn merge_tags(
+    sources: Vec<String>,
+    target: String,
+    db: tauri::State<'_, Db>,
+) -> Result<TagTree, String> {
+    log::info!("[merge_tags] sources={:?} target={}", sources, target);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[merge_tags] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    curate(&conn, |conn| crate::db::merge_tags(conn, &sources, &target)).map_err(|e| {
+        log::error!("[merge_tags] {}", e);
+        e
+    })
+}
+
+#[tauri::command]
+pub fn remove_tag_alias(alias: String, db: tauri::State<'_, Db>) -> Result<TagTree, String> {
+    log::info!("[remove_tag_alias] alias={}", alias);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[remove_tag_alias] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::remove_tag_alias(&conn, &alias)?;
+    crate::db::get_tag_tree(&conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::create_test_db;
+    use crate::types::TagAlias;
+
+    #[test]
+    fn test_curate_returns_tree_after_merge() {
+        let conn = create_test_db();
+        crate::db::upsert_skill(&conn, "revenue", "skill-builder", "domain").unwrap();
+        crate::db::set_skill_tags(&conn, "revenue", &["sfdc".into(), "finance/revenue".into()])
+            .unwrap();
+
+        let tree = curate(&conn, |conn| {
+            crate::db::merge_tags(conn, &["sfdc".to_string()], "salesforce")
+        })
+        .unwrap();
+        let paths: Vec<&str> = tree.tags.iter().map(|n| n.path.as_str()).collect();
+        assert_eq!(paths, ["finance", "salesforce"]);
+        assert_eq!(
+            tree.aliases,
+            vec![TagAlias { alias: "sfdc".into(), tag: "salesforce".into() }]
+        );
+
+        let err = curate(&conn, |conn| crate::db::rename_tag(conn, "finance", "salesforce"));
+        assert!(err.unwrap_err().contains("merge"));
+    }
+
+    #[test]
+    fn test_curate_reports_taxonomy_warnings_for_retagged_skills() {
+        let conn = create_test_db();
+        crate::db::upsert_skill(&conn, "revenue", "skill-builder", "domain").unwrap();
+        crate::db::set_skill_tags(&conn, "revenue", &["finance".into()]).unwrap();
+        crate::db::write_team_taxonomy(
+            &conn,
+            &crate::types::TeamTaxonomy {
+                allowed_tags: vec!["finance".into()],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let tree = curate(&conn, |conn| crate::db::rename_tag(conn, "finance", "fin")).unwrap();
+        assert_eq!(tree.warnings.len(), 1);
+        assert_eq!((tree.warnings[0].skill_name.as_str(), tree.warnings[0].value.as_str()), ("revenue", "fin"));
+    }
+}
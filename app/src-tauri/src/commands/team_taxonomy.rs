@@ -0,0 +1,582 @@
+use std::collections::HashMap;
+
+use crate::commands::github_import::{build_github_client, get_default_branch, parse_github_url_for};
+use crate::db::Db;
+use crate::github::GitHubServer;
+use crate::types::{GitHubRepoInfo, TaxonomyProposal, TaxonomyWarning, TeamTaxonomy};
+
+/// Repo-relative path of the shared taxonomy file in the team repository.
+pub(crate) const TAXONOMY_FILE_PATH: &str = ".skill-builder/taxonomy.json";
+
+/// Read the configured team repo, GitHub server and token from settings.
+pub(crate) fn read_team_repo(db: &Db) -> Result<(String, GitHubServer, Option<String>), String> {
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let settings = crate::db::read_settings_hydrated(&conn)?;
+    let server = GitHubServer::from_settings(&settings)?;
+    let team_repo = settings
+        .team_repo
+        .filter(|r| !r.trim().is_empty())
+        .ok_or_else(|| "No team repository configured. Set one in Settings.".to_string())?;
+    Ok((team_repo, server, settings.github_oauth_token))
+}
+
+/// Resolve the branch to read from: an explicit `#branch` / `/tree/branch` wins,
+/// otherwise the repo's default branch.
+pub(crate) async fn resolve_team_branch(
+    client: &reqwest::Client,
+    server: &GitHubServer,
+    team_repo: &str,
+    info: &GitHubRepoInfo,
+) -> String {
+    if team_repo.contains('#') || team_repo.contains("/tree/") {
+        return info.branch.clone();
+    }
+    get_default_branch(client, server, &info.owner, &info.repo)
+        .await
+        .unwrap_or_else(|_| info.branch.clone())
+}
+
+/// Trim, lowercase, and de-duplicate tags; trim and case-insensitively de-duplicate
+/// domains (keeping the first spelling).
+fn normalize_taxonomy(taxonomy: &mut TeamTaxonomy) {
+    fn normalize_tags(tags: &mut Vec<String>) {
+        let mut out: Vec<String> = tags
+            .iter()
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty())
+            .collect();
+        out.sort();
+        out.dedup();
+        *tags = out;
+    }
+    normalize_tags(&mut taxonomy.allowed_tags);
+    normalize_tags(&mut taxonomy.suggested_tags);
+
+    let mut seen = std::collections::HashSet::new();
+    taxonomy.domains = taxonomy
+        .domains
+        .iter()
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty() && seen.insert(d.to_lowercase()))
+        .collect();
+}
+
+async fn fetch_team_taxonomy(
+    client: &reqwest::Client,
+    api_base: &str,
+    info: &GitHubRepoInfo,
+    branch: &str,
+) -> Result<TeamTaxonomy, String> {
+    let file = crate::github::get_file(
+        client,
+        api_base,
+        &info.owner,
+        &info.repo,
+        TAXONOMY_FILE_PATH,
+        branch,
+    )
+    .await?
+    .ok_or_else(|| {
+        format!(
+            "{}/{} has no {} on branch '{}'",
+            info.owner, info.repo, TAXONOMY_FILE_PATH, branch
+        )
+    })?;
+    let mut taxonomy: TeamTaxonomy = serde_json::from_str(&file.content)
+        .map_err(|e| format!("Invalid {}: {}", TAXONOMY_FILE_PATH, e))?;
+    normalize_taxonomy(&mut taxonomy);
+    taxonomy.source_repo = Some(format!("{}/{}", info.owner, info.repo));
+    taxonomy.source_sha = Some(file.sha);
+    taxonomy.synced_at = Some(chrono::Utc::now().to_rfc3339());
+    Ok(taxonomy)
+}
+
+/// Pull the team taxonomy from the configured team repo and store it locally.
+#[tauri::command]
+pub async fn sync_team_taxonomy(db: tauri::State<'_, Db>) -> Result<TeamTaxonomy, String> {
+    log::info!("[sync_team_taxonomy]");
+    super::offline::require_feature(&db, super::offline::NetworkFeature::GitHub, "sync_team_taxonomy")?;
+    let (team_repo, server, token) = read_team_repo(&db).map_err(|e| {
+        log::error!("[sync_team_taxonomy] {}", e);
+        e
+    })?;
+    let info = parse_github_url_for(&team_repo, &server)?;
+    let client = build_github_client(token.as_deref(), &server)?;
+    let branch = resolve_team_branch(&client, &server, &team_repo, &info).await;
+
+    let taxonomy = fetch_team_taxonomy(&client, &server.api_base, &info, &branch)
+        .await
+        .map_err(|e| {
+            log::error!("[sync_team_taxonomy] failed to fetch from {}/{}: {}", info.owner, info.repo, e);
+            e
+        })?;
+
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[sync_team_taxonomy] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::write_team_taxonomy(&conn, &taxonomy)?;
+    log::info!(
+        "[sync_team_taxonomy] synced {} allowed tags, {} suggested tags, {} domains from {}/{}",
+        taxonomy.allowed_tags.len(),
+        taxonomy.suggested_tags.len(),
+        taxonomy.domains.len(),
+        info.owner,
+        info.repo
+    );
+    Ok(taxonomy)
+}
+
+#[tauri::command]
+pub fn get_team_taxonomy(db: tauri::State<'_, Db>) -> Result<Option<TeamTaxonomy>, String> {
+    log::info!("[get_team_taxonomy]");
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[get_team_taxonomy] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::read_team_taxonomy(&conn)
+}
+
+/// Check one skill's name, tags, and domain against the taxonomy.
+/// Never fails — violations are returned as warnings for the UI to surface.
+pub(crate) fn validate_against_taxonomy(
+    taxonomy: &TeamTaxonomy,
+    skill_name: &str,
+    tags: &[String],
+    domain: Option<&str>,
+) -> Vec<TaxonomyWarning> {
+    let mut warnings = Vec::new();
+    let mut warn = |field: &str, value: &str, message: String| {
+        warnings.push(TaxonomyWarning {
+            skill_name: skill_name.to_string(),
+            field: field.to_string(),
+            value: value.to_string(),
+            message,
+        });
+    };
+    let rules = &taxonomy.naming_rules;
+
+    if let Some(prefix) = rules.skill_name_prefix.as_deref().filter(|p| !p.is_empty()) {
+        if !skill_name.starts_with(prefix) {
+            warn("name", skill_name, format!("Skill names should start with '{}'", prefix));
+        }
+    }
+
+    for tag in tags {
+        let known = taxonomy.allowed_tags.iter().any(|t| t == tag)
+            || taxonomy.suggested_tags.iter().any(|t| t == tag);
+        if !taxonomy.allowed_tags.is_empty() && !known {
+            warn("tag", tag, "Tag is not in the team vocabulary".to_string());
+        }
+        if rules.kebab_case_tags && !tag.split('/').all(crate::commands::skill::is_valid_kebab) {
+            warn("tag", tag, "Tags should be kebab-case (e.g. data-quality)".to_string());
+        }
+        if let Some(max) = rules.max_tag_length {
+            if tag.chars().count() > max {
+                warn("tag", tag, format!("Tags should be at most {} characters", max));
+            }
+        }
+    }
+
+    if let Some(domain) = domain.map(str::trim).filter(|d| !d.is_empty()) {
+        if !taxonomy.domains.is_empty()
+            && !taxonomy.domains.iter().any(|d| d.eq_ignore_ascii_case(domain))
+        {
+            warn("domain", domain, "Domain is not in the team domain list".to_string());
+        }
+    }
+
+    warnings
+}
+
+/// Tag warnings for `tags` on `skill_name`; empty when no taxonomy has been synced.
+pub(crate) fn tag_warnings(
+    conn: &rusqlite::Connection,
+    skill_name: &str,
+    tags: &[String],
+) -> Result<Vec<TaxonomyWarning>, String> {
+    let Some(taxonomy) = crate::db::read_team_taxonomy(conn)? else {
+        return Ok(Vec::new());
+    };
+    let mut warnings = validate_against_taxonomy(&taxonomy, skill_name, tags, None);
+    warnings.retain(|w| w.field == "tag");
+    Ok(warnings)
+}
+
+/// Extract the `domain` field from a workflow run's intake JSON.
+fn intake_domain(intake_json: Option<&str>) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(intake_json?).ok()?;
+    value["domain"].as_str().map(|s| s.to_string())
+}
+
+fn validate_skill_taxonomy_inner(
+    conn: &rusqlite::Connection,
+    skill_name: Option<&str>,
+) -> Result<Vec<TaxonomyWarning>, String> {
+    let Some(taxonomy) = crate::db::read_team_taxonomy(conn)? else {
+        return Ok(Vec::new());
+    };
+    let names: Vec<String> = match skill_name {
+        Some(name) => vec![name.to_string()],
+        None => crate::db::list_all_skills(conn)?
+            .into_iter()
+            .map(|s| s.name)
+            .collect(),
+    };
+    let tags_map = crate::db::get_tags_for_skills(conn, &names)?;
+    let domains: HashMap<String, Option<String>> = crate::db::list_all_workflow_runs(conn)?
+        .into_iter()
+        .map(|r| (r.skill_name, intake_domain(r.intake_json.as_deref())))
+        .collect();
+
+    let mut warnings = Vec::new();
+    for name in &names {
+        let tags = tags_map.get(name).cloned().unwrap_or_default();
+        let domain = domains.get(name).cloned().flatten();
+        warnings.extend(validate_against_taxonomy(&taxonomy, name, &tags, domain.as_deref()));
+    }
+    Ok(warnings)
+}
+
+/// Validate one skill (or the whole library when `skill_name` is None) against the
+/// synced team taxonomy. Returns an empty list when no taxonomy has been synced.
+#[tauri::command]
+pub fn validate_skill_taxonomy(
+    skill_name: Option<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<TaxonomyWarning>, String> {
+    log::info!("[validate_skill_taxonomy] skill={:?}", skill_name);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[validate_skill_taxonomy] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let warnings = validate_skill_taxonomy_inner(&conn, skill_name.as_deref())?;
+    log::debug!("[validate_skill_taxonomy] {} warning(s)", warnings.len());
+    Ok(warnings)
+}
+
+/// Check tags being edited on a skill against the synced team taxonomy, before they are saved.
+#[tauri::command]
+pub fn check_skill_tags(
+    skill_name: String,
+    tags: Vec<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<TaxonomyWarning>, String> {
+    log::info!("[check_skill_tags] skill={} tags={:?}", skill_name, tags);
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[check_skill_tags] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    tag_warnings(&conn, &skill_name, &tags)
+}
+
+/// Append proposed entries to the taxonomy file, preserving any fields this app
+/// doesn't know about. Returns the new file content plus the entries actually added.
+fn merge_proposal(
+    existing: Option<&str>,
+    tags: &[String],
+    domains: &[String],
+) -> Result<(String, Vec<String>, Vec<String>), String> {
+    let mut doc: serde_json::Value = match existing {
+        Some(content) => serde_json::from_str(content)
+            .map_err(|e| format!("Invalid {}: {}", TAXONOMY_FILE_PATH, e))?,
+        None => serde_json::json!({}),
+    };
+    let obj = doc
+        .as_object_mut()
+        .ok_or_else(|| format!("{} must contain a JSON object", TAXONOMY_FILE_PATH))?;
+
+    let mut append = |key: &str, values: Vec<String>, case_insensitive: bool| -> Vec<String> {
+        let entry = obj
+            .entry(key)
+            .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+        if !entry.is_array() {
+            *entry = serde_json::Value::Array(Vec::new());
+        }
+        let arr = entry.as_array_mut().expect("array ensured above");
+        let mut added = Vec::new();
+        for value in values {
+            let exists = arr.iter().filter_map(|v| v.as_str()).any(|v| {
+                if case_insensitive {
+                    v.eq_ignore_ascii_case(&value)
+                } else {
+                    v == value
+                }
+            });
+            if !exists && !added.contains(&value) {
+                arr.push(serde_json::Value::String(value.clone()));
+                added.push(value);
+            }
+        }
+        added
+    };
+
+    let added_tags = append(
+        "allowed_tags",
+        tags.iter()
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty())
+            .collect(),
+        false,
+    );
+    let added_domains = append(
+        "domains",
+        domains
+            .iter()
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty())
+            .collect(),
+        true,
+    );
+
+    if added_tags.is_empty() && added_domains.is_empty() {
+        return Err("All proposed entries already exist in the team taxonomy".to_string());
+    }
+    let content = serde_json::to_string_pretty(&doc).map_err(|e| e.to_string())? + "\n";
+    Ok((content, added_tags, added_domains))
+}
+
+/// Open a pull request against the team repo adding new tags and/or domains.
+#[tauri::command]
+pub async fn propose_taxonomy_entries(
+    tags: Vec<String>,
+    domains: Vec<String>,
+    rationale: Option<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<TaxonomyProposal, String> {
+    log::info!(
+        "[propose_taxonomy_entries] tags={:?} domains={:?}",
+        tags,
+        domains
+    );
+    super::offline::require_feature(&db, super::offline::NetworkFeature::GitHub, "propose_taxonomy_entries")?;
+    let (team_repo, server, token) = read_team_repo(&db).map_err(|e| {
+        log::error!("[propose_taxonomy_entries] {}", e);
+        e
+    })?;
+    let token = token
+        .ok_or_else(|| "Not signed in to GitHub. Sign in with GitHub in Settings.".to_string())?;
+    let info = parse_github_url_for(&team_repo, &server)?;
+    let client = build_github_client(Some(&token), &server)?;
+    let base = resolve_team_branch(&client, &server, &team_repo, &info).await;
+    let api_base = server.api_base.as_str();
+    let (owner, repo) = (info.owner.as_str(), info.repo.as_str());
+
+    let result: Result<TaxonomyProposal, String> = async {
+        let base_sha = crate::github::get_branch_sha(&client, api_base, owner, repo, &base).await?;
+        let existing =
+            crate::github::get_file(&client, api_base, owner, repo, TAXONOMY_FILE_PATH, &base).await?;
+        let (content, added_tags, added_domains) =
+            merge_proposal(existing.as_ref().map(|f| f.content.as_str()), &tags, &domains)?;
+
+        let branch = format!(
+            "skill-builder/taxonomy-{}",
+            chrono::Utc::now().format("%Y%m%d-%H%M%S")
+        );
+        crate::github::create_branch(&client, api_base, owner, repo, &branch, &base_sha).await?;
+        crate::github::put_file(
+            &client,
+            api_base,
+            owner,
+            repo,
+            &branch,
+            TAXONOMY_FILE_PATH,
+            &content,
+            "Propose team taxonomy entries",
+            existing.as_ref().map(|f| f.sha.as_str()),
+        )
+        .await?;
+
+        let mut body = String::from("Proposed from Skill Builder.\n");
+        if !added_tags.is_empty() {
+            body.push_str(&format!("\n**Tags:** {}\n", added_tags.join(", ")));
+        }
+        if !added_domains.is_empty() {
+            body.push_str(&format!("\n**Domains:** {}\n", added_domains.join(", ")));
+        }
+        if let Some(r) = rationale.as_deref().map(str::trim).filter(|r| !r.is_empty()) {
+            body.push_str(&format!("\n**Rationale:** {}\n", r));
+        }
+        let pr = crate::github::create_pull_request(
+            &client,
+            api_base,
+            owner,
+            repo,
+            &branch,
+            &base,
+            "Add team taxonomy entries",
+            &body,
+        )
+        .await?;
+
+        Ok(TaxonomyProposal {
+            pr_url: pr.url,
+            pr_number: pr.number,
+            branch,
+            added_tags,
+            added_domains,
+        })
+    }
+    .await;
+
+    match &result {
+        Ok(p) => log::info!(
+            "[propose_taxonomy_entries] opened PR #{} on {}/{}",
+            p.pr_number,
+            owner,
+            repo
+        ),
+        Err(e) => log::error!("[propose_taxonomy_entries] failed: {}", e),
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TaxonomyNamingRules;
+
+    fn taxonomy() -> TeamTaxonomy {
+        TeamTaxonomy {
+            allowed_tags: vec!["finance".to_string(), "sales".to_string()],
+            suggested_tags: vec!["forecasting".to_string()],
+            domains: vec!["Revenue recognition".to_string()],
+            naming_rules: TaxonomyNamingRules {
+                max_tag_length: Some(12),
+                kebab_case_tags: true,
+                skill_name_prefix: Some("acme-".to_string()),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_accepts_known_vocabulary() {
+        let warnings = validate_against_taxonomy(
+            &taxonomy(),
+            "acme-revenue",
+            &["finance".to_string(), "forecasting".to_string()],
+            Some("revenue recognition"),
+        );
+        assert!(warnings.is_empty(), "unexpected warnings: {:?}", warnings);
+    }
+
+    #[test]
+    fn test_validate_flags_unknown_tag_domain_and_prefix() {
+        let warnings = validate_against_taxonomy(
+            &taxonomy(),
+            "revenue",
+            &["Marketing_Ops-Long".to_string()],
+            Some("Payroll"),
+        );
+        let fields: Vec<&str> = warnings.iter().map(|w| w.field.as_str()).collect();
+        assert_eq!(fields, vec!["name", "tag", "tag", "tag", "domain"]);
+    }
+
+    #[test]
+    fn test_validate_checks_each_level_of_a_hierarchical_tag() {
+        let mut taxonomy = taxonomy();
+        taxonomy.allowed_tags.push("finance/rev-rec".to_string());
+        taxonomy.naming_rules.max_tag_length = None;
+        let tags = ["finance/rev-rec".to_string(), "finance/Rev_Rec".to_string()];
+        let warnings = validate_against_taxonomy(&taxonomy, "acme-revenue", &tags, None);
+        let flagged: Vec<&str> = warnings.iter().map(|w| w.value.as_str()).collect();
+        assert_eq!(flagged, vec!["finance/Rev_Rec", "finance/Rev_Rec"]);
+    }
+
+    #[test]
+    fn test_tag_warnings_only_reports_tags() {
+        let conn = crate::commands::test_utils::create_test_db();
+        let tags = ["unknown".to_string()];
+        assert!(tag_warnings(&conn, "revenue", &tags).unwrap().is_empty());
+
+        crate::db::write_team_taxonomy(&conn, &taxonomy()).unwrap();
+        let warnings = tag_warnings(&conn, "revenue", &tags).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!((warnings[0].field.as_str(), warnings[0].value.as_str()), ("tag", "unknown"));
+    }
+
+    #[test]
+    fn test_validate_open_vocabulary_only_applies_naming_rules() {
+        let open = TeamTaxonomy::default();
+        let warnings = validate_against_taxonomy(&open, "any", &["anything".to_string()], Some("Any"));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_normalize_taxonomy_dedupes() {
+        let mut t = TeamTaxonomy {
+            allowed_tags: vec![" Finance ".to_string(), "finance".to_string(), "".to_string()],
+            domains: vec!["Sales ops".to_string(), "sales OPS".to_string()],
+            ..Default::default()
+        };
+        normalize_taxonomy(&mut t);
+        assert_eq!(t.allowed_tags, vec!["finance"]);
+        assert_eq!(t.domains, vec!["Sales ops"]);
+    }
+
+    #[test]
+    fn test_merge_proposal_preserves_unknown_fields_and_skips_existing() {
+        let existing = r#"{"allowed_tags":["finance"],"domains":["Sales"],"owner":"data-team"}"#;
+        let (content, tags, domains) = merge_proposal(
+            Some(existing),
+            &["Finance".to_string(), "hr".to_string()],
+            &["sales".to_string(), "Payroll".to_string()],
+        )
+        .unwrap();
+        assert_eq!(tags, vec!["hr"]);
+        assert_eq!(domains, vec!["Payroll"]);
+        let doc: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(doc["owner"], "data-team");
+        assert_eq!(doc["allowed_tags"], serde_json::json!(["finance", "hr"]));
+    }
+
+    #[test]
+    fn test_merge_proposal_rejects_no_op() {
+        let err = merge_proposal(Some(r#"{"allowed_tags":["finance"]}"#), &["finance".to_string()], &[])
+            .unwrap_err();
+        assert!(err.contains("already exist"));
+    }
+
+    #[test]
+    fn test_validate_inner_reads_tags_and_intake_domain() {
+        let conn = crate::commands::test_utils::create_test_db();
+        crate::db::save_workflow_run(&conn, "acme-skill", 0, "pending", "domain").unwrap();
+        crate::db::set_skill_tags(&conn, "acme-skill", &["unknown".to_string()]).unwrap();
+        crate::db::set_skill_intake(&conn, "acme-skill", Some(r#"{"domain":"Payroll"}"#)).unwrap();
+
+        assert!(validate_skill_taxonomy_inner(&conn, None).unwrap().is_empty());
+
+        crate::db::write_team_taxonomy(&conn, &taxonomy()).unwrap();
+        let warnings = validate_skill_taxonomy_inner(&conn, Some("acme-skill")).unwrap();
+        let fields: Vec<&str> = warnings.iter().map(|w| w.field.as_str()).collect();
+        assert_eq!(fields, vec!["tag", "domain"]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_team_taxonomy_normalizes_and_records_source() {
+        use base64::Engine;
+        let mut server = mockito::Server::new_async().await;
+        let file = r#"{"allowed_tags":["Finance"],"domains":["Sales"]}"#;
+        let _mock = server
+            .mock("GET", "/repos/acme/team/contents/.skill-builder/taxonomy.json?ref=main")
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "sha": "deadbeef",
+                    "content": base64::engine::general_purpose::STANDARD.encode(file),
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let info = crate::commands::github_import::parse_github_url_inner("acme/team").unwrap();
+        let client = reqwest::Client::new();
+        let t = fetch_team_taxonomy(&client, &server.url(), &info, "main").await.unwrap();
+        assert_eq!(t.allowed_tags, vec!["finance"]);
+        assert_eq!(t.source_repo.as_deref(), Some("acme/team"));
+        assert_eq!(t.source_sha.as_deref(), Some("deadbeef"));
+        assert!(t.synced_at.is_some());
+    }
+}
@@ -20,7 +20,10 @@ pub fn create_test_db() -> rusqlite::Connection {
             model        TEXT,
             argument_hint TEXT,
             user_invocable INTEGER,
-            disable_model_invocation INTEGER
+            disable_model_invocation INTEGER,
+            review_by    TEXT,
+            reviewed_at  TEXT,
+            package_format TEXT
         );
         CREATE TABLE IF NOT EXISTS workflow_runs (
             id          INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -41,7 +44,8 @@ pub fn create_test_db() -> rusqlite::Connection {
             argument_hint TEXT,
             user_invocable INTEGER DEFAULT 1,
             disable_model_invocation INTEGER DEFAULT 0,
-            skill_id INTEGER REFERENCES skills(id)
+            skill_id INTEGER REFERENCES skills(id),
+            contract_version INTEGER NOT NULL DEFAULT 1
         );
         CREATE TABLE IF NOT EXISTS workflow_steps (
             skill_name TEXT NOT NULL,
@@ -61,12 +65,22 @@ pub fn create_test_db() -> rusqlite::Connection {
             input_tokens INTEGER,
             output_tokens INTEGER,
             total_cost REAL,
+            duration_ms INTEGER,
             session_id TEXT,
             started_at TEXT NOT NULL DEFAULT (datetime('now')),
             completed_at TEXT,
             workflow_run_id INTEGER REFERENCES workflow_runs(id),
+            reset_marker TEXT,
             PRIMARY KEY (agent_id, model)
         );
+        CREATE TABLE IF NOT EXISTS marketplace_catalog_cache (
+            cache_key TEXT PRIMARY KEY,
+            tree_sha TEXT,
+            blob_shas_json TEXT NOT NULL DEFAULT '{}',
+            registry_name TEXT,
+            skills_json TEXT NOT NULL,
+            fetched_at TEXT NOT NULL
+        );
         CREATE TABLE IF NOT EXISTS workflow_artifacts (
             skill_name TEXT NOT NULL,
             step_id INTEGER NOT NULL,
@@ -135,7 +149,328 @@ pub fn create_test_db() -> rusqlite::Connection {
             ended_at TEXT,
             reset_marker TEXT,
             skill_id INTEGER REFERENCES skills(id)
-        );",
+        );
+        CREATE TABLE IF NOT EXISTS trusted_signing_keys (
+            key_id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            public_key TEXT NOT NULL,
+            added_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE TABLE IF NOT EXISTS skill_parameter_sets (
+            skill_name TEXT PRIMARY KEY,
+            source_name TEXT,
+            values_json TEXT NOT NULL,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE TABLE IF NOT EXISTS skill_library_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            skill_name TEXT NOT NULL,
+            source TEXT NOT NULL,
+            event_type TEXT NOT NULL,
+            version TEXT,
+            is_active INTEGER NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE TABLE IF NOT EXISTS activation_decisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            skill_name TEXT NOT NULL,
+            is_active INTEGER NOT NULL,
+            matched_rule TEXT NOT NULL,
+            reason TEXT NOT NULL,
+            overridden_at TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE TABLE IF NOT EXISTS command_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            command TEXT NOT NULL,
+            params_json TEXT NOT NULL,
+            redacted INTEGER NOT NULL DEFAULT 0,
+            outcome TEXT NOT NULL,
+            error TEXT,
+            duration_ms INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE TABLE IF NOT EXISTS skill_benchmarks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            skill_name TEXT NOT NULL,
+            skill_version TEXT,
+            scenario_set TEXT NOT NULL,
+            model TEXT NOT NULL,
+            judge_model TEXT NOT NULL,
+            skill_wins INTEGER NOT NULL DEFAULT 0,
+            baseline_wins INTEGER NOT NULL DEFAULT 0,
+            ties INTEGER NOT NULL DEFAULT 0,
+            avg_skill_score REAL NOT NULL DEFAULT 0,
+            avg_baseline_score REAL NOT NULL DEFAULT 0,
+            token_overhead_pct REAL NOT NULL DEFAULT 0,
+            latency_overhead_pct REAL NOT NULL DEFAULT 0,
+            results_json TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE TABLE IF NOT EXISTS skill_linked_references (
+            skill_name TEXT NOT NULL,
+            reference_path TEXT NOT NULL,
+            repo TEXT NOT NULL,
+            git_ref TEXT NOT NULL,
+            source_path TEXT NOT NULL,
+            synced_sha TEXT NOT NULL,
+            synced_at TEXT NOT NULL,
+            upstream_sha TEXT,
+            checked_at TEXT,
+            PRIMARY KEY (skill_name, reference_path)
+        );
+        CREATE TABLE IF NOT EXISTS step_perf_history (
+            agent_id TEXT NOT NULL,
+            model TEXT NOT NULL,
+            skill_name TEXT NOT NULL,
+            step_id INTEGER NOT NULL,
+            app_version TEXT NOT NULL,
+            prompt_version TEXT,
+            prompt_tokens INTEGER NOT NULL DEFAULT 0,
+            output_tokens INTEGER NOT NULL DEFAULT 0,
+            total_cost REAL NOT NULL DEFAULT 0,
+            time_to_first_token_ms INTEGER,
+            duration_ms INTEGER NOT NULL DEFAULT 0,
+            recorded_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            PRIMARY KEY (agent_id, model)
+        );
+        CREATE TABLE IF NOT EXISTS tool_policies (
+            scope TEXT PRIMARY KEY,
+            policy_json TEXT NOT NULL,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE TABLE IF NOT EXISTS agent_run_ticks (
+            agent_id TEXT PRIMARY KEY,
+            skill_name TEXT NOT NULL,
+            step_id INTEGER NOT NULL,
+            model TEXT NOT NULL,
+            input_tokens INTEGER NOT NULL DEFAULT 0,
+            output_tokens INTEGER NOT NULL DEFAULT 0,
+            cache_read_tokens INTEGER NOT NULL DEFAULT 0,
+            cache_write_tokens INTEGER NOT NULL DEFAULT 0,
+            total_cost REAL NOT NULL DEFAULT 0.0,
+            num_turns INTEGER NOT NULL DEFAULT 0,
+            elapsed_ms INTEGER NOT NULL DEFAULT 0,
+            workflow_session_id TEXT,
+            pid INTEGER NOT NULL,
+            started_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE TABLE IF NOT EXISTS workflow_queue (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            skill_name TEXT NOT NULL,
+            step_id INTEGER NOT NULL,
+            workspace_path TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'queued',
+            error TEXT,
+            pid INTEGER,
+            enqueued_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            started_at TEXT,
+            finished_at TEXT
+        );
+        CREATE TABLE IF NOT EXISTS quality_gate_waivers (
+            skill_name TEXT NOT NULL,
+            check_id TEXT NOT NULL,
+            justification TEXT NOT NULL,
+            waived_by TEXT,
+            waived_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            PRIMARY KEY (skill_name, check_id)
+        );
+        CREATE TABLE IF NOT EXISTS skill_changelog (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            skill_name TEXT NOT NULL,
+            version TEXT NOT NULL,
+            previous_version TEXT,
+            bump TEXT NOT NULL,
+            summary TEXT NOT NULL,
+            previous_sha TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE TABLE IF NOT EXISTS artifact_annotations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            skill_name TEXT NOT NULL,
+            file TEXT NOT NULL,
+            start_line INTEGER NOT NULL,
+            end_line INTEGER NOT NULL,
+            anchor_text TEXT NOT NULL,
+            comment TEXT NOT NULL,
+            author TEXT,
+            orphaned INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE TABLE IF NOT EXISTS step_run_cache (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            skill_name TEXT NOT NULL,
+            step_id INTEGER NOT NULL,
+            fingerprint TEXT NOT NULL,
+            agent_id TEXT NOT NULL,
+            model TEXT NOT NULL,
+            cached INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE TABLE IF NOT EXISTS refine_sessions (
+            session_id TEXT PRIMARY KEY,
+            skill_name TEXT NOT NULL,
+            sdk_session_id TEXT,
+            messages TEXT NOT NULL DEFAULT '[]',
+            pending_diff TEXT,
+            last_agent_id TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            file_reviews TEXT NOT NULL DEFAULT '[]'
+        );
+        CREATE TABLE IF NOT EXISTS skill_upstream_reviews (
+            skill_name TEXT PRIMARY KEY,
+            host TEXT NOT NULL,
+            repo TEXT NOT NULL,
+            repo_url TEXT NOT NULL,
+            branch TEXT NOT NULL,
+            base_branch TEXT NOT NULL,
+            number INTEGER NOT NULL,
+            url TEXT NOT NULL,
+            state TEXT NOT NULL DEFAULT 'open',
+            commit_sha TEXT,
+            pushed_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS skill_dependencies (
+            skill_name TEXT NOT NULL,
+            depends_on TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            PRIMARY KEY (skill_name, depends_on)
+        );
+        CREATE TABLE IF NOT EXISTS skill_test_cases (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            skill_name TEXT NOT NULL,
+            name TEXT NOT NULL,
+            prompt TEXT NOT NULL,
+            expect_trigger INTEGER NOT NULL DEFAULT 1,
+            expected_contains TEXT NOT NULL DEFAULT '[]',
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE TABLE IF NOT EXISTS skill_test_results (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            test_case_id INTEGER NOT NULL,
+            skill_name TEXT NOT NULL,
+            run_id TEXT NOT NULL,
+            skill_version TEXT,
+            triggered INTEGER NOT NULL,
+            passed INTEGER NOT NULL,
+            response TEXT NOT NULL,
+            failures TEXT NOT NULL DEFAULT '[]',
+            error TEXT,
+            duration_ms INTEGER NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE TABLE IF NOT EXISTS agent_tool_calls (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            agent_id TEXT NOT NULL,
+            skill_name TEXT NOT NULL,
+            step_id INTEGER NOT NULL,
+            tool_use_id TEXT NOT NULL,
+            tool_name TEXT NOT NULL,
+            input_summary TEXT NOT NULL DEFAULT '',
+            allowed INTEGER,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            UNIQUE(agent_id, tool_use_id)
+        );
+        CREATE TABLE IF NOT EXISTS skill_push_bases (
+            skill_name TEXT NOT NULL,
+            repo_url TEXT NOT NULL,
+            skill_path TEXT NOT NULL,
+            content TEXT NOT NULL,
+            commit_sha TEXT,
+            pushed_at TEXT NOT NULL,
+            PRIMARY KEY (skill_name, repo_url)
+        );
+        CREATE TABLE IF NOT EXISTS skill_generation_bases (
+            skill_name TEXT PRIMARY KEY,
+            decisions_hash TEXT NOT NULL,
+            decisions_json TEXT NOT NULL,
+            generated_at TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS skill_invocations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            event_id TEXT NOT NULL UNIQUE,
+            skill_name TEXT NOT NULL,
+            triggered_by TEXT NOT NULL,
+            session_id TEXT,
+            project_path TEXT,
+            invoked_at TEXT NOT NULL,
+            imported_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE INDEX IF NOT EXISTS idx_skill_invocations_skill ON skill_invocations(skill_name, invoked_at);
+        CREATE TABLE IF NOT EXISTS agent_run_checkpoints (
+            agent_id TEXT PRIMARY KEY,
+            skill_name TEXT NOT NULL,
+            step_id INTEGER NOT NULL,
+            pid INTEGER NOT NULL,
+            sdk_session_id TEXT,
+            transcript_path TEXT,
+            turns INTEGER NOT NULL DEFAULT 0,
+            status TEXT NOT NULL DEFAULT 'running',
+            resumed_from TEXT,
+            started_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE TABLE IF NOT EXISTS skill_scan_reports (
+            skill_name TEXT PRIMARY KEY,
+            flagged INTEGER NOT NULL,
+            report_json TEXT NOT NULL,
+            scanned_at TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS agent_transcripts (
+            agent_id TEXT PRIMARY KEY,
+            skill_name TEXT NOT NULL,
+            content BLOB NOT NULL,
+            uncompressed_bytes INTEGER NOT NULL,
+            compressed_bytes INTEGER NOT NULL,
+            message_count INTEGER NOT NULL,
+            tool_call_count INTEGER NOT NULL,
+            access_seq INTEGER NOT NULL,
+            archived_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE TABLE IF NOT EXISTS refine_batches (
+            batch_id TEXT PRIMARY KEY,
+            instruction TEXT NOT NULL,
+            workspace_path TEXT NOT NULL,
+            workflow_session_id TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE TABLE IF NOT EXISTS refine_batch_items (
+            batch_id TEXT NOT NULL REFERENCES refine_batches(batch_id) ON DELETE CASCADE,
+            skill_name TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            status TEXT NOT NULL DEFAULT 'queued',
+            agent_id TEXT,
+            diff TEXT,
+            error TEXT,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            PRIMARY KEY (batch_id, skill_name)
+        );
+        CREATE TABLE IF NOT EXISTS tag_aliases (
+            alias TEXT PRIMARY KEY,
+            tag TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE TABLE IF NOT EXISTS audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            instance_id TEXT NOT NULL,
+            actor TEXT,
+            action TEXT NOT NULL,
+            skill_name TEXT,
+            summary TEXT NOT NULL
+        );
+        CREATE TRIGGER IF NOT EXISTS audit_log_no_update BEFORE UPDATE ON audit_log
+        BEGIN
+            SELECT RAISE(ABORT, 'audit_log is append-only');
+        END;
+        CREATE TRIGGER IF NOT EXISTS audit_log_no_delete BEFORE DELETE ON audit_log
+        BEGIN
+            SELECT RAISE(ABORT, 'audit_log is append-only');
+        END;",
     )
     .unwrap();
     conn
@@ -0,0 +1,238 @@
+//! Tool policies for agent runs.
+//!
+//! A policy is stored globally and optionally per skill; a skill's own policy
+//! replaces the global one entirely rather than merging with it. When the Bash
+//! sandbox is enabled, the sidecar checks every command against the allow/deny
+//! patterns, refuses network commands unless allowed, keeps paths inside the
+//! skill's directories, and logs each decision to the agent's transcript.
+//!
+//! Step roles trim the tools a workflow step declares before its
+//! `SidecarConfig` is built: `no_shell` drops Bash and `read_only` keeps only
+//! the file-reading tools. Trimmed tools are also sent as `disallowedTools`,
+//! since `allowedTools` does not stop a tool under `bypassPermissions`. Every
+//! tool call an agent makes is recorded by `agents::tool_audit`.
+
+use crate::agents::sidecar::BashSandboxConfig;
+use crate::db::{Db, GLOBAL_TOOL_POLICY_SCOPE};
+use crate::types::{AgentToolCall, ToolPolicy, ToolPolicyView};
+
+pub(crate) const ROLE_FULL: &str = "full";
+pub(crate) const ROLE_NO_SHELL: &str = "no_shell";
+pub(crate) const ROLE_READ_ONLY: &str = "read_only";
+
+const SHELL_TOOLS: &[&str] = &["Bash"];
+const READ_ONLY_TOOLS: &[&str] = &["Read", "Glob", "Grep"];
+
+pub(crate) fn validate_policy(policy: &ToolPolicy) -> Result<(), String> {
+    for (step_id, role) in &policy.step_roles {
+        if ![ROLE_FULL, ROLE_NO_SHELL, ROLE_READ_ONLY].contains(&role.as_str()) {
+            return Err(format!(
+                "Invalid tool role '{}' for step {}: expected full, no_shell or read_only",
+                role, step_id
+            ));
+        }
+    }
+    let sandbox = &policy.bash_sandbox;
+    for pattern in sandbox.allow_patterns.iter().chain(&sandbox.deny_patterns) {
+        if pattern.trim().is_empty() {
+            return Err("Bash sandbox patterns cannot be empty".to_string());
+        }
+        if pattern.contains('\n') {
+            return Err(format!("Bash sandbox pattern '{}' must be a single line", pattern.trim()));
+        }
+    }
+    Ok(())
+}
+
+fn load_view(conn: &rusqlite::Connection, skill_name: Option<&str>) -> Result<ToolPolicyView, String> {
+    let global = crate::db::get_tool_policy(conn, GLOBAL_TOOL_POLICY_SCOPE)?.unwrap_or_default();
+    let skill_override = match skill_name {
+        Some(name) => crate::db::get_tool_policy(conn, name)?,
+        None => None,
+    };
+    let effective = skill_override.clone().unwrap_or_else(|| global.clone());
+    Ok(ToolPolicyView { global, skill_override, effective })
+}
+
+/// Sidecar Bash sandbox for a run of `skill_name` confined to `allowed_dirs`,
+/// or `None` when the effective policy leaves Bash unrestricted. Bash
+/// commands start in the first of `allowed_dirs`.
+pub(crate) fn bash_sandbox_for_run(
+    conn: &rusqlite::Connection,
+    skill_name: &str,
+    allowed_dirs: &[&str],
+) -> Result<Option<BashSandboxConfig>, String> {
+    let sandbox = load_view(conn, Some(skill_name))?.effective.bash_sandbox;
+    if !sandbox.enabled {
+        return Ok(None);
+    }
+    Ok(Some(BashSandboxConfig {
+        allow_patterns: sandbox.allow_patterns,
+        deny_patterns: sandbox.deny_patterns,
+        allow_network: sandbox.allow_network,
+        allowed_dirs: allowed_dirs.iter().map(|d| d.to_string()).collect(),
+        cwd: allowed_dirs.first().map(|d| d.to_string()).unwrap_or_default(),
+    }))
+}
+
+/// Role of `step_id` under the effective policy for `skill_name`.
+pub(crate) fn step_tool_role(
+    conn: &rusqlite::Connection,
+    skill_name: &str,
+    step_id: u32,
+) -> Result<String, String> {
+    let policy = load_view(conn, Some(skill_name))?.effective;
+    Ok(policy
+        .step_roles
+        .get(&step_id)
+        .cloned()
+        .unwrap_or_else(|| ROLE_FULL.to_string()))
+}
+
+/// Split a step's declared tools into those `role` keeps and those it removes.
+pub(crate) fn trim_tools(tools: Vec<String>, role: &str) -> (Vec<String>, Vec<String>) {
+    tools.into_iter().partition(|tool| match role {
+        ROLE_NO_SHELL => !SHELL_TOOLS.contains(&tool.as_str()),
+        ROLE_READ_ONLY => READ_ONLY_TOOLS.contains(&tool.as_str()),
+        _ => true,
+    })
+}
+
+#[tauri::command]
+pub fn get_tool_policy(
+    skill_name: Option<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<ToolPolicyView, String> {
+    log::info!("[get_tool_policy] skill={:?}", skill_name);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[get_tool_policy] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    load_view(&conn, skill_name.as_deref())
+}
+
+/// Set the global policy (`skill_name` = `None`) or a skill's own policy.
+/// Passing `None` as the policy resets the global one to defaults or makes the
+/// skill inherit the global one again.
+#[tauri::command]
+pub fn set_tool_policy(
+    skill_name: Option<String>,
+    policy: Option<ToolPolicy>,
+    db: tauri::State<'_, Db>,
+) -> Result<ToolPolicyView, String> {
+    log::info!(
+        "[set_tool_policy] skill={:?} bash_sandbox={:?}",
+        skill_name,
+        policy.as_ref().map(|p| p.bash_sandbox.enabled)
+    );
+    if let Some(name) = skill_name.as_deref() {
+        super::observer::ensure_writable(name)?;
+    }
+    if let Some(ref p) = policy {
+        validate_policy(p).map_err(|e| {
+            log::error!("[set_tool_policy] {}", e);
+            e
+        })?;
+    }
+    if let Some(ref name) = skill_name {
+        super::imported_skills::validate_skill_name(name)?;
+    }
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[set_tool_policy] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let scope = skill_name.as_deref().unwrap_or(GLOBAL_TOOL_POLICY_SCOPE);
+    crate::db::set_tool_policy(&conn, scope, policy.as_ref())?;
+    load_view(&conn, skill_name.as_deref())
+}
+
+/// Audit log of the tool calls one agent run made, in call order.
+#[tauri::command]
+pub fn get_agent_tool_calls(
+    agent_id: String,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<AgentToolCall>, String> {
+    log::info!("[get_agent_tool_calls] agent={}", agent_id);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[get_agent_tool_calls] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::list_agent_tool_calls(&conn, &agent_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::create_test_db;
+    use crate::types::BashSandboxPolicy;
+
+    fn sandboxed(allow: &[&str]) -> ToolPolicy {
+        ToolPolicy {
+            bash_sandbox: BashSandboxPolicy {
+                enabled: true,
+                allow_patterns: allow.iter().map(|s| s.to_string()).collect(),
+                deny_patterns: vec!["sudo *".to_string()],
+                allow_network: false,
+            },
+            ..ToolPolicy::default()
+        }
+    }
+
+    #[test]
+    fn test_skill_policy_replaces_global() {
+        let conn = create_test_db();
+        assert!(bash_sandbox_for_run(&conn, "my-skill", &["/w/my-skill"]).unwrap().is_none());
+
+        crate::db::set_tool_policy(&conn, GLOBAL_TOOL_POLICY_SCOPE, Some(&sandboxed(&["ls *"]))).unwrap();
+        let global = bash_sandbox_for_run(&conn, "my-skill", &["/w/my-skill"]).unwrap().unwrap();
+        assert_eq!(global.allow_patterns, vec!["ls *".to_string()]);
+        assert_eq!(global.allowed_dirs, vec!["/w/my-skill".to_string()]);
+        assert_eq!(global.cwd, "/w/my-skill");
+        assert!(!global.allow_network);
+
+        crate::db::set_tool_policy(&conn, "my-skill", Some(&ToolPolicy::default())).unwrap();
+        assert!(bash_sandbox_for_run(&conn, "my-skill", &["/w/my-skill"]).unwrap().is_none());
+        assert!(bash_sandbox_for_run(&conn, "other-skill", &["/w/other-skill"]).unwrap().is_some());
+
+        crate::db::set_tool_policy(&conn, "my-skill", None).unwrap();
+        let view = load_view(&conn, Some("my-skill")).unwrap();
+        assert!(view.skill_override.is_none());
+        assert_eq!(view.effective, view.global);
+    }
+
+    #[test]
+    fn test_validate_policy_rejects_blank_patterns() {
+        assert!(validate_policy(&sandboxed(&["git status*"])).is_ok());
+        assert!(validate_policy(&sandboxed(&["  "])).is_err());
+        assert!(validate_policy(&sandboxed(&["ls\nrm -rf /"])).is_err());
+    }
+
+    #[test]
+    fn test_step_roles_trim_declared_tools() {
+        let conn = create_test_db();
+        let tools = |names: &[&str]| names.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let declared = tools(&["Read", "Write", "Glob", "Grep", "Bash", "Task"]);
+
+        // Default table: research steps lose Bash, Confirm Decisions is read-only.
+        assert_eq!(step_tool_role(&conn, "my-skill", 0).unwrap(), ROLE_NO_SHELL);
+        assert_eq!(step_tool_role(&conn, "my-skill", 2).unwrap(), ROLE_READ_ONLY);
+        assert_eq!(step_tool_role(&conn, "my-skill", 7).unwrap(), ROLE_FULL);
+
+        let (kept, removed) = trim_tools(declared.clone(), ROLE_NO_SHELL);
+        assert_eq!(kept, tools(&["Read", "Write", "Glob", "Grep", "Task"]));
+        assert_eq!(removed, tools(&["Bash"]));
+        let (kept, removed) = trim_tools(declared.clone(), ROLE_READ_ONLY);
+        assert_eq!(kept, tools(&["Read", "Glob", "Grep"]));
+        assert_eq!(removed, tools(&["Write", "Bash", "Task"]));
+        assert_eq!(trim_tools(declared.clone(), ROLE_FULL).0, declared);
+
+        let mut policy = ToolPolicy::default();
+        policy.step_roles.insert(3, ROLE_READ_ONLY.to_string());
+        crate::db::set_tool_policy(&conn, "my-skill", Some(&policy)).unwrap();
+        assert_eq!(step_tool_role(&conn, "my-skill", 3).unwrap(), ROLE_READ_ONLY);
+        assert_eq!(step_tool_role(&conn, "other-skill", 3).unwrap(), ROLE_FULL);
+
+        policy.step_roles.insert(3, "admin".to_string());
+        assert!(validate_policy(&policy).is_err());
+    }
+}
@@ -0,0 +1,257 @@
+use std::path::{Path, PathBuf};
+
+use super::imported_skills::validate_skill_name;
+use crate::db::Db;
+use crate::transcripts;
+use crate::types::{AgentTranscript, TranscriptInfo, TranscriptSearchHit, TranscriptTurns};
+
+/// Largest number of turns returned by one `read_transcript_turns` call.
+const MAX_TURNS_PER_READ: usize = 200;
+/// Default and largest number of hits returned by `search_transcripts`.
+const DEFAULT_SEARCH_HITS: usize = 50;
+const MAX_SEARCH_HITS: usize = 500;
+
+fn logs_dir(workspace_path: &str, skill_name: &str) -> PathBuf {
+    Path::new(workspace_path).join(skill_name).join("logs")
+}
+
+fn list_transcripts_inner(logs_dir: &Path) -> Result<Vec<TranscriptInfo>, String> {
+    if !logs_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut infos: Vec<TranscriptInfo> = std::fs::read_dir(logs_dir)
+        .map_err(|e| format!("Failed to read transcript dir {}: {}", logs_dir.display(), e))?
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if !transcripts::is_transcript(&file_name) {
+                return None;
+            }
+            let size_bytes = entry.metadata().ok()?.len();
+            let compressed = file_name.ends_with(transcripts::COMPRESSED_EXT);
+            let index = compressed
+                .then(|| transcripts::read_index(&entry.path()).ok())
+                .flatten();
+            Some(TranscriptInfo {
+                file_name,
+                compressed,
+                size_bytes,
+                uncompressed_bytes: index.as_ref().map(|i| i.uncompressed_bytes),
+                turns: index.as_ref().map(|i| i.turns.len()),
+            })
+        })
+        .collect();
+    // Newest first by the timestamp suffix
+    infos.sort_by(|a, b| {
+        let ts = |name: &str| {
+            let stem = transcripts::transcript_stem(name);
+            stem.get(stem.len().saturating_sub(19)..).unwrap_or(stem).to_string()
+        };
+        ts(&b.file_name).cmp(&ts(&a.file_name)).then_with(|| a.file_name.cmp(&b.file_name))
+    });
+    Ok(infos)
+}
+
+fn read_transcript_turns_inner(
+    logs_dir: &Path,
+    file_name: &str,
+    start_turn: usize,
+    limit: usize,
+) -> Result<TranscriptTurns, String> {
+    if !transcripts::is_transcript(file_name) || file_name.contains(['/', '\\']) {
+        return Err(format!("Invalid transcript name: {}", file_name));
+    }
+    let (total_turns, turns) =
+        transcripts::read_turns(&logs_dir.join(file_name), start_turn, limit.min(MAX_TURNS_PER_READ))?;
+    let turns = turns
+        .into_iter()
+        .map(|lines| {
+            lines
+                .into_iter()
+                .map(|line| serde_json::from_str(&line).unwrap_or(serde_json::Value::String(line)))
+                .collect()
+        })
+        .collect();
+    Ok(TranscriptTurns {
+        file_name: file_name.to_string(),
+        total_turns,
+        start_turn,
+        turns,
+    })
+}
+
+/// Transcripts on disk for a skill, newest first, with compressed and
+/// decompressed sizes.
+#[tauri::command]
+pub fn list_transcripts(workspace_path: String, skill_name: String) -> Result<Vec<TranscriptInfo>, String> {
+    log::info!("[list_transcripts] skill={}", skill_name);
+    validate_skill_name(&skill_name)?;
+    list_transcripts_inner(&logs_dir(&workspace_path, &skill_name)).map_err(|e| {
+        log::error!("[list_transcripts] {}", e);
+        e
+    })
+}
+
+/// A page of turns from one transcript, for replay. Compressed transcripts
+/// only decompress the requested turns.
+#[tauri::command]
+pub fn read_transcript_turns(
+    workspace_path: String,
+    skill_name: String,
+    file_name: String,
+    start_turn: usize,
+    limit: usize,
+) -> Result<TranscriptTurns, String> {
+    log::info!(
+        "[read_transcript_turns] skill={} file={} start={} limit={}",
+        skill_name,
+        file_name,
+        start_turn,
+        limit
+    );
+    validate_skill_name(&skill_name)?;
+    read_transcript_turns_inner(&logs_dir(&workspace_path, &skill_name), &file_name, start_turn, limit).map_err(|e| {
+        log::error!("[read_transcript_turns] {}", e);
+        e
+    })
+}
+
+fn search_transcripts_inner(
+    conn: &rusqlite::Connection,
+    query: &str,
+    skill_name: Option<&str>,
+    limit: usize,
+) -> Result<Vec<TranscriptSearchHit>, String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut hits = Vec::new();
+    crate::db::for_each_agent_transcript(conn, skill_name, |agent_id, skill, archived_at, content| {
+        match transcripts::unpack_archive(content) {
+            Ok(messages) => {
+                for (message_index, message_type, snippet) in transcripts::search_messages(&messages, query) {
+                    if hits.len() == limit {
+                        break;
+                    }
+                    hits.push(TranscriptSearchHit {
+                        agent_id: agent_id.to_string(),
+                        skill_name: skill.to_string(),
+                        archived_at: archived_at.to_string(),
+                        message_index,
+                        message_type,
+                        snippet,
+                    });
+                }
+            }
+            Err(e) => log::warn!("[search_transcripts] skipping '{}': {}", agent_id, e),
+        }
+        hits.len() < limit
+    })?;
+    Ok(hits)
+}
+
+/// A finished agent run's archived transcript, or `None` if it was never
+/// archived or has been evicted.
+#[tauri::command]
+pub fn get_agent_transcript(agent_id: String, db: tauri::State<'_, Db>) -> Result<Option<AgentTranscript>, String> {
+    log::info!("[get_agent_transcript] agent={}", agent_id);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[get_agent_transcript] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::get_agent_transcript(&conn, &agent_id).map_err(|e| {
+        log::error!("[get_agent_transcript] {}", e);
+        e
+    })
+}
+
+/// Messages in archived transcripts containing `query` (case-insensitive),
+/// newest run first, optionally limited to one skill.
+#[tauri::command]
+pub fn search_transcripts(
+    query: String,
+    skill_name: Option<String>,
+    limit: Option<usize>,
+    db: tauri::State<'_, Db>,
+) -> Result<Vec<TranscriptSearchHit>, String> {
+    log::info!("[search_transcripts] query_len={} skill={:?}", query.len(), skill_name);
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[search_transcripts] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_HITS).clamp(1, MAX_SEARCH_HITS);
+    search_transcripts_inner(&conn, &query, skill_name.as_deref(), limit).map_err(|e| {
+        log::error!("[search_transcripts] {}", e);
+        e
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const TRANSCRIPT: &str = concat!(
+        "{\"type\":\"config\",\"config\":{\"prompt\":\"go\"}}\n",
+        "{\"type\":\"assistant\",\"message\":{\"id\":\"m1\",\"content\":[]}}\n",
+        "not json\n",
+        "{\"type\":\"assistant\",\"message\":{\"id\":\"m2\",\"content\":[]}}\n",
+        "{\"type\":\"result\",\"subtype\":\"success\"}\n",
+    );
+
+    #[test]
+    fn test_list_and_read_compressed_and_plain_transcripts() {
+        let tmp = tempdir().unwrap();
+        let logs = tmp.path().join("logs");
+        std::fs::create_dir_all(&logs).unwrap();
+        std::fs::write(logs.join("step0-2026-01-01T00-00-00.jsonl"), TRANSCRIPT).unwrap();
+        transcripts::compress_transcript(&logs.join("step0-2026-01-01T00-00-00.jsonl")).unwrap();
+        std::fs::write(logs.join("step1-2026-01-02T00-00-00.jsonl"), TRANSCRIPT).unwrap();
+        std::fs::write(logs.join("notes.md"), "x").unwrap();
+
+        let infos = list_transcripts_inner(&logs).unwrap();
+        let names: Vec<&str> = infos.iter().map(|i| i.file_name.as_str()).collect();
+        assert_eq!(names, vec!["step1-2026-01-02T00-00-00.jsonl", "step0-2026-01-01T00-00-00.jsonl.zst"]);
+        assert!(!infos[0].compressed && infos[0].turns.is_none());
+        assert!(infos[1].compressed);
+        assert_eq!(infos[1].turns, Some(3));
+        assert_eq!(infos[1].uncompressed_bytes, Some(TRANSCRIPT.len() as u64));
+
+        for name in ["step0-2026-01-01T00-00-00.jsonl.zst", "step1-2026-01-02T00-00-00.jsonl"] {
+            let page = read_transcript_turns_inner(&logs, name, 1, 1).unwrap();
+            assert_eq!(page.total_turns, 3);
+            assert_eq!(page.turns.len(), 1);
+            assert_eq!(page.turns[0][0]["message"]["id"], "m1");
+            assert_eq!(page.turns[0][1], serde_json::Value::String("not json".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_search_archived_transcripts() {
+        let conn = crate::commands::test_utils::create_test_db();
+        let lines: Vec<String> = TRANSCRIPT.lines().map(str::to_string).collect();
+        let archived = transcripts::pack_for_archive(&lines).unwrap();
+        crate::db::save_agent_transcript(&conn, "sales-step0-1", "sales", &archived).unwrap();
+        crate::db::save_agent_transcript(&conn, "hr-step0-1", "hr", &archived).unwrap();
+
+        let hits = search_transcripts_inner(&conn, "SUCCESS", None, 10).unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].message_index, 4);
+        assert_eq!(hits[0].message_type, "result");
+
+        let hits = search_transcripts_inner(&conn, "not json", Some("sales"), 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].agent_id.as_str(), hits[0].message_type.as_str()), ("sales-step0-1", "text"));
+
+        assert_eq!(search_transcripts_inner(&conn, "success", None, 1).unwrap().len(), 1);
+        assert!(search_transcripts_inner(&conn, "  ", None, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_rejects_paths_outside_logs() {
+        let tmp = tempdir().unwrap();
+        assert!(read_transcript_turns_inner(tmp.path(), "../secret.jsonl", 0, 10).is_err());
+        assert!(read_transcript_turns_inner(tmp.path(), "notes.md", 0, 10).is_err());
+    }
+}
@@ -1,5 +1,5 @@
 use crate::db::Db;
-use crate::types::{AgentRunRecord, UsageByDay, UsageByModel, UsageByStep, UsageSummary, WorkflowSessionRecord};
+use crate::types::{AgentRunRecord, BudgetStatus, SkillAnalytics, UsageByDay, UsageByModel, UsageByStep, UsageSummary, WorkflowSessionRecord};
 
 #[tauri::command]
 #[allow(clippy::too_many_arguments)]
@@ -23,6 +23,7 @@ pub fn persist_agent_run(
     compaction_count: i32,
     session_id: Option<String>,
     workflow_session_id: Option<String>,
+    time_to_first_token_ms: Option<i64>,
 ) -> Result<(), String> {
     log::info!("[persist_agent_run] agent={} skill={} step={} model={} status={}", agent_id, skill_name, step_id, model, status);
     let conn = db.0.lock().map_err(|e| {
@@ -35,13 +36,131 @@ pub fn persist_agent_run(
         total_cost, duration_ms, num_turns, stop_reason.as_deref(), duration_api_ms,
         tool_use_count, compaction_count,
         session_id.as_deref(), workflow_session_id.as_deref(),
-    )
+    )?;
+    let retries = crate::agents::sidecar::retry_count(&agent_id);
+    if retries > 0 {
+        crate::db::set_agent_run_retry_count(&conn, &agent_id, retries)?;
+    }
+    let total_cost = if clear_cost_when_offline(&conn, &agent_id)? { 0.0 } else { total_cost };
+    let prompt_tokens = input_tokens as i64 + cache_read_tokens as i64 + cache_write_tokens as i64;
+    if let Err(e) = super::step_perf::record_completed_step(
+        &conn, &agent_id, &skill_name, step_id, &model, &status,
+        prompt_tokens, output_tokens as i64, total_cost, time_to_first_token_ms, duration_ms,
+    ) {
+        log::warn!("[persist_agent_run] Failed to record step performance: {}", e);
+    }
+    Ok(())
+}
+
+/// Local model runs have no price: keep the tokens, store the cost as NULL.
+/// Returns whether the cost was cleared.
+fn clear_cost_when_offline(conn: &rusqlite::Connection, agent_id: &str) -> Result<bool, String> {
+    let unpriced = crate::db::read_settings(conn)
+        .map(|s| super::offline::local_target(&s).is_some())
+        .unwrap_or(false);
+    if unpriced {
+        crate::db::clear_agent_run_cost(conn, agent_id)?;
+    }
+    Ok(unpriced)
+}
+
+/// Token and cost rows of a `result` message as
+/// `(model, input, output, cache read, cache write, cost)`.
+fn usage_rows(result: &serde_json::Value, model: &str) -> Vec<(String, i32, i32, i32, i32, f64)> {
+    let int = |v: &serde_json::Value| v.as_i64().unwrap_or(0) as i32;
+    match result["modelUsage"].as_object() {
+        Some(by_model) if !by_model.is_empty() => by_model
+            .iter()
+            .map(|(id, mu)| {
+                (
+                    id.clone(),
+                    int(&mu["inputTokens"]),
+                    int(&mu["outputTokens"]),
+                    int(&mu["cacheReadInputTokens"]),
+                    int(&mu["cacheCreationInputTokens"]),
+                    mu["costUSD"].as_f64().or_else(|| mu["cost"].as_f64()).unwrap_or(0.0),
+                )
+            })
+            .collect(),
+        _ => vec![(
+            model.to_string(),
+            int(&result["usage"]["input_tokens"]),
+            int(&result["usage"]["output_tokens"]),
+            int(&result["usage"]["cache_read_input_tokens"]),
+            int(&result["usage"]["cache_creation_input_tokens"]),
+            result["total_cost_usd"].as_f64().unwrap_or(0.0),
+        )],
+    }
+}
+
+/// Record usage for a backend-driven agent run from its `result` message: one
+/// row per model in `modelUsage`, or a single `model` row from the aggregate
+/// `usage` when it is absent. Runs without a result record nothing.
+pub(crate) fn record_result_usage(
+    conn: &rusqlite::Connection,
+    agent_id: &str,
+    skill_name: &str,
+    step_id: i32,
+    model: &str,
+    workflow_session_id: &str,
+    messages: &[serde_json::Value],
+) -> Result<(), String> {
+    let Some(result) = messages.iter().rev().find(|m| m["type"] == "result") else {
+        return Ok(());
+    };
+    let subtype = result["subtype"].as_str().unwrap_or("success");
+    let status = if result["is_error"].as_bool().unwrap_or(false) || subtype.starts_with("error_") {
+        "error"
+    } else {
+        "completed"
+    };
+    let tool_use_count = messages
+        .iter()
+        .filter(|m| m["type"] == "assistant")
+        .filter_map(|m| m["message"]["content"].as_array())
+        .flatten()
+        .filter(|block| block["type"] == "tool_use")
+        .count() as i32;
+    let compaction_count = messages
+        .iter()
+        .filter(|m| m["type"] == "system" && m["subtype"] == "compact_boundary")
+        .count() as i32;
+    let int = |v: &serde_json::Value| v.as_i64().unwrap_or(0) as i32;
+    for (model, input, output, cache_read, cache_write, cost) in usage_rows(result, model) {
+        crate::db::persist_agent_run(
+            conn,
+            agent_id,
+            skill_name,
+            step_id,
+            &model,
+            status,
+            input,
+            output,
+            cache_read,
+            cache_write,
+            cost,
+            result["duration_ms"].as_i64().unwrap_or(0),
+            int(&result["num_turns"]),
+            result["stop_reason"].as_str(),
+            result["duration_api_ms"].as_i64(),
+            tool_use_count,
+            compaction_count,
+            result["session_id"].as_str(),
+            Some(workflow_session_id),
+        )?;
+    }
+    let retries = crate::agents::sidecar::retry_count(agent_id);
+    if retries > 0 {
+        crate::db::set_agent_run_retry_count(conn, agent_id, retries)?;
+    }
+    clear_cost_when_offline(conn, agent_id)?;
+    Ok(())
 }
 
 #[tauri::command]
 pub fn get_usage_summary(db: tauri::State<'_, Db>, hide_cancelled: bool, start_date: Option<String>, skill_name: Option<String>) -> Result<UsageSummary, String> {
     log::info!("[get_usage_summary] hide_cancelled={} start_date={:?} skill_name={:?}", hide_cancelled, start_date, skill_name);
-    let conn = db.0.lock().map_err(|e| {
+    let conn = db.0.read().map_err(|e| {
         log::error!("[get_usage_summary] Failed to acquire DB lock: {}", e);
         e.to_string()
     })?;
@@ -51,7 +170,7 @@ pub fn get_usage_summary(db: tauri::State<'_, Db>, hide_cancelled: bool, start_d
 #[tauri::command]
 pub fn get_workflow_skill_names(db: tauri::State<'_, Db>) -> Result<Vec<String>, String> {
     log::info!("[get_workflow_skill_names]");
-    let conn = db.0.lock().map_err(|e| {
+    let conn = db.0.read().map_err(|e| {
         log::error!("[get_workflow_skill_names] Failed to acquire DB lock: {}", e);
         e.to_string()
     })?;
@@ -64,7 +183,7 @@ pub fn get_recent_runs(
     limit: usize,
 ) -> Result<Vec<AgentRunRecord>, String> {
     log::info!("[get_recent_runs] limit={}", limit);
-    let conn = db.0.lock().map_err(|e| {
+    let conn = db.0.read().map_err(|e| {
         log::error!("[get_recent_runs] Failed to acquire DB lock: {}", e);
         e.to_string()
     })?;
@@ -74,7 +193,7 @@ pub fn get_recent_runs(
 #[tauri::command]
 pub fn get_usage_by_step(db: tauri::State<'_, Db>, hide_cancelled: bool, start_date: Option<String>, skill_name: Option<String>) -> Result<Vec<UsageByStep>, String> {
     log::info!("[get_usage_by_step] hide_cancelled={} start_date={:?} skill_name={:?}", hide_cancelled, start_date, skill_name);
-    let conn = db.0.lock().map_err(|e| {
+    let conn = db.0.read().map_err(|e| {
         log::error!("[get_usage_by_step] Failed to acquire DB lock: {}", e);
         e.to_string()
     })?;
@@ -84,7 +203,7 @@ pub fn get_usage_by_step(db: tauri::State<'_, Db>, hide_cancelled: bool, start_d
 #[tauri::command]
 pub fn get_usage_by_model(db: tauri::State<'_, Db>, hide_cancelled: bool, start_date: Option<String>, skill_name: Option<String>) -> Result<Vec<UsageByModel>, String> {
     log::info!("[get_usage_by_model] hide_cancelled={} start_date={:?} skill_name={:?}", hide_cancelled, start_date, skill_name);
-    let conn = db.0.lock().map_err(|e| {
+    let conn = db.0.read().map_err(|e| {
         log::error!("[get_usage_by_model] Failed to acquire DB lock: {}", e);
         e.to_string()
     })?;
@@ -94,13 +213,24 @@ pub fn get_usage_by_model(db: tauri::State<'_, Db>, hide_cancelled: bool, start_
 #[tauri::command]
 pub fn get_usage_by_day(db: tauri::State<'_, Db>, hide_cancelled: bool, start_date: Option<String>, skill_name: Option<String>) -> Result<Vec<UsageByDay>, String> {
     log::info!("[get_usage_by_day] hide_cancelled={} start_date={:?} skill_name={:?}", hide_cancelled, start_date, skill_name);
-    let conn = db.0.lock().map_err(|e| {
+    let conn = db.0.read().map_err(|e| {
         log::error!("[get_usage_by_day] Failed to acquire DB lock: {}", e);
         e.to_string()
     })?;
     crate::db::get_usage_by_day(&conn, hide_cancelled, start_date.as_deref(), skill_name.as_deref())
 }
 
+/// Per-skill cost, refine, duration and cache aggregates; all skills when `skill_name` is None.
+#[tauri::command]
+pub fn get_skill_analytics(db: tauri::State<'_, Db>, skill_name: Option<String>) -> Result<Vec<SkillAnalytics>, String> {
+    log::info!("[get_skill_analytics] skill_name={:?}", skill_name);
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[get_skill_analytics] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::get_skill_analytics(&conn, skill_name.as_deref())
+}
+
 #[tauri::command]
 pub fn reset_usage(db: tauri::State<'_, Db>) -> Result<(), String> {
     log::info!("[reset_usage]");
@@ -120,7 +250,7 @@ pub fn get_recent_workflow_sessions(
     skill_name: Option<String>,
 ) -> Result<Vec<WorkflowSessionRecord>, String> {
     log::info!("[get_recent_workflow_sessions] limit={} hide_cancelled={} start_date={:?} skill_name={:?}", limit, hide_cancelled, start_date, skill_name);
-    let conn = db.0.lock().map_err(|e| {
+    let conn = db.0.read().map_err(|e| {
         log::error!("[get_recent_workflow_sessions] Failed to acquire DB lock: {}", e);
         e.to_string()
     })?;
@@ -133,7 +263,7 @@ pub fn get_session_agent_runs(
     session_id: String,
 ) -> Result<Vec<AgentRunRecord>, String> {
     log::info!("[get_session_agent_runs] session=[REDACTED]");
-    let conn = db.0.lock().map_err(|e| {
+    let conn = db.0.read().map_err(|e| {
         log::error!("[get_session_agent_runs] Failed to acquire DB lock: {}", e);
         e.to_string()
     })?;
@@ -150,7 +280,7 @@ pub fn get_agent_runs(
     limit: usize,
 ) -> Result<Vec<AgentRunRecord>, String> {
     log::info!("[get_agent_runs] hide_cancelled={} start_date={:?} skill_name={:?} model_family={:?} limit={}", hide_cancelled, start_date, skill_name, model_family, limit);
-    let conn = db.0.lock().map_err(|e| {
+    let conn = db.0.read().map_err(|e| {
         log::error!("[get_agent_runs] Failed to acquire DB lock: {}", e);
         e.to_string()
     })?;
@@ -164,9 +294,131 @@ pub fn get_step_agent_runs(
     step_id: i32,
 ) -> Result<Vec<AgentRunRecord>, String> {
     log::info!("[get_step_agent_runs] skill={} step={}", skill_name, step_id);
-    let conn = db.0.lock().map_err(|e| {
+    let conn = db.0.read().map_err(|e| {
         log::error!("[get_step_agent_runs] Failed to acquire DB lock: {}", e);
         e.to_string()
     })?;
     crate::db::get_step_agent_runs(&conn, &skill_name, step_id)
 }
+
+/// Fraction of a budget at which the status turns to `warning`.
+const BUDGET_WARNING_RATIO: f64 = 0.8;
+
+/// `(state, exceeded_scope)` for spend against optional limits. The skill
+/// budget is reported when both are exceeded.
+fn evaluate_budget(skill: (f64, Option<f64>), global: (f64, Option<f64>)) -> (&'static str, Option<&'static str>) {
+    let used = |(spent, limit): (f64, Option<f64>)| limit.map(|l| if l <= 0.0 { f64::INFINITY } else { spent / l });
+    let (skill_used, global_used) = (used(skill), used(global));
+    if skill_used.is_some_and(|u| u >= 1.0) {
+        return ("exceeded", Some("skill"));
+    }
+    if global_used.is_some_and(|u| u >= 1.0) {
+        return ("exceeded", Some("global"));
+    }
+    if [skill_used, global_used].into_iter().flatten().any(|u| u >= BUDGET_WARNING_RATIO) {
+        return ("warning", None);
+    }
+    ("ok", None)
+}
+
+/// Recorded spend since the last reset plus the live cost of running agents,
+/// against the budgets in settings.
+pub(crate) fn budget_status(conn: &rusqlite::Connection, skill_name: Option<&str>) -> Result<BudgetStatus, String> {
+    let settings = crate::db::read_settings(conn)?;
+    let skill_spent_usd = match skill_name {
+        Some(name) => crate::db::spend_since_reset(conn, Some(name))? + crate::agents::cost_ticker::live_cost(Some(name)),
+        None => 0.0,
+    };
+    let global_spent_usd = crate::db::spend_since_reset(conn, None)? + crate::agents::cost_ticker::live_cost(None);
+    let skill_budget_usd = skill_name.and_then(|name| settings.skill_budgets_usd.get(name).copied());
+    let (state, scope) = evaluate_budget(
+        (skill_spent_usd, skill_budget_usd),
+        (global_spent_usd, settings.global_budget_usd),
+    );
+    Ok(BudgetStatus {
+        skill_name: skill_name.map(str::to_string),
+        skill_spent_usd,
+        skill_budget_usd,
+        global_spent_usd,
+        global_budget_usd: settings.global_budget_usd,
+        state: state.to_string(),
+        exceeded_scope: scope.map(str::to_string),
+    })
+}
+
+/// Refuse to start an agent for a skill whose budget, or the global budget,
+/// is already spent.
+pub(crate) fn check_budget_allows_run(conn: &rusqlite::Connection, skill_name: &str) -> Result<(), String> {
+    let status = budget_status(conn, Some(skill_name))?;
+    match status.exceeded_scope.as_deref() {
+        Some("skill") => Err(format!(
+            "'{}' has used its ${:.2} budget (${:.2} spent). Raise the budget in Settings or reset usage to continue.",
+            skill_name,
+            status.skill_budget_usd.unwrap_or_default(),
+            status.skill_spent_usd
+        )),
+        Some(_) => Err(format!(
+            "The global ${:.2} budget is used up (${:.2} spent). Raise the budget in Settings or reset usage to continue.",
+            status.global_budget_usd.unwrap_or_default(),
+            status.global_spent_usd
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Spend against the per-skill (when `skill_name` is given) and global
+/// budgets, so the UI can warn before starting an expensive step.
+#[tauri::command]
+pub fn get_budget_status(db: tauri::State<'_, Db>, skill_name: Option<String>) -> Result<BudgetStatus, String> {
+    log::info!("[get_budget_status] skill_name={:?}", skill_name);
+    let conn = db.0.read().map_err(|e| {
+        log::error!("[get_budget_status] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    budget_status(&conn, skill_name.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_rows_per_model_or_aggregate() {
+        let result = serde_json::json!({
+            "type": "result",
+            "usage": {"input_tokens": 10, "output_tokens": 5},
+            "total_cost_usd": 0.5,
+            "modelUsage": {
+                "claude-sonnet-4-6": {"inputTokens": 8, "outputTokens": 4, "cacheReadInputTokens": 100, "costUSD": 0.4},
+                "claude-haiku-4-5-20251001": {"inputTokens": 2, "outputTokens": 1, "costUSD": 0.1}
+            }
+        });
+        let mut rows = usage_rows(&result, "sonnet");
+        rows.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].0, "claude-sonnet-4-6");
+        assert_eq!((rows[1].1, rows[1].2, rows[1].3, rows[1].4), (8, 4, 100, 0));
+        assert!((rows[1].5 - 0.4).abs() < f64::EPSILON);
+
+        let aggregate = serde_json::json!({
+            "type": "result",
+            "usage": {"input_tokens": 10, "output_tokens": 5, "cache_creation_input_tokens": 7},
+            "total_cost_usd": 0.5
+        });
+        let rows = usage_rows(&aggregate, "sonnet");
+        assert_eq!(rows.len(), 1);
+        assert_eq!((rows[0].0.as_str(), rows[0].1, rows[0].2, rows[0].4), ("sonnet", 10, 5, 7));
+        assert!((rows[0].5 - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_evaluate_budget() {
+        assert_eq!(evaluate_budget((5.0, None), (50.0, None)), ("ok", None));
+        assert_eq!(evaluate_budget((5.0, Some(10.0)), (50.0, Some(100.0))), ("ok", None));
+        assert_eq!(evaluate_budget((8.0, Some(10.0)), (50.0, Some(100.0))), ("warning", None));
+        assert_eq!(evaluate_budget((10.0, Some(10.0)), (100.0, Some(100.0))), ("exceeded", Some("skill")));
+        assert_eq!(evaluate_budget((1.0, None), (100.5, Some(100.0))), ("exceeded", Some("global")));
+        assert_eq!(evaluate_budget((0.0, Some(0.0)), (0.0, None)), ("exceeded", Some("skill")));
+    }
+
+}
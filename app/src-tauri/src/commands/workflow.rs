@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
@@ -6,10 +6,10 @@ use std::sync::Mutex;
 use crate::agents::sidecar::{self, SidecarConfig};
 use crate::agents::sidecar_pool::SidecarPool;
 use crate::db::Db;
-use crate::types::{PackageResult, StepConfig, StepStatusUpdate, WorkflowStateResponse};
+use crate::types::{AgentRunCheckpoint, PackageResult, PromptReload, SkillValidationReport, StepConfig, StepStatusUpdate, WorkflowStateResponse};
 use serde_json;
 
-const FULL_TOOLS: &[&str] = &[
+pub(crate) const FULL_TOOLS: &[&str] = &[
     "Read", "Write", "Edit", "Glob", "Grep", "Bash", "Task", "Skill",
 ];
 const CONTRACT_NO_WRITE_TOOLS: &[&str] = &["Read", "Glob", "Grep", "Task", "Skill"];
@@ -23,7 +23,7 @@ pub fn resolve_model_id(shorthand: &str) -> String {
     }
 }
 
-fn get_step_config(step_id: u32) -> Result<StepConfig, String> {
+pub(crate) fn get_step_config(step_id: u32) -> Result<StepConfig, String> {
     match step_id {
         0 => Ok(StepConfig {
             step_id: 0,
@@ -36,6 +36,9 @@ fn get_step_config(step_id: u32) -> Result<StepConfig, String> {
                 .map(|s| s.to_string())
                 .collect(),
             max_turns: 50,
+            thinking_budget: thinking_budget_for_step(0),
+            description: None,
+            custom: false,
         }),
         1 => Ok(StepConfig {
             step_id: 1,
@@ -48,6 +51,9 @@ fn get_step_config(step_id: u32) -> Result<StepConfig, String> {
                 .map(|s| s.to_string())
                 .collect(),
             max_turns: 50,
+            thinking_budget: thinking_budget_for_step(1),
+            description: None,
+            custom: false,
         }),
         2 => Ok(StepConfig {
             step_id: 2,
@@ -60,6 +66,9 @@ fn get_step_config(step_id: u32) -> Result<StepConfig, String> {
                 .map(|s| s.to_string())
                 .collect(),
             max_turns: 100,
+            thinking_budget: thinking_budget_for_step(2),
+            description: None,
+            custom: false,
         }),
         3 => Ok(StepConfig {
             step_id: 3,
@@ -68,19 +77,34 @@ fn get_step_config(step_id: u32) -> Result<StepConfig, String> {
             output_file: "skill/SKILL.md".to_string(),
             allowed_tools: FULL_TOOLS.iter().map(|s| s.to_string()).collect(),
             max_turns: 120,
+            thinking_budget: thinking_budget_for_step(3),
+            description: None,
+            custom: false,
         }),
         _ => Err(format!("Unknown step_id {}. Valid steps are 0-3.", step_id)),
     }
 }
 
-/// Session-scoped set of workspaces whose prompts have already been copied.
-/// Prompts are bundled with the app and don't change during a session,
-/// so we only need to copy once per workspace.
-///
-/// **Dev-mode caveat:** In development, prompts are read from the repo root.
-/// Edits to `agent-sources/agents/` or `workspace/` while the app is running won't be
-/// picked up until the app is restarted.
-static COPIED_WORKSPACES: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+/// Short SHA-256 of the agent prompt deployed for `step_id`, so step metrics can
+/// be compared across prompt changes. `None` if the prompt isn't deployed.
+pub(crate) fn step_prompt_version(workspace_path: &str, step_id: u32) -> Option<String> {
+    use sha2::{Digest, Sha256};
+    let bytes = std::fs::read(step_prompt_path(workspace_path, step_id)?).ok()?;
+    Some(hex::encode(&Sha256::digest(&bytes)[..6]))
+}
+
+/// Where the agent prompt for `step_id` is deployed in the workspace.
+fn step_prompt_path(workspace_path: &str, step_id: u32) -> Option<PathBuf> {
+    let template = get_step_config(step_id).ok()?.prompt_template;
+    Some(Path::new(workspace_path).join(".claude").join("agents").join(template))
+}
+
+/// Session-scoped map of workspace → checksum of the prompt sources last
+/// deployed there. A workspace is re-deployed when the checksum of the
+/// bundled agents (plus any custom prompt overrides) no longer matches, so
+/// edits to `agent-sources/agents/` or the custom prompts directory are
+/// picked up without an app restart.
+static COPIED_WORKSPACES: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
 
 /// Public wrapper for `resolve_prompt_source_dirs` — used by `workspace.rs`
 /// to pass the bundled CLAUDE.md path into `rebuild_claude_md`.
@@ -291,20 +315,78 @@ fn resolve_prompt_source_dirs(app_handle: &tauri::AppHandle) -> (PathBuf, PathBu
     (agents_dir, claude_md)
 }
 
-/// Returns true if this workspace has already been initialized this session.
-fn workspace_already_copied(workspace_path: &str) -> bool {
+/// Checksum of the prompt sources deployed to this workspace this session, if any.
+fn workspace_copied_checksum(workspace_path: &str) -> Option<String> {
     let cache = COPIED_WORKSPACES.lock().unwrap_or_else(|e| e.into_inner());
     cache
         .as_ref()
-        .is_some_and(|set| set.contains(workspace_path))
+        .and_then(|map| map.get(workspace_path).cloned())
 }
 
-/// Mark a workspace as initialized for this session.
-fn mark_workspace_copied(workspace_path: &str) {
+/// Record the checksum of the prompt sources just deployed to a workspace.
+fn mark_workspace_copied(workspace_path: &str, checksum: &str) {
     let mut cache = COPIED_WORKSPACES.lock().unwrap_or_else(|e| e.into_inner());
     cache
-        .get_or_insert_with(HashSet::new)
-        .insert(workspace_path.to_string());
+        .get_or_insert_with(HashMap::new)
+        .insert(workspace_path.to_string(), checksum.to_string());
+}
+
+/// The configured custom prompts directory, if set and present on disk.
+fn custom_prompts_dir(app_handle: &tauri::AppHandle) -> Option<PathBuf> {
+    use tauri::Manager;
+
+    let db = app_handle.try_state::<Db>()?;
+    let conn = db.0.lock().ok()?;
+    let dir = crate::db::read_settings(&conn).ok()?.custom_prompts_dir?;
+    let path = PathBuf::from(dir.trim());
+    if path.is_dir() {
+        Some(path)
+    } else {
+        log::warn!(
+            "[custom_prompts_dir] {} is not a directory; using bundled prompts",
+            path.display()
+        );
+        None
+    }
+}
+
+/// Names of the `.md` files directly inside `dir`, sorted.
+fn list_prompt_files(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().is_file())
+        .filter(|e| e.path().extension().and_then(|x| x.to_str()) == Some("md"))
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+/// The file deployed for bundled template `name`: the same-named file in
+/// `overrides` when present, otherwise the bundled one.
+fn effective_prompt_source(agents_src: &Path, overrides: Option<&Path>, name: &str) -> PathBuf {
+    overrides
+        .map(|dir| dir.join(name))
+        .filter(|p| p.is_file())
+        .unwrap_or_else(|| agents_src.join(name))
+}
+
+/// SHA-256 over every bundled template name and the content that would be
+/// deployed for it. Changes whenever a bundled or override template changes.
+fn prompt_sources_checksum(agents_src: &Path, overrides: Option<&Path>) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    for name in list_prompt_files(agents_src) {
+        hasher.update(name.as_bytes());
+        hasher.update([0u8]);
+        let source = effective_prompt_source(agents_src, overrides, &name);
+        hasher.update(std::fs::read(&source).unwrap_or_default());
+        hasher.update([0u8]);
+    }
+    hex::encode(hasher.finalize())
 }
 
 /// Remove a workspace from the session cache so the next
@@ -317,12 +399,21 @@ pub fn invalidate_workspace_cache(workspace_path: &str) {
     }
 }
 
+/// Forget every initialized workspace. Subscribed to settings changes of
+/// `workspace_path` and `skills_path` so the next run re-deploys prompts
+/// into the new location without an app restart.
+pub fn clear_workspace_cache() {
+    let mut cache = COPIED_WORKSPACES.lock().unwrap_or_else(|e| e.into_inner());
+    *cache = None;
+}
+
 /// Copy bundled agent .md files and workspace CLAUDE.md into workspace.
 /// Creates the directories if they don't exist. Overwrites existing files
 /// to keep them in sync with the app version.
 ///
-/// Copies once per workspace per session — prompts are bundled with the app
-/// and don't change at runtime.
+/// Skips the copy when the workspace was already deployed this session with
+/// the same prompt-source checksum; templates in the custom prompts
+/// directory replace bundled ones with the same file name.
 ///
 /// File I/O is offloaded to `spawn_blocking` to avoid blocking the tokio runtime.
 ///
@@ -333,14 +424,11 @@ pub async fn ensure_workspace_prompts(
     app_handle: &tauri::AppHandle,
     workspace_path: &str,
 ) -> Result<(), String> {
-    if workspace_already_copied(workspace_path) {
-        return Ok(());
-    }
-
     // Extract paths from AppHandle before moving into the blocking closure
     // (AppHandle is !Send so it cannot cross the spawn_blocking boundary)
     let (agents_dir, claude_md) = resolve_prompt_source_dirs(app_handle);
     let plugins_dir = resolve_bundled_plugins_dir(app_handle);
+    let overrides = custom_prompts_dir(app_handle);
 
     if !agents_dir.is_dir() && !claude_md.is_file() && !plugins_dir.is_dir() {
         return Ok(()); // No sources found anywhere — skip silently
@@ -351,11 +439,20 @@ pub async fn ensure_workspace_prompts(
     let plugins = plugins_dir.clone();
     let cmd = claude_md.clone();
 
-    tokio::task::spawn_blocking(move || copy_prompts_sync(&agents, &plugins, &cmd, &workspace))
-        .await
-        .map_err(|e| format!("Prompt copy task failed: {}", e))??;
+    let checksum = tokio::task::spawn_blocking(move || {
+        let checksum = prompt_sources_checksum(&agents, overrides.as_deref());
+        if workspace_copied_checksum(&workspace).as_deref() == Some(checksum.as_str()) {
+            return Ok(None);
+        }
+        copy_prompts_sync(&agents, &plugins, &cmd, overrides.as_deref(), &workspace)
+            .map(|_| Some(checksum))
+    })
+    .await
+    .map_err(|e| format!("Prompt copy task failed: {}", e))??;
 
-    mark_workspace_copied(workspace_path);
+    if let Some(checksum) = checksum {
+        mark_workspace_copied(workspace_path, &checksum);
+    }
     Ok(())
 }
 
@@ -365,10 +462,11 @@ fn copy_prompts_sync(
     agents_dir: &Path,
     plugins_dir: &Path,
     _claude_md: &Path,
+    overrides: Option<&Path>,
     workspace_path: &str,
 ) -> Result<(), String> {
     if agents_dir.is_dir() {
-        copy_agents_to_claude_dir(agents_dir, workspace_path)?;
+        copy_agents_to_claude_dir(agents_dir, overrides, workspace_path)?;
     }
     if plugins_dir.is_dir() {
         copy_managed_plugins_to_claude_dir(plugins_dir, workspace_path)?;
@@ -378,39 +476,93 @@ fn copy_prompts_sync(
 
 /// Synchronous variant of `ensure_workspace_prompts` for callers that cannot be async
 /// (e.g. `init_workspace` called from Tauri's synchronous `setup` hook).
-/// Uses the same session-scoped cache to skip redundant copies.
+/// Uses the same session-scoped checksum cache to skip redundant copies.
 pub fn ensure_workspace_prompts_sync(
     app_handle: &tauri::AppHandle,
     workspace_path: &str,
 ) -> Result<(), String> {
-    if workspace_already_copied(workspace_path) {
-        return Ok(());
-    }
-
     let (agents_dir, claude_md) = resolve_prompt_source_dirs(app_handle);
     let plugins_dir = resolve_bundled_plugins_dir(app_handle);
+    let overrides = custom_prompts_dir(app_handle);
 
     if !agents_dir.is_dir() && !claude_md.is_file() && !plugins_dir.is_dir() {
         return Ok(());
     }
 
-    copy_prompts_sync(&agents_dir, &plugins_dir, &claude_md, workspace_path)?;
-    mark_workspace_copied(workspace_path);
+    let checksum = prompt_sources_checksum(&agents_dir, overrides.as_deref());
+    if workspace_copied_checksum(workspace_path).as_deref() == Some(checksum.as_str()) {
+        return Ok(());
+    }
+
+    copy_prompts_sync(&agents_dir, &plugins_dir, &claude_md, overrides.as_deref(), workspace_path)?;
+    mark_workspace_copied(workspace_path, &checksum);
     Ok(())
 }
 
 /// Re-deploy only the bundled agents to `.claude/agents/`, preserving
 /// other contents of the `.claude/` directory (skills, agents, etc.).
-pub fn redeploy_agents(app_handle: &tauri::AppHandle, workspace_path: &str) -> Result<(), String> {
+/// Returns the checksum of the deployed prompt sources.
+pub fn redeploy_agents(app_handle: &tauri::AppHandle, workspace_path: &str) -> Result<String, String> {
     let (agents_dir, _) = resolve_prompt_source_dirs(app_handle);
     let plugins_dir = resolve_bundled_plugins_dir(app_handle);
+    let overrides = custom_prompts_dir(app_handle);
+    let checksum = prompt_sources_checksum(&agents_dir, overrides.as_deref());
     if agents_dir.is_dir() {
-        copy_agents_to_claude_dir(&agents_dir, workspace_path)?;
+        copy_agents_to_claude_dir(&agents_dir, overrides.as_deref(), workspace_path)?;
     }
     if plugins_dir.is_dir() {
         copy_managed_plugins_to_claude_dir(&plugins_dir, workspace_path)?;
     }
-    Ok(())
+    mark_workspace_copied(workspace_path, &checksum);
+    Ok(checksum)
+}
+
+/// Force a redeploy of agent prompts into the current workspace and report
+/// which bundled templates are overridden by the custom prompts directory.
+#[tauri::command]
+pub fn reload_prompts(
+    app: tauri::AppHandle,
+    db: tauri::State<'_, Db>,
+) -> Result<PromptReload, String> {
+    log::info!("[reload_prompts]");
+    let workspace_path = {
+        let conn = db.0.lock().map_err(|e| {
+            log::error!("[reload_prompts] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        crate::db::read_settings(&conn)?
+            .workspace_path
+            .ok_or_else(|| "Workspace path not initialized".to_string())?
+    };
+
+    let (agents_dir, _) = resolve_prompt_source_dirs(&app);
+    let overrides = custom_prompts_dir(&app);
+    let bundled = list_prompt_files(&agents_dir);
+    let custom = overrides.as_deref().map(list_prompt_files).unwrap_or_default();
+    let (overridden, ignored): (Vec<String>, Vec<String>) =
+        custom.into_iter().partition(|name| bundled.contains(name));
+
+    let previous_checksum = workspace_copied_checksum(&workspace_path);
+    let checksum = redeploy_agents(&app, &workspace_path).map_err(|e| {
+        log::error!("[reload_prompts] Failed to redeploy agents: {}", e);
+        e
+    })?;
+    let changed = previous_checksum.as_deref() != Some(checksum.as_str());
+    log::info!(
+        "[reload_prompts] checksum={} changed={} overridden={} ignored={}",
+        &checksum[..12],
+        changed,
+        overridden.len(),
+        ignored.len()
+    );
+
+    Ok(PromptReload {
+        checksum,
+        previous_checksum,
+        changed,
+        overridden,
+        ignored,
+    })
 }
 
 /// Extract the user's customization content from an existing CLAUDE.md.
@@ -533,7 +685,13 @@ pub fn update_skills_section(
 
 /// Copy agent .md files from flat bundled agent source to <workspace>/.claude/agents/.
 /// agent-sources/agents/{name}.md → .claude/agents/{name}.md
-fn copy_agents_to_claude_dir(agents_src: &Path, workspace_path: &str) -> Result<(), String> {
+///
+/// A same-named file in `overrides` is copied in place of the bundled one.
+fn copy_agents_to_claude_dir(
+    agents_src: &Path,
+    overrides: Option<&Path>,
+    workspace_path: &str,
+) -> Result<(), String> {
     let claude_agents_dir = Path::new(workspace_path).join(".claude").join("agents");
     if claude_agents_dir.is_dir() {
         std::fs::remove_dir_all(&claude_agents_dir)
@@ -548,7 +706,9 @@ fn copy_agents_to_claude_dir(agents_src: &Path, workspace_path: &str) -> Result<
         let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
         let path = entry.path();
         if path.extension().and_then(|e| e.to_str()) == Some("md") {
-            let dest = claude_agents_dir.join(entry.file_name());
+            let name = entry.file_name().to_string_lossy().to_string();
+            let path = effective_prompt_source(agents_src, overrides, &name);
+            let dest = claude_agents_dir.join(&name);
             std::fs::copy(&path, &dest).map_err(|e| {
                 format!("Failed to copy {} to .claude/agents: {}", path.display(), e)
             })?;
@@ -617,6 +777,11 @@ fn copy_managed_plugins_to_claude_dir(plugins_src: &Path, workspace_path: &str)
 /// Agent files live at `{workspace}/.claude/agents/{phase}.md`.
 /// Returns `None` if the file doesn't exist or has no `name:` field.
 fn read_agent_frontmatter_name(workspace_path: &str, phase: &str) -> Option<String> {
+    read_agent_frontmatter_field(workspace_path, phase, "name")
+}
+
+/// Read a `key:` field from an agent file's YAML frontmatter.
+pub(crate) fn read_agent_frontmatter_field(workspace_path: &str, phase: &str, key: &str) -> Option<String> {
     let agent_file = Path::new(workspace_path)
         .join(".claude")
         .join("agents")
@@ -629,12 +794,12 @@ fn read_agent_frontmatter_name(workspace_path: &str, phase: &str) -> Option<Stri
     let end = after_start.find("---")?;
     let frontmatter = &after_start[..end];
     for line in frontmatter.lines() {
-        let trimmed = line.trim();
-        if let Some(name) = trimmed.strip_prefix("name:") {
-            let name = name.trim();
-            if !name.is_empty() {
-                return Some(name.to_string());
-            }
+        let Some(value) = line.trim().strip_prefix(key).and_then(|rest| rest.strip_prefix(':')) else {
+            continue;
+        };
+        let value = value.trim();
+        if !value.is_empty() {
+            return Some(value.to_string());
         }
     }
     None
@@ -924,7 +1089,7 @@ fn materialize_workflow_step_output_value(
     skill_root: &Path,
     step_id: u32,
     structured_output: &serde_json::Value,
-) -> Result<(), String> {
+) -> Result<PathBuf, String> {
     let payload = structured_output
         .as_object()
         .ok_or_else(|| "structured_output must be a JSON object".to_string())?;
@@ -980,7 +1145,7 @@ fn materialize_workflow_step_output_value(
                     e
                 )
             })?;
-            Ok(())
+            Ok(clarifications_path)
         }
         1 => {
             require_const_status("detailed_research_complete")?;
@@ -1002,7 +1167,7 @@ fn materialize_workflow_step_output_value(
                     e
                 )
             })?;
-            Ok(())
+            Ok(clarifications_path)
         }
         2 => {
             let decisions_pretty = serde_json::to_string_pretty(&serde_json::Value::Object(payload.clone()))
@@ -1015,7 +1180,7 @@ fn materialize_workflow_step_output_value(
                     e
                 )
             })?;
-            Ok(())
+            Ok(decisions_path)
         }
         3 => {
             require_const_status("generated")?;
@@ -1036,7 +1201,7 @@ fn materialize_workflow_step_output_value(
                     e
                 )
             })?;
-            Ok(())
+            Ok(evaluations_path)
         }
         _ => Err(format!(
             "materialize_workflow_step_output supports only steps 0-3; got {}",
@@ -1050,6 +1215,7 @@ pub fn materialize_workflow_step_output(
     skill_name: String,
     step_id: u32,
     structured_output: serde_json::Value,
+    instance: tauri::State<'_, crate::InstanceInfo>,
     db: tauri::State<'_, Db>,
 ) -> Result<(), String> {
     log::info!(
@@ -1057,10 +1223,23 @@ pub fn materialize_workflow_step_output(
         skill_name,
         step_id
     );
+    super::observer::ensure_writable(&skill_name)?;
     let workspace_path = read_workspace_path(&db)
         .ok_or_else(|| "Workspace path not configured. Please set it in Settings.".to_string())?;
     let skill_root = Path::new(&workspace_path).join(&skill_name);
-    materialize_workflow_step_output_value(&skill_root, step_id, &structured_output)
+    let written = materialize_workflow_step_output_value(&skill_root, step_id, &structured_output)?;
+    if let Ok(conn) = db.0.lock() {
+        super::observer::record(&conn, &instance, &skill_name, super::observer::EVENT_STEP_OUTPUT);
+    }
+    crate::journal::record(crate::journal::JournalEvent::FileWritten {
+        skill: skill_name,
+        path: written
+            .strip_prefix(&skill_root)
+            .unwrap_or(&written)
+            .to_string_lossy()
+            .to_string(),
+    });
+    Ok(())
 }
 
 fn answer_evaluator_output_format() -> serde_json::Value {
@@ -1267,6 +1446,7 @@ pub fn materialize_answer_evaluation_output(
         "[materialize_answer_evaluation_output] skill={}",
         skill_name
     );
+    super::observer::ensure_writable(&skill_name)?;
     log::debug!(
         "[materialize_answer_evaluation_output] skill={} structured_output={}",
         skill_name,
@@ -1487,7 +1667,7 @@ pub(crate) fn write_skill_output_dir_file(workspace_dir: &Path, skill_output_dir
 fn build_prompt(
     skill_name: &str,
     workspace_path: &str,
-    _skills_path: &str,
+    skills_path: &str,
     author_login: Option<&str>,
     created_at: Option<&str>,
     max_dimensions: u32,
@@ -1520,6 +1700,17 @@ fn build_prompt(
         max_dimensions
     ));
 
+    if Path::new(skills_path)
+        .join(skill_name)
+        .join(super::repo_context::OVERVIEW_FILE)
+        .is_file()
+    {
+        prompt.push_str(&format!(
+            " Repository context captured from the source system is in {} in the skill output directory, with copies of the source files beside it. Read it before researching and prefer it over assumptions about how the system works.",
+            super::repo_context::OVERVIEW_FILE
+        ));
+    }
+
     prompt.push_str(" The workspace directory may contain other files written by the workflow (such as answer-evaluation.json) — read only the files explicitly named in your agent instructions. Do not read the logs/ directory or any file not named in your instructions.");
 
     prompt
@@ -1530,7 +1721,12 @@ fn read_skills_path(db: &tauri::State<'_, Db>) -> Option<String> {
     crate::db::read_settings(&conn).ok()?.skills_path
 }
 
-fn read_workspace_path(db: &tauri::State<'_, Db>) -> Option<String> {
+fn read_accessibility_lint(db: &tauri::State<'_, Db>) -> Option<String> {
+    let conn = db.0.lock().ok()?;
+    Some(crate::db::read_settings(&conn).ok()?.accessibility_lint)
+}
+
+pub(crate) fn read_workspace_path(db: &tauri::State<'_, Db>) -> Option<String> {
     let conn = db.0.lock().ok()?;
     crate::db::read_settings(&conn).ok()?.workspace_path
 }
@@ -1566,7 +1762,7 @@ pub fn build_betas(
 }
 
 /// Generate a unique agent ID from skill name, label, and timestamp.
-fn make_agent_id(skill_name: &str, label: &str) -> String {
+pub(crate) fn make_agent_id(skill_name: &str, label: &str) -> String {
     let ts = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
@@ -1606,6 +1802,9 @@ struct WorkflowSettings {
     skills_path: String,
     api_key: String,
     preferred_model: String,
+    /// Model a route in `model_routes` assigns to the step (see `model_routing`);
+    /// `None` leaves the choice to the agent's front matter.
+    step_model: Option<String>,
     extended_thinking: bool,
     interleaved_thinking_beta: bool,
     sdk_effort: Option<String>,
@@ -1624,6 +1823,19 @@ struct WorkflowSettings {
     argument_hint: Option<String>,
     user_invocable: Option<bool>,
     disable_model_invocation: Option<bool>,
+    bash_sandbox: Option<crate::agents::sidecar::BashSandboxConfig>,
+    /// Tool policy role for the step being run (see `tool_policy`).
+    tool_role: String,
+    /// Decisions changed since SKILL.md was generated, when step 3 can patch
+    /// it instead of rewriting it (see `incremental_generation`).
+    incremental_changes: Option<Vec<crate::types::DecisionDelta>>,
+}
+
+impl WorkflowSettings {
+    /// The routed model when a route matched, else the preferred model.
+    fn model(&self) -> &str {
+        self.step_model.as_deref().unwrap_or(&self.preferred_model)
+    }
 }
 
 /// Read all workflow settings from the DB in a single lock acquisition.
@@ -1641,7 +1853,7 @@ fn read_workflow_settings(
         "Skills path not configured. Please set it in Settings before running workflow steps."
             .to_string()
     })?;
-    let api_key = match settings.anthropic_api_key {
+    let api_key = match crate::commands::offline::agent_api_key(&settings) {
         Some(k) => k,
         None => return Err("Anthropic API key not configured".to_string()),
     };
@@ -1661,6 +1873,7 @@ fn read_workflow_settings(
 
     // Get skill purpose
     let purpose = crate::db::get_purpose(&conn, skill_name)?;
+    let step_model = super::model_routing::routed_model(&settings.model_routes, step_id, &purpose);
 
     // Read author info and intake data from workflow run
     let run_row = crate::db::get_workflow_run(&conn, skill_name)
@@ -1679,11 +1892,39 @@ fn read_workflow_settings(
         .unwrap_or_default()
         .remove(skill_name)
         .unwrap_or_default();
+    let workspace_dir = Path::new(workspace_path).join(skill_name);
+    let skill_output_dir = Path::new(&skills_path).join(skill_name);
+    let bash_sandbox = crate::commands::tool_policy::bash_sandbox_for_run(
+        &conn,
+        skill_name,
+        &[&workspace_dir.to_string_lossy(), &skill_output_dir.to_string_lossy()],
+    )?;
+    let tool_role = crate::commands::tool_policy::step_tool_role(&conn, skill_name, step_id)?;
+    let incremental_changes = if step_id == 3 {
+        match super::incremental_generation::regeneration_mode(&conn, workspace_path, &skills_path, skill_name) {
+            Ok(mode) if mode.incremental => Some(mode.changes),
+            Ok(mode) => {
+                log::debug!(
+                    "[run_workflow_step] '{}' step 3 runs in full: {}",
+                    skill_name,
+                    mode.reason.unwrap_or_default()
+                );
+                None
+            }
+            Err(e) => {
+                log::warn!("[run_workflow_step] Failed to check incremental regeneration: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
 
     Ok(WorkflowSettings {
         skills_path,
         api_key,
         preferred_model,
+        step_model,
         extended_thinking,
         interleaved_thinking_beta,
         sdk_effort,
@@ -1702,57 +1943,113 @@ fn read_workflow_settings(
         argument_hint,
         user_invocable,
         disable_model_invocation,
+        bash_sandbox,
+        tool_role,
+        incremental_changes,
     })
 }
 
+fn write_user_context_for_settings(workspace_path: &str, skill_name: &str, settings: &WorkflowSettings) {
+    write_user_context_file(
+        workspace_path,
+        skill_name,
+        &settings.tags,
+        settings.industry.as_deref(),
+        settings.function_role.as_deref(),
+        settings.intake_json.as_deref(),
+        settings.description.as_deref(),
+        Some(settings.purpose.as_str()),
+        settings.version.as_deref(),
+        settings.skill_model.as_deref(),
+        settings.argument_hint.as_deref(),
+        settings.user_invocable,
+        settings.disable_model_invocation,
+    );
+}
+
+/// Fingerprint the inputs `run_workflow_step` would give `step_id` now,
+/// refreshing `user-context.md` first as a run would.
+pub(crate) fn step_run_inputs(
+    db: &Db,
+    skill_name: &str,
+    step_id: u32,
+    workspace_path: &str,
+) -> Result<super::step_cache::StepRunInputs, String> {
+    let settings = read_workflow_settings(db, skill_name, step_id, workspace_path)?;
+    write_user_context_for_settings(workspace_path, skill_name, &settings);
+    let prompt_path = step_prompt_path(workspace_path, step_id);
+    Ok(super::step_cache::StepRunInputs {
+        fingerprint: super::step_cache::input_fingerprint(
+            workspace_path,
+            skill_name,
+            step_id,
+            settings.model(),
+            prompt_path.as_deref(),
+        ),
+        model: settings.model().to_string(),
+        skills_path: settings.skills_path,
+    })
+}
+
+/// First message of a resumed step run. The SDK session restores the
+/// interrupted conversation, so only the instruction to carry on is needed.
+const RESUME_PROMPT: &str = "The app stopped while you were working on this step, and your earlier \
+turns have been restored. Continue the step from where you left off. Check which output files \
+already exist instead of redoing finished work, then complete the step exactly as originally instructed.";
+
 /// Core logic for launching a single workflow step. Builds the prompt,
 /// constructs the sidecar config, and spawns the agent. Returns the agent_id.
+/// With `resume`, the agent continues that run's SDK session instead.
 ///
-/// Used by `run_workflow_step` to avoid duplicating step logic.
+/// Used by `run_workflow_step` and `resume_workflow_step` to avoid duplicating step logic.
+#[allow(clippy::too_many_arguments)]
 async fn run_workflow_step_inner(
     app: &tauri::AppHandle,
     pool: &SidecarPool,
+    db: &Db,
     skill_name: &str,
     step_id: u32,
     workspace_path: &str,
     settings: &WorkflowSettings,
+    resume: Option<&AgentRunCheckpoint>,
 ) -> Result<String, String> {
-    let step = get_step_config(step_id)?;
+    let step = super::workflow_manifest::resolve_step(workspace_path, &settings.purpose, step_id)?;
+    super::workflow_manifest::deploy_step_template(workspace_path, &step)?;
     let thinking_budget = if settings.extended_thinking {
-        thinking_budget_for_step(step_id)
+        step.thinking_budget
     } else {
         None
     };
     // Write user-context.md to workspace directory so sub-agents can read it.
     // Refreshed before every step to pick up mid-workflow settings edits.
-    write_user_context_file(
-        workspace_path,
-        skill_name,
-        &settings.tags,
-        settings.industry.as_deref(),
-        settings.function_role.as_deref(),
-        settings.intake_json.as_deref(),
-        settings.description.as_deref(),
-        Some(settings.purpose.as_str()),
-        settings.version.as_deref(),
-        settings.skill_model.as_deref(),
-        settings.argument_hint.as_deref(),
-        settings.user_invocable,
-        settings.disable_model_invocation,
-    );
+    write_user_context_for_settings(workspace_path, skill_name, settings);
 
     let workspace_dir = Path::new(workspace_path).join(skill_name);
     let skill_output_dir = Path::new(&settings.skills_path).join(skill_name);
     write_skill_output_dir_file(&workspace_dir, &skill_output_dir);
 
-    let prompt = build_prompt(
-        skill_name,
-        workspace_path,
-        &settings.skills_path,
-        settings.author_login.as_deref(),
-        settings.created_at.as_deref(),
-        settings.max_dimensions,
-    );
+    let prompt = match resume {
+        Some(_) => RESUME_PROMPT.to_string(),
+        None => {
+            let mut prompt = build_prompt(
+                skill_name,
+                workspace_path,
+                &settings.skills_path,
+                settings.author_login.as_deref(),
+                settings.created_at.as_deref(),
+                settings.max_dimensions,
+            );
+            if let Some(changes) = settings.incremental_changes.as_deref().filter(|_| step_id == 3) {
+                log::info!(
+                    "[run_workflow_step] '{}' step 3 patches SKILL.md for {} changed decision(s)",
+                    skill_name,
+                    changes.len()
+                );
+                prompt.push_str(&super::incremental_generation::prompt_section(changes));
+            }
+            prompt
+        }
+    };
     log::debug!(
         "[run_workflow_step] prompt for step {}: {}",
         step_id,
@@ -1765,26 +2062,42 @@ async fn run_workflow_step_inner(
         "run_workflow_step: skill={} step={} model={}",
         skill_name,
         step_id,
-        settings.preferred_model
+        settings.step_model.as_deref().unwrap_or("agent default")
     );
 
+    let (allowed_tools, disallowed_tools) =
+        super::tool_policy::trim_tools(step.allowed_tools, &settings.tool_role);
+    if !disallowed_tools.is_empty() {
+        log::info!(
+            "[run_workflow_step] tool role '{}' removed {:?} from step {}",
+            settings.tool_role,
+            disallowed_tools,
+            step_id
+        );
+    }
+
     let required_plugins = if agent_name == "research-orchestrator" {
         Some(vec!["skill-content-researcher".to_string()])
     } else {
         None
     };
 
-    let config = SidecarConfig {
+    // The SDK refuses a fallback model equal to the main model.
+    let fallback_model = settings
+        .fallback_model
+        .clone()
+        .filter(|f| settings.step_model.as_ref() != Some(f));
+    let mut config = SidecarConfig {
         prompt,
-        model: None,
+        model: settings.step_model.clone(),
         api_key: settings.api_key.clone(),
         cwd: workspace_path.to_string(),
-        allowed_tools: Some(step.allowed_tools),
+        allowed_tools: Some(allowed_tools),
         max_turns: Some(step.max_turns),
         permission_mode: Some("bypassPermissions".to_string()),
         betas: build_betas(
             thinking_budget,
-            &settings.preferred_model,
+            settings.model(),
             settings.interleaved_thinking_beta,
         ),
         thinking: thinking_budget.map(|budget| {
@@ -1793,7 +2106,7 @@ async fn run_workflow_step_inner(
                 "budgetTokens": budget
             })
         }),
-        fallback_model: settings.fallback_model.clone(),
+        fallback_model,
         effort: settings.sdk_effort.clone(),
         output_format: workflow_output_format_for_agent(&agent_name),
         prompt_suggestions: None,
@@ -1801,8 +2114,52 @@ async fn run_workflow_step_inner(
         agent_name: Some(agent_name),
         required_plugins,
         conversation_history: None,
+        resume: resume.and_then(|r| r.sdk_session_id.clone()),
+        bash_sandbox: settings.bash_sandbox.clone(),
+        disallowed_tools: (!disallowed_tools.is_empty()).then_some(disallowed_tools),
+        local_model: None,
     };
 
+    // Research the selected dimensions side by side before the orchestrator
+    // starts, instead of one Task sub-agent after another inside its session.
+    if resume.is_none() && config.agent_name.as_deref() == Some("research-orchestrator") {
+        match super::research_fanout::prefetch_dimension_research(app, pool, skill_name, workspace_path, &config)
+            .await
+        {
+            Ok(true) => config.prompt.push_str(&super::research_fanout::prompt_section(&workspace_dir)),
+            Ok(false) => {}
+            Err(e) => log::warn!(
+                "[run_workflow_step] Concurrent dimension research skipped, the research skill runs it: {}",
+                e
+            ),
+        }
+    }
+
+    {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        crate::db::create_agent_run_checkpoint(
+            &conn,
+            &agent_id,
+            skill_name,
+            step_id,
+            std::process::id(),
+            resume.map(|r| r.agent_id.as_str()),
+        )?;
+    }
+    crate::agents::run_checkpoint::start(&agent_id);
+
+    if let Err(e) = super::step_replay::capture_step_environment(
+        workspace_path,
+        &settings.skills_path,
+        skill_name,
+        step_id,
+        &agent_id,
+        settings.model(),
+        &config,
+    ) {
+        log::warn!("[run_workflow_step] Failed to capture step environment for replay: {}", e);
+    }
+
     sidecar::spawn_sidecar(
         agent_id.clone(),
         config,
@@ -1825,38 +2182,141 @@ pub async fn run_workflow_step(
     skill_name: String,
     step_id: u32,
     workspace_path: String,
+    incremental: Option<bool>,
 ) -> Result<String, String> {
-    log::info!("[run_workflow_step] skill={} step={}", skill_name, step_id);
-    crate::commands::workflow_lifecycle::validate_run_request(
+    log::info!(
+        "[run_workflow_step] skill={} step={} incremental={:?}",
+        skill_name,
+        step_id,
+        incremental
+    );
+    launch_workflow_step(
+        &app,
+        pool.inner(),
+        &db,
+        &skill_name,
+        step_id,
+        &workspace_path,
+        None,
+        incremental.unwrap_or(true),
+    )
+    .await
+}
+
+/// The newest run of a step that was cut short by a crash, quit or error and
+/// can be continued with `resume_workflow_step`.
+#[tauri::command]
+pub fn find_resumable_step_run(
+    skill_name: String,
+    step_id: u32,
+    db: tauri::State<'_, Db>,
+) -> Result<Option<AgentRunCheckpoint>, String> {
+    log::info!("[find_resumable_step_run] skill={} step={}", skill_name, step_id);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[find_resumable_step_run] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::find_resumable_checkpoint(&conn, &skill_name, step_id)
+}
+
+/// Continue an interrupted run of a step in its SDK session, restoring its
+/// turns instead of running the step from scratch. Returns the new agent_id.
+#[tauri::command]
+pub async fn resume_workflow_step(
+    app: tauri::AppHandle,
+    pool: tauri::State<'_, SidecarPool>,
+    db: tauri::State<'_, Db>,
+    skill_name: String,
+    step_id: u32,
+    workspace_path: String,
+) -> Result<String, String> {
+    log::info!("[resume_workflow_step] skill={} step={}", skill_name, step_id);
+    let checkpoint = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        crate::db::find_resumable_checkpoint(&conn, &skill_name, step_id)?
+    }
+    .ok_or_else(|| {
+        let msg = format!("Step {} has no interrupted run to resume", step_id);
+        log::warn!("[resume_workflow_step] {}", msg);
+        msg
+    })?;
+    log::info!(
+        "[resume_workflow_step] resuming {} after {} turn(s)",
+        checkpoint.agent_id,
+        checkpoint.turns
+    );
+    launch_workflow_step(
+        &app,
+        pool.inner(),
+        &db,
         &skill_name,
         step_id,
         &workspace_path,
+        Some(&checkpoint),
+        false,
+    )
+    .await
+}
+
+/// `allow_incremental` lets step 3 patch an existing SKILL.md when that is safe.
+#[allow(clippy::too_many_arguments)]
+async fn launch_workflow_step(
+    app: &tauri::AppHandle,
+    pool: &SidecarPool,
+    db: &Db,
+    skill_name: &str,
+    step_id: u32,
+    workspace_path: &str,
+    resume: Option<&AgentRunCheckpoint>,
+    allow_incremental: bool,
+) -> Result<String, String> {
+    super::observer::ensure_writable(skill_name)?;
+    let last_step_id = crate::commands::workflow_manifest::last_step_id(db, skill_name, workspace_path)?;
+    crate::commands::workflow_lifecycle::validate_run_request(
+        skill_name,
+        step_id,
+        workspace_path,
+        last_step_id,
     )?;
+    {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        crate::commands::prompt_contract::ensure_current_contract(&conn, skill_name).map_err(|e| {
+            log::error!("[run_workflow_step] {}", e);
+            e
+        })?;
+        crate::commands::usage::check_budget_allows_run(&conn, skill_name).map_err(|e| {
+            log::warn!("[run_workflow_step] {}", e);
+            e
+        })?;
+    }
     // Ensure prompt files exist in workspace before running
-    ensure_workspace_prompts(&app, &workspace_path).await?;
+    ensure_workspace_prompts(app, workspace_path).await?;
 
     // Deploy purpose-resolved bundled skills.
     // Research is plugin-owned, so only validate and skill-building are deployed from bundled skills.
     {
-        let bundled_skills_dir = resolve_bundled_skills_dir(&app);
+        let bundled_skills_dir = resolve_bundled_skills_dir(app);
         let conn = db.0.lock().map_err(|e| e.to_string())?;
         deploy_skill_for_workflow(
             &conn,
-            &workspace_path,
+            workspace_path,
             &bundled_skills_dir,
             "validate-skill",
             "validate",
         );
         deploy_skill_for_workflow(
             &conn,
-            &workspace_path,
+            workspace_path,
             &bundled_skills_dir,
             "skill-creator",
             "skill-building",
         );
     }
 
-    let settings = read_workflow_settings(&db, &skill_name, step_id, &workspace_path)?;
+    let mut settings = read_workflow_settings(db, skill_name, step_id, workspace_path)?;
+    if !allow_incremental {
+        settings.incremental_changes = None;
+    }
     log::info!(
         "[run_workflow_step] settings: skills_path={} purpose={} intake={} industry={:?} function={:?}",
         settings.skills_path, settings.purpose,
@@ -1865,8 +2325,8 @@ pub async fn run_workflow_step(
     );
 
     // Gate: reject disabled steps when guard conditions are active
-    let context_dir = Path::new(&workspace_path)
-        .join(&skill_name)
+    let context_dir = Path::new(workspace_path)
+        .join(skill_name)
         .join("context");
 
     if step_id >= 1 {
@@ -1895,8 +2355,8 @@ pub async fn run_workflow_step(
 
     // Step 0 fresh start — wipe the context directory and all artifacts so
     // the agent doesn't see stale files from a previous workflow run.
-    // Context lives in workspace_path.
-    if step_id == 0 && context_dir.is_dir() {
+    // Context lives in workspace_path. A resumed run keeps what it wrote.
+    if step_id == 0 && resume.is_none() && context_dir.is_dir() {
         log::debug!(
             "[run_workflow_step] step 0: wiping context dir {}",
             context_dir.display()
@@ -1905,24 +2365,69 @@ pub async fn run_workflow_step(
         let _ = std::fs::create_dir_all(&context_dir);
     }
 
-    run_workflow_step_inner(
-        &app,
-        pool.inner(),
-        &skill_name,
+    crate::journal::record(crate::journal::JournalEvent::StepStarted {
+        skill: skill_name.to_string(),
+        step: step_id,
+    });
+
+    let agent_id = run_workflow_step_inner(
+        app,
+        pool,
+        db,
+        skill_name,
         step_id,
-        &workspace_path,
+        workspace_path,
         &settings,
+        resume,
     )
-    .await
+    .await?;
+
+    // Remember what this run was given so a later re-run with the same inputs
+    // can reuse its outputs (see step_cache).
+    let prompt_path = step_prompt_path(workspace_path, step_id);
+    let fingerprint = super::step_cache::input_fingerprint(
+        workspace_path,
+        skill_name,
+        step_id,
+        settings.model(),
+        prompt_path.as_deref(),
+    );
+    match db.0.lock() {
+        Ok(conn) => {
+            if let Err(e) = crate::db::record_pending_step_run(
+                &conn,
+                skill_name,
+                step_id,
+                &fingerprint,
+                &agent_id,
+                settings.model(),
+            ) {
+                log::warn!("[run_workflow_step] Failed to record input fingerprint: {}", e);
+            }
+        }
+        Err(e) => log::warn!("[run_workflow_step] Failed to acquire DB lock: {}", e),
+    }
+    Ok(agent_id)
 }
 
 #[tauri::command]
 pub async fn package_skill(
     skill_name: String,
     _workspace_path: String,
+    refuse_on_errors: Option<bool>,
+    dependencies: Option<String>,
+    locale: Option<String>,
+    target_format: Option<String>,
     db: tauri::State<'_, Db>,
 ) -> Result<PackageResult, String> {
-    log::info!("[package_skill] skill={}", skill_name);
+    log::info!(
+        "[package_skill] skill={} refuse_on_errors={:?} dependencies={:?} locale={:?} target_format={:?}",
+        skill_name, refuse_on_errors, dependencies, locale, target_format
+    );
+    super::observer::ensure_writable(&skill_name)?;
+    if let Some(lang) = locale.as_deref() {
+        super::skill_localization::validate_language(lang)?;
+    }
     let skills_path = read_skills_path(&db)
         .ok_or_else(|| "Skills path not configured. Please set it in Settings.".to_string())?;
 
@@ -1940,9 +2445,157 @@ pub async fn package_skill(
         ));
     }
 
-    let output_path = source_dir.join(format!("{}.skill", skill_name));
+    let format = {
+        let conn = db.0.lock().map_err(|e| {
+            log::error!("[package_skill] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        super::package_formats::resolve_format(&conn, &skill_name, target_format.as_deref()).map_err(|e| {
+            log::error!("[package_skill] {}", e);
+            e
+        })?
+    };
+    let output_path = source_dir.join(format.file_name(&skill_name, locale.as_deref()));
+    package_skill_dir(
+        &db,
+        &skill_name,
+        source_dir,
+        output_path,
+        refuse_on_errors.unwrap_or(false),
+        dependencies.as_deref(),
+        locale.as_deref(),
+        format,
+    )
+    .await
+}
 
-    let result = tokio::task::spawn_blocking(move || create_skill_zip(&source_dir, &output_path))
+/// Lint SKILL.md and references/ for frontmatter schema problems, broken
+/// relative links, a missing argument-hint, weak trigger text and oversized
+/// files. Diagnostics are sorted errors first.
+#[tauri::command]
+pub fn validate_skill(skill_name: String, db: tauri::State<'_, Db>) -> Result<SkillValidationReport, String> {
+    log::info!("[validate_skill] skill={}", skill_name);
+    super::imported_skills::validate_skill_name(&skill_name)?;
+    let skills_path = read_skills_path(&db)
+        .ok_or_else(|| "Skills path not configured. Please set it in Settings.".to_string())?;
+    let skill_dir = Path::new(&skills_path).join(&skill_name);
+    if !skill_dir.is_dir() {
+        let msg = format!("Skill directory not found: {}", skill_dir.display());
+        log::error!("[validate_skill] {}", msg);
+        return Err(msg);
+    }
+    let (files_checked, diagnostics) = super::skill_validation::validate_skill_dir(&skill_dir);
+    let count = |severity: &str| diagnostics.iter().filter(|d| d.severity == severity).count();
+    let report = SkillValidationReport {
+        skill_name,
+        files_checked,
+        error_count: count(super::skill_validation::SEVERITY_ERROR),
+        warning_count: count(super::skill_validation::SEVERITY_WARNING),
+        diagnostics,
+    };
+    log::info!(
+        "[validate_skill] skill={} errors={} warnings={}",
+        report.skill_name,
+        report.error_count,
+        report.warning_count
+    );
+    Ok(report)
+}
+
+/// Run the review, quality gate, validation and lint checks on `source_dir`
+/// and zip it to `output_path`. Shared by `package_skill`, deployment slot
+/// packaging and parameterized packages. Validation errors fail packaging only when `refuse_on_errors` is set.
+/// Declared dependencies are listed in the package, and verified or bundled
+/// according to `dependency_mode` (see `skill_dependencies`). The finished
+/// package is laid out for `format` (see `package_formats`).
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn package_skill_dir(
+    db: &tauri::State<'_, Db>,
+    skill_name: &str,
+    source_dir: PathBuf,
+    output_path: PathBuf,
+    refuse_on_errors: bool,
+    dependency_mode: Option<&str>,
+    locale: Option<&str>,
+    format: super::package_formats::PackageFormat,
+) -> Result<PackageResult, String> {
+    let (quality_gate, dependencies) = {
+        let conn = db.0.lock().map_err(|e| {
+            log::error!("[package_skill] Failed to acquire DB lock: {}", e);
+            e.to_string()
+        })?;
+        super::skill_review::check_packaging_allowed(&conn, skill_name).map_err(|e| {
+            log::error!("[package_skill] {}", e);
+            e
+        })?;
+        let skill_type = crate::db::get_workflow_run(&conn, skill_name)?.map(|run| run.purpose);
+        crate::fs_validation::FileRuleSet::load(&conn)?
+            .check_skill_dir(&source_dir, skill_type.as_deref())
+            .map_err(|e| {
+                log::error!("[package_skill] {}", e);
+                format!("Packaging refused: {}", e)
+            })?;
+        let dependencies = super::skill_dependencies::prepare_for_packaging(&conn, skill_name, dependency_mode)
+            .map_err(|e| {
+                log::error!("[package_skill] {}", e);
+                e
+            })?;
+        let quality_gate = super::quality_gates::gate_for_packaging(&conn, skill_name, &source_dir).map_err(|e| {
+            log::error!("[package_skill] {}", e);
+            e
+        })?;
+        (quality_gate, dependencies)
+    };
+
+    let lint_mode = read_accessibility_lint(db).unwrap_or_else(|| super::skill_lint::LINT_ADVISORY.to_string());
+
+    let (packaged_skill, packaged_locale) = (skill_name.to_string(), locale.map(str::to_string));
+    let skill_name = skill_name.to_string();
+    let locale = locale.map(str::to_string);
+    let result = tokio::task::spawn_blocking(move || {
+        let lint_warnings = super::skill_lint::lint_for_packaging(&lint_mode, &source_dir).map_err(|e| {
+            log::error!("[package_skill] {}", e);
+            e
+        })?;
+        if !lint_warnings.is_empty() {
+            log::warn!("[package_skill] {} accessibility lint issue(s) in '{}'", lint_warnings.len(), skill_name);
+        }
+        let validation = super::skill_validation::validate_for_packaging(&source_dir, refuse_on_errors).map_err(|e| {
+            log::error!("[package_skill] {}", e);
+            e
+        })?;
+        if !validation.is_empty() {
+            log::warn!("[package_skill] {} validation diagnostic(s) in '{}'", validation.len(), skill_name);
+        }
+        // Generate the onboarding checklist once; later packages keep author edits.
+        if !source_dir.join(super::onboarding::CHECKLIST_FILE).exists() {
+            if let Err(e) = super::onboarding::write_checklist(&source_dir, &skill_name) {
+                log::warn!("[package_skill] Could not generate onboarding checklist: {}", e);
+            }
+        }
+        // The Claude formats are rewritten from a generic package built alongside.
+        let generic_path = match format {
+            super::package_formats::PackageFormat::GenericZip => output_path.clone(),
+            _ => output_path.with_extension("generic.tmp"),
+        };
+        let mut result = create_localized_skill_zip(&source_dir, &generic_path, locale.as_deref())?;
+        if let Some(dependencies) = dependencies {
+            result.size_bytes = super::skill_dependencies::append_to_package(&generic_path, &dependencies)?;
+            result.dependencies = dependencies.names;
+        }
+        if generic_path != output_path {
+            let converted =
+                super::package_formats::convert_package(&generic_path, &output_path, format, &skill_name, &source_dir);
+            let _ = std::fs::remove_file(&generic_path);
+            result.size_bytes = converted.map_err(|e| {
+                log::error!("[package_skill] {}", e);
+                e
+            })?;
+            result.file_path = output_path.to_string_lossy().to_string();
+            result.format = format.as_str().to_string();
+        }
+        Ok::<_, String>(PackageResult { lint_warnings, quality_gate, validation, ..result })
+    })
         .await
         .map_err(|e| {
             let msg = format!("Packaging task failed: {}", e);
@@ -1950,6 +2603,12 @@ pub async fn package_skill(
             msg
         })??;
 
+    crate::automation_hooks::publish(crate::automation_hooks::HookEvent::SkillPackaged {
+        skill_name: packaged_skill,
+        file_path: result.file_path.clone(),
+        size_bytes: result.size_bytes,
+        locale: packaged_locale,
+    });
     Ok(result)
 }
 
@@ -1978,7 +2637,22 @@ fn copy_directory_recursive(src: &Path, dest: &Path) -> Result<(), String> {
     Ok(())
 }
 
-fn create_skill_zip(source_dir: &Path, output_path: &Path) -> Result<PackageResult, String> {
+pub(crate) fn create_skill_zip(source_dir: &Path, output_path: &Path) -> Result<PackageResult, String> {
+    create_localized_skill_zip(source_dir, output_path, None)
+}
+
+/// Package `source_dir`. Without a locale every `SKILL.<lang>.md` variant is
+/// bundled next to SKILL.md; with one, that variant becomes the package's
+/// SKILL.md and no other variant is included.
+pub(crate) fn create_localized_skill_zip(
+    source_dir: &Path,
+    output_path: &Path,
+    locale: Option<&str>,
+) -> Result<PackageResult, String> {
+    let localized_md = locale.map(|lang| source_dir.join(super::skill_localization::variant_file_name(lang)));
+    if let Some(path) = localized_md.as_ref().filter(|p| !p.exists()) {
+        return Err(format!("No translation found at {}", path.display()));
+    }
     let file = std::fs::File::create(output_path)
         .map_err(|e| format!("Failed to create zip file: {}", e))?;
     let mut zip = zip::ZipWriter::new(file);
@@ -1986,11 +2660,26 @@ fn create_skill_zip(source_dir: &Path, output_path: &Path) -> Result<PackageResu
         .compression_method(zip::CompressionMethod::Deflated);
 
     // SKILL.md and references/ are directly in source_dir
-    let skill_md = source_dir.join("SKILL.md");
+    let skill_md = localized_md.unwrap_or_else(|| source_dir.join("SKILL.md"));
     if skill_md.exists() {
         add_file_to_zip(&mut zip, &skill_md, "SKILL.md", options)?;
     }
 
+    let locales = if locale.is_some() {
+        Vec::new()
+    } else {
+        super::skill_localization::list_variants(source_dir)
+    };
+    for lang in &locales {
+        let name = super::skill_localization::variant_file_name(lang);
+        add_file_to_zip(&mut zip, &source_dir.join(&name), &name, options)?;
+    }
+
+    let checklist = source_dir.join(super::onboarding::CHECKLIST_FILE);
+    if checklist.exists() {
+        add_file_to_zip(&mut zip, &checklist, super::onboarding::CHECKLIST_FILE, options)?;
+    }
+
     let references_dir = source_dir.join("references");
     if references_dir.exists() && references_dir.is_dir() {
         add_dir_to_zip(&mut zip, &references_dir, "references", options)?;
@@ -2005,10 +2694,16 @@ fn create_skill_zip(source_dir: &Path, output_path: &Path) -> Result<PackageResu
     Ok(PackageResult {
         file_path: output_path.to_string_lossy().to_string(),
         size_bytes: metadata.len(),
+        lint_warnings: Vec::new(),
+        quality_gate: None,
+        validation: Vec::new(),
+        dependencies: Vec::new(),
+        locales,
+        format: super::package_formats::FORMAT_GENERIC_ZIP.to_string(),
     })
 }
 
-fn add_file_to_zip(
+pub(crate) fn add_file_to_zip(
     zip: &mut zip::ZipWriter<std::fs::File>,
     file_path: &Path,
     archive_name: &str,
@@ -2026,7 +2721,7 @@ fn add_file_to_zip(
     Ok(())
 }
 
-fn add_dir_to_zip(
+pub(crate) fn add_dir_to_zip(
     zip: &mut zip::ZipWriter<std::fs::File>,
     dir: &Path,
     prefix: &str,
@@ -2074,6 +2769,7 @@ pub fn save_workflow_state(
     status: String,
     purpose: String,
     step_statuses: Vec<StepStatusUpdate>,
+    instance: tauri::State<'_, crate::InstanceInfo>,
     db: tauri::State<'_, Db>,
 ) -> Result<(), String> {
     log::info!(
@@ -2082,6 +2778,7 @@ pub fn save_workflow_state(
         current_step,
         status
     );
+    super::observer::ensure_writable(&skill_name)?;
     let conn = db.0.lock().map_err(|e| {
         log::error!("[save_workflow_state] Failed to acquire DB lock: {}", e);
         e.to_string()
@@ -2107,6 +2804,25 @@ pub fn save_workflow_state(
         status
     };
 
+    // Journal only transitions into "completed"; debounced saves resend every status.
+    let previous_steps = crate::db::get_workflow_steps(&conn, &skill_name).unwrap_or_default();
+    let previously_completed: HashSet<i32> = previous_steps
+        .iter()
+        .filter(|s| s.status == "completed")
+        .map(|s| s.step_id)
+        .collect();
+    // Observers only need to reload when the step or a step status moved.
+    let previous_step = crate::db::get_workflow_run(&conn, &skill_name)
+        .ok()
+        .flatten()
+        .map(|run| run.current_step);
+    let changed = previous_step != Some(current_step)
+        || step_statuses.iter().any(|update| {
+            !previous_steps
+                .iter()
+                .any(|s| s.step_id == update.step_id && s.status == update.status)
+        });
+
     crate::db::save_workflow_run(
         &conn,
         &skill_name,
@@ -2114,8 +2830,54 @@ pub fn save_workflow_state(
         &effective_status,
         &purpose,
     )?;
+    let mut newly_completed = Vec::new();
     for step in &step_statuses {
         crate::db::save_workflow_step(&conn, &skill_name, step.step_id, &step.status)?;
+        if step.status == "completed" && !previously_completed.contains(&step.step_id) {
+            crate::journal::record(crate::journal::JournalEvent::StepCompleted {
+                skill: skill_name.clone(),
+                step: step.step_id as u32,
+            });
+            crate::automation_hooks::publish(crate::automation_hooks::HookEvent::StepCompleted {
+                skill_name: skill_name.clone(),
+                step_id: step.step_id as u32,
+            });
+            newly_completed.push(step.step_id as u32);
+        }
+    }
+
+    if changed {
+        super::observer::record(&conn, &instance, &skill_name, super::observer::EVENT_WORKFLOW_SAVED);
+    }
+
+    let regenerated = newly_completed.contains(&3);
+    if regenerated {
+        if let Ok(Some(workspace_path)) = crate::db::read_settings(&conn).map(|s| s.workspace_path) {
+            super::incremental_generation::record_base(&conn, &workspace_path, &skill_name);
+        }
+    }
+
+    // Retain sub-agent outputs from the step's transcript for later inspection.
+    if !newly_completed.is_empty() {
+        if let Ok(Some(workspace_path)) = crate::db::read_settings(&conn).map(|s| s.workspace_path) {
+            for &step_id in &newly_completed {
+                match super::step_artifacts::capture_step_artifacts(&workspace_path, &skill_name, step_id) {
+                    Ok(Some(index)) => log::info!(
+                        "[save_workflow_state] retained {} sub-agent artifact(s) for '{}' step {}",
+                        index.artifacts.len(),
+                        skill_name,
+                        step_id
+                    ),
+                    Ok(None) => {}
+                    Err(e) => log::warn!(
+                        "[save_workflow_state] failed to capture artifacts for '{}' step {}: {}",
+                        skill_name,
+                        step_id,
+                        e
+                    ),
+                }
+            }
+        }
     }
 
     // Auto-commit when a step is completed.
@@ -2137,7 +2899,7 @@ pub fn save_workflow_state(
                     .filter(|s| s.status == "completed")
                     .map(|s| s.step_id)
                     .collect();
-                let msg = format!(
+                let mut msg = format!(
                     "{}: step {} completed",
                     skill_name,
                     completed_steps
@@ -2146,9 +2908,37 @@ pub fn save_workflow_state(
                         .collect::<Vec<_>>()
                         .join(", ")
                 );
+                // A regenerated SKILL.md gets its version bumped before the commit
+                // so the new version lands in the same commit as the content.
+                if regenerated {
+                    match super::skill_version::version_regenerated_skill(&conn, &skills_path, &skill_name) {
+                        Ok(Some(entry)) => msg.push_str(&format!(" (v{})", entry.version)),
+                        Ok(None) => {}
+                        Err(e) => log::warn!("[save_workflow_state] Failed to version '{}': {}", skill_name, e),
+                    }
+                }
                 if let Err(e) = crate::git::commit_all(std::path::Path::new(&skills_path), &msg) {
                     log::warn!("Git auto-commit failed ({}): {}", msg, e);
                 }
+                // Cache outputs after versioning so a reused SKILL.md matches the commit.
+                if let Some(workspace_path) = settings.workspace_path.as_deref() {
+                    for &step_id in &newly_completed {
+                        if let Err(e) = super::step_cache::snapshot_completed_step(
+                            &conn,
+                            workspace_path,
+                            &skills_path,
+                            &skill_name,
+                            step_id,
+                        ) {
+                            log::warn!(
+                                "[save_workflow_state] Failed to cache outputs of '{}' step {}: {}",
+                                skill_name,
+                                step_id,
+                                e
+                            );
+                        }
+                    }
+                }
             }
             Err(e) => {
                 log::warn!(
@@ -2250,6 +3040,7 @@ pub fn save_clarifications_content(
     workspace_path: String,
     content: String,
 ) -> Result<(), String> {
+    super::observer::ensure_writable(&skill_name)?;
     let parsed: serde_json::Value =
         serde_json::from_str(&content).map_err(|e| format!("Invalid clarifications JSON: {}", e))?;
     validate_clarifications_json(&parsed)
@@ -2281,6 +3072,7 @@ pub fn save_decisions_content(
     workspace_path: String,
     content: String,
 ) -> Result<(), String> {
+    super::observer::ensure_writable(&skill_name)?;
     if content.trim().is_empty() {
         return Err("decisions.json content cannot be empty".to_string());
     }
@@ -2330,7 +3122,7 @@ pub async fn run_answer_evaluator(
             log::error!("run_answer_evaluator: failed to read settings: {}", e);
             e.to_string()
         })?;
-        let key = match settings.anthropic_api_key {
+        let key = match crate::commands::offline::agent_api_key(&settings) {
             Some(k) => k,
             None => {
                 log::error!("run_answer_evaluator: API key not configured");
@@ -2419,6 +3211,10 @@ pub async fn run_answer_evaluator(
         agent_name: Some("answer-evaluator".to_string()),
         required_plugins: None,
         conversation_history: None,
+        resume: None,
+        bash_sandbox: None,
+        disallowed_tools: None,
+        local_model: None,
     };
 
     sidecar::spawn_sidecar(
@@ -2443,6 +3239,7 @@ pub fn autofill_clarifications(
     db: tauri::State<'_, Db>,
 ) -> Result<u32, String> {
     log::info!("autofill_clarifications: skill={}", skill_name);
+    super::observer::ensure_writable(&skill_name)?;
 
     let workspace_path =
         read_workspace_path(&db).ok_or_else(|| "Workspace path not configured".to_string())?;
@@ -2500,6 +3297,7 @@ pub fn log_gate_decision(skill_name: String, verdict: String, decision: String)
 #[tauri::command]
 pub fn autofill_refinements(skill_name: String, db: tauri::State<'_, Db>) -> Result<u32, String> {
     log::info!("autofill_refinements: skill={}", skill_name);
+    super::observer::ensure_writable(&skill_name)?;
 
     let workspace_path =
         read_workspace_path(&db).ok_or_else(|| "Workspace path not configured".to_string())?;
@@ -2644,11 +3442,27 @@ fn autofill_answers(content: &str) -> (String, u32) {
     (updated, count)
 }
 
+/// Snapshot the skill before its step outputs are deleted, so the reset can be
+/// undone with `restore_workspace_snapshot`. Failure is logged, not fatal.
+fn snapshot_before_reset(db: &Db, workspace_path: &str, skills_path: &str, skill_name: &str, reason: &str) {
+    let conn = match db.0.lock() {
+        Ok(conn) => conn,
+        Err(e) => {
+            log::warn!("[snapshot_before_reset] Failed to acquire DB lock: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = super::workspace_snapshots::create_snapshot(&conn, workspace_path, skills_path, skill_name, reason) {
+        log::warn!("[snapshot_before_reset] Failed to snapshot '{}': {}", skill_name, e);
+    }
+}
+
 #[tauri::command]
 pub fn reset_workflow_step(
     workspace_path: String,
     skill_name: String,
     from_step_id: u32,
+    instance: tauri::State<'_, crate::InstanceInfo>,
     db: tauri::State<'_, Db>,
 ) -> Result<(), String> {
     log::info!(
@@ -2657,10 +3471,19 @@ pub fn reset_workflow_step(
         from_step_id,
         workspace_path
     );
+    super::observer::ensure_writable(&skill_name)?;
     let skills_path = read_skills_path(&db)
         .ok_or_else(|| "Skills path not configured. Please set it in Settings.".to_string())?;
     log::debug!("[reset_workflow_step] skills_path={}", skills_path);
 
+    snapshot_before_reset(
+        &db,
+        &workspace_path,
+        &skills_path,
+        &skill_name,
+        &format!("before reset to step {}", from_step_id),
+    );
+
     // Auto-commit: checkpoint before artifacts are deleted
     let msg = format!(
         "{}: checkpoint before reset to step {}",
@@ -2670,12 +3493,24 @@ pub fn reset_workflow_step(
         log::warn!("Git auto-commit failed ({}): {}", msg, e);
     }
 
-    crate::cleanup::delete_step_output_files(
-        &workspace_path,
-        &skill_name,
-        from_step_id,
-        &skills_path,
-    );
+    // Re-running Generate Skill after a decisions edit patches the existing
+    // SKILL.md, so keep it when the run will be incremental.
+    let keep_generated = from_step_id == 3
+        && db.0.lock().is_ok_and(|conn| {
+            super::incremental_generation::regeneration_mode(&conn, &workspace_path, &skills_path, &skill_name)
+                .is_ok_and(|mode| mode.incremental)
+        });
+    if keep_generated {
+        log::info!("[reset_workflow_step] keeping SKILL.md of '{}' for incremental regeneration", skill_name);
+        super::step_artifacts::delete_step_artifacts(&workspace_path, &skill_name, 3);
+    } else {
+        crate::cleanup::delete_step_output_files(
+            &workspace_path,
+            &skill_name,
+            from_step_id,
+            &skills_path,
+        );
+    }
 
     // Reset steps in SQLite
     let conn = db.0.lock().map_err(|e| e.to_string())?;
@@ -2691,10 +3526,98 @@ pub fn reset_workflow_step(
             &run.purpose,
         )?;
     }
+    super::observer::record(&conn, &instance, &skill_name, super::observer::EVENT_WORKFLOW_RESET);
 
     Ok(())
 }
 
+/// Reset from `from_step_id` like `reset_workflow_step`, but keep the listed
+/// output files (paths as returned by `preview_step_reset`). The workflow
+/// restarts at the earliest step that loses a file, so keeping
+/// clarifications.json while dropping decisions.json re-runs only from
+/// Confirm Decisions.
+#[tauri::command]
+pub fn reset_workflow_artifacts(
+    workspace_path: String,
+    skill_name: String,
+    from_step_id: u32,
+    keep_files: Vec<String>,
+    instance: tauri::State<'_, crate::InstanceInfo>,
+    db: tauri::State<'_, Db>,
+) -> Result<crate::types::ArtifactResetResult, String> {
+    log::info!(
+        "[reset_workflow_artifacts] skill={} from_step={} keep={}",
+        skill_name,
+        from_step_id,
+        keep_files.len()
+    );
+    super::observer::ensure_writable(&skill_name)?;
+    let skills_path = read_skills_path(&db)
+        .ok_or_else(|| "Skills path not configured. Please set it in Settings.".to_string())?;
+    let preview = preview_step_reset_inner(&workspace_path, &skills_path, &skill_name, from_step_id);
+    let (restart_step, kept, deleted_files) = plan_artifact_reset(&preview, &keep_files).map_err(|e| {
+        log::error!("[reset_workflow_artifacts] {}", e);
+        e
+    })?;
+
+    snapshot_before_reset(
+        &db,
+        &workspace_path,
+        &skills_path,
+        &skill_name,
+        &format!("before selective reset to step {}", restart_step),
+    );
+    let msg = format!(
+        "{}: checkpoint before selective reset to step {}",
+        skill_name, restart_step
+    );
+    if let Err(e) = crate::git::commit_all(Path::new(&skills_path), &msg) {
+        log::warn!("Git auto-commit failed ({}): {}", msg, e);
+    }
+
+    // Steps before the restart step keep all their files, so only wipe from
+    // there. Kept files of later steps are held in memory and put back.
+    let mut saved = Vec::new();
+    for (step_id, file) in kept.iter().filter(|(step_id, _)| *step_id >= restart_step) {
+        let path = step_output_dir(&workspace_path, &skills_path, &skill_name, *step_id).join(file);
+        let content = std::fs::read(&path).map_err(|e| {
+            log::error!("[reset_workflow_artifacts] Failed to read {}: {}", path.display(), e);
+            format!("Failed to read {}: {}", file, e)
+        })?;
+        saved.push((path, content));
+    }
+    crate::cleanup::delete_step_output_files(&workspace_path, &skill_name, restart_step, &skills_path);
+    for (path, content) in &saved {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(path, content).map_err(|e| {
+            log::error!("[reset_workflow_artifacts] Failed to restore {}: {}", path.display(), e);
+            format!("Failed to restore {}: {}", path.display(), e)
+        })?;
+    }
+
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    crate::db::reset_workflow_steps_from(&conn, &skill_name, restart_step as i32)?;
+    if let Some(run) = crate::db::get_workflow_run(&conn, &skill_name)? {
+        crate::db::save_workflow_run(&conn, &skill_name, restart_step as i32, "pending", &run.purpose)?;
+    }
+    super::observer::record(&conn, &instance, &skill_name, super::observer::EVENT_WORKFLOW_RESET);
+
+    log::info!(
+        "[reset_workflow_artifacts] skill={} restart_step={} deleted={} kept={}",
+        skill_name,
+        restart_step,
+        deleted_files.len(),
+        kept.len()
+    );
+    Ok(crate::types::ArtifactResetResult {
+        current_step: restart_step,
+        deleted_files,
+        kept_files: kept.into_iter().map(|(_, file)| file).collect(),
+    })
+}
+
 /// Navigate back to a completed step: preserves the target step's output files and DB status,
 /// deletes only the files of subsequent steps, and sets current_step to target_step_id.
 /// This makes the DB the canonical source of truth for navigate-back transitions.
@@ -2703,6 +3626,7 @@ pub fn navigate_back_to_step(
     workspace_path: String,
     skill_name: String,
     target_step_id: u32,
+    instance: tauri::State<'_, crate::InstanceInfo>,
     db: tauri::State<'_, Db>,
 ) -> Result<(), String> {
     log::info!(
@@ -2711,10 +3635,19 @@ pub fn navigate_back_to_step(
         target_step_id,
         workspace_path
     );
+    super::observer::ensure_writable(&skill_name)?;
     let skills_path = read_skills_path(&db)
         .ok_or_else(|| "Skills path not configured. Please set it in Settings.".to_string())?;
     log::debug!("[navigate_back_to_step] skills_path={}", skills_path);
 
+    snapshot_before_reset(
+        &db,
+        &workspace_path,
+        &skills_path,
+        &skill_name,
+        &format!("before navigating back to step {}", target_step_id),
+    );
+
     // Auto-commit: checkpoint before artifacts are deleted
     let msg = format!(
         "{}: checkpoint before navigate back to step {}",
@@ -2750,6 +3683,7 @@ pub fn navigate_back_to_step(
             &run.purpose,
         )?;
     }
+    super::observer::record(&conn, &instance, &skill_name, super::observer::EVENT_WORKFLOW_RESET);
 
     log::info!(
         "[navigate_back_to_step] done skill={} current_step={}",
@@ -2876,8 +3810,41 @@ pub fn preview_step_reset(
     );
     let skills_path = read_skills_path(&db)
         .ok_or_else(|| "Skills path not configured. Please set it in Settings.".to_string())?;
-    let skill_output_dir = Path::new(&skills_path).join(&skill_name);
+    Ok(preview_step_reset_inner(&workspace_path, &skills_path, &skill_name, from_step_id))
+}
+
+/// Directory a step's output files are relative to: the skill output dir for
+/// step 3, the workspace skill dir otherwise.
+fn step_output_dir(workspace_path: &str, skills_path: &str, skill_name: &str, step_id: u32) -> PathBuf {
+    if step_id == 3 {
+        Path::new(skills_path).join(skill_name)
+    } else {
+        Path::new(workspace_path).join(skill_name)
+    }
+}
 
+/// Files under `dir`, relative to `base`, in sorted order.
+fn list_files_recursive(base: &Path, dir: &Path, out: &mut Vec<String>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            list_files_recursive(base, &path, out);
+        } else if let Ok(rel) = path.strip_prefix(base) {
+            out.push(rel.to_string_lossy().replace('\\', "/"));
+        }
+    }
+}
+
+fn preview_step_reset_inner(
+    workspace_path: &str,
+    skills_path: &str,
+    skill_name: &str,
+    from_step_id: u32,
+) -> Vec<crate::types::StepResetPreview> {
     let step_names = [
         "Research",
         "Detailed Research",
@@ -2888,35 +3855,16 @@ pub fn preview_step_reset(
     let mut result = Vec::new();
     for step_id in from_step_id..=3 {
         // skills_path is required — single code path, no workspace fallback
-        let mut existing_files: Vec<String> = Vec::new();
-
-        for file in get_step_output_files(step_id) {
-            let exists = if step_id == 3 {
-                skill_output_dir.join(file).exists()
-            } else {
-                Path::new(&workspace_path).join(&skill_name).join(file).exists()
-            };
-            if exists {
-                existing_files.push(file.to_string());
-            }
-        }
+        let dir = step_output_dir(workspace_path, skills_path, skill_name, step_id);
+        let mut existing_files: Vec<String> = get_step_output_files(step_id)
+            .into_iter()
+            .filter(|file| dir.join(file).exists())
+            .map(str::to_string)
+            .collect();
 
-        // Step 3: also list individual files in references/ directory
+        // Step 3: also list every file in the references/ tree
         if step_id == 3 {
-            let refs_dir = skill_output_dir.join("references");
-            if refs_dir.is_dir() {
-                if let Ok(entries) = std::fs::read_dir(&refs_dir) {
-                    for entry in entries.flatten() {
-                        let path = entry.path();
-                        if path.is_file() {
-                            if let Some(name) = path.file_name() {
-                                existing_files
-                                    .push(format!("references/{}", name.to_string_lossy()));
-                            }
-                        }
-                    }
-                }
-            }
+            list_files_recursive(&dir, &dir.join("references"), &mut existing_files);
         }
 
         if !existing_files.is_empty() {
@@ -2932,7 +3880,39 @@ pub fn preview_step_reset(
         }
     }
 
-    Ok(result)
+    result
+}
+
+/// Split a reset preview into the files to keep and delete, and pick the step
+/// the workflow restarts at: the earliest step that loses a file. Every kept
+/// file must be one the preview lists.
+fn plan_artifact_reset(
+    preview: &[crate::types::StepResetPreview],
+    keep_files: &[String],
+) -> Result<(u32, Vec<(u32, String)>, Vec<String>), String> {
+    if let Some(unknown) = keep_files
+        .iter()
+        .find(|k| !preview.iter().any(|s| s.files.contains(k)))
+    {
+        return Err(format!("'{}' is not an output of the steps being reset", unknown));
+    }
+    let mut kept = Vec::new();
+    let mut deleted = Vec::new();
+    for step in preview {
+        for file in &step.files {
+            if keep_files.contains(file) {
+                kept.push((step.step_id, file.clone()));
+            } else {
+                deleted.push(file.clone());
+            }
+        }
+    }
+    let restart_step = preview
+        .iter()
+        .find(|s| s.files.iter().any(|f| !keep_files.contains(f)))
+        .map(|s| s.step_id)
+        .ok_or_else(|| "Nothing to reset: every file is kept.".to_string())?;
+    Ok((restart_step, kept, deleted))
 }
 
 #[cfg(test)]
@@ -3749,6 +4729,31 @@ mod tests {
         assert!(!names.contains(&"workflow.md".to_string()));
     }
 
+    #[test]
+    fn test_package_skill_bundles_or_selects_translations() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source_dir = tmp.path().join("my-skill");
+        std::fs::create_dir_all(&source_dir).unwrap();
+        std::fs::write(source_dir.join("SKILL.md"), "# My Skill").unwrap();
+        std::fs::write(source_dir.join("SKILL.de.md"), "# Mein Skill").unwrap();
+
+        let all = create_skill_zip(&source_dir, &source_dir.join("my-skill.skill")).unwrap();
+        assert_eq!(all.locales, vec!["de"]);
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&all.file_path).unwrap()).unwrap();
+        assert!(archive.by_name("SKILL.de.md").is_ok());
+
+        let german_path = source_dir.join("my-skill.de.skill");
+        let german = create_localized_skill_zip(&source_dir, &german_path, Some("de")).unwrap();
+        assert!(german.locales.is_empty());
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&german.file_path).unwrap()).unwrap();
+        let mut skill_md = String::new();
+        archive.by_name("SKILL.md").unwrap().read_to_string(&mut skill_md).unwrap();
+        assert_eq!(skill_md, "# Mein Skill");
+        assert!(archive.by_name("SKILL.de.md").is_err());
+
+        assert!(create_localized_skill_zip(&source_dir, &source_dir.join("x.skill"), Some("fr")).is_err());
+    }
+
     #[test]
     fn test_package_skill_nested_references() {
         let tmp = tempfile::tempdir().unwrap();
@@ -4051,7 +5056,7 @@ mod tests {
         std::fs::write(src.path().join("README.txt"), "ignore me").unwrap();
 
         let workspace_path = workspace.path().to_str().unwrap();
-        copy_agents_to_claude_dir(src.path(), workspace_path).unwrap();
+        copy_agents_to_claude_dir(src.path(), None, workspace_path).unwrap();
 
         let claude_agents_dir = workspace.path().join(".claude").join("agents");
         assert!(claude_agents_dir.is_dir());
@@ -4069,6 +5074,33 @@ mod tests {
         assert_eq!(content, "# Research Entities");
     }
 
+    #[test]
+    fn test_copy_agents_to_claude_dir_prefers_overrides() {
+        let src = tempfile::tempdir().unwrap();
+        let custom = tempfile::tempdir().unwrap();
+        let workspace = tempfile::tempdir().unwrap();
+
+        std::fs::write(src.path().join("research-entities.md"), "# Bundled Entities").unwrap();
+        std::fs::write(src.path().join("consolidate-research.md"), "# Bundled Consolidate").unwrap();
+        std::fs::write(custom.path().join("research-entities.md"), "# Custom Entities").unwrap();
+        // Templates without a bundled counterpart are not deployed
+        std::fs::write(custom.path().join("extra-agent.md"), "# Extra").unwrap();
+
+        let workspace_path = workspace.path().to_str().unwrap();
+        copy_agents_to_claude_dir(src.path(), Some(custom.path()), workspace_path).unwrap();
+
+        let claude_agents_dir = workspace.path().join(".claude").join("agents");
+        assert_eq!(
+            std::fs::read_to_string(claude_agents_dir.join("research-entities.md")).unwrap(),
+            "# Custom Entities"
+        );
+        assert_eq!(
+            std::fs::read_to_string(claude_agents_dir.join("consolidate-research.md")).unwrap(),
+            "# Bundled Consolidate"
+        );
+        assert!(!claude_agents_dir.join("extra-agent.md").exists());
+    }
+
     #[test]
     fn test_copy_managed_plugins_replaces_managed_and_preserves_unmanaged() {
         let src = tempfile::tempdir().unwrap();
@@ -4447,36 +5479,57 @@ mod tests {
     }
 
     #[test]
-    fn test_workspace_already_copied_returns_false_for_unknown() {
+    fn test_workspace_copied_checksum_is_none_for_unknown() {
         // Use a unique path to avoid interference from other tests
         let path = format!("/tmp/test-workspace-unknown-{}", std::process::id());
-        assert!(!super::workspace_already_copied(&path));
+        assert!(super::workspace_copied_checksum(&path).is_none());
     }
 
     #[test]
-    fn test_mark_workspace_copied_then_already_copied() {
+    fn test_mark_workspace_copied_records_checksum() {
         let path = format!("/tmp/test-workspace-mark-{}", std::process::id());
-        assert!(!super::workspace_already_copied(&path));
-        super::mark_workspace_copied(&path);
-        assert!(super::workspace_already_copied(&path));
+        assert!(super::workspace_copied_checksum(&path).is_none());
+        super::mark_workspace_copied(&path, "abc");
+        assert_eq!(super::workspace_copied_checksum(&path).as_deref(), Some("abc"));
+        super::mark_workspace_copied(&path, "def");
+        assert_eq!(super::workspace_copied_checksum(&path).as_deref(), Some("def"));
     }
 
     #[test]
     fn test_workspace_copy_cache_is_per_workspace() {
         let path_a = format!("/tmp/test-ws-a-{}", std::process::id());
         let path_b = format!("/tmp/test-ws-b-{}", std::process::id());
-        super::mark_workspace_copied(&path_a);
-        assert!(super::workspace_already_copied(&path_a));
-        assert!(!super::workspace_already_copied(&path_b));
+        super::mark_workspace_copied(&path_a, "abc");
+        assert!(super::workspace_copied_checksum(&path_a).is_some());
+        assert!(super::workspace_copied_checksum(&path_b).is_none());
     }
 
     #[test]
     fn test_invalidate_workspace_cache() {
         let path = format!("/tmp/test-ws-invalidate-{}", std::process::id());
-        super::mark_workspace_copied(&path);
-        assert!(super::workspace_already_copied(&path));
+        super::mark_workspace_copied(&path, "abc");
+        assert!(super::workspace_copied_checksum(&path).is_some());
         super::invalidate_workspace_cache(&path);
-        assert!(!super::workspace_already_copied(&path));
+        assert!(super::workspace_copied_checksum(&path).is_none());
+    }
+
+    #[test]
+    fn test_prompt_sources_checksum_tracks_overrides() {
+        let src = tempfile::tempdir().unwrap();
+        let custom = tempfile::tempdir().unwrap();
+        std::fs::write(src.path().join("research-entities.md"), "# Bundled").unwrap();
+
+        let bundled = super::prompt_sources_checksum(src.path(), None);
+        // An empty override dir or an unrelated override file changes nothing
+        std::fs::write(custom.path().join("unknown-agent.md"), "# Unused").unwrap();
+        assert_eq!(super::prompt_sources_checksum(src.path(), Some(custom.path())), bundled);
+
+        std::fs::write(custom.path().join("research-entities.md"), "# Custom").unwrap();
+        let overridden = super::prompt_sources_checksum(src.path(), Some(custom.path()));
+        assert_ne!(overridden, bundled);
+
+        std::fs::write(custom.path().join("research-entities.md"), "# Custom v2").unwrap();
+        assert_ne!(super::prompt_sources_checksum(src.path(), Some(custom.path())), overridden);
     }
 
     #[test]
@@ -4516,6 +5569,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_preview_and_plan_selective_reset() {
+        let workspace_tmp = tempfile::tempdir().unwrap();
+        let skills_path_tmp = tempfile::tempdir().unwrap();
+        let workspace = workspace_tmp.path().to_str().unwrap();
+        let skills_path = skills_path_tmp.path().to_str().unwrap();
+        let context_dir = workspace_tmp.path().join("my-skill").join("context");
+        std::fs::create_dir_all(&context_dir).unwrap();
+        std::fs::write(context_dir.join("clarifications.json"), "{}").unwrap();
+        std::fs::write(context_dir.join("decisions.json"), "{}").unwrap();
+        let refs_dir = skills_path_tmp.path().join("my-skill").join("references").join("api");
+        std::fs::create_dir_all(&refs_dir).unwrap();
+        std::fs::write(skills_path_tmp.path().join("my-skill").join("SKILL.md"), "# Skill").unwrap();
+        std::fs::write(refs_dir.join("endpoints.md"), "# Endpoints").unwrap();
+
+        let preview = preview_step_reset_inner(workspace, skills_path, "my-skill", 0);
+        let files: Vec<(u32, &str)> = preview
+            .iter()
+            .flat_map(|s| s.files.iter().map(move |f| (s.step_id, f.as_str())))
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                (0, "context/clarifications.json"),
+                (2, "context/decisions.json"),
+                (3, "SKILL.md"),
+                (3, "references/api/endpoints.md"),
+            ]
+        );
+
+        // Keeping clarifications restarts at Confirm Decisions
+        let keep = vec!["context/clarifications.json".to_string(), "SKILL.md".to_string()];
+        let (restart, kept, deleted) = plan_artifact_reset(&preview, &keep).unwrap();
+        assert_eq!(restart, 2);
+        assert_eq!(kept, vec![(0, "context/clarifications.json".to_string()), (3, "SKILL.md".to_string())]);
+        assert_eq!(deleted, vec!["context/decisions.json", "references/api/endpoints.md"]);
+
+        assert!(plan_artifact_reset(&preview, &["../secrets".to_string()]).is_err());
+        let everything: Vec<String> = files.iter().map(|(_, f)| f.to_string()).collect();
+        assert!(plan_artifact_reset(&preview, &everything).is_err());
+    }
+
     // --- VD-664: parse_scope_recommendation tests ---
 
     #[test]
@@ -4718,6 +5813,20 @@ mod tests {
 
     // --- build_prompt user context integration tests ---
     // User context fields (industry, intake, behaviour) are now in user-context.md,
+    #[test]
+    fn test_build_prompt_points_to_repo_context_when_captured() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skills_path = tmp.path().to_str().unwrap();
+        let prompt = build_prompt("billing", "/tmp/ws", skills_path, None, None, 5);
+        assert!(!prompt.contains("Repository context"));
+
+        let overview = tmp.path().join("billing").join(crate::commands::repo_context::OVERVIEW_FILE);
+        std::fs::create_dir_all(overview.parent().unwrap()).unwrap();
+        std::fs::write(&overview, "# Repository context").unwrap();
+        let prompt = build_prompt("billing", "/tmp/ws", skills_path, None, None, 5);
+        assert!(prompt.contains("references/repo-context/overview.md"));
+    }
+
     // not inlined in the prompt. These tests verify the prompt references the file.
 
     #[test]
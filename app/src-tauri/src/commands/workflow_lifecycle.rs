@@ -1,7 +1,7 @@
 use rusqlite::Connection;
 use std::path::Path;
 
-const MAX_WORKFLOW_STEP_ID: u32 = 3;
+pub(crate) const MAX_WORKFLOW_STEP_ID: u32 = 3;
 
 pub fn start_session(
     conn: &Connection,
@@ -38,10 +38,12 @@ pub fn shutdown_sessions_for_pid(conn: &Connection, pid: u32) -> Result<u32, Str
     crate::db::end_all_sessions_for_pid(conn, pid)
 }
 
+/// `last_step_id` is `MAX_WORKFLOW_STEP_ID` unless a workflow manifest adds steps.
 pub fn validate_run_request(
     skill_name: &str,
     step_id: u32,
     workspace_path: &str,
+    last_step_id: u32,
 ) -> Result<(), String> {
     if skill_name.trim().is_empty() {
         return Err("Skill name is required".to_string());
@@ -49,10 +51,10 @@ pub fn validate_run_request(
     if workspace_path.trim().is_empty() {
         return Err("Workspace path is required".to_string());
     }
-    if step_id > MAX_WORKFLOW_STEP_ID {
+    if step_id > last_step_id {
         return Err(format!(
             "Unknown step_id {}. Valid steps are 0-{}.",
-            step_id, MAX_WORKFLOW_STEP_ID
+            step_id, last_step_id
         ));
     }
     if !Path::new(workspace_path).exists() {
@@ -96,14 +98,14 @@ mod tests {
     #[test]
     fn test_run_request_happy_path() {
         let tmp = tempfile::tempdir().unwrap();
-        let result = validate_run_request("my-skill", 2, &tmp.path().to_string_lossy());
+        let result = validate_run_request("my-skill", 2, &tmp.path().to_string_lossy(), MAX_WORKFLOW_STEP_ID);
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_run_request_failure_path_rejects_unknown_step() {
         let tmp = tempfile::tempdir().unwrap();
-        let err = validate_run_request("my-skill", 99, &tmp.path().to_string_lossy()).unwrap_err();
+        let err = validate_run_request("my-skill", 99, &tmp.path().to_string_lossy(), MAX_WORKFLOW_STEP_ID).unwrap_err();
         assert!(err.contains("Unknown step_id"));
     }
 
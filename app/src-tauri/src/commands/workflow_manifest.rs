@@ -0,0 +1,357 @@
+//! Per-purpose workflow manifests.
+//!
+//! The four built-in steps come from `workflow::get_step_config`. A manifest at
+//! `{workspace}/.claude/workflows/{purpose}.json` (or `default.json` for every
+//! purpose) can tune those steps and append custom ones, e.g. a "Compliance
+//! Review" after Generate Skill:
+//!
+//! ```json
+//! { "steps": [
+//!     { "builtin": 2, "thinking_budget": 48000 },
+//!     { "name": "Compliance Review", "prompt_template": "compliance-review.md",
+//!       "output_file": "context/compliance-review.md",
+//!       "allowed_tools": ["Read", "Write", "Glob", "Grep"], "max_turns": 40 }
+//! ] }
+//! ```
+//!
+//! Entries with `builtin` override fields of that step; entries without it are
+//! custom steps numbered from 4 in manifest order. Built-in steps keep their
+//! ids and relative order because later steps read their artifacts, so
+//! overrides must come before any custom step. Custom prompt templates live
+//! in `.claude/workflows/agents/` and are copied into `.claude/agents/` (which
+//! is re-deployed from the bundle) just before the step runs.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use super::workflow::{get_step_config, FULL_TOOLS};
+use super::workflow_lifecycle::MAX_WORKFLOW_STEP_ID;
+use crate::db::Db;
+use crate::types::StepConfig;
+
+const WORKFLOWS_DIR: &str = "workflows";
+const CUSTOM_AGENTS_DIR: &str = "agents";
+const DEFAULT_MANIFEST: &str = "default";
+
+const MAX_TURNS_LIMIT: u32 = 500;
+const MIN_THINKING_BUDGET: u32 = 1_024;
+const MAX_THINKING_BUDGET: u32 = 64_000;
+const EXTRA_TOOLS: &[&str] = &["WebSearch", "WebFetch", "TodoWrite"];
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WorkflowManifest {
+    steps: Vec<ManifestStep>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestStep {
+    builtin: Option<u32>,
+    name: Option<String>,
+    description: Option<String>,
+    prompt_template: Option<String>,
+    output_file: Option<String>,
+    allowed_tools: Option<Vec<String>>,
+    max_turns: Option<u32>,
+    thinking_budget: Option<u32>,
+}
+
+fn workflows_dir(workspace_path: &str) -> PathBuf {
+    Path::new(workspace_path).join(".claude").join(WORKFLOWS_DIR)
+}
+
+/// The manifest that applies to `purpose`, if any.
+fn manifest_path(workspace_path: &str, purpose: &str) -> Option<PathBuf> {
+    let dir = workflows_dir(workspace_path);
+    [purpose, DEFAULT_MANIFEST]
+        .iter()
+        .filter(|name| !name.is_empty())
+        .map(|name| dir.join(format!("{}.json", name)))
+        .find(|path| path.is_file())
+}
+
+fn builtin_steps() -> Vec<StepConfig> {
+    (0..=MAX_WORKFLOW_STEP_ID)
+        .map(|id| get_step_config(id).expect("built-in step ids are valid"))
+        .collect()
+}
+
+fn validate_template(workspace_path: &str, label: &str, template: &str) -> Result<(), String> {
+    if !template.ends_with(".md") || template.contains(['/', '\\']) || template.starts_with('.') {
+        return Err(format!(
+            "{}: prompt_template '{}' must be a .md file name without a directory",
+            label, template
+        ));
+    }
+    let custom = workflows_dir(workspace_path).join(CUSTOM_AGENTS_DIR).join(template);
+    let bundled = Path::new(workspace_path).join(".claude").join("agents").join(template);
+    if !custom.is_file() && !bundled.is_file() {
+        return Err(format!(
+            "{}: prompt_template '{}' not found in .claude/workflows/{}/",
+            label, template, CUSTOM_AGENTS_DIR
+        ));
+    }
+    Ok(())
+}
+
+fn validate_output_file(label: &str, output_file: &str) -> Result<(), String> {
+    let path = Path::new(output_file);
+    let relative = !output_file.is_empty()
+        && path.components().all(|c| matches!(c, std::path::Component::Normal(_)));
+    if !relative {
+        return Err(format!(
+            "{}: output_file '{}' must be a relative path inside the skill directory",
+            label, output_file
+        ));
+    }
+    Ok(())
+}
+
+fn apply_overrides(
+    workspace_path: &str,
+    label: &str,
+    step: &mut StepConfig,
+    entry: ManifestStep,
+) -> Result<(), String> {
+    if let Some(template) = entry.prompt_template {
+        validate_template(workspace_path, label, &template)?;
+        step.prompt_template = template;
+    }
+    if let Some(tools) = entry.allowed_tools {
+        if let Some(unknown) = tools
+            .iter()
+            .find(|t| !FULL_TOOLS.contains(&t.as_str()) && !EXTRA_TOOLS.contains(&t.as_str()))
+        {
+            return Err(format!("{}: unknown tool '{}' in allowed_tools", label, unknown));
+        }
+        step.allowed_tools = tools;
+    }
+    if let Some(max_turns) = entry.max_turns {
+        if max_turns == 0 || max_turns > MAX_TURNS_LIMIT {
+            return Err(format!("{}: max_turns must be between 1 and {}", label, MAX_TURNS_LIMIT));
+        }
+        step.max_turns = max_turns;
+    }
+    if let Some(budget) = entry.thinking_budget {
+        // 0 turns thinking off for the step even when it is enabled in settings.
+        if budget != 0 && !(MIN_THINKING_BUDGET..=MAX_THINKING_BUDGET).contains(&budget) {
+            return Err(format!(
+                "{}: thinking_budget must be 0 or between {} and {}",
+                label, MIN_THINKING_BUDGET, MAX_THINKING_BUDGET
+            ));
+        }
+        step.thinking_budget = (budget != 0).then_some(budget);
+    }
+    if entry.description.is_some() {
+        step.description = entry.description;
+    }
+    Ok(())
+}
+
+/// Built-in steps with the manifest applied. Errors name the manifest file
+/// and the offending entry.
+fn parse_manifest(workspace_path: &str, content: &str) -> Result<Vec<StepConfig>, String> {
+    let manifest: WorkflowManifest =
+        serde_json::from_str(content).map_err(|e| format!("Invalid workflow manifest: {}", e))?;
+    let mut steps = builtin_steps();
+    let mut overridden = HashSet::new();
+    let mut names: HashSet<String> = steps.iter().map(|s| s.name.to_lowercase()).collect();
+
+    for (index, mut entry) in manifest.steps.into_iter().enumerate() {
+        let label = format!("steps[{}]", index);
+        if let Some(builtin) = entry.builtin {
+            if builtin > MAX_WORKFLOW_STEP_ID {
+                return Err(format!(
+                    "{}: builtin must be 0-{}, got {}",
+                    label, MAX_WORKFLOW_STEP_ID, builtin
+                ));
+            }
+            if steps.len() as u32 > MAX_WORKFLOW_STEP_ID + 1 {
+                return Err(format!("{}: built-in step overrides must come before custom steps", label));
+            }
+            if !overridden.insert(builtin) {
+                return Err(format!("{}: builtin step {} is listed more than once", label, builtin));
+            }
+            if entry.output_file.is_some() {
+                return Err(format!("{}: the output_file of a built-in step cannot be changed", label));
+            }
+            let step = &mut steps[builtin as usize];
+            if let Some(name) = entry.name.take() {
+                step.name = name;
+            }
+            apply_overrides(workspace_path, &label, step, entry)?;
+            continue;
+        }
+
+        let name = entry.name.take().map(|n| n.trim().to_string()).unwrap_or_default();
+        if name.is_empty() {
+            return Err(format!("{}: custom steps need a name", label));
+        }
+        if !names.insert(name.to_lowercase()) {
+            return Err(format!("{}: duplicate step name '{}'", label, name));
+        }
+        let Some(prompt_template) = entry.prompt_template.clone() else {
+            return Err(format!("{}: custom step '{}' needs a prompt_template", label, name));
+        };
+        let output_file = entry.output_file.take().unwrap_or_default();
+        validate_output_file(&label, &output_file)?;
+        let mut step = StepConfig {
+            step_id: steps.len() as u32,
+            name,
+            prompt_template,
+            output_file,
+            allowed_tools: FULL_TOOLS.iter().map(|s| s.to_string()).collect(),
+            max_turns: 50,
+            thinking_budget: None,
+            description: None,
+            custom: true,
+        };
+        apply_overrides(workspace_path, &label, &mut step, entry)?;
+        steps.push(step);
+    }
+    Ok(steps)
+}
+
+/// Steps for a workflow of `purpose`: the built-in steps, or the manifest's
+/// pipeline when one exists.
+pub(crate) fn load_steps(workspace_path: &str, purpose: &str) -> Result<Vec<StepConfig>, String> {
+    let Some(path) = manifest_path(workspace_path, purpose) else {
+        return Ok(builtin_steps());
+    };
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse_manifest(workspace_path, &content).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// The configuration of one step, honouring the manifest.
+pub(crate) fn resolve_step(workspace_path: &str, purpose: &str, step_id: u32) -> Result<StepConfig, String> {
+    let steps = load_steps(workspace_path, purpose)?;
+    let last = steps.len().saturating_sub(1);
+    steps
+        .into_iter()
+        .find(|s| s.step_id == step_id)
+        .ok_or_else(|| format!("Unknown step_id {}. Valid steps are 0-{}.", step_id, last))
+}
+
+/// Highest step id a skill's workflow accepts.
+pub(crate) fn last_step_id(db: &Db, skill_name: &str, workspace_path: &str) -> Result<u32, String> {
+    let purpose = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        crate::db::get_purpose(&conn, skill_name)?
+    };
+    let steps = load_steps(workspace_path, &purpose)?;
+    Ok(steps.len().saturating_sub(1) as u32)
+}
+
+/// Copy a manifest-provided prompt template into `.claude/agents/` so the SDK
+/// can load it. Bundled templates are left alone.
+pub(crate) fn deploy_step_template(workspace_path: &str, step: &StepConfig) -> Result<(), String> {
+    let source = workflows_dir(workspace_path)
+        .join(CUSTOM_AGENTS_DIR)
+        .join(&step.prompt_template);
+    if !source.is_file() {
+        return Ok(());
+    }
+    let agents_dir = Path::new(workspace_path).join(".claude").join("agents");
+    std::fs::create_dir_all(&agents_dir)
+        .map_err(|e| format!("Failed to create .claude/agents dir: {}", e))?;
+    std::fs::copy(&source, agents_dir.join(&step.prompt_template))
+        .map_err(|e| format!("Failed to deploy {}: {}", source.display(), e))?;
+    Ok(())
+}
+
+/// Effective step list for a skill, for the workflow page.
+#[tauri::command]
+pub fn get_workflow_steps(skill_name: String, db: tauri::State<'_, Db>) -> Result<Vec<StepConfig>, String> {
+    log::info!("[get_workflow_steps] skill={}", skill_name);
+    let workspace_path = super::workflow::read_workspace_path(&db)
+        .ok_or_else(|| "Workspace path not configured".to_string())?;
+    let purpose = {
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        crate::db::get_purpose(&conn, &skill_name)?
+    };
+    load_steps(&workspace_path, &purpose).map_err(|e| {
+        log::error!("[get_workflow_steps] {}", e);
+        e
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_template(workspace: &Path, name: &str) {
+        let dir = workflows_dir(&workspace.to_string_lossy()).join(CUSTOM_AGENTS_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(name), "---\nname: compliance-review\n---\nReview.").unwrap();
+    }
+
+    #[test]
+    fn test_no_manifest_uses_builtin_steps() {
+        let tmp = tempdir().unwrap();
+        let steps = load_steps(&tmp.path().to_string_lossy(), "domain").unwrap();
+        assert_eq!(steps.len(), 4);
+        assert!(steps.iter().all(|s| !s.custom));
+        assert!(resolve_step(&tmp.path().to_string_lossy(), "domain", 4).is_err());
+    }
+
+    #[test]
+    fn test_manifest_overrides_builtin_and_appends_custom_step() {
+        let tmp = tempdir().unwrap();
+        let workspace = tmp.path().to_string_lossy().to_string();
+        write_template(tmp.path(), "compliance-review.md");
+        std::fs::write(
+            workflows_dir(&workspace).join("platform.json"),
+            r#"{ "steps": [
+                { "builtin": 2, "thinking_budget": 48000, "max_turns": 80 },
+                { "name": "Compliance Review", "description": "Check policy",
+                  "prompt_template": "compliance-review.md",
+                  "output_file": "context/compliance-review.md",
+                  "allowed_tools": ["Read", "Write", "Grep"], "max_turns": 40 }
+            ] }"#,
+        )
+        .unwrap();
+
+        let steps = load_steps(&workspace, "platform").unwrap();
+        assert_eq!(steps.len(), 5);
+        assert_eq!(steps[2].thinking_budget, Some(48_000));
+        assert_eq!(steps[2].max_turns, 80);
+        let custom = resolve_step(&workspace, "platform", 4).unwrap();
+        assert!(custom.custom);
+        assert_eq!(custom.name, "Compliance Review");
+        assert_eq!(custom.allowed_tools, vec!["Read", "Write", "Grep"]);
+        // Other purposes keep the built-in pipeline.
+        assert_eq!(load_steps(&workspace, "domain").unwrap().len(), 4);
+
+        deploy_step_template(&workspace, &custom).unwrap();
+        assert!(tmp.path().join(".claude/agents/compliance-review.md").is_file());
+    }
+
+    #[test]
+    fn test_manifest_validation_errors() {
+        let tmp = tempdir().unwrap();
+        let workspace = tmp.path().to_string_lossy().to_string();
+        write_template(tmp.path(), "extra.md");
+        let custom = r#"{ "name": "Extra", "prompt_template": "extra.md", "output_file": "context/extra.md" }"#;
+        let cases = [
+            (r#"{ "steps": [ { "builtin": 7 } ] }"#.to_string(), "builtin must be 0-3"),
+            (format!(r#"{{ "steps": [ {}, {{ "builtin": 1 }} ] }}"#, custom), "before custom steps"),
+            (r#"{ "steps": [ { "builtin": 3, "output_file": "x.md" } ] }"#.to_string(), "cannot be changed"),
+            (r#"{ "steps": [ { "name": "Research", "prompt_template": "extra.md", "output_file": "a" } ] }"#.to_string(), "duplicate step name"),
+            (r#"{ "steps": [ { "name": "X", "prompt_template": "missing.md", "output_file": "a" } ] }"#.to_string(), "not found"),
+            (r#"{ "steps": [ { "name": "X", "prompt_template": "extra.md", "output_file": "../a" } ] }"#.to_string(), "relative path"),
+            (r#"{ "steps": [ { "builtin": 0, "allowed_tools": ["Teleport"] } ] }"#.to_string(), "unknown tool"),
+            (r#"{ "steps": [ { "builtin": 0, "max_turns": 0 } ] }"#.to_string(), "max_turns"),
+            (r#"{ "steps": [ { "builtin": 0, "thinking_budget": 10 } ] }"#.to_string(), "thinking_budget"),
+            (r#"{ "steps": [ { "builtin": 0, "colour": "red" } ] }"#.to_string(), "unknown field"),
+        ];
+        for (content, expected) in cases {
+            let err = parse_manifest(&workspace, &content).unwrap_err();
+            assert!(err.contains(expected), "{} -> {}", content, err);
+        }
+    }
+}
@@ -0,0 +1,194 @@
+//! Queue of workflow steps run unattended, e.g. Research for five skills
+//! overnight.
+//!
+//! Jobs live in the `workflow_queue` table so they survive restarts; a job
+//! left running by a crashed process is requeued at startup. The worker runs
+//! up to `workflow_queue_concurrency` jobs at once against the sidecar pool,
+//! never two steps of the same skill, and each job goes through the same
+//! run, materialize, verify and save path as `--headless run-step`. A failed
+//! step cancels the skill's later queued steps.
+
+use tauri::Manager;
+
+use crate::db::Db;
+use crate::types::WorkflowQueueJob;
+
+/// How often the worker re-checks the queue without being woken.
+const TICK_SECS: u64 = 30;
+
+/// Wakes the worker when jobs are queued or finish.
+#[derive(Default)]
+pub struct WorkflowQueue {
+    wake: tokio::sync::Notify,
+    /// Set while agents are paused from the tray; no new jobs are claimed.
+    paused: std::sync::atomic::AtomicBool,
+}
+
+impl WorkflowQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Hold or release queued jobs. Resuming wakes the worker immediately.
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, std::sync::atomic::Ordering::SeqCst);
+        if !paused {
+            self.wake.notify_one();
+        }
+    }
+}
+
+/// Run one claimed job, holding the skill lock unless this instance already
+/// holds it (the skill is open in the workflow page).
+async fn run_job(app: &tauri::AppHandle, job: &WorkflowQueueJob) -> Result<(), String> {
+    let instance = app.state::<crate::InstanceInfo>();
+    let held = {
+        let db = app.state::<Db>();
+        let conn = db.0.lock().map_err(|e| e.to_string())?;
+        let held = crate::db::get_skill_lock(&conn, &job.skill_name)?.is_some_and(|l| l.instance_id == instance.id);
+        if !held {
+            crate::db::acquire_skill_lock(&conn, &job.skill_name, &instance.id, instance.pid)?;
+        }
+        held
+    };
+    let result = crate::headless::run_step(app, &job.skill_name, job.step_id, &job.workspace_path, |_| {}).await;
+    if !held {
+        let db = app.state::<Db>();
+        let released = db
+            .0
+            .lock()
+            .map_err(|e| e.to_string())
+            .and_then(|conn| crate::db::release_skill_lock(&conn, &job.skill_name, &instance.id));
+        if let Err(e) = released {
+            log::warn!("[workflow_queue] Failed to release lock on '{}': {}", job.skill_name, e);
+        }
+    }
+    result
+}
+
+/// Claim as many jobs as the concurrency setting allows.
+fn claim(app: &tauri::AppHandle) -> Result<Vec<WorkflowQueueJob>, String> {
+    let db = app.state::<Db>();
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    let concurrency = crate::db::read_settings(&conn)?.workflow_queue_concurrency;
+    crate::db::claim_workflow_jobs(&conn, concurrency)
+}
+
+/// Requeue jobs orphaned by a crash and start the worker loop. Safe to call
+/// from `setup()`.
+pub fn start(app: tauri::AppHandle) {
+    {
+        let db = app.state::<Db>();
+        let requeued = db
+            .0
+            .lock()
+            .map_err(|e| e.to_string())
+            .and_then(|conn| crate::db::requeue_orphaned_workflow_jobs(&conn));
+        match requeued {
+            Ok(0) => {}
+            Ok(n) => log::info!("[workflow_queue] requeued {} interrupted job(s)", n),
+            Err(e) => log::warn!("[workflow_queue] Failed to requeue interrupted jobs: {}", e),
+        }
+    }
+    // A raised concurrency limit takes effect now rather than on the next tick.
+    {
+        let app = app.clone();
+        crate::settings_events::subscribe("workflow_queue", move |change| {
+            if change.touches("workflow_queue_concurrency") {
+                app.state::<WorkflowQueue>().wake.notify_one();
+            }
+        });
+    }
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let jobs = if app.state::<WorkflowQueue>().is_paused() {
+                Vec::new()
+            } else {
+                claim(&app).unwrap_or_else(|e| {
+                    log::warn!("[workflow_queue] Failed to claim jobs: {}", e);
+                    Vec::new()
+                })
+            };
+            for job in jobs {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    log::info!("[workflow_queue] job {}: skill={} step={}", job.id, job.skill_name, job.step_id);
+                    let result = run_job(&app, &job).await;
+                    if let Err(e) = &result {
+                        log::warn!("[workflow_queue] job {} failed: {}", job.id, e);
+                    }
+                    let db = app.state::<Db>();
+                    let finished = db
+                        .0
+                        .lock()
+                        .map_err(|e| e.to_string())
+                        .and_then(|conn| crate::db::finish_workflow_job(&conn, job.id, &result));
+                    if let Err(e) = finished {
+                        log::error!("[workflow_queue] Failed to record job {} result: {}", job.id, e);
+                    }
+                    app.state::<WorkflowQueue>().wake.notify_one();
+                });
+            }
+            let queue = app.state::<WorkflowQueue>();
+            let _ = tokio::time::timeout(std::time::Duration::from_secs(TICK_SECS), queue.wake.notified()).await;
+        }
+    });
+}
+
+/// Queue a workflow step to run unattended. Returns the job id.
+#[tauri::command]
+pub fn enqueue_workflow_step(
+    skill_name: String,
+    step_id: u32,
+    workspace_path: String,
+    db: tauri::State<'_, Db>,
+    queue: tauri::State<'_, WorkflowQueue>,
+) -> Result<i64, String> {
+    log::info!("[enqueue_workflow_step] skill={} step={}", skill_name, step_id);
+    super::observer::ensure_writable(&skill_name)?;
+    let last_step_id = crate::commands::workflow_manifest::last_step_id(&db, &skill_name, &workspace_path)?;
+    crate::commands::workflow_lifecycle::validate_run_request(&skill_name, step_id, &workspace_path, last_step_id)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[enqueue_workflow_step] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    if crate::db::get_workflow_run(&conn, &skill_name)?.is_none() {
+        return Err(format!("Skill '{}' has no workflow run", skill_name));
+    }
+    let id = crate::db::enqueue_workflow_job(&conn, &skill_name, step_id, &workspace_path).map_err(|e| {
+        log::error!("[enqueue_workflow_step] {}", e);
+        e
+    })?;
+    queue.wake.notify_one();
+    Ok(id)
+}
+
+/// Running and queued jobs in run order, then recently finished ones.
+#[tauri::command]
+pub fn list_queue(db: tauri::State<'_, Db>) -> Result<Vec<WorkflowQueueJob>, String> {
+    log::debug!("[list_queue]");
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[list_queue] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::list_workflow_queue(&conn)
+}
+
+/// Cancel a job that hasn't started. Running steps are stopped like any other
+/// agent run.
+#[tauri::command]
+pub fn cancel_queued(job_id: i64, db: tauri::State<'_, Db>) -> Result<(), String> {
+    log::info!("[cancel_queued] job={}", job_id);
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[cancel_queued] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    crate::db::cancel_workflow_job(&conn, job_id).map_err(|e| {
+        log::error!("[cancel_queued] {}", e);
+        e
+    })
+}
@@ -319,6 +319,17 @@ pub fn reconcile_startup(
     log::debug!("[reconcile_startup] workspace={} skills_path={}", workspace_path, skills_path);
 
     let result = if apply {
+        // Record usage from the last cost tick of agents that crashed mid-run
+        match crate::db::recover_orphaned_run_ticks(&conn) {
+            Ok(count) if count > 0 => {
+                log::info!("Recovered usage for {} interrupted agent run(s)", count);
+            }
+            Err(e) => {
+                log::warn!("Failed to recover interrupted agent runs: {}", e);
+            }
+            _ => {}
+        }
+
         // Reconcile orphaned workflow sessions from crashed instances
         match crate::db::reconcile_orphaned_sessions(&conn) {
             Ok(count) if count > 0 => {
@@ -524,7 +535,12 @@ pub fn create_workflow_session(
         &session_id,
         &skill_name,
         instance.pid,
-    )
+    )?;
+    crate::journal::record(crate::journal::JournalEvent::SessionStarted {
+        skill: skill_name,
+        session_id,
+    });
+    Ok(())
 }
 
 #[tauri::command]
@@ -537,7 +553,9 @@ pub fn end_workflow_session(
         log::error!("[end_workflow_session] Failed to acquire DB lock: {}", e);
         e.to_string()
     })?;
-    crate::commands::workflow_lifecycle::cancel_session(&conn, &session_id)
+    crate::commands::workflow_lifecycle::cancel_session(&conn, &session_id)?;
+    crate::journal::record(crate::journal::JournalEvent::SessionEnded { session_id });
+    Ok(())
 }
 
 #[cfg(test)]
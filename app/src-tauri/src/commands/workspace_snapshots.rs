@@ -0,0 +1,492 @@
+//! Undoable snapshots of a skill's workspace state.
+//!
+//! A snapshot captures everything a step reset can destroy: the skill's
+//! `context/` and `artifacts/` directories, `SKILL.md` and `references/` in
+//! the skills path, and its `workflow_runs`/`workflow_steps` rows. File
+//! contents are stored once per skill as blobs named by their SHA-256 under
+//! `{workspace}/{skill}/.snapshots/objects/`, and each snapshot is a manifest
+//! whose id is the hash of the files and rows it lists, so taking the same
+//! snapshot twice stores nothing new. Snapshots beyond `MAX_SNAPSHOTS_PER_SKILL`
+//! or older than `MAX_SNAPSHOT_AGE_DAYS` are pruned, along with blobs no
+//! manifest references any more.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::imported_skills::validate_skill_name;
+use crate::db::Db;
+use crate::types::{WorkflowRunRow, WorkflowStepRow, WorkspaceSnapshot};
+
+pub const SNAPSHOTS_DIR: &str = ".snapshots";
+const OBJECTS_DIR: &str = "objects";
+const MAX_SNAPSHOTS_PER_SKILL: usize = 20;
+/// Older snapshots are dropped, except the newest one.
+const MAX_SNAPSHOT_AGE_DAYS: i64 = 30;
+
+/// Where a snapshotted file lives: the skill's workspace directory or its
+/// output directory in the skills path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SnapshotRoot {
+    Workspace,
+    Skill,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SnapshotFile {
+    root: SnapshotRoot,
+    /// Path relative to the root, with `/` separators.
+    path: String,
+    sha256: String,
+    bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SnapshotManifest {
+    id: String,
+    skill_name: String,
+    reason: String,
+    created_at: String,
+    workflow_run: Option<WorkflowRunRow>,
+    workflow_steps: Vec<WorkflowStepRow>,
+    files: Vec<SnapshotFile>,
+}
+
+impl SnapshotManifest {
+    fn summary(&self) -> WorkspaceSnapshot {
+        WorkspaceSnapshot {
+            id: self.id.clone(),
+            skill_name: self.skill_name.clone(),
+            reason: self.reason.clone(),
+            created_at: self.created_at.clone(),
+            current_step: self.workflow_run.as_ref().map(|r| r.current_step),
+            file_count: self.files.len(),
+            total_bytes: self.files.iter().map(|f| f.bytes).sum(),
+        }
+    }
+}
+
+/// Directories (relative to the root) whose full contents are captured.
+const WORKSPACE_DIRS: &[&str] = &["context", super::step_artifacts::ARTIFACTS_DIR];
+const SKILL_DIRS: &[&str] = &["references"];
+const SKILL_FILES: &[&str] = &["SKILL.md"];
+
+fn snapshots_dir(workspace_path: &str, skill_name: &str) -> PathBuf {
+    Path::new(workspace_path).join(skill_name).join(SNAPSHOTS_DIR)
+}
+
+fn root_dir(workspace_path: &str, skills_path: &str, skill_name: &str, root: SnapshotRoot) -> PathBuf {
+    match root {
+        SnapshotRoot::Workspace => Path::new(workspace_path).join(skill_name),
+        SnapshotRoot::Skill => Path::new(skills_path).join(skill_name),
+    }
+}
+
+fn collect_files(dir: &Path, prefix: &str, out: &mut Vec<(String, PathBuf)>) -> Result<(), String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path();
+        let rel = format!("{}/{}", prefix, entry.file_name().to_string_lossy());
+        if path.is_dir() {
+            collect_files(&path, &rel, out)?;
+        } else if path.is_file() {
+            out.push((rel, path));
+        }
+    }
+    Ok(())
+}
+
+/// Files to capture under a root, sorted by relative path.
+fn captured_files(root: &Path, dirs: &[&str], files: &[&str]) -> Result<Vec<(String, PathBuf)>, String> {
+    let mut out = Vec::new();
+    for dir in dirs {
+        let path = root.join(dir);
+        if path.is_dir() {
+            collect_files(&path, dir, &mut out)?;
+        }
+    }
+    for file in files {
+        let path = root.join(file);
+        if path.is_file() {
+            out.push((file.to_string(), path));
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+fn store_blob(objects: &Path, bytes: &[u8]) -> Result<String, String> {
+    use sha2::{Digest, Sha256};
+    let hash = hex::encode(Sha256::digest(bytes));
+    let path = objects.join(&hash);
+    if !path.exists() {
+        std::fs::write(&path, bytes).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    Ok(hash)
+}
+
+/// Content id of a snapshot: the files and workflow rows, not when or why it was taken.
+fn snapshot_id(
+    workflow_run: &Option<WorkflowRunRow>,
+    workflow_steps: &[WorkflowStepRow],
+    files: &[SnapshotFile],
+) -> Result<String, String> {
+    use sha2::{Digest, Sha256};
+    let content = serde_json::to_vec(&(workflow_run, workflow_steps, files)).map_err(|e| e.to_string())?;
+    Ok(hex::encode(&Sha256::digest(&content)[..8]))
+}
+
+fn read_manifests(dir: &Path) -> Vec<SnapshotManifest> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut manifests: Vec<SnapshotManifest> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|p| {
+            let json = std::fs::read_to_string(&p).ok()?;
+            serde_json::from_str(&json)
+                .map_err(|e| log::warn!("[workspace_snapshots] skipping unreadable {}: {}", p.display(), e))
+                .ok()
+        })
+        .collect();
+    manifests.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    manifests
+}
+
+/// Apply the retention policy, then delete blobs no remaining manifest uses.
+fn prune(dir: &Path) -> Result<(), String> {
+    let cutoff = (chrono::Utc::now() - chrono::Duration::days(MAX_SNAPSHOT_AGE_DAYS)).to_rfc3339();
+    let manifests = read_manifests(dir);
+    let mut referenced = HashSet::new();
+    for (i, manifest) in manifests.iter().enumerate() {
+        if i > 0 && (i >= MAX_SNAPSHOTS_PER_SKILL || manifest.created_at < cutoff) {
+            let path = dir.join(format!("{}.json", manifest.id));
+            std::fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+            log::debug!("[workspace_snapshots] pruned snapshot {}", manifest.id);
+        } else {
+            referenced.extend(manifest.files.iter().map(|f| f.sha256.clone()));
+        }
+    }
+    if let Ok(entries) = std::fs::read_dir(dir.join(OBJECTS_DIR)) {
+        for entry in entries.flatten() {
+            if !referenced.contains(entry.file_name().to_string_lossy().as_ref()) {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Capture the skill's current files and workflow rows. Taking a snapshot
+/// identical to an existing one refreshes that snapshot's time and reason.
+pub(crate) fn create_snapshot(
+    conn: &rusqlite::Connection,
+    workspace_path: &str,
+    skills_path: &str,
+    skill_name: &str,
+    reason: &str,
+) -> Result<WorkspaceSnapshot, String> {
+    let dir = snapshots_dir(workspace_path, skill_name);
+    let objects = dir.join(OBJECTS_DIR);
+    std::fs::create_dir_all(&objects).map_err(|e| format!("Failed to create {}: {}", objects.display(), e))?;
+
+    let mut files = Vec::new();
+    for (root, dirs, names) in [
+        (SnapshotRoot::Workspace, WORKSPACE_DIRS, &[][..]),
+        (SnapshotRoot::Skill, SKILL_DIRS, SKILL_FILES),
+    ] {
+        let base = root_dir(workspace_path, skills_path, skill_name, root);
+        for (rel, path) in captured_files(&base, dirs, names)? {
+            let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            files.push(SnapshotFile {
+                root,
+                path: rel,
+                sha256: store_blob(&objects, &bytes)?,
+                bytes: bytes.len() as u64,
+            });
+        }
+    }
+    let workflow_run = crate::db::get_workflow_run(conn, skill_name)?;
+    let workflow_steps = crate::db::get_workflow_steps(conn, skill_name)?;
+    let manifest = SnapshotManifest {
+        id: snapshot_id(&workflow_run, &workflow_steps, &files)?,
+        skill_name: skill_name.to_string(),
+        reason: reason.to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        workflow_run,
+        workflow_steps,
+        files,
+    };
+    let path = dir.join(format!("{}.json", manifest.id));
+    let json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    prune(&dir)?;
+
+    log::info!(
+        "[create_workspace_snapshot] '{}' snapshot {} ({} files): {}",
+        skill_name,
+        manifest.id,
+        manifest.files.len(),
+        reason
+    );
+    Ok(manifest.summary())
+}
+
+fn remove_path(path: &Path) -> Result<(), String> {
+    let result = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else if path.exists() {
+        std::fs::remove_file(path)
+    } else {
+        return Ok(());
+    };
+    result.map_err(|e| format!("Failed to remove {}: {}", path.display(), e))
+}
+
+/// Put the skill's files and workflow rows back as they were in the snapshot.
+/// The current state is snapshotted first, so a restore can itself be undone.
+fn restore_snapshot(
+    conn: &rusqlite::Connection,
+    workspace_path: &str,
+    skills_path: &str,
+    skill_name: &str,
+    snapshot_id: &str,
+) -> Result<WorkspaceSnapshot, String> {
+    let dir = snapshots_dir(workspace_path, skill_name);
+    let manifest = read_manifests(&dir)
+        .into_iter()
+        .find(|m| m.id == snapshot_id)
+        .ok_or_else(|| format!("Snapshot '{}' not found for skill '{}'", snapshot_id, skill_name))?;
+    // Check every blob before touching anything.
+    let objects = dir.join(OBJECTS_DIR);
+    if let Some(missing) = manifest.files.iter().find(|f| !objects.join(&f.sha256).is_file()) {
+        return Err(format!("Snapshot '{}' is incomplete: content of {} is missing", snapshot_id, missing.path));
+    }
+
+    create_snapshot(
+        conn,
+        workspace_path,
+        skills_path,
+        skill_name,
+        &format!("before restoring snapshot {}", snapshot_id),
+    )?;
+
+    let workspace_root = root_dir(workspace_path, skills_path, skill_name, SnapshotRoot::Workspace);
+    let skill_root = root_dir(workspace_path, skills_path, skill_name, SnapshotRoot::Skill);
+    for name in WORKSPACE_DIRS {
+        remove_path(&workspace_root.join(name))?;
+    }
+    for name in SKILL_DIRS.iter().chain(SKILL_FILES) {
+        remove_path(&skill_root.join(name))?;
+    }
+    for file in &manifest.files {
+        let root = match file.root {
+            SnapshotRoot::Workspace => &workspace_root,
+            SnapshotRoot::Skill => &skill_root,
+        };
+        let dest = root.join(&file.path);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        std::fs::copy(objects.join(&file.sha256), &dest)
+            .map_err(|e| format!("Failed to restore {}: {}", dest.display(), e))?;
+    }
+    // Step 3 expects an (empty) references/ directory to exist.
+    std::fs::create_dir_all(skill_root.join("references")).map_err(|e| e.to_string())?;
+
+    if let Some(run) = &manifest.workflow_run {
+        crate::db::save_workflow_run(conn, skill_name, run.current_step, &run.status, &run.purpose)?;
+        crate::db::reset_workflow_steps_from(conn, skill_name, 0)?;
+        for step in &manifest.workflow_steps {
+            crate::db::restore_workflow_step(conn, step)?;
+        }
+    }
+
+    log::info!(
+        "[restore_workspace_snapshot] restored '{}' to snapshot {} ({} files)",
+        skill_name,
+        snapshot_id,
+        manifest.files.len()
+    );
+    Ok(manifest.summary())
+}
+
+fn read_skills_path(conn: &rusqlite::Connection) -> Result<String, String> {
+    crate::db::read_settings(conn)?
+        .skills_path
+        .ok_or_else(|| "Skills path not configured. Please set it in Settings.".to_string())
+}
+
+/// Snapshot a skill's workspace state so later changes can be undone.
+#[tauri::command]
+pub fn create_workspace_snapshot(
+    skill_name: String,
+    workspace_path: String,
+    reason: Option<String>,
+    db: tauri::State<'_, Db>,
+) -> Result<WorkspaceSnapshot, String> {
+    log::info!("[create_workspace_snapshot] skill={}", skill_name);
+    validate_skill_name(&skill_name)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[create_workspace_snapshot] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let skills_path = read_skills_path(&conn)?;
+    create_snapshot(
+        &conn,
+        &workspace_path,
+        &skills_path,
+        &skill_name,
+        reason.as_deref().unwrap_or("manual snapshot"),
+    )
+    .map_err(|e| {
+        log::error!("[create_workspace_snapshot] {}", e);
+        e
+    })
+}
+
+/// A skill's snapshots, newest first.
+#[tauri::command]
+pub fn list_workspace_snapshots(skill_name: String, workspace_path: String) -> Result<Vec<WorkspaceSnapshot>, String> {
+    log::info!("[list_workspace_snapshots] skill={}", skill_name);
+    validate_skill_name(&skill_name)?;
+    Ok(read_manifests(&snapshots_dir(&workspace_path, &skill_name))
+        .iter()
+        .map(SnapshotManifest::summary)
+        .collect())
+}
+
+#[tauri::command]
+pub fn restore_workspace_snapshot(
+    skill_name: String,
+    workspace_path: String,
+    snapshot_id: String,
+    db: tauri::State<'_, Db>,
+) -> Result<WorkspaceSnapshot, String> {
+    log::info!("[restore_workspace_snapshot] skill={} snapshot={}", skill_name, snapshot_id);
+    super::observer::ensure_writable(&skill_name)?;
+    validate_skill_name(&skill_name)?;
+    let conn = db.0.lock().map_err(|e| {
+        log::error!("[restore_workspace_snapshot] Failed to acquire DB lock: {}", e);
+        e.to_string()
+    })?;
+    let skills_path = read_skills_path(&conn)?;
+    let restored = restore_snapshot(&conn, &workspace_path, &skills_path, &skill_name, &snapshot_id).map_err(|e| {
+        log::error!("[restore_workspace_snapshot] {}", e);
+        e
+    })?;
+
+    let msg = format!("{}: restored workspace snapshot {}", skill_name, snapshot_id);
+    if let Err(e) = crate::git::commit_all(Path::new(&skills_path), &msg) {
+        log::warn!("Git auto-commit failed ({}): {}", msg, e);
+    }
+    Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_utils::create_test_db;
+    use tempfile::tempdir;
+
+    fn setup(workspace: &Path, skills: &Path) {
+        std::fs::create_dir_all(workspace.join("sales/context")).unwrap();
+        std::fs::create_dir_all(skills.join("sales/references")).unwrap();
+        std::fs::write(workspace.join("sales/context/clarifications.json"), r#"{"questions":[]}"#).unwrap();
+        std::fs::write(workspace.join("sales/context/decisions.json"), r#"{"decisions":[]}"#).unwrap();
+        std::fs::write(skills.join("sales/SKILL.md"), "# Sales\n").unwrap();
+        std::fs::write(skills.join("sales/references/metrics.md"), "ARR\n").unwrap();
+    }
+
+    #[test]
+    fn test_identical_snapshots_share_id_and_blobs() {
+        let conn = create_test_db();
+        let workspace = tempdir().unwrap();
+        let skills = tempdir().unwrap();
+        let (ws, sp) = (workspace.path().to_str().unwrap(), skills.path().to_str().unwrap());
+        setup(workspace.path(), skills.path());
+
+        let first = create_snapshot(&conn, ws, sp, "sales", "first").unwrap();
+        let second = create_snapshot(&conn, ws, sp, "sales", "second").unwrap();
+        assert_eq!(first.id, second.id);
+        assert_eq!(first.file_count, 4);
+
+        std::fs::write(skills.path().join("sales/SKILL.md"), "# Sales v2\n").unwrap();
+        let third = create_snapshot(&conn, ws, sp, "sales", "third").unwrap();
+        assert_ne!(first.id, third.id);
+
+        let dir = snapshots_dir(ws, "sales");
+        assert_eq!(read_manifests(&dir).len(), 2);
+        // Both SKILL.md versions plus the three unchanged files.
+        assert_eq!(std::fs::read_dir(dir.join(OBJECTS_DIR)).unwrap().count(), 5);
+    }
+
+    #[test]
+    fn test_restore_undoes_reset() {
+        let conn = create_test_db();
+        let workspace = tempdir().unwrap();
+        let skills = tempdir().unwrap();
+        let (ws, sp) = (workspace.path().to_str().unwrap(), skills.path().to_str().unwrap());
+        setup(workspace.path(), skills.path());
+        crate::db::save_workflow_run(&conn, "sales", 3, "completed", "domain").unwrap();
+        crate::db::save_workflow_step(&conn, "sales", 2, "completed").unwrap();
+
+        let snapshot = create_snapshot(&conn, ws, sp, "sales", "before reset to step 2").unwrap();
+        crate::cleanup::delete_step_output_files(ws, "sales", 2, sp);
+        crate::db::reset_workflow_steps_from(&conn, "sales", 2).unwrap();
+        crate::db::save_workflow_run(&conn, "sales", 2, "pending", "domain").unwrap();
+        assert!(!workspace.path().join("sales/context/decisions.json").exists());
+
+        let restored = restore_snapshot(&conn, ws, sp, "sales", &snapshot.id).unwrap();
+        assert_eq!(restored.current_step, Some(3));
+        assert!(workspace.path().join("sales/context/decisions.json").exists());
+        assert_eq!(std::fs::read_to_string(skills.path().join("sales/references/metrics.md")).unwrap(), "ARR\n");
+        let run = crate::db::get_workflow_run(&conn, "sales").unwrap().unwrap();
+        assert_eq!(run.current_step, 3);
+        let steps = crate::db::get_workflow_steps(&conn, "sales").unwrap();
+        assert_eq!(steps.iter().find(|s| s.step_id == 2).unwrap().status, "completed");
+
+        // The reset state was snapshotted before restoring.
+        let snapshots = list_workspace_snapshots("sales".into(), ws.into()).unwrap();
+        assert_eq!(snapshots.len(), 2);
+        assert!(snapshots.iter().any(|s| s.reason.starts_with("before restoring")));
+    }
+
+    #[test]
+    fn test_restore_unknown_snapshot_fails() {
+        let conn = create_test_db();
+        let workspace = tempdir().unwrap();
+        let skills = tempdir().unwrap();
+        let (ws, sp) = (workspace.path().to_str().unwrap(), skills.path().to_str().unwrap());
+        setup(workspace.path(), skills.path());
+        let err = restore_snapshot(&conn, ws, sp, "sales", "deadbeef").unwrap_err();
+        assert!(err.contains("not found"));
+        assert!(workspace.path().join("sales/context/decisions.json").exists());
+    }
+
+    #[test]
+    fn test_prune_keeps_newest_and_drops_unreferenced_blobs() {
+        let conn = create_test_db();
+        let workspace = tempdir().unwrap();
+        let skills = tempdir().unwrap();
+        let (ws, sp) = (workspace.path().to_str().unwrap(), skills.path().to_str().unwrap());
+        setup(workspace.path(), skills.path());
+        let skill_md = skills.path().join("sales/SKILL.md");
+        for i in 0..MAX_SNAPSHOTS_PER_SKILL + 2 {
+            std::fs::write(&skill_md, format!("# Sales {}\n", i)).unwrap();
+            create_snapshot(&conn, ws, sp, "sales", "edit").unwrap();
+        }
+        let dir = snapshots_dir(ws, "sales");
+        assert_eq!(read_manifests(&dir).len(), MAX_SNAPSHOTS_PER_SKILL);
+        // One SKILL.md blob per kept snapshot plus the three unchanged files.
+        assert_eq!(
+            std::fs::read_dir(dir.join(OBJECTS_DIR)).unwrap().count(),
+            MAX_SNAPSHOTS_PER_SKILL + 3
+        );
+    }
+}
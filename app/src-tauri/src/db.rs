@@ -1,14 +1,26 @@
 use crate::types::{
-    AgentRunRecord, AppSettings, ImportedSkill, SkillMasterRow, UsageByModel, UsageByStep,
-    UsageSummary, WorkflowRunRow, WorkflowSessionRecord, WorkflowStepRow, WorkspaceSkill,
+    ActivationDecision, AgentCostTick, AgentTranscript, AgentRunCheckpoint, AgentRunRecord, AgentToolCall, AppSettings, AuditLogEntry, AuditLogFilter, BenchmarkScenarioResult, CachedCatalog, CommandHistoryEntry, CommandHistoryFilter, GitHostCredential, ImportedSkill, LibraryEvent, LinkedReference, SkillAnalytics, SkillBenchmark, SkillGroup, SkillInvocation, SkillInvocationStats, SkillMasterRow, SkillParameterSet, SkillRunStats, SkillTestCase, SkillTestResult, StepPerfSample, StepPerfTrend, StepRunUsage, ToolPolicy,
+    TagAlias, TagNode, TagTree, TeamTaxonomy, TrustedKey, UsageByModel, UsageByStep, UsageSummary, UserDataPurgeResult, WorkflowRunRow, WorkflowSessionRecord,
+    ArtifactAnnotation, CachedStepRun, HookDelivery, QualityWaiver, RefineBatch, RefineBatchItem, RefineDiff, RefineFileReview, RefineSessionMessage, RefineSessionRecord, RetentionPolicy, RetentionPruneResult, SkillChangelogEntry, SkillEvent, SkillScanReport, SkillUpstreamReview, WorkflowQueueJob, WorkflowStepRow, WorkspaceSkill,
 };
 use rusqlite::{Connection, OptionalExtension};
+
+use crate::skill_paths::SkillRoot;
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
-use std::sync::Mutex;
 
-pub struct Db(pub Mutex<Connection>);
+use crate::db_pool::DbPool;
+
+/// The app database: `db.0.lock()` for writes, `db.0.read()` for read-only queries.
+pub struct Db(pub DbPool);
+
+impl Db {
+    /// A database whose reads share `conn`, e.g. an in-memory test database.
+    pub fn new(conn: Connection) -> Self {
+        Db(DbPool::new(conn))
+    }
+}
 
 pub fn init_db(data_dir: &Path) -> Result<Db, Box<dyn std::error::Error>> {
     fs::create_dir_all(data_dir)?;
@@ -19,7 +31,7 @@ pub fn init_db(data_dir: &Path) -> Result<Db, Box<dyn std::error::Error>> {
     let db_path = db_dir.join("skill-builder.db");
     migrate_legacy_db_path(&legacy_db_path, &db_path)?;
 
-    let conn = Connection::open(db_path)?;
+    let conn = Connection::open(&db_path)?;
     conn.pragma_update(None, "journal_mode", "WAL")
         .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
     conn.pragma_update(None, "busy_timeout", "5000")
@@ -68,6 +80,46 @@ pub fn init_db(data_dir: &Path) -> Result<Db, Box<dyn std::error::Error>> {
         (32, run_normalize_model_names_migration),
         (33, run_reconciliation_events_migration),
         (34, run_ghost_running_rows_migration),
+        (35, run_marketplace_catalog_cache_migration),
+        (36, run_trusted_signing_keys_migration),
+        (37, run_skill_parameter_sets_migration),
+        (38, run_skill_library_events_migration),
+        (39, run_activation_decisions_migration),
+        (40, run_command_history_migration),
+        (41, run_workflow_contract_version_migration),
+        (42, run_skill_benchmarks_migration),
+        (43, run_linked_references_migration),
+        (44, run_step_perf_history_migration),
+        (45, run_tool_policies_migration),
+        (46, run_agent_run_ticks_migration),
+        (47, run_skill_review_migration),
+        (48, run_portable_disk_paths_migration),
+        (49, run_workflow_queue_migration),
+        (50, run_quality_waivers_migration),
+        (51, run_skill_changelog_migration),
+        (52, run_artifact_annotations_migration),
+        (53, run_step_run_cache_migration),
+        (54, run_refine_sessions_migration),
+        (55, run_upstream_reviews_migration),
+        (56, run_skill_dependencies_migration),
+        (57, run_skill_test_cases_migration),
+        (58, run_agent_tool_calls_migration),
+        (59, run_agent_runs_skill_index_migration),
+        (60, run_skill_push_bases_migration),
+        (61, run_agent_run_checkpoints_migration),
+        (62, run_automation_hook_deliveries_migration),
+        (63, run_skill_events_migration),
+        (64, run_skill_scan_reports_migration),
+        (65, run_agent_transcripts_migration),
+        (66, run_skill_generation_bases_migration),
+        (67, run_skill_invocations_migration),
+        (68, run_agent_run_retry_count_migration),
+        (69, run_skill_package_format_migration),
+        (70, run_refine_batches_migration),
+        (71, run_tag_aliases_migration),
+        (72, run_audit_log_migration),
+        (73, run_refine_file_reviews_migration),
+        (74, run_catalog_tree_sha_migration),
     ];
 
     for &(version, migrate_fn) in migrations {
@@ -87,7 +139,12 @@ pub fn init_db(data_dir: &Path) -> Result<Db, Box<dyn std::error::Error>> {
     run_marketplace_source_url_migration(&conn)
         .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
 
-    Ok(Db(Mutex::new(conn)))
+    // Readers are opened after migrations so they see the final schema.
+    let readers = DbPool::open_readers(&db_path, crate::db_pool::READ_CONNECTIONS).unwrap_or_else(|e| {
+        log::warn!("[init_db] Failed to open read connections; reads will share the writer: {}", e);
+        Vec::new()
+    });
+    Ok(Db(DbPool::with_readers(conn, readers)))
 }
 
 fn migrate_legacy_db_path(legacy_path: &Path, new_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
@@ -669,2683 +726,7141 @@ fn run_ghost_running_rows_migration(conn: &Connection) -> Result<(), rusqlite::E
     Ok(())
 }
 
-/// Migration 18: Backfill `skills` from `workflow_runs`, add FK column, backfill FK,
-/// and remove marketplace rows from `workflow_runs` (now in skills master only).
-fn run_skills_backfill_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
-    // Step 1: Backfill skills from workflow_runs
-    let backfilled: usize = conn.execute(
-        "INSERT OR IGNORE INTO skills (name, skill_source, domain, skill_type, created_at, updated_at)
-         SELECT skill_name,
-           CASE WHEN source = 'marketplace' THEN 'marketplace' ELSE 'skill-builder' END,
-           domain, skill_type, created_at, updated_at
-         FROM workflow_runs",
-        [],
+/// Migration 35: Cache marketplace catalog listings so browse commands can answer
+/// from disk and revalidate `marketplace.json` with its ETag in the background.
+fn run_marketplace_catalog_cache_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS marketplace_catalog_cache (
+            cache_key TEXT PRIMARY KEY,
+            etag TEXT,
+            registry_name TEXT,
+            skills_json TEXT NOT NULL,
+            fetched_at TEXT NOT NULL
+        );",
     )?;
+    Ok(())
+}
 
-    // Step 2: Add FK column (check PRAGMA table_info first for idempotency)
-    let has_skill_id = conn
-        .prepare("PRAGMA table_info(workflow_runs)")?
-        .query_map([], |r| r.get::<_, String>(1))?
-        .any(|r| r.map(|n| n == "skill_id").unwrap_or(false));
-    if !has_skill_id {
-        conn.execute_batch(
-            "ALTER TABLE workflow_runs ADD COLUMN skill_id INTEGER REFERENCES skills(id);",
-        )?;
-    }
-
-    // Step 3: Backfill FK
-    conn.execute(
-        "UPDATE workflow_runs SET skill_id = (SELECT id FROM skills WHERE skills.name = workflow_runs.skill_name)",
-        [],
+/// Migration 36: Public keys trusted when verifying signed skill packages.
+fn run_trusted_signing_keys_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS trusted_signing_keys (
+            key_id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            public_key TEXT NOT NULL,
+            added_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );",
     )?;
-
-    // Step 4: Remove marketplace rows from workflow_runs (now in skills master only)
-    conn.execute(
-        "DELETE FROM workflow_steps WHERE skill_name IN (SELECT skill_name FROM workflow_runs WHERE source = 'marketplace')",
-        [],
-    ).ok(); // marketplace skills may not have step rows — ignore errors
-    let removed: usize =
-        conn.execute("DELETE FROM workflow_runs WHERE source = 'marketplace'", [])?;
-
-    log::info!(
-        "migration 18: backfilled {} skills, removed {} marketplace workflow_runs",
-        backfilled,
-        removed
-    );
     Ok(())
 }
 
-fn run_workspace_skills_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+/// Migration 37: Parameter values each installed parameterized skill was resolved with.
+fn run_skill_parameter_sets_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
     conn.execute_batch(
-        "
-        BEGIN;
+        "CREATE TABLE IF NOT EXISTS skill_parameter_sets (
+            skill_name TEXT PRIMARY KEY,
+            source_name TEXT,
+            values_json TEXT NOT NULL,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );",
+    )?;
+    Ok(())
+}
 
-        CREATE TABLE IF NOT EXISTS workspace_skills (
-            skill_id     TEXT PRIMARY KEY,
-            skill_name   TEXT UNIQUE NOT NULL,
-            domain       TEXT,
-            description  TEXT,
-            is_active    INTEGER NOT NULL DEFAULT 1,
-            is_bundled   INTEGER NOT NULL DEFAULT 0,
-            disk_path    TEXT NOT NULL,
-            imported_at  TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
-            skill_type   TEXT,
-            version      TEXT,
-            model        TEXT,
-            argument_hint TEXT,
-            user_invocable INTEGER,
-            disable_model_invocation INTEGER
+/// Migration 38: Audit log of skill library changes, seeded with the current
+/// library at each skill's install time so history queries see existing skills.
+fn run_skill_library_events_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS skill_library_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            skill_name TEXT NOT NULL,
+            source TEXT NOT NULL,
+            event_type TEXT NOT NULL,
+            version TEXT,
+            is_active INTEGER NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
         );
+        CREATE INDEX IF NOT EXISTS idx_skill_library_events_created
+            ON skill_library_events(created_at);
+        INSERT INTO skill_library_events (skill_name, source, event_type, version, is_active, created_at)
+            SELECT skill_name, 'workspace', 'installed', version, is_active,
+                   COALESCE(datetime(imported_at), datetime('now')) || 'Z'
+            FROM workspace_skills;
+        INSERT INTO skill_library_events (skill_name, source, event_type, version, is_active, created_at)
+            SELECT skill_name, 'marketplace', 'installed', version, is_active,
+                   COALESCE(datetime(imported_at), datetime('now')) || 'Z'
+            FROM imported_skills;",
+    )?;
+    Ok(())
+}
 
-        INSERT OR IGNORE INTO workspace_skills
-            (skill_id, skill_name, domain, is_active, is_bundled,
-             disk_path, imported_at, skill_type, version, model,
-             argument_hint, user_invocable, disable_model_invocation)
-        SELECT
-            skill_id, skill_name, domain, is_active, is_bundled,
-            disk_path, imported_at, skill_type, version, model,
-            argument_hint, user_invocable, disable_model_invocation
-        FROM imported_skills
-        WHERE skill_type = 'skill-builder' OR is_bundled = 1;
-
-        DELETE FROM imported_skills WHERE skill_type = 'skill-builder' OR is_bundled = 1;
+/// Migration 39: Import-time activation policy decisions, with user overrides.
+fn run_activation_decisions_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS activation_decisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            skill_name TEXT NOT NULL,
+            is_active INTEGER NOT NULL,
+            matched_rule TEXT NOT NULL,
+            reason TEXT NOT NULL,
+            overridden_at TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE INDEX IF NOT EXISTS idx_activation_decisions_skill
+            ON activation_decisions(skill_name);",
+    )?;
+    Ok(())
+}
 
-        COMMIT;
-    ",
+/// Migration 40: Searchable history of user-invoked commands (secrets redacted).
+fn run_command_history_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS command_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            command TEXT NOT NULL,
+            params_json TEXT NOT NULL,
+            redacted INTEGER NOT NULL DEFAULT 0,
+            outcome TEXT NOT NULL,
+            error TEXT,
+            duration_ms INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE INDEX IF NOT EXISTS idx_command_history_created
+            ON command_history(created_at);",
     )?;
-    log::info!("migration 20: created workspace_skills table, migrated skill-builder rows");
     Ok(())
 }
 
-/// Migration 21: Add integer primary key to `workflow_runs`.
-/// The table previously used `skill_name TEXT PRIMARY KEY`. We recreate it with
-/// `id INTEGER PRIMARY KEY AUTOINCREMENT` and `skill_name TEXT UNIQUE NOT NULL`.
-/// This unblocks rename_skill (no more INSERT+DELETE) and allows child tables to
-/// reference `workflow_runs` by integer FK instead of text `skill_name`.
-fn run_workflow_runs_id_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
-    // Idempotency guard: check whether the `id` column already exists
-    let has_id = conn
+/// Migration 41: Stamp workflow runs with the prompt contract version their step outputs follow.
+/// Existing runs predate versioning and are treated as contract 1.
+fn run_workflow_contract_version_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let has_column = conn
         .prepare("PRAGMA table_info(workflow_runs)")?
         .query_map([], |r| r.get::<_, String>(1))?
-        .any(|r| r.map(|n| n == "id").unwrap_or(false));
-    if has_id {
-        return Ok(());
+        .any(|r| r.map(|name| name == "contract_version").unwrap_or(false));
+    if !has_column {
+        conn.execute_batch(
+            "ALTER TABLE workflow_runs ADD COLUMN contract_version INTEGER NOT NULL DEFAULT 1;",
+        )?;
     }
+    Ok(())
+}
 
+/// Migration 42: Skill benchmark runs against the no-skill baseline, kept across versions.
+fn run_skill_benchmarks_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
     conn.execute_batch(
-        "
-        BEGIN;
-
-        DROP TABLE IF EXISTS workflow_runs_new;
-
-        CREATE TABLE workflow_runs_new (
-            id          INTEGER PRIMARY KEY AUTOINCREMENT,
-            skill_name  TEXT UNIQUE NOT NULL,
-            domain      TEXT NOT NULL,
-            current_step INTEGER NOT NULL DEFAULT 0,
-            status      TEXT NOT NULL DEFAULT 'pending',
-            created_at  TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
-            updated_at  TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
-            skill_type  TEXT DEFAULT 'domain',
-            source      TEXT NOT NULL DEFAULT 'created',
-            description TEXT,
-            version     TEXT DEFAULT '1.0.0',
-            model       TEXT,
-            argument_hint TEXT,
-            user_invocable INTEGER DEFAULT 1,
-            disable_model_invocation INTEGER DEFAULT 0,
-            author_login TEXT,
-            author_avatar TEXT,
-            display_name TEXT,
-            intake_json TEXT,
-            skill_id    INTEGER REFERENCES skills(id)
+        "CREATE TABLE IF NOT EXISTS skill_benchmarks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            skill_name TEXT NOT NULL,
+            skill_version TEXT,
+            scenario_set TEXT NOT NULL,
+            model TEXT NOT NULL,
+            judge_model TEXT NOT NULL,
+            skill_wins INTEGER NOT NULL DEFAULT 0,
+            baseline_wins INTEGER NOT NULL DEFAULT 0,
+            ties INTEGER NOT NULL DEFAULT 0,
+            avg_skill_score REAL NOT NULL DEFAULT 0,
+            avg_baseline_score REAL NOT NULL DEFAULT 0,
+            token_overhead_pct REAL NOT NULL DEFAULT 0,
+            latency_overhead_pct REAL NOT NULL DEFAULT 0,
+            results_json TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
         );
+        CREATE INDEX IF NOT EXISTS idx_skill_benchmarks_skill
+            ON skill_benchmarks(skill_name);",
+    )?;
+    Ok(())
+}
 
-        INSERT INTO workflow_runs_new
-            (skill_name, domain, current_step, status, created_at, updated_at,
-             skill_type, source, description, version, model, argument_hint,
-             user_invocable, disable_model_invocation, author_login, author_avatar,
-             display_name, intake_json, skill_id)
-        SELECT skill_name, domain, current_step, status, created_at, updated_at,
-               skill_type, COALESCE(source, 'created'), description, version, model,
-               argument_hint, user_invocable, disable_model_invocation,
-               author_login, author_avatar, display_name, intake_json, skill_id
-        FROM workflow_runs;
-
-        DROP TABLE workflow_runs;
-        ALTER TABLE workflow_runs_new RENAME TO workflow_runs;
+/// Migration 43: Reference files sourced from a GitHub path. `synced_sha` is the
+/// blob SHA last written to disk; `upstream_sha` is what the last check saw
+/// (NULL with `checked_at` set when the upstream file was missing).
+fn run_linked_references_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS skill_linked_references (
+            skill_name TEXT NOT NULL,
+            reference_path TEXT NOT NULL,
+            repo TEXT NOT NULL,
+            git_ref TEXT NOT NULL,
+            source_path TEXT NOT NULL,
+            synced_sha TEXT NOT NULL,
+            synced_at TEXT NOT NULL,
+            upstream_sha TEXT,
+            checked_at TEXT,
+            PRIMARY KEY (skill_name, reference_path)
+        );",
+    )?;
+    Ok(())
+}
 
-        COMMIT;
-    ",
+/// Migration 44: Per-step prompt size and latency of completed workflow agent
+/// runs, keyed like `agent_runs` and tagged with the app and prompt versions.
+fn run_step_perf_history_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS step_perf_history (
+            agent_id TEXT NOT NULL,
+            model TEXT NOT NULL,
+            skill_name TEXT NOT NULL,
+            step_id INTEGER NOT NULL,
+            app_version TEXT NOT NULL,
+            prompt_version TEXT,
+            prompt_tokens INTEGER NOT NULL DEFAULT 0,
+            output_tokens INTEGER NOT NULL DEFAULT 0,
+            total_cost REAL NOT NULL DEFAULT 0,
+            time_to_first_token_ms INTEGER,
+            duration_ms INTEGER NOT NULL DEFAULT 0,
+            recorded_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            PRIMARY KEY (agent_id, model)
+        );
+        CREATE INDEX IF NOT EXISTS idx_step_perf_history_step
+            ON step_perf_history(step_id, recorded_at);",
     )?;
+    Ok(())
+}
 
-    log::info!("migration 21: added integer PK to workflow_runs");
+/// Migration 45: Tool policies as JSON, keyed by skill name or `*` for the global policy.
+fn run_tool_policies_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS tool_policies (
+            scope TEXT PRIMARY KEY,
+            policy_json TEXT NOT NULL,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );",
+    )?;
     Ok(())
 }
 
-/// Migration 22: Add integer FK columns to child tables and backfill from skill_name.
-/// After this migration:
-///   - workflow_steps, workflow_artifacts, agent_runs: have `workflow_run_id INT FK → workflow_runs(id)`
-///   - skill_tags, skill_locks, workflow_sessions: have `skill_id INT FK → skills(id)`
-///   - imported_skills: has `skill_master_id INT FK → skills(id)`
-fn run_fk_columns_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
-    // Helper to check if a column exists in a table
-    let has_column = |table: &str, column: &str| -> bool {
-        conn.prepare(&format!("PRAGMA table_info({})", table))
-            .and_then(|mut stmt| {
-                stmt.query_map([], |r| r.get::<_, String>(1))
-                    .map(|rows| rows.filter_map(|r| r.ok()).any(|n| n == column))
-            })
-            .unwrap_or(false)
-    };
+/// Migration 46: Last live cost tick per running agent, so a crash mid-step
+/// still leaves approximate usage for startup reconciliation to record.
+fn run_agent_run_ticks_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS agent_run_ticks (
+            agent_id TEXT PRIMARY KEY,
+            skill_name TEXT NOT NULL,
+            step_id INTEGER NOT NULL,
+            model TEXT NOT NULL,
+            input_tokens INTEGER NOT NULL DEFAULT 0,
+            output_tokens INTEGER NOT NULL DEFAULT 0,
+            cache_read_tokens INTEGER NOT NULL DEFAULT 0,
+            cache_write_tokens INTEGER NOT NULL DEFAULT 0,
+            total_cost REAL NOT NULL DEFAULT 0.0,
+            num_turns INTEGER NOT NULL DEFAULT 0,
+            elapsed_ms INTEGER NOT NULL DEFAULT 0,
+            workflow_session_id TEXT,
+            pid INTEGER NOT NULL,
+            started_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );",
+    )?;
+    Ok(())
+}
 
-    // --- workflow_steps ---
-    if !has_column("workflow_steps", "workflow_run_id") {
-        conn.execute_batch(
-            "ALTER TABLE workflow_steps ADD COLUMN workflow_run_id INTEGER REFERENCES workflow_runs(id);",
-        )?;
+/// Migration 47: Review-by date per skill and when it was last marked reviewed.
+fn run_skill_review_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let columns: Vec<String> = conn
+        .prepare("PRAGMA table_info(skills)")?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?;
+    if !columns.iter().any(|c| c == "review_by") {
+        conn.execute_batch("ALTER TABLE skills ADD COLUMN review_by TEXT;")?;
     }
-
-    // --- workflow_artifacts ---
-    if !has_column("workflow_artifacts", "workflow_run_id") {
-        conn.execute_batch(
-            "ALTER TABLE workflow_artifacts ADD COLUMN workflow_run_id INTEGER REFERENCES workflow_runs(id);",
-        )?;
+    if !columns.iter().any(|c| c == "reviewed_at") {
+        conn.execute_batch("ALTER TABLE skills ADD COLUMN reviewed_at TEXT;")?;
     }
+    Ok(())
+}
 
-    // --- agent_runs ---
-    if !has_column("agent_runs", "workflow_run_id") {
-        conn.execute_batch(
-            "ALTER TABLE agent_runs ADD COLUMN workflow_run_id INTEGER REFERENCES workflow_runs(id);",
-        )?;
+/// Migration 48: Rewrite absolute `disk_path` values inside their skill root as
+/// root-relative paths so a synced library resolves on every machine.
+fn run_portable_disk_paths_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    for (table, root) in [
+        ("imported_skills", SkillRoot::Library),
+        ("workspace_skills", SkillRoot::Workspace),
+    ] {
+        let root = crate::skill_paths::root_dir(conn, root);
+        if root.is_none() {
+            continue;
+        }
+        let rows: Vec<(String, String)> = conn
+            .prepare(&format!("SELECT skill_id, disk_path FROM {}", table))?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut rewritten = 0;
+        for (id, disk_path) in rows {
+            let stored = crate::skill_paths::to_stored(
+                root.as_deref(),
+                &crate::skill_paths::resolve(root.as_deref(), &disk_path),
+            );
+            if stored != disk_path {
+                conn.execute(
+                    &format!("UPDATE {} SET disk_path = ?1 WHERE skill_id = ?2", table),
+                    rusqlite::params![stored, id],
+                )?;
+                rewritten += 1;
+            }
+        }
+        if rewritten > 0 {
+            log::info!("migration 48: stored {} {} disk paths relative to their root", rewritten, table);
+        }
     }
+    Ok(())
+}
 
-    // --- skill_tags ---
-    if !has_column("skill_tags", "skill_id") {
-        conn.execute_batch(
-            "ALTER TABLE skill_tags ADD COLUMN skill_id INTEGER REFERENCES skills(id);",
-        )?;
-    }
-
-    // --- skill_locks ---
-    if !has_column("skill_locks", "skill_id") {
-        conn.execute_batch(
-            "ALTER TABLE skill_locks ADD COLUMN skill_id INTEGER REFERENCES skills(id);",
-        )?;
-    }
+/// Migration 49: Persistent queue of workflow steps run unattended.
+/// `pid` is the process running the job, so a crashed run can be requeued.
+fn run_workflow_queue_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS workflow_queue (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            skill_name TEXT NOT NULL,
+            step_id INTEGER NOT NULL,
+            workspace_path TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'queued',
+            error TEXT,
+            pid INTEGER,
+            enqueued_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            started_at TEXT,
+            finished_at TEXT
+        );
+        CREATE INDEX IF NOT EXISTS idx_workflow_queue_status ON workflow_queue(status);",
+    )?;
+    Ok(())
+}
 
-    // --- workflow_sessions ---
-    if !has_column("workflow_sessions", "skill_id") {
-        conn.execute_batch(
-            "ALTER TABLE workflow_sessions ADD COLUMN skill_id INTEGER REFERENCES skills(id);",
-        )?;
-    }
+/// Migration 50: Quality gate waivers, one per skill and check, with the
+/// justification given for letting the check fail.
+fn run_quality_waivers_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS quality_gate_waivers (
+            skill_name TEXT NOT NULL,
+            check_id TEXT NOT NULL,
+            justification TEXT NOT NULL,
+            waived_by TEXT,
+            waived_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            PRIMARY KEY (skill_name, check_id)
+        );",
+    )?;
+    Ok(())
+}
 
-    // --- imported_skills ---
-    if !has_column("imported_skills", "skill_master_id") {
-        conn.execute_batch(
-            "ALTER TABLE imported_skills ADD COLUMN skill_master_id INTEGER REFERENCES skills(id);",
-        )?;
-    }
+/// Migration 51: Changelog of automatic version bumps when a skill is regenerated.
+fn run_skill_changelog_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS skill_changelog (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            skill_name TEXT NOT NULL,
+            version TEXT NOT NULL,
+            previous_version TEXT,
+            bump TEXT NOT NULL,
+            summary TEXT NOT NULL,
+            previous_sha TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE INDEX IF NOT EXISTS idx_skill_changelog_skill ON skill_changelog(skill_name);",
+    )?;
+    Ok(())
+}
 
-    // Backfill all new FK columns in a single transaction
+/// Migration 52: Reviewer annotations anchored to line ranges of context files.
+fn run_artifact_annotations_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
     conn.execute_batch(
-        "
-        BEGIN;
+        "CREATE TABLE IF NOT EXISTS artifact_annotations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            skill_name TEXT NOT NULL,
+            file TEXT NOT NULL,
+            start_line INTEGER NOT NULL,
+            end_line INTEGER NOT NULL,
+            anchor_text TEXT NOT NULL,
+            comment TEXT NOT NULL,
+            author TEXT,
+            orphaned INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE INDEX IF NOT EXISTS idx_artifact_annotations_skill ON artifact_annotations(skill_name, file);",
+    )?;
+    Ok(())
+}
 
-        UPDATE workflow_steps
-        SET workflow_run_id = (
-            SELECT wr.id FROM workflow_runs wr WHERE wr.skill_name = workflow_steps.skill_name
-        )
-        WHERE workflow_run_id IS NULL;
+/// Migration 53: Input fingerprints of workflow step runs, for reusing the
+/// cached outputs of a run whose inputs are unchanged. `cached` is 0 while
+/// the run is in progress and 1 once its outputs have been copied.
+fn run_step_run_cache_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS step_run_cache (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            skill_name TEXT NOT NULL,
+            step_id INTEGER NOT NULL,
+            fingerprint TEXT NOT NULL,
+            agent_id TEXT NOT NULL,
+            model TEXT NOT NULL,
+            cached INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE INDEX IF NOT EXISTS idx_step_run_cache_step ON step_run_cache(skill_name, step_id);",
+    )?;
+    Ok(())
+}
 
-        UPDATE workflow_artifacts
-        SET workflow_run_id = (
-            SELECT wr.id FROM workflow_runs wr WHERE wr.skill_name = workflow_artifacts.skill_name
-        )
-        WHERE workflow_run_id IS NULL;
+/// Migration 54: Refine sessions persisted across restarts. `messages` and
+/// `pending_diff` are JSON; `last_agent_id` routes agent replies to their session.
+fn run_refine_sessions_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS refine_sessions (
+            session_id TEXT PRIMARY KEY,
+            skill_name TEXT NOT NULL,
+            sdk_session_id TEXT,
+            messages TEXT NOT NULL DEFAULT '[]',
+            pending_diff TEXT,
+            last_agent_id TEXT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE INDEX IF NOT EXISTS idx_refine_sessions_agent ON refine_sessions(last_agent_id);",
+    )?;
+    Ok(())
+}
 
-        UPDATE agent_runs
-        SET workflow_run_id = (
-            SELECT wr.id FROM workflow_runs wr WHERE wr.skill_name = agent_runs.skill_name
-        )
-        WHERE workflow_run_id IS NULL;
+/// Migration 55: Branch-per-skill pushes — the review branch and pull/merge
+/// request opened for each skill, so the library can show changes that are
+/// still pending review upstream.
+fn run_upstream_reviews_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS skill_upstream_reviews (
+            skill_name TEXT PRIMARY KEY,
+            host TEXT NOT NULL,
+            repo TEXT NOT NULL,
+            repo_url TEXT NOT NULL,
+            branch TEXT NOT NULL,
+            base_branch TEXT NOT NULL,
+            number INTEGER NOT NULL,
+            url TEXT NOT NULL,
+            state TEXT NOT NULL DEFAULT 'open',
+            commit_sha TEXT,
+            pushed_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );",
+    )?;
+    Ok(())
+}
 
-        UPDATE skill_tags
-        SET skill_id = (
-            SELECT s.id FROM skills s WHERE s.name = skill_tags.skill_name
-        )
-        WHERE skill_id IS NULL;
+/// Migration 56: Declared dependencies between skills, one row per edge.
+fn run_skill_dependencies_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS skill_dependencies (
+            skill_name TEXT NOT NULL,
+            depends_on TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            PRIMARY KEY (skill_name, depends_on)
+        );
+        CREATE INDEX IF NOT EXISTS idx_skill_dependencies_depends_on ON skill_dependencies(depends_on);",
+    )?;
+    Ok(())
+}
 
-        UPDATE skill_locks
-        SET skill_id = (
-            SELECT s.id FROM skills s WHERE s.name = skill_locks.skill_name
-        )
-        WHERE skill_id IS NULL;
+fn run_skill_test_cases_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS skill_test_cases (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            skill_name TEXT NOT NULL,
+            name TEXT NOT NULL,
+            prompt TEXT NOT NULL,
+            expect_trigger INTEGER NOT NULL DEFAULT 1,
+            expected_contains TEXT NOT NULL DEFAULT '[]',
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE INDEX IF NOT EXISTS idx_skill_test_cases_skill ON skill_test_cases(skill_name);
+        CREATE TABLE IF NOT EXISTS skill_test_results (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            test_case_id INTEGER NOT NULL,
+            skill_name TEXT NOT NULL,
+            run_id TEXT NOT NULL,
+            skill_version TEXT,
+            triggered INTEGER NOT NULL,
+            passed INTEGER NOT NULL,
+            response TEXT NOT NULL,
+            failures TEXT NOT NULL DEFAULT '[]',
+            error TEXT,
+            duration_ms INTEGER NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE INDEX IF NOT EXISTS idx_skill_test_results_skill ON skill_test_results(skill_name, run_id);",
+    )?;
+    Ok(())
+}
 
-        UPDATE workflow_sessions
-        SET skill_id = (
-            SELECT s.id FROM skills s WHERE s.name = workflow_sessions.skill_name
-        )
-        WHERE skill_id IS NULL;
+/// Migration 58: Audit log of tool calls per agent run.
+fn run_agent_tool_calls_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS agent_tool_calls (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            agent_id TEXT NOT NULL,
+            skill_name TEXT NOT NULL,
+            step_id INTEGER NOT NULL,
+            tool_use_id TEXT NOT NULL,
+            tool_name TEXT NOT NULL,
+            input_summary TEXT NOT NULL DEFAULT '',
+            allowed INTEGER,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            UNIQUE(agent_id, tool_use_id)
+        );
+        CREATE INDEX IF NOT EXISTS idx_agent_tool_calls_skill ON agent_tool_calls(skill_name);",
+    )?;
+    Ok(())
+}
 
-        UPDATE imported_skills
-        SET skill_master_id = (
-            SELECT s.id FROM skills s WHERE s.name = imported_skills.skill_name
-        )
-        WHERE skill_master_id IS NULL;
+/// Migration 59: Index `agent_runs` by skill for per-skill analytics.
+fn run_agent_runs_skill_index_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_agent_runs_skill ON agent_runs(skill_name, step_id);",
+    )?;
+    Ok(())
+}
 
-        COMMIT;
-    ",
+/// Migration 60: The SKILL.md last pushed to each shared repo, the common
+/// ancestor for merging local and remote edits on the next push.
+fn run_skill_push_bases_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS skill_push_bases (
+            skill_name TEXT NOT NULL,
+            repo_url TEXT NOT NULL,
+            skill_path TEXT NOT NULL,
+            content TEXT NOT NULL,
+            commit_sha TEXT,
+            pushed_at TEXT NOT NULL,
+            PRIMARY KEY (skill_name, repo_url)
+        );",
     )?;
+    Ok(())
+}
 
-    log::info!("migration 22: added FK columns to child tables and backfilled");
+/// Migration 61: SDK session and transcript of each workflow step run, so a
+/// run cut short by a crash or quit can be resumed instead of rerun.
+fn run_agent_run_checkpoints_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS agent_run_checkpoints (
+            agent_id TEXT PRIMARY KEY,
+            skill_name TEXT NOT NULL,
+            step_id INTEGER NOT NULL,
+            pid INTEGER NOT NULL,
+            sdk_session_id TEXT,
+            transcript_path TEXT,
+            turns INTEGER NOT NULL DEFAULT 0,
+            status TEXT NOT NULL DEFAULT 'running',
+            resumed_from TEXT,
+            started_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE INDEX IF NOT EXISTS idx_agent_run_checkpoints_step ON agent_run_checkpoints(skill_name, step_id);",
+    )?;
     Ok(())
 }
 
-/// Migration 24: Add SKILL.md frontmatter fields to the `skills` master table.
-/// These fields (description, version, model, argument_hint, user_invocable,
-/// disable_model_invocation) apply to ALL skill sources and belong in the canonical
-/// `skills` table rather than per-source tables (workflow_runs / imported_skills).
-/// Backfills from workflow_runs (skill-builder) and imported_skills (marketplace/imported).
-fn run_frontmatter_to_skills_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
-    let existing_cols: Vec<String> = conn
-        .prepare("PRAGMA table_info(skills)")?
-        .query_map([], |r| r.get::<_, String>(1))?
-        .filter_map(|r| r.ok())
-        .collect();
+/// Migration 62: Delivery log of workflow events sent to automation hooks.
+fn run_automation_hook_deliveries_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS automation_hook_deliveries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            hook_name TEXT NOT NULL,
+            event TEXT NOT NULL,
+            skill_name TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            attempts INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            detail TEXT NOT NULL DEFAULT '',
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );",
+    )?;
+    Ok(())
+}
 
-    for (col, def) in &[
-        ("description", "TEXT"),
-        ("version", "TEXT"),
-        ("model", "TEXT"),
-        ("argument_hint", "TEXT"),
-        ("user_invocable", "INTEGER"),
-        ("disable_model_invocation", "INTEGER"),
-    ] {
-        if !existing_cols.contains(&col.to_string()) {
-            conn.execute_batch(&format!("ALTER TABLE skills ADD COLUMN {} {};", col, def))?;
-        }
-    }
+/// Migration 63: Changes each instance makes to a skill, polled by instances
+/// observing it read-only.
+fn run_skill_events_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS skill_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            skill_name TEXT NOT NULL,
+            instance_id TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE INDEX IF NOT EXISTS idx_skill_events_skill ON skill_events(skill_name, id);",
+    )?;
+    Ok(())
+}
 
-    // Backfill from workflow_runs for skill-builder skills
+/// Migration 64: Latest import security scan of each skill.
+fn run_skill_scan_reports_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
     conn.execute_batch(
-        "UPDATE skills
-         SET
-           description = COALESCE(skills.description, (
-               SELECT wr.description FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
-           version = COALESCE(skills.version, (
-               SELECT wr.version FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
-           model = COALESCE(skills.model, (
-               SELECT wr.model FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
-           argument_hint = COALESCE(skills.argument_hint, (
-               SELECT wr.argument_hint FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
-           user_invocable = COALESCE(skills.user_invocable, (
-               SELECT wr.user_invocable FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
-           disable_model_invocation = COALESCE(skills.disable_model_invocation, (
-               SELECT wr.disable_model_invocation FROM workflow_runs wr WHERE wr.skill_name = skills.name))
-         WHERE skill_source = 'skill-builder';",
+        "CREATE TABLE IF NOT EXISTS skill_scan_reports (
+            skill_name TEXT PRIMARY KEY,
+            flagged INTEGER NOT NULL,
+            report_json TEXT NOT NULL,
+            scanned_at TEXT NOT NULL
+        );",
     )?;
+    Ok(())
+}
 
-    // Backfill from imported_skills for marketplace/imported skills
-    // Note: description was dropped from imported_skills in migration 12; stays NULL here
+/// Migration 65: Compressed archive of finished agent transcripts.
+fn run_agent_transcripts_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
     conn.execute_batch(
-        "UPDATE skills
-         SET
-           version = COALESCE(skills.version, (
-               SELECT imp.version FROM imported_skills imp WHERE imp.skill_name = skills.name)),
-           model = COALESCE(skills.model, (
-               SELECT imp.model FROM imported_skills imp WHERE imp.skill_name = skills.name)),
-           argument_hint = COALESCE(skills.argument_hint, (
-               SELECT imp.argument_hint FROM imported_skills imp WHERE imp.skill_name = skills.name)),
-           user_invocable = COALESCE(skills.user_invocable, (
-               SELECT imp.user_invocable FROM imported_skills imp WHERE imp.skill_name = skills.name)),
-           disable_model_invocation = COALESCE(skills.disable_model_invocation, (
-               SELECT imp.disable_model_invocation FROM imported_skills imp WHERE imp.skill_name = skills.name))
-         WHERE skill_source IN ('marketplace', 'imported');",
+        "CREATE TABLE IF NOT EXISTS agent_transcripts (
+            agent_id TEXT PRIMARY KEY,
+            skill_name TEXT NOT NULL,
+            content BLOB NOT NULL,
+            uncompressed_bytes INTEGER NOT NULL,
+            compressed_bytes INTEGER NOT NULL,
+            message_count INTEGER NOT NULL,
+            tool_call_count INTEGER NOT NULL,
+            access_seq INTEGER NOT NULL,
+            archived_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE INDEX IF NOT EXISTS idx_agent_transcripts_skill ON agent_transcripts(skill_name);",
     )?;
+    Ok(())
+}
 
-    log::info!("migration 24: added frontmatter fields to skills master, backfilled from workflow_runs and imported_skills");
+/// Migration 66: The `decisions.json` each skill's SKILL.md was last generated
+/// from, so step 3 can patch only what changed since.
+fn run_skill_generation_bases_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS skill_generation_bases (
+            skill_name TEXT PRIMARY KEY,
+            decisions_hash TEXT NOT NULL,
+            decisions_json TEXT NOT NULL,
+            generated_at TEXT NOT NULL
+        );",
+    )?;
     Ok(())
 }
 
-/// Ensure the six frontmatter columns exist in the `skills` table and are populated.
-/// Idempotent — checks PRAGMA table_info before each ALTER TABLE.
-/// Called every startup to guard against dev builds that recorded migration 24 in
-/// schema_migrations before the ALTER TABLE statements actually executed.
-fn repair_skills_table_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
-    let cols: Vec<String> = conn
-        .prepare("PRAGMA table_info(skills)")?
-        .query_map([], |r| r.get::<_, String>(1))?
-        .filter_map(|r| r.ok())
-        .collect();
-
-    let mut added_any = false;
-    for (col, def) in &[
-        ("deleted_at", "TEXT"),
-        ("description", "TEXT"),
-        ("version", "TEXT"),
-        ("model", "TEXT"),
-        ("argument_hint", "TEXT"),
-        ("user_invocable", "INTEGER"),
-        ("disable_model_invocation", "INTEGER"),
-    ] {
-        if !cols.contains(&col.to_string()) {
-            conn.execute_batch(&format!("ALTER TABLE skills ADD COLUMN {} {};", col, def))?;
-            log::info!(
-                "repair_skills_table_schema: added missing column '{}' to skills",
-                col
-            );
-            added_any = true;
-        }
-    }
-
-    // If any column was missing, the migration 24 backfill never ran either.
-    // Run it now so existing imported/marketplace skills have their version/model populated.
-    if added_any {
-        conn.execute_batch(
-            "UPDATE skills
-             SET
-               description = COALESCE(skills.description, (
-                   SELECT wr.description FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
-               version = COALESCE(skills.version, (
-                   SELECT wr.version FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
-               model = COALESCE(skills.model, (
-                   SELECT wr.model FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
-               argument_hint = COALESCE(skills.argument_hint, (
-                   SELECT wr.argument_hint FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
-               user_invocable = COALESCE(skills.user_invocable, (
-                   SELECT wr.user_invocable FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
-               disable_model_invocation = COALESCE(skills.disable_model_invocation, (
-                   SELECT wr.disable_model_invocation FROM workflow_runs wr WHERE wr.skill_name = skills.name))
-             WHERE skill_source = 'skill-builder';"
-        )?;
-        conn.execute_batch(
-            "UPDATE skills
-             SET
-               version = COALESCE(skills.version, (
-                   SELECT imp.version FROM imported_skills imp WHERE imp.skill_name = skills.name)),
-               model = COALESCE(skills.model, (
-                   SELECT imp.model FROM imported_skills imp WHERE imp.skill_name = skills.name)),
-               argument_hint = COALESCE(skills.argument_hint, (
-                   SELECT imp.argument_hint FROM imported_skills imp WHERE imp.skill_name = skills.name)),
-               user_invocable = COALESCE(skills.user_invocable, (
-                   SELECT imp.user_invocable FROM imported_skills imp WHERE imp.skill_name = skills.name)),
-               disable_model_invocation = COALESCE(skills.disable_model_invocation, (
-                   SELECT imp.disable_model_invocation FROM imported_skills imp WHERE imp.skill_name = skills.name))
-             WHERE skill_source IN ('marketplace', 'imported');"
-        )?;
-        log::info!("repair_skills_table_schema: backfilled frontmatter fields from workflow_runs and imported_skills");
-    }
-
+/// Migration 67: Skill invocations imported from Claude Code session
+/// transcripts, unique per tool use or message id.
+fn run_skill_invocations_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS skill_invocations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            event_id TEXT NOT NULL UNIQUE,
+            skill_name TEXT NOT NULL,
+            triggered_by TEXT NOT NULL,
+            session_id TEXT,
+            project_path TEXT,
+            invoked_at TEXT NOT NULL,
+            imported_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE INDEX IF NOT EXISTS idx_skill_invocations_skill ON skill_invocations(skill_name, invoked_at);",
+    )?;
     Ok(())
 }
 
-fn run_workspace_skills_purpose_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
-    let has_column = conn
-        .prepare("PRAGMA table_info(workspace_skills)")
+/// Migration 68: Count of sidecar retries after transient API failures per agent run.
+fn run_agent_run_retry_count_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let columns: Vec<String> = conn
+        .prepare("PRAGMA table_info(agent_runs)")
         .and_then(|mut stmt| {
-            stmt.query_map([], |r| r.get::<_, String>(1))
-                .map(|rows| rows.filter_map(|r| r.ok()).any(|n| n == "purpose"))
+            stmt.query_map([], |row| row.get::<_, String>(1))
+                .map(|rows| rows.filter_map(|r| r.ok()).collect())
         })
-        .unwrap_or(false);
-    if !has_column {
-        conn.execute_batch("ALTER TABLE workspace_skills ADD COLUMN purpose TEXT;")?;
+        .unwrap_or_default();
+
+    if !columns.iter().any(|name| name == "retry_count") {
+        conn.execute_batch("ALTER TABLE agent_runs ADD COLUMN retry_count INTEGER NOT NULL DEFAULT 0;")?;
     }
-    log::info!("migration 25: added purpose column to workspace_skills");
     Ok(())
 }
 
-/// Migration 26: Add `content_hash TEXT` to workspace_skills and imported_skills.
-/// Existing rows get NULL (treated as "unmodified baseline unknown").
-fn run_content_hash_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
-    let check_col = |table: &str, col: &str| -> bool {
-        conn.prepare(&format!("PRAGMA table_info({})", table))
-            .and_then(|mut stmt| {
-                stmt.query_map([], |r| r.get::<_, String>(1))
-                    .map(|rows| rows.filter_map(|r| r.ok()).any(|n| n == col))
-            })
-            .unwrap_or(false)
-    };
-    let mut altered = false;
-    if !check_col("workspace_skills", "content_hash") {
-        conn.execute_batch("ALTER TABLE workspace_skills ADD COLUMN content_hash TEXT;")?;
-        altered = true;
-    }
-    if !check_col("imported_skills", "content_hash") {
-        conn.execute_batch("ALTER TABLE imported_skills ADD COLUMN content_hash TEXT;")?;
-        altered = true;
-    }
-    if altered {
-        log::info!("migration 26: added content_hash to workspace_skills and imported_skills");
+/// Migration 69: Package format last used for each skill (see `package_formats`).
+fn run_skill_package_format_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let columns: Vec<String> = conn
+        .prepare("PRAGMA table_info(skills)")?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?;
+    if !columns.iter().any(|c| c == "package_format") {
+        conn.execute_batch("ALTER TABLE skills ADD COLUMN package_format TEXT;")?;
     }
     Ok(())
 }
 
-fn run_backfill_null_versions_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
-    // One-time patch: set version = '1.0.0' wherever version is NULL in all three version-
-    // tracking tables. Skills imported before version tracking was introduced had no version
-    // recorded; this prevents them from showing false "Update available" badges.
-    let skills_updated = conn.execute(
-        "UPDATE skills SET version = '1.0.0' WHERE version IS NULL",
-        [],
-    )?;
-    let imported_updated = conn.execute(
-        "UPDATE imported_skills SET version = '1.0.0' WHERE version IS NULL",
-        [],
-    )?;
-    let workspace_updated = conn.execute(
-        "UPDATE workspace_skills SET version = '1.0.0' WHERE version IS NULL",
-        [],
+/// Migration 70: Batch refines — one instruction run against several skills,
+/// each skill's changes queued for review.
+fn run_refine_batches_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS refine_batches (
+            batch_id TEXT PRIMARY KEY,
+            instruction TEXT NOT NULL,
+            workspace_path TEXT NOT NULL,
+            workflow_session_id TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );
+        CREATE TABLE IF NOT EXISTS refine_batch_items (
+            batch_id TEXT NOT NULL REFERENCES refine_batches(batch_id) ON DELETE CASCADE,
+            skill_name TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            status TEXT NOT NULL DEFAULT 'queued',
+            agent_id TEXT,
+            diff TEXT,
+            error TEXT,
+            updated_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            PRIMARY KEY (batch_id, skill_name)
+        );",
     )?;
-    log::info!(
-        "migration 27: backfilled null versions to '1.0.0' — skills={}, imported_skills={}, workspace_skills={}",
-        skills_updated, imported_updated, workspace_updated
-    );
     Ok(())
 }
 
-fn run_rename_upload_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
-    // Rename upload → imported
-    conn.execute(
-        "UPDATE skills SET skill_source = 'imported' WHERE skill_source = 'upload'",
-        [],
-    )?;
-    // Clean orphaned non-bundled imported_skills with no skills master row
-    conn.execute(
-        "DELETE FROM imported_skills
-         WHERE is_bundled = 0
-           AND skill_name NOT IN (SELECT name FROM skills WHERE COALESCE(deleted_at, '') = '')",
-        [],
+/// Migration 71: Tag aliases, resolved to their canonical tag when tags are written.
+fn run_tag_aliases_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS tag_aliases (
+            alias TEXT PRIMARY KEY,
+            tag TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z')
+        );",
     )?;
-    log::info!("migration 19: renamed upload→imported, cleaned orphaned imported_skills");
     Ok(())
 }
 
-fn run_author_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
-    let has_author = conn
-        .prepare("PRAGMA table_info(workflow_runs)")
-        .and_then(|mut stmt| {
-            stmt.query_map([], |row| row.get::<_, String>(1))
-                .map(|rows| {
-                    rows.filter_map(|r| r.ok())
-                        .any(|name| name == "author_login")
-                })
-        })
-        .unwrap_or(false);
-    if !has_author {
-        conn.execute_batch(
-            "ALTER TABLE workflow_runs ADD COLUMN author_login TEXT;
-             ALTER TABLE workflow_runs ADD COLUMN author_avatar TEXT;",
-        )?;
-    }
+/// Migration 72: Append-only audit log of skill, lock and settings changes.
+/// Triggers refuse updates and deletes so entries cannot be rewritten.
+fn run_audit_log_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            created_at TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            instance_id TEXT NOT NULL,
+            actor TEXT,
+            action TEXT NOT NULL,
+            skill_name TEXT,
+            summary TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_audit_log_created ON audit_log(created_at);
+        CREATE INDEX IF NOT EXISTS idx_audit_log_skill ON audit_log(skill_name);
+        CREATE TRIGGER IF NOT EXISTS audit_log_no_update BEFORE UPDATE ON audit_log
+        BEGIN
+            SELECT RAISE(ABORT, 'audit_log is append-only');
+        END;",
+    )?;
+    conn.execute_batch(AUDIT_LOG_NO_DELETE_TRIGGER)?;
     Ok(())
 }
 
-fn run_usage_tracking_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
-    let columns: Vec<String> = conn
-        .prepare("PRAGMA table_info(agent_runs)")
-        .and_then(|mut stmt| {
-            stmt.query_map([], |row| row.get::<_, String>(1))
-                .map(|rows| rows.filter_map(|r| r.ok()).collect())
-        })
-        .unwrap_or_default();
+/// Refuses deletes from `audit_log`. Only retention and the user data purge
+/// lift it, inside the transaction that deletes entries.
+const AUDIT_LOG_NO_DELETE_TRIGGER: &str = "CREATE TRIGGER IF NOT EXISTS audit_log_no_delete BEFORE DELETE ON audit_log
+    BEGIN
+        SELECT RAISE(ABORT, 'audit_log is append-only');
+    END;";
 
-    if !columns.iter().any(|name| name == "cache_read_tokens") {
-        conn.execute_batch(
-            "ALTER TABLE agent_runs ADD COLUMN cache_read_tokens INTEGER DEFAULT 0;",
-        )?;
-    }
-    if !columns.iter().any(|name| name == "cache_write_tokens") {
-        conn.execute_batch(
-            "ALTER TABLE agent_runs ADD COLUMN cache_write_tokens INTEGER DEFAULT 0;",
-        )?;
-    }
-    if !columns.iter().any(|name| name == "duration_ms") {
-        conn.execute_batch("ALTER TABLE agent_runs ADD COLUMN duration_ms INTEGER;")?;
-    }
-    if !columns.iter().any(|name| name == "reset_marker") {
-        conn.execute_batch("ALTER TABLE agent_runs ADD COLUMN reset_marker TEXT;")?;
+/// Migration 73: Per-file accept/reject decisions of a refine session (JSON).
+fn run_refine_file_reviews_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let columns: Vec<String> = conn
+        .prepare("PRAGMA table_info(refine_sessions)")?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?;
+    if !columns.iter().any(|name| name == "file_reviews") {
+        conn.execute_batch("ALTER TABLE refine_sessions ADD COLUMN file_reviews TEXT NOT NULL DEFAULT '[]';")?;
     }
     Ok(())
 }
 
-fn run_workflow_session_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
-    let columns: Vec<String> = conn
-        .prepare("PRAGMA table_info(agent_runs)")
-        .and_then(|mut stmt| {
-            stmt.query_map([], |row| row.get::<_, String>(1))
-                .map(|rows| rows.filter_map(|r| r.ok()).collect())
-        })
-        .unwrap_or_default();
-
-    if !columns.iter().any(|name| name == "workflow_session_id") {
-        conn.execute_batch("ALTER TABLE agent_runs ADD COLUMN workflow_session_id TEXT;")?;
-    }
+/// Migration 74: Revalidate cached catalogs by tree and blob SHA instead of the
+/// `marketplace.json` ETag, and key them by server. Old rows are dropped; the
+/// next browse refetches them.
+fn run_catalog_tree_sha_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "DROP TABLE IF EXISTS marketplace_catalog_cache;
+        CREATE TABLE marketplace_catalog_cache (
+            cache_key TEXT PRIMARY KEY,
+            tree_sha TEXT,
+            blob_shas_json TEXT NOT NULL DEFAULT '{}',
+            registry_name TEXT,
+            skills_json TEXT NOT NULL,
+            fetched_at TEXT NOT NULL
+        );",
+    )?;
     Ok(())
 }
 
-fn run_agent_stats_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
-    let columns: Vec<String> = conn
-        .prepare("PRAGMA table_info(agent_runs)")
-        .and_then(|mut stmt| {
-            stmt.query_map([], |row| row.get::<_, String>(1))
-                .map(|rows| rows.filter_map(|r| r.ok()).collect())
-        })
-        .unwrap_or_default();
+/// Migration 18: Backfill `skills` from `workflow_runs`, add FK column, backfill FK,
+/// and remove marketplace rows from `workflow_runs` (now in skills master only).
+fn run_skills_backfill_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    // Step 1: Backfill skills from workflow_runs
+    let backfilled: usize = conn.execute(
+        "INSERT OR IGNORE INTO skills (name, skill_source, domain, skill_type, created_at, updated_at)
+         SELECT skill_name,
+           CASE WHEN source = 'marketplace' THEN 'marketplace' ELSE 'skill-builder' END,
+           domain, skill_type, created_at, updated_at
+         FROM workflow_runs",
+        [],
+    )?;
 
-    if !columns.iter().any(|name| name == "num_turns") {
-        conn.execute_batch("ALTER TABLE agent_runs ADD COLUMN num_turns INTEGER DEFAULT 0;")?;
-    }
-    if !columns.iter().any(|name| name == "stop_reason") {
-        conn.execute_batch("ALTER TABLE agent_runs ADD COLUMN stop_reason TEXT;")?;
-    }
-    if !columns.iter().any(|name| name == "duration_api_ms") {
-        conn.execute_batch("ALTER TABLE agent_runs ADD COLUMN duration_api_ms INTEGER;")?;
-    }
-    if !columns.iter().any(|name| name == "tool_use_count") {
-        conn.execute_batch("ALTER TABLE agent_runs ADD COLUMN tool_use_count INTEGER DEFAULT 0;")?;
-    }
-    if !columns.iter().any(|name| name == "compaction_count") {
+    // Step 2: Add FK column (check PRAGMA table_info first for idempotency)
+    let has_skill_id = conn
+        .prepare("PRAGMA table_info(workflow_runs)")?
+        .query_map([], |r| r.get::<_, String>(1))?
+        .any(|r| r.map(|n| n == "skill_id").unwrap_or(false));
+    if !has_skill_id {
         conn.execute_batch(
-            "ALTER TABLE agent_runs ADD COLUMN compaction_count INTEGER DEFAULT 0;",
+            "ALTER TABLE workflow_runs ADD COLUMN skill_id INTEGER REFERENCES skills(id);",
         )?;
     }
+
+    // Step 3: Backfill FK
+    conn.execute(
+        "UPDATE workflow_runs SET skill_id = (SELECT id FROM skills WHERE skills.name = workflow_runs.skill_name)",
+        [],
+    )?;
+
+    // Step 4: Remove marketplace rows from workflow_runs (now in skills master only)
+    conn.execute(
+        "DELETE FROM workflow_steps WHERE skill_name IN (SELECT skill_name FROM workflow_runs WHERE source = 'marketplace')",
+        [],
+    ).ok(); // marketplace skills may not have step rows — ignore errors
+    let removed: usize =
+        conn.execute("DELETE FROM workflow_runs WHERE source = 'marketplace'", [])?;
+
+    log::info!(
+        "migration 18: backfilled {} skills, removed {} marketplace workflow_runs",
+        backfilled,
+        removed
+    );
     Ok(())
 }
 
-// --- Usage Tracking ---
+fn run_workspace_skills_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute_batch(
+        "
+        BEGIN;
 
-fn step_name(step_id: i32) -> String {
-    match step_id {
-        -11 => "Test".to_string(),
-        -10 => "Refine".to_string(),
-        0 => "Research".to_string(),
-        1 => "Review".to_string(),
-        2 => "Detailed Research".to_string(),
-        3 => "Review".to_string(),
-        4 => "Confirm Decisions".to_string(),
-        5 => "Generate Skill".to_string(),
-        _ => format!("Step {}", step_id),
-    }
+        CREATE TABLE IF NOT EXISTS workspace_skills (
+            skill_id     TEXT PRIMARY KEY,
+            skill_name   TEXT UNIQUE NOT NULL,
+            domain       TEXT,
+            description  TEXT,
+            is_active    INTEGER NOT NULL DEFAULT 1,
+            is_bundled   INTEGER NOT NULL DEFAULT 0,
+            disk_path    TEXT NOT NULL,
+            imported_at  TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            skill_type   TEXT,
+            version      TEXT,
+            model        TEXT,
+            argument_hint TEXT,
+            user_invocable INTEGER,
+            disable_model_invocation INTEGER
+        );
+
+        INSERT OR IGNORE INTO workspace_skills
+            (skill_id, skill_name, domain, is_active, is_bundled,
+             disk_path, imported_at, skill_type, version, model,
+             argument_hint, user_invocable, disable_model_invocation)
+        SELECT
+            skill_id, skill_name, domain, is_active, is_bundled,
+            disk_path, imported_at, skill_type, version, model,
+            argument_hint, user_invocable, disable_model_invocation
+        FROM imported_skills
+        WHERE skill_type = 'skill-builder' OR is_bundled = 1;
+
+        DELETE FROM imported_skills WHERE skill_type = 'skill-builder' OR is_bundled = 1;
+
+        COMMIT;
+    ",
+    )?;
+    log::info!("migration 20: created workspace_skills table, migrated skill-builder rows");
+    Ok(())
 }
 
-/// Normalize model aliases to canonical full IDs so all storage is consistent.
-/// Short names ("sonnet", "Haiku") and bare partial IDs ("claude-haiku-4-5") are
-/// mapped to the current canonical ID for each model family.  Full IDs that are
-/// already canonical pass through unchanged.
-fn normalize_model_name(model: &str) -> String {
-    let lower = model.to_lowercase();
-    if lower == "haiku" || lower == "claude-haiku-4-5" {
-        return "claude-haiku-4-5-20251001".to_string();
-    }
-    if lower == "sonnet" || lower == "claude-sonnet-4-6" {
-        return "claude-sonnet-4-6".to_string();
-    }
-    if lower == "opus" || lower == "claude-opus-4-6" {
-        return "claude-opus-4-6".to_string();
+/// Migration 21: Add integer primary key to `workflow_runs`.
+/// The table previously used `skill_name TEXT PRIMARY KEY`. We recreate it with
+/// `id INTEGER PRIMARY KEY AUTOINCREMENT` and `skill_name TEXT UNIQUE NOT NULL`.
+/// This unblocks rename_skill (no more INSERT+DELETE) and allows child tables to
+/// reference `workflow_runs` by integer FK instead of text `skill_name`.
+fn run_workflow_runs_id_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    // Idempotency guard: check whether the `id` column already exists
+    let has_id = conn
+        .prepare("PRAGMA table_info(workflow_runs)")?
+        .query_map([], |r| r.get::<_, String>(1))?
+        .any(|r| r.map(|n| n == "id").unwrap_or(false));
+    if has_id {
+        return Ok(());
     }
-    model.to_string()
-}
 
-#[allow(clippy::too_many_arguments)]
-pub fn persist_agent_run(
-    conn: &Connection,
-    agent_id: &str,
-    skill_name: &str,
-    step_id: i32,
-    model: &str,
-    status: &str,
-    input_tokens: i32,
-    output_tokens: i32,
-    cache_read_tokens: i32,
-    cache_write_tokens: i32,
-    total_cost: f64,
-    duration_ms: i64,
-    num_turns: i32,
-    stop_reason: Option<&str>,
-    duration_api_ms: Option<i64>,
-    tool_use_count: i32,
-    compaction_count: i32,
-    session_id: Option<&str>,
-    workflow_session_id: Option<&str>,
-) -> Result<(), String> {
-    let model_owned = normalize_model_name(model);
-    let model = model_owned.as_str();
+    conn.execute_batch(
+        "
+        BEGIN;
 
-    // Don't overwrite a completed/error run with shutdown status — the completed
-    // data is more valuable than the partial shutdown snapshot.
-    if status == "shutdown" {
-        let existing_status: Option<String> = conn
-            .query_row(
-                "SELECT status FROM agent_runs WHERE agent_id = ?1 AND model = ?2",
-                rusqlite::params![agent_id, model],
-                |row| row.get(0),
-            )
-            .ok();
-        if matches!(
-            existing_status.as_deref(),
-            Some("completed") | Some("error")
-        ) {
-            return Ok(());
-        }
-    }
+        DROP TABLE IF EXISTS workflow_runs_new;
 
-    // Ensure session-backed usage views include this run. For workflow runs this is
-    // idempotent with create_workflow_session; for refine/test synthetic IDs this
-    // creates the required session row on first persist.
-    if let Some(ws_id) = workflow_session_id {
-        let skill_master_id = get_skill_master_id(conn, skill_name)?;
-        conn.execute(
-            "INSERT OR IGNORE INTO workflow_sessions (session_id, skill_name, skill_id, pid)
-             VALUES (?1, ?2, ?3, ?4)",
-            rusqlite::params![
-                ws_id,
-                skill_name,
-                skill_master_id,
-                std::process::id() as i64
-            ],
-        )
-        .map_err(|e| e.to_string())?;
+        CREATE TABLE workflow_runs_new (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            skill_name  TEXT UNIQUE NOT NULL,
+            domain      TEXT NOT NULL,
+            current_step INTEGER NOT NULL DEFAULT 0,
+            status      TEXT NOT NULL DEFAULT 'pending',
+            created_at  TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            updated_at  TEXT NOT NULL DEFAULT (datetime('now') || 'Z'),
+            skill_type  TEXT DEFAULT 'domain',
+            source      TEXT NOT NULL DEFAULT 'created',
+            description TEXT,
+            version     TEXT DEFAULT '1.0.0',
+            model       TEXT,
+            argument_hint TEXT,
+            user_invocable INTEGER DEFAULT 1,
+            disable_model_invocation INTEGER DEFAULT 0,
+            author_login TEXT,
+            author_avatar TEXT,
+            display_name TEXT,
+            intake_json TEXT,
+            skill_id    INTEGER REFERENCES skills(id)
+        );
 
-        // Synthetic sessions are one run per session; mark them ended on terminal status
-        // so recent sessions show completion timing.
-        if ws_id.starts_with("synthetic:") && matches!(status, "completed" | "error" | "shutdown") {
-            conn.execute(
-                "UPDATE workflow_sessions
-                 SET ended_at = COALESCE(ended_at, datetime('now') || 'Z')
-                 WHERE session_id = ?1",
-                rusqlite::params![ws_id],
-            )
-            .map_err(|e| e.to_string())?;
-        }
-    }
+        INSERT INTO workflow_runs_new
+            (skill_name, domain, current_step, status, created_at, updated_at,
+             skill_type, source, description, version, model, argument_hint,
+             user_invocable, disable_model_invocation, author_login, author_avatar,
+             display_name, intake_json, skill_id)
+        SELECT skill_name, domain, current_step, status, created_at, updated_at,
+               skill_type, COALESCE(source, 'created'), description, version, model,
+               argument_hint, user_invocable, disable_model_invocation,
+               author_login, author_avatar, display_name, intake_json, skill_id
+        FROM workflow_runs;
 
-    conn.execute(
-        "INSERT OR REPLACE INTO agent_runs
-         (agent_id, skill_name, step_id, model, status, input_tokens, output_tokens,
-          cache_read_tokens, cache_write_tokens, total_cost, duration_ms,
-          num_turns, stop_reason, duration_api_ms, tool_use_count, compaction_count,
-          session_id, workflow_session_id, started_at, completed_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11,
-                 ?12, ?13, ?14, ?15, ?16,
-                 ?17, ?18,
-                 COALESCE((SELECT started_at FROM agent_runs WHERE agent_id = ?1 AND model = ?4), datetime('now') || 'Z'),
-                 datetime('now') || 'Z')",
-        rusqlite::params![
-            agent_id,
-            skill_name,
-            step_id,
-            model,
-            status,
-            input_tokens,
-            output_tokens,
-            cache_read_tokens,
-            cache_write_tokens,
-            total_cost,
-            duration_ms,
-            num_turns,
-            stop_reason,
-            duration_api_ms,
-            tool_use_count,
-            compaction_count,
-            session_id,
-            workflow_session_id,
-        ],
-    )
-    .map_err(|e| e.to_string())?;
+        DROP TABLE workflow_runs;
+        ALTER TABLE workflow_runs_new RENAME TO workflow_runs;
+
+        COMMIT;
+    ",
+    )?;
+
+    log::info!("migration 21: added integer PK to workflow_runs");
     Ok(())
 }
 
-pub fn get_usage_summary(
-    conn: &Connection,
-    hide_cancelled: bool,
-    start_date: Option<&str>,
-    skill_name: Option<&str>,
-) -> Result<UsageSummary, String> {
-    let mut p = 1usize;
-    let date_clause = if start_date.is_some() {
-        let s = format!(" AND ws.started_at >= ?{p}");
-        p += 1;
-        s
-    } else {
-        String::new()
+/// Migration 22: Add integer FK columns to child tables and backfill from skill_name.
+/// After this migration:
+///   - workflow_steps, workflow_artifacts, agent_runs: have `workflow_run_id INT FK → workflow_runs(id)`
+///   - skill_tags, skill_locks, workflow_sessions: have `skill_id INT FK → skills(id)`
+///   - imported_skills: has `skill_master_id INT FK → skills(id)`
+fn run_fk_columns_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    // Helper to check if a column exists in a table
+    let has_column = |table: &str, column: &str| -> bool {
+        conn.prepare(&format!("PRAGMA table_info({})", table))
+            .and_then(|mut stmt| {
+                stmt.query_map([], |r| r.get::<_, String>(1))
+                    .map(|rows| rows.filter_map(|r| r.ok()).any(|n| n == column))
+            })
+            .unwrap_or(false)
     };
-    let skill_clause = if skill_name.is_some() {
-        format!(" AND ws.skill_name = ?{p}")
-    } else {
-        String::new()
+
+    // --- workflow_steps ---
+    if !has_column("workflow_steps", "workflow_run_id") {
+        conn.execute_batch(
+            "ALTER TABLE workflow_steps ADD COLUMN workflow_run_id INTEGER REFERENCES workflow_runs(id);",
+        )?;
+    }
+
+    // --- workflow_artifacts ---
+    if !has_column("workflow_artifacts", "workflow_run_id") {
+        conn.execute_batch(
+            "ALTER TABLE workflow_artifacts ADD COLUMN workflow_run_id INTEGER REFERENCES workflow_runs(id);",
+        )?;
+    }
+
+    // --- agent_runs ---
+    if !has_column("agent_runs", "workflow_run_id") {
+        conn.execute_batch(
+            "ALTER TABLE agent_runs ADD COLUMN workflow_run_id INTEGER REFERENCES workflow_runs(id);",
+        )?;
+    }
+
+    // --- skill_tags ---
+    if !has_column("skill_tags", "skill_id") {
+        conn.execute_batch(
+            "ALTER TABLE skill_tags ADD COLUMN skill_id INTEGER REFERENCES skills(id);",
+        )?;
+    }
+
+    // --- skill_locks ---
+    if !has_column("skill_locks", "skill_id") {
+        conn.execute_batch(
+            "ALTER TABLE skill_locks ADD COLUMN skill_id INTEGER REFERENCES skills(id);",
+        )?;
+    }
+
+    // --- workflow_sessions ---
+    if !has_column("workflow_sessions", "skill_id") {
+        conn.execute_batch(
+            "ALTER TABLE workflow_sessions ADD COLUMN skill_id INTEGER REFERENCES skills(id);",
+        )?;
+    }
+
+    // --- imported_skills ---
+    if !has_column("imported_skills", "skill_master_id") {
+        conn.execute_batch(
+            "ALTER TABLE imported_skills ADD COLUMN skill_master_id INTEGER REFERENCES skills(id);",
+        )?;
+    }
+
+    // Backfill all new FK columns in a single transaction
+    conn.execute_batch(
+        "
+        BEGIN;
+
+        UPDATE workflow_steps
+        SET workflow_run_id = (
+            SELECT wr.id FROM workflow_runs wr WHERE wr.skill_name = workflow_steps.skill_name
+        )
+        WHERE workflow_run_id IS NULL;
+
+        UPDATE workflow_artifacts
+        SET workflow_run_id = (
+            SELECT wr.id FROM workflow_runs wr WHERE wr.skill_name = workflow_artifacts.skill_name
+        )
+        WHERE workflow_run_id IS NULL;
+
+        UPDATE agent_runs
+        SET workflow_run_id = (
+            SELECT wr.id FROM workflow_runs wr WHERE wr.skill_name = agent_runs.skill_name
+        )
+        WHERE workflow_run_id IS NULL;
+
+        UPDATE skill_tags
+        SET skill_id = (
+            SELECT s.id FROM skills s WHERE s.name = skill_tags.skill_name
+        )
+        WHERE skill_id IS NULL;
+
+        UPDATE skill_locks
+        SET skill_id = (
+            SELECT s.id FROM skills s WHERE s.name = skill_locks.skill_name
+        )
+        WHERE skill_id IS NULL;
+
+        UPDATE workflow_sessions
+        SET skill_id = (
+            SELECT s.id FROM skills s WHERE s.name = workflow_sessions.skill_name
+        )
+        WHERE skill_id IS NULL;
+
+        UPDATE imported_skills
+        SET skill_master_id = (
+            SELECT s.id FROM skills s WHERE s.name = imported_skills.skill_name
+        )
+        WHERE skill_master_id IS NULL;
+
+        COMMIT;
+    ",
+    )?;
+
+    log::info!("migration 22: added FK columns to child tables and backfilled");
+    Ok(())
+}
+
+/// Migration 24: Add SKILL.md frontmatter fields to the `skills` master table.
+/// These fields (description, version, model, argument_hint, user_invocable,
+/// disable_model_invocation) apply to ALL skill sources and belong in the canonical
+/// `skills` table rather than per-source tables (workflow_runs / imported_skills).
+/// Backfills from workflow_runs (skill-builder) and imported_skills (marketplace/imported).
+fn run_frontmatter_to_skills_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let existing_cols: Vec<String> = conn
+        .prepare("PRAGMA table_info(skills)")?
+        .query_map([], |r| r.get::<_, String>(1))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    for (col, def) in &[
+        ("description", "TEXT"),
+        ("version", "TEXT"),
+        ("model", "TEXT"),
+        ("argument_hint", "TEXT"),
+        ("user_invocable", "INTEGER"),
+        ("disable_model_invocation", "INTEGER"),
+    ] {
+        if !existing_cols.contains(&col.to_string()) {
+            conn.execute_batch(&format!("ALTER TABLE skills ADD COLUMN {} {};", col, def))?;
+        }
+    }
+
+    // Backfill from workflow_runs for skill-builder skills
+    conn.execute_batch(
+        "UPDATE skills
+         SET
+           description = COALESCE(skills.description, (
+               SELECT wr.description FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
+           version = COALESCE(skills.version, (
+               SELECT wr.version FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
+           model = COALESCE(skills.model, (
+               SELECT wr.model FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
+           argument_hint = COALESCE(skills.argument_hint, (
+               SELECT wr.argument_hint FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
+           user_invocable = COALESCE(skills.user_invocable, (
+               SELECT wr.user_invocable FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
+           disable_model_invocation = COALESCE(skills.disable_model_invocation, (
+               SELECT wr.disable_model_invocation FROM workflow_runs wr WHERE wr.skill_name = skills.name))
+         WHERE skill_source = 'skill-builder';",
+    )?;
+
+    // Backfill from imported_skills for marketplace/imported skills
+    // Note: description was dropped from imported_skills in migration 12; stays NULL here
+    conn.execute_batch(
+        "UPDATE skills
+         SET
+           version = COALESCE(skills.version, (
+               SELECT imp.version FROM imported_skills imp WHERE imp.skill_name = skills.name)),
+           model = COALESCE(skills.model, (
+               SELECT imp.model FROM imported_skills imp WHERE imp.skill_name = skills.name)),
+           argument_hint = COALESCE(skills.argument_hint, (
+               SELECT imp.argument_hint FROM imported_skills imp WHERE imp.skill_name = skills.name)),
+           user_invocable = COALESCE(skills.user_invocable, (
+               SELECT imp.user_invocable FROM imported_skills imp WHERE imp.skill_name = skills.name)),
+           disable_model_invocation = COALESCE(skills.disable_model_invocation, (
+               SELECT imp.disable_model_invocation FROM imported_skills imp WHERE imp.skill_name = skills.name))
+         WHERE skill_source IN ('marketplace', 'imported');",
+    )?;
+
+    log::info!("migration 24: added frontmatter fields to skills master, backfilled from workflow_runs and imported_skills");
+    Ok(())
+}
+
+/// Ensure the six frontmatter columns exist in the `skills` table and are populated.
+/// Idempotent — checks PRAGMA table_info before each ALTER TABLE.
+/// Called every startup to guard against dev builds that recorded migration 24 in
+/// schema_migrations before the ALTER TABLE statements actually executed.
+fn repair_skills_table_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let cols: Vec<String> = conn
+        .prepare("PRAGMA table_info(skills)")?
+        .query_map([], |r| r.get::<_, String>(1))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut added_any = false;
+    for (col, def) in &[
+        ("deleted_at", "TEXT"),
+        ("description", "TEXT"),
+        ("version", "TEXT"),
+        ("model", "TEXT"),
+        ("argument_hint", "TEXT"),
+        ("user_invocable", "INTEGER"),
+        ("disable_model_invocation", "INTEGER"),
+    ] {
+        if !cols.contains(&col.to_string()) {
+            conn.execute_batch(&format!("ALTER TABLE skills ADD COLUMN {} {};", col, def))?;
+            log::info!(
+                "repair_skills_table_schema: added missing column '{}' to skills",
+                col
+            );
+            added_any = true;
+        }
+    }
+
+    // If any column was missing, the migration 24 backfill never ran either.
+    // Run it now so existing imported/marketplace skills have their version/model populated.
+    if added_any {
+        conn.execute_batch(
+            "UPDATE skills
+             SET
+               description = COALESCE(skills.description, (
+                   SELECT wr.description FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
+               version = COALESCE(skills.version, (
+                   SELECT wr.version FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
+               model = COALESCE(skills.model, (
+                   SELECT wr.model FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
+               argument_hint = COALESCE(skills.argument_hint, (
+                   SELECT wr.argument_hint FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
+               user_invocable = COALESCE(skills.user_invocable, (
+                   SELECT wr.user_invocable FROM workflow_runs wr WHERE wr.skill_name = skills.name)),
+               disable_model_invocation = COALESCE(skills.disable_model_invocation, (
+                   SELECT wr.disable_model_invocation FROM workflow_runs wr WHERE wr.skill_name = skills.name))
+             WHERE skill_source = 'skill-builder';"
+        )?;
+        conn.execute_batch(
+            "UPDATE skills
+             SET
+               version = COALESCE(skills.version, (
+                   SELECT imp.version FROM imported_skills imp WHERE imp.skill_name = skills.name)),
+               model = COALESCE(skills.model, (
+                   SELECT imp.model FROM imported_skills imp WHERE imp.skill_name = skills.name)),
+               argument_hint = COALESCE(skills.argument_hint, (
+                   SELECT imp.argument_hint FROM imported_skills imp WHERE imp.skill_name = skills.name)),
+               user_invocable = COALESCE(skills.user_invocable, (
+                   SELECT imp.user_invocable FROM imported_skills imp WHERE imp.skill_name = skills.name)),
+               disable_model_invocation = COALESCE(skills.disable_model_invocation, (
+                   SELECT imp.disable_model_invocation FROM imported_skills imp WHERE imp.skill_name = skills.name))
+             WHERE skill_source IN ('marketplace', 'imported');"
+        )?;
+        log::info!("repair_skills_table_schema: backfilled frontmatter fields from workflow_runs and imported_skills");
+    }
+
+    Ok(())
+}
+
+fn run_workspace_skills_purpose_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let has_column = conn
+        .prepare("PRAGMA table_info(workspace_skills)")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |r| r.get::<_, String>(1))
+                .map(|rows| rows.filter_map(|r| r.ok()).any(|n| n == "purpose"))
+        })
+        .unwrap_or(false);
+    if !has_column {
+        conn.execute_batch("ALTER TABLE workspace_skills ADD COLUMN purpose TEXT;")?;
+    }
+    log::info!("migration 25: added purpose column to workspace_skills");
+    Ok(())
+}
+
+/// Migration 26: Add `content_hash TEXT` to workspace_skills and imported_skills.
+/// Existing rows get NULL (treated as "unmodified baseline unknown").
+fn run_content_hash_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let check_col = |table: &str, col: &str| -> bool {
+        conn.prepare(&format!("PRAGMA table_info({})", table))
+            .and_then(|mut stmt| {
+                stmt.query_map([], |r| r.get::<_, String>(1))
+                    .map(|rows| rows.filter_map(|r| r.ok()).any(|n| n == col))
+            })
+            .unwrap_or(false)
+    };
+    let mut altered = false;
+    if !check_col("workspace_skills", "content_hash") {
+        conn.execute_batch("ALTER TABLE workspace_skills ADD COLUMN content_hash TEXT;")?;
+        altered = true;
+    }
+    if !check_col("imported_skills", "content_hash") {
+        conn.execute_batch("ALTER TABLE imported_skills ADD COLUMN content_hash TEXT;")?;
+        altered = true;
+    }
+    if altered {
+        log::info!("migration 26: added content_hash to workspace_skills and imported_skills");
+    }
+    Ok(())
+}
+
+fn run_backfill_null_versions_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    // One-time patch: set version = '1.0.0' wherever version is NULL in all three version-
+    // tracking tables. Skills imported before version tracking was introduced had no version
+    // recorded; this prevents them from showing false "Update available" badges.
+    let skills_updated = conn.execute(
+        "UPDATE skills SET version = '1.0.0' WHERE version IS NULL",
+        [],
+    )?;
+    let imported_updated = conn.execute(
+        "UPDATE imported_skills SET version = '1.0.0' WHERE version IS NULL",
+        [],
+    )?;
+    let workspace_updated = conn.execute(
+        "UPDATE workspace_skills SET version = '1.0.0' WHERE version IS NULL",
+        [],
+    )?;
+    log::info!(
+        "migration 27: backfilled null versions to '1.0.0' — skills={}, imported_skills={}, workspace_skills={}",
+        skills_updated, imported_updated, workspace_updated
+    );
+    Ok(())
+}
+
+fn run_rename_upload_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    // Rename upload → imported
+    conn.execute(
+        "UPDATE skills SET skill_source = 'imported' WHERE skill_source = 'upload'",
+        [],
+    )?;
+    // Clean orphaned non-bundled imported_skills with no skills master row
+    conn.execute(
+        "DELETE FROM imported_skills
+         WHERE is_bundled = 0
+           AND skill_name NOT IN (SELECT name FROM skills WHERE COALESCE(deleted_at, '') = '')",
+        [],
+    )?;
+    log::info!("migration 19: renamed upload→imported, cleaned orphaned imported_skills");
+    Ok(())
+}
+
+fn run_author_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let has_author = conn
+        .prepare("PRAGMA table_info(workflow_runs)")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| row.get::<_, String>(1))
+                .map(|rows| {
+                    rows.filter_map(|r| r.ok())
+                        .any(|name| name == "author_login")
+                })
+        })
+        .unwrap_or(false);
+    if !has_author {
+        conn.execute_batch(
+            "ALTER TABLE workflow_runs ADD COLUMN author_login TEXT;
+             ALTER TABLE workflow_runs ADD COLUMN author_avatar TEXT;",
+        )?;
+    }
+    Ok(())
+}
+
+fn run_usage_tracking_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let columns: Vec<String> = conn
+        .prepare("PRAGMA table_info(agent_runs)")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| row.get::<_, String>(1))
+                .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        })
+        .unwrap_or_default();
+
+    if !columns.iter().any(|name| name == "cache_read_tokens") {
+        conn.execute_batch(
+            "ALTER TABLE agent_runs ADD COLUMN cache_read_tokens INTEGER DEFAULT 0;",
+        )?;
+    }
+    if !columns.iter().any(|name| name == "cache_write_tokens") {
+        conn.execute_batch(
+            "ALTER TABLE agent_runs ADD COLUMN cache_write_tokens INTEGER DEFAULT 0;",
+        )?;
+    }
+    if !columns.iter().any(|name| name == "duration_ms") {
+        conn.execute_batch("ALTER TABLE agent_runs ADD COLUMN duration_ms INTEGER;")?;
+    }
+    if !columns.iter().any(|name| name == "reset_marker") {
+        conn.execute_batch("ALTER TABLE agent_runs ADD COLUMN reset_marker TEXT;")?;
+    }
+    Ok(())
+}
+
+fn run_workflow_session_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let columns: Vec<String> = conn
+        .prepare("PRAGMA table_info(agent_runs)")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| row.get::<_, String>(1))
+                .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        })
+        .unwrap_or_default();
+
+    if !columns.iter().any(|name| name == "workflow_session_id") {
+        conn.execute_batch("ALTER TABLE agent_runs ADD COLUMN workflow_session_id TEXT;")?;
+    }
+    Ok(())
+}
+
+fn run_agent_stats_migration(conn: &Connection) -> Result<(), rusqlite::Error> {
+    let columns: Vec<String> = conn
+        .prepare("PRAGMA table_info(agent_runs)")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| row.get::<_, String>(1))
+                .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        })
+        .unwrap_or_default();
+
+    if !columns.iter().any(|name| name == "num_turns") {
+        conn.execute_batch("ALTER TABLE agent_runs ADD COLUMN num_turns INTEGER DEFAULT 0;")?;
+    }
+    if !columns.iter().any(|name| name == "stop_reason") {
+        conn.execute_batch("ALTER TABLE agent_runs ADD COLUMN stop_reason TEXT;")?;
+    }
+    if !columns.iter().any(|name| name == "duration_api_ms") {
+        conn.execute_batch("ALTER TABLE agent_runs ADD COLUMN duration_api_ms INTEGER;")?;
+    }
+    if !columns.iter().any(|name| name == "tool_use_count") {
+        conn.execute_batch("ALTER TABLE agent_runs ADD COLUMN tool_use_count INTEGER DEFAULT 0;")?;
+    }
+    if !columns.iter().any(|name| name == "compaction_count") {
+        conn.execute_batch(
+            "ALTER TABLE agent_runs ADD COLUMN compaction_count INTEGER DEFAULT 0;",
+        )?;
+    }
+    Ok(())
+}
+
+// --- Usage Tracking ---
+
+fn step_name(step_id: i32) -> String {
+    match step_id {
+        -11 => "Test".to_string(),
+        -10 => "Refine".to_string(),
+        0 => "Research".to_string(),
+        1 => "Review".to_string(),
+        2 => "Detailed Research".to_string(),
+        3 => "Review".to_string(),
+        4 => "Confirm Decisions".to_string(),
+        5 => "Generate Skill".to_string(),
+        _ => format!("Step {}", step_id),
+    }
+}
+
+/// Normalize model aliases to canonical full IDs so all storage is consistent.
+/// Short names ("sonnet", "Haiku") and bare partial IDs ("claude-haiku-4-5") are
+/// mapped to the current canonical ID for each model family.  Full IDs that are
+/// already canonical pass through unchanged.
+pub(crate) fn normalize_model_name(model: &str) -> String {
+    let lower = model.to_lowercase();
+    if lower == "haiku" || lower == "claude-haiku-4-5" {
+        return "claude-haiku-4-5-20251001".to_string();
+    }
+    if lower == "sonnet" || lower == "claude-sonnet-4-6" {
+        return "claude-sonnet-4-6".to_string();
+    }
+    if lower == "opus" || lower == "claude-opus-4-6" {
+        return "claude-opus-4-6".to_string();
+    }
+    model.to_string()
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn persist_agent_run(
+    conn: &Connection,
+    agent_id: &str,
+    skill_name: &str,
+    step_id: i32,
+    model: &str,
+    status: &str,
+    input_tokens: i32,
+    output_tokens: i32,
+    cache_read_tokens: i32,
+    cache_write_tokens: i32,
+    total_cost: f64,
+    duration_ms: i64,
+    num_turns: i32,
+    stop_reason: Option<&str>,
+    duration_api_ms: Option<i64>,
+    tool_use_count: i32,
+    compaction_count: i32,
+    session_id: Option<&str>,
+    workflow_session_id: Option<&str>,
+) -> Result<(), String> {
+    let model_owned = normalize_model_name(model);
+    let model = model_owned.as_str();
+
+    // Don't overwrite a completed/error run with shutdown status — the completed
+    // data is more valuable than the partial shutdown snapshot.
+    if status == "shutdown" {
+        let existing_status: Option<String> = conn
+            .query_row(
+                "SELECT status FROM agent_runs WHERE agent_id = ?1 AND model = ?2",
+                rusqlite::params![agent_id, model],
+                |row| row.get(0),
+            )
+            .ok();
+        if matches!(
+            existing_status.as_deref(),
+            Some("completed") | Some("error")
+        ) {
+            return Ok(());
+        }
+    }
+
+    // Ensure session-backed usage views include this run. For workflow runs this is
+    // idempotent with create_workflow_session; for refine/test synthetic IDs this
+    // creates the required session row on first persist.
+    if let Some(ws_id) = workflow_session_id {
+        let skill_master_id = get_skill_master_id(conn, skill_name)?;
+        conn.execute(
+            "INSERT OR IGNORE INTO workflow_sessions (session_id, skill_name, skill_id, pid)
+             VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![
+                ws_id,
+                skill_name,
+                skill_master_id,
+                std::process::id() as i64
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+
+        // Synthetic sessions are one run per session; mark them ended on terminal status
+        // so recent sessions show completion timing.
+        if ws_id.starts_with("synthetic:") && matches!(status, "completed" | "error" | "shutdown") {
+            conn.execute(
+                "UPDATE workflow_sessions
+                 SET ended_at = COALESCE(ended_at, datetime('now') || 'Z')
+                 WHERE session_id = ?1",
+                rusqlite::params![ws_id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+
+    conn.execute(
+        "INSERT OR REPLACE INTO agent_runs
+         (agent_id, skill_name, step_id, model, status, input_tokens, output_tokens,
+          cache_read_tokens, cache_write_tokens, total_cost, duration_ms,
+          num_turns, stop_reason, duration_api_ms, tool_use_count, compaction_count,
+          session_id, workflow_session_id, started_at, completed_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11,
+                 ?12, ?13, ?14, ?15, ?16,
+                 ?17, ?18,
+                 COALESCE((SELECT started_at FROM agent_runs WHERE agent_id = ?1 AND model = ?4), datetime('now') || 'Z'),
+                 datetime('now') || 'Z')",
+        rusqlite::params![
+            agent_id,
+            skill_name,
+            step_id,
+            model,
+            status,
+            input_tokens,
+            output_tokens,
+            cache_read_tokens,
+            cache_write_tokens,
+            total_cost,
+            duration_ms,
+            num_turns,
+            stop_reason,
+            duration_api_ms,
+            tool_use_count,
+            compaction_count,
+            session_id,
+            workflow_session_id,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    // The persisted run supersedes any live tick snapshot for this agent.
+    conn.execute(
+        "DELETE FROM agent_run_ticks WHERE agent_id = ?1",
+        rusqlite::params![agent_id],
+    )
+    .map_err(|e| e.to_string())?;
+    crate::db_events::emit(crate::db_events::DbChange::UsageRecorded {
+        agent_id: agent_id.to_string(),
+        skill_name: skill_name.to_string(),
+        step_id,
+        model: model.to_string(),
+        status: status.to_string(),
+        input_tokens,
+        output_tokens,
+        total_cost,
+    });
+    Ok(())
+}
+
+/// Record how many times the sidecar retried an agent run after transient
+/// API failures, on every model row of the run.
+pub fn set_agent_run_retry_count(conn: &Connection, agent_id: &str, retry_count: u32) -> Result<(), String> {
+    conn.execute(
+        "UPDATE agent_runs SET retry_count = ?2 WHERE agent_id = ?1",
+        rusqlite::params![agent_id, retry_count],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Per-skill lifetime aggregates since the last usage reset, most expensive
+/// first. Refine sessions are counted by SDK session, since every refine turn
+/// is its own agent run; step figures cover completed workflow steps only.
+pub fn get_skill_analytics(
+    conn: &Connection,
+    skill_name: Option<&str>,
+) -> Result<Vec<SkillAnalytics>, String> {
+    let skill_clause = if skill_name.is_some() { " AND skill_name = ?1" } else { "" };
+    let sql = format!(
+        "SELECT skill_name,
+                COALESCE(SUM(total_cost), 0.0),
+                COUNT(*),
+                COUNT(DISTINCT CASE WHEN step_id = -10 THEN COALESCE(session_id, agent_id) END),
+                MAX(CASE WHEN step_id >= 0 AND status = 'completed' THEN completed_at END),
+                AVG(CASE WHEN step_id >= 0 AND status = 'completed' THEN duration_ms END),
+                COALESCE(SUM(input_tokens), 0),
+                COALESCE(SUM(output_tokens), 0),
+                COALESCE(SUM(cache_read_tokens), 0),
+                COALESCE(SUM(cache_write_tokens), 0)
+         FROM agent_runs
+         WHERE reset_marker IS NULL{skill_clause}
+         GROUP BY skill_name
+         ORDER BY SUM(total_cost) DESC, skill_name"
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let map_row = |row: &rusqlite::Row| {
+        let input_tokens: i64 = row.get(6)?;
+        let cache_read_tokens: i64 = row.get(8)?;
+        let cache_write_tokens: i64 = row.get(9)?;
+        let prompt_tokens = input_tokens + cache_read_tokens + cache_write_tokens;
+        Ok(SkillAnalytics {
+            skill_name: row.get(0)?,
+            total_cost: row.get(1)?,
+            run_count: row.get(2)?,
+            refine_sessions: row.get(3)?,
+            last_regenerated_at: row.get(4)?,
+            avg_step_duration_ms: row.get(5)?,
+            input_tokens,
+            output_tokens: row.get(7)?,
+            cache_read_tokens,
+            cache_write_tokens,
+            cache_hit_ratio: (prompt_tokens > 0)
+                .then(|| cache_read_tokens as f64 / prompt_tokens as f64),
+        })
+    };
+    let rows = match skill_name {
+        Some(name) => stmt.query_map(rusqlite::params![name], map_row),
+        None => stmt.query_map([], map_row),
+    }
+    .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+/// Completed runs averaged by `typical_step_duration_ms`.
+const STEP_DURATION_SAMPLE: i64 = 20;
+
+/// Average duration of the last completed runs of a workflow step on a model,
+/// used to estimate how long a running step has left. `None` until the step
+/// has completed at least once on that model.
+pub fn typical_step_duration_ms(conn: &Connection, step_id: i32, model: &str) -> Result<Option<i64>, String> {
+    let avg: Option<f64> = conn
+        .query_row(
+            "SELECT AVG(duration_ms) FROM (
+                 SELECT duration_ms FROM agent_runs
+                 WHERE step_id = ?1 AND model = ?2 AND status = 'completed' AND duration_ms > 0
+                 ORDER BY completed_at DESC
+                 LIMIT ?3
+             )",
+            rusqlite::params![step_id, normalize_model_name(model), STEP_DURATION_SAMPLE],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(avg.map(|ms| ms.round() as i64))
+}
+
+/// Mark an agent's cost as unknown (NULL). Runs on a local model server keep
+/// their token counts but have no price; usage totals treat them as zero.
+pub fn clear_agent_run_cost(conn: &Connection, agent_id: &str) -> Result<(), String> {
+    conn.execute(
+        "UPDATE agent_runs SET total_cost = NULL WHERE agent_id = ?1",
+        rusqlite::params![agent_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Upsert the latest cost tick for a running agent. `started_at` is fixed by
+/// the first tick (backdated by its elapsed time) and kept on later ones.
+pub fn save_agent_run_tick(conn: &Connection, tick: &AgentCostTick) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO agent_run_ticks
+         (agent_id, skill_name, step_id, model, input_tokens, output_tokens,
+          cache_read_tokens, cache_write_tokens, total_cost, num_turns, elapsed_ms,
+          workflow_session_id, pid, started_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
+                 datetime('now', ?14) || 'Z', datetime('now') || 'Z')
+         ON CONFLICT(agent_id) DO UPDATE SET
+            input_tokens = ?5, output_tokens = ?6, cache_read_tokens = ?7,
+            cache_write_tokens = ?8, total_cost = ?9, num_turns = ?10,
+            elapsed_ms = ?11, updated_at = datetime('now') || 'Z'",
+        rusqlite::params![
+            tick.agent_id,
+            tick.skill_name,
+            tick.step_id,
+            normalize_model_name(&tick.model),
+            tick.input_tokens,
+            tick.output_tokens,
+            tick.cache_read_tokens,
+            tick.cache_write_tokens,
+            tick.total_cost,
+            tick.num_turns,
+            tick.elapsed_ms,
+            tick.workflow_session_id,
+            std::process::id() as i64,
+            format!("-{} seconds", tick.elapsed_ms / 1000),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Record tick snapshots left behind by dead processes as `shutdown` runs, so a
+/// crash mid-step keeps its approximate usage. Snapshots of agents that already
+/// have a persisted run are just dropped. Returns the number of runs recorded.
+pub fn recover_orphaned_run_ticks(conn: &Connection) -> Result<u32, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT agent_id, skill_name, step_id, model, input_tokens, output_tokens,
+                    cache_read_tokens, cache_write_tokens, total_cost, num_turns, elapsed_ms,
+                    workflow_session_id, pid, started_at, updated_at
+             FROM agent_run_ticks",
+        )
+        .map_err(|e| e.to_string())?;
+    let ticks: Vec<(AgentCostTick, u32, String, String)> = stmt
+        .query_map([], |row| {
+            Ok((
+                AgentCostTick {
+                    agent_id: row.get(0)?,
+                    skill_name: row.get(1)?,
+                    step_id: row.get(2)?,
+                    model: row.get(3)?,
+                    input_tokens: row.get(4)?,
+                    output_tokens: row.get(5)?,
+                    cache_read_tokens: row.get(6)?,
+                    cache_write_tokens: row.get(7)?,
+                    total_cost: row.get(8)?,
+                    estimated: true,
+                    num_turns: row.get(9)?,
+                    elapsed_ms: row.get(10)?,
+                    workflow_session_id: row.get(11)?,
+                },
+                row.get::<_, i64>(12)? as u32,
+                row.get(13)?,
+                row.get(14)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut recovered = 0u32;
+    for (tick, pid, started_at, updated_at) in ticks {
+        if pid == std::process::id() || check_pid_alive(pid) {
+            continue;
+        }
+        let persisted: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM agent_runs WHERE agent_id = ?1)",
+                [&tick.agent_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if persisted {
+            conn.execute("DELETE FROM agent_run_ticks WHERE agent_id = ?1", [&tick.agent_id])
+                .map_err(|e| e.to_string())?;
+            continue;
+        }
+        persist_agent_run(
+            conn,
+            &tick.agent_id,
+            &tick.skill_name,
+            tick.step_id,
+            &tick.model,
+            "shutdown",
+            tick.input_tokens,
+            tick.output_tokens,
+            tick.cache_read_tokens,
+            tick.cache_write_tokens,
+            tick.total_cost,
+            tick.elapsed_ms,
+            tick.num_turns,
+            None,
+            None,
+            0,
+            0,
+            None,
+            tick.workflow_session_id.as_deref(),
+        )?;
+        // Keep the run's real timing rather than the recovery time.
+        conn.execute(
+            "UPDATE agent_runs SET started_at = ?1, completed_at = ?2 WHERE agent_id = ?3",
+            rusqlite::params![started_at, updated_at, tick.agent_id],
+        )
+        .map_err(|e| e.to_string())?;
+        log::info!(
+            "Recovered usage for agent '{}' (skill '{}') from its last cost tick",
+            tick.agent_id,
+            tick.skill_name
+        );
+        recovered += 1;
+    }
+    Ok(recovered)
+}
+
+pub fn get_usage_summary(
+    conn: &Connection,
+    hide_cancelled: bool,
+    start_date: Option<&str>,
+    skill_name: Option<&str>,
+) -> Result<UsageSummary, String> {
+    let mut p = 1usize;
+    let date_clause = if start_date.is_some() {
+        let s = format!(" AND ws.started_at >= ?{p}");
+        p += 1;
+        s
+    } else {
+        String::new()
+    };
+    let skill_clause = if skill_name.is_some() {
+        format!(" AND ws.skill_name = ?{p}")
+    } else {
+        String::new()
+    };
+    let having_clause = if hide_cancelled {
+        " HAVING COALESCE(SUM(ar.total_cost), 0) > 0 OR COUNT(ar.total_cost) < COUNT(ar.agent_id) OR COUNT(DISTINCT ar.agent_id) = 0"
+    } else {
+        ""
+    };
+    let sql = format!(
+        "SELECT COALESCE(SUM(sub.session_cost), 0.0),
+                COUNT(*),
+                COALESCE(AVG(sub.session_cost), 0.0)
+         FROM (
+           SELECT ws.session_id, COALESCE(SUM(ar.total_cost), 0.0) as session_cost
+           FROM workflow_sessions ws
+           LEFT JOIN agent_runs ar ON ar.workflow_session_id = ws.session_id
+                                  AND ar.reset_marker IS NULL
+           WHERE ws.reset_marker IS NULL{date_clause}{skill_clause}
+           GROUP BY ws.session_id{having_clause}
+         ) sub"
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    macro_rules! query {
+        ($params:expr) => {
+            stmt.query_row($params, |row| {
+                Ok(UsageSummary {
+                    total_cost: row.get(0)?,
+                    total_runs: row.get(1)?,
+                    avg_cost_per_run: row.get(2)?,
+                })
+            })
+            .map_err(|e| e.to_string())
+        };
+    }
+    match (start_date, skill_name) {
+        (Some(sd), Some(sn)) => query!(rusqlite::params![sd, sn]),
+        (Some(sd), None) => query!(rusqlite::params![sd]),
+        (None, Some(sn)) => query!(rusqlite::params![sn]),
+        (None, None) => query!([]),
+    }
+}
+
+pub fn get_workflow_skill_names(conn: &Connection) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT DISTINCT skill_name FROM workflow_sessions
+         WHERE reset_marker IS NULL
+         ORDER BY skill_name ASC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// Agent ids of a skill's runs of `step_id` in a session, newest first.
+/// `session_id` matches either the workflow session or the SDK session.
+pub fn list_step_run_agent_ids(
+    conn: &Connection,
+    skill_name: &str,
+    step_id: u32,
+    session_id: &str,
+) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT agent_id FROM agent_runs
+             WHERE skill_name = ?1 AND step_id = ?2
+               AND (workflow_session_id = ?3 OR session_id = ?3)
+             ORDER BY started_at DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![skill_name, step_id as i32, session_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<String>, _>>().map_err(|e| e.to_string())
+}
+
+pub fn get_recent_runs(conn: &Connection, limit: usize) -> Result<Vec<AgentRunRecord>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT agent_id, skill_name, step_id, model, status,
+                    COALESCE(input_tokens, 0), COALESCE(output_tokens, 0),
+                    COALESCE(cache_read_tokens, 0), COALESCE(cache_write_tokens, 0),
+                    COALESCE(total_cost, 0.0), COALESCE(duration_ms, 0),
+                    COALESCE(num_turns, 0), stop_reason, duration_api_ms,
+                    COALESCE(tool_use_count, 0), COALESCE(compaction_count, 0),
+                    session_id, started_at, completed_at, COALESCE(retry_count, 0)
+             FROM agent_runs
+             WHERE reset_marker IS NULL
+             ORDER BY completed_at DESC
+             LIMIT ?1",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(rusqlite::params![limit as i64], |row| {
+            Ok(AgentRunRecord {
+                agent_id: row.get(0)?,
+                skill_name: row.get(1)?,
+                step_id: row.get(2)?,
+                model: row.get(3)?,
+                status: row.get(4)?,
+                input_tokens: row.get(5)?,
+                output_tokens: row.get(6)?,
+                cache_read_tokens: row.get(7)?,
+                cache_write_tokens: row.get(8)?,
+                total_cost: row.get(9)?,
+                duration_ms: row.get(10)?,
+                num_turns: row.get(11)?,
+                stop_reason: row.get(12)?,
+                duration_api_ms: row.get(13)?,
+                tool_use_count: row.get(14)?,
+                compaction_count: row.get(15)?,
+                session_id: row.get(16)?,
+                started_at: row.get(17)?,
+                completed_at: row.get(18)?,
+                retry_count: row.get(19)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+pub fn get_agent_runs(
+    conn: &Connection,
+    hide_cancelled: bool,
+    start_date: Option<&str>,
+    skill_name: Option<&str>,
+    model_family: Option<&str>,
+    limit: usize,
+) -> Result<Vec<AgentRunRecord>, String> {
+    let cost_clause = if hide_cancelled { " AND (total_cost > 0 OR total_cost IS NULL)" } else { "" };
+    let mut p = 1usize;
+    let date_clause = if start_date.is_some() {
+        let s = format!(" AND started_at >= ?{p}");
+        p += 1;
+        s
+    } else {
+        String::new()
+    };
+    let skill_clause = if skill_name.is_some() {
+        let s = format!(" AND skill_name = ?{p}");
+        p += 1;
+        s
+    } else {
+        String::new()
+    };
+    let model_family_clause = if model_family.is_some() {
+        let s = format!(
+            " AND CASE \
+              WHEN lower(model) LIKE '%haiku%'  THEN 'Haiku' \
+              WHEN lower(model) LIKE '%opus%'   THEN 'Opus' \
+              WHEN lower(model) LIKE '%sonnet%' THEN 'Sonnet' \
+              ELSE model END = ?{p}"
+        );
+        p += 1;
+        s
+    } else {
+        String::new()
+    };
+    let sql = format!(
+        "SELECT agent_id, skill_name, step_id, model, status,
+                COALESCE(input_tokens, 0), COALESCE(output_tokens, 0),
+                COALESCE(cache_read_tokens, 0), COALESCE(cache_write_tokens, 0),
+                COALESCE(total_cost, 0.0), COALESCE(duration_ms, 0),
+                COALESCE(num_turns, 0), stop_reason, duration_api_ms,
+                COALESCE(tool_use_count, 0), COALESCE(compaction_count, 0),
+                session_id, started_at, completed_at, COALESCE(retry_count, 0)
+         FROM agent_runs
+         WHERE reset_marker IS NULL
+           AND workflow_session_id IS NOT NULL{cost_clause}{date_clause}{skill_clause}{model_family_clause}
+         ORDER BY started_at DESC
+         LIMIT ?{p}"
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let limit_i64 = limit as i64;
+    macro_rules! collect_rows {
+        ($params:expr) => {
+            stmt.query_map($params, |row| {
+                Ok(AgentRunRecord {
+                    agent_id: row.get(0)?,
+                    skill_name: row.get(1)?,
+                    step_id: row.get(2)?,
+                    model: row.get(3)?,
+                    status: row.get(4)?,
+                    input_tokens: row.get(5)?,
+                    output_tokens: row.get(6)?,
+                    cache_read_tokens: row.get(7)?,
+                    cache_write_tokens: row.get(8)?,
+                    total_cost: row.get(9)?,
+                    duration_ms: row.get(10)?,
+                    num_turns: row.get(11)?,
+                    stop_reason: row.get(12)?,
+                    duration_api_ms: row.get(13)?,
+                    tool_use_count: row.get(14)?,
+                    compaction_count: row.get(15)?,
+                    session_id: row.get(16)?,
+                    started_at: row.get(17)?,
+                    completed_at: row.get(18)?,
+                    retry_count: row.get(19)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())
+        };
+    }
+    match (start_date, skill_name, model_family) {
+        (Some(sd), Some(sn), Some(mf)) => collect_rows!(rusqlite::params![sd, sn, mf, limit_i64]),
+        (Some(sd), Some(sn), None)     => collect_rows!(rusqlite::params![sd, sn, limit_i64]),
+        (Some(sd), None, Some(mf))     => collect_rows!(rusqlite::params![sd, mf, limit_i64]),
+        (Some(sd), None, None)         => collect_rows!(rusqlite::params![sd, limit_i64]),
+        (None, Some(sn), Some(mf))     => collect_rows!(rusqlite::params![sn, mf, limit_i64]),
+        (None, Some(sn), None)         => collect_rows!(rusqlite::params![sn, limit_i64]),
+        (None, None, Some(mf))         => collect_rows!(rusqlite::params![mf, limit_i64]),
+        (None, None, None)             => collect_rows!(rusqlite::params![limit_i64]),
+    }
+}
+
+pub fn get_recent_workflow_sessions(
+    conn: &Connection,
+    limit: usize,
+    hide_cancelled: bool,
+    start_date: Option<&str>,
+    skill_name: Option<&str>,
+) -> Result<Vec<WorkflowSessionRecord>, String> {
+    let having_clause = if hide_cancelled {
+        " HAVING COALESCE(SUM(ar.total_cost), 0) > 0 OR COUNT(ar.total_cost) < COUNT(ar.agent_id) OR COUNT(DISTINCT ar.agent_id) = 0"
+    } else {
+        ""
+    };
+    let mut p = 1usize;
+    let date_clause = if start_date.is_some() {
+        let s = format!(" AND ws.started_at >= ?{p}");
+        p += 1;
+        s
+    } else {
+        String::new()
+    };
+    let skill_clause = if skill_name.is_some() {
+        let s = format!(" AND ws.skill_name = ?{p}");
+        p += 1;
+        s
+    } else {
+        String::new()
+    };
+    let limit_param = format!("?{p}");
+    let sql = format!(
+        "SELECT ws.session_id,
+                ws.skill_name,
+                COALESCE(MIN(ar.step_id), 0),
+                COALESCE(MAX(ar.step_id), 0),
+                COALESCE(GROUP_CONCAT(DISTINCT ar.step_id), ''),
+                COUNT(DISTINCT ar.agent_id),
+                COALESCE(SUM(ar.total_cost), 0.0),
+                COALESCE(SUM(ar.input_tokens), 0),
+                COALESCE(SUM(ar.output_tokens), 0),
+                COALESCE(SUM(ar.cache_read_tokens), 0),
+                COALESCE(SUM(ar.cache_write_tokens), 0),
+                COALESCE(SUM(ar.duration_ms), 0),
+                ws.started_at,
+                ws.ended_at
+         FROM workflow_sessions ws
+         LEFT JOIN agent_runs ar ON ar.workflow_session_id = ws.session_id
+                                AND ar.reset_marker IS NULL
+         WHERE ws.reset_marker IS NULL{date_clause}{skill_clause}
+         GROUP BY ws.session_id{having_clause}
+         ORDER BY ws.started_at DESC
+         LIMIT {limit_param}"
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    macro_rules! collect_rows {
+        ($params:expr) => {
+            stmt.query_map($params, |row| {
+                Ok(WorkflowSessionRecord {
+                    session_id: row.get(0)?,
+                    skill_name: row.get(1)?,
+                    min_step: row.get(2)?,
+                    max_step: row.get(3)?,
+                    steps_csv: row.get(4)?,
+                    agent_count: row.get(5)?,
+                    total_cost: row.get(6)?,
+                    total_input_tokens: row.get(7)?,
+                    total_output_tokens: row.get(8)?,
+                    total_cache_read: row.get(9)?,
+                    total_cache_write: row.get(10)?,
+                    total_duration_ms: row.get(11)?,
+                    started_at: row.get(12)?,
+                    completed_at: row.get(13)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())
+        };
+    }
+    match (start_date, skill_name) {
+        (Some(sd), Some(sn)) => collect_rows!(rusqlite::params![sd, sn, limit as i64]),
+        (Some(sd), None) => collect_rows!(rusqlite::params![sd, limit as i64]),
+        (None, Some(sn)) => collect_rows!(rusqlite::params![sn, limit as i64]),
+        (None, None) => collect_rows!(rusqlite::params![limit as i64]),
+    }
+}
+
+pub fn get_session_agent_runs(
+    conn: &Connection,
+    session_id: &str,
+) -> Result<Vec<AgentRunRecord>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT agent_id, skill_name, step_id, model, status,
+                    COALESCE(input_tokens, 0), COALESCE(output_tokens, 0),
+                    COALESCE(cache_read_tokens, 0), COALESCE(cache_write_tokens, 0),
+                    COALESCE(total_cost, 0.0), COALESCE(duration_ms, 0),
+                    COALESCE(num_turns, 0), stop_reason, duration_api_ms,
+                    COALESCE(tool_use_count, 0), COALESCE(compaction_count, 0),
+                    session_id, started_at, completed_at, COALESCE(retry_count, 0)
+             FROM agent_runs
+             WHERE workflow_session_id = ?1
+             ORDER BY started_at ASC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(rusqlite::params![session_id], |row| {
+            Ok(AgentRunRecord {
+                agent_id: row.get(0)?,
+                skill_name: row.get(1)?,
+                step_id: row.get(2)?,
+                model: row.get(3)?,
+                status: row.get(4)?,
+                input_tokens: row.get(5)?,
+                output_tokens: row.get(6)?,
+                cache_read_tokens: row.get(7)?,
+                cache_write_tokens: row.get(8)?,
+                total_cost: row.get(9)?,
+                duration_ms: row.get(10)?,
+                num_turns: row.get(11)?,
+                stop_reason: row.get(12)?,
+                duration_api_ms: row.get(13)?,
+                tool_use_count: row.get(14)?,
+                compaction_count: row.get(15)?,
+                session_id: row.get(16)?,
+                started_at: row.get(17)?,
+                completed_at: row.get(18)?,
+                retry_count: row.get(19)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// Completed runs of a workflow step and their average cost and duration,
+/// for one skill or across all skills. Each agent run counts once; cost is
+/// summed across the models it used.
+pub fn get_step_history_stats(
+    conn: &Connection,
+    step_id: i32,
+    skill_name: Option<&str>,
+) -> Result<(u32, Option<f64>, Option<i64>), String> {
+    conn.query_row(
+        "SELECT COUNT(*), AVG(cost), CAST(AVG(duration) AS INTEGER)
+         FROM (
+             SELECT SUM(COALESCE(total_cost, 0.0)) AS cost, MAX(COALESCE(duration_ms, 0)) AS duration
+             FROM agent_runs
+             WHERE step_id = ?1 AND status = 'completed' AND (?2 IS NULL OR skill_name = ?2)
+             GROUP BY agent_id
+         )",
+        rusqlite::params![step_id, skill_name],
+        |row| Ok((row.get::<_, i64>(0)? as u32, row.get(1)?, row.get(2)?)),
+    )
+    .map_err(|e| e.to_string())
+}
+
+pub fn get_step_agent_runs(
+    conn: &Connection,
+    skill_name: &str,
+    step_id: i32,
+) -> Result<Vec<AgentRunRecord>, String> {
+    let wr_id = match get_workflow_run_id(conn, skill_name)? {
+        Some(id) => id,
+        None => return Ok(vec![]),
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT agent_id, skill_name, step_id, model, status,
+                    COALESCE(input_tokens, 0), COALESCE(output_tokens, 0),
+                    COALESCE(cache_read_tokens, 0), COALESCE(cache_write_tokens, 0),
+                    COALESCE(total_cost, 0.0), COALESCE(duration_ms, 0),
+                    COALESCE(num_turns, 0), stop_reason, duration_api_ms,
+                    COALESCE(tool_use_count, 0), COALESCE(compaction_count, 0),
+                    session_id, started_at, completed_at, COALESCE(retry_count, 0)
+             FROM agent_runs
+             WHERE workflow_run_id = ?1 AND step_id = ?2
+               AND status IN ('completed', 'error')
+               AND reset_marker IS NULL
+             ORDER BY completed_at DESC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(rusqlite::params![wr_id, step_id], |row| {
+            Ok(AgentRunRecord {
+                agent_id: row.get(0)?,
+                skill_name: row.get(1)?,
+                step_id: row.get(2)?,
+                model: row.get(3)?,
+                status: row.get(4)?,
+                input_tokens: row.get(5)?,
+                output_tokens: row.get(6)?,
+                cache_read_tokens: row.get(7)?,
+                cache_write_tokens: row.get(8)?,
+                total_cost: row.get(9)?,
+                duration_ms: row.get(10)?,
+                num_turns: row.get(11)?,
+                stop_reason: row.get(12)?,
+                duration_api_ms: row.get(13)?,
+                tool_use_count: row.get(14)?,
+                compaction_count: row.get(15)?,
+                session_id: row.get(16)?,
+                started_at: row.get(17)?,
+                completed_at: row.get(18)?,
+                retry_count: row.get(19)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+pub fn get_usage_by_step(
+    conn: &Connection,
+    hide_cancelled: bool,
+    start_date: Option<&str>,
+    skill_name: Option<&str>,
+) -> Result<Vec<UsageByStep>, String> {
+    let cost_clause = if hide_cancelled {
+        " AND (total_cost > 0 OR total_cost IS NULL)"
+    } else {
+        ""
+    };
+    let mut p = 1usize;
+    let date_clause = if start_date.is_some() {
+        let s = format!(" AND started_at >= ?{p}");
+        p += 1;
+        s
+    } else {
+        String::new()
+    };
+    let skill_clause = if skill_name.is_some() {
+        format!(" AND skill_name = ?{p}")
+    } else {
+        String::new()
+    };
+    let sql = format!(
+        "SELECT step_id, COALESCE(SUM(total_cost), 0.0), COUNT(*)
+         FROM agent_runs
+         WHERE reset_marker IS NULL
+           AND workflow_session_id IS NOT NULL{cost_clause}{date_clause}{skill_clause}
+         GROUP BY step_id
+         ORDER BY SUM(total_cost) DESC"
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    macro_rules! collect_rows {
+        ($params:expr) => {
+            stmt.query_map($params, |row| {
+                let sid: i32 = row.get(0)?;
+                Ok(UsageByStep {
+                    step_id: sid,
+                    step_name: step_name(sid),
+                    total_cost: row.get(1)?,
+                    run_count: row.get(2)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())
+        };
+    }
+    match (start_date, skill_name) {
+        (Some(sd), Some(sn)) => collect_rows!(rusqlite::params![sd, sn]),
+        (Some(sd), None) => collect_rows!(rusqlite::params![sd]),
+        (None, Some(sn)) => collect_rows!(rusqlite::params![sn]),
+        (None, None) => collect_rows!([]),
+    }
+}
+
+pub fn get_usage_by_model(
+    conn: &Connection,
+    hide_cancelled: bool,
+    start_date: Option<&str>,
+    skill_name: Option<&str>,
+) -> Result<Vec<UsageByModel>, String> {
+    let cost_clause = if hide_cancelled {
+        " AND (total_cost > 0 OR total_cost IS NULL)"
+    } else {
+        ""
+    };
+    let mut p = 1usize;
+    let date_clause = if start_date.is_some() {
+        let s = format!(" AND started_at >= ?{p}");
+        p += 1;
+        s
+    } else {
+        String::new()
+    };
+    let skill_clause = if skill_name.is_some() {
+        format!(" AND skill_name = ?{p}")
+    } else {
+        String::new()
+    };
+    let sql = format!(
+        "SELECT
+           CASE
+             WHEN lower(model) LIKE '%haiku%' THEN 'Haiku'
+             WHEN lower(model) LIKE '%opus%'  THEN 'Opus'
+             WHEN lower(model) LIKE '%sonnet%' THEN 'Sonnet'
+             ELSE model
+           END AS model_family,
+           COALESCE(SUM(total_cost), 0.0), COUNT(*)
+         FROM agent_runs
+         WHERE reset_marker IS NULL
+           AND workflow_session_id IS NOT NULL{cost_clause}{date_clause}{skill_clause}
+         GROUP BY model_family
+         ORDER BY SUM(total_cost) DESC"
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    macro_rules! collect_rows {
+        ($params:expr) => {
+            stmt.query_map($params, |row| {
+                Ok(UsageByModel {
+                    model: row.get(0)?,
+                    total_cost: row.get(1)?,
+                    run_count: row.get(2)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())
+        };
+    }
+    match (start_date, skill_name) {
+        (Some(sd), Some(sn)) => collect_rows!(rusqlite::params![sd, sn]),
+        (Some(sd), None) => collect_rows!(rusqlite::params![sd]),
+        (None, Some(sn)) => collect_rows!(rusqlite::params![sn]),
+        (None, None) => collect_rows!([]),
+    }
+}
+
+pub fn get_usage_by_day(
+    conn: &Connection,
+    hide_cancelled: bool,
+    start_date: Option<&str>,
+    skill_name: Option<&str>,
+) -> Result<Vec<crate::types::UsageByDay>, String> {
+    let mut p = 1usize;
+    let date_clause = if start_date.is_some() {
+        let s = format!(" AND ws.started_at >= ?{p}");
+        p += 1;
+        s
+    } else {
+        String::new()
+    };
+    let skill_clause = if skill_name.is_some() {
+        format!(" AND ws.skill_name = ?{p}")
+    } else {
+        String::new()
+    };
+    let having_clause = if hide_cancelled {
+        " HAVING COALESCE(SUM(ar.total_cost), 0) > 0 OR COUNT(ar.total_cost) < COUNT(ar.agent_id)"
+    } else {
+        ""
+    };
+    let sql = format!(
+        "SELECT DATE(ws.started_at),
+                COALESCE(SUM(ar.total_cost), 0.0),
+                COALESCE(SUM(ar.input_tokens + ar.output_tokens), 0),
+                COUNT(DISTINCT ws.session_id)
+         FROM workflow_sessions ws
+         LEFT JOIN agent_runs ar ON ar.workflow_session_id = ws.session_id
+                                AND ar.reset_marker IS NULL
+         WHERE ws.reset_marker IS NULL{date_clause}{skill_clause}
+         GROUP BY DATE(ws.started_at){having_clause}
+         ORDER BY DATE(ws.started_at) ASC"
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    macro_rules! collect_rows {
+        ($params:expr) => {
+            stmt.query_map($params, |row| {
+                Ok(crate::types::UsageByDay {
+                    date: row.get(0)?,
+                    total_cost: row.get(1)?,
+                    total_tokens: row.get(2)?,
+                    run_count: row.get(3)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())
+        };
+    }
+    match (start_date, skill_name) {
+        (Some(sd), Some(sn)) => collect_rows!(rusqlite::params![sd, sn]),
+        (Some(sd), None) => collect_rows!(rusqlite::params![sd]),
+        (None, Some(sn)) => collect_rows!(rusqlite::params![sn]),
+        (None, None) => collect_rows!([]),
+    }
+}
+
+/// Total `agent_runs` cost since the last usage reset, for one skill or all.
+pub fn spend_since_reset(conn: &Connection, skill_name: Option<&str>) -> Result<f64, String> {
+    conn.query_row(
+        "SELECT COALESCE(SUM(total_cost), 0.0) FROM agent_runs
+         WHERE reset_marker IS NULL AND (?1 IS NULL OR skill_name = ?1)",
+        rusqlite::params![skill_name],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Total `agent_runs` cost of runs started today (local time), including runs
+/// since cleared by a usage reset.
+pub fn spend_today(conn: &Connection) -> Result<f64, String> {
+    conn.query_row(
+        "SELECT COALESCE(SUM(total_cost), 0.0) FROM agent_runs
+         WHERE date(started_at, 'localtime') = date('now', 'localtime')",
+        [],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+pub fn reset_usage(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "UPDATE agent_runs SET reset_marker = datetime('now') || 'Z' WHERE reset_marker IS NULL",
+        [],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE workflow_sessions SET reset_marker = datetime('now') || 'Z' WHERE reset_marker IS NULL",
+        [],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn read_settings(conn: &Connection) -> Result<AppSettings, String> {
+    let mut stmt = conn
+        .prepare("SELECT value FROM settings WHERE key = ?1")
+        .map_err(|e| e.to_string())?;
+
+    let result: Result<String, _> = stmt.query_row(["app_settings"], |row| row.get(0));
+
+    match result {
+        Ok(json) => {
+            let mut settings: AppSettings = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+            crate::github::forget_token_for_other_server(&mut settings);
+            Ok(settings)
+        }
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(AppSettings::default()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Read settings (including secrets stored directly in SQLite).
+///
+/// Alias for `read_settings()` — kept for call-site compatibility.
+pub fn read_settings_hydrated(conn: &Connection) -> Result<AppSettings, String> {
+    read_settings(conn)
+}
+
+/// Store the settings, then audit and notify `settings_events` subscribers of
+/// the fields that changed.
+pub fn write_settings(conn: &Connection, settings: &AppSettings) -> Result<(), String> {
+    let previous = read_settings(conn).unwrap_or_default();
+    let json = serde_json::to_string(settings).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+        ["app_settings", &json],
+    )
+    .map_err(|e| e.to_string())?;
+    let changed = crate::settings_events::changed_fields(&previous, settings);
+    if !changed.is_empty() {
+        crate::audit::record(conn, crate::audit::SETTINGS_CHANGED, None, &changed.join(", "));
+    }
+    crate::settings_events::publish(&previous, settings);
+    Ok(())
+}
+
+// --- Team Taxonomy ---
+
+/// Settings-table key holding the last synced team taxonomy (JSON).
+const TEAM_TAXONOMY_KEY: &str = "team_taxonomy";
+
+pub fn read_team_taxonomy(conn: &Connection) -> Result<Option<TeamTaxonomy>, String> {
+    let result: Result<String, _> = conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1",
+        [TEAM_TAXONOMY_KEY],
+        |row| row.get(0),
+    );
+    match result {
+        Ok(json) => serde_json::from_str(&json).map(Some).map_err(|e| e.to_string()),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+pub fn write_team_taxonomy(conn: &Connection, taxonomy: &TeamTaxonomy) -> Result<(), String> {
+    let json = serde_json::to_string(taxonomy).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+        [TEAM_TAXONOMY_KEY, &json],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// --- Git Hosting Credentials ---
+
+/// Settings-table key holding GitLab/Bitbucket tokens (JSON). Kept out of
+/// `app_settings` so a settings save from the frontend cannot drop them.
+const GIT_HOST_CREDENTIALS_KEY: &str = "git_host_credentials";
+
+pub fn read_git_host_credentials(conn: &Connection) -> Result<Vec<GitHostCredential>, String> {
+    let result: Result<String, _> = conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1",
+        [GIT_HOST_CREDENTIALS_KEY],
+        |row| row.get(0),
+    );
+    match result {
+        Ok(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(Vec::new()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+pub fn write_git_host_credentials(
+    conn: &Connection,
+    credentials: &[GitHostCredential],
+) -> Result<(), String> {
+    let json = serde_json::to_string(credentials).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+        [GIT_HOST_CREDENTIALS_KEY, &json],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// --- Marketplace Catalog Cache ---
+
+pub fn get_cached_catalog(conn: &Connection, cache_key: &str) -> Result<Option<CachedCatalog>, String> {
+    let row = conn
+        .query_row(
+            "SELECT tree_sha, blob_shas_json, registry_name, skills_json, fetched_at
+             FROM marketplace_catalog_cache WHERE cache_key = ?1",
+            [cache_key],
+            |row| {
+                Ok((
+                    row.get::<_, Option<String>>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                ))
+            },
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    let Some((tree_sha, blob_shas_json, registry_name, skills_json, fetched_at)) = row else {
+        return Ok(None);
+    };
+    let blob_shas = serde_json::from_str(&blob_shas_json).map_err(|e| e.to_string())?;
+    let skills = serde_json::from_str(&skills_json).map_err(|e| e.to_string())?;
+    Ok(Some(CachedCatalog {
+        cache_key: cache_key.to_string(),
+        tree_sha,
+        blob_shas,
+        registry_name,
+        skills,
+        fetched_at,
+    }))
+}
+
+pub fn save_cached_catalog(conn: &Connection, catalog: &CachedCatalog) -> Result<(), String> {
+    let blob_shas_json = serde_json::to_string(&catalog.blob_shas).map_err(|e| e.to_string())?;
+    let skills_json = serde_json::to_string(&catalog.skills).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO marketplace_catalog_cache
+             (cache_key, tree_sha, blob_shas_json, registry_name, skills_json, fetched_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![
+            catalog.cache_key,
+            catalog.tree_sha,
+            blob_shas_json,
+            catalog.registry_name,
+            skills_json,
+            catalog.fetched_at
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Every cached listing, for lookups across registries (no network).
+pub fn list_cached_catalogs(conn: &Connection) -> Result<Vec<CachedCatalog>, String> {
+    let mut stmt = conn
+        .prepare("SELECT cache_key FROM marketplace_catalog_cache ORDER BY cache_key")
+        .map_err(|e| e.to_string())?;
+    let keys = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    let mut catalogs = Vec::with_capacity(keys.len());
+    for key in keys {
+        if let Some(catalog) = get_cached_catalog(conn, &key)? {
+            catalogs.push(catalog);
+        }
+    }
+    Ok(catalogs)
+}
+
+/// Bump `fetched_at` after a revalidation found the tree unchanged, without rewriting the listing.
+pub fn touch_cached_catalog(conn: &Connection, cache_key: &str, fetched_at: &str) -> Result<(), String> {
+    conn.execute(
+        "UPDATE marketplace_catalog_cache SET fetched_at = ?2 WHERE cache_key = ?1",
+        [cache_key, fetched_at],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// --- Package Signing ---
+
+/// Settings-table key holding this installation's Ed25519 key pair (base64 PKCS#8).
+const SIGNING_KEYPAIR_KEY: &str = "signing_keypair";
+
+pub fn read_signing_keypair(conn: &Connection) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1",
+        [SIGNING_KEYPAIR_KEY],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+pub fn write_signing_keypair(conn: &Connection, pkcs8_b64: &str) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+        [SIGNING_KEYPAIR_KEY, pkcs8_b64],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn list_trusted_keys(conn: &Connection) -> Result<Vec<TrustedKey>, String> {
+    let mut stmt = conn
+        .prepare("SELECT key_id, name, public_key, added_at FROM trusted_signing_keys ORDER BY name")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(TrustedKey {
+                key_id: row.get(0)?,
+                name: row.get(1)?,
+                public_key: row.get(2)?,
+                added_at: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+pub fn get_trusted_key(conn: &Connection, key_id: &str) -> Result<Option<TrustedKey>, String> {
+    conn.query_row(
+        "SELECT key_id, name, public_key, added_at FROM trusted_signing_keys WHERE key_id = ?1",
+        [key_id],
+        |row| {
+            Ok(TrustedKey {
+                key_id: row.get(0)?,
+                name: row.get(1)?,
+                public_key: row.get(2)?,
+                added_at: row.get(3)?,
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+pub fn upsert_trusted_key(conn: &Connection, key_id: &str, name: &str, public_key: &str) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO trusted_signing_keys (key_id, name, public_key) VALUES (?1, ?2, ?3)
+         ON CONFLICT(key_id) DO UPDATE SET name = ?2",
+        [key_id, name, public_key],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn delete_trusted_key(conn: &Connection, key_id: &str) -> Result<bool, String> {
+    let deleted = conn
+        .execute("DELETE FROM trusted_signing_keys WHERE key_id = ?1", [key_id])
+        .map_err(|e| e.to_string())?;
+    Ok(deleted > 0)
+}
+
+// --- Skill Parameter Sets ---
+
+pub fn get_skill_parameter_set(
+    conn: &Connection,
+    skill_name: &str,
+) -> Result<Option<SkillParameterSet>, String> {
+    let row: Option<(Option<String>, String, String)> = conn
+        .query_row(
+            "SELECT source_name, values_json, updated_at FROM skill_parameter_sets WHERE skill_name = ?1",
+            [skill_name],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    match row {
+        Some((source_name, values_json, updated_at)) => Ok(Some(SkillParameterSet {
+            skill_name: skill_name.to_string(),
+            source_name,
+            values: serde_json::from_str(&values_json).map_err(|e| e.to_string())?,
+            updated_at,
+        })),
+        None => Ok(None),
+    }
+}
+
+pub fn save_skill_parameter_set(
+    conn: &Connection,
+    skill_name: &str,
+    source_name: Option<&str>,
+    values: &std::collections::BTreeMap<String, String>,
+) -> Result<(), String> {
+    let values_json = serde_json::to_string(values).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO skill_parameter_sets (skill_name, source_name, values_json, updated_at)
+         VALUES (?1, ?2, ?3, datetime('now') || 'Z')",
+        rusqlite::params![skill_name, source_name, values_json],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn delete_skill_parameter_set(conn: &Connection, skill_name: &str) -> Result<(), String> {
+    conn.execute("DELETE FROM skill_parameter_sets WHERE skill_name = ?1", [skill_name])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// --- Command History ---
+
+/// Entries kept in `command_history`; older rows are pruned on insert.
+const COMMAND_HISTORY_LIMIT: i64 = 5000;
+
+pub fn insert_command_history(
+    conn: &Connection,
+    command: &str,
+    params_json: &str,
+    redacted: bool,
+    outcome: &str,
+    error: Option<&str>,
+    duration_ms: u64,
+) -> Result<i64, String> {
+    conn.execute(
+        "INSERT INTO command_history (command, params_json, redacted, outcome, error, duration_ms)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![command, params_json, redacted as i64, outcome, error, duration_ms as i64],
+    )
+    .map_err(|e| e.to_string())?;
+    let id = conn.last_insert_rowid();
+    conn.execute(
+        "DELETE FROM command_history WHERE id <= ?1",
+        [id - COMMAND_HISTORY_LIMIT],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(id)
+}
+
+fn row_to_command_history(row: &rusqlite::Row) -> rusqlite::Result<CommandHistoryEntry> {
+    let params_json: String = row.get(2)?;
+    let redacted: i64 = row.get(3)?;
+    Ok(CommandHistoryEntry {
+        id: row.get(0)?,
+        command: row.get(1)?,
+        params: serde_json::from_str(&params_json).unwrap_or(serde_json::Value::Null),
+        redacted: redacted != 0,
+        outcome: row.get(4)?,
+        error: row.get(5)?,
+        duration_ms: row.get::<_, i64>(6)? as u64,
+        created_at: row.get(7)?,
+    })
+}
+
+/// Newest first. `query` matches the command name or any parameter text.
+pub fn list_command_history(
+    conn: &Connection,
+    filter: &CommandHistoryFilter,
+) -> Result<Vec<CommandHistoryEntry>, String> {
+    let query = filter
+        .query
+        .as_deref()
+        .map(str::trim)
+        .filter(|q| !q.is_empty())
+        .map(|q| format!("%{}%", q.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")));
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, command, params_json, redacted, outcome, error, duration_ms, created_at
+             FROM command_history
+             WHERE (?1 IS NULL OR command = ?1)
+               AND (?2 IS NULL OR outcome = ?2)
+               AND (?3 IS NULL OR created_at >= ?3)
+               AND (?4 IS NULL OR command LIKE ?4 ESCAPE '\\' OR params_json LIKE ?4 ESCAPE '\\')
+             ORDER BY id DESC
+             LIMIT ?5",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(
+            rusqlite::params![
+                filter.command,
+                filter.outcome,
+                filter.since,
+                query,
+                filter.limit.unwrap_or(200).min(1000) as i64
+            ],
+            row_to_command_history,
+        )
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(rows)
+}
+
+pub fn get_command_history_entry(conn: &Connection, id: i64) -> Result<Option<CommandHistoryEntry>, String> {
+    conn.query_row(
+        "SELECT id, command, params_json, redacted, outcome, error, duration_ms, created_at
+         FROM command_history WHERE id = ?1",
+        [id],
+        row_to_command_history,
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+// --- Audit Log ---
+
+pub fn insert_audit_entry(
+    conn: &Connection,
+    instance_id: &str,
+    actor: Option<&str>,
+    action: &str,
+    skill_name: Option<&str>,
+    summary: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO audit_log (instance_id, actor, action, skill_name, summary)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![instance_id, actor, action, skill_name, summary],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Audit entries matching `filter`, newest first. `limit` is applied as given;
+/// `None` returns every match.
+pub fn list_audit_log(
+    conn: &Connection,
+    filter: &AuditLogFilter,
+    limit: Option<u32>,
+) -> Result<Vec<AuditLogEntry>, String> {
+    let query = filter
+        .query
+        .as_deref()
+        .map(str::trim)
+        .filter(|q| !q.is_empty())
+        .map(|q| format!("%{}%", q.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")));
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, created_at, instance_id, actor, action, skill_name, summary
+             FROM audit_log
+             WHERE (?1 IS NULL OR action = ?1)
+               AND (?2 IS NULL OR skill_name = ?2)
+               AND (?3 IS NULL OR instance_id = ?3)
+               AND (?4 IS NULL OR actor = ?4)
+               AND (?5 IS NULL OR summary LIKE ?5 ESCAPE '\\')
+               AND (?6 IS NULL OR created_at >= ?6)
+               AND (?7 IS NULL OR created_at <= ?7)
+             ORDER BY id DESC
+             LIMIT ?8",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(
+            rusqlite::params![
+                filter.action,
+                filter.skill_name,
+                filter.instance_id,
+                filter.actor,
+                query,
+                filter.since,
+                filter.until,
+                limit.map_or(-1, i64::from)
+            ],
+            |row| {
+                Ok(AuditLogEntry {
+                    id: row.get(0)?,
+                    created_at: row.get(1)?,
+                    instance_id: row.get(2)?,
+                    actor: row.get(3)?,
+                    action: row.get(4)?,
+                    skill_name: row.get(5)?,
+                    summary: row.get(6)?,
+                })
+            },
+        )
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(rows)
+}
+
+// --- Data Retention ---
+
+/// Usage history wiped by `purge_user_data`. None of it is needed to rebuild
+/// or publish a skill. Refine conversations, test responses, benchmark
+/// results and hook payloads carry prompt and skill text, so they go too;
+/// test cases and hook definitions are kept.
+const USAGE_HISTORY_TABLES: &[&str] = &[
+    "agent_runs",
+    "agent_run_ticks",
+    "agent_tool_calls",
+    "agent_run_checkpoints",
+    "step_perf_history",
+    "command_history",
+    "skill_invocations",
+    "refine_sessions",
+    "skill_test_results",
+    "skill_benchmarks",
+    "automation_hook_deliveries",
+];
+
+/// Delete audit entries older than `age` (an SQLite datetime modifier such
+/// as `-90 days`). The delete trigger is dropped and recreated around it;
+/// call inside a transaction so the log is never left unprotected.
+fn delete_audit_entries(conn: &Connection, age: &str) -> Result<usize, String> {
+    conn.execute_batch("DROP TRIGGER IF EXISTS audit_log_no_delete;")
+        .map_err(|e| e.to_string())?;
+    let deleted = conn
+        .execute("DELETE FROM audit_log WHERE created_at < datetime('now', ?1)", [age])
+        .map_err(|e| e.to_string())?;
+    conn.execute_batch(AUDIT_LOG_NO_DELETE_TRIGGER).map_err(|e| e.to_string())?;
+    Ok(deleted)
+}
+
+/// Delete rows older than `policy` allows; categories set to 0 days are kept.
+/// Running agents and open workflow sessions are kept whatever their age.
+pub fn prune_expired_data(conn: &Connection, policy: &RetentionPolicy) -> Result<RetentionPruneResult, String> {
+    let age = |days: u32| format!("-{} days", days);
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let mut result = RetentionPruneResult::default();
+    if policy.agent_runs_days > 0 {
+        let age = age(policy.agent_runs_days);
+        result.agent_runs = tx
+            .execute(
+                "DELETE FROM agent_runs WHERE status != 'running' AND started_at < datetime('now', ?1)",
+                [&age],
+            )
+            .map_err(|e| e.to_string())?;
+        tx.execute("DELETE FROM agent_tool_calls WHERE created_at < datetime('now', ?1)", [&age])
+            .map_err(|e| e.to_string())?;
+    }
+    if policy.workflow_sessions_days > 0 {
+        result.workflow_sessions = tx
+            .execute(
+                "DELETE FROM workflow_sessions WHERE ended_at IS NOT NULL AND started_at < datetime('now', ?1)",
+                [age(policy.workflow_sessions_days)],
+            )
+            .map_err(|e| e.to_string())?;
+    }
+    if policy.transcripts_days > 0 {
+        result.transcripts = tx
+            .execute(
+                "DELETE FROM agent_transcripts WHERE archived_at < datetime('now', ?1)",
+                [age(policy.transcripts_days)],
+            )
+            .map_err(|e| e.to_string())?;
+    }
+    if policy.audit_log_days > 0 {
+        result.audit_entries = delete_audit_entries(&tx, &age(policy.audit_log_days))?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+/// Delete all usage history and archived transcripts. Skills, their workflow
+/// state, settings and the audit log (which has its own retention) are left
+/// alone. Open workflow sessions are kept: they back the skill locks of
+/// running instances.
+pub fn purge_user_data(conn: &Connection) -> Result<UserDataPurgeResult, String> {
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let mut result = UserDataPurgeResult::default();
+    for table in USAGE_HISTORY_TABLES {
+        result.usage_rows += tx
+            .execute(&format!("DELETE FROM {}", table), [])
+            .map_err(|e| e.to_string())?;
+    }
+    result.usage_rows += tx
+        .execute("DELETE FROM workflow_sessions WHERE ended_at IS NOT NULL", [])
+        .map_err(|e| e.to_string())?;
+    result.transcripts = tx
+        .execute("DELETE FROM agent_transcripts", [])
+        .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+// --- Skill Benchmarks ---
+
+/// Store a benchmark run; `id` and `created_at` on the input are ignored. Returns the new id.
+pub fn insert_skill_benchmark(conn: &Connection, benchmark: &SkillBenchmark) -> Result<i64, String> {
+    let results_json = serde_json::to_string(&benchmark.results).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO skill_benchmarks (skill_name, skill_version, scenario_set, model, judge_model,
+             skill_wins, baseline_wins, ties, avg_skill_score, avg_baseline_score,
+             token_overhead_pct, latency_overhead_pct, results_json)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        rusqlite::params![
+            benchmark.skill_name,
+            benchmark.skill_version,
+            benchmark.scenario_set,
+            benchmark.model,
+            benchmark.judge_model,
+            benchmark.skill_wins,
+            benchmark.baseline_wins,
+            benchmark.ties,
+            benchmark.avg_skill_score,
+            benchmark.avg_baseline_score,
+            benchmark.token_overhead_pct,
+            benchmark.latency_overhead_pct,
+            results_json,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Benchmark runs for a skill, newest first.
+pub fn list_skill_benchmarks(conn: &Connection, skill_name: &str) -> Result<Vec<SkillBenchmark>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, skill_name, skill_version, scenario_set, model, judge_model,
+                    skill_wins, baseline_wins, ties, avg_skill_score, avg_baseline_score,
+                    token_overhead_pct, latency_overhead_pct, results_json, created_at
+             FROM skill_benchmarks WHERE skill_name = ?1
+             ORDER BY id DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([skill_name], |row| {
+            let results_json: String = row.get(13)?;
+            Ok(SkillBenchmark {
+                id: row.get(0)?,
+                skill_name: row.get(1)?,
+                skill_version: row.get(2)?,
+                scenario_set: row.get(3)?,
+                model: row.get(4)?,
+                judge_model: row.get(5)?,
+                skill_wins: row.get(6)?,
+                baseline_wins: row.get(7)?,
+                ties: row.get(8)?,
+                avg_skill_score: row.get(9)?,
+                avg_baseline_score: row.get(10)?,
+                token_overhead_pct: row.get(11)?,
+                latency_overhead_pct: row.get(12)?,
+                results: serde_json::from_str::<Vec<BenchmarkScenarioResult>>(&results_json).unwrap_or_default(),
+                created_at: row.get(14)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(rows)
+}
+
+// --- Linked References ---
+
+fn row_to_linked_reference(row: &rusqlite::Row) -> rusqlite::Result<LinkedReference> {
+    Ok(LinkedReference {
+        skill_name: row.get(0)?,
+        reference_path: row.get(1)?,
+        repo: row.get(2)?,
+        git_ref: row.get(3)?,
+        source_path: row.get(4)?,
+        synced_sha: row.get(5)?,
+        synced_at: row.get(6)?,
+        upstream_sha: row.get(7)?,
+        checked_at: row.get(8)?,
+        drift: String::new(),
+    })
+}
+
+/// Create or replace a link after its content was written to disk at `synced_sha`.
+pub fn upsert_linked_reference(conn: &Connection, link: &LinkedReference) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO skill_linked_references
+             (skill_name, reference_path, repo, git_ref, source_path, synced_sha, synced_at, upstream_sha, checked_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        rusqlite::params![
+            link.skill_name,
+            link.reference_path,
+            link.repo,
+            link.git_ref,
+            link.source_path,
+            link.synced_sha,
+            link.synced_at,
+            link.upstream_sha,
+            link.checked_at,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Links for a skill ordered by path. `drift` is left empty for the caller to fill in.
+pub fn list_linked_references(conn: &Connection, skill_name: &str) -> Result<Vec<LinkedReference>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT skill_name, reference_path, repo, git_ref, source_path, synced_sha, synced_at, upstream_sha, checked_at
+             FROM skill_linked_references WHERE skill_name = ?1
+             ORDER BY reference_path",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([skill_name], row_to_linked_reference)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(rows)
+}
+
+/// Record what an upstream check found (`None` when the file is gone).
+pub fn record_linked_reference_check(
+    conn: &Connection,
+    skill_name: &str,
+    reference_path: &str,
+    upstream_sha: Option<&str>,
+    checked_at: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "UPDATE skill_linked_references SET upstream_sha = ?3, checked_at = ?4
+         WHERE skill_name = ?1 AND reference_path = ?2",
+        rusqlite::params![skill_name, reference_path, upstream_sha, checked_at],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn mark_linked_reference_synced(
+    conn: &Connection,
+    skill_name: &str,
+    reference_path: &str,
+    synced_sha: &str,
+    synced_at: &str,
+) -> Result<(), String> {
+    let changed = conn
+        .execute(
+            "UPDATE skill_linked_references SET synced_sha = ?3, synced_at = ?4, upstream_sha = ?3, checked_at = ?4
+             WHERE skill_name = ?1 AND reference_path = ?2",
+            rusqlite::params![skill_name, reference_path, synced_sha, synced_at],
+        )
+        .map_err(|e| e.to_string())?;
+    if changed == 0 {
+        return Err(format!("'{}' is not a linked reference of '{}'", reference_path, skill_name));
+    }
+    Ok(())
+}
+
+pub fn delete_linked_reference(conn: &Connection, skill_name: &str, reference_path: &str) -> Result<bool, String> {
+    let changed = conn
+        .execute(
+            "DELETE FROM skill_linked_references WHERE skill_name = ?1 AND reference_path = ?2",
+            rusqlite::params![skill_name, reference_path],
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(changed > 0)
+}
+
+// --- Step Performance ---
+
+pub fn record_step_perf(conn: &Connection, sample: &StepPerfSample) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO step_perf_history
+             (agent_id, model, skill_name, step_id, app_version, prompt_version, prompt_tokens,
+              output_tokens, total_cost, time_to_first_token_ms, duration_ms)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        rusqlite::params![
+            sample.agent_id,
+            sample.model,
+            sample.skill_name,
+            sample.step_id,
+            sample.app_version,
+            sample.prompt_version,
+            sample.prompt_tokens,
+            sample.output_tokens,
+            sample.total_cost,
+            sample.time_to_first_token_ms,
+            sample.duration_ms,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Usage rows of the `limit` most recently completed runs of a workflow step
+/// across all skills, one row per (agent run, model).
+pub fn get_step_run_usage(conn: &Connection, step_id: i32, limit: usize) -> Result<Vec<StepRunUsage>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT agent_id, model, COALESCE(input_tokens, 0), COALESCE(output_tokens, 0),
+                    COALESCE(cache_read_tokens, 0), COALESCE(cache_write_tokens, 0),
+                    COALESCE(total_cost, 0.0)
+             FROM agent_runs
+             WHERE agent_id IN (
+                 SELECT agent_id FROM agent_runs
+                 WHERE step_id = ?1 AND status = 'completed' AND reset_marker IS NULL
+                 GROUP BY agent_id
+                 ORDER BY MAX(completed_at) DESC
+                 LIMIT ?2
+             )
+             ORDER BY agent_id, model",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![step_id, limit as i64], |row| {
+            Ok(StepRunUsage {
+                agent_id: row.get(0)?,
+                model: row.get(1)?,
+                input_tokens: row.get(2)?,
+                output_tokens: row.get(3)?,
+                cache_read_tokens: row.get(4)?,
+                cache_write_tokens: row.get(5)?,
+                total_cost: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+/// Averages per (step, app version, prompt version), oldest combination first
+/// within each step. Rows for sub-agent models are summed into their agent run
+/// first. Change percentages are left for the caller.
+pub fn get_step_perf_trends(
+    conn: &Connection,
+    step_id: Option<i32>,
+    start_date: Option<&str>,
+) -> Result<Vec<StepPerfTrend>, String> {
+    let mut stmt = conn
+        .prepare(
+            "WITH runs AS (
+                 SELECT agent_id, step_id, app_version, prompt_version,
+                        SUM(prompt_tokens) AS prompt_tokens, SUM(output_tokens) AS output_tokens,
+                        SUM(total_cost) AS total_cost, MIN(time_to_first_token_ms) AS ttft,
+                        MAX(duration_ms) AS duration_ms, MIN(recorded_at) AS recorded_at
+                 FROM step_perf_history
+                 WHERE (?1 IS NULL OR step_id = ?1) AND (?2 IS NULL OR recorded_at >= ?2)
+                 GROUP BY agent_id
+             )
+             SELECT step_id, app_version, prompt_version, COUNT(*),
+                    AVG(prompt_tokens), AVG(output_tokens), AVG(total_cost), AVG(ttft), AVG(duration_ms),
+                    MIN(recorded_at), MAX(recorded_at)
+             FROM runs
+             GROUP BY step_id, app_version, prompt_version
+             ORDER BY step_id, MIN(recorded_at)",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![step_id, start_date], |row| {
+            Ok(StepPerfTrend {
+                step_id: row.get(0)?,
+                app_version: row.get(1)?,
+                prompt_version: row.get(2)?,
+                runs: row.get(3)?,
+                avg_prompt_tokens: row.get(4)?,
+                avg_output_tokens: row.get(5)?,
+                avg_cost: row.get(6)?,
+                avg_time_to_first_token_ms: row.get(7)?,
+                avg_duration_ms: row.get(8)?,
+                first_seen: row.get(9)?,
+                last_seen: row.get(10)?,
+                prompt_tokens_change_pct: None,
+                cost_change_pct: None,
+                time_to_first_token_change_pct: None,
+                duration_change_pct: None,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(rows)
+}
+
+// --- Tool Policies ---
+
+/// Scope under which the global tool policy is stored.
+pub const GLOBAL_TOOL_POLICY_SCOPE: &str = "*";
+
+pub fn get_tool_policy(conn: &Connection, scope: &str) -> Result<Option<ToolPolicy>, String> {
+    let json: Option<String> = conn
+        .query_row(
+            "SELECT policy_json FROM tool_policies WHERE scope = ?1",
+            [scope],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    match json {
+        Some(j) => serde_json::from_str(&j)
+            .map(Some)
+            .map_err(|e| format!("Corrupt tool policy for '{}': {}", scope, e)),
+        None => Ok(None),
+    }
+}
+
+/// Store `policy` for `scope`, or remove it when `None`.
+pub fn set_tool_policy(conn: &Connection, scope: &str, policy: Option<&ToolPolicy>) -> Result<(), String> {
+    match policy {
+        Some(p) => {
+            let json = serde_json::to_string(p).map_err(|e| e.to_string())?;
+            conn.execute(
+                "INSERT INTO tool_policies (scope, policy_json, updated_at)
+                 VALUES (?1, ?2, datetime('now') || 'Z')
+                 ON CONFLICT(scope) DO UPDATE SET policy_json = ?2, updated_at = datetime('now') || 'Z'",
+                rusqlite::params![scope, json],
+            )
+        }
+        None => conn.execute("DELETE FROM tool_policies WHERE scope = ?1", [scope]),
+    }
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// --- Agent Tool Calls ---
+
+/// Record one tool call. Repeats of a `tool_use_id` for the same agent (the
+/// SDK can resend a message) are ignored.
+#[allow(clippy::too_many_arguments)]
+pub fn insert_agent_tool_call(
+    conn: &Connection,
+    agent_id: &str,
+    skill_name: &str,
+    step_id: i32,
+    tool_use_id: &str,
+    tool_name: &str,
+    input_summary: &str,
+    allowed: Option<bool>,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR IGNORE INTO agent_tool_calls
+             (agent_id, skill_name, step_id, tool_use_id, tool_name, input_summary, allowed)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![agent_id, skill_name, step_id, tool_use_id, tool_name, input_summary, allowed],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Tool calls of one agent run, in call order.
+pub fn list_agent_tool_calls(conn: &Connection, agent_id: &str) -> Result<Vec<AgentToolCall>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, agent_id, skill_name, step_id, tool_use_id, tool_name, input_summary,
+                    allowed, created_at
+             FROM agent_tool_calls WHERE agent_id = ?1 ORDER BY id",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([agent_id], |row| {
+            Ok(AgentToolCall {
+                id: row.get(0)?,
+                agent_id: row.get(1)?,
+                skill_name: row.get(2)?,
+                step_id: row.get(3)?,
+                tool_use_id: row.get(4)?,
+                tool_name: row.get(5)?,
+                input_summary: row.get(6)?,
+                allowed: row.get(7)?,
+                created_at: row.get(8)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+// --- Activation Decisions ---
+
+pub fn record_activation_decision(
+    conn: &Connection,
+    skill_name: &str,
+    is_active: bool,
+    matched_rule: &str,
+    reason: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO activation_decisions (skill_name, is_active, matched_rule, reason)
+         VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![skill_name, is_active as i64, matched_rule, reason],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Mark the skill's latest decision as overridden when the user sets the
+/// opposite state. Returns true if a decision was marked.
+pub fn mark_activation_decision_overridden(
+    conn: &Connection,
+    skill_name: &str,
+    is_active: bool,
+) -> Result<bool, String> {
+    let rows = conn
+        .execute(
+            "UPDATE activation_decisions SET overridden_at = datetime('now') || 'Z'
+             WHERE id = (SELECT MAX(id) FROM activation_decisions WHERE skill_name = ?1)
+               AND is_active != ?2 AND overridden_at IS NULL",
+            rusqlite::params![skill_name, is_active as i64],
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(rows > 0)
+}
+
+/// Decisions newest first, optionally for one skill.
+pub fn list_activation_decisions(
+    conn: &Connection,
+    skill_name: Option<&str>,
+) -> Result<Vec<ActivationDecision>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, skill_name, is_active, matched_rule, reason, overridden_at, created_at
+             FROM activation_decisions
+             WHERE ?1 IS NULL OR skill_name = ?1
+             ORDER BY id DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([skill_name], |row| {
+            let is_active: i64 = row.get(2)?;
+            Ok(ActivationDecision {
+                id: row.get(0)?,
+                skill_name: row.get(1)?,
+                is_active: is_active != 0,
+                matched_rule: row.get(3)?,
+                reason: row.get(4)?,
+                overridden_at: row.get(5)?,
+                created_at: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(rows)
+}
+
+// --- Skill Scan Reports ---
+
+/// Replace the skill's scan report with `report`.
+pub fn save_skill_scan_report(conn: &Connection, report: &SkillScanReport) -> Result<(), String> {
+    let report_json = serde_json::to_string(report).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO skill_scan_reports (skill_name, flagged, report_json, scanned_at)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(skill_name) DO UPDATE SET
+            flagged = excluded.flagged,
+            report_json = excluded.report_json,
+            scanned_at = excluded.scanned_at",
+        rusqlite::params![report.skill_name, report.flagged as i64, report_json, report.scanned_at],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn get_skill_scan_report(conn: &Connection, skill_name: &str) -> Result<Option<SkillScanReport>, String> {
+    let report_json: Option<String> = conn
+        .query_row(
+            "SELECT report_json FROM skill_scan_reports WHERE skill_name = ?1",
+            [skill_name],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    report_json
+        .map(|json| serde_json::from_str(&json).map_err(|e| format!("Corrupt scan report for '{}': {}", skill_name, e)))
+        .transpose()
+}
+
+// --- Agent Transcripts ---
+
+/// Next value of `access_seq`, which orders transcripts from least to most
+/// recently written or read.
+const NEXT_TRANSCRIPT_ACCESS: &str = "(SELECT COALESCE(MAX(access_seq), 0) + 1 FROM agent_transcripts)";
+
+/// Archive a finished run's transcript, replacing an earlier archive of the same agent.
+pub fn save_agent_transcript(
+    conn: &Connection,
+    agent_id: &str,
+    skill_name: &str,
+    transcript: &crate::transcripts::ArchivedTranscript,
+) -> Result<(), String> {
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO agent_transcripts
+             (agent_id, skill_name, content, uncompressed_bytes, compressed_bytes, message_count, tool_call_count, access_seq)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, {})",
+            NEXT_TRANSCRIPT_ACCESS
+        ),
+        rusqlite::params![
+            agent_id,
+            skill_name,
+            transcript.content,
+            transcript.uncompressed_bytes as i64,
+            transcript.content.len() as i64,
+            transcript.message_count as i64,
+            transcript.tool_call_count as i64,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Delete the least recently used transcripts until the archive's compressed
+/// size is at most `max_bytes`. Returns how many were evicted.
+pub fn evict_agent_transcripts(conn: &Connection, max_bytes: u64) -> Result<usize, String> {
+    let mut stmt = conn
+        .prepare("SELECT agent_id, compressed_bytes FROM agent_transcripts ORDER BY access_seq DESC")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    let mut total = 0u64;
+    let mut evicted = 0;
+    for (agent_id, bytes) in rows {
+        total += bytes as u64;
+        if total > max_bytes {
+            conn.execute("DELETE FROM agent_transcripts WHERE agent_id = ?1", [&agent_id])
+                .map_err(|e| e.to_string())?;
+            evicted += 1;
+        }
+    }
+    Ok(evicted)
+}
+
+/// An archived transcript; reading it marks it most recently used.
+pub fn get_agent_transcript(conn: &Connection, agent_id: &str) -> Result<Option<AgentTranscript>, String> {
+    let row = conn
+        .query_row(
+            "SELECT skill_name, content, uncompressed_bytes, compressed_bytes, message_count, tool_call_count, archived_at
+             FROM agent_transcripts WHERE agent_id = ?1",
+            [agent_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Vec<u8>>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, i64>(4)?,
+                    row.get::<_, i64>(5)?,
+                    row.get::<_, String>(6)?,
+                ))
+            },
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    let Some((skill_name, content, uncompressed_bytes, compressed_bytes, message_count, tool_call_count, archived_at)) =
+        row
+    else {
+        return Ok(None);
     };
-    let having_clause = if hide_cancelled {
-        " HAVING COALESCE(SUM(ar.total_cost), 0) > 0 OR COUNT(DISTINCT ar.agent_id) = 0"
-    } else {
-        ""
+    conn.execute(
+        &format!(
+            "UPDATE agent_transcripts SET access_seq = {} WHERE agent_id = ?1",
+            NEXT_TRANSCRIPT_ACCESS
+        ),
+        [agent_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(Some(AgentTranscript {
+        agent_id: agent_id.to_string(),
+        skill_name,
+        message_count: message_count as usize,
+        tool_call_count: tool_call_count as usize,
+        uncompressed_bytes: uncompressed_bytes as u64,
+        compressed_bytes: compressed_bytes as u64,
+        archived_at,
+        messages: crate::transcripts::unpack_archive(&content)?,
+    }))
+}
+
+/// Call `f(agent_id, skill_name, archived_at, content)` for archived
+/// transcripts, newest first, optionally of one skill, until it returns false.
+pub fn for_each_agent_transcript(
+    conn: &Connection,
+    skill_name: Option<&str>,
+    mut f: impl FnMut(&str, &str, &str, &[u8]) -> bool,
+) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT agent_id, skill_name, archived_at, content FROM agent_transcripts
+             WHERE ?1 IS NULL OR skill_name = ?1
+             ORDER BY archived_at DESC, rowid DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    let mut rows = stmt.query([skill_name]).map_err(|e| e.to_string())?;
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let agent_id: String = row.get(0).map_err(|e| e.to_string())?;
+        let skill: String = row.get(1).map_err(|e| e.to_string())?;
+        let archived_at: String = row.get(2).map_err(|e| e.to_string())?;
+        let content: Vec<u8> = row.get(3).map_err(|e| e.to_string())?;
+        if !f(&agent_id, &skill, &archived_at, &content) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+// --- Skill Library Events ---
+
+pub const LIBRARY_SOURCE_WORKSPACE: &str = "workspace";
+pub const LIBRARY_SOURCE_MARKETPLACE: &str = "marketplace";
+
+/// Append a library audit event. Skipped when it would repeat the skill's latest
+/// event exactly, so startup re-seeding and idempotent upserts do not pile up rows.
+pub fn record_library_event(
+    conn: &Connection,
+    skill_name: &str,
+    source: &str,
+    event_type: &str,
+    version: Option<&str>,
+    is_active: bool,
+) -> Result<(), String> {
+    let latest: Option<(String, Option<String>, i64)> = conn
+        .query_row(
+            "SELECT event_type, version, is_active FROM skill_library_events
+             WHERE skill_name = ?1 AND source = ?2 ORDER BY id DESC LIMIT 1",
+            rusqlite::params![skill_name, source],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    if let Some((last_type, last_version, last_active)) = latest {
+        if last_type == event_type && last_version.as_deref() == version && (last_active != 0) == is_active {
+            return Ok(());
+        }
+    }
+    conn.execute(
+        "INSERT INTO skill_library_events (skill_name, source, event_type, version, is_active)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![skill_name, source, event_type, version, is_active as i64],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Record the skill's current version and active flag as read back from its table.
+/// Audit failures are logged rather than failing the library change itself.
+fn audit_library_state(conn: &Connection, source: &str, skill_name: &str, event_type: &str) {
+    let table = if source == LIBRARY_SOURCE_WORKSPACE { "workspace_skills" } else { "imported_skills" };
+    let row: Result<(Option<String>, i64), _> = conn.query_row(
+        &format!("SELECT version, is_active FROM {table} WHERE skill_name = ?1"),
+        [skill_name],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    );
+    let result = row
+        .map_err(|e| e.to_string())
+        .and_then(|(version, active)| {
+            record_library_event(conn, skill_name, source, event_type, version.as_deref(), active != 0)
+        });
+    if let Err(e) = result {
+        log::warn!("[library_events] Failed to record {} for '{}': {}", event_type, skill_name, e);
+    }
+}
+
+fn audit_library_removal(conn: &Connection, source: &str, skill_name: &str) {
+    if let Err(e) = record_library_event(conn, skill_name, source, "removed", None, false) {
+        log::warn!("[library_events] Failed to record removal of '{}': {}", skill_name, e);
+    }
+}
+
+/// All library events at or before `at` (`YYYY-MM-DD HH:MM:SSZ`), oldest first.
+pub fn list_library_events_until(conn: &Connection, at: &str) -> Result<Vec<LibraryEvent>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT skill_name, source, event_type, version, is_active, created_at
+             FROM skill_library_events WHERE created_at <= ?1 ORDER BY created_at, id",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([at], |row| {
+            let is_active: i64 = row.get(4)?;
+            Ok(LibraryEvent {
+                skill_name: row.get(0)?,
+                source: row.get(1)?,
+                event_type: row.get(2)?,
+                version: row.get(3)?,
+                is_active: is_active != 0,
+                created_at: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(rows)
+}
+
+// --- Skills Master ---
+
+/// Upsert a row in the `skills` master table. Used by `save_workflow_run` (skill-builder)
+/// and marketplace import. Returns the skill id.
+pub fn upsert_skill(
+    conn: &Connection,
+    name: &str,
+    skill_source: &str,
+    purpose: &str,
+) -> Result<i64, String> {
+    log::debug!("upsert_skill: name={} skill_source={}", name, skill_source);
+    conn.execute(
+        "INSERT INTO skills (name, skill_source, purpose, updated_at)
+         VALUES (?1, ?2, ?3, datetime('now'))
+         ON CONFLICT(name) DO UPDATE SET
+             purpose = ?3, updated_at = datetime('now'), deleted_at = NULL",
+        rusqlite::params![name, skill_source, purpose],
+    )
+    .map_err(|e| {
+        log::error!("upsert_skill: failed to upsert '{}': {}", name, e);
+        e.to_string()
+    })?;
+    let id: i64 = conn
+        .query_row(
+            "SELECT id FROM skills WHERE name = ?1",
+            rusqlite::params![name],
+            |row| row.get(0),
+        )
+        .map_err(|e| {
+            log::error!("upsert_skill: failed to retrieve id for '{}': {}", name, e);
+            e.to_string()
+        })?;
+    Ok(id)
+}
+
+/// Like `upsert_skill`, but ALWAYS updates `skill_source` on conflict.
+/// Use this when the caller explicitly wants to set the source (e.g. `resolve_discovery`).
+/// `upsert_skill` intentionally skips `skill_source` on conflict to prevent
+/// `save_workflow_run` from overwriting a marketplace skill's source.
+pub fn upsert_skill_with_source(
+    conn: &Connection,
+    name: &str,
+    skill_source: &str,
+    purpose: &str,
+) -> Result<i64, String> {
+    log::debug!(
+        "upsert_skill_with_source: name={} skill_source={}",
+        name,
+        skill_source
+    );
+    conn.execute(
+        "INSERT INTO skills (name, skill_source, purpose, updated_at)
+         VALUES (?1, ?2, ?3, datetime('now'))
+         ON CONFLICT(name) DO UPDATE SET
+             skill_source = ?2, purpose = ?3, updated_at = datetime('now'), deleted_at = NULL",
+        rusqlite::params![name, skill_source, purpose],
+    )
+    .map_err(|e| {
+        log::error!(
+            "upsert_skill_with_source: failed to upsert '{}': {}",
+            name,
+            e
+        );
+        e.to_string()
+    })?;
+    let id: i64 = conn
+        .query_row(
+            "SELECT id FROM skills WHERE name = ?1",
+            rusqlite::params![name],
+            |row| row.get(0),
+        )
+        .map_err(|e| {
+            log::error!(
+                "upsert_skill_with_source: failed to retrieve id for '{}': {}",
+                name,
+                e
+            );
+            e.to_string()
+        })?;
+    Ok(id)
+}
+
+/// List all skills from the master table, ordered by name.
+pub fn list_all_skills(conn: &Connection) -> Result<Vec<SkillMasterRow>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, skill_source, purpose, created_at, updated_at,
+                    description, version, model, argument_hint, user_invocable, disable_model_invocation,
+                    review_by, reviewed_at
+             FROM skills
+             WHERE COALESCE(deleted_at, '') = ''
+             ORDER BY name",
+        )
+        .map_err(|e| {
+            log::error!("list_all_skills: failed to prepare query: {}", e);
+            e.to_string()
+        })?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(SkillMasterRow {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                skill_source: row.get(2)?,
+                purpose: row.get(3)?,
+                created_at: row.get(4)?,
+                updated_at: row.get(5)?,
+                description: row.get(6)?,
+                version: row.get(7)?,
+                model: row.get(8)?,
+                argument_hint: row.get(9)?,
+                user_invocable: row.get::<_, Option<i32>>(10)?.map(|v| v != 0),
+                disable_model_invocation: row.get::<_, Option<i32>>(11)?.map(|v| v != 0),
+                review_by: row.get(12)?,
+                reviewed_at: row.get(13)?,
+            })
+        })
+        .map_err(|e| {
+            log::error!("list_all_skills: query failed: {}", e);
+            e.to_string()
+        })?;
+
+    let result: Vec<SkillMasterRow> = rows.collect::<Result<Vec<_>, _>>().map_err(|e| {
+        log::error!("list_all_skills: failed to collect rows: {}", e);
+        e.to_string()
+    })?;
+    log::debug!("list_all_skills: returning {} skills", result.len());
+    Ok(result)
+}
+
+/// Live skills grouped by `by` — `domain` (tag), `type` (purpose), `owner`
+/// (author login), `collection` (marketplace source URL) or `health` (review
+/// state) — with per-group aggregates. Skills without a value for the
+/// grouping fall in the group with no key; a skill with several tags counts in
+/// each. Largest groups first.
+pub fn list_skill_groups(conn: &Connection, by: &str) -> Result<Vec<SkillGroup>, String> {
+    use crate::commands::skill_review::{REVIEW_DUE_SOON, REVIEW_DUE_SOON_DAYS, REVIEW_EXPIRED, REVIEW_OK};
+
+    let (key_expr, join) = match by {
+        "domain" => ("t.tag", "LEFT JOIN skill_tags t ON t.skill_name = s.name"),
+        "type" => ("NULLIF(s.purpose, '')", ""),
+        "owner" => ("NULLIF(s.author_login, '')", ""),
+        "collection" => (
+            "src.source_url",
+            "LEFT JOIN (
+                 SELECT skill_name, MAX(marketplace_source_url) AS source_url FROM (
+                     SELECT skill_name, marketplace_source_url FROM imported_skills
+                     UNION ALL
+                     SELECT skill_name, marketplace_source_url FROM workspace_skills
+                 )
+                 WHERE COALESCE(marketplace_source_url, '') <> ''
+                 GROUP BY skill_name
+             ) src ON src.skill_name = s.name",
+        ),
+        "health" => ("s.health", ""),
+        other => {
+            return Err(format!(
+                "Unknown grouping '{}': use domain, type, owner, collection or health",
+                other
+            ))
+        }
+    };
+    let sql = format!(
+        "WITH live AS (
+             SELECT s.name, COALESCE(wr.purpose, s.purpose) AS purpose, wr.author_login,
+                    COALESCE(c.cost, 0.0) AS cost,
+                    CASE
+                        WHEN COALESCE(s.review_by, '') = '' THEN NULL
+                        WHEN s.review_by < date('now', 'localtime') THEN '{expired}'
+                        WHEN s.review_by <= date('now', 'localtime', '+{due_days} days') THEN '{due_soon}'
+                        ELSE '{ok}'
+                    END AS health
+             FROM skills s
+             LEFT JOIN workflow_runs wr ON wr.skill_name = s.name
+             LEFT JOIN (
+                 SELECT skill_name, SUM(COALESCE(total_cost, 0.0)) AS cost
+                 FROM agent_runs
+                 WHERE reset_marker IS NULL
+                 GROUP BY skill_name
+             ) c ON c.skill_name = s.name
+             WHERE COALESCE(s.deleted_at, '') = ''
+         )
+         SELECT {key} AS group_key,
+                COUNT(*),
+                COALESCE(SUM(s.cost), 0.0),
+                COALESCE(SUM(s.health = '{expired}'), 0),
+                json_group_array(s.name)
+         FROM live s
+         {join}
+         GROUP BY group_key
+         ORDER BY COUNT(*) DESC, group_key IS NULL, group_key",
+        key = key_expr,
+        join = join,
+        expired = REVIEW_EXPIRED,
+        due_soon = REVIEW_DUE_SOON,
+        ok = REVIEW_OK,
+        due_days = REVIEW_DUE_SOON_DAYS,
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| {
+        log::error!("list_skill_groups: failed to prepare query: {}", e);
+        e.to_string()
+    })?;
+    let rows = stmt
+        .query_map([], |row| {
+            let names: String = row.get(4)?;
+            Ok(SkillGroup {
+                key: row.get(0)?,
+                skill_count: row.get(1)?,
+                total_cost: row.get(2)?,
+                stale_count: row.get(3)?,
+                skill_names: serde_json::from_str(&names).unwrap_or_default(),
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    let mut groups = rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())?;
+    for group in &mut groups {
+        group.skill_names.sort();
+    }
+    Ok(groups)
+}
+
+/// Delete a skill from the master table by name.
+pub fn delete_skill(conn: &Connection, name: &str) -> Result<(), String> {
+    log::info!("delete_skill: name={}", name);
+    conn.execute(
+        "UPDATE skills
+         SET deleted_at = CASE
+               WHEN deleted_at IS NULL OR deleted_at = '' THEN datetime('now') || 'Z'
+               ELSE deleted_at
+             END,
+             updated_at = datetime('now')
+         WHERE name = ?1",
+        rusqlite::params![name],
+    )
+    .map_err(|e| {
+        log::error!("delete_skill: failed to delete '{}': {}", name, e);
+        e.to_string()
+    })?;
+    Ok(())
+}
+
+/// Get the `workflow_runs.id` integer for a given `skill_name`. Returns None if not found.
+pub fn get_workflow_run_id(conn: &Connection, skill_name: &str) -> Result<Option<i64>, String> {
+    conn.query_row(
+        "SELECT id FROM workflow_runs WHERE skill_name = ?1",
+        rusqlite::params![skill_name],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// Get the `skills.id` integer for a given skill name. Returns None if not found.
+pub fn get_skill_master_id(conn: &Connection, skill_name: &str) -> Result<Option<i64>, String> {
+    conn.query_row(
+        "SELECT id FROM skills WHERE name = ?1",
+        rusqlite::params![skill_name],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+// --- Workflow Run ---
+
+pub fn save_workflow_run(
+    conn: &Connection,
+    skill_name: &str,
+    current_step: i32,
+    status: &str,
+    purpose: &str,
+) -> Result<(), String> {
+    // Ensure the skills master row exists (skill-builder source)
+    let skill_id = upsert_skill(conn, skill_name, "skill-builder", purpose)?;
+    conn.execute(
+        "INSERT INTO workflow_runs (skill_name, current_step, status, purpose, skill_id, contract_version, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, datetime('now') || 'Z')
+         ON CONFLICT(skill_name) DO UPDATE SET
+             current_step = ?2, status = ?3, purpose = ?4, skill_id = ?5, updated_at = datetime('now') || 'Z'",
+        rusqlite::params![
+            skill_name,
+            current_step,
+            status,
+            purpose,
+            skill_id,
+            crate::commands::prompt_contract::PROMPT_CONTRACT_VERSION
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    emit_skill_updated(conn, skill_name);
+    Ok(())
+}
+
+/// Announce the current state of a skill-builder skill on the DB change bus.
+/// Skipped when no frontend is listening so tests don't pay for the extra reads.
+fn emit_skill_updated(conn: &Connection, skill_name: &str) {
+    if !crate::db_events::is_active() {
+        return;
+    }
+    let run = match get_workflow_run(conn, skill_name) {
+        Ok(Some(run)) => run,
+        Ok(None) => return,
+        Err(e) => {
+            log::warn!("[emit_skill_updated] Failed to read run for '{}': {}", skill_name, e);
+            return;
+        }
     };
-    let sql = format!(
-        "SELECT COALESCE(SUM(sub.session_cost), 0.0),
-                COUNT(*),
-                COALESCE(AVG(sub.session_cost), 0.0)
-         FROM (
-           SELECT ws.session_id, COALESCE(SUM(ar.total_cost), 0.0) as session_cost
-           FROM workflow_sessions ws
-           LEFT JOIN agent_runs ar ON ar.workflow_session_id = ws.session_id
-                                  AND ar.reset_marker IS NULL
-           WHERE ws.reset_marker IS NULL{date_clause}{skill_clause}
-           GROUP BY ws.session_id{having_clause}
-         ) sub"
-    );
-    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
-    macro_rules! query {
-        ($params:expr) => {
-            stmt.query_row($params, |row| {
-                Ok(UsageSummary {
-                    total_cost: row.get(0)?,
-                    total_runs: row.get(1)?,
-                    avg_cost_per_run: row.get(2)?,
-                })
-            })
-            .map_err(|e| e.to_string())
-        };
+    let tags = get_tags_for_skills(conn, &[skill_name.to_string()])
+        .ok()
+        .and_then(|mut m| m.remove(skill_name))
+        .unwrap_or_default();
+    crate::db_events::emit(crate::db_events::DbChange::SkillUpdated {
+        skill: crate::db_events::SkillChange {
+            name: run.skill_name,
+            current_step: Some(format!("Step {}", run.current_step)),
+            status: Some(run.status),
+            last_modified: Some(run.updated_at),
+            intake_json: run.intake_json,
+            author_login: run.author_login,
+            author_avatar: run.author_avatar,
+            tags,
+        },
+    });
+}
+
+/// Insert a marketplace skill into the skills master table only. No workflow_runs row.
+/// Replaces `save_marketplace_skill_run` — marketplace skills no longer get workflow_runs rows.
+pub fn save_marketplace_skill(
+    conn: &Connection,
+    skill_name: &str,
+    purpose: &str,
+) -> Result<(), String> {
+    log::info!("save_marketplace_skill: name={}", skill_name);
+    upsert_skill(conn, skill_name, "marketplace", purpose).map_err(|e| {
+        log::error!("save_marketplace_skill: failed for '{}': {}", skill_name, e);
+        e
+    })?;
+    Ok(())
+}
+
+/// Set the review-by date (`YYYY-MM-DD`, or `None` to clear). When `reviewed`
+/// is true, `reviewed_at` is stamped with the current time as well.
+pub fn set_skill_review(
+    conn: &Connection,
+    skill_name: &str,
+    review_by: Option<&str>,
+    reviewed: bool,
+) -> Result<(), String> {
+    let updated = conn
+        .execute(
+            "UPDATE skills SET
+                review_by = ?2,
+                reviewed_at = CASE WHEN ?3 THEN datetime('now') || 'Z' ELSE reviewed_at END,
+                updated_at = datetime('now')
+             WHERE name = ?1 AND COALESCE(deleted_at, '') = ''",
+            rusqlite::params![skill_name, review_by, reviewed],
+        )
+        .map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(format!("Skill '{}' not found", skill_name));
+    }
+    emit_skill_updated(conn, skill_name);
+    Ok(())
+}
+
+/// `(review_by, reviewed_at)` for a skill in the master table.
+pub fn get_skill_review(
+    conn: &Connection,
+    skill_name: &str,
+) -> Result<(Option<String>, Option<String>), String> {
+    conn.query_row(
+        "SELECT review_by, reviewed_at FROM skills WHERE name = ?1 AND COALESCE(deleted_at, '') = ''",
+        [skill_name],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+    .map(|r| r.unwrap_or((None, None)))
+}
+
+/// Remember the package format used for a skill. Skills missing from the
+/// master table are left alone.
+pub fn set_skill_package_format(conn: &Connection, skill_name: &str, format: &str) -> Result<(), String> {
+    conn.execute(
+        "UPDATE skills SET package_format = ?2 WHERE name = ?1 AND COALESCE(deleted_at, '') = ''",
+        rusqlite::params![skill_name, format],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Package format last used for a skill, if any.
+pub fn get_skill_package_format(conn: &Connection, skill_name: &str) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT package_format FROM skills WHERE name = ?1 AND COALESCE(deleted_at, '') = ''",
+        [skill_name],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+    .map(Option::flatten)
+}
+
+pub fn set_skill_author(
+    conn: &Connection,
+    skill_name: &str,
+    author_login: &str,
+    author_avatar: Option<&str>,
+) -> Result<(), String> {
+    conn.execute(
+        "UPDATE workflow_runs SET author_login = ?2, author_avatar = ?3 WHERE skill_name = ?1",
+        rusqlite::params![skill_name, author_login, author_avatar],
+    )
+    .map_err(|e| e.to_string())?;
+    emit_skill_updated(conn, skill_name);
+    Ok(())
+}
+
+#[cfg(test)]
+pub fn set_skill_display_name(
+    conn: &Connection,
+    skill_name: &str,
+    display_name: Option<&str>,
+) -> Result<(), String> {
+    conn.execute(
+        "UPDATE workflow_runs SET display_name = ?2, updated_at = datetime('now') || 'Z' WHERE skill_name = ?1",
+        rusqlite::params![skill_name, display_name],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn set_skill_intake(
+    conn: &Connection,
+    skill_name: &str,
+    intake_json: Option<&str>,
+) -> Result<(), String> {
+    conn.execute(
+        "UPDATE workflow_runs SET intake_json = ?2, updated_at = datetime('now') || 'Z' WHERE skill_name = ?1",
+        rusqlite::params![skill_name, intake_json],
+    )
+    .map_err(|e| e.to_string())?;
+    emit_skill_updated(conn, skill_name);
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn set_skill_behaviour(
+    conn: &Connection,
+    skill_name: &str,
+    description: Option<&str>,
+    version: Option<&str>,
+    model: Option<&str>,
+    argument_hint: Option<&str>,
+    user_invocable: Option<bool>,
+    disable_model_invocation: Option<bool>,
+) -> Result<(), String> {
+    let user_invocable_i: Option<i32> = user_invocable.map(|v| if v { 1 } else { 0 });
+    let disable_model_invocation_i: Option<i32> =
+        disable_model_invocation.map(|v| if v { 1 } else { 0 });
+
+    // Write to skills master — canonical store for all skill sources
+    conn.execute(
+        "UPDATE skills SET
+            description = COALESCE(?2, description),
+            version = COALESCE(?3, version),
+            model = COALESCE(?4, model),
+            argument_hint = COALESCE(?5, argument_hint),
+            user_invocable = COALESCE(?6, user_invocable),
+            disable_model_invocation = COALESCE(?7, disable_model_invocation),
+            updated_at = datetime('now')
+         WHERE name = ?1",
+        rusqlite::params![
+            skill_name,
+            description,
+            version,
+            model,
+            argument_hint,
+            user_invocable_i,
+            disable_model_invocation_i,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Dual-write to workflow_runs for skill-builder skills (no-op for marketplace/imported).
+    // These columns will be dropped from workflow_runs in a future migration.
+    conn.execute(
+        "UPDATE workflow_runs SET
+            description = COALESCE(?2, description),
+            version = COALESCE(?3, version),
+            model = COALESCE(?4, model),
+            argument_hint = COALESCE(?5, argument_hint),
+            user_invocable = COALESCE(?6, user_invocable),
+            disable_model_invocation = COALESCE(?7, disable_model_invocation),
+            updated_at = datetime('now') || 'Z'
+         WHERE skill_name = ?1",
+        rusqlite::params![
+            skill_name,
+            description,
+            version,
+            model,
+            argument_hint,
+            user_invocable_i,
+            disable_model_invocation_i,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Copy frontmatter fields of a SKILL.md edited on disk to the skill's rows
+/// (`skills`, `workflow_runs`, `imported_skills`). Fields absent from the
+/// frontmatter keep their stored value. Returns whether anything differed;
+/// rows already in sync are left alone so their `updated_at` stays put.
+pub fn sync_skill_frontmatter(
+    conn: &Connection,
+    skill_name: &str,
+    fm: &crate::commands::imported_skills::Frontmatter,
+) -> Result<bool, String> {
+    let user_invocable = fm.user_invocable.map(|v| v as i32);
+    let disable_model_invocation = fm.disable_model_invocation.map(|v| v as i32);
+    let params = rusqlite::params![
+        skill_name,
+        fm.description,
+        fm.version,
+        fm.model,
+        fm.argument_hint,
+        user_invocable,
+        disable_model_invocation,
+    ];
+    let stale: i64 = conn
+        .query_row(
+            "SELECT
+                 (SELECT COUNT(*) FROM skills
+                  WHERE name = ?1 AND COALESCE(deleted_at, '') = ''
+                    AND ((?2 IS NOT NULL AND description IS NOT ?2)
+                      OR (?3 IS NOT NULL AND version IS NOT ?3)
+                      OR (?4 IS NOT NULL AND model IS NOT ?4)
+                      OR (?5 IS NOT NULL AND argument_hint IS NOT ?5)
+                      OR (?6 IS NOT NULL AND user_invocable IS NOT ?6)
+                      OR (?7 IS NOT NULL AND disable_model_invocation IS NOT ?7)))
+               + (SELECT COUNT(*) FROM imported_skills
+                  WHERE skill_name = ?1
+                    AND ((?3 IS NOT NULL AND version IS NOT ?3)
+                      OR (?4 IS NOT NULL AND model IS NOT ?4)
+                      OR (?5 IS NOT NULL AND argument_hint IS NOT ?5)
+                      OR (?6 IS NOT NULL AND user_invocable IS NOT ?6)
+                      OR (?7 IS NOT NULL AND disable_model_invocation IS NOT ?7)))",
+            params,
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if stale == 0 {
+        return Ok(false);
+    }
+
+    set_skill_behaviour(
+        conn,
+        skill_name,
+        fm.description.as_deref(),
+        fm.version.as_deref(),
+        fm.model.as_deref(),
+        fm.argument_hint.as_deref(),
+        fm.user_invocable,
+        fm.disable_model_invocation,
+    )?;
+    conn.execute(
+        "UPDATE imported_skills SET
+            version = COALESCE(?2, version),
+            model = COALESCE(?3, model),
+            argument_hint = COALESCE(?4, argument_hint),
+            user_invocable = COALESCE(?5, user_invocable),
+            disable_model_invocation = COALESCE(?6, disable_model_invocation)
+         WHERE skill_name = ?1",
+        rusqlite::params![
+            skill_name,
+            fm.version,
+            fm.model,
+            fm.argument_hint,
+            user_invocable,
+            disable_model_invocation,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    emit_skill_updated(conn, skill_name);
+    Ok(true)
+}
+
+/// `skill_source` of a live (not soft-deleted) skill, or `None` if the
+/// library has no such skill.
+pub fn get_live_skill_source(conn: &Connection, skill_name: &str) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT skill_source FROM skills WHERE name = ?1 AND COALESCE(deleted_at, '') = ''",
+        rusqlite::params![skill_name],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+pub fn get_workflow_run(
+    conn: &Connection,
+    skill_name: &str,
+) -> Result<Option<WorkflowRunRow>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT skill_name, current_step, status, purpose, created_at, updated_at, author_login, author_avatar, display_name, intake_json, COALESCE(source, 'created'), description, version, model, argument_hint, user_invocable, disable_model_invocation
+             FROM workflow_runs WHERE skill_name = ?1",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let result = stmt.query_row(rusqlite::params![skill_name], |row| {
+        Ok(WorkflowRunRow {
+            skill_name: row.get(0)?,
+            current_step: row.get(1)?,
+            status: row.get(2)?,
+            purpose: row.get(3)?,
+            created_at: row.get(4)?,
+            updated_at: row.get(5)?,
+            author_login: row.get(6)?,
+            author_avatar: row.get(7)?,
+            display_name: row.get(8)?,
+            intake_json: row.get(9)?,
+            source: row.get(10)?,
+            description: row.get(11)?,
+            version: row.get(12)?,
+            model: row.get(13)?,
+            argument_hint: row.get(14)?,
+            user_invocable: row.get::<_, Option<i32>>(15)?.map(|v| v != 0),
+            disable_model_invocation: row.get::<_, Option<i32>>(16)?.map(|v| v != 0),
+        })
+    });
+
+    match result {
+        Ok(run) => Ok(Some(run)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
     }
-    match (start_date, skill_name) {
-        (Some(sd), Some(sn)) => query!(rusqlite::params![sd, sn]),
-        (Some(sd), None) => query!(rusqlite::params![sd]),
-        (None, Some(sn)) => query!(rusqlite::params![sn]),
-        (None, None) => query!([]),
+}
+
+/// Prompt contract version the run's step outputs follow, if the run exists.
+pub fn get_workflow_contract_version(conn: &Connection, skill_name: &str) -> Result<Option<i32>, String> {
+    match conn.query_row(
+        "SELECT contract_version FROM workflow_runs WHERE skill_name = ?1",
+        rusqlite::params![skill_name],
+        |row| row.get(0),
+    ) {
+        Ok(v) => Ok(Some(v)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
     }
 }
 
-pub fn get_workflow_skill_names(conn: &Connection) -> Result<Vec<String>, String> {
+pub fn set_workflow_contract_version(conn: &Connection, skill_name: &str, version: i32) -> Result<(), String> {
+    conn.execute(
+        "UPDATE workflow_runs SET contract_version = ?2 WHERE skill_name = ?1",
+        rusqlite::params![skill_name, version],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// In-progress runs stamped with an older contract: `(skill_name, current_step, contract_version)`.
+pub fn list_runs_before_contract(conn: &Connection, version: i32) -> Result<Vec<(String, i32, i32)>, String> {
     let mut stmt = conn
         .prepare(
-            "SELECT DISTINCT skill_name FROM workflow_sessions
-         WHERE reset_marker IS NULL
-         ORDER BY skill_name ASC",
+            "SELECT skill_name, current_step, contract_version FROM workflow_runs
+             WHERE contract_version < ?1 AND status != 'completed'
+             ORDER BY skill_name",
         )
         .map_err(|e| e.to_string())?;
     let rows = stmt
-        .query_map([], |row| row.get(0))
+        .query_map(rusqlite::params![version], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
         .map_err(|e| e.to_string())?;
-    rows.collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
 }
 
-pub fn get_recent_runs(conn: &Connection, limit: usize) -> Result<Vec<AgentRunRecord>, String> {
+pub fn get_purpose(conn: &Connection, skill_name: &str) -> Result<String, String> {
+    get_workflow_run(conn, skill_name).map(|opt| {
+        opt.map(|run| run.purpose)
+            .unwrap_or_else(|| "domain".to_string())
+    })
+}
+
+pub fn list_all_workflow_runs(conn: &Connection) -> Result<Vec<WorkflowRunRow>, String> {
     let mut stmt = conn
         .prepare(
-            "SELECT agent_id, skill_name, step_id, model, status,
-                    COALESCE(input_tokens, 0), COALESCE(output_tokens, 0),
-                    COALESCE(cache_read_tokens, 0), COALESCE(cache_write_tokens, 0),
-                    COALESCE(total_cost, 0.0), COALESCE(duration_ms, 0),
-                    COALESCE(num_turns, 0), stop_reason, duration_api_ms,
-                    COALESCE(tool_use_count, 0), COALESCE(compaction_count, 0),
-                    session_id, started_at, completed_at
-             FROM agent_runs
-             WHERE reset_marker IS NULL
-             ORDER BY completed_at DESC
-             LIMIT ?1",
+            "SELECT skill_name, current_step, status, purpose, created_at, updated_at, author_login, author_avatar, display_name, intake_json, COALESCE(source, 'created'), description, version, model, argument_hint, user_invocable, disable_model_invocation
+             FROM workflow_runs ORDER BY skill_name",
         )
         .map_err(|e| e.to_string())?;
 
     let rows = stmt
-        .query_map(rusqlite::params![limit as i64], |row| {
-            Ok(AgentRunRecord {
-                agent_id: row.get(0)?,
-                skill_name: row.get(1)?,
-                step_id: row.get(2)?,
-                model: row.get(3)?,
-                status: row.get(4)?,
-                input_tokens: row.get(5)?,
-                output_tokens: row.get(6)?,
-                cache_read_tokens: row.get(7)?,
-                cache_write_tokens: row.get(8)?,
-                total_cost: row.get(9)?,
-                duration_ms: row.get(10)?,
-                num_turns: row.get(11)?,
-                stop_reason: row.get(12)?,
-                duration_api_ms: row.get(13)?,
-                tool_use_count: row.get(14)?,
-                compaction_count: row.get(15)?,
-                session_id: row.get(16)?,
-                started_at: row.get(17)?,
-                completed_at: row.get(18)?,
+        .query_map([], |row| {
+            Ok(WorkflowRunRow {
+                skill_name: row.get(0)?,
+                current_step: row.get(1)?,
+                status: row.get(2)?,
+                purpose: row.get(3)?,
+                created_at: row.get(4)?,
+                updated_at: row.get(5)?,
+                author_login: row.get(6)?,
+                author_avatar: row.get(7)?,
+                display_name: row.get(8)?,
+                intake_json: row.get(9)?,
+                source: row.get(10)?,
+                description: row.get(11)?,
+                version: row.get(12)?,
+                model: row.get(13)?,
+                argument_hint: row.get(14)?,
+                user_invocable: row.get::<_, Option<i32>>(15)?.map(|v| v != 0),
+                disable_model_invocation: row.get::<_, Option<i32>>(16)?.map(|v| v != 0),
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+pub fn delete_workflow_run(conn: &Connection, skill_name: &str) -> Result<(), String> {
+    // Look up FK ids before deleting the parent rows
+    let wr_id = get_workflow_run_id(conn, skill_name)?
+        .ok_or_else(|| format!("Workflow run not found for skill '{}'", skill_name))?;
+    let s_id = get_skill_master_id(conn, skill_name)?
+        .ok_or_else(|| format!("Skill '{}' not found in skills master", skill_name))?;
+
+    // Delete workflow-state child rows by FK columns only.
+    // Usage history tables (agent_runs/workflow_sessions) are intentionally retained.
+    conn.execute(
+        "DELETE FROM workflow_artifacts WHERE workflow_run_id = ?1",
+        rusqlite::params![wr_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "DELETE FROM workflow_steps WHERE workflow_run_id = ?1",
+        rusqlite::params![wr_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "DELETE FROM skill_locks WHERE skill_id = ?1",
+        rusqlite::params![s_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "DELETE FROM skill_tags WHERE skill_id = ?1",
+        rusqlite::params![s_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Delete from imported_skills to prevent stale rows blocking re-import
+    conn.execute(
+        "DELETE FROM imported_skills WHERE skill_master_id = ?1",
+        rusqlite::params![s_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "DELETE FROM artifact_annotations WHERE skill_name = ?1",
+        [skill_name],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "DELETE FROM step_run_cache WHERE skill_name = ?1",
+        [skill_name],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "DELETE FROM refine_sessions WHERE skill_name = ?1",
+        [skill_name],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "DELETE FROM skill_upstream_reviews WHERE skill_name = ?1",
+        [skill_name],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Edges into the skill stay, so dependents show it as missing.
+    conn.execute(
+        "DELETE FROM skill_dependencies WHERE skill_name = ?1",
+        [skill_name],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "DELETE FROM skill_test_results WHERE skill_name = ?1",
+        [skill_name],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "DELETE FROM agent_tool_calls WHERE skill_name = ?1",
+        [skill_name],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "DELETE FROM skill_push_bases WHERE skill_name = ?1",
+        [skill_name],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "DELETE FROM skill_generation_bases WHERE skill_name = ?1",
+        [skill_name],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "DELETE FROM skill_invocations WHERE skill_name = ?1",
+        [skill_name],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "DELETE FROM agent_run_checkpoints WHERE skill_name = ?1",
+        [skill_name],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "DELETE FROM skill_test_cases WHERE skill_name = ?1",
+        [skill_name],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "DELETE FROM workflow_runs WHERE skill_name = ?1",
+        [skill_name],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Also delete from skills master table
+    delete_skill(conn, skill_name)?;
+    crate::db_events::emit(crate::db_events::DbChange::SkillDeleted {
+        skill_name: skill_name.to_string(),
+    });
+    Ok(())
+}
+
+// --- Workflow Steps ---
+
+pub fn save_workflow_step(
+    conn: &Connection,
+    skill_name: &str,
+    step_id: i32,
+    status: &str,
+) -> Result<(), String> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let (started, completed) = match status {
+        "in_progress" => (Some(now.clone()), None),
+        "completed" => (None, Some(now)),
+        _ => (None, None),
+    };
+
+    let workflow_run_id = get_workflow_run_id(conn, skill_name)?;
+
+    conn.execute(
+        "INSERT INTO workflow_steps (skill_name, step_id, status, started_at, completed_at, workflow_run_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(skill_name, step_id) DO UPDATE SET
+             status = ?3,
+             started_at = COALESCE(?4, started_at),
+             completed_at = ?5,
+             workflow_run_id = COALESCE(?6, workflow_run_id)",
+        rusqlite::params![skill_name, step_id, status, started, completed, workflow_run_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Write a step row as-is, keeping its original timestamps (skill archive import).
+pub fn restore_workflow_step(conn: &Connection, step: &WorkflowStepRow) -> Result<(), String> {
+    let workflow_run_id = get_workflow_run_id(conn, &step.skill_name)?;
+    conn.execute(
+        "INSERT OR REPLACE INTO workflow_steps
+         (skill_name, step_id, status, started_at, completed_at, workflow_run_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![
+            step.skill_name,
+            step.step_id,
+            step.status,
+            step.started_at,
+            step.completed_at,
+            workflow_run_id
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn get_workflow_steps(
+    conn: &Connection,
+    skill_name: &str,
+) -> Result<Vec<WorkflowStepRow>, String> {
+    let wr_id = match get_workflow_run_id(conn, skill_name)? {
+        Some(id) => id,
+        None => return Ok(vec![]),
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT skill_name, step_id, status, started_at, completed_at
+             FROM workflow_steps WHERE workflow_run_id = ?1 ORDER BY step_id",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![wr_id], |row| {
+            Ok(WorkflowStepRow {
+                skill_name: row.get(0)?,
+                step_id: row.get(1)?,
+                status: row.get(2)?,
+                started_at: row.get(3)?,
+                completed_at: row.get(4)?,
             })
         })
         .map_err(|e| e.to_string())?;
-
     rows.collect::<Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())
 }
 
-pub fn get_agent_runs(
+pub fn reset_workflow_steps_from(
     conn: &Connection,
-    hide_cancelled: bool,
-    start_date: Option<&str>,
-    skill_name: Option<&str>,
-    model_family: Option<&str>,
-    limit: usize,
-) -> Result<Vec<AgentRunRecord>, String> {
-    let cost_clause = if hide_cancelled { " AND total_cost > 0" } else { "" };
-    let mut p = 1usize;
-    let date_clause = if start_date.is_some() {
-        let s = format!(" AND started_at >= ?{p}");
-        p += 1;
-        s
-    } else {
-        String::new()
-    };
-    let skill_clause = if skill_name.is_some() {
-        let s = format!(" AND skill_name = ?{p}");
-        p += 1;
-        s
-    } else {
-        String::new()
-    };
-    let model_family_clause = if model_family.is_some() {
-        let s = format!(
-            " AND CASE \
-              WHEN lower(model) LIKE '%haiku%'  THEN 'Haiku' \
-              WHEN lower(model) LIKE '%opus%'   THEN 'Opus' \
-              WHEN lower(model) LIKE '%sonnet%' THEN 'Sonnet' \
-              ELSE model END = ?{p}"
-        );
-        p += 1;
-        s
-    } else {
-        String::new()
+    skill_name: &str,
+    from_step: i32,
+) -> Result<(), String> {
+    let wr_id = match get_workflow_run_id(conn, skill_name)? {
+        Some(id) => id,
+        None => return Ok(()),
     };
-    let sql = format!(
-        "SELECT agent_id, skill_name, step_id, model, status,
-                COALESCE(input_tokens, 0), COALESCE(output_tokens, 0),
-                COALESCE(cache_read_tokens, 0), COALESCE(cache_write_tokens, 0),
-                COALESCE(total_cost, 0.0), COALESCE(duration_ms, 0),
-                COALESCE(num_turns, 0), stop_reason, duration_api_ms,
-                COALESCE(tool_use_count, 0), COALESCE(compaction_count, 0),
-                session_id, started_at, completed_at
-         FROM agent_runs
-         WHERE reset_marker IS NULL
-           AND workflow_session_id IS NOT NULL{cost_clause}{date_clause}{skill_clause}{model_family_clause}
-         ORDER BY started_at DESC
-         LIMIT ?{p}"
-    );
-    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
-    let limit_i64 = limit as i64;
-    macro_rules! collect_rows {
-        ($params:expr) => {
-            stmt.query_map($params, |row| {
-                Ok(AgentRunRecord {
-                    agent_id: row.get(0)?,
-                    skill_name: row.get(1)?,
-                    step_id: row.get(2)?,
-                    model: row.get(3)?,
-                    status: row.get(4)?,
-                    input_tokens: row.get(5)?,
-                    output_tokens: row.get(6)?,
-                    cache_read_tokens: row.get(7)?,
-                    cache_write_tokens: row.get(8)?,
-                    total_cost: row.get(9)?,
-                    duration_ms: row.get(10)?,
-                    num_turns: row.get(11)?,
-                    stop_reason: row.get(12)?,
-                    duration_api_ms: row.get(13)?,
-                    tool_use_count: row.get(14)?,
-                    compaction_count: row.get(15)?,
-                    session_id: row.get(16)?,
-                    started_at: row.get(17)?,
-                    completed_at: row.get(18)?,
-                })
-            })
-            .map_err(|e| e.to_string())?
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| e.to_string())
-        };
-    }
-    match (start_date, skill_name, model_family) {
-        (Some(sd), Some(sn), Some(mf)) => collect_rows!(rusqlite::params![sd, sn, mf, limit_i64]),
-        (Some(sd), Some(sn), None)     => collect_rows!(rusqlite::params![sd, sn, limit_i64]),
-        (Some(sd), None, Some(mf))     => collect_rows!(rusqlite::params![sd, mf, limit_i64]),
-        (Some(sd), None, None)         => collect_rows!(rusqlite::params![sd, limit_i64]),
-        (None, Some(sn), Some(mf))     => collect_rows!(rusqlite::params![sn, mf, limit_i64]),
-        (None, Some(sn), None)         => collect_rows!(rusqlite::params![sn, limit_i64]),
-        (None, None, Some(mf))         => collect_rows!(rusqlite::params![mf, limit_i64]),
-        (None, None, None)             => collect_rows!(rusqlite::params![limit_i64]),
-    }
+    conn.execute(
+        "UPDATE workflow_steps SET status = 'pending', started_at = NULL, completed_at = NULL
+         WHERE workflow_run_id = ?1 AND step_id >= ?2",
+        rusqlite::params![wr_id, from_step],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
 }
 
-pub fn get_recent_workflow_sessions(
+// --- Skill Tags ---
+
+pub fn get_tags_for_skills(
     conn: &Connection,
-    limit: usize,
-    hide_cancelled: bool,
-    start_date: Option<&str>,
-    skill_name: Option<&str>,
-) -> Result<Vec<WorkflowSessionRecord>, String> {
-    let having_clause = if hide_cancelled {
-        " HAVING COALESCE(SUM(ar.total_cost), 0) > 0 OR COUNT(DISTINCT ar.agent_id) = 0"
-    } else {
-        ""
-    };
-    let mut p = 1usize;
-    let date_clause = if start_date.is_some() {
-        let s = format!(" AND ws.started_at >= ?{p}");
-        p += 1;
-        s
-    } else {
-        String::new()
-    };
-    let skill_clause = if skill_name.is_some() {
-        let s = format!(" AND ws.skill_name = ?{p}");
-        p += 1;
-        s
-    } else {
-        String::new()
-    };
-    let limit_param = format!("?{p}");
+    skill_names: &[String],
+) -> Result<HashMap<String, Vec<String>>, String> {
+    if skill_names.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    // SQLite default SQLITE_MAX_VARIABLE_NUMBER is 999; chunk if needed
+    if skill_names.len() > 900 {
+        let mut map: HashMap<String, Vec<String>> = HashMap::new();
+        for chunk in skill_names.chunks(900) {
+            let chunk_result = get_tags_for_skills(conn, chunk)?;
+            map.extend(chunk_result);
+        }
+        return Ok(map);
+    }
+
+    // Safety: The format! below only injects positional bind-parameter placeholders
+    // (?1, ?2, ...) — never user-supplied values. All skill_name values are bound via
+    // rusqlite's parameterized query API, so there is no SQL injection risk.
+    let placeholders: Vec<String> = (1..=skill_names.len()).map(|i| format!("?{}", i)).collect();
     let sql = format!(
-        "SELECT ws.session_id,
-                ws.skill_name,
-                COALESCE(MIN(ar.step_id), 0),
-                COALESCE(MAX(ar.step_id), 0),
-                COALESCE(GROUP_CONCAT(DISTINCT ar.step_id), ''),
-                COUNT(DISTINCT ar.agent_id),
-                COALESCE(SUM(ar.total_cost), 0.0),
-                COALESCE(SUM(ar.input_tokens), 0),
-                COALESCE(SUM(ar.output_tokens), 0),
-                COALESCE(SUM(ar.cache_read_tokens), 0),
-                COALESCE(SUM(ar.cache_write_tokens), 0),
-                COALESCE(SUM(ar.duration_ms), 0),
-                ws.started_at,
-                ws.ended_at
-         FROM workflow_sessions ws
-         LEFT JOIN agent_runs ar ON ar.workflow_session_id = ws.session_id
-                                AND ar.reset_marker IS NULL
-         WHERE ws.reset_marker IS NULL{date_clause}{skill_clause}
-         GROUP BY ws.session_id{having_clause}
-         ORDER BY ws.started_at DESC
-         LIMIT {limit_param}"
+        "SELECT skill_name, tag FROM skill_tags WHERE skill_id IN (SELECT id FROM skills WHERE name IN ({})) ORDER BY skill_name, tag",
+        placeholders.join(", ")
     );
+
     let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
-    macro_rules! collect_rows {
-        ($params:expr) => {
-            stmt.query_map($params, |row| {
-                Ok(WorkflowSessionRecord {
-                    session_id: row.get(0)?,
-                    skill_name: row.get(1)?,
-                    min_step: row.get(2)?,
-                    max_step: row.get(3)?,
-                    steps_csv: row.get(4)?,
-                    agent_count: row.get(5)?,
-                    total_cost: row.get(6)?,
-                    total_input_tokens: row.get(7)?,
-                    total_output_tokens: row.get(8)?,
-                    total_cache_read: row.get(9)?,
-                    total_cache_write: row.get(10)?,
-                    total_duration_ms: row.get(11)?,
-                    started_at: row.get(12)?,
-                    completed_at: row.get(13)?,
-                })
-            })
-            .map_err(|e| e.to_string())?
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| e.to_string())
-        };
-    }
-    match (start_date, skill_name) {
-        (Some(sd), Some(sn)) => collect_rows!(rusqlite::params![sd, sn, limit as i64]),
-        (Some(sd), None) => collect_rows!(rusqlite::params![sd, limit as i64]),
-        (None, Some(sn)) => collect_rows!(rusqlite::params![sn, limit as i64]),
-        (None, None) => collect_rows!(rusqlite::params![limit as i64]),
+
+    let params: Vec<&dyn rusqlite::types::ToSql> = skill_names
+        .iter()
+        .map(|s| s as &dyn rusqlite::types::ToSql)
+        .collect();
+
+    let rows = stmt
+        .query_map(params.as_slice(), |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut map: HashMap<String, Vec<String>> = HashMap::new();
+    for row in rows {
+        let (name, tag) = row.map_err(|e| e.to_string())?;
+        map.entry(name).or_default().push(tag);
     }
+
+    Ok(map)
 }
 
-pub fn get_session_agent_runs(
-    conn: &Connection,
-    session_id: &str,
-) -> Result<Vec<AgentRunRecord>, String> {
+pub fn set_skill_tags(conn: &Connection, skill_name: &str, tags: &[String]) -> Result<(), String> {
+    let s_id = get_skill_master_id(conn, skill_name)?
+        .ok_or_else(|| format!("Skill '{}' not found in skills master", skill_name))?;
+
+    conn.execute(
+        "DELETE FROM skill_tags WHERE skill_id = ?1",
+        rusqlite::params![s_id],
+    )
+    .map_err(|e| e.to_string())?;
+
     let mut stmt = conn
-        .prepare(
-            "SELECT agent_id, skill_name, step_id, model, status,
-                    COALESCE(input_tokens, 0), COALESCE(output_tokens, 0),
-                    COALESCE(cache_read_tokens, 0), COALESCE(cache_write_tokens, 0),
-                    COALESCE(total_cost, 0.0), COALESCE(duration_ms, 0),
-                    COALESCE(num_turns, 0), stop_reason, duration_api_ms,
-                    COALESCE(tool_use_count, 0), COALESCE(compaction_count, 0),
-                    session_id, started_at, completed_at
-             FROM agent_runs
-             WHERE workflow_session_id = ?1
-             ORDER BY started_at ASC",
-        )
+        .prepare("INSERT OR IGNORE INTO skill_tags (skill_name, skill_id, tag) VALUES (?1, ?2, ?3)")
         .map_err(|e| e.to_string())?;
 
-    let rows = stmt
-        .query_map(rusqlite::params![session_id], |row| {
-            Ok(AgentRunRecord {
-                agent_id: row.get(0)?,
-                skill_name: row.get(1)?,
-                step_id: row.get(2)?,
-                model: row.get(3)?,
-                status: row.get(4)?,
-                input_tokens: row.get(5)?,
-                output_tokens: row.get(6)?,
-                cache_read_tokens: row.get(7)?,
-                cache_write_tokens: row.get(8)?,
-                total_cost: row.get(9)?,
-                duration_ms: row.get(10)?,
-                num_turns: row.get(11)?,
-                stop_reason: row.get(12)?,
-                duration_api_ms: row.get(13)?,
-                tool_use_count: row.get(14)?,
-                compaction_count: row.get(15)?,
-                session_id: row.get(16)?,
-                started_at: row.get(17)?,
-                completed_at: row.get(18)?,
-            })
-        })
-        .map_err(|e| e.to_string())?;
+    for tag in tags {
+        let normalized = normalize_tag(tag);
+        if !normalized.is_empty() {
+            let canonical = resolve_tag_alias(conn, &normalized)?;
+            stmt.execute(rusqlite::params![skill_name, s_id, canonical])
+                .map_err(|e| e.to_string())?;
+        }
+    }
 
-    rows.collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())
+    emit_skill_updated(conn, skill_name);
+    Ok(())
 }
 
-pub fn get_step_agent_runs(
-    conn: &Connection,
-    skill_name: &str,
-    step_id: i32,
-) -> Result<Vec<AgentRunRecord>, String> {
-    let wr_id = match get_workflow_run_id(conn, skill_name)? {
-        Some(id) => id,
-        None => return Ok(vec![]),
-    };
+/// Lowercase a tag and trim each `/`-separated level, dropping empty levels:
+/// `" Finance / Revenue/ "` becomes `finance/revenue`.
+pub(crate) fn normalize_tag(tag: &str) -> String {
+    tag.split('/')
+        .map(|level| level.trim().to_lowercase())
+        .filter(|level| !level.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
 
-    let mut stmt = conn
-        .prepare(
-            "SELECT agent_id, skill_name, step_id, model, status,
-                    COALESCE(input_tokens, 0), COALESCE(output_tokens, 0),
-                    COALESCE(cache_read_tokens, 0), COALESCE(cache_write_tokens, 0),
-                    COALESCE(total_cost, 0.0), COALESCE(duration_ms, 0),
-                    COALESCE(num_turns, 0), stop_reason, duration_api_ms,
-                    COALESCE(tool_use_count, 0), COALESCE(compaction_count, 0),
-                    session_id, started_at, completed_at
-             FROM agent_runs
-             WHERE workflow_run_id = ?1 AND step_id = ?2
-               AND status IN ('completed', 'error')
-               AND reset_marker IS NULL
-             ORDER BY completed_at DESC",
+/// The canonical form of a normalized tag. An alias applies to the tag and
+/// everything under it, so with `sfdc` → `salesforce`, `sfdc/objects` becomes
+/// `salesforce/objects`. The longest matching alias wins.
+fn resolve_tag_alias(conn: &Connection, tag: &str) -> Result<String, String> {
+    let levels: Vec<&str> = tag.split('/').collect();
+    for depth in (1..=levels.len()).rev() {
+        let prefix = levels[..depth].join("/");
+        let target: Option<String> = conn
+            .query_row(
+                "SELECT tag FROM tag_aliases WHERE alias = ?1",
+                rusqlite::params![prefix],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?;
+        if let Some(target) = target {
+            return Ok(std::iter::once(target.as_str())
+                .chain(levels[depth..].iter().copied())
+                .collect::<Vec<_>>()
+                .join("/"));
+        }
+    }
+    Ok(tag.to_string())
+}
+
+/// SQL condition matching `tag` against `?1` and everything under it.
+const TAG_SUBTREE_CLAUSE: &str = "(tag = ?1 OR substr(tag, 1, length(?1) + 1) = ?1 || '/')";
+
+/// Move `from` and the tags under it to `to`, then make `from` an alias of
+/// `to` so later writes land on the new name. Returns the skills retagged.
+fn move_tag(conn: &Connection, from: &str, to: &str) -> Result<Vec<String>, String> {
+    let rows: Vec<(String, Option<i64>, String)> = conn
+        .prepare(&format!(
+            "SELECT skill_name, skill_id, tag FROM skill_tags WHERE {TAG_SUBTREE_CLAUSE}"
+        ))
+        .map_err(|e| e.to_string())?
+        .query_map(rusqlite::params![from], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    let mut skills = Vec::new();
+    for (skill_name, skill_id, tag) in rows {
+        let moved = format!("{}{}", to, &tag[from.len()..]);
+        conn.execute(
+            "INSERT OR IGNORE INTO skill_tags (skill_name, skill_id, tag) VALUES (?1, ?2, ?3)",
+            rusqlite::params![skill_name, skill_id, moved],
         )
         .map_err(|e| e.to_string())?;
-
-    let rows = stmt
-        .query_map(rusqlite::params![wr_id, step_id], |row| {
-            Ok(AgentRunRecord {
-                agent_id: row.get(0)?,
-                skill_name: row.get(1)?,
-                step_id: row.get(2)?,
-                model: row.get(3)?,
-                status: row.get(4)?,
-                input_tokens: row.get(5)?,
-                output_tokens: row.get(6)?,
-                cache_read_tokens: row.get(7)?,
-                cache_write_tokens: row.get(8)?,
-                total_cost: row.get(9)?,
-                duration_ms: row.get(10)?,
-                num_turns: row.get(11)?,
-                stop_reason: row.get(12)?,
-                duration_api_ms: row.get(13)?,
-                tool_use_count: row.get(14)?,
-                compaction_count: row.get(15)?,
-                session_id: row.get(16)?,
-                started_at: row.get(17)?,
-                completed_at: row.get(18)?,
-            })
-        })
+        conn.execute(
+            "DELETE FROM skill_tags WHERE skill_name = ?1 AND tag = ?2",
+            rusqlite::params![skill_name, tag],
+        )
+        .map_err(|e| e.to_string())?;
+        if !skills.contains(&skill_name) {
+            skills.push(skill_name);
+        }
+    }
+    // Keep aliases one hop from a canonical tag: repoint the ones into the
+    // moved subtree, and `to` itself can no longer be an alias.
+    conn.execute(
+        &format!("UPDATE tag_aliases SET tag = ?2 || substr(tag, length(?1) + 1) WHERE {TAG_SUBTREE_CLAUSE}"),
+        rusqlite::params![from, to],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM tag_aliases WHERE alias = ?1", rusqlite::params![to])
         .map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO tag_aliases (alias, tag) VALUES (?1, ?2)",
+        rusqlite::params![from, to],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(skills)
+}
 
-    rows.collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())
+fn tag_in_use(conn: &Connection, tag: &str) -> Result<bool, String> {
+    conn.query_row(
+        &format!("SELECT EXISTS(SELECT 1 FROM skill_tags WHERE {TAG_SUBTREE_CLAUSE})"),
+        rusqlite::params![tag],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
 }
 
-pub fn get_usage_by_step(
-    conn: &Connection,
-    hide_cancelled: bool,
-    start_date: Option<&str>,
-    skill_name: Option<&str>,
-) -> Result<Vec<UsageByStep>, String> {
-    let cost_clause = if hide_cancelled {
-        " AND total_cost > 0"
-    } else {
-        ""
-    };
-    let mut p = 1usize;
-    let date_clause = if start_date.is_some() {
-        let s = format!(" AND started_at >= ?{p}");
-        p += 1;
-        s
-    } else {
-        String::new()
-    };
-    let skill_clause = if skill_name.is_some() {
-        format!(" AND skill_name = ?{p}")
-    } else {
-        String::new()
-    };
-    let sql = format!(
-        "SELECT step_id, COALESCE(SUM(total_cost), 0.0), COUNT(*)
-         FROM agent_runs
-         WHERE reset_marker IS NULL
-           AND workflow_session_id IS NOT NULL{cost_clause}{date_clause}{skill_clause}
-         GROUP BY step_id
-         ORDER BY SUM(total_cost) DESC"
-    );
-    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
-    macro_rules! collect_rows {
-        ($params:expr) => {
-            stmt.query_map($params, |row| {
-                let sid: i32 = row.get(0)?;
-                Ok(UsageByStep {
-                    step_id: sid,
-                    step_name: step_name(sid),
-                    total_cost: row.get(1)?,
-                    run_count: row.get(2)?,
-                })
-            })
-            .map_err(|e| e.to_string())?
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| e.to_string())
-        };
+/// Rename a tag and the tags under it. The old name becomes an alias of the
+/// new one. Fails if the new name is already in use; merge instead.
+pub fn rename_tag(conn: &Connection, from: &str, to: &str) -> Result<Vec<String>, String> {
+    let (from, to) = (normalize_tag(from), normalize_tag(to));
+    if from.is_empty() || to.is_empty() {
+        return Err("Tag names cannot be empty".to_string());
     }
-    match (start_date, skill_name) {
-        (Some(sd), Some(sn)) => collect_rows!(rusqlite::params![sd, sn]),
-        (Some(sd), None) => collect_rows!(rusqlite::params![sd]),
-        (None, Some(sn)) => collect_rows!(rusqlite::params![sn]),
-        (None, None) => collect_rows!([]),
+    if from == to {
+        return Err(format!("'{}' already has that name", from));
     }
+    if to.starts_with(&format!("{}/", from)) {
+        return Err(format!("Cannot move '{}' under itself", from));
+    }
+    if !tag_in_use(conn, &from)? {
+        return Err(format!("Tag '{}' not found", from));
+    }
+    if tag_in_use(conn, &to)? {
+        return Err(format!("Tag '{}' already exists; merge the tags instead", to));
+    }
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let skills = move_tag(&tx, &from, &to)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    for skill_name in &skills {
+        emit_skill_updated(conn, skill_name);
+    }
+    Ok(skills)
 }
 
-pub fn get_usage_by_model(
-    conn: &Connection,
-    hide_cancelled: bool,
-    start_date: Option<&str>,
-    skill_name: Option<&str>,
-) -> Result<Vec<UsageByModel>, String> {
-    let cost_clause = if hide_cancelled {
-        " AND total_cost > 0"
-    } else {
-        ""
-    };
-    let mut p = 1usize;
-    let date_clause = if start_date.is_some() {
-        let s = format!(" AND started_at >= ?{p}");
-        p += 1;
-        s
-    } else {
-        String::new()
-    };
-    let skill_clause = if skill_name.is_some() {
-        format!(" AND skill_name = ?{p}")
-    } else {
-        String::new()
-    };
-    let sql = format!(
-        "SELECT
-           CASE
-             WHEN lower(model) LIKE '%haiku%' THEN 'Haiku'
-             WHEN lower(model) LIKE '%opus%'  THEN 'Opus'
-             WHEN lower(model) LIKE '%sonnet%' THEN 'Sonnet'
-             ELSE model
-           END AS model_family,
-           COALESCE(SUM(total_cost), 0.0), COUNT(*)
-         FROM agent_runs
-         WHERE reset_marker IS NULL
-           AND workflow_session_id IS NOT NULL{cost_clause}{date_clause}{skill_clause}
-         GROUP BY model_family
-         ORDER BY SUM(total_cost) DESC"
-    );
-    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
-    macro_rules! collect_rows {
-        ($params:expr) => {
-            stmt.query_map($params, |row| {
-                Ok(UsageByModel {
-                    model: row.get(0)?,
-                    total_cost: row.get(1)?,
-                    run_count: row.get(2)?,
-                })
-            })
-            .map_err(|e| e.to_string())?
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| e.to_string())
-        };
+/// Fold `sources` (and the tags under them) into `target`, recording each
+/// source as an alias of `target`. A source no skill uses only gains the
+/// alias, which is how a new alias is defined. Returns the skills retagged.
+pub fn merge_tags(conn: &Connection, sources: &[String], target: &str) -> Result<Vec<String>, String> {
+    let target = normalize_tag(target);
+    if target.is_empty() {
+        return Err("Target tag cannot be empty".to_string());
+    }
+    let target = resolve_tag_alias(conn, &target)?;
+    let mut seen = std::collections::HashSet::new();
+    let sources: Vec<String> = sources
+        .iter()
+        .map(|s| normalize_tag(s))
+        .filter(|s| !s.is_empty() && *s != target && seen.insert(s.clone()))
+        .collect();
+    if sources.is_empty() {
+        return Err("Select at least one tag to merge into another".to_string());
+    }
+    if let Some(source) = sources.iter().find(|s| target.starts_with(&format!("{}/", s))) {
+        return Err(format!("Cannot merge '{}' into a tag under it", source));
+    }
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let mut skills: Vec<String> = Vec::new();
+    for source in &sources {
+        for skill_name in move_tag(&tx, source, &target)? {
+            if !skills.contains(&skill_name) {
+                skills.push(skill_name);
+            }
+        }
     }
-    match (start_date, skill_name) {
-        (Some(sd), Some(sn)) => collect_rows!(rusqlite::params![sd, sn]),
-        (Some(sd), None) => collect_rows!(rusqlite::params![sd]),
-        (None, Some(sn)) => collect_rows!(rusqlite::params![sn]),
-        (None, None) => collect_rows!([]),
+    tx.commit().map_err(|e| e.to_string())?;
+    for skill_name in &skills {
+        emit_skill_updated(conn, skill_name);
+    }
+    Ok(skills)
+}
+
+pub fn list_tag_aliases(conn: &Connection) -> Result<Vec<TagAlias>, String> {
+    let mut stmt = conn
+        .prepare("SELECT alias, tag FROM tag_aliases ORDER BY alias")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| Ok(TagAlias { alias: row.get(0)?, tag: row.get(1)? }))
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+/// Stop resolving `alias`. Skills already retagged keep the canonical tag.
+pub fn remove_tag_alias(conn: &Connection, alias: &str) -> Result<(), String> {
+    let removed = conn
+        .execute(
+            "DELETE FROM tag_aliases WHERE alias = ?1",
+            rusqlite::params![normalize_tag(alias)],
+        )
+        .map_err(|e| e.to_string())?;
+    if removed == 0 {
+        return Err(format!("No alias '{}'", alias));
     }
+    Ok(())
 }
 
-pub fn get_usage_by_day(
-    conn: &Connection,
-    hide_cancelled: bool,
-    start_date: Option<&str>,
-    skill_name: Option<&str>,
-) -> Result<Vec<crate::types::UsageByDay>, String> {
-    let mut p = 1usize;
-    let date_clause = if start_date.is_some() {
-        let s = format!(" AND ws.started_at >= ?{p}");
-        p += 1;
-        s
-    } else {
-        String::new()
-    };
-    let skill_clause = if skill_name.is_some() {
-        format!(" AND ws.skill_name = ?{p}")
-    } else {
-        String::new()
-    };
-    let having_clause = if hide_cancelled {
-        " HAVING COALESCE(SUM(ar.total_cost), 0) > 0"
-    } else {
-        ""
-    };
-    let sql = format!(
-        "SELECT DATE(ws.started_at),
-                COALESCE(SUM(ar.total_cost), 0.0),
-                COALESCE(SUM(ar.input_tokens + ar.output_tokens), 0),
-                COUNT(DISTINCT ws.session_id)
-         FROM workflow_sessions ws
-         LEFT JOIN agent_runs ar ON ar.workflow_session_id = ws.session_id
-                                AND ar.reset_marker IS NULL
-         WHERE ws.reset_marker IS NULL{date_clause}{skill_clause}
-         GROUP BY DATE(ws.started_at){having_clause}
-         ORDER BY DATE(ws.started_at) ASC"
-    );
-    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
-    macro_rules! collect_rows {
-        ($params:expr) => {
-            stmt.query_map($params, |row| {
-                Ok(crate::types::UsageByDay {
-                    date: row.get(0)?,
-                    total_cost: row.get(1)?,
-                    total_tokens: row.get(2)?,
-                    run_count: row.get(3)?,
-                })
+/// Tags as a tree split on `/`, with parents that no skill carries directly
+/// filled in. `skill_count` counts skills tagged with the node or anything
+/// under it.
+pub fn get_tag_tree(conn: &Connection) -> Result<TagTree, String> {
+    let mut stmt = conn
+        .prepare("SELECT tag, skill_name FROM skill_tags ORDER BY tag")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    #[derive(Default)]
+    struct Branch {
+        direct: std::collections::BTreeSet<String>,
+        all: std::collections::BTreeSet<String>,
+        children: std::collections::BTreeMap<String, Branch>,
+    }
+    fn into_nodes(children: std::collections::BTreeMap<String, Branch>, parent: &str) -> Vec<TagNode> {
+        children
+            .into_iter()
+            .map(|(name, branch)| {
+                let path = if parent.is_empty() { name.clone() } else { format!("{}/{}", parent, name) };
+                TagNode {
+                    children: into_nodes(branch.children, &path),
+                    name,
+                    path,
+                    direct_count: branch.direct.len() as u32,
+                    skill_count: branch.all.len() as u32,
+                }
             })
-            .map_err(|e| e.to_string())?
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| e.to_string())
-        };
+            .collect()
+    }
+
+    let mut root = Branch::default();
+    for (tag, skill_name) in rows {
+        let mut branch = &mut root;
+        let levels: Vec<&str> = tag.split('/').collect();
+        for (i, level) in levels.iter().enumerate() {
+            branch = branch.children.entry(level.to_string()).or_default();
+            branch.all.insert(skill_name.clone());
+            if i == levels.len() - 1 {
+                branch.direct.insert(skill_name.clone());
+            }
+        }
     }
-    match (start_date, skill_name) {
-        (Some(sd), Some(sn)) => collect_rows!(rusqlite::params![sd, sn]),
-        (Some(sd), None) => collect_rows!(rusqlite::params![sd]),
-        (None, Some(sn)) => collect_rows!(rusqlite::params![sn]),
-        (None, None) => collect_rows!([]),
+    Ok(TagTree {
+        tags: into_nodes(root.children, ""),
+        aliases: list_tag_aliases(conn)?,
+        warnings: Vec::new(),
+    })
+}
+
+pub fn get_all_tags(conn: &Connection) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT DISTINCT tag FROM skill_tags ORDER BY tag")
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+// --- Imported Skills ---
+
+/// Read SKILL.md frontmatter from disk and populate `description`
+/// on an ImportedSkill struct. This field is not stored in the DB.
+pub fn hydrate_skill_metadata(skill: &mut ImportedSkill) {
+    let skill_md_path = std::path::Path::new(&skill.disk_path).join("SKILL.md");
+    if let Ok(content) = fs::read_to_string(&skill_md_path) {
+        let fm = crate::commands::imported_skills::parse_frontmatter_full(&content);
+        skill.description = fm.description;
     }
 }
 
-pub fn reset_usage(conn: &Connection) -> Result<(), String> {
+#[allow(dead_code)]
+pub fn insert_imported_skill(conn: &Connection, skill: &ImportedSkill) -> Result<(), String> {
+    let skill_master_id = get_skill_master_id(conn, &skill.skill_name)?;
     conn.execute(
-        "UPDATE agent_runs SET reset_marker = datetime('now') || 'Z' WHERE reset_marker IS NULL",
-        [],
+        "INSERT INTO imported_skills (skill_id, skill_name, is_active, disk_path, imported_at, is_bundled,
+             purpose, version, model, argument_hint, user_invocable, disable_model_invocation, skill_master_id, marketplace_source_url)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        rusqlite::params![
+            skill.skill_id,
+            skill.skill_name,
+            skill.is_active as i32,
+            crate::skill_paths::to_stored(
+                crate::skill_paths::root_dir(conn, SkillRoot::Library).as_deref(),
+                &skill.disk_path,
+            ),
+            skill.imported_at,
+            skill.is_bundled as i32,
+            skill.purpose,
+            skill.version,
+            skill.model,
+            skill.argument_hint,
+            skill.user_invocable.map(|v| v as i32),
+            skill.disable_model_invocation.map(|v| v as i32),
+            skill_master_id,
+            skill.marketplace_source_url,
+        ],
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE") {
+            format!("Skill '{}' has already been imported", skill.skill_name)
+        } else {
+            e.to_string()
+        }
+    })?;
+    audit_library_state(conn, LIBRARY_SOURCE_MARKETPLACE, &skill.skill_name, "installed");
+    Ok(())
+}
+
+/// Upsert a marketplace-imported skill. Uses `INSERT OR REPLACE` so that re-importing
+/// (e.g. after the skills_path setting changed or files were manually deleted) always
+/// updates the existing record rather than failing with a UNIQUE constraint.
+/// Also mirrors frontmatter fields to the `skills` master table (canonical store).
+pub fn upsert_imported_skill(conn: &Connection, skill: &ImportedSkill) -> Result<(), String> {
+    let skill_master_id = get_skill_master_id(conn, &skill.skill_name)?;
+    conn.execute(
+        "INSERT INTO imported_skills (skill_id, skill_name, is_active, disk_path, imported_at, is_bundled,
+             purpose, version, model, argument_hint, user_invocable, disable_model_invocation, skill_master_id, marketplace_source_url)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+         ON CONFLICT(skill_name) DO UPDATE SET
+             skill_id = excluded.skill_id,
+             disk_path = excluded.disk_path,
+             imported_at = excluded.imported_at,
+             purpose = excluded.purpose,
+             version = excluded.version,
+             model = excluded.model,
+             argument_hint = excluded.argument_hint,
+             user_invocable = excluded.user_invocable,
+             disable_model_invocation = excluded.disable_model_invocation,
+             skill_master_id = excluded.skill_master_id,
+             marketplace_source_url = excluded.marketplace_source_url",
+        rusqlite::params![
+            skill.skill_id,
+            skill.skill_name,
+            skill.is_active as i32,
+            crate::skill_paths::to_stored(
+                crate::skill_paths::root_dir(conn, SkillRoot::Library).as_deref(),
+                &skill.disk_path,
+            ),
+            skill.imported_at,
+            skill.is_bundled as i32,
+            skill.purpose,
+            skill.version,
+            skill.model,
+            skill.argument_hint,
+            skill.user_invocable.map(|v| v as i32),
+            skill.disable_model_invocation.map(|v| v as i32),
+            skill_master_id,
+            skill.marketplace_source_url,
+        ],
     )
     .map_err(|e| e.to_string())?;
+
+    // Mirror frontmatter fields to skills master — these values are the merged result
+    // (new frontmatter wins if non-empty, installed value as fallback) so we overwrite directly.
     conn.execute(
-        "UPDATE workflow_sessions SET reset_marker = datetime('now') || 'Z' WHERE reset_marker IS NULL",
-        [],
+        "UPDATE skills SET
+            version = ?2,
+            model = ?3,
+            argument_hint = ?4,
+            user_invocable = ?5,
+            disable_model_invocation = ?6,
+            updated_at = datetime('now')
+         WHERE name = ?1",
+        rusqlite::params![
+            skill.skill_name,
+            skill.version,
+            skill.model,
+            skill.argument_hint,
+            skill.user_invocable.map(|v| v as i32),
+            skill.disable_model_invocation.map(|v| v as i32),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    audit_library_state(conn, LIBRARY_SOURCE_MARKETPLACE, &skill.skill_name, "installed");
+    Ok(())
+}
+
+pub fn update_imported_skill_active(
+    conn: &Connection,
+    skill_name: &str,
+    is_active: bool,
+    new_disk_path: &str,
+) -> Result<(), String> {
+    let s_id = get_skill_master_id(conn, skill_name)?
+        .ok_or_else(|| format!("Skill '{}' not found in skills master", skill_name))?;
+
+    let rows = conn
+        .execute(
+            "UPDATE imported_skills SET is_active = ?1, disk_path = ?2 WHERE skill_master_id = ?3",
+            rusqlite::params![
+                is_active as i32,
+                crate::skill_paths::to_stored(
+                    crate::skill_paths::root_dir(conn, SkillRoot::Library).as_deref(),
+                    new_disk_path,
+                ),
+                s_id,
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+
+    if rows == 0 {
+        return Err(format!("Imported skill '{}' not found", skill_name));
+    }
+    let event = if is_active { "activated" } else { "deactivated" };
+    audit_library_state(conn, LIBRARY_SOURCE_MARKETPLACE, skill_name, event);
+    Ok(())
+}
+
+#[allow(dead_code)]
+pub fn delete_imported_skill(conn: &Connection, skill_name: &str) -> Result<(), String> {
+    let s_id = match get_skill_master_id(conn, skill_name)? {
+        Some(id) => id,
+        None => return Ok(()), // Skill not in library — nothing to delete
+    };
+    conn.execute(
+        "DELETE FROM imported_skills WHERE skill_master_id = ?1",
+        rusqlite::params![s_id],
     )
     .map_err(|e| e.to_string())?;
+    audit_library_removal(conn, LIBRARY_SOURCE_MARKETPLACE, skill_name);
+    Ok(())
+}
+
+pub fn delete_imported_skill_by_name(conn: &Connection, name: &str) -> Result<(), String> {
+    log::debug!("delete_imported_skill_by_name: name={}", name);
+    let s_id = match get_skill_master_id(conn, name)? {
+        Some(id) => id,
+        None => return Ok(()), // Skill not in library — nothing to delete
+    };
+    conn.execute(
+        "DELETE FROM imported_skills WHERE skill_master_id = ?1",
+        rusqlite::params![s_id],
+    )
+    .map_err(|e| {
+        log::error!(
+            "delete_imported_skill_by_name: failed to delete '{}': {}",
+            name,
+            e
+        );
+        e.to_string()
+    })?;
+    audit_library_removal(conn, LIBRARY_SOURCE_MARKETPLACE, name);
     Ok(())
 }
 
-pub fn read_settings(conn: &Connection) -> Result<AppSettings, String> {
+pub fn get_imported_skill(
+    conn: &Connection,
+    skill_name: &str,
+) -> Result<Option<ImportedSkill>, String> {
+    let s_id = match get_skill_master_id(conn, skill_name)? {
+        Some(id) => id,
+        None => return Ok(None),
+    };
+
     let mut stmt = conn
-        .prepare("SELECT value FROM settings WHERE key = ?1")
+        .prepare(
+            "SELECT skill_id, skill_name, is_active, disk_path, imported_at, is_bundled,
+                    purpose, version, model, argument_hint, user_invocable, disable_model_invocation, marketplace_source_url
+             FROM imported_skills WHERE skill_master_id = ?1",
+        )
         .map_err(|e| e.to_string())?;
 
-    let result: Result<String, _> = stmt.query_row(["app_settings"], |row| row.get(0));
+    let result = stmt.query_row(rusqlite::params![s_id], |row| {
+        Ok(ImportedSkill {
+            skill_id: row.get(0)?,
+            skill_name: row.get(1)?,
+            is_active: row.get::<_, i32>(2)? != 0,
+            disk_path: row.get(3)?,
+            imported_at: row.get(4)?,
+            is_bundled: row.get::<_, i32>(5)? != 0,
+            description: None,
+            purpose: row.get(6)?,
+            version: row.get(7)?,
+            model: row.get(8)?,
+            argument_hint: row.get(9)?,
+            user_invocable: row.get::<_, Option<i32>>(10)?.map(|v| v != 0),
+            disable_model_invocation: row.get::<_, Option<i32>>(11)?.map(|v| v != 0),
+            marketplace_source_url: row.get(12)?,
+        })
+    });
 
     match result {
-        Ok(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
-        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(AppSettings::default()),
+        Ok(mut skill) => {
+            let root = crate::skill_paths::root_dir(conn, SkillRoot::Library);
+            skill.disk_path = crate::skill_paths::resolve(root.as_deref(), &skill.disk_path);
+            hydrate_skill_metadata(&mut skill);
+            Ok(Some(skill))
+        }
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
         Err(e) => Err(e.to_string()),
     }
 }
 
-/// Read settings (including secrets stored directly in SQLite).
-///
-/// Alias for `read_settings()` — kept for call-site compatibility.
-pub fn read_settings_hydrated(conn: &Connection) -> Result<AppSettings, String> {
-    read_settings(conn)
+#[allow(dead_code)]
+pub fn list_active_skills(conn: &Connection) -> Result<Vec<ImportedSkill>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT skill_id, skill_name, is_active, disk_path, imported_at, is_bundled,
+                    purpose, version, model, argument_hint, user_invocable, disable_model_invocation, marketplace_source_url
+             FROM imported_skills
+             WHERE is_active = 1
+             ORDER BY skill_name",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(ImportedSkill {
+                skill_id: row.get(0)?,
+                skill_name: row.get(1)?,
+                is_active: row.get::<_, i32>(2)? != 0,
+                disk_path: row.get(3)?,
+                imported_at: row.get(4)?,
+                is_bundled: row.get::<_, i32>(5)? != 0,
+                description: None,
+                purpose: row.get(6)?,
+                version: row.get(7)?,
+                model: row.get(8)?,
+                argument_hint: row.get(9)?,
+                user_invocable: row.get::<_, Option<i32>>(10)?.map(|v| v != 0),
+                disable_model_invocation: row.get::<_, Option<i32>>(11)?.map(|v| v != 0),
+                marketplace_source_url: row.get(12)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut skills: Vec<ImportedSkill> = rows
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let root = crate::skill_paths::root_dir(conn, SkillRoot::Library);
+    for skill in &mut skills {
+        skill.disk_path = crate::skill_paths::resolve(root.as_deref(), &skill.disk_path);
+        hydrate_skill_metadata(skill);
+    }
+
+    Ok(skills)
 }
 
-pub fn write_settings(conn: &Connection, settings: &AppSettings) -> Result<(), String> {
-    let json = serde_json::to_string(settings).map_err(|e| e.to_string())?;
+// --- Workspace Skills (Settings → Skills tab) ---
+
+const WS_COLUMNS: &str = "skill_id, skill_name, description, is_active, is_bundled, disk_path, imported_at, purpose, version, model, argument_hint, user_invocable, disable_model_invocation, marketplace_source_url";
+
+fn ws_params(conn: &Connection, skill: &WorkspaceSkill) -> [rusqlite::types::Value; 14] {
+    use rusqlite::types::Value;
+    let root = crate::skill_paths::root_dir(conn, SkillRoot::Workspace);
+    [
+        Value::Text(skill.skill_id.clone()),
+        Value::Text(skill.skill_name.clone()),
+        skill
+            .description
+            .as_ref()
+            .map_or(Value::Null, |v| Value::Text(v.clone())),
+        Value::Integer(skill.is_active as i64),
+        Value::Integer(skill.is_bundled as i64),
+        Value::Text(crate::skill_paths::to_stored(root.as_deref(), &skill.disk_path)),
+        Value::Text(skill.imported_at.clone()),
+        skill
+            .purpose
+            .as_ref()
+            .map_or(Value::Null, |v| Value::Text(v.clone())),
+        skill
+            .version
+            .as_ref()
+            .map_or(Value::Null, |v| Value::Text(v.clone())),
+        skill
+            .model
+            .as_ref()
+            .map_or(Value::Null, |v| Value::Text(v.clone())),
+        skill
+            .argument_hint
+            .as_ref()
+            .map_or(Value::Null, |v| Value::Text(v.clone())),
+        skill
+            .user_invocable
+            .map_or(Value::Null, |b| Value::Integer(b as i64)),
+        skill
+            .disable_model_invocation
+            .map_or(Value::Null, |b| Value::Integer(b as i64)),
+        skill
+            .marketplace_source_url
+            .as_ref()
+            .map_or(Value::Null, |v| Value::Text(v.clone())),
+    ]
+}
+
+pub fn insert_workspace_skill(conn: &Connection, skill: &WorkspaceSkill) -> Result<(), String> {
     conn.execute(
-        "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
-        ["app_settings", &json],
-    )
-    .map_err(|e| e.to_string())?;
+        &format!("INSERT INTO workspace_skills ({WS_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)"),
+        rusqlite::params_from_iter(ws_params(conn, skill)),
+    ).map_err(|e| {
+        if e.to_string().contains("UNIQUE") {
+            format!("Skill '{}' has already been imported", skill.skill_name)
+        } else {
+            format!("insert_workspace_skill: {}", e)
+        }
+    })?;
+    audit_library_state(conn, LIBRARY_SOURCE_WORKSPACE, &skill.skill_name, "installed");
     Ok(())
 }
 
-// --- Skills Master ---
-
-/// Upsert a row in the `skills` master table. Used by `save_workflow_run` (skill-builder)
-/// and marketplace import. Returns the skill id.
-pub fn upsert_skill(
-    conn: &Connection,
-    name: &str,
-    skill_source: &str,
-    purpose: &str,
-) -> Result<i64, String> {
-    log::debug!("upsert_skill: name={} skill_source={}", name, skill_source);
+pub fn upsert_workspace_skill(conn: &Connection, skill: &WorkspaceSkill) -> Result<(), String> {
     conn.execute(
-        "INSERT INTO skills (name, skill_source, purpose, updated_at)
-         VALUES (?1, ?2, ?3, datetime('now'))
-         ON CONFLICT(name) DO UPDATE SET
-             purpose = ?3, updated_at = datetime('now'), deleted_at = NULL",
-        rusqlite::params![name, skill_source, purpose],
+        &format!(
+            "INSERT INTO workspace_skills ({WS_COLUMNS})
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+             ON CONFLICT(skill_name) DO UPDATE SET
+                 description = excluded.description,
+                 is_bundled = excluded.is_bundled,
+                 disk_path = excluded.disk_path,
+                 purpose = excluded.purpose,
+                 version = excluded.version,
+                 model = excluded.model,
+                 argument_hint = excluded.argument_hint,
+                 user_invocable = excluded.user_invocable,
+                 disable_model_invocation = excluded.disable_model_invocation,
+                 marketplace_source_url = excluded.marketplace_source_url"
+        ),
+        rusqlite::params_from_iter(ws_params(conn, skill)),
     )
-    .map_err(|e| {
-        log::error!("upsert_skill: failed to upsert '{}': {}", name, e);
-        e.to_string()
-    })?;
-    let id: i64 = conn
-        .query_row(
-            "SELECT id FROM skills WHERE name = ?1",
-            rusqlite::params![name],
-            |row| row.get(0),
-        )
-        .map_err(|e| {
-            log::error!("upsert_skill: failed to retrieve id for '{}': {}", name, e);
-            e.to_string()
-        })?;
-    Ok(id)
+    .map_err(|e| format!("upsert_workspace_skill: {}", e))?;
+    audit_library_state(conn, LIBRARY_SOURCE_WORKSPACE, &skill.skill_name, "installed");
+    Ok(())
 }
 
-/// Like `upsert_skill`, but ALWAYS updates `skill_source` on conflict.
-/// Use this when the caller explicitly wants to set the source (e.g. `resolve_discovery`).
-/// `upsert_skill` intentionally skips `skill_source` on conflict to prevent
-/// `save_workflow_run` from overwriting a marketplace skill's source.
-pub fn upsert_skill_with_source(
+/// Re-seed a bundled skill: overwrites all frontmatter + disk path, preserves is_active.
+pub fn upsert_bundled_workspace_skill(
     conn: &Connection,
-    name: &str,
-    skill_source: &str,
-    purpose: &str,
-) -> Result<i64, String> {
-    log::debug!(
-        "upsert_skill_with_source: name={} skill_source={}",
-        name,
-        skill_source
-    );
+    skill: &WorkspaceSkill,
+) -> Result<(), String> {
     conn.execute(
-        "INSERT INTO skills (name, skill_source, purpose, updated_at)
-         VALUES (?1, ?2, ?3, datetime('now'))
-         ON CONFLICT(name) DO UPDATE SET
-             skill_source = ?2, purpose = ?3, updated_at = datetime('now'), deleted_at = NULL",
-        rusqlite::params![name, skill_source, purpose],
-    )
-    .map_err(|e| {
-        log::error!(
-            "upsert_skill_with_source: failed to upsert '{}': {}",
-            name,
-            e
-        );
-        e.to_string()
-    })?;
-    let id: i64 = conn
-        .query_row(
-            "SELECT id FROM skills WHERE name = ?1",
-            rusqlite::params![name],
-            |row| row.get(0),
-        )
-        .map_err(|e| {
-            log::error!(
-                "upsert_skill_with_source: failed to retrieve id for '{}': {}",
-                name,
-                e
-            );
-            e.to_string()
-        })?;
-    Ok(id)
+        &format!(
+            "INSERT INTO workspace_skills ({WS_COLUMNS})
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+             ON CONFLICT(skill_name) DO UPDATE SET
+                 description = excluded.description,
+                 is_bundled = 1,
+                 disk_path = excluded.disk_path,
+                 version = excluded.version,
+                 model = excluded.model,
+                 argument_hint = excluded.argument_hint,
+                 user_invocable = excluded.user_invocable,
+                 disable_model_invocation = excluded.disable_model_invocation
+                 -- marketplace_source_url intentionally NOT updated: bundled skills always have NULL
+                 -- is_active intentionally NOT updated: preserves user's deactivation
+                 -- purpose intentionally NOT updated: preserves user's purpose setting"
+        ),
+        rusqlite::params_from_iter(ws_params(conn, skill)),
+    ).map_err(|e| format!("upsert_bundled_workspace_skill: {}", e))?;
+    audit_library_state(conn, LIBRARY_SOURCE_WORKSPACE, &skill.skill_name, "installed");
+    Ok(())
+}
+
+fn row_to_workspace_skill(row: &rusqlite::Row, root: Option<&Path>) -> rusqlite::Result<WorkspaceSkill> {
+    let is_active: i64 = row.get(3)?;
+    let is_bundled: i64 = row.get(4)?;
+    let user_invocable: Option<i64> = row.get(11)?;
+    let disable_model_invocation: Option<i64> = row.get(12)?;
+    Ok(WorkspaceSkill {
+        skill_id: row.get(0)?,
+        skill_name: row.get(1)?,
+        description: row.get(2)?,
+        is_active: is_active != 0,
+        is_bundled: is_bundled != 0,
+        disk_path: crate::skill_paths::resolve(root, &row.get::<_, String>(5)?),
+        imported_at: row.get(6)?,
+        purpose: row.get(7)?,
+        version: row.get(8)?,
+        model: row.get(9)?,
+        argument_hint: row.get(10)?,
+        user_invocable: user_invocable.map(|v| v != 0),
+        disable_model_invocation: disable_model_invocation.map(|v| v != 0),
+        marketplace_source_url: row.get(13)?,
+    })
 }
 
-/// List all skills from the master table, ordered by name.
-pub fn list_all_skills(conn: &Connection) -> Result<Vec<SkillMasterRow>, String> {
+pub fn list_workspace_skills(conn: &Connection) -> Result<Vec<WorkspaceSkill>, String> {
+    let root = crate::skill_paths::root_dir(conn, SkillRoot::Workspace);
     let mut stmt = conn
-        .prepare(
-            "SELECT id, name, skill_source, purpose, created_at, updated_at,
-                    description, version, model, argument_hint, user_invocable, disable_model_invocation
-             FROM skills
-             WHERE COALESCE(deleted_at, '') = ''
-             ORDER BY name",
-        )
-        .map_err(|e| {
-            log::error!("list_all_skills: failed to prepare query: {}", e);
-            e.to_string()
-        })?;
+        .prepare(&format!(
+            "SELECT {WS_COLUMNS} FROM workspace_skills ORDER BY imported_at DESC"
+        ))
+        .map_err(|e| format!("list_workspace_skills: {}", e))?;
+    let skills = stmt
+        .query_map([], |row| row_to_workspace_skill(row, root.as_deref()))
+        .map_err(|e| format!("list_workspace_skills query: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("list_workspace_skills collect: {}", e))?;
+    Ok(skills)
+}
 
-    let rows = stmt
-        .query_map([], |row| {
-            Ok(SkillMasterRow {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                skill_source: row.get(2)?,
-                purpose: row.get(3)?,
-                created_at: row.get(4)?,
-                updated_at: row.get(5)?,
-                description: row.get(6)?,
-                version: row.get(7)?,
-                model: row.get(8)?,
-                argument_hint: row.get(9)?,
-                user_invocable: row.get::<_, Option<i32>>(10)?.map(|v| v != 0),
-                disable_model_invocation: row.get::<_, Option<i32>>(11)?.map(|v| v != 0),
-            })
-        })
-        .map_err(|e| {
-            log::error!("list_all_skills: query failed: {}", e);
-            e.to_string()
-        })?;
+pub fn list_workspace_skills_by_source(
+    conn: &Connection,
+    source_url: &str,
+) -> Result<Vec<WorkspaceSkill>, String> {
+    let root = crate::skill_paths::root_dir(conn, SkillRoot::Workspace);
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {WS_COLUMNS} FROM workspace_skills
+             WHERE marketplace_source_url = ?1
+             ORDER BY imported_at DESC"
+        ))
+        .map_err(|e| format!("list_workspace_skills_by_source: {}", e))?;
+    let skills = stmt
+        .query_map(rusqlite::params![source_url], |row| row_to_workspace_skill(row, root.as_deref()))
+        .map_err(|e| format!("list_workspace_skills_by_source query: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("list_workspace_skills_by_source collect: {}", e))?;
+    Ok(skills)
+}
 
-    let result: Vec<SkillMasterRow> = rows.collect::<Result<Vec<_>, _>>().map_err(|e| {
-        log::error!("list_all_skills: failed to collect rows: {}", e);
-        e.to_string()
-    })?;
-    log::debug!("list_all_skills: returning {} skills", result.len());
-    Ok(result)
+pub fn list_active_workspace_skills(conn: &Connection) -> Result<Vec<WorkspaceSkill>, String> {
+    let root = crate::skill_paths::root_dir(conn, SkillRoot::Workspace);
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {WS_COLUMNS} FROM workspace_skills WHERE is_active = 1 ORDER BY skill_name"
+        ))
+        .map_err(|e| format!("list_active_workspace_skills: {}", e))?;
+    let skills = stmt
+        .query_map([], |row| row_to_workspace_skill(row, root.as_deref()))
+        .map_err(|e| format!("list_active_workspace_skills query: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("list_active_workspace_skills collect: {}", e))?;
+    Ok(skills)
 }
 
-/// Delete a skill from the master table by name.
-pub fn delete_skill(conn: &Connection, name: &str) -> Result<(), String> {
-    log::info!("delete_skill: name={}", name);
-    conn.execute(
-        "UPDATE skills
-         SET deleted_at = CASE
-               WHEN deleted_at IS NULL OR deleted_at = '' THEN datetime('now') || 'Z'
-               ELSE deleted_at
-             END,
-             updated_at = datetime('now')
-         WHERE name = ?1",
-        rusqlite::params![name],
-    )
-    .map_err(|e| {
-        log::error!("delete_skill: failed to delete '{}': {}", name, e);
-        e.to_string()
-    })?;
+pub fn update_workspace_skill_active(
+    conn: &Connection,
+    skill_id: &str,
+    is_active: bool,
+    new_disk_path: &str,
+) -> Result<(), String> {
+    let rows = conn
+        .execute(
+            "UPDATE workspace_skills SET is_active = ?1, disk_path = ?2 WHERE skill_id = ?3",
+            rusqlite::params![
+                is_active as i64,
+                crate::skill_paths::to_stored(
+                    crate::skill_paths::root_dir(conn, SkillRoot::Workspace).as_deref(),
+                    new_disk_path,
+                ),
+                skill_id,
+            ],
+        )
+        .map_err(|e| format!("update_workspace_skill_active: {}", e))?;
+    if rows == 0 {
+        return Err(format!("Workspace skill with id '{}' not found", skill_id));
+    }
+    if let Some(name) = workspace_skill_name(conn, skill_id) {
+        let event = if is_active { "activated" } else { "deactivated" };
+        audit_library_state(conn, LIBRARY_SOURCE_WORKSPACE, &name, event);
+    }
     Ok(())
 }
 
-/// Get the `workflow_runs.id` integer for a given `skill_name`. Returns None if not found.
-pub fn get_workflow_run_id(conn: &Connection, skill_name: &str) -> Result<Option<i64>, String> {
-    conn.query_row(
-        "SELECT id FROM workflow_runs WHERE skill_name = ?1",
-        rusqlite::params![skill_name],
-        |row| row.get(0),
+pub fn delete_workspace_skill(conn: &Connection, skill_id: &str) -> Result<(), String> {
+    let name = workspace_skill_name(conn, skill_id);
+    conn.execute(
+        "DELETE FROM workspace_skills WHERE skill_id = ?1",
+        rusqlite::params![skill_id],
     )
-    .optional()
-    .map_err(|e| e.to_string())
+    .map_err(|e| format!("delete_workspace_skill: {}", e))?;
+    if let Some(name) = name {
+        audit_library_removal(conn, LIBRARY_SOURCE_WORKSPACE, &name);
+    }
+    Ok(())
 }
 
-/// Get the `skills.id` integer for a given skill name. Returns None if not found.
-pub fn get_skill_master_id(conn: &Connection, skill_name: &str) -> Result<Option<i64>, String> {
+fn workspace_skill_name(conn: &Connection, skill_id: &str) -> Option<String> {
     conn.query_row(
-        "SELECT id FROM skills WHERE name = ?1",
-        rusqlite::params![skill_name],
+        "SELECT skill_name FROM workspace_skills WHERE skill_id = ?1",
+        [skill_id],
         |row| row.get(0),
     )
-    .optional()
-    .map_err(|e| e.to_string())
+    .ok()
 }
 
-// --- Workflow Run ---
+pub fn get_workspace_skill(
+    conn: &Connection,
+    skill_id: &str,
+) -> Result<Option<WorkspaceSkill>, String> {
+    let root = crate::skill_paths::root_dir(conn, SkillRoot::Workspace);
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {WS_COLUMNS} FROM workspace_skills WHERE skill_id = ?1"
+        ))
+        .map_err(|e| format!("get_workspace_skill: {}", e))?;
+    let mut rows = stmt
+        .query_map(rusqlite::params![skill_id], |row| row_to_workspace_skill(row, root.as_deref()))
+        .map_err(|e| format!("get_workspace_skill query: {}", e))?;
+    match rows.next() {
+        Some(row) => Ok(Some(
+            row.map_err(|e| format!("get_workspace_skill row: {}", e))?,
+        )),
+        None => Ok(None),
+    }
+}
 
-pub fn save_workflow_run(
+/// Look up a workspace skill by name. Used when skill_id is not known.
+pub fn get_workspace_skill_by_name(
     conn: &Connection,
     skill_name: &str,
-    current_step: i32,
-    status: &str,
-    purpose: &str,
-) -> Result<(), String> {
-    // Ensure the skills master row exists (skill-builder source)
-    let skill_id = upsert_skill(conn, skill_name, "skill-builder", purpose)?;
-    conn.execute(
-        "INSERT INTO workflow_runs (skill_name, current_step, status, purpose, skill_id, updated_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, datetime('now') || 'Z')
-         ON CONFLICT(skill_name) DO UPDATE SET
-             current_step = ?2, status = ?3, purpose = ?4, skill_id = ?5, updated_at = datetime('now') || 'Z'",
-        rusqlite::params![skill_name, current_step, status, purpose, skill_id],
-    )
-    .map_err(|e| e.to_string())?;
-    Ok(())
+) -> Result<Option<WorkspaceSkill>, String> {
+    let root = crate::skill_paths::root_dir(conn, SkillRoot::Workspace);
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {WS_COLUMNS} FROM workspace_skills WHERE skill_name = ?1"
+        ))
+        .map_err(|e| format!("get_workspace_skill_by_name: {}", e))?;
+    let mut rows = stmt
+        .query_map(rusqlite::params![skill_name], |row| row_to_workspace_skill(row, root.as_deref()))
+        .map_err(|e| format!("get_workspace_skill_by_name query: {}", e))?;
+    match rows.next() {
+        Some(row) => {
+            Ok(Some(row.map_err(|e| {
+                format!("get_workspace_skill_by_name row: {}", e)
+            })?))
+        }
+        None => Ok(None),
+    }
 }
 
-/// Insert a marketplace skill into the skills master table only. No workflow_runs row.
-/// Replaces `save_marketplace_skill_run` — marketplace skills no longer get workflow_runs rows.
-pub fn save_marketplace_skill(
+/// Look up a workspace skill by name and source registry URL.
+/// Returns only skills that were imported from the specified registry (marketplace_source_url = source_url).
+/// Used to avoid false-positive update notifications for bundled skills sharing a name with marketplace skills.
+#[allow(dead_code)]
+pub fn get_workspace_skill_by_name_and_source(
     conn: &Connection,
     skill_name: &str,
-    purpose: &str,
-) -> Result<(), String> {
-    log::info!("save_marketplace_skill: name={}", skill_name);
-    upsert_skill(conn, skill_name, "marketplace", purpose).map_err(|e| {
-        log::error!("save_marketplace_skill: failed for '{}': {}", skill_name, e);
-        e
-    })?;
-    Ok(())
+    source_url: &str,
+) -> Result<Option<WorkspaceSkill>, String> {
+    let root = crate::skill_paths::root_dir(conn, SkillRoot::Workspace);
+    let mut stmt = conn.prepare(
+        &format!("SELECT {WS_COLUMNS} FROM workspace_skills WHERE skill_name = ?1 AND marketplace_source_url = ?2")
+    ).map_err(|e| format!("get_workspace_skill_by_name_and_source: {}", e))?;
+    let mut rows = stmt
+        .query_map(
+            rusqlite::params![skill_name, source_url],
+            |row| row_to_workspace_skill(row, root.as_deref()),
+        )
+        .map_err(|e| format!("get_workspace_skill_by_name_and_source query: {}", e))?;
+    match rows.next() {
+        Some(row) => Ok(Some(row.map_err(|e| {
+            format!("get_workspace_skill_by_name_and_source row: {}", e)
+        })?)),
+        None => Ok(None),
+    }
 }
 
-pub fn set_skill_author(
+/// Look up an active workspace skill by its purpose tag.
+/// Returns the first active skill with the given purpose, or None if not found.
+pub fn get_workspace_skill_by_purpose(
     conn: &Connection,
-    skill_name: &str,
-    author_login: &str,
-    author_avatar: Option<&str>,
-) -> Result<(), String> {
-    conn.execute(
-        "UPDATE workflow_runs SET author_login = ?2, author_avatar = ?3 WHERE skill_name = ?1",
-        rusqlite::params![skill_name, author_login, author_avatar],
-    )
-    .map_err(|e| e.to_string())?;
-    Ok(())
+    purpose: &str,
+) -> rusqlite::Result<Option<WorkspaceSkill>> {
+    let root = crate::skill_paths::root_dir(conn, SkillRoot::Workspace);
+    let mut stmt = conn.prepare(
+        &format!(
+            "SELECT {WS_COLUMNS} FROM workspace_skills WHERE purpose = ?1 AND is_active = 1 ORDER BY imported_at DESC, skill_name ASC LIMIT 1"
+        )
+    )?;
+    let mut rows = stmt.query_map(rusqlite::params![purpose], |row| row_to_workspace_skill(row, root.as_deref()))?;
+    match rows.next() {
+        Some(row) => Ok(Some(row?)),
+        None => Ok(None),
+    }
 }
 
-#[cfg(test)]
-pub fn set_skill_display_name(
+/// Update the content_hash for a workspace skill row identified by skill_name.
+pub fn set_workspace_skill_content_hash(
     conn: &Connection,
     skill_name: &str,
-    display_name: Option<&str>,
+    hash: &str,
 ) -> Result<(), String> {
     conn.execute(
-        "UPDATE workflow_runs SET display_name = ?2, updated_at = datetime('now') || 'Z' WHERE skill_name = ?1",
-        rusqlite::params![skill_name, display_name],
+        "UPDATE workspace_skills SET content_hash = ?1 WHERE skill_name = ?2",
+        rusqlite::params![hash, skill_name],
     )
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| format!("set_workspace_skill_content_hash: {}", e))?;
     Ok(())
 }
 
-pub fn set_skill_intake(
+/// Update the content_hash for an imported skill row identified by skill_name.
+pub fn set_imported_skill_content_hash(
     conn: &Connection,
     skill_name: &str,
-    intake_json: Option<&str>,
+    hash: &str,
 ) -> Result<(), String> {
     conn.execute(
-        "UPDATE workflow_runs SET intake_json = ?2, updated_at = datetime('now') || 'Z' WHERE skill_name = ?1",
-        rusqlite::params![skill_name, intake_json],
+        "UPDATE imported_skills SET content_hash = ?1 WHERE skill_name = ?2",
+        rusqlite::params![hash, skill_name],
     )
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| format!("set_imported_skill_content_hash: {}", e))?;
     Ok(())
 }
 
-#[allow(clippy::too_many_arguments)]
-pub fn set_skill_behaviour(
+/// Read disk_path and content_hash for a workspace skill by name.
+pub fn get_workspace_skill_hash_info(
     conn: &Connection,
     skill_name: &str,
-    description: Option<&str>,
-    version: Option<&str>,
-    model: Option<&str>,
-    argument_hint: Option<&str>,
-    user_invocable: Option<bool>,
-    disable_model_invocation: Option<bool>,
-) -> Result<(), String> {
-    let user_invocable_i: Option<i32> = user_invocable.map(|v| if v { 1 } else { 0 });
-    let disable_model_invocation_i: Option<i32> =
-        disable_model_invocation.map(|v| if v { 1 } else { 0 });
-
-    // Write to skills master — canonical store for all skill sources
-    conn.execute(
-        "UPDATE skills SET
-            description = COALESCE(?2, description),
-            version = COALESCE(?3, version),
-            model = COALESCE(?4, model),
-            argument_hint = COALESCE(?5, argument_hint),
-            user_invocable = COALESCE(?6, user_invocable),
-            disable_model_invocation = COALESCE(?7, disable_model_invocation),
-            updated_at = datetime('now')
-         WHERE name = ?1",
-        rusqlite::params![
-            skill_name,
-            description,
-            version,
-            model,
-            argument_hint,
-            user_invocable_i,
-            disable_model_invocation_i,
-        ],
-    )
-    .map_err(|e| e.to_string())?;
-
-    // Dual-write to workflow_runs for skill-builder skills (no-op for marketplace/imported).
-    // These columns will be dropped from workflow_runs in a future migration.
-    conn.execute(
-        "UPDATE workflow_runs SET
-            description = COALESCE(?2, description),
-            version = COALESCE(?3, version),
-            model = COALESCE(?4, model),
-            argument_hint = COALESCE(?5, argument_hint),
-            user_invocable = COALESCE(?6, user_invocable),
-            disable_model_invocation = COALESCE(?7, disable_model_invocation),
-            updated_at = datetime('now') || 'Z'
-         WHERE skill_name = ?1",
-        rusqlite::params![
-            skill_name,
-            description,
-            version,
-            model,
-            argument_hint,
-            user_invocable_i,
-            disable_model_invocation_i,
-        ],
-    )
-    .map_err(|e| e.to_string())?;
-
-    Ok(())
+) -> Result<Option<(String, Option<String>)>, String> {
+    let root = crate::skill_paths::root_dir(conn, SkillRoot::Workspace);
+    let mut stmt = conn
+        .prepare("SELECT disk_path, content_hash FROM workspace_skills WHERE skill_name = ?1")
+        .map_err(|e| format!("get_workspace_skill_hash_info: {}", e))?;
+    let mut rows = stmt
+        .query_map(rusqlite::params![skill_name], |row| {
+            Ok((
+                crate::skill_paths::resolve(root.as_deref(), &row.get::<_, String>(0)?),
+                row.get::<_, Option<String>>(1)?,
+            ))
+        })
+        .map_err(|e| format!("get_workspace_skill_hash_info query: {}", e))?;
+    match rows.next() {
+        Some(row) => {
+            Ok(Some(row.map_err(|e| {
+                format!("get_workspace_skill_hash_info row: {}", e)
+            })?))
+        }
+        None => Ok(None),
+    }
 }
 
-pub fn get_workflow_run(
+/// Read disk_path and content_hash for an imported (marketplace/library) skill by name.
+pub fn get_imported_skill_hash_info(
     conn: &Connection,
     skill_name: &str,
-) -> Result<Option<WorkflowRunRow>, String> {
+) -> Result<Option<(String, Option<String>)>, String> {
+    let root = crate::skill_paths::root_dir(conn, SkillRoot::Library);
     let mut stmt = conn
-        .prepare(
-            "SELECT skill_name, current_step, status, purpose, created_at, updated_at, author_login, author_avatar, display_name, intake_json, COALESCE(source, 'created'), description, version, model, argument_hint, user_invocable, disable_model_invocation
-             FROM workflow_runs WHERE skill_name = ?1",
-        )
-        .map_err(|e| e.to_string())?;
+        .prepare("SELECT disk_path, content_hash FROM imported_skills WHERE skill_name = ?1")
+        .map_err(|e| format!("get_imported_skill_hash_info: {}", e))?;
+    let mut rows = stmt
+        .query_map(rusqlite::params![skill_name], |row| {
+            Ok((
+                crate::skill_paths::resolve(root.as_deref(), &row.get::<_, String>(0)?),
+                row.get::<_, Option<String>>(1)?,
+            ))
+        })
+        .map_err(|e| format!("get_imported_skill_hash_info query: {}", e))?;
+    match rows.next() {
+        Some(row) => {
+            Ok(Some(row.map_err(|e| {
+                format!("get_imported_skill_hash_info row: {}", e)
+            })?))
+        }
+        None => Ok(None),
+    }
+}
 
-    let result = stmt.query_row(rusqlite::params![skill_name], |row| {
-        Ok(WorkflowRunRow {
-            skill_name: row.get(0)?,
-            current_step: row.get(1)?,
-            status: row.get(2)?,
-            purpose: row.get(3)?,
-            created_at: row.get(4)?,
-            updated_at: row.get(5)?,
-            author_login: row.get(6)?,
-            author_avatar: row.get(7)?,
-            display_name: row.get(8)?,
-            intake_json: row.get(9)?,
-            source: row.get(10)?,
-            description: row.get(11)?,
-            version: row.get(12)?,
-            model: row.get(13)?,
-            argument_hint: row.get(14)?,
-            user_invocable: row.get::<_, Option<i32>>(15)?.map(|v| v != 0),
-            disable_model_invocation: row.get::<_, Option<i32>>(16)?.map(|v| v != 0),
+/// Look up an imported (library) skill by name and source registry URL.
+/// Returns only skills that were imported from the specified registry (marketplace_source_url = source_url).
+/// Used to avoid false-positive update notifications for bundled skills sharing a name with marketplace skills.
+#[allow(dead_code)]
+pub fn get_imported_skill_by_name_and_source(
+    conn: &Connection,
+    skill_name: &str,
+    source_url: &str,
+) -> Result<Option<ImportedSkill>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT skill_id, skill_name, is_active, disk_path, imported_at, is_bundled,
+                purpose, version, model, argument_hint, user_invocable, disable_model_invocation, marketplace_source_url
+         FROM imported_skills WHERE skill_name = ?1 AND marketplace_source_url = ?2"
+    ).map_err(|e| format!("get_imported_skill_by_name_and_source: {}", e))?;
+
+    let result = stmt.query_row(rusqlite::params![skill_name, source_url], |row| {
+        Ok(ImportedSkill {
+            skill_id: row.get(0)?,
+            skill_name: row.get(1)?,
+            is_active: row.get::<_, i32>(2)? != 0,
+            disk_path: row.get(3)?,
+            imported_at: row.get(4)?,
+            is_bundled: row.get::<_, i32>(5)? != 0,
+            description: None,
+            purpose: row.get(6)?,
+            version: row.get(7)?,
+            model: row.get(8)?,
+            argument_hint: row.get(9)?,
+            user_invocable: row.get::<_, Option<i32>>(10)?.map(|v| v != 0),
+            disable_model_invocation: row.get::<_, Option<i32>>(11)?.map(|v| v != 0),
+            marketplace_source_url: row.get(12)?,
         })
     });
 
     match result {
-        Ok(run) => Ok(Some(run)),
+        Ok(mut skill) => {
+            let root = crate::skill_paths::root_dir(conn, SkillRoot::Library);
+            skill.disk_path = crate::skill_paths::resolve(root.as_deref(), &skill.disk_path);
+            hydrate_skill_metadata(&mut skill);
+            Ok(Some(skill))
+        }
         Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-        Err(e) => Err(e.to_string()),
+        Err(e) => Err(format!("get_imported_skill_by_name_and_source: {}", e)),
     }
 }
 
-pub fn get_purpose(conn: &Connection, skill_name: &str) -> Result<String, String> {
-    get_workflow_run(conn, skill_name).map(|opt| {
-        opt.map(|run| run.purpose)
-            .unwrap_or_else(|| "domain".to_string())
-    })
-}
-
-pub fn list_all_workflow_runs(conn: &Connection) -> Result<Vec<WorkflowRunRow>, String> {
+/// Return the names of all locally installed skills.
+/// Combines workflow_runs (generated/marketplace skills), imported_skills (GitHub imports),
+/// and workspace_skills (Settings → Skills).
+pub fn get_all_installed_skill_names(conn: &Connection) -> Result<Vec<String>, String> {
     let mut stmt = conn
         .prepare(
-            "SELECT skill_name, current_step, status, purpose, created_at, updated_at, author_login, author_avatar, display_name, intake_json, COALESCE(source, 'created'), description, version, model, argument_hint, user_invocable, disable_model_invocation
-             FROM workflow_runs ORDER BY skill_name",
+            "SELECT skill_name FROM workflow_runs
+         UNION
+         SELECT skill_name FROM imported_skills
+         UNION
+         SELECT skill_name FROM workspace_skills",
         )
         .map_err(|e| e.to_string())?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(names)
+}
 
-    let rows = stmt
-        .query_map([], |row| {
-            Ok(WorkflowRunRow {
-                skill_name: row.get(0)?,
-                current_step: row.get(1)?,
-                status: row.get(2)?,
-                purpose: row.get(3)?,
-                created_at: row.get(4)?,
-                updated_at: row.get(5)?,
-                author_login: row.get(6)?,
-                author_avatar: row.get(7)?,
-                display_name: row.get(8)?,
-                intake_json: row.get(9)?,
-                source: row.get(10)?,
-                description: row.get(11)?,
-                version: row.get(12)?,
-                model: row.get(13)?,
-                argument_hint: row.get(14)?,
-                user_invocable: row.get::<_, Option<i32>>(15)?.map(|v| v != 0),
-                disable_model_invocation: row.get::<_, Option<i32>>(16)?.map(|v| v != 0),
-            })
-        })
+/// Return names of all skills in the skills master table.
+/// Used by the skill-library (dashboard) path to check which skills are already installed.
+pub fn get_dashboard_skill_names(conn: &Connection) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT name FROM skills WHERE COALESCE(deleted_at, '') = ''")
         .map_err(|e| e.to_string())?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(names)
+}
+
+// --- Workflow Queue ---
+
+/// Finished jobs kept for display by `list_workflow_queue`.
+const WORKFLOW_QUEUE_HISTORY: i64 = 20;
+
+const WORKFLOW_QUEUE_COLUMNS: &str =
+    "id, skill_name, step_id, workspace_path, status, error, enqueued_at, started_at, finished_at";
+
+fn row_to_workflow_queue_job(row: &rusqlite::Row) -> rusqlite::Result<WorkflowQueueJob> {
+    Ok(WorkflowQueueJob {
+        id: row.get(0)?,
+        skill_name: row.get(1)?,
+        step_id: row.get(2)?,
+        workspace_path: row.get(3)?,
+        status: row.get(4)?,
+        error: row.get(5)?,
+        enqueued_at: row.get(6)?,
+        started_at: row.get(7)?,
+        finished_at: row.get(8)?,
+    })
+}
 
-    rows.collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())
+fn get_workflow_queue_job(conn: &Connection, id: i64) -> Result<Option<WorkflowQueueJob>, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM workflow_queue WHERE id = ?1", WORKFLOW_QUEUE_COLUMNS),
+        [id],
+        row_to_workflow_queue_job,
+    )
+    .optional()
+    .map_err(|e| e.to_string())
 }
 
-pub fn delete_workflow_run(conn: &Connection, skill_name: &str) -> Result<(), String> {
-    // Look up FK ids before deleting the parent rows
-    let wr_id = get_workflow_run_id(conn, skill_name)?
-        .ok_or_else(|| format!("Workflow run not found for skill '{}'", skill_name))?;
-    let s_id = get_skill_master_id(conn, skill_name)?
-        .ok_or_else(|| format!("Skill '{}' not found in skills master", skill_name))?;
+fn emit_workflow_queue_change(conn: &Connection, id: i64) {
+    if !crate::db_events::is_active() {
+        return;
+    }
+    if let Ok(Some(job)) = get_workflow_queue_job(conn, id) {
+        crate::db_events::emit(crate::db_events::DbChange::QueueUpdated { job });
+    }
+}
 
-    // Delete workflow-state child rows by FK columns only.
-    // Usage history tables (agent_runs/workflow_sessions) are intentionally retained.
+pub fn enqueue_workflow_job(
+    conn: &Connection,
+    skill_name: &str,
+    step_id: u32,
+    workspace_path: &str,
+) -> Result<i64, String> {
     conn.execute(
-        "DELETE FROM workflow_artifacts WHERE workflow_run_id = ?1",
-        rusqlite::params![wr_id],
+        "INSERT INTO workflow_queue (skill_name, step_id, workspace_path) VALUES (?1, ?2, ?3)",
+        rusqlite::params![skill_name, step_id, workspace_path],
     )
     .map_err(|e| e.to_string())?;
+    let id = conn.last_insert_rowid();
+    emit_workflow_queue_change(conn, id);
+    Ok(id)
+}
 
-    conn.execute(
-        "DELETE FROM workflow_steps WHERE workflow_run_id = ?1",
-        rusqlite::params![wr_id],
-    )
-    .map_err(|e| e.to_string())?;
+/// Running and queued jobs in run order, then the most recent finished jobs.
+pub fn list_workflow_queue(conn: &Connection) -> Result<Vec<WorkflowQueueJob>, String> {
+    let query = |sql: String, params: &[&dyn rusqlite::ToSql]| -> Result<Vec<WorkflowQueueJob>, String> {
+        let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params, row_to_workflow_queue_job)
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        Ok(rows)
+    };
+    let mut jobs = query(
+        format!(
+            "SELECT {} FROM workflow_queue WHERE status IN ('running', 'queued')
+             ORDER BY status = 'queued', id",
+            WORKFLOW_QUEUE_COLUMNS
+        ),
+        &[],
+    )?;
+    jobs.extend(query(
+        format!(
+            "SELECT {} FROM workflow_queue WHERE status NOT IN ('running', 'queued')
+             ORDER BY id DESC LIMIT ?1",
+            WORKFLOW_QUEUE_COLUMNS
+        ),
+        &[&WORKFLOW_QUEUE_HISTORY],
+    )?);
+    Ok(jobs)
+}
 
+pub fn cancel_workflow_job(conn: &Connection, id: i64) -> Result<(), String> {
+    let job = get_workflow_queue_job(conn, id)?.ok_or_else(|| format!("Queued job {} not found", id))?;
+    if job.status != "queued" {
+        return Err(format!("Queued job {} is {}; only queued jobs can be cancelled", id, job.status));
+    }
     conn.execute(
-        "DELETE FROM skill_locks WHERE skill_id = ?1",
-        rusqlite::params![s_id],
+        "UPDATE workflow_queue SET status = 'cancelled', finished_at = datetime('now') || 'Z'
+         WHERE id = ?1 AND status = 'queued'",
+        [id],
     )
     .map_err(|e| e.to_string())?;
+    emit_workflow_queue_change(conn, id);
+    Ok(())
+}
 
-    conn.execute(
-        "DELETE FROM skill_tags WHERE skill_id = ?1",
-        rusqlite::params![s_id],
-    )
-    .map_err(|e| e.to_string())?;
+/// Mark up to `concurrency` minus the running count of queued jobs as running
+/// by this process and return them. Only the oldest queued job of a skill is
+/// eligible, and only while no other job of that skill is running, so a
+/// skill's steps run in the order they were queued.
+pub fn claim_workflow_jobs(conn: &Connection, concurrency: u32) -> Result<Vec<WorkflowQueueJob>, String> {
+    let running: i64 = conn
+        .query_row("SELECT COUNT(*) FROM workflow_queue WHERE status = 'running'", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    let slots = (concurrency.max(1) as i64 - running).max(0);
+    if slots == 0 {
+        return Ok(Vec::new());
+    }
+    let ids: Vec<i64> = conn
+        .prepare(
+            "SELECT q.id FROM workflow_queue q
+             WHERE q.status = 'queued'
+               AND q.id = (SELECT MIN(id) FROM workflow_queue
+                           WHERE skill_name = q.skill_name AND status = 'queued')
+               AND NOT EXISTS (SELECT 1 FROM workflow_queue
+                               WHERE skill_name = q.skill_name AND status = 'running')
+             ORDER BY q.id LIMIT ?1",
+        )
+        .map_err(|e| e.to_string())?
+        .query_map([slots], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
 
-    // Delete from imported_skills to prevent stale rows blocking re-import
-    conn.execute(
-        "DELETE FROM imported_skills WHERE skill_master_id = ?1",
-        rusqlite::params![s_id],
-    )
-    .map_err(|e| e.to_string())?;
+    let mut claimed = Vec::new();
+    for id in ids {
+        // Guarded on status so two instances never claim the same job.
+        let updated = conn
+            .execute(
+                "UPDATE workflow_queue SET status = 'running', pid = ?2, started_at = datetime('now') || 'Z'
+                 WHERE id = ?1 AND status = 'queued'",
+                rusqlite::params![id, std::process::id() as i64],
+            )
+            .map_err(|e| e.to_string())?;
+        if updated == 1 {
+            emit_workflow_queue_change(conn, id);
+            if let Some(job) = get_workflow_queue_job(conn, id)? {
+                claimed.push(job);
+            }
+        }
+    }
+    Ok(claimed)
+}
 
+/// Record a job's outcome. When it failed, the skill's later queued steps are
+/// cancelled since they depend on its output.
+pub fn finish_workflow_job(conn: &Connection, id: i64, result: &Result<(), String>) -> Result<(), String> {
+    let (status, error) = match result {
+        Ok(()) => ("completed", None),
+        Err(e) => ("failed", Some(e.as_str())),
+    };
     conn.execute(
-        "DELETE FROM workflow_runs WHERE skill_name = ?1",
-        [skill_name],
+        "UPDATE workflow_queue SET status = ?2, error = ?3, pid = NULL, finished_at = datetime('now') || 'Z'
+         WHERE id = ?1",
+        rusqlite::params![id, status, error],
     )
     .map_err(|e| e.to_string())?;
-
-    // Also delete from skills master table
-    delete_skill(conn, skill_name)?;
+    emit_workflow_queue_change(conn, id);
+    if result.is_err() {
+        let job = get_workflow_queue_job(conn, id)?.ok_or_else(|| format!("Queued job {} not found", id))?;
+        let skipped: Vec<i64> = conn
+            .prepare("SELECT id FROM workflow_queue WHERE skill_name = ?1 AND status = 'queued' AND id > ?2")
+            .map_err(|e| e.to_string())?
+            .query_map(rusqlite::params![job.skill_name, id], |row| row.get(0))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        for skipped_id in skipped {
+            conn.execute(
+                "UPDATE workflow_queue SET status = 'cancelled', error = ?2, finished_at = datetime('now') || 'Z'
+                 WHERE id = ?1",
+                rusqlite::params![skipped_id, format!("Skipped: step {} failed", job.step_id)],
+            )
+            .map_err(|e| e.to_string())?;
+            emit_workflow_queue_change(conn, skipped_id);
+        }
+    }
     Ok(())
 }
 
-// --- Workflow Steps ---
-
-pub fn save_workflow_step(
-    conn: &Connection,
-    skill_name: &str,
-    step_id: i32,
-    status: &str,
-) -> Result<(), String> {
-    let now = chrono::Utc::now().to_rfc3339();
-    let (started, completed) = match status {
-        "in_progress" => (Some(now.clone()), None),
-        "completed" => (None, Some(now)),
-        _ => (None, None),
-    };
+/// Put jobs left running by a dead process back in the queue so they run
+/// again. Returns the number requeued.
+pub fn requeue_orphaned_workflow_jobs(conn: &Connection) -> Result<u32, String> {
+    let orphaned: Vec<(i64, Option<i64>)> = conn
+        .prepare("SELECT id, pid FROM workflow_queue WHERE status = 'running'")
+        .map_err(|e| e.to_string())?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    let mut requeued = 0u32;
+    for (id, pid) in orphaned {
+        if pid.is_some_and(|pid| pid as u32 == std::process::id() || check_pid_alive(pid as u32)) {
+            continue;
+        }
+        conn.execute(
+            "UPDATE workflow_queue SET status = 'queued', pid = NULL, started_at = NULL WHERE id = ?1",
+            [id],
+        )
+        .map_err(|e| e.to_string())?;
+        requeued += 1;
+    }
+    Ok(requeued)
+}
 
-    let workflow_run_id = get_workflow_run_id(conn, skill_name)?;
+// --- Skill Changelog ---
 
+pub fn insert_skill_changelog_entry(conn: &Connection, entry: &SkillChangelogEntry) -> Result<(), String> {
     conn.execute(
-        "INSERT INTO workflow_steps (skill_name, step_id, status, started_at, completed_at, workflow_run_id)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
-         ON CONFLICT(skill_name, step_id) DO UPDATE SET
-             status = ?3,
-             started_at = COALESCE(?4, started_at),
-             completed_at = ?5,
-             workflow_run_id = COALESCE(?6, workflow_run_id)",
-        rusqlite::params![skill_name, step_id, status, started, completed, workflow_run_id],
+        "INSERT INTO skill_changelog (skill_name, version, previous_version, bump, summary, previous_sha, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![
+            entry.skill_name,
+            entry.version,
+            entry.previous_version,
+            entry.bump,
+            entry.summary,
+            entry.previous_sha,
+            entry.created_at
+        ],
     )
     .map_err(|e| e.to_string())?;
     Ok(())
 }
 
-pub fn get_workflow_steps(
-    conn: &Connection,
-    skill_name: &str,
-) -> Result<Vec<WorkflowStepRow>, String> {
-    let wr_id = match get_workflow_run_id(conn, skill_name)? {
-        Some(id) => id,
-        None => return Ok(vec![]),
-    };
-
+/// Changelog entries for a skill, newest first.
+pub fn list_skill_changelog(conn: &Connection, skill_name: &str) -> Result<Vec<SkillChangelogEntry>, String> {
     let mut stmt = conn
         .prepare(
-            "SELECT skill_name, step_id, status, started_at, completed_at
-             FROM workflow_steps WHERE workflow_run_id = ?1 ORDER BY step_id",
+            "SELECT skill_name, version, previous_version, bump, summary, previous_sha, created_at
+             FROM skill_changelog WHERE skill_name = ?1 ORDER BY id DESC",
         )
         .map_err(|e| e.to_string())?;
     let rows = stmt
-        .query_map(rusqlite::params![wr_id], |row| {
-            Ok(WorkflowStepRow {
+        .query_map([skill_name], |row| {
+            Ok(SkillChangelogEntry {
                 skill_name: row.get(0)?,
-                step_id: row.get(1)?,
-                status: row.get(2)?,
-                started_at: row.get(3)?,
-                completed_at: row.get(4)?,
+                version: row.get(1)?,
+                previous_version: row.get(2)?,
+                bump: row.get(3)?,
+                summary: row.get(4)?,
+                previous_sha: row.get(5)?,
+                created_at: row.get(6)?,
             })
         })
         .map_err(|e| e.to_string())?;
-    rows.collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
 }
 
-pub fn reset_workflow_steps_from(
+// --- Artifact Annotations ---
+
+const ANNOTATION_COLUMNS: &str = "id, skill_name, file, start_line, end_line, anchor_text, comment, author, orphaned, created_at, updated_at";
+
+fn row_to_annotation(row: &rusqlite::Row) -> rusqlite::Result<ArtifactAnnotation> {
+    Ok(ArtifactAnnotation {
+        id: row.get(0)?,
+        skill_name: row.get(1)?,
+        file: row.get(2)?,
+        start_line: row.get(3)?,
+        end_line: row.get(4)?,
+        anchor_text: row.get(5)?,
+        comment: row.get(6)?,
+        author: row.get(7)?,
+        orphaned: row.get(8)?,
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+    })
+}
+
+/// Insert an annotation and return it with its new id. `annotation.id` is ignored.
+pub fn insert_artifact_annotation(conn: &Connection, annotation: &ArtifactAnnotation) -> Result<ArtifactAnnotation, String> {
+    conn.execute(
+        "INSERT INTO artifact_annotations
+            (skill_name, file, start_line, end_line, anchor_text, comment, author, orphaned, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        rusqlite::params![
+            annotation.skill_name,
+            annotation.file,
+            annotation.start_line,
+            annotation.end_line,
+            annotation.anchor_text,
+            annotation.comment,
+            annotation.author,
+            annotation.orphaned,
+            annotation.created_at,
+            annotation.updated_at
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(ArtifactAnnotation {
+        id: conn.last_insert_rowid(),
+        ..annotation.clone()
+    })
+}
+
+pub fn get_artifact_annotation(conn: &Connection, id: i64) -> Result<Option<ArtifactAnnotation>, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM artifact_annotations WHERE id = ?1", ANNOTATION_COLUMNS),
+        [id],
+        row_to_annotation,
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// Annotations on a skill, optionally limited to one file, in file and line order.
+pub fn list_artifact_annotations(
     conn: &Connection,
     skill_name: &str,
-    from_step: i32,
+    file: Option<&str>,
+) -> Result<Vec<ArtifactAnnotation>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM artifact_annotations
+             WHERE skill_name = ?1 AND (?2 IS NULL OR file = ?2)
+             ORDER BY file, start_line, id",
+            ANNOTATION_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![skill_name, file], row_to_annotation)
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+/// Move an annotation to where its anchor was found, or mark it orphaned.
+pub fn update_artifact_annotation_anchor(
+    conn: &Connection,
+    id: i64,
+    start_line: u32,
+    end_line: u32,
+    anchor_text: &str,
+    orphaned: bool,
 ) -> Result<(), String> {
-    let wr_id = match get_workflow_run_id(conn, skill_name)? {
-        Some(id) => id,
-        None => return Ok(()),
-    };
     conn.execute(
-        "UPDATE workflow_steps SET status = 'pending', started_at = NULL, completed_at = NULL
-         WHERE workflow_run_id = ?1 AND step_id >= ?2",
-        rusqlite::params![wr_id, from_step],
+        "UPDATE artifact_annotations SET start_line = ?2, end_line = ?3, anchor_text = ?4, orphaned = ?5
+         WHERE id = ?1",
+        rusqlite::params![id, start_line, end_line, anchor_text, orphaned],
     )
     .map_err(|e| e.to_string())?;
     Ok(())
 }
 
-// --- Skill Tags ---
-
-pub fn get_tags_for_skills(
-    conn: &Connection,
-    skill_names: &[String],
-) -> Result<HashMap<String, Vec<String>>, String> {
-    if skill_names.is_empty() {
-        return Ok(HashMap::new());
+pub fn update_artifact_annotation_comment(conn: &Connection, id: i64, comment: &str) -> Result<(), String> {
+    let updated = conn
+        .execute(
+            "UPDATE artifact_annotations SET comment = ?2, updated_at = datetime('now') || 'Z' WHERE id = ?1",
+            rusqlite::params![id, comment],
+        )
+        .map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(format!("Annotation {} not found", id));
     }
+    Ok(())
+}
 
-    // SQLite default SQLITE_MAX_VARIABLE_NUMBER is 999; chunk if needed
-    if skill_names.len() > 900 {
-        let mut map: HashMap<String, Vec<String>> = HashMap::new();
-        for chunk in skill_names.chunks(900) {
-            let chunk_result = get_tags_for_skills(conn, chunk)?;
-            map.extend(chunk_result);
-        }
-        return Ok(map);
-    }
+pub fn delete_artifact_annotation(conn: &Connection, id: i64) -> Result<(), String> {
+    conn.execute("DELETE FROM artifact_annotations WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
 
-    // Safety: The format! below only injects positional bind-parameter placeholders
-    // (?1, ?2, ...) — never user-supplied values. All skill_name values are bound via
-    // rusqlite's parameterized query API, so there is no SQL injection risk.
-    let placeholders: Vec<String> = (1..=skill_names.len()).map(|i| format!("?{}", i)).collect();
-    let sql = format!(
-        "SELECT skill_name, tag FROM skill_tags WHERE skill_id IN (SELECT id FROM skills WHERE name IN ({})) ORDER BY skill_name, tag",
-        placeholders.join(", ")
-    );
+// --- Step Run Cache ---
 
-    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+/// Record the input fingerprint of a step run that just started, replacing
+/// any earlier run of the step that never completed.
+pub fn record_pending_step_run(
+    conn: &Connection,
+    skill_name: &str,
+    step_id: u32,
+    fingerprint: &str,
+    agent_id: &str,
+    model: &str,
+) -> Result<(), String> {
+    clear_pending_step_runs(conn, skill_name, step_id)?;
+    conn.execute(
+        "INSERT INTO step_run_cache (skill_name, step_id, fingerprint, agent_id, model)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![skill_name, step_id, fingerprint, agent_id, model],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
 
-    let params: Vec<&dyn rusqlite::types::ToSql> = skill_names
-        .iter()
-        .map(|s| s as &dyn rusqlite::types::ToSql)
-        .collect();
+pub fn clear_pending_step_runs(conn: &Connection, skill_name: &str, step_id: u32) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM step_run_cache WHERE skill_name = ?1 AND step_id = ?2 AND cached = 0",
+        rusqlite::params![skill_name, step_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
 
-    let rows = stmt
-        .query_map(params.as_slice(), |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-        })
+/// The in-progress run of a step as `(id, fingerprint)`, if any.
+pub fn pending_step_run(conn: &Connection, skill_name: &str, step_id: u32) -> Result<Option<(i64, String)>, String> {
+    conn.query_row(
+        "SELECT id, fingerprint FROM step_run_cache
+         WHERE skill_name = ?1 AND step_id = ?2 AND cached = 0
+         ORDER BY id DESC LIMIT 1",
+        rusqlite::params![skill_name, step_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+pub fn mark_step_run_cached(conn: &Connection, id: i64) -> Result<(), String> {
+    conn.execute("UPDATE step_run_cache SET cached = 1 WHERE id = ?1", [id])
         .map_err(|e| e.to_string())?;
+    Ok(())
+}
 
-    let mut map: HashMap<String, Vec<String>> = HashMap::new();
-    for row in rows {
-        let (name, tag) = row.map_err(|e| e.to_string())?;
-        map.entry(name).or_default().push(tag);
+/// Drop cached runs of a step superseded by the newest one with `fingerprint`
+/// and all but the newest `keep`. Returns the fingerprints whose cached
+/// outputs are no longer referenced.
+pub fn prune_cached_step_runs(
+    conn: &Connection,
+    skill_name: &str,
+    step_id: u32,
+    fingerprint: &str,
+    keep: usize,
+) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, fingerprint FROM step_run_cache
+             WHERE skill_name = ?1 AND step_id = ?2 AND cached = 1
+             ORDER BY id DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows: Vec<(i64, String)> = stmt
+        .query_map(rusqlite::params![skill_name, step_id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+    let mut kept: Vec<&str> = Vec::new();
+    let mut released = Vec::new();
+    for (id, fp) in &rows {
+        let duplicate = kept.contains(&fp.as_str());
+        if duplicate || kept.len() >= keep {
+            conn.execute("DELETE FROM step_run_cache WHERE id = ?1", [id])
+                .map_err(|e| e.to_string())?;
+            if !duplicate && fp != fingerprint {
+                released.push(fp.clone());
+            }
+        } else {
+            kept.push(fp);
+        }
     }
+    Ok(released)
+}
 
-    Ok(map)
+/// The newest cached run of a step with `fingerprint`, with the cost of the
+/// agent run that produced it.
+pub fn find_cached_step_run(
+    conn: &Connection,
+    skill_name: &str,
+    step_id: u32,
+    fingerprint: &str,
+) -> Result<Option<CachedStepRun>, String> {
+    conn.query_row(
+        "SELECT c.skill_name, c.step_id, c.fingerprint, c.agent_id, c.model,
+                COALESCE((SELECT SUM(total_cost) FROM agent_runs a WHERE a.agent_id = c.agent_id), 0.0),
+                c.created_at
+         FROM step_run_cache c
+         WHERE c.skill_name = ?1 AND c.step_id = ?2 AND c.fingerprint = ?3 AND c.cached = 1
+         ORDER BY c.id DESC LIMIT 1",
+        rusqlite::params![skill_name, step_id, fingerprint],
+        |row| {
+            Ok(CachedStepRun {
+                skill_name: row.get(0)?,
+                step_id: row.get(1)?,
+                fingerprint: row.get(2)?,
+                agent_id: row.get(3)?,
+                model: row.get(4)?,
+                cost_usd: row.get(5)?,
+                created_at: row.get(6)?,
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| e.to_string())
 }
 
-pub fn set_skill_tags(conn: &Connection, skill_name: &str, tags: &[String]) -> Result<(), String> {
-    let s_id = get_skill_master_id(conn, skill_name)?
-        .ok_or_else(|| format!("Skill '{}' not found in skills master", skill_name))?;
+// --- Refine Sessions ---
 
+pub fn insert_refine_session(conn: &Connection, session_id: &str, skill_name: &str) -> Result<(), String> {
     conn.execute(
-        "DELETE FROM skill_tags WHERE skill_id = ?1",
-        rusqlite::params![s_id],
+        "INSERT OR REPLACE INTO refine_sessions (session_id, skill_name) VALUES (?1, ?2)",
+        rusqlite::params![session_id, skill_name],
     )
     .map_err(|e| e.to_string())?;
+    Ok(())
+}
 
-    let mut stmt = conn
-        .prepare("INSERT OR IGNORE INTO skill_tags (skill_name, skill_id, tag) VALUES (?1, ?2, ?3)")
-        .map_err(|e| e.to_string())?;
-
-    for tag in tags {
-        let normalized = tag.trim().to_lowercase();
-        if !normalized.is_empty() {
-            stmt.execute(rusqlite::params![skill_name, s_id, normalized])
-                .map_err(|e| e.to_string())?;
-        }
-    }
+fn row_to_refine_session(row: &rusqlite::Row) -> rusqlite::Result<RefineSessionRecord> {
+    let messages: String = row.get(3)?;
+    let pending_diff: Option<String> = row.get(4)?;
+    let file_reviews: String = row.get(7)?;
+    Ok(RefineSessionRecord {
+        session_id: row.get(0)?,
+        skill_name: row.get(1)?,
+        sdk_session_id: row.get(2)?,
+        messages: serde_json::from_str(&messages).unwrap_or_default(),
+        pending_diff: pending_diff.and_then(|d| serde_json::from_str(&d).ok()),
+        file_reviews: serde_json::from_str(&file_reviews).unwrap_or_default(),
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
 
-    Ok(())
+pub fn get_refine_session(conn: &Connection, session_id: &str) -> Result<Option<RefineSessionRecord>, String> {
+    conn.query_row(
+        "SELECT session_id, skill_name, sdk_session_id, messages, pending_diff, created_at, updated_at, file_reviews
+         FROM refine_sessions WHERE session_id = ?1",
+        [session_id],
+        row_to_refine_session,
+    )
+    .optional()
+    .map_err(|e| e.to_string())
 }
 
-pub fn get_all_tags(conn: &Connection) -> Result<Vec<String>, String> {
+/// All persisted refine sessions, most recently active first.
+pub fn list_refine_sessions(conn: &Connection) -> Result<Vec<RefineSessionRecord>, String> {
     let mut stmt = conn
-        .prepare("SELECT DISTINCT tag FROM skill_tags ORDER BY tag")
+        .prepare(
+            "SELECT session_id, skill_name, sdk_session_id, messages, pending_diff, created_at, updated_at, file_reviews
+             FROM refine_sessions ORDER BY updated_at DESC",
+        )
         .map_err(|e| e.to_string())?;
-
     let rows = stmt
-        .query_map([], |row| row.get(0))
+        .query_map([], row_to_refine_session)
         .map_err(|e| e.to_string())?;
-
-    rows.collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())
-}
-
-// --- Imported Skills ---
-
-/// Read SKILL.md frontmatter from disk and populate `description`
-/// on an ImportedSkill struct. This field is not stored in the DB.
-pub fn hydrate_skill_metadata(skill: &mut ImportedSkill) {
-    let skill_md_path = std::path::Path::new(&skill.disk_path).join("SKILL.md");
-    if let Ok(content) = fs::read_to_string(&skill_md_path) {
-        let fm = crate::commands::imported_skills::parse_frontmatter_full(&content);
-        skill.description = fm.description;
-    }
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
 }
 
-#[allow(dead_code)]
-pub fn insert_imported_skill(conn: &Connection, skill: &ImportedSkill) -> Result<(), String> {
-    let skill_master_id = get_skill_master_id(conn, &skill.skill_name)?;
-    conn.execute(
-        "INSERT INTO imported_skills (skill_id, skill_name, is_active, disk_path, imported_at, is_bundled,
-             purpose, version, model, argument_hint, user_invocable, disable_model_invocation, skill_master_id, marketplace_source_url)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
-        rusqlite::params![
-            skill.skill_id,
-            skill.skill_name,
-            skill.is_active as i32,
-            skill.disk_path,
-            skill.imported_at,
-            skill.is_bundled as i32,
-            skill.purpose,
-            skill.version,
-            skill.model,
-            skill.argument_hint,
-            skill.user_invocable.map(|v| v as i32),
-            skill.disable_model_invocation.map(|v| v as i32),
-            skill_master_id,
-            skill.marketplace_source_url,
-        ],
+/// The `(session_id, skill_name)` of the session whose latest message went to `agent_id`.
+pub fn find_refine_session_for_agent(conn: &Connection, agent_id: &str) -> Result<Option<(String, String)>, String> {
+    conn.query_row(
+        "SELECT session_id, skill_name FROM refine_sessions WHERE last_agent_id = ?1",
+        [agent_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
     )
-    .map_err(|e| {
-        if e.to_string().contains("UNIQUE") {
-            format!("Skill '{}' has already been imported", skill.skill_name)
-        } else {
-            e.to_string()
-        }
-    })?;
-    Ok(())
+    .optional()
+    .map_err(|e| e.to_string())
 }
 
-/// Upsert a marketplace-imported skill. Uses `INSERT OR REPLACE` so that re-importing
-/// (e.g. after the skills_path setting changed or files were manually deleted) always
-/// updates the existing record rather than failing with a UNIQUE constraint.
-/// Also mirrors frontmatter fields to the `skills` master table (canonical store).
-pub fn upsert_imported_skill(conn: &Connection, skill: &ImportedSkill) -> Result<(), String> {
-    let skill_master_id = get_skill_master_id(conn, &skill.skill_name)?;
-    conn.execute(
-        "INSERT INTO imported_skills (skill_id, skill_name, is_active, disk_path, imported_at, is_bundled,
-             purpose, version, model, argument_hint, user_invocable, disable_model_invocation, skill_master_id, marketplace_source_url)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
-         ON CONFLICT(skill_name) DO UPDATE SET
-             skill_id = excluded.skill_id,
-             disk_path = excluded.disk_path,
-             imported_at = excluded.imported_at,
-             purpose = excluded.purpose,
-             version = excluded.version,
-             model = excluded.model,
-             argument_hint = excluded.argument_hint,
-             user_invocable = excluded.user_invocable,
-             disable_model_invocation = excluded.disable_model_invocation,
-             skill_master_id = excluded.skill_master_id,
-             marketplace_source_url = excluded.marketplace_source_url",
-        rusqlite::params![
-            skill.skill_id,
-            skill.skill_name,
-            skill.is_active as i32,
-            skill.disk_path,
-            skill.imported_at,
-            skill.is_bundled as i32,
-            skill.purpose,
-            skill.version,
-            skill.model,
-            skill.argument_hint,
-            skill.user_invocable.map(|v| v as i32),
-            skill.disable_model_invocation.map(|v| v as i32),
-            skill_master_id,
-            skill.marketplace_source_url,
-        ],
-    )
-    .map_err(|e| e.to_string())?;
-
-    // Mirror frontmatter fields to skills master — these values are the merged result
-    // (new frontmatter wins if non-empty, installed value as fallback) so we overwrite directly.
+pub fn append_refine_session_message(
+    conn: &Connection,
+    session_id: &str,
+    message: &RefineSessionMessage,
+) -> Result<(), String> {
+    let Some(mut record) = get_refine_session(conn, session_id)? else {
+        return Err("Refine session is not persisted".to_string());
+    };
+    record.messages.push(message.clone());
+    let messages = serde_json::to_string(&record.messages).map_err(|e| e.to_string())?;
     conn.execute(
-        "UPDATE skills SET
-            version = ?2,
-            model = ?3,
-            argument_hint = ?4,
-            user_invocable = ?5,
-            disable_model_invocation = ?6,
-            updated_at = datetime('now')
-         WHERE name = ?1",
-        rusqlite::params![
-            skill.skill_name,
-            skill.version,
-            skill.model,
-            skill.argument_hint,
-            skill.user_invocable.map(|v| v as i32),
-            skill.disable_model_invocation.map(|v| v as i32),
-        ],
+        "UPDATE refine_sessions
+         SET messages = ?2, last_agent_id = COALESCE(?3, last_agent_id), updated_at = datetime('now') || 'Z'
+         WHERE session_id = ?1",
+        rusqlite::params![session_id, messages, message.agent_id],
     )
     .map_err(|e| e.to_string())?;
-
     Ok(())
 }
 
-#[allow(dead_code)]
-pub fn update_imported_skill_active(
+/// Record the SDK session id (kept when `None`) and the current pending diff.
+pub fn update_refine_session_state(
     conn: &Connection,
-    skill_name: &str,
-    is_active: bool,
-    new_disk_path: &str,
+    session_id: &str,
+    sdk_session_id: Option<&str>,
+    pending_diff: Option<&RefineDiff>,
 ) -> Result<(), String> {
-    let s_id = get_skill_master_id(conn, skill_name)?
-        .ok_or_else(|| format!("Skill '{}' not found in skills master", skill_name))?;
-
-    let rows = conn
-        .execute(
-            "UPDATE imported_skills SET is_active = ?1, disk_path = ?2 WHERE skill_master_id = ?3",
-            rusqlite::params![is_active as i32, new_disk_path, s_id],
-        )
+    let pending_diff = pending_diff
+        .filter(|d| !d.files.is_empty())
+        .map(serde_json::to_string)
+        .transpose()
         .map_err(|e| e.to_string())?;
-
-    if rows == 0 {
-        return Err(format!("Imported skill '{}' not found", skill_name));
-    }
+    conn.execute(
+        "UPDATE refine_sessions
+         SET sdk_session_id = COALESCE(?2, sdk_session_id), pending_diff = ?3, updated_at = datetime('now') || 'Z'
+         WHERE session_id = ?1",
+        rusqlite::params![session_id, sdk_session_id, pending_diff],
+    )
+    .map_err(|e| e.to_string())?;
     Ok(())
 }
 
-#[allow(dead_code)]
-pub fn delete_imported_skill(conn: &Connection, skill_name: &str) -> Result<(), String> {
-    let s_id = match get_skill_master_id(conn, skill_name)? {
-        Some(id) => id,
-        None => return Ok(()), // Skill not in library — nothing to delete
+/// Record an accept/reject decision for one file, replacing any earlier
+/// decision for that file.
+pub fn record_refine_file_review(
+    conn: &Connection,
+    session_id: &str,
+    review: &RefineFileReview,
+) -> Result<(), String> {
+    let Some(mut record) = get_refine_session(conn, session_id)? else {
+        return Err("Refine session is not persisted".to_string());
     };
+    record.file_reviews.retain(|r| r.path != review.path);
+    record.file_reviews.push(review.clone());
+    let file_reviews = serde_json::to_string(&record.file_reviews).map_err(|e| e.to_string())?;
     conn.execute(
-        "DELETE FROM imported_skills WHERE skill_master_id = ?1",
-        rusqlite::params![s_id],
+        "UPDATE refine_sessions SET file_reviews = ?2, updated_at = datetime('now') || 'Z' WHERE session_id = ?1",
+        rusqlite::params![session_id, file_reviews],
     )
     .map_err(|e| e.to_string())?;
     Ok(())
 }
 
-pub fn delete_imported_skill_by_name(conn: &Connection, name: &str) -> Result<(), String> {
-    log::debug!("delete_imported_skill_by_name: name={}", name);
-    let s_id = match get_skill_master_id(conn, name)? {
-        Some(id) => id,
-        None => return Ok(()), // Skill not in library — nothing to delete
-    };
-    conn.execute(
-        "DELETE FROM imported_skills WHERE skill_master_id = ?1",
-        rusqlite::params![s_id],
-    )
-    .map_err(|e| {
-        log::error!(
-            "delete_imported_skill_by_name: failed to delete '{}': {}",
-            name,
-            e
-        );
-        e.to_string()
-    })?;
+pub fn delete_refine_session(conn: &Connection, session_id: &str) -> Result<(), String> {
+    conn.execute("DELETE FROM refine_sessions WHERE session_id = ?1", [session_id])
+        .map_err(|e| e.to_string())?;
     Ok(())
 }
 
-pub fn get_imported_skill(
-    conn: &Connection,
-    skill_name: &str,
-) -> Result<Option<ImportedSkill>, String> {
-    let s_id = match get_skill_master_id(conn, skill_name)? {
-        Some(id) => id,
-        None => return Ok(None),
-    };
+// --- Refine Batches ---
 
-    let mut stmt = conn
-        .prepare(
-            "SELECT skill_id, skill_name, is_active, disk_path, imported_at, is_bundled,
-                    purpose, version, model, argument_hint, user_invocable, disable_model_invocation, marketplace_source_url
-             FROM imported_skills WHERE skill_master_id = ?1",
+pub fn insert_refine_batch(
+    conn: &Connection,
+    batch_id: &str,
+    instruction: &str,
+    workspace_path: &str,
+    workflow_session_id: &str,
+    skill_names: &[String],
+) -> Result<(), String> {
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT INTO refine_batches (batch_id, instruction, workspace_path, workflow_session_id)
+         VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![batch_id, instruction, workspace_path, workflow_session_id],
+    )
+    .map_err(|e| e.to_string())?;
+    for (position, skill_name) in skill_names.iter().enumerate() {
+        tx.execute(
+            "INSERT INTO refine_batch_items (batch_id, skill_name, position) VALUES (?1, ?2, ?3)",
+            rusqlite::params![batch_id, skill_name, position as i64],
         )
         .map_err(|e| e.to_string())?;
-
-    let result = stmt.query_row(rusqlite::params![s_id], |row| {
-        Ok(ImportedSkill {
-            skill_id: row.get(0)?,
-            skill_name: row.get(1)?,
-            is_active: row.get::<_, i32>(2)? != 0,
-            disk_path: row.get(3)?,
-            imported_at: row.get(4)?,
-            is_bundled: row.get::<_, i32>(5)? != 0,
-            description: None,
-            purpose: row.get(6)?,
-            version: row.get(7)?,
-            model: row.get(8)?,
-            argument_hint: row.get(9)?,
-            user_invocable: row.get::<_, Option<i32>>(10)?.map(|v| v != 0),
-            disable_model_invocation: row.get::<_, Option<i32>>(11)?.map(|v| v != 0),
-            marketplace_source_url: row.get(12)?,
-        })
-    });
-
-    match result {
-        Ok(mut skill) => {
-            hydrate_skill_metadata(&mut skill);
-            Ok(Some(skill))
-        }
-        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-        Err(e) => Err(e.to_string()),
     }
+    tx.commit().map_err(|e| e.to_string())
 }
 
-#[allow(dead_code)]
-pub fn list_active_skills(conn: &Connection) -> Result<Vec<ImportedSkill>, String> {
+fn refine_batch_items(conn: &Connection, batch_id: &str) -> Result<Vec<RefineBatchItem>, String> {
     let mut stmt = conn
         .prepare(
-            "SELECT skill_id, skill_name, is_active, disk_path, imported_at, is_bundled,
-                    purpose, version, model, argument_hint, user_invocable, disable_model_invocation, marketplace_source_url
-             FROM imported_skills
-             WHERE is_active = 1
-             ORDER BY skill_name",
+            "SELECT i.skill_name, i.position, i.status, i.agent_id, i.diff, i.error,
+                    COALESCE((SELECT SUM(total_cost) FROM agent_runs a WHERE a.agent_id = i.agent_id), 0.0),
+                    i.updated_at
+             FROM refine_batch_items i WHERE i.batch_id = ?1 ORDER BY i.position",
         )
         .map_err(|e| e.to_string())?;
-
     let rows = stmt
-        .query_map([], |row| {
-            Ok(ImportedSkill {
-                skill_id: row.get(0)?,
-                skill_name: row.get(1)?,
-                is_active: row.get::<_, i32>(2)? != 0,
-                disk_path: row.get(3)?,
-                imported_at: row.get(4)?,
-                is_bundled: row.get::<_, i32>(5)? != 0,
-                description: None,
-                purpose: row.get(6)?,
-                version: row.get(7)?,
-                model: row.get(8)?,
-                argument_hint: row.get(9)?,
-                user_invocable: row.get::<_, Option<i32>>(10)?.map(|v| v != 0),
-                disable_model_invocation: row.get::<_, Option<i32>>(11)?.map(|v| v != 0),
-                marketplace_source_url: row.get(12)?,
+        .query_map([batch_id], |row| {
+            let diff: Option<String> = row.get(4)?;
+            Ok(RefineBatchItem {
+                skill_name: row.get(0)?,
+                position: row.get(1)?,
+                status: row.get(2)?,
+                agent_id: row.get(3)?,
+                diff: diff.and_then(|d| serde_json::from_str(&d).ok()),
+                error: row.get(5)?,
+                cost_usd: row.get(6)?,
+                updated_at: row.get(7)?,
             })
         })
         .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
 
-    let mut skills: Vec<ImportedSkill> = rows
-        .collect::<Result<Vec<_>, _>>()
+/// A batch with its items and the cost of every agent run in its session.
+pub fn get_refine_batch(conn: &Connection, batch_id: &str) -> Result<Option<RefineBatch>, String> {
+    let batch = conn
+        .query_row(
+            "SELECT b.batch_id, b.instruction, b.workspace_path, b.workflow_session_id,
+                    COALESCE((SELECT SUM(total_cost) FROM agent_runs a WHERE a.workflow_session_id = b.workflow_session_id), 0.0),
+                    b.created_at
+             FROM refine_batches b WHERE b.batch_id = ?1",
+            [batch_id],
+            |row| {
+                Ok(RefineBatch {
+                    batch_id: row.get(0)?,
+                    instruction: row.get(1)?,
+                    workspace_path: row.get(2)?,
+                    workflow_session_id: row.get(3)?,
+                    total_cost_usd: row.get(4)?,
+                    created_at: row.get(5)?,
+                    items: Vec::new(),
+                })
+            },
+        )
+        .optional()
         .map_err(|e| e.to_string())?;
+    let Some(mut batch) = batch else {
+        return Ok(None);
+    };
+    batch.items = refine_batch_items(conn, batch_id)?;
+    Ok(Some(batch))
+}
 
-    for skill in &mut skills {
-        hydrate_skill_metadata(skill);
+/// Batches, newest first.
+pub fn list_refine_batches(conn: &Connection, limit: u32) -> Result<Vec<RefineBatch>, String> {
+    let ids: Vec<String> = conn
+        .prepare("SELECT batch_id FROM refine_batches ORDER BY created_at DESC, rowid DESC LIMIT ?1")
+        .and_then(|mut stmt| {
+            stmt.query_map([limit], |row| row.get(0))?
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|e| e.to_string())?;
+    let mut batches = Vec::with_capacity(ids.len());
+    for id in ids {
+        batches.extend(get_refine_batch(conn, &id)?);
     }
+    Ok(batches)
+}
 
-    Ok(skills)
+/// The first queued skill of a batch, if any.
+pub fn next_queued_refine_batch_item(conn: &Connection, batch_id: &str) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT skill_name FROM refine_batch_items
+         WHERE batch_id = ?1 AND status = 'queued' ORDER BY position LIMIT 1",
+        [batch_id],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
 }
 
-// --- Workspace Skills (Settings → Skills tab) ---
+/// Set an item's status, keeping its agent id and diff when `None`.
+pub fn update_refine_batch_item(
+    conn: &Connection,
+    batch_id: &str,
+    skill_name: &str,
+    status: &str,
+    agent_id: Option<&str>,
+    diff: Option<&RefineDiff>,
+    error: Option<&str>,
+) -> Result<(), String> {
+    let diff = diff.map(serde_json::to_string).transpose().map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE refine_batch_items
+         SET status = ?3, agent_id = COALESCE(?4, agent_id), diff = COALESCE(?5, diff), error = ?6,
+             updated_at = datetime('now') || 'Z'
+         WHERE batch_id = ?1 AND skill_name = ?2",
+        rusqlite::params![batch_id, skill_name, status, agent_id, diff, error],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
 
-const WS_COLUMNS: &str = "skill_id, skill_name, description, is_active, is_bundled, disk_path, imported_at, purpose, version, model, argument_hint, user_invocable, disable_model_invocation, marketplace_source_url";
+/// Skills with a batch item that is queued, running or awaiting review.
+pub fn open_refine_batch_skills(conn: &Connection) -> Result<Vec<String>, String> {
+    conn.prepare(
+        "SELECT DISTINCT skill_name FROM refine_batch_items
+         WHERE status IN ('queued', 'running', 'review') ORDER BY skill_name",
+    )
+    .and_then(|mut stmt| stmt.query_map([], |row| row.get(0))?.collect::<Result<Vec<_>, _>>())
+    .map_err(|e| e.to_string())
+}
 
-fn ws_params(skill: &WorkspaceSkill) -> [rusqlite::types::Value; 14] {
-    use rusqlite::types::Value;
-    [
-        Value::Text(skill.skill_id.clone()),
-        Value::Text(skill.skill_name.clone()),
-        skill
-            .description
-            .as_ref()
-            .map_or(Value::Null, |v| Value::Text(v.clone())),
-        Value::Integer(skill.is_active as i64),
-        Value::Integer(skill.is_bundled as i64),
-        Value::Text(skill.disk_path.clone()),
-        Value::Text(skill.imported_at.clone()),
-        skill
-            .purpose
-            .as_ref()
-            .map_or(Value::Null, |v| Value::Text(v.clone())),
-        skill
-            .version
-            .as_ref()
-            .map_or(Value::Null, |v| Value::Text(v.clone())),
-        skill
-            .model
-            .as_ref()
-            .map_or(Value::Null, |v| Value::Text(v.clone())),
-        skill
-            .argument_hint
-            .as_ref()
-            .map_or(Value::Null, |v| Value::Text(v.clone())),
-        skill
-            .user_invocable
-            .map_or(Value::Null, |b| Value::Integer(b as i64)),
-        skill
-            .disable_model_invocation
-            .map_or(Value::Null, |b| Value::Integer(b as i64)),
-        skill
-            .marketplace_source_url
-            .as_ref()
-            .map_or(Value::Null, |v| Value::Text(v.clone())),
-    ]
+/// Requeue items left running by a crash; returns the batches with queued items.
+pub fn requeue_interrupted_refine_batches(conn: &Connection) -> Result<Vec<String>, String> {
+    conn.execute(
+        "UPDATE refine_batch_items SET status = 'queued', updated_at = datetime('now') || 'Z'
+         WHERE status = 'running'",
+        [],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.prepare(
+        "SELECT DISTINCT b.batch_id FROM refine_batches b
+         JOIN refine_batch_items i ON i.batch_id = b.batch_id
+         WHERE i.status = 'queued' ORDER BY b.created_at",
+    )
+    .and_then(|mut stmt| stmt.query_map([], |row| row.get(0))?.collect::<Result<Vec<_>, _>>())
+    .map_err(|e| e.to_string())
 }
 
-pub fn insert_workspace_skill(conn: &Connection, skill: &WorkspaceSkill) -> Result<(), String> {
+// --- Upstream Reviews ---
+
+/// Record the review opened (or updated) by a branch-per-skill push,
+/// replacing any earlier one for the skill.
+pub fn upsert_upstream_review(conn: &Connection, review: &SkillUpstreamReview) -> Result<(), String> {
     conn.execute(
-        &format!("INSERT INTO workspace_skills ({WS_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)"),
-        rusqlite::params_from_iter(ws_params(skill)),
-    ).map_err(|e| {
-        if e.to_string().contains("UNIQUE") {
-            format!("Skill '{}' has already been imported", skill.skill_name)
-        } else {
-            format!("insert_workspace_skill: {}", e)
-        }
-    })?;
+        "INSERT OR REPLACE INTO skill_upstream_reviews
+            (skill_name, host, repo, repo_url, branch, base_branch, number, url, state, commit_sha, pushed_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        rusqlite::params![
+            review.skill_name,
+            review.host,
+            review.repo,
+            review.repo_url,
+            review.branch,
+            review.base_branch,
+            review.number as i64,
+            review.url,
+            review.state,
+            review.commit_sha,
+            review.pushed_at,
+            review.updated_at,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
     Ok(())
 }
 
-pub fn upsert_workspace_skill(conn: &Connection, skill: &WorkspaceSkill) -> Result<(), String> {
+pub fn list_upstream_reviews(conn: &Connection) -> Result<Vec<SkillUpstreamReview>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT skill_name, host, repo, repo_url, branch, base_branch, number, url, state, commit_sha,
+                    pushed_at, updated_at
+             FROM skill_upstream_reviews ORDER BY skill_name",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(SkillUpstreamReview {
+                skill_name: row.get(0)?,
+                host: row.get(1)?,
+                repo: row.get(2)?,
+                repo_url: row.get(3)?,
+                branch: row.get(4)?,
+                base_branch: row.get(5)?,
+                number: row.get::<_, i64>(6)? as u64,
+                url: row.get(7)?,
+                state: row.get(8)?,
+                commit_sha: row.get(9)?,
+                pushed_at: row.get(10)?,
+                updated_at: row.get(11)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+pub fn update_upstream_review_state(conn: &Connection, skill_name: &str, state: &str) -> Result<(), String> {
     conn.execute(
-        &format!(
-            "INSERT INTO workspace_skills ({WS_COLUMNS})
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
-             ON CONFLICT(skill_name) DO UPDATE SET
-                 description = excluded.description,
-                 is_bundled = excluded.is_bundled,
-                 disk_path = excluded.disk_path,
-                 purpose = excluded.purpose,
-                 version = excluded.version,
-                 model = excluded.model,
-                 argument_hint = excluded.argument_hint,
-                 user_invocable = excluded.user_invocable,
-                 disable_model_invocation = excluded.disable_model_invocation,
-                 marketplace_source_url = excluded.marketplace_source_url"
-        ),
-        rusqlite::params_from_iter(ws_params(skill)),
+        "UPDATE skill_upstream_reviews SET state = ?2, updated_at = ?3 WHERE skill_name = ?1",
+        rusqlite::params![skill_name, state, chrono::Utc::now().to_rfc3339()],
     )
-    .map_err(|e| format!("upsert_workspace_skill: {}", e))?;
+    .map_err(|e| e.to_string())?;
     Ok(())
 }
 
-/// Re-seed a bundled skill: overwrites all frontmatter + disk path, preserves is_active.
-pub fn upsert_bundled_workspace_skill(
+// --- Skill Push Bases ---
+
+/// The SKILL.md content last pushed for `skill_name` to `repo_url`, if any.
+pub fn get_skill_push_base(conn: &Connection, skill_name: &str, repo_url: &str) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT content FROM skill_push_bases WHERE skill_name = ?1 AND repo_url = ?2",
+        rusqlite::params![skill_name, repo_url],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// Record `content` as the SKILL.md last pushed for `skill_name` to `repo_url`.
+pub fn set_skill_push_base(
     conn: &Connection,
-    skill: &WorkspaceSkill,
+    skill_name: &str,
+    repo_url: &str,
+    skill_path: &str,
+    content: &str,
+    commit_sha: Option<&str>,
 ) -> Result<(), String> {
     conn.execute(
-        &format!(
-            "INSERT INTO workspace_skills ({WS_COLUMNS})
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
-             ON CONFLICT(skill_name) DO UPDATE SET
-                 description = excluded.description,
-                 is_bundled = 1,
-                 disk_path = excluded.disk_path,
-                 version = excluded.version,
-                 model = excluded.model,
-                 argument_hint = excluded.argument_hint,
-                 user_invocable = excluded.user_invocable,
-                 disable_model_invocation = excluded.disable_model_invocation
-                 -- marketplace_source_url intentionally NOT updated: bundled skills always have NULL
-                 -- is_active intentionally NOT updated: preserves user's deactivation
-                 -- purpose intentionally NOT updated: preserves user's purpose setting"
-        ),
-        rusqlite::params_from_iter(ws_params(skill)),
-    ).map_err(|e| format!("upsert_bundled_workspace_skill: {}", e))?;
+        "INSERT OR REPLACE INTO skill_push_bases (skill_name, repo_url, skill_path, content, commit_sha, pushed_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![skill_name, repo_url, skill_path, content, commit_sha, chrono::Utc::now().to_rfc3339()],
+    )
+    .map_err(|e| e.to_string())?;
     Ok(())
 }
 
-fn row_to_workspace_skill(row: &rusqlite::Row) -> rusqlite::Result<WorkspaceSkill> {
-    let is_active: i64 = row.get(3)?;
-    let is_bundled: i64 = row.get(4)?;
-    let user_invocable: Option<i64> = row.get(11)?;
-    let disable_model_invocation: Option<i64> = row.get(12)?;
-    Ok(WorkspaceSkill {
-        skill_id: row.get(0)?,
-        skill_name: row.get(1)?,
-        description: row.get(2)?,
-        is_active: is_active != 0,
-        is_bundled: is_bundled != 0,
-        disk_path: row.get(5)?,
-        imported_at: row.get(6)?,
-        purpose: row.get(7)?,
-        version: row.get(8)?,
-        model: row.get(9)?,
-        argument_hint: row.get(10)?,
-        user_invocable: user_invocable.map(|v| v != 0),
-        disable_model_invocation: disable_model_invocation.map(|v| v != 0),
-        marketplace_source_url: row.get(13)?,
-    })
-}
+// --- Generation Bases ---
 
-pub fn list_workspace_skills(conn: &Connection) -> Result<Vec<WorkspaceSkill>, String> {
-    let mut stmt = conn
-        .prepare(&format!(
-            "SELECT {WS_COLUMNS} FROM workspace_skills ORDER BY imported_at DESC"
-        ))
-        .map_err(|e| format!("list_workspace_skills: {}", e))?;
-    let skills = stmt
-        .query_map([], row_to_workspace_skill)
-        .map_err(|e| format!("list_workspace_skills query: {}", e))?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| format!("list_workspace_skills collect: {}", e))?;
-    Ok(skills)
+/// Hash and content of the `decisions.json` SKILL.md was last generated from.
+pub fn get_generation_base(conn: &Connection, skill_name: &str) -> Result<Option<(String, String)>, String> {
+    conn.query_row(
+        "SELECT decisions_hash, decisions_json FROM skill_generation_bases WHERE skill_name = ?1",
+        [skill_name],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
 }
 
-pub fn list_workspace_skills_by_source(
+/// Record `decisions_json` as the decisions SKILL.md was generated from.
+pub fn set_generation_base(
     conn: &Connection,
-    source_url: &str,
-) -> Result<Vec<WorkspaceSkill>, String> {
-    let mut stmt = conn
-        .prepare(&format!(
-            "SELECT {WS_COLUMNS} FROM workspace_skills
-             WHERE marketplace_source_url = ?1
-             ORDER BY imported_at DESC"
-        ))
-        .map_err(|e| format!("list_workspace_skills_by_source: {}", e))?;
-    let skills = stmt
-        .query_map(rusqlite::params![source_url], row_to_workspace_skill)
-        .map_err(|e| format!("list_workspace_skills_by_source query: {}", e))?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| format!("list_workspace_skills_by_source collect: {}", e))?;
-    Ok(skills)
+    skill_name: &str,
+    decisions_hash: &str,
+    decisions_json: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO skill_generation_bases (skill_name, decisions_hash, decisions_json, generated_at)
+         VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![skill_name, decisions_hash, decisions_json, chrono::Utc::now().to_rfc3339()],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
 }
 
-pub fn list_active_workspace_skills(conn: &Connection) -> Result<Vec<WorkspaceSkill>, String> {
+// --- Skill Invocations ---
+
+/// Store invocations imported from Claude Code; ones already stored (same
+/// `event_id`) are skipped. Returns how many were new.
+pub fn insert_skill_invocations(conn: &Connection, invocations: &[SkillInvocation]) -> Result<usize, String> {
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let mut added = 0;
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT OR IGNORE INTO skill_invocations
+                     (event_id, skill_name, triggered_by, session_id, project_path, invoked_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )
+            .map_err(|e| e.to_string())?;
+        for inv in invocations {
+            added += stmt
+                .execute(rusqlite::params![
+                    inv.event_id,
+                    inv.skill_name,
+                    inv.triggered_by,
+                    inv.session_id,
+                    inv.project_path,
+                    inv.invoked_at,
+                ])
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(added)
+}
+
+/// Invocation counts per skill, optionally only since `since` (RFC 3339).
+/// Every installed skill is listed, never-invoked ones with zero counts;
+/// most invoked first.
+pub fn get_skill_invocation_stats(conn: &Connection, since: Option<&str>) -> Result<Vec<SkillInvocationStats>, String> {
     let mut stmt = conn
-        .prepare(&format!(
-            "SELECT {WS_COLUMNS} FROM workspace_skills WHERE is_active = 1 ORDER BY skill_name"
-        ))
-        .map_err(|e| format!("list_active_workspace_skills: {}", e))?;
-    let skills = stmt
-        .query_map([], row_to_workspace_skill)
-        .map_err(|e| format!("list_active_workspace_skills query: {}", e))?
+        .prepare(
+            "SELECT skill_name,
+                    COUNT(*),
+                    SUM(triggered_by = 'model'),
+                    SUM(triggered_by = 'user'),
+                    COUNT(DISTINCT session_id),
+                    COUNT(DISTINCT project_path),
+                    MIN(invoked_at),
+                    MAX(invoked_at)
+             FROM skill_invocations
+             WHERE ?1 IS NULL OR invoked_at >= ?1
+             GROUP BY skill_name",
+        )
+        .map_err(|e| e.to_string())?;
+    let mut by_skill: HashMap<String, SkillInvocationStats> = stmt
+        .query_map([since], |row| {
+            Ok(SkillInvocationStats {
+                skill_name: row.get(0)?,
+                invocations: row.get(1)?,
+                model_invocations: row.get(2)?,
+                user_invocations: row.get(3)?,
+                sessions: row.get(4)?,
+                projects: row.get(5)?,
+                first_invoked_at: row.get(6)?,
+                last_invoked_at: row.get(7)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
         .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| format!("list_active_workspace_skills collect: {}", e))?;
-    Ok(skills)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|s| (s.skill_name.clone(), s))
+        .collect();
+    for name in get_all_installed_skill_names(conn)? {
+        by_skill.entry(name.clone()).or_insert(SkillInvocationStats {
+            skill_name: name,
+            invocations: 0,
+            model_invocations: 0,
+            user_invocations: 0,
+            sessions: 0,
+            projects: 0,
+            first_invoked_at: None,
+            last_invoked_at: None,
+        });
+    }
+    let mut stats: Vec<SkillInvocationStats> = by_skill.into_values().collect();
+    stats.sort_by(|a, b| {
+        b.invocations
+            .cmp(&a.invocations)
+            .then_with(|| a.skill_name.cmp(&b.skill_name))
+    });
+    Ok(stats)
 }
 
-pub fn update_workspace_skill_active(
+// --- Agent Run Checkpoints ---
+
+const CHECKPOINT_COLUMNS: &str =
+    "agent_id, skill_name, step_id, pid, sdk_session_id, transcript_path, turns, status, resumed_from, started_at, updated_at";
+
+fn row_to_checkpoint(row: &rusqlite::Row) -> rusqlite::Result<AgentRunCheckpoint> {
+    Ok(AgentRunCheckpoint {
+        agent_id: row.get(0)?,
+        skill_name: row.get(1)?,
+        step_id: row.get::<_, i64>(2)? as u32,
+        pid: row.get::<_, i64>(3)? as u32,
+        sdk_session_id: row.get(4)?,
+        transcript_path: row.get(5)?,
+        turns: row.get::<_, i64>(6)? as u32,
+        status: row.get(7)?,
+        resumed_from: row.get(8)?,
+        started_at: row.get(9)?,
+        updated_at: row.get(10)?,
+    })
+}
+
+/// Start checkpointing a workflow step run, replacing earlier checkpoints of
+/// the step: only the newest run of a step can be resumed.
+pub fn create_agent_run_checkpoint(
     conn: &Connection,
-    skill_id: &str,
-    is_active: bool,
-    new_disk_path: &str,
+    agent_id: &str,
+    skill_name: &str,
+    step_id: u32,
+    pid: u32,
+    resumed_from: Option<&str>,
 ) -> Result<(), String> {
-    let rows = conn
-        .execute(
-            "UPDATE workspace_skills SET is_active = ?1, disk_path = ?2 WHERE skill_id = ?3",
-            rusqlite::params![is_active as i64, new_disk_path, skill_id],
-        )
-        .map_err(|e| format!("update_workspace_skill_active: {}", e))?;
-    if rows == 0 {
-        return Err(format!("Workspace skill with id '{}' not found", skill_id));
-    }
+    conn.execute(
+        "DELETE FROM agent_run_checkpoints WHERE skill_name = ?1 AND step_id = ?2",
+        rusqlite::params![skill_name, step_id],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO agent_run_checkpoints (agent_id, skill_name, step_id, pid, resumed_from)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![agent_id, skill_name, step_id, pid as i64, resumed_from],
+    )
+    .map_err(|e| e.to_string())?;
     Ok(())
 }
 
-pub fn delete_workspace_skill(conn: &Connection, skill_id: &str) -> Result<(), String> {
+pub fn set_checkpoint_sdk_session(conn: &Connection, agent_id: &str, sdk_session_id: &str) -> Result<(), String> {
     conn.execute(
-        "DELETE FROM workspace_skills WHERE skill_id = ?1",
-        rusqlite::params![skill_id],
+        "UPDATE agent_run_checkpoints SET sdk_session_id = ?2, updated_at = datetime('now') || 'Z' WHERE agent_id = ?1",
+        rusqlite::params![agent_id, sdk_session_id],
     )
-    .map_err(|e| format!("delete_workspace_skill: {}", e))?;
+    .map_err(|e| e.to_string())?;
     Ok(())
 }
 
-pub fn get_workspace_skill(
-    conn: &Connection,
-    skill_id: &str,
-) -> Result<Option<WorkspaceSkill>, String> {
-    let mut stmt = conn
-        .prepare(&format!(
-            "SELECT {WS_COLUMNS} FROM workspace_skills WHERE skill_id = ?1"
-        ))
-        .map_err(|e| format!("get_workspace_skill: {}", e))?;
-    let mut rows = stmt
-        .query_map(rusqlite::params![skill_id], row_to_workspace_skill)
-        .map_err(|e| format!("get_workspace_skill query: {}", e))?;
-    match rows.next() {
-        Some(row) => Ok(Some(
-            row.map_err(|e| format!("get_workspace_skill row: {}", e))?,
-        )),
-        None => Ok(None),
-    }
+pub fn set_checkpoint_transcript(conn: &Connection, agent_id: &str, transcript_path: &str) -> Result<(), String> {
+    conn.execute(
+        "UPDATE agent_run_checkpoints SET transcript_path = ?2 WHERE agent_id = ?1",
+        rusqlite::params![agent_id, transcript_path],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
 }
 
-/// Look up a workspace skill by name. Used when skill_id is not known.
-pub fn get_workspace_skill_by_name(
-    conn: &Connection,
-    skill_name: &str,
-) -> Result<Option<WorkspaceSkill>, String> {
-    let mut stmt = conn
-        .prepare(&format!(
-            "SELECT {WS_COLUMNS} FROM workspace_skills WHERE skill_name = ?1"
-        ))
-        .map_err(|e| format!("get_workspace_skill_by_name: {}", e))?;
-    let mut rows = stmt
-        .query_map(rusqlite::params![skill_name], row_to_workspace_skill)
-        .map_err(|e| format!("get_workspace_skill_by_name query: {}", e))?;
-    match rows.next() {
-        Some(row) => {
-            Ok(Some(row.map_err(|e| {
-                format!("get_workspace_skill_by_name row: {}", e)
-            })?))
-        }
-        None => Ok(None),
-    }
+pub fn set_checkpoint_turns(conn: &Connection, agent_id: &str, turns: u32) -> Result<(), String> {
+    conn.execute(
+        "UPDATE agent_run_checkpoints SET turns = ?2, updated_at = datetime('now') || 'Z' WHERE agent_id = ?1",
+        rusqlite::params![agent_id, turns],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
 }
 
-/// Look up a workspace skill by name and source registry URL.
-/// Returns only skills that were imported from the specified registry (marketplace_source_url = source_url).
-/// Used to avoid false-positive update notifications for bundled skills sharing a name with marketplace skills.
-#[allow(dead_code)]
-pub fn get_workspace_skill_by_name_and_source(
-    conn: &Connection,
-    skill_name: &str,
-    source_url: &str,
-) -> Result<Option<WorkspaceSkill>, String> {
-    let mut stmt = conn.prepare(
-        &format!("SELECT {WS_COLUMNS} FROM workspace_skills WHERE skill_name = ?1 AND marketplace_source_url = ?2")
-    ).map_err(|e| format!("get_workspace_skill_by_name_and_source: {}", e))?;
-    let mut rows = stmt
-        .query_map(
-            rusqlite::params![skill_name, source_url],
-            row_to_workspace_skill,
-        )
-        .map_err(|e| format!("get_workspace_skill_by_name_and_source query: {}", e))?;
-    match rows.next() {
-        Some(row) => Ok(Some(row.map_err(|e| {
-            format!("get_workspace_skill_by_name_and_source row: {}", e)
-        })?)),
-        None => Ok(None),
-    }
+/// `status` is "completed" or "interrupted".
+pub fn set_checkpoint_status(conn: &Connection, agent_id: &str, status: &str) -> Result<(), String> {
+    conn.execute(
+        "UPDATE agent_run_checkpoints SET status = ?2, updated_at = datetime('now') || 'Z' WHERE agent_id = ?1",
+        rusqlite::params![agent_id, status],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
 }
 
-/// Look up an active workspace skill by its purpose tag.
-/// Returns the first active skill with the given purpose, or None if not found.
-pub fn get_workspace_skill_by_purpose(
+/// The newest run of a step that can be resumed: one that was interrupted, or
+/// is still marked running although the app instance that started it is gone.
+/// Runs whose SDK session never started have nothing to resume.
+pub fn find_resumable_checkpoint(
     conn: &Connection,
-    purpose: &str,
-) -> rusqlite::Result<Option<WorkspaceSkill>> {
-    let mut stmt = conn.prepare(
-        &format!(
-            "SELECT {WS_COLUMNS} FROM workspace_skills WHERE purpose = ?1 AND is_active = 1 ORDER BY imported_at DESC, skill_name ASC LIMIT 1"
+    skill_name: &str,
+    step_id: u32,
+) -> Result<Option<AgentRunCheckpoint>, String> {
+    let checkpoint = conn
+        .query_row(
+            &format!(
+                "SELECT {CHECKPOINT_COLUMNS} FROM agent_run_checkpoints
+                 WHERE skill_name = ?1 AND step_id = ?2 AND sdk_session_id IS NOT NULL
+                   AND status IN ('running', 'interrupted')
+                 ORDER BY started_at DESC LIMIT 1"
+            ),
+            rusqlite::params![skill_name, step_id],
+            row_to_checkpoint,
         )
-    )?;
-    let mut rows = stmt.query_map(rusqlite::params![purpose], row_to_workspace_skill)?;
-    match rows.next() {
-        Some(row) => Ok(Some(row?)),
-        None => Ok(None),
-    }
+        .optional()
+        .map_err(|e| e.to_string())?;
+    Ok(checkpoint.filter(|c| {
+        c.status == "interrupted" || (c.pid != std::process::id() && !check_pid_alive(c.pid))
+    }))
 }
 
-/// Update the content_hash for a workspace skill row identified by skill_name.
-pub fn set_workspace_skill_content_hash(
+// --- Automation Hook Deliveries ---
+
+/// Deliveries kept in the log; older rows are pruned on insert.
+const MAX_HOOK_DELIVERIES: i64 = 500;
+
+#[allow(clippy::too_many_arguments)]
+pub fn record_hook_delivery(
     conn: &Connection,
+    hook_name: &str,
+    event: &str,
     skill_name: &str,
-    hash: &str,
+    payload: &str,
+    attempts: u32,
+    status: &str,
+    detail: &str,
 ) -> Result<(), String> {
     conn.execute(
-        "UPDATE workspace_skills SET content_hash = ?1 WHERE skill_name = ?2",
-        rusqlite::params![hash, skill_name],
+        "INSERT INTO automation_hook_deliveries (hook_name, event, skill_name, payload, attempts, status, detail)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![hook_name, event, skill_name, payload, attempts, status, detail],
     )
-    .map_err(|e| format!("set_workspace_skill_content_hash: {}", e))?;
-    Ok(())
-}
-
-/// Update the content_hash for an imported skill row identified by skill_name.
-pub fn set_imported_skill_content_hash(
-    conn: &Connection,
-    skill_name: &str,
-    hash: &str,
-) -> Result<(), String> {
+    .map_err(|e| e.to_string())?;
     conn.execute(
-        "UPDATE imported_skills SET content_hash = ?1 WHERE skill_name = ?2",
-        rusqlite::params![hash, skill_name],
+        "DELETE FROM automation_hook_deliveries WHERE id <= (SELECT MAX(id) FROM automation_hook_deliveries) - ?1",
+        [MAX_HOOK_DELIVERIES],
     )
-    .map_err(|e| format!("set_imported_skill_content_hash: {}", e))?;
+    .map_err(|e| e.to_string())?;
     Ok(())
 }
 
-/// Read disk_path and content_hash for a workspace skill by name.
-pub fn get_workspace_skill_hash_info(
-    conn: &Connection,
-    skill_name: &str,
-) -> Result<Option<(String, Option<String>)>, String> {
+/// Most recent deliveries first.
+pub fn list_hook_deliveries(conn: &Connection, limit: u32) -> Result<Vec<HookDelivery>, String> {
     let mut stmt = conn
-        .prepare("SELECT disk_path, content_hash FROM workspace_skills WHERE skill_name = ?1")
-        .map_err(|e| format!("get_workspace_skill_hash_info: {}", e))?;
-    let mut rows = stmt
-        .query_map(rusqlite::params![skill_name], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+        .prepare(
+            "SELECT id, hook_name, event, skill_name, payload, attempts, status, detail, created_at
+             FROM automation_hook_deliveries ORDER BY id DESC LIMIT ?1",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([limit], |row| {
+            Ok(HookDelivery {
+                id: row.get(0)?,
+                hook_name: row.get(1)?,
+                event: row.get(2)?,
+                skill_name: row.get(3)?,
+                payload: row.get(4)?,
+                attempts: row.get(5)?,
+                status: row.get(6)?,
+                detail: row.get(7)?,
+                created_at: row.get(8)?,
+            })
         })
-        .map_err(|e| format!("get_workspace_skill_hash_info query: {}", e))?;
-    match rows.next() {
-        Some(row) => {
-            Ok(Some(row.map_err(|e| {
-                format!("get_workspace_skill_hash_info row: {}", e)
-            })?))
-        }
-        None => Ok(None),
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+// --- Skill Dependencies ---
+
+/// Replace the declared dependencies of `skill_name`.
+pub fn set_skill_dependencies(conn: &Connection, skill_name: &str, depends_on: &[String]) -> Result<(), String> {
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM skill_dependencies WHERE skill_name = ?1", [skill_name])
+        .map_err(|e| e.to_string())?;
+    for dependency in depends_on {
+        tx.execute(
+            "INSERT OR IGNORE INTO skill_dependencies (skill_name, depends_on) VALUES (?1, ?2)",
+            rusqlite::params![skill_name, dependency],
+        )
+        .map_err(|e| e.to_string())?;
     }
+    tx.commit().map_err(|e| e.to_string())
 }
 
-/// Read disk_path and content_hash for an imported (marketplace/library) skill by name.
-pub fn get_imported_skill_hash_info(
-    conn: &Connection,
-    skill_name: &str,
-) -> Result<Option<(String, Option<String>)>, String> {
+/// Every declared `(skill_name, depends_on)` edge.
+pub fn list_skill_dependencies(conn: &Connection) -> Result<Vec<(String, String)>, String> {
     let mut stmt = conn
-        .prepare("SELECT disk_path, content_hash FROM imported_skills WHERE skill_name = ?1")
-        .map_err(|e| format!("get_imported_skill_hash_info: {}", e))?;
-    let mut rows = stmt
-        .query_map(rusqlite::params![skill_name], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
-        })
-        .map_err(|e| format!("get_imported_skill_hash_info query: {}", e))?;
-    match rows.next() {
-        Some(row) => {
-            Ok(Some(row.map_err(|e| {
-                format!("get_imported_skill_hash_info row: {}", e)
-            })?))
-        }
-        None => Ok(None),
-    }
+        .prepare("SELECT skill_name, depends_on FROM skill_dependencies ORDER BY skill_name, depends_on")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
 }
 
-/// Look up an imported (library) skill by name and source registry URL.
-/// Returns only skills that were imported from the specified registry (marketplace_source_url = source_url).
-/// Used to avoid false-positive update notifications for bundled skills sharing a name with marketplace skills.
-#[allow(dead_code)]
-pub fn get_imported_skill_by_name_and_source(
-    conn: &Connection,
-    skill_name: &str,
-    source_url: &str,
-) -> Result<Option<ImportedSkill>, String> {
-    let mut stmt = conn.prepare(
-        "SELECT skill_id, skill_name, is_active, disk_path, imported_at, is_bundled,
-                purpose, version, model, argument_hint, user_invocable, disable_model_invocation, marketplace_source_url
-         FROM imported_skills WHERE skill_name = ?1 AND marketplace_source_url = ?2"
-    ).map_err(|e| format!("get_imported_skill_by_name_and_source: {}", e))?;
+// --- Skill Test Cases ---
 
-    let result = stmt.query_row(rusqlite::params![skill_name, source_url], |row| {
-        Ok(ImportedSkill {
-            skill_id: row.get(0)?,
-            skill_name: row.get(1)?,
-            is_active: row.get::<_, i32>(2)? != 0,
-            disk_path: row.get(3)?,
-            imported_at: row.get(4)?,
-            is_bundled: row.get::<_, i32>(5)? != 0,
-            description: None,
-            purpose: row.get(6)?,
-            version: row.get(7)?,
-            model: row.get(8)?,
-            argument_hint: row.get(9)?,
-            user_invocable: row.get::<_, Option<i32>>(10)?.map(|v| v != 0),
-            disable_model_invocation: row.get::<_, Option<i32>>(11)?.map(|v| v != 0),
-            marketplace_source_url: row.get(12)?,
+fn row_to_skill_test_case(row: &rusqlite::Row) -> rusqlite::Result<SkillTestCase> {
+    let expected_contains: String = row.get(5)?;
+    Ok(SkillTestCase {
+        id: row.get(0)?,
+        skill_name: row.get(1)?,
+        name: row.get(2)?,
+        prompt: row.get(3)?,
+        expect_trigger: row.get(4)?,
+        expected_contains: serde_json::from_str(&expected_contains).unwrap_or_default(),
+        created_at: row.get(6)?,
+    })
+}
+
+/// Store a test case; `id` and `created_at` on the input are ignored. Returns the stored row.
+pub fn insert_skill_test_case(conn: &Connection, case: &SkillTestCase) -> Result<SkillTestCase, String> {
+    let expected_contains = serde_json::to_string(&case.expected_contains).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO skill_test_cases (skill_name, name, prompt, expect_trigger, expected_contains)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![case.skill_name, case.name, case.prompt, case.expect_trigger, expected_contains],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.query_row(
+        "SELECT id, skill_name, name, prompt, expect_trigger, expected_contains, created_at
+         FROM skill_test_cases WHERE id = ?1",
+        [conn.last_insert_rowid()],
+        row_to_skill_test_case,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Test cases of a skill, oldest first.
+pub fn list_skill_test_cases(conn: &Connection, skill_name: &str) -> Result<Vec<SkillTestCase>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, skill_name, name, prompt, expect_trigger, expected_contains, created_at
+             FROM skill_test_cases WHERE skill_name = ?1 ORDER BY id",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([skill_name], row_to_skill_test_case)
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+/// Delete a test case and its recorded results. Returns whether it existed.
+pub fn delete_skill_test_case(conn: &Connection, id: i64) -> Result<bool, String> {
+    conn.execute("DELETE FROM skill_test_results WHERE test_case_id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    let deleted = conn
+        .execute("DELETE FROM skill_test_cases WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(deleted > 0)
+}
+
+/// Store one test result; `id` and `created_at` on the input are ignored. Returns the new id.
+pub fn insert_skill_test_result(conn: &Connection, result: &SkillTestResult) -> Result<i64, String> {
+    let failures = serde_json::to_string(&result.failures).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO skill_test_results (test_case_id, skill_name, run_id, skill_version,
+             triggered, passed, response, failures, error, duration_ms)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        rusqlite::params![
+            result.test_case_id,
+            result.skill_name,
+            result.run_id,
+            result.skill_version,
+            result.triggered,
+            result.passed,
+            result.response,
+            failures,
+            result.error,
+            result.duration_ms,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Results of one run of a skill's tests, or of its latest run when `run_id`
+/// is `None`, in test case order.
+pub fn list_skill_test_results(
+    conn: &Connection,
+    skill_name: &str,
+    run_id: Option<&str>,
+) -> Result<Vec<SkillTestResult>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT r.id, r.test_case_id, c.name, r.skill_name, r.run_id, r.skill_version,
+                    r.triggered, r.passed, r.response, r.failures, r.error, r.duration_ms, r.created_at
+             FROM skill_test_results r
+             JOIN skill_test_cases c ON c.id = r.test_case_id
+             WHERE r.skill_name = ?1
+               AND r.run_id = COALESCE(?2, (SELECT run_id FROM skill_test_results
+                                            WHERE skill_name = ?1 ORDER BY id DESC LIMIT 1))
+             ORDER BY r.test_case_id",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params![skill_name, run_id], |row| {
+            let failures: String = row.get(9)?;
+            Ok(SkillTestResult {
+                id: row.get(0)?,
+                test_case_id: row.get(1)?,
+                test_name: row.get(2)?,
+                skill_name: row.get(3)?,
+                run_id: row.get(4)?,
+                skill_version: row.get(5)?,
+                triggered: row.get(6)?,
+                passed: row.get(7)?,
+                response: row.get(8)?,
+                failures: serde_json::from_str(&failures).unwrap_or_default(),
+                error: row.get(10)?,
+                duration_ms: row.get(11)?,
+                created_at: row.get(12)?,
+            })
         })
-    });
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
 
-    match result {
-        Ok(mut skill) => {
-            hydrate_skill_metadata(&mut skill);
-            Ok(Some(skill))
-        }
-        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-        Err(e) => Err(format!("get_imported_skill_by_name_and_source: {}", e)),
-    }
+// --- Quality Gate Waivers ---
+
+/// Record (or replace) the waiver for `check` on a skill.
+pub fn upsert_quality_waiver(conn: &Connection, waiver: &QualityWaiver) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO quality_gate_waivers (skill_name, check_id, justification, waived_by, waived_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(skill_name, check_id) DO UPDATE SET
+            justification = ?3, waived_by = ?4, waived_at = ?5",
+        rusqlite::params![
+            waiver.skill_name,
+            waiver.check,
+            waiver.justification,
+            waiver.waived_by,
+            waiver.waived_at
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
 }
 
-/// Return the names of all locally installed skills.
-/// Combines workflow_runs (generated/marketplace skills), imported_skills (GitHub imports),
-/// and workspace_skills (Settings → Skills).
-pub fn get_all_installed_skill_names(conn: &Connection) -> Result<Vec<String>, String> {
+/// Remove a waiver. Returns whether one existed.
+pub fn delete_quality_waiver(conn: &Connection, skill_name: &str, check: &str) -> Result<bool, String> {
+    let deleted = conn
+        .execute(
+            "DELETE FROM quality_gate_waivers WHERE skill_name = ?1 AND check_id = ?2",
+            rusqlite::params![skill_name, check],
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(deleted > 0)
+}
+
+pub fn list_quality_waivers(conn: &Connection, skill_name: &str) -> Result<Vec<QualityWaiver>, String> {
     let mut stmt = conn
         .prepare(
-            "SELECT skill_name FROM workflow_runs
-         UNION
-         SELECT skill_name FROM imported_skills
-         UNION
-         SELECT skill_name FROM workspace_skills",
+            "SELECT skill_name, check_id, justification, waived_by, waived_at
+             FROM quality_gate_waivers WHERE skill_name = ?1 ORDER BY check_id",
         )
         .map_err(|e| e.to_string())?;
-    let names = stmt
-        .query_map([], |row| row.get::<_, String>(0))
+    let rows = stmt
+        .query_map([skill_name], |row| {
+            Ok(QualityWaiver {
+                skill_name: row.get(0)?,
+                check: row.get(1)?,
+                justification: row.get(2)?,
+                waived_by: row.get(3)?,
+                waived_at: row.get(4)?,
+            })
+        })
         .map_err(|e| e.to_string())?
-        .filter_map(|r| r.ok())
-        .collect();
-    Ok(names)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(rows)
 }
 
-/// Return names of all skills in the skills master table.
-/// Used by the skill-library (dashboard) path to check which skills are already installed.
-pub fn get_dashboard_skill_names(conn: &Connection) -> Result<Vec<String>, String> {
+// --- Skill Events ---
+
+/// Skill events kept; observers only need the ones since they last polled.
+const MAX_SKILL_EVENTS: i64 = 1000;
+
+pub fn record_skill_event(conn: &Connection, skill_name: &str, instance_id: &str, kind: &str) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO skill_events (skill_name, instance_id, kind) VALUES (?1, ?2, ?3)",
+        rusqlite::params![skill_name, instance_id, kind],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM skill_events WHERE id <= (SELECT MAX(id) FROM skill_events) - ?1",
+        [MAX_SKILL_EVENTS],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Events for `skill_name` after `after_id` made by instances other than `instance_id`, oldest first.
+pub fn list_skill_events_after(
+    conn: &Connection,
+    skill_name: &str,
+    after_id: i64,
+    instance_id: &str,
+) -> Result<Vec<SkillEvent>, String> {
     let mut stmt = conn
-        .prepare("SELECT name FROM skills WHERE COALESCE(deleted_at, '') = ''")
+        .prepare(
+            "SELECT id, skill_name, instance_id, kind, created_at FROM skill_events
+             WHERE skill_name = ?1 AND id > ?2 AND instance_id != ?3 ORDER BY id",
+        )
         .map_err(|e| e.to_string())?;
-    let names = stmt
-        .query_map([], |row| row.get::<_, String>(0))
-        .map_err(|e| e.to_string())?
-        .filter_map(|r| r.ok())
-        .collect();
-    Ok(names)
+    let rows = stmt
+        .query_map(rusqlite::params![skill_name, after_id, instance_id], |row| {
+            Ok(SkillEvent {
+                id: row.get(0)?,
+                skill_name: row.get(1)?,
+                instance_id: row.get(2)?,
+                kind: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+}
+
+/// Id of the newest event recorded by any instance, or 0.
+pub fn latest_skill_event_id(conn: &Connection) -> Result<i64, String> {
+    conn.query_row("SELECT COALESCE(MAX(id), 0) FROM skill_events", [], |row| row.get(0))
+        .map_err(|e| e.to_string())
 }
 
 // --- Skill Locks ---
@@ -3404,6 +7919,11 @@ pub fn acquire_skill_lock(
 
     if result.is_ok() {
         conn.execute_batch("COMMIT").map_err(|e| e.to_string())?;
+        crate::db_events::emit(crate::db_events::DbChange::LockAcquired {
+            skill_name: skill_name.to_string(),
+            instance_id: instance_id.to_string(),
+            pid,
+        });
     } else {
         let _ = conn.execute_batch("ROLLBACK");
     }
@@ -3419,11 +7939,18 @@ pub fn release_skill_lock(
         Some(id) => id,
         None => return Ok(()), // Lock doesn't exist — nothing to release
     };
-    conn.execute(
-        "DELETE FROM skill_locks WHERE skill_id = ?1 AND instance_id = ?2",
-        rusqlite::params![s_id, instance_id],
-    )
-    .map_err(|e| e.to_string())?;
+    let released = conn
+        .execute(
+            "DELETE FROM skill_locks WHERE skill_id = ?1 AND instance_id = ?2",
+            rusqlite::params![s_id, instance_id],
+        )
+        .map_err(|e| e.to_string())?;
+    if released > 0 {
+        crate::db_events::emit(crate::db_events::DbChange::LockReleased {
+            skill_name: skill_name.to_string(),
+            instance_id: instance_id.to_string(),
+        });
+    }
     Ok(())
 }
 
@@ -3555,14 +8082,37 @@ pub fn create_workflow_session(
 }
 
 pub fn end_workflow_session(conn: &Connection, session_id: &str) -> Result<(), String> {
-    conn.execute(
-        "UPDATE workflow_sessions SET ended_at = datetime('now') || 'Z' WHERE session_id = ?1 AND ended_at IS NULL",
-        [session_id],
-    )
-    .map_err(|e| e.to_string())?;
+    let ended = conn
+        .execute(
+            "UPDATE workflow_sessions SET ended_at = datetime('now') || 'Z' WHERE session_id = ?1 AND ended_at IS NULL",
+            [session_id],
+        )
+        .map_err(|e| e.to_string())?;
+    if ended > 0 {
+        crate::db_events::emit(crate::db_events::DbChange::SessionEnded {
+            session_id: session_id.to_string(),
+        });
+    }
     Ok(())
 }
 
+/// Most recent open workflow session for `skill_name` owned by `pid`.
+pub fn open_workflow_session_for_pid(
+    conn: &Connection,
+    skill_name: &str,
+    pid: u32,
+) -> Result<Option<String>, String> {
+    conn.query_row(
+        "SELECT session_id FROM workflow_sessions
+         WHERE skill_name = ?1 AND pid = ?2 AND ended_at IS NULL
+         ORDER BY started_at DESC LIMIT 1",
+        rusqlite::params![skill_name, pid as i64],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
 pub fn end_all_sessions_for_pid(conn: &Connection, pid: u32) -> Result<u32, String> {
     let count = conn
         .execute(
@@ -3586,6 +8136,32 @@ pub fn record_reconciliation_event(
     Ok(())
 }
 
+/// Agent runs for a skill over the last `days` days (workflow steps, tests and
+/// refine), excluding runs hidden by a reset.
+pub fn get_skill_run_stats(conn: &Connection, skill_name: &str, days: u32) -> Result<SkillRunStats, String> {
+    conn.query_row(
+        "SELECT COUNT(*),
+                COALESCE(SUM(CASE WHEN status = 'completed' THEN 1 ELSE 0 END), 0),
+                COALESCE(SUM(total_cost), 0.0),
+                MAX(started_at)
+         FROM agent_runs
+         WHERE skill_name = ?1
+           AND reset_marker IS NULL
+           AND started_at >= datetime('now', ?2)",
+        rusqlite::params![skill_name, format!("-{} days", days)],
+        |row| {
+            Ok(SkillRunStats {
+                days,
+                runs: row.get(0)?,
+                completed_runs: row.get(1)?,
+                total_cost: row.get(2)?,
+                last_run_at: row.get(3)?,
+            })
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
 /// Returns true if the given skill has an active workflow session (ended_at IS NULL)
 /// whose PID is still alive. Used by startup reconciliation to skip skills owned by
 /// another running instance.
@@ -3910,6 +8486,45 @@ mod tests {
         run_normalize_model_names_migration(&conn).unwrap();
         run_reconciliation_events_migration(&conn).unwrap();
         run_ghost_running_rows_migration(&conn).unwrap();
+        run_marketplace_catalog_cache_migration(&conn).unwrap();
+        run_trusted_signing_keys_migration(&conn).unwrap();
+        run_skill_parameter_sets_migration(&conn).unwrap();
+        run_skill_library_events_migration(&conn).unwrap();
+        run_activation_decisions_migration(&conn).unwrap();
+        run_command_history_migration(&conn).unwrap();
+        run_workflow_contract_version_migration(&conn).unwrap();
+        run_skill_benchmarks_migration(&conn).unwrap();
+        run_linked_references_migration(&conn).unwrap();
+        run_step_perf_history_migration(&conn).unwrap();
+        run_tool_policies_migration(&conn).unwrap();
+        run_agent_run_ticks_migration(&conn).unwrap();
+        run_skill_review_migration(&conn).unwrap();
+        run_portable_disk_paths_migration(&conn).unwrap();
+        run_workflow_queue_migration(&conn).unwrap();
+        run_quality_waivers_migration(&conn).unwrap();
+        run_skill_changelog_migration(&conn).unwrap();
+        run_artifact_annotations_migration(&conn).unwrap();
+        run_step_run_cache_migration(&conn).unwrap();
+        run_refine_sessions_migration(&conn).unwrap();
+        run_upstream_reviews_migration(&conn).unwrap();
+        run_skill_test_cases_migration(&conn).unwrap();
+        run_agent_tool_calls_migration(&conn).unwrap();
+        run_agent_runs_skill_index_migration(&conn).unwrap();
+        run_skill_push_bases_migration(&conn).unwrap();
+        run_agent_run_checkpoints_migration(&conn).unwrap();
+        run_automation_hook_deliveries_migration(&conn).unwrap();
+        run_skill_events_migration(&conn).unwrap();
+        run_skill_scan_reports_migration(&conn).unwrap();
+        run_agent_transcripts_migration(&conn).unwrap();
+        run_skill_generation_bases_migration(&conn).unwrap();
+        run_skill_invocations_migration(&conn).unwrap();
+        run_agent_run_retry_count_migration(&conn).unwrap();
+        run_skill_package_format_migration(&conn).unwrap();
+        run_refine_batches_migration(&conn).unwrap();
+        run_tag_aliases_migration(&conn).unwrap();
+        run_audit_log_migration(&conn).unwrap();
+        run_refine_file_reviews_migration(&conn).unwrap();
+        run_catalog_tree_sha_migration(&conn).unwrap();
         conn
     }
 
@@ -3939,9 +8554,14 @@ mod tests {
             refine_prompt_suggestions: true,
             splash_shown: false,
             github_oauth_token: None,
+            github_oauth_host: None,
             github_user_login: None,
             github_user_avatar: None,
             github_user_email: None,
+            github_enterprise_url: None,
+            github_oauth_client_id: None,
+            github_ca_cert_path: None,
+            github_accept_invalid_certs: false,
             marketplace_url: None,
             marketplace_registries: vec![],
             marketplace_initialized: false,
@@ -3950,6 +8570,31 @@ mod tests {
             function_role: None,
             dashboard_view_mode: None,
             auto_update: false,
+            team_repo: None,
+            local_model_endpoint: None,
+            local_model: None,
+            activation_policy: Default::default(),
+            accessibility_lint: "advisory".to_string(),
+            block_expired_packaging: false,
+            require_trusted_packages: false,
+            workflow_queue_concurrency: 1,
+            max_concurrent_agents: 3,
+            quality_gate_profile: None,
+            global_budget_usd: None,
+            skill_budgets_usd: Default::default(),
+            feedback_redaction_keywords: vec![],
+            feedback_public_key: None,
+            custom_prompts_dir: None,
+            offline_mode: false,
+            disable_marketplace: false,
+            disable_github: false,
+            automation_hooks: vec![],
+            transcript_archive_mb: 256,
+            retention: Default::default(),
+            claude_data_dir: None,
+            settings_sync_repo: None,
+            settings_sync_local_overrides: vec![],
+            model_routes: vec![],
         };
         write_settings(&conn, &settings).unwrap();
 
@@ -3976,9 +8621,14 @@ mod tests {
             refine_prompt_suggestions: true,
             splash_shown: false,
             github_oauth_token: None,
+            github_oauth_host: None,
             github_user_login: None,
             github_user_avatar: None,
             github_user_email: None,
+            github_enterprise_url: None,
+            github_oauth_client_id: None,
+            github_ca_cert_path: None,
+            github_accept_invalid_certs: false,
             marketplace_url: None,
             marketplace_registries: vec![],
             marketplace_initialized: false,
@@ -3987,6 +8637,31 @@ mod tests {
             function_role: None,
             dashboard_view_mode: None,
             auto_update: false,
+            team_repo: None,
+            local_model_endpoint: None,
+            local_model: None,
+            activation_policy: Default::default(),
+            accessibility_lint: "advisory".to_string(),
+            block_expired_packaging: false,
+            require_trusted_packages: false,
+            workflow_queue_concurrency: 1,
+            max_concurrent_agents: 3,
+            quality_gate_profile: None,
+            global_budget_usd: None,
+            skill_budgets_usd: Default::default(),
+            feedback_redaction_keywords: vec![],
+            feedback_public_key: None,
+            custom_prompts_dir: None,
+            offline_mode: false,
+            disable_marketplace: false,
+            disable_github: false,
+            automation_hooks: vec![],
+            transcript_archive_mb: 256,
+            retention: Default::default(),
+            claude_data_dir: None,
+            settings_sync_repo: None,
+            settings_sync_local_overrides: vec![],
+            model_routes: vec![],
         };
         write_settings(&conn, &settings).unwrap();
 
@@ -4012,9 +8687,14 @@ mod tests {
             refine_prompt_suggestions: true,
             splash_shown: false,
             github_oauth_token: None,
+            github_oauth_host: None,
             github_user_login: None,
             github_user_avatar: None,
             github_user_email: None,
+            github_enterprise_url: None,
+            github_oauth_client_id: None,
+            github_ca_cert_path: None,
+            github_accept_invalid_certs: false,
             marketplace_url: None,
             marketplace_registries: vec![],
             marketplace_initialized: false,
@@ -4023,6 +8703,31 @@ mod tests {
             function_role: None,
             dashboard_view_mode: None,
             auto_update: false,
+            team_repo: None,
+            local_model_endpoint: None,
+            local_model: None,
+            activation_policy: Default::default(),
+            accessibility_lint: "advisory".to_string(),
+            block_expired_packaging: false,
+            require_trusted_packages: false,
+            workflow_queue_concurrency: 1,
+            max_concurrent_agents: 3,
+            quality_gate_profile: None,
+            global_budget_usd: None,
+            skill_budgets_usd: Default::default(),
+            feedback_redaction_keywords: vec![],
+            feedback_public_key: None,
+            custom_prompts_dir: None,
+            offline_mode: false,
+            disable_marketplace: false,
+            disable_github: false,
+            automation_hooks: vec![],
+            transcript_archive_mb: 256,
+            retention: Default::default(),
+            claude_data_dir: None,
+            settings_sync_repo: None,
+            settings_sync_local_overrides: vec![],
+            model_routes: vec![],
         };
         write_settings(&conn, &v1).unwrap();
 
@@ -4041,9 +8746,14 @@ mod tests {
             refine_prompt_suggestions: true,
             splash_shown: false,
             github_oauth_token: None,
+            github_oauth_host: None,
             github_user_login: None,
             github_user_avatar: None,
             github_user_email: None,
+            github_enterprise_url: None,
+            github_oauth_client_id: None,
+            github_ca_cert_path: None,
+            github_accept_invalid_certs: false,
             marketplace_url: None,
             marketplace_registries: vec![],
             marketplace_initialized: false,
@@ -4052,6 +8762,31 @@ mod tests {
             function_role: None,
             dashboard_view_mode: None,
             auto_update: false,
+            team_repo: None,
+            local_model_endpoint: None,
+            local_model: None,
+            activation_policy: Default::default(),
+            accessibility_lint: "advisory".to_string(),
+            block_expired_packaging: false,
+            require_trusted_packages: false,
+            workflow_queue_concurrency: 1,
+            max_concurrent_agents: 3,
+            quality_gate_profile: None,
+            global_budget_usd: None,
+            skill_budgets_usd: Default::default(),
+            feedback_redaction_keywords: vec![],
+            feedback_public_key: None,
+            custom_prompts_dir: None,
+            offline_mode: false,
+            disable_marketplace: false,
+            disable_github: false,
+            automation_hooks: vec![],
+            transcript_archive_mb: 256,
+            retention: Default::default(),
+            claude_data_dir: None,
+            settings_sync_repo: None,
+            settings_sync_local_overrides: vec![],
+            model_routes: vec![],
         };
         write_settings(&conn, &v2).unwrap();
 
@@ -4060,6 +8795,60 @@ mod tests {
         assert_eq!(loaded.workspace_path.as_deref(), Some("/new/path"));
     }
 
+    #[test]
+    fn test_team_taxonomy_roundtrip_is_separate_from_app_settings() {
+        let conn = create_test_db();
+        assert!(read_team_taxonomy(&conn).unwrap().is_none());
+
+        let taxonomy = TeamTaxonomy {
+            allowed_tags: vec!["finance".to_string()],
+            domains: vec!["Revenue recognition".to_string()],
+            source_repo: Some("acme/team".to_string()),
+            ..Default::default()
+        };
+        write_team_taxonomy(&conn, &taxonomy).unwrap();
+        assert_eq!(read_team_taxonomy(&conn).unwrap(), Some(taxonomy));
+
+        // App settings live under their own key and are unaffected
+        let settings = read_settings(&conn).unwrap();
+        assert!(settings.team_repo.is_none());
+    }
+
+    #[test]
+    fn test_cached_catalog_roundtrip_and_touch() {
+        let conn = create_test_db();
+        assert!(get_cached_catalog(&conn, "github.com/acme/skills@main").unwrap().is_none());
+
+        let catalog = CachedCatalog {
+            cache_key: "github.com/acme/skills@main".to_string(),
+            tree_sha: Some("abc".to_string()),
+            blob_shas: [("plugins/a/skills/x/SKILL.md".to_string(), "def".to_string())].into(),
+            registry_name: Some("Acme".to_string()),
+            skills: vec![crate::types::AvailableSkill {
+                path: "plugins/a/skills/x".to_string(),
+                name: "x".to_string(),
+                plugin_name: None,
+                description: None,
+                purpose: None,
+                version: Some("1.0.0".to_string()),
+                model: None,
+                argument_hint: None,
+                user_invocable: None,
+                disable_model_invocation: None,
+            }],
+            fetched_at: "2026-01-01T00:00:00+00:00".to_string(),
+        };
+        save_cached_catalog(&conn, &catalog).unwrap();
+        touch_cached_catalog(&conn, "github.com/acme/skills@main", "2026-01-02T00:00:00+00:00").unwrap();
+
+        let loaded = get_cached_catalog(&conn, "github.com/acme/skills@main").unwrap().unwrap();
+        assert_eq!(loaded.tree_sha.as_deref(), Some("abc"));
+        assert_eq!(loaded.blob_shas, catalog.blob_shas);
+        assert_eq!(loaded.skills.len(), 1);
+        assert_eq!(loaded.skills[0].name, "x");
+        assert_eq!(loaded.fetched_at, "2026-01-02T00:00:00+00:00");
+    }
+
     #[test]
     fn test_migration_is_idempotent() {
         let conn = Connection::open_in_memory().unwrap();
@@ -4411,6 +9200,7 @@ mod tests {
         run_backfill_null_versions_migration(&conn).unwrap();
         run_rename_purpose_drop_domain_migration(&conn).unwrap();
         run_skills_soft_delete_migration(&conn).unwrap();
+        run_skill_review_migration(&conn).unwrap();
 
         // Verify skills master was populated
         let skills = list_all_skills(&conn).unwrap();
@@ -4465,79 +9255,353 @@ mod tests {
     }
 
     #[test]
-    fn test_tags_normalize_lowercase_trim() {
+    fn test_tags_normalize_lowercase_trim() {
+        let conn = create_test_db();
+        upsert_skill(&conn, "my-skill", "skill-builder", "domain").unwrap();
+        set_skill_tags(
+            &conn,
+            "my-skill",
+            &["  Analytics ".into(), "SALESFORCE".into(), "  ".into()],
+        )
+        .unwrap();
+        let tags = get_tags_for_skills(&conn, &vec!["my-skill".to_string()])
+            .unwrap()
+            .remove("my-skill")
+            .unwrap_or_default();
+        assert_eq!(tags, vec!["analytics", "salesforce"]);
+    }
+
+    #[test]
+    fn test_tags_deduplicate() {
+        let conn = create_test_db();
+        upsert_skill(&conn, "my-skill", "skill-builder", "domain").unwrap();
+        set_skill_tags(
+            &conn,
+            "my-skill",
+            &["analytics".into(), "analytics".into(), "Analytics".into()],
+        )
+        .unwrap();
+        let tags = get_tags_for_skills(&conn, &vec!["my-skill".to_string()])
+            .unwrap()
+            .remove("my-skill")
+            .unwrap_or_default();
+        assert_eq!(tags, vec!["analytics"]);
+    }
+
+    #[test]
+    fn test_set_tags_replaces() {
+        let conn = create_test_db();
+        upsert_skill(&conn, "my-skill", "skill-builder", "domain").unwrap();
+        set_skill_tags(&conn, "my-skill", &["old-tag".into()]).unwrap();
+        set_skill_tags(&conn, "my-skill", &["new-tag".into()]).unwrap();
+        let tags = get_tags_for_skills(&conn, &vec!["my-skill".to_string()])
+            .unwrap()
+            .remove("my-skill")
+            .unwrap_or_default();
+        assert_eq!(tags, vec!["new-tag"]);
+    }
+
+    #[test]
+    fn test_get_tags_for_skills_batch() {
+        let conn = create_test_db();
+        upsert_skill(&conn, "skill-a", "skill-builder", "domain").unwrap();
+        upsert_skill(&conn, "skill-b", "skill-builder", "domain").unwrap();
+        upsert_skill(&conn, "skill-c", "skill-builder", "domain").unwrap();
+        set_skill_tags(&conn, "skill-a", &["tag1".into(), "tag2".into()]).unwrap();
+        set_skill_tags(&conn, "skill-b", &["tag2".into(), "tag3".into()]).unwrap();
+        set_skill_tags(&conn, "skill-c", &["tag1".into()]).unwrap();
+
+        let names = vec!["skill-a".into(), "skill-b".into(), "skill-c".into()];
+        let map = get_tags_for_skills(&conn, &names).unwrap();
+        assert_eq!(map.get("skill-a").unwrap(), &vec!["tag1", "tag2"]);
+        assert_eq!(map.get("skill-b").unwrap(), &vec!["tag2", "tag3"]);
+        assert_eq!(map.get("skill-c").unwrap(), &vec!["tag1"]);
+    }
+
+    #[test]
+    fn test_get_all_tags() {
+        let conn = create_test_db();
+        upsert_skill(&conn, "skill-a", "skill-builder", "domain").unwrap();
+        upsert_skill(&conn, "skill-b", "skill-builder", "domain").unwrap();
+        set_skill_tags(&conn, "skill-a", &["beta".into(), "alpha".into()]).unwrap();
+        set_skill_tags(&conn, "skill-b", &["beta".into(), "gamma".into()]).unwrap();
+
+        let all = get_all_tags(&conn).unwrap();
+        assert_eq!(all, vec!["alpha", "beta", "gamma"]);
+    }
+
+    fn tags_of(conn: &Connection, skill: &str) -> Vec<String> {
+        get_tags_for_skills(conn, &[skill.to_string()])
+            .unwrap()
+            .remove(skill)
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_set_tags_normalizes_hierarchy_and_resolves_aliases() {
+        let conn = create_test_db();
+        upsert_skill(&conn, "my-skill", "skill-builder", "domain").unwrap();
+        conn.execute("INSERT INTO tag_aliases (alias, tag) VALUES ('sfdc', 'salesforce')", [])
+            .unwrap();
+        set_skill_tags(
+            &conn,
+            "my-skill",
+            &[" Finance / Revenue/ ".into(), "SFDC".into(), "sfdc/objects".into(), "sfdcx".into()],
+        )
+        .unwrap();
+        assert_eq!(
+            tags_of(&conn, "my-skill"),
+            vec!["finance/revenue", "salesforce", "salesforce/objects", "sfdcx"]
+        );
+    }
+
+    #[test]
+    fn test_rename_tag_moves_subtree_and_leaves_alias() {
+        let conn = create_test_db();
+        upsert_skill(&conn, "a", "skill-builder", "domain").unwrap();
+        upsert_skill(&conn, "b", "skill-builder", "domain").unwrap();
+        set_skill_tags(&conn, "a", &["finance/revenue".into(), "financeops".into()]).unwrap();
+        set_skill_tags(&conn, "b", &["finance".into()]).unwrap();
+
+        let mut skills = rename_tag(&conn, "finance", "fin").unwrap();
+        skills.sort();
+        assert_eq!(skills, vec!["a", "b"]);
+        assert_eq!(tags_of(&conn, "a"), vec!["fin/revenue", "financeops"]);
+        assert_eq!(tags_of(&conn, "b"), vec!["fin"]);
+
+        // Old spellings written later land on the new name.
+        set_skill_tags(&conn, "b", &["finance/costs".into()]).unwrap();
+        assert_eq!(tags_of(&conn, "b"), vec!["fin/costs"]);
+
+        assert!(rename_tag(&conn, "fin", "fin/old").unwrap_err().contains("under itself"));
+        assert!(rename_tag(&conn, "fin/costs", "financeops").unwrap_err().contains("merge"));
+        assert!(rename_tag(&conn, "missing", "other").unwrap_err().contains("not found"));
+
+        // Renaming back makes the old name canonical again.
+        rename_tag(&conn, "fin", "finance").unwrap();
+        let aliases = list_tag_aliases(&conn).unwrap();
+        assert_eq!(aliases, vec![TagAlias { alias: "fin".into(), tag: "finance".into() }]);
+    }
+
+    #[test]
+    fn test_merge_tags_folds_sources_into_target() {
+        let conn = create_test_db();
+        upsert_skill(&conn, "a", "skill-builder", "domain").unwrap();
+        upsert_skill(&conn, "b", "skill-builder", "domain").unwrap();
+        set_skill_tags(&conn, "a", &["sfdc".into(), "salesforce".into()]).unwrap();
+        set_skill_tags(&conn, "b", &["sf/objects".into()]).unwrap();
+
+        let mut skills = merge_tags(&conn, &["sfdc".into(), "SF".into(), "crm".into()], "salesforce").unwrap();
+        skills.sort();
+        assert_eq!(skills, vec!["a", "b"]);
+        assert_eq!(tags_of(&conn, "a"), vec!["salesforce"]);
+        assert_eq!(tags_of(&conn, "b"), vec!["salesforce/objects"]);
+
+        // An unused source only gains the alias.
+        set_skill_tags(&conn, "b", &["crm".into()]).unwrap();
+        assert_eq!(tags_of(&conn, "b"), vec!["salesforce"]);
+        // A target that is itself an alias resolves first.
+        merge_tags(&conn, &["force".into()], "sfdc").unwrap();
+        assert!(list_tag_aliases(&conn)
+            .unwrap()
+            .contains(&TagAlias { alias: "force".into(), tag: "salesforce".into() }));
+
+        assert!(merge_tags(&conn, &["salesforce".into()], "salesforce").is_err());
+        assert!(merge_tags(&conn, &["salesforce".into()], "salesforce/objects").is_err());
+        remove_tag_alias(&conn, "crm").unwrap();
+        assert!(remove_tag_alias(&conn, "crm").is_err());
+    }
+
+    #[test]
+    fn test_get_tag_tree_fills_in_parents() {
+        let conn = create_test_db();
+        upsert_skill(&conn, "a", "skill-builder", "domain").unwrap();
+        upsert_skill(&conn, "b", "skill-builder", "domain").unwrap();
+        set_skill_tags(&conn, "a", &["finance/revenue/rev-rec".into(), "finance/revenue".into()]).unwrap();
+        set_skill_tags(&conn, "b", &["finance/costs".into(), "sales".into()]).unwrap();
+
+        let tree = get_tag_tree(&conn).unwrap();
+        assert_eq!(tree.tags.iter().map(|n| n.path.as_str()).collect::<Vec<_>>(), ["finance", "sales"]);
+        let finance = &tree.tags[0];
+        assert_eq!((finance.direct_count, finance.skill_count), (0, 2));
+        let revenue = &finance.children[1];
+        assert_eq!(revenue.path, "finance/revenue");
+        assert_eq!((revenue.direct_count, revenue.skill_count), (1, 1));
+        assert_eq!(revenue.children[0].name, "rev-rec");
+        assert_eq!(revenue.children[0].path, "finance/revenue/rev-rec");
+        assert!(tree.aliases.is_empty());
+    }
+
+    #[test]
+    fn test_audit_log_is_append_only() {
+        let conn = create_test_db();
+        insert_audit_entry(&conn, "inst-1", Some("alice"), "skill.created", Some("revenue"), "created")
+            .unwrap();
+
+        let update = conn.execute("UPDATE audit_log SET summary = 'edited'", []);
+        assert!(update.unwrap_err().to_string().contains("append-only"));
+        let delete = conn.execute("DELETE FROM audit_log", []);
+        assert!(delete.unwrap_err().to_string().contains("append-only"));
+
+        let entries = list_audit_log(&conn, &AuditLogFilter::default(), None).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].summary, "created");
+        assert_eq!(entries[0].actor.as_deref(), Some("alice"));
+    }
+
+    #[test]
+    fn test_list_audit_log_filters_newest_first() {
         let conn = create_test_db();
-        upsert_skill(&conn, "my-skill", "skill-builder", "domain").unwrap();
-        set_skill_tags(
-            &conn,
-            "my-skill",
-            &["  Analytics ".into(), "SALESFORCE".into(), "  ".into()],
+        insert_audit_entry(&conn, "inst-1", None, "skill.created", Some("revenue"), "created").unwrap();
+        insert_audit_entry(&conn, "inst-2", None, "lock.acquired", Some("revenue"), "locked").unwrap();
+        insert_audit_entry(&conn, "inst-1", None, "skill.renamed", Some("billing"), "renamed from 100%_bill")
+            .unwrap();
+
+        let all = list_audit_log(&conn, &AuditLogFilter::default(), None).unwrap();
+        assert_eq!(
+            all.iter().map(|e| e.action.as_str()).collect::<Vec<_>>(),
+            ["skill.renamed", "lock.acquired", "skill.created"]
+        );
+        assert_eq!(list_audit_log(&conn, &AuditLogFilter::default(), Some(1)).unwrap().len(), 1);
+
+        let by_skill = AuditLogFilter { skill_name: Some("revenue".into()), ..Default::default() };
+        assert_eq!(list_audit_log(&conn, &by_skill, None).unwrap().len(), 2);
+        let by_instance = AuditLogFilter {
+            instance_id: Some("inst-2".into()),
+            action: Some("lock.acquired".into()),
+            ..Default::default()
+        };
+        assert_eq!(list_audit_log(&conn, &by_instance, None).unwrap().len(), 1);
+        // LIKE wildcards in the query match literally.
+        let literal = AuditLogFilter { query: Some("100%_b".into()), ..Default::default() };
+        assert_eq!(list_audit_log(&conn, &literal, None).unwrap()[0].skill_name.as_deref(), Some("billing"));
+        let wildcard = AuditLogFilter { query: Some("1_0".into()), ..Default::default() };
+        assert!(list_audit_log(&conn, &wildcard, None).unwrap().is_empty());
+        let future = AuditLogFilter { since: Some("2999-01-01 00:00:00Z".into()), ..Default::default() };
+        assert!(list_audit_log(&conn, &future, None).unwrap().is_empty());
+    }
+
+    /// Usage rows started `days_ago` days back: a finished agent run, its tool
+    /// call, an ended workflow session and an audit entry.
+    fn insert_aged_usage(conn: &Connection, id: &str, days_ago: u32) {
+        let age = format!("-{} days", days_ago);
+        conn.execute(
+            "INSERT INTO agent_runs (agent_id, skill_name, step_id, model, status, started_at)
+             VALUES (?1, 'sales', 0, 'sonnet', 'completed', datetime('now', ?2) || 'Z')",
+            rusqlite::params![id, age],
         )
         .unwrap();
-        let tags = get_tags_for_skills(&conn, &vec!["my-skill".to_string()])
-            .unwrap()
-            .remove("my-skill")
-            .unwrap_or_default();
-        assert_eq!(tags, vec!["analytics", "salesforce"]);
-    }
-
-    #[test]
-    fn test_tags_deduplicate() {
-        let conn = create_test_db();
-        upsert_skill(&conn, "my-skill", "skill-builder", "domain").unwrap();
-        set_skill_tags(
-            &conn,
-            "my-skill",
-            &["analytics".into(), "analytics".into(), "Analytics".into()],
+        conn.execute(
+            "INSERT INTO agent_tool_calls (agent_id, skill_name, step_id, tool_use_id, tool_name, created_at)
+             VALUES (?1, 'sales', 0, 't1', 'Read', datetime('now', ?2) || 'Z')",
+            rusqlite::params![id, age],
         )
         .unwrap();
-        let tags = get_tags_for_skills(&conn, &vec!["my-skill".to_string()])
+        conn.execute(
+            "INSERT INTO workflow_sessions (session_id, skill_name, pid, started_at, ended_at)
+             VALUES (?1, 'sales', 1, datetime('now', ?2) || 'Z', datetime('now', ?2) || 'Z')",
+            rusqlite::params![id, age],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO audit_log (instance_id, action, summary, created_at)
+             VALUES ('inst-1', 'skill.created', ?1, datetime('now', ?2) || 'Z')",
+            rusqlite::params![id, age],
+        )
+        .unwrap();
+    }
+
+    fn count_rows(conn: &Connection, table: &str) -> i64 {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
             .unwrap()
-            .remove("my-skill")
-            .unwrap_or_default();
-        assert_eq!(tags, vec!["analytics"]);
     }
 
     #[test]
-    fn test_set_tags_replaces() {
+    fn test_prune_expired_data_by_category() {
         let conn = create_test_db();
-        upsert_skill(&conn, "my-skill", "skill-builder", "domain").unwrap();
-        set_skill_tags(&conn, "my-skill", &["old-tag".into()]).unwrap();
-        set_skill_tags(&conn, "my-skill", &["new-tag".into()]).unwrap();
-        let tags = get_tags_for_skills(&conn, &vec!["my-skill".to_string()])
-            .unwrap()
-            .remove("my-skill")
-            .unwrap_or_default();
-        assert_eq!(tags, vec!["new-tag"]);
+        insert_aged_usage(&conn, "old", 100);
+        insert_aged_usage(&conn, "recent", 5);
+
+        // Nothing is pruned without a policy.
+        let none = prune_expired_data(&conn, &RetentionPolicy::default()).unwrap();
+        assert_eq!(none, RetentionPruneResult::default());
+
+        let policy = RetentionPolicy {
+            agent_runs_days: 30,
+            workflow_sessions_days: 30,
+            transcripts_days: 0,
+            audit_log_days: 90,
+        };
+        let result = prune_expired_data(&conn, &policy).unwrap();
+        assert_eq!(
+            result,
+            RetentionPruneResult { agent_runs: 1, workflow_sessions: 1, transcripts: 0, audit_entries: 1 }
+        );
+        assert_eq!(count_rows(&conn, "agent_tool_calls"), 1);
+        let entries = list_audit_log(&conn, &AuditLogFilter::default(), None).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].summary, "recent");
+
+        // The audit log is append-only again once the pass is done.
+        let delete = conn.execute("DELETE FROM audit_log", []);
+        assert!(delete.unwrap_err().to_string().contains("append-only"));
     }
 
     #[test]
-    fn test_get_tags_for_skills_batch() {
+    fn test_purge_user_data_keeps_open_sessions_and_audit_log() {
         let conn = create_test_db();
-        upsert_skill(&conn, "skill-a", "skill-builder", "domain").unwrap();
-        upsert_skill(&conn, "skill-b", "skill-builder", "domain").unwrap();
-        upsert_skill(&conn, "skill-c", "skill-builder", "domain").unwrap();
-        set_skill_tags(&conn, "skill-a", &["tag1".into(), "tag2".into()]).unwrap();
-        set_skill_tags(&conn, "skill-b", &["tag2".into(), "tag3".into()]).unwrap();
-        set_skill_tags(&conn, "skill-c", &["tag1".into()]).unwrap();
+        insert_aged_usage(&conn, "old", 100);
+        conn.execute(
+            "INSERT INTO workflow_sessions (session_id, skill_name, pid) VALUES ('open', 'sales', 1)",
+            [],
+        )
+        .unwrap();
+        let lines = vec![r#"{"type":"assistant","message":{"id":"m1","content":[]}}"#.to_string()];
+        let archived = crate::transcripts::pack_for_archive(&lines).unwrap();
+        save_agent_transcript(&conn, "old", "sales", &archived).unwrap();
+        conn.execute_batch(
+            "INSERT INTO refine_sessions (session_id, skill_name, messages) VALUES ('r1', 'sales', '[]');
+             INSERT INTO skill_test_results (test_case_id, skill_name, run_id, triggered, passed, response, duration_ms)
+                 VALUES (1, 'sales', 'run-1', 1, 1, 'answer', 10);
+             INSERT INTO skill_benchmarks (skill_name, scenario_set, model, judge_model, results_json)
+                 VALUES ('sales', 'default', 'sonnet', 'haiku', '[]');
+             INSERT INTO automation_hook_deliveries (hook_name, event, skill_name, payload, attempts, status)
+                 VALUES ('notify', 'skill.published', 'sales', '{}', 1, 'delivered');",
+        )
+        .unwrap();
 
-        let names = vec!["skill-a".into(), "skill-b".into(), "skill-c".into()];
-        let map = get_tags_for_skills(&conn, &names).unwrap();
-        assert_eq!(map.get("skill-a").unwrap(), &vec!["tag1", "tag2"]);
-        assert_eq!(map.get("skill-b").unwrap(), &vec!["tag2", "tag3"]);
-        assert_eq!(map.get("skill-c").unwrap(), &vec!["tag1"]);
+        let result = purge_user_data(&conn).unwrap();
+        // Agent run, tool call, ended session, refine session, test result,
+        // benchmark and hook delivery
+        assert_eq!(result.usage_rows, 7);
+        assert_eq!(result.transcripts, 1);
+        for table in [
+            "agent_runs",
+            "agent_transcripts",
+            "refine_sessions",
+            "skill_test_results",
+            "skill_benchmarks",
+            "automation_hook_deliveries",
+        ] {
+            assert_eq!(count_rows(&conn, table), 0, "{table}");
+        }
+        assert_eq!(count_rows(&conn, "workflow_sessions"), 1);
+        assert_eq!(count_rows(&conn, "audit_log"), 1);
     }
 
     #[test]
-    fn test_get_all_tags() {
+    fn test_write_settings_audits_changed_fields() {
         let conn = create_test_db();
-        upsert_skill(&conn, "skill-a", "skill-builder", "domain").unwrap();
-        upsert_skill(&conn, "skill-b", "skill-builder", "domain").unwrap();
-        set_skill_tags(&conn, "skill-a", &["beta".into(), "alpha".into()]).unwrap();
-        set_skill_tags(&conn, "skill-b", &["beta".into(), "gamma".into()]).unwrap();
+        let mut settings = read_settings(&conn).unwrap();
+        settings.max_dimensions += 1;
+        write_settings(&conn, &settings).unwrap();
+        // Writing the same settings again records nothing.
+        write_settings(&conn, &settings).unwrap();
 
-        let all = get_all_tags(&conn).unwrap();
-        assert_eq!(all, vec!["alpha", "beta", "gamma"]);
+        let entries = list_audit_log(&conn, &AuditLogFilter::default(), None).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, "settings.changed");
+        assert_eq!(entries[0].summary, "max_dimensions");
     }
 
     #[test]
@@ -4810,6 +9874,10 @@ mod tests {
         assert_eq!(run.duration_api_ms, None);
         assert_eq!(run.tool_use_count, 0);
         assert_eq!(run.compaction_count, 0);
+        assert_eq!(run.retry_count, 0);
+
+        set_agent_run_retry_count(&conn, "agent-1", 2).unwrap();
+        assert_eq!(get_recent_runs(&conn, 10).unwrap()[0].retry_count, 2);
     }
 
     #[test]
@@ -4843,6 +9911,96 @@ mod tests {
         assert!(runs[0].session_id.is_none());
     }
 
+    #[test]
+    fn test_clear_agent_run_cost_keeps_tokens() {
+        let conn = create_test_db();
+        persist_agent_run(
+            &conn, "agent-3", "my-skill", 1, "qwen3-coder", "completed", 500, 200, 0, 0, 0.01, 5000,
+            0, None, None, 0, 0, None, None,
+        )
+        .unwrap();
+        clear_agent_run_cost(&conn, "agent-3").unwrap();
+
+        let cost: Option<f64> = conn
+            .query_row("SELECT total_cost FROM agent_runs WHERE agent_id = 'agent-3'", [], |r| r.get(0))
+            .unwrap();
+        assert!(cost.is_none());
+        let runs = get_recent_runs(&conn, 10).unwrap();
+        assert_eq!(runs[0].input_tokens, 500);
+        assert!(runs[0].total_cost.abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_typical_step_duration_ms_averages_completed_runs_per_model() {
+        let conn = create_test_db();
+        let run = |agent: &str, step: i32, model: &str, status: &str, duration: i64| {
+            persist_agent_run(
+                &conn, agent, "alpha", step, model, status, 100, 50, 0, 0, 0.01, duration, 1, None,
+                None, 0, 0, None, None,
+            )
+            .unwrap();
+        };
+        run("a0", 0, "sonnet", "completed", 60_000);
+        run("a1", 0, "claude-sonnet-4-6", "completed", 90_000);
+        run("a2", 0, "sonnet", "error", 5_000);
+        run("a3", 0, "opus", "completed", 300_000);
+        run("a4", 1, "sonnet", "completed", 10_000);
+
+        assert_eq!(typical_step_duration_ms(&conn, 0, "sonnet").unwrap(), Some(75_000));
+        assert_eq!(typical_step_duration_ms(&conn, 0, "opus").unwrap(), Some(300_000));
+        assert_eq!(typical_step_duration_ms(&conn, 2, "sonnet").unwrap(), None);
+    }
+
+    #[test]
+    fn test_get_skill_analytics_aggregates_per_skill() {
+        let conn = create_test_db();
+        let run = |agent: &str, skill: &str, step: i32, status: &str, cost: f64, duration: i64, session: Option<&str>| {
+            persist_agent_run(
+                &conn, agent, skill, step, "sonnet", status, 100, 50, 300, 100, cost, duration, 1, None,
+                None, 0, 0, session, None,
+            )
+            .unwrap();
+        };
+        run("s0", "alpha", 0, "completed", 0.10, 1000, None);
+        run("s1", "alpha", 1, "completed", 0.20, 3000, None);
+        run("s2", "alpha", 2, "error", 0.05, 9000, None);
+        run("r1", "alpha", -10, "completed", 0.01, 500, Some("sdk-a"));
+        run("r2", "alpha", -10, "completed", 0.01, 500, Some("sdk-a"));
+        run("r3", "alpha", -10, "completed", 0.01, 500, Some("sdk-b"));
+        run("b0", "beta", 0, "completed", 0.02, 2000, None);
+
+        let all = get_skill_analytics(&conn, None).unwrap();
+        assert_eq!(all.iter().map(|a| a.skill_name.as_str()).collect::<Vec<_>>(), ["alpha", "beta"]);
+        let alpha = &all[0];
+        assert!((alpha.total_cost - 0.38).abs() < 1e-10);
+        assert_eq!(alpha.run_count, 6);
+        assert_eq!(alpha.refine_sessions, 2);
+        assert!(alpha.last_regenerated_at.is_some());
+        assert!((alpha.avg_step_duration_ms.unwrap() - 2000.0).abs() < 1e-9);
+        assert_eq!(alpha.input_tokens, 600);
+        assert!((alpha.cache_hit_ratio.unwrap() - 0.6).abs() < 1e-10);
+
+        let beta = get_skill_analytics(&conn, Some("beta")).unwrap();
+        assert_eq!(beta.len(), 1);
+        assert_eq!(beta[0].refine_sessions, 0);
+        assert!(get_skill_analytics(&conn, Some("missing")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_agent_tool_calls_ignore_repeated_tool_use_ids() {
+        let conn = create_test_db();
+        insert_agent_tool_call(&conn, "agent-1", "my-skill", 0, "t1", "Read", "/w/a.md", Some(true)).unwrap();
+        insert_agent_tool_call(&conn, "agent-1", "my-skill", 0, "t1", "Read", "/w/a.md", Some(true)).unwrap();
+        insert_agent_tool_call(&conn, "agent-1", "my-skill", 0, "t2", "Bash", "ls", Some(false)).unwrap();
+        insert_agent_tool_call(&conn, "agent-2", "my-skill", 3, "t1", "Write", "/w/b.md", None).unwrap();
+
+        let calls = list_agent_tool_calls(&conn, "agent-1").unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].tool_name, "Read");
+        assert_eq!(calls[1].allowed, Some(false));
+        assert_eq!(list_agent_tool_calls(&conn, "agent-2").unwrap()[0].allowed, None);
+    }
+
     #[test]
     fn test_persist_agent_run_shutdown_does_not_overwrite_completed() {
         let conn = create_test_db();
@@ -5166,6 +10324,66 @@ mod tests {
         assert!((by_step[1].total_cost - 0.18).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_get_step_run_usage_returns_completed_runs_of_step() {
+        let conn = create_test_db();
+        for (agent_id, step_id, model, status) in [
+            ("run-1", 1, "sonnet", "completed"),
+            ("run-1", 1, "haiku", "completed"),
+            ("run-2", 1, "sonnet", "error"),
+            ("run-3", 2, "sonnet", "completed"),
+        ] {
+            persist_agent_run(
+                &conn, agent_id, "skill-a", step_id, model, status, 1000, 500, 0, 0, 0.10, 5000, 0, None, None,
+                0, 0, None, None,
+            )
+            .unwrap();
+        }
+
+        let rows = get_step_run_usage(&conn, 1, 10).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|r| r.agent_id == "run-1"));
+        assert_eq!(rows[0].output_tokens, 500);
+    }
+
+    #[test]
+    fn test_list_skill_groups_aggregates_per_group() {
+        let conn = create_test_db();
+        save_workflow_run(&conn, "skill-a", 3, "in_progress", "domain").unwrap();
+        save_workflow_run(&conn, "skill-b", 5, "completed", "domain").unwrap();
+        upsert_skill(&conn, "skill-c", "marketplace", "platform").unwrap();
+        set_skill_tags(&conn, "skill-a", &["sales".to_string(), "finance".to_string()]).unwrap();
+        set_skill_tags(&conn, "skill-b", &["sales".to_string()]).unwrap();
+        set_skill_review(&conn, "skill-a", Some("2020-01-01"), false).unwrap();
+        set_skill_review(&conn, "skill-b", Some("2999-01-01"), false).unwrap();
+        for (agent_id, skill, cost) in [("run-1", "skill-a", 0.25), ("run-2", "skill-b", 0.50)] {
+            persist_agent_run(
+                &conn, agent_id, skill, 1, "sonnet", "completed", 1000, 500, 0, 0, cost, 5000, 0, None, None, 0, 0,
+                None, None,
+            )
+            .unwrap();
+        }
+
+        let by_type = list_skill_groups(&conn, "type").unwrap();
+        assert_eq!(by_type[0].key.as_deref(), Some("domain"));
+        assert_eq!(by_type[0].skill_count, 2);
+        assert!((by_type[0].total_cost - 0.75).abs() < 1e-9);
+        assert_eq!(by_type[0].stale_count, 1);
+        assert_eq!(by_type[0].skill_names, vec!["skill-a", "skill-b"]);
+        assert_eq!(by_type[1].key.as_deref(), Some("platform"));
+
+        let by_domain = list_skill_groups(&conn, "domain").unwrap();
+        let keys: Vec<Option<&str>> = by_domain.iter().map(|g| g.key.as_deref()).collect();
+        assert_eq!(keys, vec![Some("sales"), Some("finance"), None]);
+        assert_eq!(by_domain[2].skill_names, vec!["skill-c"]);
+
+        let by_health = list_skill_groups(&conn, "health").unwrap();
+        let keys: Vec<Option<&str>> = by_health.iter().map(|g| g.key.as_deref()).collect();
+        assert_eq!(keys, vec![Some("expired"), Some("ok"), None]);
+
+        assert!(list_skill_groups(&conn, "color").is_err());
+    }
+
     #[test]
     fn test_get_usage_by_model_groups_correctly() {
         let conn = create_test_db();
@@ -5824,6 +11042,104 @@ mod tests {
         assert!(ended_at.is_some());
     }
 
+    #[test]
+    fn test_find_resumable_checkpoint() {
+        let conn = create_test_db();
+        let pid = std::process::id();
+        create_agent_run_checkpoint(&conn, "my-skill-step1-1", "my-skill", 1, pid, None).unwrap();
+        // No SDK session yet: nothing to resume.
+        set_checkpoint_status(&conn, "my-skill-step1-1", "interrupted").unwrap();
+        assert!(find_resumable_checkpoint(&conn, "my-skill", 1).unwrap().is_none());
+
+        set_checkpoint_sdk_session(&conn, "my-skill-step1-1", "sdk-1").unwrap();
+        set_checkpoint_turns(&conn, "my-skill-step1-1", 7).unwrap();
+        let found = find_resumable_checkpoint(&conn, "my-skill", 1).unwrap().unwrap();
+        assert_eq!(found.sdk_session_id.as_deref(), Some("sdk-1"));
+        assert_eq!(found.turns, 7);
+
+        // Still running in this process: not resumable.
+        create_agent_run_checkpoint(&conn, "my-skill-step1-2", "my-skill", 1, pid, Some("my-skill-step1-1")).unwrap();
+        set_checkpoint_sdk_session(&conn, "my-skill-step1-2", "sdk-1").unwrap();
+        assert!(find_resumable_checkpoint(&conn, "my-skill", 1).unwrap().is_none());
+
+        // Running in a process that is gone: resumable. The new run replaced the old one.
+        create_agent_run_checkpoint(&conn, "my-skill-step1-3", "my-skill", 1, 99999999, None).unwrap();
+        set_checkpoint_sdk_session(&conn, "my-skill-step1-3", "sdk-3").unwrap();
+        let found = find_resumable_checkpoint(&conn, "my-skill", 1).unwrap().unwrap();
+        assert_eq!(found.agent_id, "my-skill-step1-3");
+        let rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM agent_run_checkpoints", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, 1);
+
+        set_checkpoint_status(&conn, "my-skill-step1-3", "completed").unwrap();
+        assert!(find_resumable_checkpoint(&conn, "my-skill", 1).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_agent_transcripts_evict_least_recently_used() {
+        let conn = create_test_db();
+        let lines = vec![r#"{"type":"assistant","message":{"id":"m1","content":[]}}"#.to_string()];
+        let archived = crate::transcripts::pack_for_archive(&lines).unwrap();
+        let size = archived.content.len() as u64;
+        for agent_id in ["a", "b", "c"] {
+            save_agent_transcript(&conn, agent_id, "sales", &archived).unwrap();
+        }
+        // Reading "a" makes "b" the least recently used.
+        let a = get_agent_transcript(&conn, "a").unwrap().unwrap();
+        assert_eq!((a.skill_name.as_str(), a.message_count, a.compressed_bytes), ("sales", 1, size));
+        assert_eq!(a.messages[0]["message"]["id"], "m1");
+
+        assert_eq!(evict_agent_transcripts(&conn, size * 2).unwrap(), 1);
+        assert!(get_agent_transcript(&conn, "b").unwrap().is_none());
+        assert!(get_agent_transcript(&conn, "c").unwrap().is_some());
+        assert_eq!(evict_agent_transcripts(&conn, size * 2).unwrap(), 0);
+
+        let mut seen = Vec::new();
+        for_each_agent_transcript(&conn, Some("sales"), |agent_id, _, _, _| {
+            seen.push(agent_id.to_string());
+            true
+        })
+        .unwrap();
+        assert_eq!(seen, vec!["c", "a"]);
+    }
+
+    #[test]
+    fn test_hook_deliveries_newest_first_and_pruned() {
+        let conn = create_test_db();
+        record_hook_delivery(&conn, "slack", "skill_packaged", "sales", "{}", 1, "delivered", "HTTP 200").unwrap();
+        record_hook_delivery(&conn, "notify", "step_completed", "sales", "{}", 3, "failed", "exit status: 1").unwrap();
+        let deliveries = list_hook_deliveries(&conn, 10).unwrap();
+        assert_eq!(deliveries.len(), 2);
+        assert_eq!(deliveries[0].hook_name, "notify");
+        assert_eq!(deliveries[0].attempts, 3);
+        assert_eq!(deliveries[1].status, "delivered");
+
+        for _ in 0..MAX_HOOK_DELIVERIES {
+            record_hook_delivery(&conn, "slack", "skill_packaged", "sales", "{}", 1, "delivered", "HTTP 200").unwrap();
+        }
+        let rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM automation_hook_deliveries", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, MAX_HOOK_DELIVERIES);
+    }
+
+    #[test]
+    fn test_skill_events_skip_own_instance() {
+        let conn = create_test_db();
+        assert_eq!(latest_skill_event_id(&conn).unwrap(), 0);
+        record_skill_event(&conn, "sales", "editor", "workflow.saved").unwrap();
+        let start = latest_skill_event_id(&conn).unwrap();
+        record_skill_event(&conn, "sales", "observer", "workflow.saved").unwrap();
+        record_skill_event(&conn, "churn", "editor", "workflow.saved").unwrap();
+        record_skill_event(&conn, "sales", "editor", "lock.released").unwrap();
+
+        let events = list_skill_events_after(&conn, "sales", start, "observer").unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, "lock.released");
+        assert_eq!(list_skill_events_after(&conn, "sales", 0, "observer").unwrap().len(), 2);
+    }
+
     #[test]
     fn test_reconcile_orphaned_sessions_live_pid() {
         let conn = create_test_db();
@@ -5844,6 +11160,81 @@ mod tests {
         assert!(ended_at.is_none());
     }
 
+    fn sample_tick(agent_id: &str) -> AgentCostTick {
+        AgentCostTick {
+            agent_id: agent_id.to_string(),
+            skill_name: "my-skill".to_string(),
+            step_id: 2,
+            model: "sonnet".to_string(),
+            input_tokens: 1200,
+            output_tokens: 300,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+            total_cost: 0.0081,
+            estimated: true,
+            num_turns: 4,
+            elapsed_ms: 90_000,
+            workflow_session_id: Some("sess-1".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_recover_orphaned_run_ticks() {
+        let conn = create_test_db();
+        create_workflow_session(&conn, "sess-1", "my-skill", 99999999).unwrap();
+        for id in ["crashed", "persisted", "live"] {
+            save_agent_run_tick(&conn, &sample_tick(id)).unwrap();
+        }
+        conn.execute(
+            "UPDATE agent_run_ticks SET pid = 99999999 WHERE agent_id != 'live'",
+            [],
+        )
+        .unwrap();
+        // A later persist would normally clear the tick; simulate one that raced the crash.
+        persist_agent_run(
+            &conn, "persisted", "my-skill", 2, "sonnet", "completed", 5000, 900, 0, 0, 0.5,
+            120_000, 9, None, None, 0, 0, None, Some("sess-1"),
+        )
+        .unwrap();
+        save_agent_run_tick(&conn, &sample_tick("persisted")).unwrap();
+        conn.execute(
+            "UPDATE agent_run_ticks SET pid = 99999999 WHERE agent_id = 'persisted'",
+            [],
+        )
+        .unwrap();
+
+        assert_eq!(recover_orphaned_run_ticks(&conn).unwrap(), 1);
+
+        let (status, cost, model): (String, f64, String) = conn
+            .query_row(
+                "SELECT status, total_cost, model FROM agent_runs WHERE agent_id = 'crashed'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(status, "shutdown");
+        assert!((cost - 0.0081).abs() < 1e-9);
+        assert_eq!(model, "claude-sonnet-4-6");
+
+        let persisted_cost: f64 = conn
+            .query_row(
+                "SELECT total_cost FROM agent_runs WHERE agent_id = 'persisted'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!((persisted_cost - 0.5).abs() < 1e-9);
+
+        let remaining: Vec<String> = conn
+            .prepare("SELECT agent_id FROM agent_run_ticks")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(remaining, vec!["live".to_string()]);
+    }
+
     #[test]
     fn test_delete_workflow_run_preserves_usage_sessions() {
         let conn = create_test_db();
@@ -6892,6 +12283,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_library_events_record_workspace_lifecycle() {
+        let conn = create_test_db();
+        let mut skill = make_ws_skill("id-lib", "lib-skill", None, true);
+        skill.version = Some("1.0.0".to_string());
+        insert_workspace_skill(&conn, &skill).unwrap();
+        // Idempotent re-upsert does not add a duplicate event
+        upsert_workspace_skill(&conn, &skill).unwrap();
+        update_workspace_skill_active(&conn, "id-lib", false, "/tmp/lib-skill").unwrap();
+        delete_workspace_skill(&conn, "id-lib").unwrap();
+
+        let events = list_library_events_until(&conn, "9999-12-31 00:00:00Z").unwrap();
+        let kinds: Vec<(&str, bool)> = events
+            .iter()
+            .map(|e| (e.event_type.as_str(), e.is_active))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![("installed", true), ("deactivated", false), ("removed", false)]
+        );
+        assert!(events.iter().all(|e| e.source == LIBRARY_SOURCE_WORKSPACE));
+        assert_eq!(events[0].version.as_deref(), Some("1.0.0"));
+
+        assert!(list_library_events_until(&conn, "2000-01-01 00:00:00Z").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_library_events_migration_seeds_existing_skills_at_install_time() {
+        let conn = create_test_db();
+        insert_workspace_skill(&conn, &make_ws_skill("id-old", "old-skill", None, true)).unwrap();
+        conn.execute("DELETE FROM skill_library_events", []).unwrap();
+
+        run_skill_library_events_migration(&conn).unwrap();
+        let events = list_library_events_until(&conn, "2025-01-01 00:00:00Z").unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].skill_name, "old-skill");
+        assert_eq!(events[0].created_at, "2025-01-01 00:00:00Z");
+    }
+
+    #[test]
+    fn test_activation_decision_override_marks_latest_only() {
+        let conn = create_test_db();
+        record_activation_decision(&conn, "s", true, "default", "source=upload").unwrap();
+        record_activation_decision(&conn, "s", false, "settings rule 1", "source=upload").unwrap();
+
+        // Same state as the decision is not an override
+        assert!(!mark_activation_decision_overridden(&conn, "s", false).unwrap());
+        assert!(mark_activation_decision_overridden(&conn, "s", true).unwrap());
+        // Already overridden
+        assert!(!mark_activation_decision_overridden(&conn, "s", true).unwrap());
+
+        let decisions = list_activation_decisions(&conn, Some("s")).unwrap();
+        assert_eq!(decisions.len(), 2);
+        assert_eq!(decisions[0].matched_rule, "settings rule 1");
+        assert!(decisions[0].overridden_at.is_some());
+        assert!(decisions[1].overridden_at.is_none());
+        assert!(list_activation_decisions(&conn, Some("other")).unwrap().is_empty());
+        assert_eq!(list_activation_decisions(&conn, None).unwrap().len(), 2);
+    }
+
     fn make_ws_skill(
         skill_id: &str,
         skill_name: &str,
@@ -7034,6 +12485,110 @@ mod tests {
         assert!(not_found.is_none());
     }
 
+    #[test]
+    fn test_imported_skill_disk_path_stored_relative_and_resolved_on_read() {
+        let conn = create_test_db();
+        let old_root = tempfile::tempdir().unwrap();
+        let new_root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(new_root.path().join("portable")).unwrap();
+        let mut settings = read_settings(&conn).unwrap();
+        settings.skills_path = Some(old_root.path().to_string_lossy().to_string());
+        write_settings(&conn, &settings).unwrap();
+        upsert_skill(&conn, "portable", "marketplace", "domain").unwrap();
+
+        let imported = ImportedSkill {
+            skill_id: "imp-portable".to_string(),
+            skill_name: "portable".to_string(),
+            is_active: true,
+            disk_path: old_root.path().join("portable").to_string_lossy().to_string(),
+            imported_at: "2025-01-01T00:00:00Z".to_string(),
+            is_bundled: false,
+            description: None,
+            purpose: None,
+            version: None,
+            model: None,
+            argument_hint: None,
+            user_invocable: None,
+            disable_model_invocation: None,
+            marketplace_source_url: None,
+        };
+        insert_imported_skill(&conn, &imported).unwrap();
+        let stored: String = conn
+            .query_row("SELECT disk_path FROM imported_skills WHERE skill_name = 'portable'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(stored, "portable");
+
+        // Same DB opened where the library lives elsewhere.
+        settings.skills_path = Some(new_root.path().to_string_lossy().to_string());
+        write_settings(&conn, &settings).unwrap();
+        let skill = get_imported_skill(&conn, "portable").unwrap().unwrap();
+        assert_eq!(skill.disk_path, new_root.path().join("portable").to_string_lossy());
+    }
+
+    #[test]
+    fn test_budget_status_counts_runs_since_reset() {
+        let conn = create_test_db();
+        let run = |agent: &str, skill: &str, cost: f64| {
+            persist_agent_run(
+                &conn, agent, skill, 1, "sonnet", "completed", 100, 10, 0, 0, cost, 1000, 1, None, None, 0, 0,
+                None, None,
+            )
+            .unwrap();
+        };
+        run("a1", "billing", 4.0);
+        reset_usage(&conn).unwrap();
+        run("a2", "billing", 1.5);
+        run("a3", "hr", 2.0);
+
+        let mut settings = read_settings(&conn).unwrap();
+        settings.skill_budgets_usd.insert("billing".to_string(), 1.5);
+        settings.global_budget_usd = Some(4.0);
+        write_settings(&conn, &settings).unwrap();
+
+        let status = crate::commands::usage::budget_status(&conn, Some("billing")).unwrap();
+        assert!((status.skill_spent_usd - 1.5).abs() < 1e-9);
+        assert!((status.global_spent_usd - 3.5).abs() < 1e-9);
+        assert_eq!(status.state, "exceeded");
+        assert_eq!(status.exceeded_scope.as_deref(), Some("skill"));
+        let err = crate::commands::usage::check_budget_allows_run(&conn, "billing").unwrap_err();
+        assert!(err.contains("$1.50 budget"), "{}", err);
+
+        let status = crate::commands::usage::budget_status(&conn, Some("hr")).unwrap();
+        assert_eq!(status.state, "warning");
+        assert_eq!(status.skill_budget_usd, None);
+        crate::commands::usage::check_budget_allows_run(&conn, "hr").unwrap();
+    }
+
+    #[test]
+    fn test_workflow_queue_claims_in_order_and_skips_after_failure() {
+        let conn = create_test_db();
+        let a0 = enqueue_workflow_job(&conn, "alpha", 0, "/ws").unwrap();
+        let a1 = enqueue_workflow_job(&conn, "alpha", 1, "/ws").unwrap();
+        let b0 = enqueue_workflow_job(&conn, "beta", 0, "/ws").unwrap();
+
+        // One step per skill at a time, oldest first, up to the limit.
+        let ids = |jobs: Vec<WorkflowQueueJob>| jobs.into_iter().map(|j| j.id).collect::<Vec<_>>();
+        assert_eq!(ids(claim_workflow_jobs(&conn, 1).unwrap()), vec![a0]);
+        assert!(claim_workflow_jobs(&conn, 1).unwrap().is_empty());
+        assert_eq!(ids(claim_workflow_jobs(&conn, 3).unwrap()), vec![b0]);
+        assert!(cancel_workflow_job(&conn, a0).is_err());
+
+        finish_workflow_job(&conn, a0, &Err("agent crashed".to_string())).unwrap();
+        let jobs = list_workflow_queue(&conn).unwrap();
+        assert_eq!(jobs[0].id, b0);
+        assert_eq!(jobs[0].status, "running");
+        let skipped = jobs.iter().find(|j| j.id == a1).unwrap();
+        assert_eq!(skipped.status, "cancelled");
+        assert_eq!(skipped.error.as_deref(), Some("Skipped: step 0 failed"));
+
+        // A job left running by a dead process goes back in the queue.
+        conn.execute("UPDATE workflow_queue SET pid = 99999999 WHERE id = ?1", [b0]).unwrap();
+        assert_eq!(requeue_orphaned_workflow_jobs(&conn).unwrap(), 1);
+        assert_eq!(get_workflow_queue_job(&conn, b0).unwrap().unwrap().status, "queued");
+        cancel_workflow_job(&conn, b0).unwrap();
+        assert!(claim_workflow_jobs(&conn, 3).unwrap().is_empty());
+    }
+
     #[test]
     fn test_migration_34_converts_ghost_running_rows_to_shutdown() {
         // Use create_test_db() to get a fully-migrated schema (through migration 34).
@@ -0,0 +1,134 @@
+//! Central bus for database change events.
+//!
+//! Mutations in `db.rs` that the UI lists depend on call `emit` after they
+//! succeed, and the change is sent to the frontend on the `db-change` channel
+//! with a `kind` field naming what changed (`skill.updated`, `lock.acquired`,
+//! ...). Pages update their state from the payload instead of re-running
+//! `list_skills`. Emitting is a no-op until `init` runs in `setup()`, so
+//! database code stays usable from tests and background tasks.
+
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::Emitter;
+
+use crate::types::WorkflowQueueJob;
+
+pub const DB_CHANGE_EVENT: &str = "db-change";
+
+static EMITTER: Mutex<Option<tauri::AppHandle>> = Mutex::new(None);
+
+/// Skill fields as they appear in `SkillSummary`, so the frontend can merge
+/// them into its list without a reload.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SkillChange {
+    pub name: String,
+    pub current_step: Option<String>,
+    pub status: Option<String>,
+    pub last_modified: Option<String>,
+    pub intake_json: Option<String>,
+    pub author_login: Option<String>,
+    pub author_avatar: Option<String>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind")]
+pub enum DbChange {
+    #[serde(rename = "skill.updated")]
+    SkillUpdated { skill: SkillChange },
+    #[serde(rename = "skill.deleted")]
+    SkillDeleted { skill_name: String },
+    #[serde(rename = "lock.acquired")]
+    LockAcquired {
+        skill_name: String,
+        instance_id: String,
+        pid: u32,
+    },
+    #[serde(rename = "lock.released")]
+    LockReleased { skill_name: String, instance_id: String },
+    #[serde(rename = "usage.recorded")]
+    UsageRecorded {
+        agent_id: String,
+        skill_name: String,
+        step_id: i32,
+        model: String,
+        status: String,
+        input_tokens: i32,
+        output_tokens: i32,
+        total_cost: f64,
+    },
+    #[serde(rename = "session.ended")]
+    SessionEnded { session_id: String },
+    #[serde(rename = "queue.updated")]
+    QueueUpdated { job: WorkflowQueueJob },
+}
+
+/// Start delivering events to the frontend. Called once from `setup()`.
+pub fn init(app: tauri::AppHandle) {
+    match EMITTER.lock() {
+        Ok(mut emitter) => *emitter = Some(app),
+        Err(e) => log::warn!("[db_events] Failed to acquire emitter lock: {}", e),
+    }
+}
+
+/// Whether events are being delivered; lets callers skip building payloads that need extra queries.
+pub fn is_active() -> bool {
+    EMITTER.lock().map(|e| e.is_some()).unwrap_or(false)
+}
+
+/// Send a change to the frontend. No-op before `init`; failures are logged
+/// and never surface to the caller.
+pub fn emit(change: DbChange) {
+    let guard = match EMITTER.lock() {
+        Ok(g) => g,
+        Err(e) => {
+            log::warn!("[db_events] Failed to acquire emitter lock: {}", e);
+            return;
+        }
+    };
+    let Some(app) = guard.as_ref() else {
+        return;
+    };
+    if let Err(e) = app.emit(DB_CHANGE_EVENT, &change) {
+        log::warn!("[db_events] Failed to emit {:?}: {}", change, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_is_tagged_with_dotted_kind() {
+        let value = serde_json::to_value(DbChange::LockAcquired {
+            skill_name: "sales".into(),
+            instance_id: "inst-1".into(),
+            pid: 42,
+        })
+        .unwrap();
+        assert_eq!(value["kind"], "lock.acquired");
+        assert_eq!(value["skill_name"], "sales");
+        assert_eq!(value["pid"], 42);
+
+        let skill = SkillChange {
+            name: "sales".into(),
+            current_step: Some("Step 2".into()),
+            status: Some("pending".into()),
+            last_modified: None,
+            intake_json: None,
+            author_login: None,
+            author_avatar: None,
+            tags: vec!["finance".into()],
+        };
+        let value = serde_json::to_value(DbChange::SkillUpdated { skill }).unwrap();
+        assert_eq!(value["kind"], "skill.updated");
+        assert_eq!(value["skill"]["current_step"], "Step 2");
+        assert_eq!(value["skill"]["tags"][0], "finance");
+    }
+
+    #[test]
+    fn test_emit_before_init_is_noop() {
+        assert!(!is_active());
+        emit(DbChange::SessionEnded { session_id: "s1".into() });
+    }
+}
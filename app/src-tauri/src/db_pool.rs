@@ -0,0 +1,325 @@
+//! Connections to the app database: one writer and a few readers.
+//!
+//! SQLite allows a single writer at a time, so every write still goes through
+//! one connection behind a mutex (`lock`). Queries that only read and can run
+//! long (usage and analytics, history, search) call `read` instead, which
+//! hands out one of a few read-only connections. In WAL mode those see the
+//! last committed data without waiting for the writer, so a slow report no
+//! longer holds up agents persisting their runs. When no reader is free, or
+//! none could be opened (in-memory test databases), `read` falls back to the
+//! writer.
+//!
+//! Both paths count how long callers waited and how long connections were
+//! held (`get_db_contention`), and log the call site of any wait or hold slow
+//! enough to stall the UI.
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::panic::Location;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use rusqlite::{Connection, OpenFlags};
+
+use crate::types::{DbContention, DbLockStats};
+
+/// Read-only connections opened beside the writer.
+pub const READ_CONNECTIONS: usize = 3;
+/// Waits and holds at least this long are logged with their call site.
+const SLOW: Duration = Duration::from_millis(250);
+/// How long `read` waits for a free reader before using the writer.
+const READER_WAIT: Duration = Duration::from_secs(2);
+
+#[derive(Debug)]
+pub struct DbLockError(String);
+
+impl fmt::Display for DbLockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for DbLockError {}
+
+#[derive(Default)]
+struct LockCounters {
+    acquisitions: AtomicU64,
+    wait_us_total: AtomicU64,
+    wait_us_max: AtomicU64,
+    slow_waits: AtomicU64,
+    hold_us_max: AtomicU64,
+    slow_holds: AtomicU64,
+}
+
+impl LockCounters {
+    fn record_wait(&self, waited: Duration, kind: &str, caller: &Location<'_>) {
+        let us = waited.as_micros() as u64;
+        self.acquisitions.fetch_add(1, Ordering::Relaxed);
+        self.wait_us_total.fetch_add(us, Ordering::Relaxed);
+        self.wait_us_max.fetch_max(us, Ordering::Relaxed);
+        if waited >= SLOW {
+            self.slow_waits.fetch_add(1, Ordering::Relaxed);
+            log::warn!(
+                "[db] waited {} ms for the {} connection at {}",
+                waited.as_millis(),
+                kind,
+                caller
+            );
+        }
+    }
+
+    fn record_hold(&self, held: Duration, kind: &str, caller: &Location<'_>) {
+        self.hold_us_max
+            .fetch_max(held.as_micros() as u64, Ordering::Relaxed);
+        if held >= SLOW {
+            self.slow_holds.fetch_add(1, Ordering::Relaxed);
+            log::info!(
+                "[db] held the {} connection for {} ms at {}",
+                kind,
+                held.as_millis(),
+                caller
+            );
+        }
+    }
+
+    fn snapshot(&self) -> DbLockStats {
+        DbLockStats {
+            acquisitions: self.acquisitions.load(Ordering::Relaxed),
+            total_wait_ms: self.wait_us_total.load(Ordering::Relaxed) / 1000,
+            max_wait_ms: self.wait_us_max.load(Ordering::Relaxed) / 1000,
+            slow_waits: self.slow_waits.load(Ordering::Relaxed),
+            max_hold_ms: self.hold_us_max.load(Ordering::Relaxed) / 1000,
+            slow_holds: self.slow_holds.load(Ordering::Relaxed),
+        }
+    }
+}
+
+pub struct DbPool {
+    writer: Mutex<Connection>,
+    /// Idle read-only connections.
+    readers: Mutex<Vec<Connection>>,
+    reader_freed: Condvar,
+    read_connections: usize,
+    writer_stats: LockCounters,
+    reader_stats: LockCounters,
+    reader_fallbacks: AtomicU64,
+}
+
+impl DbPool {
+    /// A pool whose reads share the writer, e.g. for an in-memory database.
+    pub fn new(writer: Connection) -> Self {
+        Self::with_readers(writer, Vec::new())
+    }
+
+    pub fn with_readers(writer: Connection, readers: Vec<Connection>) -> Self {
+        DbPool {
+            writer: Mutex::new(writer),
+            read_connections: readers.len(),
+            readers: Mutex::new(readers),
+            reader_freed: Condvar::new(),
+            writer_stats: LockCounters::default(),
+            reader_stats: LockCounters::default(),
+            reader_fallbacks: AtomicU64::new(0),
+        }
+    }
+
+    /// Open `count` read-only connections to the database file at `path`.
+    pub fn open_readers(path: &Path, count: usize) -> Result<Vec<Connection>, rusqlite::Error> {
+        (0..count)
+            .map(|_| {
+                let conn = Connection::open_with_flags(
+                    path,
+                    OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+                )?;
+                conn.pragma_update(None, "busy_timeout", "5000")?;
+                Ok(conn)
+            })
+            .collect()
+    }
+
+    /// The writer connection, for anything that writes or must see its own
+    /// uncommitted changes.
+    #[track_caller]
+    pub fn lock(&self) -> Result<DbGuard<'_>, DbLockError> {
+        let caller = Location::caller();
+        let started = Instant::now();
+        let conn = self
+            .writer
+            .lock()
+            .map_err(|e| DbLockError(format!("Database lock poisoned: {}", e)))?;
+        self.writer_stats
+            .record_wait(started.elapsed(), "writer", caller);
+        Ok(DbGuard {
+            conn,
+            stats: &self.writer_stats,
+            acquired: Instant::now(),
+            caller,
+        })
+    }
+
+    /// A read-only connection, for queries that do not write.
+    #[track_caller]
+    pub fn read(&self) -> Result<DbReadGuard<'_>, DbLockError> {
+        let caller = Location::caller();
+        if self.read_connections > 0 {
+            let started = Instant::now();
+            let mut idle = self.readers.lock().unwrap_or_else(|e| e.into_inner());
+            loop {
+                if let Some(conn) = idle.pop() {
+                    self.reader_stats
+                        .record_wait(started.elapsed(), "reader", caller);
+                    return Ok(DbReadGuard::Reader {
+                        conn: Some(conn),
+                        pool: self,
+                        acquired: Instant::now(),
+                        caller,
+                    });
+                }
+                let Some(remaining) = READER_WAIT.checked_sub(started.elapsed()) else {
+                    break;
+                };
+                idle = self
+                    .reader_freed
+                    .wait_timeout(idle, remaining)
+                    .unwrap_or_else(|e| e.into_inner())
+                    .0;
+            }
+            log::debug!("[db] no free reader at {}; reading through the writer", caller);
+        }
+        self.reader_fallbacks.fetch_add(1, Ordering::Relaxed);
+        Ok(DbReadGuard::Writer(self.lock()?))
+    }
+
+    pub fn contention(&self) -> DbContention {
+        DbContention {
+            writer: self.writer_stats.snapshot(),
+            readers: self.reader_stats.snapshot(),
+            read_connections: self.read_connections,
+            reader_fallbacks: self.reader_fallbacks.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// The writer connection; released on drop.
+pub struct DbGuard<'a> {
+    conn: MutexGuard<'a, Connection>,
+    stats: &'a LockCounters,
+    acquired: Instant,
+    caller: &'static Location<'static>,
+}
+
+impl Deref for DbGuard<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        &self.conn
+    }
+}
+
+impl DerefMut for DbGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Connection {
+        &mut self.conn
+    }
+}
+
+impl Drop for DbGuard<'_> {
+    fn drop(&mut self) {
+        self.stats
+            .record_hold(self.acquired.elapsed(), "writer", self.caller);
+    }
+}
+
+/// A read-only connection (or the writer, when no reader was free); returned
+/// to the pool on drop.
+pub enum DbReadGuard<'a> {
+    Reader {
+        conn: Option<Connection>,
+        pool: &'a DbPool,
+        acquired: Instant,
+        caller: &'static Location<'static>,
+    },
+    Writer(DbGuard<'a>),
+}
+
+impl Deref for DbReadGuard<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        match self {
+            DbReadGuard::Reader { conn, .. } => conn.as_ref().expect("reader returned to pool"),
+            DbReadGuard::Writer(guard) => guard,
+        }
+    }
+}
+
+impl Drop for DbReadGuard<'_> {
+    fn drop(&mut self) {
+        if let DbReadGuard::Reader {
+            conn,
+            pool,
+            acquired,
+            caller,
+        } = self
+        {
+            pool.reader_stats
+                .record_hold(acquired.elapsed(), "reader", caller);
+            if let Some(conn) = conn.take() {
+                pool.readers
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push(conn);
+                pool.reader_freed.notify_one();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readers_see_committed_writes_while_writer_is_held() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pool.db");
+        let writer = Connection::open(&path).unwrap();
+        writer.pragma_update(None, "journal_mode", "WAL").unwrap();
+        writer
+            .execute_batch("CREATE TABLE t (v INTEGER); INSERT INTO t VALUES (1);")
+            .unwrap();
+        let pool = DbPool::with_readers(writer, DbPool::open_readers(&path, 2).unwrap());
+
+        let write = pool.lock().unwrap();
+        let read = pool.read().unwrap();
+        let count: i64 = read
+            .query_row("SELECT COUNT(*) FROM t", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+        assert!(read.execute("INSERT INTO t VALUES (2)", []).is_err());
+        drop(read);
+        drop(write);
+
+        let stats = pool.contention();
+        assert_eq!(stats.read_connections, 2);
+        assert_eq!(stats.writer.acquisitions, 1);
+        assert_eq!(stats.readers.acquisitions, 1);
+        assert_eq!(stats.reader_fallbacks, 0);
+    }
+
+    #[test]
+    fn test_read_without_readers_uses_writer() {
+        let pool = DbPool::new(Connection::open_in_memory().unwrap());
+        pool.lock()
+            .unwrap()
+            .execute_batch("CREATE TABLE t (v INTEGER);")
+            .unwrap();
+        let read = pool.read().unwrap();
+        assert!(read.execute("INSERT INTO t VALUES (1)", []).is_ok());
+        drop(read);
+        let stats = pool.contention();
+        assert_eq!(stats.reader_fallbacks, 1);
+        assert_eq!(stats.writer.acquisitions, 2);
+    }
+}
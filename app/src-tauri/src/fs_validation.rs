@@ -1,5 +1,7 @@
 use crate::cleanup::cleanup_step_files;
+use crate::commands::repo_context::glob_match;
 use crate::commands::workflow::get_step_output_files;
+use crate::types::FileRule;
 use std::path::Path;
 
 /// Inspect files on disk to determine the furthest completed step for a skill.
@@ -108,6 +110,181 @@ pub fn has_skill_output(skill_name: &str, skills_path: &str) -> bool {
     result
 }
 
+// --- File rules ---
+
+/// Size limit of the built-in file rule, matching the base64 read limit.
+const BUILTIN_MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Rules every skill file must pass before team rules are applied.
+fn builtin_file_rules() -> Vec<FileRule> {
+    vec![
+        FileRule {
+            description: Some("every skill needs a SKILL.md".to_string()),
+            required_files: vec!["SKILL.md".to_string()],
+            ..Default::default()
+        },
+        FileRule {
+            description: Some("skills must not ship executables or native libraries".to_string()),
+            forbidden_extensions: ["exe", "dll", "so", "dylib", "msi", "bat", "cmd", "com", "scr"]
+                .iter()
+                .map(|e| e.to_string())
+                .collect(),
+            ..Default::default()
+        },
+        FileRule {
+            description: Some("skill files are limited to 5 MiB".to_string()),
+            max_bytes: Some(BUILTIN_MAX_FILE_BYTES),
+            ..Default::default()
+        },
+    ]
+}
+
+/// Built-in rules followed by the team's, each labelled with where it came from
+/// (`built-in rule N` or `team rule N from owner/repo`) for error messages.
+pub(crate) struct FileRuleSet {
+    rules: Vec<(FileRule, String)>,
+}
+
+impl FileRuleSet {
+    pub(crate) fn new(team_rules: Vec<FileRule>, team_repo: Option<&str>) -> Self {
+        let builtin = builtin_file_rules()
+            .into_iter()
+            .enumerate()
+            .map(|(i, rule)| (rule, format!("built-in rule {}", i + 1)));
+        let team = team_rules.into_iter().enumerate().map(|(i, rule)| {
+            let label = match team_repo {
+                Some(repo) => format!("team rule {} from {}", i + 1, repo),
+                None => format!("team rule {}", i + 1),
+            };
+            (rule, label)
+        });
+        Self { rules: builtin.chain(team).collect() }
+    }
+
+    /// Built-in rules plus the rules in the synced team taxonomy.
+    pub(crate) fn load(conn: &rusqlite::Connection) -> Result<Self, String> {
+        Ok(match crate::db::read_team_taxonomy(conn)? {
+            Some(taxonomy) => Self::new(taxonomy.file_rules, taxonomy.source_repo.as_deref()),
+            None => Self::new(Vec::new(), None),
+        })
+    }
+
+    fn applicable<'a>(&'a self, skill_type: Option<&'a str>) -> impl Iterator<Item = &'a (FileRule, String)> {
+        self.rules.iter().filter(move |(rule, _)| {
+            rule.skill_types.is_empty()
+                || skill_type.is_some_and(|t| rule.skill_types.iter().any(|s| s.eq_ignore_ascii_case(t)))
+        })
+    }
+
+    /// Check one file, `relative_path` being `/`-separated within the skill.
+    pub(crate) fn check_file(&self, relative_path: &str, size: u64, skill_type: Option<&str>) -> Result<(), String> {
+        let extension = Path::new(relative_path)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase());
+        for (rule, label) in self.applicable(skill_type) {
+            if !rule.paths.is_empty() && !rule.paths.iter().any(|p| glob_match(p, relative_path)) {
+                continue;
+            }
+            if let Some(ext) = extension.as_deref() {
+                if rule
+                    .forbidden_extensions
+                    .iter()
+                    .any(|f| f.trim_start_matches('.').eq_ignore_ascii_case(ext))
+                {
+                    return Err(violation(
+                        format!("'{}' has a forbidden extension '.{}'", relative_path, ext),
+                        rule,
+                        label,
+                    ));
+                }
+            }
+            if let Some(max) = rule.max_bytes.filter(|max| size > *max) {
+                return Err(violation(
+                    format!("'{}' is {} bytes, over the {} byte limit", relative_path, size, max),
+                    rule,
+                    label,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check every file in a set, then (with `require_files`) the rules'
+    /// required files.
+    pub(crate) fn check_files(
+        &self,
+        files: &[(String, u64)],
+        skill_type: Option<&str>,
+        require_files: bool,
+    ) -> Result<(), String> {
+        for (path, size) in files {
+            self.check_file(path, *size, skill_type)?;
+        }
+        if !require_files {
+            return Ok(());
+        }
+        for (rule, label) in self.applicable(skill_type) {
+            if let Some(missing) = rule.required_files.iter().find(|r| !files.iter().any(|(p, _)| p == *r)) {
+                return Err(violation(format!("required file '{}' is missing", missing), rule, label));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check a skill directory on disk, skipping hidden entries.
+    pub(crate) fn check_skill_dir(&self, skill_dir: &Path, skill_type: Option<&str>) -> Result<(), String> {
+        let mut files = Vec::new();
+        collect_files(skill_dir, skill_dir, &mut files)?;
+        self.check_files(&files, skill_type, true)
+    }
+
+    /// Check a skill archive before extracting it. Entries outside `prefix`
+    /// are ignored, as `extract_archive` skips them.
+    pub(crate) fn check_archive(
+        &self,
+        archive: &mut zip::ZipArchive<std::fs::File>,
+        prefix: &str,
+        skill_type: Option<&str>,
+        require_files: bool,
+    ) -> Result<(), String> {
+        let mut files = Vec::new();
+        for i in 0..archive.len() {
+            let entry = archive.by_index(i).map_err(|e| e.to_string())?;
+            if entry.is_dir() || entry.name() == crate::commands::skill_signing::SIGNATURE_ENTRY {
+                continue;
+            }
+            if let Some(relative) = entry.name().strip_prefix(prefix).filter(|r| !r.is_empty()) {
+                files.push((relative.to_string(), entry.size()));
+            }
+        }
+        self.check_files(&files, skill_type, require_files)
+    }
+}
+
+fn violation(problem: String, rule: &FileRule, label: &str) -> String {
+    match &rule.description {
+        Some(description) => format!("{} ({}: {})", problem, label, description),
+        None => format!("{} ({})", problem, label),
+    }
+}
+
+fn collect_files(base: &Path, dir: &Path, files: &mut Vec<(String, u64)>) -> Result<(), String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let Ok(meta) = entry.metadata() else { continue };
+        if meta.is_dir() {
+            collect_files(base, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(base) {
+            files.push((relative.to_string_lossy().replace('\\', "/"), meta.len()));
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -327,4 +504,69 @@ mod tests {
         );
         assert_eq!(step, Some(2), "Should detect step 2 with step 0 and 2 output");
     }
+
+    // --- File rules ---
+
+    #[test]
+    fn test_file_rules_builtin_forbidden_extension_names_rule() {
+        let rules = FileRuleSet::new(Vec::new(), None);
+        let err = rules.check_file("references/tool.EXE", 10, None).unwrap_err();
+        assert!(err.contains("forbidden extension '.exe'"));
+        assert!(err.contains("built-in rule 2"));
+        assert!(rules.check_file("references/notes.md", 10, None).is_ok());
+    }
+
+    #[test]
+    fn test_file_rules_team_rule_scoped_by_path_and_skill_type() {
+        let rules = FileRuleSet::new(
+            vec![FileRule {
+                description: Some("keep references small".to_string()),
+                paths: vec!["references/**".to_string()],
+                skill_types: vec!["platform".to_string()],
+                max_bytes: Some(100),
+                ..Default::default()
+            }],
+            Some("acme/skills"),
+        );
+        let err = rules.check_file("references/big.md", 500, Some("platform")).unwrap_err();
+        assert!(err.contains("over the 100 byte limit"));
+        assert!(err.contains("team rule 1 from acme/skills: keep references small"));
+        assert!(rules.check_file("SKILL.md", 500, Some("platform")).is_ok());
+        assert!(rules.check_file("references/big.md", 500, Some("domain")).is_ok());
+    }
+
+    #[test]
+    fn test_file_rules_required_files_only_when_requested() {
+        let rules = FileRuleSet::new(
+            vec![FileRule {
+                skill_types: vec!["source".to_string()],
+                required_files: vec!["references/schema.md".to_string()],
+                ..Default::default()
+            }],
+            None,
+        );
+        let files = vec![("SKILL.md".to_string(), 10)];
+        assert!(rules.check_files(&files, Some("source"), false).is_ok());
+        let err = rules.check_files(&files, Some("source"), true).unwrap_err();
+        assert!(err.contains("required file 'references/schema.md' is missing"));
+        assert!(err.contains("team rule 1"));
+        assert!(rules.check_files(&files, Some("domain"), true).is_ok());
+    }
+
+    #[test]
+    fn test_file_rules_check_skill_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skill_dir = tmp.path().join("my-skill");
+        std::fs::create_dir_all(skill_dir.join("references")).unwrap();
+        std::fs::create_dir_all(skill_dir.join(".git")).unwrap();
+        std::fs::write(skill_dir.join(".git").join("hook.sh"), "x").unwrap();
+        std::fs::write(skill_dir.join("references").join("ref.md"), "# Ref").unwrap();
+
+        let rules = FileRuleSet::new(Vec::new(), None);
+        let err = rules.check_skill_dir(&skill_dir, None).unwrap_err();
+        assert!(err.contains("required file 'SKILL.md' is missing"));
+
+        std::fs::write(skill_dir.join("SKILL.md"), "# Skill").unwrap();
+        assert!(rules.check_skill_dir(&skill_dir, None).is_ok());
+    }
 }
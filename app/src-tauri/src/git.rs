@@ -91,6 +91,69 @@ pub fn commit_all(path: &Path, message: &str) -> Result<Option<String>, String>
         }
     }
 
+    commit_index(&repo, &mut index, message)
+}
+
+/// Stage and commit only the changes under `skill_name/`, leaving any other
+/// uncommitted changes in the working tree. Returns the commit SHA, or
+/// Ok(None) if the skill has nothing to commit.
+pub fn commit_skill(path: &Path, skill_name: &str, message: &str) -> Result<Option<String>, String> {
+    log::debug!("[git] commit_skill '{}' at {} — \"{}\"", skill_name, path.display(), message);
+    let repo = ensure_repo(path)?;
+
+    let mut index = repo
+        .index()
+        .map_err(|e| format!("Failed to get index: {}", e))?;
+    let pathspec = [format!("{}/", skill_name)];
+    index
+        .add_all(pathspec.iter(), git2::IndexAddOption::DEFAULT, None)
+        .map_err(|e| format!("Failed to stage files: {}", e))?;
+    // Drops index entries for files deleted from the skill
+    index
+        .update_all(pathspec.iter(), None)
+        .map_err(|e| format!("Failed to stage deletions: {}", e))?;
+
+    commit_index(&repo, &mut index, message)
+}
+
+/// Put a skill's files back to HEAD, discarding its uncommitted changes.
+pub fn discard_skill_changes(path: &Path, skill_name: &str) -> Result<(), String> {
+    let repo = Repository::open(path).map_err(|e| format!("Failed to open repo: {}", e))?;
+    let head = repo
+        .head()
+        .and_then(|h| h.peel_to_commit())
+        .map_err(|e| format!("Failed to resolve HEAD: {}", e))?;
+    restore_version(path, &head.id().to_string(), skill_name)
+}
+
+/// Put one file (relative to the repo root) back to HEAD, removing it when
+/// HEAD does not have it.
+pub fn discard_file_changes(repo_path: &Path, path: &str) -> Result<(), String> {
+    let repo = Repository::open(repo_path).map_err(|e| format!("Failed to open repo: {}", e))?;
+    let head = repo
+        .head()
+        .ok()
+        .and_then(|h| h.peel_to_tree().ok())
+        .and_then(|tree| read_blob_bytes(&repo, &tree, path));
+    let file_path = repo_path.join(path);
+    match head {
+        Some(content) => {
+            if let Some(parent) = file_path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            std::fs::write(&file_path, content).map_err(|e| format!("Failed to restore {}: {}", path, e))?;
+        }
+        None if file_path.exists() => {
+            std::fs::remove_file(&file_path).map_err(|e| format!("Failed to remove {}: {}", path, e))?;
+        }
+        None => {}
+    }
+    log::debug!("[git] Discarded changes to {}", path);
+    Ok(())
+}
+
+/// Write `index` and commit it on HEAD unless its tree matches HEAD's.
+fn commit_index(repo: &Repository, index: &mut git2::Index, message: &str) -> Result<Option<String>, String> {
     index
         .write()
         .map_err(|e| format!("Failed to write index: {}", e))?;
@@ -121,7 +184,7 @@ pub fn commit_all(path: &Path, message: &str) -> Result<Option<String>, String>
         }
     }
 
-    let sig = default_signature(&repo)?;
+    let sig = default_signature(repo)?;
     let parents: Vec<&git2::Commit> = head_commit.as_ref().into_iter().collect();
     let oid = repo
         .commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
@@ -353,8 +416,314 @@ pub fn restore_version(
     Ok(())
 }
 
+/// Skills present in the last commit made at or before a point in time.
+pub struct TreeSkillsAt {
+    pub sha: String,
+    pub message: String,
+    pub timestamp: String,
+    /// `(skill_name, SKILL.md content)` for each top-level directory with a SKILL.md.
+    pub skills: Vec<(String, String)>,
+}
+
+/// Find the newest commit reachable from HEAD with a commit time at or before
+/// `at_secs` (Unix seconds) and list the skills in its tree. Returns `Ok(None)`
+/// when the repo has no commit that old.
+pub fn skills_at(repo_path: &Path, at_secs: i64) -> Result<Option<TreeSkillsAt>, String> {
+    log::debug!("[git] skills_at {}", at_secs);
+    let repo = Repository::open(repo_path)
+        .map_err(|e| format!("Failed to open repo: {}", e))?;
+
+    let mut revwalk = repo
+        .revwalk()
+        .map_err(|e| format!("Failed to create revwalk: {}", e))?;
+    revwalk
+        .push_head()
+        .map_err(|e| format!("Failed to push HEAD: {}", e))?;
+    revwalk.set_sorting(git2::Sort::TIME).ok();
+
+    let mut found = None;
+    for oid_result in revwalk {
+        let oid = oid_result.map_err(|e| format!("Revwalk error: {}", e))?;
+        let commit = repo
+            .find_commit(oid)
+            .map_err(|e| format!("Failed to find commit {}: {}", oid, e))?;
+        if commit.time().seconds() <= at_secs {
+            found = Some(commit);
+            break;
+        }
+    }
+    let Some(commit) = found else {
+        return Ok(None);
+    };
+
+    let tree = commit
+        .tree()
+        .map_err(|e| format!("Failed to get tree for {}: {}", commit.id(), e))?;
+    let mut skills = Vec::new();
+    for entry in tree.iter() {
+        let Some(name) = entry.name() else { continue };
+        if name.starts_with('.') || entry.kind() != Some(git2::ObjectType::Tree) {
+            continue;
+        }
+        if let Some(content) = read_blob_content(&repo, &tree, &format!("{}/SKILL.md", name)) {
+            skills.push((name.to_string(), content));
+        }
+    }
+
+    let timestamp = chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_default();
+    Ok(Some(TreeSkillsAt {
+        sha: commit.id().to_string(),
+        message: commit.message().unwrap_or("").to_string(),
+        timestamp,
+        skills,
+    }))
+}
+
+/// Content of `path` (relative to the repo root) in the newest commit
+/// reachable from HEAD that has it, with that commit's SHA. Looks past
+/// commits that deleted the file, e.g. a step reset removing SKILL.md before
+/// it is regenerated. Returns `Ok(None)` when no commit has the file.
+pub fn last_committed_file(repo_path: &Path, path: &str) -> Result<Option<(String, String)>, String> {
+    let repo = Repository::open(repo_path)
+        .map_err(|e| format!("Failed to open repo: {}", e))?;
+    if repo.head().is_err() {
+        return Ok(None);
+    }
+
+    let mut revwalk = repo
+        .revwalk()
+        .map_err(|e| format!("Failed to create revwalk: {}", e))?;
+    revwalk
+        .push_head()
+        .map_err(|e| format!("Failed to push HEAD: {}", e))?;
+    revwalk.set_sorting(git2::Sort::TIME).ok();
+
+    for oid_result in revwalk {
+        let oid = oid_result.map_err(|e| format!("Revwalk error: {}", e))?;
+        let commit = repo
+            .find_commit(oid)
+            .map_err(|e| format!("Failed to find commit {}: {}", oid, e))?;
+        let tree = commit
+            .tree()
+            .map_err(|e| format!("Failed to get tree for {}: {}", oid, e))?;
+        if let Some(content) = read_blob_content(&repo, &tree, path) {
+            return Ok(Some((oid.to_string(), content)));
+        }
+    }
+    Ok(None)
+}
+
+/// Content of `path` (relative to the repo root) in the HEAD commit. `Ok(None)`
+/// when there is no HEAD yet or HEAD does not have the file.
+pub fn head_file_content(repo_path: &Path, path: &str) -> Result<Option<String>, String> {
+    let repo = Repository::open(repo_path)
+        .map_err(|e| format!("Failed to open repo: {}", e))?;
+    let Some(tree) = repo.head().ok().and_then(|h| h.peel_to_tree().ok()) else {
+        return Ok(None);
+    };
+    Ok(read_blob_content(&repo, &tree, path))
+}
+
+/// Files under `skill_name/` in commit `sha` (full or abbreviated), as paths
+/// relative to the skill directory. Binary files have no content. Empty when
+/// the commit does not have the skill.
+pub fn skill_files_at(
+    repo_path: &Path,
+    sha: &str,
+    skill_name: &str,
+) -> Result<Vec<(String, Option<String>)>, String> {
+    let repo = Repository::open(repo_path)
+        .map_err(|e| format!("Failed to open repo: {}", e))?;
+    let commit = repo
+        .revparse_single(sha)
+        .and_then(|obj| obj.peel_to_commit())
+        .map_err(|e| format!("Commit {} not found: {}", sha, e))?;
+    let tree = commit
+        .tree()
+        .map_err(|e| format!("Failed to get tree for {}: {}", sha, e))?;
+    let Ok(entry) = tree.get_path(Path::new(skill_name)) else {
+        return Ok(Vec::new());
+    };
+    let skill_tree = repo
+        .find_tree(entry.id())
+        .map_err(|e| format!("'{}' is not a directory in {}: {}", skill_name, sha, e))?;
+
+    let mut files = Vec::new();
+    skill_tree
+        .walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+            if entry.kind() == Some(git2::ObjectType::Blob) {
+                let path = format!("{}{}", dir, entry.name().unwrap_or_default());
+                let content = repo
+                    .find_blob(entry.id())
+                    .ok()
+                    .filter(|blob| !blob.is_binary())
+                    .map(|blob| String::from_utf8_lossy(blob.content()).to_string());
+                files.push((path, content));
+            }
+            git2::TreeWalkResult::Ok
+        })
+        .map_err(|e| format!("Failed to walk {} in {}: {}", skill_name, sha, e))?;
+    Ok(files)
+}
+
+/// Full message of commit `sha`.
+pub fn commit_message(repo_path: &Path, sha: &str) -> Result<String, String> {
+    let repo = Repository::open(repo_path)
+        .map_err(|e| format!("Failed to open repo: {}", e))?;
+    let oid = git2::Oid::from_str(sha)
+        .map_err(|e| format!("Invalid SHA {}: {}", sha, e))?;
+    let commit = repo
+        .find_commit(oid)
+        .map_err(|e| format!("Commit {} not found: {}", sha, e))?;
+    Ok(commit.message().unwrap_or("").to_string())
+}
+
+/// Put back the parent-commit content of every file changed by commit `sha`,
+/// leaving the result uncommitted. Refuses (and changes nothing) if any of
+/// those files has been edited on disk since `sha`. Returns the reverted paths.
+pub fn revert_commit_files(repo_path: &Path, sha: &str) -> Result<Vec<String>, String> {
+    log::info!("[git] Reverting files of commit {}", &sha[..8.min(sha.len())]);
+    let repo = Repository::open(repo_path)
+        .map_err(|e| format!("Failed to open repo: {}", e))?;
+    let oid = git2::Oid::from_str(sha)
+        .map_err(|e| format!("Invalid SHA {}: {}", sha, e))?;
+    let commit = repo
+        .find_commit(oid)
+        .map_err(|e| format!("Commit {} not found: {}", sha, e))?;
+    let tree = commit
+        .tree()
+        .map_err(|e| format!("Failed to get tree for {}: {}", sha, e))?;
+    let parent_tree = commit
+        .parent(0)
+        .map_err(|e| format!("Commit {} has no parent to revert to: {}", sha, e))?
+        .tree()
+        .map_err(|e| format!("Failed to get parent tree for {}: {}", sha, e))?;
+
+    let diff = repo
+        .diff_tree_to_tree(Some(&parent_tree), Some(&tree), None)
+        .map_err(|e| format!("Failed to compute diff: {}", e))?;
+    let paths: Vec<String> = diff
+        .deltas()
+        .filter_map(|d| d.new_file().path().or_else(|| d.old_file().path()))
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+
+    let mut edited = Vec::new();
+    for path in &paths {
+        let on_disk = std::fs::read(repo_path.join(path)).ok();
+        if on_disk != read_blob_bytes(&repo, &tree, path) {
+            edited.push(path.clone());
+        }
+    }
+    if !edited.is_empty() {
+        return Err(format!(
+            "Files changed since commit {}: {}",
+            &sha[..8.min(sha.len())],
+            edited.join(", ")
+        ));
+    }
+
+    for path in &paths {
+        let file_path = repo_path.join(path);
+        match read_blob_bytes(&repo, &parent_tree, path) {
+            Some(content) => std::fs::write(&file_path, content)
+                .map_err(|e| format!("Failed to restore {}: {}", path, e))?,
+            None => std::fs::remove_file(&file_path)
+                .map_err(|e| format!("Failed to remove {}: {}", path, e))?,
+        }
+    }
+    Ok(paths)
+}
+
+/// Clone `url` into `path`, or fetch into the clone already there, and check
+/// out `branch` exactly as it is on the remote. `path` is a working copy the
+/// app owns: local changes and unpushed commits are discarded, and a clone of
+/// a different remote is replaced. A remote without `branch` yet (e.g. an
+/// empty repo) leaves it unborn, ready for a first commit.
+pub fn pull_remote(path: &Path, url: &str, branch: &str, token: Option<&str>) -> Result<(), String> {
+    let same_remote = Repository::open(path).ok().is_some_and(|repo| {
+        repo.find_remote("origin").ok().and_then(|r| r.url().map(str::to_string)).as_deref() == Some(url)
+    });
+    if !same_remote {
+        if path.exists() {
+            std::fs::remove_dir_all(path)
+                .map_err(|e| format!("Failed to clear {}: {}", path.display(), e))?;
+        }
+        std::fs::create_dir_all(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        let repo = Repository::init(path).map_err(|e| format!("Failed to init git repo at {}: {}", path.display(), e))?;
+        repo.remote("origin", url).map_err(|e| format!("Failed to add remote {}: {}", url, e))?;
+    }
+    let repo = Repository::open(path).map_err(|e| format!("Failed to open git repo at {}: {}", path.display(), e))?;
+
+    log::debug!("[git] Fetching {} from {}", branch, url);
+    let mut remote = repo.find_remote("origin").map_err(|e| format!("Failed to find remote: {}", e))?;
+    let mut fetch = git2::FetchOptions::new();
+    fetch.remote_callbacks(remote_callbacks(token));
+    remote
+        .fetch(&[format!("+refs/heads/{0}:refs/remotes/origin/{0}", branch)], Some(&mut fetch), None)
+        .map_err(|e| format!("Failed to fetch {} from {}: {}", branch, url, e))?;
+
+    let local_ref = format!("refs/heads/{}", branch);
+    if let Ok(tracking) = repo.find_reference(&format!("refs/remotes/origin/{}", branch)) {
+        let target = tracking.peel_to_commit().map_err(|e| format!("Failed to read {}: {}", branch, e))?;
+        repo.reference(&local_ref, target.id(), true, "pull")
+            .map_err(|e| format!("Failed to update {}: {}", branch, e))?;
+    }
+    repo.set_head(&local_ref).map_err(|e| format!("Failed to check out {}: {}", branch, e))?;
+    if repo.head().is_ok() {
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force().remove_untracked(true)))
+            .map_err(|e| format!("Failed to check out {}: {}", branch, e))?;
+    }
+    Ok(())
+}
+
+/// Push `branch` of the clone at `path` to its `origin`. Fails when the
+/// remote rejects the update, e.g. because someone pushed in the meantime.
+pub fn push_branch(path: &Path, branch: &str, token: Option<&str>) -> Result<(), String> {
+    let repo = Repository::open(path).map_err(|e| format!("Failed to open git repo at {}: {}", path.display(), e))?;
+    let mut remote = repo.find_remote("origin").map_err(|e| format!("Failed to find remote: {}", e))?;
+    let rejected = std::cell::RefCell::new(None);
+    let mut callbacks = remote_callbacks(token);
+    callbacks.push_update_reference(|_refname, status| {
+        if let Some(status) = status {
+            *rejected.borrow_mut() = Some(status.to_string());
+        }
+        Ok(())
+    });
+    let mut push = git2::PushOptions::new();
+    push.remote_callbacks(callbacks);
+    remote
+        .push(&[format!("refs/heads/{0}:refs/heads/{0}", branch)], Some(&mut push))
+        .map_err(|e| format!("Failed to push {}: {}", branch, e))?;
+    if let Some(status) = rejected.borrow().as_ref() {
+        return Err(format!("The remote rejected the push of {}: {}", branch, status));
+    }
+    log::info!("[git] Pushed {} to {}", branch, remote.url().unwrap_or("origin"));
+    Ok(())
+}
+
 // --- Helpers ---
 
+/// Credentials for remotes: HTTPS with `token` as the password (GitHub OAuth
+/// tokens work this way), SSH through the running agent.
+fn remote_callbacks(token: Option<&str>) -> git2::RemoteCallbacks<'_> {
+    let mut callbacks = git2::RemoteCallbacks::new();
+    callbacks.credentials(move |_url, username, allowed| {
+        if allowed.contains(git2::CredentialType::USER_PASS_PLAINTEXT) {
+            if let Some(token) = token {
+                return git2::Cred::userpass_plaintext("x-access-token", token);
+            }
+        }
+        if allowed.contains(git2::CredentialType::SSH_KEY) {
+            return git2::Cred::ssh_key_from_agent(username.unwrap_or("git"));
+        }
+        git2::Cred::default()
+    });
+    callbacks
+}
+
 fn default_signature(repo: &Repository) -> Result<Signature<'static>, String> {
     // Try repo config first, fall back to a generic signature
     repo.signature()
@@ -397,6 +766,11 @@ fn read_blob_content(repo: &Repository, tree: &git2::Tree, path: &str) -> Option
     Some(String::from_utf8_lossy(blob.content()).to_string())
 }
 
+fn read_blob_bytes(repo: &Repository, tree: &git2::Tree, path: &str) -> Option<Vec<u8>> {
+    let entry = tree.get_path(Path::new(path)).ok()?;
+    Some(repo.find_blob(entry.id()).ok()?.content().to_vec())
+}
+
 /// Remove all files and subdirectories inside a directory (but not the directory itself).
 fn remove_dir_contents(dir: &Path) -> Result<(), String> {
     if !dir.exists() {
@@ -464,6 +838,30 @@ mod tests {
         assert_eq!(sha.as_ref().unwrap().len(), 40); // SHA hex length
     }
 
+    #[test]
+    fn test_revert_commit_files_refuses_later_edits() {
+        let dir = tempdir().unwrap();
+        ensure_repo(dir.path()).unwrap();
+        let skill_md = dir.path().join("my-skill/SKILL.md");
+        std::fs::create_dir_all(skill_md.parent().unwrap()).unwrap();
+        std::fs::write(&skill_md, "v1").unwrap();
+        commit_all(dir.path(), "v1").unwrap();
+        std::fs::write(&skill_md, "v2").unwrap();
+        std::fs::write(dir.path().join("my-skill/notes.md"), "new").unwrap();
+        let sha = commit_all(dir.path(), "v2").unwrap().unwrap();
+
+        std::fs::write(&skill_md, "v3").unwrap();
+        assert!(revert_commit_files(dir.path(), &sha).unwrap_err().contains("my-skill/SKILL.md"));
+        assert_eq!(std::fs::read_to_string(&skill_md).unwrap(), "v3");
+
+        std::fs::write(&skill_md, "v2").unwrap();
+        let mut reverted = revert_commit_files(dir.path(), &sha).unwrap();
+        reverted.sort();
+        assert_eq!(reverted, vec!["my-skill/SKILL.md", "my-skill/notes.md"]);
+        assert_eq!(std::fs::read_to_string(&skill_md).unwrap(), "v1");
+        assert!(!dir.path().join("my-skill/notes.md").exists());
+    }
+
     #[test]
     fn test_commit_all_no_changes() {
         let dir = tempdir().unwrap();
@@ -616,6 +1014,31 @@ mod tests {
         assert!(!skill_dir.join("new-file.md").exists());
     }
 
+    #[test]
+    fn test_discard_file_changes_leaves_other_files() {
+        let dir = tempdir().unwrap();
+        ensure_repo(dir.path()).unwrap();
+        let skill_dir = dir.path().join("my-skill");
+        std::fs::create_dir_all(skill_dir.join("references")).unwrap();
+        std::fs::write(skill_dir.join("SKILL.md"), "# V1").unwrap();
+        std::fs::write(skill_dir.join("references").join("guide.md"), "guide v1").unwrap();
+        commit_all(dir.path(), "v1").unwrap();
+
+        std::fs::write(skill_dir.join("SKILL.md"), "# V2").unwrap();
+        std::fs::remove_file(skill_dir.join("references").join("guide.md")).unwrap();
+        std::fs::write(skill_dir.join("references").join("new.md"), "new").unwrap();
+
+        discard_file_changes(dir.path(), "my-skill/references/guide.md").unwrap();
+        discard_file_changes(dir.path(), "my-skill/references/new.md").unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(skill_dir.join("references").join("guide.md")).unwrap(),
+            "guide v1"
+        );
+        assert!(!skill_dir.join("references").join("new.md").exists());
+        assert_eq!(std::fs::read_to_string(skill_dir.join("SKILL.md")).unwrap(), "# V2");
+    }
+
     #[test]
     fn test_corrupted_repo_handling() {
         let dir = tempdir().unwrap();
@@ -715,4 +1138,48 @@ mod tests {
         let untracked = get_untracked_dirs(dir.path()).unwrap();
         assert!(untracked.is_empty());
     }
+
+    #[test]
+    fn test_skills_at_lists_skills_in_commit_before_time() {
+        let dir = tempdir().unwrap();
+        ensure_repo(dir.path()).unwrap();
+
+        let a_dir = dir.path().join("skill-a");
+        std::fs::create_dir_all(a_dir.join("references")).unwrap();
+        std::fs::write(a_dir.join("SKILL.md"), "---\nversion: 1.2.0\n---\n# A").unwrap();
+        std::fs::create_dir_all(dir.path().join("not-a-skill")).unwrap();
+        std::fs::write(dir.path().join("not-a-skill/notes.md"), "x").unwrap();
+        let sha = commit_all(dir.path(), "add skill-a").unwrap().unwrap();
+
+        let now = chrono::Utc::now().timestamp() + 1;
+        let snapshot = skills_at(dir.path(), now).unwrap().unwrap();
+        assert_eq!(snapshot.sha, sha);
+        assert_eq!(snapshot.skills.len(), 1);
+        assert_eq!(snapshot.skills[0].0, "skill-a");
+        assert!(snapshot.skills[0].1.contains("version: 1.2.0"));
+
+        // Before the repo existed
+        assert!(skills_at(dir.path(), 0).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_last_committed_file_looks_past_deletions() {
+        let dir = tempdir().unwrap();
+        ensure_repo(dir.path()).unwrap();
+        assert!(last_committed_file(dir.path(), "my-skill/SKILL.md").unwrap().is_none());
+
+        let skill_md = dir.path().join("my-skill/SKILL.md");
+        std::fs::create_dir_all(skill_md.parent().unwrap()).unwrap();
+        std::fs::write(&skill_md, "v1").unwrap();
+        let sha = commit_all(dir.path(), "v1").unwrap().unwrap();
+        std::fs::write(dir.path().join("my-skill/notes.md"), "x").unwrap();
+        std::fs::remove_file(&skill_md).unwrap();
+        commit_all(dir.path(), "reset").unwrap().unwrap();
+        std::fs::write(&skill_md, "v2").unwrap();
+
+        let (found, content) = last_committed_file(dir.path(), "my-skill/SKILL.md").unwrap().unwrap();
+        assert_eq!(found, sha);
+        assert_eq!(content, "v1");
+        assert!(last_committed_file(dir.path(), "other/SKILL.md").unwrap().is_none());
+    }
 }
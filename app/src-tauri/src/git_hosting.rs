@@ -0,0 +1,1175 @@
+//! Host-neutral access to git hosting providers (GitHub, GitLab, Bitbucket) for
+//! the team-sharing features: account lookup, repo listing, file trees, raw file
+//! URLs and multi-file commits.
+//!
+//! Every call goes through the `api_base` carried on `HostedRepo` (or passed in
+//! directly), so tests can point it at a mockito server. GitHub writes reuse the
+//! contents-API helpers in `crate::github`.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::github::{GitHubServer, GITHUB_API_BASE};
+
+/// GitLab.com REST API base URL. Self-managed instances use `https://{host}/api/v4`.
+pub const GITLAB_API_BASE: &str = "https://gitlab.com/api/v4";
+
+/// Bitbucket Cloud REST API base URL.
+pub const BITBUCKET_API_BASE: &str = "https://api.bitbucket.org/2.0";
+
+/// Page cap for paginated listings (100 entries per page).
+const MAX_PAGES: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitHost {
+    GitHub,
+    GitLab,
+    Bitbucket,
+}
+
+impl GitHost {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GitHost::GitHub => "github",
+            GitHost::GitLab => "gitlab",
+            GitHost::Bitbucket => "bitbucket",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<GitHost, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "github" => Ok(GitHost::GitHub),
+            "gitlab" => Ok(GitHost::GitLab),
+            "bitbucket" => Ok(GitHost::Bitbucket),
+            other => Err(format!(
+                "Unknown git host '{}': expected github, gitlab or bitbucket",
+                other
+            )),
+        }
+    }
+
+    /// Display name used in error messages.
+    fn label(&self) -> &'static str {
+        match self {
+            GitHost::GitHub => "GitHub",
+            GitHost::GitLab => "GitLab",
+            GitHost::Bitbucket => "Bitbucket",
+        }
+    }
+
+    /// API base for the provider's public cloud.
+    pub fn default_api_base(&self) -> &'static str {
+        match self {
+            GitHost::GitHub => GITHUB_API_BASE,
+            GitHost::GitLab => GITLAB_API_BASE,
+            GitHost::Bitbucket => BITBUCKET_API_BASE,
+        }
+    }
+}
+
+/// A repository on a git host, parsed from a URL by `parse_repo_url`.
+///
+/// `owner` is the GitHub owner, the GitLab namespace (possibly nested, e.g.
+/// `group/sub`) or the Bitbucket workspace. `branch` is `None` when the URL did
+/// not name one; callers resolve it with `default_branch`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostedRepo {
+    pub host: GitHost,
+    pub api_base: String,
+    pub owner: String,
+    pub repo: String,
+    pub branch: Option<String>,
+    pub subpath: Option<String>,
+}
+
+impl HostedRepo {
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.owner, self.repo)
+    }
+
+    /// GitLab addresses projects by their URL-encoded full path.
+    fn gitlab_project_id(&self) -> String {
+        encode_component(&self.full_name())
+    }
+}
+
+/// Percent-encode everything outside the RFC 3986 unreserved set.
+fn encode_component(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+/// Encode each segment of a repo path, keeping the `/` separators.
+fn encode_path(path: &str) -> String {
+    path.split('/').map(encode_component).collect::<Vec<_>>().join("/")
+}
+
+// ---------------------------------------------------------------------------
+// parse_repo_url
+// ---------------------------------------------------------------------------
+
+/// Parse a repository URL on any supported host.
+///
+/// Supported formats:
+/// - GitHub: anything `parse_github_url` accepts (including `owner/repo`
+///   shorthand), resolved on `github` — github.com or the enterprise server
+/// - GitLab: `https://gitlab.com/group[/sub]/repo[/-/tree/branch[/path]]`, and
+///   self-managed hosts whose name contains `gitlab`
+/// - Bitbucket: `https://bitbucket.org/workspace/repo[/src/branch[/path]]`
+pub fn parse_repo_url(url: &str, github: &GitHubServer) -> Result<HostedRepo, String> {
+    let trimmed = url.trim();
+    let without_scheme = trimmed
+        .strip_prefix("https://")
+        .or_else(|| trimmed.strip_prefix("http://"))
+        .unwrap_or(trimmed);
+    let (host_name, path) = without_scheme
+        .split_once('/')
+        .unwrap_or((without_scheme, ""));
+    let host_name = host_name.to_ascii_lowercase();
+
+    if host_name == "bitbucket.org" {
+        return parse_path(GitHost::Bitbucket, BITBUCKET_API_BASE.to_string(), path, url);
+    }
+    if host_name.contains("gitlab") && host_name.contains('.') {
+        let api_base = if host_name == "gitlab.com" {
+            GITLAB_API_BASE.to_string()
+        } else {
+            gitlab_api_base(&host_name)?
+        };
+        return parse_path(GitHost::GitLab, api_base, path, url);
+    }
+
+    let info = crate::commands::github_import::parse_github_url_for(trimmed, github)?;
+    // parse_github_url fills in "main" when no branch is given; leave that to
+    // `default_branch` instead.
+    let explicit_branch = trimmed.contains('#') || trimmed.contains("/tree/");
+    Ok(HostedRepo {
+        host: GitHost::GitHub,
+        api_base: github.api_base.clone(),
+        owner: info.owner,
+        repo: info.repo,
+        branch: explicit_branch.then_some(info.branch),
+        subpath: info.subpath,
+    })
+}
+
+fn parse_path(host: GitHost, api_base: String, path: &str, url: &str) -> Result<HostedRepo, String> {
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    if segments.iter().any(|s| *s == ".." || s.contains('\\')) {
+        return Err(format!("Invalid repository path in URL '{}'", url));
+    }
+
+    // Split the repo path from the branch/subpath marker.
+    let (repo_segments, rest): (&[&str], &[&str]) = match host {
+        GitHost::GitLab => match segments.iter().position(|s| *s == "-") {
+            Some(idx) => (&segments[..idx], &segments[idx + 1..]),
+            None => (&segments[..], &[]),
+        },
+        _ => {
+            let split = segments.len().min(2);
+            (&segments[..split], &segments[split..])
+        }
+    };
+    if repo_segments.len() < 2 {
+        return Err(format!(
+            "Invalid {} URL '{}': expected at least owner/repo",
+            host.label(),
+            url
+        ));
+    }
+
+    let tree_marker = match host {
+        GitHost::Bitbucket => "src",
+        _ => "tree",
+    };
+    let (branch, subpath) = match rest {
+        [] => (None, None),
+        [marker, branch, sub @ ..] if *marker == tree_marker => {
+            let subpath = if sub.is_empty() { None } else { Some(sub.join("/")) };
+            (Some(branch.to_string()), subpath)
+        }
+        _ => {
+            return Err(format!(
+                "Unsupported {} URL format '{}': expected a repository or a /{}/branch[/path] link",
+                host.label(),
+                url,
+                tree_marker
+            ))
+        }
+    };
+
+    let (owner, repo) = repo_segments.split_at(repo_segments.len() - 1);
+    Ok(HostedRepo {
+        host,
+        api_base,
+        owner: owner.join("/"),
+        repo: repo[0].to_string(),
+        branch,
+        subpath,
+    })
+}
+
+/// API base for a self-managed GitLab instance URL such as `https://gitlab.acme.io`.
+pub fn gitlab_api_base(instance_url: &str) -> Result<String, String> {
+    let trimmed = instance_url.trim().trim_end_matches('/');
+    let host_name = trimmed
+        .strip_prefix("https://")
+        .or_else(|| trimmed.strip_prefix("http://"))
+        .unwrap_or(trimmed);
+    if host_name.is_empty() || host_name.contains('/') {
+        return Err(format!(
+            "Invalid GitLab instance URL '{}': expected https://host",
+            instance_url
+        ));
+    }
+    Ok(format!("https://{}/api/v4", host_name.to_ascii_lowercase()))
+}
+
+// ---------------------------------------------------------------------------
+// HTTP helpers
+// ---------------------------------------------------------------------------
+
+/// Build a client authenticated for `host`.
+///
+/// GitHub clients carry `github`'s TLS overrides. GitLab personal/project
+/// access tokens are sent as Bearer tokens. Bitbucket accepts either an access
+/// token (Bearer) or `username:app_password` (Basic).
+pub fn build_client(
+    host: GitHost,
+    token: Option<&str>,
+    github: &GitHubServer,
+) -> Result<reqwest::Client, String> {
+    if host == GitHost::GitHub {
+        return crate::commands::github_import::build_github_client(token, github);
+    }
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert("Accept", "application/json".parse().unwrap());
+    headers.insert("User-Agent", "SkillBuilder".parse().unwrap());
+    if let Some(tok) = token.map(str::trim).filter(|t| !t.is_empty()) {
+        let value = if host == GitHost::Bitbucket && tok.contains(':') {
+            format!(
+                "Basic {}",
+                base64::engine::general_purpose::STANDARD.encode(tok.as_bytes())
+            )
+        } else {
+            format!("Bearer {}", tok)
+        };
+        if let Ok(val) = value.parse() {
+            headers.insert("Authorization", val);
+        }
+    }
+    Ok(reqwest::Client::builder()
+        .default_headers(headers)
+        .timeout(std::time::Duration::from_secs(30))
+        .connect_timeout(std::time::Duration::from_secs(10))
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new()))
+}
+
+async fn send_json(
+    host: GitHost,
+    request: reqwest::RequestBuilder,
+) -> Result<(reqwest::StatusCode, serde_json::Value), String> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("{} API request failed: {}", host.label(), e))?;
+    let status = response.status();
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .unwrap_or(serde_json::Value::Null);
+    Ok((status, body))
+}
+
+fn api_error(host: GitHost, status: reqwest::StatusCode, body: &serde_json::Value) -> String {
+    // GitHub and GitLab use `message`; Bitbucket nests it under `error.message`.
+    let message = body["message"]
+        .as_str()
+        .or_else(|| body["error"]["message"].as_str())
+        .or_else(|| body["error"].as_str())
+        .unwrap_or("Unknown error");
+    format!("{} API error ({}): {}", host.label(), status, message)
+}
+
+async fn get_json(
+    client: &reqwest::Client,
+    host: GitHost,
+    url: &str,
+) -> Result<serde_json::Value, String> {
+    let (status, body) = send_json(host, client.get(url)).await?;
+    if !status.is_success() {
+        return Err(api_error(host, status, &body));
+    }
+    Ok(body)
+}
+
+// ---------------------------------------------------------------------------
+// Accounts and repositories
+// ---------------------------------------------------------------------------
+
+/// A repository visible to the signed-in account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostedRepoSummary {
+    pub full_name: String,
+    pub default_branch: Option<String>,
+    pub web_url: String,
+}
+
+/// Return the login of the account that owns the client's token.
+pub async fn fetch_login(
+    client: &reqwest::Client,
+    host: GitHost,
+    api_base: &str,
+) -> Result<String, String> {
+    let body = get_json(client, host, &format!("{}/user", api_base)).await?;
+    let field = match host {
+        GitHost::GitHub => "login",
+        GitHost::GitLab | GitHost::Bitbucket => "username",
+    };
+    body[field]
+        .as_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| format!("Missing {} in {} user response", field, host.label()))
+}
+
+/// List repositories the signed-in account is a member of.
+pub async fn list_repos(
+    client: &reqwest::Client,
+    host: GitHost,
+    api_base: &str,
+) -> Result<Vec<HostedRepoSummary>, String> {
+    let mut repos = Vec::new();
+    match host {
+        GitHost::GitHub | GitHost::GitLab => {
+            for page in 1..=MAX_PAGES {
+                let url = if host == GitHost::GitHub {
+                    format!("{}/user/repos?per_page=100&sort=updated&page={}", api_base, page)
+                } else {
+                    format!(
+                        "{}/projects?membership=true&simple=true&order_by=last_activity_at&per_page=100&page={}",
+                        api_base, page
+                    )
+                };
+                let body = get_json(client, host, &url).await?;
+                let items = body.as_array().cloned().unwrap_or_default();
+                let count = items.len();
+                for item in items {
+                    let (name_key, url_key) = if host == GitHost::GitHub {
+                        ("full_name", "html_url")
+                    } else {
+                        ("path_with_namespace", "web_url")
+                    };
+                    if let Some(full_name) = item[name_key].as_str() {
+                        repos.push(HostedRepoSummary {
+                            full_name: full_name.to_string(),
+                            default_branch: item["default_branch"].as_str().map(|s| s.to_string()),
+                            web_url: item[url_key].as_str().unwrap_or_default().to_string(),
+                        });
+                    }
+                }
+                if count < 100 {
+                    break;
+                }
+            }
+        }
+        GitHost::Bitbucket => {
+            let mut next = Some(format!("{}/repositories?role=member&pagelen=100", api_base));
+            let mut pages = 0;
+            while let Some(url) = next.take() {
+                let body = get_json(client, host, &url).await?;
+                for item in body["values"].as_array().cloned().unwrap_or_default() {
+                    if let Some(full_name) = item["full_name"].as_str() {
+                        repos.push(HostedRepoSummary {
+                            full_name: full_name.to_string(),
+                            default_branch: item["mainbranch"]["name"].as_str().map(|s| s.to_string()),
+                            web_url: item["links"]["html"]["href"]
+                                .as_str()
+                                .unwrap_or_default()
+                                .to_string(),
+                        });
+                    }
+                }
+                pages += 1;
+                if pages < MAX_PAGES {
+                    next = body["next"].as_str().map(|s| s.to_string());
+                }
+            }
+        }
+    }
+    Ok(repos)
+}
+
+/// Resolve the repository's default branch.
+pub async fn default_branch(client: &reqwest::Client, repo: &HostedRepo) -> Result<String, String> {
+    let (url, pointer) = match repo.host {
+        GitHost::GitHub => (
+            format!("{}/repos/{}/{}", repo.api_base, repo.owner, repo.repo),
+            "/default_branch",
+        ),
+        GitHost::GitLab => (
+            format!("{}/projects/{}", repo.api_base, repo.gitlab_project_id()),
+            "/default_branch",
+        ),
+        GitHost::Bitbucket => (
+            format!("{}/repositories/{}/{}", repo.api_base, repo.owner, repo.repo),
+            "/mainbranch/name",
+        ),
+    };
+    let body = get_json(client, repo.host, &url).await?;
+    Ok(body
+        .pointer(pointer)
+        .and_then(|v| v.as_str())
+        .unwrap_or("main")
+        .to_string())
+}
+
+/// Resolve the branch named in the URL, falling back to the default branch.
+pub async fn resolve_branch(client: &reqwest::Client, repo: &HostedRepo) -> Result<String, String> {
+    match repo.branch.as_deref().filter(|b| !b.is_empty()) {
+        Some(branch) => Ok(branch.to_string()),
+        None => default_branch(client, repo).await,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Files
+// ---------------------------------------------------------------------------
+
+/// List every file path in the repository at `branch`.
+pub async fn list_files(
+    client: &reqwest::Client,
+    repo: &HostedRepo,
+    branch: &str,
+) -> Result<Vec<String>, String> {
+    let mut files = Vec::new();
+    match repo.host {
+        GitHost::GitHub => {
+            let url = format!(
+                "{}/repos/{}/{}/git/trees/{}?recursive=1",
+                repo.api_base,
+                repo.owner,
+                repo.repo,
+                encode_component(branch)
+            );
+            let body = get_json(client, repo.host, &url).await?;
+            for entry in body["tree"].as_array().cloned().unwrap_or_default() {
+                if entry["type"].as_str() == Some("blob") {
+                    if let Some(path) = entry["path"].as_str() {
+                        files.push(path.to_string());
+                    }
+                }
+            }
+        }
+        GitHost::GitLab => {
+            for page in 1..=MAX_PAGES {
+                let url = format!(
+                    "{}/projects/{}/repository/tree?recursive=true&per_page=100&ref={}&page={}",
+                    repo.api_base,
+                    repo.gitlab_project_id(),
+                    encode_component(branch),
+                    page
+                );
+                let body = get_json(client, repo.host, &url).await?;
+                let items = body.as_array().cloned().unwrap_or_default();
+                let count = items.len();
+                for entry in items {
+                    if entry["type"].as_str() == Some("blob") {
+                        if let Some(path) = entry["path"].as_str() {
+                            files.push(path.to_string());
+                        }
+                    }
+                }
+                if count < 100 {
+                    break;
+                }
+            }
+        }
+        GitHost::Bitbucket => {
+            let mut next = Some(format!(
+                "{}/repositories/{}/{}/src/{}/?max_depth=20&pagelen=100",
+                repo.api_base,
+                repo.owner,
+                repo.repo,
+                encode_component(branch)
+            ));
+            let mut pages = 0;
+            while let Some(url) = next.take() {
+                let body = get_json(client, repo.host, &url).await?;
+                for entry in body["values"].as_array().cloned().unwrap_or_default() {
+                    if entry["type"].as_str() == Some("commit_file") {
+                        if let Some(path) = entry["path"].as_str() {
+                            files.push(path.to_string());
+                        }
+                    }
+                }
+                pages += 1;
+                if pages < MAX_PAGES {
+                    next = body["next"].as_str().map(|s| s.to_string());
+                }
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// URL that serves the raw content of `path` at `branch`, authenticated with
+/// the same client headers as the API calls.
+pub fn raw_file_url(repo: &HostedRepo, branch: &str, path: &str) -> String {
+    match repo.host {
+        GitHost::GitHub => format!(
+            "{}/{}/{}/{}/{}",
+            github_raw_base(&repo.api_base),
+            repo.owner,
+            repo.repo,
+            branch,
+            path
+        ),
+        GitHost::GitLab => format!(
+            "{}/projects/{}/repository/files/{}/raw?ref={}",
+            repo.api_base,
+            repo.gitlab_project_id(),
+            encode_component(path),
+            encode_component(branch)
+        ),
+        GitHost::Bitbucket => format!(
+            "{}/repositories/{}/{}/src/{}/{}",
+            repo.api_base,
+            repo.owner,
+            repo.repo,
+            encode_component(branch),
+            encode_path(path)
+        ),
+    }
+}
+
+/// Raw file base for a GitHub API base: raw.githubusercontent.com for
+/// github.com, `{host}/raw` for an enterprise server (`{host}/api/v3`).
+fn github_raw_base(api_base: &str) -> String {
+    match api_base.strip_suffix("/api/v3") {
+        Some(web_base) => format!("{}/raw", web_base),
+        None if api_base == GITHUB_API_BASE => GitHubServer::default().raw_base,
+        None => api_base.to_string(),
+    }
+}
+
+/// Fetch a text file. Returns `Ok(None)` on 404.
+pub async fn get_text(
+    client: &reqwest::Client,
+    repo: &HostedRepo,
+    branch: &str,
+    path: &str,
+) -> Result<Option<String>, String> {
+    let response = client
+        .get(raw_file_url(repo, branch, path))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch {}: {}", path, e))?;
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        return Err(format!(
+            "{} API error ({}) fetching {}",
+            repo.host.label(),
+            status,
+            path
+        ));
+    }
+    response
+        .text()
+        .await
+        .map(Some)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))
+}
+
+/// Commit text files to `branch` in one push where the host supports it.
+///
+/// GitLab and Bitbucket write all files in a single commit. GitHub's contents API
+/// writes one commit per file. Returns the last commit SHA when the host reports it.
+pub async fn commit_files(
+    client: &reqwest::Client,
+    repo: &HostedRepo,
+    branch: &str,
+    files: &[(String, String)],
+    message: &str,
+) -> Result<Option<String>, String> {
+    match repo.host {
+        GitHost::GitHub => {
+            let mut last_commit = None;
+            for (path, content) in files {
+                let existing = crate::github::get_file(
+                    client,
+                    &repo.api_base,
+                    &repo.owner,
+                    &repo.repo,
+                    path,
+                    branch,
+                )
+                .await?;
+                let sha = crate::github::put_file(
+                    client,
+                    &repo.api_base,
+                    &repo.owner,
+                    &repo.repo,
+                    branch,
+                    path,
+                    content,
+                    message,
+                    existing.as_ref().map(|f| f.sha.as_str()),
+                )
+                .await?;
+                last_commit = Some(sha);
+            }
+            Ok(last_commit)
+        }
+        GitHost::GitLab => {
+            let mut actions = Vec::new();
+            for (path, content) in files {
+                let probe = format!(
+                    "{}/projects/{}/repository/files/{}?ref={}",
+                    repo.api_base,
+                    repo.gitlab_project_id(),
+                    encode_component(path),
+                    encode_component(branch)
+                );
+                let (status, body) = send_json(repo.host, client.head(&probe)).await?;
+                let action = if status.is_success() {
+                    "update"
+                } else if status == reqwest::StatusCode::NOT_FOUND {
+                    "create"
+                } else {
+                    return Err(api_error(repo.host, status, &body));
+                };
+                actions.push(serde_json::json!({
+                    "action": action,
+                    "file_path": path,
+                    "content": content,
+                }));
+            }
+            let url = format!(
+                "{}/projects/{}/repository/commits",
+                repo.api_base,
+                repo.gitlab_project_id()
+            );
+            let (status, body) = send_json(
+                repo.host,
+                client.post(&url).json(&serde_json::json!({
+                    "branch": branch,
+                    "commit_message": message,
+                    "actions": actions,
+                })),
+            )
+            .await?;
+            if !status.is_success() {
+                return Err(api_error(repo.host, status, &body));
+            }
+            Ok(body["id"].as_str().map(|s| s.to_string()))
+        }
+        GitHost::Bitbucket => {
+            let url = format!(
+                "{}/repositories/{}/{}/src",
+                repo.api_base, repo.owner, repo.repo
+            );
+            let mut form: Vec<(&str, &str)> = vec![("message", message), ("branch", branch)];
+            for (path, content) in files {
+                form.push((path.as_str(), content.as_str()));
+            }
+            let (status, body) = send_json(repo.host, client.post(&url).form(&form)).await?;
+            if !status.is_success() {
+                return Err(api_error(repo.host, status, &body));
+            }
+            // Bitbucket answers 201 with an empty body; the commit is only in `Location`.
+            Ok(None)
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Review branches and pull/merge requests
+// ---------------------------------------------------------------------------
+
+pub const REVIEW_OPEN: &str = "open";
+pub const REVIEW_MERGED: &str = "merged";
+pub const REVIEW_CLOSED: &str = "closed";
+
+/// A GitHub/Bitbucket pull request or GitLab merge request. `state` is
+/// normalized to `REVIEW_OPEN`, `REVIEW_MERGED` or `REVIEW_CLOSED`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewRequest {
+    pub number: u64,
+    pub url: String,
+    pub state: String,
+}
+
+fn review_from(host: GitHost, body: &serde_json::Value) -> Result<ReviewRequest, String> {
+    let (number, url, state) = match host {
+        GitHost::GitHub => (
+            body["number"].as_u64(),
+            body["html_url"].as_str(),
+            match (body["state"].as_str(), !body["merged_at"].is_null()) {
+                (_, true) => REVIEW_MERGED,
+                (Some("open"), _) => REVIEW_OPEN,
+                _ => REVIEW_CLOSED,
+            },
+        ),
+        GitHost::GitLab => (
+            body["iid"].as_u64(),
+            body["web_url"].as_str(),
+            match body["state"].as_str() {
+                Some("opened") => REVIEW_OPEN,
+                Some("merged") => REVIEW_MERGED,
+                _ => REVIEW_CLOSED,
+            },
+        ),
+        GitHost::Bitbucket => (
+            body["id"].as_u64(),
+            body["links"]["html"]["href"].as_str(),
+            match body["state"].as_str() {
+                Some("OPEN") => REVIEW_OPEN,
+                Some("MERGED") => REVIEW_MERGED,
+                _ => REVIEW_CLOSED,
+            },
+        ),
+    };
+    Ok(ReviewRequest {
+        number: number.ok_or_else(|| format!("{} review response has no number", host.label()))?,
+        url: url.unwrap_or_default().to_string(),
+        state: state.to_string(),
+    })
+}
+
+/// Create `branch` from the tip of `from` unless it already exists.
+/// Returns true when the branch was created.
+pub async fn ensure_branch(
+    client: &reqwest::Client,
+    repo: &HostedRepo,
+    branch: &str,
+    from: &str,
+) -> Result<bool, String> {
+    let probe = match repo.host {
+        GitHost::GitHub => format!(
+            "{}/repos/{}/{}/git/ref/heads/{}",
+            repo.api_base, repo.owner, repo.repo, branch
+        ),
+        GitHost::GitLab => format!(
+            "{}/projects/{}/repository/branches/{}",
+            repo.api_base,
+            repo.gitlab_project_id(),
+            encode_component(branch)
+        ),
+        GitHost::Bitbucket => format!(
+            "{}/repositories/{}/{}/refs/branches/{}",
+            repo.api_base,
+            repo.owner,
+            repo.repo,
+            encode_component(branch)
+        ),
+    };
+    let (status, body) = send_json(repo.host, client.get(&probe)).await?;
+    if status.is_success() {
+        return Ok(false);
+    }
+    if status != reqwest::StatusCode::NOT_FOUND {
+        return Err(api_error(repo.host, status, &body));
+    }
+
+    match repo.host {
+        GitHost::GitHub => {
+            let sha = crate::github::get_branch_sha(client, &repo.api_base, &repo.owner, &repo.repo, from).await?;
+            crate::github::create_branch(client, &repo.api_base, &repo.owner, &repo.repo, branch, &sha).await?;
+        }
+        GitHost::GitLab => {
+            let url = format!(
+                "{}/projects/{}/repository/branches",
+                repo.api_base,
+                repo.gitlab_project_id()
+            );
+            let (status, body) = send_json(
+                repo.host,
+                client.post(&url).json(&serde_json::json!({ "branch": branch, "ref": from })),
+            )
+            .await?;
+            if !status.is_success() {
+                return Err(api_error(repo.host, status, &body));
+            }
+        }
+        GitHost::Bitbucket => {
+            let base = format!("{}/repositories/{}/{}/refs/branches", repo.api_base, repo.owner, repo.repo);
+            let from_ref = get_json(client, repo.host, &format!("{}/{}", base, encode_component(from))).await?;
+            let hash = from_ref["target"]["hash"]
+                .as_str()
+                .ok_or_else(|| format!("Bitbucket branch '{}' has no target commit", from))?;
+            let (status, body) = send_json(
+                repo.host,
+                client.post(&base).json(&serde_json::json!({ "name": branch, "target": { "hash": hash } })),
+            )
+            .await?;
+            if !status.is_success() {
+                return Err(api_error(repo.host, status, &body));
+            }
+        }
+    }
+    Ok(true)
+}
+
+/// The open review from `head` into `base`, if there is one.
+pub async fn find_open_review(
+    client: &reqwest::Client,
+    repo: &HostedRepo,
+    head: &str,
+    base: &str,
+) -> Result<Option<ReviewRequest>, String> {
+    let reviews = match repo.host {
+        GitHost::GitHub => get_json(
+            client,
+            repo.host,
+            &format!(
+                "{}/repos/{}/{}/pulls?state=open&head={}&base={}",
+                repo.api_base,
+                repo.owner,
+                repo.repo,
+                encode_component(&format!("{}:{}", repo.owner, head)),
+                encode_component(base)
+            ),
+        )
+        .await?,
+        GitHost::GitLab => get_json(
+            client,
+            repo.host,
+            &format!(
+                "{}/projects/{}/merge_requests?state=opened&source_branch={}&target_branch={}",
+                repo.api_base,
+                repo.gitlab_project_id(),
+                encode_component(head),
+                encode_component(base)
+            ),
+        )
+        .await?,
+        GitHost::Bitbucket => {
+            let body = get_json(
+                client,
+                repo.host,
+                &format!(
+                    "{}/repositories/{}/{}/pullrequests?state=OPEN&pagelen=50",
+                    repo.api_base, repo.owner, repo.repo
+                ),
+            )
+            .await?;
+            serde_json::Value::Array(
+                body["values"]
+                    .as_array()
+                    .map(|values| {
+                        values
+                            .iter()
+                            .filter(|pr| {
+                                pr["source"]["branch"]["name"].as_str() == Some(head)
+                                    && pr["destination"]["branch"]["name"].as_str() == Some(base)
+                            })
+                            .cloned()
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            )
+        }
+    };
+    reviews
+        .as_array()
+        .and_then(|r| r.first())
+        .map(|r| review_from(repo.host, r))
+        .transpose()
+}
+
+/// Open a review from `head` into `base`.
+pub async fn open_review(
+    client: &reqwest::Client,
+    repo: &HostedRepo,
+    head: &str,
+    base: &str,
+    title: &str,
+    description: &str,
+) -> Result<ReviewRequest, String> {
+    let (url, payload) = match repo.host {
+        GitHost::GitHub => {
+            let pr = crate::github::create_pull_request(
+                client,
+                &repo.api_base,
+                &repo.owner,
+                &repo.repo,
+                head,
+                base,
+                title,
+                description,
+            )
+            .await?;
+            return Ok(ReviewRequest {
+                number: pr.number,
+                url: pr.url,
+                state: REVIEW_OPEN.to_string(),
+            });
+        }
+        GitHost::GitLab => (
+            format!("{}/projects/{}/merge_requests", repo.api_base, repo.gitlab_project_id()),
+            serde_json::json!({
+                "source_branch": head,
+                "target_branch": base,
+                "title": title,
+                "description": description,
+            }),
+        ),
+        GitHost::Bitbucket => (
+            format!("{}/repositories/{}/{}/pullrequests", repo.api_base, repo.owner, repo.repo),
+            serde_json::json!({
+                "title": title,
+                "description": description,
+                "source": { "branch": { "name": head } },
+                "destination": { "branch": { "name": base } },
+            }),
+        ),
+    };
+    let (status, body) = send_json(repo.host, client.post(&url).json(&payload)).await?;
+    if !status.is_success() {
+        return Err(api_error(repo.host, status, &body));
+    }
+    review_from(repo.host, &body)
+}
+
+/// Replace the title and description of review `number`.
+pub async fn update_review(
+    client: &reqwest::Client,
+    repo: &HostedRepo,
+    number: u64,
+    title: &str,
+    description: &str,
+) -> Result<(), String> {
+    let request = match repo.host {
+        GitHost::GitHub => client
+            .patch(format!("{}/repos/{}/{}/pulls/{}", repo.api_base, repo.owner, repo.repo, number))
+            .json(&serde_json::json!({ "title": title, "body": description })),
+        GitHost::GitLab => client
+            .put(format!(
+                "{}/projects/{}/merge_requests/{}",
+                repo.api_base,
+                repo.gitlab_project_id(),
+                number
+            ))
+            .json(&serde_json::json!({ "title": title, "description": description })),
+        GitHost::Bitbucket => client
+            .put(format!(
+                "{}/repositories/{}/{}/pullrequests/{}",
+                repo.api_base, repo.owner, repo.repo, number
+            ))
+            .json(&serde_json::json!({ "title": title, "description": description })),
+    };
+    let (status, body) = send_json(repo.host, request).await?;
+    if !status.is_success() {
+        return Err(api_error(repo.host, status, &body));
+    }
+    Ok(())
+}
+
+/// Fetch the current state of review `number`.
+pub async fn get_review(client: &reqwest::Client, repo: &HostedRepo, number: u64) -> Result<ReviewRequest, String> {
+    let url = match repo.host {
+        GitHost::GitHub => format!("{}/repos/{}/{}/pulls/{}", repo.api_base, repo.owner, repo.repo, number),
+        GitHost::GitLab => format!(
+            "{}/projects/{}/merge_requests/{}",
+            repo.api_base,
+            repo.gitlab_project_id(),
+            number
+        ),
+        GitHost::Bitbucket => format!(
+            "{}/repositories/{}/{}/pullrequests/{}",
+            repo.api_base, repo.owner, repo.repo, number
+        ),
+    };
+    let body = get_json(client, repo.host, &url).await?;
+    review_from(repo.host, &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_repo_url_detects_each_host() {
+        let public = GitHubServer::default();
+        let gitlab = parse_repo_url("https://gitlab.com/acme/platform/skills/-/tree/dev/plugins", &public).unwrap();
+        assert_eq!(gitlab.host, GitHost::GitLab);
+        assert_eq!(gitlab.api_base, GITLAB_API_BASE);
+        assert_eq!(gitlab.owner, "acme/platform");
+        assert_eq!(gitlab.repo, "skills");
+        assert_eq!(gitlab.branch.as_deref(), Some("dev"));
+        assert_eq!(gitlab.subpath.as_deref(), Some("plugins"));
+
+        let self_managed = parse_repo_url("https://gitlab.acme.io/team/skills.git", &public).unwrap();
+        assert_eq!(self_managed.api_base, "https://gitlab.acme.io/api/v4");
+        assert_eq!(self_managed.branch, None);
+
+        let bitbucket = parse_repo_url("bitbucket.org/acme/skills/src/main/team", &public).unwrap();
+        assert_eq!(bitbucket.host, GitHost::Bitbucket);
+        assert_eq!(bitbucket.full_name(), "acme/skills");
+        assert_eq!(bitbucket.branch.as_deref(), Some("main"));
+        assert_eq!(bitbucket.subpath.as_deref(), Some("team"));
+
+        let github = parse_repo_url("acme/skills", &public).unwrap();
+        assert_eq!(github.host, GitHost::GitHub);
+        assert_eq!(github.branch, None);
+
+        assert!(parse_repo_url("https://bitbucket.org/acme", &public).is_err());
+        assert!(parse_repo_url("https://gitlab.com/acme/skills/-/blob/main/x", &public).is_err());
+    }
+
+    #[test]
+    fn test_parse_repo_url_uses_enterprise_github() {
+        let ghe = GitHubServer::enterprise("https://github.acme.com").unwrap();
+        let repo = parse_repo_url("https://github.acme.com/acme/skills/tree/dev", &ghe).unwrap();
+        assert_eq!(repo.host, GitHost::GitHub);
+        assert_eq!(repo.api_base, "https://github.acme.com/api/v3");
+        assert_eq!(repo.branch.as_deref(), Some("dev"));
+        assert_eq!(
+            raw_file_url(&repo, "dev", "skills/a/SKILL.md"),
+            "https://github.acme.com/raw/acme/skills/dev/skills/a/SKILL.md"
+        );
+
+        let public = parse_repo_url("acme/skills", &GitHubServer::default()).unwrap();
+        assert_eq!(
+            raw_file_url(&public, "main", "SKILL.md"),
+            "https://raw.githubusercontent.com/acme/skills/main/SKILL.md"
+        );
+    }
+
+    #[test]
+    fn test_raw_file_url_encodes_gitlab_paths() {
+        let repo = parse_repo_url("https://gitlab.com/acme/platform/skills", &GitHubServer::default()).unwrap();
+        assert_eq!(
+            raw_file_url(&repo, "main", "skills/my skill/SKILL.md"),
+            "https://gitlab.com/api/v4/projects/acme%2Fplatform%2Fskills/repository/files/skills%2Fmy%20skill%2FSKILL.md/raw?ref=main"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gitlab_commit_files_creates_and_updates_in_one_commit() {
+        let mut server = mockito::Server::new_async().await;
+        let _existing = server
+            .mock("HEAD", "/projects/acme%2Fskills/repository/files/SKILL.md?ref=main")
+            .with_status(200)
+            .create_async()
+            .await;
+        let _missing = server
+            .mock("HEAD", "/projects/acme%2Fskills/repository/files/notes.md?ref=main")
+            .with_status(404)
+            .create_async()
+            .await;
+        let commit = server
+            .mock("POST", "/projects/acme%2Fskills/repository/commits")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "branch": "main",
+                "actions": [
+                    {"action": "update", "file_path": "SKILL.md"},
+                    {"action": "create", "file_path": "notes.md"},
+                ],
+            })))
+            .with_status(201)
+            .with_body(r#"{"id":"c0ffee"}"#)
+            .create_async()
+            .await;
+
+        let mut repo = parse_repo_url("https://gitlab.com/acme/skills", &GitHubServer::default()).unwrap();
+        repo.api_base = server.url();
+        let client = build_client(GitHost::GitLab, Some("glpat-test"), &GitHubServer::default()).unwrap();
+        let files = vec![
+            ("SKILL.md".to_string(), "# Skill".to_string()),
+            ("notes.md".to_string(), "notes".to_string()),
+        ];
+        let sha = commit_files(&client, &repo, "main", &files, "Share skill")
+            .await
+            .unwrap();
+        assert_eq!(sha.as_deref(), Some("c0ffee"));
+        commit.assert_async().await;
+    }
+
+    #[test]
+    fn test_review_from_normalizes_host_states() {
+        let merged = review_from(
+            GitHost::GitHub,
+            &serde_json::json!({"number": 7, "html_url": "https://github.com/a/b/pull/7", "state": "closed", "merged_at": "2026-01-01T00:00:00Z"}),
+        )
+        .unwrap();
+        assert_eq!((merged.number, merged.state.as_str()), (7, REVIEW_MERGED));
+        let opened = review_from(GitHost::GitLab, &serde_json::json!({"iid": 3, "web_url": "u", "state": "opened"})).unwrap();
+        assert_eq!(opened.state, REVIEW_OPEN);
+        let declined = review_from(
+            GitHost::Bitbucket,
+            &serde_json::json!({"id": 9, "links": {"html": {"href": "u"}}, "state": "DECLINED"}),
+        )
+        .unwrap();
+        assert_eq!(declined.state, REVIEW_CLOSED);
+    }
+
+    #[tokio::test]
+    async fn test_gitlab_ensure_branch_creates_missing_branch_once() {
+        let mut server = mockito::Server::new_async().await;
+        let _missing = server
+            .mock("GET", "/projects/acme%2Fskills/repository/branches/skill%2Fstandup")
+            .with_status(404)
+            .create_async()
+            .await;
+        let create = server
+            .mock("POST", "/projects/acme%2Fskills/repository/branches")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({"branch": "skill/standup", "ref": "main"})))
+            .with_status(201)
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let mut repo = parse_repo_url("https://gitlab.com/acme/skills", &GitHubServer::default()).unwrap();
+        repo.api_base = server.url();
+        let client = build_client(GitHost::GitLab, Some("glpat-test"), &GitHubServer::default()).unwrap();
+        assert!(ensure_branch(&client, &repo, "skill/standup", "main").await.unwrap());
+        create.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_bitbucket_list_files_follows_pagination() {
+        let mut server = mockito::Server::new_async().await;
+        let next = format!("{}/page2", server.url());
+        let _first = server
+            .mock("GET", "/repositories/acme/skills/src/main/?max_depth=20&pagelen=100")
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "values": [
+                        {"type": "commit_directory", "path": "skills"},
+                        {"type": "commit_file", "path": "skills/a/SKILL.md"},
+                    ],
+                    "next": next,
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let _second = server
+            .mock("GET", "/page2")
+            .with_status(200)
+            .with_body(r#"{"values":[{"type":"commit_file","path":"skills/b/SKILL.md"}]}"#)
+            .create_async()
+            .await;
+
+        let mut repo = parse_repo_url("https://bitbucket.org/acme/skills", &GitHubServer::default()).unwrap();
+        repo.api_base = server.url();
+        let files = list_files(&reqwest::Client::new(), &repo, "main").await.unwrap();
+        assert_eq!(files, vec!["skills/a/SKILL.md", "skills/b/SKILL.md"]);
+    }
+}
@@ -0,0 +1,804 @@
+//! Thin helpers over the GitHub REST API for writing to repositories
+//! (branches, file contents, pull requests).
+//!
+//! Every helper takes the API base URL so tests can point it at a mockito server.
+//! Callers build the client with `commands::github_import::build_github_client`.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// Public GitHub REST API base URL.
+pub const GITHUB_API_BASE: &str = "https://api.github.com";
+
+const GITHUB_WEB_BASE: &str = "https://github.com";
+const GITHUB_RAW_BASE: &str = "https://raw.githubusercontent.com";
+
+/// The GitHub instance requests go to: github.com, or the GitHub Enterprise
+/// Server set in Settings (`github_enterprise_url`), plus the TLS overrides
+/// applied to every client built for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitHubServer {
+    /// Web URL, e.g. `https://github.com`. Device-flow endpoints live here.
+    pub web_base: String,
+    pub api_base: String,
+    /// Base for `{owner}/{repo}/{branch}/{path}` raw file URLs.
+    pub raw_base: String,
+    /// OAuth app client ID registered on an enterprise instance.
+    pub oauth_client_id: Option<String>,
+    pub ca_cert_path: Option<String>,
+    pub accept_invalid_certs: bool,
+}
+
+impl Default for GitHubServer {
+    fn default() -> Self {
+        Self {
+            web_base: GITHUB_WEB_BASE.to_string(),
+            api_base: GITHUB_API_BASE.to_string(),
+            raw_base: GITHUB_RAW_BASE.to_string(),
+            oauth_client_id: None,
+            ca_cert_path: None,
+            accept_invalid_certs: false,
+        }
+    }
+}
+
+impl GitHubServer {
+    /// A GitHub Enterprise Server instance at `url`, its web address. A pasted
+    /// API URL (`.../api/v3`) is accepted too.
+    pub fn enterprise(url: &str) -> Result<Self, String> {
+        let trimmed = url.trim().trim_end_matches('/');
+        let trimmed = trimmed.strip_suffix("/api/v3").unwrap_or(trimmed);
+        let parsed = reqwest::Url::parse(trimmed)
+            .map_err(|e| format!("Invalid GitHub Enterprise URL '{}': {}", url, e))?;
+        if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+            return Err(format!(
+                "Invalid GitHub Enterprise URL '{}': expected an http(s) address like https://github.acme.com",
+                url
+            ));
+        }
+        if parsed.query().is_some() || parsed.fragment().is_some() {
+            return Err(format!(
+                "Invalid GitHub Enterprise URL '{}': remove the query or fragment",
+                url
+            ));
+        }
+        if parsed.host_str() == Some("github.com") {
+            return Err("github.com is not a GitHub Enterprise Server; leave the URL empty".to_string());
+        }
+        Ok(Self {
+            web_base: trimmed.to_string(),
+            api_base: format!("{}/api/v3", trimmed),
+            raw_base: format!("{}/raw", trimmed),
+            ..Self::default()
+        })
+    }
+
+    /// The server configured in `settings`, with its TLS overrides.
+    pub fn from_settings(settings: &crate::types::AppSettings) -> Result<Self, String> {
+        let mut server = match settings.github_enterprise_url.as_deref().map(str::trim) {
+            Some(url) if !url.is_empty() => Self::enterprise(url)?,
+            _ => Self::default(),
+        };
+        server.oauth_client_id = settings
+            .github_oauth_client_id
+            .as_deref()
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(String::from);
+        server.ca_cert_path = settings
+            .github_ca_cert_path
+            .as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(String::from);
+        server.accept_invalid_certs = settings.github_accept_invalid_certs;
+        Ok(server)
+    }
+
+    pub fn is_enterprise(&self) -> bool {
+        self.web_base != GITHUB_WEB_BASE
+    }
+
+    /// Host name (with port, if any) of the web URL.
+    pub fn host(&self) -> &str {
+        self.web_base
+            .split_once("://")
+            .map_or(self.web_base.as_str(), |(_, rest)| rest)
+            .split('/')
+            .next()
+            .unwrap_or_default()
+    }
+
+    /// Strip this server's web address from `url`, leaving the
+    /// `owner/repo/...` path that `parse_github_url_inner` understands.
+    /// github.com URLs and shorthand are returned unchanged, except that an
+    /// enterprise server refuses github.com URLs rather than looking the repo
+    /// up on the wrong host.
+    pub fn repo_path<'a>(&self, url: &'a str) -> Result<&'a str, String> {
+        let url = url.trim();
+        if !self.is_enterprise() {
+            return Ok(url);
+        }
+        let rest = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))
+            .unwrap_or(url);
+        if rest == "github.com" || rest.starts_with("github.com/") {
+            return Err(format!(
+                "'{}' is on github.com, but Skill Builder is set up for GitHub Enterprise Server at {}. \
+                 Use a repository on {} or clear the enterprise URL in Settings.",
+                url,
+                self.web_base,
+                self.host()
+            ));
+        }
+        match rest.strip_prefix(self.host()) {
+            Some(path) if path.starts_with('/') => Ok(&path[1..]),
+            _ => Ok(url),
+        }
+    }
+
+    pub fn raw_url(&self, owner: &str, repo: &str, branch: &str, path: &str) -> String {
+        format!("{}/{}/{}/{}/{}", self.raw_base, owner, repo, branch, path)
+    }
+
+    pub fn device_code_url(&self) -> String {
+        format!("{}/login/device/code", self.web_base)
+    }
+
+    pub fn access_token_url(&self) -> String {
+        format!("{}/login/oauth/access_token", self.web_base)
+    }
+
+    /// A client builder that trusts `ca_cert_path` and honours
+    /// `accept_invalid_certs`.
+    pub fn client_builder(&self) -> Result<reqwest::ClientBuilder, String> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(path) = &self.ca_cert_path {
+            let pem = std::fs::read(path)
+                .map_err(|e| format!("Failed to read GitHub CA certificate {}: {}", path, e))?;
+            let certs = reqwest::Certificate::from_pem_bundle(&pem)
+                .map_err(|e| format!("Invalid GitHub CA certificate {}: {}", path, e))?;
+            if certs.is_empty() {
+                return Err(format!("No certificates found in {}", path));
+            }
+            for cert in certs {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+        if self.accept_invalid_certs {
+            log::warn!("[github] TLS certificate verification is disabled for {}", self.web_base);
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+        Ok(builder)
+    }
+}
+
+/// Sign out of GitHub when the token in `settings` was issued by another
+/// server than the one now configured, so it is never sent to another host.
+/// A token saved before the issuing server was recorded is taken to belong
+/// to the configured server.
+pub fn forget_token_for_other_server(settings: &mut crate::types::AppSettings) {
+    if settings.github_oauth_token.is_none() {
+        return;
+    }
+    let configured = GitHubServer::from_settings(settings).map(|server| server.web_base);
+    match (settings.github_oauth_host.as_deref(), configured) {
+        (None, Ok(web_base)) => settings.github_oauth_host = Some(web_base),
+        (Some(host), Ok(web_base)) if host == web_base => {}
+        (host, configured) => {
+            log::warn!(
+                "[github] GitHub token was issued by {} but the configured server is {}; signing out",
+                host.unwrap_or("an unknown server"),
+                configured.unwrap_or_else(|e| e)
+            );
+            settings.github_oauth_token = None;
+            settings.github_oauth_host = None;
+            settings.github_user_login = None;
+            settings.github_user_avatar = None;
+            settings.github_user_email = None;
+        }
+    }
+}
+
+/// A file fetched via the contents API, with the blob SHA needed to update it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoFile {
+    pub path: String,
+    pub sha: String,
+    pub content: String,
+}
+
+/// A pull request created by `create_pull_request`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestRef {
+    pub url: String,
+    pub number: u64,
+}
+
+/// Send a request and return its status with the parsed JSON body
+/// (`Null` when the body is empty or not JSON).
+async fn send_json(request: reqwest::RequestBuilder) -> Result<(reqwest::StatusCode, serde_json::Value), String> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("GitHub API request failed: {}", e))?;
+    let status = response.status();
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .unwrap_or(serde_json::Value::Null);
+    Ok((status, body))
+}
+
+fn api_error(status: reqwest::StatusCode, body: &serde_json::Value) -> String {
+    let message = body["message"].as_str().unwrap_or("Unknown error");
+    format!("GitHub API error ({}): {}", status, message)
+}
+
+/// Fetch a text file from a repo at `git_ref`. Returns `Ok(None)` on 404.
+pub async fn get_file(
+    client: &reqwest::Client,
+    api_base: &str,
+    owner: &str,
+    repo: &str,
+    path: &str,
+    git_ref: &str,
+) -> Result<Option<RepoFile>, String> {
+    let url = format!(
+        "{}/repos/{}/{}/contents/{}?ref={}",
+        api_base, owner, repo, path, git_ref
+    );
+    let (status, body) = send_json(client.get(&url)).await?;
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        return Err(api_error(status, &body));
+    }
+    let sha = body["sha"]
+        .as_str()
+        .ok_or("Invalid contents response: missing 'sha'")?
+        .to_string();
+    // The contents API wraps base64 at 60 columns — strip whitespace before decoding.
+    let encoded: String = body["content"]
+        .as_str()
+        .unwrap_or("")
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| format!("Failed to decode {}: {}", path, e))?;
+    let content = String::from_utf8(bytes)
+        .map_err(|_| format!("{} is not valid UTF-8", path))?;
+    Ok(Some(RepoFile {
+        path: path.to_string(),
+        sha,
+        content,
+    }))
+}
+
+/// Resolve the commit SHA a branch currently points at.
+pub async fn get_branch_sha(
+    client: &reqwest::Client,
+    api_base: &str,
+    owner: &str,
+    repo: &str,
+    branch: &str,
+) -> Result<String, String> {
+    let url = format!(
+        "{}/repos/{}/{}/git/ref/heads/{}",
+        api_base, owner, repo, branch
+    );
+    let (status, body) = send_json(client.get(&url)).await?;
+    if !status.is_success() {
+        return Err(api_error(status, &body));
+    }
+    body["object"]["sha"]
+        .as_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| "Invalid ref response: missing object.sha".to_string())
+}
+
+/// Create `branch` pointing at `from_sha`.
+pub async fn create_branch(
+    client: &reqwest::Client,
+    api_base: &str,
+    owner: &str,
+    repo: &str,
+    branch: &str,
+    from_sha: &str,
+) -> Result<(), String> {
+    let url = format!("{}/repos/{}/{}/git/refs", api_base, owner, repo);
+    let (status, body) = send_json(client.post(&url).json(&serde_json::json!({
+        "ref": format!("refs/heads/{}", branch),
+        "sha": from_sha,
+    })))
+    .await?;
+    if !status.is_success() {
+        return Err(api_error(status, &body));
+    }
+    Ok(())
+}
+
+/// Create or update a file on `branch`. Pass the existing blob SHA when updating.
+/// Returns the new commit SHA.
+#[allow(clippy::too_many_arguments)]
+pub async fn put_file(
+    client: &reqwest::Client,
+    api_base: &str,
+    owner: &str,
+    repo: &str,
+    branch: &str,
+    path: &str,
+    content: &str,
+    message: &str,
+    existing_sha: Option<&str>,
+) -> Result<String, String> {
+    let url = format!("{}/repos/{}/{}/contents/{}", api_base, owner, repo, path);
+    let mut payload = serde_json::json!({
+        "message": message,
+        "content": base64::engine::general_purpose::STANDARD.encode(content.as_bytes()),
+        "branch": branch,
+    });
+    if let Some(sha) = existing_sha {
+        payload["sha"] = serde_json::Value::String(sha.to_string());
+    }
+    let (status, body) = send_json(client.put(&url).json(&payload)).await?;
+    if !status.is_success() {
+        return Err(api_error(status, &body));
+    }
+    Ok(body["commit"]["sha"].as_str().unwrap_or_default().to_string())
+}
+
+/// Open a pull request from `head` into `base`.
+#[allow(clippy::too_many_arguments)]
+pub async fn create_pull_request(
+    client: &reqwest::Client,
+    api_base: &str,
+    owner: &str,
+    repo: &str,
+    head: &str,
+    base: &str,
+    title: &str,
+    body_text: &str,
+) -> Result<PullRequestRef, String> {
+    let url = format!("{}/repos/{}/{}/pulls", api_base, owner, repo);
+    let (status, body) = send_json(client.post(&url).json(&serde_json::json!({
+        "title": title,
+        "head": head,
+        "base": base,
+        "body": body_text,
+    })))
+    .await?;
+    if !status.is_success() {
+        return Err(api_error(status, &body));
+    }
+    let url = body["html_url"]
+        .as_str()
+        .ok_or("Missing html_url in pull request response")?
+        .to_string();
+    let number = body["number"]
+        .as_u64()
+        .ok_or("Missing number in pull request response")?;
+    Ok(PullRequestRef { url, number })
+}
+
+/// Ruleset rule types that reject a direct commit to a branch.
+const BLOCKING_RULES: &[(&str, &str)] = &[
+    ("pull_request", "changes must go through a pull request"),
+    ("required_status_checks", "status checks must pass first"),
+    ("required_deployments", "deployments must succeed first"),
+    ("update", "updates are restricted"),
+];
+
+/// Check that the client's token can commit to `branch` (the default branch
+/// when `None`) before any work is done, so a push fails with an actionable
+/// message instead of a late 403. Returns the branch to push to.
+///
+/// Classic OAuth tokens report their scopes in `X-OAuth-Scopes`; fine-grained
+/// tokens don't, so their access is judged from the repo's `permissions` alone.
+pub async fn preflight_push(
+    client: &reqwest::Client,
+    api_base: &str,
+    owner: &str,
+    repo: &str,
+    branch: Option<&str>,
+) -> Result<String, String> {
+    preflight(client, api_base, owner, repo, branch, true).await
+}
+
+/// Like `preflight_push`, for pushes that go to a review branch and open a
+/// pull request into `base`: the base branch must exist but may be protected.
+/// Returns the base branch.
+pub async fn preflight_review_push(
+    client: &reqwest::Client,
+    api_base: &str,
+    owner: &str,
+    repo: &str,
+    base: Option<&str>,
+) -> Result<String, String> {
+    preflight(client, api_base, owner, repo, base, false).await
+}
+
+async fn preflight(
+    client: &reqwest::Client,
+    api_base: &str,
+    owner: &str,
+    repo: &str,
+    branch: Option<&str>,
+    check_protection: bool,
+) -> Result<String, String> {
+    let full_name = format!("{}/{}", owner, repo);
+    let response = client
+        .get(format!("{}/repos/{}/{}", api_base, owner, repo))
+        .send()
+        .await
+        .map_err(|e| format!("GitHub API request failed: {}", e))?;
+    let status = response.status();
+    let scopes: Option<Vec<String>> = response
+        .headers()
+        .get("x-oauth-scopes")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect());
+    let body: serde_json::Value = response.json().await.unwrap_or(serde_json::Value::Null);
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Err(format!(
+            "Repository {} was not found or your GitHub account cannot see it. If it is private, \
+             check that Skill Builder is allowed to access the '{}' organization.",
+            full_name, owner
+        ));
+    }
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+        return Err("Your GitHub sign-in has expired. Sign in with GitHub again in Settings.".to_string());
+    }
+    if !status.is_success() {
+        return Err(api_error(status, &body));
+    }
+
+    let private = body["private"].as_bool().unwrap_or(true);
+    if let Some(scopes) = &scopes {
+        let has = |s: &str| scopes.iter().any(|scope| scope == s);
+        if !has("repo") && (private || !has("public_repo")) {
+            return Err(format!(
+                "Your GitHub sign-in lacks the 'repo' scope needed to push to {} (granted: {}). \
+                 Sign out of GitHub in Settings and sign in again.",
+                full_name,
+                if scopes.is_empty() { "none".to_string() } else { scopes.join(", ") }
+            ));
+        }
+    }
+    if body["archived"].as_bool() == Some(true) {
+        return Err(format!(
+            "{} is archived and read-only. Unarchive it on GitHub or choose another repository.",
+            full_name
+        ));
+    }
+    if body["permissions"]["push"].as_bool() == Some(false) {
+        return Err(format!(
+            "Your GitHub account has read-only access to {}. Ask a repository admin for write access.",
+            full_name
+        ));
+    }
+    let admin = body["permissions"]["admin"].as_bool() == Some(true);
+
+    let branch = match branch.filter(|b| !b.is_empty()) {
+        Some(b) => b.to_string(),
+        None => body["default_branch"].as_str().unwrap_or("main").to_string(),
+    };
+    let url = format!("{}/repos/{}/{}/branches/{}", api_base, owner, repo, branch);
+    let (status, branch_body) = send_json(client.get(&url)).await?;
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Err(format!(
+            "Branch '{}' does not exist in {}. Create it on GitHub or push to another branch.",
+            branch, full_name
+        ));
+    }
+    if !status.is_success() {
+        return Err(api_error(status, &branch_body));
+    }
+    if !check_protection {
+        return Ok(branch);
+    }
+
+    // Rulesets apply to admins too unless they are on the bypass list; classic
+    // protection details are only visible to admins, who can usually bypass it.
+    let url = format!("{}/repos/{}/{}/rules/branches/{}", api_base, owner, repo, branch);
+    let (status, rules) = send_json(client.get(&url)).await?;
+    let mut reasons: Vec<&str> = if status.is_success() {
+        BLOCKING_RULES
+            .iter()
+            .filter(|(rule, _)| {
+                rules
+                    .as_array()
+                    .is_some_and(|r| r.iter().any(|v| v["type"].as_str() == Some(*rule)))
+            })
+            .map(|(_, reason)| *reason)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    if reasons.is_empty() && branch_body["protected"].as_bool() == Some(true) && !admin {
+        reasons.push("it has branch protection rules");
+    }
+    if !reasons.is_empty() {
+        return Err(format!(
+            "Branch '{}' in {} is protected ({}), so Skill Builder cannot commit to it directly. \
+             Push for review to open a pull request instead, add '/tree/<branch>' to the repository \
+             URL to push to an unprotected branch, or ask a repository admin to allow direct pushes.",
+            branch,
+            full_name,
+            reasons.join("; ")
+        ));
+    }
+    Ok(branch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_file_decodes_wrapped_base64() {
+        let mut server = mockito::Server::new_async().await;
+        let encoded = base64::engine::general_purpose::STANDARD.encode("{\"domains\":[]}");
+        let wrapped = format!("{}\n{}", &encoded[..8], &encoded[8..]);
+        let _mock = server
+            .mock("GET", "/repos/acme/team/contents/taxonomy.json?ref=main")
+            .with_status(200)
+            .with_body(serde_json::json!({"sha": "abc123", "content": wrapped}).to_string())
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let file = get_file(&client, &server.url(), "acme", "team", "taxonomy.json", "main")
+            .await
+            .unwrap()
+            .expect("file should exist");
+        assert_eq!(file.sha, "abc123");
+        assert_eq!(file.content, "{\"domains\":[]}");
+    }
+
+    #[tokio::test]
+    async fn test_get_file_returns_none_on_404() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/repos/acme/team/contents/missing.json?ref=main")
+            .with_status(404)
+            .with_body(r#"{"message":"Not Found"}"#)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let file = get_file(&client, &server.url(), "acme", "team", "missing.json", "main")
+            .await
+            .unwrap();
+        assert!(file.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_create_pull_request_surfaces_api_message() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/repos/acme/team/pulls")
+            .with_status(422)
+            .with_body(r#"{"message":"Validation Failed"}"#)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let err = create_pull_request(
+            &client,
+            &server.url(),
+            "acme",
+            "team",
+            "feature",
+            "main",
+            "title",
+            "body",
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("422"), "got: {err}");
+        assert!(err.contains("Validation Failed"), "got: {err}");
+    }
+
+    async fn mock_repo(server: &mut mockito::ServerGuard, scopes: &str, repo_json: serde_json::Value) -> mockito::Mock {
+        server
+            .mock("GET", "/repos/acme/team")
+            .with_status(200)
+            .with_header("x-oauth-scopes", scopes)
+            .with_body(repo_json.to_string())
+            .create_async()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_preflight_push_reports_missing_scope() {
+        let mut server = mockito::Server::new_async().await;
+        let _repo = mock_repo(
+            &mut server,
+            "read:user",
+            serde_json::json!({"private": true, "default_branch": "main", "permissions": {"push": true}}),
+        )
+        .await;
+
+        let client = reqwest::Client::new();
+        let err = preflight_push(&client, &server.url(), "acme", "team", None).await.unwrap_err();
+        assert!(err.contains("'repo' scope"), "got: {err}");
+        assert!(err.contains("granted: read:user"), "got: {err}");
+    }
+
+    #[tokio::test]
+    async fn test_preflight_push_reports_archived_and_read_only() {
+        let mut server = mockito::Server::new_async().await;
+        let archived = mock_repo(
+            &mut server,
+            "repo",
+            serde_json::json!({"archived": true, "permissions": {"push": true}}),
+        )
+        .await;
+        let client = reqwest::Client::new();
+        let err = preflight_push(&client, &server.url(), "acme", "team", None).await.unwrap_err();
+        assert!(err.contains("archived"), "got: {err}");
+        archived.remove_async().await;
+
+        let _read_only = mock_repo(
+            &mut server,
+            "repo",
+            serde_json::json!({"archived": false, "permissions": {"push": false}}),
+        )
+        .await;
+        let err = preflight_push(&client, &server.url(), "acme", "team", None).await.unwrap_err();
+        assert!(err.contains("read-only access"), "got: {err}");
+    }
+
+    #[tokio::test]
+    async fn test_preflight_push_reports_protected_branch() {
+        let mut server = mockito::Server::new_async().await;
+        let _repo = mock_repo(
+            &mut server,
+            "repo, read:user",
+            serde_json::json!({"default_branch": "main", "permissions": {"push": true, "admin": false}}),
+        )
+        .await;
+        let _branch = server
+            .mock("GET", "/repos/acme/team/branches/main")
+            .with_status(200)
+            .with_body(r#"{"name":"main","protected":true}"#)
+            .create_async()
+            .await;
+        let _rules = server
+            .mock("GET", "/repos/acme/team/rules/branches/main")
+            .with_status(200)
+            .with_body(r#"[{"type":"pull_request"},{"type":"deletion"}]"#)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let err = preflight_push(&client, &server.url(), "acme", "team", None).await.unwrap_err();
+        assert!(err.contains("Branch 'main' in acme/team is protected"), "got: {err}");
+        assert!(err.contains("pull request"), "got: {err}");
+
+        // Review pushes target their own branch, so a protected base is fine.
+        let base = preflight_review_push(&client, &server.url(), "acme", "team", None).await.unwrap();
+        assert_eq!(base, "main");
+    }
+
+    #[tokio::test]
+    async fn test_preflight_push_accepts_writable_branch() {
+        let mut server = mockito::Server::new_async().await;
+        let _repo = mock_repo(
+            &mut server,
+            "repo",
+            serde_json::json!({"default_branch": "main", "permissions": {"push": true}}),
+        )
+        .await;
+        let _branch = server
+            .mock("GET", "/repos/acme/team/branches/skills")
+            .with_status(200)
+            .with_body(r#"{"name":"skills","protected":false}"#)
+            .create_async()
+            .await;
+        let _rules = server
+            .mock("GET", "/repos/acme/team/rules/branches/skills")
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let branch = preflight_push(&client, &server.url(), "acme", "team", Some("skills")).await.unwrap();
+        assert_eq!(branch, "skills");
+    }
+
+    #[test]
+    fn test_enterprise_server_endpoints() {
+        let server = GitHubServer::enterprise(" https://github.acme.com/api/v3/ ").unwrap();
+        assert!(server.is_enterprise());
+        assert_eq!(server.host(), "github.acme.com");
+        assert_eq!(server.api_base, "https://github.acme.com/api/v3");
+        assert_eq!(server.device_code_url(), "https://github.acme.com/login/device/code");
+        assert_eq!(
+            server.raw_url("acme", "skills", "main", "a/SKILL.md"),
+            "https://github.acme.com/raw/acme/skills/main/a/SKILL.md"
+        );
+        assert_eq!(server.repo_path("https://github.acme.com/acme/skills/tree/dev").unwrap(), "acme/skills/tree/dev");
+        assert_eq!(server.repo_path("acme/skills").unwrap(), "acme/skills");
+        assert!(server.repo_path("https://github.com/acme/skills").unwrap_err().contains("github.acme.com"));
+        assert_eq!(
+            GitHubServer::default().repo_path("https://github.com/acme/skills").unwrap(),
+            "https://github.com/acme/skills"
+        );
+
+        assert!(GitHubServer::enterprise("ftp://github.acme.com").is_err());
+        assert!(GitHubServer::enterprise("github.acme.com").is_err());
+        assert!(GitHubServer::enterprise("https://github.com").is_err());
+        assert!(!GitHubServer::default().is_enterprise());
+    }
+
+    #[test]
+    fn test_from_settings_applies_overrides() {
+        let settings = crate::types::AppSettings {
+            github_enterprise_url: Some("https://github.acme.com".into()),
+            github_oauth_client_id: Some(" Iv1.abc ".into()),
+            github_ca_cert_path: Some("  ".into()),
+            github_accept_invalid_certs: true,
+            ..Default::default()
+        };
+        let server = GitHubServer::from_settings(&settings).unwrap();
+        assert_eq!(server.web_base, "https://github.acme.com");
+        assert_eq!(server.oauth_client_id.as_deref(), Some("Iv1.abc"));
+        assert_eq!(server.ca_cert_path, None);
+        assert!(server.accept_invalid_certs);
+
+        let public = GitHubServer::from_settings(&crate::types::AppSettings::default()).unwrap();
+        assert_eq!(public, GitHubServer::default());
+    }
+
+    #[test]
+    fn test_token_is_dropped_when_the_server_changes() {
+        let mut settings = crate::types::AppSettings {
+            github_oauth_token: Some("gho_public".into()),
+            github_user_login: Some("octocat".into()),
+            ..Default::default()
+        };
+        forget_token_for_other_server(&mut settings);
+        assert_eq!(settings.github_oauth_host.as_deref(), Some("https://github.com"));
+        assert_eq!(settings.github_oauth_token.as_deref(), Some("gho_public"));
+
+        settings.github_enterprise_url = Some("https://github.acme.com".into());
+        forget_token_for_other_server(&mut settings);
+        assert!(settings.github_oauth_token.is_none());
+        assert!(settings.github_oauth_host.is_none());
+        assert!(settings.github_user_login.is_none());
+
+        settings.github_oauth_token = Some("ghe_acme".into());
+        settings.github_oauth_host = Some("https://github.acme.com".into());
+        forget_token_for_other_server(&mut settings);
+        assert_eq!(settings.github_oauth_token.as_deref(), Some("ghe_acme"));
+
+        settings.github_enterprise_url = Some("not a url".into());
+        forget_token_for_other_server(&mut settings);
+        assert!(settings.github_oauth_token.is_none());
+    }
+
+    #[test]
+    fn test_client_builder_rejects_unreadable_ca_bundle() {
+        let dir = tempfile::tempdir().unwrap();
+        let empty = dir.path().join("empty.pem");
+        std::fs::write(&empty, "").unwrap();
+        let server = GitHubServer {
+            ca_cert_path: Some(empty.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        assert!(server.client_builder().unwrap_err().contains("No certificates"));
+
+        let missing = GitHubServer {
+            ca_cert_path: Some(dir.path().join("missing.pem").to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        assert!(missing.client_builder().unwrap_err().contains("Failed to read"));
+    }
+}
@@ -0,0 +1,388 @@
+//! Headless mode for terminals and CI pipelines.
+//!
+//! `skill-builder --headless <command>` starts the app as usual (same SQLite
+//! DB, workspace and sidecar pool) but never creates the main window. The
+//! command's progress and result are written to stdout as JSON lines, with
+//! agent events forwarded as they are emitted, and the process exits with 0
+//! on success, 1 on failure and 2 on a usage error. Logs still go to stderr
+//! and the log file.
+//!
+//! Commands:
+//! - `state <skill>`: print the saved workflow state.
+//! - `run-step <skill> <step> [--workspace <path>]`: run one workflow step to
+//!   completion, materialize and verify its outputs, and save the step status
+//!   the same way the workflow page does.
+//! - `package <skill>`: package the skill as a `.skill` archive.
+
+use std::io::Write;
+
+use tauri::{Listener, Manager};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+use crate::commands::workflow;
+use crate::commands::workflow_lifecycle::MAX_WORKFLOW_STEP_ID;
+use crate::types::StepStatusUpdate;
+
+pub const HEADLESS_FLAG: &str = "--headless";
+pub const EXIT_FAILED: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+
+const USAGE: &str = "usage: skill-builder --headless (state <skill> | run-step <skill> <step> [--workspace <path>] | package <skill>)";
+
+/// Agent events forwarded to stdout while a step runs.
+const FORWARDED_EVENTS: [&str; 5] = [
+    "agent-init-progress",
+    "agent-init-error",
+    "agent-message",
+    "agent-exit",
+    "agent-shutdown",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HeadlessCommand {
+    State {
+        skill_name: String,
+    },
+    RunStep {
+        skill_name: String,
+        step_id: u32,
+        workspace_path: Option<String>,
+    },
+    Package {
+        skill_name: String,
+    },
+}
+
+/// Parse process arguments (without the program name). `None` when
+/// `--headless` is absent, i.e. normal GUI startup.
+pub fn parse_args(args: &[String]) -> Option<Result<HeadlessCommand, String>> {
+    let pos = args.iter().position(|a| a == HEADLESS_FLAG)?;
+    Some(parse_command(&args[pos + 1..]))
+}
+
+fn parse_command(args: &[String]) -> Result<HeadlessCommand, String> {
+    let mut positional = Vec::new();
+    let mut workspace_path = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--workspace" {
+            let path = iter.next().ok_or_else(|| "--workspace needs a path".to_string())?;
+            workspace_path = Some(path.clone());
+        } else if arg.starts_with("--") {
+            return Err(format!("Unknown option '{}'. {}", arg, USAGE));
+        } else {
+            positional.push(arg.as_str());
+        }
+    }
+    let command = match positional.as_slice() {
+        ["state", skill] => HeadlessCommand::State {
+            skill_name: skill.to_string(),
+        },
+        ["package", skill] => HeadlessCommand::Package {
+            skill_name: skill.to_string(),
+        },
+        ["run-step", skill, step] => HeadlessCommand::RunStep {
+            skill_name: skill.to_string(),
+            step_id: step.parse().map_err(|_| format!("Invalid step '{}'", step))?,
+            workspace_path: workspace_path.clone(),
+        },
+        _ => return Err(USAGE.to_string()),
+    };
+    if workspace_path.is_some() && !matches!(command, HeadlessCommand::RunStep { .. }) {
+        return Err("--workspace only applies to run-step".to_string());
+    }
+    Ok(command)
+}
+
+/// Write one JSON line to stdout, flushed so CI logs stream.
+pub fn emit(value: &serde_json::Value) {
+    let mut out = std::io::stdout().lock();
+    let _ = writeln!(out, "{}", value);
+    let _ = out.flush();
+}
+
+/// Run `command` on the async runtime, then exit the app with its status.
+pub fn start(app: tauri::AppHandle, command: HeadlessCommand) {
+    tauri::async_runtime::spawn(async move {
+        let code = match execute(&app, command).await {
+            Ok(()) => 0,
+            Err(e) => {
+                log::error!("[headless] {}", e);
+                emit(&serde_json::json!({ "type": "error", "message": e }));
+                EXIT_FAILED
+            }
+        };
+        app.exit(code);
+    });
+}
+
+fn settings_workspace_path(app: &tauri::AppHandle) -> Result<String, String> {
+    let db = app.state::<crate::db::Db>();
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    crate::db::read_settings(&conn)?
+        .workspace_path
+        .ok_or_else(|| "Workspace path not initialized".to_string())
+}
+
+async fn execute(app: &tauri::AppHandle, command: HeadlessCommand) -> Result<(), String> {
+    log::info!("[headless] {:?}", command);
+    match command {
+        HeadlessCommand::State { skill_name } => {
+            let state = workflow::get_workflow_state(skill_name, app.state())?;
+            emit(&serde_json::json!({ "type": "workflow_state", "state": state }));
+        }
+        HeadlessCommand::Package { skill_name } => {
+            let workspace_path = settings_workspace_path(app)?;
+            let result = workflow::package_skill(skill_name, workspace_path, None, None, None, None, app.state()).await?;
+            emit(&serde_json::json!({ "type": "package", "result": result }));
+        }
+        HeadlessCommand::RunStep {
+            skill_name,
+            step_id,
+            workspace_path,
+        } => {
+            let workspace_path = match workspace_path {
+                Some(path) => path,
+                None => settings_workspace_path(app)?,
+            };
+            crate::commands::skill::acquire_lock(skill_name.clone(), app.state(), app.state())?;
+            let result = run_step(app, &skill_name, step_id, &workspace_path, emit).await;
+            if let Err(e) = crate::commands::skill::release_lock(skill_name.clone(), app.state(), app.state()) {
+                log::warn!("[headless] Failed to release lock on '{}': {}", skill_name, e);
+            }
+            result?;
+            emit(&serde_json::json!({
+                "type": "step_completed",
+                "skill_name": skill_name,
+                "step_id": step_id,
+            }));
+        }
+    }
+    Ok(())
+}
+
+/// Save `status` for `step_id`, sending every step's status like the
+/// workflow page does so the run-level status is derived correctly.
+fn save_step_status(app: &tauri::AppHandle, skill_name: &str, step_id: u32, status: &str) -> Result<(), String> {
+    let state = workflow::get_workflow_state(skill_name.to_string(), app.state())?;
+    let purpose = state
+        .run
+        .as_ref()
+        .map(|r| r.purpose.clone())
+        .ok_or_else(|| format!("Skill '{}' has no workflow run; create it in the app first", skill_name))?;
+    let step_statuses: Vec<StepStatusUpdate> = (0..=MAX_WORKFLOW_STEP_ID as i32)
+        .map(|id| StepStatusUpdate {
+            step_id: id,
+            status: if id == step_id as i32 {
+                status.to_string()
+            } else {
+                state
+                    .steps
+                    .iter()
+                    .find(|s| s.step_id == id)
+                    .map_or("pending", |s| s.status.as_str())
+                    .to_string()
+            },
+        })
+        .collect();
+    let run_status = if status == "in_progress" {
+        "in_progress"
+    } else if step_statuses.iter().all(|s| s.status == "completed") {
+        "completed"
+    } else {
+        "pending"
+    };
+    workflow::save_workflow_state(
+        skill_name.to_string(),
+        step_id as i32,
+        run_status.to_string(),
+        purpose,
+        step_statuses,
+        app.state(),
+        app.state(),
+    )
+}
+
+fn step_status(app: &tauri::AppHandle, skill_name: &str, step_id: u32) -> Option<String> {
+    let db = app.state::<crate::db::Db>();
+    let conn = db.0.lock().ok()?;
+    crate::db::get_workflow_steps(&conn, skill_name)
+        .ok()?
+        .into_iter()
+        .find(|s| s.step_id == step_id as i32)
+        .map(|s| s.status)
+}
+
+/// Run one step to completion, passing progress lines and forwarded agent
+/// events to `report`. Also used by the workflow queue, which ignores them.
+pub(crate) async fn run_step(
+    app: &tauri::AppHandle,
+    skill_name: &str,
+    step_id: u32,
+    workspace_path: &str,
+    report: fn(&serde_json::Value),
+) -> Result<(), String> {
+    // Listen before starting: events can arrive before run_workflow_step returns.
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let listeners: Vec<_> = FORWARDED_EVENTS
+        .iter()
+        .map(|&name| {
+            let tx = tx.clone();
+            app.listen_any(name, move |event| {
+                let payload = serde_json::from_str(event.payload()).unwrap_or(serde_json::Value::Null);
+                let _ = tx.send((name, payload));
+            })
+        })
+        .collect();
+
+    save_step_status(app, skill_name, step_id, "in_progress")?;
+    let outcome = drive_step(app, &mut rx, skill_name, step_id, workspace_path, report).await;
+    for id in listeners {
+        app.unlisten(id);
+    }
+    // The cost ticker marks a step it stopped for going over budget.
+    let (final_status, outcome) = match outcome {
+        Ok(()) => ("completed", Ok(())),
+        Err(_) if step_status(app, skill_name, step_id).as_deref() == Some("budget_exceeded") => {
+            ("budget_exceeded", Err(format!("Step {} stopped: budget exceeded", step_id)))
+        }
+        Err(e) => ("error", Err(e)),
+    };
+    let saved = save_step_status(app, skill_name, step_id, final_status);
+    if let Err(ref e) = saved {
+        log::error!("[headless] Failed to save step {} status for '{}': {}", step_id, skill_name, e);
+    }
+    outcome.and(saved)
+}
+
+/// The structured payload of a `result` message: `structured_output`, or a
+/// non-string `result` from older SDK versions.
+fn structured_result(message: &serde_json::Value) -> Option<serde_json::Value> {
+    if message["type"] != "result" {
+        return None;
+    }
+    [&message["structured_output"], &message["result"]]
+        .into_iter()
+        .find(|v| !v.is_null() && !v.is_string())
+        .cloned()
+}
+
+async fn drive_step(
+    app: &tauri::AppHandle,
+    rx: &mut UnboundedReceiver<(&'static str, serde_json::Value)>,
+    skill_name: &str,
+    step_id: u32,
+    workspace_path: &str,
+    report: fn(&serde_json::Value),
+) -> Result<(), String> {
+    let agent_id = workflow::run_workflow_step(
+        app.clone(),
+        app.state(),
+        app.state(),
+        skill_name.to_string(),
+        step_id,
+        workspace_path.to_string(),
+        None,
+    )
+    .await?;
+    report(&serde_json::json!({
+        "type": "step_started",
+        "skill_name": skill_name,
+        "step_id": step_id,
+        "agent_id": agent_id,
+    }));
+
+    let mut structured_output = None;
+    let success = loop {
+        let (name, payload) = rx
+            .recv()
+            .await
+            .ok_or_else(|| "Agent event stream closed".to_string())?;
+        let is_startup_error = name == "agent-init-error";
+        if !is_startup_error && payload["agent_id"] != agent_id.as_str() {
+            continue;
+        }
+        report(&serde_json::json!({ "type": name, "payload": payload }));
+        match name {
+            "agent-init-error" => {
+                return Err(payload["message"].as_str().unwrap_or("Agent failed to start").to_string());
+            }
+            "agent-message" => {
+                if let Some(output) = structured_result(&payload["message"]) {
+                    structured_output = Some(output);
+                }
+            }
+            "agent-exit" => break payload["success"].as_bool().unwrap_or(false),
+            "agent-shutdown" => break false,
+            _ => {}
+        }
+    };
+    if !success {
+        return Err(format!("Step {} failed", step_id));
+    }
+
+    // Steps 0-2 return their artifacts as structured output; only step 1 requires it.
+    match structured_output.filter(|o| o.is_object()) {
+        Some(output) if step_id <= 2 => {
+            workflow::materialize_workflow_step_output(
+                skill_name.to_string(),
+                step_id,
+                output,
+                app.state(),
+                app.state(),
+            )?;
+        }
+        None if step_id == 1 => {
+            return Err(format!("Step {} completed but produced no structured output", step_id));
+        }
+        _ => {}
+    }
+    if !workflow::verify_step_output(workspace_path.to_string(), skill_name.to_string(), step_id, app.state())? {
+        return Err(format!("Step {} completed but produced no output files", step_id));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse_args(&args("")), None);
+        assert_eq!(
+            parse_args(&args("--headless run-step my-skill 2 --workspace /tmp/ws")),
+            Some(Ok(HeadlessCommand::RunStep {
+                skill_name: "my-skill".to_string(),
+                step_id: 2,
+                workspace_path: Some("/tmp/ws".to_string()),
+            }))
+        );
+        assert_eq!(
+            parse_args(&args("--headless state my-skill")),
+            Some(Ok(HeadlessCommand::State {
+                skill_name: "my-skill".to_string()
+            }))
+        );
+        assert!(parse_args(&args("--headless run-step my-skill two")).unwrap().is_err());
+        assert!(parse_args(&args("--headless package my-skill --workspace /tmp")).unwrap().is_err());
+        assert!(parse_args(&args("--headless deploy my-skill")).unwrap().is_err());
+        assert!(parse_args(&args("--headless run-step my-skill 1 --workspace")).unwrap().is_err());
+    }
+
+    #[test]
+    fn test_structured_result_prefers_structured_output() {
+        let msg = serde_json::json!({ "type": "result", "result": "done", "structured_output": { "a": 1 } });
+        assert_eq!(structured_result(&msg), Some(serde_json::json!({ "a": 1 })));
+        let legacy = serde_json::json!({ "type": "result", "result": { "b": 2 } });
+        assert_eq!(structured_result(&legacy), Some(serde_json::json!({ "b": 2 })));
+        let text_only = serde_json::json!({ "type": "result", "result": "done" });
+        assert_eq!(structured_result(&text_only), None);
+        let assistant = serde_json::json!({ "type": "assistant", "structured_output": { "a": 1 } });
+        assert_eq!(structured_result(&assistant), None);
+    }
+}
@@ -0,0 +1,228 @@
+//! Append-only workspace event journal for crash forensics.
+//!
+//! Each app run writes JSONL events to `{workspace}/.journal`, one line per
+//! event, synced to disk before `record` returns so the tail survives a crash.
+//! On startup the previous run's journal is rotated to `.journal.prev`, which
+//! startup reconciliation reads to find steps that started but never finished.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const JOURNAL_FILE: &str = ".journal";
+pub const PREVIOUS_JOURNAL_FILE: &str = ".journal.prev";
+
+/// Journal path for the current run; `None` until `init` is called.
+static JOURNAL_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JournalEvent {
+    StepStarted { skill: String, step: u32 },
+    StepCompleted { skill: String, step: u32 },
+    FileWritten { skill: String, path: String },
+    SidecarSpawned { skill: String, pid: u32 },
+    SessionStarted { skill: String, session_id: String },
+    SessionEnded { session_id: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub ts: String,
+    #[serde(flatten)]
+    pub event: JournalEvent,
+}
+
+/// Rotate the previous run's journal and start recording to a fresh one.
+/// Called once from `setup()` after the workspace directory exists.
+pub fn init(workspace_path: &str) {
+    let workspace = Path::new(workspace_path);
+    rotate_previous(workspace);
+    match JOURNAL_PATH.lock() {
+        Ok(mut path) => *path = Some(workspace.join(JOURNAL_FILE)),
+        Err(e) => log::warn!("[journal] Failed to acquire journal lock: {}", e),
+    }
+}
+
+/// Move `.journal` to `.journal.prev`, replacing any older one.
+fn rotate_previous(workspace: &Path) {
+    let current = workspace.join(JOURNAL_FILE);
+    if !current.exists() {
+        return;
+    }
+    if let Err(e) = std::fs::rename(&current, workspace.join(PREVIOUS_JOURNAL_FILE)) {
+        log::warn!("[journal] Failed to rotate {}: {}", current.display(), e);
+    }
+}
+
+/// Record an event in the current run's journal. No-op before `init`;
+/// write failures are logged and never surface to the caller.
+pub fn record(event: JournalEvent) {
+    let guard = match JOURNAL_PATH.lock() {
+        Ok(g) => g,
+        Err(e) => {
+            log::warn!("[journal] Failed to acquire journal lock: {}", e);
+            return;
+        }
+    };
+    let Some(path) = guard.as_ref() else {
+        return;
+    };
+    if let Err(e) = append_to(path, &event) {
+        log::warn!("[journal] Failed to append to {}: {}", path.display(), e);
+    }
+}
+
+/// Append one event line and flush it to disk before returning.
+pub(crate) fn append_to(path: &Path, event: &JournalEvent) -> Result<(), String> {
+    let entry = JournalEntry {
+        ts: chrono::Utc::now().to_rfc3339(),
+        event: event.clone(),
+    };
+    let mut line = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+    line.push('\n');
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| e.to_string())?;
+    file.write_all(line.as_bytes()).map_err(|e| e.to_string())?;
+    file.sync_data().map_err(|e| e.to_string())
+}
+
+/// Read a journal file. Missing files yield no entries; lines that fail to
+/// parse (e.g. a write torn by the crash) are skipped.
+pub fn read_entries(path: &Path) -> Vec<JournalEntry> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                log::debug!("[journal] skipping unreadable line in {}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Entries from the previous run's journal, for startup reconciliation.
+pub fn read_previous(workspace_path: &str) -> Vec<JournalEntry> {
+    read_entries(&Path::new(workspace_path).join(PREVIOUS_JOURNAL_FILE))
+}
+
+/// Per skill, the last step that was started but never recorded as completed.
+pub fn interrupted_steps(entries: &[JournalEntry]) -> HashMap<String, u32> {
+    let mut open: HashMap<String, u32> = HashMap::new();
+    for entry in entries {
+        match &entry.event {
+            JournalEvent::StepStarted { skill, step } => {
+                open.insert(skill.clone(), *step);
+            }
+            JournalEvent::StepCompleted { skill, step } if open.get(skill) == Some(step) => {
+                open.remove(skill);
+            }
+            _ => {}
+        }
+    }
+    open
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(event: JournalEvent) -> JournalEntry {
+        JournalEntry {
+            ts: "2026-01-01T00:00:00+00:00".to_string(),
+            event,
+        }
+    }
+
+    #[test]
+    fn test_append_and_read_skips_torn_line() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join(JOURNAL_FILE);
+        append_to(
+            &path,
+            &JournalEvent::StepStarted {
+                skill: "my-skill".into(),
+                step: 3,
+            },
+        )
+        .unwrap();
+        append_to(
+            &path,
+            &JournalEvent::SidecarSpawned {
+                skill: "my-skill".into(),
+                pid: 42,
+            },
+        )
+        .unwrap();
+        // Simulate a crash mid-write.
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"ts\":\"2026-01-01T00:00:00+00:00\",\"event\":\"file_wr").unwrap();
+
+        let entries = read_entries(&path);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[1].event,
+            JournalEvent::SidecarSpawned {
+                skill: "my-skill".into(),
+                pid: 42
+            }
+        );
+        assert!(read_entries(&tmp.path().join("missing")).is_empty());
+    }
+
+    #[test]
+    fn test_event_serializes_flat_with_snake_case_tag() {
+        let line = serde_json::to_value(entry(JournalEvent::FileWritten {
+            skill: "s".into(),
+            path: "context/decisions.json".into(),
+        }))
+        .unwrap();
+        assert_eq!(line["event"], "file_written");
+        assert_eq!(line["path"], "context/decisions.json");
+    }
+
+    #[test]
+    fn test_interrupted_steps_tracks_last_unfinished_step() {
+        let entries = vec![
+            entry(JournalEvent::StepStarted { skill: "a".into(), step: 0 }),
+            entry(JournalEvent::StepCompleted { skill: "a".into(), step: 0 }),
+            entry(JournalEvent::StepStarted { skill: "a".into(), step: 2 }),
+            entry(JournalEvent::StepStarted { skill: "b".into(), step: 0 }),
+            entry(JournalEvent::StepCompleted { skill: "b".into(), step: 0 }),
+            entry(JournalEvent::SessionEnded { session_id: "x".into() }),
+        ];
+        let interrupted = interrupted_steps(&entries);
+        assert_eq!(interrupted.get("a"), Some(&2));
+        assert!(!interrupted.contains_key("b"));
+    }
+
+    #[test]
+    fn test_rotate_moves_journal_to_previous() {
+        let tmp = tempfile::tempdir().unwrap();
+        let workspace = tmp.path().to_str().unwrap();
+        append_to(
+            &tmp.path().join(JOURNAL_FILE),
+            &JournalEvent::StepStarted {
+                skill: "a".into(),
+                step: 3,
+            },
+        )
+        .unwrap();
+
+        rotate_previous(tmp.path());
+
+        assert!(!tmp.path().join(JOURNAL_FILE).exists());
+        let previous = read_previous(workspace);
+        assert_eq!(interrupted_steps(&previous).get("a"), Some(&3));
+    }
+}
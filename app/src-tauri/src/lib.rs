@@ -1,11 +1,26 @@
 mod agents;
+mod audit;
+mod automation_hooks;
 mod cleanup;
 mod commands;
 mod db;
+mod db_events;
+mod db_pool;
 mod fs_validation;
 pub mod git;
+mod git_hosting;
+mod github;
+mod headless;
+mod journal;
 mod logging;
+mod path_input;
 mod reconciliation;
+mod scheduler;
+mod settings_events;
+mod skill_paths;
+mod skill_watcher;
+mod status_tray;
+mod transcripts;
 mod types;
 
 use std::fs;
@@ -135,13 +150,36 @@ fn migrate_legacy_app_data_dir(new_data_dir: &Path) {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let headless = match headless::parse_args(&args) {
+        Some(Ok(command)) => Some(command),
+        Some(Err(e)) => {
+            headless::emit(&serde_json::json!({ "type": "error", "message": e }));
+            std::process::exit(headless::EXIT_USAGE);
+        }
+        None => None,
+    };
+
     tauri::Builder::default()
         .plugin(logging::build_log_plugin().build())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
-        .setup(|app| {
+        .setup(move |app| {
             use tauri::Manager;
 
+            // The main window is declared with `create: false` so headless runs
+            // never open it; GUI runs create it here.
+            if headless.is_none() {
+                let window_config = app
+                    .config()
+                    .app
+                    .windows
+                    .first()
+                    .cloned()
+                    .ok_or("no window configured in tauri.conf.json")?;
+                tauri::WebviewWindowBuilder::from_config(app.handle(), &window_config)?.build()?;
+            }
+
             // Native app menu with About item (macOS only)
             #[cfg(target_os = "macos")]
             {
@@ -227,6 +265,7 @@ pub fn run() {
                 pid: std::process::id(),
             };
             log::info!("Instance ID: [REDACTED], PID: [REDACTED]");
+            audit::init(&instance_info.id);
             app.manage(instance_info);
 
             // Apply persisted log level setting (fall back to info if DB read fails).
@@ -259,33 +298,122 @@ pub fn run() {
             // Non-fatal: errors are logged as warnings and startup continues.
             logging::prune_transcript_files(&workspace_path);
 
+            // Compress plain transcripts left by earlier versions or runs that
+            // never finished. Off the main thread: large logs take a while.
+            {
+                let workspace_path = workspace_path.clone();
+                std::thread::spawn(move || transcripts::compress_existing_transcripts(&workspace_path));
+            }
+
+            // Rotate the previous run's event journal (read by startup
+            // reconciliation) and start a fresh one for this run.
+            journal::init(&workspace_path);
+
+            // Push DB changes (skill updates, locks, usage) to the frontend.
+            db_events::init(app.handle().clone());
+
+            // Run the user's shell commands and webhooks on workflow events.
+            automation_hooks::init(app.handle().clone());
+
+            // Refresh settings-derived caches when settings change, and tell
+            // the frontend to reload its settings store.
+            settings_events::init(app.handle().clone());
+            settings_events::subscribe("workflow", |change| {
+                if change.touches("workspace_path") || change.touches("skills_path") {
+                    commands::workflow::clear_workspace_cache();
+                }
+            });
+
+            // Merge the organization's shared settings from the sync repo.
+            commands::settings_sync::start(app.handle().clone(), data_dir.clone());
+
             // Start the sidecar pool's idle cleanup task via Tauri's async runtime.
             // setup() runs on the main macOS thread which is not a Tokio thread.
             let pool = app.state::<agents::sidecar_pool::SidecarPool>();
             pool.start_on_tauri_runtime();
 
+            // Run queued heavy work (catalog prefetch, ...) when agents and power allow.
+            scheduler::start(app.handle().clone());
+
+            // Run queued workflow steps. Headless runs leave the queue to the app.
+            if headless.is_none() {
+                commands::workflow_queue::start(app.handle().clone());
+                // Finish batch refines interrupted by a quit or crash.
+                commands::refine::resume_refine_batches(app.handle().clone());
+                // Sync the library with skill folders edited outside the app.
+                skill_watcher::start(app.handle().clone());
+                // Menu bar quick stats with a "pause all agents" action.
+                if let Err(e) = status_tray::start(app.handle()) {
+                    log::warn!("[setup] Failed to create tray icon: {}", e);
+                }
+            }
+
+            // Queue marketplace catalog prefetches for the background scheduler.
+            commands::marketplace_catalog::start_idle_prefetch(app.handle().clone());
+
+            // Delete usage history past the retention policy, now and daily.
+            commands::data_retention::start(app.handle().clone());
+
+            if let Some(command) = headless {
+                #[cfg(target_os = "macos")]
+                app.set_activation_policy(tauri::ActivationPolicy::Accessory);
+                headless::start(app.handle().clone(), command);
+            }
+
             Ok(())
         })
         .manage(agents::sidecar_pool::SidecarPool::new())
         .manage(commands::refine::RefineSessionManager::new())
+        .manage(scheduler::BackgroundScheduler::new())
+        .manage(commands::workflow_queue::WorkflowQueue::new())
         .invoke_handler(tauri::generate_handler![
             commands::agent::start_agent,
             commands::node::check_node,
             commands::node::check_startup_deps,
+            commands::diagnostics::get_db_contention,
+            commands::diagnostics::run_diagnostics,
             commands::settings::get_data_dir,
             commands::settings::get_settings,
             commands::settings::save_settings,
+            commands::settings_sync::get_settings_sync_document,
+            commands::settings_sync::push_settings_sync,
+            commands::settings_sync::pull_settings_sync,
             commands::settings::test_api_key,
+            commands::local_model::list_local_models,
+            commands::offline::get_network_features,
             commands::settings::list_models,
             commands::settings::set_log_level,
             commands::settings::get_log_file_path,
             commands::settings::get_default_skills_path,
             commands::skill::list_skills,
+            commands::skill::list_skills_grouped,
             commands::skill::create_skill,
+            commands::skill_templates::list_skill_templates,
+            commands::skill_templates::create_skill_from_template,
             commands::skill::delete_skill,
+            commands::skill_impact::analyze_skill_impact,
+            commands::skill_invocations::import_skill_invocations,
+            commands::skill_invocations::get_skill_invocation_stats,
+            commands::skill_similarity::find_similar_skills,
+            commands::skill_dependencies::set_skill_dependencies,
+            commands::skill_dependencies::get_dependency_graph,
+            commands::skill_lint::lint_skill,
+            commands::skill_lint::fix_skill_accessibility,
+            commands::skill_preview::render_skill_preview,
+            commands::skill_review::set_skill_review_date,
+            commands::skill_review::mark_skill_reviewed,
+            commands::skill_review::get_skill_review_report,
+            commands::skill_scan::get_skill_scan_report,
             commands::skill::update_skill_tags,
             commands::skill::update_skill_metadata,
+            commands::intake::get_intake_questions,
+            commands::intake::submit_intake_answers,
+            commands::bulk_skills::bulk_update_skills,
+            commands::bulk_skills::bulk_delete_skills,
             commands::skill::rename_skill,
+            commands::skill::duplicate_skill,
+            commands::skill::export_skill_archive,
+            commands::skill::import_skill_archive,
             commands::skill::generate_suggestions,
             commands::skill::get_all_tags,
             commands::skill::get_installed_skill_names,
@@ -293,6 +421,9 @@ pub fn run() {
             commands::skill::release_lock,
             commands::skill::get_locked_skills,
             commands::skill::check_lock,
+            commands::observer::open_skill_readonly,
+            commands::observer::close_skill_readonly,
+            commands::observer::poll_skill_events,
             commands::skill::list_refinable_skills,
             commands::clarification::save_raw_file,
             commands::files::list_skill_files,
@@ -300,17 +431,28 @@ pub fn run() {
             commands::files::write_file,
             commands::files::copy_file,
             commands::files::read_file_as_base64,
+            commands::files::check_path_input,
+            commands::step_artifacts::list_step_artifacts,
             commands::files::write_base64_to_temp_file,
             commands::workflow::run_workflow_step,
+            commands::model_routing::get_effective_model,
+            commands::workflow::find_resumable_step_run,
+            commands::workflow::resume_workflow_step,
             commands::workflow::materialize_workflow_step_output,
             commands::workflow::package_skill,
+            commands::skill_localization::list_skill_translations,
+            commands::skill_localization::generate_skill_translation,
+            commands::skill_localization::materialize_skill_translation,
+            commands::workflow::validate_skill,
             commands::workflow::reset_workflow_step,
+            commands::workflow::reset_workflow_artifacts,
             commands::workflow::navigate_back_to_step,
             commands::workflow::preview_step_reset,
             commands::workflow::get_workflow_state,
             commands::workflow::save_workflow_state,
             commands::workflow::verify_step_output,
             commands::workflow::get_disabled_steps,
+            commands::workflow_manifest::get_workflow_steps,
             commands::workflow::get_clarifications_content,
             commands::workflow::save_clarifications_content,
             commands::workflow::get_decisions_content,
@@ -328,6 +470,32 @@ pub fn run() {
             commands::workflow::log_gate_decision,
             commands::workflow::scan_legacy_clarifications,
             commands::workflow::reset_legacy_skills,
+            commands::workflow::reload_prompts,
+            commands::workflow_queue::enqueue_workflow_step,
+            commands::workflow_queue::list_queue,
+            commands::workflow_queue::cancel_queued,
+            commands::quality_gates::list_quality_gate_profiles,
+            commands::quality_gates::run_quality_gate,
+            commands::quality_gates::waive_quality_check,
+            commands::quality_gates::revoke_quality_waiver,
+            commands::skill_version::get_skill_changelog,
+            commands::artifact_annotations::list_artifact_annotations,
+            commands::artifact_annotations::add_artifact_annotation,
+            commands::artifact_annotations::update_artifact_annotation,
+            commands::artifact_annotations::delete_artifact_annotation,
+            commands::automation_hooks::list_hook_deliveries,
+            commands::step_cache::find_cached_step_run,
+            commands::step_cache::reuse_cached_step_run,
+            commands::incremental_generation::get_regeneration_mode,
+            commands::workspace_snapshots::create_workspace_snapshot,
+            commands::workspace_snapshots::list_workspace_snapshots,
+            commands::workspace_snapshots::restore_workspace_snapshot,
+            commands::deployment_slots::get_skill_slots,
+            commands::deployment_slots::package_skill_slot,
+            commands::deployment_slots::install_skill_slot,
+            commands::deployment_slots::uninstall_skill_slot,
+            commands::deployment_slots::promote_beta_to_stable,
+            commands::repo_context::attach_repo_context,
             commands::sidecar_lifecycle::cleanup_skill_sidecar,
             commands::sidecar_lifecycle::graceful_shutdown,
             commands::workspace::get_workspace_path,
@@ -346,10 +514,12 @@ pub fn run() {
             commands::imported_skills::get_skill_content,
             commands::imported_skills::export_skill,
             commands::feedback::create_github_issue,
+            commands::feedback_bundle::scrub_feedback_attachments,
             commands::github_import::parse_github_url,
             commands::github_import::check_marketplace_url,
             commands::github_import::list_github_skills,
             commands::github_import::import_github_skills,
+            commands::import_progress::cancel_github_import,
             commands::github_auth::github_start_device_flow,
             commands::github_auth::github_poll_for_token,
             commands::github_auth::github_get_user,
@@ -358,6 +528,16 @@ pub fn run() {
             commands::github_import::get_dashboard_skill_names,
             commands::github_import::check_marketplace_updates,
             commands::github_import::check_skill_customized,
+            commands::git_hosting::git_host_connect,
+            commands::git_hosting::git_host_disconnect,
+            commands::git_hosting::git_host_list_accounts,
+            commands::git_hosting::git_host_list_repos,
+            commands::git_hosting::list_hosted_skills,
+            commands::git_hosting::import_hosted_skills,
+            commands::git_hosting::push_skill_to_host,
+            commands::git_hosting::push_skill_resolve_conflicts,
+            commands::git_hosting::list_upstream_reviews,
+            commands::git_hosting::refresh_upstream_reviews,
             commands::usage::persist_agent_run,
             commands::usage::get_usage_summary,
             commands::usage::get_recent_runs,
@@ -369,21 +549,99 @@ pub fn run() {
             commands::usage::get_step_agent_runs,
             commands::usage::get_agent_runs,
             commands::usage::get_usage_by_day,
+            commands::usage::get_skill_analytics,
             commands::usage::get_workflow_skill_names,
+            commands::usage::get_budget_status,
+            commands::step_perf::get_step_perf_trends,
+            commands::step_replay::replay_step,
+            commands::cost_estimate::estimate_step_cost,
+            commands::tool_policy::get_tool_policy,
+            commands::tool_policy::set_tool_policy,
+            commands::tool_policy::get_agent_tool_calls,
+            commands::transcripts::list_transcripts,
+            commands::transcripts::read_transcript_turns,
+            commands::transcripts::get_agent_transcript,
+            commands::transcripts::search_transcripts,
             commands::git::get_skill_history,
             commands::git::get_skill_diff,
+            commands::skill_compare::compare_skill_versions,
             commands::git::restore_skill_version,
             commands::skill::list_refinable_skills,
             commands::refine::get_skill_content_for_refine,
             commands::refine::get_refine_diff,
+            commands::structured_diff::get_structured_refine_diff,
+            commands::structured_diff::get_structured_skill_diff,
+            commands::structured_diff::apply_partial_diff,
             commands::refine::start_refine_session,
             commands::refine::send_refine_message,
             commands::refine::close_refine_session,
+            commands::refine::list_resumable_refine_sessions,
+            commands::refine::resume_refine_session,
+            commands::refine::review_refine_file,
             commands::refine::materialize_refine_validation_output,
+            commands::refine::start_refine_batch,
+            commands::refine::get_refine_batch,
+            commands::refine::list_refine_batches,
+            commands::refine::resolve_refine_batch_item,
             commands::skill_test::prepare_skill_test,
             commands::skill_test::cleanup_skill_test,
+            commands::skill_test_cases::create_test_case,
+            commands::skill_test_cases::list_test_cases,
+            commands::skill_test_cases::delete_test_case,
+            commands::skill_test_cases::run_skill_tests,
+            commands::skill_test_cases::get_test_results,
+            commands::eval_export::export_eval_dataset,
+            commands::project_context::package_project_context,
+            commands::error_help::get_error_help,
             commands::imported_skills::parse_skill_file,
             commands::imported_skills::import_skill_from_file,
+            commands::skill_signing::get_signing_public_key,
+            commands::skill_signing::sign_skill_package,
+            commands::skill_signing::verify_skill_package,
+            commands::skill_signing::list_trusted_keys,
+            commands::skill_signing::add_trusted_key,
+            commands::skill_signing::remove_trusted_key,
+            commands::skill_params::get_skill_parameters,
+            commands::skill_params::package_skill_with_parameters,
+            commands::onboarding::generate_onboarding_checklist,
+            commands::library_history::get_library_at,
+            commands::activation_policy::list_activation_decisions,
+            commands::command_history::record_command,
+            commands::command_history::list_command_history,
+            commands::command_history::rerun_command,
+            commands::prompt_contract::list_contract_upgrades,
+            commands::prompt_contract::apply_contract_upgrade,
+            commands::benchmark::benchmark_skill,
+            commands::benchmark::list_skill_benchmarks,
+            commands::example_skills::import_example_skills,
+            commands::step_explain::explain_step,
+            commands::frontmatter_migration::preview_frontmatter_migration,
+            commands::frontmatter_migration::apply_frontmatter_migration,
+            commands::frontmatter_migration::rollback_frontmatter_migration,
+            commands::background_tasks::list_background_tasks,
+            commands::background_tasks::enqueue_background_task,
+            commands::background_tasks::set_background_task_priority,
+            commands::background_tasks::cancel_background_task,
+            commands::linked_references::link_reference,
+            commands::linked_references::unlink_reference,
+            commands::linked_references::list_linked_references,
+            commands::linked_references::refresh_linked_references,
+            commands::linked_references::apply_linked_reference_updates,
+            commands::skill_references::list_skill_references,
+            commands::skill_references::add_skill_reference,
+            commands::skill_references::remove_skill_reference,
+            commands::tag_taxonomy::get_tag_tree,
+            commands::tag_taxonomy::rename_tag,
+            commands::tag_taxonomy::merge_tags,
+            commands::tag_taxonomy::remove_tag_alias,
+            commands::audit_log::get_audit_log,
+            commands::audit_log::export_audit_log,
+            commands::data_retention::purge_all_user_data,
+            commands::team_taxonomy::sync_team_taxonomy,
+            commands::team_taxonomy::get_team_taxonomy,
+            commands::team_taxonomy::validate_skill_taxonomy,
+            commands::team_taxonomy::check_skill_tags,
+            commands::team_taxonomy::propose_taxonomy_entries,
         ])
         .on_window_event(|window, event| {
             use tauri::Emitter;
@@ -86,24 +86,48 @@ pub fn get_log_file_path(app: &tauri::AppHandle) -> Result<String, String> {
         .ok_or_else(|| "Log file path contains invalid UTF-8".to_string())
 }
 
-/// Delete `.jsonl` transcript files older than today from all `{skill}/logs/`
-/// directories under the workspace path.
+/// Delete transcript files older than today (`.jsonl`, and `.jsonl.zst` with
+/// its `.jsonl.idx` index) from all `{skill}/logs/` directories under the
+/// workspace path. Compressed transcripts keep their original mtime, so they
+/// age out on the same schedule.
 ///
 /// Called early in the startup sequence (inside `.setup()`) after the workspace
 /// path is known. Errors are non-fatal: each failure is logged as a warning
 /// and cleanup continues.
 pub fn prune_transcript_files(workspace_path: &str) {
+    let today = chrono::Local::now().date_naive();
+    let (pruned, skills_affected, freed_bytes) = remove_transcript_files(workspace_path, Some(today));
+    if pruned > 0 {
+        log::info!(
+            "Pruned {} transcript files from {} skills ({} KB on disk)",
+            pruned,
+            skills_affected,
+            freed_bytes / 1024
+        );
+    }
+}
+
+/// Delete every transcript file under the workspace, whatever its age, for
+/// the user data purge. Returns how many transcripts were deleted.
+pub fn delete_all_transcript_files(workspace_path: &str) -> u32 {
+    remove_transcript_files(workspace_path, None).0
+}
+
+/// Delete transcripts last modified before `before` (all of them when `None`)
+/// and their indexes. Returns the transcripts deleted, the skills they
+/// belonged to and the bytes freed.
+fn remove_transcript_files(workspace_path: &str, before: Option<chrono::NaiveDate>) -> (u32, u32, u64) {
     use chrono::Local;
     use std::path::Path;
 
     let workspace = Path::new(workspace_path);
     if !workspace.exists() {
-        return;
+        return (0, 0, 0);
     }
 
-    let today = Local::now().date_naive();
     let mut pruned: u32 = 0;
     let mut skills_affected: u32 = 0;
+    let mut freed_bytes: u64 = 0;
 
     // Infrastructure directories to skip (all live under .claude/)
     const SKIP_DIRS: &[&str] = &[".claude"];
@@ -112,7 +136,7 @@ pub fn prune_transcript_files(workspace_path: &str) {
         Ok(e) => e,
         Err(e) => {
             log::warn!("Transcript pruning: failed to read workspace dir: {}", e);
-            return;
+            return (0, 0, 0);
         }
     };
 
@@ -149,8 +173,12 @@ pub fn prune_transcript_files(workspace_path: &str) {
         for log_entry in log_entries.flatten() {
             let log_path = log_entry.path();
 
-            // Only target .jsonl files
-            if log_path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+            // Only target transcripts (plain or compressed) and their indexes
+            let file_name = log_entry.file_name();
+            let file_name = file_name.to_string_lossy();
+            if !crate::transcripts::is_transcript(&file_name)
+                && !file_name.ends_with(crate::transcripts::INDEX_EXT)
+            {
                 continue;
             }
 
@@ -182,7 +210,7 @@ pub fn prune_transcript_files(workspace_path: &str) {
             let modified_date: chrono::NaiveDate =
                 chrono::DateTime::<Local>::from(modified).date_naive();
 
-            if modified_date < today {
+            if before.is_none_or(|before| modified_date < before) {
                 if let Err(e) = std::fs::remove_file(&log_path) {
                     log::warn!(
                         "Transcript pruning: failed to delete '{}': {}",
@@ -190,7 +218,12 @@ pub fn prune_transcript_files(workspace_path: &str) {
                         e
                     );
                 } else {
-                    skill_pruned += 1;
+                    // Sizes on disk, i.e. compressed sizes for `.jsonl.zst`
+                    freed_bytes += metadata.len();
+                    // Count transcripts, not their index files
+                    if !file_name.ends_with(crate::transcripts::INDEX_EXT) {
+                        skill_pruned += 1;
+                    }
                 }
             }
         }
@@ -201,13 +234,7 @@ pub fn prune_transcript_files(workspace_path: &str) {
         }
     }
 
-    if pruned > 0 {
-        log::info!(
-            "Pruned {} transcript files from {} skills",
-            pruned,
-            skills_affected
-        );
-    }
+    (pruned, skills_affected, freed_bytes)
 }
 
 #[cfg(test)]
@@ -288,6 +315,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_prune_deletes_old_compressed_transcripts_with_index() {
+        let tmp = tempdir().unwrap();
+        let workspace = tmp.path();
+
+        create_jsonl(workspace, "my-skill", "step0-old.jsonl.zst", 2);
+        create_jsonl(workspace, "my-skill", "step0-old.jsonl.idx", 2);
+        create_jsonl(workspace, "my-skill", "step0-new.jsonl.zst", 0);
+        create_jsonl(workspace, "my-skill", "step0-new.jsonl.idx", 0);
+
+        prune_transcript_files(workspace.to_str().unwrap());
+
+        let logs_dir = workspace.join("my-skill").join("logs");
+        assert!(!logs_dir.join("step0-old.jsonl.zst").exists());
+        assert!(!logs_dir.join("step0-old.jsonl.idx").exists());
+        assert!(logs_dir.join("step0-new.jsonl.zst").exists());
+        assert!(logs_dir.join("step0-new.jsonl.idx").exists());
+    }
+
     #[test]
     fn test_prune_skips_infrastructure_dirs() {
         let tmp = tempdir().unwrap();
@@ -347,6 +393,21 @@ mod tests {
         // Should complete without error
     }
 
+    #[test]
+    fn test_delete_all_transcript_files_includes_todays() {
+        let tmp = tempdir().unwrap();
+        let workspace = tmp.path();
+        create_jsonl(workspace, "my-skill", "step0-research-old.jsonl", 2);
+        create_jsonl(workspace, "my-skill", "step0-research-today.jsonl", 0);
+        fs::write(workspace.join("my-skill").join("logs").join("step0.log"), "log").unwrap();
+
+        assert_eq!(delete_all_transcript_files(workspace.to_str().unwrap()), 2);
+
+        let logs_dir = workspace.join("my-skill").join("logs");
+        assert!(!logs_dir.join("step0-research-today.jsonl").exists());
+        assert!(logs_dir.join("step0.log").exists(), ".log files should be kept");
+    }
+
     #[test]
     fn test_log_file_name_is_app() {
         assert_eq!(LOG_FILE_NAME, "app");
@@ -0,0 +1,331 @@
+//! Normalization and validation for user-entered file system paths.
+//!
+//! Paths typed or pasted into settings and import dialogs arrive in many shapes:
+//! wrapped in quotes by "Copy as path", using `~`, with Windows backslashes on
+//! macOS/Linux, or relative. `normalize` turns them into one canonical absolute
+//! form and reports what it changed; `check` additionally inspects the target
+//! on disk. Problems that make the path unusable are errors; anything the user
+//! may want to know about but that does not block them is a warning.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+/// What the caller expects the path to point at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathKind {
+    File,
+    Directory,
+    Any,
+}
+
+/// Result of normalizing and inspecting a path input.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PathCheck {
+    /// The raw input, unchanged.
+    pub input: String,
+    /// Canonical absolute path to store and pass to other commands.
+    pub path: String,
+    /// `path` with the home directory shortened to `~`, for display only.
+    pub display: String,
+    pub exists: bool,
+    pub is_dir: bool,
+    pub readable: bool,
+    pub writable: bool,
+    /// Non-blocking notes about what was changed or may cause trouble later.
+    pub warnings: Vec<String>,
+}
+
+/// Strip one pair of matching surrounding quotes.
+fn strip_quotes(raw: &str) -> Option<&str> {
+    for quote in ['"', '\''] {
+        if raw.len() >= 2 && raw.starts_with(quote) && raw.ends_with(quote) {
+            return Some(&raw[1..raw.len() - 1]);
+        }
+    }
+    None
+}
+
+/// `C:\…`, `C:/…` or a UNC share (`\\server\share`).
+fn is_windows_absolute(raw: &str) -> bool {
+    let bytes = raw.as_bytes();
+    let drive = bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && (bytes[2] == b'\\' || bytes[2] == b'/');
+    drive || raw.starts_with("\\\\")
+}
+
+/// Resolve `.` and `..` without touching the file system.
+fn clean_components(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+/// `canonicalize` on Windows returns `\\?\C:\…`; strip the verbatim prefix so the
+/// stored path matches what users see in Explorer.
+fn strip_verbatim_prefix(path: PathBuf) -> PathBuf {
+    let s = path.to_string_lossy();
+    match s.strip_prefix(r"\\?\") {
+        Some(rest) if !rest.starts_with("UNC\\") => PathBuf::from(rest),
+        _ => path,
+    }
+}
+
+fn home_dir() -> Result<PathBuf, String> {
+    dirs::home_dir().ok_or_else(|| "Could not determine home directory".to_string())
+}
+
+/// Normalize a user-entered path into a canonical absolute path.
+///
+/// Trims whitespace and surrounding quotes, strips a `file://` prefix, expands
+/// `~`, converts separators for the current platform, resolves relative paths
+/// against `base` (the home directory when `None`) and, when the target exists,
+/// resolves symlinks. Returns the path plus warnings describing any rewrites.
+pub fn normalize(raw: &str, base: Option<&Path>) -> Result<(PathBuf, Vec<String>), String> {
+    let mut warnings = Vec::new();
+    let mut text = raw.trim();
+    if let Some(unquoted) = strip_quotes(text) {
+        text = unquoted.trim();
+        warnings.push("Removed surrounding quotes".to_string());
+    }
+    if let Some(rest) = text.strip_prefix("file://") {
+        text = rest;
+        warnings.push("Removed file:// prefix".to_string());
+    }
+    if text.is_empty() {
+        return Err("Path is empty".to_string());
+    }
+
+    let mut text = text.to_string();
+    if cfg!(windows) {
+        if text.contains('/') {
+            text = text.replace('/', "\\");
+        }
+    } else {
+        if is_windows_absolute(&text) {
+            return Err(format!(
+                "'{}' is a Windows path and cannot be used on this system",
+                text
+            ));
+        }
+        if text.contains('\\') {
+            text = text.replace('\\', "/");
+            warnings.push("Converted backslashes to forward slashes".to_string());
+        }
+    }
+
+    let expanded = if text == "~" {
+        home_dir()?
+    } else if let Some(rest) = text.strip_prefix("~/").or_else(|| text.strip_prefix("~\\")) {
+        home_dir()?.join(rest)
+    } else if text.starts_with('~') {
+        return Err(format!(
+            "'{}' refers to another user's home directory, which is not supported",
+            text
+        ));
+    } else {
+        PathBuf::from(&text)
+    };
+
+    let absolute = if expanded.is_absolute() {
+        expanded
+    } else {
+        let base = match base {
+            Some(b) => b.to_path_buf(),
+            None => home_dir()?,
+        };
+        warnings.push(format!("Resolved relative path against {}", base.display()));
+        base.join(expanded)
+    };
+
+    let cleaned = clean_components(&absolute);
+    let canonical = match std::fs::canonicalize(&cleaned) {
+        Ok(resolved) => strip_verbatim_prefix(resolved),
+        Err(_) => cleaned,
+    };
+    Ok((canonical, warnings))
+}
+
+/// Shorten the home directory prefix to `~` for display.
+pub fn display_path(path: &Path) -> String {
+    if let Some(home) = dirs::home_dir() {
+        if let Ok(rest) = path.strip_prefix(&home) {
+            let sep = std::path::MAIN_SEPARATOR;
+            return if rest.as_os_str().is_empty() {
+                "~".to_string()
+            } else {
+                format!("~{}{}", sep, rest.display())
+            };
+        }
+    }
+    path.display().to_string()
+}
+
+/// Normalize `raw` and inspect the target. Fails when the path cannot be
+/// normalized, when `must_exist` is set and it is missing, or when it exists
+/// but is the wrong kind. A missing path's nearest existing ancestor is used
+/// to report whether it could be created.
+pub fn check(raw: &str, kind: PathKind, must_exist: bool, base: Option<&Path>) -> Result<PathCheck, String> {
+    let (path, mut warnings) = normalize(raw, base)?;
+    let metadata = std::fs::metadata(&path).ok();
+    let exists = metadata.is_some();
+    let is_dir = metadata.as_ref().is_some_and(|m| m.is_dir());
+
+    if !exists && must_exist {
+        return Err(format!("'{}' does not exist", path.display()));
+    }
+    match kind {
+        PathKind::File if is_dir => {
+            return Err(format!("'{}' is a folder, expected a file", path.display()));
+        }
+        PathKind::Directory if exists && !is_dir => {
+            return Err(format!("'{}' is a file, expected a folder", path.display()));
+        }
+        _ => {}
+    }
+
+    let readable = match &metadata {
+        Some(m) if m.is_dir() => std::fs::read_dir(&path).is_ok(),
+        Some(_) => std::fs::File::open(&path).is_ok(),
+        None => false,
+    };
+    let writable = match &metadata {
+        Some(m) => !m.permissions().readonly(),
+        None => path
+            .ancestors()
+            .skip(1)
+            .find_map(|a| std::fs::metadata(a).ok())
+            .is_some_and(|m| !m.permissions().readonly()),
+    };
+
+    if exists && !readable {
+        warnings.push("Permission denied reading this location".to_string());
+    }
+    if !writable {
+        warnings.push("This location is read-only".to_string());
+    }
+    if !exists {
+        warnings.push("Does not exist yet and will be created".to_string());
+    }
+
+    Ok(PathCheck {
+        input: raw.to_string(),
+        path: path.to_string_lossy().to_string(),
+        display: display_path(&path),
+        exists,
+        is_dir,
+        readable,
+        writable,
+        warnings,
+    })
+}
+
+/// Normalize a path to an existing, readable file for a command. Warnings are
+/// logged under the caller's prefix; the canonical path is returned.
+pub fn require_file(raw: &str, caller: &str) -> Result<String, String> {
+    let result = check(raw, PathKind::File, true, None).and_then(|c| {
+        if c.readable {
+            Ok(c)
+        } else {
+            Err(format!("Permission denied reading '{}'", c.path))
+        }
+    });
+    match result {
+        Ok(c) => {
+            for warning in &c.warnings {
+                log::debug!("[{}] path '{}': {}", caller, raw, warning);
+            }
+            Ok(c.path)
+        }
+        Err(e) => {
+            log::error!("[{}] {}", caller, e);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strips_quotes_and_file_prefix() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = std::fs::canonicalize(tmp.path()).unwrap();
+        let quoted = format!("  \"{}\"  ", dir.display());
+        let (path, warnings) = normalize(&quoted, None).unwrap();
+        assert_eq!(path, dir);
+        assert_eq!(warnings, vec!["Removed surrounding quotes"]);
+
+        let (path, _) = normalize(&format!("file://{}", dir.display()), None).unwrap();
+        assert_eq!(path, dir);
+        assert!(normalize("  ''  ", None).is_err());
+    }
+
+    #[test]
+    fn test_expands_home_and_resolves_relative() {
+        let home = dirs::home_dir().unwrap();
+        let (path, _) = normalize("~/skill-builder-nonexistent/x", None).unwrap();
+        assert_eq!(path, home.join("skill-builder-nonexistent").join("x"));
+        assert!(normalize("~someone/x", None).is_err());
+
+        let tmp = tempfile::tempdir().unwrap();
+        let (path, warnings) = normalize("a/./b/../c", Some(tmp.path())).unwrap();
+        assert_eq!(path, tmp.path().join("a").join("c"));
+        assert!(warnings[0].starts_with("Resolved relative path"));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_windows_paths_on_unix() {
+        assert!(normalize(r"C:\Users\me\skills", None).unwrap_err().contains("Windows path"));
+        assert!(normalize(r"\\server\share", None).is_err());
+
+        let (path, warnings) = normalize(r"/tmp/skill-builder-nonexistent\skills", None).unwrap();
+        assert_eq!(path, PathBuf::from("/tmp/skill-builder-nonexistent/skills"));
+        assert!(warnings.contains(&"Converted backslashes to forward slashes".to_string()));
+    }
+
+    #[test]
+    fn test_check_reports_existence_and_kind() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("pkg.skill");
+        std::fs::write(&file, "x").unwrap();
+
+        let c = check(file.to_str().unwrap(), PathKind::File, true, None).unwrap();
+        assert!(c.exists && !c.is_dir && c.readable && c.writable);
+        assert!(c.warnings.is_empty());
+
+        let err = check(tmp.path().to_str().unwrap(), PathKind::File, true, None).unwrap_err();
+        assert!(err.contains("expected a file"));
+        let err = check(file.to_str().unwrap(), PathKind::Directory, false, None).unwrap_err();
+        assert!(err.contains("expected a folder"));
+
+        let missing = tmp.path().join("new-dir");
+        assert!(check(missing.to_str().unwrap(), PathKind::Directory, true, None).is_err());
+        let c = check(missing.to_str().unwrap(), PathKind::Directory, false, None).unwrap();
+        assert!(!c.exists && c.writable);
+        assert!(c.warnings.iter().any(|w| w.contains("will be created")));
+    }
+
+    #[test]
+    fn test_display_path_shortens_home() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(display_path(&home), "~");
+        let nested = home.join("skills");
+        assert_eq!(
+            display_path(&nested),
+            format!("~{}skills", std::path::MAIN_SEPARATOR)
+        );
+    }
+}
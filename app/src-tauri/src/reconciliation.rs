@@ -1,6 +1,6 @@
 use crate::cleanup::cleanup_future_steps;
 use crate::fs_validation::{detect_furthest_step, detect_furthest_step_with_options, has_skill_output};
-use crate::types::{DiscoveredSkill, ReconciliationResult};
+use crate::types::{DiscoveredSkill, ReconciliationResult, WorkflowStepRow};
 use std::collections::HashSet;
 use std::path::Path;
 
@@ -27,6 +27,9 @@ use std::path::Path;
 /// 10. Master row, no workflow_runs → auto-create workflow_runs
 /// 11. Marketplace SKILL.md exists → no action
 /// 12. Marketplace SKILL.md missing → delete from master
+///
+/// Steps the previous run's journal shows as started but never completed are
+/// not trusted as disk evidence: their partial output is discarded.
 pub fn reconcile_on_startup(
     conn: &rusqlite::Connection,
     workspace_path: &str,
@@ -36,6 +39,7 @@ pub fn reconcile_on_startup(
     // ── Pass 1: DB-driven — loop over skills master, branch on skill_source ──
 
     let all_skills = crate::db::list_all_skills(conn)?;
+    let interrupted = crate::journal::interrupted_steps(&crate::journal::read_previous(workspace_path));
 
     log::info!(
         "[reconcile_on_startup] starting: {} skills in master, workspace={} skills_path={}",
@@ -52,6 +56,7 @@ pub fn reconcile_on_startup(
                     &skill.name,
                     workspace_path,
                     skills_path,
+                    interrupted.get(&skill.name).copied(),
                     &mut notifications,
                 )?;
             }
@@ -249,6 +254,7 @@ pub fn preview_reconcile_on_startup(
     let mut notifications = Vec::new();
     let mut discovered_skills = Vec::new();
     let all_skills = crate::db::list_all_skills(conn)?;
+    let interrupted = crate::journal::interrupted_steps(&crate::journal::read_previous(workspace_path));
 
     for skill in &all_skills {
         match skill.skill_source.as_str() {
@@ -279,8 +285,17 @@ pub fn preview_reconcile_on_startup(
                 }
 
                 let run = maybe_run.expect("checked above");
-                let maybe_disk_step =
+                let detected =
                     detect_furthest_step_with_options(workspace_path, &skill.name, skills_path, false);
+                let steps = crate::db::get_workflow_steps(conn, &skill.name)?;
+                let maybe_disk_step = cap_at_interrupted_step(
+                    detected,
+                    interrupted.get(&skill.name).copied(),
+                    &steps,
+                );
+                if let Some(step) = interrupted_step_discarded(detected, maybe_disk_step) {
+                    notifications.push(interrupted_notification(&skill.name, step));
+                }
 
                 if let Some(disk_step) = maybe_disk_step.map(|s| s as i32) {
                     const DETECTABLE_STEPS: &[i32] = &[0, 2, 3];
@@ -390,6 +405,7 @@ fn reconcile_skill_builder(
     name: &str,
     workspace_path: &str,
     skills_path: &str,
+    interrupted_step: Option<u32>,
     notifications: &mut Vec<String>,
 ) -> Result<(), String> {
     // Scenario 7: active session — skip entirely
@@ -472,7 +488,23 @@ fn reconcile_skill_builder(
     );
 
     // Reconcile DB step state against disk evidence
-    let maybe_disk_step = detect_furthest_step(workspace_path, name, skills_path);
+    let detected = detect_furthest_step(workspace_path, name, skills_path);
+
+    // Output from a step that crashed mid-run is partial — discard it
+    let maybe_disk_step = cap_at_interrupted_step(detected, interrupted_step, &steps);
+    if let Some(step) = interrupted_step_discarded(detected, maybe_disk_step) {
+        log::info!(
+            "[reconcile] '{}': skill_source=skill-builder, action=discard_interrupted (journal shows step {} unfinished, disk_step={:?})",
+            name, step, detected
+        );
+        cleanup_future_steps(
+            workspace_path,
+            name,
+            maybe_disk_step.map(|s| s as i32).unwrap_or(-1),
+            skills_path,
+        );
+        notifications.push(interrupted_notification(name, step));
+    }
 
     log::debug!(
         "[reconcile] '{}': disk furthest step = {:?}",
@@ -611,6 +643,41 @@ fn reconcile_skill_builder(
     Ok(())
 }
 
+/// Cap the disk-detected step below a step the journal shows was interrupted,
+/// unless the DB recorded that step as completed after all.
+fn cap_at_interrupted_step(
+    disk_step: Option<u32>,
+    interrupted_step: Option<u32>,
+    steps: &[WorkflowStepRow],
+) -> Option<u32> {
+    let Some(interrupted) = interrupted_step else {
+        return disk_step;
+    };
+    let completed_in_db = steps
+        .iter()
+        .any(|s| s.step_id == interrupted as i32 && s.status == "completed");
+    match disk_step {
+        Some(disk) if disk >= interrupted && !completed_in_db => interrupted.checked_sub(1),
+        other => other,
+    }
+}
+
+/// The first discarded step when capping changed the detected step.
+fn interrupted_step_discarded(detected: Option<u32>, capped: Option<u32>) -> Option<u32> {
+    if detected == capped {
+        return None;
+    }
+    Some(capped.map(|s| s + 1).unwrap_or(0))
+}
+
+fn interrupted_notification(name: &str, step: u32) -> String {
+    format!(
+        "'{}' step {} was interrupted before finishing — its partial output was discarded",
+        name,
+        step + 1
+    )
+}
+
 /// Reconcile a marketplace skill: check that SKILL.md still exists on disk.
 /// If missing, delete from skills master (scenario 12).
 fn reconcile_marketplace(
@@ -1288,6 +1355,41 @@ mod tests {
         assert_eq!(run.current_step, 3);
     }
 
+    #[test]
+    fn test_journal_interrupted_step_output_is_discarded() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skills_tmp = tempfile::tempdir().unwrap();
+        let workspace = tmp.path().to_str().unwrap();
+        let skills_path = skills_tmp.path().to_str().unwrap();
+        let conn = create_test_db();
+
+        crate::db::save_workflow_run(&conn, "my-skill", 3, "pending", "domain").unwrap();
+        create_skill_dir(tmp.path(), "my-skill", "sales");
+        create_step_output(tmp.path(), "my-skill", 0);
+        create_step_output(tmp.path(), "my-skill", 2);
+        // The app crashed while step 3 was writing SKILL.md.
+        create_step_output(skills_tmp.path(), "my-skill", 3);
+        crate::journal::append_to(
+            &tmp.path().join(crate::journal::PREVIOUS_JOURNAL_FILE),
+            &crate::journal::JournalEvent::StepStarted {
+                skill: "my-skill".into(),
+                step: 3,
+            },
+        )
+        .unwrap();
+
+        let result = reconcile_on_startup(&conn, workspace, skills_path).unwrap();
+
+        assert!(result
+            .notifications
+            .iter()
+            .any(|n| n.contains("step 4 was interrupted")));
+        assert!(!skills_tmp.path().join("my-skill/SKILL.md").exists());
+        let run = crate::db::get_workflow_run(&conn, "my-skill").unwrap().unwrap();
+        assert_eq!(run.current_step, 2);
+        assert_eq!(run.status, "pending");
+    }
+
     // --- Edge cases ---
 
     #[test]
@@ -0,0 +1,376 @@
+//! Central scheduler for heavy background work.
+//!
+//! Jobs such as the marketplace catalog prefetch are queued here instead of
+//! running on their own timers. A single worker runs one task at a time, most
+//! urgent first. Before starting a task it checks whether agents are running
+//! and whether the machine is on a low battery; tasks whose priority doesn't
+//! allow running under those conditions stay queued with the reason recorded,
+//! so the queue shown in Settings explains what is waiting and why. Users can
+//! raise a task's priority (High runs regardless) or cancel it.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use tauri::Manager;
+
+use crate::agents::sidecar_pool::SidecarPool;
+use crate::types::{BackgroundQueue, BackgroundTask, BackgroundTaskKind, PowerState, TaskPriority};
+
+/// How often the worker re-checks deferred tasks without being woken.
+const TICK_SECS: u64 = 15;
+
+/// Battery level at or below which Low-priority tasks wait (when on battery).
+pub(crate) const LOW_BATTERY_PERCENT: u8 = 20;
+
+/// Finished tasks kept for display.
+const FINISHED_HISTORY: usize = 20;
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+/// Why a task of `priority` can't start now, or `None` if it can.
+pub(crate) fn deferral_reason(priority: TaskPriority, agents_running: bool, power: Option<&PowerState>) -> Option<String> {
+    let battery_low = power.is_some_and(|p| p.on_battery && p.battery_percent.is_some_and(|b| b <= LOW_BATTERY_PERCENT));
+    match priority {
+        TaskPriority::High => None,
+        _ if agents_running => Some("Waiting for running agents to finish".to_string()),
+        TaskPriority::Low if battery_low => Some("Waiting for power (battery low)".to_string()),
+        _ => None,
+    }
+}
+
+#[derive(Default)]
+struct SchedulerState {
+    next_id: u64,
+    tasks: Vec<BackgroundTask>,
+}
+
+pub struct BackgroundScheduler {
+    state: Mutex<SchedulerState>,
+    wake: tokio::sync::Notify,
+}
+
+impl Default for BackgroundScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BackgroundScheduler {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(SchedulerState::default()),
+            wake: tokio::sync::Notify::new(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SchedulerState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Queue `kind` unless one is already queued or running. Returns the task id
+    /// (the existing one when deduplicated, with its priority raised if lower).
+    pub fn enqueue(&self, kind: BackgroundTaskKind, priority: TaskPriority) -> u64 {
+        let mut state = self.lock();
+        if let Some(task) = state
+            .tasks
+            .iter_mut()
+            .find(|t| t.kind == kind && (t.status == "queued" || t.status == "running"))
+        {
+            task.priority = task.priority.max(priority);
+            return task.id;
+        }
+        state.next_id += 1;
+        let id = state.next_id;
+        state.tasks.push(BackgroundTask {
+            id,
+            kind,
+            priority,
+            status: "queued".to_string(),
+            deferred_reason: None,
+            enqueued_at: now(),
+            started_at: None,
+            finished_at: None,
+            error: None,
+        });
+        drop(state);
+        self.wake.notify_one();
+        id
+    }
+
+    /// Active tasks first (running, then queued by urgency), then finished ones newest first.
+    pub fn tasks(&self) -> Vec<BackgroundTask> {
+        let mut tasks = self.lock().tasks.clone();
+        let rank = |t: &BackgroundTask| match t.status.as_str() {
+            "running" => 0,
+            "queued" => 1,
+            _ => 2,
+        };
+        tasks.sort_by(|a, b| {
+            rank(a)
+                .cmp(&rank(b))
+                .then_with(|| if rank(a) == 1 { b.priority.cmp(&a.priority) } else { std::cmp::Ordering::Equal })
+                .then_with(|| if rank(a) == 2 { b.id.cmp(&a.id) } else { a.id.cmp(&b.id) })
+        });
+        tasks
+    }
+
+    pub fn set_priority(&self, id: u64, priority: TaskPriority) -> Result<BackgroundTask, String> {
+        let mut state = self.lock();
+        let task = state
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or_else(|| format!("Background task {} not found", id))?;
+        if task.status != "queued" {
+            return Err(format!("Background task {} is {}; only queued tasks can be reprioritized", id, task.status));
+        }
+        task.priority = priority;
+        let updated = task.clone();
+        drop(state);
+        self.wake.notify_one();
+        Ok(updated)
+    }
+
+    pub fn cancel(&self, id: u64) -> Result<(), String> {
+        let mut state = self.lock();
+        let task = state
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or_else(|| format!("Background task {} not found", id))?;
+        if task.status != "queued" {
+            return Err(format!("Background task {} is {}; only queued tasks can be cancelled", id, task.status));
+        }
+        task.status = "cancelled".to_string();
+        task.deferred_reason = None;
+        task.finished_at = Some(now());
+        Self::prune(&mut state);
+        Ok(())
+    }
+
+    /// Pick the most urgent queued task allowed to run and mark it running.
+    /// Tasks left waiting get their `deferred_reason` refreshed.
+    fn start_next(&self, agents_running: bool, power: Option<&PowerState>) -> Option<(u64, BackgroundTaskKind)> {
+        let mut state = self.lock();
+        if state.tasks.iter().any(|t| t.status == "running") {
+            return None;
+        }
+        for task in state.tasks.iter_mut().filter(|t| t.status == "queued") {
+            task.deferred_reason = deferral_reason(task.priority, agents_running, power);
+        }
+        let next = state
+            .tasks
+            .iter_mut()
+            .filter(|t| t.status == "queued" && t.deferred_reason.is_none())
+            .max_by(|a, b| a.priority.cmp(&b.priority).then_with(|| b.id.cmp(&a.id)))?;
+        next.status = "running".to_string();
+        next.started_at = Some(now());
+        Some((next.id, next.kind))
+    }
+
+    fn finish(&self, id: u64, result: Result<(), String>) {
+        let mut state = self.lock();
+        if let Some(task) = state.tasks.iter_mut().find(|t| t.id == id) {
+            task.finished_at = Some(now());
+            match result {
+                Ok(()) => task.status = "completed".to_string(),
+                Err(e) => {
+                    task.status = "failed".to_string();
+                    task.error = Some(e);
+                }
+            }
+        }
+        Self::prune(&mut state);
+    }
+
+    fn prune(state: &mut SchedulerState) {
+        let finished = |t: &BackgroundTask| !matches!(t.status.as_str(), "queued" | "running");
+        let excess = state.tasks.iter().filter(|t| finished(t)).count().saturating_sub(FINISHED_HISTORY);
+        let mut removed = 0;
+        state.tasks.retain(|t| {
+            if removed < excess && finished(t) {
+                removed += 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+/// Battery state from Linux `/sys/class/power_supply`.
+pub(crate) fn read_sysfs_power(dir: &Path) -> Option<PowerState> {
+    let read = |p: &Path, f: &str| std::fs::read_to_string(p.join(f)).ok().map(|s| s.trim().to_string());
+    let mut battery = None;
+    let mut on_mains = None;
+    for entry in std::fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+        match read(&path, "type").as_deref() {
+            Some("Battery") if battery.is_none() => {
+                let percent = read(&path, "capacity").and_then(|c| c.parse::<u8>().ok());
+                let discharging = read(&path, "status").as_deref() == Some("Discharging");
+                battery = Some((percent, discharging));
+            }
+            Some("Mains") => on_mains = Some(on_mains.unwrap_or(false) || read(&path, "online").as_deref() == Some("1")),
+            _ => {}
+        }
+    }
+    let (battery_percent, discharging) = battery?;
+    Some(PowerState {
+        on_battery: on_mains.map_or(discharging, |online| !online),
+        battery_percent,
+    })
+}
+
+/// Battery state from macOS `pmset -g batt` output.
+pub(crate) fn parse_pmset(output: &str) -> Option<PowerState> {
+    let battery_line = output.lines().find(|l| l.contains("InternalBattery"))?;
+    let battery_percent = battery_line
+        .split(|c: char| c.is_whitespace() || c == ';')
+        .find_map(|part| part.strip_suffix('%')?.parse::<u8>().ok());
+    Some(PowerState {
+        on_battery: output.contains("'Battery Power'"),
+        battery_percent,
+    })
+}
+
+/// Current power state; `None` on desktops and where it can't be read.
+pub fn power_state() -> Option<PowerState> {
+    if cfg!(target_os = "linux") {
+        read_sysfs_power(Path::new("/sys/class/power_supply"))
+    } else if cfg!(target_os = "macos") {
+        let output = std::process::Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+        parse_pmset(&String::from_utf8_lossy(&output.stdout))
+    } else {
+        None
+    }
+}
+
+async fn run_task(app: &tauri::AppHandle, kind: BackgroundTaskKind) -> Result<(), String> {
+    match kind {
+        BackgroundTaskKind::CatalogPrefetch => {
+            crate::commands::marketplace_catalog::prefetch_registries(app).await;
+            Ok(())
+        }
+        BackgroundTaskKind::RetentionPrune => {
+            crate::commands::data_retention::prune_expired(&app.state::<crate::db::Db>()).map(|_| ())
+        }
+    }
+}
+
+/// Start the worker loop. Safe to call from `setup()`.
+pub fn start(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let scheduler = app.state::<BackgroundScheduler>();
+            let agents_running = !app.state::<SidecarPool>().is_idle().await;
+            let power = tokio::task::spawn_blocking(power_state).await.ok().flatten();
+            match scheduler.start_next(agents_running, power.as_ref()) {
+                Some((id, kind)) => {
+                    log::debug!("[scheduler] running task {} ({:?})", id, kind);
+                    let result = run_task(&app, kind).await;
+                    if let Err(e) = &result {
+                        log::warn!("[scheduler] task {} ({:?}) failed: {}", id, kind, e);
+                    }
+                    scheduler.finish(id, result);
+                }
+                None => {
+                    let _ = tokio::time::timeout(std::time::Duration::from_secs(TICK_SECS), scheduler.wake.notified())
+                        .await;
+                }
+            }
+        }
+    });
+}
+
+/// Snapshot for the Settings queue view.
+pub async fn queue(app: &tauri::AppHandle) -> BackgroundQueue {
+    let agents_running = !app.state::<SidecarPool>().is_idle().await;
+    let power = tokio::task::spawn_blocking(power_state).await.ok().flatten();
+    BackgroundQueue {
+        tasks: app.state::<BackgroundScheduler>().tasks(),
+        agents_running,
+        power,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn battery(percent: u8, on_battery: bool) -> PowerState {
+        PowerState {
+            on_battery,
+            battery_percent: Some(percent),
+        }
+    }
+
+    #[test]
+    fn test_deferral_reason_by_priority() {
+        let low = battery(10, true);
+        assert!(deferral_reason(TaskPriority::Low, true, None).is_some());
+        assert!(deferral_reason(TaskPriority::Low, false, Some(&low)).unwrap().contains("battery"));
+        assert!(deferral_reason(TaskPriority::Low, false, Some(&battery(10, false))).is_none());
+        assert!(deferral_reason(TaskPriority::Low, false, Some(&battery(80, true))).is_none());
+        assert!(deferral_reason(TaskPriority::Normal, false, Some(&low)).is_none());
+        assert!(deferral_reason(TaskPriority::Normal, true, None).is_some());
+        assert!(deferral_reason(TaskPriority::High, true, Some(&low)).is_none());
+    }
+
+    #[test]
+    fn test_scheduler_defers_dedupes_and_reprioritizes() {
+        let scheduler = BackgroundScheduler::new();
+        let id = scheduler.enqueue(BackgroundTaskKind::CatalogPrefetch, TaskPriority::Low);
+        assert_eq!(scheduler.enqueue(BackgroundTaskKind::CatalogPrefetch, TaskPriority::Low), id);
+
+        assert_eq!(scheduler.start_next(true, None), None);
+        assert!(scheduler.tasks()[0].deferred_reason.as_deref().unwrap().contains("agents"));
+
+        scheduler.set_priority(id, TaskPriority::High).unwrap();
+        assert_eq!(scheduler.start_next(true, None), Some((id, BackgroundTaskKind::CatalogPrefetch)));
+        assert!(scheduler.set_priority(id, TaskPriority::Low).is_err());
+        assert!(scheduler.cancel(id).is_err());
+
+        // A new request while running is folded into the running task
+        assert_eq!(scheduler.enqueue(BackgroundTaskKind::CatalogPrefetch, TaskPriority::Low), id);
+        scheduler.finish(id, Err("offline".to_string()));
+        let task = &scheduler.tasks()[0];
+        assert_eq!(task.status, "failed");
+        assert_eq!(task.error.as_deref(), Some("offline"));
+
+        let next = scheduler.enqueue(BackgroundTaskKind::CatalogPrefetch, TaskPriority::Low);
+        assert_ne!(next, id);
+        scheduler.cancel(next).unwrap();
+        assert_eq!(scheduler.start_next(false, None), None);
+        assert!(scheduler.cancel(999).is_err());
+    }
+
+    #[test]
+    fn test_read_sysfs_power() {
+        let tmp = tempfile::tempdir().unwrap();
+        let write = |dir: &str, files: &[(&str, &str)]| {
+            std::fs::create_dir_all(tmp.path().join(dir)).unwrap();
+            for (name, content) in files {
+                std::fs::write(tmp.path().join(dir).join(name), content).unwrap();
+            }
+        };
+        assert_eq!(read_sysfs_power(tmp.path()), None);
+
+        write("BAT0", &[("type", "Battery\n"), ("capacity", "15\n"), ("status", "Discharging\n")]);
+        assert_eq!(read_sysfs_power(tmp.path()), Some(battery(15, true)));
+
+        write("AC", &[("type", "Mains\n"), ("online", "1\n")]);
+        assert_eq!(read_sysfs_power(tmp.path()), Some(battery(15, false)));
+    }
+
+    #[test]
+    fn test_parse_pmset() {
+        let on_battery = "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1234)\t18%; discharging; 1:02 remaining present: true\n";
+        assert_eq!(parse_pmset(on_battery), Some(battery(18, true)));
+        let charging = "Now drawing from 'AC Power'\n -InternalBattery-0 (id=1234)\t95%; charging; 0:20 remaining present: true\n";
+        assert_eq!(parse_pmset(charging), Some(battery(95, false)));
+        assert_eq!(parse_pmset("Now drawing from 'AC Power'\n"), None);
+    }
+}
@@ -0,0 +1,137 @@
+//! Notifies subsystems when app settings change.
+//!
+//! `db::write_settings` calls `publish` after every successful write with
+//! the names of the fields that differ from the stored value. Backend
+//! subsystems that cache settings-derived state register with `subscribe`
+//! in `setup()` and refresh it in place, and the change is sent to the
+//! frontend on the `settings-changed` channel so open windows reload their
+//! settings store. Subscribers run synchronously while the caller still
+//! holds the DB lock, so they must not touch the database; delivery to the
+//! frontend is a no-op until `init` runs.
+
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::Emitter;
+
+use crate::types::AppSettings;
+
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+type Subscriber = Box<dyn Fn(&SettingsChange) + Send>;
+
+static EMITTER: Mutex<Option<tauri::AppHandle>> = Mutex::new(None);
+static SUBSCRIBERS: Mutex<Vec<(&'static str, Subscriber)>> = Mutex::new(Vec::new());
+
+/// Settings fields that changed, named as in `AppSettings`. Values are left
+/// out so API keys and tokens never reach the event channel.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SettingsChange {
+    pub changed: Vec<String>,
+}
+
+impl SettingsChange {
+    pub fn touches(&self, field: &str) -> bool {
+        self.changed.iter().any(|f| f == field)
+    }
+}
+
+/// Start delivering changes to the frontend. Called once from `setup()`.
+pub fn init(app: tauri::AppHandle) {
+    match EMITTER.lock() {
+        Ok(mut emitter) => *emitter = Some(app),
+        Err(e) => log::warn!("[settings_events] Failed to acquire emitter lock: {}", e),
+    }
+}
+
+/// Register a callback run on every settings change. `name` is only used in logs.
+pub fn subscribe(name: &'static str, callback: impl Fn(&SettingsChange) + Send + 'static) {
+    match SUBSCRIBERS.lock() {
+        Ok(mut subscribers) => subscribers.push((name, Box::new(callback))),
+        Err(e) => log::warn!("[settings_events] Failed to register '{}': {}", name, e),
+    }
+}
+
+/// Top-level fields whose serialized values differ between `old` and `new`.
+pub fn changed_fields(old: &AppSettings, new: &AppSettings) -> Vec<String> {
+    let (Ok(serde_json::Value::Object(old)), Ok(serde_json::Value::Object(new))) =
+        (serde_json::to_value(old), serde_json::to_value(new))
+    else {
+        return Vec::new();
+    };
+    new.iter()
+        .filter(|(key, value)| old.get(*key) != Some(*value))
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+/// Tell subscribers and the frontend which fields changed. Does nothing when
+/// the write left every field as it was.
+pub fn publish(old: &AppSettings, new: &AppSettings) {
+    let changed = changed_fields(old, new);
+    if changed.is_empty() {
+        return;
+    }
+    log::debug!("[settings_events] changed: {}", changed.join(", "));
+    let change = SettingsChange { changed };
+    match SUBSCRIBERS.lock() {
+        Ok(subscribers) => {
+            for (name, callback) in subscribers.iter() {
+                log::debug!("[settings_events] notifying {}", name);
+                callback(&change);
+            }
+        }
+        Err(e) => log::warn!("[settings_events] Failed to acquire subscriber lock: {}", e),
+    }
+    let guard = match EMITTER.lock() {
+        Ok(g) => g,
+        Err(e) => {
+            log::warn!("[settings_events] Failed to acquire emitter lock: {}", e);
+            return;
+        }
+    };
+    if let Some(app) = guard.as_ref() {
+        if let Err(e) = app.emit(SETTINGS_CHANGED_EVENT, &change) {
+            log::warn!("[settings_events] Failed to emit {:?}: {}", change, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_changed_fields_lists_only_differing_fields() {
+        let old = AppSettings::default();
+        let mut new = old.clone();
+        assert!(changed_fields(&old, &new).is_empty());
+
+        new.skills_path = Some("/tmp/skills".into());
+        new.workflow_queue_concurrency = old.workflow_queue_concurrency + 1;
+        let mut changed = changed_fields(&old, &new);
+        changed.sort();
+        assert_eq!(changed, vec!["skills_path", "workflow_queue_concurrency"]);
+    }
+
+    #[test]
+    fn test_publish_notifies_subscribers_of_changes_only() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let seen = calls.clone();
+        subscribe("test", move |change| {
+            if change.touches("preferred_model") {
+                seen.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        let old = AppSettings::default();
+        publish(&old, &old.clone());
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        let mut new = old.clone();
+        new.preferred_model = Some("opus".into());
+        publish(&old, &new);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,176 @@
+//! Portable `disk_path` values for library and workspace skills.
+//!
+//! A skills library synced between machines (Dropbox between macOS and
+//! Windows, say) sits at a different absolute path on each, so the DB stores
+//! `disk_path` relative to its root — `skills_path` for `imported_skills`,
+//! `<workspace>/.claude/skills` for `workspace_skills` — with `/` separators.
+//! Paths outside the root stay absolute. On read, relative paths are joined to
+//! the current root, absolute paths written on another machine fall back to
+//! the same skill folder under the current root, and a component whose case
+//! differs from the folder on disk is matched case-insensitively.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::Connection;
+
+/// Folder that deactivated workspace skills are moved into.
+const INACTIVE_DIR: &str = ".inactive";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkillRoot {
+    /// `skills_path` — rows in `imported_skills`.
+    Library,
+    /// `<workspace>/.claude/skills` — rows in `workspace_skills`.
+    Workspace,
+}
+
+/// Current root directory for `root`, from settings. `None` when not configured.
+pub fn root_dir(conn: &Connection, root: SkillRoot) -> Option<PathBuf> {
+    let settings = crate::db::read_settings(conn).ok()?;
+    match root {
+        SkillRoot::Library => settings.skills_path.map(PathBuf::from),
+        SkillRoot::Workspace => settings
+            .workspace_path
+            .map(|w| Path::new(&w).join(".claude").join("skills")),
+    }
+}
+
+fn components(path: &str) -> Vec<&str> {
+    path.split(['/', '\\'])
+        .filter(|c| !c.is_empty() && *c != ".")
+        .collect()
+}
+
+/// Absolute on any OS: `/…`, `\\server\…` or a drive letter.
+fn is_absolute(path: &str) -> bool {
+    path.starts_with('/') || path.starts_with('\\') || path.as_bytes().get(1) == Some(&b':')
+}
+
+/// Path components compare case-insensitively where the file system does.
+fn same_component(a: &str, b: &str) -> bool {
+    a == b || (cfg!(any(windows, target_os = "macos")) && a.eq_ignore_ascii_case(b))
+}
+
+/// Value to store for `disk_path`: relative to `root` when inside it,
+/// otherwise unchanged.
+pub fn to_stored(root: Option<&Path>, disk_path: &str) -> String {
+    let Some(root) = root else {
+        return disk_path.to_string();
+    };
+    if !is_absolute(disk_path) {
+        return disk_path.to_string();
+    }
+    let root = root.to_string_lossy();
+    let root_parts = components(&root);
+    let parts = components(disk_path);
+    let inside = parts.len() > root_parts.len()
+        && root_parts.iter().zip(&parts).all(|(a, b)| same_component(a, b));
+    if inside {
+        parts[root_parts.len()..].join("/")
+    } else {
+        disk_path.to_string()
+    }
+}
+
+/// `root` joined with `parts`, falling back to a case-insensitive match for
+/// any component not found as written. `None` when a component is missing.
+fn find_on_disk(root: &Path, parts: &[&str]) -> Option<PathBuf> {
+    let mut path = root.to_path_buf();
+    for part in parts {
+        let exact = path.join(part);
+        if exact.exists() {
+            path = exact;
+            continue;
+        }
+        let entry = std::fs::read_dir(&path)
+            .ok()?
+            .flatten()
+            .find(|e| e.file_name().to_string_lossy().eq_ignore_ascii_case(part))?;
+        path = entry.path();
+    }
+    Some(path)
+}
+
+/// Absolute `disk_path` for a stored value.
+pub fn resolve(root: Option<&Path>, stored: &str) -> String {
+    if is_absolute(stored) {
+        if Path::new(stored).exists() {
+            return stored.to_string();
+        }
+        // Written on another machine: keep the skill folder, swap the root.
+        let parts = components(stored);
+        let tail = match parts.as_slice() {
+            [.., dir, name] if *dir == INACTIVE_DIR => vec![INACTIVE_DIR, *name],
+            [.., name] => vec![*name],
+            [] => return stored.to_string(),
+        };
+        return root
+            .and_then(|r| find_on_disk(r, &tail))
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| stored.to_string());
+    }
+    let Some(root) = root else {
+        return stored.to_string();
+    };
+    let parts = components(stored);
+    let joined = parts.iter().fold(root.to_path_buf(), |p, c| p.join(c));
+    if joined.exists() {
+        return joined.to_string_lossy().to_string();
+    }
+    find_on_disk(root, &parts)
+        .unwrap_or(joined)
+        .to_string_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_to_stored_is_relative_inside_root_only() {
+        let root = Path::new("/home/me/Dropbox/skills");
+        assert_eq!(to_stored(Some(root), "/home/me/Dropbox/skills/sales"), "sales");
+        assert_eq!(
+            to_stored(Some(root), "/home/me/Dropbox/skills/.inactive/sales"),
+            ".inactive/sales"
+        );
+        assert_eq!(to_stored(Some(root), "/opt/bundled/sales"), "/opt/bundled/sales");
+        assert_eq!(to_stored(Some(root), "/home/me/Dropbox/skills"), "/home/me/Dropbox/skills");
+        assert_eq!(to_stored(Some(root), "sales"), "sales");
+        assert_eq!(to_stored(None, "/home/me/Dropbox/skills/sales"), "/home/me/Dropbox/skills/sales");
+    }
+
+    #[test]
+    fn test_resolve_handles_relative_foreign_and_case_mismatch() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("Sales-Pipeline")).unwrap();
+        std::fs::create_dir_all(root.join(".inactive/hr")).unwrap();
+
+        // Relative paths join to the current root, matching case on disk.
+        assert_eq!(
+            resolve(Some(root), "sales-pipeline"),
+            root.join("Sales-Pipeline").to_string_lossy()
+        );
+        assert_eq!(resolve(Some(root), ".inactive/hr"), root.join(".inactive/hr").to_string_lossy());
+        assert_eq!(resolve(Some(root), "missing"), root.join("missing").to_string_lossy());
+
+        // Absolute paths from another OS fall back to the skill folder under the root.
+        assert_eq!(
+            resolve(Some(root), "C:\\Users\\me\\Dropbox\\skills\\.inactive\\hr"),
+            root.join(".inactive/hr").to_string_lossy()
+        );
+        assert_eq!(
+            resolve(Some(root), "/Users/me/Dropbox/skills/Sales-Pipeline"),
+            root.join("Sales-Pipeline").to_string_lossy()
+        );
+        assert_eq!(resolve(Some(root), "/elsewhere/unknown"), "/elsewhere/unknown");
+        assert_eq!(resolve(None, "sales"), "sales");
+
+        // Round trip through the stored form.
+        let abs = root.join("Sales-Pipeline").to_string_lossy().to_string();
+        assert_eq!(resolve(Some(root), &to_stored(Some(root), &abs)), abs);
+    }
+}
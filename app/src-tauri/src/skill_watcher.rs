@@ -0,0 +1,305 @@
+//! Picks up skill edits made outside the app.
+//!
+//! A notify watcher on `skills_path` collects events for each skill folder
+//! and, after `DEBOUNCE` without further events, brings the library rows in
+//! line with disk:
+//! - SKILL.md changed: frontmatter fields are copied to `skills` and
+//!   `imported_skills`.
+//! - Folder or SKILL.md gone: marketplace and imported skills leave the
+//!   library, as in startup reconciliation. Skill-builder skills keep their
+//!   rows and are reported as missing; reconciliation settles their steps on
+//!   the next start.
+//! - One known folder gone and one unknown folder with a SKILL.md appearing
+//!   in the same batch is a rename: the rows follow the folder.
+//!
+//! Other unknown folders are left to startup discovery. Each batch that
+//! changed something is sent to the frontend as `SKILLS_CHANGED_EVENT`. The
+//! watcher moves when `skills_path` changes in settings.
+
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::db::Db;
+
+pub const SKILLS_CHANGED_EVENT: &str = "skills-changed";
+
+/// Quiet time after the last event before a batch is applied; lets editors
+/// finish their write-and-rename saves and copies finish landing.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SkillsChanged {
+    /// Skills whose rows took new frontmatter values.
+    pub updated: Vec<String>,
+    /// Skills removed from the library because their folder is gone.
+    pub removed: Vec<String>,
+    /// `(old, new)` names of skills whose folder was renamed.
+    pub renamed: Vec<(String, String)>,
+    /// Skill-builder skills whose SKILL.md is gone; their rows are kept.
+    pub missing: Vec<String>,
+}
+
+impl SkillsChanged {
+    fn is_empty(&self) -> bool {
+        self.updated.is_empty() && self.removed.is_empty() && self.renamed.is_empty() && self.missing.is_empty()
+    }
+}
+
+enum Message {
+    Fs(notify::Result<notify::Event>),
+    /// `skills_path` changed in settings.
+    Rewatch,
+}
+
+/// Start watching. Called once from `setup()`.
+pub fn start(app: tauri::AppHandle) {
+    let (tx, rx) = mpsc::channel();
+    let rewatch = tx.clone();
+    crate::settings_events::subscribe("skill_watcher", move |change| {
+        if change.touches("skills_path") {
+            let _ = rewatch.send(Message::Rewatch);
+        }
+    });
+    let spawned = std::thread::Builder::new()
+        .name("skill-watcher".to_string())
+        .spawn(move || run(app, tx, rx));
+    if let Err(e) = spawned {
+        log::warn!("[skill_watcher] Failed to start: {}", e);
+    }
+}
+
+struct Watched {
+    root: PathBuf,
+    /// `root` as the OS reports it in events (symlinks resolved).
+    canonical_root: PathBuf,
+    _watcher: RecommendedWatcher,
+}
+
+fn watch(app: &tauri::AppHandle, tx: &mpsc::Sender<Message>) -> Option<Watched> {
+    let skills_path = {
+        let db = app.state::<Db>();
+        let conn = db.0.lock().ok()?;
+        crate::db::read_settings(&conn).ok()?.skills_path?
+    };
+    let root = PathBuf::from(skills_path);
+    if !root.is_dir() {
+        log::info!("[skill_watcher] {} does not exist; not watching", root.display());
+        return None;
+    }
+    let tx = tx.clone();
+    let result = notify::recommended_watcher(move |event| {
+        let _ = tx.send(Message::Fs(event));
+    })
+    .and_then(|mut watcher| watcher.watch(&root, RecursiveMode::Recursive).map(|()| watcher));
+    match result {
+        Ok(watcher) => {
+            log::info!("[skill_watcher] watching {}", root.display());
+            Some(Watched {
+                canonical_root: std::fs::canonicalize(&root).unwrap_or_else(|_| root.clone()),
+                root,
+                _watcher: watcher,
+            })
+        }
+        Err(e) => {
+            log::warn!("[skill_watcher] Failed to watch {}: {}", root.display(), e);
+            None
+        }
+    }
+}
+
+fn run(app: tauri::AppHandle, tx: mpsc::Sender<Message>, rx: mpsc::Receiver<Message>) {
+    let mut watched = watch(&app, &tx);
+    let mut pending: BTreeSet<String> = BTreeSet::new();
+    let mut deadline: Option<Instant> = None;
+    loop {
+        let message = match deadline {
+            Some(deadline) => rx.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+            None => rx.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+        };
+        match message {
+            Ok(Message::Fs(Ok(event))) => {
+                let Some(w) = watched.as_ref() else { continue };
+                if matches!(event.kind, EventKind::Access(_)) {
+                    continue;
+                }
+                let names: Vec<String> = event
+                    .paths
+                    .iter()
+                    .filter_map(|p| skill_name_for(&w.canonical_root, p).or_else(|| skill_name_for(&w.root, p)))
+                    .collect();
+                // Only skill events push the batch back; `.git` churn doesn't.
+                if !names.is_empty() {
+                    pending.extend(names);
+                    deadline = Some(Instant::now() + DEBOUNCE);
+                }
+            }
+            Ok(Message::Fs(Err(e))) => log::warn!("[skill_watcher] watch error: {}", e),
+            Ok(Message::Rewatch) => {
+                pending.clear();
+                deadline = None;
+                watched = watch(&app, &tx);
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                deadline = None;
+                let names: Vec<String> = std::mem::take(&mut pending).into_iter().collect();
+                if let Some(w) = watched.as_ref() {
+                    apply(&app, &w.root, &names);
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+/// The skill a path under `root` belongs to, if it is a skill folder itself
+/// or its SKILL.md. Dot folders (`.git`, ...) and other files are ignored.
+fn skill_name_for(root: &Path, path: &Path) -> Option<String> {
+    let rel = path.strip_prefix(root).ok()?;
+    let mut components = rel.components();
+    let Some(Component::Normal(name)) = components.next() else {
+        return None;
+    };
+    let name = name.to_str()?;
+    if name.starts_with('.') || crate::commands::imported_skills::validate_skill_name(name).is_err() {
+        return None;
+    }
+    match components.next() {
+        None => Some(name.to_string()),
+        Some(Component::Normal(file)) if file == "SKILL.md" && components.next().is_none() => Some(name.to_string()),
+        _ => None,
+    }
+}
+
+fn apply(app: &tauri::AppHandle, skills_path: &Path, names: &[String]) {
+    let db = app.state::<Db>();
+    let result = db.0.lock().map_err(|e| e.to_string()).and_then(|mut conn| {
+        let workspace_path = crate::db::read_settings(&conn)?
+            .workspace_path
+            .ok_or_else(|| "Workspace path not configured".to_string())?;
+        sync_skills(&mut conn, skills_path, &workspace_path, names)
+    });
+    match result {
+        Ok(change) if change.is_empty() => {}
+        Ok(change) => {
+            log::info!("[skill_watcher] {:?}", change);
+            if let Err(e) = app.emit(SKILLS_CHANGED_EVENT, &change) {
+                log::warn!("[skill_watcher] Failed to emit {}: {}", SKILLS_CHANGED_EVENT, e);
+            }
+        }
+        Err(e) => log::warn!("[skill_watcher] Failed to sync {:?}: {}", names, e),
+    }
+}
+
+/// Bring the rows of `names` in line with their folders under `skills_path`.
+fn sync_skills(
+    conn: &mut rusqlite::Connection,
+    skills_path: &Path,
+    workspace_path: &str,
+    names: &[String],
+) -> Result<SkillsChanged, String> {
+    let mut change = SkillsChanged::default();
+    let mut gone: Vec<(String, String)> = Vec::new();
+    let mut appeared: Vec<(String, String)> = Vec::new();
+    for name in names {
+        let skill_md = std::fs::read_to_string(skills_path.join(name).join("SKILL.md"));
+        match (crate::db::get_live_skill_source(conn, name)?, skill_md) {
+            (Some(_), Ok(content)) => {
+                if sync_frontmatter(conn, name, &content)? {
+                    change.updated.push(name.clone());
+                }
+            }
+            (Some(source), Err(_)) => gone.push((name.clone(), source)),
+            (None, Ok(content)) => appeared.push((name.clone(), content)),
+            (None, Err(_)) => {}
+        }
+    }
+
+    if let ([(old, _)], [(new, content)]) = (gone.as_slice(), appeared.as_slice()) {
+        if !skills_path.join(old).exists() && crate::commands::skill::is_valid_kebab(new) {
+            crate::commands::skill::rename_skill_inner(old, new, workspace_path, conn, None)?;
+            crate::commands::deployment_slots::rename_slots(skills_path, old, new);
+            sync_frontmatter(conn, new, content)?;
+            change.renamed.push((old.clone(), new.clone()));
+            return Ok(change);
+        }
+    }
+
+    for (name, source) in gone {
+        match source.as_str() {
+            "marketplace" | "imported" => {
+                crate::db::delete_imported_skill_by_name(conn, &name)?;
+                crate::db::delete_skill(conn, &name)?;
+                crate::db_events::emit(crate::db_events::DbChange::SkillDeleted { skill_name: name.clone() });
+                change.removed.push(name);
+            }
+            _ => change.missing.push(name),
+        }
+    }
+    Ok(change)
+}
+
+fn sync_frontmatter(conn: &rusqlite::Connection, name: &str, skill_md: &str) -> Result<bool, String> {
+    let fm = crate::commands::imported_skills::parse_frontmatter_full(skill_md);
+    let changed = crate::db::sync_skill_frontmatter(conn, name, &fm)?;
+    crate::commands::skill_review::sync_review_from_frontmatter(conn, name, skill_md);
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_skill(root: &Path, name: &str, version: &str) {
+        std::fs::create_dir_all(root.join(name)).unwrap();
+        std::fs::write(
+            root.join(name).join("SKILL.md"),
+            format!("---\nname: {}\ndescription: Test\nversion: {}\n---\n# Body\n", name, version),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_skill_name_for_only_matches_skill_folders_and_skill_md() {
+        let root = Path::new("/skills");
+        assert_eq!(skill_name_for(root, Path::new("/skills/standup")).as_deref(), Some("standup"));
+        assert_eq!(skill_name_for(root, Path::new("/skills/standup/SKILL.md")).as_deref(), Some("standup"));
+        assert_eq!(skill_name_for(root, Path::new("/skills/standup/references/a.md")), None);
+        assert_eq!(skill_name_for(root, Path::new("/skills/.git/index")), None);
+        assert_eq!(skill_name_for(root, Path::new("/elsewhere/standup")), None);
+    }
+
+    #[test]
+    fn test_sync_skills_updates_removes_and_renames() {
+        let tmp = tempfile::tempdir().unwrap();
+        let workspace = tempfile::tempdir().unwrap();
+        let workspace = workspace.path().to_str().unwrap();
+        let mut conn = crate::commands::test_utils::create_test_db();
+        for (name, source) in [("edited", "imported"), ("deleted", "marketplace"), ("built", "skill-builder")] {
+            crate::db::upsert_skill(&conn, name, source, "domain").unwrap();
+        }
+        write_skill(tmp.path(), "edited", "2.0.0");
+        write_skill(tmp.path(), "built", "1.0.0");
+
+        let names: Vec<String> = ["edited", "deleted", "built"].iter().map(|s| s.to_string()).collect();
+        let change = sync_skills(&mut conn, tmp.path(), workspace, &names).unwrap();
+        assert_eq!(change.updated, vec!["edited", "built"]);
+        assert_eq!(change.removed, vec!["deleted"]);
+        assert!(crate::db::get_live_skill_source(&conn, "deleted").unwrap().is_none());
+
+        // A second pass finds nothing new.
+        let change = sync_skills(&mut conn, tmp.path(), workspace, &names[..1]).unwrap();
+        assert!(change.is_empty());
+
+        std::fs::rename(tmp.path().join("edited"), tmp.path().join("renamed")).unwrap();
+        let names: Vec<String> = vec!["edited".into(), "renamed".into()];
+        let change = sync_skills(&mut conn, tmp.path(), workspace, &names).unwrap();
+        assert_eq!(change.renamed, vec![("edited".to_string(), "renamed".to_string())]);
+        assert_eq!(crate::db::get_live_skill_source(&conn, "renamed").unwrap().as_deref(), Some("imported"));
+    }
+}
@@ -0,0 +1,200 @@
+//! Menu bar / system tray status.
+//!
+//! A tray icon whose menu shows lightweight library stats — agents running
+//! now, today's spend, branch-per-skill pushes pending review upstream and
+//! skills past their review-by date — refreshed every `REFRESH_SECS` so
+//! activity can be checked without opening the main window. "Pause all
+//! agents" cancels every in-flight agent request and holds the workflow
+//! queue until "Resume agents" is chosen.
+
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Manager, Wry};
+
+use crate::agents::sidecar_pool::SidecarPool;
+use crate::commands::workflow_queue::WorkflowQueue;
+use crate::db::Db;
+
+const REFRESH_SECS: u64 = 30;
+
+const TRAY_ID: &str = "status";
+const MENU_OPEN: &str = "status-open";
+const MENU_PAUSE: &str = "status-pause";
+
+/// Stats shown in the tray menu.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct QuickStats {
+    pub running_agents: usize,
+    pub today_spend_usd: f64,
+    pub pending_reviews: usize,
+    pub stale_skills: usize,
+}
+
+impl QuickStats {
+    fn lines(&self) -> [String; 4] {
+        [
+            match self.running_agents {
+                0 => "No agents running".to_string(),
+                1 => "1 agent running".to_string(),
+                n => format!("{} agents running", n),
+            },
+            format!("Today's spend: ${:.2}", self.today_spend_usd),
+            format!("Pending upstream reviews: {}", self.pending_reviews),
+            format!("Skills past review date: {}", self.stale_skills),
+        ]
+    }
+
+    fn tooltip(&self) -> String {
+        format!(
+            "Skill Builder — {} running, ${:.2} today",
+            self.running_agents, self.today_spend_usd
+        )
+    }
+}
+
+/// Database-backed stats; the caller supplies the live agent count.
+pub(crate) fn compute_stats(conn: &rusqlite::Connection, running_agents: usize) -> Result<QuickStats, String> {
+    let pending_reviews = crate::db::list_upstream_reviews(conn)?
+        .iter()
+        .filter(|r| r.state == crate::git_hosting::REVIEW_OPEN)
+        .count();
+    let stale_skills = crate::commands::skill_review::review_report(conn)?
+        .iter()
+        .filter(|r| r.state == crate::commands::skill_review::REVIEW_EXPIRED)
+        .count();
+    Ok(QuickStats {
+        running_agents,
+        today_spend_usd: crate::db::spend_today(conn)?,
+        pending_reviews,
+        stale_skills,
+    })
+}
+
+async fn current_stats(app: &AppHandle) -> Result<QuickStats, String> {
+    let running = app.state::<SidecarPool>().running_count().await;
+    let db = app.state::<Db>();
+    let conn = db.0.lock().map_err(|e| e.to_string())?;
+    compute_stats(&conn, running)
+}
+
+fn pause_label(paused: bool) -> &'static str {
+    if paused {
+        "Resume agents"
+    } else {
+        "Pause all agents"
+    }
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Pause: cancel in-flight agents and hold the workflow queue. Resume: release it.
+fn toggle_pause(app: &AppHandle, pause_item: &MenuItem<Wry>) {
+    let queue = app.state::<WorkflowQueue>();
+    let pause = !queue.is_paused();
+    queue.set_paused(pause);
+    let _ = pause_item.set_text(pause_label(pause));
+    if !pause {
+        log::info!("[status_tray] agents resumed");
+        return;
+    }
+    let pool = app.state::<SidecarPool>().inner().clone();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let message = serde_json::json!({
+            "type": "error",
+            "subtype": "paused",
+            "error": "Stopped: all agents were paused from the menu bar",
+        });
+        let cancelled = pool.cancel_all_requests(message, &app).await;
+        log::info!("[status_tray] agents paused; cancelled {} running request(s)", cancelled);
+    });
+}
+
+/// Build the tray icon and start the refresh loop. GUI runs only.
+pub fn start(app: &AppHandle) -> tauri::Result<()> {
+    let stats = QuickStats::default();
+    let stat_items = stats
+        .lines()
+        .iter()
+        .enumerate()
+        .map(|(i, text)| MenuItem::with_id(app, format!("status-stat-{}", i), text, false, None::<&str>))
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let open = MenuItem::with_id(app, MENU_OPEN, "Open Skill Builder", true, None::<&str>)?;
+    let pause = MenuItem::with_id(app, MENU_PAUSE, pause_label(false), true, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+
+    let menu = Menu::new(app)?;
+    for item in &stat_items {
+        menu.append(item)?;
+    }
+    menu.append_items(&[&separator, &open, &pause])?;
+
+    let pause_item = pause.clone();
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&menu)
+        .tooltip(stats.tooltip())
+        .on_menu_event(move |app: &AppHandle, event: MenuEvent| match event.id().as_ref() {
+            MENU_OPEN => show_main_window(app),
+            MENU_PAUSE => toggle_pause(app, &pause_item),
+            _ => {}
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    let tray = builder.build(app)?;
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut last = None;
+        loop {
+            match current_stats(&app).await {
+                Ok(stats) if last.as_ref() != Some(&stats) => {
+                    for (item, text) in stat_items.iter().zip(stats.lines()) {
+                        let _ = item.set_text(text);
+                    }
+                    let _ = tray.set_tooltip(Some(stats.tooltip()));
+                    last = Some(stats);
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("[status_tray] Failed to compute stats: {}", e),
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(REFRESH_SECS)).await;
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_stats_counts_spend_reviews_and_stale_skills() {
+        let conn = crate::commands::test_utils::create_test_db();
+        conn.execute_batch(
+            "INSERT INTO agent_runs (agent_id, skill_name, step_id, model, total_cost, started_at)
+             VALUES ('a1', 'standup', 0, 'sonnet', 0.25, datetime('now')),
+                    ('a2', 'standup', 1, 'sonnet', 0.50, datetime('now') || 'Z'),
+                    ('a3', 'standup', 2, 'sonnet', 9.00, '2020-01-01 10:00:00');
+             INSERT INTO skills (name, skill_source, review_by) VALUES ('standup', 'skill-builder', '2020-01-01');
+             INSERT INTO skill_upstream_reviews
+                (skill_name, host, repo, repo_url, branch, base_branch, number, url, state, pushed_at, updated_at)
+             VALUES ('standup', 'github', 'acme/skills', 'acme/skills', 'skill/standup', 'main', 1, 'u', 'open', 'x', 'x'),
+                    ('retro', 'github', 'acme/skills', 'acme/skills', 'skill/retro', 'main', 2, 'u', 'merged', 'x', 'x');",
+        )
+        .unwrap();
+
+        let stats = compute_stats(&conn, 2).unwrap();
+        assert_eq!(stats.running_agents, 2);
+        assert!((stats.today_spend_usd - 0.75).abs() < 1e-9);
+        assert_eq!(stats.pending_reviews, 1);
+        assert_eq!(stats.stale_skills, 1);
+        assert_eq!(stats.lines()[0], "2 agents running");
+    }
+}
@@ -0,0 +1,510 @@
+//! Compressed storage for agent JSONL transcripts.
+//!
+//! Transcripts are written as plain `{label}-{timestamp}.jsonl` while an agent
+//! runs. Once the run finishes the file is rewritten as
+//! `{label}-{timestamp}.jsonl.zst` plus a `{label}-{timestamp}.jsonl.idx` index.
+//! Each turn is compressed as its own zstd frame, so the `.zst` file still
+//! decompresses as one stream while the index records where every turn's
+//! frame starts. Replay can decompress just the turns it shows.
+//!
+//! A turn is one assistant message (identified by `message.id`) together with
+//! the tool results and system messages that follow it. Lines before the first
+//! assistant message (the config line, SDK init) form turn 0.
+//!
+//! Finished transcripts are also archived in the `agent_transcripts` table,
+//! keyed by agent id, so a run can be read back after its log files are
+//! pruned. Each archive is one zstd blob of the run's messages, with thinking
+//! blocks cut down to a summary.
+
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+pub const TRANSCRIPT_EXT: &str = ".jsonl";
+pub const COMPRESSED_EXT: &str = ".jsonl.zst";
+pub const INDEX_EXT: &str = ".jsonl.idx";
+
+const INDEX_VERSION: u32 = 1;
+const ZSTD_LEVEL: i32 = 9;
+
+/// Plain transcripts idle for less than this are assumed to still be written
+/// to (possibly by another app instance) and are left alone by
+/// `compress_existing_transcripts`.
+const MIN_IDLE_SECS: u64 = 10 * 60;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TurnOffset {
+    /// Byte offset of the turn's zstd frame in the `.zst` file.
+    pub offset: u64,
+    pub compressed_len: u64,
+    pub lines: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptIndex {
+    pub version: u32,
+    pub uncompressed_bytes: u64,
+    pub turns: Vec<TurnOffset>,
+}
+
+/// Whether `file_name` is a transcript, compressed or not.
+pub fn is_transcript(file_name: &str) -> bool {
+    file_name.ends_with(TRANSCRIPT_EXT) || file_name.ends_with(COMPRESSED_EXT)
+}
+
+/// `{label}-{timestamp}` part of a transcript file name.
+pub fn transcript_stem(file_name: &str) -> &str {
+    file_name
+        .strip_suffix(COMPRESSED_EXT)
+        .or_else(|| file_name.strip_suffix(TRANSCRIPT_EXT))
+        .unwrap_or(file_name)
+}
+
+fn index_path(compressed: &Path) -> PathBuf {
+    let name = compressed.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    compressed.with_file_name(format!("{}{}", transcript_stem(name), INDEX_EXT))
+}
+
+fn is_compressed(path: &Path) -> bool {
+    path.to_str().is_some_and(|p| p.ends_with(COMPRESSED_EXT))
+}
+
+/// Group transcript lines into turns (see the module docs).
+fn split_turns(lines: Vec<String>) -> Vec<Vec<String>> {
+    let mut turns: Vec<Vec<String>> = vec![Vec::new()];
+    let mut current_message: Option<String> = None;
+    for line in lines {
+        let msg: Option<serde_json::Value> = serde_json::from_str(&line).ok();
+        let assistant_id = msg
+            .as_ref()
+            .filter(|m| m.get("type").and_then(|t| t.as_str()) == Some("assistant"))
+            .map(|m| {
+                m.pointer("/message/id")
+                    .and_then(|id| id.as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            });
+        if let Some(id) = assistant_id {
+            let same_message = !id.is_empty() && current_message.as_deref() == Some(id.as_str());
+            if !same_message {
+                turns.push(Vec::new());
+                current_message = Some(id);
+            }
+        }
+        turns.last_mut().expect("turns is never empty").push(line);
+    }
+    turns.retain(|t| !t.is_empty());
+    turns
+}
+
+fn read_lines<R: BufRead>(reader: R) -> Result<Vec<String>, String> {
+    reader
+        .lines()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read transcript: {}", e))
+}
+
+/// Open a transcript for sequential reading, decompressing `.jsonl.zst` files.
+/// A plain transcript that was compressed after it was listed is read from its
+/// `.jsonl.zst` replacement.
+pub fn open_transcript(path: &Path) -> Result<Box<dyn BufRead>, String> {
+    let (path, file) = match std::fs::File::open(path) {
+        Ok(file) => (path.to_path_buf(), file),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound && !is_compressed(path) => {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            let compressed = path.with_file_name(format!("{}{}", transcript_stem(name), COMPRESSED_EXT));
+            let file = std::fs::File::open(&compressed)
+                .map_err(|_| format!("Failed to open {}: {}", path.display(), e))?;
+            (compressed, file)
+        }
+        Err(e) => return Err(format!("Failed to open {}: {}", path.display(), e)),
+    };
+    if is_compressed(&path) {
+        let decoder = zstd::stream::read::Decoder::new(file)
+            .map_err(|e| format!("Failed to decompress {}: {}", path.display(), e))?;
+        Ok(Box::new(BufReader::new(decoder)))
+    } else {
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
+pub fn read_index(compressed: &Path) -> Result<TranscriptIndex, String> {
+    let path = index_path(compressed);
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid transcript index {}: {}", path.display(), e))
+}
+
+/// Compress `path` (a plain `.jsonl` transcript) into `.jsonl.zst` + index and
+/// remove the original. The compressed file keeps the original's mtime so
+/// date-based pruning is unaffected. Returns the compressed path.
+pub fn compress_transcript(path: &Path) -> Result<PathBuf, String> {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .filter(|n| n.ends_with(TRANSCRIPT_EXT))
+        .ok_or_else(|| format!("Not a plain transcript: {}", path.display()))?;
+    let stem = transcript_stem(name).to_string();
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+
+    let file = std::fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let turns = split_turns(read_lines(BufReader::new(file))?);
+
+    let compressed_path = path.with_file_name(format!("{}{}", stem, COMPRESSED_EXT));
+    let tmp_path = path.with_file_name(format!("{}{}.tmp", stem, COMPRESSED_EXT));
+    let mut out = std::fs::File::create(&tmp_path)
+        .map_err(|e| format!("Failed to create {}: {}", tmp_path.display(), e))?;
+    let mut index = TranscriptIndex { version: INDEX_VERSION, uncompressed_bytes: 0, turns: Vec::with_capacity(turns.len()) };
+    let mut offset = 0u64;
+    for turn in &turns {
+        let mut raw = turn.join("\n");
+        raw.push('\n');
+        let frame = zstd::bulk::compress(raw.as_bytes(), ZSTD_LEVEL)
+            .map_err(|e| format!("Failed to compress {}: {}", path.display(), e))?;
+        out.write_all(&frame).map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
+        index.turns.push(TurnOffset { offset, compressed_len: frame.len() as u64, lines: turn.len() as u32 });
+        index.uncompressed_bytes += raw.len() as u64;
+        offset += frame.len() as u64;
+    }
+    if let Some(t) = modified {
+        let _ = out.set_modified(t);
+    }
+    drop(out);
+
+    // Index first: a `.zst` that exists is always complete and indexed.
+    let index_json = serde_json::to_string(&index).map_err(|e| e.to_string())?;
+    std::fs::write(index_path(&compressed_path), index_json)
+        .map_err(|e| format!("Failed to write transcript index for {}: {}", name, e))?;
+    std::fs::rename(&tmp_path, &compressed_path)
+        .map_err(|e| format!("Failed to rename {}: {}", tmp_path.display(), e))?;
+    std::fs::remove_file(path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+    Ok(compressed_path)
+}
+
+/// Turns `[start, start + limit)` of a transcript as raw JSONL lines, plus the
+/// total turn count. Compressed transcripts decompress only the requested
+/// frames; plain (in-progress) ones are read in full.
+pub fn read_turns(path: &Path, start: usize, limit: usize) -> Result<(usize, Vec<Vec<String>>), String> {
+    if !is_compressed(path) {
+        let turns = split_turns(read_lines(open_transcript(path)?)?);
+        let total = turns.len();
+        return Ok((total, turns.into_iter().skip(start).take(limit).collect()));
+    }
+
+    let index = read_index(path)?;
+    let mut file = std::fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut turns = Vec::new();
+    for turn in index.turns.iter().skip(start).take(limit) {
+        let mut frame = vec![0u8; turn.compressed_len as usize];
+        file.seek(SeekFrom::Start(turn.offset))
+            .and_then(|_| file.read_exact(&mut frame))
+            .map_err(|e| format!("Failed to read turn from {}: {}", path.display(), e))?;
+        let raw = zstd::stream::decode_all(frame.as_slice())
+            .map_err(|e| format!("Failed to decompress turn from {}: {}", path.display(), e))?;
+        turns.push(read_lines(raw.as_slice())?);
+    }
+    Ok((index.turns.len(), turns))
+}
+
+/// `{skill}/logs` directories under the workspace, skipping `.claude`.
+pub fn skill_log_dirs(workspace_path: &str) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(workspace_path) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| e.file_name() != ".claude")
+        .map(|e| e.path().join("logs"))
+        .filter(|p| p.is_dir())
+        .collect()
+}
+
+/// Compress plain transcripts left from earlier versions or interrupted runs.
+/// Runs at startup; files modified in the last few minutes are skipped in case
+/// another instance is still writing them. Errors are logged and skipped.
+pub fn compress_existing_transcripts(workspace_path: &str) {
+    let now = std::time::SystemTime::now();
+    let mut compressed = 0u32;
+    let mut before = 0u64;
+    let mut after = 0u64;
+    for logs_dir in skill_log_dirs(workspace_path) {
+        let Ok(entries) = std::fs::read_dir(&logs_dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.to_str().is_some_and(|p| p.ends_with(TRANSCRIPT_EXT)) {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            let idle = meta
+                .modified()
+                .ok()
+                .and_then(|m| now.duration_since(m).ok())
+                .is_some_and(|d| d.as_secs() >= MIN_IDLE_SECS);
+            if !idle {
+                continue;
+            }
+            match compress_transcript(&path) {
+                Ok(out) => {
+                    compressed += 1;
+                    before += meta.len();
+                    after += std::fs::metadata(&out).map(|m| m.len()).unwrap_or(0);
+                }
+                Err(e) => log::warn!("Transcript compression: {}", e),
+            }
+        }
+    }
+    if compressed > 0 {
+        log::info!(
+            "Compressed {} transcripts ({} KB -> {} KB)",
+            compressed,
+            before / 1024,
+            after / 1024
+        );
+    }
+}
+
+/// Characters of a thinking block kept in the archive.
+const THINKING_SUMMARY_CHARS: usize = 400;
+/// Characters of context on each side of a search match.
+const SNIPPET_CONTEXT_CHARS: usize = 60;
+
+/// A transcript packed for the `agent_transcripts` table.
+pub struct ArchivedTranscript {
+    pub content: Vec<u8>,
+    pub uncompressed_bytes: u64,
+    pub message_count: usize,
+    pub tool_call_count: usize,
+}
+
+/// Keep the start of each thinking block and drop signatures and redacted
+/// thinking data, which are large and unreadable.
+fn summarize_thinking(message: &mut serde_json::Value) {
+    let Some(blocks) = message.pointer_mut("/message/content").and_then(|c| c.as_array_mut()) else {
+        return;
+    };
+    for block in blocks {
+        let Some(obj) = block.as_object_mut() else {
+            continue;
+        };
+        match obj.get("type").and_then(|t| t.as_str()) {
+            Some("thinking") => {
+                obj.remove("signature");
+                if let Some(text) = obj.get("thinking").and_then(|t| t.as_str()) {
+                    if text.chars().count() > THINKING_SUMMARY_CHARS {
+                        let summary: String = text.chars().take(THINKING_SUMMARY_CHARS).collect();
+                        obj.insert("thinking".to_string(), serde_json::Value::String(format!("{}…", summary)));
+                    }
+                }
+            }
+            Some("redacted_thinking") => {
+                obj.remove("data");
+            }
+            _ => {}
+        }
+    }
+}
+
+fn count_tool_calls(message: &serde_json::Value) -> usize {
+    if message.get("type").and_then(|t| t.as_str()) != Some("assistant") {
+        return 0;
+    }
+    message
+        .pointer("/message/content")
+        .and_then(|c| c.as_array())
+        .map_or(0, |blocks| {
+            blocks
+                .iter()
+                .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("tool_use"))
+                .count()
+        })
+}
+
+/// Pack the lines of a finished transcript for archiving. Lines that are not
+/// JSON are kept as strings.
+pub fn pack_for_archive(lines: &[String]) -> Result<ArchivedTranscript, String> {
+    let mut tool_call_count = 0;
+    let messages: Vec<serde_json::Value> = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut message =
+                serde_json::from_str(line).unwrap_or_else(|_| serde_json::Value::String(line.clone()));
+            summarize_thinking(&mut message);
+            tool_call_count += count_tool_calls(&message);
+            message
+        })
+        .collect();
+    let raw = serde_json::to_vec(&messages).map_err(|e| e.to_string())?;
+    let content = zstd::bulk::compress(&raw, ZSTD_LEVEL).map_err(|e| format!("Failed to compress transcript: {}", e))?;
+    Ok(ArchivedTranscript {
+        content,
+        uncompressed_bytes: raw.len() as u64,
+        message_count: messages.len(),
+        tool_call_count,
+    })
+}
+
+/// Read a transcript file and pack it for archiving.
+pub fn pack_file_for_archive(path: &Path) -> Result<ArchivedTranscript, String> {
+    pack_for_archive(&read_lines(open_transcript(path)?)?)
+}
+
+pub fn unpack_archive(content: &[u8]) -> Result<Vec<serde_json::Value>, String> {
+    let raw = zstd::stream::decode_all(content).map_err(|e| format!("Failed to decompress transcript: {}", e))?;
+    serde_json::from_slice(&raw).map_err(|e| format!("Invalid archived transcript: {}", e))
+}
+
+/// Every string value in a message, skipping object keys.
+fn collect_text<'a>(value: &'a serde_json::Value, out: &mut Vec<&'a str>) {
+    match value {
+        serde_json::Value::String(s) => out.push(s),
+        serde_json::Value::Array(items) => items.iter().for_each(|v| collect_text(v, out)),
+        serde_json::Value::Object(map) => map.values().for_each(|v| collect_text(v, out)),
+        _ => {}
+    }
+}
+
+fn snippet(text: &str, char_index: usize, match_chars: usize) -> String {
+    let start = char_index.saturating_sub(SNIPPET_CONTEXT_CHARS);
+    let take = (char_index - start) + match_chars + SNIPPET_CONTEXT_CHARS;
+    let body: String = text.chars().skip(start).take(take).collect();
+    let body = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if start + take < text.chars().count() { "…" } else { "" };
+    format!("{}{}{}", prefix, body, suffix)
+}
+
+/// Messages whose text contains `query` (case-insensitive), as
+/// `(message index, message type, snippet)`, at most one hit per message.
+pub fn search_messages(messages: &[serde_json::Value], query: &str) -> Vec<(usize, String, String)> {
+    let needle: Vec<char> = query.to_lowercase().chars().collect();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    messages
+        .iter()
+        .enumerate()
+        .filter_map(|(index, message)| {
+            let mut texts = Vec::new();
+            collect_text(message, &mut texts);
+            texts.into_iter().find_map(|text| {
+                // Compare char by char so the match index maps back onto `text`.
+                let hay: Vec<char> = text.chars().flat_map(|c| c.to_lowercase().next()).collect();
+                let pos = hay.windows(needle.len()).position(|w| w == needle.as_slice())?;
+                let kind = message.get("type").and_then(|t| t.as_str()).unwrap_or("text").to_string();
+                Some((index, kind, snippet(text, pos, needle.len())))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn sample_transcript() -> String {
+        [
+            r#"{"type":"config","config":{"prompt":"go"}}"#,
+            r#"{"type":"system","subtype":"init"}"#,
+            r#"{"type":"assistant","message":{"id":"m1","content":[{"type":"text","text":"a"}]}}"#,
+            r#"{"type":"assistant","message":{"id":"m1","content":[{"type":"tool_use","id":"t1"}]}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t1"}]}}"#,
+            r#"{"type":"assistant","message":{"id":"m2","content":[{"type":"text","text":"done"}]}}"#,
+            r#"{"type":"result","subtype":"success","result":"done"}"#,
+        ]
+        .join("\n")
+            + "\n"
+    }
+
+    #[test]
+    fn test_compress_round_trips_and_seeks_by_turn() {
+        let tmp = tempdir().unwrap();
+        let plain = tmp.path().join("step0-2026-01-01T00-00-00.jsonl");
+        std::fs::write(&plain, sample_transcript()).unwrap();
+        let (plain_total, plain_turns) = read_turns(&plain, 1, 1).unwrap();
+
+        let compressed = compress_transcript(&plain).unwrap();
+        assert!(!plain.exists());
+        assert_eq!(compressed.file_name().unwrap(), "step0-2026-01-01T00-00-00.jsonl.zst");
+        assert!(tmp.path().join("step0-2026-01-01T00-00-00.jsonl.idx").exists());
+
+        let mut full = String::new();
+        open_transcript(&compressed).unwrap().read_to_string(&mut full).unwrap();
+        assert_eq!(full, sample_transcript());
+
+        let index = read_index(&compressed).unwrap();
+        assert_eq!(index.turns.iter().map(|t| t.lines).collect::<Vec<_>>(), vec![2, 3, 2]);
+        assert_eq!(index.uncompressed_bytes, sample_transcript().len() as u64);
+
+        let (total, turns) = read_turns(&compressed, 1, 1).unwrap();
+        assert_eq!((total, &turns), (plain_total, &plain_turns));
+        assert_eq!(turns[0].len(), 3);
+        assert!(turns[0][2].contains("tool_result"));
+        assert!(read_turns(&compressed, 5, 10).unwrap().1.is_empty());
+    }
+
+    #[test]
+    fn test_compress_existing_skips_recent_files() {
+        let tmp = tempdir().unwrap();
+        let logs = tmp.path().join("my-skill").join("logs");
+        std::fs::create_dir_all(&logs).unwrap();
+        let old = logs.join("step1-2026-01-01T00-00-00.jsonl");
+        let fresh = logs.join("step2-2026-01-01T00-00-00.jsonl");
+        std::fs::write(&old, sample_transcript()).unwrap();
+        std::fs::write(&fresh, sample_transcript()).unwrap();
+        let past = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+        std::fs::File::options().write(true).open(&old).unwrap().set_modified(past).unwrap();
+
+        compress_existing_transcripts(tmp.path().to_str().unwrap());
+
+        assert!(!old.exists());
+        let compressed = logs.join("step1-2026-01-01T00-00-00.jsonl.zst");
+        assert!(compressed.exists());
+        assert_eq!(std::fs::metadata(&compressed).unwrap().modified().unwrap(), past);
+        assert!(fresh.exists());
+    }
+
+    #[test]
+    fn test_archive_summarizes_thinking_and_finds_text() {
+        let long_thought = "x".repeat(THINKING_SUMMARY_CHARS + 50);
+        let lines = vec![
+            r#"{"type":"config","config":{"prompt":"Build the Revenue skill"}}"#.to_string(),
+            format!(
+                r#"{{"type":"assistant","message":{{"id":"m1","content":[{{"type":"thinking","thinking":"{}","signature":"sig"}},{{"type":"tool_use","id":"t1","name":"Read","input":{{"file_path":"clarifications.json"}}}}]}}}}"#,
+                long_thought
+            ),
+            "not json".to_string(),
+            String::new(),
+        ];
+        let archived = pack_for_archive(&lines).unwrap();
+        assert_eq!(archived.message_count, 3);
+        assert_eq!(archived.tool_call_count, 1);
+
+        let messages = unpack_archive(&archived.content).unwrap();
+        let thinking = &messages[1]["message"]["content"][0];
+        assert!(thinking.get("signature").is_none());
+        assert_eq!(thinking["thinking"].as_str().unwrap().chars().count(), THINKING_SUMMARY_CHARS + 1);
+        assert_eq!(messages[2], serde_json::Value::String("not json".to_string()));
+
+        let hits = search_messages(&messages, "REVENUE");
+        assert_eq!(hits, vec![(0, "config".to_string(), "Build the Revenue skill".to_string())]);
+        let hits = search_messages(&messages, "clarifications");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].1, "assistant");
+        assert!(search_messages(&messages, "").is_empty());
+    }
+
+    #[test]
+    fn test_transcript_names() {
+        assert!(is_transcript("step0-x.jsonl"));
+        assert!(is_transcript("step0-x.jsonl.zst"));
+        assert!(!is_transcript("step0-x.jsonl.idx"));
+        assert_eq!(transcript_stem("step0-x.jsonl.zst"), "step0-x");
+        assert_eq!(transcript_stem("step0-x.jsonl"), "step0-x");
+    }
+}
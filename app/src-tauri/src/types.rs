@@ -55,6 +55,49 @@ pub struct MarketplaceRegistry {
     pub enabled: bool,
 }
 
+/// Routes workflow steps to a model. A route matches a step id, a skill type
+/// (purpose), or both; the most specific match wins (see `model_routing`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelRoute {
+    #[serde(default)]
+    pub step_id: Option<u32>,
+    #[serde(default)]
+    pub skill_type: Option<String>,
+    /// Shorthand ("haiku", "sonnet", "opus") or a full model id.
+    pub model: String,
+}
+
+/// How long usage history is kept, in days; 0 keeps it forever. Expired rows
+/// are deleted by the `RetentionPrune` background task (see `data_retention`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// Finished `agent_runs` and their tool call log, by start time.
+    #[serde(default)]
+    pub agent_runs_days: u32,
+    /// Ended `workflow_sessions`, by start time.
+    #[serde(default)]
+    pub workflow_sessions_days: u32,
+    /// Archived agent transcripts, by archive time.
+    #[serde(default)]
+    pub transcripts_days: u32,
+    /// `audit_log` entries, by creation time.
+    #[serde(default)]
+    pub audit_log_days: u32,
+}
+
+/// A shell command or webhook run when one of `events` is published.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutomationHook {
+    pub name: String,
+    /// "step_completed" | "skill_packaged" | "budget_exceeded".
+    pub events: Vec<String>,
+    /// "command" runs `target` in the shell; "webhook" POSTs to the `target` URL.
+    pub kind: String,
+    pub target: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct AppSettings {
     pub anthropic_api_key: Option<String>,
@@ -83,12 +126,33 @@ pub struct AppSettings {
     pub splash_shown: bool,
     #[serde(default)]
     pub github_oauth_token: Option<String>,
+    /// Web URL of the GitHub server that issued `github_oauth_token`. The
+    /// token is dropped when the configured server no longer matches it.
+    #[serde(default)]
+    pub github_oauth_host: Option<String>,
     #[serde(default)]
     pub github_user_login: Option<String>,
     #[serde(default)]
     pub github_user_avatar: Option<String>,
     #[serde(default)]
     pub github_user_email: Option<String>,
+    /// Web URL of a GitHub Enterprise Server instance (e.g.
+    /// `https://github.acme.com`) used instead of github.com for sign-in,
+    /// imports, pushes and the team repository.
+    #[serde(default)]
+    pub github_enterprise_url: Option<String>,
+    /// Client ID of the OAuth app registered on the enterprise instance,
+    /// needed to sign in there.
+    #[serde(default)]
+    pub github_oauth_client_id: Option<String>,
+    /// PEM file of extra CA certificates trusted for GitHub requests, e.g. a
+    /// corporate root.
+    #[serde(default)]
+    pub github_ca_cert_path: Option<String>,
+    /// Skip TLS certificate verification for GitHub requests. For test
+    /// instances only.
+    #[serde(default)]
+    pub github_accept_invalid_certs: bool,
     #[serde(default)]
     pub marketplace_url: Option<String>,
     #[serde(default)]
@@ -108,6 +172,96 @@ pub struct AppSettings {
     /// Automatically apply marketplace updates at startup (default: false).
     #[serde(default)]
     pub auto_update: bool,
+    /// Team repository (owner/repo or owner/repo#branch) holding shared
+    /// configuration such as the tag and domain taxonomy.
+    #[serde(default)]
+    pub team_repo: Option<String>,
+    /// Base URL of a local Ollama server (e.g. `http://localhost:11434`).
+    /// When set with `local_model`, auxiliary passes such as field suggestions
+    /// run locally and fall back to the Anthropic API on failure. In offline
+    /// mode, agents run against it too.
+    #[serde(default)]
+    pub local_model_endpoint: Option<String>,
+    /// Model name for auxiliary passes and offline agents (e.g. `llama3.1:8b`).
+    #[serde(default)]
+    pub local_model: Option<String>,
+    /// Rules deciding whether newly imported workspace skills start active.
+    #[serde(default)]
+    pub activation_policy: ActivationPolicy,
+    /// How accessibility lint findings gate packaging:
+    /// "off" | "advisory" (warn, default) | "blocking" (refuse to package).
+    #[serde(default = "default_accessibility_lint")]
+    pub accessibility_lint: String,
+    /// Refuse to package skills whose review-by date has passed until they are
+    /// marked reviewed.
+    #[serde(default)]
+    pub block_expired_packaging: bool,
+    /// Refuse to import skill packages that are unsigned or signed by a key
+    /// that is not in the trusted-keys store.
+    #[serde(default)]
+    pub require_trusted_packages: bool,
+    /// Queued workflow steps run at once, across different skills. Steps of
+    /// the same skill always run one after another.
+    #[serde(default = "default_workflow_queue_concurrency")]
+    pub workflow_queue_concurrency: u32,
+    /// Independent agent requests of one skill run at once on its sidecar
+    /// (e.g. a batch of test cases).
+    #[serde(default = "default_max_concurrent_agents")]
+    pub max_concurrent_agents: u32,
+    /// Quality gate profile enforced when packaging; `None` runs no gate.
+    #[serde(default)]
+    pub quality_gate_profile: Option<String>,
+    /// Spend limit in USD across all skills since the last usage reset.
+    /// Agents are stopped once it is crossed; `None` for no limit.
+    #[serde(default)]
+    pub global_budget_usd: Option<f64>,
+    /// Per-skill spend limits in USD since the last usage reset.
+    #[serde(default)]
+    pub skill_budgets_usd: std::collections::BTreeMap<String, f64>,
+    /// Words replaced with `[REDACTED]` in text attached to feedback reports.
+    #[serde(default)]
+    pub feedback_redaction_keywords: Vec<String>,
+    /// Base64 X25519 public key of the maintainers. Feedback attachments can
+    /// be encrypted to it; without one they are only sent in the clear.
+    #[serde(default)]
+    pub feedback_public_key: Option<String>,
+    /// Folder of agent prompt templates that replace the bundled ones with the same file name.
+    #[serde(default)]
+    pub custom_prompts_dir: Option<String>,
+    /// Air-gapped mode: agents run against `local_model_endpoint` with
+    /// `local_model`, and marketplace and GitHub features are disabled.
+    #[serde(default)]
+    pub offline_mode: bool,
+    /// Turn off marketplace browsing, imports and update checks.
+    #[serde(default)]
+    pub disable_marketplace: bool,
+    /// Turn off GitHub sign-in, imports, issue filing and git host sync.
+    #[serde(default)]
+    pub disable_github: bool,
+    /// Shell commands and webhooks run on workflow events (see `automation_hooks`).
+    #[serde(default)]
+    pub automation_hooks: Vec<AutomationHook>,
+    /// Size cap of the `agent_transcripts` archive in MB (compressed); the
+    /// least recently read transcripts are evicted first. 0 turns archiving off.
+    #[serde(default = "default_transcript_archive_mb")]
+    pub transcript_archive_mb: u32,
+    /// How long usage history, transcripts and audit entries are kept.
+    #[serde(default)]
+    pub retention: RetentionPolicy,
+    /// Claude Code's data directory, read by `import_skill_invocations`;
+    /// `None` for `~/.claude`.
+    #[serde(default)]
+    pub claude_data_dir: Option<String>,
+    /// Git remote holding the organization's shared settings, optionally
+    /// `url#branch` (see `settings_sync`).
+    #[serde(default)]
+    pub settings_sync_repo: Option<String>,
+    /// Shared settings fields that keep this machine's value when syncing.
+    #[serde(default)]
+    pub settings_sync_local_overrides: Vec<String>,
+    /// Per-step and per-skill-type model overrides for workflow steps.
+    #[serde(default)]
+    pub model_routes: Vec<ModelRoute>,
 }
 
 impl std::fmt::Debug for AppSettings {
@@ -127,9 +281,14 @@ impl std::fmt::Debug for AppSettings {
             .field("refine_prompt_suggestions", &self.refine_prompt_suggestions)
             .field("splash_shown", &self.splash_shown)
             .field("github_oauth_token", &"[REDACTED]")
+            .field("github_oauth_host", &self.github_oauth_host)
             .field("github_user_login", &self.github_user_login)
             .field("github_user_avatar", &self.github_user_avatar)
             .field("github_user_email", &self.github_user_email)
+            .field("github_enterprise_url", &self.github_enterprise_url)
+            .field("github_oauth_client_id", &self.github_oauth_client_id)
+            .field("github_ca_cert_path", &self.github_ca_cert_path)
+            .field("github_accept_invalid_certs", &self.github_accept_invalid_certs)
             .field("marketplace_url", &self.marketplace_url)
             .field("marketplace_registries", &self.marketplace_registries)
             .field("marketplace_initialized", &self.marketplace_initialized)
@@ -138,6 +297,32 @@ impl std::fmt::Debug for AppSettings {
             .field("function_role", &self.function_role)
             .field("dashboard_view_mode", &self.dashboard_view_mode)
             .field("auto_update", &self.auto_update)
+            .field("team_repo", &self.team_repo)
+            .field("local_model_endpoint", &self.local_model_endpoint)
+            .field("local_model", &self.local_model)
+            .field("activation_policy", &self.activation_policy)
+            .field("accessibility_lint", &self.accessibility_lint)
+            .field("block_expired_packaging", &self.block_expired_packaging)
+            .field("require_trusted_packages", &self.require_trusted_packages)
+            .field("workflow_queue_concurrency", &self.workflow_queue_concurrency)
+            .field("max_concurrent_agents", &self.max_concurrent_agents)
+            .field("quality_gate_profile", &self.quality_gate_profile)
+            .field("global_budget_usd", &self.global_budget_usd)
+            .field("skill_budgets_usd", &self.skill_budgets_usd)
+            .field("feedback_redaction_keywords", &"[REDACTED]")
+            .field("feedback_public_key", &self.feedback_public_key)
+            .field("custom_prompts_dir", &self.custom_prompts_dir)
+            .field("offline_mode", &self.offline_mode)
+            .field("disable_marketplace", &self.disable_marketplace)
+            .field("disable_github", &self.disable_github)
+            // Webhook URLs often embed a secret token.
+            .field("automation_hooks", &format!("{} hook(s)", self.automation_hooks.len()))
+            .field("transcript_archive_mb", &self.transcript_archive_mb)
+            .field("retention", &self.retention)
+            .field("claude_data_dir", &self.claude_data_dir)
+            .field("settings_sync_repo", &self.settings_sync_repo)
+            .field("settings_sync_local_overrides", &self.settings_sync_local_overrides)
+            .field("model_routes", &self.model_routes)
             .finish()
     }
 }
@@ -159,9 +344,14 @@ impl Default for AppSettings {
             refine_prompt_suggestions: true,
             splash_shown: false,
             github_oauth_token: None,
+            github_oauth_host: None,
             github_user_login: None,
             github_user_avatar: None,
             github_user_email: None,
+            github_enterprise_url: None,
+            github_oauth_client_id: None,
+            github_ca_cert_path: None,
+            github_accept_invalid_certs: false,
             marketplace_url: None,
             marketplace_registries: vec![],
             marketplace_initialized: false,
@@ -170,6 +360,31 @@ impl Default for AppSettings {
             function_role: None,
             dashboard_view_mode: None,
             auto_update: false,
+            team_repo: None,
+            local_model_endpoint: None,
+            local_model: None,
+            activation_policy: ActivationPolicy::default(),
+            accessibility_lint: default_accessibility_lint(),
+            block_expired_packaging: false,
+            require_trusted_packages: false,
+            workflow_queue_concurrency: default_workflow_queue_concurrency(),
+            max_concurrent_agents: default_max_concurrent_agents(),
+            quality_gate_profile: None,
+            global_budget_usd: None,
+            skill_budgets_usd: Default::default(),
+            feedback_redaction_keywords: vec![],
+            feedback_public_key: None,
+            custom_prompts_dir: None,
+            offline_mode: false,
+            disable_marketplace: false,
+            disable_github: false,
+            automation_hooks: vec![],
+            transcript_archive_mb: default_transcript_archive_mb(),
+            retention: RetentionPolicy::default(),
+            claude_data_dir: None,
+            settings_sync_repo: None,
+            settings_sync_local_overrides: vec![],
+            model_routes: vec![],
         }
     }
 }
@@ -225,6 +440,40 @@ pub struct StartupDeps {
     pub checks: Vec<DepStatus>,
 }
 
+/// Result of `run_diagnostics`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticsReport {
+    pub generated_at: String,
+    pub app_version: String,
+    /// `{os}-{arch}`, e.g. "macos-aarch64"
+    pub os: String,
+    pub all_ok: bool,
+    pub checks: Vec<DepStatus>,
+}
+
+/// Waits for and holds of one kind of database connection since startup.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DbLockStats {
+    pub acquisitions: u64,
+    pub total_wait_ms: u64,
+    pub max_wait_ms: u64,
+    /// Waits long enough to stall the UI (250 ms or more).
+    pub slow_waits: u64,
+    pub max_hold_ms: u64,
+    pub slow_holds: u64,
+}
+
+/// Database connection contention since startup (`get_db_contention`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DbContention {
+    pub writer: DbLockStats,
+    pub readers: DbLockStats,
+    /// Read-only connections in the pool; 0 when reads share the writer.
+    pub read_connections: usize,
+    /// Reads that used the writer because no reader was free.
+    pub reader_fallbacks: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SkillSummary {
     pub name: String,
@@ -259,6 +508,9 @@ pub struct SkillSummary {
     pub user_invocable: Option<bool>,
     #[serde(default, rename = "disableModelInvocation")]
     pub disable_model_invocation: Option<bool>,
+    /// Review-by date (`YYYY-MM-DD`) when one is set.
+    #[serde(default)]
+    pub review_by: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -269,12 +521,98 @@ pub struct StepConfig {
     pub output_file: String,
     pub allowed_tools: Vec<String>,
     pub max_turns: u32,
+    /// Extended-thinking budget used when thinking is enabled in settings.
+    #[serde(default)]
+    pub thinking_budget: Option<u32>,
+    /// Shown under the step name for steps defined in a workflow manifest.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// True for steps added by a workflow manifest rather than built in.
+    #[serde(default)]
+    pub custom: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PackageResult {
     pub file_path: String,
     pub size_bytes: u64,
+    /// Accessibility lint findings when the lint mode is "advisory".
+    #[serde(default)]
+    pub lint_warnings: Vec<LintIssue>,
+    /// Quality gate report when a profile is selected in settings.
+    #[serde(default)]
+    pub quality_gate: Option<QualityGateReport>,
+    /// Skill validation diagnostics (none are errors when packaging refused on errors).
+    #[serde(default)]
+    pub validation: Vec<SkillDiagnostic>,
+    /// Transitive dependencies listed in the package's `dependencies.json`,
+    /// in install order.
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    /// Languages of the `SKILL.<lang>.md` variants in the package; empty when
+    /// a single locale was packaged as SKILL.md.
+    #[serde(default)]
+    pub locales: Vec<String>,
+    /// Layout of the package: `generic-zip`, `claude-code` or `claude-desktop`.
+    #[serde(default)]
+    pub format: String,
+}
+
+/// A `SKILL.<lang>.md` translation of a skill.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillTranslation {
+    pub language: String,
+    pub file_name: String,
+    /// SKILL.md was modified after the translation was written.
+    pub stale: bool,
+}
+
+/// Outcome of `reload_prompts`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PromptReload {
+    /// SHA-256 of the prompt sources now deployed to the workspace.
+    pub checksum: String,
+    /// Checksum deployed earlier this session, if any.
+    pub previous_checksum: Option<String>,
+    pub changed: bool,
+    /// Custom templates that replaced a bundled one.
+    pub overridden: Vec<String>,
+    /// Custom templates with no bundled counterpart (not deployed).
+    pub ignored: Vec<String>,
+}
+
+/// Which network-backed features are available under the current settings.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NetworkFeatures {
+    pub offline_mode: bool,
+    pub marketplace: bool,
+    pub github: bool,
+    /// Offline mode has both a local endpoint and model to run agents against.
+    pub local_model_ready: bool,
+}
+
+/// A skill in the dependency graph. `available` is false for a declared
+/// dependency that is neither built nor installed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillDependencyNode {
+    pub name: String,
+    pub available: bool,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillDependencyEdge {
+    pub skill_name: String,
+    pub depends_on: String,
+}
+
+/// Declared skill dependencies, with any cycles (each listed once, starting
+/// from its alphabetically first skill).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillDependencyGraph {
+    pub nodes: Vec<SkillDependencyNode>,
+    pub edges: Vec<SkillDependencyEdge>,
+    pub cycles: Vec<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -295,6 +633,22 @@ fn default_max_dimensions() -> u32 {
     5
 }
 
+fn default_accessibility_lint() -> String {
+    "advisory".to_string()
+}
+
+fn default_workflow_queue_concurrency() -> u32 {
+    1
+}
+
+fn default_transcript_archive_mb() -> u32 {
+    256
+}
+
+fn default_max_concurrent_agents() -> u32 {
+    3
+}
+
 fn default_true() -> bool {
     true
 }
@@ -361,6 +715,11 @@ pub struct SkillMasterRow {
     pub user_invocable: Option<bool>,
     #[serde(default)]
     pub disable_model_invocation: Option<bool>,
+    /// Date (`YYYY-MM-DD`) the skill's content should be reviewed by.
+    #[serde(default)]
+    pub review_by: Option<String>,
+    #[serde(default)]
+    pub reviewed_at: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -422,6 +781,12 @@ pub struct SkillFileMeta {
     pub argument_hint: Option<String>,
     pub user_invocable: Option<bool>,
     pub disable_model_invocation: Option<bool>,
+    /// Template variables the package declares; the import dialog asks for their values.
+    #[serde(default)]
+    pub variables: Vec<SkillVariable>,
+    /// Setup prerequisites from the package's CHECKLIST.md, or derived from its content.
+    #[serde(default)]
+    pub checklist: OnboardingChecklist,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -490,6 +855,27 @@ impl std::fmt::Debug for SkillLock {
     }
 }
 
+/// A change one instance made to a skill, for other instances observing it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillEvent {
+    pub id: i64,
+    pub skill_name: String,
+    pub instance_id: String,
+    /// "workflow.saved" | "workflow.reset" | "step.output" | "lock.released".
+    pub kind: String,
+    pub created_at: String,
+}
+
+/// A skill opened read-only because another instance holds its lock.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadonlySkill {
+    pub skill_name: String,
+    /// The other instance's lock; `None` if it was released in the meantime.
+    pub lock: Option<SkillLock>,
+    /// Poll `poll_skill_events` for events after this id.
+    pub last_event_id: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiscoveredSkill {
     pub name: String,
@@ -544,6 +930,111 @@ pub enum GitHubAuthResult {
     Success { user: GitHubUser },
 }
 
+/// A GitLab or Bitbucket access token with the account it belongs to.
+/// Stored under its own settings key, never sent to the frontend.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GitHostCredential {
+    pub host: String,
+    pub login: String,
+    pub token: String,
+    /// API base for self-managed GitLab; `None` means the public cloud.
+    #[serde(default)]
+    pub api_base: Option<String>,
+}
+
+impl std::fmt::Debug for GitHostCredential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GitHostCredential")
+            .field("host", &self.host)
+            .field("login", &self.login)
+            .field("token", &"[REDACTED]")
+            .field("api_base", &self.api_base)
+            .finish()
+    }
+}
+
+/// A connected git hosting account, as shown in Settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHostAccount {
+    pub host: String,
+    pub login: String,
+    pub api_base: Option<String>,
+}
+
+/// Outcome of pushing a skill to a hosted team repository.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostedSkillPushResult {
+    pub repo: String,
+    pub branch: String,
+    /// Repo-relative directory the skill was written to.
+    pub skill_path: String,
+    pub files: Vec<String>,
+    /// Files left out because they are hidden, binary or too large.
+    pub skipped: Vec<String>,
+    /// True when `marketplace.json` was created or gained a plugin entry.
+    pub manifest_updated: bool,
+    pub commit_sha: Option<String>,
+    /// Review opened or updated by a branch-per-skill push; `None` for direct pushes.
+    #[serde(default)]
+    pub review: Option<SkillUpstreamReview>,
+    /// Markdown description attached to `review`.
+    #[serde(default)]
+    pub review_description: Option<String>,
+    /// True when the local SKILL.md was replaced by a merge with the remote copy.
+    #[serde(default)]
+    pub skill_md_merged: bool,
+    /// Set when local and remote both changed SKILL.md in overlapping places.
+    /// Nothing is committed; resolve with `push_skill_resolve_conflicts`.
+    #[serde(default)]
+    pub conflict: Option<SkillPushConflict>,
+}
+
+/// SKILL.md edits that could not be merged automatically on push.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillPushConflict {
+    /// Repo-relative path of the conflicting file.
+    pub path: String,
+    /// SHA-256 of the remote content the conflict was computed against.
+    pub remote_digest: String,
+    /// The merge with git-style conflict markers around each hunk.
+    pub merged_with_markers: String,
+    pub hunks: Vec<SkillMergeHunk>,
+}
+
+/// One region changed differently on both sides.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillMergeHunk {
+    /// 1-based line in the local SKILL.md where the hunk starts.
+    pub local_start: usize,
+    /// 1-based line in the remote SKILL.md where the hunk starts.
+    pub remote_start: usize,
+    pub base: String,
+    pub local: String,
+    pub remote: String,
+}
+
+/// The review branch and pull/merge request a branch-per-skill push opened.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillUpstreamReview {
+    pub skill_name: String,
+    /// "github" | "gitlab" | "bitbucket"
+    pub host: String,
+    /// `owner/repo` on the host.
+    pub repo: String,
+    pub repo_url: String,
+    pub branch: String,
+    /// Branch the review merges into (the protected main branch).
+    pub base_branch: String,
+    /// Pull request number, GitLab merge request IID or Bitbucket pull request id.
+    pub number: u64,
+    pub url: String,
+    /// "open" | "merged" | "closed"
+    pub state: String,
+    pub commit_sha: Option<String>,
+    pub pushed_at: String,
+    pub updated_at: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StepResetPreview {
     pub step_id: u32,
@@ -551,6 +1042,15 @@ pub struct StepResetPreview {
     pub files: Vec<String>,
 }
 
+/// Outcome of `reset_workflow_artifacts`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactResetResult {
+    /// Step the workflow restarts at.
+    pub current_step: u32,
+    pub deleted_files: Vec<String>,
+    pub kept_files: Vec<String>,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct AgentRunRecord {
     pub agent_id: String,
@@ -572,6 +1072,7 @@ pub struct AgentRunRecord {
     pub session_id: Option<String>,
     pub started_at: String,
     pub completed_at: Option<String>,
+    pub retry_count: u32,
 }
 
 impl std::fmt::Debug for AgentRunRecord {
@@ -596,6 +1097,7 @@ impl std::fmt::Debug for AgentRunRecord {
             .field("session_id", &"[REDACTED]")
             .field("started_at", &self.started_at)
             .field("completed_at", &self.completed_at)
+            .field("retry_count", &self.retry_count)
             .finish()
     }
 }
@@ -639,6 +1141,47 @@ impl std::fmt::Debug for WorkflowSessionRecord {
     }
 }
 
+/// Cumulative usage for a running agent, emitted as `agent-cost-tick` and
+/// snapshotted to `agent_run_ticks`. `estimated` is true until the SDK's own
+/// `total_cost_usd` arrives with the result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentCostTick {
+    pub agent_id: String,
+    pub skill_name: String,
+    pub step_id: i32,
+    pub model: String,
+    pub input_tokens: i32,
+    pub output_tokens: i32,
+    pub cache_read_tokens: i32,
+    pub cache_write_tokens: i32,
+    pub total_cost: f64,
+    pub estimated: bool,
+    pub num_turns: i32,
+    pub elapsed_ms: i64,
+    pub workflow_session_id: Option<String>,
+}
+
+/// Progress of a running workflow step, emitted as `workflow-step-progress`.
+/// `eta_ms` and `estimated_total_ms` come from how long the step took on the
+/// same model before; `percent` falls back to turns against `max_turns` when
+/// there is no history and stays below 100 until the result arrives.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepProgress {
+    pub agent_id: String,
+    pub skill_name: String,
+    pub step_id: i32,
+    pub model: String,
+    pub turns_completed: u32,
+    pub max_turns: Option<u32>,
+    pub current_tool: Option<String>,
+    pub tokens_used: i64,
+    pub elapsed_ms: i64,
+    pub estimated_total_ms: Option<i64>,
+    pub eta_ms: Option<i64>,
+    pub percent: Option<u8>,
+    pub finished: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UsageSummary {
     pub total_cost: f64,
@@ -646,6 +1189,21 @@ pub struct UsageSummary {
     pub avg_cost_per_run: f64,
 }
 
+/// Spend against the configured budgets since the last usage reset,
+/// including agents still running.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BudgetStatus {
+    pub skill_name: Option<String>,
+    pub skill_spent_usd: f64,
+    pub skill_budget_usd: Option<f64>,
+    pub global_spent_usd: f64,
+    pub global_budget_usd: Option<f64>,
+    /// `ok`, `warning` (a budget is at least 80% used) or `exceeded`
+    pub state: String,
+    /// `skill` or `global` when a budget is exceeded
+    pub exceeded_scope: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UsageByStep {
     pub step_id: i32,
@@ -669,6 +1227,24 @@ pub struct UsageByDay {
     pub run_count: i32,
 }
 
+/// Lifetime usage of one skill since the last usage reset.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillAnalytics {
+    pub skill_name: String,
+    pub total_cost: f64,
+    pub run_count: i64,
+    pub refine_sessions: i64,
+    /// Completion time of the latest successful workflow step run.
+    pub last_regenerated_at: Option<String>,
+    pub avg_step_duration_ms: Option<f64>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    /// Cache reads over all prompt tokens (input, cache read and cache write).
+    pub cache_hit_ratio: Option<f64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubRepoInfo {
     pub owner: String,
@@ -703,6 +1279,40 @@ pub struct AvailableSkill {
     pub disable_model_invocation: Option<bool>,
 }
 
+/// A marketplace catalog listing cached in `marketplace_catalog_cache`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedCatalog {
+    pub cache_key: String,
+    /// SHA of the repository tree the listing was built from.
+    pub tree_sha: Option<String>,
+    /// Blob SHA of `marketplace.json` and of each listed SKILL.md, by repo path.
+    pub blob_shas: std::collections::BTreeMap<String, String>,
+    pub registry_name: Option<String>,
+    pub skills: Vec<AvailableSkill>,
+    /// RFC 3339 timestamp of the last fetch or 304 revalidation.
+    pub fetched_at: String,
+}
+
+/// Cumulative progress of a GitHub listing or import, emitted as
+/// `github-import-progress`. `phase` is `discovering`, `reading_metadata`,
+/// `downloading`, `finished` or `cancelled`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ImportProgress {
+    pub operation_id: String,
+    pub phase: String,
+    pub repos_scanned: u32,
+    /// Entries in the fetched repo tree.
+    pub tree_entries: usize,
+    /// Candidate skills listed, or skills requested for import.
+    pub skills_found: usize,
+    /// Skills whose metadata was read (listing) or that were imported or skipped.
+    pub skills_processed: usize,
+    pub files_downloaded: usize,
+    pub bytes_downloaded: u64,
+    /// Skill path being worked on.
+    pub current: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SkillMetadataOverride {
     pub name: Option<String>,
@@ -752,6 +1362,10 @@ pub struct RefineFileDiff {
     pub status: String,
     /// Unified diff text for this file
     pub diff: String,
+    /// `accepted` when the refine session accepted this exact change; `None`
+    /// while it awaits review.
+    #[serde(default)]
+    pub review: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -761,6 +1375,81 @@ pub struct RefineDiff {
     pub files: Vec<RefineFileDiff>,
 }
 
+/// A frontmatter field whose value differs between the two sides. `None`
+/// means the field is absent on that side.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrontmatterFieldChange {
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WordDiffSegment {
+    /// One of "equal", "insert", "delete"
+    pub kind: String,
+    pub text: String,
+}
+
+/// One changed region of a file body, as whole lines on each side plus a
+/// word-level breakdown for display.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructuredDiffHunk {
+    /// Derived from the hunk's position and content, so it stays the same
+    /// across recomputations of an unchanged diff.
+    pub id: String,
+    /// 1-based line in the old file where the hunk starts
+    pub old_start: usize,
+    /// 1-based line in the new file where the hunk starts
+    pub new_start: usize,
+    pub old_text: String,
+    pub new_text: String,
+    pub words: Vec<WordDiffSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructuredFileDiff {
+    pub path: String,
+    /// One of "added", "modified", "deleted"
+    pub status: String,
+    /// False for binary files, which have no frontmatter or hunks.
+    pub text: bool,
+    pub frontmatter: Vec<FrontmatterFieldChange>,
+    pub hunks: Vec<StructuredDiffHunk>,
+}
+
+/// Lines a file gained and lost between two versions of a skill.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkillFileChangeStat {
+    /// Relative to the skill directory
+    pub path: String,
+    /// One of "added", "modified", "deleted"
+    pub status: String,
+    /// False for binary files, which have no line counts.
+    pub text: bool,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+/// What changed in a skill between the two refs given to `compare_skill_versions`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkillVersionComparison {
+    pub skill_name: String,
+    pub base_ref: String,
+    pub target_ref: String,
+    /// SKILL.md frontmatter fields that differ.
+    pub frontmatter: Vec<FrontmatterFieldChange>,
+    pub added_references: Vec<String>,
+    pub removed_references: Vec<String>,
+    pub modified_references: Vec<String>,
+    /// Changed regions of the SKILL.md body and the lines they add and remove.
+    pub body_hunks: usize,
+    pub body_lines_added: usize,
+    pub body_lines_removed: usize,
+    /// Every file that differs, SKILL.md and references included.
+    pub files: Vec<SkillFileChangeStat>,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct RefineSessionInfo {
     pub session_id: String,
@@ -787,85 +1476,1911 @@ pub struct ConversationMessage {
     pub content: String,
 }
 
+/// One entry of a persisted refine conversation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RefineSessionMessage {
+    /// `user` or `agent`.
+    pub role: String,
+    /// The user's message, or the agent's final reply.
+    pub text: Option<String>,
+    #[serde(default)]
+    pub target_files: Vec<String>,
+    pub command: Option<String>,
+    /// The agent run that answered (user) or produced (agent) this message.
+    pub agent_id: Option<String>,
+    pub created_at: String,
+}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// A file of a refine session's pending changes that was accepted or rejected.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RefineFileReview {
+    /// Path relative to the skills repo, as in `RefineFileDiff`.
+    pub path: String,
+    /// `accepted` or `rejected`.
+    pub decision: String,
+    /// The file's diff when it was reviewed. An accepted file that changes
+    /// again is back to awaiting review.
+    pub diff: String,
+    pub reviewed_at: String,
+}
 
-    #[test]
-    fn test_app_settings_default() {
-        let settings = AppSettings::default();
-        assert!(settings.anthropic_api_key.is_none());
-        assert!(settings.workspace_path.is_none());
-        assert!(settings.skills_path.is_none());
-        assert!(settings.preferred_model.is_none());
-        assert_eq!(settings.log_level, "info");
-        assert!(!settings.extended_context);
-        assert!(!settings.extended_thinking);
-        assert!(!settings.splash_shown);
-        assert!(settings.github_oauth_token.is_none());
-        assert!(settings.github_user_login.is_none());
-        assert!(settings.github_user_avatar.is_none());
-        assert!(settings.github_user_email.is_none());
-        assert!(settings.marketplace_url.is_none());
-        assert!(settings.marketplace_registries.is_empty());
-        assert!(!settings.marketplace_initialized);
-        assert!(settings.industry.is_none());
-        assert!(settings.function_role.is_none());
-        assert!(settings.dashboard_view_mode.is_none());
-    }
+/// A row of `refine_sessions`: everything needed to pick a refine session
+/// back up after the app restarts.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RefineSessionRecord {
+    pub session_id: String,
+    pub skill_name: String,
+    /// SDK session of the conversation, from the agent's result messages.
+    pub sdk_session_id: Option<String>,
+    pub messages: Vec<RefineSessionMessage>,
+    /// Uncommitted skill changes as of the last agent reply.
+    pub pending_diff: Option<RefineDiff>,
+    /// Per-file accept/reject decisions, oldest first.
+    pub file_reviews: Vec<RefineFileReview>,
+    pub created_at: String,
+    pub updated_at: String,
+}
 
-    #[test]
-    fn test_app_settings_serde_roundtrip() {
-        let settings = AppSettings {
-            anthropic_api_key: Some("sk-ant-test-key".to_string()),
-            workspace_path: Some("/home/user/skills".to_string()),
-            skills_path: Some("/home/user/output".to_string()),
-            preferred_model: Some("sonnet".to_string()),
-            debug_mode: false,
-            log_level: "info".to_string(),
-            extended_context: false,
-            extended_thinking: true,
-            interleaved_thinking_beta: true,
-            sdk_effort: None,
-            fallback_model: None,
-            refine_prompt_suggestions: true,
-            splash_shown: false,
-            github_oauth_token: Some("test-github-token".to_string()),
-            github_user_login: Some("testuser".to_string()),
-            github_user_avatar: Some("https://avatars.githubusercontent.com/u/12345".to_string()),
-            github_user_email: Some("test@example.com".to_string()),
-            marketplace_url: Some("https://github.com/my-org/skills".to_string()),
-            marketplace_registries: vec![MarketplaceRegistry {
-                name: "Test".to_string(),
-                source_url: "https://github.com/owner/repo".to_string(),
-                enabled: true,
-            }],
-            marketplace_initialized: false,
-            max_dimensions: 5,
-            industry: Some("Financial Services".to_string()),
-            function_role: Some("Analytics Engineer".to_string()),
-            dashboard_view_mode: Some("grid".to_string()),
-            auto_update: false,
-        };
-        let json = serde_json::to_string(&settings).unwrap();
-        let deserialized: AppSettings = serde_json::from_str(&json).unwrap();
-        assert_eq!(
-            deserialized.anthropic_api_key.as_deref(),
-            Some("sk-ant-test-key")
-        );
-        assert_eq!(
-            deserialized.workspace_path.as_deref(),
-            Some("/home/user/skills")
-        );
-        assert_eq!(
-            deserialized.skills_path.as_deref(),
-            Some("/home/user/output")
-        );
-        assert_eq!(
-            deserialized.preferred_model.as_deref(),
-            Some("sonnet")
-        );
+/// A persisted refine session that is not open, from `list_resumable_refine_sessions`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ResumableRefineSession {
+    pub session_id: String,
+    pub skill_name: String,
+    pub message_count: usize,
+    pub last_user_message: Option<String>,
+    /// Whether the agent left uncommitted changes to the skill.
+    pub has_pending_changes: bool,
+    /// Whether the agent conversation itself can be continued, not just the chat log.
+    pub can_resume_conversation: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Result of `resume_refine_session`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ResumedRefineSession {
+    pub session: RefineSessionInfo,
+    pub messages: Vec<RefineSessionMessage>,
+    /// Current uncommitted changes to the skill, with the session's accepted files marked.
+    pub pending_diff: RefineDiff,
+    pub can_resume_conversation: bool,
+}
+
+/// One skill of a batch refine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefineBatchItem {
+    pub skill_name: String,
+    pub position: u32,
+    /// One of "queued", "running", "review", "no_changes", "failed",
+    /// "approved", "rejected".
+    pub status: String,
+    pub agent_id: Option<String>,
+    /// The agent's uncommitted changes, kept after review for the record.
+    pub diff: Option<RefineDiff>,
+    pub error: Option<String>,
+    pub cost_usd: f64,
+    pub updated_at: String,
+}
+
+/// One refine instruction run against several skills in turn, from
+/// `start_refine_batch` / `get_refine_batch`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefineBatch {
+    pub batch_id: String,
+    pub instruction: String,
+    pub workspace_path: String,
+    /// Usage session every agent run of the batch is recorded under.
+    pub workflow_session_id: String,
+    pub items: Vec<RefineBatchItem>,
+    pub total_cost_usd: f64,
+    pub created_at: String,
+}
+
+// ─── Team taxonomy types ─────────────────────────────────────────────────────
+
+/// Naming rules enforced (as warnings) by the team taxonomy.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct TaxonomyNamingRules {
+    /// Maximum tag length in characters.
+    #[serde(default)]
+    pub max_tag_length: Option<usize>,
+    /// Require tags to be kebab-case (e.g. `data-quality`).
+    #[serde(default)]
+    pub kebab_case_tags: bool,
+    /// Required prefix for skill names (e.g. `acme-`).
+    #[serde(default)]
+    pub skill_name_prefix: Option<String>,
+}
+
+/// A level of the tag hierarchy. `finance/revenue/rev-rec` sits under
+/// `finance` and `finance/revenue`, which exist as nodes even when no skill
+/// carries them directly.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagNode {
+    /// Last level of the path, e.g. `rev-rec`.
+    pub name: String,
+    pub path: String,
+    /// Skills tagged with exactly this path.
+    pub direct_count: u32,
+    /// Skills tagged with this path or anything under it.
+    pub skill_count: u32,
+    pub children: Vec<TagNode>,
+}
+
+/// An alternative spelling rewritten to `tag` whenever skill tags are saved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagAlias {
+    pub alias: String,
+    pub tag: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagTree {
+    pub tags: Vec<TagNode>,
+    pub aliases: Vec<TagAlias>,
+    /// Team taxonomy warnings for the skills a rename or merge just retagged.
+    pub warnings: Vec<TaxonomyWarning>,
+}
+
+/// Shared tag/domain vocabulary synced from the team repo's `.skill-builder/taxonomy.json`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct TeamTaxonomy {
+    /// When non-empty, tags outside `allowed_tags` + `suggested_tags` produce warnings.
+    #[serde(default)]
+    pub allowed_tags: Vec<String>,
+    #[serde(default)]
+    pub suggested_tags: Vec<String>,
+    /// When non-empty, a skill's intake domain outside this list produces a warning.
+    #[serde(default)]
+    pub domains: Vec<String>,
+    #[serde(default)]
+    pub naming_rules: TaxonomyNamingRules,
+    /// Team default activation rules, evaluated after the local rules in settings.
+    #[serde(default)]
+    pub activation_rules: Vec<ActivationRule>,
+    /// Named quality gate profiles (e.g. "team-standard", "strict") selectable
+    /// in settings and enforced at packaging.
+    #[serde(default)]
+    pub quality_gates: Vec<QualityGateProfile>,
+    /// Team file rules, applied after the built-in rules wherever skill files
+    /// are written, imported, uploaded or packaged.
+    #[serde(default)]
+    pub file_rules: Vec<FileRule>,
+    /// Team repo the taxonomy was synced from (set locally, not read from the file).
+    #[serde(default)]
+    pub source_repo: Option<String>,
+    /// Blob SHA of the taxonomy file at sync time.
+    #[serde(default)]
+    pub source_sha: Option<String>,
+    #[serde(default)]
+    pub synced_at: Option<String>,
+}
+
+/// A file validation rule. Every set field is enforced; empty fields are ignored.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct FileRule {
+    /// Shown in error messages next to the rule's provenance.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Globs (relative to the skill directory) the size and extension checks
+    /// apply to. Empty means every file.
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Skill types (purposes) the rule applies to. Empty means all.
+    #[serde(default)]
+    pub skill_types: Vec<String>,
+    #[serde(default)]
+    pub max_bytes: Option<u64>,
+    /// Extensions without the dot, matched case-insensitively.
+    #[serde(default)]
+    pub forbidden_extensions: Vec<String>,
+    /// Files a skill must contain, checked on import and packaging.
+    #[serde(default)]
+    pub required_files: Vec<String>,
+}
+
+/// A non-blocking taxonomy violation for one skill field.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TaxonomyWarning {
+    pub skill_name: String,
+    /// One of "tag", "domain", "name"
+    pub field: String,
+    pub value: String,
+    pub message: String,
+}
+
+/// Result of proposing new vocabulary entries upstream.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxonomyProposal {
+    pub pr_url: String,
+    pub pr_number: u64,
+    pub branch: String,
+    pub added_tags: Vec<String>,
+    pub added_domains: Vec<String>,
+}
+
+// ─── Package signing types ───────────────────────────────────────────────────
+
+/// This installation's signing identity, shared with recipients so they can trust it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SigningKeyInfo {
+    pub key_id: String,
+    /// Base64-encoded raw Ed25519 public key.
+    pub public_key: String,
+}
+
+/// A public key the user has chosen to trust for package verification.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrustedKey {
+    pub key_id: String,
+    pub name: String,
+    pub public_key: String,
+    pub added_at: String,
+}
+
+/// Result of checking a package's embedded signature.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PackageVerification {
+    /// One of `unsigned`, `trusted`, `untrusted`, `invalid`.
+    pub status: String,
+    pub key_id: Option<String>,
+    /// Name of the trusted key that signed the package, when trusted.
+    pub signer_name: Option<String>,
+    pub signed_at: Option<String>,
+    /// Why verification failed (`invalid` only).
+    pub detail: Option<String>,
+}
+
+// ─── Skill parameter types ───────────────────────────────────────────────────
+
+/// A template variable declared under `variables:` in SKILL.md frontmatter.
+/// Files reference it as `{{name}}`; values are substituted at install or packaging time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct SkillVariable {
+    pub name: String,
+    pub description: Option<String>,
+    pub default: Option<String>,
+    /// Required variables without a default must be given a value.
+    pub required: bool,
+    /// When non-empty, the value must be one of these.
+    #[serde(default)]
+    pub allowed: Vec<String>,
+}
+
+/// Values an installed parameterized skill was resolved with.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillParameterSet {
+    pub skill_name: String,
+    /// Name of the package skill the install was resolved from.
+    pub source_name: Option<String>,
+    pub values: std::collections::BTreeMap<String, String>,
+    pub updated_at: String,
+}
+
+// ─── Onboarding checklist types ──────────────────────────────────────────────
+
+/// Setup prerequisites for a skill's consumers, shipped as CHECKLIST.md.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct OnboardingChecklist {
+    /// MCP servers the skill calls (e.g. from `mcp__server__tool` references).
+    pub mcp_servers: Vec<String>,
+    /// Environment variables holding keys, tokens or passwords.
+    pub credentials: Vec<String>,
+    /// Tool and runtime versions the skill expects (e.g. `dbt >=1.7`).
+    pub environment: Vec<String>,
+    /// Prompts to try first once set up.
+    pub first_prompts: Vec<String>,
+}
+
+// ─── Activation policy types ─────────────────────────────────────────────────
+
+/// One import-time activation rule. Every criterion that is set must match;
+/// a rule with no criteria matches every import.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActivationRule {
+    /// `activate` or `deactivate`.
+    pub action: String,
+    /// Prefix of the source URL (e.g. `acme/skills`), or `upload` for file uploads.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// `author:` from SKILL.md frontmatter (case-insensitive).
+    #[serde(default)]
+    pub author: Option<String>,
+    /// `domain:` from SKILL.md frontmatter (case-insensitive).
+    #[serde(default)]
+    pub domain: Option<String>,
+    /// Package signature status: `unsigned`, `trusted` or `untrusted`.
+    #[serde(default)]
+    pub signature: Option<String>,
+}
+
+/// Local activation rules, evaluated first-match-wins before team rules.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActivationPolicy {
+    #[serde(default)]
+    pub rules: Vec<ActivationRule>,
+    /// Applied when no rule matches: `activate` or `deactivate`.
+    #[serde(default = "default_activation_action")]
+    pub default_action: String,
+}
+
+fn default_activation_action() -> String {
+    "activate".to_string()
+}
+
+impl Default for ActivationPolicy {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            default_action: default_activation_action(),
+        }
+    }
+}
+
+/// A recorded import-time activation decision.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActivationDecision {
+    pub id: i64,
+    pub skill_name: String,
+    pub is_active: bool,
+    /// `settings rule N`, `team rule N` or `default` (N is 1-based).
+    pub matched_rule: String,
+    /// The facts the rule was evaluated against.
+    pub reason: String,
+    /// Set when the user later toggled the skill against this decision.
+    pub overridden_at: Option<String>,
+    pub created_at: String,
+}
+
+// ─── Prompt contract types ───────────────────────────────────────────────────
+
+/// An in-progress workflow run whose completed step outputs follow an older
+/// prompt contract than this app version expects.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ContractUpgrade {
+    pub skill_name: String,
+    pub current_step: i32,
+    pub from_version: i32,
+    pub to_version: i32,
+    /// Summaries of the contract changes that affect this run, oldest first.
+    pub changes: Vec<String>,
+    /// Earliest step whose output must be regenerated when re-running.
+    pub rerun_from_step: u32,
+    /// True when every affecting change ships an artifact transform.
+    pub can_transform: bool,
+}
+
+// ─── Benchmark types ─────────────────────────────────────────────────────────
+
+/// One scenario run with the skill loaded and without it, scored by the judge.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BenchmarkScenarioResult {
+    pub name: String,
+    pub prompt: String,
+    /// Judge scores on a 1-10 scale.
+    pub skill_score: f64,
+    pub baseline_score: f64,
+    /// `skill`, `baseline` or `tie`.
+    pub winner: String,
+    pub reason: String,
+    /// Input plus output tokens.
+    pub skill_tokens: i64,
+    pub baseline_tokens: i64,
+    pub skill_ms: i64,
+    pub baseline_ms: i64,
+}
+
+/// A stored benchmark run of one skill version against the no-skill baseline.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillBenchmark {
+    pub id: i64,
+    pub skill_name: String,
+    /// `version` from SKILL.md frontmatter at benchmark time.
+    pub skill_version: Option<String>,
+    pub scenario_set: String,
+    pub model: String,
+    pub judge_model: String,
+    pub skill_wins: u32,
+    pub baseline_wins: u32,
+    pub ties: u32,
+    pub avg_skill_score: f64,
+    pub avg_baseline_score: f64,
+    /// Extra tokens with the skill loaded, as a percentage of the baseline.
+    pub token_overhead_pct: f64,
+    /// Extra latency with the skill loaded, as a percentage of the baseline.
+    pub latency_overhead_pct: f64,
+    pub results: Vec<BenchmarkScenarioResult>,
+    pub created_at: String,
+}
+
+/// A saved prompt a skill is regression-tested against.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillTestCase {
+    pub id: i64,
+    pub skill_name: String,
+    pub name: String,
+    pub prompt: String,
+    /// Whether the agent should load the skill for this prompt.
+    pub expect_trigger: bool,
+    /// Substrings the response must contain (case-insensitive).
+    pub expected_contains: Vec<String>,
+    pub created_at: String,
+}
+
+/// The outcome of one test case in one `run_skill_tests` run.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillTestResult {
+    pub id: i64,
+    pub test_case_id: i64,
+    pub test_name: String,
+    pub skill_name: String,
+    pub run_id: String,
+    /// `version` from SKILL.md frontmatter at run time.
+    pub skill_version: Option<String>,
+    pub triggered: bool,
+    pub passed: bool,
+    pub response: String,
+    /// Why the case failed; empty when it passed.
+    pub failures: Vec<String>,
+    /// Set when the agent itself failed to run.
+    pub error: Option<String>,
+    pub duration_ms: i64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillTestRun {
+    pub run_id: String,
+    pub skill_name: String,
+    pub passed: u32,
+    pub failed: u32,
+    pub results: Vec<SkillTestResult>,
+}
+
+// ─── Step explanation types ──────────────────────────────────────────────────
+
+/// An input a workflow step reads, and whether it is ready.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StepInput {
+    pub label: String,
+    /// Path relative to the skill's workspace directory; `None` for DB-held inputs.
+    pub path: Option<String>,
+    pub available: bool,
+}
+
+/// State-aware description of an upcoming workflow step.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StepExplanation {
+    pub skill_name: String,
+    pub step_id: u32,
+    pub step_name: String,
+    pub summary: String,
+    pub reads: Vec<StepInput>,
+    pub writes: Vec<String>,
+    /// Completed runs the estimates are based on; 0 when there is no history.
+    pub history_runs: u32,
+    /// `skill` when estimates come from this skill's own runs, `all` otherwise.
+    pub history_scope: String,
+    pub expected_cost_usd: Option<f64>,
+    pub expected_duration_ms: Option<i64>,
+    /// What the user should do before running the step.
+    pub prepare: Vec<String>,
+}
+
+// ─── Tool policy types ───────────────────────────────────────────────────────
+
+/// Restrictions applied to the Bash tool by the sidecar before each command runs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct BashSandboxPolicy {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Glob patterns (`*` wildcard); when non-empty every command must match one.
+    #[serde(default)]
+    pub allow_patterns: Vec<String>,
+    /// Glob patterns that are always refused, checked before the allowlist.
+    #[serde(default)]
+    pub deny_patterns: Vec<String>,
+    /// When false, commands that reach the network (curl, git fetch, ...) are refused.
+    #[serde(default)]
+    pub allow_network: bool,
+}
+
+/// Tool restrictions for agent runs, stored globally and optionally per skill.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ToolPolicy {
+    #[serde(default)]
+    pub bash_sandbox: BashSandboxPolicy,
+    /// Role per workflow step id that trims the step's tools: "full",
+    /// "no_shell" (no Bash) or "read_only" (Read, Glob, Grep). Steps not
+    /// listed keep the tools they declare.
+    #[serde(default = "default_step_tool_roles")]
+    pub step_roles: std::collections::BTreeMap<u32, String>,
+}
+
+impl Default for ToolPolicy {
+    fn default() -> Self {
+        Self {
+            bash_sandbox: BashSandboxPolicy::default(),
+            step_roles: default_step_tool_roles(),
+        }
+    }
+}
+
+/// Research steps never need a shell and Confirm Decisions only reviews answers.
+fn default_step_tool_roles() -> std::collections::BTreeMap<u32, String> {
+    [(0, "no_shell"), (1, "no_shell"), (2, "read_only"), (3, "full")]
+        .into_iter()
+        .map(|(step, role)| (step, role.to_string()))
+        .collect()
+}
+
+/// One tool call an agent made, recorded for the run's audit log.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AgentToolCall {
+    pub id: i64,
+    pub agent_id: String,
+    pub skill_name: String,
+    pub step_id: i32,
+    pub tool_use_id: String,
+    pub tool_name: String,
+    /// Command, path or pattern the tool was called with, truncated.
+    pub input_summary: String,
+    /// Whether the tool was in the run's allowed tools; `None` for sub-agent
+    /// calls and when the run's tools are not known (streaming sessions).
+    pub allowed: Option<bool>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ToolPolicyView {
+    pub global: ToolPolicy,
+    /// The skill's own policy; `None` when it inherits the global one.
+    pub skill_override: Option<ToolPolicy>,
+    pub effective: ToolPolicy,
+}
+
+// ─── Step performance types ──────────────────────────────────────────────────
+
+/// One completed workflow step agent run, as recorded in `step_perf_history`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepPerfSample {
+    pub agent_id: String,
+    pub model: String,
+    pub skill_name: String,
+    pub step_id: i32,
+    pub app_version: String,
+    /// Short hash of the step's agent prompt; `None` when it couldn't be read.
+    pub prompt_version: Option<String>,
+    /// Input tokens including cache reads and writes.
+    pub prompt_tokens: i64,
+    pub output_tokens: i64,
+    pub total_cost: f64,
+    pub time_to_first_token_ms: Option<i64>,
+    pub duration_ms: i64,
+}
+
+/// Step performance for one (step, app version, prompt version) combination.
+/// The `*_change_pct` fields compare against the previous combination seen
+/// for the same step and are `None` for the first one.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StepPerfTrend {
+    pub step_id: i32,
+    pub app_version: String,
+    pub prompt_version: Option<String>,
+    pub runs: i64,
+    pub avg_prompt_tokens: f64,
+    pub avg_output_tokens: f64,
+    pub avg_cost: f64,
+    pub avg_time_to_first_token_ms: Option<f64>,
+    pub avg_duration_ms: f64,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub prompt_tokens_change_pct: Option<f64>,
+    pub cost_change_pct: Option<f64>,
+    pub time_to_first_token_change_pct: Option<f64>,
+    pub duration_change_pct: Option<f64>,
+}
+
+/// A replay of a historical step run, from `replay_step`. The replay writes
+/// into `output_dir` (`workspace/` and `skills/` mirror the real roots);
+/// the version pairs show what changed since the original run.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StepReplay {
+    pub replay_id: String,
+    pub agent_id: String,
+    pub source_agent_id: String,
+    pub skill_name: String,
+    pub step_id: u32,
+    pub output_dir: String,
+    pub model: String,
+    pub captured_at: String,
+    pub captured_app_version: String,
+    pub current_app_version: String,
+    pub captured_prompt_version: Option<String>,
+    pub current_prompt_version: Option<String>,
+}
+
+/// Token usage of one model within a completed step run, from `agent_runs`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepRunUsage {
+    pub agent_id: String,
+    pub model: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    pub total_cost: f64,
+}
+
+/// Pre-run cost of a step on one model. `low_cost`..`high_cost` covers the
+/// middle 80% of comparable runs. `basis` is `history` when enough earlier
+/// runs exist, `blended` when a few do, and `size` when the estimate comes
+/// from input sizes alone.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelCostEstimate {
+    pub model: String,
+    pub expected_cost: f64,
+    pub low_cost: f64,
+    pub high_cost: f64,
+    pub basis: String,
+    /// `high`, `medium` or `low`.
+    pub confidence: String,
+    pub sample_runs: usize,
+}
+
+/// What running a step is likely to cost, from `estimate_step_cost`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StepCostEstimate {
+    pub skill_name: String,
+    pub step_id: u32,
+    pub prompt_template_bytes: u64,
+    /// `context/` plus `user-context.md`.
+    pub context_bytes: u64,
+    /// `SKILL.md` plus `references/` in the skills path.
+    pub skill_bytes: u64,
+    /// Rough token count of everything above (4 bytes per token).
+    pub input_tokens: i64,
+    pub estimates: Vec<ModelCostEstimate>,
+}
+
+// ─── Skill duplication types ─────────────────────────────────────────────────
+
+/// What `duplicate_skill` copies besides SKILL.md.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DuplicateSkillOptions {
+    /// Paths under `references/` to copy; `None` copies all of them.
+    #[serde(default)]
+    pub references: Option<Vec<String>>,
+    #[serde(default = "default_true")]
+    pub copy_tags: bool,
+    #[serde(default = "default_true")]
+    pub copy_intake: bool,
+    /// Research questions and answers (`context/clarifications.json`).
+    #[serde(default)]
+    pub copy_clarifications: bool,
+    /// `context/decisions.json`; implies `copy_clarifications`.
+    #[serde(default)]
+    pub copy_decisions: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DuplicateSkillResult {
+    pub skill_name: String,
+    /// Workflow step the copy starts at.
+    pub current_step: i32,
+    pub status: String,
+    pub references: Vec<String>,
+}
+
+/// `manifest.json` at the root of a `.skillpack` archive. Skill files sit under
+/// `skill/` and workspace context under `context/`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillArchiveManifest {
+    pub format_version: u32,
+    pub skill_name: String,
+    pub app_version: String,
+    pub exported_at: String,
+    pub workflow_run: WorkflowRunRow,
+    #[serde(default)]
+    pub workflow_steps: Vec<WorkflowStepRow>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub annotations: Vec<ArtifactAnnotation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillArchiveExportResult {
+    pub file_path: String,
+    pub size_bytes: u64,
+    /// Skill and context files in the archive, excluding the manifest.
+    pub file_count: usize,
+    /// Key the archive was signed with; `None` when no signing key exists yet.
+    pub signed_key_id: Option<String>,
+}
+
+/// One skill's document in a project context bundle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectContextSkill {
+    pub skill_name: String,
+    /// Frontmatter version, else the workflow run's version.
+    pub version: Option<String>,
+    /// File name of the skill's document inside the bundle.
+    pub document: String,
+    /// Estimated tokens in the document.
+    pub tokens: usize,
+    /// True when SKILL.md sections were dropped to fit the budget.
+    pub condensed: bool,
+    pub included_references: Vec<String>,
+    pub omitted_references: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectContextPackage {
+    pub output_dir: String,
+    /// The index document listing every included skill and version.
+    pub index_file: String,
+    pub budget_tokens: usize,
+    /// Estimated tokens across all documents, index included.
+    pub total_tokens: usize,
+    pub skills: Vec<ProjectContextSkill>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillArchiveImportResult {
+    pub skill_name: String,
+    pub current_step: i32,
+    pub status: String,
+    pub file_count: usize,
+    /// "unsigned" | "trusted" | "untrusted"; tampered archives are rejected.
+    pub signature_status: String,
+}
+
+// ─── Frontmatter migration types ─────────────────────────────────────────────
+
+/// A library-wide change to SKILL.md frontmatter.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FrontmatterTransform {
+    /// Set `key`; an existing value is kept unless `overwrite` is true.
+    SetField {
+        key: String,
+        value: String,
+        #[serde(default)]
+        overwrite: bool,
+    },
+    RenameField { from: String, to: String },
+    RemoveField { key: String },
+    /// Split `description` at its trigger phrase ("Use when ...") into two keys.
+    SplitDescription {
+        #[serde(default = "default_summary_key")]
+        summary_key: String,
+        #[serde(default = "default_trigger_key")]
+        trigger_key: String,
+        #[serde(default = "default_true")]
+        keep_description: bool,
+    },
+    /// Shell command that reads SKILL.md on stdin and writes the new SKILL.md to stdout.
+    Script { command: String },
+}
+
+fn default_summary_key() -> String {
+    "summary".to_string()
+}
+
+fn default_trigger_key() -> String {
+    "trigger".to_string()
+}
+
+/// Dry-run outcome for one skill.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FrontmatterMigrationPreview {
+    pub skill_name: String,
+    pub changed: bool,
+    /// SKILL.md before and after; set only when `changed`.
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FrontmatterMigrationResult {
+    /// Commit recording the change in the skills repo; `None` when nothing changed.
+    pub commit_sha: Option<String>,
+    pub skills: Vec<String>,
+}
+
+// ─── Bulk skill operation types ──────────────────────────────────────────────
+
+/// A change `bulk_update_skills` applies to every listed skill.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BulkSkillOperation {
+    AddTags { tags: Vec<String> },
+    RemoveTags { tags: Vec<String> },
+    /// Set the intake `domain` of skills built in Skill Builder; `None` clears it.
+    SetDomain { domain: Option<String> },
+    /// Move active workspace skills to `.claude/skills/.inactive`.
+    Deactivate,
+}
+
+/// What a bulk operation did, or would do, to one skill.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BulkSkillChange {
+    pub skill_name: String,
+    pub changed: bool,
+    /// e.g. `tags: a, b -> a, b, c`, or why the skill was skipped.
+    pub summary: String,
+    /// Set when the DB change committed but a follow-up file move or delete failed.
+    pub error: Option<String>,
+    /// Tags the change would set that the team taxonomy flags.
+    pub warnings: Vec<TaxonomyWarning>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BulkSkillResult {
+    pub dry_run: bool,
+    pub changes: Vec<BulkSkillChange>,
+}
+
+// ─── Linked reference types ──────────────────────────────────────────────────
+
+/// A skill reference file kept in sync with a file in a GitHub repo.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LinkedReference {
+    pub skill_name: String,
+    /// Path inside the skill directory, e.g. `references/style-guide.md`.
+    pub reference_path: String,
+    /// `owner/repo`
+    pub repo: String,
+    pub git_ref: String,
+    pub source_path: String,
+    /// Git blob SHA of the upstream content last written to disk.
+    pub synced_sha: String,
+    pub synced_at: String,
+    /// Blob SHA seen by the last check; `None` if unchecked or the file was gone.
+    pub upstream_sha: Option<String>,
+    pub checked_at: Option<String>,
+    /// `current`, `upstream_changed`, `local_modified`, `upstream_missing` or `local_missing`
+    pub drift: String,
+}
+
+/// Result of checking one linked reference against upstream. Nothing is written.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LinkedReferenceRefresh {
+    pub reference_path: String,
+    pub drift: String,
+    pub current_content: Option<String>,
+    /// Latest upstream content when it differs from the file on disk.
+    pub upstream_content: Option<String>,
+    pub upstream_sha: Option<String>,
+    pub error: Option<String>,
+}
+
+/// An upstream version the user approved, as returned by the refresh.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApprovedReferenceUpdate {
+    pub reference_path: String,
+    pub content: String,
+    pub upstream_sha: String,
+}
+
+/// A file in a skill's `references/` folder.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillReference {
+    /// Path inside the skill directory, e.g. `references/style-guide.md`.
+    pub path: String,
+    pub size_bytes: u64,
+    /// Not UTF-8 text (images and other binary files).
+    pub binary: bool,
+    /// Mentioned in SKILL.md.
+    pub indexed: bool,
+    /// Kept in sync with GitHub (see `linked_references`).
+    pub linked: bool,
+}
+
+// ─── Repo context types ──────────────────────────────────────────────────────
+
+/// A source file copied into `references/repo-context/files/`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RepoContextFile {
+    /// Path relative to the repo root, `/`-separated.
+    pub path: String,
+    pub size_bytes: u64,
+    /// Cut at the per-file limit.
+    pub truncated: bool,
+}
+
+/// Outcome of capturing a repo into a skill's references.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RepoContextResult {
+    pub files: Vec<RepoContextFile>,
+    /// Matching files left out: binary, or over the total size budget.
+    pub skipped: Vec<String>,
+    /// Reference paths written, relative to the skill directory.
+    pub reference_paths: Vec<String>,
+    /// Model that wrote the structure summary; `None` when summarizing failed.
+    pub summary_model: Option<String>,
+}
+
+// ─── Background task types ───────────────────────────────────────────────────
+
+/// Heavy job the background scheduler knows how to run.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackgroundTaskKind {
+    /// Revalidate stale marketplace catalog listings.
+    CatalogPrefetch,
+    /// Delete usage history older than the retention policy allows.
+    RetentionPrune,
+}
+
+/// Ordered lowest first, so `max` picks the most urgent task.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskPriority {
+    /// Waits while agents run or the battery is low.
+    Low,
+    /// Waits while agents run.
+    Normal,
+    /// Runs as soon as the worker is free.
+    High,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BackgroundTask {
+    pub id: u64,
+    pub kind: BackgroundTaskKind,
+    pub priority: TaskPriority,
+    /// `queued`, `running`, `completed`, `failed` or `cancelled`
+    pub status: String,
+    /// Why a queued task is waiting, from the last scheduling pass.
+    pub deferred_reason: Option<String>,
+    pub enqueued_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PowerState {
+    pub on_battery: bool,
+    pub battery_percent: Option<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BackgroundQueue {
+    /// Queued and running tasks, then recently finished ones.
+    pub tasks: Vec<BackgroundTask>,
+    pub agents_running: bool,
+    /// `None` when the platform's power state can't be read (treated as mains power).
+    pub power: Option<PowerState>,
+}
+
+// ─── Workflow queue types ────────────────────────────────────────────────────
+
+/// A workflow step queued to run unattended (see `commands::workflow_queue`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkflowQueueJob {
+    pub id: i64,
+    pub skill_name: String,
+    pub step_id: u32,
+    pub workspace_path: String,
+    /// `queued`, `running`, `completed`, `failed` or `cancelled`
+    pub status: String,
+    pub error: Option<String>,
+    pub enqueued_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+}
+
+// ─── Command history types ───────────────────────────────────────────────────
+
+/// One recorded command invocation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CommandHistoryEntry {
+    pub id: i64,
+    pub command: String,
+    /// Invocation arguments as sent by the frontend, with secret values replaced.
+    pub params: serde_json::Value,
+    /// True when any argument was redacted; such entries cannot be re-run.
+    pub redacted: bool,
+    /// `ok` or `error`.
+    pub outcome: String,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommandHistoryFilter {
+    /// Substring of the command name or parameters.
+    #[serde(default)]
+    pub query: Option<String>,
+    /// Exact command name.
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub outcome: Option<String>,
+    /// Only entries at or after this `YYYY-MM-DD HH:MM:SSZ` timestamp.
+    #[serde(default)]
+    pub since: Option<String>,
+    /// Defaults to 200, capped at 1000.
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+/// What the frontend should invoke to repeat a history entry.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CommandRerun {
+    pub command: String,
+    pub params: serde_json::Value,
+}
+
+// ─── Audit log types ─────────────────────────────────────────────────────────
+
+/// One entry of the append-only audit log (see `audit`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuditLogEntry {
+    pub id: i64,
+    pub created_at: String,
+    /// App instance that made the change.
+    pub instance_id: String,
+    /// GitHub login, else the OS user.
+    pub actor: Option<String>,
+    /// e.g. `skill.renamed`; see `audit::AUDIT_ACTIONS`.
+    pub action: String,
+    pub skill_name: Option<String>,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditLogFilter {
+    #[serde(default)]
+    pub action: Option<String>,
+    #[serde(default)]
+    pub skill_name: Option<String>,
+    #[serde(default)]
+    pub instance_id: Option<String>,
+    #[serde(default)]
+    pub actor: Option<String>,
+    /// Substring of the summary.
+    #[serde(default)]
+    pub query: Option<String>,
+    /// Only entries at or after this `YYYY-MM-DD HH:MM:SSZ` timestamp.
+    #[serde(default)]
+    pub since: Option<String>,
+    /// Only entries at or before this timestamp.
+    #[serde(default)]
+    pub until: Option<String>,
+    /// `get_audit_log` defaults to 200, capped at 1000; exports are not limited.
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuditLogExport {
+    pub output_path: String,
+    pub entry_count: usize,
+}
+
+// ─── Data retention types ────────────────────────────────────────────────────
+
+/// Rows deleted by one retention pass.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RetentionPruneResult {
+    pub agent_runs: usize,
+    pub workflow_sessions: usize,
+    pub transcripts: usize,
+    pub audit_entries: usize,
+}
+
+/// What `purge_all_user_data` removed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct UserDataPurgeResult {
+    /// Rows deleted from usage history: agent runs, their tool calls and
+    /// checkpoints, ended workflow sessions, performance history, command
+    /// history, imported skill invocations, refine sessions, test and
+    /// benchmark results and automation hook deliveries.
+    pub usage_rows: usize,
+    /// Archived agent transcripts deleted from the database.
+    pub transcripts: usize,
+    /// Transcript files deleted from `{skill}/logs/`.
+    pub transcript_files: usize,
+    /// Whether a GitHub token or git host credentials were cleared.
+    pub credentials_cleared: bool,
+}
+
+// ─── Library history types ───────────────────────────────────────────────────
+
+/// One row of the skill library audit log.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LibraryEvent {
+    pub skill_name: String,
+    /// `workspace` (installed into the workspace) or `marketplace` (imported into the skills folder).
+    pub source: String,
+    /// `installed`, `activated`, `deactivated` or `removed`.
+    pub event_type: String,
+    pub version: Option<String>,
+    pub is_active: bool,
+    pub created_at: String,
+}
+
+/// State of one skill at a past point in time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LibrarySkillState {
+    pub skill_name: String,
+    /// `built` (from git history of the skills folder), `workspace` or `marketplace`.
+    pub source: String,
+    pub version: Option<String>,
+    pub is_active: bool,
+    /// When this state began: the commit time for built skills, otherwise the audit event time.
+    pub since: String,
+}
+
+/// The skills library as it was at `at`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LibrarySnapshot {
+    pub at: String,
+    /// Commit of the skills folder the built skills were read from, if any existed then.
+    pub commit_sha: Option<String>,
+    pub commit_message: Option<String>,
+    pub skills: Vec<LibrarySkillState>,
+}
+
+// ─── Transcript types ────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TranscriptInfo {
+    pub file_name: String,
+    pub compressed: bool,
+    /// Size on disk (compressed size for `.jsonl.zst`, excluding the index).
+    pub size_bytes: u64,
+    /// Decompressed size; `None` for plain transcripts (same as `size_bytes`).
+    pub uncompressed_bytes: Option<u64>,
+    /// Number of turns from the index; `None` for plain transcripts.
+    pub turns: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TranscriptTurns {
+    pub file_name: String,
+    pub total_turns: usize,
+    pub start_turn: usize,
+    /// Messages of each returned turn; lines that are not valid JSON are
+    /// returned as strings.
+    pub turns: Vec<Vec<serde_json::Value>>,
+}
+
+/// A finished agent run's transcript from the `agent_transcripts` archive.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AgentTranscript {
+    pub agent_id: String,
+    pub skill_name: String,
+    pub message_count: usize,
+    pub tool_call_count: usize,
+    pub uncompressed_bytes: u64,
+    pub compressed_bytes: u64,
+    pub archived_at: String,
+    /// Every message of the run; thinking blocks are cut down to a summary
+    /// and lines that are not valid JSON are kept as strings.
+    pub messages: Vec<serde_json::Value>,
+}
+
+/// A message in an archived transcript that matched `search_transcripts`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TranscriptSearchHit {
+    pub agent_id: String,
+    pub skill_name: String,
+    pub archived_at: String,
+    /// Index into `AgentTranscript::messages`.
+    pub message_index: usize,
+    pub message_type: String,
+    pub snippet: String,
+}
+
+// ─── Skill impact types ──────────────────────────────────────────────────────
+
+/// Another skill whose SKILL.md or references mention this one.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillDependent {
+    pub skill_name: String,
+    /// Files (relative to the dependent skill's directory) that mention the skill.
+    pub files: Vec<String>,
+}
+
+/// A copy of the skill on disk where Claude or the app picks it up.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillInstallation {
+    /// `skills_library`, `workspace`, `workspace_inactive` or `user`.
+    pub location: String,
+    pub path: String,
+}
+
+/// A team registry whose cached `marketplace.json` listing includes the skill.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillTeamConsumer {
+    pub registry: String,
+    pub registry_name: Option<String>,
+    pub skill_path: String,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillRunStats {
+    pub days: u32,
+    pub runs: i64,
+    pub completed_runs: i64,
+    pub total_cost: f64,
+    pub last_run_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillImpactReport {
+    pub skill_name: String,
+    pub dependents: Vec<SkillDependent>,
+    pub installations: Vec<SkillInstallation>,
+    pub team_consumers: Vec<SkillTeamConsumer>,
+    pub recent_runs: SkillRunStats,
+    /// Refine sessions open on the skill in this app instance.
+    pub open_refine_sessions: usize,
+    /// Whether a running app instance (this one or another) has an open
+    /// workflow session on the skill.
+    pub active_workflow_session: bool,
+}
+
+/// Two skills whose SKILL.md content looks like copies of each other.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SimilarSkill {
+    pub skill_name: String,
+    /// "skills_library" | "workspace" | "workspace_inactive".
+    pub location: String,
+    pub similar_to: String,
+    pub similar_location: String,
+    /// Combined score in 0..=1 that the near-duplicate threshold applies to.
+    pub score: f64,
+    /// Jaccard overlap of the SKILL.md bodies' word shingles.
+    pub content_similarity: f64,
+    /// Overlap of the frontmatter descriptions; `None` unless both have one.
+    pub description_similarity: Option<f64>,
+}
+
+/// One finding from the skill lint pass.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LintIssue {
+    /// Path relative to the skill directory, e.g. `references/setup.md`.
+    pub file: String,
+    /// 1-based line number.
+    pub line: usize,
+    /// Rule id: "image-alt-missing" | "heading-first-level" | "heading-skip".
+    pub rule: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillLintReport {
+    pub skill_name: String,
+    pub files_checked: usize,
+    pub issues: Vec<LintIssue>,
+}
+
+/// One finding from skill validation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillDiagnostic {
+    /// Path relative to the skill directory, e.g. `references/setup.md`.
+    pub file: String,
+    /// 1-based line number, when the finding points at a line.
+    pub line: Option<usize>,
+    /// Rule id, e.g. "frontmatter-name" or "broken-link".
+    pub rule: String,
+    /// "error" | "warning" | "info"
+    pub severity: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillValidationReport {
+    pub skill_name: String,
+    pub files_checked: usize,
+    pub error_count: usize,
+    pub warning_count: usize,
+    /// Errors first, then warnings, then info.
+    pub diagnostics: Vec<SkillDiagnostic>,
+}
+
+/// SKILL.md as the model reads it, structured for the preview.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillPreview {
+    pub skill_name: String,
+    /// Frontmatter keys in file order.
+    pub fields: Vec<PreviewField>,
+    /// The frontmatter description, with the sentences that say when to use
+    /// the skill as `trigger` spans.
+    pub description: Vec<PreviewInline>,
+    /// The body after the frontmatter.
+    pub blocks: Vec<PreviewBlock>,
+    /// Skill files linked from the body, in order of first link.
+    pub references: Vec<ReferenceStub>,
+}
+
+/// One frontmatter key. Folded and multi-line values are joined into one line.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PreviewField {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PreviewBlock {
+    Heading { level: usize, content: Vec<PreviewInline> },
+    Paragraph { content: Vec<PreviewInline> },
+    List { ordered: bool, items: Vec<PreviewListItem> },
+    Code { language: Option<String>, text: String },
+    Quote { content: Vec<PreviewInline> },
+    Table { header: Vec<Vec<PreviewInline>>, rows: Vec<Vec<Vec<PreviewInline>>> },
+    Rule,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PreviewListItem {
+    /// Nesting depth, 0 for top-level items.
+    pub depth: usize,
+    pub content: Vec<PreviewInline>,
+}
+
+/// Inline markdown. Raw HTML is kept as literal text.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PreviewInline {
+    Text { text: String },
+    Code { text: String },
+    Strong { text: String },
+    Emphasis { text: String },
+    /// `reference` is the linked skill file relative to the skill directory,
+    /// matching a `ReferenceStub::path`; `None` for external links and anchors.
+    Link { text: String, href: String, reference: Option<String> },
+    Trigger { text: String },
+}
+
+/// What the model learns about a linked file before opening it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReferenceStub {
+    /// Path relative to the skill directory, e.g. `references/stages.md`.
+    pub path: String,
+    pub exists: bool,
+    /// First heading of a markdown file.
+    pub title: Option<String>,
+    /// First paragraph of a markdown file, truncated.
+    pub summary: Option<String>,
+    pub lines: usize,
+    pub bytes: u64,
+}
+
+/// One suspicious passage found by the import security scan.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScanFinding {
+    /// Path relative to the skill directory, e.g. `references/setup.md`.
+    pub file: String,
+    /// 1-based line number.
+    pub line: usize,
+    /// Rule id: "instruction-override" | "exfiltration-url" | "frontmatter-command" | "hidden-text".
+    pub rule: String,
+    /// "high" | "medium"
+    pub severity: String,
+    /// The offending line, trimmed and truncated.
+    pub excerpt: String,
+}
+
+/// A decision that changed since SKILL.md was last generated.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DecisionDelta {
+    pub id: String,
+    pub title: String,
+    /// "added" | "changed" | "removed"
+    pub change: String,
+    pub previous: Option<String>,
+    pub decision: Option<String>,
+}
+
+/// How step 3 would regenerate a skill if run now.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RegenerationMode {
+    /// True when only the sections affected by `changes` would be rewritten.
+    pub incremental: bool,
+    /// Why the run would rewrite SKILL.md in full; `None` when incremental.
+    pub reason: Option<String>,
+    pub changes: Vec<DecisionDelta>,
+}
+
+/// A bundled starter scaffold for `create_skill_from_template`. `{{title}}`
+/// in the description and SKILL.md is replaced with the skill's title.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillTemplate {
+    pub id: String,
+    pub name: String,
+    /// The skill purpose the template is for: domain, source, platform or data-engineering.
+    pub skill_type: String,
+    pub summary: String,
+    pub description: String,
+    pub intake_json: String,
+    pub skill_md: String,
+}
+
+/// One skill invocation found in a Claude Code session transcript.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkillInvocation {
+    /// Tool use id or message uuid; importing the same transcript twice is a no-op.
+    pub event_id: String,
+    pub skill_name: String,
+    /// "model" (the `Skill` tool) | "user" (a `/name` command).
+    pub triggered_by: String,
+    pub session_id: Option<String>,
+    pub project_path: Option<String>,
+    pub invoked_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillInvocationImport {
+    pub source_dir: String,
+    pub files_scanned: usize,
+    /// Invocations of skills known to the app, including ones imported before.
+    pub invocations_matched: usize,
+    pub invocations_added: usize,
+    /// Invocations of other skills and built-in commands, which are not stored.
+    pub invocations_unmatched: usize,
+}
+
+/// How often a skill was invoked in Claude Code. Skills never invoked are
+/// listed with zero counts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillInvocationStats {
+    pub skill_name: String,
+    pub invocations: i64,
+    pub model_invocations: i64,
+    pub user_invocations: i64,
+    pub sessions: i64,
+    pub projects: i64,
+    pub first_invoked_at: Option<String>,
+    pub last_invoked_at: Option<String>,
+}
+
+/// Show a question only when an earlier answer is one of `equals` (for
+/// multi-choice answers: contains one of them; for yes/no: `"true"` or `"false"`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IntakeCondition {
+    pub question_id: String,
+    pub equals: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IntakeQuestion {
+    pub id: String,
+    pub prompt: String,
+    pub help: Option<String>,
+    /// "text" | "long_text" | "single_choice" | "multi_choice" | "boolean"
+    pub kind: String,
+    pub options: Vec<String>,
+    pub required: bool,
+    pub show_if: Option<IntakeCondition>,
+}
+
+/// The questions a skill type's intake wizard shows for the answers so far.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IntakeQuestionnaire {
+    pub schema_version: u32,
+    pub skill_type: String,
+    /// Visible questions in order; hidden branches are left out.
+    pub questions: Vec<IntakeQuestion>,
+    /// The answers passed in, or the skill's stored intake migrated to this schema.
+    pub answers: std::collections::BTreeMap<String, serde_json::Value>,
+    /// First visible required question without an answer.
+    pub next_question_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IntakeAnswerError {
+    pub question_id: String,
+    pub message: String,
+}
+
+/// Result of `submit_intake_answers`; the intake is saved only when `errors` is empty.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IntakeSubmission {
+    pub errors: Vec<IntakeAnswerError>,
+    /// The stored intake JSON; `None` when validation failed.
+    pub intake_json: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillScanReport {
+    pub skill_name: String,
+    pub files_scanned: usize,
+    /// True when any finding is high severity.
+    pub flagged: bool,
+    /// True when the scan deactivated the skill on import.
+    pub quarantined: bool,
+    pub findings: Vec<ScanFinding>,
+    pub scanned_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessibilityFixResult {
+    pub skill_name: String,
+    pub files_changed: Vec<String>,
+    pub headings_fixed: usize,
+    pub alt_texts_added: usize,
+    /// Issues still present after the fix pass.
+    pub remaining: Vec<LintIssue>,
+    /// Commit SHA when the fixes were committed to the skills repo.
+    pub commit_sha: Option<String>,
+}
+
+// ─── Quality gate types ──────────────────────────────────────────────────────
+
+/// Size and readability limits used by the `size` and `readability` checks.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QualityBudgets {
+    #[serde(default = "default_skill_md_max_lines")]
+    pub skill_md_max_lines: usize,
+    /// SKILL.md plus reference files, in KB.
+    #[serde(default = "default_total_max_kb")]
+    pub total_max_kb: u64,
+    /// Average words per sentence in SKILL.md prose.
+    #[serde(default = "default_max_sentence_words")]
+    pub max_sentence_words: usize,
+}
+
+impl Default for QualityBudgets {
+    fn default() -> Self {
+        Self {
+            skill_md_max_lines: default_skill_md_max_lines(),
+            total_max_kb: default_total_max_kb(),
+            max_sentence_words: default_max_sentence_words(),
+        }
+    }
+}
+
+fn default_skill_md_max_lines() -> usize {
+    500
+}
+
+fn default_total_max_kb() -> u64 {
+    512
+}
+
+fn default_max_sentence_words() -> usize {
+    25
+}
+
+/// A named set of checks that must pass before a skill is packaged.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QualityGateProfile {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Enforced check ids: "lint" | "size" | "secrets" | "readability" | "compatibility".
+    /// The others still run and are reported as advisory.
+    pub checks: Vec<String>,
+    #[serde(default)]
+    pub budgets: QualityBudgets,
+}
+
+/// A recorded decision to let a failing check pass for one skill.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QualityWaiver {
+    pub skill_name: String,
+    pub check: String,
+    pub justification: String,
+    pub waived_by: Option<String>,
+    pub waived_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QualityCheckResult {
+    pub check: String,
+    /// Whether the profile enforces this check.
+    pub enforced: bool,
+    pub passed: bool,
+    pub findings: Vec<String>,
+    pub waiver: Option<QualityWaiver>,
+}
+
+/// Consolidated result of every check for one skill under one profile.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QualityGateReport {
+    pub skill_name: String,
+    pub profile: String,
+    /// False when an enforced check failed without a waiver.
+    pub passed: bool,
+    pub checks: Vec<QualityCheckResult>,
+}
+
+/// One version bump of a skill, recorded when step 3 regenerates SKILL.md
+/// or beta is promoted to stable. Returned newest first by
+/// `get_skill_changelog`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillChangelogEntry {
+    pub skill_name: String,
+    pub version: String,
+    pub previous_version: Option<String>,
+    /// "major", "minor" or "patch"; "initial" for a skill's first promotion
+    /// to the stable slot.
+    pub bump: String,
+    /// Why this bump was chosen, e.g. "removed section: Joins".
+    pub summary: String,
+    /// Commit holding the previous SKILL.md, for diffing against.
+    pub previous_sha: Option<String>,
+    pub created_at: String,
+}
+
+/// One deployment slot of a skill, from `get_skill_slots`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillSlot {
+    /// "stable" or "beta".
+    pub slot: String,
+    pub version: Option<String>,
+    pub path: String,
+    /// Last `.skill` package built for this slot, if any.
+    pub package_path: Option<String>,
+    /// When beta was last promoted into this slot (stable only).
+    pub promoted_at: Option<String>,
+}
+
+/// Stable and beta slots of a skill and which one this machine's Claude
+/// installation uses.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillSlotsStatus {
+    pub skill_name: String,
+    /// `None` until beta is first promoted.
+    pub stable: Option<SkillSlot>,
+    pub beta: SkillSlot,
+    /// Slot installed in `~/.claude/skills` on this machine, if any.
+    pub installed_slot: Option<String>,
+    pub install_path: String,
+}
+
+/// A reviewer comment anchored to a line range of a skill's context file,
+/// e.g. `context/clarifications.json`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArtifactAnnotation {
+    pub id: i64,
+    pub skill_name: String,
+    /// Path relative to the skill's workspace directory.
+    pub file: String,
+    /// 1-based, inclusive.
+    pub start_line: u32,
+    pub end_line: u32,
+    /// The annotated lines as last seen, used to find them again after edits.
+    pub anchor_text: String,
+    pub comment: String,
+    pub author: Option<String>,
+    /// True when the anchored lines could no longer be found in the file.
+    pub orphaned: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Where a workflow step run got to: its SDK session and transcript so far.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AgentRunCheckpoint {
+    pub agent_id: String,
+    pub skill_name: String,
+    pub step_id: u32,
+    /// App instance that started the run.
+    pub pid: u32,
+    /// Claude SDK session to resume, known once the agent's first message arrives.
+    pub sdk_session_id: Option<String>,
+    /// JSONL transcript of the run so far.
+    pub transcript_path: Option<String>,
+    /// Top-level assistant turns completed.
+    pub turns: u32,
+    /// "running" | "completed" | "interrupted".
+    pub status: String,
+    /// The interrupted run this one continues.
+    pub resumed_from: Option<String>,
+    pub started_at: String,
+    pub updated_at: String,
+}
+
+/// One delivery of a workflow event to an automation hook, after retries.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HookDelivery {
+    pub id: i64,
+    pub hook_name: String,
+    pub event: String,
+    pub skill_name: String,
+    /// JSON sent to the hook.
+    pub payload: String,
+    pub attempts: u32,
+    /// "delivered" | "failed".
+    pub status: String,
+    /// HTTP status or exit code, and the error of the last failed attempt.
+    pub detail: String,
+    pub created_at: String,
+}
+
+/// An earlier completed run of a workflow step whose cached outputs can be
+/// reused, from `find_cached_step_run`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CachedStepRun {
+    pub skill_name: String,
+    pub step_id: u32,
+    /// Hash of the step's inputs when the run started.
+    pub fingerprint: String,
+    /// The agent run that produced the cached outputs.
+    pub agent_id: String,
+    pub model: String,
+    /// What that run cost, i.e. what reusing it saves.
+    pub cost_usd: f64,
+    pub created_at: String,
+}
+
+/// A point-in-time capture of a skill's context, artifacts, skill files and
+/// workflow rows, from `create_workspace_snapshot`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkspaceSnapshot {
+    /// Hash of the captured content; identical captures share an id.
+    pub id: String,
+    pub skill_name: String,
+    /// Why it was taken, e.g. "before reset to step 2".
+    pub reason: String,
+    pub created_at: String,
+    /// `current_step` of the captured workflow run, if the skill had one.
+    pub current_step: Option<i32>,
+    pub file_count: usize,
+    pub total_bytes: u64,
+}
+
+/// Review status of one skill, from `get_skill_review_report`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillReviewStatus {
+    pub skill_name: String,
+    /// `YYYY-MM-DD`.
+    pub review_by: String,
+    pub reviewed_at: Option<String>,
+    /// "ok" | "due_soon" | "expired".
+    pub state: String,
+    /// Days until the review-by date; negative once it has passed.
+    pub days_remaining: i64,
+}
+
+/// One group of `list_skills_grouped`. `key` is `None` for skills without a
+/// value for the grouping (untagged, no owner, not from a marketplace, ...).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillGroup {
+    pub key: Option<String>,
+    pub skill_count: i64,
+    /// Agent run cost of the group's skills since their last reset, in USD.
+    pub total_cost: f64,
+    /// Skills past their review-by date.
+    pub stale_count: i64,
+    pub skill_names: Vec<String>,
+}
+
+/// An attachment as it will be sent with a feedback report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScrubbedAttachment {
+    pub name: String,
+    /// "image" | "text".
+    pub kind: String,
+    pub original_bytes: u64,
+    pub scrubbed_bytes: u64,
+    /// Metadata blocks dropped from an image (`EXIF`, `XMP`, `tEXt`, ...).
+    pub removed_metadata: Vec<String>,
+    /// Redaction keyword matches replaced in a text file.
+    pub redactions: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_settings_default() {
+        let settings = AppSettings::default();
+        assert!(settings.anthropic_api_key.is_none());
+        assert!(settings.workspace_path.is_none());
+        assert!(settings.skills_path.is_none());
+        assert!(settings.preferred_model.is_none());
+        assert_eq!(settings.log_level, "info");
+        assert!(!settings.extended_context);
+        assert!(!settings.extended_thinking);
+        assert!(!settings.splash_shown);
+        assert!(settings.github_oauth_token.is_none());
+        assert!(settings.github_oauth_host.is_none());
+        assert!(settings.github_user_login.is_none());
+        assert!(settings.github_user_avatar.is_none());
+        assert!(settings.github_user_email.is_none());
+        assert!(settings.marketplace_url.is_none());
+        assert!(settings.marketplace_registries.is_empty());
+        assert!(!settings.marketplace_initialized);
+        assert!(settings.industry.is_none());
+        assert!(settings.function_role.is_none());
+        assert!(settings.dashboard_view_mode.is_none());
+    }
+
+    #[test]
+    fn test_app_settings_serde_roundtrip() {
+        let settings = AppSettings {
+            anthropic_api_key: Some("sk-ant-test-key".to_string()),
+            workspace_path: Some("/home/user/skills".to_string()),
+            skills_path: Some("/home/user/output".to_string()),
+            preferred_model: Some("sonnet".to_string()),
+            debug_mode: false,
+            log_level: "info".to_string(),
+            extended_context: false,
+            extended_thinking: true,
+            interleaved_thinking_beta: true,
+            sdk_effort: None,
+            fallback_model: None,
+            refine_prompt_suggestions: true,
+            splash_shown: false,
+            github_oauth_token: Some("test-github-token".to_string()),
+            github_oauth_host: None,
+            github_user_login: Some("testuser".to_string()),
+            github_user_avatar: Some("https://avatars.githubusercontent.com/u/12345".to_string()),
+            github_user_email: Some("test@example.com".to_string()),
+            github_enterprise_url: None,
+            github_oauth_client_id: None,
+            github_ca_cert_path: None,
+            github_accept_invalid_certs: false,
+            marketplace_url: Some("https://github.com/my-org/skills".to_string()),
+            marketplace_registries: vec![MarketplaceRegistry {
+                name: "Test".to_string(),
+                source_url: "https://github.com/owner/repo".to_string(),
+                enabled: true,
+            }],
+            marketplace_initialized: false,
+            max_dimensions: 5,
+            industry: Some("Financial Services".to_string()),
+            function_role: Some("Analytics Engineer".to_string()),
+            dashboard_view_mode: Some("grid".to_string()),
+            auto_update: false,
+            team_repo: None,
+            local_model_endpoint: None,
+            local_model: None,
+            activation_policy: Default::default(),
+            accessibility_lint: "advisory".to_string(),
+            block_expired_packaging: false,
+            require_trusted_packages: false,
+            workflow_queue_concurrency: 1,
+            max_concurrent_agents: 3,
+            quality_gate_profile: None,
+            global_budget_usd: None,
+            skill_budgets_usd: Default::default(),
+            feedback_redaction_keywords: vec![],
+            feedback_public_key: None,
+            custom_prompts_dir: None,
+            offline_mode: false,
+            disable_marketplace: false,
+            disable_github: false,
+            automation_hooks: vec![],
+            transcript_archive_mb: 256,
+            retention: Default::default(),
+            claude_data_dir: None,
+            settings_sync_repo: None,
+            settings_sync_local_overrides: vec![],
+            model_routes: vec![],
+        };
+        let json = serde_json::to_string(&settings).unwrap();
+        let deserialized: AppSettings = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            deserialized.anthropic_api_key.as_deref(),
+            Some("sk-ant-test-key")
+        );
+        assert_eq!(
+            deserialized.workspace_path.as_deref(),
+            Some("/home/user/skills")
+        );
+        assert_eq!(
+            deserialized.skills_path.as_deref(),
+            Some("/home/user/output")
+        );
+        assert_eq!(
+            deserialized.preferred_model.as_deref(),
+            Some("sonnet")
+        );
         assert_eq!(
             deserialized.marketplace_url.as_deref(),
             Some("https://github.com/my-org/skills")
@@ -931,6 +3446,10 @@ mod tests {
             agent_name: Some("research-entities".to_string()),
             required_plugins: None,
             conversation_history: None,
+            resume: None,
+            bash_sandbox: None,
+            disallowed_tools: None,
+            local_model: None,
         };
         let json = serde_json::to_string(&config).unwrap();
         assert!(json.contains("\"apiKey\""));
@@ -945,3 +3464,41 @@ mod tests {
         assert!(!json.contains("\"thinking\""));
     }
 }
+
+// ─── Settings sync types ─────────────────────────────────────────────────────
+
+/// Shared settings as stored in the settings sync repo.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SettingsSyncDocument {
+    pub version: u32,
+    pub exported_at: String,
+    /// GitHub login of whoever pushed the document, when signed in.
+    pub exported_by: Option<String>,
+    /// Shared `AppSettings` fields by name, plus `tool_policy` for the global tool policy.
+    pub settings: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SettingsSyncResult {
+    /// Commit pushed to the sync repo; `None` when nothing changed or on pull.
+    pub commit_sha: Option<String>,
+    /// Shared fields applied over the local values.
+    pub applied: Vec<String>,
+    /// Shared fields that differ from the local values but are local overrides.
+    pub kept_local: Vec<String>,
+}
+
+// ─── Model routing types ─────────────────────────────────────────────────────
+
+/// The model a workflow step will run on, and why.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EffectiveModel {
+    pub step_id: u32,
+    /// Full model id passed to the SDK.
+    pub model: String,
+    /// "route" (a model route matched), "agent" (the step agent's front
+    /// matter) or "preferred" (the preferred model in settings).
+    pub source: String,
+    /// The matching route when `source` is "route".
+    pub route: Option<ModelRoute>,
+}
@@ -28,6 +28,24 @@ describe("AgentStatusHeader", () => {
     expect(screen.getByText("Sonnet")).toBeInTheDocument();
   });
 
+  it("shows turn limit, current tool and ETA from step progress", () => {
+    useAgentStore.getState().startRun("test-agent", "sonnet");
+    useAgentStore.getState().recordStepProgress("test-agent", {
+      turnsCompleted: 4,
+      maxTurns: 50,
+      currentTool: "Read",
+      tokensUsed: 18000,
+      percent: 25,
+      etaMs: 90_900,
+      receivedAt: Date.now(),
+    });
+    render(<AgentStatusHeader agentId="test-agent" />);
+
+    expect(screen.getByText("Turn 4/50")).toBeInTheDocument();
+    expect(screen.getByText("Read")).toBeInTheDocument();
+    expect(screen.getByText(/25% · ~1m 30s left/)).toBeInTheDocument();
+  });
+
   it("shows Initializing badge with spinner when run has no messages", () => {
     useAgentStore.getState().startRun("test-agent", "sonnet");
     render(<AgentStatusHeader agentId="test-agent" />);
@@ -4,6 +4,7 @@ import userEvent from "@testing-library/user-event";
 import {
   mockInvoke,
   mockInvokeCommands,
+  mockListen,
   resetTauriMocks,
 } from "@/test/mocks/tauri";
 import { toast } from "sonner";
@@ -431,6 +432,33 @@ describe("AppLayout", () => {
     // splash dismisses, so it never mounts)
   });
 
+  it("reloads the settings store when the backend reports a settings change", async () => {
+    let onChange!: (event: { payload: { changed: string[] } }) => void;
+    mockListen.mockImplementation(((event: string, cb: typeof onChange) => {
+      if (event === "settings-changed") onChange = cb;
+      return Promise.resolve(() => {});
+    }) as never);
+    mockInvokeCommands({
+      get_settings: defaultSettings,
+      reconcile_startup: emptyReconciliation,
+    });
+
+    render(<AppLayout />);
+    await waitFor(() => {
+      expect(useSettingsStore.getState().skillsPath).toBe("/home/user/skills");
+    });
+
+    mockInvokeCommands({
+      get_settings: { ...defaultSettings, skills_path: "/mnt/shared/skills" },
+      reconcile_startup: emptyReconciliation,
+    });
+    onChange({ payload: { changed: ["skills_path"] } });
+
+    await waitFor(() => {
+      expect(useSettingsStore.getState().skillsPath).toBe("/mnt/shared/skills");
+    });
+  });
+
   describe("marketplace update toasts", () => {
     const marketplaceSettings = {
       ...defaultSettings,
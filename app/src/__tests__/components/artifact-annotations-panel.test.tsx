@@ -0,0 +1,94 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+import { render, screen, waitFor } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { mockInvoke, resetTauriMocks } from "@/test/mocks/tauri";
+import { ArtifactAnnotationsPanel } from "@/components/artifact-annotations-panel";
+import type { ArtifactAnnotation } from "@/lib/types";
+
+vi.mock("sonner", () => ({
+  toast: { success: vi.fn(), error: vi.fn(), info: vi.fn() },
+}));
+
+const CONTENT = "# Decisions\n\n### D1: Revenue\nUse net revenue.\n";
+
+function annotation(overrides: Partial<ArtifactAnnotation> = {}): ArtifactAnnotation {
+  return {
+    id: 1,
+    skill_name: "sales",
+    file: "context/decisions.md",
+    start_line: 3,
+    end_line: 4,
+    anchor_text: "### D1: Revenue\nUse net revenue.",
+    comment: "Gross or net?",
+    author: "alice",
+    orphaned: false,
+    created_at: "2026-10-01T00:00:00Z",
+    updated_at: "2026-10-01T00:00:00Z",
+    ...overrides,
+  };
+}
+
+function renderPanel() {
+  return render(
+    <ArtifactAnnotationsPanel skillName="sales" workspacePath="/ws" file="context/decisions.md" content={CONTENT} />,
+  );
+}
+
+describe("ArtifactAnnotationsPanel", () => {
+  beforeEach(() => {
+    resetTauriMocks();
+    mockInvoke.mockImplementation((cmd: string, args?: Record<string, unknown>) => {
+      if (cmd === "list_artifact_annotations") return Promise.resolve([annotation({ orphaned: true })]);
+      if (cmd === "add_artifact_annotation") {
+        return Promise.resolve(
+          annotation({ id: 2, start_line: args?.startLine as number, end_line: args?.endLine as number, comment: args?.comment as string }),
+        );
+      }
+      return Promise.resolve(undefined);
+    });
+  });
+
+  it("lists annotations for the file and flags outdated ones", async () => {
+    const user = userEvent.setup();
+    renderPanel();
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("list_artifact_annotations", {
+        skillName: "sales",
+        file: "context/decisions.md",
+        workspacePath: "/ws",
+      });
+    });
+    await user.click(screen.getByRole("button", { name: /Annotations/ }));
+
+    expect(screen.getByText("Gross or net?")).toBeInTheDocument();
+    expect(screen.getByText("Outdated")).toBeInTheDocument();
+  });
+
+  it("adds a comment on a shift-click line range", async () => {
+    const user = userEvent.setup();
+    renderPanel();
+    await user.click(screen.getByRole("button", { name: /Annotations/ }));
+
+    await user.click(screen.getByTestId("annotation-line-1"));
+    await user.keyboard("{Shift>}");
+    await user.click(screen.getByTestId("annotation-line-3"));
+    await user.keyboard("{/Shift}");
+    expect(screen.getByText("Lines 1–3")).toBeInTheDocument();
+
+    await user.type(screen.getByPlaceholderText("Comment on the selected lines"), "Missing context");
+    await user.click(screen.getByRole("button", { name: /Add comment/ }));
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("add_artifact_annotation", {
+        skillName: "sales",
+        file: "context/decisions.md",
+        startLine: 1,
+        endLine: 3,
+        comment: "Missing context",
+        workspacePath: "/ws",
+      });
+    });
+    expect(await screen.findByText("Missing context")).toBeInTheDocument();
+  });
+});
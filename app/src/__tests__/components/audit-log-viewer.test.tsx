@@ -0,0 +1,88 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+import { render, screen, waitFor } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { toast } from "sonner";
+import { mockInvoke, resetTauriMocks } from "@/test/mocks/tauri";
+import { AuditLogViewer } from "@/components/audit-log-viewer";
+import type { AuditLogEntry } from "@/lib/types";
+
+vi.mock("sonner", () => ({
+  toast: { success: vi.fn(), error: vi.fn(), info: vi.fn() },
+}));
+
+const entries: AuditLogEntry[] = [
+  {
+    id: 2,
+    created_at: "2026-10-01 10:05:00Z",
+    instance_id: "inst-1",
+    actor: "octocat",
+    action: "skill.renamed",
+    skill_name: "revenue-recognition",
+    summary: "renamed from revenue",
+  },
+  {
+    id: 1,
+    created_at: "2026-10-01 10:00:00Z",
+    instance_id: "inst-1",
+    actor: null,
+    action: "settings.changed",
+    skill_name: null,
+    summary: "log_level",
+  },
+];
+
+describe("AuditLogViewer", () => {
+  beforeEach(() => {
+    resetTauriMocks();
+    mockInvoke.mockImplementation((cmd: string) => {
+      if (cmd === "get_audit_log") return Promise.resolve(entries);
+      if (cmd === "export_audit_log") {
+        return Promise.resolve({ output_path: "/ws/exports/audit-log.csv", entry_count: 2 });
+      }
+      return Promise.resolve(undefined);
+    });
+  });
+
+  it("lists entries and refetches when the action filter changes", async () => {
+    const user = userEvent.setup();
+    render(<AuditLogViewer workspacePath="/ws" />);
+
+    const rows = await screen.findAllByTestId("audit-entry");
+    expect(rows).toHaveLength(2);
+    expect(rows[0]).toHaveTextContent("renamed from revenue");
+    expect(rows[0]).toHaveTextContent("octocat");
+    expect(rows[1]).toHaveTextContent("unknown");
+
+    await user.selectOptions(screen.getByLabelText("Action"), "skill.renamed");
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("get_audit_log", {
+        filter: { action: "skill.renamed", skill_name: null, query: null },
+      });
+    });
+  });
+
+  it("exports the filtered entries as CSV", async () => {
+    const user = userEvent.setup();
+    render(<AuditLogViewer workspacePath="/ws" />);
+    await screen.findAllByTestId("audit-entry");
+
+    await user.type(screen.getByLabelText("Skill"), "revenue-recognition");
+    await user.click(screen.getByRole("button", { name: /Export CSV/ }));
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("export_audit_log", {
+        workspacePath: "/ws",
+        filter: { action: null, skill_name: "revenue-recognition", query: null },
+        format: "csv",
+        outputPath: null,
+      });
+    });
+    expect(toast.success).toHaveBeenCalledWith("Exported 2 entries to /ws/exports/audit-log.csv");
+  });
+
+  it("disables export without a workspace", async () => {
+    render(<AuditLogViewer workspacePath={null} />);
+    await screen.findAllByTestId("audit-entry");
+    expect(screen.getByRole("button", { name: /Export JSONL/ })).toBeDisabled();
+  });
+});
@@ -0,0 +1,77 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+import { render, screen, waitFor } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { mockInvoke, resetTauriMocks } from "@/test/mocks/tauri";
+import { AutomationHooksSettings } from "@/components/automation-hooks-settings";
+import type { AutomationHook, HookDelivery } from "@/lib/types";
+
+const slack: AutomationHook = {
+  name: "slack",
+  events: ["skill_packaged"],
+  kind: "webhook",
+  target: "https://hooks.example.com/T0",
+  enabled: true,
+};
+
+const deliveries: HookDelivery[] = [
+  {
+    id: 2,
+    hook_name: "slack",
+    event: "skill_packaged",
+    skill_name: "sales",
+    payload: "{}",
+    attempts: 3,
+    status: "failed",
+    detail: "HTTP 500 Internal Server Error",
+    created_at: "2026-10-16T00:00:00Z",
+  },
+];
+
+describe("AutomationHooksSettings", () => {
+  beforeEach(() => {
+    resetTauriMocks();
+    mockInvoke.mockImplementation((cmd: string) => {
+      if (cmd === "list_hook_deliveries") return Promise.resolve(deliveries);
+      return Promise.resolve(undefined);
+    });
+  });
+
+  it("lists hooks and recent deliveries", async () => {
+    render(<AutomationHooksSettings hooks={[slack]} onChange={vi.fn()} />);
+
+    expect(screen.getByText("https://hooks.example.com/T0")).toBeInTheDocument();
+    expect(await screen.findByText("HTTP 500 Internal Server Error")).toBeInTheDocument();
+    expect(screen.getByText(/3 attempts/)).toBeInTheDocument();
+  });
+
+  it("adds a command hook only once it is complete", async () => {
+    const user = userEvent.setup();
+    const onChange = vi.fn();
+    render(<AutomationHooksSettings hooks={[slack]} onChange={onChange} />);
+
+    const add = screen.getByRole("button", { name: /Add hook/i });
+    await user.type(screen.getByLabelText("Hook name"), "slack");
+    await user.selectOptions(screen.getByLabelText("Hook kind"), "command");
+    await user.type(screen.getByLabelText("Hook target"), "./notify.sh");
+    expect(add).toBeDisabled();
+    expect(screen.getByText(/already exists/)).toBeInTheDocument();
+
+    await user.clear(screen.getByLabelText("Hook name"));
+    await user.type(screen.getByLabelText("Hook name"), "notify");
+    await user.click(screen.getByLabelText("Step completed"));
+    await user.click(add);
+
+    await waitFor(() => {
+      expect(onChange).toHaveBeenCalledWith([
+        slack,
+        {
+          name: "notify",
+          kind: "command",
+          target: "./notify.sh",
+          events: ["skill_packaged", "step_completed"],
+          enabled: true,
+        },
+      ]);
+    });
+  });
+});
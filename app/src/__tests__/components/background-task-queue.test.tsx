@@ -0,0 +1,63 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+import { render, screen, waitFor } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { mockInvoke, resetTauriMocks } from "@/test/mocks/tauri";
+import { BackgroundTaskQueue } from "@/components/background-task-queue";
+import type { BackgroundQueue } from "@/lib/types";
+
+vi.mock("sonner", () => ({
+  toast: { success: vi.fn(), error: vi.fn(), info: vi.fn() },
+}));
+
+const queue: BackgroundQueue = {
+  agents_running: true,
+  power: { on_battery: true, battery_percent: 15 },
+  tasks: [
+    {
+      id: 3,
+      kind: "catalog_prefetch",
+      priority: "low",
+      status: "queued",
+      deferred_reason: "Waiting for running agents to finish",
+      enqueued_at: "2026-01-01T00:00:00Z",
+      started_at: null,
+      finished_at: null,
+      error: null,
+    },
+  ],
+};
+
+describe("BackgroundTaskQueue", () => {
+  beforeEach(() => {
+    resetTauriMocks();
+    mockInvoke.mockImplementation((cmd: string) => {
+      if (cmd === "list_background_tasks") return Promise.resolve(queue);
+      return Promise.resolve(undefined);
+    });
+  });
+
+  it("shows queued tasks with why they are waiting", async () => {
+    render(<BackgroundTaskQueue />);
+
+    expect(await screen.findByText("Marketplace catalog refresh")).toBeInTheDocument();
+    expect(screen.getByText("Waiting for running agents to finish")).toBeInTheDocument();
+    expect(screen.getByText("Agents running")).toBeInTheDocument();
+    expect(screen.getByText("Power: battery (15%)")).toBeInTheDocument();
+  });
+
+  it("reprioritizes, cancels and queues tasks", async () => {
+    const user = userEvent.setup();
+    render(<BackgroundTaskQueue />);
+
+    await user.selectOptions(await screen.findByLabelText("Priority for task 3"), "high");
+    expect(mockInvoke).toHaveBeenCalledWith("set_background_task_priority", { taskId: 3, priority: "high" });
+
+    await user.click(screen.getByRole("button", { name: "Cancel task 3" }));
+    expect(mockInvoke).toHaveBeenCalledWith("cancel_background_task", { taskId: 3 });
+
+    await user.click(screen.getByRole("button", { name: /Refresh catalogs now/ }));
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("enqueue_background_task", { kind: "catalog_prefetch", priority: "high" });
+    });
+  });
+});
@@ -0,0 +1,78 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+import { render, screen, waitFor } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { mockInvoke, resetTauriMocks } from "@/test/mocks/tauri";
+import { BashSandboxSettings } from "@/components/bash-sandbox-settings";
+import type { ToolPolicy, ToolPolicyView } from "@/lib/types";
+
+vi.mock("sonner", () => ({
+  toast: { success: vi.fn(), error: vi.fn(), info: vi.fn() },
+}));
+
+const global: ToolPolicy = {
+  bash_sandbox: {
+    enabled: true,
+    allow_patterns: ["ls", "cat *"],
+    deny_patterns: ["sudo"],
+    allow_network: false,
+  },
+};
+
+const view = (skillOverride: ToolPolicy | null): ToolPolicyView => ({
+  global,
+  skill_override: skillOverride,
+  effective: skillOverride ?? global,
+});
+
+describe("BashSandboxSettings", () => {
+  beforeEach(() => {
+    resetTauriMocks();
+    mockInvoke.mockImplementation((cmd: string, args?: { policy?: ToolPolicy | null }) => {
+      if (cmd === "get_tool_policy") return Promise.resolve(view(null));
+      if (cmd === "set_tool_policy") return Promise.resolve(view(args?.policy ?? null));
+      return Promise.resolve(undefined);
+    });
+  });
+
+  it("saves edited patterns to the global policy", async () => {
+    const user = userEvent.setup();
+    render(<BashSandboxSettings />);
+
+    const allow = await screen.findByLabelText(/Allowed commands/);
+    expect(allow).toHaveValue("ls\ncat *");
+    await user.type(allow, "\n  git status  \n");
+    await user.click(screen.getByRole("button", { name: /Save patterns/ }));
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("set_tool_policy", {
+        skillName: null,
+        policy: {
+          bash_sandbox: {
+            enabled: true,
+            allow_patterns: ["ls", "cat *", "git status"],
+            deny_patterns: ["sudo"],
+            allow_network: false,
+          },
+        },
+      });
+    });
+  });
+
+  it("shows an inherited skill policy and can revert an override", async () => {
+    const user = userEvent.setup();
+    render(<BashSandboxSettings skillName="my-skill" />);
+
+    expect(await screen.findByText(/Using the global policy/)).toBeInTheDocument();
+    expect(screen.queryByRole("button", { name: "Use global policy" })).not.toBeInTheDocument();
+
+    await user.click(screen.getByLabelText("Allow network commands"));
+    await waitFor(() => {
+      expect(screen.queryByText(/Using the global policy/)).not.toBeInTheDocument();
+    });
+
+    await user.click(screen.getByRole("button", { name: "Use global policy" }));
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("set_tool_policy", { skillName: "my-skill", policy: null });
+    });
+  });
+});
@@ -0,0 +1,56 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+import { render, screen, waitFor } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { mockInvoke, resetTauriMocks } from "@/test/mocks/tauri";
+import { DataRetentionSettings, KEEP_FOREVER } from "@/components/data-retention-settings";
+import { useAuthStore } from "@/stores/auth-store";
+
+vi.mock("sonner", () => ({
+  toast: { success: vi.fn(), error: vi.fn(), info: vi.fn() },
+}));
+
+describe("DataRetentionSettings", () => {
+  beforeEach(() => {
+    resetTauriMocks();
+    useAuthStore.setState({ isLoggedIn: true });
+  });
+
+  it("saves a changed retention period on blur", async () => {
+    const user = userEvent.setup();
+    const onChange = vi.fn();
+    render(<DataRetentionSettings policy={KEEP_FOREVER} workspacePath="/ws" onChange={onChange} />);
+
+    const runs = screen.getByLabelText("Agent runs (days)");
+    await user.clear(runs);
+    await user.type(runs, "90");
+    await user.tab();
+
+    expect(onChange).toHaveBeenCalledWith({ ...KEEP_FOREVER, agent_runs_days: 90 });
+  });
+
+  it("does not save when nothing changed", async () => {
+    const user = userEvent.setup();
+    const onChange = vi.fn();
+    render(<DataRetentionSettings policy={KEEP_FOREVER} workspacePath="/ws" onChange={onChange} />);
+
+    await user.click(screen.getByLabelText("Audit log (days)"));
+    await user.tab();
+
+    expect(onChange).not.toHaveBeenCalled();
+  });
+
+  it("purges user data after confirmation and signs out", async () => {
+    const user = userEvent.setup();
+    mockInvoke.mockResolvedValue({ usage_rows: 12, transcripts: 3, transcript_files: 4, credentials_cleared: true });
+    render(<DataRetentionSettings policy={KEEP_FOREVER} workspacePath="/ws" onChange={vi.fn()} />);
+
+    await user.click(screen.getByRole("button", { name: /Purge user data/ }));
+    expect(mockInvoke).not.toHaveBeenCalled();
+    await user.click(screen.getByRole("button", { name: "Purge" }));
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("purge_all_user_data", { workspacePath: "/ws" });
+    });
+    await waitFor(() => expect(useAuthStore.getState().isLoggedIn).toBe(false));
+  });
+});
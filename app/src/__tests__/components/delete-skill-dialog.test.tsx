@@ -11,7 +11,7 @@ vi.mock("sonner", () => ({
 }));
 
 import DeleteSkillDialog from "@/components/delete-skill-dialog";
-import type { SkillSummary } from "@/lib/types";
+import type { SkillImpactReport, SkillSummary } from "@/lib/types";
 
 const sampleSkill: SkillSummary = {
   name: "sales-pipeline",
@@ -283,4 +283,45 @@ describe("DeleteSkillDialog", () => {
       screen.getByText(/permanently remove all files/)
     ).toBeInTheDocument();
   });
+
+  // --- impact analysis ---
+
+  it("lists what else the skill affects before confirming", async () => {
+    const impact: SkillImpactReport = {
+      skill_name: "sales-pipeline",
+      dependents: [{ skill_name: "revenue-report", files: ["SKILL.md"] }],
+      installations: [
+        { location: "skills_library", path: "/skills/sales-pipeline" },
+        { location: "workspace", path: "/workspace/.claude/skills/sales-pipeline" },
+      ],
+      team_consumers: [{ registry: "acme/skills@main", registry_name: "Acme", skill_path: "skills/sales-pipeline", version: "1.0.0" }],
+      recent_runs: { days: 30, runs: 3, completed_runs: 2, total_cost: 1.5, last_run_at: null },
+      open_refine_sessions: 1,
+      active_workflow_session: false,
+    };
+    mockInvoke.mockImplementation((cmd: string) =>
+      cmd === "analyze_skill_impact" ? Promise.resolve(impact) : Promise.resolve(undefined),
+    );
+
+    render(
+      <DeleteSkillDialog
+        skill={sampleSkill}
+        workspacePath="/workspace"
+        open={true}
+        onOpenChange={vi.fn()}
+        onDeleted={vi.fn()}
+      />
+    );
+
+    expect(await screen.findByText("Referenced by revenue-report")).toBeInTheDocument();
+    expect(mockInvoke).toHaveBeenCalledWith("analyze_skill_impact", {
+      workspacePath: "/workspace",
+      skillName: "sales-pipeline",
+    });
+    expect(screen.getByText("Installed in workspace (active)")).toBeInTheDocument();
+    expect(screen.getByText("Published in team registries: Acme")).toBeInTheDocument();
+    expect(screen.getByText("3 agent runs in the last 30 days")).toBeInTheDocument();
+    expect(screen.getByText("1 open refine session")).toBeInTheDocument();
+    expect(screen.queryByText("A workflow session is open")).not.toBeInTheDocument();
+  });
 });
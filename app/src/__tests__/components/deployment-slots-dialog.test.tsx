@@ -0,0 +1,75 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+import { render, screen, waitFor, within } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { toast } from "sonner";
+import { mockInvoke, resetTauriMocks } from "@/test/mocks/tauri";
+import DeploymentSlotsDialog from "@/components/deployment-slots-dialog";
+import type { SkillSlotsStatus, SkillSummary } from "@/lib/types";
+
+vi.mock("sonner", () => ({
+  toast: { success: vi.fn(), error: vi.fn(), info: vi.fn() },
+}));
+
+const skill = { name: "sales" } as SkillSummary;
+
+function slots(overrides: Partial<SkillSlotsStatus> = {}): SkillSlotsStatus {
+  return {
+    skill_name: "sales",
+    stable: {
+      slot: "stable",
+      version: "1.0.0",
+      path: "/skills/.slots/sales/stable",
+      package_path: null,
+      promoted_at: "2026-10-01T00:00:00Z",
+    },
+    beta: { slot: "beta", version: "1.1.0", path: "/skills/sales", package_path: null, promoted_at: null },
+    installed_slot: "stable",
+    install_path: "/home/me/.claude/skills/sales",
+    ...overrides,
+  };
+}
+
+describe("DeploymentSlotsDialog", () => {
+  beforeEach(() => {
+    resetTauriMocks();
+    vi.mocked(toast.success).mockReset();
+    mockInvoke.mockImplementation((cmd: string) => {
+      if (cmd === "get_skill_slots") return Promise.resolve(slots());
+      if (cmd === "install_skill_slot") return Promise.resolve(slots({ installed_slot: "beta" }));
+      if (cmd === "promote_beta_to_stable") {
+        return Promise.resolve({ skill_name: "sales", version: "1.1.0", bump: "minor" });
+      }
+      return Promise.resolve(undefined);
+    });
+  });
+
+  it("switches this machine's install from stable to beta", async () => {
+    const user = userEvent.setup();
+    render(<DeploymentSlotsDialog skill={skill} open onOpenChange={() => {}} />);
+
+    const stable = await screen.findByTestId("slot-stable");
+    expect(within(stable).getByText("Installed")).toBeInTheDocument();
+    expect(within(stable).getByRole("button", { name: /Switch/ })).toBeDisabled();
+
+    await user.click(within(screen.getByTestId("slot-beta")).getByRole("button", { name: /Switch/ }));
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("install_skill_slot", { skillName: "sales", slot: "beta" });
+    });
+    expect(await within(screen.getByTestId("slot-beta")).findByText("Installed")).toBeInTheDocument();
+  });
+
+  it("promotes beta to stable and reports the new version", async () => {
+    const user = userEvent.setup();
+    const onPromoted = vi.fn();
+    render(<DeploymentSlotsDialog skill={skill} open onOpenChange={() => {}} onPromoted={onPromoted} />);
+
+    await user.click(await screen.findByRole("button", { name: /Promote beta to stable/ }));
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("promote_beta_to_stable", { skillName: "sales" });
+    });
+    expect(toast.success).toHaveBeenCalledWith("Promoted sales to stable v1.1.0");
+    await waitFor(() => expect(onPromoted).toHaveBeenCalled());
+  });
+});
@@ -102,6 +102,32 @@ describe("SkillDialog (edit mode)", () => {
     expect(screen.getByText("crm")).toBeInTheDocument();
   });
 
+  it("checks tags against the team taxonomy and shows its warnings", async () => {
+    mockInvoke.mockImplementation((cmd: string) =>
+      cmd === "check_skill_tags"
+        ? Promise.resolve([
+            { skill_name: "sales-pipeline", field: "tag", value: "crm", message: "Tag is not in the team vocabulary" },
+          ])
+        : Promise.resolve(undefined)
+    );
+    render(
+      <SkillDialog
+        mode="edit"
+        skill={sampleSkill}
+        open={true}
+        onOpenChange={vi.fn()}
+        onSaved={vi.fn()}
+        tagSuggestions={[]}
+      />
+    );
+
+    expect(await screen.findByTestId("tag-warnings")).toHaveTextContent("crm: Tag is not in the team vocabulary");
+    expect(mockInvoke).toHaveBeenCalledWith("check_skill_tags", {
+      skillName: "sales-pipeline",
+      tags: ["analytics", "crm"],
+    });
+  });
+
   it("has Cancel and Next buttons on step 1", () => {
     render(
       <SkillDialog
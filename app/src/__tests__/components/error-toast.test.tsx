@@ -0,0 +1,76 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+import { waitFor } from "@testing-library/react";
+import { toast } from "sonner";
+import { mockInvoke, resetTauriMocks } from "@/test/mocks/tauri";
+import { toastCommandError } from "@/components/error-toast";
+
+vi.mock("sonner", () => ({
+  toast: { success: vi.fn(), error: vi.fn(), info: vi.fn() },
+}));
+
+const mockNavigate = vi.fn();
+vi.mock("@/router", () => ({
+  router: { navigate: (opts: unknown) => mockNavigate(opts) },
+}));
+
+describe("toastCommandError", () => {
+  beforeEach(() => {
+    resetTauriMocks();
+    vi.mocked(toast.error).mockClear();
+    mockNavigate.mockClear();
+  });
+
+  it("adds remediation steps and a settings link for known errors", async () => {
+    mockInvoke.mockResolvedValue({
+      code: "api_key_missing",
+      title: "Anthropic API key is not set",
+      message: "API key not configured",
+      steps: ["Open Settings > General and paste your Anthropic API key."],
+      settings_tab: "general",
+    });
+
+    await toastCommandError("Failed to start agent", new Error("API key not configured"));
+
+    expect(mockInvoke).toHaveBeenCalledWith("get_error_help", { error: "API key not configured" });
+    const [message, options] = vi.mocked(toast.error).mock.calls[0];
+    expect(message).toBe("Failed to start agent: API key not configured");
+    expect(options?.description).toBeDefined();
+    const action = options?.action as { label: string; onClick: () => void };
+    expect(action.label).toBe("Open Settings");
+
+    action.onClick();
+    await waitFor(() => {
+      expect(mockNavigate).toHaveBeenCalledWith({ to: "/settings", search: { tab: "general" } });
+    });
+  });
+
+  it("shows the plain message when no help applies", async () => {
+    mockInvoke.mockResolvedValue({
+      code: "unknown",
+      title: "Something went wrong",
+      message: "boom",
+      steps: ["Retry the action."],
+      settings_tab: null,
+    });
+
+    await toastCommandError("Export failed", "boom", { id: "export" });
+
+    expect(toast.error).toHaveBeenCalledWith("Export failed: boom", {
+      id: "export",
+      duration: Infinity,
+      description: undefined,
+      action: undefined,
+    });
+  });
+
+  it("still shows the error when the help lookup fails", async () => {
+    mockInvoke.mockRejectedValue(new Error("ipc down"));
+
+    await toastCommandError("Import failed", "conflict_overwrite_required:my-skill");
+
+    expect(toast.error).toHaveBeenCalledWith(
+      "Import failed: conflict_overwrite_required:my-skill",
+      expect.objectContaining({ duration: Infinity }),
+    );
+  });
+});
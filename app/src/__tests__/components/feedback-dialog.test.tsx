@@ -18,7 +18,7 @@ vi.mock("@tauri-apps/api/app", () => ({
   getVersion: vi.fn(() => Promise.resolve("1.2.3")),
 }));
 
-const { mockStartAgent, mockGetWorkspacePath, mockCreateGithubIssue } = vi.hoisted(() => ({
+const { mockStartAgent, mockGetWorkspacePath, mockCreateGithubIssue, mockRunDiagnostics } = vi.hoisted(() => ({
   mockStartAgent: vi.fn<(...args: unknown[]) => Promise<string>>(() =>
     Promise.resolve("feedback-123"),
   ),
@@ -28,12 +28,22 @@ const { mockStartAgent, mockGetWorkspacePath, mockCreateGithubIssue } = vi.hoist
   mockCreateGithubIssue: vi.fn<(request: unknown) => Promise<{ url: string; number: number }>>(() =>
     Promise.resolve({ url: "https://github.com/hbanerjee74/skill-builder/issues/42", number: 42 }),
   ),
+  mockRunDiagnostics: vi.fn(() =>
+    Promise.resolve({
+      generated_at: "2026-10-16T00:00:00Z",
+      app_version: "1.2.3",
+      os: "macos-aarch64",
+      all_ok: true,
+      checks: [{ name: "Git", ok: true, detail: "git version 2.44" }],
+    }),
+  ),
 }));
 
 vi.mock("@/lib/tauri", () => ({
   startAgent: mockStartAgent,
   getWorkspacePath: mockGetWorkspacePath,
   createGithubIssue: mockCreateGithubIssue,
+  runDiagnostics: mockRunDiagnostics,
   persistAgentRun: vi.fn(() => Promise.resolve()),
   githubGetUser: vi.fn(() => Promise.resolve(null)),
   githubLogout: vi.fn(),
@@ -149,6 +159,7 @@ describe("FeedbackDialog", () => {
       url: "https://github.com/hbanerjee74/skill-builder/issues/42",
       number: 42,
     });
+    mockRunDiagnostics.mockClear();
     vi.mocked(toast.success).mockReset();
     vi.mocked(toast.warning).mockReset();
     vi.mocked(toast.error).mockReset();
@@ -349,6 +360,39 @@ describe("FeedbackDialog", () => {
     expect(request.labels).toContain("crash");
   });
 
+  it("attaches a diagnostics report only when the checkbox is ticked", async () => {
+    const user = userEvent.setup();
+    render(<FeedbackDialog />);
+
+    await user.click(screen.getByTitle("Send feedback"));
+    await user.type(screen.getByLabelText("Title"), "App crashes");
+    await user.click(screen.getByRole("button", { name: /Analyze/i }));
+
+    await waitFor(() => {
+      expect(mockStartAgent).toHaveBeenCalledTimes(1);
+    });
+
+    const enrichAgentId = mockStartAgent.mock.calls[0][0] as string;
+    act(() => {
+      simulateEnrichmentComplete(enrichAgentId);
+    });
+
+    await waitFor(() => {
+      expect(screen.getByRole("button", { name: /Create GitHub Issue/i })).toBeInTheDocument();
+    });
+
+    await user.click(screen.getByLabelText(/Attach diagnostics report/i));
+    await user.click(screen.getByRole("button", { name: /Create GitHub Issue/i }));
+
+    await waitFor(() => {
+      expect(mockCreateGithubIssue).toHaveBeenCalledTimes(1);
+    });
+
+    expect(mockRunDiagnostics).toHaveBeenCalledTimes(1);
+    const request = mockCreateGithubIssue.mock.calls[0][0] as { diagnostics: { all_ok: boolean } | null };
+    expect(request.diagnostics?.all_ok).toBe(true);
+  });
+
   it("shows success toast with GitHub issue URL on submission completion", async () => {
     const user = userEvent.setup();
     render(<FeedbackDialog />);
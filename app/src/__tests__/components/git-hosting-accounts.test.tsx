@@ -0,0 +1,65 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+import { render, screen, waitFor, within } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { mockInvoke, resetTauriMocks } from "@/test/mocks/tauri";
+import { GitHostingAccounts } from "@/components/git-hosting-accounts";
+import type { GitHostAccount } from "@/lib/types";
+
+vi.mock("sonner", () => ({
+  toast: { success: vi.fn(), error: vi.fn(), info: vi.fn() },
+}));
+
+describe("GitHostingAccounts", () => {
+  let accounts: GitHostAccount[];
+
+  beforeEach(() => {
+    resetTauriMocks();
+    accounts = [{ host: "bitbucket", login: "team-bot", api_base: null }];
+    mockInvoke.mockImplementation((cmd: string, args?: { host?: string; instanceUrl?: string | null }) => {
+      if (cmd === "git_host_list_accounts") return Promise.resolve(accounts);
+      if (cmd === "git_host_connect") {
+        return Promise.resolve({
+          host: args?.host,
+          login: "dev",
+          api_base: args?.instanceUrl ? `${args.instanceUrl}/api/v4` : null,
+        });
+      }
+      return Promise.resolve(undefined);
+    });
+  });
+
+  it("connects GitLab with a token and self-managed instance URL", async () => {
+    const user = userEvent.setup();
+    render(<GitHostingAccounts />);
+
+    const gitlab = await screen.findByTestId("git-host-gitlab");
+    expect(within(gitlab).getByText("Not connected")).toBeInTheDocument();
+    await user.type(within(gitlab).getByLabelText(/Instance URL/), "https://gitlab.acme.io");
+    await user.type(within(gitlab).getByLabelText("GitLab token"), "glpat-123");
+    await user.click(within(gitlab).getByRole("button", { name: /Connect/ }));
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("git_host_connect", {
+        host: "gitlab",
+        token: "glpat-123",
+        instanceUrl: "https://gitlab.acme.io",
+      });
+    });
+    expect(await within(gitlab).findByText("@dev")).toBeInTheDocument();
+    expect(within(gitlab).getByText("https://gitlab.acme.io")).toBeInTheDocument();
+  });
+
+  it("disconnects a connected Bitbucket account", async () => {
+    const user = userEvent.setup();
+    render(<GitHostingAccounts />);
+
+    const bitbucket = await screen.findByTestId("git-host-bitbucket");
+    expect(within(bitbucket).getByText("@team-bot")).toBeInTheDocument();
+    await user.click(within(bitbucket).getByRole("button", { name: /Disconnect/ }));
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("git_host_disconnect", { host: "bitbucket" });
+    });
+    expect(await within(bitbucket).findByLabelText("Bitbucket token")).toBeInTheDocument();
+  });
+});
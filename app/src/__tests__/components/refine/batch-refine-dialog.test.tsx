@@ -0,0 +1,110 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+import { render, screen, waitFor, within } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { mockInvoke, resetTauriMocks } from "@/test/mocks/tauri";
+import { BatchRefineDialog } from "@/components/refine/batch-refine-dialog";
+import type { RefineBatch, RefineBatchItem, SkillSummary } from "@/lib/types";
+
+vi.mock("sonner", () => ({
+  toast: { success: vi.fn(), error: vi.fn(), info: vi.fn() },
+}));
+
+const skills = [
+  { name: "revenue", purpose: "source" },
+  { name: "billing", purpose: "source" },
+  { name: "churn", purpose: "domain" },
+] as SkillSummary[];
+
+function item(skill_name: string, status: RefineBatchItem["status"], position: number): RefineBatchItem {
+  return {
+    skill_name,
+    position,
+    status,
+    agent_id: status === "queued" ? null : `refine-${skill_name}-1`,
+    diff:
+      status === "review"
+        ? {
+            stat: "1 file(s) changed, 1 insertion(s)(+), 1 deletion(s)(-)",
+            files: [{ path: `${skill_name}/SKILL.md`, status: "modified", diff: "-FY24\n+FY25\n" }],
+          }
+        : null,
+    error: null,
+    cost_usd: status === "queued" ? 0 : 0.0125,
+    updated_at: "2026-10-01T00:00:00Z",
+  };
+}
+
+function batch(items: RefineBatchItem[]): RefineBatch {
+  return {
+    batch_id: "batch-1",
+    instruction: "Update FY24 to FY25",
+    workspace_path: "/ws",
+    workflow_session_id: "session-1",
+    items,
+    total_cost_usd: items.reduce((sum, i) => sum + i.cost_usd, 0),
+    created_at: "2026-10-01T00:00:00Z",
+  };
+}
+
+describe("BatchRefineDialog", () => {
+  beforeEach(() => {
+    resetTauriMocks();
+    mockInvoke.mockImplementation((cmd: string, args?: Record<string, unknown>) => {
+      if (cmd === "list_refine_batches") return Promise.resolve([]);
+      if (cmd === "start_refine_batch") {
+        return Promise.resolve(batch([item("revenue", "review", 0), item("billing", "queued", 1)]));
+      }
+      if (cmd === "resolve_refine_batch_item") {
+        return Promise.resolve(
+          batch([item("revenue", args?.approve ? "approved" : "rejected", 0), item("billing", "queued", 1)]),
+        );
+      }
+      return Promise.resolve(undefined);
+    });
+  });
+
+  it("starts a batch for the selected skills and approves one skill's changes", async () => {
+    const user = userEvent.setup();
+    render(<BatchRefineDialog skills={skills} workspacePath="/ws" open onOpenChange={() => {}} />);
+
+    await user.type(screen.getByLabelText("Batch refine instruction"), "Update FY24 to FY25");
+    await user.click(screen.getByLabelText("revenue"));
+    await user.click(screen.getByLabelText("billing"));
+    await user.click(screen.getByRole("button", { name: /Refine 2 skills/ }));
+
+    expect(mockInvoke).toHaveBeenCalledWith("start_refine_batch", {
+      instruction: "Update FY24 to FY25",
+      skillNames: ["revenue", "billing"],
+      workspacePath: "/ws",
+    });
+    const [revenue, billing] = await screen.findAllByTestId("batch-refine-item");
+    expect(within(revenue).getByText("Awaiting review")).toBeInTheDocument();
+    expect(within(billing).getByRole("button", { name: "Skip" })).toBeInTheDocument();
+    expect(screen.getByText("Total cost $0.0125")).toBeInTheDocument();
+
+    await user.click(within(revenue).getByRole("button", { name: /Show changes to revenue/ }));
+    expect(within(revenue).getByText(/\+FY25/)).toBeInTheDocument();
+
+    await user.click(within(revenue).getByRole("button", { name: /Approve/ }));
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("resolve_refine_batch_item", {
+        batchId: "batch-1",
+        skillName: "revenue",
+        approve: true,
+      });
+    });
+    expect(await within(screen.getAllByTestId("batch-refine-item")[0]).findByText("Approved")).toBeInTheDocument();
+  });
+
+  it("reopens the latest batch that still awaits review", async () => {
+    mockInvoke.mockImplementation((cmd: string) =>
+      cmd === "list_refine_batches"
+        ? Promise.resolve([batch([item("churn", "review", 0)])])
+        : Promise.resolve(undefined),
+    );
+    render(<BatchRefineDialog skills={skills} workspacePath="/ws" open onOpenChange={() => {}} />);
+
+    expect(await screen.findByTestId("batch-refine-item")).toHaveTextContent("churn");
+    expect(screen.queryByLabelText("Batch refine instruction")).not.toBeInTheDocument();
+  });
+});
@@ -0,0 +1,70 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+import { render, screen, waitFor } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { mockInvoke, resetTauriMocks } from "@/test/mocks/tauri";
+import { useRefineStore } from "@/stores/refine-store";
+import { useSettingsStore } from "@/stores/settings-store";
+import { FileReviewList } from "@/components/refine/file-review-list";
+import type { RefineDiff, SkillSummary } from "@/lib/types";
+
+vi.mock("sonner", () => ({
+  toast: { success: vi.fn(), error: vi.fn(), info: vi.fn() },
+}));
+
+const PENDING: RefineDiff = {
+  stat: "2 file(s) changed, 2 insertion(s)(+), 2 deletion(s)(-)",
+  files: [
+    { path: "my-skill/SKILL.md", status: "modified", diff: "@@ -1 +1 @@\n-# V1\n+# V2\n", review: "accepted" },
+    { path: "my-skill/references/guide.md", status: "added", diff: "@@ -0,0 +1 @@\n+guide\n", review: null },
+  ],
+};
+
+describe("FileReviewList", () => {
+  beforeEach(() => {
+    resetTauriMocks();
+    useSettingsStore.setState({ workspacePath: "/ws" });
+    useRefineStore.setState({
+      selectedSkill: { name: "my-skill" } as SkillSummary,
+      sessionId: "sess-1",
+      isRunning: false,
+      pendingDiff: PENDING,
+    });
+  });
+
+  it("lists each changed file relative to the skill", () => {
+    render(<FileReviewList />);
+
+    const rows = screen.getAllByTestId("refine-file-review");
+    expect(rows).toHaveLength(2);
+    expect(rows[0]).toHaveTextContent("SKILL.md");
+    expect(rows[0]).toHaveTextContent("Accepted");
+    expect(rows[1]).toHaveTextContent("references/guide.md");
+    expect(screen.getAllByRole("button", { name: /Reject/ })).toHaveLength(1);
+  });
+
+  it("rejects one file and reloads the preview", async () => {
+    const user = userEvent.setup();
+    const onFileRejected = vi.fn();
+    mockInvoke.mockResolvedValue({ stat: PENDING.stat, files: [PENDING.files[0]] });
+    render(<FileReviewList onFileRejected={onFileRejected} />);
+
+    await user.click(screen.getByRole("button", { name: /Reject/ }));
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("review_refine_file", {
+        sessionId: "sess-1",
+        workspacePath: "/ws",
+        path: "my-skill/references/guide.md",
+        accept: false,
+      });
+    });
+    await waitFor(() => expect(screen.getAllByTestId("refine-file-review")).toHaveLength(1));
+    expect(onFileRejected).toHaveBeenCalled();
+  });
+
+  it("renders nothing without pending changes", () => {
+    useRefineStore.setState({ pendingDiff: { stat: "no changes", files: [] } });
+    const { container } = render(<FileReviewList />);
+    expect(container).toBeEmptyDOMElement();
+  });
+});
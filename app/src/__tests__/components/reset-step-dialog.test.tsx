@@ -112,6 +112,47 @@ describe("ResetStepDialog", () => {
     });
   });
 
+  it("keeps ticked files through reset_workflow_artifacts", async () => {
+    const user = userEvent.setup();
+    const onReset = vi.fn();
+    mockInvoke.mockImplementation((cmd: string) => {
+      if (cmd === "preview_step_reset") return Promise.resolve(mockPreview);
+      if (cmd === "reset_workflow_artifacts") {
+        return Promise.resolve({
+          current_step: 2,
+          deleted_files: ["context/decisions.json"],
+          kept_files: ["context/clarifications.json"],
+        });
+      }
+      return Promise.reject(new Error(`Unmocked: ${cmd}`));
+    });
+
+    render(
+      <ResetStepDialog
+        targetStep={1}
+        workspacePath="/workspace"
+        skillName="test-skill"
+        open={true}
+        onOpenChange={vi.fn()}
+        onReset={onReset}
+      />
+    );
+
+    await user.click(await screen.findByRole("checkbox", { name: "Keep context/clarifications.json" }));
+    await user.click(screen.getByRole("button", { name: "Delete 1 file & Reset" }));
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("reset_workflow_artifacts", {
+        workspacePath: "/workspace",
+        skillName: "test-skill",
+        fromStepId: 1,
+        keepFiles: ["context/clarifications.json"],
+      });
+      expect(onReset).toHaveBeenCalledWith(2);
+    });
+    expect(mockInvoke).not.toHaveBeenCalledWith("reset_workflow_step", expect.anything());
+  });
+
   it("calls onOpenChange(false) on cancel", async () => {
     const user = userEvent.setup();
     const onOpenChange = vi.fn();
@@ -38,6 +38,23 @@ describe("RuntimeErrorDialog", () => {
     expect(screen.getByText(/usually temporary/i)).toBeInTheDocument();
   });
 
+  it("classifies protocol mismatches as version mismatches", () => {
+    render(
+      <RuntimeErrorDialog
+        error={{
+          error_type: "protocol_incompatible",
+          message: "Agent runtime speaks protocol version 2.0, but this app requires 1.x.",
+          fix_hint: "Repair the agent runtime: run `npm run sidecar:build` in the app/ directory.",
+        }}
+        onDismiss={vi.fn()}
+      />
+    );
+
+    expect(screen.getByText("Agent Runtime Out of Date")).toBeInTheDocument();
+    expect(screen.getByText("Version mismatch")).toBeInTheDocument();
+    expect(screen.queryByRole("link", { name: /nodejs\.org/i })).not.toBeInTheDocument();
+  });
+
   it("shows nodejs.org link for node compatibility errors", () => {
     render(
       <RuntimeErrorDialog
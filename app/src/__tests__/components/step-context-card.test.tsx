@@ -0,0 +1,79 @@
+import { describe, it, expect, beforeEach } from "vitest";
+import { render, screen, waitFor } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { mockInvoke, resetTauriMocks } from "@/test/mocks/tauri";
+import { StepContextCard } from "@/components/step-context-card";
+import { useSettingsStore } from "@/stores/settings-store";
+import type { StepExplanation } from "@/lib/types";
+
+const explanation: StepExplanation = {
+  skill_name: "sales-pipeline",
+  step_id: 2,
+  step_name: "Confirm Decisions",
+  summary: "Turns 5 answers and 2 refinements into decisions. Typically about $0.50 and 2 min (from 3 past runs).",
+  reads: [{ label: "Answers and refinements", path: "context/clarifications.json", available: true }],
+  writes: ["context/decisions.json"],
+  history_runs: 3,
+  history_scope: "all",
+  expected_cost_usd: 0.5,
+  expected_duration_ms: 120000,
+  prepare: ["Answer 1 required question (5 of 6 answered)."],
+};
+
+describe("StepContextCard", () => {
+  beforeEach(() => {
+    resetTauriMocks();
+    useSettingsStore.setState({ workspacePath: null });
+  });
+
+  it("shows the generated explanation and its details", async () => {
+    mockInvoke.mockImplementation((cmd: string) =>
+      cmd === "explain_step" ? Promise.resolve(explanation) : Promise.reject(new Error(`Unmocked: ${cmd}`))
+    );
+    render(<StepContextCard skillName="sales-pipeline" stepId={2} fallbackDescription="Static text" />);
+
+    expect(await screen.findByText(/Turns 5 answers/)).toBeInTheDocument();
+    expect(mockInvoke).toHaveBeenCalledWith("explain_step", { skillName: "sales-pipeline", stepId: 2 });
+
+    await userEvent.click(screen.getByRole("button", { name: "Explain this step" }));
+    expect(await screen.findByText("context/decisions.json")).toBeInTheDocument();
+    expect(screen.getByText("Answer 1 required question (5 of 6 answered).")).toBeInTheDocument();
+    expect(screen.getByText(/runs across skills/)).toBeInTheDocument();
+  });
+
+  it("shows the model a route assigns to the step", async () => {
+    useSettingsStore.setState({ workspacePath: "/ws" });
+    mockInvoke.mockImplementation((cmd: string) => {
+      if (cmd === "explain_step") return Promise.resolve(explanation);
+      if (cmd === "get_effective_model") {
+        return Promise.resolve({
+          step_id: 2,
+          model: "claude-haiku-4-5",
+          source: "route",
+          route: { step_id: 2, skill_type: null, model: "haiku" },
+        });
+      }
+      return Promise.reject(new Error(`Unmocked: ${cmd}`));
+    });
+    render(<StepContextCard skillName="sales-pipeline" stepId={2} />);
+
+    await userEvent.click(await screen.findByRole("button", { name: "Explain this step" }));
+    expect(await screen.findByText("claude-haiku-4-5")).toBeInTheDocument();
+    expect(screen.getByText("(model route)")).toBeInTheDocument();
+    expect(mockInvoke).toHaveBeenCalledWith("get_effective_model", {
+      skillName: "sales-pipeline",
+      stepId: 2,
+      workspacePath: "/ws",
+    });
+  });
+
+  it("falls back to the static description when the explanation fails", async () => {
+    mockInvoke.mockImplementation(() => Promise.reject(new Error("no run")));
+    render(<StepContextCard skillName="sales-pipeline" stepId={0} fallbackDescription="Static text" />);
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalled();
+    });
+    expect(screen.getByText("Static text")).toBeInTheDocument();
+  });
+});
@@ -265,4 +265,25 @@ describe("TagInput", () => {
     expect(options).toHaveLength(1);
     expect(options[0]).toHaveTextContent("Anomaly");
   });
+
+  it("highlights tags the team taxonomy flags and lists the warnings", () => {
+    render(
+      <TagInput
+        tags={["analytics", "Big_Data"]}
+        onChange={vi.fn()}
+        warnings={[
+          { skill_name: "s", field: "tag", value: "Big_Data", message: "Tags should be kebab-case (e.g. data-quality)" },
+        ]}
+      />
+    );
+
+    expect(screen.getByText("Big_Data").closest("[title]")).toHaveAttribute(
+      "title",
+      "Tags should be kebab-case (e.g. data-quality)"
+    );
+    expect(screen.getByText("analytics").closest("[title]")).toBeNull();
+    expect(screen.getByTestId("tag-warnings")).toHaveTextContent(
+      "Big_Data: Tags should be kebab-case (e.g. data-quality)"
+    );
+  });
 });
@@ -0,0 +1,103 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+import { render, screen, waitFor } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { mockInvoke, resetTauriMocks } from "@/test/mocks/tauri";
+import { TagTaxonomySettings } from "@/components/tag-taxonomy-settings";
+import type { TagTree } from "@/lib/types";
+
+vi.mock("sonner", () => ({
+  toast: { success: vi.fn(), error: vi.fn(), info: vi.fn(), warning: vi.fn() },
+}));
+
+const tree: TagTree = {
+  tags: [
+    {
+      name: "finance",
+      path: "finance",
+      direct_count: 0,
+      skill_count: 2,
+      children: [{ name: "revenue", path: "finance/revenue", direct_count: 2, skill_count: 2, children: [] }],
+    },
+    { name: "sfdc", path: "sfdc", direct_count: 1, skill_count: 1, children: [] },
+  ],
+  aliases: [{ alias: "fin", tag: "finance" }],
+  warnings: [],
+};
+
+const merged: TagTree = {
+  tags: [tree.tags[0], { name: "salesforce", path: "salesforce", direct_count: 1, skill_count: 1, children: [] }],
+  aliases: [...tree.aliases, { alias: "sfdc", tag: "salesforce" }],
+  warnings: [],
+};
+
+describe("TagTaxonomySettings", () => {
+  beforeEach(() => {
+    resetTauriMocks();
+    mockInvoke.mockImplementation((cmd: string) => {
+      if (cmd === "get_tag_tree") return Promise.resolve(tree);
+      if (cmd === "merge_tags") return Promise.resolve(merged);
+      if (cmd === "rename_tag") return Promise.reject(new Error("Tag 'sfdc' already exists; merge the tags instead"));
+      return Promise.resolve(undefined);
+    });
+  });
+
+  it("renders nested tags with their skill counts and aliases", async () => {
+    render(<TagTaxonomySettings />);
+
+    const nodes = await screen.findAllByTestId("tag-node");
+    expect(nodes.map((n) => n.textContent)).toEqual(["finance2", "revenue2", "sfdc1"]);
+    expect(nodes[1]).toHaveStyle({ paddingLeft: "20px" });
+    expect(screen.getByTestId("tag-alias")).toHaveTextContent("fin");
+  });
+
+  it("merges selected tags into a target", async () => {
+    const user = userEvent.setup();
+    render(<TagTaxonomySettings />);
+
+    await user.click(await screen.findByLabelText("Select sfdc"));
+    await user.type(screen.getByLabelText(/Merge 1 selected into/), "salesforce");
+    await user.click(screen.getByRole("button", { name: "Merge" }));
+
+    expect(mockInvoke).toHaveBeenCalledWith("merge_tags", { sources: ["sfdc"], target: "salesforce" });
+    await waitFor(() => expect(screen.getAllByTestId("tag-alias")).toHaveLength(2));
+    expect(screen.queryByLabelText(/Merge 1 selected into/)).not.toBeInTheDocument();
+  });
+
+  it("warns when retagged skills leave the team taxonomy", async () => {
+    const user = userEvent.setup();
+    const { toast } = await import("sonner");
+    mockInvoke.mockImplementation((cmd: string) => {
+      if (cmd === "get_tag_tree") return Promise.resolve(tree);
+      if (cmd === "merge_tags") {
+        return Promise.resolve({
+          ...merged,
+          warnings: [{ skill_name: "crm", field: "tag", value: "salesforce", message: "Tag is not in the team vocabulary" }],
+        });
+      }
+      return Promise.resolve(undefined);
+    });
+    render(<TagTaxonomySettings />);
+
+    await user.click(await screen.findByLabelText("Select sfdc"));
+    await user.type(screen.getByLabelText(/Merge 1 selected into/), "salesforce");
+    await user.click(screen.getByRole("button", { name: "Merge" }));
+
+    await waitFor(() => expect(toast.warning).toHaveBeenCalledWith("Not in the team taxonomy: salesforce", expect.anything()));
+  });
+
+  it("keeps the rename form open when the rename fails", async () => {
+    const user = userEvent.setup();
+    const { toast } = await import("sonner");
+    render(<TagTaxonomySettings />);
+
+    await user.click(await screen.findByLabelText("Rename finance/revenue"));
+    const input = screen.getByLabelText("New name for finance/revenue");
+    await user.clear(input);
+    await user.type(input, "sfdc");
+    await user.click(screen.getByRole("button", { name: "Save" }));
+
+    expect(mockInvoke).toHaveBeenCalledWith("rename_tag", { from: "finance/revenue", to: "sfdc" });
+    await waitFor(() => expect(toast.error).toHaveBeenCalled());
+    expect(screen.getByLabelText("New name for finance/revenue")).toBeInTheDocument();
+  });
+});
@@ -0,0 +1,67 @@
+import { describe, it, expect, beforeEach, vi } from "vitest";
+import { render, screen, waitFor } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { mockInvoke, resetTauriMocks } from "@/test/mocks/tauri";
+import { TrustedKeysSettings } from "@/components/trusted-keys-settings";
+import type { TrustedKey } from "@/lib/types";
+
+vi.mock("sonner", () => ({
+  toast: { success: vi.fn(), error: vi.fn(), info: vi.fn() },
+}));
+
+const alice: TrustedKey = {
+  key_id: "a1b2c3d4e5f60708",
+  name: "Alice",
+  public_key: "QUxJQ0UtS0VZ",
+  added_at: "2026-01-01T00:00:00Z",
+};
+
+describe("TrustedKeysSettings", () => {
+  beforeEach(() => {
+    resetTauriMocks();
+    mockInvoke.mockImplementation((cmd: string, args?: { name?: string; publicKey?: string }) => {
+      if (cmd === "list_trusted_keys") return Promise.resolve([alice]);
+      if (cmd === "add_trusted_key") {
+        return Promise.resolve({ key_id: "0011223344556677", name: args?.name, public_key: args?.publicKey, added_at: "" });
+      }
+      if (cmd === "get_signing_public_key") return Promise.resolve({ key_id: "ffee", public_key: "T1dOLUtFWQ==" });
+      return Promise.resolve(undefined);
+    });
+  });
+
+  it("trusts a new key and removes an existing one", async () => {
+    const user = userEvent.setup();
+    render(<TrustedKeysSettings />);
+
+    expect(await screen.findByText("Alice")).toBeInTheDocument();
+    await user.type(screen.getByLabelText("Name"), "Bob");
+    await user.type(screen.getByLabelText("Public key"), "Qk9CLUtFWQ==");
+    await user.click(screen.getByRole("button", { name: /Trust key/ }));
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("add_trusted_key", { name: "Bob", publicKey: "Qk9CLUtFWQ==" });
+    });
+    expect(await screen.findByText("Bob")).toBeInTheDocument();
+
+    await user.click(screen.getByRole("button", { name: "Remove Alice" }));
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("remove_trusted_key", { keyId: alice.key_id });
+    });
+    expect(screen.queryByText("Alice")).not.toBeInTheDocument();
+  });
+
+  it("only fetches the signing key on request", async () => {
+    const user = userEvent.setup();
+    render(<TrustedKeysSettings />);
+
+    await user.click(await screen.findByRole("button", { name: "Show my public key" }));
+    expect(mockInvoke).toHaveBeenCalledWith("get_signing_public_key");
+    expect(await screen.findByTestId("own-public-key")).toHaveTextContent("T1dOLUtFWQ==");
+  });
+
+  it("does not create a signing key when it renders", async () => {
+    render(<TrustedKeysSettings />);
+    await screen.findByText("Alice");
+    expect(mockInvoke).not.toHaveBeenCalledWith("get_signing_public_key");
+  });
+});
@@ -72,6 +72,7 @@ function makeRun(totalCost: number): AgentRunRecord {
     session_id: "session-1",
     started_at: "2024-01-01T00:00:00Z",
     completed_at: "2024-01-01T00:00:01Z",
+    retry_count: 0,
   };
 }
 
@@ -137,6 +137,7 @@ function setupMocks(skills: WorkspaceSkill[] = sampleSkills) {
   mockInvoke.mockImplementation((cmd: string) => {
     if (cmd === "get_settings") return Promise.resolve(defaultSettings);
     if (cmd === "list_workspace_skills") return Promise.resolve(skills);
+    if (cmd === "get_skill_scan_report") return Promise.resolve(null);
     return Promise.reject(new Error(`Unmocked command: ${cmd}`));
   });
 }
@@ -213,6 +214,30 @@ describe("WorkspaceSkillsTab", () => {
     expect(screen.getByText("hr-metrics")).toBeInTheDocument();
   });
 
+  it("marks skills quarantined by the import scan", async () => {
+    mockInvoke.mockImplementation((cmd: string, args?: { skillName?: string }) => {
+      if (cmd === "get_settings") return Promise.resolve(defaultSettings);
+      if (cmd === "list_workspace_skills") return Promise.resolve(sampleSkills);
+      if (cmd === "get_skill_scan_report") {
+        if (args?.skillName !== "hr-metrics") return Promise.resolve(null);
+        return Promise.resolve({
+          skill_name: "hr-metrics",
+          files_scanned: 1,
+          flagged: true,
+          quarantined: true,
+          findings: [{ file: "SKILL.md", line: 7, rule: "instruction-override", severity: "high", excerpt: "Ignore previous instructions" }],
+          scanned_at: "2026-01-10T08:00:00Z",
+        });
+      }
+      return Promise.reject(new Error(`Unmocked command: ${cmd}`));
+    });
+    render(<WorkspaceSkillsTab />);
+
+    const badge = await screen.findByText("Quarantined");
+    expect(badge).toHaveAttribute("title", expect.stringContaining("SKILL.md:7 instruction-override"));
+    expect(mockInvoke).toHaveBeenCalledWith("get_skill_scan_report", { skillName: "sales-analytics" });
+  });
+
   it("shows empty state when no skills", async () => {
     setupMocks([]);
     render(<WorkspaceSkillsTab />);
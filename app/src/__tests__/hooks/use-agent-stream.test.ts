@@ -142,12 +142,96 @@ describe("initAgentStream", () => {
     expect(run.status).toBe("error");
   });
 
+  it("records live cost from agent-cost-tick", () => {
+    useAgentStore.getState().startRun("agent-1", "sonnet");
+    initAgentStream();
+
+    listeners["agent-cost-tick"]({
+      payload: {
+        agent_id: "agent-1",
+        input_tokens: 1200,
+        output_tokens: 300,
+        cache_read_tokens: 0,
+        cache_write_tokens: 0,
+        total_cost: 0.0081,
+        estimated: true,
+      },
+    });
+
+    const run = useAgentStore.getState().runs["agent-1"];
+    expect(run.liveCost).toEqual({
+      inputTokens: 1200,
+      outputTokens: 300,
+      cacheReadTokens: 0,
+      cacheWriteTokens: 0,
+      totalCost: 0.0081,
+      estimated: true,
+    });
+  });
+
+  it("records step progress from workflow-step-progress", () => {
+    useAgentStore.getState().startRun("agent-1", "sonnet");
+    initAgentStream();
+
+    listeners["workflow-step-progress"]({
+      payload: {
+        agent_id: "agent-1",
+        skill_name: "my-skill",
+        step_id: 2,
+        model: "sonnet",
+        turns_completed: 4,
+        max_turns: 50,
+        current_tool: "Read",
+        tokens_used: 18000,
+        elapsed_ms: 30000,
+        estimated_total_ms: 120000,
+        eta_ms: 90000,
+        percent: 25,
+        finished: false,
+      },
+    });
+
+    expect(useAgentStore.getState().runs["agent-1"].stepProgress).toEqual({
+      turnsCompleted: 4,
+      maxTurns: 50,
+      currentTool: "Read",
+      tokensUsed: 18000,
+      percent: 25,
+      etaMs: 90000,
+      receivedAt: expect.any(Number),
+    });
+  });
+
+  it("shows a retry notice from agent-retry without ending the run", () => {
+    useAgentStore.getState().startRun("agent-1", "sonnet");
+    initAgentStream();
+
+    listeners["agent-retry"]({
+      payload: {
+        agent_id: "agent-1",
+        attempt: 1,
+        max_retries: 3,
+        error_class: "overloaded",
+        message: "API Error: 529 overloaded_error",
+        delay_ms: 2400,
+        resumed: false,
+      },
+    });
+    flushMessageBuffer();
+
+    const run = useAgentStore.getState().runs["agent-1"];
+    expect(run.status).toBe("running");
+    expect(run.messages).toHaveLength(1);
+    expect(run.messages[0].type).toBe("error");
+    expect(run.messages[0].content).toBe("API overloaded — retrying in 2s (attempt 1 of 3)");
+  });
+
   it("only registers listeners once for multiple init calls", () => {
     initAgentStream();
     initAgentStream();
 
-    // listen should only be called 5 times (agent-init-progress, agent-init-error, agent-message, agent-exit, agent-shutdown)
-    expect(mockListen).toHaveBeenCalledTimes(5);
+    // listen should only be called 8 times (agent-init-progress, agent-init-error, agent-message, agent-cost-tick, workflow-step-progress, agent-retry, agent-exit, agent-shutdown)
+    expect(mockListen).toHaveBeenCalledTimes(8);
   });
 
   it("auto-creates run for messages arriving before startRun", () => {
@@ -1,12 +1,15 @@
 import { describe, it, expect, beforeEach, vi } from "vitest";
-import { render, screen, waitFor } from "@testing-library/react";
+import { act, render, screen, waitFor } from "@testing-library/react";
 import userEvent from "@testing-library/user-event";
 import {
   mockInvoke,
   mockInvokeCommands,
   mockDialogSave,
+  mockListen,
   resetTauriMocks,
 } from "@/test/mocks/tauri";
+import { open as mockOpen } from "@tauri-apps/plugin-dialog";
+import { toast } from "sonner";
 import { useSettingsStore } from "@/stores/settings-store";
 import type { SkillSummary, AppSettings } from "@/lib/types";
 
@@ -35,6 +38,7 @@ vi.mock("sonner", () => ({
     success: vi.fn(),
     error: vi.fn(),
     info: vi.fn(),
+    warning: vi.fn(),
     loading: vi.fn(() => "toast-id"),
     dismiss: vi.fn(),
   }),
@@ -110,6 +114,9 @@ function setupMocks(
     package_skill: { file_path: "/tmp/test.skill", size_bytes: 1024 },
     copy_file: undefined,
     save_settings: undefined,
+    import_example_skills: [],
+    get_skill_review_report: [],
+    refresh_upstream_reviews: [],
   });
 
   // Hydrate the Zustand settings store (normally done by app-layout.tsx)
@@ -193,6 +200,93 @@ describe("DashboardPage", () => {
     ).toBeInTheDocument();
   });
 
+  it("imports Anthropic example skills from the empty library", async () => {
+    setupMocks({ settings: { skills_path: "/home/user/skills" }, skills: [] });
+    render(<DashboardPage />);
+
+    const button = await screen.findByRole("button", { name: /Import Anthropic Examples/i });
+    await userEvent.click(button);
+
+    await waitFor(() => {
+      expect(mockInvoke.mock.calls.some(([cmd]) => cmd === "import_example_skills")).toBe(true);
+    });
+  });
+
+  it("warns about skills that are due or overdue for review", async () => {
+    setupMocks();
+    mockInvokeCommands({
+      get_settings: defaultSettings,
+      list_skills: sampleSkills,
+      get_all_tags: [],
+      get_skill_review_report: [
+        { skill_name: "sales-pipeline", review_by: "2026-01-01", reviewed_at: null, state: "expired", days_remaining: -30 },
+        { skill_name: "hr-analytics", review_by: "2026-02-10", reviewed_at: null, state: "due_soon", days_remaining: 10 },
+      ],
+    });
+    render(<DashboardPage />);
+
+    await waitFor(() => {
+      expect(toast.warning).toHaveBeenCalledWith(
+        "Skill reviews: 1 overdue (sales-pipeline); 1 due soon (hr-analytics)",
+        { duration: Infinity },
+      );
+    });
+    expect(await screen.findByText("Review overdue")).toBeInTheDocument();
+    expect(screen.getByText("Review due")).toBeInTheDocument();
+  });
+
+  it("marks skills whose pushed changes are pending upstream review", async () => {
+    setupMocks();
+    mockInvokeCommands({
+      get_settings: defaultSettings,
+      list_skills: sampleSkills,
+      get_all_tags: [],
+      get_skill_review_report: [],
+      refresh_upstream_reviews: [
+        {
+          skill_name: "sales-pipeline", host: "github", repo: "acme/skills", repo_url: "https://github.com/acme/skills",
+          branch: "skill/sales-pipeline", base_branch: "main", number: 12, url: "https://github.com/acme/skills/pull/12",
+          state: "open", commit_sha: null, pushed_at: "2026-01-01T00:00:00Z", updated_at: "2026-01-01T00:00:00Z",
+        },
+        {
+          skill_name: "hr-analytics", host: "github", repo: "acme/skills", repo_url: "https://github.com/acme/skills",
+          branch: "skill/hr-analytics", base_branch: "main", number: 9, url: "https://github.com/acme/skills/pull/9",
+          state: "merged", commit_sha: null, pushed_at: "2026-01-01T00:00:00Z", updated_at: "2026-01-02T00:00:00Z",
+        },
+      ],
+    });
+    render(<DashboardPage />);
+
+    expect(await screen.findByText("Pending upstream review")).toBeInTheDocument();
+    expect(screen.getAllByText("Pending upstream review")).toHaveLength(1);
+  });
+
+  it("restores a .skillpack archive from the Import button", async () => {
+    setupMocks({ settings: { skills_path: "/home/user/skills" } });
+    mockInvokeCommands({
+      get_settings: { ...defaultSettings, skills_path: "/home/user/skills" },
+      list_skills: sampleSkills,
+      get_all_tags: [],
+      import_skill_archive: { skill_name: "sales-pipeline", current_step: 3, status: "in_progress", file_count: 4, signature_status: "unsigned" },
+    });
+    (mockOpen as ReturnType<typeof vi.fn>).mockResolvedValue("/tmp/sales-pipeline.skillpack");
+    render(<DashboardPage />);
+
+    await waitFor(() => {
+      expect(screen.getByText("sales-pipeline")).toBeInTheDocument();
+    });
+    await userEvent.click(screen.getByRole("button", { name: /^Import$/i }));
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("import_skill_archive", {
+        filePath: "/tmp/sales-pipeline.skillpack",
+        newName: null,
+        workspacePath: "/home/user/workspace",
+      });
+    });
+    expect(mockInvoke).not.toHaveBeenCalledWith("parse_skill_file", expect.anything());
+  });
+
   it("hides New Skill button and shows banner when skills_path is not set", async () => {
     setupMocks(); // skills_path is null by default
     render(<DashboardPage />);
@@ -504,4 +598,26 @@ describe("DashboardPage", () => {
       workspacePath: "/home/user/workspace",
     });
   });
+
+  it("applies db-change events without reloading the skill list", async () => {
+    let handler: ((event: { payload: unknown }) => void) | undefined;
+    mockListen.mockImplementation(((event: string, cb: (event: { payload: unknown }) => void) => {
+      if (event === "db-change") handler = cb;
+      return Promise.resolve(() => {});
+    }) as never);
+    setupMocks();
+    render(<DashboardPage />);
+
+    await waitFor(() => {
+      expect(screen.getByText("sales-pipeline")).toBeInTheDocument();
+    });
+    const listCalls = mockInvoke.mock.calls.filter(([cmd]) => cmd === "list_skills").length;
+
+    act(() => handler?.({ payload: { kind: "skill.deleted", skill_name: "hr-analytics" } }));
+    await waitFor(() => {
+      expect(screen.queryByText("hr-analytics")).not.toBeInTheDocument();
+    });
+    expect(screen.getByText("sales-pipeline")).toBeInTheDocument();
+    expect(mockInvoke.mock.calls.filter(([cmd]) => cmd === "list_skills")).toHaveLength(listCalls);
+  });
 });
@@ -25,8 +25,10 @@ vi.mock("next-themes", () => ({
 
 // Mock @tanstack/react-router
 const mockNavigate = vi.fn();
+let mockSearch: { tab?: string } = {};
 vi.mock("@tanstack/react-router", () => ({
   useNavigate: () => mockNavigate,
+  useSearch: () => mockSearch,
 }));
 
 
@@ -51,6 +53,22 @@ vi.mock("@/components/feedback-dialog", () => ({
   FeedbackDialog: () => null,
 }));
 
+vi.mock("@/components/background-task-queue", () => ({
+  BackgroundTaskQueue: () => null,
+}));
+
+vi.mock("@/components/automation-hooks-settings", () => ({
+  AutomationHooksSettings: () => null,
+}));
+
+vi.mock("@/components/bash-sandbox-settings", () => ({
+  BashSandboxSettings: () => null,
+}));
+
+vi.mock("@/components/git-hosting-accounts", () => ({
+  GitHostingAccounts: () => null,
+}));
+
 // Import after mocks are set up
 import SettingsPage from "@/pages/settings";
 import { useSettingsStore } from "@/stores/settings-store";
@@ -107,6 +125,10 @@ function setupDefaultMocks(settingsOverride?: Partial<AppSettings>) {
     test_api_key: true,
     get_log_file_path: "/tmp/com.vibedata.skill-builder/skill-builder.log",
     set_log_level: undefined,
+    list_quality_gate_profiles: [
+      { name: "team-standard", description: null, checks: ["lint", "secrets", "compatibility"], budgets: { skill_md_max_lines: 500, total_max_kb: 512, max_sentence_words: 25 } },
+      { name: "strict", description: null, checks: ["lint", "size", "secrets", "readability", "compatibility"], budgets: { skill_md_max_lines: 500, total_max_kb: 512, max_sentence_words: 25 } },
+    ],
   });
 }
 
@@ -127,6 +149,7 @@ describe("SettingsPage", () => {
     useSettingsStore.getState().reset();
     // Reset URL search params so tab defaults to "general"
     window.history.replaceState({}, "", window.location.pathname);
+    mockSearch = {};
   });
 
   it("back button navigates to dashboard", async () => {
@@ -171,6 +194,27 @@ describe("SettingsPage", () => {
     expect(screen.getByText("User Profile")).toBeInTheDocument();
   });
 
+  it("opens the section named by the tab search param", async () => {
+    mockSearch = { tab: "advanced" };
+    setupDefaultMocks();
+    render(<SettingsPage />);
+
+    await waitFor(() => {
+      expect(screen.getByText("Storage")).toBeInTheDocument();
+    });
+    expect(screen.queryByText("API Configuration")).not.toBeInTheDocument();
+  });
+
+  it("ignores an unknown tab search param", async () => {
+    mockSearch = { tab: "nope" };
+    setupDefaultMocks();
+    render(<SettingsPage />);
+
+    await waitFor(() => {
+      expect(screen.getByText("API Configuration")).toBeInTheDocument();
+    });
+  });
+
   it("shows loading spinner initially", () => {
     // Don't resolve get_settings immediately - make it hang
     mockInvoke.mockImplementation(
@@ -572,6 +616,155 @@ describe("SettingsPage", () => {
     });
   });
 
+  it("auto-saves accessibility_lint when the lint mode is changed", async () => {
+    const user = userEvent.setup();
+    setupDefaultMocks(populatedSettings);
+    render(<SettingsPage />);
+
+    await waitFor(() => {
+      expect(screen.getByText("Settings")).toBeInTheDocument();
+    });
+
+    await switchToSection(/^Skills$/);
+
+    const select = screen.getByRole("combobox", { name: /When issues are found/i });
+    expect(select).toHaveValue("advisory");
+    await user.selectOptions(select, "blocking");
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("save_settings", {
+        settings: expect.objectContaining({
+          accessibility_lint: "blocking",
+        }),
+      });
+    });
+  });
+
+  it("auto-saves block_expired_packaging when the switch is toggled", async () => {
+    const user = userEvent.setup();
+    setupDefaultMocks(populatedSettings);
+    render(<SettingsPage />);
+
+    await waitFor(() => {
+      expect(screen.getByText("Settings")).toBeInTheDocument();
+    });
+
+    await switchToSection(/^Skills$/);
+    await user.click(screen.getByRole("switch", { name: /Block packaging of expired skills/i }));
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("save_settings", {
+        settings: expect.objectContaining({
+          block_expired_packaging: true,
+        }),
+      });
+    });
+  });
+
+  it("auto-saves require_trusted_packages when the switch is toggled", async () => {
+    const user = userEvent.setup();
+    setupDefaultMocks(populatedSettings);
+    render(<SettingsPage />);
+
+    await waitFor(() => {
+      expect(screen.getByText("Settings")).toBeInTheDocument();
+    });
+
+    await switchToSection(/^Skills$/);
+    await user.click(screen.getByRole("switch", { name: /Only import packages signed by a trusted key/i }));
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("save_settings", {
+        settings: expect.objectContaining({
+          require_trusted_packages: true,
+        }),
+      });
+    });
+  });
+
+  it("auto-saves workflow_queue_concurrency on blur", async () => {
+    const user = userEvent.setup();
+    setupDefaultMocks(populatedSettings);
+    render(<SettingsPage />);
+
+    await waitFor(() => {
+      expect(screen.getByText("Settings")).toBeInTheDocument();
+    });
+
+    await switchToSection(/Skill Building/i);
+    const input = screen.getByLabelText("Skills at once");
+    await user.clear(input);
+    await user.type(input, "3");
+    await user.tab();
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("save_settings", {
+        settings: expect.objectContaining({
+          workflow_queue_concurrency: 3,
+        }),
+      });
+    });
+  });
+
+  it("auto-saves the global and per-skill budgets", async () => {
+    const user = userEvent.setup();
+    setupDefaultMocks(populatedSettings);
+    render(<SettingsPage />);
+
+    await waitFor(() => {
+      expect(screen.getByText("Settings")).toBeInTheDocument();
+    });
+
+    await switchToSection(/Skill Building/i);
+    await user.type(screen.getByLabelText("All skills (USD)"), "25");
+    await user.tab();
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("save_settings", {
+        settings: expect.objectContaining({
+          global_budget_usd: 25,
+        }),
+      });
+    });
+
+    await user.type(screen.getByLabelText("Skill name"), "sales-pipeline");
+    await user.type(screen.getByLabelText("Skill budget (USD)"), "2.5");
+    await user.click(screen.getByRole("button", { name: /Add budget/i }));
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("save_settings", {
+        settings: expect.objectContaining({
+          skill_budgets_usd: { "sales-pipeline": 2.5 },
+        }),
+      });
+    });
+    expect(screen.getByTestId("skill-budget")).toHaveTextContent("sales-pipeline");
+  });
+
+  it("auto-saves the selected quality gate profile", async () => {
+    const user = userEvent.setup();
+    setupDefaultMocks(populatedSettings);
+    render(<SettingsPage />);
+
+    await waitFor(() => {
+      expect(screen.getByText("Settings")).toBeInTheDocument();
+    });
+
+    await switchToSection(/^Skills$/);
+    await waitFor(() => {
+      expect(screen.getByRole("option", { name: /^strict/ })).toBeInTheDocument();
+    });
+    await user.selectOptions(screen.getByLabelText("Profile"), "strict");
+
+    await waitFor(() => {
+      expect(mockInvoke).toHaveBeenCalledWith("save_settings", {
+        settings: expect.objectContaining({
+          quality_gate_profile: "strict",
+        }),
+      });
+    });
+  });
+
   it("renders logging helper text in Logging card", async () => {
     setupDefaultMocks();
     render(<SettingsPage />);
@@ -23,6 +23,7 @@ vi.mock("@/lib/tauri", () => ({
   getUsageByStep: vi.fn(() => Promise.resolve([])),
   getUsageByModel: vi.fn(() => Promise.resolve([])),
   resetUsage: vi.fn(() => Promise.resolve()),
+  getAgentToolCalls: vi.fn(() => Promise.resolve([])),
 }));
 
 import UsagePage from "@/pages/usage";
@@ -65,6 +66,7 @@ const mockAgentRuns: AgentRunRecord[] = [
     session_id: "ws-1",
     started_at: "2025-02-15T07:30:00.000Z",
     completed_at: "2025-02-15T07:31:00.000Z",
+    retry_count: 0,
   },
   {
     agent_id: "run-2",
@@ -86,6 +88,7 @@ const mockAgentRuns: AgentRunRecord[] = [
     session_id: "ws-2",
     started_at: "2025-02-15T08:00:00.000Z",
     completed_at: "2025-02-15T08:03:00.000Z",
+    retry_count: 0,
   },
 ];
 
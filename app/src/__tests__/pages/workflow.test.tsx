@@ -33,6 +33,7 @@ vi.mock("sonner", () => ({
 // Mock @/lib/tauri
 vi.mock("@/lib/tauri", () => ({
   runWorkflowStep: vi.fn(),
+  getErrorHelp: vi.fn(() => Promise.resolve({ code: "unknown", title: "", message: "", steps: [], settings_tab: null })),
   readFile: vi.fn(() => Promise.reject("not found")),
   writeFile: vi.fn(() => Promise.resolve()),
   getClarificationsContent: vi.fn(() => Promise.reject("not found")),
@@ -43,6 +44,9 @@ vi.mock("@/lib/tauri", () => ({
   cleanupSkillSidecar: vi.fn(() => Promise.resolve()),
   acquireLock: vi.fn(() => Promise.resolve()),
   releaseLock: vi.fn(() => Promise.resolve()),
+  openSkillReadonly: vi.fn(() => Promise.resolve({ skill_name: "test-skill", lock: null, last_event_id: 0 })),
+  closeSkillReadonly: vi.fn(() => Promise.resolve()),
+  pollSkillEvents: vi.fn(() => Promise.resolve([])),
   persistAgentRun: vi.fn(() => Promise.resolve()),
   createWorkflowSession: vi.fn(() => Promise.resolve()),
   endWorkflowSession: vi.fn(() => Promise.resolve()),
@@ -51,11 +55,27 @@ vi.mock("@/lib/tauri", () => ({
   materializeAnswerEvaluationOutput: vi.fn(() => Promise.resolve()),
   previewStepReset: vi.fn(() => Promise.resolve([])),
   getDisabledSteps: vi.fn(() => Promise.resolve([])),
+  getWorkflowSteps: vi.fn(() => Promise.resolve([])),
   runAnswerEvaluator: vi.fn(() => Promise.reject("not available")),
   autofillClarifications: vi.fn(() => Promise.resolve(0)),
   logGateDecision: vi.fn(() => Promise.resolve()),
   navigateBackToStepDb: vi.fn(() => Promise.resolve()),
   getContextFileContent: vi.fn(() => Promise.resolve(null)),
+  explainStep: vi.fn(() => Promise.reject("not available")),
+  getEffectiveModel: vi.fn(() => Promise.reject("not available")),
+  findCachedStepRun: vi.fn(() => Promise.resolve(null)),
+  reuseCachedStepRun: vi.fn(() => Promise.resolve()),
+  findResumableStepRun: vi.fn(() => Promise.resolve(null)),
+  resumeWorkflowStep: vi.fn(() => Promise.resolve("test-skill-step0-2")),
+  getBudgetStatus: vi.fn(() => Promise.resolve({
+    skill_name: "test-skill",
+    skill_spent_usd: 0,
+    skill_budget_usd: null,
+    global_spent_usd: 0,
+    global_budget_usd: null,
+    state: "ok",
+    exceeded_scope: null,
+  })),
 }));
 
 // Mock ClarificationsEditor — renders a simple div with testid and
@@ -107,9 +127,17 @@ import {
   previewStepReset,
   runAnswerEvaluator,
   getDisabledSteps,
+  getWorkflowSteps,
   materializeWorkflowStepOutput,
   materializeAnswerEvaluationOutput,
   getContextFileContent,
+  findCachedStepRun,
+  reuseCachedStepRun,
+  findResumableStepRun,
+  resumeWorkflowStep,
+  acquireLock,
+  openSkillReadonly,
+  closeSkillReadonly,
 } from "@/lib/tauri";
 import { WorkflowSidebar } from "@/components/workflow-sidebar";
 import { WorkflowStepComplete } from "@/components/workflow-step-complete";
@@ -309,6 +337,33 @@ describe("WorkflowPage — agent completion lifecycle", () => {
     expect(mockToast.error).toHaveBeenCalledTimes(1);
   });
 
+  it("marks step as budget_exceeded when the backend stops the agent over budget", async () => {
+    useWorkflowStore.getState().initWorkflow("test-skill", "test domain");
+    useWorkflowStore.getState().setHydrated(true);
+    useWorkflowStore.getState().updateStepStatus(0, "in_progress");
+    useWorkflowStore.getState().setRunning(true);
+    useAgentStore.getState().startRun("agent-1", "sonnet");
+
+    render(<WorkflowPage />);
+
+    const reason = "'test-skill' has used its $1.00 budget ($1.02 spent).";
+    act(() => {
+      useAgentStore.getState().addMessage("agent-1", {
+        type: "error",
+        content: reason,
+        raw: { type: "error", subtype: "budget_exceeded", error: reason },
+        timestamp: Date.now(),
+      });
+      useAgentStore.getState().completeRun("agent-1", false);
+    });
+
+    await waitFor(() => {
+      expect(useWorkflowStore.getState().steps[0].status).toBe("budget_exceeded");
+    });
+    expect(useWorkflowStore.getState().isRunning).toBe(false);
+    expect(mockToast.error).toHaveBeenCalledWith(reason, { duration: Infinity });
+  });
+
   it("does not overwrite saved state during hydration", async () => {
     // Simulate: SQLite has step 0 completed from a previous session
     vi.mocked(getWorkflowState).mockResolvedValueOnce({
@@ -370,6 +425,48 @@ describe("WorkflowPage — agent completion lifecycle", () => {
     });
   });
 
+  it("appends custom steps from the workflow manifest after the built-in steps", async () => {
+    vi.mocked(getWorkflowSteps).mockResolvedValueOnce([
+      {
+        step_id: 4,
+        name: "Compliance Review",
+        prompt_template: "compliance-review.md",
+        output_file: "context/compliance-review.md",
+        allowed_tools: ["Read", "Write"],
+        max_turns: 40,
+        thinking_budget: null,
+        description: "Check the skill against policy",
+        custom: true,
+      },
+    ]);
+    vi.mocked(getWorkflowState).mockResolvedValueOnce({
+      run: {
+        skill_name: "test-skill",
+        current_step: 4,
+        status: "pending",
+        purpose: "domain",
+        created_at: "",
+        updated_at: "",
+      },
+      steps: [0, 1, 2, 3].map((step_id) => ({
+        skill_name: "test-skill",
+        step_id,
+        status: "completed",
+        started_at: null,
+        completed_at: null,
+      })),
+    });
+
+    render(<WorkflowPage />);
+
+    await waitFor(() => {
+      const { steps, currentStep } = useWorkflowStore.getState();
+      expect(steps).toHaveLength(5);
+      expect(steps[4]).toMatchObject({ id: 4, name: "Compliance Review", status: "pending" });
+      expect(currentStep).toBe(4);
+    });
+  });
+
   it("does not complete a step that is not in_progress", async () => {
     // Edge case: agent completion arrives but step is already completed
     // (e.g., from a stale agent)
@@ -530,6 +627,35 @@ describe("WorkflowPage — agent completion lifecycle", () => {
     expect(useAgentStore.getState().runs).not.toHaveProperty("old-agent");
   });
 
+  it("opens the skill read-only when another instance holds the lock", async () => {
+    mockNavigate.mockClear();
+    vi.mocked(acquireLock).mockRejectedValueOnce("Skill 'test-skill' is being edited in another instance");
+    useWorkflowStore.getState().initWorkflow("test-skill", "test domain");
+    useWorkflowStore.getState().setHydrated(true);
+    useWorkflowStore.getState().setReviewMode(false);
+
+    const { unmount } = render(<WorkflowPage />);
+
+    await waitFor(() => {
+      expect(useWorkflowStore.getState().readOnly).toBe(true);
+    });
+    expect(vi.mocked(openSkillReadonly)).toHaveBeenCalledWith("test-skill");
+    expect(useWorkflowStore.getState().reviewMode).toBe(true);
+    expect(screen.getByTestId("read-only-banner")).toBeDefined();
+    expect(mockNavigate).not.toHaveBeenCalled();
+
+    act(() => {
+      useWorkflowStore.getState().updateStepStatus(0, "completed");
+    });
+    await act(async () => {
+      await new Promise((r) => setTimeout(r, 400));
+    });
+    expect(vi.mocked(saveWorkflowState)).not.toHaveBeenCalled();
+
+    unmount();
+    expect(vi.mocked(closeSkillReadonly)).toHaveBeenCalledWith("test-skill");
+  });
+
   it("shows Start Step button on initial create-flow load (no auto-start)", async () => {
     useWorkflowStore.getState().initWorkflow("test-skill", "test domain");
     useWorkflowStore.getState().setHydrated(true);
@@ -547,6 +673,77 @@ describe("WorkflowPage — agent completion lifecycle", () => {
     expect(vi.mocked(runWorkflowStep)).not.toHaveBeenCalled();
   });
 
+  it("offers to reuse a cached run instead of starting the agent", async () => {
+    vi.mocked(findCachedStepRun).mockResolvedValueOnce({
+      skill_name: "test-skill",
+      step_id: 0,
+      fingerprint: "abc123",
+      agent_id: "test-skill-step0-1",
+      model: "sonnet",
+      cost_usd: 0.42,
+      created_at: "2026-10-01T12:00:00Z",
+    });
+    vi.mocked(runWorkflowStep).mockClear();
+    useWorkflowStore.getState().initWorkflow("test-skill", "test domain");
+    useWorkflowStore.getState().setHydrated(true);
+    useWorkflowStore.getState().setReviewMode(false);
+
+    render(<WorkflowPage />);
+
+    await act(async () => {
+      screen.getByText("Start Step").click();
+    });
+    expect(await screen.findByText("Reuse Previous Output?")).toBeTruthy();
+    expect(screen.getByText(/saves about \$0\.42/)).toBeTruthy();
+
+    await act(async () => {
+      screen.getByRole("button", { name: "Reuse Output" }).click();
+    });
+
+    await waitFor(() => {
+      expect(useWorkflowStore.getState().steps[0].status).toBe("completed");
+    });
+    expect(vi.mocked(reuseCachedStepRun)).toHaveBeenCalledWith("test-skill", 0, "/test/workspace");
+    expect(vi.mocked(runWorkflowStep)).not.toHaveBeenCalled();
+  });
+
+  it("offers to resume an interrupted run of the step", async () => {
+    vi.mocked(findResumableStepRun).mockResolvedValueOnce({
+      agent_id: "test-skill-step0-1",
+      skill_name: "test-skill",
+      step_id: 0,
+      pid: 4242,
+      sdk_session_id: "sdk-1",
+      transcript_path: null,
+      turns: 12,
+      status: "interrupted",
+      resumed_from: null,
+      started_at: "2026-10-01T12:00:00Z",
+      updated_at: "2026-10-01T12:05:00Z",
+    });
+    vi.mocked(runWorkflowStep).mockClear();
+    useWorkflowStore.getState().initWorkflow("test-skill", "test domain");
+    useWorkflowStore.getState().setHydrated(true);
+    useWorkflowStore.getState().setReviewMode(false);
+
+    render(<WorkflowPage />);
+
+    await act(async () => {
+      screen.getByText("Start Step").click();
+    });
+    expect(await screen.findByText("Resume Interrupted Run?")).toBeTruthy();
+    expect(screen.getByText(/stopped after 12 turns/)).toBeTruthy();
+
+    await act(async () => {
+      screen.getByRole("button", { name: "Resume" }).click();
+    });
+
+    await waitFor(() => {
+      expect(vi.mocked(resumeWorkflowStep)).toHaveBeenCalledWith("test-skill", 0, "/test/workspace");
+    });
+    expect(vi.mocked(runWorkflowStep)).not.toHaveBeenCalled();
+  });
+
   it("renders completion screen on last step (step 3)", async () => {
     // Simulate all steps complete, on step 3 (the last step)
     useWorkflowStore.getState().initWorkflow("test-skill", "test domain");
@@ -354,6 +354,31 @@ describe("shutdownRun", () => {
     expect(run.endTime).toBeGreaterThanOrEqual(beforeShutdown);
   });
 
+  it("persists the last live cost tick when shut down before a result", () => {
+    mockInvoke.mockReset().mockResolvedValue(undefined);
+    useAgentStore.getState().startRun("agent-1", "sonnet");
+    useAgentStore.getState().recordCostTick("agent-1", {
+      inputTokens: 1200,
+      outputTokens: 300,
+      cacheReadTokens: 4000,
+      cacheWriteTokens: 0,
+      totalCost: 0.0093,
+      estimated: true,
+    });
+    useAgentStore.getState().shutdownRun("agent-1");
+
+    const persistCalls = (mockInvoke.mock.calls as [string, Record<string, unknown>][]).filter(
+      ([cmd]) => cmd === "persist_agent_run",
+    );
+    expect(persistCalls).toHaveLength(1);
+    const args = persistCalls[0][1] as Record<string, unknown>;
+    expect(args.status).toBe("shutdown");
+    expect(args.inputTokens).toBe(1200);
+    expect(args.outputTokens).toBe(300);
+    expect(args.cacheReadTokens).toBe(4000);
+    expect(args.totalCost).toBe(0.0093);
+  });
+
   it("no-ops when run doesn't exist", () => {
     useAgentStore.getState().shutdownRun("nonexistent");
     const state = useAgentStore.getState();
@@ -980,6 +1005,27 @@ describe("completeRun persistence with modelUsageBreakdown", () => {
     mockInvoke.mockReset().mockResolvedValue(undefined);
   });
 
+  it("passes time to first token from the first assistant message", () => {
+    useAgentStore.getState().startRun("agent-ttft", "sonnet");
+    const startTime = useAgentStore.getState().runs["agent-ttft"].startTime;
+    mockInvoke.mockClear();
+
+    useAgentStore.getState().addMessage("agent-ttft", {
+      type: "assistant",
+      content: "Thinking",
+      raw: {},
+      timestamp: startTime + 1500,
+    });
+    flushMessageBuffer();
+    useAgentStore.getState().completeRun("agent-ttft", true);
+
+    const persistCalls = (mockInvoke.mock.calls as [string, Record<string, unknown>][]).filter(
+      ([cmd]) => cmd === "persist_agent_run",
+    );
+    expect(persistCalls).toHaveLength(1);
+    expect(persistCalls[0][1].timeToFirstTokenMs).toBe(1500);
+  });
+
   it("calls persistAgentRun once per model when breakdown has 2+ models", () => {
     useAgentStore.getState().startRun("agent-1", "sonnet");
     mockInvoke.mockClear();
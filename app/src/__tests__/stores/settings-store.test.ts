@@ -35,6 +35,14 @@ describe("useSettingsStore", () => {
     expect(state.isConfigured).toBe(false);
   });
 
+  it("offline mode with skillsPath is configured without an apiKey", () => {
+    useSettingsStore.getState().setSettings({
+      offlineMode: true,
+      skillsPath: "/some/skills",
+    });
+    expect(useSettingsStore.getState().isConfigured).toBe(true);
+  });
+
   it("setSettings without apiKey keeps isConfigured false", () => {
     useSettingsStore.getState().setSettings({
       workspacePath: "/some/path",
@@ -64,6 +64,7 @@ const mockAgentRuns: AgentRunRecord[] = [
     session_id: "sess-1",
     started_at: "2026-02-15T10:00:00Z",
     completed_at: "2026-02-15T10:00:12Z",
+    retry_count: 0,
   },
 ];
 
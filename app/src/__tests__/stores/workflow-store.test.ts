@@ -439,4 +439,18 @@ describe("useWorkflowStore", () => {
       expect(useWorkflowStore.getState().disabledSteps).toEqual([]);
     });
   });
+
+  describe("readOnly", () => {
+    it("forces review mode and survives initWorkflow but not reset", () => {
+      useWorkflowStore.getState().setReviewMode(false);
+      useWorkflowStore.getState().setReadOnly(true);
+      expect(useWorkflowStore.getState().reviewMode).toBe(true);
+
+      useWorkflowStore.getState().initWorkflow("my-skill", "domain");
+      expect(useWorkflowStore.getState().readOnly).toBe(true);
+
+      useWorkflowStore.getState().reset();
+      expect(useWorkflowStore.getState().readOnly).toBe(false);
+    });
+  });
 });
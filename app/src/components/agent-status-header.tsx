@@ -6,6 +6,7 @@ import {
   Clock,
   Cpu,
   Brain,
+  Wrench,
 } from "lucide-react";
 import { CardHeader, CardTitle } from "@/components/ui/card";
 import { Badge } from "@/components/ui/badge";
@@ -15,6 +16,7 @@ import {
   formatTokenCount,
   getLatestContextTokens,
   getContextUtilization,
+  type StepProgress,
 } from "@/stores/agent-store";
 import { useWorkflowStore } from "@/stores/workflow-store";
 import { formatElapsed } from "@/lib/utils";
@@ -49,6 +51,29 @@ function ContextMeter({ agentId }: { agentId: string }) {
   );
 }
 
+/** Percent done and time left for a running workflow step, from `workflow-step-progress`. */
+function StepProgressMeter({ progress }: { progress: StepProgress }) {
+  if (progress.percent === null) return null;
+  // Count the ETA down between events; the header re-renders every second.
+  const remaining =
+    progress.etaMs === null ? null : progress.etaMs - (Date.now() - progress.receivedAt);
+
+  return (
+    <div className="flex items-center gap-1.5" title="Estimated from previous runs of this step">
+      <div className="h-2 w-16 overflow-hidden rounded-full bg-muted">
+        <div
+          className="h-full transition-all"
+          style={{ width: `${Math.max(1, progress.percent)}%`, background: "var(--color-pacific)" }}
+        />
+      </div>
+      <span className="text-xs tabular-nums text-muted-foreground">
+        {progress.percent}%
+        {remaining !== null && remaining > 0 && ` · ~${formatElapsed(remaining)} left`}
+      </span>
+    </div>
+  );
+}
+
 interface AgentStatusHeaderProps {
   agentId: string;
   title?: string;
@@ -127,6 +152,7 @@ export function AgentStatusHeader({
 
   const currentStatus = displayStatus ?? "running";
   const turnCount = run.messages.filter((m) => m.type === "assistant").length;
+  const progress = run.status === "running" ? run.stepProgress : undefined;
 
   return (
     <CardHeader className="shrink-0 flex-row items-center justify-between space-y-0 pb-3">
@@ -146,9 +172,21 @@ export function AgentStatusHeader({
           <Clock className="size-3" />
           {formatElapsed(elapsed)}
         </Badge>
-        {turnCount > 0 && (
+        {progress?.maxTurns ? (
           <Badge variant="secondary" className="text-xs">
-            Turn {turnCount}
+            Turn {progress.turnsCompleted}/{progress.maxTurns}
+          </Badge>
+        ) : (
+          turnCount > 0 && (
+            <Badge variant="secondary" className="text-xs">
+              Turn {turnCount}
+            </Badge>
+          )
+        )}
+        {progress?.currentTool && (
+          <Badge variant="secondary" className="gap-1 text-xs">
+            <Wrench className="size-3" />
+            {progress.currentTool}
           </Badge>
         )}
         {run.thinkingEnabled && (
@@ -167,6 +205,16 @@ export function AgentStatusHeader({
             ${run.totalCost.toFixed(4)}
           </Badge>
         )}
+        {run.totalCost === undefined && run.liveCost && (
+          <Badge
+            variant="secondary"
+            className="text-xs tabular-nums"
+            title={run.liveCost.estimated ? "Estimated from token usage so far" : undefined}
+          >
+            {run.liveCost.estimated ? "~" : ""}${run.liveCost.totalCost.toFixed(4)}
+          </Badge>
+        )}
+        {progress && <StepProgressMeter progress={progress} />}
         <ContextMeter agentId={agentId} />
       </div>
     </CardHeader>
@@ -0,0 +1,225 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import { ChevronDown, ChevronRight, MessageSquarePlus, Pencil, Trash2 } from "lucide-react";
+import { Badge } from "@/components/ui/badge";
+import { Button } from "@/components/ui/button";
+import { Textarea } from "@/components/ui/textarea";
+import {
+  addArtifactAnnotation,
+  deleteArtifactAnnotation,
+  listArtifactAnnotations,
+  updateArtifactAnnotation,
+} from "@/lib/tauri";
+import type { ArtifactAnnotation } from "@/lib/types";
+import { cn } from "@/lib/utils";
+
+interface ArtifactAnnotationsPanelProps {
+  skillName: string;
+  workspacePath: string;
+  /** Relative to the skill's workspace directory, e.g. `context/clarifications.json`. */
+  file: string;
+  content: string;
+}
+
+interface LineSelection {
+  start: number;
+  end: number;
+}
+
+const errorMessage = (err: unknown) => (err instanceof Error ? err.message : String(err));
+
+const lineLabel = (start: number, end: number) => (start === end ? `Line ${start}` : `Lines ${start}–${end}`);
+
+/**
+ * Reviewer comments anchored to lines of a context file. Click a line to
+ * select it, shift-click to extend the selection, then add a comment.
+ */
+export function ArtifactAnnotationsPanel({ skillName, workspacePath, file, content }: ArtifactAnnotationsPanelProps) {
+  const [expanded, setExpanded] = useState(false);
+  const [annotations, setAnnotations] = useState<ArtifactAnnotation[]>([]);
+  const [selection, setSelection] = useState<LineSelection | null>(null);
+  const [draft, setDraft] = useState("");
+  const [editing, setEditing] = useState<{ id: number; comment: string } | null>(null);
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    listArtifactAnnotations(skillName, workspacePath, file)
+      .then(setAnnotations)
+      .catch((err) => console.error("[annotations] list failed:", err));
+  }, [skillName, workspacePath, file, content]);
+
+  const lines = content.split("\n");
+  const annotatedLines = new Set(
+    annotations
+      .filter((a) => !a.orphaned)
+      .flatMap((a) => Array.from({ length: a.end_line - a.start_line + 1 }, (_, i) => a.start_line + i)),
+  );
+
+  const handleLineClick = (line: number, extend: boolean) => {
+    if (extend && selection) {
+      setSelection({ start: Math.min(selection.start, line), end: Math.max(selection.start, line) });
+    } else {
+      setSelection({ start: line, end: line });
+    }
+  };
+
+  const handleAdd = async () => {
+    if (!selection || !draft.trim()) return;
+    setSaving(true);
+    try {
+      const created = await addArtifactAnnotation(skillName, workspacePath, file, selection.start, selection.end, draft);
+      setAnnotations((prev) => [...prev, created].sort((a, b) => a.start_line - b.start_line || a.id - b.id));
+      setSelection(null);
+      setDraft("");
+    } catch (err) {
+      console.error("[annotations] add failed:", err);
+      toast.error(`Failed to add annotation: ${errorMessage(err)}`, { duration: Infinity });
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleUpdate = async () => {
+    if (!editing || !editing.comment.trim()) return;
+    setSaving(true);
+    try {
+      const updated = await updateArtifactAnnotation(editing.id, editing.comment);
+      setAnnotations((prev) => prev.map((a) => (a.id === updated.id ? updated : a)));
+      setEditing(null);
+    } catch (err) {
+      console.error("[annotations] update failed:", err);
+      toast.error(`Failed to update annotation: ${errorMessage(err)}`, { duration: Infinity });
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleDelete = async (id: number) => {
+    try {
+      await deleteArtifactAnnotation(id);
+      setAnnotations((prev) => prev.filter((a) => a.id !== id));
+    } catch (err) {
+      console.error("[annotations] delete failed:", err);
+      toast.error(`Failed to delete annotation: ${errorMessage(err)}`, { duration: Infinity });
+    }
+  };
+
+  const isSelected = (line: number) => !!selection && line >= selection.start && line <= selection.end;
+
+  return (
+    <div className="shrink-0 rounded-lg border" data-testid="artifact-annotations">
+      <button
+        type="button"
+        className="flex w-full items-center gap-2 px-3 py-2 text-sm font-medium"
+        onClick={() => setExpanded((v) => !v)}
+      >
+        {expanded ? <ChevronDown className="size-4" /> : <ChevronRight className="size-4" />}
+        Annotations
+        <Badge variant="secondary">{annotations.length}</Badge>
+        <span className="ml-auto font-mono text-xs text-muted-foreground">{file}</span>
+      </button>
+
+      {expanded && (
+        <div className="flex flex-col gap-3 border-t p-3">
+          <div className="max-h-64 overflow-auto rounded-md border bg-muted/30 font-mono text-xs">
+            {lines.map((text, i) => {
+              const line = i + 1;
+              return (
+                <div
+                  key={line}
+                  role="button"
+                  tabIndex={-1}
+                  data-testid={`annotation-line-${line}`}
+                  className={cn(
+                    "flex cursor-pointer gap-3 px-2 hover:bg-muted",
+                    annotatedLines.has(line) && "bg-amber-500/10",
+                    isSelected(line) && "bg-primary/15",
+                  )}
+                  onClick={(e) => handleLineClick(line, e.shiftKey)}
+                >
+                  <span className="w-8 shrink-0 select-none text-right text-muted-foreground">{line}</span>
+                  <span className="whitespace-pre-wrap break-all">{text || " "}</span>
+                </div>
+              );
+            })}
+          </div>
+
+          {selection && (
+            <div className="flex flex-col gap-2">
+              <span className="text-xs text-muted-foreground">{lineLabel(selection.start, selection.end)}</span>
+              <Textarea
+                value={draft}
+                onChange={(e) => setDraft(e.target.value)}
+                placeholder="Comment on the selected lines"
+                rows={2}
+              />
+              <div className="flex justify-end gap-2">
+                <Button variant="ghost" size="sm" onClick={() => setSelection(null)}>
+                  Cancel
+                </Button>
+                <Button size="sm" disabled={saving || !draft.trim()} onClick={handleAdd}>
+                  <MessageSquarePlus className="size-4" />
+                  Add comment
+                </Button>
+              </div>
+            </div>
+          )}
+
+          {annotations.length === 0 ? (
+            <p className="text-xs text-muted-foreground">No annotations yet. Click a line to comment on it.</p>
+          ) : (
+            <ul className="flex flex-col gap-2">
+              {annotations.map((a) => (
+                <li key={a.id} className="rounded-md border p-2 text-sm" data-testid={`annotation-${a.id}`}>
+                  <div className="flex items-center gap-2 text-xs text-muted-foreground">
+                    <button
+                      type="button"
+                      className="hover:underline"
+                      onClick={() => setSelection({ start: a.start_line, end: a.end_line })}
+                    >
+                      {lineLabel(a.start_line, a.end_line)}
+                    </button>
+                    {a.orphaned && <Badge variant="outline">Outdated</Badge>}
+                    {a.author && <span>· {a.author}</span>}
+                    <div className="ml-auto flex gap-1">
+                      <Button
+                        variant="ghost"
+                        size="icon-xs"
+                        aria-label="Edit annotation"
+                        onClick={() => setEditing({ id: a.id, comment: a.comment })}
+                      >
+                        <Pencil className="size-3" />
+                      </Button>
+                      <Button variant="ghost" size="icon-xs" aria-label="Delete annotation" onClick={() => handleDelete(a.id)}>
+                        <Trash2 className="size-3" />
+                      </Button>
+                    </div>
+                  </div>
+                  {editing?.id === a.id ? (
+                    <div className="mt-1 flex flex-col gap-2">
+                      <Textarea
+                        value={editing.comment}
+                        onChange={(e) => setEditing({ id: a.id, comment: e.target.value })}
+                        rows={2}
+                      />
+                      <div className="flex justify-end gap-2">
+                        <Button variant="ghost" size="sm" onClick={() => setEditing(null)}>
+                          Cancel
+                        </Button>
+                        <Button size="sm" disabled={saving || !editing.comment.trim()} onClick={handleUpdate}>
+                          Save
+                        </Button>
+                      </div>
+                    </div>
+                  ) : (
+                    <p className="mt-1 whitespace-pre-wrap">{a.comment}</p>
+                  )}
+                </li>
+              ))}
+            </ul>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,134 @@
+import { useCallback, useEffect, useState } from "react"
+import { toast } from "sonner"
+import { Download, Loader2 } from "lucide-react"
+import { Badge } from "@/components/ui/badge"
+import { Button } from "@/components/ui/button"
+import { Input } from "@/components/ui/input"
+import { exportAuditLog, getAuditLog } from "@/lib/tauri"
+import type { AuditLogEntry, AuditLogFilter, AuditLogFormat } from "@/lib/types"
+
+const ACTIONS: { value: string; label: string }[] = [
+  { value: "skill.created", label: "Skill created" },
+  { value: "skill.renamed", label: "Skill renamed" },
+  { value: "skill.deleted", label: "Skill deleted" },
+  { value: "skill.pushed", label: "Skill pushed" },
+  { value: "lock.acquired", label: "Lock acquired" },
+  { value: "lock.released", label: "Lock released" },
+  { value: "settings.changed", label: "Settings changed" },
+  { value: "data.pruned", label: "Retention cleanup" },
+  { value: "data.purged", label: "User data purged" },
+]
+
+const errorMessage = (err: unknown) => (err instanceof Error ? err.message : String(err))
+
+/** Read-only view of the audit log, with filters and CSV / JSON Lines export. */
+export function AuditLogViewer({ workspacePath }: { workspacePath: string | null }) {
+  const [entries, setEntries] = useState<AuditLogEntry[] | null>(null)
+  const [action, setAction] = useState("")
+  const [skillName, setSkillName] = useState("")
+  const [query, setQuery] = useState("")
+  const [exporting, setExporting] = useState(false)
+
+  const filter = useCallback(
+    (): AuditLogFilter => ({
+      action: action || null,
+      skill_name: skillName.trim() || null,
+      query: query.trim() || null,
+    }),
+    [action, skillName, query],
+  )
+
+  useEffect(() => {
+    getAuditLog(filter())
+      .then((result) => setEntries(result ?? []))
+      .catch((err) => {
+        console.warn("[audit-log-viewer] get_audit_log failed:", err)
+        setEntries([])
+      })
+  }, [filter])
+
+  const handleExport = async (format: AuditLogFormat) => {
+    if (!workspacePath) return
+    setExporting(true)
+    try {
+      const result = await exportAuditLog(workspacePath, format, filter())
+      toast.success(`Exported ${result.entry_count} entries to ${result.output_path}`)
+    } catch (err) {
+      toast.error(`Failed to export audit log: ${errorMessage(err)}`, { duration: Infinity })
+    } finally {
+      setExporting(false)
+    }
+  }
+
+  return (
+    <div className="flex flex-col gap-4">
+      <div className="flex flex-wrap items-center gap-2">
+        <select
+          aria-label="Action"
+          value={action}
+          onChange={(e) => setAction(e.target.value)}
+          className="flex h-9 w-fit rounded-md border border-input bg-transparent px-3 py-1 text-sm shadow-xs transition-colors focus-visible:outline-none focus-visible:ring-1 focus-visible:ring-ring"
+        >
+          <option value="">All actions</option>
+          {ACTIONS.map((a) => (
+            <option key={a.value} value={a.value}>
+              {a.label}
+            </option>
+          ))}
+        </select>
+        <Input
+          aria-label="Skill"
+          className="w-40"
+          placeholder="Skill name"
+          value={skillName}
+          onChange={(e) => setSkillName(e.target.value)}
+        />
+        <Input
+          aria-label="Search summaries"
+          className="w-48"
+          placeholder="Search"
+          value={query}
+          onChange={(e) => setQuery(e.target.value)}
+        />
+        <div className="ml-auto flex gap-2">
+          {(["csv", "jsonl"] as const).map((format) => (
+            <Button
+              key={format}
+              variant="outline"
+              size="sm"
+              disabled={exporting || !workspacePath}
+              onClick={() => handleExport(format)}
+            >
+              <Download className="size-3.5" />
+              Export {format.toUpperCase()}
+            </Button>
+          ))}
+        </div>
+      </div>
+
+      {entries === null ? (
+        <Loader2 className="size-4 animate-spin text-muted-foreground" />
+      ) : entries.length === 0 ? (
+        <p className="text-sm text-muted-foreground">No audit entries match.</p>
+      ) : (
+        <div className="flex max-h-80 flex-col gap-1 overflow-y-auto">
+          {entries.map((entry) => (
+            <div key={entry.id} className="flex items-center gap-2 text-sm" data-testid="audit-entry">
+              <span className="shrink-0 font-mono text-xs text-muted-foreground">{entry.created_at}</span>
+              <Badge variant="secondary" className="shrink-0 text-xs">
+                {entry.action}
+              </Badge>
+              {entry.skill_name && <span className="shrink-0 font-medium">{entry.skill_name}</span>}
+              <span className="truncate text-muted-foreground" title={entry.summary}>
+                {entry.summary}
+              </span>
+              <span className="ml-auto shrink-0 text-xs text-muted-foreground" title={`Instance ${entry.instance_id}`}>
+                {entry.actor ?? "unknown"}
+              </span>
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
+  )
+}
@@ -0,0 +1,168 @@
+import { useCallback, useEffect, useState } from "react"
+import { Plus, RefreshCw, Trash2 } from "lucide-react"
+import { Badge } from "@/components/ui/badge"
+import { Button } from "@/components/ui/button"
+import { Checkbox } from "@/components/ui/checkbox"
+import { Input } from "@/components/ui/input"
+import { Label } from "@/components/ui/label"
+import { Switch } from "@/components/ui/switch"
+import { listHookDeliveries } from "@/lib/tauri"
+import type { AutomationHook, AutomationHookEvent, HookDelivery } from "@/lib/types"
+
+const EVENT_LABELS: Record<AutomationHookEvent, string> = {
+  step_completed: "Step completed",
+  skill_packaged: "Skill packaged",
+  budget_exceeded: "Budget exceeded",
+}
+
+const RECENT_DELIVERIES = 20
+
+function isValidTarget(kind: AutomationHook["kind"], target: string): boolean {
+  const trimmed = target.trim()
+  if (kind === "webhook") return trimmed.startsWith("https://") || trimmed.startsWith("http://")
+  return trimmed.length > 0
+}
+
+/** Edits the hooks in settings; `onChange` saves them. Also lists recent deliveries. */
+export function AutomationHooksSettings({
+  hooks,
+  onChange,
+}: {
+  hooks: AutomationHook[]
+  onChange: (hooks: AutomationHook[]) => void
+}) {
+  const [name, setName] = useState("")
+  const [kind, setKind] = useState<AutomationHook["kind"]>("webhook")
+  const [target, setTarget] = useState("")
+  const [events, setEvents] = useState<AutomationHookEvent[]>(["skill_packaged"])
+  const [deliveries, setDeliveries] = useState<HookDelivery[]>([])
+
+  const refresh = useCallback(async () => {
+    try {
+      setDeliveries(await listHookDeliveries(RECENT_DELIVERIES))
+    } catch (err) {
+      console.warn("[automation-hooks-settings] list_hook_deliveries failed:", err)
+    }
+  }, [])
+
+  useEffect(() => {
+    refresh()
+  }, [refresh])
+
+  const nameTaken = hooks.some((h) => h.name === name.trim())
+  const canAdd = name.trim().length > 0 && !nameTaken && events.length > 0 && isValidTarget(kind, target)
+
+  const toggleEvent = (event: AutomationHookEvent, checked: boolean) => {
+    setEvents(checked ? [...events, event] : events.filter((e) => e !== event))
+  }
+
+  const addHook = () => {
+    onChange([...hooks, { name: name.trim(), kind, target: target.trim(), events, enabled: true }])
+    setName("")
+    setTarget("")
+    setEvents(["skill_packaged"])
+  }
+
+  return (
+    <div className="flex flex-col gap-4">
+      {hooks.map((hook, index) => (
+        <div key={hook.name} className="flex items-center gap-3 rounded-md border p-3" data-testid="automation-hook">
+          <Switch
+            aria-label={`Enable ${hook.name}`}
+            checked={hook.enabled}
+            onCheckedChange={(checked) => onChange(hooks.map((h, i) => (i === index ? { ...h, enabled: checked } : h)))}
+          />
+          <div className="flex min-w-0 flex-1 flex-col gap-1">
+            <div className="flex items-center gap-2">
+              <span className="text-sm font-medium">{hook.name}</span>
+              <Badge variant="outline">{hook.kind}</Badge>
+              {hook.events.map((event) => (
+                <Badge key={event} variant="secondary">{EVENT_LABELS[event] ?? event}</Badge>
+              ))}
+            </div>
+            <span className="truncate font-mono text-xs text-muted-foreground">{hook.target}</span>
+          </div>
+          <Button
+            variant="ghost"
+            size="icon"
+            aria-label={`Remove hook ${hook.name}`}
+            onClick={() => onChange(hooks.filter((_, i) => i !== index))}
+          >
+            <Trash2 className="size-4" />
+          </Button>
+        </div>
+      ))}
+
+      <div className="flex flex-col gap-2 rounded-md border border-dashed p-3">
+        <div className="flex items-center gap-2">
+          <Input
+            aria-label="Hook name"
+            placeholder="slack-announce"
+            value={name}
+            onChange={(e) => setName(e.target.value)}
+            className="w-40"
+          />
+          <select
+            aria-label="Hook kind"
+            value={kind}
+            onChange={(e) => setKind(e.target.value as AutomationHook["kind"])}
+            className="flex h-9 rounded-md border border-input bg-transparent px-3 py-1 text-sm shadow-sm"
+          >
+            <option value="webhook">Webhook</option>
+            <option value="command">Shell command</option>
+          </select>
+          <Input
+            aria-label="Hook target"
+            placeholder={kind === "webhook" ? "https://hooks.slack.com/services/..." : "./notify.sh"}
+            value={target}
+            onChange={(e) => setTarget(e.target.value)}
+            className="flex-1 font-mono"
+          />
+        </div>
+        <div className="flex items-center gap-4">
+          {(Object.keys(EVENT_LABELS) as AutomationHookEvent[]).map((event) => (
+            <div key={event} className="flex items-center gap-2">
+              <Checkbox
+                id={`hook-event-${event}`}
+                checked={events.includes(event)}
+                onCheckedChange={(checked) => toggleEvent(event, checked === true)}
+              />
+              <Label htmlFor={`hook-event-${event}`} className="font-normal">{EVENT_LABELS[event]}</Label>
+            </div>
+          ))}
+          <Button variant="outline" size="sm" className="ml-auto" disabled={!canAdd} onClick={addHook}>
+            <Plus className="size-4" />
+            Add hook
+          </Button>
+        </div>
+        {nameTaken && <p className="text-xs text-destructive">A hook named &quot;{name.trim()}&quot; already exists.</p>}
+      </div>
+
+      <div className="flex flex-col gap-2">
+        <div className="flex items-center justify-between">
+          <Label>Recent deliveries</Label>
+          <Button variant="ghost" size="icon" aria-label="Refresh deliveries" onClick={refresh}>
+            <RefreshCw className="size-4" />
+          </Button>
+        </div>
+        {deliveries.length === 0 ? (
+          <p className="text-sm text-muted-foreground">No events delivered yet.</p>
+        ) : (
+          <ul className="flex flex-col gap-1 text-sm">
+            {deliveries.map((d) => (
+              <li key={d.id} className="flex items-center gap-2" data-testid="hook-delivery">
+                <Badge variant={d.status === "delivered" ? "secondary" : "destructive"}>{d.status}</Badge>
+                <span className="font-medium">{d.hook_name}</span>
+                <span className="text-muted-foreground">
+                  {EVENT_LABELS[d.event] ?? d.event} · {d.skill_name}
+                  {d.attempts > 1 ? ` · ${d.attempts} attempts` : ""}
+                </span>
+                <span className="ml-auto truncate text-xs text-muted-foreground" title={d.detail}>{d.detail}</span>
+              </li>
+            ))}
+          </ul>
+        )}
+      </div>
+    </div>
+  )
+}
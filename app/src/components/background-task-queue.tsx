@@ -0,0 +1,125 @@
+import { useCallback, useEffect, useState } from "react"
+import { toast } from "sonner"
+import { Loader2, RefreshCw, X } from "lucide-react"
+import { Badge } from "@/components/ui/badge"
+import { Button } from "@/components/ui/button"
+import {
+  cancelBackgroundTask,
+  enqueueBackgroundTask,
+  listBackgroundTasks,
+  setBackgroundTaskPriority,
+} from "@/lib/tauri"
+import type { BackgroundQueue, BackgroundTaskKind, TaskPriority } from "@/lib/types"
+
+const POLL_INTERVAL_MS = 5000
+
+const KIND_LABELS: Record<BackgroundTaskKind, string> = {
+  catalog_prefetch: "Marketplace catalog refresh",
+  retention_prune: "Data retention cleanup",
+}
+
+function powerLabel(queue: BackgroundQueue): string {
+  const power = queue.power
+  if (!power) return "Power: mains"
+  const percent = power.battery_percent !== null ? ` (${power.battery_percent}%)` : ""
+  return power.on_battery ? `Power: battery${percent}` : `Power: charging${percent}`
+}
+
+export function BackgroundTaskQueue() {
+  const [queue, setQueue] = useState<BackgroundQueue | null>(null)
+
+  const refresh = useCallback(async () => {
+    try {
+      const result = await listBackgroundTasks()
+      if (result) setQueue(result)
+    } catch (err) {
+      console.warn("[background-task-queue] list_background_tasks failed:", err)
+    }
+  }, [])
+
+  useEffect(() => {
+    refresh()
+    const timer = setInterval(refresh, POLL_INTERVAL_MS)
+    return () => clearInterval(timer)
+  }, [refresh])
+
+  const run = async (action: Promise<unknown>, failure: string) => {
+    try {
+      await action
+    } catch (err) {
+      toast.error(`${failure}: ${err instanceof Error ? err.message : String(err)}`, { duration: Infinity })
+    }
+    refresh()
+  }
+
+  if (!queue) {
+    return <Loader2 className="size-4 animate-spin text-muted-foreground" />
+  }
+
+  return (
+    <div className="flex flex-col gap-3">
+      <div className="flex items-center gap-2 text-sm text-muted-foreground">
+        <span>{queue.agents_running ? "Agents running" : "No agents running"}</span>
+        <span>·</span>
+        <span>{powerLabel(queue)}</span>
+        <Button
+          variant="outline"
+          size="sm"
+          className="ml-auto"
+          onClick={() => run(enqueueBackgroundTask("catalog_prefetch", "high"), "Failed to queue refresh")}
+        >
+          <RefreshCw className="size-3.5" />
+          Refresh catalogs now
+        </Button>
+      </div>
+      {queue.tasks.length === 0 ? (
+        <p className="text-sm text-muted-foreground">No background tasks yet.</p>
+      ) : (
+        <ul className="flex flex-col gap-2">
+          {queue.tasks.map((task) => (
+            <li key={task.id} className="flex items-center gap-2 text-sm">
+              <span className="flex-1">
+                {KIND_LABELS[task.kind] ?? task.kind}
+                {task.deferred_reason && (
+                  <span className="ml-2 text-xs text-muted-foreground">{task.deferred_reason}</span>
+                )}
+                {task.error && <span className="ml-2 text-xs text-destructive">{task.error}</span>}
+              </span>
+              <Badge variant={task.status === "failed" ? "destructive" : "secondary"}>{task.status}</Badge>
+              {task.status === "queued" ? (
+                <>
+                  <select
+                    aria-label={`Priority for task ${task.id}`}
+                    value={task.priority}
+                    onChange={(e) =>
+                      run(
+                        setBackgroundTaskPriority(task.id, e.target.value as TaskPriority),
+                        "Failed to change priority",
+                      )
+                    }
+                    className="flex h-8 w-fit rounded-md border border-input bg-transparent px-2 text-sm shadow-xs"
+                  >
+                    <option value="low">Low</option>
+                    <option value="normal">Normal</option>
+                    <option value="high">High</option>
+                  </select>
+                  <Button
+                    variant="ghost"
+                    size="icon"
+                    className="size-7"
+                    aria-label={`Cancel task ${task.id}`}
+                    onClick={() => run(cancelBackgroundTask(task.id), "Failed to cancel task")}
+                  >
+                    <X className="size-3.5" />
+                  </Button>
+                </>
+              ) : (
+                <span className="w-fit text-xs text-muted-foreground">{task.priority}</span>
+              )}
+            </li>
+          ))}
+        </ul>
+      )}
+    </div>
+  )
+}
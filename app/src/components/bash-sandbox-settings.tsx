@@ -0,0 +1,137 @@
+import { useEffect, useState } from "react"
+import { toast } from "sonner"
+import { Loader2 } from "lucide-react"
+import { Button } from "@/components/ui/button"
+import { Label } from "@/components/ui/label"
+import { Switch } from "@/components/ui/switch"
+import { Textarea } from "@/components/ui/textarea"
+import { getToolPolicy, setToolPolicy } from "@/lib/tauri"
+import type { BashSandboxPolicy, ToolPolicy } from "@/lib/types"
+
+const toLines = (patterns: string[]) => patterns.join("\n")
+
+const fromLines = (text: string) =>
+  text.split("\n").map((line) => line.trim()).filter((line) => line.length > 0)
+
+/** Edits the Bash sandbox of the global tool policy, or of one skill when `skillName` is set. */
+export function BashSandboxSettings({ skillName = null }: { skillName?: string | null }) {
+  const [sandbox, setSandbox] = useState<BashSandboxPolicy | null>(null)
+  const [effective, setEffective] = useState<ToolPolicy | null>(null)
+  const [inherited, setInherited] = useState(false)
+  const [allowText, setAllowText] = useState("")
+  const [denyText, setDenyText] = useState("")
+  const [saving, setSaving] = useState(false)
+
+  useEffect(() => {
+    getToolPolicy(skillName)
+      .then((view) => {
+        setEffective(view.effective)
+        const policy = view.effective.bash_sandbox
+        setSandbox(policy)
+        setInherited(skillName !== null && view.skill_override === null)
+        setAllowText(toLines(policy.allow_patterns))
+        setDenyText(toLines(policy.deny_patterns))
+      })
+      .catch((err) => console.warn("[bash-sandbox-settings] get_tool_policy failed:", err))
+  }, [skillName])
+
+  if (!sandbox) {
+    return <Loader2 className="size-4 animate-spin text-muted-foreground" />
+  }
+
+  const save = async (next: BashSandboxPolicy | null) => {
+    setSaving(true)
+    try {
+      // Keep the rest of the policy (step roles) when saving the sandbox.
+      const view = await setToolPolicy(skillName, next ? { ...effective, bash_sandbox: next } : null)
+      setEffective(view.effective)
+      const policy = view.effective.bash_sandbox
+      setSandbox(policy)
+      setInherited(skillName !== null && view.skill_override === null)
+      setAllowText(toLines(policy.allow_patterns))
+      setDenyText(toLines(policy.deny_patterns))
+      toast.success("Bash sandbox saved", { duration: 1500 })
+    } catch (err) {
+      toast.error(`Failed to save Bash sandbox: ${err instanceof Error ? err.message : String(err)}`, { duration: Infinity })
+    } finally {
+      setSaving(false)
+    }
+  }
+
+  const current = (): BashSandboxPolicy => ({
+    ...sandbox,
+    allow_patterns: fromLines(allowText),
+    deny_patterns: fromLines(denyText),
+  })
+
+  return (
+    <div className="flex flex-col gap-4">
+      {inherited && (
+        <p className="text-sm text-muted-foreground">Using the global policy. Saving creates a policy for this skill.</p>
+      )}
+      <div className="flex items-center justify-between">
+        <div className="flex flex-col gap-0.5">
+          <Label htmlFor="bash-sandbox-enabled">Sandbox Bash commands</Label>
+          <span className="text-sm text-muted-foreground">
+            Check every Bash command an agent runs and keep paths inside the skill's directories.
+          </span>
+        </div>
+        <Switch
+          id="bash-sandbox-enabled"
+          checked={sandbox.enabled}
+          disabled={saving}
+          onCheckedChange={(checked) => save({ ...current(), enabled: checked })}
+        />
+      </div>
+
+      <div className="flex items-center justify-between">
+        <div className="flex flex-col gap-0.5">
+          <Label htmlFor="bash-sandbox-network">Allow network commands</Label>
+          <span className="text-sm text-muted-foreground">curl, wget, git fetch, package installs and similar.</span>
+        </div>
+        <Switch
+          id="bash-sandbox-network"
+          checked={sandbox.allow_network}
+          disabled={saving || !sandbox.enabled}
+          onCheckedChange={(checked) => save({ ...current(), allow_network: checked })}
+        />
+      </div>
+
+      <div className="grid gap-2">
+        <Label htmlFor="bash-sandbox-allow">Allowed commands (one pattern per line, empty allows all)</Label>
+        <Textarea
+          id="bash-sandbox-allow"
+          value={allowText}
+          disabled={!sandbox.enabled}
+          placeholder={"ls\ncat *\ngit status"}
+          onChange={(e) => setAllowText(e.target.value)}
+          className="font-mono text-sm"
+        />
+      </div>
+
+      <div className="grid gap-2">
+        <Label htmlFor="bash-sandbox-deny">Denied commands (one pattern per line)</Label>
+        <Textarea
+          id="bash-sandbox-deny"
+          value={denyText}
+          disabled={!sandbox.enabled}
+          placeholder={"rm -rf *\nsudo"}
+          onChange={(e) => setDenyText(e.target.value)}
+          className="font-mono text-sm"
+        />
+      </div>
+
+      <div className="flex gap-2">
+        <Button size="sm" disabled={saving || !sandbox.enabled} onClick={() => save(current())}>
+          {saving && <Loader2 className="size-3.5 animate-spin" />}
+          Save patterns
+        </Button>
+        {skillName !== null && !inherited && (
+          <Button size="sm" variant="outline" disabled={saving} onClick={() => save(null)}>
+            Use global policy
+          </Button>
+        )}
+      </div>
+    </div>
+  )
+}
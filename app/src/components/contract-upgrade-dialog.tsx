@@ -0,0 +1,123 @@
+import { useState } from "react"
+import { toast } from "sonner"
+import { RotateCcw, Wand2, Loader2, AlertTriangle } from "lucide-react"
+import { Button } from "@/components/ui/button"
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog"
+import { applyContractUpgrade } from "@/lib/tauri"
+import type { ContractUpgrade } from "@/lib/types"
+
+interface ContractUpgradeDialogProps {
+  upgrades: ContractUpgrade[]
+  open: boolean
+  onResolved: () => void
+}
+
+export default function ContractUpgradeDialog({
+  upgrades,
+  open,
+  onResolved,
+}: ContractUpgradeDialogProps) {
+  const [upgrading, setUpgrading] = useState<string | null>(null)
+  const [remaining, setRemaining] = useState<ContractUpgrade[]>(upgrades)
+
+  const handleUpgrade = async (
+    upgrade: ContractUpgrade,
+    action: "transform" | "rerun"
+  ) => {
+    setUpgrading(upgrade.skill_name)
+    try {
+      await applyContractUpgrade(upgrade.skill_name, action)
+      const label =
+        action === "transform"
+          ? "artifacts migrated"
+          : `reset to step ${upgrade.rerun_from_step + 1}`
+      toast.success(`"${upgrade.skill_name}" upgraded: ${label}`)
+      const updated = remaining.filter((u) => u.skill_name !== upgrade.skill_name)
+      setRemaining(updated)
+      if (updated.length === 0) {
+        onResolved()
+      }
+    } catch (err) {
+      const msg = err instanceof Error ? err.message : String(err)
+      toast.error(`Failed to upgrade "${upgrade.skill_name}": ${msg}`, { duration: Infinity })
+    } finally {
+      setUpgrading(null)
+    }
+  }
+
+  return (
+    <Dialog open={open && remaining.length > 0}>
+      <DialogContent showCloseButton={false}>
+        <DialogHeader>
+          <DialogTitle className="flex items-center gap-2">
+            <AlertTriangle className="size-5 text-amber-600 dark:text-amber-400" />
+            Skills Need an Upgrade
+          </DialogTitle>
+          <DialogDescription>
+            This version changed the output format of some workflow steps. These
+            in-progress skills have outputs in the old format. Migrate them in
+            place where possible, or re-run from the first affected step.
+          </DialogDescription>
+        </DialogHeader>
+
+        <div className="flex flex-col gap-3 py-2">
+          {remaining.map((upgrade) => (
+            <div
+              key={upgrade.skill_name}
+              className="flex items-center justify-between gap-3 rounded-lg border p-3"
+            >
+              <div className="flex flex-col gap-0.5">
+                <span className="text-sm font-medium">{upgrade.skill_name}</span>
+                {upgrade.changes.map((change) => (
+                  <span key={change} className="text-xs text-muted-foreground">
+                    {change}
+                  </span>
+                ))}
+              </div>
+              <div className="flex shrink-0 gap-2">
+                {upgrade.can_transform && (
+                  <Button
+                    size="sm"
+                    variant="outline"
+                    disabled={upgrading !== null}
+                    onClick={() => handleUpgrade(upgrade, "transform")}
+                  >
+                    {upgrading === upgrade.skill_name ? (
+                      <Loader2 className="size-3.5 animate-spin" />
+                    ) : (
+                      <Wand2 className="size-3.5" />
+                    )}
+                    Migrate
+                  </Button>
+                )}
+                <Button
+                  size="sm"
+                  variant={upgrade.can_transform ? "ghost" : "outline"}
+                  disabled={upgrading !== null}
+                  onClick={() => handleUpgrade(upgrade, "rerun")}
+                >
+                  {upgrading === upgrade.skill_name ? (
+                    <Loader2 className="size-3.5 animate-spin" />
+                  ) : (
+                    <RotateCcw className="size-3.5" />
+                  )}
+                  Re-run from step {upgrade.rerun_from_step + 1}
+                </Button>
+              </div>
+            </div>
+          ))}
+        </div>
+
+        <p className="text-xs text-muted-foreground">
+          {remaining.length} skill{remaining.length !== 1 ? "s" : ""} remaining
+        </p>
+      </DialogContent>
+    </Dialog>
+  )
+}
@@ -0,0 +1,133 @@
+import { useEffect, useState } from "react"
+import { toast } from "sonner"
+import { Loader2, Trash2 } from "lucide-react"
+import {
+  AlertDialog,
+  AlertDialogAction,
+  AlertDialogCancel,
+  AlertDialogContent,
+  AlertDialogDescription,
+  AlertDialogFooter,
+  AlertDialogHeader,
+  AlertDialogTitle,
+} from "@/components/ui/alert-dialog"
+import { Button } from "@/components/ui/button"
+import { Input } from "@/components/ui/input"
+import { Label } from "@/components/ui/label"
+import { purgeAllUserData } from "@/lib/tauri"
+import type { RetentionPolicy } from "@/lib/types"
+import { useAuthStore } from "@/stores/auth-store"
+
+export const KEEP_FOREVER: RetentionPolicy = {
+  agent_runs_days: 0,
+  workflow_sessions_days: 0,
+  transcripts_days: 0,
+  audit_log_days: 0,
+}
+
+const FIELDS: { key: keyof RetentionPolicy; label: string }[] = [
+  { key: "agent_runs_days", label: "Agent runs" },
+  { key: "workflow_sessions_days", label: "Workflow sessions" },
+  { key: "transcripts_days", label: "Transcripts" },
+  { key: "audit_log_days", label: "Audit log" },
+]
+
+const errorMessage = (err: unknown) => (err instanceof Error ? err.message : String(err))
+
+/** Edits the retention policy; `onChange` saves it. Also purges all user data on request. */
+export function DataRetentionSettings({
+  policy,
+  workspacePath,
+  onChange,
+}: {
+  policy: RetentionPolicy
+  workspacePath: string | null
+  onChange: (policy: RetentionPolicy) => void
+}) {
+  const [draft, setDraft] = useState(policy)
+  const [confirmOpen, setConfirmOpen] = useState(false)
+  const [purging, setPurging] = useState(false)
+
+  useEffect(() => {
+    setDraft(policy)
+  }, [policy])
+
+  const commit = () => {
+    if (FIELDS.some(({ key }) => draft[key] !== policy[key])) onChange(draft)
+  }
+
+  const handlePurge = async () => {
+    if (!workspacePath) return
+    setPurging(true)
+    try {
+      const result = await purgeAllUserData(workspacePath)
+      if (result.credentials_cleared) useAuthStore.getState().setUser(null)
+      toast.success(
+        `Deleted ${result.usage_rows} usage records, ${result.transcripts + result.transcript_files} transcripts${result.credentials_cleared ? " and stored credentials" : ""}`,
+      )
+      setConfirmOpen(false)
+    } catch (err) {
+      console.error("[data-retention-settings] purge_all_user_data failed:", err)
+      toast.error(`Failed to purge user data: ${errorMessage(err)}`, { duration: Infinity })
+    } finally {
+      setPurging(false)
+    }
+  }
+
+  return (
+    <div className="space-y-4">
+      <div className="grid grid-cols-2 gap-3">
+        {FIELDS.map(({ key, label }) => (
+          <div key={key} className="flex items-center gap-3">
+            <Label htmlFor={`retention-${key}`} className="w-36">
+              {label} (days)
+            </Label>
+            <Input
+              id={`retention-${key}`}
+              type="number"
+              min={0}
+              value={draft[key]}
+              onChange={(e) => setDraft({ ...draft, [key]: Math.max(0, parseInt(e.target.value) || 0) })}
+              onBlur={commit}
+              className="w-24"
+            />
+          </div>
+        ))}
+      </div>
+      <p className="text-xs text-muted-foreground">
+        Older records are deleted once a day. 0 keeps them forever.
+      </p>
+      <div className="flex items-center gap-3">
+        <Button variant="outline" size="sm" disabled={!workspacePath} onClick={() => setConfirmOpen(true)}>
+          <Trash2 className="size-4" />
+          Purge user data
+        </Button>
+        <span className="text-xs text-muted-foreground">
+          Deletes usage history, transcripts and stored GitHub and git host tokens now. Skills are kept.
+        </span>
+      </div>
+
+      <AlertDialog open={confirmOpen}>
+        <AlertDialogContent>
+          <AlertDialogHeader>
+            <AlertDialogTitle>Purge User Data</AlertDialogTitle>
+            <AlertDialogDescription>
+              This permanently deletes all agent runs, workflow sessions, refine conversations, test and benchmark
+              results, hook deliveries, transcripts and usage history, and signs out of GitHub and connected git
+              hosts. Skills, settings and the audit log are kept.
+            </AlertDialogDescription>
+          </AlertDialogHeader>
+          <AlertDialogFooter>
+            <AlertDialogCancel onClick={() => setConfirmOpen(false)} disabled={purging}>
+              Cancel
+            </AlertDialogCancel>
+            <AlertDialogAction variant="destructive" onClick={handlePurge} disabled={purging}>
+              {purging && <Loader2 className="size-4 animate-spin" />}
+              Purge
+            </AlertDialogAction>
+          </AlertDialogFooter>
+        </AlertDialogContent>
+      </AlertDialog>
+    </div>
+  )
+}
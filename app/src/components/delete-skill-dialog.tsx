@@ -1,7 +1,7 @@
-import { useState } from "react"
+import { useEffect, useState } from "react"
 import { invoke } from "@tauri-apps/api/core"
 import { toast } from "sonner"
-import { Loader2, Lock } from "lucide-react"
+import { AlertTriangle, Loader2, Lock } from "lucide-react"
 import {
   AlertDialog,
   AlertDialogAction,
@@ -12,7 +12,8 @@ import {
   AlertDialogHeader,
   AlertDialogTitle,
 } from "@/components/ui/alert-dialog"
-import type { SkillSummary } from "@/lib/types"
+import { analyzeSkillImpact } from "@/lib/tauri"
+import type { SkillImpactReport, SkillInstallation, SkillSummary } from "@/lib/types"
 
 interface DeleteSkillDialogProps {
   skill: SkillSummary | null
@@ -23,6 +24,41 @@ interface DeleteSkillDialogProps {
   isLocked?: boolean
 }
 
+const LOCATION_LABELS: Record<SkillInstallation["location"], string> = {
+  skills_library: "skills folder",
+  workspace: "workspace (active)",
+  workspace_inactive: "workspace (inactive)",
+  user: "~/.claude/skills",
+}
+
+const plural = (n: number, word: string) => `${n} ${word}${n === 1 ? "" : "s"}`
+
+/** One line per kind of impact; empty when deleting affects nothing else. */
+function impactLines(impact: SkillImpactReport): string[] {
+  const lines: string[] = []
+  if (impact.dependents.length > 0) {
+    lines.push(`Referenced by ${impact.dependents.map((d) => d.skill_name).join(", ")}`)
+  }
+  const installed = impact.installations.filter((i) => i.location !== "skills_library")
+  if (installed.length > 0) {
+    lines.push(`Installed in ${installed.map((i) => LOCATION_LABELS[i.location]).join(", ")}`)
+  }
+  if (impact.team_consumers.length > 0) {
+    const registries = impact.team_consumers.map((c) => c.registry_name ?? c.registry)
+    lines.push(`Published in team registries: ${registries.join(", ")}`)
+  }
+  if (impact.recent_runs.runs > 0) {
+    lines.push(`${plural(impact.recent_runs.runs, "agent run")} in the last ${impact.recent_runs.days} days`)
+  }
+  if (impact.open_refine_sessions > 0) {
+    lines.push(`${plural(impact.open_refine_sessions, "open refine session")}`)
+  }
+  if (impact.active_workflow_session) {
+    lines.push("A workflow session is open")
+  }
+  return lines
+}
+
 export default function DeleteSkillDialog({
   skill,
   workspacePath,
@@ -32,6 +68,31 @@ export default function DeleteSkillDialog({
   isLocked,
 }: DeleteSkillDialogProps) {
   const [loading, setLoading] = useState(false)
+  const [impact, setImpact] = useState<SkillImpactReport | null>(null)
+  const [analyzing, setAnalyzing] = useState(false)
+
+  const skillName = skill?.name
+
+  useEffect(() => {
+    setImpact(null)
+    if (!open || !skillName || isLocked) return
+    let cancelled = false
+    const analyze = async () => {
+      setAnalyzing(true)
+      try {
+        const report = await analyzeSkillImpact(workspacePath, skillName)
+        if (!cancelled && report) setImpact(report)
+      } catch (err) {
+        console.warn("[delete-skill-dialog] analyze_skill_impact failed:", err)
+      } finally {
+        if (!cancelled) setAnalyzing(false)
+      }
+    }
+    analyze()
+    return () => {
+      cancelled = true
+    }
+  }, [open, skillName, workspacePath, isLocked])
 
   const handleDelete = async () => {
     if (!skill) return
@@ -55,6 +116,8 @@ export default function DeleteSkillDialog({
     }
   }
 
+  const lines = impact ? impactLines(impact) : []
+
   return (
     <AlertDialog open={open}>
       <AlertDialogContent>
@@ -83,6 +146,25 @@ export default function DeleteSkillDialog({
             This skill is being edited in another window and cannot be deleted
           </div>
         )}
+        {!isLocked && analyzing && (
+          <div className="flex items-center gap-2 text-sm text-muted-foreground">
+            <Loader2 className="size-3.5 animate-spin" />
+            Checking what else uses this skill…
+          </div>
+        )}
+        {lines.length > 0 && (
+          <div className="rounded-md border border-amber-500/50 bg-amber-50 px-3 py-2 text-sm text-amber-800 dark:bg-amber-950/20 dark:text-amber-300">
+            <div className="flex items-center gap-2 font-medium">
+              <AlertTriangle className="size-4 shrink-0" />
+              Deleting affects
+            </div>
+            <ul className="mt-1 list-disc pl-6">
+              {lines.map((line) => (
+                <li key={line}>{line}</li>
+              ))}
+            </ul>
+          </div>
+        )}
         <AlertDialogFooter>
           <AlertDialogCancel onClick={() => onOpenChange(false)} disabled={loading}>
             Cancel
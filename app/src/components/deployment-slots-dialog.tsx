@@ -0,0 +1,181 @@
+import { useEffect, useState } from "react"
+import { invoke } from "@tauri-apps/api/core"
+import { save } from "@tauri-apps/plugin-dialog"
+import { toast } from "sonner"
+import { ArrowUpCircle, Download, HardDriveDownload, Loader2, Trash2 } from "lucide-react"
+import { Badge } from "@/components/ui/badge"
+import { Button } from "@/components/ui/button"
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog"
+import {
+  getSkillSlots,
+  installSkillSlot,
+  packageSkillSlot,
+  promoteBetaToStable,
+  uninstallSkillSlot,
+} from "@/lib/tauri"
+import type { SkillSlot, SkillSlotName, SkillSlotsStatus, SkillSummary } from "@/lib/types"
+
+interface DeploymentSlotsDialogProps {
+  skill: SkillSummary | null
+  open: boolean
+  onOpenChange: (open: boolean) => void
+  /** Called after a promotion changes the skill's version. */
+  onPromoted?: () => void
+}
+
+const SLOT_LABELS: Record<SkillSlotName, string> = { stable: "Stable", beta: "Beta" }
+
+const errorMessage = (err: unknown) => (err instanceof Error ? err.message : String(err))
+
+/** Stable/beta slots of a skill: package each, pick which one Claude uses on this machine, promote beta. */
+export default function DeploymentSlotsDialog({ skill, open, onOpenChange, onPromoted }: DeploymentSlotsDialogProps) {
+  const [status, setStatus] = useState<SkillSlotsStatus | null>(null)
+  const [busy, setBusy] = useState<string | null>(null)
+
+  useEffect(() => {
+    if (!open || !skill) return
+    setStatus(null)
+    getSkillSlots(skill.name)
+      .then(setStatus)
+      .catch((err) => {
+        console.error("[deployment-slots] get_skill_slots failed:", err)
+        toast.error(`Failed to load deployment slots: ${errorMessage(err)}`, { duration: Infinity })
+      })
+  }, [open, skill])
+
+  if (!skill) return null
+
+  const run = async (key: string, action: () => Promise<void>) => {
+    setBusy(key)
+    try {
+      await action()
+    } finally {
+      setBusy(null)
+    }
+  }
+
+  const handlePackage = (slot: SkillSlotName) =>
+    run(`package-${slot}`, async () => {
+      try {
+        const result = await packageSkillSlot(skill.name, slot)
+        const savePath = await save({
+          defaultPath: `${skill.name}-${slot}.skill`,
+          filters: [{ name: "Skill Package", extensions: ["skill"] }],
+        })
+        if (savePath) {
+          await invoke("copy_file", { src: result.file_path, dest: savePath })
+          toast.success(`${SLOT_LABELS[slot]} package saved`, { duration: 1500 })
+        }
+        setStatus(await getSkillSlots(skill.name))
+      } catch (err) {
+        console.error("[deployment-slots] package failed:", err)
+        toast.error(`Packaging ${slot} failed: ${errorMessage(err)}`, { duration: Infinity })
+      }
+    })
+
+  const handleInstall = (slot: SkillSlotName) =>
+    run(`install-${slot}`, async () => {
+      try {
+        setStatus(await installSkillSlot(skill.name, slot))
+        toast.success(`Claude on this machine now uses ${slot}`, { duration: 1500 })
+      } catch (err) {
+        console.error("[deployment-slots] install failed:", err)
+        toast.error(`Install failed: ${errorMessage(err)}`, { duration: Infinity })
+      }
+    })
+
+  const handleUninstall = () =>
+    run("uninstall", async () => {
+      try {
+        setStatus(await uninstallSkillSlot(skill.name))
+      } catch (err) {
+        console.error("[deployment-slots] uninstall failed:", err)
+        toast.error(`Uninstall failed: ${errorMessage(err)}`, { duration: Infinity })
+      }
+    })
+
+  const handlePromote = () =>
+    run("promote", async () => {
+      try {
+        const entry = await promoteBetaToStable(skill.name)
+        console.log("[deployment-slots] promoted %s to %s (%s)", skill.name, entry.version, entry.bump)
+        toast.success(`Promoted ${skill.name} to stable v${entry.version}`)
+        setStatus(await getSkillSlots(skill.name))
+        onPromoted?.()
+      } catch (err) {
+        console.error("[deployment-slots] promote failed:", err)
+        toast.error(`Promotion failed: ${errorMessage(err)}`, { duration: Infinity })
+      }
+    })
+
+  const renderSlot = (name: SkillSlotName, slot: SkillSlot | null) => {
+    const installed = status?.installed_slot === name
+    return (
+      <div key={name} className="flex items-center justify-between gap-3 rounded-lg border p-3" data-testid={`slot-${name}`}>
+        <div className="flex flex-col gap-0.5">
+          <div className="flex items-center gap-2">
+            <span className="text-sm font-medium">{SLOT_LABELS[name]}</span>
+            {installed && <Badge variant="secondary">Installed</Badge>}
+          </div>
+          <span className="text-xs text-muted-foreground">
+            {slot ? `v${slot.version ?? "?"}` : "Not promoted yet"}
+            {slot?.promoted_at && ` · promoted ${new Date(slot.promoted_at).toLocaleDateString()}`}
+          </span>
+        </div>
+        <div className="flex items-center gap-2">
+          <Button variant="outline" size="sm" disabled={!slot || busy !== null} onClick={() => handlePackage(name)}>
+            {busy === `package-${name}` ? <Loader2 className="size-4 animate-spin" /> : <Download className="size-4" />}
+            Package
+          </Button>
+          <Button variant="outline" size="sm" disabled={!slot || installed || busy !== null} onClick={() => handleInstall(name)}>
+            {busy === `install-${name}` ? <Loader2 className="size-4 animate-spin" /> : <HardDriveDownload className="size-4" />}
+            {status?.installed_slot ? "Switch" : "Install"}
+          </Button>
+        </div>
+      </div>
+    )
+  }
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent>
+        <DialogHeader>
+          <DialogTitle>Deployment slots</DialogTitle>
+          <DialogDescription>
+            Beta is the skill you edit; stable changes only when you promote beta.
+            {status && ` The installed slot lives in ${status.install_path}.`}
+          </DialogDescription>
+        </DialogHeader>
+
+        {status ? (
+          <div className="flex flex-col gap-3 py-2">
+            {renderSlot("stable", status.stable)}
+            {renderSlot("beta", status.beta)}
+          </div>
+        ) : (
+          <Loader2 className="size-4 animate-spin text-muted-foreground" />
+        )}
+
+        <DialogFooter>
+          {status?.installed_slot && (
+            <Button variant="ghost" size="sm" disabled={busy !== null} onClick={handleUninstall}>
+              <Trash2 className="size-4" />
+              Uninstall
+            </Button>
+          )}
+          <Button size="sm" disabled={!status || busy !== null} onClick={handlePromote}>
+            {busy === "promote" ? <Loader2 className="size-4 animate-spin" /> : <ArrowUpCircle className="size-4" />}
+            Promote beta to stable
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  )
+}
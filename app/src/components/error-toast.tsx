@@ -0,0 +1,50 @@
+import { toast } from "sonner"
+import { getErrorHelp } from "@/lib/tauri"
+import type { ErrorHelp } from "@/lib/types"
+
+const errorMessage = (err: unknown) => (err instanceof Error ? err.message : String(err))
+
+async function lookupHelp(message: string): Promise<ErrorHelp | null> {
+  try {
+    const help = await getErrorHelp(message)
+    return help.code === "unknown" ? null : help
+  } catch (err) {
+    console.warn("[error-toast] get_error_help failed:", err)
+    return null
+  }
+}
+
+function openSettings(tab: string) {
+  // Loaded lazily: the router imports every page, and pages import this module.
+  void import("@/router").then(({ router }) => router.navigate({ to: "/settings", search: { tab } }))
+}
+
+/**
+ * Show a failed command's error as a persistent toast, with the bundled
+ * remediation steps and a link to the relevant settings section when
+ * `get_error_help` knows the error.
+ */
+export async function toastCommandError(prefix: string, err: unknown, options: { id?: string | number } = {}) {
+  const message = errorMessage(err)
+  const help = await lookupHelp(message)
+  toast.error(`${prefix}: ${message}`, {
+    id: options.id,
+    duration: Infinity,
+    description: help ? (
+      <div className="space-y-1">
+        <p className="font-medium">{help.title}</p>
+        <ol className="list-decimal pl-4">
+          {help.steps.map((step) => (
+            <li key={step}>{step}</li>
+          ))}
+        </ol>
+      </div>
+    ) : undefined,
+    action: help?.settings_tab
+      ? {
+          label: "Open Settings",
+          onClick: () => openSettings(help.settings_tab as string),
+        }
+      : undefined,
+  })
+}
@@ -2,9 +2,11 @@ import { useCallback, useEffect, useRef, useState } from "react"
 import { toast } from "sonner"
 import { openUrl } from "@tauri-apps/plugin-opener"
 import { getVersion } from "@tauri-apps/api/app"
-import { Bug, Github, Lightbulb, Loader2, MessageSquarePlus } from "lucide-react"
+import { open as openFileDialog } from "@tauri-apps/plugin-dialog"
+import { Bug, Github, Lightbulb, Loader2, MessageSquarePlus, Paperclip, X } from "lucide-react"
 import { Badge } from "@/components/ui/badge"
 import { Button } from "@/components/ui/button"
+import { Checkbox } from "@/components/ui/checkbox"
 import {
   Dialog,
   DialogContent,
@@ -20,7 +22,8 @@ import { RadioGroup, RadioGroupItem } from "@/components/ui/radio-group"
 import { ScrollArea } from "@/components/ui/scroll-area"
 import { Separator } from "@/components/ui/separator"
 import { Textarea } from "@/components/ui/textarea"
-import { startAgent, getWorkspacePath, createGithubIssue } from "@/lib/tauri"
+import { startAgent, getWorkspacePath, createGithubIssue, getSettings, scrubFeedbackAttachments, runDiagnostics } from "@/lib/tauri"
+import type { ScrubbedAttachment } from "@/lib/types"
 import { useAgentStore } from "@/stores/agent-store"
 import { useAuthStore } from "@/stores/auth-store"
 import { GitHubLoginDialog } from "@/components/github-login-dialog"
@@ -152,12 +155,33 @@ export function FeedbackDialog() {
   // --- Agent tracking ---
   const [pendingAgentId, setPendingAgentId] = useState<string | null>(null)
 
+  // --- Attachments (scrubbed before upload) ---
+  const [attachments, setAttachments] = useState<string[]>([])
+  const [scrubbed, setScrubbed] = useState<ScrubbedAttachment[]>([])
+  const [hasPublicKey, setHasPublicKey] = useState(false)
+  const [encryptAttachments, setEncryptAttachments] = useState(false)
+  const [attachDiagnostics, setAttachDiagnostics] = useState(false)
+
+  useEffect(() => {
+    if (!open) return
+    getSettings()
+      .then((s) => {
+        const configured = !!s.feedback_public_key
+        setHasPublicKey(configured)
+        setEncryptAttachments(configured)
+      })
+      .catch(() => setHasPublicKey(false))
+  }, [open])
+
   const resetForm = () => {
     setTitle("")
     setDescription("")
     setEnriched(null)
     setStep("input")
     setPendingAgentId(null)
+    setAttachments([])
+    setScrubbed([])
+    setAttachDiagnostics(false)
   }
 
   // -----------------------------------------------------------------------
@@ -235,6 +259,34 @@ export function FeedbackDialog() {
     }
   }
 
+  const updateAttachments = async (paths: string[]) => {
+    try {
+      setScrubbed(paths.length > 0 ? await scrubFeedbackAttachments(paths) : [])
+      setAttachments(paths)
+    } catch (err) {
+      toast.error(
+        `Failed to read attachment: ${err instanceof Error ? err.message : String(err)}`,
+        { duration: Infinity },
+      )
+    }
+  }
+
+  const handleAddAttachments = async () => {
+    const selected = await openFileDialog({
+      title: "Attach Files",
+      multiple: true,
+      filters: [
+        { name: "Screenshots & Logs", extensions: ["png", "jpg", "jpeg", "txt", "log", "md", "json", "jsonl"] },
+        { name: "All Files", extensions: ["*"] },
+      ],
+    })
+    if (!selected) return
+    const added = (Array.isArray(selected) ? selected : [selected]).filter(
+      (p) => !attachments.includes(p),
+    )
+    if (added.length > 0) await updateAttachments([...attachments, ...added])
+  }
+
   const handleSubmit = async () => {
     if (!enriched) return
     setStep("submitting")
@@ -247,10 +299,15 @@ export function FeedbackDialog() {
         (l) => l !== typeLabel && l !== versionLabel,
       )]
 
+      const diagnostics = attachDiagnostics ? await runDiagnostics() : null
+
       const result = await createGithubIssue({
         title: enriched.title,
         body: enriched.body,
         labels: allLabels,
+        attachments,
+        encrypt_attachments: attachments.length > 0 && encryptAttachments,
+        diagnostics,
       })
 
       toast.success(`Issue #${result.number} created`, {
@@ -418,6 +475,70 @@ export function FeedbackDialog() {
               />
             </div>
 
+            <Separator />
+
+            {/* ── Attachments ── */}
+            <div className="grid gap-1.5">
+              <div className="flex items-center justify-between">
+                <Label>Attachments</Label>
+                <Button variant="outline" size="sm" onClick={handleAddAttachments}>
+                  <Paperclip className="size-3.5" />
+                  Add Files
+                </Button>
+              </div>
+              {scrubbed.length > 0 && (
+                <ul className="space-y-1">
+                  {scrubbed.map((a, i) => (
+                    <li key={attachments[i]} className="flex items-center gap-2 text-xs">
+                      <span className="truncate font-mono">{a.name}</span>
+                      {a.removed_metadata.length > 0 && (
+                        <Badge variant="outline" className="text-xs">
+                          {a.removed_metadata.join(", ")} removed
+                        </Badge>
+                      )}
+                      {a.redactions > 0 && (
+                        <Badge variant="outline" className="text-xs">
+                          {a.redactions} redacted
+                        </Badge>
+                      )}
+                      <Button
+                        variant="ghost"
+                        size="icon-xs"
+                        className="ml-auto"
+                        title="Remove attachment"
+                        onClick={() => updateAttachments(attachments.filter((_, j) => j !== i))}
+                      >
+                        <X className="size-3" />
+                      </Button>
+                    </li>
+                  ))}
+                </ul>
+              )}
+              <div className="flex items-center gap-2">
+                <Checkbox
+                  id="review-encrypt"
+                  checked={encryptAttachments}
+                  disabled={!hasPublicKey}
+                  onCheckedChange={(v) => setEncryptAttachments(v === true)}
+                />
+                <Label htmlFor="review-encrypt" className="font-normal text-muted-foreground">
+                  {hasPublicKey
+                    ? "Encrypt attachments to the maintainer key"
+                    : "Set a feedback public key in Settings to encrypt attachments"}
+                </Label>
+              </div>
+              <div className="flex items-center gap-2">
+                <Checkbox
+                  id="review-diagnostics"
+                  checked={attachDiagnostics}
+                  onCheckedChange={(v) => setAttachDiagnostics(v === true)}
+                />
+                <Label htmlFor="review-diagnostics" className="font-normal text-muted-foreground">
+                  Attach diagnostics report (Node.js, sidecar, API key, database, GitHub token)
+                </Label>
+              </div>
+            </div>
+
           </div>
         </ScrollArea>
 
@@ -0,0 +1,130 @@
+import { useEffect, useState } from "react"
+import { toast } from "sonner"
+import { Loader2, LogOut } from "lucide-react"
+import { Badge } from "@/components/ui/badge"
+import { Button } from "@/components/ui/button"
+import { Input } from "@/components/ui/input"
+import { Label } from "@/components/ui/label"
+import { gitHostConnect, gitHostDisconnect, gitHostListAccounts } from "@/lib/tauri"
+import type { GitHost, GitHostAccount } from "@/lib/types"
+
+const HOSTS: { host: Exclude<GitHost, "github">; label: string; tokenHint: string }[] = [
+  { host: "gitlab", label: "GitLab", tokenHint: "Personal access token with the api scope" },
+  { host: "bitbucket", label: "Bitbucket", tokenHint: "Access token, or username:app_password" },
+]
+
+/** Connects GitLab and Bitbucket accounts with access tokens for team sharing. */
+export function GitHostingAccounts() {
+  const [accounts, setAccounts] = useState<GitHostAccount[] | null>(null)
+  const [tokens, setTokens] = useState<Record<string, string>>({})
+  const [instanceUrl, setInstanceUrl] = useState("")
+  const [busy, setBusy] = useState<GitHost | null>(null)
+
+  useEffect(() => {
+    gitHostListAccounts()
+      .then(setAccounts)
+      .catch((err) => {
+        console.warn("[git-hosting-accounts] git_host_list_accounts failed:", err)
+        setAccounts([])
+      })
+  }, [])
+
+  if (!accounts) {
+    return <Loader2 className="size-4 animate-spin text-muted-foreground" />
+  }
+
+  const connect = async (host: GitHost, label: string) => {
+    setBusy(host)
+    try {
+      const account = await gitHostConnect(host, tokens[host] ?? "", host === "gitlab" ? instanceUrl.trim() || null : null)
+      setAccounts([...accounts.filter((a) => a.host !== host), account])
+      setTokens({ ...tokens, [host]: "" })
+      toast.success(`Connected to ${label} as ${account.login}`, { duration: 1500 })
+    } catch (err) {
+      toast.error(`Failed to connect ${label}: ${err instanceof Error ? err.message : String(err)}`, { duration: Infinity })
+    } finally {
+      setBusy(null)
+    }
+  }
+
+  const disconnect = async (host: GitHost, label: string) => {
+    setBusy(host)
+    try {
+      await gitHostDisconnect(host)
+      setAccounts(accounts.filter((a) => a.host !== host))
+    } catch (err) {
+      toast.error(`Failed to disconnect ${label}: ${err instanceof Error ? err.message : String(err)}`, { duration: Infinity })
+    } finally {
+      setBusy(null)
+    }
+  }
+
+  return (
+    <div className="flex flex-col gap-6">
+      {HOSTS.map(({ host, label, tokenHint }) => {
+        const account = accounts.find((a) => a.host === host)
+        return (
+          <div key={host} className="flex flex-col gap-2" data-testid={`git-host-${host}`}>
+            <div className="flex items-center gap-2">
+              <span className="text-sm font-medium">{label}</span>
+              <Badge variant={account ? "secondary" : "outline"}>{account ? "Connected" : "Not connected"}</Badge>
+            </div>
+            {account ? (
+              <div className="flex items-center gap-3">
+                <span className="text-sm">@{account.login}</span>
+                {account.api_base && (
+                  <span className="text-sm text-muted-foreground">{account.api_base.replace(/\/api\/v4$/, "")}</span>
+                )}
+                <Button
+                  variant="outline"
+                  size="sm"
+                  disabled={busy === host}
+                  onClick={() => disconnect(host, label)}
+                >
+                  <LogOut className="size-4" />
+                  Disconnect
+                </Button>
+              </div>
+            ) : (
+              <>
+                {host === "gitlab" && (
+                  <div className="grid gap-1">
+                    <Label htmlFor="gitlab-instance-url">Instance URL (self-managed only)</Label>
+                    <Input
+                      id="gitlab-instance-url"
+                      placeholder="https://gitlab.com"
+                      value={instanceUrl}
+                      onChange={(e) => setInstanceUrl(e.target.value)}
+                    />
+                  </div>
+                )}
+                <div className="grid gap-1">
+                  <Label htmlFor={`${host}-token`}>{label} token</Label>
+                  <div className="flex items-center gap-2">
+                    <Input
+                      id={`${host}-token`}
+                      type="password"
+                      placeholder={tokenHint}
+                      value={tokens[host] ?? ""}
+                      onChange={(e) => setTokens({ ...tokens, [host]: e.target.value })}
+                      className="flex-1"
+                    />
+                    <Button
+                      variant="outline"
+                      size="sm"
+                      disabled={busy === host || !(tokens[host] ?? "").trim()}
+                      onClick={() => connect(host, label)}
+                    >
+                      {busy === host && <Loader2 className="size-4 animate-spin" />}
+                      Connect
+                    </Button>
+                  </div>
+                </div>
+              </>
+            )}
+          </div>
+        )
+      })}
+    </div>
+  )
+}
@@ -1,6 +1,7 @@
 import { useState, useCallback, useEffect, useRef } from "react"
 import { Loader2, AlertCircle, Download, RefreshCw, CheckCircle2, CheckCheck } from "lucide-react"
 import { toast } from "sonner"
+import { listen } from "@tauri-apps/api/event"
 import {
   Dialog,
   DialogContent,
@@ -28,9 +29,9 @@ import { Label } from "@/components/ui/label"
 import { Checkbox } from "@/components/ui/checkbox"
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "@/components/ui/select"
 import { Tabs, TabsList, TabsTrigger, TabsContent } from "@/components/ui/tabs"
-import { parseGitHubUrl, listGitHubSkills, importGitHubSkills, importMarketplaceToLibrary, listWorkspaceSkills, getDashboardSkillNames, listSkills, checkSkillCustomized } from "@/lib/tauri"
+import { parseGitHubUrl, listGitHubSkills, cancelGitHubImport, importGitHubSkills, importMarketplaceToLibrary, listWorkspaceSkills, getDashboardSkillNames, listSkills, checkSkillCustomized } from "@/lib/tauri"
 import type { WorkspaceSkillImportRequest } from "@/lib/tauri"
-import type { AvailableSkill, GitHubRepoInfo, SkillMetadataOverride, SkillSummary, WorkspaceSkill, MarketplaceRegistry } from "@/lib/types"
+import type { AvailableSkill, GitHubRepoInfo, ImportProgress, SkillMetadataOverride, SkillSummary, WorkspaceSkill, MarketplaceRegistry } from "@/lib/types"
 import { PURPOSE_OPTIONS } from "@/lib/types"
 import { useSettingsStore } from "@/stores/settings-store"
 
@@ -166,6 +167,11 @@ export default function GitHubImportDialog({
   const [pendingUpgradeSkill, setPendingUpgradeSkill] = useState<AvailableSkill | null>(null)
   const [showCustomizationWarning, setShowCustomizationWarning] = useState(false)
 
+  // Progress of the in-flight listing; a cancel returns the skills read so far
+  const [listProgress, setListProgress] = useState<ImportProgress | null>(null)
+  const listOperationRef = useRef<string | null>(null)
+  const listCancelledRef = useRef(false)
+
   // Keep the ref in sync synchronously whenever setActiveTab is called
   // (useEffect would delay by one render; direct assignment is safer for callbacks)
   activeTabRef.current = activeTab
@@ -219,8 +225,37 @@ export default function GitHubImportDialog({
     [onOpenChange, reset]
   )
 
+  useEffect(() => {
+    if (!open) return
+    let unlisten: (() => void) | undefined
+    let disposed = false
+    listen<ImportProgress>("github-import-progress", (event) => {
+      if (event.payload.operation_id === listOperationRef.current) setListProgress(event.payload)
+    }).then((fn) => {
+      if (disposed) fn()
+      else unlisten = fn
+    })
+    return () => {
+      disposed = true
+      unlisten?.()
+    }
+  }, [open])
+
+  const cancelListing = useCallback(() => {
+    const operationId = listOperationRef.current
+    if (!operationId) return
+    listCancelledRef.current = true
+    cancelGitHubImport(operationId).catch((err) => {
+      console.warn("[github-import] Failed to cancel listing:", err)
+    })
+  }, [])
+
   const browseRegistry = useCallback(async (registry: MarketplaceRegistry) => {
     const tabKey = registry.source_url
+    const operationId = `list-${Date.now()}`
+    listOperationRef.current = operationId
+    listCancelledRef.current = false
+    setListProgress(null)
     setTabStates(prev => ({
       ...prev,
       [tabKey]: { ...EMPTY_TAB, loading: true }
@@ -232,7 +267,11 @@ export default function GitHubImportDialog({
         info.repo,
         info.branch,
         info.subpath ?? undefined,
+        operationId,
       )
+      if (listCancelledRef.current) {
+        toast.info(`Listing stopped — showing the ${available.length} skill${available.length !== 1 ? "s" : ""} read so far`)
+      }
       if (mode === "dashboard-library" && typeFilter && typeFilter.length > 0) {
         available = available.filter(
           (s) => s.purpose != null && typeFilter.includes(s.purpose)
@@ -283,6 +322,11 @@ export default function GitHubImportDialog({
         ...prev,
         [tabKey]: { ...EMPTY_TAB, error: err instanceof Error ? err.message : String(err) }
       }))
+    } finally {
+      if (listOperationRef.current === operationId) {
+        listOperationRef.current = null
+        setListProgress(null)
+      }
     }
   }, [typeFilter, workspacePath, mode])
 
@@ -423,6 +467,16 @@ export default function GitHubImportDialog({
         <div className="flex flex-col items-center gap-3 py-8">
           <Loader2 className="size-8 animate-spin text-muted-foreground" />
           <p className="text-sm text-muted-foreground">Loading skills...</p>
+          {listProgress && listProgress.skills_found > 0 && (
+            <p className="text-xs text-muted-foreground">
+              Read {listProgress.skills_processed} of {listProgress.skills_found} skills
+            </p>
+          )}
+          {listProgress && (
+            <Button variant="outline" size="sm" onClick={cancelListing}>
+              Stop and show results
+            </Button>
+          )}
         </div>
       )
     }
@@ -42,6 +42,8 @@ export interface ImportConfirmParams {
   disableModelInvocation: boolean
   forceOverwrite: boolean
   purpose?: string | null
+  /** Values for the skill's template variables, keyed by variable name */
+  parameters?: Record<string, string>
 }
 
 interface ImportSkillDialogProps {
@@ -75,6 +77,7 @@ export function ImportSkillDialog({
   const [userInvocable, setUserInvocable] = useState(false)
   const [disableModelInvocation, setDisableModelInvocation] = useState(false)
   const [purpose, setPurpose] = useState<string | null>(null)
+  const [parameters, setParameters] = useState<Record<string, string>>({})
   const [submitting, setSubmitting] = useState(false)
   const [nameConflictError, setNameConflictError] = useState<string | null>(null)
   const [showOverwriteConfirm, setShowOverwriteConfirm] = useState(false)
@@ -90,16 +93,33 @@ export function ImportSkillDialog({
       setUserInvocable(meta.user_invocable ?? false)
       setDisableModelInvocation(meta.disable_model_invocation ?? false)
       setPurpose(null)
+      setParameters(
+        Object.fromEntries((meta.variables ?? []).map((v) => [v.name, v.default ?? ""]))
+      )
       setSubmitting(false)
       setNameConflictError(null)
       setShowOverwriteConfirm(false)
     }
   }, [open, filePath, meta])
 
+  const variables = meta.variables ?? []
+  const checklistSections = meta.checklist
+    ? [
+        { title: "MCP servers", items: meta.checklist.mcp_servers },
+        { title: "Credentials", items: meta.checklist.credentials },
+        { title: "Environment", items: meta.checklist.environment },
+        { title: "First prompts", items: meta.checklist.first_prompts },
+      ].filter((s) => s.items.length > 0)
+    : []
+  const missingRequired = variables.some(
+    (v) => v.required && (parameters[v.name] ?? "").trim() === ""
+  )
+
   const canSubmit =
     name.trim() !== "" &&
     description.trim() !== "" &&
     version.trim() !== "" &&
+    !missingRequired &&
     !submitting
 
   const doImport = useCallback(
@@ -119,6 +139,7 @@ export function ImportSkillDialog({
         disableModelInvocation,
         forceOverwrite,
         purpose: showPurpose ? purpose : undefined,
+        parameters: variables.length > 0 ? parameters : undefined,
       }
 
       try {
@@ -148,7 +169,7 @@ export function ImportSkillDialog({
     },
     [
       filePath, name, description, version, model, argumentHint,
-      userInvocable, disableModelInvocation, purpose,
+      userInvocable, disableModelInvocation, purpose, parameters, variables,
       showPurpose, onConfirm, onOpenChange, onImported,
     ]
   )
@@ -262,6 +283,72 @@ export function ImportSkillDialog({
               </div>
             )}
 
+            {variables.length > 0 && (
+              <div className="flex flex-col gap-3 rounded-md border p-3">
+                <div className="flex flex-col gap-0.5">
+                  <span className="text-sm font-medium">Parameters</span>
+                  <span className="text-xs text-muted-foreground">
+                    Values substituted into the skill&apos;s files for this install
+                  </span>
+                </div>
+                {variables.map((v) => (
+                  <div key={v.name} className="flex flex-col gap-1.5">
+                    <Label htmlFor={`import-param-${v.name}`}>
+                      <code className="text-xs">{v.name}</code>
+                      {v.required && <span className="text-destructive">*</span>}
+                    </Label>
+                    {v.allowed.length > 0 ? (
+                      <select
+                        id={`import-param-${v.name}`}
+                        value={parameters[v.name] ?? ""}
+                        onChange={(e) => setParameters((p) => ({ ...p, [v.name]: e.target.value }))}
+                        disabled={submitting}
+                        className="flex h-9 w-full rounded-md border border-input bg-transparent px-3 py-1 text-sm shadow-sm transition-colors focus-visible:outline-none focus-visible:ring-1 focus-visible:ring-ring disabled:cursor-not-allowed disabled:opacity-50"
+                      >
+                        <option value="">Select a value</option>
+                        {v.allowed.map((opt) => (
+                          <option key={opt} value={opt}>{opt}</option>
+                        ))}
+                      </select>
+                    ) : (
+                      <Input
+                        id={`import-param-${v.name}`}
+                        value={parameters[v.name] ?? ""}
+                        onChange={(e) => setParameters((p) => ({ ...p, [v.name]: e.target.value }))}
+                        disabled={submitting}
+                      />
+                    )}
+                    {v.description && (
+                      <span className="text-xs text-muted-foreground">{v.description}</span>
+                    )}
+                  </div>
+                ))}
+              </div>
+            )}
+
+            {checklistSections.length > 0 && (
+              <div className="flex flex-col gap-3 rounded-md border p-3" data-testid="import-checklist">
+                <div className="flex flex-col gap-0.5">
+                  <span className="text-sm font-medium">Before you use this skill</span>
+                  <span className="text-xs text-muted-foreground">
+                    Setup detected from the skill&apos;s onboarding checklist
+                  </span>
+                </div>
+                {checklistSections.map((section) => (
+                  <div key={section.title} className="flex flex-col gap-1">
+                    <span className="text-xs font-medium">{section.title}</span>
+                    <ul className="flex flex-col gap-0.5 pl-4 text-xs text-muted-foreground list-disc">
+                      {section.items.map((item) => (
+                        <li key={item}>
+                          {section.title === "First prompts" ? item : <code>{item}</code>}
+                        </li>
+                      ))}
+                    </ul>
+                  </div>
+                ))}
+              </div>
+            )}
+
             <div className="flex flex-col gap-2">
               <Label htmlFor="import-model">Model</Label>
               <select
@@ -8,12 +8,15 @@ import { SplashScreen } from "@/components/splash-screen";
 import { SetupScreen } from "@/components/setup-screen";
 import OrphanResolutionDialog from "@/components/orphan-resolution-dialog";
 import ReconciliationAckDialog from "@/components/reconciliation-ack-dialog";
+import ContractUpgradeDialog from "@/components/contract-upgrade-dialog";
 import { useSettingsStore } from "@/stores/settings-store";
+import { onSettingsChange } from "@/lib/settings-events";
+import { onSkillNearDuplicates } from "@/lib/skills-events";
 import { useAuthStore } from "@/stores/auth-store";
-import { getSettings, saveSettings, reconcileStartup, recordReconciliationCancel, parseGitHubUrl, checkMarketplaceUpdates, importGitHubSkills, importMarketplaceToLibrary, checkSkillCustomized } from "@/lib/tauri";
+import { getSettings, saveSettings, reconcileStartup, recordReconciliationCancel, listContractUpgrades, parseGitHubUrl, checkMarketplaceUpdates, importGitHubSkills, importMarketplaceToLibrary, checkSkillCustomized } from "@/lib/tauri";
 import { invoke } from "@tauri-apps/api/core";
 import type { ModelInfo } from "@/stores/settings-store";
-import type { AppSettings, DiscoveredSkill, OrphanSkill, SkillUpdateInfo } from "@/lib/types";
+import type { AppSettings, ContractUpgrade, DiscoveredSkill, OrphanSkill, SkillUpdateInfo } from "@/lib/types";
 
 /** Filter out customized skills, returning only those safe to auto-update. */
 async function filterNonCustomized(skills: SkillUpdateInfo[]): Promise<SkillUpdateInfo[]> {
@@ -160,6 +163,28 @@ function showManualUpdateToasts(
   }
 }
 
+/** Map backend settings onto the settings store fields the app reads. */
+function toStoreSettings(s: AppSettings) {
+  return {
+    anthropicApiKey: s.anthropic_api_key,
+    workspacePath: s.workspace_path,
+    skillsPath: s.skills_path,
+    preferredModel: s.preferred_model,
+    logLevel: s.log_level,
+    extendedThinking: s.extended_thinking,
+    githubOauthToken: s.github_oauth_token,
+    githubUserLogin: s.github_user_login,
+    githubUserAvatar: s.github_user_avatar,
+    githubUserEmail: s.github_user_email,
+    marketplaceRegistries: s.marketplace_registries ?? [],
+    marketplaceInitialized: s.marketplace_initialized ?? false,
+    dashboardViewMode: s.dashboard_view_mode,
+    offlineMode: s.offline_mode ?? false,
+    marketplaceEnabled: !s.offline_mode && !s.disable_marketplace,
+    githubEnabled: !s.offline_mode && !s.disable_github,
+  };
+}
+
 export function AppLayout() {
   const setSettings = useSettingsStore((s) => s.setSettings);
   const isConfigured = useSettingsStore((s) => s.isConfigured);
@@ -177,6 +202,7 @@ export function AppLayout() {
   const [ackDone, setAckDone] = useState(true);
   const [reconRequiresApply, setReconRequiresApply] = useState(false);
   const [reconApplying, setReconApplying] = useState(false);
+  const [contractUpgrades, setContractUpgrades] = useState<ContractUpgrade[]>([]);
 
   // Hydrate settings store from Tauri backend on app startup
   useEffect(() => {
@@ -184,24 +210,10 @@ export function AppLayout() {
 
     getSettings().then((s) => {
       if (cancelledRef.current) return;
-      setSettings({
-        anthropicApiKey: s.anthropic_api_key,
-        workspacePath: s.workspace_path,
-        skillsPath: s.skills_path,
-        preferredModel: s.preferred_model,
-        logLevel: s.log_level,
-        extendedThinking: s.extended_thinking,
-        githubOauthToken: s.github_oauth_token,
-        githubUserLogin: s.github_user_login,
-        githubUserAvatar: s.github_user_avatar,
-        githubUserEmail: s.github_user_email,
-        marketplaceRegistries: s.marketplace_registries ?? [],
-        marketplaceInitialized: s.marketplace_initialized ?? false,
-        dashboardViewMode: s.dashboard_view_mode,
-      });
+      setSettings(toStoreSettings(s));
       setSettingsLoaded(true);
       // Fetch available models in the background — no need to await
-      if (s.anthropic_api_key) {
+      if (s.anthropic_api_key && !s.offline_mode) {
         invoke<ModelInfo[]>("list_models", { apiKey: s.anthropic_api_key })
           .then((models) => { if (!cancelledRef.current) setSettings({ availableModels: models }); })
           .catch((err) => console.warn("[app-layout] Could not fetch model list:", err));
@@ -209,7 +221,8 @@ export function AppLayout() {
       // Check for marketplace updates in the background, and refresh stored registry names
       // from marketplace.json if they have changed since the registry was added.
       const enabledRegistries = (s.marketplace_registries ?? []).filter(r => r.enabled);
-      if (enabledRegistries.length > 0) {
+      const marketplaceEnabled = !s.offline_mode && !s.disable_marketplace;
+      if (marketplaceEnabled && enabledRegistries.length > 0) {
         checkForMarketplaceUpdates(s, cancelledRef, router)
           .then(async (resolvedNamesBySource) => {
             if (resolvedNamesBySource.size === 0) return;
@@ -240,6 +253,36 @@ export function AppLayout() {
     return () => { cancelledRef.current = true; };
   }, [setSettings]);
 
+  // Reload the store when settings are written elsewhere (another window,
+  // a backend command) so pages never run with stale paths or models.
+  useEffect(() => {
+    let cancelled = false;
+    const unlisten = onSettingsChange(() => {
+      getSettings()
+        .then((s) => { if (!cancelled) setSettings(toStoreSettings(s)); })
+        .catch((err) => console.warn("[app-layout] Failed to reload settings:", err));
+    });
+    return () => {
+      cancelled = true;
+      unlisten.then((fn) => fn());
+    };
+  }, [setSettings]);
+
+  // Warn when an import looks like a copy of a skill already in the library.
+  useEffect(() => {
+    const unlisten = onSkillNearDuplicates((matches) => {
+      for (const m of matches) {
+        toast.warning(
+          `"${m.skill_name}" looks like a copy of "${m.similar_to}" (${Math.round(m.score * 100)}% similar)`,
+          { description: "Consider keeping one of them to avoid duplicate skills in the library." }
+        );
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   // Run reconciliation after settings are loaded
   useEffect(() => {
     if (!settingsLoaded) return;
@@ -273,6 +316,20 @@ export function AppLayout() {
       });
   }, [settingsLoaded]);
 
+  // After reconciliation, surface in-progress skills whose step outputs predate a prompt contract change
+  useEffect(() => {
+    if (!reconciled) return;
+
+    listContractUpgrades()
+      .then((upgrades) => {
+        if (upgrades.length > 0) {
+          console.warn("[app-layout] %d skill(s) need a contract upgrade", upgrades.length);
+          setContractUpgrades(upgrades);
+        }
+      })
+      .catch((err) => console.warn("[app-layout] Contract upgrade check failed:", err));
+  }, [reconciled]);
+
   // Global keyboard shortcuts
   useEffect(() => {
     const handleKeyDown = (e: KeyboardEvent) => {
@@ -323,6 +380,13 @@ export function AppLayout() {
           onResolved={() => setOrphans([])}
         />
       )}
+      {ackDone && orphans.length === 0 && contractUpgrades.length > 0 && (
+        <ContractUpgradeDialog
+          upgrades={contractUpgrades}
+          open
+          onResolved={() => setContractUpgrades([])}
+        />
+      )}
       {!ackDone && (
         <ReconciliationAckDialog
           notifications={reconNotifications}
@@ -0,0 +1,109 @@
+import { useState } from "react"
+import { Plus, Trash2 } from "lucide-react"
+import { Button } from "@/components/ui/button"
+import { Input } from "@/components/ui/input"
+import type { ModelRoute } from "@/lib/types"
+import { WORKFLOW_STEP_DEFINITIONS } from "@/lib/workflow-steps"
+
+const SKILL_TYPE_LABELS: Record<string, string> = {
+  domain: "Domain",
+  source: "Source",
+  "data-engineering": "Data Engineering",
+  platform: "Platform",
+}
+
+const SELECT_CLASS =
+  "flex h-9 rounded-md border border-input bg-transparent px-3 py-1 text-sm shadow-xs transition-colors focus-visible:outline-none focus-visible:ring-1 focus-visible:ring-ring"
+
+function stepLabel(stepId: number | null): string {
+  if (stepId === null) return "Any step"
+  return WORKFLOW_STEP_DEFINITIONS.find((s) => s.id === stepId)?.name ?? `Step ${stepId}`
+}
+
+function sameMatch(a: ModelRoute, b: ModelRoute): boolean {
+  return a.step_id === b.step_id && a.skill_type === b.skill_type
+}
+
+/** Edits the model routes in settings; `onChange` saves them. */
+export function ModelRoutesSettings({
+  routes,
+  onChange,
+}: {
+  routes: ModelRoute[]
+  onChange: (routes: ModelRoute[]) => void
+}) {
+  const [stepId, setStepId] = useState<number | null>(0)
+  const [skillType, setSkillType] = useState<string | null>(null)
+  const [model, setModel] = useState("haiku")
+
+  const draft: ModelRoute = { step_id: stepId, skill_type: skillType, model: model.trim() }
+  const duplicate = routes.some((r) => sameMatch(r, draft))
+  const canAdd = (stepId !== null || skillType !== null) && draft.model.length > 0 && !/\s/.test(draft.model) && !duplicate
+
+  const addRoute = () => {
+    onChange([...routes, draft])
+  }
+
+  return (
+    <div className="flex flex-col gap-3">
+      {routes.map((route, index) => (
+        <div
+          key={`${route.step_id}-${route.skill_type}`}
+          className="flex items-center gap-3 rounded-md border p-3 text-sm"
+          data-testid="model-route"
+        >
+          <span className="font-medium">{stepLabel(route.step_id)}</span>
+          <span className="text-muted-foreground">
+            {route.skill_type ? SKILL_TYPE_LABELS[route.skill_type] ?? route.skill_type : "Any skill type"}
+          </span>
+          <code className="ml-auto text-xs">{route.model}</code>
+          <Button
+            variant="ghost"
+            size="icon"
+            aria-label={`Remove route for ${stepLabel(route.step_id)}`}
+            onClick={() => onChange(routes.filter((_, i) => i !== index))}
+          >
+            <Trash2 className="size-4" />
+          </Button>
+        </div>
+      ))}
+
+      <div className="flex items-center gap-2 rounded-md border border-dashed p-3">
+        <select
+          aria-label="Route step"
+          value={stepId ?? ""}
+          onChange={(e) => setStepId(e.target.value === "" ? null : Number(e.target.value))}
+          className={SELECT_CLASS}
+        >
+          <option value="">Any step</option>
+          {WORKFLOW_STEP_DEFINITIONS.map((step) => (
+            <option key={step.id} value={step.id}>{step.name}</option>
+          ))}
+        </select>
+        <select
+          aria-label="Route skill type"
+          value={skillType ?? ""}
+          onChange={(e) => setSkillType(e.target.value === "" ? null : e.target.value)}
+          className={SELECT_CLASS}
+        >
+          <option value="">Any skill type</option>
+          {Object.entries(SKILL_TYPE_LABELS).map(([value, label]) => (
+            <option key={value} value={value}>{label}</option>
+          ))}
+        </select>
+        <Input
+          aria-label="Route model"
+          placeholder="haiku, sonnet, opus or a model id"
+          value={model}
+          onChange={(e) => setModel(e.target.value)}
+          className="flex-1 font-mono"
+        />
+        <Button variant="outline" size="sm" disabled={!canAdd} onClick={addRoute}>
+          <Plus className="size-4" />
+          Add route
+        </Button>
+      </div>
+      {duplicate && <p className="text-xs text-destructive">A route for this step and skill type already exists.</p>}
+    </div>
+  )
+}
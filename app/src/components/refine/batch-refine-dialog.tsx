@@ -0,0 +1,250 @@
+import { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { toast } from "sonner";
+import { Check, ChevronDown, ChevronRight, Loader2, X } from "lucide-react";
+import { Badge } from "@/components/ui/badge";
+import { Button } from "@/components/ui/button";
+import { Checkbox } from "@/components/ui/checkbox";
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog";
+import { Label } from "@/components/ui/label";
+import { ScrollArea } from "@/components/ui/scroll-area";
+import { Textarea } from "@/components/ui/textarea";
+import { listRefineBatches, resolveRefineBatchItem, startRefineBatch } from "@/lib/tauri";
+import type { RefineBatch, RefineBatchItem, RefineBatchItemStatus, SkillSummary } from "@/lib/types";
+
+const STATUS_LABELS: Record<RefineBatchItemStatus, string> = {
+  queued: "Queued",
+  running: "Running",
+  review: "Awaiting review",
+  no_changes: "No changes",
+  failed: "Failed",
+  approved: "Approved",
+  rejected: "Rejected",
+};
+
+const OPEN_STATUSES: RefineBatchItemStatus[] = ["queued", "running", "review"];
+
+const errorMessage = (err: unknown) => (err instanceof Error ? err.message : String(err));
+
+interface BatchRefineDialogProps {
+  skills: SkillSummary[];
+  workspacePath: string;
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+}
+
+function BatchItemRow({
+  item,
+  busy,
+  onResolve,
+}: {
+  item: RefineBatchItem;
+  busy: boolean;
+  onResolve: (approve: boolean) => void;
+}) {
+  const [expanded, setExpanded] = useState(false);
+  const files = item.diff?.files ?? [];
+
+  return (
+    <div className="rounded-md border p-3 text-sm" data-testid="batch-refine-item">
+      <div className="flex items-center gap-2">
+        {files.length > 0 ? (
+          <button
+            className="flex items-center gap-1 font-medium"
+            aria-label={`${expanded ? "Hide" : "Show"} changes to ${item.skill_name}`}
+            onClick={() => setExpanded(!expanded)}
+          >
+            {expanded ? <ChevronDown className="size-4" /> : <ChevronRight className="size-4" />}
+            {item.skill_name}
+          </button>
+        ) : (
+          <span className="pl-5 font-medium">{item.skill_name}</span>
+        )}
+        <Badge variant={item.status === "failed" ? "destructive" : "secondary"}>
+          {item.status === "running" && <Loader2 className="size-3 animate-spin" />}
+          {STATUS_LABELS[item.status]}
+        </Badge>
+        {item.diff && <span className="text-xs text-muted-foreground">{item.diff.stat}</span>}
+        <span className="ml-auto text-xs text-muted-foreground">${item.cost_usd.toFixed(4)}</span>
+        {item.status === "review" && (
+          <>
+            <Button size="sm" variant="outline" disabled={busy} onClick={() => onResolve(true)}>
+              <Check className="size-4" />
+              Approve
+            </Button>
+            <Button size="sm" variant="outline" disabled={busy} onClick={() => onResolve(false)}>
+              <X className="size-4" />
+              Reject
+            </Button>
+          </>
+        )}
+        {item.status === "queued" && (
+          <Button size="sm" variant="ghost" disabled={busy} onClick={() => onResolve(false)}>
+            Skip
+          </Button>
+        )}
+      </div>
+      {item.error && <p className="mt-2 pl-5 text-xs text-destructive">{item.error}</p>}
+      {expanded &&
+        files.map((file) => (
+          <div key={file.path} className="mt-2 pl-5">
+            <div className="font-mono text-xs text-muted-foreground">
+              {file.path} ({file.status})
+            </div>
+            <pre className="mt-1 max-h-64 overflow-auto rounded bg-muted p-2 font-mono text-xs whitespace-pre-wrap">
+              {file.diff}
+            </pre>
+          </div>
+        ))}
+    </div>
+  );
+}
+
+/** Run one refine instruction against several skills, then approve or reject each skill's changes. */
+export function BatchRefineDialog({ skills, workspacePath, open, onOpenChange }: BatchRefineDialogProps) {
+  const [instruction, setInstruction] = useState("");
+  const [selected, setSelected] = useState<string[]>([]);
+  const [batch, setBatch] = useState<RefineBatch | null>(null);
+  const [busy, setBusy] = useState(false);
+
+  // Reopen the latest batch while it still has skills to run or review.
+  useEffect(() => {
+    if (!open) return;
+    listRefineBatches(1)
+      .then(([latest]) => {
+        if (latest?.items.some((i) => OPEN_STATUSES.includes(i.status))) setBatch(latest);
+      })
+      .catch((err) => console.warn("[batch-refine] list_refine_batches failed:", err));
+  }, [open]);
+
+  useEffect(() => {
+    if (!open) return;
+    let unlisten: (() => void) | undefined;
+    let disposed = false;
+    listen<RefineBatch>("refine-batch-progress", (event) => {
+      setBatch((current) => (current?.batch_id === event.payload.batch_id ? event.payload : current));
+    }).then((fn) => {
+      if (disposed) fn();
+      else unlisten = fn;
+    });
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, [open]);
+
+  const toggle = (name: string, checked: boolean) =>
+    setSelected((current) => (checked ? [...current, name] : current.filter((n) => n !== name)));
+
+  const handleStart = async () => {
+    setBusy(true);
+    try {
+      setBatch(await startRefineBatch(instruction, selected, workspacePath));
+    } catch (err) {
+      console.error("[batch-refine] start_refine_batch failed:", err);
+      toast.error(`Batch refine failed to start: ${errorMessage(err)}`, { duration: Infinity });
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const handleResolve = async (skillName: string, approve: boolean) => {
+    if (!batch) return;
+    setBusy(true);
+    try {
+      setBatch(await resolveRefineBatchItem(batch.batch_id, skillName, approve));
+    } catch (err) {
+      console.error("[batch-refine] resolve_refine_batch_item failed:", err);
+      toast.error(`Failed to ${approve ? "approve" : "reject"} ${skillName}: ${errorMessage(err)}`, {
+        duration: Infinity,
+      });
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const finished = batch !== null && !batch.items.some((i) => OPEN_STATUSES.includes(i.status));
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="sm:max-w-3xl">
+        <DialogHeader>
+          <DialogTitle>Batch Refine</DialogTitle>
+          <DialogDescription>
+            {batch
+              ? batch.instruction
+              : "Apply one instruction to several skills. Each skill's changes wait for your review."}
+          </DialogDescription>
+        </DialogHeader>
+
+        {batch ? (
+          <ScrollArea className="max-h-[60vh]">
+            <div className="flex flex-col gap-2">
+              {batch.items.map((item) => (
+                <BatchItemRow
+                  key={item.skill_name}
+                  item={item}
+                  busy={busy}
+                  onResolve={(approve) => handleResolve(item.skill_name, approve)}
+                />
+              ))}
+            </div>
+          </ScrollArea>
+        ) : (
+          <div className="flex flex-col gap-3">
+            <Textarea
+              aria-label="Batch refine instruction"
+              placeholder="e.g. Update all references from fiscal year FY24 to FY25"
+              value={instruction}
+              onChange={(e) => setInstruction(e.target.value)}
+            />
+            <ScrollArea className="max-h-64 rounded-md border p-3">
+              <div className="flex flex-col gap-2">
+                {skills.map((skill) => (
+                  <div key={skill.name} className="flex items-center gap-2">
+                    <Checkbox
+                      id={`batch-refine-${skill.name}`}
+                      checked={selected.includes(skill.name)}
+                      onCheckedChange={(checked) => toggle(skill.name, checked === true)}
+                    />
+                    <Label htmlFor={`batch-refine-${skill.name}`} className="font-normal">
+                      {skill.name}
+                    </Label>
+                    {skill.purpose && <span className="text-xs text-muted-foreground">{skill.purpose}</span>}
+                  </div>
+                ))}
+              </div>
+            </ScrollArea>
+          </div>
+        )}
+
+        <DialogFooter className="items-center">
+          {batch && (
+            <span className="mr-auto text-sm text-muted-foreground">
+              Total cost ${batch.total_cost_usd.toFixed(4)}
+            </span>
+          )}
+          {batch ? (
+            finished && (
+              <Button variant="outline" onClick={() => setBatch(null)}>
+                New batch
+              </Button>
+            )
+          ) : (
+            <Button disabled={busy || !instruction.trim() || selected.length === 0} onClick={handleStart}>
+              {busy && <Loader2 className="size-4 animate-spin" />}
+              Refine {selected.length} skill{selected.length === 1 ? "" : "s"}
+            </Button>
+          )}
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -0,0 +1,113 @@
+import { useState } from "react";
+import { toast } from "sonner";
+import { Check, ChevronDown, ChevronRight, X } from "lucide-react";
+import { Badge } from "@/components/ui/badge";
+import { Button } from "@/components/ui/button";
+import { reviewRefineFile } from "@/lib/tauri";
+import type { RefineFileDiff } from "@/lib/types";
+import { useRefineStore } from "@/stores/refine-store";
+import { useSettingsStore } from "@/stores/settings-store";
+
+const errorMessage = (err: unknown) => (err instanceof Error ? err.message : String(err));
+
+interface FileReviewListProps {
+  /** Called after a file is put back to HEAD so the preview can reload it. */
+  onFileRejected?: () => void;
+}
+
+function FileReviewRow({
+  file,
+  skillName,
+  busy,
+  onReview,
+}: {
+  file: RefineFileDiff;
+  skillName: string;
+  busy: boolean;
+  onReview: (accept: boolean) => void;
+}) {
+  const [expanded, setExpanded] = useState(false);
+  const label = file.path.startsWith(`${skillName}/`) ? file.path.slice(skillName.length + 1) : file.path;
+
+  return (
+    <div className="text-sm" data-testid="refine-file-review">
+      <div className="flex items-center gap-2">
+        <button
+          className="flex min-w-0 items-center gap-1 font-mono text-xs"
+          aria-label={`${expanded ? "Hide" : "Show"} changes to ${label}`}
+          onClick={() => setExpanded(!expanded)}
+        >
+          {expanded ? <ChevronDown className="size-3.5 shrink-0" /> : <ChevronRight className="size-3.5 shrink-0" />}
+          <span className="truncate">{label}</span>
+        </button>
+        <Badge variant="outline" className="text-xs">
+          {file.status}
+        </Badge>
+        {file.review === "accepted" ? (
+          <Badge variant="secondary" className="ml-auto text-xs">
+            Accepted
+          </Badge>
+        ) : (
+          <div className="ml-auto flex gap-1">
+            <Button size="sm" variant="outline" disabled={busy} onClick={() => onReview(true)}>
+              <Check className="size-3.5" />
+              Accept
+            </Button>
+            <Button size="sm" variant="outline" disabled={busy} onClick={() => onReview(false)}>
+              <X className="size-3.5" />
+              Reject
+            </Button>
+          </div>
+        )}
+      </div>
+      {expanded && (
+        <pre className="mt-1 max-h-48 overflow-auto rounded bg-muted p-2 font-mono text-xs whitespace-pre-wrap">
+          {file.diff}
+        </pre>
+      )}
+    </div>
+  );
+}
+
+/** Changed skill files of the refine session, each accepted or rejected on its own. */
+export function FileReviewList({ onFileRejected }: FileReviewListProps) {
+  const workspacePath = useSettingsStore((s) => s.workspacePath);
+  const pendingDiff = useRefineStore((s) => s.pendingDiff);
+  const sessionId = useRefineStore((s) => s.sessionId);
+  const selectedSkill = useRefineStore((s) => s.selectedSkill);
+  const isRunning = useRefineStore((s) => s.isRunning);
+  const setPendingDiff = useRefineStore((s) => s.setPendingDiff);
+  const [busy, setBusy] = useState(false);
+
+  const files = pendingDiff?.files ?? [];
+  if (files.length === 0 || !selectedSkill) return null;
+
+  const handleReview = async (path: string, accept: boolean) => {
+    if (!sessionId || !workspacePath) return;
+    setBusy(true);
+    try {
+      setPendingDiff(await reviewRefineFile(sessionId, workspacePath, path, accept));
+      if (!accept) onFileRejected?.();
+    } catch (err) {
+      console.error("[file-review-list] review_refine_file failed:", err);
+      toast.error(`Failed to ${accept ? "accept" : "reject"} ${path}: ${errorMessage(err)}`, { duration: Infinity });
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  return (
+    <div className="flex flex-col gap-1.5 border-b px-3 py-2" data-testid="refine-file-review-list">
+      <div className="text-xs text-muted-foreground">{pendingDiff?.stat}</div>
+      {files.map((file) => (
+        <FileReviewRow
+          key={file.path}
+          file={file}
+          skillName={selectedSkill.name}
+          busy={busy || isRunning || !sessionId}
+          onReview={(accept) => handleReview(file.path, accept)}
+        />
+      ))}
+    </div>
+  );
+}
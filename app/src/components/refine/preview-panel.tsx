@@ -18,6 +18,7 @@ import { ScrollArea } from "@/components/ui/scroll-area";
 import { Skeleton } from "@/components/ui/skeleton";
 import { useRefineStore } from "@/stores/refine-store";
 import { DiffView } from "./diff-view";
+import { FileReviewList } from "./file-review-list";
 
 const REMARK_PLUGINS = [remarkGfm];
 const REHYPE_PLUGINS = [rehypeHighlight];
@@ -33,7 +34,12 @@ const MarkdownPreview = memo(function MarkdownPreview({ content }: { content: st
   );
 });
 
-export function PreviewPanel() {
+interface PreviewPanelProps {
+  /** Reload skill files after a changed file is rejected. */
+  onFileRejected?: () => void;
+}
+
+export function PreviewPanel({ onFileRejected }: PreviewPanelProps = {}) {
   const skillFiles = useRefineStore((s) => s.skillFiles);
   const activeFileTab = useRefineStore((s) => s.activeFileTab);
   const diffMode = useRefineStore((s) => s.diffMode);
@@ -112,6 +118,7 @@ export function PreviewPanel() {
           {diffMode ? "Preview" : "Diff"}
         </Button>
       </div>
+      <FileReviewList onFileRejected={onFileRejected} />
       <div className="min-h-0 flex-1">
         {diffMode && baselineFile ? (
           <DiffView
@@ -11,7 +11,13 @@ import {
   AlertDialogHeader,
   AlertDialogTitle,
 } from "@/components/ui/alert-dialog"
-import { previewStepReset, resetWorkflowStep, type StepResetPreview } from "@/lib/tauri"
+import { Checkbox } from "@/components/ui/checkbox"
+import {
+  previewStepReset,
+  resetWorkflowArtifacts,
+  resetWorkflowStep,
+  type StepResetPreview,
+} from "@/lib/tauri"
 
 interface ResetStepDialogProps {
   targetStep: number | null
@@ -24,10 +30,13 @@ interface ResetStepDialogProps {
   skillName: string
   open: boolean
   onOpenChange: (open: boolean) => void
-  onReset: () => void
+  /** Called after a successful reset. When the user kept some files, receives the
+   *  step the workflow now restarts at (set by reset_workflow_artifacts). */
+  onReset: (currentStep?: number) => void
   /** Override the default resetWorkflowStep Tauri call. When provided, this is
    *  called instead of resetWorkflowStep(workspacePath, skillName, effectiveDeleteFrom).
-   *  Use this for navigate-back flows that need a different DB command (e.g. navigate_back_to_step). */
+   *  Use this for navigate-back flows that need a different DB command (e.g. navigate_back_to_step).
+   *  Not used when files are kept: that always goes through resetWorkflowArtifacts. */
   executeReset?: () => Promise<void>
 }
 
@@ -44,10 +53,12 @@ export default function ResetStepDialog({
   const [loading, setLoading] = useState(false)
   const [preview, setPreview] = useState<StepResetPreview[] | null>(null)
   const [loadingPreview, setLoadingPreview] = useState(false)
+  const [keepFiles, setKeepFiles] = useState<Set<string>>(new Set())
 
   const effectiveDeleteFrom = deleteFromStep ?? targetStep
 
   useEffect(() => {
+    setKeepFiles(new Set())
     if (!open || effectiveDeleteFrom === null) {
       setPreview(null)
       return
@@ -69,6 +80,18 @@ export default function ResetStepDialog({
     if (effectiveDeleteFrom === null) return
     setLoading(true)
     try {
+      if (keepFiles.size > 0) {
+        const result = await resetWorkflowArtifacts(
+          workspacePath,
+          skillName,
+          effectiveDeleteFrom,
+          [...keepFiles],
+        )
+        toast.success(`Workflow reset, kept ${result.kept_files.length} file${result.kept_files.length !== 1 ? "s" : ""}`)
+        onOpenChange(false)
+        onReset(result.current_step)
+        return
+      }
       if (executeReset) {
         await executeReset()
       } else {
@@ -87,7 +110,17 @@ export default function ResetStepDialog({
     }
   }
 
+  const toggleKeep = (file: string, keep: boolean) => {
+    setKeepFiles((prev) => {
+      const next = new Set(prev)
+      if (keep) next.add(file)
+      else next.delete(file)
+      return next
+    })
+  }
+
   const totalFiles = preview?.reduce((sum, s) => sum + s.files.length, 0) ?? 0
+  const deleteCount = totalFiles - keepFiles.size
 
   return (
     <AlertDialog open={open}>
@@ -96,6 +129,7 @@ export default function ResetStepDialog({
           <AlertDialogTitle>Reset to Earlier Step</AlertDialogTitle>
           <AlertDialogDescription>
             Going back will delete all artifacts from step {(effectiveDeleteFrom ?? 0) + 1} onward and reset their statuses.
+            Tick a file to keep it; the workflow then restarts at the first step that loses a file.
           </AlertDialogDescription>
         </AlertDialogHeader>
 
@@ -112,8 +146,17 @@ export default function ResetStepDialog({
                 </p>
                 <ul className="mt-1 space-y-0.5 text-muted-foreground">
                   {step.files.map((file) => (
-                    <li key={file} className="font-mono text-xs pl-3">
-                      {file}
+                    <li key={file} className="flex items-center gap-2 pl-3">
+                      <Checkbox
+                        id={`keep-${file}`}
+                        aria-label={`Keep ${file}`}
+                        checked={keepFiles.has(file)}
+                        onCheckedChange={(checked) => toggleKeep(file, !!checked)}
+                        disabled={loading}
+                      />
+                      <label htmlFor={`keep-${file}`} className="font-mono text-xs cursor-pointer">
+                        {file}
+                      </label>
                     </li>
                   ))}
                 </ul>
@@ -133,10 +176,10 @@ export default function ResetStepDialog({
           <AlertDialogAction
             variant="destructive"
             onClick={handleReset}
-            disabled={loading || loadingPreview || preview === null}
+            disabled={loading || loadingPreview || preview === null || (totalFiles > 0 && deleteCount === 0)}
           >
             {loading && <Loader2 className="size-4 animate-spin" />}
-            {totalFiles > 0 ? `Delete ${totalFiles} file${totalFiles !== 1 ? "s" : ""} & Reset` : "Reset"}
+            {deleteCount > 0 ? `Delete ${deleteCount} file${deleteCount !== 1 ? "s" : ""} & Reset` : "Reset"}
           </AlertDialogAction>
         </AlertDialogFooter>
       </AlertDialogContent>
@@ -6,6 +6,7 @@ export function ReviewModeToggle() {
   const setReviewMode = useWorkflowStore((s) => s.setReviewMode);
   const isRunning = useWorkflowStore((s) => s.isRunning);
   const gateLoading = useWorkflowStore((s) => s.gateLoading);
+  const readOnly = useWorkflowStore((s) => s.readOnly);
   const locked = isRunning || gateLoading;
 
   return (
@@ -23,7 +24,8 @@ export function ReviewModeToggle() {
         size="sm"
         variant={!reviewMode ? "default" : "ghost"}
         onClick={() => setReviewMode(false)}
-        disabled={locked}
+        disabled={locked || readOnly}
+        title={readOnly ? "Another instance is editing this skill" : undefined}
         className="h-7 px-3 text-xs"
       >
         Update
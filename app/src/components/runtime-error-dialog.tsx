@@ -32,6 +32,8 @@ function getErrorTitle(errorType: string): string {
       return "Incompatible Node.js Version";
     case "spawn_failed":
       return "Failed to Start Agent Runtime";
+    case "protocol_incompatible":
+      return "Agent Runtime Out of Date";
     case "ready_timeout":
       return "Agent Runtime Initialization Timeout";
     default:
@@ -44,6 +46,7 @@ function getErrorIcon(errorType: string) {
   switch (errorType) {
     case "sidecar_missing":
     case "spawn_failed":
+    case "protocol_incompatible":
       return <Terminal className="size-5 text-destructive" />;
     case "node_missing":
     case "node_incompatible":
@@ -69,6 +72,13 @@ function getFailureClass(errorType: string): {
     };
   }
 
+  if (errorType === "protocol_incompatible") {
+    return {
+      label: "Version mismatch",
+      description: "The agent runtime and the app were built from different versions. Retrying will not help until the runtime is repaired.",
+    };
+  }
+
   return {
     label: "Transient startup issue",
     description: "This is usually temporary. Retry the workflow step after applying the fix hint if needed.",
@@ -13,7 +13,7 @@ import {
   ContextMenuTrigger,
 } from "@/components/ui/context-menu"
 import { Progress } from "@/components/ui/progress"
-import { Download, FlaskConical, Lock, MessageSquare, Pencil, SquarePen, Trash2 } from "lucide-react"
+import { Download, FlaskConical, Layers, Lock, MessageSquare, PackageOpen, Pencil, SquarePen, Trash2 } from "lucide-react"
 import { SkillSourceBadge } from "@/components/skill-source-badge"
 import {
   Tooltip,
@@ -21,7 +21,7 @@ import {
   TooltipProvider,
   TooltipTrigger,
 } from "@/components/ui/tooltip"
-import type { SkillSummary, Purpose } from "@/lib/types"
+import type { SkillSummary, SkillReviewState, SkillUpstreamReview, Purpose } from "@/lib/types"
 import { PURPOSE_SHORT_LABELS, PURPOSE_COLORS } from "@/lib/types"
 import { cn } from "@/lib/utils"
 
@@ -40,8 +40,16 @@ interface SkillCardProps {
   onDownload?: (skill: SkillSummary) => void
   onEdit?: (skill: SkillSummary) => void
   onEditWorkflow?: (skill: SkillSummary) => void
+  /** Export the skill with its workflow state as a portable `.skillpack`. */
+  onExport?: (skill: SkillSummary) => void
   onRefine?: (skill: SkillSummary) => void
+  /** Open the stable/beta deployment slots of the skill. */
+  onSlots?: (skill: SkillSummary) => void
   onTest?: (skill: SkillSummary) => void
+  /** Review state from the skill's review-by date, when it has one. */
+  reviewState?: SkillReviewState
+  /** Pull/merge request from the skill's last branch-per-skill push. */
+  upstreamReview?: SkillUpstreamReview
 }
 
 export function parseStepProgress(currentStep: string | null, status: string | null): number {
@@ -120,6 +128,34 @@ export function IconAction({ icon, label, tooltip, onClick, disabled, className
   )
 }
 
+/** Shown when a skill is due for review or past its review-by date. */
+export function ReviewBadge({ state, reviewBy }: { state?: SkillReviewState; reviewBy?: string | null }) {
+  if (state !== "due_soon" && state !== "expired") return null
+  return (
+    <Badge
+      variant={state === "expired" ? "destructive" : "outline"}
+      className="w-fit text-xs"
+      title={reviewBy ? `Review by ${reviewBy}` : undefined}
+    >
+      {state === "expired" ? "Review overdue" : "Review due"}
+    </Badge>
+  )
+}
+
+/** Shown while a branch-per-skill push is still waiting for review upstream. */
+export function UpstreamReviewBadge({ review }: { review?: SkillUpstreamReview }) {
+  if (review?.state !== "open") return null
+  return (
+    <Badge
+      variant="outline"
+      className="w-fit text-xs"
+      title={`Pull request #${review.number} into ${review.base_branch} on ${review.repo} is not merged yet`}
+    >
+      Pending upstream review
+    </Badge>
+  )
+}
+
 export default function SkillCard({
   skill,
   isLocked,
@@ -128,8 +164,12 @@ export default function SkillCard({
   onDownload,
   onEdit,
   onEditWorkflow,
+  onExport,
   onRefine,
+  onSlots,
   onTest,
+  reviewState,
+  upstreamReview,
 }: SkillCardProps) {
   const isMarketplace = skill.skill_source === 'marketplace'
   const progress = isMarketplace ? 100 : parseStepProgress(skill.current_step, skill.status)
@@ -170,6 +210,8 @@ export default function SkillCard({
             </Badge>
           )}
           <SkillSourceBadge skillSource={skill.skill_source} />
+          <ReviewBadge state={reviewState} reviewBy={skill.review_by} />
+          <UpstreamReviewBadge review={upstreamReview} />
         </div>
         {skill.tags && skill.tags.length > 0 && (
           <div className="flex flex-wrap gap-1">
@@ -273,6 +315,18 @@ export default function SkillCard({
               <SquarePen className="size-4" />
               Edit details
             </ContextMenuItem>
+            {onExport && (
+              <ContextMenuItem onSelect={() => onExport(skill)}>
+                <PackageOpen className="size-4" />
+                Export .skillpack
+              </ContextMenuItem>
+            )}
+            {onSlots && (
+              <ContextMenuItem onSelect={() => onSlots(skill)}>
+                <Layers className="size-4" />
+                Deployment slots
+              </ContextMenuItem>
+            )}
           </ContextMenuContent>
         </ContextMenu>
       ) : (
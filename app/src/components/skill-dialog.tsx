@@ -23,9 +23,9 @@ import { GhostTextarea } from "@/components/ghost-input"
 import { Textarea } from "@/components/ui/textarea"
 import { useSettingsStore } from "@/stores/settings-store"
 import { useWorkflowStore } from "@/stores/workflow-store"
-import { renameSkill, updateSkillMetadata, generateSuggestions, type FieldSuggestions } from "@/lib/tauri"
+import { renameSkill, updateSkillMetadata, setSkillReviewDate, markSkillReviewed, generateSuggestions, checkSkillTags, type FieldSuggestions } from "@/lib/tauri"
 import { isValidKebab, toKebabChars, buildIntakeJson } from "@/lib/utils"
-import type { SkillSummary } from "@/lib/types"
+import type { SkillSummary, TaxonomyWarning } from "@/lib/types"
 import { PURPOSES, PURPOSE_LABELS } from "@/lib/types"
 
 // --- Built skill detection ---
@@ -154,12 +154,15 @@ export default function SkillDialog(props: SkillDialogProps) {
   const [purpose, setPurpose] = useState("")
   const [description, setDescription] = useState("")
   const [tags, setTags] = useState<string[]>([])
+  const [tagWarnings, setTagWarnings] = useState<TaxonomyWarning[]>([])
   const [contextQuestions, setContextQuestions] = useState("")
   // Step 2 behaviour fields
   const [version, setVersion] = useState("1.0.0")
   const [argumentHint, setArgumentHint] = useState("")
   const [userInvocable, setUserInvocable] = useState(true)
   const [disableModelInvocation, setDisableModelInvocation] = useState(false)
+  const [reviewBy, setReviewBy] = useState("")
+  const [markReviewed, setMarkReviewed] = useState(false)
   const [submitting, setSubmitting] = useState(false)
   const [error, setError] = useState<string | null>(null)
 
@@ -188,11 +191,14 @@ export default function SkillDialog(props: SkillDialogProps) {
     setPurpose("")
     setDescription("")
     setTags([])
+    setTagWarnings([])
     setContextQuestions("")
     setVersion("1.0.0")
     setArgumentHint("")
     setUserInvocable(true)
     setDisableModelInvocation(false)
+    setReviewBy("")
+    setMarkReviewed(false)
     setDescriptionSuggestion(null)
     setError(null)
     setSubmitting(false)
@@ -213,6 +219,7 @@ export default function SkillDialog(props: SkillDialogProps) {
       setArgumentHint(editSkill.argumentHint || "")
       setUserInvocable(editSkill.userInvocable ?? true)
       setDisableModelInvocation(editSkill.disableModelInvocation ?? false)
+      setReviewBy(editSkill.review_by ?? "")
     } else if (!dialogOpen) {
       resetForm()
     }
@@ -228,6 +235,22 @@ export default function SkillDialog(props: SkillDialogProps) {
     }
   }, [editOnOpenChange, createOnOpenChange])
 
+  // Check tags against the team taxonomy as they are edited; warnings never block saving
+  useEffect(() => {
+    if (!dialogOpen || tags.length === 0) { setTagWarnings([]); return }
+    let stale = false
+    const check = async () => {
+      try {
+        const warnings = await checkSkillTags(skillName, tags)
+        if (!stale) setTagWarnings(warnings ?? [])
+      } catch (err) {
+        console.warn("[skill-dialog] check_skill_tags failed:", err)
+      }
+    }
+    check()
+    return () => { stale = true }
+  }, [dialogOpen, skillName, tags])
+
   // --- Cascading ghost suggestions ---
   // Group 0: description <- name + purpose (skip in edit mode)
   // Context questions: fires when name + description + purpose are all set
@@ -312,6 +335,12 @@ export default function SkillDialog(props: SkillDialogProps) {
           userInvocable,
           disableModelInvocation,
         )
+        const savedName = nameChanged ? skillName : editSkill.name
+        if (markReviewed && reviewBy) {
+          await markSkillReviewed(savedName, reviewBy)
+        } else if (reviewBy !== (editSkill.review_by ?? "")) {
+          await setSkillReviewDate(savedName, reviewBy || null)
+        }
         console.log(`[skill] Updated skill "${skillName}"`)
         toast.success("Skill updated")
         handleOpenChange(false)
@@ -496,6 +525,7 @@ export default function SkillDialog(props: SkillDialogProps) {
                     tags={tags}
                     onChange={setTags}
                     suggestions={tagSuggestions}
+                    warnings={tagWarnings}
                     disabled={submitting || isBuilt}
                     placeholder="e.g., salesforce, analytics"
                   />
@@ -550,6 +580,36 @@ export default function SkillDialog(props: SkillDialogProps) {
                     Optional hint shown to users when invoking this skill
                   </p>
                 </div>
+                {isEdit && (
+                  <div className="flex flex-col gap-2">
+                    <Label htmlFor="review-by">Review By</Label>
+                    <Input
+                      id="review-by"
+                      type="date"
+                      value={reviewBy}
+                      onChange={(e) => setReviewBy(e.target.value)}
+                      disabled={submitting}
+                    />
+                    <p className="text-xs text-muted-foreground">
+                      Date the skill&apos;s content should be checked again; written to SKILL.md as review-by
+                    </p>
+                  </div>
+                )}
+                {isEdit && editSkill?.review_by && (
+                  <div className="flex items-center justify-between rounded-md border p-3">
+                    <div className="flex flex-col gap-0.5">
+                      <span className="text-sm font-medium">Mark Reviewed</span>
+                      <span className="text-xs text-muted-foreground">
+                        Record that the content was reviewed and set the next review date above
+                      </span>
+                    </div>
+                    <Switch
+                      checked={markReviewed}
+                      onCheckedChange={setMarkReviewed}
+                      disabled={submitting}
+                    />
+                  </div>
+                )}
                 <div className="flex items-center justify-between rounded-md border p-3">
                   <div className="flex flex-col gap-0.5">
                     <span className="text-sm font-medium">User Invocable</span>
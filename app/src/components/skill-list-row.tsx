@@ -1,9 +1,11 @@
 import {
   Download,
   FlaskConical,
+  Layers,
   Lock,
   MessageSquare,
   MoreHorizontal,
+  PackageOpen,
   Pencil,
   SquarePen,
   Trash2,
@@ -24,10 +26,12 @@ import {
 } from "@/components/ui/tooltip"
 import {
   IconAction,
+  ReviewBadge,
+  UpstreamReviewBadge,
   isWorkflowComplete,
 } from "@/components/skill-card"
 import { SkillSourceBadge } from "@/components/skill-source-badge"
-import type { SkillSummary, Purpose } from "@/lib/types"
+import type { SkillSummary, SkillReviewState, SkillUpstreamReview, Purpose } from "@/lib/types"
 import { PURPOSE_SHORT_LABELS } from "@/lib/types"
 import { cn } from "@/lib/utils"
 
@@ -63,8 +67,14 @@ interface SkillListRowProps {
   onDownload?: (skill: SkillSummary) => void
   onEdit?: (skill: SkillSummary) => void
   onEditWorkflow?: (skill: SkillSummary) => void
+  /** Export the skill with its workflow state as a portable `.skillpack`. */
+  onExport?: (skill: SkillSummary) => void
   onRefine?: (skill: SkillSummary) => void
+  /** Open the stable/beta deployment slots of the skill. */
+  onSlots?: (skill: SkillSummary) => void
   onTest?: (skill: SkillSummary) => void
+  reviewState?: SkillReviewState
+  upstreamReview?: SkillUpstreamReview
 }
 
 export default function SkillListRow({
@@ -75,8 +85,12 @@ export default function SkillListRow({
   onDownload,
   onEdit,
   onEditWorkflow,
+  onExport,
   onRefine,
+  onSlots,
   onTest,
+  reviewState,
+  upstreamReview,
 }: SkillListRowProps) {
   const isMarketplace = skill.skill_source === 'marketplace'
   const canDownload = isMarketplace || isWorkflowComplete(skill)
@@ -118,8 +132,10 @@ export default function SkillListRow({
 
       {/* Col 2: Source */}
       <td className={cn(tdBase, "hidden sm:table-cell")}>
-        <div className="flex items-center">
+        <div className="flex items-center gap-1">
           <SkillSourceBadge skillSource={skill.skill_source} />
+          <ReviewBadge state={reviewState} reviewBy={skill.review_by} />
+          <UpstreamReviewBadge review={upstreamReview} />
         </div>
       </td>
 
@@ -209,6 +225,18 @@ export default function SkillListRow({
                   <SquarePen className="size-4" />
                   Edit details
                 </DropdownMenuItem>
+                {onExport && (
+                  <DropdownMenuItem onSelect={() => onExport(skill)}>
+                    <PackageOpen className="size-4" />
+                    Export .skillpack
+                  </DropdownMenuItem>
+                )}
+                {onSlots && (
+                  <DropdownMenuItem onSelect={() => onSlots(skill)}>
+                    <Layers className="size-4" />
+                    Deployment slots
+                  </DropdownMenuItem>
+                )}
               </DropdownMenuContent>
             </DropdownMenu>
           )}
@@ -0,0 +1,133 @@
+import { useEffect, useState } from "react"
+import { CheckCircle2, Circle, Info } from "lucide-react"
+import { Button } from "@/components/ui/button"
+import { Popover, PopoverContent, PopoverTrigger } from "@/components/ui/popover"
+import { explainStep, getEffectiveModel } from "@/lib/tauri"
+import { formatElapsed } from "@/lib/utils"
+import type { EffectiveModel, StepExplanation } from "@/lib/types"
+import { useSettingsStore } from "@/stores/settings-store"
+
+const MODEL_SOURCE_LABELS: Record<EffectiveModel["source"], string> = {
+  route: "model route",
+  agent: "step default",
+  preferred: "preferred model",
+}
+
+interface StepContextCardProps {
+  skillName: string
+  stepId: number
+  /** Static description shown until the explanation loads, or if it fails. */
+  fallbackDescription?: string
+  /** Change to re-fetch, e.g. when the step status changes. */
+  refreshKey?: string
+}
+
+export function StepContextCard({ skillName, stepId, fallbackDescription, refreshKey }: StepContextCardProps) {
+  const [explanation, setExplanation] = useState<StepExplanation | null>(null)
+  const [effectiveModel, setEffectiveModel] = useState<EffectiveModel | null>(null)
+  const workspacePath = useSettingsStore((s) => s.workspacePath)
+
+  useEffect(() => {
+    let cancelled = false
+    setExplanation(null)
+    explainStep(skillName, stepId)
+      .then((result) => {
+        if (!cancelled && result) setExplanation(result)
+      })
+      .catch((err) => {
+        console.warn("[step-context-card] explain_step failed:", err)
+      })
+    return () => {
+      cancelled = true
+    }
+  }, [skillName, stepId, refreshKey])
+
+  useEffect(() => {
+    if (!workspacePath) return
+    let cancelled = false
+    setEffectiveModel(null)
+    getEffectiveModel(skillName, stepId, workspacePath)
+      .then((result) => {
+        if (!cancelled) setEffectiveModel(result)
+      })
+      .catch((err) => {
+        console.warn("[step-context-card] get_effective_model failed:", err)
+      })
+    return () => {
+      cancelled = true
+    }
+  }, [skillName, stepId, workspacePath, refreshKey])
+
+  if (!explanation) {
+    return <p className="text-sm text-muted-foreground">{fallbackDescription}</p>
+  }
+
+  return (
+    <div className="flex items-start gap-1">
+      <p className="text-sm text-muted-foreground">{explanation.summary}</p>
+      <Popover>
+        <PopoverTrigger asChild>
+          <Button variant="ghost" size="icon" className="size-6 shrink-0" aria-label="Explain this step">
+            <Info className="size-3.5" />
+          </Button>
+        </PopoverTrigger>
+        <PopoverContent align="start" className="w-96 text-sm">
+          <div className="flex flex-col gap-3">
+            <div className="flex flex-col gap-1">
+              <span className="text-xs font-medium uppercase tracking-wide text-muted-foreground">Reads</span>
+              {explanation.reads.map((input) => (
+                <span key={input.label} className="flex items-center gap-1.5">
+                  {input.available ? (
+                    <CheckCircle2 className="size-3.5 text-green-600 dark:text-green-400" />
+                  ) : (
+                    <Circle className="size-3.5 text-muted-foreground" />
+                  )}
+                  {input.label}
+                  {input.path && <code className="text-xs text-muted-foreground">{input.path}</code>}
+                </span>
+              ))}
+            </div>
+            <div className="flex flex-col gap-1">
+              <span className="text-xs font-medium uppercase tracking-wide text-muted-foreground">Writes</span>
+              {explanation.writes.map((path) => (
+                <code key={path} className="text-xs">{path}</code>
+              ))}
+            </div>
+            <div className="flex flex-col gap-1">
+              <span className="text-xs font-medium uppercase tracking-wide text-muted-foreground">Expected</span>
+              {explanation.history_runs > 0 && explanation.expected_cost_usd !== null && explanation.expected_duration_ms !== null ? (
+                <span>
+                  ~${explanation.expected_cost_usd.toFixed(2)} · ~{formatElapsed(explanation.expected_duration_ms)}{" "}
+                  <span className="text-xs text-muted-foreground">
+                    ({explanation.history_runs} {explanation.history_scope === "skill" ? "runs of this skill" : "runs across skills"})
+                  </span>
+                </span>
+              ) : (
+                <span className="text-muted-foreground">No past runs to estimate from yet</span>
+              )}
+            </div>
+            {effectiveModel && (
+              <div className="flex flex-col gap-1">
+                <span className="text-xs font-medium uppercase tracking-wide text-muted-foreground">Model</span>
+                <span>
+                  <code className="text-xs">{effectiveModel.model}</code>{" "}
+                  <span className="text-xs text-muted-foreground">({MODEL_SOURCE_LABELS[effectiveModel.source]})</span>
+                </span>
+              </div>
+            )}
+            {explanation.prepare.length > 0 && (
+              <div className="flex flex-col gap-1">
+                <span className="text-xs font-medium uppercase tracking-wide text-muted-foreground">Before you run</span>
+                <ul className="list-disc pl-4">
+                  {explanation.prepare.map((item) => (
+                    <li key={item}>{item}</li>
+                  ))}
+                </ul>
+              </div>
+            )}
+          </div>
+        </PopoverContent>
+      </Popover>
+    </div>
+  )
+}
@@ -0,0 +1,67 @@
+import { useEffect, useState } from "react"
+import { toast } from "sonner"
+import { Loader2 } from "lucide-react"
+import { Label } from "@/components/ui/label"
+import { getToolPolicy, setToolPolicy } from "@/lib/tauri"
+import type { StepToolRole, ToolPolicy } from "@/lib/types"
+import { WORKFLOW_STEP_DEFINITIONS } from "@/lib/workflow-steps"
+
+const ROLE_LABELS: Record<StepToolRole, string> = {
+  full: "All declared tools",
+  no_shell: "No shell (Bash removed)",
+  read_only: "Read-only (Read, Glob, Grep)",
+}
+
+/** Edits the per-step tool roles of the global tool policy. */
+export function StepToolRoleSettings() {
+  const [policy, setPolicy] = useState<ToolPolicy | null>(null)
+  const [saving, setSaving] = useState(false)
+
+  useEffect(() => {
+    getToolPolicy(null)
+      .then((view) => setPolicy(view.global))
+      .catch((err) => console.warn("[step-tool-role-settings] get_tool_policy failed:", err))
+  }, [])
+
+  if (!policy) {
+    return <Loader2 className="size-4 animate-spin text-muted-foreground" />
+  }
+
+  const save = async (stepId: number, role: StepToolRole) => {
+    setSaving(true)
+    try {
+      const next = { ...policy, step_roles: { ...policy.step_roles, [stepId]: role } }
+      const view = await setToolPolicy(null, next)
+      setPolicy(view.global)
+      toast.success("Step tools saved", { duration: 1500 })
+    } catch (err) {
+      toast.error(`Failed to save step tools: ${err instanceof Error ? err.message : String(err)}`, { duration: Infinity })
+    } finally {
+      setSaving(false)
+    }
+  }
+
+  return (
+    <div className="flex flex-col gap-3">
+      {WORKFLOW_STEP_DEFINITIONS.map((step) => {
+        const id = `step-tool-role-${step.id}`
+        return (
+          <div key={step.id} className="flex items-center justify-between gap-4">
+            <Label htmlFor={id}>{step.name}</Label>
+            <select
+              id={id}
+              value={policy.step_roles?.[step.id] ?? "full"}
+              disabled={saving}
+              onChange={(e) => save(step.id, e.target.value as StepToolRole)}
+              className="flex h-9 w-64 rounded-md border border-input bg-transparent px-3 py-1 text-sm shadow-xs transition-colors focus-visible:outline-none focus-visible:ring-1 focus-visible:ring-ring"
+            >
+              {(Object.keys(ROLE_LABELS) as StepToolRole[]).map((role) => (
+                <option key={role} value={role}>{ROLE_LABELS[role]}</option>
+              ))}
+            </select>
+          </div>
+        )
+      })}
+    </div>
+  )
+}
@@ -1,6 +1,7 @@
 import { useState, useRef, useEffect } from "react"
 import { Badge } from "@/components/ui/badge"
 import { X } from "lucide-react"
+import type { TaxonomyWarning } from "@/lib/types"
 
 interface TagInputProps {
   tags: string[]
@@ -8,10 +9,17 @@ interface TagInputProps {
   suggestions?: string[]
   disabled?: boolean
   placeholder?: string
+  /** Team taxonomy warnings for the current tags; flagged tags are highlighted. */
+  warnings?: TaxonomyWarning[]
 }
 
+/** Lowercase and trim each `/`-separated level, as the backend stores tags. */
 function normalizeTag(tag: string): string {
-  return tag.trim().toLowerCase()
+  return tag
+    .split("/")
+    .map((level) => level.trim().toLowerCase())
+    .filter(Boolean)
+    .join("/")
 }
 
 export default function TagInput({
@@ -20,6 +28,7 @@ export default function TagInput({
   suggestions = [],
   disabled = false,
   placeholder = "Add tag...",
+  warnings = [],
 }: TagInputProps) {
   const [inputValue, setInputValue] = useState("")
   const [showSuggestions, setShowSuggestions] = useState(false)
@@ -100,24 +109,32 @@ export default function TagInput({
         className="border-input flex min-h-9 flex-wrap items-center gap-1 rounded-md border px-2 py-1 focus-within:border-ring focus-within:ring-ring/50 focus-within:ring-[3px]"
         onClick={() => inputRef.current?.focus()}
       >
-        {tags.map((tag) => (
-          <Badge key={tag} variant="secondary" className="gap-1 text-xs">
-            {tag}
-            {!disabled && (
-              <button
-                type="button"
-                onClick={(e) => {
-                  e.stopPropagation()
-                  removeTag(tag)
-                }}
-                className="hover:text-destructive ml-0.5"
-                aria-label={`Remove ${tag}`}
-              >
-                <X className="size-3" />
-              </button>
-            )}
-          </Badge>
-        ))}
+        {tags.map((tag) => {
+          const flagged = warnings.filter((w) => w.value === tag)
+          return (
+            <Badge
+              key={tag}
+              variant="secondary"
+              className={`gap-1 text-xs ${flagged.length > 0 ? "text-amber-600 dark:text-amber-400" : ""}`}
+              title={flagged.length > 0 ? flagged.map((w) => w.message).join("\n") : undefined}
+            >
+              {tag}
+              {!disabled && (
+                <button
+                  type="button"
+                  onClick={(e) => {
+                    e.stopPropagation()
+                    removeTag(tag)
+                  }}
+                  className="hover:text-destructive ml-0.5"
+                  aria-label={`Remove ${tag}`}
+                >
+                  <X className="size-3" />
+                </button>
+              )}
+            </Badge>
+          )
+        })}
         <input
           ref={inputRef}
           type="text"
@@ -159,6 +176,13 @@ export default function TagInput({
           ))}
         </div>
       )}
+      {warnings.length > 0 && (
+        <ul data-testid="tag-warnings" className="mt-1 flex flex-col gap-0.5 text-xs text-amber-600 dark:text-amber-400">
+          {warnings.map((w) => (
+            <li key={`${w.value}:${w.message}`}>{w.value}: {w.message}</li>
+          ))}
+        </ul>
+      )}
     </div>
   )
 }
@@ -0,0 +1,244 @@
+import { useEffect, useState } from "react"
+import { toast } from "sonner"
+import { ArrowRight, Loader2, Pencil, X } from "lucide-react"
+import { Badge } from "@/components/ui/badge"
+import { Button } from "@/components/ui/button"
+import { Checkbox } from "@/components/ui/checkbox"
+import { Input } from "@/components/ui/input"
+import { Label } from "@/components/ui/label"
+import { getTagTree, mergeTags, removeTagAlias, renameTag } from "@/lib/tauri"
+import type { TagNode, TagTree } from "@/lib/types"
+
+const errorMessage = (err: unknown) => (err instanceof Error ? err.message : String(err))
+
+function TagRow({
+  node,
+  depth,
+  selected,
+  busy,
+  onToggle,
+  onRename,
+}: {
+  node: TagNode
+  depth: number
+  selected: string[]
+  busy: boolean
+  onToggle: (path: string, checked: boolean) => void
+  onRename: (from: string, to: string) => Promise<boolean>
+}) {
+  const [editing, setEditing] = useState(false)
+  const [name, setName] = useState(node.path)
+
+  const save = async () => {
+    if (await onRename(node.path, name)) setEditing(false)
+  }
+
+  return (
+    <>
+      <div className="flex items-center gap-2 py-0.5" style={{ paddingLeft: depth * 20 }} data-testid="tag-node">
+        <Checkbox
+          aria-label={`Select ${node.path}`}
+          checked={selected.includes(node.path)}
+          onCheckedChange={(checked) => onToggle(node.path, checked === true)}
+        />
+        {editing ? (
+          <>
+            <Input
+              aria-label={`New name for ${node.path}`}
+              className="h-7 w-64"
+              value={name}
+              onChange={(e) => setName(e.target.value)}
+              onKeyDown={(e) => e.key === "Enter" && save()}
+            />
+            <Button size="sm" variant="outline" disabled={busy || !name.trim()} onClick={save}>
+              Save
+            </Button>
+            <Button size="sm" variant="ghost" onClick={() => setEditing(false)}>
+              Cancel
+            </Button>
+          </>
+        ) : (
+          <>
+            <span className="text-sm">{node.name}</span>
+            <Badge variant="secondary" className="text-xs" title="Skills tagged with this tag or a tag under it">
+              {node.skill_count}
+            </Badge>
+            <Button
+              size="icon"
+              variant="ghost"
+              className="size-6"
+              aria-label={`Rename ${node.path}`}
+              onClick={() => {
+                setName(node.path)
+                setEditing(true)
+              }}
+            >
+              <Pencil className="size-3" />
+            </Button>
+          </>
+        )}
+      </div>
+      {node.children.map((child) => (
+        <TagRow
+          key={child.path}
+          node={child}
+          depth={depth + 1}
+          selected={selected}
+          busy={busy}
+          onToggle={onToggle}
+          onRename={onRename}
+        />
+      ))}
+    </>
+  )
+}
+
+/** Curates the tag library: rename and merge hierarchical tags, and manage the aliases they leave behind. */
+export function TagTaxonomySettings() {
+  const [tree, setTree] = useState<TagTree | null>(null)
+  const [selected, setSelected] = useState<string[]>([])
+  const [mergeTarget, setMergeTarget] = useState("")
+  const [alias, setAlias] = useState("")
+  const [aliasTarget, setAliasTarget] = useState("")
+  const [busy, setBusy] = useState(false)
+
+  useEffect(() => {
+    getTagTree()
+      .then(setTree)
+      .catch((err) => {
+        console.warn("[tag-taxonomy-settings] get_tag_tree failed:", err)
+        setTree({ tags: [], aliases: [], warnings: [] })
+      })
+  }, [])
+
+  if (!tree) {
+    return <Loader2 className="size-4 animate-spin text-muted-foreground" />
+  }
+
+  const run = async (action: () => Promise<TagTree>, failure: string) => {
+    setBusy(true)
+    try {
+      const next = await action()
+      setTree(next)
+      if (next.warnings.length > 0) {
+        const tags = [...new Set(next.warnings.map((w) => w.value))].join(", ")
+        toast.warning(`Not in the team taxonomy: ${tags}`, { duration: 5000 })
+      }
+      return true
+    } catch (err) {
+      toast.error(`${failure}: ${errorMessage(err)}`, { duration: Infinity })
+      return false
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  const toggle = (path: string, checked: boolean) =>
+    setSelected((current) => (checked ? [...current, path] : current.filter((p) => p !== path)))
+
+  const merge = async () => {
+    if (await run(() => mergeTags(selected, mergeTarget), "Failed to merge tags")) {
+      toast.success(`Merged ${selected.length} tag${selected.length === 1 ? "" : "s"} into ${mergeTarget.trim()}`, {
+        duration: 1500,
+      })
+      setSelected([])
+      setMergeTarget("")
+    }
+  }
+
+  const addAlias = async () => {
+    if (await run(() => mergeTags([alias], aliasTarget), "Failed to add alias")) {
+      setAlias("")
+      setAliasTarget("")
+    }
+  }
+
+  return (
+    <div className="flex flex-col gap-6">
+      {tree.tags.length === 0 ? (
+        <p className="text-sm text-muted-foreground">No tags yet.</p>
+      ) : (
+        <div className="flex flex-col">
+          {tree.tags.map((node) => (
+            <TagRow
+              key={node.path}
+              node={node}
+              depth={0}
+              selected={selected}
+              busy={busy}
+              onToggle={toggle}
+              onRename={(from, to) => run(() => renameTag(from, to), `Failed to rename ${from}`)}
+            />
+          ))}
+        </div>
+      )}
+
+      {selected.length > 0 && (
+        <div className="flex items-end gap-2">
+          <div className="grid gap-1">
+            <Label htmlFor="tag-merge-target">Merge {selected.length} selected into</Label>
+            <Input
+              id="tag-merge-target"
+              className="w-64"
+              placeholder="e.g. finance/revenue"
+              value={mergeTarget}
+              onChange={(e) => setMergeTarget(e.target.value)}
+            />
+          </div>
+          <Button variant="outline" size="sm" disabled={busy || !mergeTarget.trim()} onClick={merge}>
+            Merge
+          </Button>
+        </div>
+      )}
+
+      <div className="flex flex-col gap-2">
+        <span className="text-sm font-medium">Aliases</span>
+        <p className="text-xs text-muted-foreground">
+          Tags saved with an alias, or a tag under it, are stored under the canonical tag instead.
+        </p>
+        {tree.aliases.map(({ alias: name, tag }) => (
+          <div key={name} className="flex items-center gap-2 text-sm" data-testid="tag-alias">
+            <span className="font-mono">{name}</span>
+            <ArrowRight className="size-3 text-muted-foreground" />
+            <span className="font-mono">{tag}</span>
+            <Button
+              size="icon"
+              variant="ghost"
+              className="size-6"
+              aria-label={`Remove alias ${name}`}
+              disabled={busy}
+              onClick={() => run(() => removeTagAlias(name), `Failed to remove alias ${name}`)}
+            >
+              <X className="size-3" />
+            </Button>
+          </div>
+        ))}
+        <div className="flex items-center gap-2">
+          <Input
+            aria-label="Alias"
+            className="w-40"
+            placeholder="sfdc"
+            value={alias}
+            onChange={(e) => setAlias(e.target.value)}
+          />
+          <ArrowRight className="size-3 text-muted-foreground" />
+          <Input
+            aria-label="Canonical tag"
+            className="w-40"
+            placeholder="salesforce"
+            value={aliasTarget}
+            onChange={(e) => setAliasTarget(e.target.value)}
+          />
+          <Button
+            variant="outline"
+            size="sm"
+            disabled={busy || !alias.trim() || !aliasTarget.trim()}
+            onClick={addAlias}
+          >
+            Add alias
+          </Button>
+        </div>
+      </div>
+    </div>
+  )
+}
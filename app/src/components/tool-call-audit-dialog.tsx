@@ -0,0 +1,65 @@
+import { useEffect, useState } from "react"
+import { Loader2, ShieldAlert } from "lucide-react"
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog"
+import { getAgentToolCalls } from "@/lib/tauri"
+import type { AgentToolCall } from "@/lib/types"
+
+interface ToolCallAuditDialogProps {
+  agentId: string | null
+  onOpenChange: (open: boolean) => void
+}
+
+/** Lists the tool calls one agent run made, flagging calls outside its allowed tools. */
+export function ToolCallAuditDialog({ agentId, onOpenChange }: ToolCallAuditDialogProps) {
+  const [calls, setCalls] = useState<AgentToolCall[] | null>(null)
+
+  useEffect(() => {
+    if (!agentId) return
+    setCalls(null)
+    getAgentToolCalls(agentId)
+      .then(setCalls)
+      .catch((err) => {
+        console.warn("[tool-call-audit-dialog] get_agent_tool_calls failed:", err)
+        setCalls([])
+      })
+  }, [agentId])
+
+  return (
+    <Dialog open={agentId !== null} onOpenChange={onOpenChange}>
+      <DialogContent className="max-w-2xl">
+        <DialogHeader>
+          <DialogTitle>Tool calls</DialogTitle>
+          <DialogDescription className="font-mono text-xs">{agentId}</DialogDescription>
+        </DialogHeader>
+        {calls === null ? (
+          <Loader2 className="size-4 animate-spin text-muted-foreground" />
+        ) : calls.length === 0 ? (
+          <p className="text-sm text-muted-foreground">No tool calls were recorded for this run.</p>
+        ) : (
+          <ul className="max-h-96 overflow-y-auto divide-y text-xs">
+            {calls.map((call) => (
+              <li key={call.id} className="flex items-center gap-3 py-1.5">
+                <span className="w-20 shrink-0 font-medium">{call.tool_name}</span>
+                <span className="flex-1 truncate font-mono text-muted-foreground" title={call.input_summary}>
+                  {call.input_summary}
+                </span>
+                {call.allowed === false && (
+                  <span className="flex items-center gap-1 text-destructive">
+                    <ShieldAlert className="size-3.5" />
+                    Not allowed
+                  </span>
+                )}
+              </li>
+            ))}
+          </ul>
+        )}
+      </DialogContent>
+    </Dialog>
+  )
+}
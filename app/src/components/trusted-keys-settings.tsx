@@ -0,0 +1,120 @@
+import { useEffect, useState } from "react"
+import { toast } from "sonner"
+import { Copy, Loader2, Plus, Trash2 } from "lucide-react"
+import { Button } from "@/components/ui/button"
+import { Input } from "@/components/ui/input"
+import { Label } from "@/components/ui/label"
+import { addTrustedKey, getSigningPublicKey, listTrustedKeys, removeTrustedKey } from "@/lib/tauri"
+import type { SigningKeyInfo, TrustedKey } from "@/lib/types"
+
+/** Manages the public keys trusted for package verification, and shows this installation's own key to share. */
+export function TrustedKeysSettings() {
+  const [keys, setKeys] = useState<TrustedKey[] | null>(null)
+  const [ownKey, setOwnKey] = useState<SigningKeyInfo | null>(null)
+  const [name, setName] = useState("")
+  const [publicKey, setPublicKey] = useState("")
+  const [busy, setBusy] = useState(false)
+
+  useEffect(() => {
+    listTrustedKeys()
+      .then(setKeys)
+      .catch((err) => {
+        console.warn("[trusted-keys-settings] list_trusted_keys failed:", err)
+        setKeys([])
+      })
+  }, [])
+
+  if (!keys) {
+    return <Loader2 className="size-4 animate-spin text-muted-foreground" />
+  }
+
+  const add = async () => {
+    setBusy(true)
+    try {
+      const key = await addTrustedKey(name, publicKey)
+      setKeys([...keys.filter((k) => k.key_id !== key.key_id), key])
+      setName("")
+      setPublicKey("")
+    } catch (err) {
+      toast.error(`Failed to trust key: ${err instanceof Error ? err.message : String(err)}`, { duration: Infinity })
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  const remove = async (key: TrustedKey) => {
+    try {
+      await removeTrustedKey(key.key_id)
+      setKeys(keys.filter((k) => k.key_id !== key.key_id))
+    } catch (err) {
+      toast.error(`Failed to remove key: ${err instanceof Error ? err.message : String(err)}`, { duration: Infinity })
+    }
+  }
+
+  // Fetching the key creates it on first use, and packages are signed once
+  // a key exists, so it is only fetched on request.
+  const showOwnKey = async () => {
+    try {
+      setOwnKey(await getSigningPublicKey())
+    } catch (err) {
+      toast.error(`Failed to load signing key: ${err instanceof Error ? err.message : String(err)}`, { duration: Infinity })
+    }
+  }
+
+  return (
+    <div className="flex flex-col gap-4">
+      {keys.length === 0 ? (
+        <p className="text-sm text-muted-foreground">No trusted keys yet.</p>
+      ) : (
+        <ul className="flex flex-col gap-2">
+          {keys.map((key) => (
+            <li key={key.key_id} className="flex items-center gap-3" data-testid="trusted-key">
+              <span className="text-sm font-medium">{key.name}</span>
+              <code className="text-xs text-muted-foreground">{key.key_id}</code>
+              <Button variant="ghost" size="icon" aria-label={`Remove ${key.name}`} onClick={() => remove(key)}>
+                <Trash2 className="size-4" />
+              </Button>
+            </li>
+          ))}
+        </ul>
+      )}
+      <div className="flex flex-wrap items-end gap-2">
+        <div className="grid gap-1">
+          <Label htmlFor="trusted-key-name">Name</Label>
+          <Input id="trusted-key-name" placeholder="Alice" value={name} onChange={(e) => setName(e.target.value)} />
+        </div>
+        <div className="grid flex-1 gap-1">
+          <Label htmlFor="trusted-key-public-key">Public key</Label>
+          <Input
+            id="trusted-key-public-key"
+            placeholder="Base64 public key"
+            value={publicKey}
+            onChange={(e) => setPublicKey(e.target.value)}
+          />
+        </div>
+        <Button variant="outline" size="sm" disabled={busy || !name.trim() || !publicKey.trim()} onClick={add}>
+          {busy ? <Loader2 className="size-4 animate-spin" /> : <Plus className="size-4" />}
+          Trust key
+        </Button>
+      </div>
+      {ownKey ? (
+        <div className="flex items-center gap-2">
+          <span className="text-sm">Your public key</span>
+          <code className="min-w-0 flex-1 truncate text-xs" data-testid="own-public-key">{ownKey.public_key}</code>
+          <Button
+            variant="ghost"
+            size="icon"
+            aria-label="Copy public key"
+            onClick={() => navigator.clipboard.writeText(ownKey.public_key)}
+          >
+            <Copy className="size-4" />
+          </Button>
+        </div>
+      ) : (
+        <Button variant="link" size="sm" className="w-fit px-0" onClick={showOwnKey}>
+          Show my public key
+        </Button>
+      )}
+    </div>
+  )
+}
@@ -5,6 +5,7 @@ import {
   Loader2,
   AlertCircle,
   SkipForward,
+  CircleDollarSign,
 } from "lucide-react";
 import { cn } from "@/lib/utils";
 import type { WorkflowStep } from "@/stores/workflow-store";
@@ -22,6 +23,8 @@ function StepStatusIcon({ status, isDisabled }: { status: WorkflowStep["status"]
       return <Clock className="size-4" style={{ color: "var(--color-pacific)" }} />;
     case "error":
       return <AlertCircle className="size-4 text-destructive" />;
+    case "budget_exceeded":
+      return <CircleDollarSign className="size-4 text-destructive" />;
     default:
       return <Circle className="size-4 text-muted-foreground" />;
   }
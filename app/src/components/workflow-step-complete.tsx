@@ -17,6 +17,7 @@ import { AgentStatsBar } from "@/components/agent-stats-bar";
 import { ClarificationsEditor } from "@/components/clarifications-editor";
 import { ResearchSummaryCard } from "@/components/research-summary-card";
 import { DecisionsSummaryCard } from "@/components/decisions-summary-card";
+import { ArtifactAnnotationsPanel } from "@/components/artifact-annotations-panel";
 import { type ClarificationsFile, parseClarifications } from "@/lib/clarifications-types";
 import type { AgentRunRecord } from "@/lib/types";
 import { formatElapsed } from "@/lib/utils";
@@ -254,6 +255,12 @@ export function WorkflowStepComplete({
     );
   }
 
+  /** Reviewer annotations on a context file, shown only while reviewing a completed step. */
+  const renderAnnotations = (file: string, content: string | undefined) =>
+    reviewMode && skillName && workspacePath && content && content !== "__NOT_FOUND__" ? (
+      <ArtifactAnnotationsPanel skillName={skillName} workspacePath={workspacePath} file={file} content={content} />
+    ) : null;
+
   // Step 0 (Research) now uses a single canonical artifact: clarifications.json
   const researchPlanContent = fileContents.get("context/research-plan.md");
   const clarificationsContent = fileContents.get("context/clarifications.json");
@@ -340,6 +347,7 @@ export function WorkflowStepComplete({
                 />
               </div>
             </ScrollArea>
+            {renderAnnotations("context/clarifications.json", clarificationsContent)}
             <StepActionBar
               isLastStep={isLastStep}
               reviewMode={reviewMode}
@@ -388,6 +396,7 @@ export function WorkflowStepComplete({
               <div className="rounded-lg border shadow-sm min-h-0 flex-1" style={{ height: "min(600px, 60vh)" }}>
                 <ClarificationsEditor data={clarOnlyData} onChange={() => {}} readOnly />
               </div>
+              {renderAnnotations("context/clarifications.json", clarificationsContent)}
               <StepActionBar
                 isLastStep={isLastStep}
                 reviewMode={reviewMode}
@@ -454,6 +463,7 @@ export function WorkflowStepComplete({
             />
           </div>
         </ScrollArea>
+        {renderAnnotations("context/decisions.json", decisionsContent)}
         {!reviewMode && decisionsSaveStatus !== "idle" && (
           <div className="flex justify-start">
             <span className="text-xs text-muted-foreground">
@@ -25,8 +25,8 @@ import { useSettingsStore } from "@/stores/settings-store"
 import GitHubImportDialog from "@/components/github-import-dialog"
 import { ImportSkillDialog } from "@/components/import-skill-dialog"
 import type { ImportConfirmParams } from "@/components/import-skill-dialog"
-import { parseSkillFile } from "@/lib/tauri"
-import type { SkillFileMeta } from "@/lib/types"
+import { getSkillScanReport, parseSkillFile } from "@/lib/tauri"
+import type { SkillFileMeta, SkillScanReport } from "@/lib/types"
 import { PURPOSE_OPTIONS } from "@/lib/types"
 
 export function WorkspaceSkillsTab() {
@@ -42,6 +42,7 @@ export function WorkspaceSkillsTab() {
 
   const marketplaceRegistries = useSettingsStore((s) => s.marketplaceRegistries)
   const hasEnabledRegistry = marketplaceRegistries.some(r => r.enabled)
+  const marketplaceEnabled = useSettingsStore((s) => s.marketplaceEnabled)
   const pendingUpgrade = useSettingsStore((s) => s.pendingUpgradeOpen)
   const [showGitHubImport, setShowGitHubImport] = useState(false)
   const [workspaceImportOpen, setWorkspaceImportOpen] = useState(false)
@@ -51,10 +52,35 @@ export function WorkspaceSkillsTab() {
     argument_hint: null, user_invocable: null, disable_model_invocation: null,
   })
 
+  const [scanReports, setScanReports] = useState<Record<string, SkillScanReport>>({})
+
   useEffect(() => {
     fetchSkills()
   }, [fetchSkills])
 
+  useEffect(() => {
+    let cancelled = false
+    const imported = skills.filter((s) => !s.is_bundled)
+    Promise.all(
+      imported.map((s) =>
+        getSkillScanReport(s.skill_name).catch((err) => {
+          console.warn("[workspace-skills-tab] get_skill_scan_report failed:", err)
+          return null
+        })
+      )
+    ).then((reports) => {
+      if (cancelled) return
+      const flagged: Record<string, SkillScanReport> = {}
+      for (const report of reports) {
+        if (report?.flagged) flagged[report.skill_name] = report
+      }
+      setScanReports(flagged)
+    })
+    return () => {
+      cancelled = true
+    }
+  }, [skills])
+
   useEffect(() => {
     if (pendingUpgrade?.mode === "workspace-skills") {
       setShowGitHubImport(true)
@@ -150,8 +176,12 @@ export function WorkspaceSkillsTab() {
           variant="outline"
           className="w-36"
           onClick={() => setShowGitHubImport(true)}
-          disabled={!hasEnabledRegistry}
-          title={!hasEnabledRegistry ? "Enable a marketplace registry in Settings → Marketplace" : undefined}
+          disabled={!marketplaceEnabled || !hasEnabledRegistry}
+          title={
+            !marketplaceEnabled
+              ? "Marketplace is turned off in Settings → Skill Building → Network Access"
+              : !hasEnabledRegistry ? "Enable a marketplace registry in Settings → Marketplace" : undefined
+          }
         >
           <Github className="size-4" />
           Marketplace
@@ -204,6 +234,17 @@ export function WorkspaceSkillsTab() {
                   {skill.is_bundled && (
                     <Badge variant="secondary" className="text-xs">Built-in</Badge>
                   )}
+                  {scanReports[skill.skill_name] && (
+                    <Badge
+                      variant="destructive"
+                      className="text-xs"
+                      title={scanReports[skill.skill_name].findings
+                        .map((f) => `${f.file}:${f.line} ${f.rule} — ${f.excerpt}`)
+                        .join("\n")}
+                    >
+                      {scanReports[skill.skill_name].quarantined ? "Quarantined" : "Flagged"}
+                    </Badge>
+                  )}
                 </div>
                 {skill.description && (
                   <div className="text-xs text-muted-foreground">{skill.description}</div>
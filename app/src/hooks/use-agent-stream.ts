@@ -22,12 +22,49 @@ interface AgentExitPayload {
   success: boolean;
 }
 
+interface AgentCostTickPayload {
+  agent_id: string;
+  input_tokens: number;
+  output_tokens: number;
+  cache_read_tokens: number;
+  cache_write_tokens: number;
+  total_cost: number;
+  estimated: boolean;
+}
+
+interface WorkflowStepProgressPayload {
+  agent_id: string;
+  turns_completed: number;
+  max_turns: number | null;
+  current_tool: string | null;
+  tokens_used: number;
+  percent: number | null;
+  eta_ms: number | null;
+}
+
 interface AgentInitProgressPayload {
   agent_id: string;
   subtype: string;
   timestamp: number;
 }
 
+interface AgentRetryPayload {
+  agent_id: string;
+  attempt: number;
+  max_retries: number;
+  error_class: string;
+  message: string;
+  delay_ms: number;
+  resumed: boolean;
+}
+
+/** User-facing names for the transient error classes the backend retries. */
+const RETRY_ERROR_LABELS: Record<string, string> = {
+  rate_limit: "API rate limit reached",
+  overloaded: "API overloaded",
+  network: "Network error",
+};
+
 interface AgentInitErrorPayload {
   error_type: string;
   message: string;
@@ -108,6 +145,43 @@ export function initAgentStream() {
     });
   });
 
+  listen<AgentCostTickPayload>("agent-cost-tick", (event) => {
+    const tick = event.payload;
+    useAgentStore.getState().recordCostTick(tick.agent_id, {
+      inputTokens: tick.input_tokens,
+      outputTokens: tick.output_tokens,
+      cacheReadTokens: tick.cache_read_tokens,
+      cacheWriteTokens: tick.cache_write_tokens,
+      totalCost: tick.total_cost,
+      estimated: tick.estimated,
+    });
+  });
+
+  listen<WorkflowStepProgressPayload>("workflow-step-progress", (event) => {
+    const progress = event.payload;
+    useAgentStore.getState().recordStepProgress(progress.agent_id, {
+      turnsCompleted: progress.turns_completed,
+      maxTurns: progress.max_turns,
+      currentTool: progress.current_tool,
+      tokensUsed: progress.tokens_used,
+      percent: progress.percent,
+      etaMs: progress.eta_ms,
+      receivedAt: Date.now(),
+    });
+  });
+
+  listen<AgentRetryPayload>("agent-retry", (event) => {
+    const { agent_id, attempt, max_retries, error_class, delay_ms } = event.payload;
+    const label = RETRY_ERROR_LABELS[error_class] ?? "Temporary error";
+    const seconds = Math.round(delay_ms / 1000);
+    useAgentStore.getState().addMessage(agent_id, {
+      type: "error",
+      content: `${label} — retrying in ${seconds}s (attempt ${attempt} of ${max_retries})`,
+      raw: event.payload as unknown as Record<string, unknown>,
+      timestamp: Date.now(),
+    });
+  });
+
   listen<AgentExitPayload>("agent-exit", (event) => {
     useAgentStore.getState().completeRun(
       event.payload.agent_id,
@@ -0,0 +1,42 @@
+import { listen, type UnlistenFn } from "@tauri-apps/api/event"
+import type { SkillSummary, WorkflowQueueJob } from "@/lib/types"
+
+/** Channel the backend emits database changes on (see `db_events.rs`). */
+export const DB_CHANGE_EVENT = "db-change"
+
+/** Skill fields carried by `skill.updated`, named as in `SkillSummary`. */
+export type SkillChange = Pick<
+  SkillSummary,
+  | "name"
+  | "current_step"
+  | "status"
+  | "last_modified"
+  | "intake_json"
+  | "author_login"
+  | "author_avatar"
+  | "tags"
+>
+
+export type DbChangeEvent =
+  | { kind: "skill.updated"; skill: SkillChange }
+  | { kind: "skill.deleted"; skill_name: string }
+  | { kind: "lock.acquired"; skill_name: string; instance_id: string; pid: number }
+  | { kind: "lock.released"; skill_name: string; instance_id: string }
+  | {
+      kind: "usage.recorded"
+      agent_id: string
+      skill_name: string
+      step_id: number
+      model: string
+      status: string
+      input_tokens: number
+      output_tokens: number
+      total_cost: number
+    }
+  | { kind: "session.ended"; session_id: string }
+  | { kind: "queue.updated"; job: WorkflowQueueJob }
+
+/** Subscribe to database change events. Resolves to the unsubscribe function. */
+export function onDbChange(handler: (change: DbChangeEvent) => void): Promise<UnlistenFn> {
+  return listen<DbChangeEvent>(DB_CHANGE_EVENT, (event) => handler(event.payload))
+}
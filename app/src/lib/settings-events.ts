@@ -0,0 +1,14 @@
+import { listen, type UnlistenFn } from "@tauri-apps/api/event"
+
+/** Channel the backend emits settings changes on (see `settings_events.rs`). */
+export const SETTINGS_CHANGED_EVENT = "settings-changed"
+
+/** Names of the `AppSettings` fields a write changed. Values are not included. */
+export interface SettingsChangeEvent {
+  changed: string[]
+}
+
+/** Subscribe to settings changes. Resolves to the unsubscribe function. */
+export function onSettingsChange(handler: (change: SettingsChangeEvent) => void): Promise<UnlistenFn> {
+  return listen<SettingsChangeEvent>(SETTINGS_CHANGED_EVENT, (event) => handler(event.payload))
+}
@@ -0,0 +1,28 @@
+import { listen, type UnlistenFn } from "@tauri-apps/api/event"
+import type { SimilarSkill } from "@/lib/types"
+
+/** Channel the backend emits external skill folder changes on (see `skill_watcher.rs`). */
+export const SKILLS_CHANGED_EVENT = "skills-changed"
+
+/** Library changes made to match edits under the skills path outside the app. */
+export interface SkillsChangedEvent {
+  updated: string[]
+  removed: string[]
+  /** `[old, new]` names. */
+  renamed: [string, string][]
+  /** Skill-builder skills whose SKILL.md is gone; kept until the next startup reconciliation. */
+  missing: string[]
+}
+
+/** Subscribe to external skill changes. Resolves to the unsubscribe function. */
+export function onSkillsChanged(handler: (change: SkillsChangedEvent) => void): Promise<UnlistenFn> {
+  return listen<SkillsChangedEvent>(SKILLS_CHANGED_EVENT, (event) => handler(event.payload))
+}
+
+/** Channel imports report near-duplicates of the skills they installed on (see `skill_similarity.rs`). */
+export const SKILL_NEAR_DUPLICATES_EVENT = "skill-near-duplicates"
+
+/** Subscribe to near-duplicate warnings from imports. Resolves to the unsubscribe function. */
+export function onSkillNearDuplicates(handler: (matches: SimilarSkill[]) => void): Promise<UnlistenFn> {
+  return listen<SimilarSkill[]>(SKILL_NEAR_DUPLICATES_EVENT, (event) => handler(event.payload))
+}
@@ -1,5 +1,5 @@
-import { invoke } from "@tauri-apps/api/core";
-import type { AppSettings, PackageResult, ReconciliationResult, DeviceFlowResponse, GitHubAuthResult, GitHubUser, AgentRunRecord, WorkflowSessionRecord, UsageSummary, UsageByStep, UsageByModel, UsageByDay, ImportedSkill, WorkspaceSkill, GitHubRepoInfo, AvailableSkill, SkillFileContent, SkillSummary, RefineDiff, RefineSessionInfo, MarketplaceImportResult, MarketplaceUpdateResult, SkillMetadataOverride, SkillFileMeta } from "@/lib/types";
+import { invoke as tauriInvoke } from "@tauri-apps/api/core";
+import type { AppSettings, ErrorHelp, AuditLogEntry, AuditLogExport, AuditLogFilter, AuditLogFormat, RefineBatch, UserDataPurgeResult, TagTree, TaxonomyWarning, SkillReference, EffectiveModel, SettingsSyncDocument, SettingsSyncResult, PackageResult, PackageFormat, SkillTranslation, DbContention, DiagnosticsReport, HookDelivery, PromptReload, NetworkFeatures, DependencyPackagingMode, SkillDependencyGraph, PathCheck, PathKind, StepArtifactIndex, ReconciliationResult, DeviceFlowResponse, GitHubAuthResult, GitHubUser, AgentRunRecord, WorkflowSessionRecord, UsageSummary, UsageByStep, UsageByModel, UsageByDay, SkillAnalytics, SkillInvocationImport, SkillInvocationStats, ImportedSkill, WorkspaceSkill, GitHubRepoInfo, AvailableSkill, SkillFileContent, SkillSummary, SkillGroup, SkillGrouping, ScrubbedAttachment, RefineDiff, StructuredFileDiff, SkillVersionComparison, RefineSessionInfo, ResumableRefineSession, ResumedRefineSession, MarketplaceImportResult, MarketplaceUpdateResult, SkillMetadataOverride, SkillFileMeta, OnboardingChecklist, LibrarySnapshot, ActivationDecision, CommandHistoryEntry, CommandHistoryFilter, CommandRerun, ContractUpgrade, SkillBenchmark, SkillTestCase, SkillTestResult, SkillTestRun, StepExplanation, FrontmatterTransform, FrontmatterMigrationPreview, FrontmatterMigrationResult, BulkSkillOperation, BulkSkillResult, BackgroundQueue, BackgroundTask, BackgroundTaskKind, TaskPriority, LinkedReference, LinkedReferenceRefresh, ApprovedReferenceUpdate, DuplicateSkillOptions, DuplicateSkillResult, SkillArchiveExportResult, ProjectContextPackage, SkillArchiveImportResult, StepPerfTrend, StepReplay, StepCostEstimate, ToolPolicy, ToolPolicyView, AgentToolCall, AgentTranscript, TranscriptInfo, TranscriptSearchHit, TranscriptTurns, SkillImpactReport, SimilarSkill, SkillLintReport, SkillValidationReport, SkillPreview, SkillScanReport, SkillTemplate, RegenerationMode, IntakeAnswer, IntakeQuestionnaire, IntakeSubmission, AccessibilityFixResult, SkillReviewStatus, WorkflowQueueJob, WorkflowStepConfig, QualityCheck, QualityGateProfile, QualityGateReport, QualityWaiver, SkillChangelogEntry, SkillSlotName, SkillSlotsStatus, ArtifactAnnotation, AgentRunCheckpoint, CachedStepRun, WorkspaceSnapshot, RepoContextResult, BudgetStatus, GitHost, GitHostAccount, HostedRepoSummary, HostedSkillPushResult, PushStrategy, SkillUpstreamReview, SigningKeyInfo, TrustedKey } from "@/lib/types";
 
 // Re-export shared types so existing imports from "@/lib/tauri" continue to work
 export type { AppSettings, SkillSummary, NodeStatus, PackageResult, ReconciliationResult, DeviceFlowResponse, GitHubAuthResult, GitHubUser, AgentRunRecord, WorkflowSessionRecord, UsageSummary, UsageByStep, UsageByModel, UsageByDay, ImportedSkill, WorkspaceSkill, GitHubRepoInfo, AvailableSkill, SkillFileContent, RefineDiff, RefineSessionInfo, MarketplaceImportResult, MarketplaceUpdateResult, SkillMetadataOverride, SkillUpdateInfo, SkillFileMeta } from "@/lib/types";
@@ -11,6 +11,64 @@ export interface WorkspaceSkillImportRequest {
   version: string | null;
 }
 
+// --- Command history ---
+
+// Read-only queries are not recorded; mirrors `is_recorded` in commands/command_history.rs.
+const UNRECORDED_PREFIXES = ["get_", "list_", "check_", "read_", "parse_", "verify_", "has_", "is_"];
+const UNRECORDED_COMMANDS = new Set(["record_command", "rerun_command"]);
+
+let commandHistoryEnabled = false;
+
+/** Start recording invoked commands to the command history. Called once at app startup. */
+export const enableCommandHistory = () => {
+  commandHistoryEnabled = true;
+};
+
+const isRecorded = (command: string) =>
+  !UNRECORDED_COMMANDS.has(command) && !UNRECORDED_PREFIXES.some((p) => command.startsWith(p));
+
+const recordCommand = (command: string, args: Record<string, unknown> | undefined, started: number, error?: unknown) => {
+  // Fire-and-forget: history is best-effort and must never fail the original call.
+  Promise.resolve()
+    .then(() =>
+      tauriInvoke("record_command", {
+        command,
+        params: args ?? null,
+        outcome: error === undefined ? "ok" : "error",
+        error: error === undefined ? null : String(error),
+        durationMs: Math.max(0, Math.round(performance.now() - started)),
+      }),
+    )
+    .catch(() => {});
+};
+
+/** `invoke` that records mutating commands (secrets are redacted by the backend). */
+function invoke<T>(command: string, args?: Record<string, unknown>): Promise<T> {
+  if (!commandHistoryEnabled || !isRecorded(command)) {
+    return tauriInvoke<T>(command, args);
+  }
+  const started = performance.now();
+  return tauriInvoke<T>(command, args).then(
+    (result) => {
+      recordCommand(command, args, started);
+      return result;
+    },
+    (err: unknown) => {
+      recordCommand(command, args, started, err);
+      throw err;
+    },
+  );
+}
+
+export const listCommandHistory = (filter?: CommandHistoryFilter) =>
+  invoke<CommandHistoryEntry[]>("list_command_history", { filter: filter ?? null });
+
+/** Re-run a recorded command with its stored arguments; the re-run is recorded as a new entry. */
+export const rerunCommand = async (historyId: number) => {
+  const rerun = await invoke<CommandRerun>("rerun_command", { historyId });
+  return invoke<unknown>(rerun.command, rerun.params as Record<string, unknown>);
+};
+
 // --- Settings ---
 
 export const getSettings = () => invoke<AppSettings>("get_settings");
@@ -23,6 +81,9 @@ export const testApiKey = (apiKey: string) =>
 
 export const getDataDir = () => invoke<string>("get_data_dir");
 
+/** Remediation steps and a settings link for a failed command's error string. */
+export const getErrorHelp = (error: string) => invoke<ErrorHelp>("get_error_help", { error });
+
 export const getDefaultSkillsPath = () => invoke<string>("get_default_skills_path");
 
 // --- Skills ---
@@ -30,8 +91,63 @@ export const getDefaultSkillsPath = () => invoke<string>("get_default_skills_pat
 export const deleteSkill = (workspacePath: string, name: string) =>
   invoke("delete_skill", { workspacePath, name });
 
+export const listSkillTemplates = (skillType?: string) =>
+  invoke<SkillTemplate[]>("list_skill_templates", { skillType: skillType ?? null });
+
+/** Create a skill pre-filled from a starter template (purpose, description, intake and skeleton SKILL.md). */
+export const createSkillFromTemplate = (
+  workspacePath: string,
+  name: string,
+  templateId: string,
+  tags?: string[],
+) => invoke("create_skill_from_template", { workspacePath, name, templateId, tags: tags ?? null });
+
+/** Save a skill's tags; returns the team taxonomy's warnings for them. */
 export const updateSkillTags = (skillName: string, tags: string[]) =>
-  invoke("update_skill_tags", { skillName, tags });
+  invoke<TaxonomyWarning[]>("update_skill_tags", { skillName, tags });
+
+/** Team taxonomy warnings for tags being edited, before they are saved. */
+export const checkSkillTags = (skillName: string, tags: string[]) =>
+  invoke<TaxonomyWarning[]>("check_skill_tags", { skillName, tags });
+
+export const getTagTree = () => invoke<TagTree>("get_tag_tree");
+
+/** Rename a tag and the tags under it; the old name becomes an alias. */
+export const renameTag = (from: string, to: string) => invoke<TagTree>("rename_tag", { from, to });
+
+/** Fold `sources` into `target`, keeping each source as an alias of it. */
+export const mergeTags = (sources: string[], target: string) =>
+  invoke<TagTree>("merge_tags", { sources, target });
+
+export const removeTagAlias = (alias: string) => invoke<TagTree>("remove_tag_alias", { alias });
+
+export const getAuditLog = (filter?: AuditLogFilter) =>
+  invoke<AuditLogEntry[]>("get_audit_log", { filter: filter ?? null });
+
+/** Write every entry matching `filter` to `{workspace}/exports/` unless `outputPath` is given. */
+export const exportAuditLog = (
+  workspacePath: string,
+  format: AuditLogFormat,
+  filter?: AuditLogFilter,
+  outputPath?: string,
+) =>
+  invoke<AuditLogExport>("export_audit_log", {
+    workspacePath,
+    filter: filter ?? null,
+    format,
+    outputPath: outputPath ?? null,
+  });
+
+/** Delete usage history, transcripts and stored GitHub / git host tokens. Skills are kept. */
+export const purgeAllUserData = (workspacePath: string) =>
+  invoke<UserDataPurgeResult>("purge_all_user_data", { workspacePath });
+
+/** Apply `operation` to all `skillNames` in one transaction; with `dryRun`, only report what would change. */
+export const bulkUpdateSkills = (skillNames: string[], operation: BulkSkillOperation, dryRun = false) =>
+  invoke<BulkSkillResult>("bulk_update_skills", { skillNames, operation, dryRun });
+
+export const bulkDeleteSkills = (skillNames: string[], dryRun = false) =>
+  invoke<BulkSkillResult>("bulk_delete_skills", { skillNames, dryRun });
 
 export const updateSkillMetadata = (
   skillName: string,
@@ -57,12 +173,117 @@ export const updateSkillMetadata = (
   disableModelInvocation: disableModelInvocation ?? null,
 });
 
+/** Intake wizard questions for the answers so far, or prefilled from a skill's stored intake. */
+export const getIntakeQuestions = (skillType: string, skillName?: string | null, answers?: Record<string, IntakeAnswer> | null) =>
+  invoke<IntakeQuestionnaire>("get_intake_questions", { skillType, skillName: skillName ?? null, answers: answers ?? null });
+
+export const submitIntakeAnswers = (skillName: string, answers: Record<string, IntakeAnswer>) =>
+  invoke<IntakeSubmission>("submit_intake_answers", { skillName, answers });
+
 export const renameSkill = (
   oldName: string,
   newName: string,
   workspacePath: string,
 ) => invoke("rename_skill", { oldName, newName, workspacePath });
 
+export const duplicateSkill = (
+  source: string,
+  newName: string,
+  options: DuplicateSkillOptions,
+  workspacePath: string,
+) => invoke<DuplicateSkillResult>("duplicate_skill", { source, newName, options, workspacePath });
+
+export const exportSkillArchive = (skillName: string, workspacePath: string, outputPath: string) =>
+  invoke<SkillArchiveExportResult>("export_skill_archive", { skillName, workspacePath, outputPath });
+
+/** Condense several skills into a token-budgeted document set for a Claude Project. */
+export const packageProjectContext = (skillNames: string[], budgetTokens: number, outputDir?: string) =>
+  invoke<ProjectContextPackage>("package_project_context", { skillNames, budgetTokens, outputDir: outputDir ?? null });
+
+export const importSkillArchive = (filePath: string, newName: string | null, workspacePath: string) =>
+  invoke<SkillArchiveImportResult>("import_skill_archive", { filePath, newName, workspacePath });
+
+export const analyzeSkillImpact = (workspacePath: string, skillName: string) =>
+  invoke<SkillImpactReport>("analyze_skill_impact", { workspacePath, skillName });
+
+/** Near-duplicate skills across the library; pass a name to get only that skill's matches. */
+export const findSimilarSkills = (skillName?: string | null, threshold?: number | null) =>
+  invoke<SimilarSkill[]>("find_similar_skills", { skillName: skillName ?? null, threshold: threshold ?? null });
+
+export const lintSkill = (skillName: string) =>
+  invoke<SkillLintReport>("lint_skill", { skillName });
+
+export const fixSkillAccessibility = (skillName: string) =>
+  invoke<AccessibilityFixResult>("fix_skill_accessibility", { skillName });
+
+export const setSkillReviewDate = (skillName: string, reviewBy: string | null) =>
+  invoke<void>("set_skill_review_date", { skillName, reviewBy });
+
+export const markSkillReviewed = (skillName: string, nextReviewBy: string) =>
+  invoke<void>("mark_skill_reviewed", { skillName, nextReviewBy });
+
+export const getSkillReviewReport = () =>
+  invoke<SkillReviewStatus[]>("get_skill_review_report");
+
+/** Latest import scan of a skill; null if it was never scanned. */
+export const getSkillScanReport = (skillName: string) =>
+  invoke<SkillScanReport | null>("get_skill_scan_report", { skillName });
+
+// --- Quality Gates ---
+
+export const listQualityGateProfiles = () =>
+  invoke<QualityGateProfile[]>("list_quality_gate_profiles");
+
+/** Run every check for a skill; `profile` defaults to the one selected in settings. */
+export const runQualityGate = (skillName: string, profile?: string) =>
+  invoke<QualityGateReport>("run_quality_gate", { skillName, profile: profile ?? null });
+
+export const waiveQualityCheck = (skillName: string, check: QualityCheck, justification: string) =>
+  invoke<QualityWaiver>("waive_quality_check", { skillName, check, justification });
+
+export const revokeQualityWaiver = (skillName: string, check: QualityCheck) =>
+  invoke<void>("revoke_quality_waiver", { skillName, check });
+
+/** Automatic version bumps for a skill, newest first. */
+export const getSkillChangelog = (skillName: string) =>
+  invoke<SkillChangelogEntry[]>("get_skill_changelog", { skillName });
+
+export const getSkillSlots = (skillName: string) =>
+  invoke<SkillSlotsStatus>("get_skill_slots", { skillName });
+
+export const packageSkillSlot = (skillName: string, slot: SkillSlotName) =>
+  invoke<PackageResult>("package_skill_slot", { skillName, slot });
+
+/** Install a slot into `~/.claude/skills` on this machine, replacing the other slot. */
+export const installSkillSlot = (skillName: string, slot: SkillSlotName) =>
+  invoke<SkillSlotsStatus>("install_skill_slot", { skillName, slot });
+
+export const uninstallSkillSlot = (skillName: string) =>
+  invoke<SkillSlotsStatus>("uninstall_skill_slot", { skillName });
+
+/** Copy beta over stable with a version bump and changelog entry. */
+export const promoteBetaToStable = (skillName: string) =>
+  invoke<SkillChangelogEntry>("promote_beta_to_stable", { skillName });
+
+export const listArtifactAnnotations = (skillName: string, workspacePath: string, file?: string) =>
+  invoke<ArtifactAnnotation[]>("list_artifact_annotations", { skillName, file: file ?? null, workspacePath });
+
+export const addArtifactAnnotation = (
+  skillName: string,
+  workspacePath: string,
+  file: string,
+  startLine: number,
+  endLine: number,
+  comment: string,
+) =>
+  invoke<ArtifactAnnotation>("add_artifact_annotation", { skillName, file, startLine, endLine, comment, workspacePath });
+
+export const updateArtifactAnnotation = (id: number, comment: string) =>
+  invoke<ArtifactAnnotation>("update_artifact_annotation", { id, comment });
+
+export const deleteArtifactAnnotation = (id: number) =>
+  invoke("delete_artifact_annotation", { id });
+
 export interface FieldSuggestions {
   description: string;
   domain: string;
@@ -117,11 +338,72 @@ export const startAgent = (
 
 // --- Workflow ---
 
+/** With `incremental: false`, step 3 rewrites SKILL.md even when it could patch it. */
 export const runWorkflowStep = (
   skillName: string,
   stepId: number,
   workspacePath: string,
-) => invoke<string>("run_workflow_step", { skillName, stepId, workspacePath });
+  incremental?: boolean,
+) => invoke<string>("run_workflow_step", { skillName, stepId, workspacePath, incremental: incremental ?? null });
+
+/** Whether re-running Generate Skill would patch SKILL.md for changed decisions or rewrite it. */
+export const getRegenerationMode = (skillName: string, workspacePath: string) =>
+  invoke<RegenerationMode>("get_regeneration_mode", { skillName, workspacePath });
+
+/** Queue a step to run unattended; returns the job id. */
+export const enqueueWorkflowStep = (
+  skillName: string,
+  stepId: number,
+  workspacePath: string,
+) => invoke<number>("enqueue_workflow_step", { skillName, stepId, workspacePath });
+
+/** The newest run of this step that stopped before finishing (crash, quit or error), if it can be resumed. */
+export const findResumableStepRun = (skillName: string, stepId: number) =>
+  invoke<AgentRunCheckpoint | null>("find_resumable_step_run", { skillName, stepId });
+
+/** Continue the interrupted run of a step in its SDK session; returns the new agent id. */
+export const resumeWorkflowStep = (
+  skillName: string,
+  stepId: number,
+  workspacePath: string,
+) => invoke<string>("resume_workflow_step", { skillName, stepId, workspacePath });
+
+/** A previous run of this step made with the same inputs, if its outputs are still cached. */
+export const findCachedStepRun = (
+  skillName: string,
+  stepId: number,
+  workspacePath: string,
+) => invoke<CachedStepRun | null>("find_cached_step_run", { skillName, stepId, workspacePath });
+
+/** Restore the cached outputs instead of running the step's agent. */
+export const reuseCachedStepRun = (
+  skillName: string,
+  stepId: number,
+  workspacePath: string,
+) => invoke<CachedStepRun>("reuse_cached_step_run", { skillName, stepId, workspacePath });
+
+/** Capture a skill's workspace state so later changes can be undone. */
+export const createWorkspaceSnapshot = (
+  skillName: string,
+  workspacePath: string,
+  reason?: string,
+) => invoke<WorkspaceSnapshot>("create_workspace_snapshot", { skillName, workspacePath, reason: reason ?? null });
+
+/** A skill's snapshots, newest first. Resets snapshot the skill automatically. */
+export const listWorkspaceSnapshots = (skillName: string, workspacePath: string) =>
+  invoke<WorkspaceSnapshot[]>("list_workspace_snapshots", { skillName, workspacePath });
+
+export const restoreWorkspaceSnapshot = (
+  skillName: string,
+  workspacePath: string,
+  snapshotId: string,
+) => invoke<WorkspaceSnapshot>("restore_workspace_snapshot", { skillName, workspacePath, snapshotId });
+
+export const listQueue = () =>
+  invoke<WorkflowQueueJob[]>("list_queue");
+
+export const cancelQueued = (jobId: number) =>
+  invoke<void>("cancel_queued", { jobId });
 
 export const materializeWorkflowStepOutput = (
   skillName: string,
@@ -136,7 +418,70 @@ export const materializeWorkflowStepOutput = (
 export const packageSkill = (
   skillName: string,
   workspacePath: string,
-) => invoke<PackageResult>("package_skill", { skillName, workspacePath });
+  refuseOnErrors?: boolean,
+  dependencies?: DependencyPackagingMode,
+  /** Package the SKILL.<locale>.md translation as SKILL.md instead of bundling every translation. */
+  locale?: string,
+  /** Package layout; remembered per skill, so omit it to reuse the last one. */
+  targetFormat?: PackageFormat,
+) =>
+  invoke<PackageResult>("package_skill", {
+    skillName,
+    workspacePath,
+    refuseOnErrors: refuseOnErrors ?? null,
+    dependencies: dependencies ?? null,
+    locale: locale ?? null,
+    targetFormat: targetFormat ?? null,
+  });
+
+export const listSkillTranslations = (skillName: string) =>
+  invoke<SkillTranslation[]>("list_skill_translations", { skillName });
+
+/** Start a translation agent; pass its structured output to materializeSkillTranslation. */
+export const generateSkillTranslation = (skillName: string, language: string) =>
+  invoke<string>("generate_skill_translation", { skillName, language });
+
+export const materializeSkillTranslation = (
+  skillName: string,
+  language: string,
+  structuredOutput: unknown,
+) => invoke<SkillTranslation>("materialize_skill_translation", { skillName, language, structuredOutput });
+
+export const setSkillDependencies = (skillName: string, dependsOn: string[]) =>
+  invoke<SkillDependencyGraph>("set_skill_dependencies", { skillName, dependsOn });
+
+export const getDependencyGraph = () =>
+  invoke<SkillDependencyGraph>("get_dependency_graph");
+
+export const validateSkill = (skillName: string) =>
+  invoke<SkillValidationReport>("validate_skill", { skillName });
+
+/** SKILL.md parsed for preview: frontmatter fields, trigger text, body blocks and reference stubs. */
+export const renderSkillPreview = (skillName: string) =>
+  invoke<SkillPreview>("render_skill_preview", { skillName });
+
+/** The model `run_workflow_step` will use for this step, after model routes. */
+export const getEffectiveModel = (skillName: string, stepId: number, workspacePath: string) =>
+  invoke<EffectiveModel>("get_effective_model", { skillName, stepId, workspacePath });
+
+/** The shared settings document a push would write. */
+export const getSettingsSyncDocument = () =>
+  invoke<SettingsSyncDocument>("get_settings_sync_document");
+
+/** Commit this machine's shared settings to the settings sync repo and push. */
+export const pushSettingsSync = () =>
+  invoke<SettingsSyncResult>("push_settings_sync");
+
+/** Pull the settings sync repo and merge its settings, keeping local overrides. */
+export const pullSettingsSync = () =>
+  invoke<SettingsSyncResult>("pull_settings_sync");
+
+/** Redeploy agent prompts, applying templates from the custom prompts folder. */
+export const reloadPrompts = () =>
+  invoke<PromptReload>("reload_prompts");
+
+export const getNetworkFeatures = () =>
+  invoke<NetworkFeatures>("get_network_features");
 
 export const resetWorkflowStep = (
   workspacePath: string,
@@ -165,6 +510,21 @@ export const previewStepReset = (
   fromStepId: number,
 ) => invoke<StepResetPreview[]>("preview_step_reset", { workspacePath, skillName, fromStepId });
 
+export interface ArtifactResetResult {
+  current_step: number;
+  deleted_files: string[];
+  kept_files: string[];
+}
+
+/** Reset from fromStepId but keep the listed files (paths from previewStepReset).
+ *  The workflow restarts at the earliest step that loses a file. */
+export const resetWorkflowArtifacts = (
+  workspacePath: string,
+  skillName: string,
+  fromStepId: number,
+  keepFiles: string[],
+) => invoke<ArtifactResetResult>("reset_workflow_artifacts", { workspacePath, skillName, fromStepId, keepFiles });
+
 export const verifyStepOutput = (
   workspacePath: string,
   skillName: string,
@@ -174,6 +534,10 @@ export const verifyStepOutput = (
 export const getDisabledSteps = (skillName: string) =>
   invoke<number[]>("get_disabled_steps", { skillName });
 
+/** Built-in steps plus any custom steps from the skill purpose's workflow manifest. */
+export const getWorkflowSteps = (skillName: string) =>
+  invoke<WorkflowStepConfig[]>("get_workflow_steps", { skillName });
+
 // --- Workflow State (SQLite) ---
 
 interface WorkflowRunRow {
@@ -222,6 +586,15 @@ export const readFile = (filePath: string) =>
 export const writeFile = (path: string, content: string) =>
   invoke<void>("write_file", { path, content });
 
+export const listStepArtifacts = (workspacePath: string, skillName: string, stepId: number) =>
+  invoke<StepArtifactIndex | null>("list_step_artifacts", { workspacePath, skillName, stepId });
+
+export const checkPathInput = (
+  path: string,
+  kind?: PathKind,
+  mustExist?: boolean,
+) => invoke<PathCheck>("check_path_input", { path, kind: kind ?? null, mustExist: mustExist ?? null });
+
 export const listSkillFiles = (workspacePath: string, skillName: string) =>
   invoke<import("./types").SkillFileEntry[]>("list_skill_files", { workspacePath, skillName });
 
@@ -253,6 +626,12 @@ export const reconcileStartup = (apply = false) =>
     ? invoke<ReconciliationResult>("reconcile_startup", { apply: true })
     : invoke<ReconciliationResult>("reconcile_startup");
 
+export const listContractUpgrades = () =>
+  invoke<ContractUpgrade[]>("list_contract_upgrades");
+
+export const applyContractUpgrade = (skillName: string, action: "transform" | "rerun") =>
+  invoke<void>("apply_contract_upgrade", { skillName, action });
+
 export const recordReconciliationCancel = (
   notificationCount: number,
   discoveredCount: number,
@@ -274,6 +653,12 @@ interface CreateGithubIssueRequest {
   title: string;
   body: string;
   labels: string[];
+  /** Files scrubbed and bundled into the issue body */
+  attachments?: string[];
+  /** Encrypt the bundle to the configured feedback public key */
+  encrypt_attachments?: boolean;
+  /** Health-check report appended to the issue body */
+  diagnostics?: DiagnosticsReport | null;
 }
 
 interface CreateGithubIssueResponse {
@@ -284,6 +669,18 @@ interface CreateGithubIssueResponse {
 export const createGithubIssue = (request: CreateGithubIssueRequest) =>
   invoke<CreateGithubIssueResponse>("create_github_issue", { request });
 
+export const scrubFeedbackAttachments = (paths: string[]) =>
+  invoke<ScrubbedAttachment[]>("scrub_feedback_attachments", { paths });
+
+export const runDiagnostics = () =>
+  invoke<DiagnosticsReport>("run_diagnostics");
+
+export const getDbContention = () =>
+  invoke<DbContention>("get_db_contention");
+
+export const listHookDeliveries = (limit?: number) =>
+  invoke<HookDelivery[]>("list_hook_deliveries", { limit: limit ?? null });
+
 // --- GitHub OAuth ---
 
 export const githubStartDeviceFlow = () =>
@@ -316,6 +713,32 @@ export const releaseLock = (skillName: string) =>
 export const getLockedSkills = () =>
   invoke<SkillLock[]>("get_locked_skills");
 
+// --- Read-only Observer ---
+
+export interface SkillEvent {
+  id: number;
+  skill_name: string;
+  instance_id: string;
+  kind: "workflow.saved" | "workflow.reset" | "step.output" | "lock.released";
+  created_at: string;
+}
+
+export interface ReadonlySkill {
+  skill_name: string;
+  /** The other instance's lock; null if it was released in the meantime. */
+  lock: SkillLock | null;
+  last_event_id: number;
+}
+
+export const openSkillReadonly = (skillName: string) =>
+  invoke<ReadonlySkill>("open_skill_readonly", { skillName });
+
+export const closeSkillReadonly = (skillName: string) =>
+  invoke<void>("close_skill_readonly", { skillName });
+
+export const pollSkillEvents = (skillName: string, afterId: number) =>
+  invoke<SkillEvent[]>("poll_skill_events", { skillName, afterId });
+
 // --- Usage Tracking ---
 
 export const persistAgentRun = (params: {
@@ -337,6 +760,7 @@ export const persistAgentRun = (params: {
   compactionCount?: number;
   sessionId?: string;
   workflowSessionId?: string;
+  timeToFirstTokenMs?: number | null;
 }) => invoke<void>("persist_agent_run", {
   agentId: params.agentId,
   skillName: params.skillName,
@@ -356,8 +780,18 @@ export const persistAgentRun = (params: {
   compactionCount: params.compactionCount ?? 0,
   sessionId: params.sessionId ?? null,
   workflowSessionId: params.workflowSessionId ?? null,
+  timeToFirstTokenMs: params.timeToFirstTokenMs ?? null,
 });
 
+export const getStepPerfTrends = (stepId?: number | null, startDate?: string | null) =>
+  invoke<StepPerfTrend[]>("get_step_perf_trends", { stepId: stepId ?? null, startDate: startDate ?? null });
+
+export const replayStep = (skillName: string, stepId: number, sessionId: string) =>
+  invoke<StepReplay>("replay_step", { skillName, stepId, sessionId });
+
+export const estimateStepCost = (skillName: string, stepId: number, workspacePath: string, models?: string[] | null) =>
+  invoke<StepCostEstimate>("estimate_step_cost", { skillName, stepId, workspacePath, models: models ?? null });
+
 export const getUsageSummary = (hideCancelled: boolean = false, startDate?: string | null, skillName?: string | null) =>
   invoke<UsageSummary>("get_usage_summary", { hideCancelled, startDate: startDate ?? null, skillName: skillName ?? null });
 
@@ -382,12 +816,26 @@ export const getUsageByModel = (hideCancelled: boolean = false, startDate?: stri
 export const getUsageByDay = (hideCancelled: boolean = false, startDate?: string | null, skillName?: string | null) =>
   invoke<UsageByDay[]>("get_usage_by_day", { hideCancelled, startDate: startDate ?? null, skillName: skillName ?? null });
 
+export const getSkillAnalytics = (skillName?: string | null) =>
+  invoke<SkillAnalytics[]>("get_skill_analytics", { skillName: skillName ?? null });
+
+/** Import skill invocations from Claude Code session transcripts. */
+export const importSkillInvocations = (sourceDir?: string | null) =>
+  invoke<SkillInvocationImport>("import_skill_invocations", { sourceDir: sourceDir ?? null });
+
+export const getSkillInvocationStats = (since?: string | null) =>
+  invoke<SkillInvocationStats[]>("get_skill_invocation_stats", { since: since ?? null });
+
 export const getWorkflowSkillNames = () =>
   invoke<string[]>("get_workflow_skill_names");
 
 export const resetUsage = () =>
   invoke<void>("reset_usage");
 
+/** Spend against the per-skill (when given) and global budgets, including running agents. */
+export const getBudgetStatus = (skillName?: string | null) =>
+  invoke<BudgetStatus>("get_budget_status", { skillName: skillName ?? null });
+
 // --- Imported Skills ---
 
 export const exportSkill = (skillName: string) =>
@@ -408,9 +856,122 @@ export async function listSkills(workspacePath: string, sourceUrl?: string | nul
   })
 }
 
+export const listSkillsGrouped = (by: SkillGrouping) =>
+  invoke<SkillGroup[]>("list_skills_grouped", { by })
+
 export const listWorkspaceSkills = (sourceUrl?: string | null) =>
   invoke<WorkspaceSkill[]>("list_workspace_skills", { sourceUrl: sourceUrl ?? null })
 
+export const listActivationDecisions = (skillName?: string | null) =>
+  invoke<ActivationDecision[]>("list_activation_decisions", { skillName: skillName ?? null })
+
+/** Run `scenarioSet` (default: the skill's `evaluations.md`) with and without the skill and judge the answers. */
+export const benchmarkSkill = (skillName: string, scenarioSet?: string | null) =>
+  invoke<SkillBenchmark>("benchmark_skill", { skillName, scenarioSet: scenarioSet ?? null })
+
+export const listSkillBenchmarks = (skillName: string) =>
+  invoke<SkillBenchmark[]>("list_skill_benchmarks", { skillName })
+
+export const createTestCase = (
+  skillName: string,
+  name: string,
+  prompt: string,
+  expectTrigger = true,
+  expectedContains: string[] = [],
+) =>
+  invoke<SkillTestCase>("create_test_case", { skillName, name, prompt, expectTrigger, expectedContains })
+
+export const listTestCases = (skillName: string) =>
+  invoke<SkillTestCase[]>("list_test_cases", { skillName })
+
+export const deleteTestCase = (id: number) =>
+  invoke<void>("delete_test_case", { id })
+
+/** Run the skill's saved test cases (all, or `testCaseIds`) through the agent and record the results. */
+export const runSkillTests = (skillName: string, testCaseIds?: number[] | null) =>
+  invoke<SkillTestRun>("run_skill_tests", { skillName, testCaseIds: testCaseIds ?? null })
+
+/** Results of `runId`, or of the latest run when omitted. */
+export const getTestResults = (skillName: string, runId?: string | null) =>
+  invoke<SkillTestResult[]>("get_test_results", { skillName, runId: runId ?? null })
+
+/** What the step will do for this skill, what it reads and writes, expected cost/time, and what to prepare. */
+export const explainStep = (skillName: string, stepId: number) =>
+  invoke<StepExplanation>("explain_step", { skillName, stepId })
+
+/** Library state at a past time; `timestamp` is RFC 3339 or a `YYYY-MM-DD` date (end of day, UTC). */
+export const getLibraryAt = (timestamp: string) =>
+  invoke<LibrarySnapshot>("get_library_at", { timestamp })
+
+/** Dry run: what `transform` would change in each library skill's SKILL.md. Writes nothing. */
+export const previewFrontmatterMigration = (transform: FrontmatterTransform) =>
+  invoke<FrontmatterMigrationPreview[]>("preview_frontmatter_migration", { transform })
+
+/** Apply `transform` to all skills (or `skillNames`) as one commit; keep `commit_sha` to roll back. */
+export const applyFrontmatterMigration = (transform: FrontmatterTransform, skillNames?: string[] | null) =>
+  invoke<FrontmatterMigrationResult>("apply_frontmatter_migration", { transform, skillNames: skillNames ?? null })
+
+export const rollbackFrontmatterMigration = (commitSha: string) =>
+  invoke<FrontmatterMigrationResult>("rollback_frontmatter_migration", { commitSha })
+
+/** Link a file under `references/` to `sourcePath` in `repo` (`owner/repo`) at `gitRef`, writing its current content. */
+export const linkReference = (skillName: string, referencePath: string, repo: string, gitRef: string, sourcePath: string) =>
+  invoke<LinkedReference>("link_reference", { skillName, referencePath, repo, gitRef, sourcePath })
+
+export const unlinkReference = (skillName: string, referencePath: string) =>
+  invoke<void>("unlink_reference", { skillName, referencePath })
+
+export const listLinkedReferences = (skillName: string) =>
+  invoke<LinkedReference[]>("list_linked_references", { skillName })
+
+/** Fetch upstream for each linked reference without writing; diff `current_content` against `upstream_content`. */
+export const refreshLinkedReferences = (skillName: string) =>
+  invoke<LinkedReferenceRefresh[]>("refresh_linked_references", { skillName })
+
+export const applyLinkedReferenceUpdates = (skillName: string, updates: ApprovedReferenceUpdate[]) =>
+  invoke<string[]>("apply_linked_reference_updates", { skillName, updates })
+
+export const listSkillReferences = (skillName: string) =>
+  invoke<SkillReference[]>("list_skill_references", { skillName })
+
+/** Add a reference from a local file or a URL, saved as `references/{fileName}` and indexed in SKILL.md. */
+export const addSkillReference = (
+  skillName: string,
+  source: { filePath: string } | { url: string },
+  fileName?: string,
+  replace?: boolean,
+) =>
+  invoke<SkillReference>("add_skill_reference", {
+    skillName,
+    filePath: "filePath" in source ? source.filePath : null,
+    url: "url" in source ? source.url : null,
+    fileName: fileName ?? null,
+    replace: replace ?? null,
+  })
+
+/** Delete a reference file, dropping it from SKILL.md's index and unlinking it. */
+export const removeSkillReference = (skillName: string, path: string) =>
+  invoke<void>("remove_skill_reference", { skillName, path })
+
+/** Capture files matching `globs` (all when empty) from a local repo into `references/repo-context/`, replacing any earlier capture. */
+export const attachRepoContext = (skillName: string, repoPath: string, globs: string[]) =>
+  invoke<RepoContextResult>("attach_repo_context", { skillName, repoPath, globs })
+
+// --- Background tasks ---
+
+export const listBackgroundTasks = () =>
+  invoke<BackgroundQueue>("list_background_tasks")
+
+/** Queue a job now; returns its id (an already-queued job of the same kind is reused). */
+export const enqueueBackgroundTask = (kind: BackgroundTaskKind, priority: TaskPriority) =>
+  invoke<number>("enqueue_background_task", { kind, priority })
+
+export const setBackgroundTaskPriority = (taskId: number, priority: TaskPriority) =>
+  invoke<BackgroundTask>("set_background_task_priority", { taskId, priority })
+
+export const cancelBackgroundTask = (taskId: number) =>
+  invoke<void>("cancel_background_task", { taskId })
+
 // --- GitHub Import ---
 
 export const parseGitHubUrl = (url: string) =>
@@ -420,11 +981,74 @@ export const parseGitHubUrl = (url: string) =>
 export const checkMarketplaceUrl = (url: string) =>
   invoke<string>("check_marketplace_url", { url });
 
-export const listGitHubSkills = (owner: string, repo: string, branch: string, subpath?: string) =>
-  invoke<AvailableSkill[]>("list_github_skills", { owner, repo, branch, subpath: subpath ?? null });
+/** With an `operationId`, progress arrives as `github-import-progress` events and `cancelGitHubImport` stops the listing. */
+export const listGitHubSkills = (owner: string, repo: string, branch: string, subpath?: string, operationId?: string | null) =>
+  invoke<AvailableSkill[]>("list_github_skills", { owner, repo, branch, subpath: subpath ?? null, operationId: operationId ?? null });
+
+export const importGitHubSkills = (owner: string, repo: string, branch: string, skillRequests: WorkspaceSkillImportRequest[], sourceUrl?: string | null, operationId?: string | null) =>
+  invoke<ImportedSkill[]>("import_github_skills", { owner, repo, branch, skillRequests, sourceUrl: sourceUrl ?? null, operationId: operationId ?? null });
+
+/** Stop a listing or import at its next skill boundary; it resolves with the skills finished so far. */
+export const cancelGitHubImport = (operationId: string) =>
+  invoke<void>("cancel_github_import", { operationId });
+
+// --- Package signing ---
+
+export const getSigningPublicKey = () =>
+  invoke<SigningKeyInfo>("get_signing_public_key");
+
+export const listTrustedKeys = () =>
+  invoke<TrustedKey[]>("list_trusted_keys");
+
+export const addTrustedKey = (name: string, publicKey: string) =>
+  invoke<TrustedKey>("add_trusted_key", { name, publicKey });
+
+export const removeTrustedKey = (keyId: string) =>
+  invoke<void>("remove_trusted_key", { keyId });
+
+// --- Git Hosting (GitLab, Bitbucket) ---
 
-export const importGitHubSkills = (owner: string, repo: string, branch: string, skillRequests: WorkspaceSkillImportRequest[], sourceUrl?: string | null) =>
-  invoke<ImportedSkill[]>("import_github_skills", { owner, repo, branch, skillRequests, sourceUrl: sourceUrl ?? null });
+export const gitHostConnect = (host: GitHost, token: string, instanceUrl?: string | null) =>
+  invoke<GitHostAccount>("git_host_connect", { host, token, instanceUrl: instanceUrl ?? null });
+
+export const gitHostDisconnect = (host: GitHost) =>
+  invoke<void>("git_host_disconnect", { host });
+
+export const gitHostListAccounts = () =>
+  invoke<GitHostAccount[]>("git_host_list_accounts");
+
+export const gitHostListRepos = (host: GitHost) =>
+  invoke<HostedRepoSummary[]>("git_host_list_repos", { host });
+
+export const listHostedSkills = (repoUrl: string) =>
+  invoke<AvailableSkill[]>("list_hosted_skills", { repoUrl });
+
+export const importHostedSkills = (repoUrl: string, skillRequests: WorkspaceSkillImportRequest[]) =>
+  invoke<ImportedSkill[]>("import_hosted_skills", { repoUrl, skillRequests });
+
+export const pushSkillToHost = (skillName: string, repoUrl: string, strategy?: PushStrategy) =>
+  invoke<HostedSkillPushResult>("push_skill_to_host", { skillName, repoUrl, strategy: strategy ?? null });
+
+export const pushSkillResolveConflicts = (
+  skillName: string,
+  repoUrl: string,
+  resolvedContent: string,
+  remoteDigest: string,
+  strategy?: PushStrategy,
+) =>
+  invoke<HostedSkillPushResult>("push_skill_resolve_conflicts", {
+    skillName,
+    repoUrl,
+    strategy: strategy ?? null,
+    resolvedContent,
+    remoteDigest,
+  });
+
+export const listUpstreamReviews = () =>
+  invoke<SkillUpstreamReview[]>("list_upstream_reviews");
+
+export const refreshUpstreamReviews = () =>
+  invoke<SkillUpstreamReview[]>("refresh_upstream_reviews");
 
 export const setWorkspaceSkillPurpose = (skillId: string, purpose: string | null) =>
   invoke<void>("set_workspace_skill_purpose", { skillId, purpose });
@@ -434,6 +1058,10 @@ export const setWorkspaceSkillPurpose = (skillId: string, purpose: string | null
 export const importMarketplaceToLibrary = (skillPaths: string[], sourceUrl: string, metadataOverrides?: Record<string, SkillMetadataOverride>) =>
   invoke<MarketplaceImportResult[]>("import_marketplace_to_library", { sourceUrl, skillPaths, metadataOverrides: metadataOverrides ?? null })
 
+/** Import Anthropic's example skills into the library and register their repo for update tracking. */
+export const importExampleSkills = () =>
+  invoke<MarketplaceImportResult[]>("import_example_skills")
+
 export const checkMarketplaceUpdates = (): Promise<MarketplaceUpdateResult> =>
   invoke<MarketplaceUpdateResult>("check_marketplace_updates")
 
@@ -448,8 +1076,39 @@ export const listRefinableSkills = (workspacePath: string) =>
 export const getSkillContentForRefine = (skillName: string, workspacePath: string) =>
   invoke<SkillFileContent[]>("get_skill_content_for_refine", { skillName, workspacePath })
 
-export const getRefineDiff = (skillName: string, workspacePath: string) =>
-  invoke<RefineDiff>("get_refine_diff", { skillName, workspacePath })
+/** Per-file pending changes; with a session, the files it accepted are marked. */
+export const getRefineDiff = (skillName: string, workspacePath: string, sessionId?: string) =>
+  invoke<RefineDiff>("get_refine_diff", { skillName, workspacePath, sessionId: sessionId ?? null })
+
+/** Accept (keep) or reject (put back to HEAD) one changed file of a refine session. */
+export const reviewRefineFile = (sessionId: string, workspacePath: string, path: string, accept: boolean) =>
+  invoke<RefineDiff>("review_refine_file", { sessionId, workspacePath, path, accept })
+
+/** Pending changes split into frontmatter field changes and word-level body hunks. */
+export const getStructuredRefineDiff = (skillName: string, workspacePath: string) =>
+  invoke<StructuredFileDiff[]>("get_structured_refine_diff", { skillName, workspacePath })
+
+export const getStructuredSkillDiff = (workspacePath: string, skillName: string, shaA: string, shaB: string) =>
+  invoke<StructuredFileDiff[]>("get_structured_skill_diff", { workspacePath, skillName, shaA, shaB })
+
+/** Compare two versions of a skill; each ref is a commit SHA, "working" or "team". */
+export const compareSkillVersions = (
+  workspacePath: string,
+  skillName: string,
+  baseRef: string,
+  targetRef: string,
+) =>
+  invoke<SkillVersionComparison>("compare_skill_versions", { workspacePath, skillName, baseRef, targetRef })
+
+/** Keep only the accepted hunks and frontmatter fields of a file's pending changes. */
+export const applyPartialDiff = (
+  skillName: string,
+  workspacePath: string,
+  path: string,
+  acceptedHunkIds: string[],
+  acceptedFields: string[],
+) =>
+  invoke<void>("apply_partial_diff", { skillName, workspacePath, path, acceptedHunkIds, acceptedFields })
 
 export const startRefineSession = (skillName: string, workspacePath: string) =>
   invoke<RefineSessionInfo>("start_refine_session", { skillName, workspacePath })
@@ -457,6 +1116,12 @@ export const startRefineSession = (skillName: string, workspacePath: string) =>
 export const closeRefineSession = (sessionId: string) =>
   invoke<void>("close_refine_session", { sessionId })
 
+export const listResumableRefineSessions = () =>
+  invoke<ResumableRefineSession[]>("list_resumable_refine_sessions")
+
+export const resumeRefineSession = (sessionId: string, workspacePath: string) =>
+  invoke<ResumedRefineSession>("resume_refine_session", { sessionId, workspacePath })
+
 export const sendRefineMessage = (
   sessionId: string,
   userMessage: string,
@@ -465,6 +1130,20 @@ export const sendRefineMessage = (
   command?: string,
 ) => invoke<string>("send_refine_message", { sessionId, userMessage, workspacePath, targetFiles: targetFiles ?? null, command: command ?? null })
 
+/** Refine each skill in turn with one instruction; progress arrives as `refine-batch-progress` events. */
+export const startRefineBatch = (instruction: string, skillNames: string[], workspacePath: string) =>
+  invoke<RefineBatch>("start_refine_batch", { instruction, skillNames, workspacePath })
+
+export const getRefineBatch = (batchId: string) =>
+  invoke<RefineBatch>("get_refine_batch", { batchId })
+
+export const listRefineBatches = (limit?: number) =>
+  invoke<RefineBatch[]>("list_refine_batches", { limit: limit ?? null })
+
+/** Approve (commit) or reject (discard) one skill's changes; rejecting a queued skill skips it. */
+export const resolveRefineBatchItem = (batchId: string, skillName: string, approve: boolean) =>
+  invoke<RefineBatch>("resolve_refine_batch_item", { batchId, skillName, approve })
+
 export const materializeRefineValidationOutput = (
   skillName: string,
   workspacePath: string,
@@ -597,6 +1276,9 @@ export const buildTestEvaluatorPrompt = (
 export const parseSkillFile = (filePath: string): Promise<SkillFileMeta> =>
   invoke<SkillFileMeta>("parse_skill_file", { filePath })
 
+export const generateOnboardingChecklist = (skillName: string): Promise<OnboardingChecklist> =>
+  invoke<OnboardingChecklist>("generate_onboarding_checklist", { skillName })
+
 export const importSkillFromFile = (params: {
   filePath: string
   name: string
@@ -607,6 +1289,7 @@ export const importSkillFromFile = (params: {
   userInvocable?: boolean | null
   disableModelInvocation?: boolean | null
   forceOverwrite: boolean
+  parameters?: Record<string, string> | null
 }): Promise<string> =>
   invoke<string>("import_skill_from_file", {
     filePath: params.filePath,
@@ -618,4 +1301,32 @@ export const importSkillFromFile = (params: {
     userInvocable: params.userInvocable ?? null,
     disableModelInvocation: params.disableModelInvocation ?? null,
     forceOverwrite: params.forceOverwrite,
+    parameters: params.parameters ?? null,
   })
+
+// --- Tool Policies ---
+
+export const getToolPolicy = (skillName?: string | null) =>
+  invoke<ToolPolicyView>("get_tool_policy", { skillName: skillName ?? null });
+
+export const setToolPolicy = (skillName: string | null, policy: ToolPolicy | null) =>
+  invoke<ToolPolicyView>("set_tool_policy", { skillName, policy });
+
+/** Audit log of the tool calls one agent run made. */
+export const getAgentToolCalls = (agentId: string) =>
+  invoke<AgentToolCall[]>("get_agent_tool_calls", { agentId });
+
+// --- Transcripts ---
+
+export const listTranscripts = (workspacePath: string, skillName: string) =>
+  invoke<TranscriptInfo[]>("list_transcripts", { workspacePath, skillName });
+
+export const readTranscriptTurns = (workspacePath: string, skillName: string, fileName: string, startTurn: number, limit: number) =>
+  invoke<TranscriptTurns>("read_transcript_turns", { workspacePath, skillName, fileName, startTurn, limit });
+
+/** Archived transcript of a finished run; null if it was never archived or has been evicted. */
+export const getAgentTranscript = (agentId: string) =>
+  invoke<AgentTranscript | null>("get_agent_transcript", { agentId });
+
+export const searchTranscripts = (query: string, skillName?: string | null, limit?: number) =>
+  invoke<TranscriptSearchHit[]>("search_transcripts", { query, skillName: skillName ?? null, limit: limit ?? null });
@@ -42,6 +42,8 @@ export interface AppSettings {
   refine_prompt_suggestions?: boolean
   splash_shown: boolean
   github_oauth_token: string | null
+  /** Web URL of the GitHub server that issued the token; set by sign-in */
+  github_oauth_host?: string | null
   github_user_login: string | null
   github_user_avatar: string | null
   github_user_email: string | null
@@ -52,6 +54,148 @@ export interface AppSettings {
   function_role: string | null
   dashboard_view_mode: string | null
   auto_update: boolean
+  team_repo?: string | null
+  /** GitHub Enterprise Server base URL; null for github.com */
+  github_enterprise_url?: string | null
+  /** Client ID of the OAuth app used for device-flow sign-in on the enterprise server */
+  github_oauth_client_id?: string | null
+  /** PEM bundle trusted in addition to the system roots for GitHub requests */
+  github_ca_cert_path?: string | null
+  /** Skip TLS certificate checks for GitHub requests */
+  github_accept_invalid_certs?: boolean
+  local_model_endpoint?: string | null
+  local_model?: string | null
+  activation_policy?: ActivationPolicy
+  accessibility_lint?: AccessibilityLintMode
+  block_expired_packaging?: boolean
+  /** Refuse to import packages that are unsigned or signed by an untrusted key */
+  require_trusted_packages?: boolean
+  workflow_queue_concurrency?: number
+  max_concurrent_agents?: number
+  quality_gate_profile?: string | null
+  /** Spend limit across all skills since the last usage reset; null for none */
+  global_budget_usd?: number | null
+  /** Per-skill spend limits since the last usage reset */
+  skill_budgets_usd?: Record<string, number>
+  /** Words replaced with [REDACTED] in text feedback attachments */
+  feedback_redaction_keywords?: string[]
+  /** Base64 X25519 key that feedback bundles are encrypted to */
+  feedback_public_key?: string | null
+  /** Folder of agent prompt templates that replace bundled ones by file name */
+  custom_prompts_dir?: string | null
+  /** Claude Code data directory for skill usage imports; null for ~/.claude */
+  claude_data_dir?: string | null
+  /** Run agents on local_model_endpoint and turn off marketplace and GitHub features */
+  offline_mode?: boolean
+  disable_marketplace?: boolean
+  disable_github?: boolean
+  /** Shell commands and webhooks run on workflow events */
+  automation_hooks?: AutomationHook[]
+  /** Size cap in MB of the archived agent transcripts; 0 turns archiving off */
+  transcript_archive_mb?: number
+  /** Days usage history, transcripts and audit entries are kept; 0 keeps them forever */
+  retention?: RetentionPolicy
+  /** Git remote (url or url#branch) holding the organization's shared settings */
+  settings_sync_repo?: string | null
+  /** Shared settings fields that keep this machine's value when syncing */
+  settings_sync_local_overrides?: string[]
+  /** Per-step and per-skill-type model overrides for workflow steps */
+  model_routes?: ModelRoute[]
+}
+
+/** Days each kind of history is kept before the daily retention pass deletes it; 0 keeps it forever. */
+export interface RetentionPolicy {
+  agent_runs_days: number
+  workflow_sessions_days: number
+  transcripts_days: number
+  audit_log_days: number
+}
+
+/** Routes workflow steps to a model; the most specific matching route wins. */
+export interface ModelRoute {
+  step_id: number | null
+  /** Skill purpose: domain, source, data-engineering or platform */
+  skill_type: string | null
+  /** Shorthand (haiku, sonnet, opus) or a full model id */
+  model: string
+}
+
+/** The model a workflow step will run on, and why. */
+export interface EffectiveModel {
+  step_id: number
+  model: string
+  source: "route" | "agent" | "preferred"
+  route: ModelRoute | null
+}
+
+/** Shared settings as stored in the settings sync repo. */
+export interface SettingsSyncDocument {
+  version: number
+  exported_at: string
+  exported_by: string | null
+  /** Shared AppSettings fields by name, plus tool_policy for the global tool policy */
+  settings: Record<string, unknown>
+}
+
+export interface SettingsSyncResult {
+  /** Commit pushed to the sync repo; null when nothing changed or on pull */
+  commit_sha: string | null
+  applied: string[]
+  /** Shared fields that differ locally but are kept as local overrides */
+  kept_local: string[]
+}
+
+export type AutomationHookEvent = "step_completed" | "skill_packaged" | "budget_exceeded"
+
+export interface AutomationHook {
+  name: string
+  events: AutomationHookEvent[]
+  /** `command` runs target in the shell with the event JSON on stdin; `webhook` POSTs it to the target URL */
+  kind: "command" | "webhook"
+  target: string
+  enabled: boolean
+}
+
+/** One delivery of an event to an automation hook, after retries. */
+export interface HookDelivery {
+  id: number
+  hook_name: string
+  event: AutomationHookEvent
+  skill_name: string
+  payload: string
+  attempts: number
+  status: "delivered" | "failed"
+  detail: string
+  created_at: string
+}
+
+/** How accessibility lint findings gate packaging. */
+export type AccessibilityLintMode = "off" | "advisory" | "blocking"
+
+/** Import-time activation rule; every set criterion must match. */
+export interface ActivationRule {
+  action: "activate" | "deactivate"
+  /** Source URL prefix (e.g. `acme/skills`), or `upload` for file uploads */
+  source?: string | null
+  author?: string | null
+  domain?: string | null
+  signature?: "unsigned" | "trusted" | "untrusted" | null
+}
+
+export interface ActivationPolicy {
+  rules: ActivationRule[]
+  default_action: "activate" | "deactivate"
+}
+
+export interface ActivationDecision {
+  id: number
+  skill_name: string
+  is_active: boolean
+  /** `settings rule N`, `team rule N` or `default` */
+  matched_rule: string
+  reason: string
+  overridden_at: string | null
+  created_at: string
 }
 
 export interface SkillUpdateInfo {
@@ -87,6 +231,88 @@ export type GitHubAuthResult =
   | { status: 'slow_down' }
   | { status: 'success'; user: GitHubUser }
 
+/** This installation's package signing key. */
+export interface SigningKeyInfo {
+  key_id: string
+  /** Base64 raw Ed25519 public key */
+  public_key: string
+}
+
+/** A public key trusted for package verification. */
+export interface TrustedKey {
+  key_id: string
+  name: string
+  public_key: string
+  added_at: string
+}
+
+export type GitHost = 'github' | 'gitlab' | 'bitbucket'
+
+export interface GitHostAccount {
+  host: GitHost
+  login: string
+  /** Self-managed GitLab API base; null for the public cloud. */
+  api_base: string | null
+}
+
+export interface HostedRepoSummary {
+  full_name: string
+  default_branch: string | null
+  web_url: string
+}
+
+export interface HostedSkillPushResult {
+  repo: string
+  branch: string
+  skill_path: string
+  files: string[]
+  skipped: string[]
+  manifest_updated: boolean
+  commit_sha: string | null
+  /** Set for branch-per-skill pushes */
+  review?: SkillUpstreamReview | null
+  /** Markdown description attached to `review` */
+  review_description?: string | null
+  /** True when the local SKILL.md was replaced by a merge with the remote copy */
+  skill_md_merged: boolean
+  /** Set when SKILL.md edits overlap; nothing was pushed */
+  conflict: SkillPushConflict | null
+}
+
+export interface SkillMergeHunk {
+  local_start: number
+  remote_start: number
+  base: string
+  local: string
+  remote: string
+}
+
+export interface SkillPushConflict {
+  path: string
+  remote_digest: string
+  merged_with_markers: string
+  hunks: SkillMergeHunk[]
+}
+
+export type PushStrategy = "direct" | "branch-per-skill"
+
+export type UpstreamReviewState = "open" | "merged" | "closed"
+
+export interface SkillUpstreamReview {
+  skill_name: string
+  host: GitHost
+  repo: string
+  repo_url: string
+  branch: string
+  base_branch: string
+  number: number
+  url: string
+  state: UpstreamReviewState
+  commit_sha: string | null
+  pushed_at: string
+  updated_at: string
+}
+
 export interface SkillSummary {
   name: string
   current_step: string | null
@@ -105,6 +331,7 @@ export interface SkillSummary {
   argumentHint?: string | null
   userInvocable?: boolean | null
   disableModelInvocation?: boolean | null
+  review_by?: string | null
 }
 
 export interface SkillFileContent {
@@ -116,6 +343,8 @@ export interface RefineFileDiff {
   path: string
   status: string
   diff: string
+  /** "accepted" when the refine session accepted this exact change */
+  review?: "accepted" | null
 }
 
 export interface RefineDiff {
@@ -123,12 +352,125 @@ export interface RefineDiff {
   files: RefineFileDiff[]
 }
 
+export interface FrontmatterFieldChange {
+  field: string
+  old_value: string | null
+  new_value: string | null
+}
+
+export interface WordDiffSegment {
+  kind: "equal" | "insert" | "delete"
+  text: string
+}
+
+export interface StructuredDiffHunk {
+  /** Stable across recomputations of an unchanged diff; pass to applyPartialDiff. */
+  id: string
+  old_start: number
+  new_start: number
+  old_text: string
+  new_text: string
+  words: WordDiffSegment[]
+}
+
+export interface StructuredFileDiff {
+  path: string
+  status: "added" | "modified" | "deleted"
+  /** False for binary files, which have no frontmatter changes or hunks. */
+  text: boolean
+  frontmatter: FrontmatterFieldChange[]
+  hunks: StructuredDiffHunk[]
+}
+
+export interface SkillFileChangeStat {
+  path: string
+  status: "added" | "modified" | "deleted"
+  text: boolean
+  lines_added: number
+  lines_removed: number
+}
+
+/** Summary of how a skill differs between two refs (commit SHA, "working" or "team"). */
+export interface SkillVersionComparison {
+  skill_name: string
+  base_ref: string
+  target_ref: string
+  frontmatter: FrontmatterFieldChange[]
+  added_references: string[]
+  removed_references: string[]
+  modified_references: string[]
+  body_hunks: number
+  body_lines_added: number
+  body_lines_removed: number
+  files: SkillFileChangeStat[]
+}
+
 export interface RefineSessionInfo {
   session_id: string
   skill_name: string
   created_at: string
 }
 
+export interface RefineSessionMessage {
+  role: "user" | "agent"
+  text: string | null
+  target_files: string[]
+  command: string | null
+  agent_id: string | null
+  created_at: string
+}
+
+export interface ResumableRefineSession {
+  session_id: string
+  skill_name: string
+  message_count: number
+  last_user_message: string | null
+  has_pending_changes: boolean
+  can_resume_conversation: boolean
+  created_at: string
+  updated_at: string
+}
+
+export interface ResumedRefineSession {
+  session: RefineSessionInfo
+  messages: RefineSessionMessage[]
+  pending_diff: RefineDiff
+  can_resume_conversation: boolean
+}
+
+export type RefineBatchItemStatus =
+  | "queued"
+  | "running"
+  | "review"
+  | "no_changes"
+  | "failed"
+  | "approved"
+  | "rejected"
+
+export interface RefineBatchItem {
+  skill_name: string
+  position: number
+  status: RefineBatchItemStatus
+  agent_id: string | null
+  /** The agent's changes, kept after review for the record. */
+  diff: RefineDiff | null
+  error: string | null
+  cost_usd: number
+  updated_at: string
+}
+
+/** One refine instruction run against several skills in turn. */
+export interface RefineBatch {
+  batch_id: string
+  instruction: string
+  workspace_path: string
+  /** Usage session every agent run of the batch is recorded under. */
+  workflow_session_id: string
+  items: RefineBatchItem[]
+  total_cost_usd: number
+  created_at: string
+}
+
 
 export interface NodeStatus {
   available: boolean
@@ -152,9 +494,96 @@ export interface StartupDeps {
   checks: DepStatus[]
 }
 
+export interface DbLockStats {
+  acquisitions: number
+  total_wait_ms: number
+  max_wait_ms: number
+  /** Waits of 250 ms or more */
+  slow_waits: number
+  max_hold_ms: number
+  slow_holds: number
+}
+
+export interface DbContention {
+  writer: DbLockStats
+  readers: DbLockStats
+  /** 0 when reads share the writer */
+  read_connections: number
+  reader_fallbacks: number
+}
+
+export interface DiagnosticsReport {
+  generated_at: string
+  app_version: string
+  os: string
+  all_ok: boolean
+  checks: DepStatus[]
+}
+
 export interface PackageResult {
   file_path: string
   size_bytes: number
+  lint_warnings?: LintIssue[]
+  /** Present when a quality gate profile is selected in settings */
+  quality_gate?: QualityGateReport | null
+  validation?: SkillDiagnostic[]
+  /** Transitive dependencies listed in the package, in install order */
+  dependencies?: string[]
+  /** Languages of the SKILL.<lang>.md translations bundled in the package */
+  locales?: string[]
+  /** Layout the package was written in */
+  format?: PackageFormat
+}
+
+/** Package layouts `package_skill` can write. */
+export type PackageFormat = "generic-zip" | "claude-code" | "claude-desktop"
+
+/** A SKILL.<lang>.md translation of a skill. */
+export interface SkillTranslation {
+  language: string
+  file_name: string
+  /** SKILL.md changed after the translation was written */
+  stale: boolean
+}
+
+/** Result of `reload_prompts`. */
+export interface PromptReload {
+  checksum: string
+  previous_checksum: string | null
+  changed: boolean
+  /** Custom templates that replaced a bundled one */
+  overridden: string[]
+  /** Custom templates with no bundled counterpart (not deployed) */
+  ignored: string[]
+}
+
+/** Which network-backed features are available under the current settings. */
+export interface NetworkFeatures {
+  offline_mode: boolean
+  marketplace: boolean
+  github: boolean
+  /** Offline mode has both a local endpoint and model to run agents against */
+  local_model_ready: boolean
+}
+
+export type DependencyPackagingMode = "verify" | "bundle"
+
+export interface SkillDependencyNode {
+  name: string
+  /** False when the skill is neither built nor installed */
+  available: boolean
+  version: string | null
+}
+
+export interface SkillDependencyEdge {
+  skill_name: string
+  depends_on: string
+}
+
+export interface SkillDependencyGraph {
+  nodes: SkillDependencyNode[]
+  edges: SkillDependencyEdge[]
+  cycles: string[][]
 }
 
 export interface OrphanSkill {
@@ -195,6 +624,7 @@ export interface AgentRunRecord {
   session_id: string | null
   started_at: string
   completed_at: string | null
+  retry_count: number
 }
 
 export interface WorkflowSessionRecord {
@@ -220,6 +650,18 @@ export interface UsageSummary {
   avg_cost_per_run: number
 }
 
+/** Spend since the last usage reset against the configured budgets. */
+export interface BudgetStatus {
+  skill_name: string | null
+  skill_spent_usd: number
+  skill_budget_usd: number | null
+  global_spent_usd: number
+  global_budget_usd: number | null
+  /** `warning` once a budget is at least 80% used */
+  state: "ok" | "warning" | "exceeded"
+  exceeded_scope: "skill" | "global" | null
+}
+
 export interface UsageByStep {
   step_id: number
   step_name: string
@@ -240,6 +682,44 @@ export interface UsageByDay {
   run_count: number
 }
 
+export interface SkillInvocationImport {
+  source_dir: string
+  files_scanned: number
+  invocations_matched: number
+  invocations_added: number
+  /** Invocations of other skills and built-in commands, not stored */
+  invocations_unmatched: number
+}
+
+/** How often a skill was invoked in Claude Code; zero counts when never. */
+export interface SkillInvocationStats {
+  skill_name: string
+  invocations: number
+  model_invocations: number
+  user_invocations: number
+  sessions: number
+  projects: number
+  first_invoked_at: string | null
+  last_invoked_at: string | null
+}
+
+/** Lifetime usage of one skill since the last usage reset. */
+export interface SkillAnalytics {
+  skill_name: string
+  total_cost: number
+  run_count: number
+  refine_sessions: number
+  /** Completion time of the latest successful workflow step run */
+  last_regenerated_at: string | null
+  avg_step_duration_ms: number | null
+  input_tokens: number
+  output_tokens: number
+  cache_read_tokens: number
+  cache_write_tokens: number
+  /** Cache reads over all prompt tokens; null when no prompt tokens were used */
+  cache_hit_ratio: number | null
+}
+
 export interface ImportedSkill {
   skill_id: string
   skill_name: string
@@ -296,6 +776,67 @@ export interface AvailableSkill {
   argument_hint: string | null
   user_invocable: boolean | null
   disable_model_invocation: boolean | null
+  variables?: SkillVariable[]
+}
+
+/** Cumulative progress of a GitHub listing or import, from the `github-import-progress` event. */
+export interface ImportProgress {
+  operation_id: string
+  phase: "discovering" | "reading_metadata" | "downloading" | "finished" | "cancelled"
+  repos_scanned: number
+  tree_entries: number
+  skills_found: number
+  skills_processed: number
+  files_downloaded: number
+  bytes_downloaded: number
+  current: string | null
+}
+
+/** Retained output of one sub-agent call during a workflow step. */
+export interface StepArtifact {
+  seq: number
+  tool_use_id: string
+  subagent: string
+  description: string | null
+  /** Relative to `{workspace}/{skill}`; null when the step's size cap was reached */
+  relative_path: string | null
+  bytes: number
+  truncated: boolean
+  is_error: boolean
+}
+
+export interface StepArtifactIndex {
+  skill_name: string
+  step_id: number
+  /** Transcript file under `{skill}/logs/` the artifacts came from */
+  transcript: string
+  captured_at: string
+  artifacts: StepArtifact[]
+}
+
+export type PathKind = "file" | "directory" | "any"
+
+/** Result of normalizing a user-entered path (`check_path_input`). */
+export interface PathCheck {
+  input: string
+  /** Canonical absolute path to store */
+  path: string
+  /** `path` with the home directory shown as `~` */
+  display: string
+  exists: boolean
+  is_dir: boolean
+  readable: boolean
+  writable: boolean
+  warnings: string[]
+}
+
+/** Template variable declared in SKILL.md frontmatter, resolved at install time. */
+export interface SkillVariable {
+  name: string
+  description: string | null
+  default: string | null
+  required: boolean
+  allowed: string[]
 }
 
 export interface SkillMetadataOverride {
@@ -340,5 +881,985 @@ export interface SkillFileMeta {
   argument_hint: string | null
   user_invocable: boolean | null
   disable_model_invocation: boolean | null
+  variables?: SkillVariable[]
+  /** From the package's CHECKLIST.md, or derived from SKILL.md when absent */
+  checklist?: OnboardingChecklist
+}
+
+/** What a consumer needs to set up before using a skill. */
+export interface OnboardingChecklist {
+  mcp_servers: string[]
+  credentials: string[]
+  environment: string[]
+  first_prompts: string[]
+}
+
+
+/** State of one skill at a past point in time. */
+export interface LibrarySkillState {
+  skill_name: string
+  /** `built` (git history of the skills folder), `workspace` or `marketplace` */
+  source: "built" | "workspace" | "marketplace"
+  version: string | null
+  is_active: boolean
+  /** When this state began */
+  since: string
+}
+
+export interface BenchmarkScenarioResult {
+  name: string
+  prompt: string
+  /** Judge scores, 1-10 */
+  skill_score: number
+  baseline_score: number
+  winner: "skill" | "baseline" | "tie"
+  reason: string
+  skill_tokens: number
+  baseline_tokens: number
+  skill_ms: number
+  baseline_ms: number
+}
+
+/** A skill version benchmarked against the no-skill baseline. */
+export interface SkillBenchmark {
+  id: number
+  skill_name: string
+  skill_version: string | null
+  scenario_set: string
+  model: string
+  judge_model: string
+  skill_wins: number
+  baseline_wins: number
+  ties: number
+  avg_skill_score: number
+  avg_baseline_score: number
+  token_overhead_pct: number
+  latency_overhead_pct: number
+  results: BenchmarkScenarioResult[]
+  created_at: string
+}
+
+/** A saved prompt a skill is regression-tested against. */
+export interface SkillTestCase {
+  id: number
+  skill_name: string
+  name: string
+  prompt: string
+  /** Whether the agent should load the skill for this prompt */
+  expect_trigger: boolean
+  /** Substrings the response must contain (case-insensitive) */
+  expected_contains: string[]
+  created_at: string
+}
+
+export interface SkillTestResult {
+  id: number
+  test_case_id: number
+  test_name: string
+  skill_name: string
+  run_id: string
+  skill_version: string | null
+  triggered: boolean
+  passed: boolean
+  response: string
+  /** Why the case failed; empty when it passed */
+  failures: string[]
+  /** Set when the agent itself failed to run */
+  error: string | null
+  duration_ms: number
+  created_at: string
+}
+
+export interface SkillTestRun {
+  run_id: string
+  skill_name: string
+  passed: number
+  failed: number
+  results: SkillTestResult[]
+}
+
+/** An input a workflow step reads, and whether it is ready. */
+export interface StepInput {
+  label: string
+  /** Relative to the skill's workspace directory; null for DB-held inputs */
+  path: string | null
+  available: boolean
+}
+
+/** State-aware explanation of an upcoming workflow step. */
+export interface StepExplanation {
+  skill_name: string
+  step_id: number
+  step_name: string
+  summary: string
+  reads: StepInput[]
+  writes: string[]
+  history_runs: number
+  /** `skill` when estimates come from this skill's runs, `all` otherwise */
+  history_scope: string
+  expected_cost_usd: number | null
+  expected_duration_ms: number | null
+  prepare: string[]
+}
+
+/** In-progress run whose completed step outputs follow an older prompt contract. */
+export interface ContractUpgrade {
+  skill_name: string
+  current_step: number
+  from_version: number
+  to_version: number
+  changes: string[]
+  /** 0-based step id re-run resets to */
+  rerun_from_step: number
+  can_transform: boolean
+}
+
+export interface CommandHistoryEntry {
+  id: number
+  command: string
+  /** Invocation arguments; secret values are replaced with `[REDACTED]`. */
+  params: unknown
+  redacted: boolean
+  /** `ok` or `error` */
+  outcome: string
+  error: string | null
+  duration_ms: number
+  created_at: string
+}
+
+export interface CommandHistoryFilter {
+  /** Substring match on command name and arguments */
+  query?: string | null
+  command?: string | null
+  outcome?: string | null
+  /** Only entries at or after this `YYYY-MM-DD HH:MM:SSZ` timestamp */
+  since?: string | null
+  limit?: number | null
+}
+
+export interface CommandRerun {
+  command: string
+  params: unknown
+}
+
+export interface LibrarySnapshot {
+  at: string
+  commit_sha: string | null
+  commit_message: string | null
+  skills: LibrarySkillState[]
+}
+
+/** Step metrics for one app/prompt version; `*_change_pct` compares with the previous version of the same step. */
+export interface StepPerfTrend {
+  step_id: number
+  app_version: string
+  prompt_version: string | null
+  runs: number
+  avg_prompt_tokens: number
+  avg_output_tokens: number
+  avg_cost: number
+  avg_time_to_first_token_ms: number | null
+  avg_duration_ms: number
+  first_seen: string
+  last_seen: string
+  prompt_tokens_change_pct: number | null
+  cost_change_pct: number | null
+  time_to_first_token_change_pct: number | null
+  duration_change_pct: number | null
 }
 
+/** A replay of a historical step run; outputs land under `output_dir` (`workspace/` and `skills/`). */
+export interface StepReplay {
+  replay_id: string
+  agent_id: string
+  source_agent_id: string
+  skill_name: string
+  step_id: number
+  output_dir: string
+  model: string
+  captured_at: string
+  captured_app_version: string
+  current_app_version: string
+  captured_prompt_version: string | null
+  current_prompt_version: string | null
+}
+
+/** Pre-run cost of a step on one model; low..high covers the middle 80% of comparable runs. */
+export interface ModelCostEstimate {
+  model: string
+  expected_cost: number
+  low_cost: number
+  high_cost: number
+  basis: "history" | "blended" | "size"
+  confidence: "high" | "medium" | "low"
+  sample_runs: number
+}
+
+export interface StepCostEstimate {
+  skill_name: string
+  step_id: number
+  prompt_template_bytes: number
+  context_bytes: number
+  skill_bytes: number
+  input_tokens: number
+  estimates: ModelCostEstimate[]
+}
+
+export interface DuplicateSkillOptions {
+  /** Paths under `references/` to copy; null copies all of them. */
+  references: string[] | null
+  copy_tags: boolean
+  copy_intake: boolean
+  copy_clarifications: boolean
+  /** Implies copy_clarifications. */
+  copy_decisions: boolean
+}
+
+export interface DuplicateSkillResult {
+  skill_name: string
+  current_step: number
+  status: string
+  references: string[]
+}
+
+export interface SkillArchiveExportResult {
+  file_path: string
+  size_bytes: number
+  file_count: number
+  /** Key the archive was signed with; null when no signing key exists yet */
+  signed_key_id: string | null
+}
+
+/** One skill's document in a Claude Project context bundle. */
+export interface ProjectContextSkill {
+  skill_name: string
+  version: string | null
+  /** File name inside the bundle, e.g. `standup.md` */
+  document: string
+  /** Estimated tokens */
+  tokens: number
+  /** True when SKILL.md sections were dropped to fit the budget */
+  condensed: boolean
+  included_references: string[]
+  omitted_references: string[]
+}
+
+export interface ProjectContextPackage {
+  output_dir: string
+  index_file: string
+  budget_tokens: number
+  total_tokens: number
+  skills: ProjectContextSkill[]
+}
+
+export interface SkillArchiveImportResult {
+  skill_name: string
+  current_step: number
+  status: string
+  file_count: number
+  signature_status: "unsigned" | "trusted" | "untrusted"
+}
+
+/** Library-wide SKILL.md frontmatter change. `script` receives SKILL.md on stdin (with `SKILL_NAME` set) and prints the new file. */
+export type FrontmatterTransform =
+  | { kind: "set_field"; key: string; value: string; overwrite?: boolean }
+  | { kind: "rename_field"; from: string; to: string }
+  | { kind: "remove_field"; key: string }
+  | { kind: "split_description"; summary_key?: string; trigger_key?: string; keep_description?: boolean }
+  | { kind: "script"; command: string }
+
+export interface FrontmatterMigrationPreview {
+  skill_name: string
+  changed: boolean
+  /** Full SKILL.md before and after; null when unchanged or failed */
+  before: string | null
+  after: string | null
+  error: string | null
+}
+
+export interface FrontmatterMigrationResult {
+  /** Commit to pass to `rollbackFrontmatterMigration`; null when nothing changed */
+  commit_sha: string | null
+  skills: string[]
+}
+
+/** A change `bulkUpdateSkills` applies to every listed skill. */
+export type BulkSkillOperation =
+  | { kind: "add_tags"; tags: string[] }
+  | { kind: "remove_tags"; tags: string[] }
+  /** Intake `domain` of skills built in Skill Builder; null clears it */
+  | { kind: "set_domain"; domain: string | null }
+  | { kind: "deactivate" }
+
+/** What a bulk operation did, or would do, to one skill. */
+export interface BulkSkillChange {
+  skill_name: string
+  changed: boolean
+  /** e.g. `tags: a, b -> a, b, c`, or why the skill was skipped */
+  summary: string
+  /** Set when the DB change committed but moving or removing files failed */
+  error: string | null
+  /** Tags the change would set that the team taxonomy flags */
+  warnings: TaxonomyWarning[]
+}
+
+export interface BulkSkillResult {
+  dry_run: boolean
+  changes: BulkSkillChange[]
+}
+
+/** One entry of the append-only audit log. */
+export interface AuditLogEntry {
+  id: number
+  created_at: string
+  /** App instance that made the change */
+  instance_id: string
+  /** GitHub login, else the OS user */
+  actor: string | null
+  /** e.g. `skill.renamed` or `settings.changed` */
+  action: string
+  skill_name: string | null
+  summary: string
+}
+
+export interface AuditLogFilter {
+  action?: string | null
+  skill_name?: string | null
+  instance_id?: string | null
+  actor?: string | null
+  /** Substring match on the summary */
+  query?: string | null
+  /** Only entries at or after this `YYYY-MM-DD HH:MM:SSZ` timestamp */
+  since?: string | null
+  until?: string | null
+  /** Defaults to 200, capped at 1000; exports are not limited */
+  limit?: number | null
+}
+
+export type AuditLogFormat = "csv" | "jsonl"
+
+export interface AuditLogExport {
+  output_path: string
+  entry_count: number
+}
+
+/** What `purge_all_user_data` deleted. Skills, settings and the audit log are kept. */
+export interface UserDataPurgeResult {
+  /** Agent runs, tool calls, ended workflow sessions and other usage history rows */
+  usage_rows: number
+  transcripts: number
+  /** Transcript files deleted from each skill's logs folder */
+  transcript_files: number
+  /** Whether a GitHub token or git host credentials were removed */
+  credentials_cleared: boolean
+}
+
+/** A level of the tag hierarchy; parents exist even when no skill carries them directly. */
+export interface TagNode {
+  /** Last level of the path, e.g. `rev-rec` */
+  name: string
+  /** e.g. `finance/revenue/rev-rec` */
+  path: string
+  /** Skills tagged with exactly this path */
+  direct_count: number
+  /** Skills tagged with this path or anything under it */
+  skill_count: number
+  children: TagNode[]
+}
+
+/** An alternative spelling rewritten to `tag` whenever skill tags are saved. */
+export interface TagAlias {
+  alias: string
+  tag: string
+}
+
+export interface TagTree {
+  tags: TagNode[]
+  aliases: TagAlias[]
+  /** Team taxonomy warnings for the skills a rename or merge just retagged */
+  warnings: TaxonomyWarning[]
+}
+
+/** A non-blocking team taxonomy violation for one skill field. */
+export interface TaxonomyWarning {
+  skill_name: string
+  field: "tag" | "domain" | "name"
+  value: string
+  message: string
+}
+
+export type BackgroundTaskKind = "catalog_prefetch" | "retention_prune"
+
+/** `low` waits while agents run or the battery is low; `normal` waits for agents; `high` runs next. */
+export type TaskPriority = "low" | "normal" | "high"
+
+export interface BackgroundTask {
+  id: number
+  kind: BackgroundTaskKind
+  priority: TaskPriority
+  status: "queued" | "running" | "completed" | "failed" | "cancelled"
+  deferred_reason: string | null
+  enqueued_at: string
+  started_at: string | null
+  finished_at: string | null
+  error: string | null
+}
+
+export interface PowerState {
+  on_battery: boolean
+  battery_percent: number | null
+}
+
+export interface BackgroundQueue {
+  tasks: BackgroundTask[]
+  agents_running: boolean
+  /** Null when the power state can't be read */
+  power: PowerState | null
+}
+
+/** One step of a skill's workflow: built in, or added by a workflow manifest. */
+export interface WorkflowStepConfig {
+  step_id: number
+  name: string
+  prompt_template: string
+  output_file: string
+  allowed_tools: string[]
+  max_turns: number
+  thinking_budget: number | null
+  description: string | null
+  custom: boolean
+}
+
+/** A workflow step queued to run unattended. */
+export interface WorkflowQueueJob {
+  id: number
+  skill_name: string
+  step_id: number
+  workspace_path: string
+  status: "queued" | "running" | "completed" | "failed" | "cancelled"
+  error: string | null
+  enqueued_at: string
+  started_at: string | null
+  finished_at: string | null
+}
+
+/** A skill reference file kept in sync with a file in a GitHub repo. */
+export interface LinkedReference {
+  skill_name: string
+  /** Path inside the skill, e.g. `references/style-guide.md` */
+  reference_path: string
+  /** `owner/repo` */
+  repo: string
+  git_ref: string
+  source_path: string
+  synced_sha: string
+  synced_at: string
+  upstream_sha: string | null
+  checked_at: string | null
+  drift: "current" | "upstream_changed" | "local_modified" | "upstream_missing" | "local_missing"
+}
+
+export interface LinkedReferenceRefresh {
+  reference_path: string
+  drift: LinkedReference["drift"]
+  current_content: string | null
+  /** Latest upstream content; null when identical to the file on disk or unavailable */
+  upstream_content: string | null
+  upstream_sha: string | null
+  error: string | null
+}
+
+export interface ApprovedReferenceUpdate {
+  reference_path: string
+  content: string
+  upstream_sha: string
+}
+
+/** A file in a skill's `references/` folder. */
+export interface SkillReference {
+  /** Path inside the skill, e.g. `references/style-guide.md` */
+  path: string
+  size_bytes: number
+  binary: boolean
+  /** Mentioned in SKILL.md */
+  indexed: boolean
+  /** Kept in sync with GitHub (see linkReference) */
+  linked: boolean
+}
+
+export interface RepoContextFile {
+  /** Path relative to the repo root */
+  path: string
+  size_bytes: number
+  truncated: boolean
+}
+
+export interface RepoContextResult {
+  files: RepoContextFile[]
+  /** Matching files left out: binary, or over the total size budget */
+  skipped: string[]
+  /** Paths written, relative to the skill directory */
+  reference_paths: string[]
+  /** null when the structure summary failed */
+  summary_model: string | null
+}
+
+export interface BashSandboxPolicy {
+  enabled: boolean
+  /** Command patterns; `*` is a wildcard and a plain pattern also matches extra arguments */
+  allow_patterns: string[]
+  deny_patterns: string[]
+  allow_network: boolean
+}
+
+/** How a tool policy trims a workflow step's tools. */
+export type StepToolRole = "full" | "no_shell" | "read_only"
+
+export interface ToolPolicy {
+  bash_sandbox: BashSandboxPolicy
+  /** Role per workflow step id; steps not listed keep their declared tools */
+  step_roles?: Record<string, StepToolRole>
+}
+
+/** One tool call from an agent run's audit log. */
+export interface AgentToolCall {
+  id: number
+  agent_id: string
+  skill_name: string
+  step_id: number
+  tool_use_id: string
+  tool_name: string
+  input_summary: string
+  /** null for sub-agent calls and streaming sessions */
+  allowed: boolean | null
+  created_at: string
+}
+
+export interface ToolPolicyView {
+  global: ToolPolicy
+  /** The skill's own policy; null when it inherits the global one */
+  skill_override: ToolPolicy | null
+  effective: ToolPolicy
+}
+
+export interface TranscriptInfo {
+  file_name: string
+  compressed: boolean
+  /** Size on disk (compressed size for `.jsonl.zst`) */
+  size_bytes: number
+  uncompressed_bytes: number | null
+  turns: number | null
+}
+
+export interface TranscriptTurns {
+  file_name: string
+  total_turns: number
+  start_turn: number
+  /** Messages of each turn; lines that are not valid JSON come back as strings */
+  turns: unknown[][]
+}
+
+/** A finished agent run's transcript from the archive. */
+export interface AgentTranscript {
+  agent_id: string
+  skill_name: string
+  message_count: number
+  tool_call_count: number
+  uncompressed_bytes: number
+  compressed_bytes: number
+  archived_at: string
+  /** Thinking blocks are cut down to a summary; lines that are not valid JSON come back as strings */
+  messages: unknown[]
+}
+
+export interface TranscriptSearchHit {
+  agent_id: string
+  skill_name: string
+  archived_at: string
+  /** Index into `AgentTranscript.messages` */
+  message_index: number
+  message_type: string
+  snippet: string
+}
+
+export interface SkillDependent {
+  skill_name: string
+  /** Files (relative to the dependent skill) that mention the skill */
+  files: string[]
+}
+
+export interface SkillInstallation {
+  location: "skills_library" | "workspace" | "workspace_inactive" | "user"
+  path: string
+}
+
+export interface SkillTeamConsumer {
+  registry: string
+  registry_name: string | null
+  skill_path: string
+  version: string | null
+}
+
+export interface SkillImpactReport {
+  skill_name: string
+  dependents: SkillDependent[]
+  installations: SkillInstallation[]
+  team_consumers: SkillTeamConsumer[]
+  recent_runs: {
+    days: number
+    runs: number
+    completed_runs: number
+    total_cost: number
+    last_run_at: string | null
+  }
+  open_refine_sessions: number
+  active_workflow_session: boolean
+}
+
+/** Two skills whose SKILL.md content looks like copies of each other. */
+export interface SimilarSkill {
+  skill_name: string
+  location: "skills_library" | "workspace" | "workspace_inactive"
+  similar_to: string
+  similar_location: "skills_library" | "workspace" | "workspace_inactive"
+  /** 0..1; pairs at or above the threshold (0.5 by default) are reported. */
+  score: number
+  content_similarity: number
+  description_similarity: number | null
+}
+
+export interface LintIssue {
+  file: string
+  line: number
+  rule: "image-alt-missing" | "heading-first-level" | "heading-skip"
+  message: string
+}
+
+export interface SkillLintReport {
+  skill_name: string
+  files_checked: number
+  issues: LintIssue[]
+}
+
+export type DiagnosticSeverity = "error" | "warning" | "info"
+
+export interface SkillDiagnostic {
+  file: string
+  line: number | null
+  rule: string
+  severity: DiagnosticSeverity
+  message: string
+}
+
+/** Bundled help for a command error, from `get_error_help`. */
+export interface ErrorHelp {
+  /** "unknown" when no help entry matched */
+  code: string
+  title: string
+  message: string
+  steps: string[]
+  /** Settings section to open, e.g. "general" */
+  settings_tab: string | null
+}
+
+export interface SkillValidationReport {
+  skill_name: string
+  files_checked: number
+  error_count: number
+  warning_count: number
+  diagnostics: SkillDiagnostic[]
+}
+
+/** SKILL.md as the model reads it, structured for the preview. */
+export interface SkillPreview {
+  skill_name: string
+  /** Frontmatter keys in file order */
+  fields: { key: string; value: string }[]
+  /** The description, with the sentences saying when to use the skill as `trigger` spans */
+  description: PreviewInline[]
+  blocks: PreviewBlock[]
+  /** Skill files linked from the body, in order of first link */
+  references: ReferenceStub[]
+}
+
+export type PreviewBlock =
+  | { type: "heading"; level: number; content: PreviewInline[] }
+  | { type: "paragraph"; content: PreviewInline[] }
+  | { type: "list"; ordered: boolean; items: { depth: number; content: PreviewInline[] }[] }
+  | { type: "code"; language: string | null; text: string }
+  | { type: "quote"; content: PreviewInline[] }
+  | { type: "table"; header: PreviewInline[][]; rows: PreviewInline[][][] }
+  | { type: "rule" }
+
+/** Inline markdown; raw HTML arrives as plain `text`. */
+export type PreviewInline =
+  | { type: "text"; text: string }
+  | { type: "code"; text: string }
+  | { type: "strong"; text: string }
+  | { type: "emphasis"; text: string }
+  /** `reference` matches a `ReferenceStub.path`; null for external links and anchors */
+  | { type: "link"; text: string; href: string; reference: string | null }
+  | { type: "trigger"; text: string }
+
+/** What the model learns about a linked file before opening it. */
+export interface ReferenceStub {
+  path: string
+  exists: boolean
+  title: string | null
+  summary: string | null
+  lines: number
+  bytes: number
+}
+
+export type QualityCheck = "lint" | "size" | "secrets" | "readability" | "compatibility"
+
+export interface QualityBudgets {
+  skill_md_max_lines: number
+  total_max_kb: number
+  max_sentence_words: number
+}
+
+/** Named set of checks enforced at packaging, from the team taxonomy or built in. */
+export interface QualityGateProfile {
+  name: string
+  description: string | null
+  checks: QualityCheck[]
+  budgets: QualityBudgets
+}
+
+export interface QualityWaiver {
+  skill_name: string
+  check: QualityCheck
+  justification: string
+  waived_by: string | null
+  waived_at: string
+}
+
+/** One version bump, recorded when step 3 regenerates SKILL.md or beta is promoted to stable. */
+export interface SkillChangelogEntry {
+  skill_name: string
+  version: string
+  previous_version: string | null
+  bump: "major" | "minor" | "patch" | "initial"
+  summary: string
+  previous_sha: string | null
+  created_at: string
+}
+
+export type SkillSlotName = "stable" | "beta"
+
+export interface SkillSlot {
+  slot: SkillSlotName
+  version: string | null
+  path: string
+  package_path: string | null
+  promoted_at: string | null
+}
+
+/** Stable and beta slots of a skill and which one `~/.claude/skills` has on this machine. */
+export interface SkillSlotsStatus {
+  skill_name: string
+  stable: SkillSlot | null
+  beta: SkillSlot
+  installed_slot: SkillSlotName | null
+  install_path: string
+}
+
+/** A reviewer comment on a line range of a skill's context file. */
+export interface ArtifactAnnotation {
+  id: number
+  skill_name: string
+  /** Relative to the skill's workspace directory, e.g. `context/clarifications.json`. */
+  file: string
+  /** 1-based, inclusive. */
+  start_line: number
+  end_line: number
+  anchor_text: string
+  comment: string
+  author: string | null
+  /** The annotated lines could no longer be found after the file changed. */
+  orphaned: boolean
+  created_at: string
+  updated_at: string
+}
+
+/** Where a workflow step run got to, from `find_resumable_step_run`. */
+export interface AgentRunCheckpoint {
+  agent_id: string
+  skill_name: string
+  step_id: number
+  pid: number
+  /** Claude SDK session the resumed run continues. */
+  sdk_session_id: string | null
+  /** JSONL transcript of the run so far. */
+  transcript_path: string | null
+  /** Top-level assistant turns completed before it stopped. */
+  turns: number
+  status: "running" | "completed" | "interrupted"
+  resumed_from: string | null
+  started_at: string
+  updated_at: string
+}
+
+/** A completed run of a workflow step whose outputs can be restored instead of re-running the agent. */
+export interface CachedStepRun {
+  skill_name: string
+  step_id: number
+  /** Hash of the step's inputs when the run started. */
+  fingerprint: string
+  agent_id: string
+  model: string
+  /** What that run cost, i.e. what reusing it saves. */
+  cost_usd: number
+  created_at: string
+}
+
+/** A capture of a skill's context, artifacts, skill files and workflow state that can be restored. */
+export interface WorkspaceSnapshot {
+  /** Hash of the captured content; identical captures share an id. */
+  id: string
+  skill_name: string
+  reason: string
+  created_at: string
+  current_step: number | null
+  file_count: number
+  total_bytes: number
+}
+
+export interface QualityCheckResult {
+  check: QualityCheck
+  enforced: boolean
+  passed: boolean
+  findings: string[]
+  waiver: QualityWaiver | null
+}
+
+export interface QualityGateReport {
+  skill_name: string
+  profile: string
+  /** False when an enforced check failed without a waiver */
+  passed: boolean
+  checks: QualityCheckResult[]
+}
+
+export interface ScanFinding {
+  /** Path relative to the skill directory */
+  file: string
+  line: number
+  rule: "instruction-override" | "exfiltration-url" | "frontmatter-command" | "hidden-text"
+  severity: "high" | "medium"
+  excerpt: string
+}
+
+export interface DecisionDelta {
+  id: string
+  title: string
+  change: "added" | "changed" | "removed"
+  previous: string | null
+  decision: string | null
+}
+
+export interface RegenerationMode {
+  /** True when step 3 would patch only the sections affected by `changes`. */
+  incremental: boolean
+  /** Why step 3 would rewrite SKILL.md in full; null when incremental. */
+  reason: string | null
+  changes: DecisionDelta[]
+}
+
+/** A bundled starter scaffold; `{{title}}` is replaced with the skill's title on creation. */
+export interface SkillTemplate {
+  id: string
+  name: string
+  skill_type: Purpose
+  summary: string
+  description: string
+  intake_json: string
+  skill_md: string
+}
+
+export type IntakeQuestionKind = "text" | "long_text" | "single_choice" | "multi_choice" | "boolean"
+
+export type IntakeAnswer = string | string[] | boolean
+
+export interface IntakeQuestion {
+  id: string
+  prompt: string
+  help: string | null
+  kind: IntakeQuestionKind
+  options: string[]
+  required: boolean
+  /** Shown only when the answer to `question_id` is (or, for multi-choice, contains) one of `equals`; "true"/"false" for yes/no */
+  show_if: { question_id: string; equals: string[] } | null
+}
+
+/** Visible intake questions for the answers so far; hidden branches are left out. */
+export interface IntakeQuestionnaire {
+  schema_version: number
+  skill_type: Purpose
+  questions: IntakeQuestion[]
+  answers: Record<string, IntakeAnswer>
+  next_question_id: string | null
+}
+
+/** Saved only when `errors` is empty. */
+export interface IntakeSubmission {
+  errors: { question_id: string; message: string }[]
+  intake_json: string | null
+}
+
+export interface SkillScanReport {
+  skill_name: string
+  files_scanned: number
+  /** True when any finding is high severity */
+  flagged: boolean
+  /** True when the import scan deactivated the skill */
+  quarantined: boolean
+  findings: ScanFinding[]
+  scanned_at: string
+}
+
+export interface AccessibilityFixResult {
+  skill_name: string
+  files_changed: string[]
+  headings_fixed: number
+  alt_texts_added: number
+  remaining: LintIssue[]
+  commit_sha: string | null
+}
+
+export type SkillReviewState = "ok" | "due_soon" | "expired"
+
+export interface SkillReviewStatus {
+  skill_name: string
+  /** YYYY-MM-DD */
+  review_by: string
+  reviewed_at: string | null
+  state: SkillReviewState
+  /** Negative once the review-by date has passed. */
+  days_remaining: number
+}
+
+export type SkillGrouping = "domain" | "type" | "owner" | "collection" | "health"
+
+export interface SkillGroup {
+  /** Null for skills without a value for the grouping (e.g. untagged). */
+  key: string | null
+  skill_count: number
+  total_cost: number
+  /** Skills past their review-by date. */
+  stale_count: number
+  skill_names: string[]
+}
+
+/** What scrubbing removed from one feedback attachment. */
+export interface ScrubbedAttachment {
+  name: string
+  kind: "jpeg" | "png" | "text" | "binary"
+  original_bytes: number
+  scrubbed_bytes: number
+  /** Metadata blocks dropped, e.g. `EXIF`, `PNG tEXt` */
+  removed_metadata: string[]
+  /** Keyword occurrences replaced in text attachments */
+  redactions: number
+}
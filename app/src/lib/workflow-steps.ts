@@ -34,6 +34,7 @@ const LEGACY_STEP_ID_ALIASES: Record<number, number> = {
 };
 
 const SYNTHETIC_STEP_LABELS: Record<number, string> = {
+  [-12]: "Auxiliary",
   [-11]: "Test",
   [-10]: "Refine",
 };
@@ -6,6 +6,7 @@ import { ThemeProvider } from "./components/theme-provider";
 import { ErrorBoundary } from "./components/error-boundary";
 import { Toaster } from "./components/ui/sonner";
 import { router } from "./router";
+import { enableCommandHistory } from "./lib/tauri";
 import '@fontsource-variable/jetbrains-mono';
 import "github-markdown-css/github-markdown.css";
 import "./styles/globals.css";
@@ -15,6 +16,8 @@ attachConsole().catch((err) => {
   console.error('Failed to attach console logger:', err);
 });
 
+enableCommandHistory();
+
 ReactDOM.createRoot(document.getElementById("root")!).render(
   <React.StrictMode>
     <ThemeProvider>
@@ -3,7 +3,7 @@ import { useNavigate } from "@tanstack/react-router"
 import { invoke } from "@tauri-apps/api/core"
 import { open, save } from "@tauri-apps/plugin-dialog"
 import { toast } from "sonner"
-import { FolderOpen, Search, Filter, AlertCircle, Settings, Plus, Github, ChevronUp, ChevronDown, Upload } from "lucide-react"
+import { FolderOpen, Search, Filter, AlertCircle, Settings, Plus, Github, ChevronUp, ChevronDown, Upload, BookOpen, Loader2 } from "lucide-react"
 import {
   Card,
   CardContent,
@@ -28,16 +28,20 @@ import SkillListRow from "@/components/skill-list-row"
 import { DashboardViewToggle, type ViewMode } from "@/components/dashboard-view-toggle"
 import SkillDialog from "@/components/skill-dialog"
 import DeleteSkillDialog from "@/components/delete-skill-dialog"
+import DeploymentSlotsDialog from "@/components/deployment-slots-dialog"
 import TagFilter from "@/components/tag-filter"
 import GitHubImportDialog from "@/components/github-import-dialog"
 import { ImportSkillDialog } from "@/components/import-skill-dialog"
 import { useSettingsStore } from "@/stores/settings-store"
 import { useSkillStore } from "@/stores/skill-store"
 import { useWorkflowStore } from "@/stores/workflow-store"
-import { packageSkill, getLockedSkills, parseSkillFile } from "@/lib/tauri"
-import type { SkillSummary, AppSettings, SkillFileMeta } from "@/lib/types"
+import { packageSkill, fixSkillAccessibility, getLockedSkills, parseSkillFile, importExampleSkills, exportSkillArchive, importSkillArchive, getSkillReviewReport, refreshUpstreamReviews } from "@/lib/tauri"
+import { onDbChange } from "@/lib/db-events"
+import { onSkillsChanged } from "@/lib/skills-events"
+import type { SkillSummary, SkillReviewState, SkillUpstreamReview, AppSettings, SkillFileMeta } from "@/lib/types"
 import { PURPOSES, PURPOSE_LABELS } from "@/lib/types"
 import { SOURCE_DISPLAY_LABELS } from "@/components/skill-source-badge"
+import { toastCommandError } from "@/components/error-toast"
 
 function SortHeader({ label, column, sortBy, sortDir, onSort }: {
   label: string
@@ -66,8 +70,10 @@ export default function DashboardPage() {
   const [createOpen, setCreateOpen] = useState(false)
   const [dashboardLibraryMarketplaceOpen, setDashboardLibraryMarketplaceOpen] = useState(false)
   const [importState, setImportState] = useState<{ filePath: string; meta: SkillFileMeta } | null>(null)
+  const [importingExamples, setImportingExamples] = useState(false)
   const pendingUpgrade = useSettingsStore((s) => s.pendingUpgradeOpen)
   const [deleteTarget, setDeleteTarget] = useState<SkillSummary | null>(null)
+  const [slotsTarget, setSlotsTarget] = useState<SkillSummary | null>(null)
   const [editTarget, setEditTarget] = useState<SkillSummary | null>(null)
   const [searchQuery, setSearchQuery] = useState("")
   const [selectedTags, setSelectedTags] = useState<string[]>([])
@@ -77,16 +83,22 @@ export default function DashboardPage() {
   const [sortBy, setSortBy] = useState<string>('updated')
   const [sortDir, setSortDir] = useState<'asc' | 'desc'>('desc')
   const [availableTags, setAvailableTags] = useState<string[]>([])
+  const [reviewStates, setReviewStates] = useState<Map<string, SkillReviewState>>(new Map())
+  const [upstreamReviews, setUpstreamReviews] = useState<Map<string, SkillUpstreamReview>>(new Map())
+  const reviewReminderShown = useRef(false)
   const navigate = useNavigate()
   const skillsPath = useSettingsStore((s) => s.skillsPath)
   const marketplaceRegistries = useSettingsStore((s) => s.marketplaceRegistries)
   const hasEnabledRegistry = marketplaceRegistries.some(r => r.enabled)
+  const marketplaceEnabled = useSettingsStore((s) => s.marketplaceEnabled)
   const savedViewMode = useSettingsStore((s) => s.dashboardViewMode) as ViewMode | null
   const [viewMode, setViewMode] = useState<ViewMode>(savedViewMode ?? "grid")
   const viewModeInitialized = useRef(false)
   const lockedSkills = useSkillStore((s) => s.lockedSkills)
   const setLockedSkills = useSkillStore((s) => s.setLockedSkills)
   const existingSkillNames = useMemo(() => skills.map((s) => s.name), [skills])
+  const skillsRef = useRef(skills)
+  skillsRef.current = skills
 
   const refreshLocks = useCallback(async () => {
     try {
@@ -136,6 +148,25 @@ export default function DashboardPage() {
     }
   }, [])
 
+  const handleImportExamples = useCallback(async () => {
+    setImportingExamples(true)
+    try {
+      const results = await importExampleSkills()
+      const failed = results.filter((r) => !r.success)
+      const imported = results.length - failed.length
+      if (failed.length > 0) {
+        toast.error(`Imported ${imported} example skills; ${failed.length} failed: ${failed.map((r) => r.skill_name).join(", ")}`, { duration: Infinity })
+      } else {
+        toast.success(`Imported ${imported} example skills from Anthropic`)
+      }
+      await Promise.all([loadSkills(), loadTags()])
+    } catch (err) {
+      void toastCommandError("Failed to import example skills", err)
+    } finally {
+      setImportingExamples(false)
+    }
+  }, [loadSkills, loadTags])
+
   useEffect(() => {
     loadSettings()
   }, [loadSettings])
@@ -153,6 +184,77 @@ export default function DashboardPage() {
     refreshLocks()
   }, [loadSkills, loadTags, refreshLocks])
 
+  // Review-by states for the card badges; the first load also raises a reminder
+  // for skills that are due or overdue.
+  useEffect(() => {
+    if (!workspacePath || skills.length === 0) return
+    getSkillReviewReport()
+      .then((report) => {
+        setReviewStates(new Map(report.map((r) => [r.skill_name, r.state])))
+        if (reviewReminderShown.current) return
+        reviewReminderShown.current = true
+        const expired = report.filter((r) => r.state === "expired")
+        const due = report.filter((r) => r.state === "due_soon")
+        if (expired.length === 0 && due.length === 0) return
+        console.log("[dashboard] review reminder: expired=%d due=%d", expired.length, due.length)
+        const parts = [
+          expired.length > 0 ? `${expired.length} overdue (${expired.map((r) => r.skill_name).join(", ")})` : null,
+          due.length > 0 ? `${due.length} due soon (${due.map((r) => r.skill_name).join(", ")})` : null,
+        ].filter(Boolean)
+        toast.warning(`Skill reviews: ${parts.join("; ")}`, { duration: Infinity })
+      })
+      .catch((err) => console.error("[dashboard] getSkillReviewReport failed:", err))
+  }, [workspacePath, skills])
+
+  // Branch-per-skill pushes still waiting for review upstream. Refreshing asks
+  // each host whether the requests were merged, so only do it once per load.
+  useEffect(() => {
+    if (!workspacePath) return
+    refreshUpstreamReviews()
+      .then((reviews) => setUpstreamReviews(new Map(reviews.map((r) => [r.skill_name, r]))))
+      .catch((err) => console.error("[dashboard] refreshUpstreamReviews failed:", err))
+  }, [workspacePath])
+
+  // Apply backend change events in place; unknown skills fall back to a full reload.
+  useEffect(() => {
+    const unlisten = onDbChange((change) => {
+      switch (change.kind) {
+        case "skill.updated":
+          if (!skillsRef.current.some((s) => s.name === change.skill.name)) {
+            loadSkills()
+            break
+          }
+          setSkills((prev) =>
+            prev.map((s) => (s.name === change.skill.name ? { ...s, ...change.skill } : s))
+          )
+          break
+        case "skill.deleted":
+          setSkills((prev) => prev.filter((s) => s.name !== change.skill_name))
+          break
+        case "lock.acquired":
+        case "lock.released": {
+          const next = new Set(useSkillStore.getState().lockedSkills)
+          if (change.kind === "lock.acquired") next.add(change.skill_name)
+          else next.delete(change.skill_name)
+          setLockedSkills(next)
+          break
+        }
+      }
+    })
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [loadSkills, setLockedSkills])
+
+  // Skill folders edited, renamed or removed outside the app: reload the list.
+  useEffect(() => {
+    const unlisten = onSkillsChanged(() => loadSkills())
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [loadSkills])
+
+  // Locks taken by other app instances are not emitted here; keep polling for those.
   useEffect(() => {
     refreshLocks()
     const interval = setInterval(refreshLocks, 30000)
@@ -201,7 +303,8 @@ export default function DashboardPage() {
     }
     if (selectedTags.length > 0) {
       result = result.filter((s) =>
-        selectedTags.every((tag) => s.tags?.includes(tag))
+        // A parent tag also matches the tags under it.
+        selectedTags.every((tag) => s.tags?.some((t) => t === tag || t.startsWith(`${tag}/`)))
       )
     }
     if (selectedTypes.length > 0) {
@@ -291,6 +394,23 @@ export default function DashboardPage() {
     navigate({ to: "/test", search: { skill: skill.name } })
   }, [navigate])
 
+  const handleFixAccessibility = useCallback(async (skillName: string) => {
+    const toastId = toast.loading("Fixing accessibility issues...")
+    try {
+      const result = await fixSkillAccessibility(skillName)
+      const fixed = result.headings_fixed + result.alt_texts_added
+      const remaining = result.remaining.length
+      console.log("[dashboard] accessibility fix: skill=%s fixed=%d remaining=%d", skillName, fixed, remaining)
+      toast.success(
+        `Fixed ${fixed} accessibility issue${fixed === 1 ? "" : "s"}${remaining > 0 ? `, ${remaining} remaining` : ""}`,
+        { id: toastId },
+      )
+    } catch (err) {
+      console.error("[dashboard] Accessibility fix failed:", err)
+      void toastCommandError("Accessibility fix failed", err, { id: toastId })
+    }
+  }, [])
+
   const handleDownload = useCallback(async (skill: SkillSummary) => {
     if (!workspacePath) return
     const toastId = toast.loading("Packaging skill...")
@@ -307,29 +427,74 @@ export default function DashboardPage() {
         // User cancelled the save dialog
         toast.dismiss(toastId)
       }
+      const warnings = result.lint_warnings ?? []
+      if (warnings.length > 0) {
+        toast.warning(
+          `${warnings.length} accessibility issue${warnings.length === 1 ? "" : "s"} in ${skill.name}: ${warnings[0].file}:${warnings[0].line} ${warnings[0].message}`,
+          {
+            duration: Infinity,
+            action: { label: "Fix", onClick: () => handleFixAccessibility(skill.name) },
+          },
+        )
+      }
+      const advisory = result.quality_gate?.checks.filter((c) => !c.passed && !c.enforced) ?? []
+      if (advisory.length > 0) {
+        toast.warning(
+          `Quality gate "${result.quality_gate?.profile}" passed with advisory findings in ${skill.name}: ${advisory.map((c) => c.check).join(", ")}`,
+          { duration: Infinity },
+        )
+      }
     } catch (err) {
       console.error("[dashboard] Download failed:", err)
-      toast.error(`Download failed: ${err instanceof Error ? err.message : String(err)}`, { id: toastId, duration: Infinity })
+      void toastCommandError("Download failed", err, { id: toastId })
+    }
+  }, [workspacePath, handleFixAccessibility])
+
+  const handleExportArchive = useCallback(async (skill: SkillSummary) => {
+    if (!workspacePath) return
+    const savePath = await save({
+      defaultPath: `${skill.name}.skillpack`,
+      filters: [{ name: "Skill Archive", extensions: ["skillpack"] }],
+    })
+    if (!savePath) return
+    try {
+      const result = await exportSkillArchive(skill.name, workspacePath, savePath)
+      console.log("[dashboard] exported archive: skill=%s files=%d", skill.name, result.file_count)
+      toast.success(`Exported ${skill.name} (${result.file_count} files${result.signed_key_id ? ", signed" : ""})`)
+    } catch (err) {
+      console.error("[dashboard] Export failed:", err)
+      void toastCommandError("Export failed", err)
     }
   }, [workspacePath])
 
   const handleImportFromFile = useCallback(async () => {
     const filePath = await open({
       title: "Import Skill",
-      filters: [{ name: "Skill Package", extensions: ["skill", "zip"] }],
+      filters: [{ name: "Skill Package", extensions: ["skill", "zip", "skillpack"] }],
     })
     if (!filePath) return
 
     console.log("[dashboard] import from file: path=%s", filePath)
+    if (filePath.endsWith(".skillpack")) {
+      if (!workspacePath) return
+      try {
+        const result = await importSkillArchive(filePath, null, workspacePath)
+        toast.success(`Imported ${result.skill_name} at step ${result.current_step}`)
+        await Promise.all([loadSkills(), loadTags()])
+      } catch (err) {
+        console.error("[dashboard] importSkillArchive failed:", err)
+        void toastCommandError("Import failed", err)
+      }
+      return
+    }
     try {
       const meta = await parseSkillFile(filePath)
       setImportState({ filePath, meta })
     } catch (err) {
       console.error("[dashboard] parseSkillFile failed:", err)
-      const msg = err instanceof Error ? err.message : String(err)
-      toast.error(`Import failed: ${msg}`, { duration: Infinity })
+      void toastCommandError("Import failed", err)
     }
-  }, [])
+  }, [workspacePath, loadSkills, loadTags])
 
   function sharedSkillProps(skill: SkillSummary) {
     return {
@@ -340,8 +505,12 @@ export default function DashboardPage() {
       onDownload: handleDownload,
       onEdit: setEditTarget,
       onEditWorkflow: handleEditWorkflow,
+      onExport: handleExportArchive,
       onRefine: handleRefine,
+      onSlots: setSlotsTarget,
       onTest: handleTest,
+      reviewState: reviewStates.get(skill.name),
+      upstreamReview: upstreamReviews.get(skill.name),
     }
   }
 
@@ -415,11 +584,15 @@ export default function DashboardPage() {
             </CardDescription>
           </CardHeader>
           {workspacePath && skillsPath && (
-            <CardContent className="flex justify-center">
+            <CardContent className="flex justify-center gap-2">
               <Button onClick={() => setCreateOpen(true)}>
                 <Plus className="size-4" />
                 New Skill
               </Button>
+              <Button variant="outline" disabled={importingExamples} onClick={handleImportExamples}>
+                {importingExamples ? <Loader2 className="size-4 animate-spin" /> : <BookOpen className="size-4" />}
+                Import Anthropic Examples
+              </Button>
             </CardContent>
           )}
         </Card>
@@ -489,8 +662,12 @@ export default function DashboardPage() {
           <Button
             variant="outline"
             onClick={() => setDashboardLibraryMarketplaceOpen(true)}
-            disabled={!hasEnabledRegistry}
-            title={!hasEnabledRegistry ? "Enable a marketplace registry in Settings → Marketplace" : undefined}
+            disabled={!marketplaceEnabled || !hasEnabledRegistry}
+            title={
+              !marketplaceEnabled
+                ? "Marketplace is turned off in Settings → Skill Building → Network Access"
+                : !hasEnabledRegistry ? "Enable a marketplace registry in Settings → Marketplace" : undefined
+            }
           >
             <Github className="size-4" />
             Marketplace
@@ -705,6 +882,15 @@ export default function DashboardPage() {
         isLocked={deleteTarget ? lockedSkills.has(deleteTarget.name) : false}
       />
 
+      <DeploymentSlotsDialog
+        skill={slotsTarget}
+        open={slotsTarget !== null}
+        onOpenChange={(open) => {
+          if (!open) setSlotsTarget(null)
+        }}
+        onPromoted={loadSkills}
+      />
+
       <GitHubImportDialog
         open={dashboardLibraryMarketplaceOpen}
         onOpenChange={setDashboardLibraryMarketplaceOpen}
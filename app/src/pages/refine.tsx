@@ -1,5 +1,5 @@
 import { useCallback, useEffect, useMemo, useRef, useState } from "react";
-import { AlertTriangle } from "lucide-react";
+import { AlertTriangle, Layers } from "lucide-react";
 import { useNavigate, useSearch, useBlocker } from "@tanstack/react-router";
 import { toast } from "sonner";
 import {
@@ -18,6 +18,7 @@ import { useAgentStore, flushMessageBuffer } from "@/stores/agent-store";
 import {
   listRefinableSkills,
   getSkillContentForRefine,
+  getRefineDiff,
   startRefineSession,
   sendRefineMessage,
   closeRefineSession,
@@ -34,6 +35,7 @@ import { ResizableSplitPane } from "@/components/refine/resizable-split-pane";
 import { SkillPicker } from "@/components/refine/skill-picker";
 import { ChatPanel } from "@/components/refine/chat-panel";
 import { PreviewPanel } from "@/components/refine/preview-panel";
+import { BatchRefineDialog } from "@/components/refine/batch-refine-dialog";
 
 // Ensure agent-stream listeners are registered
 import "@/hooks/use-agent-stream";
@@ -62,6 +64,16 @@ async function loadSkillFiles(basePath: string, skillName: string): Promise<Skil
   }
 }
 
+/** Load the skill's pending changes with the session's per-file reviews into the store. */
+async function refreshPendingDiff(basePath: string, skillName: string, sessionId: string | null): Promise<void> {
+  try {
+    const diff = await getRefineDiff(skillName, basePath, sessionId ?? undefined);
+    useRefineStore.getState().setPendingDiff(diff);
+  } catch (err) {
+    console.warn("[refine] Failed to load pending changes:", err);
+  }
+}
+
 export default function RefinePage() {
   const { skill: skillParam } = useSearch({ from: "/refine" });
   const navigate = useNavigate();
@@ -87,6 +99,7 @@ export default function RefinePage() {
     activeAgentId ? s.runs[activeAgentId]?.totalCost : undefined,
   );
   const [lastTurnCost, setLastTurnCost] = useState<number | undefined>(undefined);
+  const [batchOpen, setBatchOpen] = useState(false);
 
   // Track which skillParam was last auto-selected so navigating back with a
   // different skill (e.g. from the skill library) triggers a fresh selection.
@@ -244,6 +257,7 @@ export default function RefinePage() {
           if (files.length > 0) {
             store.setActiveFileTab(files[0].filename);
           }
+          await refreshPendingDiff(workspacePath, skill.name, useRefineStore.getState().sessionId);
         } else {
           store.setLoadingFiles(false);
           toast.error("Could not load skill files", { duration: Infinity });
@@ -324,6 +338,7 @@ export default function RefinePage() {
       if (workspacePath && selectedSkill) {
         const files = await loadSkillFiles(workspacePath, selectedSkill.name);
         if (files) store.updateSkillFiles(files);
+        await refreshPendingDiff(workspacePath, selectedSkill.name, store.sessionId);
       }
 
       store.setRunning(false);
@@ -413,6 +428,13 @@ export default function RefinePage() {
     [selectedSkill, workspacePath, preferredModel, isRunning],
   );
 
+  // --- Reload files after a per-file reject puts one back to HEAD ---
+  const handleFileRejected = useCallback(async () => {
+    if (!workspacePath || !selectedSkill) return;
+    const files = await loadSkillFiles(workspacePath, selectedSkill.name);
+    if (files) useRefineStore.getState().updateSkillFiles(files);
+  }, [workspacePath, selectedSkill]);
+
   // --- Status bar ---
   const [elapsed, setElapsed] = useState(0);
   const runStartRef = useRef<number | null>(null);
@@ -462,6 +484,16 @@ export default function RefinePage() {
           lockedSkills={lockedSkills}
           onSelect={handleSelectSkill}
         />
+        <Button
+          variant="outline"
+          size="sm"
+          className="ml-auto"
+          disabled={!workspacePath || refinableSkills.length === 0}
+          onClick={() => setBatchOpen(true)}
+        >
+          <Layers className="size-4" />
+          Batch refine
+        </Button>
       </div>
 
       {scopeBlocked && selectedSkill && (
@@ -489,7 +521,7 @@ export default function RefinePage() {
               scopeBlocked={scopeBlocked}
             />
           }
-          right={<PreviewPanel />}
+          right={<PreviewPanel onFileRejected={handleFileRejected} />}
         />
       </div>
 
@@ -521,6 +553,15 @@ export default function RefinePage() {
         )}
       </div>
 
+      {workspacePath && (
+        <BatchRefineDialog
+          skills={refinableSkills}
+          workspacePath={workspacePath}
+          open={batchOpen}
+          onOpenChange={setBatchOpen}
+        />
+      )}
+
       {/* Navigation guard dialog */}
       {blockerStatus === "blocked" && (
         <Dialog open onOpenChange={(open) => { if (!open) handleNavStay(); }}>
@@ -3,9 +3,9 @@ import { invoke } from "@tauri-apps/api/core"
 import { getVersion } from "@tauri-apps/api/app"
 import { toast } from "sonner"
 import { open } from "@tauri-apps/plugin-dialog"
-import { Loader2, Eye, EyeOff, CheckCircle2, XCircle, PlugZap, FolderOpen, FolderSearch, Trash2, Github, LogOut, Monitor, Sun, Moon, Info, ArrowLeft, Plus } from "lucide-react"
+import { Loader2, Eye, EyeOff, CheckCircle2, XCircle, PlugZap, FolderOpen, FolderSearch, Trash2, Github, LogOut, Monitor, Sun, Moon, Info, ArrowLeft, Plus, RefreshCw, Upload, Download } from "lucide-react"
 import { useTheme } from "next-themes"
-import { useNavigate } from "@tanstack/react-router"
+import { useNavigate, useSearch } from "@tanstack/react-router"
 import { Button } from "@/components/ui/button"
 import {
   Card,
@@ -18,16 +18,26 @@ import { Badge } from "@/components/ui/badge"
 import { Input } from "@/components/ui/input"
 import { Label } from "@/components/ui/label"
 import { Switch } from "@/components/ui/switch"
-import type { AppSettings, MarketplaceRegistry } from "@/lib/types"
+import type { AccessibilityLintMode, ActivationPolicy, ActivationRule, AppSettings, AutomationHook, MarketplaceRegistry, ModelRoute, QualityGateProfile, RetentionPolicy } from "@/lib/types"
 import { cn } from "@/lib/utils"
 import { useSettingsStore, type ModelInfo } from "@/stores/settings-store"
 import { useAuthStore } from "@/stores/auth-store"
-import { getDataDir, checkMarketplaceUrl, parseGitHubUrl } from "@/lib/tauri"
+import { getDataDir, checkMarketplaceUrl, parseGitHubUrl, checkPathInput, listQualityGateProfiles, reloadPrompts, importSkillInvocations, pushSettingsSync, pullSettingsSync } from "@/lib/tauri"
 import { Avatar, AvatarImage, AvatarFallback } from "@/components/ui/avatar"
 import { GitHubLoginDialog } from "@/components/github-login-dialog"
 import { AboutDialog } from "@/components/about-dialog"
 import { FeedbackDialog } from "@/components/feedback-dialog"
 import { WorkspaceSkillsTab } from "@/components/workspace-skills-tab"
+import { AuditLogViewer } from "@/components/audit-log-viewer"
+import { BackgroundTaskQueue } from "@/components/background-task-queue"
+import { AutomationHooksSettings } from "@/components/automation-hooks-settings"
+import { DataRetentionSettings, KEEP_FOREVER } from "@/components/data-retention-settings"
+import { ModelRoutesSettings } from "@/components/model-routes-settings"
+import { BashSandboxSettings } from "@/components/bash-sandbox-settings"
+import { StepToolRoleSettings } from "@/components/step-tool-role-settings"
+import { GitHostingAccounts } from "@/components/git-hosting-accounts"
+import { TagTaxonomySettings } from "@/components/tag-taxonomy-settings"
+import { TrustedKeysSettings } from "@/components/trusted-keys-settings"
 
 /** Must match DEFAULT_MARKETPLACE_URL in app/src-tauri/src/commands/settings.rs */
 const DEFAULT_MARKETPLACE_URL = "hbanerjee74/skills"
@@ -43,6 +53,19 @@ const sections = [
 
 type SectionId = typeof sections[number]["id"]
 
+const isSectionId = (tab: string | undefined): tab is SectionId => sections.some(({ id }) => id === tab)
+
+/** A positive USD amount, or null for no limit. */
+function parseBudget(value: string): number | null {
+  const amount = parseFloat(value)
+  return Number.isFinite(amount) && amount > 0 ? amount : null
+}
+
+/** Comma-separated keywords, trimmed, without empties. */
+function parseKeywords(value: string): string[] {
+  return value.split(",").map((k) => k.trim()).filter(Boolean)
+}
+
 type RegistryTestState = "checking" | "valid" | "invalid" | undefined
 
 function RegistryTestIcon({ state }: { state: RegistryTestState }) {
@@ -54,10 +77,12 @@ function RegistryTestIcon({ state }: { state: RegistryTestState }) {
 
 export default function SettingsPage() {
   const navigate = useNavigate()
-  const [activeSection, setActiveSection] = useState<SectionId>("general")
+  const { tab } = useSearch({ from: "/settings" })
+  const [activeSection, setActiveSection] = useState<SectionId>(isSectionId(tab) ? tab : "general")
   const [apiKey, setApiKey] = useState<string | null>(null)
   const [workspacePath, setWorkspacePath] = useState<string | null>(null)
   const [skillsPath, setSkillsPath] = useState<string | null>(null)
+  const [skillsPathInput, setSkillsPathInput] = useState("")
   const [preferredModel, setPreferredModel] = useState<string>("sonnet")
   const [logLevel, setLogLevel] = useState("info")
   const [extendedThinking, setExtendedThinking] = useState(false)
@@ -77,6 +102,45 @@ export default function SettingsPage() {
   const [loginDialogOpen, setLoginDialogOpen] = useState(false)
   const [aboutDialogOpen, setAboutDialogOpen] = useState(false)
   const [autoUpdate, setAutoUpdate] = useState(false)
+  const [teamRepo, setTeamRepo] = useState("")
+  const [githubEnterpriseUrl, setGithubEnterpriseUrl] = useState("")
+  const [savedGithubEnterpriseUrl, setSavedGithubEnterpriseUrl] = useState("")
+  const [githubOauthClientId, setGithubOauthClientId] = useState("")
+  const [githubCaCertPath, setGithubCaCertPath] = useState("")
+  const [githubAcceptInvalidCerts, setGithubAcceptInvalidCerts] = useState(false)
+  const [settingsSyncRepo, setSettingsSyncRepo] = useState("")
+  const [settingsSyncOverrides, setSettingsSyncOverrides] = useState("")
+  const [settingsSyncing, setSettingsSyncing] = useState<"push" | "pull" | null>(null)
+  const [settingsReloadKey, setSettingsReloadKey] = useState(0)
+  const [feedbackKeywords, setFeedbackKeywords] = useState("")
+  const [feedbackPublicKey, setFeedbackPublicKey] = useState("")
+  const [customPromptsDir, setCustomPromptsDir] = useState("")
+  const [reloadingPrompts, setReloadingPrompts] = useState(false)
+  const [claudeDataDir, setClaudeDataDir] = useState("")
+  const [importingInvocations, setImportingInvocations] = useState(false)
+  const [activationPolicy, setActivationPolicy] = useState<ActivationPolicy>({ rules: [], default_action: "activate" })
+  const [accessibilityLint, setAccessibilityLint] = useState<AccessibilityLintMode>("advisory")
+  const [blockExpiredPackaging, setBlockExpiredPackaging] = useState(false)
+  const [requireTrustedPackages, setRequireTrustedPackages] = useState(false)
+  const [queueConcurrency, setQueueConcurrency] = useState(1)
+  const [maxConcurrentAgents, setMaxConcurrentAgents] = useState(3)
+  const [transcriptArchiveMb, setTranscriptArchiveMb] = useState(256)
+  const [retention, setRetention] = useState<RetentionPolicy>(KEEP_FOREVER)
+  const [qualityGateProfile, setQualityGateProfile] = useState("")
+  const [qualityGateProfiles, setQualityGateProfiles] = useState<QualityGateProfile[]>([])
+  const [globalBudget, setGlobalBudget] = useState("")
+  const [skillBudgets, setSkillBudgets] = useState<Record<string, number>>({})
+  const [newBudgetSkill, setNewBudgetSkill] = useState("")
+  const [newBudgetAmount, setNewBudgetAmount] = useState("")
+  const [localModelEndpoint, setLocalModelEndpoint] = useState("")
+  const [localModel, setLocalModel] = useState("")
+  const [localModels, setLocalModels] = useState<string[]>([])
+  const [checkingLocalModels, setCheckingLocalModels] = useState(false)
+  const [offlineMode, setOfflineMode] = useState(false)
+  const [disableMarketplace, setDisableMarketplace] = useState(false)
+  const [disableGithub, setDisableGithub] = useState(false)
+  const [automationHooks, setAutomationHooks] = useState<AutomationHook[]>([])
+  const [modelRoutes, setModelRoutes] = useState<ModelRoute[]>([])
   const setStoreSettings = useSettingsStore((s) => s.setSettings)
   const marketplaceRegistries = useSettingsStore((s) => s.marketplaceRegistries)
   const [addingRegistry, setAddingRegistry] = useState(false)
@@ -94,6 +158,11 @@ export default function SettingsPage() {
       setActiveSection("skills")
     }
   }, [pendingUpgrade])
+
+  // Follow ?tab= links (e.g. from error help) that arrive while the page is open
+  useEffect(() => {
+    if (isSectionId(tab)) setActiveSection(tab)
+  }, [tab])
   const { theme, setTheme } = useTheme()
 
   useEffect(() => {
@@ -116,10 +185,40 @@ export default function SettingsPage() {
             setIndustry(result.industry ?? "")
             setFunctionRole(result.function_role ?? "")
             setAutoUpdate(result.auto_update ?? false)
+            setTeamRepo(result.team_repo ?? "")
+            setGithubEnterpriseUrl(result.github_enterprise_url ?? "")
+            setSavedGithubEnterpriseUrl(result.github_enterprise_url ?? "")
+            setGithubOauthClientId(result.github_oauth_client_id ?? "")
+            setGithubCaCertPath(result.github_ca_cert_path ?? "")
+            setGithubAcceptInvalidCerts(result.github_accept_invalid_certs ?? false)
+            setSettingsSyncRepo(result.settings_sync_repo ?? "")
+            setSettingsSyncOverrides((result.settings_sync_local_overrides ?? []).join(", "))
+            setFeedbackKeywords((result.feedback_redaction_keywords ?? []).join(", "))
+            setFeedbackPublicKey(result.feedback_public_key ?? "")
+            setCustomPromptsDir(result.custom_prompts_dir ?? "")
+            setClaudeDataDir(result.claude_data_dir ?? "")
+            setActivationPolicy(result.activation_policy ?? { rules: [], default_action: "activate" })
+            setAccessibilityLint(result.accessibility_lint ?? "advisory")
+            setBlockExpiredPackaging(result.block_expired_packaging ?? false)
+            setRequireTrustedPackages(result.require_trusted_packages ?? false)
+            setQueueConcurrency(result.workflow_queue_concurrency ?? 1)
+            setMaxConcurrentAgents(result.max_concurrent_agents ?? 3)
+            setTranscriptArchiveMb(result.transcript_archive_mb ?? 256)
+            setRetention(result.retention ?? KEEP_FOREVER)
+            setQualityGateProfile(result.quality_gate_profile ?? "")
+            setGlobalBudget(result.global_budget_usd != null ? String(result.global_budget_usd) : "")
+            setSkillBudgets(result.skill_budgets_usd ?? {})
+            setLocalModelEndpoint(result.local_model_endpoint ?? "")
+            setLocalModel(result.local_model ?? "")
+            setOfflineMode(result.offline_mode ?? false)
+            setDisableMarketplace(result.disable_marketplace ?? false)
+            setDisableGithub(result.disable_github ?? false)
+            setAutomationHooks(result.automation_hooks ?? [])
+            setModelRoutes(result.model_routes ?? [])
             setStoreSettings({ marketplaceRegistries: result.marketplace_registries ?? [], marketplaceInitialized: result.marketplace_initialized ?? false })
             setLoading(false)
             // Fetch available models once we have an API key
-            if (result.anthropic_api_key) {
+            if (result.anthropic_api_key && !result.offline_mode) {
               fetchModels(result.anthropic_api_key)
             }
           }
@@ -134,7 +233,7 @@ export default function SettingsPage() {
     }
     load()
     return () => { cancelled = true }
-  }, [])
+  }, [settingsReloadKey])
 
   useEffect(() => {
     getVersion()
@@ -148,6 +247,12 @@ export default function SettingsPage() {
       .catch(() => setDataDir(null))
   }, [])
 
+  useEffect(() => {
+    listQualityGateProfiles()
+      .then((profiles) => setQualityGateProfiles(profiles ?? []))
+      .catch((err) => console.warn("[settings] Could not load quality gate profiles:", err))
+  }, [])
+
   const fetchModels = async (key: string) => {
     try {
       const models = await invoke<ModelInfo[]>("list_models", { apiKey: key })
@@ -158,6 +263,21 @@ export default function SettingsPage() {
     }
   }
 
+  const saveActivationPolicy = (policy: ActivationPolicy) => {
+    setActivationPolicy(policy)
+    autoSave({ activationPolicy: policy })
+  }
+
+  /** Text fields save on blur; selects save immediately. */
+  const updateActivationRule = (index: number, patch: Partial<ActivationRule>, save: boolean) => {
+    const policy = {
+      ...activationPolicy,
+      rules: activationPolicy.rules.map((r, i) => (i === index ? { ...r, ...patch } : r)),
+    }
+    if (save) saveActivationPolicy(policy)
+    else setActivationPolicy(policy)
+  }
+
   const autoSave = async (overrides: Partial<{
     apiKey: string | null;
     skillsPath: string | null;
@@ -172,6 +292,35 @@ export default function SettingsPage() {
     industry: string | null;
     functionRole: string | null;
     autoUpdate: boolean;
+    teamRepo: string | null;
+    githubEnterpriseUrl: string | null;
+    githubOauthClientId: string | null;
+    githubCaCertPath: string | null;
+    githubAcceptInvalidCerts: boolean;
+    settingsSyncRepo: string | null;
+    settingsSyncOverrides: string[];
+    activationPolicy: ActivationPolicy;
+    accessibilityLint: AccessibilityLintMode;
+    blockExpiredPackaging: boolean;
+    requireTrustedPackages: boolean;
+    queueConcurrency: number;
+    maxConcurrentAgents: number;
+    transcriptArchiveMb: number;
+    retention: RetentionPolicy;
+    qualityGateProfile: string | null;
+    globalBudget: number | null;
+    skillBudgets: Record<string, number>;
+    localModelEndpoint: string | null;
+    localModel: string | null;
+    offlineMode: boolean;
+    disableMarketplace: boolean;
+    disableGithub: boolean;
+    automationHooks: AutomationHook[];
+    modelRoutes: ModelRoute[];
+    feedbackKeywords: string[];
+    feedbackPublicKey: string | null;
+    customPromptsDir: string | null;
+    claudeDataDir: string | null;
   }>) => {
     const settings: AppSettings = {
       anthropic_api_key: overrides.apiKey !== undefined ? overrides.apiKey : apiKey,
@@ -199,6 +348,35 @@ export default function SettingsPage() {
       function_role: overrides.functionRole !== undefined ? overrides.functionRole : (functionRole || null),
       dashboard_view_mode: useSettingsStore.getState().dashboardViewMode ?? null,
       auto_update: overrides.autoUpdate !== undefined ? overrides.autoUpdate : autoUpdate,
+      team_repo: overrides.teamRepo !== undefined ? overrides.teamRepo : (teamRepo || null),
+      github_enterprise_url: overrides.githubEnterpriseUrl !== undefined ? overrides.githubEnterpriseUrl : (savedGithubEnterpriseUrl || null),
+      github_oauth_client_id: overrides.githubOauthClientId !== undefined ? overrides.githubOauthClientId : (githubOauthClientId || null),
+      github_ca_cert_path: overrides.githubCaCertPath !== undefined ? overrides.githubCaCertPath : (githubCaCertPath || null),
+      github_accept_invalid_certs: overrides.githubAcceptInvalidCerts !== undefined ? overrides.githubAcceptInvalidCerts : githubAcceptInvalidCerts,
+      settings_sync_repo: overrides.settingsSyncRepo !== undefined ? overrides.settingsSyncRepo : (settingsSyncRepo || null),
+      settings_sync_local_overrides: overrides.settingsSyncOverrides !== undefined ? overrides.settingsSyncOverrides : parseKeywords(settingsSyncOverrides),
+      local_model_endpoint: overrides.localModelEndpoint !== undefined ? overrides.localModelEndpoint : (localModelEndpoint || null),
+      local_model: overrides.localModel !== undefined ? overrides.localModel : (localModel || null),
+      activation_policy: overrides.activationPolicy !== undefined ? overrides.activationPolicy : activationPolicy,
+      accessibility_lint: overrides.accessibilityLint !== undefined ? overrides.accessibilityLint : accessibilityLint,
+      block_expired_packaging: overrides.blockExpiredPackaging !== undefined ? overrides.blockExpiredPackaging : blockExpiredPackaging,
+      require_trusted_packages: overrides.requireTrustedPackages !== undefined ? overrides.requireTrustedPackages : requireTrustedPackages,
+      workflow_queue_concurrency: overrides.queueConcurrency !== undefined ? overrides.queueConcurrency : queueConcurrency,
+      max_concurrent_agents: overrides.maxConcurrentAgents !== undefined ? overrides.maxConcurrentAgents : maxConcurrentAgents,
+      transcript_archive_mb: overrides.transcriptArchiveMb !== undefined ? overrides.transcriptArchiveMb : transcriptArchiveMb,
+      retention: overrides.retention !== undefined ? overrides.retention : retention,
+      quality_gate_profile: overrides.qualityGateProfile !== undefined ? overrides.qualityGateProfile : (qualityGateProfile || null),
+      global_budget_usd: overrides.globalBudget !== undefined ? overrides.globalBudget : parseBudget(globalBudget),
+      skill_budgets_usd: overrides.skillBudgets !== undefined ? overrides.skillBudgets : skillBudgets,
+      feedback_redaction_keywords: overrides.feedbackKeywords !== undefined ? overrides.feedbackKeywords : parseKeywords(feedbackKeywords),
+      feedback_public_key: overrides.feedbackPublicKey !== undefined ? overrides.feedbackPublicKey : (feedbackPublicKey || null),
+      custom_prompts_dir: overrides.customPromptsDir !== undefined ? overrides.customPromptsDir : (customPromptsDir || null),
+      offline_mode: overrides.offlineMode !== undefined ? overrides.offlineMode : offlineMode,
+      disable_marketplace: overrides.disableMarketplace !== undefined ? overrides.disableMarketplace : disableMarketplace,
+      disable_github: overrides.disableGithub !== undefined ? overrides.disableGithub : disableGithub,
+      automation_hooks: overrides.automationHooks !== undefined ? overrides.automationHooks : automationHooks,
+      model_routes: overrides.modelRoutes !== undefined ? overrides.modelRoutes : modelRoutes,
+      claude_data_dir: overrides.claudeDataDir !== undefined ? overrides.claudeDataDir : (claudeDataDir || null),
     }
     try {
       await invoke("save_settings", { settings })
@@ -218,6 +396,9 @@ export default function SettingsPage() {
         industry: settings.industry,
         functionRole: settings.function_role,
         autoUpdate: settings.auto_update,
+        offlineMode: settings.offline_mode ?? false,
+        marketplaceEnabled: !settings.offline_mode && !settings.disable_marketplace,
+        githubEnabled: !settings.offline_mode && !settings.disable_github,
       })
       const changed = Object.entries(overrides)
         .filter(([, v]) => v !== undefined)
@@ -226,9 +407,23 @@ export default function SettingsPage() {
       console.log(`[settings] Saved: ${changed}`)
       setSaved(true)
       setTimeout(() => setSaved(false), 2000)
+      return true
     } catch (err) {
       console.error("settings: auto-save failed", err)
       toast.error(`Failed to save: ${err}`, { duration: Infinity })
+      return false
+    }
+  }
+
+  const handleGithubEnterpriseUrlBlur = async () => {
+    const url = githubEnterpriseUrl.trim()
+    if (url === savedGithubEnterpriseUrl) return
+    if (!(await autoSave({ githubEnterpriseUrl: url || null }))) return
+    setSavedGithubEnterpriseUrl(url)
+    // The backend drops the token when the server changes; clear the session to match
+    if (isLoggedIn) {
+      await logout()
+      toast.info(`Signed out of GitHub. Sign in again to ${url || "github.com"}.`)
     }
   }
 
@@ -256,6 +451,32 @@ export default function SettingsPage() {
     }
   }
 
+  const handleCheckLocalModels = async () => {
+    if (!localModelEndpoint) {
+      toast.error("Enter the local model server URL first", { duration: Infinity })
+      return
+    }
+    setCheckingLocalModels(true)
+    try {
+      const models = await invoke<string[]>("list_local_models", { endpoint: localModelEndpoint })
+      setLocalModels(models)
+      if (models.length === 0) {
+        toast.error("Server is reachable but has no models installed", { duration: Infinity })
+      } else {
+        toast.success(`Found ${models.length} local model${models.length === 1 ? "" : "s"}`)
+      }
+    } catch (err) {
+      console.error("settings: local model check failed", err)
+      setLocalModels([])
+      toast.error(
+        err instanceof Error ? err.message : String(err),
+        { duration: Infinity },
+      )
+    } finally {
+      setCheckingLocalModels(false)
+    }
+  }
+
   const handleBrowseSkillsPath = async () => {
     const folder = await open({ directory: true, title: "Select Skills Folder" })
     if (folder) {
@@ -271,6 +492,76 @@ export default function SettingsPage() {
     }
   }
 
+  const handleApplySkillsPathInput = async () => {
+    if (!skillsPathInput.trim()) return
+    try {
+      const result = await checkPathInput(skillsPathInput, "directory", false)
+      setSkillsPath(result.path)
+      setSkillsPathInput("")
+      autoSave({ skillsPath: result.path })
+      if (result.warnings.length > 0) {
+        toast.info(`Skills folder set to ${result.display}: ${result.warnings.join("; ")}`)
+      }
+    } catch (err) {
+      console.error("[settings] Invalid skills path:", err)
+      toast.error(`Invalid skills folder: ${err}`, { duration: Infinity })
+    }
+  }
+
+  const handleReloadPrompts = async () => {
+    setReloadingPrompts(true)
+    try {
+      const result = await reloadPrompts()
+      const detail = result.overridden.length > 0
+        ? `${result.overridden.length} custom template${result.overridden.length === 1 ? "" : "s"} in use`
+        : "using bundled templates"
+      toast.success(result.changed ? `Prompts reloaded — ${detail}` : `Prompts already up to date — ${detail}`)
+      if (result.ignored.length > 0) {
+        toast.warning(`No bundled template matches: ${result.ignored.join(", ")}`)
+      }
+    } catch (err) {
+      console.error("[settings] Failed to reload prompts:", err)
+      toast.error(`Failed to reload prompts: ${err}`, { duration: Infinity })
+    } finally {
+      setReloadingPrompts(false)
+    }
+  }
+
+  const handleSettingsSync = async (direction: "push" | "pull") => {
+    setSettingsSyncing(direction)
+    try {
+      if (direction === "push") {
+        const result = await pushSettingsSync()
+        toast.success(result.commit_sha ? "Shared settings pushed" : "Shared settings already up to date")
+      } else {
+        const result = await pullSettingsSync()
+        const kept = result.kept_local.length > 0 ? ` (${result.kept_local.length} kept as local overrides)` : ""
+        toast.success(`Applied ${result.applied.length} shared setting${result.applied.length === 1 ? "" : "s"}${kept}`)
+        if (result.applied.length > 0) setSettingsReloadKey((k) => k + 1)
+      }
+    } catch (err) {
+      console.error(`[settings] Failed to ${direction} shared settings:`, err)
+      toast.error(`Failed to ${direction} shared settings: ${err}`, { duration: Infinity })
+    } finally {
+      setSettingsSyncing(null)
+    }
+  }
+
+  const handleImportInvocations = async () => {
+    setImportingInvocations(true)
+    try {
+      const result = await importSkillInvocations(claudeDataDir.trim() || null)
+      toast.success(
+        `Imported ${result.invocations_added} new skill invocation${result.invocations_added === 1 ? "" : "s"} from ${result.files_scanned} session${result.files_scanned === 1 ? "" : "s"}`
+      )
+    } catch (err) {
+      console.error("[settings] Failed to import skill invocations:", err)
+      toast.error(`Failed to import skill usage: ${err}`, { duration: Infinity })
+    } finally {
+      setImportingInvocations(false)
+    }
+  }
+
   const githubStatusLabel = isAuthLoading ? "Checking" : isLoggedIn && user ? "Connected" : "Not connected"
 
 
@@ -478,6 +769,24 @@ export default function SettingsPage() {
               </CardContent>
             </Card>
 
+            <Card>
+              <CardHeader>
+                <CardTitle>Model Routing</CardTitle>
+                <CardDescription>
+                  Run workflow steps on a different model per step or skill type. The most specific route wins; steps without a route use their default model.
+                </CardDescription>
+              </CardHeader>
+              <CardContent>
+                <ModelRoutesSettings
+                  routes={modelRoutes}
+                  onChange={(routes) => {
+                    setModelRoutes(routes)
+                    autoSave({ modelRoutes: routes })
+                  }}
+                />
+              </CardContent>
+            </Card>
+
             <Card>
               <CardHeader>
                 <CardTitle>Agent Features</CardTitle>
@@ -544,6 +853,119 @@ export default function SettingsPage() {
               </CardContent>
             </Card>
 
+            <Card>
+              <CardHeader>
+                <CardTitle>Bash Sandbox</CardTitle>
+                <CardDescription>
+                  Restrict the shell commands agents can run. Skills can override this policy.
+                </CardDescription>
+              </CardHeader>
+              <CardContent>
+                <BashSandboxSettings />
+              </CardContent>
+            </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle>Step Tools</CardTitle>
+                <CardDescription>
+                  Trim the tools each workflow step may use. Every tool call is recorded and can be reviewed per run on the Usage page.
+                </CardDescription>
+              </CardHeader>
+              <CardContent>
+                <StepToolRoleSettings />
+              </CardContent>
+            </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle>Local Model</CardTitle>
+                <CardDescription>
+                  Run low-stakes passes such as field suggestions on a local Ollama model at no cost. Falls back to the Anthropic API if the local model is unavailable. In offline mode, agents run on this server too.
+                </CardDescription>
+              </CardHeader>
+              <CardContent className="flex flex-col gap-4">
+                <div className="flex flex-col gap-2">
+                  <Label htmlFor="local-model-endpoint">Server URL</Label>
+                  <div className="flex items-center gap-2">
+                    <Input
+                      id="local-model-endpoint"
+                      placeholder="http://localhost:11434"
+                      value={localModelEndpoint}
+                      onChange={(e) => setLocalModelEndpoint(e.target.value)}
+                      onBlur={() => autoSave({ localModelEndpoint: localModelEndpoint.trim() || null })}
+                    />
+                    <Button
+                      variant="outline"
+                      size="sm"
+                      onClick={handleCheckLocalModels}
+                      disabled={checkingLocalModels || !localModelEndpoint}
+                    >
+                      {checkingLocalModels ? <Loader2 className="size-3.5 animate-spin" /> : "Check"}
+                    </Button>
+                  </div>
+                </div>
+                <div className="flex flex-col gap-2">
+                  <Label htmlFor="local-model">Model</Label>
+                  <Input
+                    id="local-model"
+                    list="local-model-options"
+                    placeholder="e.g., llama3.1:8b"
+                    value={localModel}
+                    onChange={(e) => setLocalModel(e.target.value)}
+                    onBlur={() => autoSave({ localModel: localModel.trim() || null })}
+                  />
+                  <datalist id="local-model-options">
+                    {localModels.map((m) => (
+                      <option key={m} value={m} />
+                    ))}
+                  </datalist>
+                </div>
+              </CardContent>
+            </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle>Network Access</CardTitle>
+                <CardDescription>
+                  For air-gapped environments. Offline mode runs every agent on the local model server above and turns off marketplace and GitHub features. The server must accept Anthropic Messages API requests (Ollama, vLLM, or a LiteLLM proxy). Token usage is still recorded; cost is not.
+                </CardDescription>
+              </CardHeader>
+              <CardContent className="space-y-4">
+                <div className="flex items-center justify-between">
+                  <div className="flex flex-col gap-0.5">
+                    <Label htmlFor="offline-mode">Offline mode</Label>
+                    {offlineMode && (!localModelEndpoint.trim() || !localModel.trim()) && (
+                      <span className="text-sm text-destructive">Set a server URL and model above before running agents.</span>
+                    )}
+                  </div>
+                  <Switch
+                    id="offline-mode"
+                    checked={offlineMode}
+                    onCheckedChange={(checked) => { setOfflineMode(checked); autoSave({ offlineMode: checked }); }}
+                  />
+                </div>
+                <div className="flex items-center justify-between">
+                  <Label htmlFor="disable-marketplace">Disable marketplace</Label>
+                  <Switch
+                    id="disable-marketplace"
+                    checked={offlineMode || disableMarketplace}
+                    disabled={offlineMode}
+                    onCheckedChange={(checked) => { setDisableMarketplace(checked); autoSave({ disableMarketplace: checked }); }}
+                  />
+                </div>
+                <div className="flex items-center justify-between">
+                  <Label htmlFor="disable-github">Disable GitHub</Label>
+                  <Switch
+                    id="disable-github"
+                    checked={offlineMode || disableGithub}
+                    disabled={offlineMode}
+                    onCheckedChange={(checked) => { setDisableGithub(checked); autoSave({ disableGithub: checked }); }}
+                  />
+                </div>
+              </CardContent>
+            </Card>
+
             <Card>
               <CardHeader>
                 <CardTitle>Research Scope Limit</CardTitle>
@@ -573,12 +995,381 @@ export default function SettingsPage() {
                 </div>
               </CardContent>
             </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle>Step Queue</CardTitle>
+                <CardDescription>
+                  Queued workflow steps run unattended. Steps of the same skill always run one at a time,
+                  but independent agents within a skill, such as test cases, can run side by side.
+                </CardDescription>
+              </CardHeader>
+              <CardContent className="flex flex-col gap-4">
+                <div className="flex items-center gap-4">
+                  <Label htmlFor="queue-concurrency">Skills at once</Label>
+                  <Input
+                    id="queue-concurrency"
+                    type="number"
+                    min={1}
+                    max={8}
+                    value={queueConcurrency}
+                    onChange={(e) => {
+                      const val = Math.max(1, Math.min(8, parseInt(e.target.value) || 1))
+                      setQueueConcurrency(val)
+                    }}
+                    onBlur={() => autoSave({ queueConcurrency })}
+                    className="w-20"
+                  />
+                </div>
+                <div className="flex items-center gap-4">
+                  <Label htmlFor="max-concurrent-agents">Agents at once per skill</Label>
+                  <Input
+                    id="max-concurrent-agents"
+                    type="number"
+                    min={1}
+                    max={8}
+                    value={maxConcurrentAgents}
+                    onChange={(e) => {
+                      const val = Math.max(1, Math.min(8, parseInt(e.target.value) || 1))
+                      setMaxConcurrentAgents(val)
+                    }}
+                    onBlur={() => autoSave({ maxConcurrentAgents })}
+                    className="w-20"
+                  />
+                </div>
+              </CardContent>
+            </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle>Budgets</CardTitle>
+                <CardDescription>
+                  Spend limits since the last usage reset. A running step is stopped when a limit is reached, and new steps won&apos;t start until it is raised.
+                </CardDescription>
+              </CardHeader>
+              <CardContent className="flex flex-col gap-4">
+                <div className="flex items-center gap-4">
+                  <Label htmlFor="global-budget">All skills (USD)</Label>
+                  <Input
+                    id="global-budget"
+                    type="number"
+                    min={0}
+                    step="0.01"
+                    placeholder="No limit"
+                    value={globalBudget}
+                    onChange={(e) => setGlobalBudget(e.target.value)}
+                    onBlur={() => autoSave({ globalBudget: parseBudget(globalBudget) })}
+                    className="w-28"
+                  />
+                </div>
+                {Object.entries(skillBudgets).map(([skill, amount]) => (
+                  <div key={skill} className="flex items-center gap-2" data-testid="skill-budget">
+                    <span className="flex-1 font-mono text-sm">{skill}</span>
+                    <span className="text-sm text-muted-foreground">${amount.toFixed(2)}</span>
+                    <Button
+                      variant="ghost"
+                      size="icon"
+                      aria-label={`Remove budget for ${skill}`}
+                      onClick={() => {
+                        const next = { ...skillBudgets }
+                        delete next[skill]
+                        setSkillBudgets(next)
+                        autoSave({ skillBudgets: next })
+                      }}
+                    >
+                      <Trash2 className="size-4" />
+                    </Button>
+                  </div>
+                ))}
+                <div className="flex items-center gap-2">
+                  <Input
+                    aria-label="Skill name"
+                    placeholder="skill-name"
+                    value={newBudgetSkill}
+                    onChange={(e) => setNewBudgetSkill(e.target.value)}
+                    className="flex-1"
+                  />
+                  <Input
+                    aria-label="Skill budget (USD)"
+                    type="number"
+                    min={0}
+                    step="0.01"
+                    placeholder="USD"
+                    value={newBudgetAmount}
+                    onChange={(e) => setNewBudgetAmount(e.target.value)}
+                    className="w-28"
+                  />
+                  <Button
+                    variant="outline"
+                    size="sm"
+                    disabled={!newBudgetSkill.trim() || parseBudget(newBudgetAmount) === null}
+                    onClick={() => {
+                      const amount = parseBudget(newBudgetAmount)
+                      if (amount === null) return
+                      const next = { ...skillBudgets, [newBudgetSkill.trim()]: amount }
+                      setSkillBudgets(next)
+                      setNewBudgetSkill("")
+                      setNewBudgetAmount("")
+                      autoSave({ skillBudgets: next })
+                    }}
+                  >
+                    <Plus className="size-4" />
+                    Add budget
+                  </Button>
+                </div>
+              </CardContent>
+            </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle>Prompt Templates</CardTitle>
+                <CardDescription>
+                  Agent prompts in this folder replace the bundled ones with the same file name. Edits are picked up on the next step run.
+                </CardDescription>
+              </CardHeader>
+              <CardContent className="flex flex-col gap-2">
+                <Label htmlFor="custom-prompts-dir">Custom prompts folder</Label>
+                <div className="flex items-center gap-2">
+                  <Input
+                    id="custom-prompts-dir"
+                    placeholder="Leave empty to use bundled prompts"
+                    className="font-mono"
+                    value={customPromptsDir}
+                    onChange={(e) => setCustomPromptsDir(e.target.value)}
+                    onBlur={() => autoSave({ customPromptsDir: customPromptsDir.trim() || null })}
+                  />
+                  <Button variant="outline" size="sm" disabled={reloadingPrompts} onClick={handleReloadPrompts}>
+                    {reloadingPrompts ? <Loader2 className="size-4 animate-spin" /> : <RefreshCw className="size-4" />}
+                    Reload prompts
+                  </Button>
+                </div>
+              </CardContent>
+            </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle>Skill Usage</CardTitle>
+                <CardDescription>
+                  Import how often your skills are invoked from Claude Code's local session history.
+                </CardDescription>
+              </CardHeader>
+              <CardContent className="flex flex-col gap-2">
+                <Label htmlFor="claude-data-dir">Claude Code data folder</Label>
+                <div className="flex items-center gap-2">
+                  <Input
+                    id="claude-data-dir"
+                    placeholder="~/.claude"
+                    className="font-mono"
+                    value={claudeDataDir}
+                    onChange={(e) => setClaudeDataDir(e.target.value)}
+                    onBlur={() => autoSave({ claudeDataDir: claudeDataDir.trim() || null })}
+                  />
+                  <Button variant="outline" size="sm" disabled={importingInvocations} onClick={handleImportInvocations}>
+                    {importingInvocations ? <Loader2 className="size-4 animate-spin" /> : <RefreshCw className="size-4" />}
+                    Import usage
+                  </Button>
+                </div>
+              </CardContent>
+            </Card>
           </div>
           )}
 
           {activeSection === "skills" && (
           <div className="space-y-6 p-6">
             <WorkspaceSkillsTab />
+
+            <Card>
+              <CardHeader>
+                <CardTitle>Tags</CardTitle>
+                <CardDescription>
+                  Use <code>/</code> to nest tags, e.g. <code>finance/revenue/rev-rec</code>. Renaming or merging a tag moves the tags under it and keeps the old name as an alias.
+                </CardDescription>
+              </CardHeader>
+              <CardContent>
+                <TagTaxonomySettings />
+              </CardContent>
+            </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle>Import Activation</CardTitle>
+                <CardDescription>
+                  Decide whether newly imported skills start active. Rules are checked in order, then your team&apos;s default rules; the first match wins.
+                </CardDescription>
+              </CardHeader>
+              <CardContent className="flex flex-col gap-4">
+                {activationPolicy.rules.map((rule, index) => (
+                  <div key={index} className="flex flex-wrap items-end gap-2 rounded-md border p-3" data-testid="activation-rule">
+                    <div className="flex flex-col gap-1">
+                      <Label htmlFor={`activation-action-${index}`} className="text-xs">Action</Label>
+                      <select
+                        id={`activation-action-${index}`}
+                        value={rule.action}
+                        onChange={(e) => updateActivationRule(index, { action: e.target.value as ActivationRule["action"] }, true)}
+                        className="flex h-9 rounded-md border border-input bg-transparent px-3 py-1 text-sm shadow-sm"
+                      >
+                        <option value="activate">Activate</option>
+                        <option value="deactivate">Keep inactive</option>
+                      </select>
+                    </div>
+                    {(["source", "author", "domain"] as const).map((field) => (
+                      <div key={field} className="flex min-w-32 flex-1 flex-col gap-1">
+                        <Label htmlFor={`activation-${field}-${index}`} className="text-xs capitalize">{field}</Label>
+                        <Input
+                          id={`activation-${field}-${index}`}
+                          placeholder={field === "source" ? "owner/repo or upload" : "any"}
+                          value={rule[field] ?? ""}
+                          onChange={(e) => updateActivationRule(index, { [field]: e.target.value || null }, false)}
+                          onBlur={() => autoSave({ activationPolicy })}
+                        />
+                      </div>
+                    ))}
+                    <div className="flex flex-col gap-1">
+                      <Label htmlFor={`activation-signature-${index}`} className="text-xs">Signature</Label>
+                      <select
+                        id={`activation-signature-${index}`}
+                        value={rule.signature ?? ""}
+                        onChange={(e) => updateActivationRule(index, { signature: (e.target.value || null) as ActivationRule["signature"] }, true)}
+                        className="flex h-9 rounded-md border border-input bg-transparent px-3 py-1 text-sm shadow-sm"
+                      >
+                        <option value="">Any</option>
+                        <option value="trusted">Trusted</option>
+                        <option value="untrusted">Untrusted</option>
+                        <option value="unsigned">Unsigned</option>
+                      </select>
+                    </div>
+                    <Button
+                      variant="ghost"
+                      size="icon"
+                      aria-label={`Remove rule ${index + 1}`}
+                      onClick={() => saveActivationPolicy({ ...activationPolicy, rules: activationPolicy.rules.filter((_, i) => i !== index) })}
+                    >
+                      <Trash2 className="size-4" />
+                    </Button>
+                  </div>
+                ))}
+                <div className="flex items-center justify-between gap-4">
+                  <Button
+                    variant="outline"
+                    size="sm"
+                    className="w-fit"
+                    onClick={() => saveActivationPolicy({ ...activationPolicy, rules: [...activationPolicy.rules, { action: "activate", source: null, author: null, domain: null, signature: null }] })}
+                  >
+                    <Plus className="size-4" />
+                    Add rule
+                  </Button>
+                  <div className="flex items-center gap-2">
+                    <Label htmlFor="activation-default" className="text-sm">When no rule matches</Label>
+                    <select
+                      id="activation-default"
+                      value={activationPolicy.default_action}
+                      onChange={(e) => saveActivationPolicy({ ...activationPolicy, default_action: e.target.value as ActivationPolicy["default_action"] })}
+                      className="flex h-9 rounded-md border border-input bg-transparent px-3 py-1 text-sm shadow-sm"
+                    >
+                      <option value="activate">Activate</option>
+                      <option value="deactivate">Keep inactive</option>
+                    </select>
+                  </div>
+                </div>
+              </CardContent>
+            </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle>Package Signatures</CardTitle>
+                <CardDescription>
+                  Packages you export are signed once you have a signing key. Share your public key so others can trust your packages, and add the keys of people whose packages you trust.
+                </CardDescription>
+              </CardHeader>
+              <CardContent className="flex flex-col gap-4">
+                <div className="flex items-center justify-between gap-4">
+                  <Label htmlFor="require-trusted-packages">Only import packages signed by a trusted key</Label>
+                  <Switch
+                    id="require-trusted-packages"
+                    checked={requireTrustedPackages}
+                    onCheckedChange={(checked) => {
+                      setRequireTrustedPackages(checked)
+                      autoSave({ requireTrustedPackages: checked })
+                    }}
+                  />
+                </div>
+                <TrustedKeysSettings />
+              </CardContent>
+            </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle>Accessibility Lint</CardTitle>
+                <CardDescription>
+                  Check skill files for images without alt text and skipped heading levels when packaging.
+                </CardDescription>
+              </CardHeader>
+              <CardContent className="flex items-center justify-between gap-4">
+                <Label htmlFor="accessibility-lint">When issues are found</Label>
+                <select
+                  id="accessibility-lint"
+                  value={accessibilityLint}
+                  onChange={(e) => {
+                    const mode = e.target.value as AccessibilityLintMode
+                    setAccessibilityLint(mode)
+                    autoSave({ accessibilityLint: mode })
+                  }}
+                  className="flex h-9 rounded-md border border-input bg-transparent px-3 py-1 text-sm shadow-sm"
+                >
+                  <option value="advisory">Warn and package</option>
+                  <option value="blocking">Block packaging</option>
+                  <option value="off">Don&apos;t check</option>
+                </select>
+              </CardContent>
+            </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle>Quality Gate</CardTitle>
+                <CardDescription>
+                  Checks that must pass before a skill is packaged. Profiles come from the team repository or are built in; failing checks can be waived per skill with a justification.
+                </CardDescription>
+              </CardHeader>
+              <CardContent className="flex items-center justify-between gap-4">
+                <Label htmlFor="quality-gate-profile">Profile</Label>
+                <select
+                  id="quality-gate-profile"
+                  value={qualityGateProfile}
+                  onChange={(e) => {
+                    setQualityGateProfile(e.target.value)
+                    autoSave({ qualityGateProfile: e.target.value || null })
+                  }}
+                  className="flex h-9 rounded-md border border-input bg-transparent px-3 py-1 text-sm shadow-sm"
+                >
+                  <option value="">No gate</option>
+                  {qualityGateProfiles.map((p) => (
+                    <option key={p.name} value={p.name} title={p.description ?? undefined}>
+                      {p.name} ({p.checks.join(", ")})
+                    </option>
+                  ))}
+                </select>
+              </CardContent>
+            </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle>Review Dates</CardTitle>
+                <CardDescription>
+                  Skills with a review-by date show a reminder on the dashboard two weeks before it passes.
+                </CardDescription>
+              </CardHeader>
+              <CardContent className="flex items-center justify-between gap-4">
+                <Label htmlFor="block-expired-packaging">Block packaging of expired skills until reviewed</Label>
+                <Switch
+                  id="block-expired-packaging"
+                  checked={blockExpiredPackaging}
+                  onCheckedChange={(checked) => {
+                    setBlockExpiredPackaging(checked)
+                    autoSave({ blockExpiredPackaging: checked })
+                  }}
+                />
+              </CardContent>
+            </Card>
           </div>
           )}
 
@@ -638,6 +1429,153 @@ export default function SettingsPage() {
               </CardContent>
             </Card>
 
+            <Card>
+              <CardHeader>
+                <CardTitle>GitHub Enterprise Server</CardTitle>
+                <CardDescription>
+                  Use a self-hosted GitHub instead of github.com for sign-in, marketplace registries, imports, the team repository and pushes. Leave the URL empty for github.com.
+                </CardDescription>
+              </CardHeader>
+              <CardContent className="flex flex-col gap-2">
+                <Label htmlFor="github-enterprise-url">Server URL</Label>
+                <Input
+                  id="github-enterprise-url"
+                  placeholder="https://github.example.com"
+                  className="font-mono"
+                  value={githubEnterpriseUrl}
+                  onChange={(e) => setGithubEnterpriseUrl(e.target.value)}
+                  onBlur={handleGithubEnterpriseUrlBlur}
+                />
+                <Label htmlFor="github-oauth-client-id">OAuth app client ID</Label>
+                <Input
+                  id="github-oauth-client-id"
+                  placeholder="Required to sign in to an enterprise server"
+                  className="font-mono"
+                  value={githubOauthClientId}
+                  onChange={(e) => setGithubOauthClientId(e.target.value)}
+                  onBlur={() => autoSave({ githubOauthClientId: githubOauthClientId.trim() || null })}
+                />
+                <Label htmlFor="github-ca-cert-path">CA certificate bundle</Label>
+                <Input
+                  id="github-ca-cert-path"
+                  placeholder="/etc/ssl/certs/corp-ca.pem"
+                  className="font-mono"
+                  value={githubCaCertPath}
+                  onChange={(e) => setGithubCaCertPath(e.target.value)}
+                  onBlur={() => autoSave({ githubCaCertPath: githubCaCertPath.trim() || null })}
+                />
+                <div className="flex items-center justify-between gap-4 pt-2">
+                  <Label htmlFor="github-accept-invalid-certs">Accept invalid TLS certificates (insecure)</Label>
+                  <Switch
+                    id="github-accept-invalid-certs"
+                    checked={githubAcceptInvalidCerts}
+                    onCheckedChange={(checked) => {
+                      setGithubAcceptInvalidCerts(checked)
+                      autoSave({ githubAcceptInvalidCerts: checked })
+                    }}
+                  />
+                </div>
+              </CardContent>
+            </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle>GitLab &amp; Bitbucket</CardTitle>
+                <CardDescription>
+                  Connect with an access token to browse, import and share skills in GitLab or Bitbucket repositories.
+                </CardDescription>
+              </CardHeader>
+              <CardContent>
+                <GitHostingAccounts />
+              </CardContent>
+            </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle>Team Repository</CardTitle>
+                <CardDescription>
+                  Repository holding your team's shared configuration, such as the tag and domain taxonomy.
+                </CardDescription>
+              </CardHeader>
+              <CardContent className="flex flex-col gap-2">
+                <Label htmlFor="team-repo">Repository</Label>
+                <Input
+                  id="team-repo"
+                  placeholder="owner/repo or owner/repo#branch"
+                  value={teamRepo}
+                  onChange={(e) => setTeamRepo(e.target.value)}
+                  onBlur={() => autoSave({ teamRepo: teamRepo.trim() || null })}
+                />
+              </CardContent>
+            </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle>Settings Sync</CardTitle>
+                <CardDescription>
+                  Share models, registries, tool policy and budgets across machines through a git repository. API keys, sign-in and local paths are never shared. Shared settings are pulled at startup.
+                </CardDescription>
+              </CardHeader>
+              <CardContent className="flex flex-col gap-2">
+                <Label htmlFor="settings-sync-repo">Git repository</Label>
+                <Input
+                  id="settings-sync-repo"
+                  placeholder="https://github.com/org/settings.git#main"
+                  className="font-mono"
+                  value={settingsSyncRepo}
+                  onChange={(e) => setSettingsSyncRepo(e.target.value)}
+                  onBlur={() => autoSave({ settingsSyncRepo: settingsSyncRepo.trim() || null })}
+                />
+                <Label htmlFor="settings-sync-overrides">Local overrides</Label>
+                <Input
+                  id="settings-sync-overrides"
+                  placeholder="preferred_model, max_concurrent_agents"
+                  className="font-mono"
+                  value={settingsSyncOverrides}
+                  onChange={(e) => setSettingsSyncOverrides(e.target.value)}
+                  onBlur={() => autoSave({ settingsSyncOverrides: parseKeywords(settingsSyncOverrides) })}
+                />
+                <div className="flex items-center gap-2">
+                  <Button variant="outline" size="sm" disabled={!settingsSyncRepo.trim() || settingsSyncing !== null} onClick={() => handleSettingsSync("pull")}>
+                    {settingsSyncing === "pull" ? <Loader2 className="size-4 animate-spin" /> : <Download className="size-4" />}
+                    Pull
+                  </Button>
+                  <Button variant="outline" size="sm" disabled={!settingsSyncRepo.trim() || settingsSyncing !== null} onClick={() => handleSettingsSync("push")}>
+                    {settingsSyncing === "push" ? <Loader2 className="size-4 animate-spin" /> : <Upload className="size-4" />}
+                    Push
+                  </Button>
+                </div>
+              </CardContent>
+            </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle>Feedback Attachments</CardTitle>
+                <CardDescription>
+                  Attachments are stripped of image metadata before upload. Keywords are redacted from text files; text inside screenshots is not inspected.
+                </CardDescription>
+              </CardHeader>
+              <CardContent className="flex flex-col gap-2">
+                <Label htmlFor="feedback-keywords">Redacted keywords</Label>
+                <Input
+                  id="feedback-keywords"
+                  placeholder="acme, internal-host, customer names"
+                  value={feedbackKeywords}
+                  onChange={(e) => setFeedbackKeywords(e.target.value)}
+                  onBlur={() => autoSave({ feedbackKeywords: parseKeywords(feedbackKeywords) })}
+                />
+                <Label htmlFor="feedback-public-key">Maintainer public key</Label>
+                <Input
+                  id="feedback-public-key"
+                  placeholder="Base64 X25519 key"
+                  className="font-mono"
+                  value={feedbackPublicKey}
+                  onChange={(e) => setFeedbackPublicKey(e.target.value)}
+                  onBlur={() => autoSave({ feedbackPublicKey: feedbackPublicKey.trim() || null })}
+                />
+              </CardContent>
+            </Card>
+
           </div>
           )}
 
@@ -886,6 +1824,67 @@ export default function SettingsPage() {
               </CardContent>
             </Card>
 
+            <Card>
+              <CardHeader>
+                <CardTitle>Background Tasks</CardTitle>
+                <CardDescription>
+                  Heavy work such as catalog refreshes waits while agents are running or the battery is low. Raise a task to High to run it anyway.
+                </CardDescription>
+              </CardHeader>
+              <CardContent>
+                <BackgroundTaskQueue />
+              </CardContent>
+            </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle>Automation Hooks</CardTitle>
+                <CardDescription>
+                  Run a shell command or call a webhook when a step completes, a skill is packaged or a budget stops an agent. The event is sent as JSON; failed deliveries are retried.
+                </CardDescription>
+              </CardHeader>
+              <CardContent>
+                <AutomationHooksSettings
+                  hooks={automationHooks}
+                  onChange={(hooks) => {
+                    setAutomationHooks(hooks)
+                    autoSave({ automationHooks: hooks })
+                  }}
+                />
+              </CardContent>
+            </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle>Audit Log</CardTitle>
+                <CardDescription>
+                  Every skill creation, rename, deletion, push, lock change and settings change, with the instance and user that made it. Entries cannot be edited, and are only deleted by the retention policy below.
+                </CardDescription>
+              </CardHeader>
+              <CardContent>
+                <AuditLogViewer workspacePath={workspacePath} />
+              </CardContent>
+            </Card>
+
+            <Card>
+              <CardHeader>
+                <CardTitle>Data Retention</CardTitle>
+                <CardDescription>
+                  How long agent runs, workflow sessions, archived transcripts and audit entries are kept.
+                </CardDescription>
+              </CardHeader>
+              <CardContent>
+                <DataRetentionSettings
+                  policy={retention}
+                  workspacePath={workspacePath}
+                  onChange={(policy) => {
+                    setRetention(policy)
+                    autoSave({ retention: policy })
+                  }}
+                />
+              </CardContent>
+            </Card>
+
             <Card>
               <CardHeader>
                 <CardTitle>Storage</CardTitle>
@@ -906,6 +1905,25 @@ export default function SettingsPage() {
                       Browse
                     </Button>
                   </div>
+                  <div className="flex items-center gap-2">
+                    <Input
+                      placeholder="Or paste a folder path (e.g. ~/skills)"
+                      value={skillsPathInput}
+                      onChange={(e) => setSkillsPathInput(e.target.value)}
+                      onKeyDown={(e) => {
+                        if (e.key === "Enter") handleApplySkillsPathInput()
+                      }}
+                      className="text-sm"
+                    />
+                    <Button
+                      variant="outline"
+                      size="sm"
+                      onClick={handleApplySkillsPathInput}
+                      disabled={!skillsPathInput.trim()}
+                    >
+                      Apply
+                    </Button>
+                  </div>
                 </div>
                 <div className="flex flex-col gap-1.5">
                   <Label>Data Directory</Label>
@@ -916,6 +1934,23 @@ export default function SettingsPage() {
                     </code>
                   </div>
                 </div>
+                <div className="flex flex-col gap-1.5">
+                  <div className="flex items-center gap-4">
+                    <Label htmlFor="transcript-archive-mb">Transcript archive (MB)</Label>
+                    <Input
+                      id="transcript-archive-mb"
+                      type="number"
+                      min={0}
+                      value={transcriptArchiveMb}
+                      onChange={(e) => setTranscriptArchiveMb(Math.max(0, parseInt(e.target.value) || 0))}
+                      onBlur={() => autoSave({ transcriptArchiveMb })}
+                      className="w-24"
+                    />
+                  </div>
+                  <p className="text-xs text-muted-foreground">
+                    Finished agent transcripts are kept in the database up to this size; the least recently viewed go first. 0 turns the archive off.
+                  </p>
+                </div>
               </CardContent>
             </Card>
 
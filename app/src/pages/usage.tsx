@@ -1,5 +1,5 @@
 import { useEffect, useState, useMemo } from "react"
-import { Loader2, DollarSign, Activity, TrendingUp, RotateCcw, ChevronUp, ChevronDown, CheckCircle2, XCircle } from "lucide-react"
+import { Loader2, DollarSign, Activity, TrendingUp, RotateCcw, ChevronUp, ChevronDown, CheckCircle2, XCircle, ListChecks } from "lucide-react"
 import type { UsageByDay } from "@/lib/types"
 import { toast } from "sonner"
 import { Button } from "@/components/ui/button"
@@ -23,6 +23,7 @@ import {
   AlertDialogTrigger,
 } from "@/components/ui/alert-dialog"
 import { useUsageStore, type DateRange } from "@/stores/usage-store"
+import { ToolCallAuditDialog } from "@/components/tool-call-audit-dialog"
 
 const STEP_NAMES: Record<number, string> = {
   [-11]: "Test",
@@ -217,6 +218,7 @@ export default function UsagePage() {
   const [stepFilter, setStepFilter] = useState<number | "all">("all")
   const [sortCol, setSortCol] = useState<SortCol>("date")
   const [sortDir, setSortDir] = useState<"asc" | "desc">("desc")
+  const [auditAgentId, setAuditAgentId] = useState<string | null>(null)
 
   useEffect(() => {
     fetchUsage()
@@ -592,6 +594,7 @@ export default function UsagePage() {
                       </button>
                     </th>
                   ))}
+                  <th scope="col" className="pr-4 py-2 text-xs font-medium text-muted-foreground border-b border-border text-center">Tools</th>
                 </tr>
               </thead>
               <tbody>
@@ -632,6 +635,16 @@ export default function UsagePage() {
                       <td className="pr-4 py-2 text-right text-xs font-mono text-muted-foreground border-b border-border/50">
                         {formatTokensShort(tokens)}
                       </td>
+                      <td className="pr-4 py-2 text-center border-b border-border/50">
+                        <button
+                          type="button"
+                          aria-label={`Tool calls for ${run.agent_id}`}
+                          onClick={() => setAuditAgentId(run.agent_id)}
+                          className="text-muted-foreground hover:text-foreground transition-colors"
+                        >
+                          <ListChecks className="size-3.5 mx-auto" />
+                        </button>
+                      </td>
                     </tr>
                   )
                 })}
@@ -640,6 +653,10 @@ export default function UsagePage() {
           )}
         </CardContent>
       </Card>
+      <ToolCallAuditDialog
+        agentId={auditAgentId}
+        onOpenChange={(open) => { if (!open) setAuditAgentId(null) }}
+      />
     </div>
   )
 }
@@ -7,6 +7,7 @@ import {
   AlertCircle,
   RotateCcw,
   Loader2,
+  Eye,
 } from "lucide-react";
 import { toast } from "sonner";
 import { Button } from "@/components/ui/button";
@@ -25,9 +26,10 @@ import { AgentOutputPanel } from "@/components/agent-output-panel";
 import { AgentInitializingIndicator } from "@/components/agent-initializing-indicator";
 import { RuntimeErrorDialog } from "@/components/runtime-error-dialog";
 import { WorkflowStepComplete } from "@/components/workflow-step-complete";
+import { StepContextCard } from "@/components/step-context-card";
 import ResetStepDialog from "@/components/reset-step-dialog";
 import "@/hooks/use-agent-stream";
-import { useWorkflowStore } from "@/stores/workflow-store";
+import { useWorkflowStore, type WorkflowStep } from "@/stores/workflow-store";
 import { useAgentStore, flushMessageBuffer } from "@/stores/agent-store";
 import { useSettingsStore } from "@/stores/settings-store";
 import {
@@ -43,17 +45,28 @@ import {
   cleanupSkillSidecar,
   acquireLock,
   releaseLock,
+  openSkillReadonly,
+  closeSkillReadonly,
+  pollSkillEvents,
   verifyStepOutput,
   endWorkflowSession,
   getDisabledSteps,
+  getWorkflowSteps,
   runAnswerEvaluator,
   logGateDecision,
   materializeAnswerEvaluationOutput,
   materializeWorkflowStepOutput,
   navigateBackToStepDb,
+  getBudgetStatus,
+  findCachedStepRun,
+  findResumableStepRun,
+  resumeWorkflowStep,
+  reuseCachedStepRun,
   type AnswerEvaluation,
 } from "@/lib/tauri";
+import type { AgentRunCheckpoint, CachedStepRun } from "@/lib/types";
 import { TransitionGateDialog, type GateVerdict } from "@/components/transition-gate-dialog";
+import { toastCommandError } from "@/components/error-toast";
 import { resolveModelId } from "@/lib/models";
 
 // --- Step config ---
@@ -74,6 +87,16 @@ const STEP_CONFIGS: Record<number, StepConfig> = {
   3: { type: "agent", outputFiles: ["skill/SKILL.md", "skill/references/"], model: "sonnet" },
 };
 
+/** Steps added by a workflow manifest run as plain agent steps. */
+const CUSTOM_STEP_CONFIG: StepConfig = { type: "agent" };
+
+/** How often a read-only workflow checks for changes from the editing instance. */
+const SKILL_EVENT_POLL_MS = 3000;
+
+function stepConfigFor(stepId: number): StepConfig {
+  return STEP_CONFIGS[stepId] ?? CUSTOM_STEP_CONFIG;
+}
+
 export default function WorkflowPage() {
   const { skillName } = useParams({ from: "/skill/$skillName" });
   const navigate = useNavigate();
@@ -87,6 +110,7 @@ export default function WorkflowPage() {
     isInitializing,
     hydrated,
     reviewMode,
+    readOnly,
     disabledSteps,
     gateLoading,
     setGateLoading,
@@ -195,7 +219,7 @@ export default function WorkflowPage() {
     };
   }, [skillName]);
 
-  const stepConfig = STEP_CONFIGS[currentStep];
+  const stepConfig = stepConfigFor(currentStep);
 
   // Clarifications editing state (for steps with clarificationsEditable)
   const [reviewContent, setReviewContent] = useState<string | null>(null);
@@ -249,6 +273,10 @@ export default function WorkflowPage() {
   const lastCompletedCostRef = useRef<number | undefined>(undefined);
   const [gateContext, setGateContext] = useState<"clarifications" | "refinements">("clarifications");
 
+  // Earlier run with identical inputs — offered for reuse before starting the agent
+  const [cachedRun, setCachedRun] = useState<CachedStepRun | null>(null);
+  const [resumableRun, setResumableRun] = useState<AgentRunCheckpoint | null>(null);
+
   // Target step for reset confirmation dialog (when clicking a prior step)
   const [resetTarget, setResetTarget] = useState<number | null>(null);
 
@@ -258,7 +286,7 @@ export default function WorkflowPage() {
     const store = useWorkflowStore.getState();
     if (store.pendingUpdateMode) {
       store.setPendingUpdateMode(false);
-      store.setReviewMode(false);
+      if (!store.readOnly) store.setReviewMode(false);
     }
   };
 
@@ -285,14 +313,27 @@ export default function WorkflowPage() {
 
     // Read workflow state from SQLite
     getWorkflowState(skillName)
-      .then((state) => {
+      .then(async (state) => {
         if (cancelled) return;
         if (!state.run) {
           setHydrated(true);
           return;
         }
 
-        initWorkflow(skillName, state.run.purpose);
+        // Custom steps from the purpose's workflow manifest follow the built-in ones.
+        const customSteps = await getWorkflowSteps(skillName)
+          .then((configs) =>
+            configs
+              .filter((c) => c.custom)
+              .map((c) => ({ id: c.step_id, name: c.name, description: c.description ?? "" })),
+          )
+          .catch((err) => {
+            toast.error(`Could not load workflow steps: ${err instanceof Error ? err.message : String(err)}`);
+            return [];
+          });
+        if (cancelled) return;
+
+        initWorkflow(skillName, state.run.purpose, customSteps);
 
         const completedIds = state.steps
           .filter((s) => s.status === "completed")
@@ -329,24 +370,78 @@ export default function WorkflowPage() {
   }, [skillName]);
 
   // --- Skill lock management ---
-  // Acquire lock when entering workflow, release when leaving.
+  // Acquire lock when entering workflow, release when leaving. If another
+  // instance holds the lock, open the skill read-only instead of leaving.
+  const lastEventIdRef = useRef(0);
   useEffect(() => {
     let mounted = true;
+    useWorkflowStore.getState().setReadOnly(false);
 
-    acquireLock(skillName).catch((err) => {
-      if (mounted) {
-        toast.error(`Could not lock skill: ${err instanceof Error ? err.message : String(err)}`, { duration: Infinity });
+    acquireLock(skillName).catch(async (err) => {
+      if (!mounted) return;
+      try {
+        const opened = await openSkillReadonly(skillName);
+        if (!mounted) {
+          closeSkillReadonly(skillName).catch(() => {});
+          return;
+        }
+        lastEventIdRef.current = opened.last_event_id;
+        useWorkflowStore.getState().setReadOnly(true);
+        toast.info("Another instance is editing this skill. Opened read-only.");
+      } catch {
+        void toastCommandError("Could not lock skill", err);
         navigate({ to: "/" });
       }
     });
 
     return () => {
       mounted = false;
-      // Fire-and-forget: release lock on unmount
-      releaseLock(skillName).catch(() => {});
+      // Fire-and-forget: release lock (or stop observing) on unmount
+      if (useWorkflowStore.getState().readOnly) {
+        useWorkflowStore.getState().setReadOnly(false);
+        closeSkillReadonly(skillName).catch(() => {});
+      } else {
+        releaseLock(skillName).catch(() => {});
+      }
     };
   }, [skillName, navigate]);
 
+  // While read-only, reload the workflow state whenever the editing instance changes it.
+  useEffect(() => {
+    if (!readOnly) return;
+    let polling = false;
+
+    const refresh = async () => {
+      const state = await getWorkflowState(skillName);
+      const store = useWorkflowStore.getState();
+      if (!state.run || store.skillName !== skillName) return;
+      for (const step of state.steps) {
+        store.updateStepStatus(step.step_id, step.status as WorkflowStep["status"]);
+      }
+      store.setCurrentStep(state.run.current_step);
+    };
+
+    const timer = setInterval(async () => {
+      if (polling) return;
+      polling = true;
+      try {
+        const events = await pollSkillEvents(skillName, lastEventIdRef.current);
+        if (events.length === 0) return;
+        lastEventIdRef.current = events[events.length - 1].id;
+        await refresh();
+        if (events.some((e) => e.kind === "lock.released")) {
+          toast.info("The other instance closed this skill. Reopen it to edit.", { duration: Infinity });
+        }
+      } catch (err) {
+        console.warn("[workflow] poll_skill_events failed:", err);
+      } finally {
+        polling = false;
+      }
+    }, SKILL_EVENT_POLL_MS);
+
+    return () => clearInterval(timer);
+  }, [readOnly, skillName]);
+
   // Reset state when moving to a new step
   useEffect(() => {
     setErrorHasArtifacts(false);
@@ -358,7 +453,7 @@ export default function WorkflowPage() {
     const stepStatus = steps[currentStep]?.status;
 
     if (stepStatus === "error" && skillName) {
-      const cfg = STEP_CONFIGS[currentStep];
+      const cfg = stepConfigFor(currentStep);
       const firstOutput = cfg?.outputFiles?.[0];
       if (firstOutput) {
         if (firstOutput.startsWith("context/") && workspacePath) {
@@ -386,13 +481,13 @@ export default function WorkflowPage() {
   // Debounced SQLite persistence — saves workflow state at most once per 300ms
   // instead of firing synchronously on every step/status change.
   useEffect(() => {
-    if (!hydrated) return;
+    if (!hydrated || readOnly) return;
     const store = useWorkflowStore.getState();
     if (store.skillName !== skillName) return;
 
     const timer = setTimeout(() => {
       const latestStore = useWorkflowStore.getState();
-      if (latestStore.skillName !== skillName) return;
+      if (latestStore.skillName !== skillName || latestStore.readOnly) return;
 
       const stepStatuses = latestStore.steps.map((s) => ({
         step_id: s.id,
@@ -414,7 +509,7 @@ export default function WorkflowPage() {
     }, 300);
 
     return () => clearTimeout(timer);
-  }, [steps, currentStep, skillName, purpose, hydrated]);
+  }, [steps, currentStep, skillName, purpose, hydrated, readOnly]);
 
   // Load clarifications file when viewing a completed clarifications-editable step.
   // Context files are backend-owned under workspace path.
@@ -446,7 +541,7 @@ export default function WorkflowPage() {
   const isAgentType = stepConfig?.type === "agent" || stepConfig?.type === "reasoning";
 
   const autoStartAfterReset = (stepId: number) => {
-    const cfg = STEP_CONFIGS[stepId];
+    const cfg = stepConfigFor(stepId);
     if ((cfg?.type === "agent" || cfg?.type === "reasoning") && !useWorkflowStore.getState().reviewMode) {
       setPendingAutoStartStep(stepId);
     }
@@ -510,7 +605,7 @@ export default function WorkflowPage() {
   // they're switching to update mode specifically to edit answers.
   useEffect(() => {
     if (!hydrated || reviewMode) return;
-    const currentCfg = STEP_CONFIGS[currentStep];
+    const currentCfg = stepConfigFor(currentStep);
     if (currentCfg?.clarificationsEditable && steps[currentStep]?.status === "completed") {
       return; // stay on this step for editing
     }
@@ -653,7 +748,10 @@ export default function WorkflowPage() {
 
       finish();
     } else if (activeRunStatus === "error") {
-      updateStepStatus(step, "error");
+      // The backend ends an agent that crosses a budget with a budget_exceeded error.
+      const budgetStop = useAgentStore.getState().runs[activeAgentId]?.messages
+        .find((m) => m.raw?.subtype === "budget_exceeded");
+      updateStepStatus(step, budgetStop ? "budget_exceeded" : "error");
       setRunning(false);
       setActiveAgent(null);
       // Clear initializing state if the agent errored before sending any messages
@@ -661,7 +759,7 @@ export default function WorkflowPage() {
       if (workflowState.isInitializing) {
         workflowState.clearInitializing();
       }
-      toast.error(`Step ${step + 1} failed`, { duration: Infinity });
+      toast.error(budgetStop?.content ?? `Step ${step + 1} failed`, { duration: Infinity });
     }
   }, [
     activeRunStatus,
@@ -686,6 +784,55 @@ export default function WorkflowPage() {
       return;
     }
 
+    try {
+      const interrupted = await findResumableStepRun(skillName, currentStep);
+      if (interrupted) {
+        setResumableRun(interrupted);
+        return;
+      }
+    } catch (err) {
+      console.warn("[workflow] findResumableStepRun failed:", err);
+    }
+
+    await startOrReuseStep();
+  };
+
+  const startOrReuseStep = async () => {
+    if (!workspacePath) return;
+    try {
+      const cached = await findCachedStepRun(skillName, currentStep, workspacePath);
+      if (cached) {
+        setCachedRun(cached);
+        return;
+      }
+    } catch (err) {
+      console.warn("[workflow] findCachedStepRun failed:", err);
+    }
+
+    await runAgentStep();
+  };
+
+  const runAgentStep = async (resume = false) => {
+    if (!workspacePath) return;
+
+    try {
+      const budget = await getBudgetStatus(skillName);
+      if (budget.state === "warning") {
+        const skillPart = budget.skill_budget_usd != null
+          ? `${skillName}: $${budget.skill_spent_usd.toFixed(2)} of $${budget.skill_budget_usd.toFixed(2)}`
+          : null;
+        const globalPart = budget.global_budget_usd != null
+          ? `all skills: $${budget.global_spent_usd.toFixed(2)} of $${budget.global_budget_usd.toFixed(2)}`
+          : null;
+        toast.warning(
+          `Close to budget (${[skillPart, globalPart].filter(Boolean).join("; ")}). This step stops if a budget runs out.`,
+          { duration: Infinity },
+        );
+      }
+    } catch (err) {
+      console.warn("[workflow] getBudgetStatus failed:", err);
+    }
+
     try {
       clearRuns();
       clearRuntimeError();
@@ -693,12 +840,10 @@ export default function WorkflowPage() {
       setRunning(true);
       setInitializing();
 
-      console.log(`[workflow] Starting step ${currentStep} for skill "${skillName}"`);
-      const agentId = await runWorkflowStep(
-        skillName,
-        currentStep,
-        workspacePath,
-      );
+      console.log(`[workflow] ${resume ? "Resuming" : "Starting"} step ${currentStep} for skill "${skillName}"`);
+      const agentId = resume
+        ? await resumeWorkflowStep(skillName, currentStep, workspacePath)
+        : await runWorkflowStep(skillName, currentStep, workspacePath);
       agentStartRun(
         agentId,
         resolveModelId(
@@ -709,13 +854,48 @@ export default function WorkflowPage() {
       updateStepStatus(currentStep, "error");
       setRunning(false);
       clearInitializing();
-      toast.error(
-        `Failed to start agent: ${err instanceof Error ? err.message : String(err)}`,
-        { duration: Infinity },
-      );
+      void toastCommandError("Failed to start agent", err);
+    }
+  };
+
+  const handleReuseCachedRun = async () => {
+    if (!workspacePath || !cachedRun) return;
+    const step = cachedRun.step_id;
+    setCachedRun(null);
+    try {
+      await reuseCachedStepRun(skillName, step, workspacePath);
+      if (step === 0) {
+        try {
+          const disabled = await getDisabledSteps(skillName);
+          useWorkflowStore.getState().setDisabledSteps(disabled);
+        } catch {
+          // Non-fatal: proceed normally
+        }
+      }
+      clearRuns();
+      lastCompletedCostRef.current = 0;
+      updateStepStatus(step, "completed");
+      toast.success(`Step ${step + 1} completed from previous run`, { duration: 1500 });
+    } catch (err) {
+      void toastCommandError("Failed to reuse previous output", err);
     }
   };
 
+  const handleRunAgain = () => {
+    setCachedRun(null);
+    runAgentStep();
+  };
+
+  const handleResumeRun = () => {
+    setResumableRun(null);
+    runAgentStep(true);
+  };
+
+  const handleStartOver = () => {
+    setResumableRun(null);
+    startOrReuseStep();
+  };
+
   const runGateEvaluation = async () => {
     if (!workspacePath) return;
     console.log(`[workflow] Running answer evaluator gate for "${skillName}"`);
@@ -1126,14 +1306,19 @@ export default function WorkflowPage() {
     }
 
     // 4. Error state with retry
-    if (currentStepDef?.status === "error") {
+    if (currentStepDef?.status === "error" || currentStepDef?.status === "budget_exceeded") {
+      const overBudget = currentStepDef.status === "budget_exceeded";
       return (
         <div className="flex flex-1 flex-col items-center justify-center gap-4 text-muted-foreground">
           <AlertCircle className="size-8 text-destructive/50" />
           <div className="text-center">
-            <p className="font-medium text-destructive">Step {currentStep + 1} failed</p>
+            <p className="font-medium text-destructive">
+              {overBudget ? `Step ${currentStep + 1} stopped: budget reached` : `Step ${currentStep + 1} failed`}
+            </p>
             <p className="mt-1 text-sm">
-              An error occurred. You can retry this step.
+              {overBudget
+                ? "Raise the budget in Settings or reset usage, then retry this step."
+                : "An error occurred. You can retry this step."}
             </p>
           </div>
           {!reviewMode && (
@@ -1262,11 +1447,15 @@ export default function WorkflowPage() {
         executeReset={resetTarget !== null && resetTarget > 0
           ? () => navigateBackToStepDb(workspacePath ?? "", skillName, resetTarget)
           : undefined}
-        onReset={() => {
+        onReset={(currentStep) => {
           if (resetTarget !== null) {
             endActiveSession();
             clearRuns();
-            if (resetTarget === 0) {
+            if (currentStep !== undefined) {
+              // Selective reset: reset_workflow_artifacts restarted at the first
+              // step that lost a file; earlier steps keep their status.
+              resetToStep(currentStep);
+            } else if (resetTarget === 0) {
               // Step 0: full reset — re-runs from scratch.
               resetToStep(0);
             } else {
@@ -1304,6 +1493,55 @@ export default function WorkflowPage() {
         </Dialog>
       )}
 
+      {/* Resume dialog — shown when the last run of this step stopped before finishing */}
+      {resumableRun && (
+        <Dialog open onOpenChange={(open) => { if (!open) setResumableRun(null); }}>
+          <DialogContent showCloseButton={false}>
+            <DialogHeader>
+              <DialogTitle>Resume Interrupted Run?</DialogTitle>
+              <DialogDescription>
+                The run of this step started{" "}
+                {new Date(resumableRun.started_at).toLocaleString()} stopped after{" "}
+                {resumableRun.turns} turn{resumableRun.turns !== 1 ? "s" : ""}. Resuming picks up
+                where it left off instead of running the whole step again.
+              </DialogDescription>
+            </DialogHeader>
+            <DialogFooter>
+              <Button variant="outline" onClick={handleStartOver}>
+                Start Over
+              </Button>
+              <Button onClick={handleResumeRun}>
+                Resume
+              </Button>
+            </DialogFooter>
+          </DialogContent>
+        </Dialog>
+      )}
+
+      {/* Reuse dialog — shown when an earlier run of this step had identical inputs */}
+      {cachedRun && (
+        <Dialog open onOpenChange={(open) => { if (!open) setCachedRun(null); }}>
+          <DialogContent showCloseButton={false}>
+            <DialogHeader>
+              <DialogTitle>Reuse Previous Output?</DialogTitle>
+              <DialogDescription>
+                This step already ran with the same inputs on{" "}
+                {new Date(cachedRun.created_at).toLocaleString()}. Reusing its output
+                skips the agent{cachedRun.cost_usd > 0 ? ` and saves about $${cachedRun.cost_usd.toFixed(2)}` : ""}.
+              </DialogDescription>
+            </DialogHeader>
+            <DialogFooter>
+              <Button variant="outline" onClick={handleRunAgain}>
+                Run Again
+              </Button>
+              <Button onClick={handleReuseCachedRun}>
+                Reuse Output
+              </Button>
+            </DialogFooter>
+          </DialogContent>
+        </Dialog>
+      )}
+
       {/* Transition gate dialog — shown after step 0 (gate 1) or step 1 (gate 2) */}
       <TransitionGateDialog
         open={showGateDialog}
@@ -1365,10 +1603,22 @@ export default function WorkflowPage() {
               <h2 className="text-lg font-semibold">
                 Step {currentStep + 1}: {currentStepDef?.name}
               </h2>
-              <p className="text-sm text-muted-foreground">
-                {currentStepDef?.description}
-              </p>
+              <StepContextCard
+                skillName={skillName}
+                stepId={currentStep}
+                fallbackDescription={currentStepDef?.description}
+                refreshKey={currentStepDef?.status}
+              />
             </div>
+            {readOnly && (
+              <div
+                className="flex items-center gap-1.5 rounded-md border px-2 py-1 text-xs text-muted-foreground"
+                data-testid="read-only-banner"
+              >
+                <Eye className="size-3.5" />
+                Read-only: another instance is editing this skill
+              </div>
+            )}
           </div>
 
           {/* Content area — agent output panel manages its own padding */}
@@ -157,6 +157,31 @@ export interface ModelUsageBreakdown {
   cost: number;
 }
 
+/** Cumulative usage from the backend's `agent-cost-tick` events while a run is live. */
+export interface LiveCost {
+  inputTokens: number;
+  outputTokens: number;
+  cacheReadTokens: number;
+  cacheWriteTokens: number;
+  totalCost: number;
+  /** True until the SDK reports the actual cost with the result. */
+  estimated: boolean;
+}
+
+/** Latest `workflow-step-progress` event for a running workflow step. */
+export interface StepProgress {
+  turnsCompleted: number;
+  maxTurns: number | null;
+  currentTool: string | null;
+  tokensUsed: number;
+  /** Null when there is neither step history nor a turn limit to measure against. */
+  percent: number | null;
+  /** Null without step history, or once the step runs past its usual time. */
+  etaMs: number | null;
+  /** `Date.now()` when the event arrived, so the ETA can count down between events. */
+  receivedAt: number;
+}
+
 interface AgentRun {
   agentId: string;
   model: string;
@@ -179,6 +204,8 @@ interface AgentRun {
   numTurns?: number;
   durationApiMs?: number | null;
   modelUsageBreakdown?: ModelUsageBreakdown[];
+  liveCost?: LiveCost;
+  stepProgress?: StepProgress;
   runSource?: "workflow" | "refine" | "test";
   /** Optional synthetic session key used for non-workflow usage grouping. */
   usageSessionId?: string;
@@ -199,6 +226,8 @@ interface AgentState {
     usageSessionId?: string,
   ) => void;
   addMessage: (agentId: string, message: AgentMessage) => void;
+  recordCostTick: (agentId: string, liveCost: LiveCost) => void;
+  recordStepProgress: (agentId: string, stepProgress: StepProgress) => void;
   completeRun: (agentId: string, success: boolean) => void;
   shutdownRun: (agentId: string) => void;
   setActiveAgent: (agentId: string | null) => void;
@@ -243,6 +272,20 @@ function buildModelEntries(
     }));
   }
 
+  // A run cut short before its result falls back to the last cost tick, which
+  // carries cumulative figures across all turns.
+  const live = run.liveCost;
+  if (run.totalCost === undefined && live) {
+    return [{
+      model: run.model,
+      inputTokens: live.inputTokens,
+      outputTokens: live.outputTokens,
+      cacheReadTokens: live.cacheReadTokens,
+      cacheWriteTokens: live.cacheWriteTokens,
+      totalCost: live.totalCost,
+    }];
+  }
+
   // Fallback: single-model persistence using aggregate totals.
   // Extract cache tokens from the last assistant message's raw usage.
   let cacheRead = 0;
@@ -389,6 +432,22 @@ export const useAgentStore = create<AgentState>((set) => ({
     }
   },
 
+  recordCostTick: (agentId, liveCost) => {
+    set((state) => {
+      const run = state.runs[agentId];
+      if (!run) return state;
+      return { runs: { ...state.runs, [agentId]: { ...run, liveCost } } };
+    });
+  },
+
+  recordStepProgress: (agentId, stepProgress) => {
+    set((state) => {
+      const run = state.runs[agentId];
+      if (!run) return state;
+      return { runs: { ...state.runs, [agentId]: { ...run, stepProgress } } };
+    });
+  },
+
   completeRun: (agentId, success) => {
     // Flush any buffered messages so all data is applied before status changes
     flushMessageBuffer();
@@ -425,6 +484,12 @@ export const useAgentStore = create<AgentState>((set) => ({
         workflow.currentStep,
       );
 
+      // Time to first token: first assistant message relative to run start
+      const firstAssistant = runBeforeUpdate.messages.find((m) => m.type === "assistant");
+      const timeToFirstTokenMs = firstAssistant
+        ? Math.max(0, firstAssistant.timestamp - runBeforeUpdate.startTime)
+        : null;
+
       // Count tool uses across all assistant messages
       let toolUseCount = 0;
       for (const msg of runBeforeUpdate.messages) {
@@ -452,6 +517,7 @@ export const useAgentStore = create<AgentState>((set) => ({
           compactionCount: runBeforeUpdate.compactionEvents.length,
           sessionId: runBeforeUpdate.sessionId,
           workflowSessionId: persistenceContext.workflowSessionId,
+          timeToFirstTokenMs,
         },
         buildModelEntries(runBeforeUpdate),
       );
@@ -1,5 +1,5 @@
 import { create } from "zustand";
-import type { SkillSummary } from "@/lib/types";
+import type { RefineDiff, SkillSummary } from "@/lib/types";
 
 export interface SkillFile {
   filename: string; // e.g. "SKILL.md", "references/domain-glossary.md"
@@ -33,6 +33,7 @@ interface RefineState {
   activeFileTab: string; // filename key e.g. "SKILL.md"
   diffMode: boolean;
   baselineFiles: SkillFile[]; // snapshot before agent run
+  pendingDiff: RefineDiff | null; // uncommitted changes, reviewed per file
 
   // Chat messages
   messages: RefineMessage[];
@@ -56,6 +57,7 @@ interface RefineState {
   setActiveFileTab: (filename: string) => void;
   setDiffMode: (v: boolean) => void;
   snapshotBaseline: () => void;
+  setPendingDiff: (diff: RefineDiff | null) => void;
   addUserMessage: (text: string, targetFiles?: string[], command?: RefineCommand) => RefineMessage;
   addAgentTurn: (agentId: string) => RefineMessage;
   updateSkillFiles: (files: SkillFile[]) => void;
@@ -74,6 +76,7 @@ const SESSION_DEFAULTS = {
   sessionExhausted: false,
   diffMode: false,
   baselineFiles: [] as SkillFile[],
+  pendingDiff: null as RefineDiff | null,
   skillFiles: [] as SkillFile[],
   activeFileTab: "SKILL.md",
   pendingInitialMessage: null as string | null,
@@ -105,6 +108,8 @@ export const useRefineStore = create<RefineState>((set, get) => ({
     set({ baselineFiles: skillFiles.map((f) => ({ ...f })) });
   },
 
+  setPendingDiff: (diff) => set({ pendingDiff: diff }),
+
   addUserMessage: (text, targetFiles, command) => {
     const message: RefineMessage = {
       id: crypto.randomUUID(),
@@ -28,6 +28,10 @@ interface SettingsState {
   functionRole: string | null;
   dashboardViewMode: string | null;
   autoUpdate: boolean;
+  /** Agents run on a local model server; no Anthropic API key is needed. */
+  offlineMode: boolean;
+  marketplaceEnabled: boolean;
+  githubEnabled: boolean;
   isConfigured: boolean;
   availableModels: ModelInfo[];
   pendingUpgradeOpen: { mode: "dashboard-library" | "workspace-skills"; skills: string[] } | null;
@@ -58,6 +62,9 @@ const initialState = {
   functionRole: null,
   dashboardViewMode: null,
   autoUpdate: false,
+  offlineMode: false,
+  marketplaceEnabled: true,
+  githubEnabled: true,
   isConfigured: false,
   availableModels: [] as ModelInfo[],
   pendingUpgradeOpen: null as { mode: "dashboard-library" | "workspace-skills"; skills: string[] } | null,
@@ -70,7 +77,7 @@ export const useSettingsStore = create<SettingsState>((set) => ({
       const next = { ...state, ...settings };
       return {
         ...next,
-        isConfigured: !!next.anthropicApiKey && !!next.skillsPath,
+        isConfigured: (!!next.anthropicApiKey || next.offlineMode) && !!next.skillsPath,
       };
     }),
   setPendingUpgradeOpen: (value) => set({ pendingUpgradeOpen: value }),
@@ -1,13 +1,13 @@
 import { create } from "zustand";
 import type { RuntimeError } from "@/components/runtime-error-dialog";
 import { createWorkflowSession } from "@/lib/tauri";
-import { WORKFLOW_STEP_DEFINITIONS } from "@/lib/workflow-steps";
+import { WORKFLOW_STEP_DEFINITIONS, type WorkflowStepDefinition } from "@/lib/workflow-steps";
 
 export interface WorkflowStep {
   id: number;
   name: string;
   description: string;
-  status: "pending" | "in_progress" | "waiting_for_user" | "completed" | "error";
+  status: "pending" | "in_progress" | "waiting_for_user" | "completed" | "error" | "budget_exceeded";
 }
 
 interface WorkflowState {
@@ -18,6 +18,8 @@ interface WorkflowState {
   isRunning: boolean;
   /** When true, users can browse completed steps without triggering resets. */
   reviewMode: boolean;
+  /** True while another instance holds the skill's lock: review only, nothing is saved. */
+  readOnly: boolean;
   /** Active workflow session ID for usage tracking. Created when running starts, ended on navigate-away. */
   workflowSessionId: string | null;
   isInitializing: boolean;
@@ -37,9 +39,11 @@ interface WorkflowState {
   /** Transient: signals the workflow page to start in update mode. Set before navigation, consumed once by the init effect. */
   pendingUpdateMode: boolean;
 
-  initWorkflow: (skillName: string, purpose?: string) => void;
+  /** `customSteps` are appended after the built-in steps (from the purpose's workflow manifest). */
+  initWorkflow: (skillName: string, purpose?: string, customSteps?: WorkflowStepDefinition[]) => void;
   setPurpose: (purpose: string | null) => void;
   setReviewMode: (mode: boolean) => void;
+  setReadOnly: (readOnly: boolean) => void;
   setCurrentStep: (step: number) => void;
   updateStepStatus: (stepId: number, status: WorkflowStep["status"]) => void;
   setRunning: (running: boolean) => void;
@@ -74,6 +78,7 @@ export const useWorkflowStore = create<WorkflowState>((set, get) => ({
   steps: defaultSteps.map((s) => ({ ...s })),
   isRunning: false,
   reviewMode: true,
+  readOnly: false,
   workflowSessionId: null,
   isInitializing: false,
   initStartTime: null,
@@ -84,12 +89,15 @@ export const useWorkflowStore = create<WorkflowState>((set, get) => ({
   hydrated: false,
   disabledSteps: [],
 
-  initWorkflow: (skillName, purpose) =>
+  initWorkflow: (skillName, purpose, customSteps = []) =>
     set({
       skillName,
       purpose: purpose ?? null,
       currentStep: 0,
-      steps: defaultSteps.map((s) => ({ ...s })),
+      steps: [
+        ...defaultSteps.map((s) => ({ ...s })),
+        ...customSteps.map((s) => ({ ...s, status: "pending" as const })),
+      ],
       isRunning: false,
       reviewMode: true,
       workflowSessionId: null,
@@ -106,6 +114,8 @@ export const useWorkflowStore = create<WorkflowState>((set, get) => ({
 
   setReviewMode: (mode) => set({ reviewMode: mode }),
 
+  setReadOnly: (readOnly) => set(readOnly ? { readOnly, reviewMode: true } : { readOnly }),
+
   setCurrentStep: (step) => set({ currentStep: step }),
 
   updateStepStatus: (stepId, status) =>
@@ -214,6 +224,7 @@ export const useWorkflowStore = create<WorkflowState>((set, get) => ({
       steps: defaultSteps.map((s) => ({ ...s })),
       isRunning: false,
       reviewMode: true,
+      readOnly: false,
       workflowSessionId: null,
       isInitializing: false,
       initStartTime: null,
@@ -17,6 +17,23 @@ const defaultSettings = {
 const mockResponses: Record<string, unknown> = {
   get_settings: defaultSettings,
   save_settings: undefined,
+  start_refine_batch: {
+    batch_id: "e2e-batch-001",
+    instruction: "Update FY24 to FY25",
+    workspace_path: "/tmp/e2e-workspace",
+    workflow_session_id: "e2e-batch-session-001",
+    items: [],
+    total_cost_usd: 0,
+    created_at: "2026-01-01T00:00:00Z",
+  },
+  list_refine_batches: [],
+  list_skill_references: [],
+  add_skill_reference: { path: "references/notes.md", size_bytes: 12, binary: false, indexed: true, linked: false },
+  remove_skill_reference: undefined,
+  get_effective_model: { step_id: 0, model: "claude-sonnet-4-6", source: "agent", route: null },
+  get_settings_sync_document: { version: 1, exported_at: "2026-01-01T00:00:00Z", exported_by: null, settings: {} },
+  push_settings_sync: { commit_sha: null, applied: [], kept_local: [] },
+  pull_settings_sync: { commit_sha: null, applied: [], kept_local: [] },
   test_api_key: true,
   get_default_skills_path: "/tmp/e2e-skills",
   check_node: {
@@ -35,11 +52,30 @@ const mockResponses: Record<string, unknown> = {
       { code: "git_binary", name: "Git", ok: true, detail: "git version 2.50.1" },
     ],
   },
+  get_db_contention: {
+    writer: { acquisitions: 0, total_wait_ms: 0, max_wait_ms: 0, slow_waits: 0, max_hold_ms: 0, slow_holds: 0 },
+    readers: { acquisitions: 0, total_wait_ms: 0, max_wait_ms: 0, slow_waits: 0, max_hold_ms: 0, slow_holds: 0 },
+    read_connections: 3,
+    reader_fallbacks: 0,
+  },
   list_skills: [],
   create_skill: undefined,
+  list_skill_templates: [],
+  get_intake_questions: { schema_version: 1, skill_type: "domain", questions: [], answers: {}, next_question_id: null },
+  submit_intake_answers: { errors: [], intake_json: null },
+  create_skill_from_template: undefined,
   delete_skill: undefined,
-  update_skill_tags: undefined,
+  update_skill_tags: [],
+  check_skill_tags: [],
   get_all_tags: [],
+  get_tag_tree: { tags: [], aliases: [], warnings: [] },
+  rename_tag: { tags: [], aliases: [], warnings: [] },
+  merge_tags: { tags: [], aliases: [], warnings: [] },
+  remove_tag_alias: { tags: [], aliases: [], warnings: [] },
+  get_audit_log: [],
+  export_audit_log: { output_path: "/tmp/e2e-workspace/exports/audit-log.csv", entry_count: 0 },
+  purge_all_user_data: { usage_rows: 0, transcripts: 0, transcript_files: 0, credentials_cleared: false },
+  get_error_help: { code: "unknown", title: "Something went wrong", message: "", steps: [], settings_tab: null },
   parse_clarifications: {
     sections: [
       {
@@ -67,6 +103,7 @@ const mockResponses: Record<string, unknown> = {
   has_running_agents: false,
   start_agent: "agent-001",
   run_workflow_step: "agent-001",
+  get_regeneration_mode: { incremental: false, reason: "SKILL.md has not been generated yet", changes: [] },
   run_parallel_agents: { agent_id_a: "agent-001", agent_id_b: "agent-002" },
   package_skill: { file_path: "/tmp/test/my-skill.skill", size_bytes: 12345 },
   // Workflow state
@@ -86,6 +123,9 @@ const mockResponses: Record<string, unknown> = {
   release_lock: undefined,
   get_locked_skills: [],
   check_lock: false,
+  open_skill_readonly: { skill_name: "", lock: null, last_event_id: 0 },
+  close_skill_readonly: undefined,
+  poll_skill_events: [],
   // Refine page
   start_refine_session: {
     session_id: "e2e-refine-session-001",
@@ -94,6 +134,8 @@ const mockResponses: Record<string, unknown> = {
   },
   send_refine_message: "refine-test-skill-e2e-001",
   close_refine_session: undefined,
+  get_refine_diff: { stat: "no changes", files: [] },
+  review_refine_file: { stat: "no changes", files: [] },
   list_refinable_skills: [
     {
       name: "test-skill",
@@ -116,6 +158,7 @@ const mockResponses: Record<string, unknown> = {
   validate_remote_repo: undefined,
   // Imported skills (Skills Library page)
   list_workspace_skills: [],
+  get_skill_scan_report: null,
   upload_skill: {
     skill_id: "skill-001",
     skill_name: "test-skill",
@@ -165,9 +208,13 @@ const mockResponses: Record<string, unknown> = {
   list_models: [],
   // Usage
   get_usage_summary: { total_cost: 0, total_runs: 0, avg_cost_per_run: 0 },
+  import_skill_invocations: { source_dir: "~/.claude", files_scanned: 0, invocations_matched: 0, invocations_added: 0, invocations_unmatched: 0 },
+  get_skill_invocation_stats: [],
   get_recent_workflow_sessions: [],
   get_agent_runs: [],
   get_session_agent_runs: [],
+  get_agent_transcript: null,
+  search_transcripts: [],
   get_usage_by_step: [],
   get_usage_by_model: [],
   get_usage_by_day: [],